@@ -6,7 +6,7 @@ use crate::vm::analysis::{AnalysisDatabase, CheckError, CheckErrors, ContractAna
 use crate::vm::ast::errors::{ParseError, ParseErrors};
 use crate::vm::ast::{ASTRules, ContractAST};
 use crate::vm::contexts::{AssetMap, Environment, OwnedEnvironment};
-use crate::vm::costs::{ExecutionCost, LimitedCostTracker};
+use crate::vm::costs::{CostErrors, ExecutionCost, LimitedCostTracker};
 use crate::vm::database::ClarityDatabase;
 use crate::vm::errors::Error as InterpreterError;
 use crate::vm::events::StacksTransactionEvent;
@@ -113,6 +113,22 @@ pub trait ClarityConnection {
         self.with_clarity_db_readonly_owned(|mut db| (to_do(&mut db), db))
     }
 
+    /// Run `to_do` against a read-only view of the Clarity database, handing it a `charge`
+    ///  callback with which to account for the data it reads.  `charge` refuses (with
+    ///  `CostBalanceExceeded`) any charge that would take the total spent past `limit`, so
+    ///  `to_do` can stop as soon as its read budget is exhausted.
+    fn with_clarity_db_readonly_limited<F, R>(&mut self, limit: &ExecutionCost, to_do: F) -> R
+    where
+        F: FnOnce(
+            &mut ClarityDatabase,
+            &mut dyn FnMut(&ExecutionCost) -> Result<(), CostErrors>,
+        ) -> R,
+    {
+        let mut spent = ExecutionCost::ZERO;
+        let mut charge = |cost: &ExecutionCost| spent.add_within_limit(cost, limit);
+        self.with_clarity_db_readonly(|db| to_do(db, &mut charge))
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn with_readonly_clarity_env<F, R>(
         &mut self,
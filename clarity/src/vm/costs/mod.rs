@@ -1253,6 +1253,17 @@ impl ExecutionCost {
         }
     }
 
+    /// The cost of `read_count` reads totalling `read_length` bytes
+    pub fn reads(read_count: u64, read_length: u64) -> ExecutionCost {
+        Self {
+            runtime: 0,
+            write_length: 0,
+            read_count,
+            write_count: 0,
+            read_length,
+        }
+    }
+
     pub fn add_runtime(&mut self, runtime: u64) -> Result<()> {
         self.runtime = self.runtime.cost_overflow_add(runtime)?;
         Ok(())
@@ -1304,6 +1315,18 @@ impl ExecutionCost {
             || self.read_length > other.read_length
     }
 
+    /// Adds `other` to this cost, unless the sum would exceed `limit` in any dimension, in which
+    ///  case this cost is left unchanged and `CostBalanceExceeded` is returned.
+    pub fn add_within_limit(&mut self, other: &ExecutionCost, limit: &ExecutionCost) -> Result<()> {
+        let mut total = self.clone();
+        total.add(other)?;
+        if total.exceeds(limit) {
+            return Err(CostErrors::CostBalanceExceeded(total, limit.clone()));
+        }
+        *self = total;
+        Ok(())
+    }
+
     pub fn max_cost(first: ExecutionCost, second: ExecutionCost) -> ExecutionCost {
         Self {
             runtime: first.runtime.max(second.runtime),
@@ -1345,6 +1368,51 @@ mod unit_tests {
         assert_eq!(0u64.cost_overflow_sub(1), Err(CostErrors::CostOverflow));
     }
 
+    #[test]
+    fn test_add_within_limit() {
+        let limit = ExecutionCost::reads(3, 100);
+        let mut spent = ExecutionCost::ZERO;
+        spent
+            .add_within_limit(&ExecutionCost::reads(1, 60), &limit)
+            .unwrap();
+        spent
+            .add_within_limit(&ExecutionCost::reads(1, 40), &limit)
+            .unwrap();
+        assert_eq!(spent, ExecutionCost::reads(2, 100));
+
+        // a charge that would exceed the limit is refused, and not counted
+        assert_eq!(
+            spent.add_within_limit(&ExecutionCost::reads(1, 1), &limit),
+            Err(CostErrors::CostBalanceExceeded(
+                ExecutionCost::reads(3, 101),
+                limit.clone()
+            ))
+        );
+        assert_eq!(spent, ExecutionCost::reads(2, 100));
+
+        assert_eq!(
+            spent.add_within_limit(&ExecutionCost::runtime(1), &limit),
+            Err(CostErrors::CostBalanceExceeded(
+                ExecutionCost {
+                    runtime: 1,
+                    ..ExecutionCost::reads(2, 100)
+                },
+                limit.clone()
+            ))
+        );
+
+        spent
+            .add_within_limit(&ExecutionCost::reads(1, 0), &limit)
+            .unwrap();
+        assert_eq!(spent, limit);
+
+        let mut spent = ExecutionCost::reads(1, u64::MAX);
+        assert_eq!(
+            spent.add_within_limit(&ExecutionCost::reads(0, 1), &ExecutionCost::max_value()),
+            Err(CostErrors::CostOverflow)
+        );
+    }
+
     #[test]
     fn test_simple_log2s() {
         let inputs = [
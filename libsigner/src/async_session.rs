@@ -0,0 +1,511 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use blockstack_lib::net::api::poststackerdbchunk::StackerDBErrorCodes;
+use clarity::vm::types::QualifiedContractIdentifier;
+use hashbrown::HashMap;
+use libstackerdb::{
+    stackerdb_get_chunk_path, stackerdb_get_metadata_path, stackerdb_post_chunk_path, SlotMetadata,
+    StackerDBChunkAckData, StackerDBChunkData, SIGNERS_STACKERDB_CHUNK_SIZE,
+    STACKERDB_MAX_CHUNK_SIZE,
+};
+use stacks_common::codec::{StacksMessageCodec, MAX_MESSAGE_LEN};
+use stacks_common::types::chainstate::StacksPrivateKey;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::error::RPCError;
+use crate::http::{decode_http_body, decode_http_response};
+
+/// Default time to wait for a connection to be established
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Default time to wait for a full request/response round-trip
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Default number of idle connections kept open per host
+pub const DEFAULT_MAX_IDLE_PER_HOST: usize = 4;
+/// Default number of times `put_chunk_with_retry` will re-sign and resend a chunk whose slot
+/// version was stale
+pub const DEFAULT_MAX_VERSION_RETRIES: u32 = 8;
+
+/// Pool of idle keep-alive connections to one or more Stacks node RPC endpoints.
+/// Cloning the pool is cheap; all clones share the same set of idle connections, so a single
+/// pool can be handed to every session the signer or miner creates.
+#[derive(Debug, Clone)]
+pub struct StackerDBConnectionPool {
+    /// idle connections, keyed by host
+    idle: Arc<Mutex<HashMap<String, Vec<TcpStream>>>>,
+    /// how many idle connections to keep per host
+    max_idle_per_host: usize,
+    /// how long to wait for a TCP connection to be established
+    connect_timeout: Duration,
+}
+
+impl Default for StackerDBConnectionPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_IDLE_PER_HOST, DEFAULT_CONNECT_TIMEOUT)
+    }
+}
+
+impl StackerDBConnectionPool {
+    /// Make a new, empty pool
+    pub fn new(max_idle_per_host: usize, connect_timeout: Duration) -> Self {
+        Self {
+            idle: Arc::new(Mutex::new(HashMap::new())),
+            max_idle_per_host,
+            connect_timeout,
+        }
+    }
+
+    /// Take an idle connection to `host` out of the pool, if there is one
+    pub fn take_idle(&self, host: &str) -> Option<TcpStream> {
+        let mut idle = self.idle.lock().expect("FATAL: connection pool lock poisoned");
+        idle.get_mut(host).and_then(|conns| conns.pop())
+    }
+
+    /// Open a new connection to `host`
+    pub async fn connect(&self, host: &str) -> Result<TcpStream, RPCError> {
+        debug!("connect to {host}");
+        let sock = timeout(self.connect_timeout, TcpStream::connect(host))
+            .await
+            .map_err(|_| RPCError::Timeout)??;
+        sock.set_nodelay(true)?;
+        Ok(sock)
+    }
+
+    /// Return a connection to the pool so it can be reused by a later request.
+    /// The connection is dropped if the pool for this host is already full.
+    pub fn checkin(&self, host: &str, sock: TcpStream) {
+        let mut idle = self.idle.lock().expect("FATAL: connection pool lock poisoned");
+        let conns = idle.entry(host.to_string()).or_default();
+        if conns.len() < self.max_idle_per_host {
+            conns.push(sock);
+        }
+    }
+
+    /// Number of idle connections currently held for `host`
+    pub fn num_idle(&self, host: &str) -> usize {
+        let idle = self.idle.lock().expect("FATAL: connection pool lock poisoned");
+        idle.get(host).map(|conns| conns.len()).unwrap_or(0)
+    }
+
+    /// Drop all idle connections to `host`
+    pub fn clear(&self, host: &str) {
+        let mut idle = self.idle.lock().expect("FATAL: connection pool lock poisoned");
+        idle.remove(host);
+    }
+}
+
+/// Find the end of the HTTP headers in `buf`, if they have all arrived.
+/// Returns the offset of the first byte of the body.
+pub fn find_headers_end(buf: &[u8]) -> Option<usize> {
+    buf.windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .map(|pos| pos + 4)
+}
+
+/// Determine whether or not the body of a chunked-encoded response has been fully received.
+pub fn chunked_body_complete(body: &[u8]) -> bool {
+    body.ends_with(b"0\r\n\r\n")
+}
+
+/// Send an HTTP request over a keep-alive connection and read back exactly one response.
+/// Returns the decoded response body, and whether or not the connection can be reused.
+pub async fn run_http_request_async(
+    sock: &mut TcpStream,
+    host: &str,
+    verb: &str,
+    path: &str,
+    content_type: Option<&str>,
+    payload: &[u8],
+) -> Result<(Vec<u8>, bool), RPCError> {
+    let content_length_hdr = if !payload.is_empty() {
+        format!("Content-Length: {}\r\n", payload.len())
+    } else {
+        "".to_string()
+    };
+    let content_type_hdr = content_type
+        .map(|content_type| format!("Content-Type: {content_type}\r\n"))
+        .unwrap_or_default();
+
+    let req_txt = format!(
+        "{verb} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{content_type_hdr}{content_length_hdr}User-Agent: libsigner/0.1\r\nAccept: */*\r\n\r\n"
+    );
+    debug!("HTTP request\n{req_txt}");
+
+    sock.write_all(req_txt.as_bytes()).await?;
+    sock.write_all(payload).await?;
+    sock.flush().await?;
+
+    let mut buf = vec![];
+    let mut read_buf = [0u8; 4096];
+    let body_offset = loop {
+        if let Some(offset) = find_headers_end(&buf) {
+            break offset;
+        }
+        let nr = sock.read(&mut read_buf).await?;
+        if nr == 0 {
+            if buf.is_empty() {
+                // peer hung up without replying
+                return Err(RPCError::NotConnected);
+            }
+            return Err(RPCError::MalformedResponse(
+                "Connection closed before HTTP headers were received".into(),
+            ));
+        }
+        buf.extend_from_slice(&read_buf[..nr]);
+        if buf.len() > MAX_MESSAGE_LEN as usize {
+            return Err(RPCError::MalformedResponse("HTTP response too big".into()));
+        }
+    };
+
+    // NOTE: this will return RPCError::HttpError(..) for non-200 responses.  The connection is
+    // dropped in that case, since we don't know how much of the body remains unread.
+    let (headers, _) = decode_http_response(&buf[..body_offset])?;
+
+    let mut reusable = headers
+        .get("connection")
+        .map(|val| !val.eq_ignore_ascii_case("close"))
+        .unwrap_or(true);
+
+    let chunked = headers
+        .get("transfer-encoding")
+        .map(|val| val == "chunked")
+        .unwrap_or(false);
+
+    let content_length = headers
+        .get("content-length")
+        .map(|val| {
+            val.parse::<usize>()
+                .map_err(|_| RPCError::MalformedResponse("Invalid Content-Length".into()))
+        })
+        .transpose()?;
+
+    loop {
+        let body = &buf[body_offset..];
+        let done = if chunked {
+            chunked_body_complete(body)
+        } else if let Some(len) = content_length {
+            body.len() >= len
+        } else {
+            // no framing, so the server will close the connection when done
+            false
+        };
+        if done {
+            break;
+        }
+        let nr = sock.read(&mut read_buf).await?;
+        if nr == 0 {
+            if chunked || content_length.is_some() {
+                return Err(RPCError::MalformedResponse(
+                    "Connection closed before HTTP body was received".into(),
+                ));
+            }
+            reusable = false;
+            break;
+        }
+        buf.extend_from_slice(&read_buf[..nr]);
+        if buf.len() > MAX_MESSAGE_LEN as usize {
+            return Err(RPCError::MalformedResponse("HTTP response too big".into()));
+        }
+    }
+
+    let body_end = match content_length {
+        Some(len) if !chunked => body_offset + len,
+        _ => buf.len(),
+    };
+    if body_offset >= body_end {
+        debug!("No HTTP body");
+        return Ok((vec![], reusable));
+    }
+    let body = decode_http_body(&headers, &buf[body_offset..body_end])?;
+    Ok((body, reusable))
+}
+
+/// Asynchronous signer session for a stackerdb instance.
+/// Unlike `StackerDBSession`, this session draws its connections from a shared
+/// `StackerDBConnectionPool`, so consecutive requests to the same node reuse the same socket.
+#[derive(Debug, Clone)]
+pub struct AsyncStackerDBSession {
+    /// host we're talking to
+    pub host: String,
+    /// contract we're talking to
+    pub stackerdb_contract_id: QualifiedContractIdentifier,
+    /// pool of connections to draw from
+    pool: StackerDBConnectionPool,
+    /// how long to wait for a request/response round-trip
+    request_timeout: Duration,
+    /// how many times to retry a put on a stale slot version
+    max_version_retries: u32,
+}
+
+impl AsyncStackerDBSession {
+    /// instantiate with a private connection pool
+    pub fn new(host: &str, stackerdb_contract_id: QualifiedContractIdentifier) -> Self {
+        Self::new_with_pool(
+            host,
+            stackerdb_contract_id,
+            StackerDBConnectionPool::default(),
+        )
+    }
+
+    /// instantiate with a shared connection pool
+    pub fn new_with_pool(
+        host: &str,
+        stackerdb_contract_id: QualifiedContractIdentifier,
+        pool: StackerDBConnectionPool,
+    ) -> Self {
+        Self {
+            host: host.to_owned(),
+            stackerdb_contract_id,
+            pool,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            max_version_retries: DEFAULT_MAX_VERSION_RETRIES,
+        }
+    }
+
+    /// Set the request timeout
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Set the maximum number of slot-version conflict retries
+    pub fn with_max_version_retries(mut self, max_version_retries: u32) -> Self {
+        self.max_version_retries = max_version_retries;
+        self
+    }
+
+    /// Get a handle to the connection pool this session uses
+    pub fn pool(&self) -> &StackerDBConnectionPool {
+        &self.pool
+    }
+
+    /// send an HTTP RPC request and receive a reply.
+    /// Return the HTTP reply, decoded if it was chunked.
+    /// The connection is returned to the pool if the node allows it to be reused.
+    async fn rpc_request(
+        &self,
+        verb: &str,
+        path: &str,
+        content_type: Option<&str>,
+        payload: &[u8],
+    ) -> Result<Vec<u8>, RPCError> {
+        // An idle connection may have been closed by the node since it was last used, in which
+        // case the request is retried once on a fresh connection.
+        if let Some(mut sock) = self.pool.take_idle(&self.host) {
+            match self
+                .rpc_request_on(&mut sock, verb, path, content_type, payload)
+                .await
+            {
+                Err(RPCError::IO(_)) | Err(RPCError::NotConnected) => {
+                    debug!("Pooled connection to {} went stale; reconnecting", &self.host);
+                }
+                res => return res.map(|(body, reusable)| self.finish(sock, body, reusable)),
+            }
+        }
+        let mut sock = self.pool.connect(&self.host).await?;
+        let (body, reusable) = self
+            .rpc_request_on(&mut sock, verb, path, content_type, payload)
+            .await?;
+        Ok(self.finish(sock, body, reusable))
+    }
+
+    /// Run a single request/response round-trip on `sock`, subject to the request timeout
+    async fn rpc_request_on(
+        &self,
+        sock: &mut TcpStream,
+        verb: &str,
+        path: &str,
+        content_type: Option<&str>,
+        payload: &[u8],
+    ) -> Result<(Vec<u8>, bool), RPCError> {
+        timeout(
+            self.request_timeout,
+            run_http_request_async(sock, &self.host, verb, path, content_type, payload),
+        )
+        .await
+        .map_err(|_| RPCError::Timeout)?
+    }
+
+    /// Return `sock` to the pool if it can be reused, and pass through the response body
+    fn finish(&self, sock: TcpStream, body: Vec<u8>, reusable: bool) -> Vec<u8> {
+        if reusable {
+            self.pool.checkin(&self.host, sock);
+        }
+        body
+    }
+
+    /// query the replica for a list of chunks
+    pub async fn list_chunks(&self) -> Result<Vec<SlotMetadata>, RPCError> {
+        let bytes = self
+            .rpc_request(
+                "GET",
+                &stackerdb_get_metadata_path(self.stackerdb_contract_id.clone()),
+                None,
+                &[],
+            )
+            .await?;
+        let metadata: Vec<SlotMetadata> = serde_json::from_slice(&bytes)
+            .map_err(|e| RPCError::Deserialize(format!("{:?}", &e)))?;
+        Ok(metadata)
+    }
+
+    /// Get a single chunk at the given slot and version, or the latest version if `version` is
+    /// `None`.  Returns Ok(None) if there is no such chunk.
+    async fn get_chunk_at(
+        &self,
+        slot_id: u32,
+        version: Option<u32>,
+    ) -> Result<Option<Vec<u8>>, RPCError> {
+        let path = stackerdb_get_chunk_path(self.stackerdb_contract_id.clone(), slot_id, version);
+        match self.rpc_request("GET", &path, None, &[]).await {
+            Ok(body_bytes) => Ok(Some(body_bytes)),
+            Err(RPCError::HttpError(404)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// query the replica for zero or more chunks
+    pub async fn get_chunks(
+        &self,
+        slots_and_versions: &[(u32, u32)],
+    ) -> Result<Vec<Option<Vec<u8>>>, RPCError> {
+        let mut payloads = vec![];
+        for (slot_id, slot_version) in slots_and_versions.iter() {
+            payloads.push(self.get_chunk_at(*slot_id, Some(*slot_version)).await?);
+        }
+        Ok(payloads)
+    }
+
+    /// query the replica for zero or more latest chunks
+    pub async fn get_latest_chunks(
+        &self,
+        slot_ids: &[u32],
+    ) -> Result<Vec<Option<Vec<u8>>>, RPCError> {
+        let limit = if self.stackerdb_contract_id.name.starts_with("signer") {
+            SIGNERS_STACKERDB_CHUNK_SIZE
+        } else {
+            usize::try_from(STACKERDB_MAX_CHUNK_SIZE)
+                .expect("infallible: StackerDB chunk size exceeds usize::MAX")
+        };
+        let mut payloads = vec![];
+        for slot_id in slot_ids.iter() {
+            // Verify that the chunk is not too large
+            let chunk = self
+                .get_chunk_at(*slot_id, None)
+                .await?
+                .filter(|body_bytes| body_bytes.len() <= limit);
+            payloads.push(chunk);
+        }
+        Ok(payloads)
+    }
+
+    /// Get a single latest chunk.
+    /// Returns Ok(Some(..)) if the slot exists
+    /// Returns Ok(None) if not
+    /// Returns Err(..) on transport error
+    pub async fn get_latest_chunk(&self, slot_id: u32) -> Result<Option<Vec<u8>>, RPCError> {
+        let mut latest_chunks = self.get_latest_chunks(&[slot_id]).await?;
+        if latest_chunks.is_empty() {
+            return Ok(None);
+        }
+        Ok(latest_chunks.swap_remove(0))
+    }
+
+    /// Get a single latest chunk from the StackerDB and deserialize into `T` using the
+    /// StacksMessageCodec.
+    pub async fn get_latest<T: StacksMessageCodec>(
+        &self,
+        slot_id: u32,
+    ) -> Result<Option<T>, RPCError> {
+        let Some(latest_bytes) = self.get_latest_chunk(slot_id).await? else {
+            return Ok(None);
+        };
+        T::consensus_deserialize(&mut latest_bytes.as_slice())
+            .map(Some)
+            .map_err(|e| {
+                let msg = format!("StacksMessageCodec::consensus_deserialize failure: {e}");
+                RPCError::Deserialize(msg)
+            })
+    }
+
+    /// upload a chunk
+    pub async fn put_chunk(
+        &self,
+        chunk: &StackerDBChunkData,
+    ) -> Result<StackerDBChunkAckData, RPCError> {
+        let body =
+            serde_json::to_vec(chunk).map_err(|e| RPCError::Deserialize(format!("{:?}", &e)))?;
+        let path = stackerdb_post_chunk_path(self.stackerdb_contract_id.clone());
+        let resp_bytes = self
+            .rpc_request("POST", &path, Some("application/json"), &body)
+            .await?;
+        let ack: StackerDBChunkAckData = serde_json::from_slice(&resp_bytes)
+            .map_err(|e| RPCError::Deserialize(format!("{:?}", &e)))?;
+        Ok(ack)
+    }
+
+    /// Sign and upload `data` to `slot_id`, starting at `slot_version`.
+    /// If the node rejects the chunk because the slot already holds data at that version, the
+    /// chunk is re-signed with the next version after the one the node reports and resent, up
+    /// to the configured number of retries.
+    /// Returns the ack for the last attempt made.
+    pub async fn put_chunk_with_retry(
+        &self,
+        slot_id: u32,
+        mut slot_version: u32,
+        data: Vec<u8>,
+        privkey: &StacksPrivateKey,
+    ) -> Result<StackerDBChunkAckData, RPCError> {
+        let mut attempts = 0;
+        loop {
+            let mut chunk = StackerDBChunkData::new(slot_id, slot_version, data.clone());
+            chunk
+                .sign(privkey)
+                .map_err(|e| RPCError::MalformedRequest(format!("Failed to sign chunk: {e}")))?;
+
+            let ack = self.put_chunk(&chunk).await?;
+            if ack.accepted {
+                return Ok(ack);
+            }
+
+            let conflict = ack.code.and_then(StackerDBErrorCodes::from_code)
+                == Some(StackerDBErrorCodes::DataAlreadyExists);
+            if !conflict || attempts >= self.max_version_retries {
+                return Ok(ack);
+            }
+            attempts += 1;
+
+            let next_version = ack
+                .metadata
+                .as_ref()
+                .map(|md| md.slot_version)
+                .unwrap_or(slot_version)
+                .saturating_add(1);
+            warn!(
+                "Slot version conflict on StackerDB put. Attempted {slot_version}, retrying with {next_version}";
+                "contract_id" => %self.stackerdb_contract_id,
+                "slot_id" => slot_id,
+                "attempt" => attempts,
+            );
+            slot_version = next_version;
+        }
+    }
+}
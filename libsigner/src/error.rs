@@ -39,6 +39,9 @@ pub enum RPCError {
     /// HTTP error
     #[error("HTTP code {0}")]
     HttpError(u32),
+    /// Request timed out
+    #[error("Request timed out")]
+    Timeout,
 }
 
 /// Errors originating from receiving event data from the Stacks node
@@ -36,6 +36,7 @@ extern crate libc;
 #[cfg(test)]
 mod tests;
 
+mod async_session;
 mod error;
 mod events;
 mod http;
@@ -54,6 +55,7 @@ use clarity::codec::StacksMessageCodec;
 use clarity::vm::types::QualifiedContractIdentifier;
 use lazy_static::lazy_static;
 
+pub use crate::async_session::{AsyncStackerDBSession, StackerDBConnectionPool};
 pub use crate::error::{EventError, RPCError};
 pub use crate::events::{
     BlockProposal, EventReceiver, EventStopSignaler, SignerEvent, SignerEventReceiver,
@@ -0,0 +1,191 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use clarity::util::hash::Sha512Trunc256Sum;
+use clarity::vm::types::QualifiedContractIdentifier;
+use libstackerdb::{SlotMetadata, StackerDBChunkAckData, StackerDBChunkData};
+use stacks_common::types::chainstate::StacksPrivateKey;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::async_session::{chunked_body_complete, find_headers_end};
+use crate::{AsyncStackerDBSession, StackerDBConnectionPool};
+
+/// Read one HTTP request off of `sock`.  Returns the body, or None if the peer hung up.
+async fn read_request(sock: &mut TcpStream) -> Option<Vec<u8>> {
+    let mut buf = vec![];
+    let mut read_buf = [0u8; 1024];
+    let body_offset = loop {
+        if let Some(offset) = find_headers_end(&buf) {
+            break offset;
+        }
+        let nr = sock.read(&mut read_buf).await.ok()?;
+        if nr == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&read_buf[..nr]);
+    };
+    let headers = std::str::from_utf8(&buf[..body_offset]).unwrap().to_lowercase();
+    let content_length = headers
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length: "))
+        .map(|len| len.trim().parse::<usize>().unwrap())
+        .unwrap_or(0);
+    while buf.len() < body_offset + content_length {
+        let nr = sock.read(&mut read_buf).await.ok()?;
+        buf.extend_from_slice(&read_buf[..nr]);
+    }
+    Some(buf[body_offset..].to_vec())
+}
+
+/// Serve keep-alive connections, answering the nth request on any connection with
+/// `replies[n]`.  Returns the number of connections accepted and the request bodies received.
+fn spawn_server(
+    listener: TcpListener,
+    replies: Vec<String>,
+) -> (Arc<AtomicUsize>, Arc<std::sync::Mutex<Vec<Vec<u8>>>>) {
+    let accepted = Arc::new(AtomicUsize::new(0));
+    let bodies = Arc::new(std::sync::Mutex::new(vec![]));
+    let replies = Arc::new(replies);
+    let next = Arc::new(AtomicUsize::new(0));
+    let accepted_ret = accepted.clone();
+    let bodies_ret = bodies.clone();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut sock, _)) = listener.accept().await else {
+                return;
+            };
+            accepted.fetch_add(1, Ordering::SeqCst);
+            let replies = replies.clone();
+            let next = next.clone();
+            let bodies = bodies.clone();
+            tokio::spawn(async move {
+                while let Some(body) = read_request(&mut sock).await {
+                    bodies.lock().unwrap().push(body);
+                    let idx = next.fetch_add(1, Ordering::SeqCst);
+                    let reply = &replies[idx];
+                    let resp = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                        reply.len(),
+                        reply
+                    );
+                    sock.write_all(resp.as_bytes()).await.unwrap();
+                }
+            });
+        }
+    });
+    (accepted_ret, bodies_ret)
+}
+
+#[test]
+fn test_find_headers_end() {
+    assert_eq!(find_headers_end(b"HTTP/1.1 200 OK\r\n"), None);
+    assert_eq!(find_headers_end(b"HTTP/1.1 200 OK\r\n\r\n"), Some(19));
+    assert_eq!(find_headers_end(b"HTTP/1.1 200 OK\r\n\r\nbody"), Some(19));
+}
+
+#[test]
+fn test_chunked_body_complete() {
+    assert!(!chunked_body_complete(b"5\r\nhello\r\n"));
+    assert!(chunked_body_complete(b"5\r\nhello\r\n0\r\n\r\n"));
+}
+
+/// Run a future to completion on a fresh single-threaded runtime
+fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(fut)
+}
+
+#[test]
+fn test_async_session_reuses_connection() {
+    block_on(async_session_reuses_connection());
+}
+
+async fn async_session_reuses_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let host = listener.local_addr().unwrap().to_string();
+    let (accepted, _) = spawn_server(listener, vec!["[]".into(), "[]".into(), "[]".into()]);
+
+    let pool = StackerDBConnectionPool::default();
+    let contract_id = QualifiedContractIdentifier::transient();
+    let session_1 = AsyncStackerDBSession::new_with_pool(&host, contract_id.clone(), pool.clone());
+    let session_2 = AsyncStackerDBSession::new_with_pool(&host, contract_id, pool.clone());
+
+    assert!(session_1.list_chunks().await.unwrap().is_empty());
+    assert_eq!(pool.num_idle(&host), 1);
+    assert!(session_1.list_chunks().await.unwrap().is_empty());
+    assert!(session_2.list_chunks().await.unwrap().is_empty());
+    assert_eq!(pool.num_idle(&host), 1);
+
+    // all three requests went over the same connection
+    assert_eq!(accepted.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_async_session_retries_version_conflict() {
+    block_on(async_session_retries_version_conflict());
+}
+
+async fn async_session_retries_version_conflict() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let host = listener.local_addr().unwrap().to_string();
+
+    let conflict = StackerDBChunkAckData {
+        accepted: false,
+        reason: Some("Data for this slot and version already exist".into()),
+        metadata: Some(SlotMetadata::new_unsigned(
+            0,
+            5,
+            Sha512Trunc256Sum([0u8; 32]),
+        )),
+        code: Some(0),
+    };
+    let accepted = StackerDBChunkAckData {
+        accepted: true,
+        reason: None,
+        metadata: None,
+        code: None,
+    };
+    let (_, bodies) = spawn_server(
+        listener,
+        vec![
+            serde_json::to_string(&conflict).unwrap(),
+            serde_json::to_string(&accepted).unwrap(),
+        ],
+    );
+
+    let session = AsyncStackerDBSession::new(&host, QualifiedContractIdentifier::transient());
+    let privkey = StacksPrivateKey::new();
+    let ack = session
+        .put_chunk_with_retry(0, 1, vec![1, 2, 3], &privkey)
+        .await
+        .unwrap();
+    assert!(ack.accepted);
+
+    let bodies = bodies.lock().unwrap();
+    assert_eq!(bodies.len(), 2);
+    let first: StackerDBChunkData = serde_json::from_slice(&bodies[0]).unwrap();
+    let second: StackerDBChunkData = serde_json::from_slice(&bodies[1]).unwrap();
+    assert_eq!(first.slot_version, 1);
+    assert_eq!(second.slot_version, 6);
+    assert_eq!(second.data, vec![1, 2, 3]);
+}
@@ -399,6 +399,12 @@ impl StacksEpochId {
         self >= &StacksEpochId::Epoch30
     }
 
+    /// Whether or not burnchain stack-aggregation-commit operations are parsed and applied.
+    /// Before 3.1, such transactions are ignored like any other unknown opcode.
+    pub fn supports_stack_aggregation_commit_op(&self) -> bool {
+        self >= &StacksEpochId::Epoch31
+    }
+
     /// Does this epoch use the nakamoto reward set, or the epoch2 reward set?
     /// We use the epoch2 reward set in all pre-3.0 epochs.
     /// We also use the epoch2 reward set in the first 3.0 reward cycle.
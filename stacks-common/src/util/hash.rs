@@ -311,6 +311,26 @@ impl Sha256Sum {
     pub fn zero() -> Sha256Sum {
         Sha256Sum([0u8; 32])
     }
+
+    /// HMAC-SHA256 (RFC 2104) of `data` under `key`
+    pub fn hmac(key: &[u8], data: &[u8]) -> Sha256Sum {
+        const BLOCK_SIZE: usize = 64;
+        let mut key_block = [0u8; BLOCK_SIZE];
+        if key.len() > BLOCK_SIZE {
+            key_block[..32].copy_from_slice(&Sha256::digest(key));
+        } else {
+            key_block[..key.len()].copy_from_slice(key);
+        }
+
+        let mut inner = Sha256::new();
+        inner.update(key_block.map(|b| b ^ 0x36));
+        inner.update(data);
+
+        let mut outer = Sha256::new();
+        outer.update(key_block.map(|b| b ^ 0x5c));
+        outer.update(inner.finalize());
+        Sha256Sum(outer.finalize().into())
+    }
 }
 
 impl DoubleSha256 {
@@ -658,7 +678,34 @@ pub fn bytes_to_hex(s: &[u8]) -> String {
 
 #[cfg(test)]
 mod test {
-    use super::{bin_bytes, hex_bytes, to_bin, DoubleSha256, MerkleHashFunc, MerkleTree};
+    use super::{
+        bin_bytes, hex_bytes, to_bin, to_hex, DoubleSha256, MerkleHashFunc, MerkleTree, Sha256Sum,
+    };
+
+    #[test]
+    fn test_hmac_sha256() {
+        // test vectors from RFC 4231
+        let fixtures: [(Vec<u8>, &[u8], &str); 3] = [
+            (
+                vec![0x0b; 20],
+                b"Hi There",
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            ),
+            (
+                b"Jefe".to_vec(),
+                b"what do ya want for nothing?",
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            ),
+            (
+                vec![0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            ),
+        ];
+        for (key, data, expected) in fixtures.iter() {
+            assert_eq!(to_hex(Sha256Sum::hmac(key, data).as_bytes()), *expected);
+        }
+    }
 
     struct MerkleTreeFixture {
         data: Vec<Vec<u8>>,
@@ -15,8 +15,11 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::io::Write;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
 use std::time::{Duration, SystemTime};
-use std::{env, io, thread};
+use std::{env, fmt, io, thread};
 
 use chrono::prelude::*;
 use lazy_static::lazy_static;
@@ -190,8 +193,6 @@ impl<D: Decorator> TermFormat<D> {
 
 #[cfg(feature = "slog_json")]
 fn make_json_logger() -> Logger {
-    use std::sync::Mutex;
-
     use slog::FnValue;
 
     let def_keys = o!("file" => FnValue(move |info| {
@@ -200,6 +201,9 @@ fn make_json_logger() -> Logger {
                       "line" => FnValue(move |info| {
                           info.line()
                       }),
+                      "module" => FnValue(move |info| {
+                          info.module()
+                      }),
                       "thread" => FnValue(move |_| {
                           match thread::current().name() {
                               None => format!("{:?}", thread::current().id()),
@@ -208,9 +212,10 @@ fn make_json_logger() -> Logger {
                       }),
     );
 
+    // Levels are filtered per-component by the logging macros, so the drain must not filter
+    // them again.
     let drain = Mutex::new(slog_json::Json::default(std::io::stderr()));
-    let filtered_drain = slog::LevelFilter::new(drain, get_loglevel()).ignore_res();
-    slog::Logger::root(filtered_drain, def_keys)
+    slog::Logger::root(drain.ignore_res(), def_keys)
 }
 
 #[cfg(not(feature = "slog_json"))]
@@ -218,8 +223,59 @@ fn make_json_logger() -> Logger {
     panic!("Tried to construct JSON logger, but stacks-blockchain built without slog_json feature enabled.")
 }
 
+/// Format of the log lines written to stderr
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    /// Human-readable slog lines
+    Text,
+    /// One JSON object per line, with the key/value pairs as fields
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!(
+                "Invalid log format '{s}': expected `text` or `json`"
+            )),
+        }
+    }
+}
+
+/// Log format chosen by the program before the logger was first used.  Takes precedence over
+/// `STACKS_LOG_JSON`.
+static LOG_FORMAT: Mutex<Option<LogFormat>> = Mutex::new(None);
+/// Set once `LOGGER` has been constructed, after which the format can no longer change
+static LOGGER_INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// Choose the log format.  This must be called before anything is logged, since the format is
+/// fixed once the logger is constructed.
+pub fn set_log_format(format: LogFormat) -> Result<(), String> {
+    let mut log_format = LOG_FORMAT.lock().expect("FATAL: log format lock poisoned");
+    if LOGGER_INITIALIZED.load(Ordering::SeqCst) {
+        return Err("Log format must be set before the logger is used".into());
+    }
+    *log_format = Some(format);
+    Ok(())
+}
+
 fn make_logger() -> Logger {
-    if env::var("STACKS_LOG_JSON") == Ok("1".into()) {
+    let log_format = {
+        let log_format = LOG_FORMAT.lock().expect("FATAL: log format lock poisoned");
+        LOGGER_INITIALIZED.store(true, Ordering::SeqCst);
+        log_format.unwrap_or_else(|| {
+            if env::var("STACKS_LOG_JSON") == Ok("1".into()) {
+                LogFormat::Json
+            } else {
+                LogFormat::Text
+            }
+        })
+    };
+    if log_format == LogFormat::Json {
         make_json_logger()
     } else {
         let debug = env::var("STACKS_LOG_DEBUG") == Ok("1".into());
@@ -263,10 +319,142 @@ pub fn get_loglevel() -> slog::Level {
     *LOGLEVEL
 }
 
+/// A log level override for every module path containing `component`.  The component is one or
+/// more `::`-separated path segments (e.g. `net`, `net::p2p`, `miner`), or empty to override the
+/// default level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentLogLevel {
+    pub component: String,
+    pub level: Level,
+}
+
+impl ComponentLogLevel {
+    /// Does this override apply to `module_path`?  Returns the number of path segments matched,
+    /// so the most specific override can win.
+    fn matches(&self, module_path: &str) -> Option<usize> {
+        if self.component.is_empty() {
+            return Some(0);
+        }
+        let component: Vec<_> = self.component.split("::").collect();
+        let path: Vec<_> = module_path.split("::").collect();
+        path.windows(component.len())
+            .any(|window| window == component.as_slice())
+            .then_some(component.len())
+    }
+}
+
+impl fmt::Display for ComponentLogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let level = self.level.as_str().to_lowercase();
+        if self.component.is_empty() {
+            write!(f, "{level}")
+        } else {
+            write!(f, "{}={level}", self.component)
+        }
+    }
+}
+
+/// Parse a log level name, as used in `STACKS_LOG_LEVELS`
+fn parse_level(level: &str) -> Result<Level, String> {
+    match level.to_lowercase().as_str() {
+        "trace" => Ok(Level::Trace),
+        "debug" => Ok(Level::Debug),
+        "info" => Ok(Level::Info),
+        "warn" | "warning" => Ok(Level::Warning),
+        "error" => Ok(Level::Error),
+        "crit" | "critical" => Ok(Level::Critical),
+        _ => Err(format!("Invalid log level '{level}'")),
+    }
+}
+
+/// Parse a comma-separated list of component log levels, such as
+/// `net=debug,chainstate=info,miner=trace`.  A bare level (e.g. `debug,net=trace`) overrides the
+/// default level.
+pub fn parse_component_loglevels(spec: &str) -> Result<Vec<ComponentLogLevel>, String> {
+    let mut levels: Vec<ComponentLogLevel> = vec![];
+    for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (component, level) = entry.split_once('=').unwrap_or(("", entry));
+        let component = component.trim();
+        if !component.is_empty() && component.split("::").any(str::is_empty) {
+            return Err(format!("Invalid log component '{component}'"));
+        }
+        let level = parse_level(level.trim())?;
+        levels.retain(|l| l.component != component);
+        levels.push(ComponentLogLevel {
+            component: component.to_string(),
+            level,
+        });
+    }
+    Ok(levels)
+}
+
+/// Find the level for `module_path` among `levels`, falling back to `default`
+fn find_loglevel(levels: &[ComponentLogLevel], module_path: &str, default: Level) -> Level {
+    levels
+        .iter()
+        .filter_map(|l| {
+            l.matches(module_path)
+                .map(|specificity| (specificity, l.level))
+        })
+        .max_by_key(|(specificity, _)| *specificity)
+        .map(|(_, level)| level)
+        .unwrap_or(default)
+}
+
+fn inner_get_component_loglevels() -> Vec<ComponentLogLevel> {
+    let Ok(spec) = env::var("STACKS_LOG_LEVELS") else {
+        return vec![];
+    };
+    parse_component_loglevels(&spec).unwrap_or_else(|e| {
+        eprintln!("Ignoring STACKS_LOG_LEVELS: {e}");
+        vec![]
+    })
+}
+
+lazy_static! {
+    static ref COMPONENT_LOGLEVELS: RwLock<Vec<ComponentLogLevel>> =
+        RwLock::new(inner_get_component_loglevels());
+    /// Fast path for the common case where no component overrides are set
+    static ref HAS_COMPONENT_LOGLEVELS: AtomicBool = AtomicBool::new(
+        !COMPONENT_LOGLEVELS
+            .read()
+            .expect("FATAL: log levels lock poisoned")
+            .is_empty()
+    );
+}
+
+/// Get the log level for the module at `module_path`, taking component overrides into account
+pub fn get_loglevel_for(module_path: &str) -> Level {
+    if !HAS_COMPONENT_LOGLEVELS.load(Ordering::Relaxed) {
+        return get_loglevel();
+    }
+    let levels = COMPONENT_LOGLEVELS
+        .read()
+        .expect("FATAL: log levels lock poisoned");
+    find_loglevel(&levels, module_path, get_loglevel())
+}
+
+/// Get the current component log level overrides
+pub fn get_component_loglevels() -> Vec<ComponentLogLevel> {
+    COMPONENT_LOGLEVELS
+        .read()
+        .expect("FATAL: log levels lock poisoned")
+        .clone()
+}
+
+/// Replace the component log level overrides.  Takes effect immediately for all threads.
+pub fn set_component_loglevels(levels: Vec<ComponentLogLevel>) {
+    let mut cur_levels = COMPONENT_LOGLEVELS
+        .write()
+        .expect("FATAL: log levels lock poisoned");
+    HAS_COMPONENT_LOGLEVELS.store(!levels.is_empty(), Ordering::Relaxed);
+    *cur_levels = levels;
+}
+
 #[macro_export]
 macro_rules! trace {
     ($($arg:tt)*) => ({
-        let cur_level = $crate::util::log::get_loglevel();
+        let cur_level = $crate::util::log::get_loglevel_for(module_path!());
         if slog::Level::Trace.is_at_least(cur_level) {
             slog_trace!($crate::util::log::LOGGER, $($arg)*)
         }
@@ -276,7 +464,7 @@ macro_rules! trace {
 #[macro_export]
 macro_rules! error {
     ($($arg:tt)*) => ({
-        let cur_level = $crate::util::log::get_loglevel();
+        let cur_level = $crate::util::log::get_loglevel_for(module_path!());
         if slog::Level::Error.is_at_least(cur_level) {
             slog_error!($crate::util::log::LOGGER, $($arg)*)
         }
@@ -286,7 +474,7 @@ macro_rules! error {
 #[macro_export]
 macro_rules! warn {
     ($($arg:tt)*) => ({
-        let cur_level = $crate::util::log::get_loglevel();
+        let cur_level = $crate::util::log::get_loglevel_for(module_path!());
         if slog::Level::Warning.is_at_least(cur_level) {
             slog_warn!($crate::util::log::LOGGER, $($arg)*)
         }
@@ -296,7 +484,7 @@ macro_rules! warn {
 #[macro_export]
 macro_rules! info {
     ($($arg:tt)*) => ({
-        let cur_level = $crate::util::log::get_loglevel();
+        let cur_level = $crate::util::log::get_loglevel_for(module_path!());
         if slog::Level::Info.is_at_least(cur_level) {
             slog_info!($crate::util::log::LOGGER, $($arg)*)
         }
@@ -306,7 +494,7 @@ macro_rules! info {
 #[macro_export]
 macro_rules! debug {
     ($($arg:tt)*) => ({
-        let cur_level = $crate::util::log::get_loglevel();
+        let cur_level = $crate::util::log::get_loglevel_for(module_path!());
         if slog::Level::Debug.is_at_least(cur_level) {
             slog_debug!($crate::util::log::LOGGER, $($arg)*)
         }
@@ -316,7 +504,7 @@ macro_rules! debug {
 #[macro_export]
 macro_rules! fatal {
     ($($arg:tt)*) => ({
-        let cur_level = $crate::util::log::get_loglevel();
+        let cur_level = $crate::util::log::get_loglevel_for(module_path!());
         if slog::Level::Critical.is_at_least(cur_level) {
             slog_crit!($crate::util::log::LOGGER, $($arg)*)
         }
@@ -349,3 +537,74 @@ fn isatty(stream: Stream) -> bool {
 fn isatty(stream: Stream) -> bool {
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_component_loglevels() {
+        let levels = parse_component_loglevels("net=debug, chainstate=info,miner=trace").unwrap();
+        assert_eq!(
+            levels,
+            vec![
+                ComponentLogLevel {
+                    component: "net".into(),
+                    level: Level::Debug
+                },
+                ComponentLogLevel {
+                    component: "chainstate".into(),
+                    level: Level::Info
+                },
+                ComponentLogLevel {
+                    component: "miner".into(),
+                    level: Level::Trace
+                },
+            ]
+        );
+        let rendered: Vec<_> = levels.iter().map(|l| l.to_string()).collect();
+        assert_eq!(
+            rendered,
+            vec!["net=debug", "chainstate=info", "miner=trace"]
+        );
+
+        // bare level sets the default, and later entries replace earlier ones
+        let levels = parse_component_loglevels("warn,net=debug,net=error").unwrap();
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[0].to_string(), "warn");
+        assert_eq!(levels[1].to_string(), "net=error");
+
+        assert!(parse_component_loglevels("").unwrap().is_empty());
+        assert!(parse_component_loglevels("net=loud").is_err());
+        assert!(parse_component_loglevels("net::=debug").is_err());
+        assert!(parse_component_loglevels("::net=debug").is_err());
+    }
+
+    #[test]
+    fn test_find_loglevel() {
+        let levels =
+            parse_component_loglevels("error,net=debug,net::p2p=trace,miner=warn").unwrap();
+        let default = Level::Info;
+        assert_eq!(
+            find_loglevel(&levels, "blockstack_lib::net::relay", default),
+            Level::Debug
+        );
+        assert_eq!(
+            find_loglevel(&levels, "blockstack_lib::net::p2p", default),
+            Level::Trace
+        );
+        assert_eq!(
+            find_loglevel(&levels, "stacks_node::nakamoto_node::miner", default),
+            Level::Warning
+        );
+        // segments must match whole
+        assert_eq!(
+            find_loglevel(&levels, "blockstack_lib::network", default),
+            Level::Error
+        );
+        assert_eq!(
+            find_loglevel(&[], "blockstack_lib::net::p2p", default),
+            Level::Info
+        );
+    }
+}
@@ -13,6 +13,8 @@
 //
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
+use std::thread;
+
 use rand::RngCore;
 use secp256k1;
 use secp256k1::ecdsa::{
@@ -33,6 +35,11 @@ use crate::util::hash::{hex_bytes, to_hex};
 // per-thread Secp256k1 context
 thread_local!(static _secp256k1: Secp256k1<secp256k1::All> = Secp256k1::new());
 
+/// Fewest signatures each thread recovers in `Secp256k1PublicKey::recover_batch_to_pubkeys()`
+pub const RECOVER_BATCH_MIN_PER_WORKER: usize = 16;
+/// Most threads `Secp256k1PublicKey::recover_batch_to_pubkeys()` uses
+pub const RECOVER_BATCH_MAX_WORKERS: usize = 4;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, Hash)]
 pub struct Secp256k1PublicKey {
     // serde is broken for secp256k1, so do it ourselves
@@ -174,19 +181,72 @@ impl Secp256k1PublicKey {
             let msg = LibSecp256k1Message::from_slice(msg).map_err(|_e| {
                 "Invalid message: failed to decode data hash: must be a 32-byte hash"
             })?;
+            Self::recover_with_ctx(ctx, &msg, sig)
+        })
+    }
 
-            let secp256k1_sig = sig
-                .to_secp256k1_recoverable()
-                .ok_or("Invalid signature: failed to decode recoverable signature")?;
+    /// Recover the (compressed) public keys of a batch of signatures over the same message, in
+    /// the same order as `sigs`.  The message is decoded once, and batches of at least
+    /// `RECOVER_BATCH_MIN_PER_WORKER * 2` signatures are split across up to
+    /// `RECOVER_BATCH_MAX_WORKERS` threads.
+    pub fn recover_batch_to_pubkeys(
+        msg: &[u8],
+        sigs: &[MessageSignature],
+    ) -> Vec<Result<Secp256k1PublicKey, &'static str>> {
+        let Ok(msg) = LibSecp256k1Message::from_slice(msg) else {
+            return sigs
+                .iter()
+                .map(|_| Err("Invalid message: failed to decode data hash: must be a 32-byte hash"))
+                .collect();
+        };
+        let recover_all = |sigs: &[MessageSignature]| {
+            _secp256k1.with(|ctx| {
+                sigs.iter()
+                    .map(|sig| Self::recover_with_ctx(ctx, &msg, sig))
+                    .collect::<Vec<_>>()
+            })
+        };
 
-            let recovered_pubkey = ctx
-                .recover_ecdsa(&msg, &secp256k1_sig)
-                .map_err(|_e| "Invalid signature: failed to recover public key")?;
+        let num_workers = (sigs.len() / RECOVER_BATCH_MIN_PER_WORKER)
+            .min(RECOVER_BATCH_MAX_WORKERS)
+            .min(thread::available_parallelism().map_or(1, |n| n.get()));
+        if num_workers <= 1 {
+            return recover_all(sigs);
+        }
 
-            Ok(Secp256k1PublicKey {
-                key: recovered_pubkey,
-                compressed: true,
-            })
+        let chunk_size = sigs.len().div_ceil(num_workers);
+        thread::scope(|s| {
+            let workers: Vec<_> = sigs
+                .chunks(chunk_size)
+                .map(|chunk| s.spawn(move || recover_all(chunk)))
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| {
+                    worker
+                        .join()
+                        .expect("FATAL: secp256k1 public key recovery worker panicked")
+                })
+                .collect()
+        })
+    }
+
+    fn recover_with_ctx(
+        ctx: &Secp256k1<secp256k1::All>,
+        msg: &LibSecp256k1Message,
+        sig: &MessageSignature,
+    ) -> Result<Secp256k1PublicKey, &'static str> {
+        let secp256k1_sig = sig
+            .to_secp256k1_recoverable()
+            .ok_or("Invalid signature: failed to decode recoverable signature")?;
+
+        let recovered_pubkey = ctx
+            .recover_ecdsa(msg, &secp256k1_sig)
+            .map_err(|_e| "Invalid signature: failed to recover public key")?;
+
+        Ok(Secp256k1PublicKey {
+            key: recovered_pubkey,
+            compressed: true,
         })
     }
 
@@ -637,6 +697,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_recover_batch() {
+        let mut msg = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut msg);
+
+        // enough signatures to be split across workers, one of which is bad
+        let privks: Vec<_> = (0..(RECOVER_BATCH_MIN_PER_WORKER * 3))
+            .map(|_| Secp256k1PrivateKey::random())
+            .collect();
+        let mut sigs: Vec<_> = privks
+            .iter()
+            .map(|privk| privk.sign(&msg).unwrap())
+            .collect();
+        sigs[20].0[0] = 0x02;
+
+        for batch in [&sigs[..2], &sigs[..]] {
+            let recovered = Secp256k1PublicKey::recover_batch_to_pubkeys(&msg, batch);
+            assert_eq!(recovered.len(), batch.len());
+            for (i, (sig, pubk_res)) in batch.iter().zip(recovered.iter()).enumerate() {
+                assert_eq!(pubk_res, &Secp256k1PublicKey::recover_to_pubkey(&msg, sig));
+                if i != 20 {
+                    assert_eq!(
+                        pubk_res.as_ref().unwrap(),
+                        &Secp256k1PublicKey::from_private(&privks[i])
+                    );
+                }
+            }
+        }
+
+        let recovered = Secp256k1PublicKey::recover_batch_to_pubkeys(&msg[..31], &sigs[..2]);
+        assert!(recovered.iter().all(|pubk_res| pubk_res.is_err()));
+    }
+
     #[test]
     #[ignore]
     fn test_verify_benchmark_roundtrip() {
@@ -73,6 +73,8 @@ pub enum Command {
     VerifyVote(VerifyVoteArgs),
     /// Verify signer signatures by checking stackerdb slots contain the correct data
     MonitorSigners(MonitorSignersArgs),
+    /// Export the signer's decisions on block proposals as signed JSON lines
+    ExportAudit(ExportAuditArgs),
 }
 
 /// Basic arguments for all cyrptographic and stacker-db functionality
@@ -248,6 +250,24 @@ pub struct MonitorSignersArgs {
     pub max_age: u64,
 }
 
+#[derive(Parser, Debug, Clone)]
+/// Arguments for the export-audit command
+pub struct ExportAuditArgs {
+    /// Path to signer config file. The decisions are read from its `db_path` and signed with
+    /// its `stacks_private_key`.
+    #[arg(long, short, value_name = "FILE")]
+    pub config: PathBuf,
+    /// Export decisions made at or after this time (epoch time in seconds)
+    #[arg(long, default_value = "0")]
+    pub from: u64,
+    /// Export decisions made at or before this time (epoch time in seconds). Defaults to now.
+    #[arg(long)]
+    pub to: Option<u64>,
+    /// File to write the export to. Defaults to stdout.
+    #[arg(long, short, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 /// Wrapper around `Pox4SignatureTopic` to implement `ValueEnum`
 pub struct StackingSignatureMethod(Pox4SignatureTopic);
@@ -417,6 +417,8 @@ pub(crate) mod tests {
             block_proposal_validation_timeout: config.block_proposal_validation_timeout,
             tenure_idle_timeout: config.tenure_idle_timeout,
             block_proposal_max_age_secs: config.block_proposal_max_age_secs,
+            chain_id: config.to_chain_id(),
+            checkpoint_interval: config.checkpoint_interval,
         }
     }
 
@@ -17,6 +17,7 @@ use std::collections::{HashMap, VecDeque};
 use std::fmt::Display;
 use std::time::{Duration, Instant};
 
+use blockstack_lib::burnchains::Txid;
 use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
 use blockstack_lib::chainstate::stacks::boot::{NakamotoSignerEntry, SIGNERS_NAME};
 use blockstack_lib::chainstate::stacks::db::StacksBlockHeaderTypes;
@@ -594,6 +595,30 @@ impl StacksClient {
         Ok(account_entry)
     }
 
+    /// Submit a transaction to the stacks node's mempool, returning its txid
+    pub fn submit_transaction(&self, tx: &StacksTransaction) -> Result<Txid, ClientError> {
+        debug!("StacksClient: Submitting transaction to the stacks node";
+            "txid" => %tx.txid(),
+        );
+        let path = self.transactions_path();
+        let timer = crate::monitoring::actions::new_rpc_call_timer(&path, &self.http_origin);
+        let send_request = || {
+            self.stacks_node_client
+                .post(&path)
+                .header("Content-Type", "application/octet-stream")
+                .body(tx.serialize_to_vec())
+                .send()
+                .map_err(backoff::Error::transient)
+        };
+        let response = retry_with_exponential_backoff(send_request)?;
+        timer.stop_and_record();
+        if !response.status().is_success() {
+            return Err(ClientError::RequestFailure(response.status()));
+        }
+        let txid = response.json::<Txid>()?;
+        Ok(txid)
+    }
+
     /// Post a block to the stacks-node, retry forever on errors.
     ///
     /// In tests, this panics if the retry takes longer than 30 seconds.
@@ -746,6 +771,10 @@ impl StacksClient {
         format!("{}/v2/info", self.http_origin)
     }
 
+    fn transactions_path(&self) -> String {
+        format!("{}/v2/transactions", self.http_origin)
+    }
+
     fn accounts_path(&self, stacks_address: &StacksAddress) -> String {
         format!("{}/v2/accounts/{stacks_address}?proof=0", self.http_origin)
     }
@@ -1174,6 +1203,48 @@ mod tests {
         assert!(h.join().unwrap().is_err());
     }
 
+    #[test]
+    fn submit_transaction_should_succeed() {
+        let mock = MockServerClient::new();
+        let unsigned_tx = StacksClient::build_unsigned_contract_call_transaction(
+            &mock.client.stacks_address,
+            ContractName::from("contract-name"),
+            ClarityName::from("function-name"),
+            &[],
+            &mock.client.stacks_private_key,
+            mock.client.tx_version,
+            mock.client.chain_id,
+            0,
+        )
+        .unwrap();
+        let tx = mock.client.sign_transaction(unsigned_tx).unwrap();
+        let txid = tx.txid();
+        let h = spawn(move || mock.client.submit_transaction(&tx));
+        let response = format!("HTTP/1.1 200 OK\n\n\"{txid}\"");
+        write_response(mock.server, response.as_bytes());
+        assert_eq!(h.join().unwrap().unwrap(), txid);
+    }
+
+    #[test]
+    fn submit_transaction_should_fail() {
+        let mock = MockServerClient::new();
+        let unsigned_tx = StacksClient::build_unsigned_contract_call_transaction(
+            &mock.client.stacks_address,
+            ContractName::from("contract-name"),
+            ClarityName::from("function-name"),
+            &[],
+            &mock.client.stacks_private_key,
+            mock.client.tx_version,
+            mock.client.chain_id,
+            0,
+        )
+        .unwrap();
+        let tx = mock.client.sign_transaction(unsigned_tx).unwrap();
+        let h = spawn(move || mock.client.submit_transaction(&tx));
+        write_response(mock.server, b"HTTP/1.1 400 Bad Request\n\n");
+        assert!(h.join().unwrap().is_err());
+    }
+
     #[test]
     fn get_peer_info_should_succeed() {
         let mock = MockServerClient::new();
@@ -20,8 +20,11 @@ use std::net::{SocketAddr, ToSocketAddrs};
 use std::path::PathBuf;
 use std::time::Duration;
 
+use blockstack_lib::chainstate::stacks::address::PoxAddress;
 use blockstack_lib::chainstate::stacks::TransactionVersion;
 use blockstack_lib::net::connection::DEFAULT_BLOCK_PROPOSAL_MAX_AGE_SECS;
+use blockstack_lib::util_lib::signed_structured_data::pox4::Pox4SignatureTopic;
+use clap::ValueEnum;
 use clarity::util::hash::to_hex;
 use libsigner::SignerEntries;
 use serde::Deserialize;
@@ -32,6 +35,7 @@ use stacks_common::consts::{CHAIN_ID_MAINNET, CHAIN_ID_TESTNET};
 use stacks_common::types::chainstate::{StacksAddress, StacksPrivateKey, StacksPublicKey};
 use stacks_common::util::hash::Hash160;
 
+use crate::cli::{parse_pox_addr, StackingSignatureMethod};
 use crate::client::SignerSlotID;
 
 const EVENT_TIMEOUT_MS: u64 = 5000;
@@ -41,6 +45,9 @@ const DEFAULT_FIRST_PROPOSAL_BURN_BLOCK_TIMING_SECS: u64 = 60;
 const DEFAULT_TENURE_LAST_BLOCK_PROPOSAL_TIMEOUT_SECS: u64 = 30;
 const DEFAULT_DRY_RUN: bool = false;
 const TENURE_IDLE_TIMEOUT_SECS: u64 = 120;
+const DEFAULT_KEY_ROTATION_PERIOD: u64 = 1;
+const DEFAULT_KEY_ROTATION_TX_FEE_USTX: u64 = 10_000;
+const DEFAULT_CHECKPOINT_INTERVAL: u64 = 100;
 
 #[derive(thiserror::Error, Debug)]
 /// An error occurred parsing the provided configuration
@@ -165,6 +172,11 @@ pub struct SignerConfig {
     pub block_proposal_max_age_secs: u64,
     /// The running mode for the signer (dry-run or normal)
     pub signer_mode: SignerConfigMode,
+    /// The chain ID of the network the signer is running on
+    pub chain_id: u32,
+    /// How many Stacks blocks apart the chain checkpoints the signer publishes are (0 to
+    /// publish none)
+    pub checkpoint_interval: u64,
 }
 
 /// The parsed configuration for the signer
@@ -207,6 +219,99 @@ pub struct GlobalConfig {
     pub block_proposal_max_age_secs: u64,
     /// Is this signer binary going to be running in dry-run mode?
     pub dry_run: bool,
+    /// The key to rotate to in an upcoming reward cycle, if any
+    pub key_rotation: Option<KeyRotationConfig>,
+    /// How many Stacks blocks apart the chain checkpoints the signer publishes to the
+    /// `.checkpoints` StackerDB are (0 to publish none)
+    pub checkpoint_interval: u64,
+}
+
+/// How the signer rotates to a new signing key
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyRotationConfig {
+    /// The signer's next Stacks private key
+    pub next_stacks_private_key: StacksPrivateKey,
+    /// The PoX address of the stacking operation which registers the next key
+    pub pox_address: PoxAddress,
+    /// The pox-4 function which the stacker will call to register the next key
+    pub method: Pox4SignatureTopic,
+    /// The lock period of the stacking operation
+    pub period: u64,
+    /// The most uSTX the stacking operation may lock
+    pub max_amount: u128,
+    /// The authorization ID to use.  If not set, the authorization's reward cycle is used.
+    pub auth_id: Option<u128>,
+    /// Whether to submit a `set-signer-key-authorization` transaction from the next key's
+    /// address, or only to log the authorization for the stacker
+    pub submit_authorization: bool,
+    /// The fee (in uSTX) to pay for the `set-signer-key-authorization` transaction
+    pub tx_fee_ustx: u64,
+}
+
+/// Internal struct for loading up the `[key_rotation]` section of the config file
+#[derive(Deserialize, Debug)]
+struct RawKeyRotationConfig {
+    /// The hex representation of the signer's next Stacks private key
+    pub next_stacks_private_key: String,
+    /// The PoX address of the stacking operation which registers the next key
+    pub pox_address: String,
+    /// The pox-4 stacking method (e.g. "stack-extend" or "aggregation-commit")
+    pub method: Option<String>,
+    /// The lock period of the stacking operation
+    pub period: Option<u64>,
+    /// The most uSTX the stacking operation may lock
+    pub max_amount: Option<u128>,
+    /// The authorization ID to use
+    pub auth_id: Option<u128>,
+    /// Whether to submit a `set-signer-key-authorization` transaction
+    pub submit_authorization: Option<bool>,
+    /// The fee (in uSTX) to pay for the `set-signer-key-authorization` transaction
+    pub tx_fee_ustx: Option<u64>,
+}
+
+impl RawKeyRotationConfig {
+    fn try_into_config(
+        self,
+        stacks_private_key: &StacksPrivateKey,
+    ) -> Result<KeyRotationConfig, ConfigError> {
+        let next_stacks_private_key = StacksPrivateKey::from_hex(&self.next_stacks_private_key)
+            .map_err(|e| {
+                ConfigError::BadField("key_rotation.next_stacks_private_key".to_string(), e.into())
+            })?;
+        if StacksPublicKey::from_private(&next_stacks_private_key).to_bytes_compressed()
+            == StacksPublicKey::from_private(stacks_private_key).to_bytes_compressed()
+        {
+            return Err(ConfigError::InvalidConfig(
+                "key_rotation.next_stacks_private_key must differ from stacks_private_key"
+                    .to_string(),
+            ));
+        }
+        let pox_address = parse_pox_addr(&self.pox_address)
+            .map_err(|e| ConfigError::BadField("key_rotation.pox_address".to_string(), e))?;
+        let method = match self.method {
+            Some(method) => *StackingSignatureMethod::from_str(&method, false)
+                .map_err(|e| ConfigError::BadField("key_rotation.method".to_string(), e))?
+                .topic(),
+            None => Pox4SignatureTopic::StackExtend,
+        };
+        let period = self.period.unwrap_or(DEFAULT_KEY_ROTATION_PERIOD);
+        if period == 0 {
+            return Err(ConfigError::BadField(
+                "key_rotation.period".to_string(),
+                period.to_string(),
+            ));
+        }
+        Ok(KeyRotationConfig {
+            next_stacks_private_key,
+            pox_address,
+            method,
+            period,
+            max_amount: self.max_amount.unwrap_or(u128::MAX),
+            auth_id: self.auth_id,
+            submit_authorization: self.submit_authorization.unwrap_or(false),
+            tx_fee_ustx: self.tx_fee_ustx.unwrap_or(DEFAULT_KEY_ROTATION_TX_FEE_USTX),
+        })
+    }
 }
 
 /// Internal struct for loading up the config file
@@ -248,6 +353,11 @@ struct RawConfigFile {
     pub block_proposal_max_age_secs: Option<u64>,
     /// Is this signer binary going to be running in dry-run mode?
     pub dry_run: Option<bool>,
+    /// The key to rotate to in an upcoming reward cycle, if any
+    pub key_rotation: Option<RawKeyRotationConfig>,
+    /// Publish a signed checkpoint of every globally accepted block whose height is a multiple
+    /// of this many blocks to the `.checkpoints` StackerDB.  0 disables checkpoints.
+    pub checkpoint_interval: Option<u64>,
 }
 
 impl RawConfigFile {
@@ -351,6 +461,15 @@ impl TryFrom<RawConfigFile> for GlobalConfig {
 
         let dry_run = raw_data.dry_run.unwrap_or(DEFAULT_DRY_RUN);
 
+        let key_rotation = raw_data
+            .key_rotation
+            .map(|key_rotation| key_rotation.try_into_config(&stacks_private_key))
+            .transpose()?;
+
+        let checkpoint_interval = raw_data
+            .checkpoint_interval
+            .unwrap_or(DEFAULT_CHECKPOINT_INTERVAL);
+
         Ok(Self {
             node_host: raw_data.node_host,
             endpoint,
@@ -369,6 +488,8 @@ impl TryFrom<RawConfigFile> for GlobalConfig {
             tenure_idle_timeout,
             block_proposal_max_age_secs,
             dry_run,
+            key_rotation,
+            checkpoint_interval,
         })
     }
 }
@@ -681,4 +802,23 @@ db_path = ":memory:"
         let global_config = GlobalConfig::try_from(config).unwrap();
         assert_eq!(global_config.to_chain_id(), 0x80000100);
     }
+
+    #[test]
+    fn test_checkpoint_interval() {
+        let config_toml = r#"
+stacks_private_key = "eb05c83546fdd2c79f10f5ad5434a90dd28f7e3acb7c092157aa1bc3656b012c01"
+node_host = "localhost"
+endpoint = "localhost:30000"
+network = "testnet"
+auth_password = "abcd"
+db_path = ":memory:"
+"#;
+        let config = GlobalConfig::load_from_str(config_toml).unwrap();
+        assert_eq!(config.checkpoint_interval, DEFAULT_CHECKPOINT_INTERVAL);
+
+        let config =
+            GlobalConfig::load_from_str(&format!("{config_toml}checkpoint_interval = 0\n"))
+                .unwrap();
+        assert_eq!(config.checkpoint_interval, 0);
+    }
 }
@@ -0,0 +1,390 @@
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Rotation of the signer's key across reward cycles (the `[key_rotation]` config section).
+//!
+//! A signer's key is registered for a reward cycle by the stacking operation (e.g.
+//! `stack-extend` or `stack-aggregation-commit`) which its stacker submits in the cycle before.
+//! To rotate to a new key, the new key must authorize that operation, and the signer must
+//! switch to the new key (and its StackerDB slot) exactly when the reward set it was registered
+//! in becomes active.
+//!
+//! Until the next key appears in a reward set, the signer authorizes it once per reward cycle:
+//! either by submitting a `set-signer-key-authorization` transaction from the next key's
+//! address (`submit_authorization = true`), or by logging a signer signature and the call the
+//! stacker must make.  Each reward cycle's signer then uses whichever of its keys is registered
+//! in that cycle's reward set, so the switch happens at the cycle boundary while the previous
+//! cycle's signer finishes with the old key.
+
+use blockstack_lib::burnchains::Txid;
+use blockstack_lib::chainstate::stacks::boot::POX_4_NAME;
+use blockstack_lib::chainstate::stacks::{
+    StacksTransaction, StacksTransactionSigner, TransactionVersion,
+};
+use blockstack_lib::util_lib::boot::boot_code_addr;
+use blockstack_lib::util_lib::signed_structured_data::pox4::{
+    make_pox_4_signer_key_signature, Pox4SignatureTopic,
+};
+use clarity::util::hash::to_hex;
+use clarity::util::secp256k1::MessageSignature;
+use clarity::vm::{ClarityName, ContractName, Value as ClarityValue};
+use slog::{slog_info, slog_warn};
+use stacks_common::types::chainstate::{StacksAddress, StacksPrivateKey, StacksPublicKey};
+use stacks_common::{info, warn};
+
+use crate::client::{ClientError, StacksClient};
+use crate::config::{GlobalConfig, KeyRotationConfig};
+
+/// Tracks the signer's rotation to its next key
+#[derive(Debug, Clone)]
+pub struct KeyRotation {
+    /// The rotation settings
+    config: KeyRotationConfig,
+    /// The signer's current private key
+    current_private_key: StacksPrivateKey,
+    /// Whether the signer is running on mainnet
+    mainnet: bool,
+    /// The chain ID of the network
+    chain_id: u32,
+    /// The first reward cycle whose reward set includes the next key
+    activation_cycle: Option<u64>,
+    /// The last reward cycle in which the next key was authorized
+    last_authorized_cycle: Option<u64>,
+}
+
+impl KeyRotation {
+    /// Set up the key rotation described in `config`, if there is one
+    pub fn new(config: &GlobalConfig) -> Option<Self> {
+        Some(Self {
+            config: config.key_rotation.clone()?,
+            current_private_key: config.stacks_private_key,
+            mainnet: config.network.is_mainnet(),
+            chain_id: config.to_chain_id(),
+            activation_cycle: None,
+            last_authorized_cycle: None,
+        })
+    }
+
+    /// The first reward cycle in which the signer uses its next key, once it is known
+    pub const fn activation_cycle(&self) -> Option<u64> {
+        self.activation_cycle
+    }
+
+    /// The address of the signer's next key
+    pub fn next_address(&self) -> StacksAddress {
+        StacksAddress::p2pkh(
+            self.mainnet,
+            &StacksPublicKey::from_private(&self.config.next_stacks_private_key),
+        )
+    }
+
+    /// Choose the key to sign with in `reward_cycle`: the next key if `is_registered` says its
+    /// address is in the cycle's signer set, and the current key otherwise.
+    pub fn key_for_cycle<F>(&mut self, reward_cycle: u64, is_registered: F) -> StacksPrivateKey
+    where
+        F: Fn(&StacksAddress) -> bool,
+    {
+        if !is_registered(&self.next_address()) {
+            return self.current_private_key;
+        }
+        if self
+            .activation_cycle
+            .map_or(true, |cycle| reward_cycle < cycle)
+        {
+            info!(
+                "Signer key rotation: next key {} is registered as of reward cycle {reward_cycle}",
+                self.next_address()
+            );
+            self.activation_cycle = Some(reward_cycle);
+        }
+        self.config.next_stacks_private_key
+    }
+
+    /// The reward cycle that an authorization made in `current_reward_cycle` must name.  The
+    /// aggregation functions name the cycle being committed to; the others act on the current
+    /// cycle.
+    pub const fn authorization_cycle(&self, current_reward_cycle: u64) -> u64 {
+        match self.config.method {
+            Pox4SignatureTopic::AggregationCommit | Pox4SignatureTopic::AggregationIncrease => {
+                current_reward_cycle.saturating_add(1)
+            }
+            Pox4SignatureTopic::StackStx
+            | Pox4SignatureTopic::StackExtend
+            | Pox4SignatureTopic::StackIncrease => current_reward_cycle,
+        }
+    }
+
+    /// The authorization ID for an authorization naming `authorization_cycle`
+    pub fn auth_id(&self, authorization_cycle: u64) -> u128 {
+        self.config.auth_id.unwrap_or(authorization_cycle.into())
+    }
+
+    /// Sign the stacking operation which registers the next key, so that the stacker can pass
+    /// the signature to pox-4 instead of relying on `set-signer-key-authorization`
+    pub fn make_signature(&self, authorization_cycle: u64) -> Result<MessageSignature, String> {
+        make_pox_4_signer_key_signature(
+            &self.config.pox_address,
+            &self.config.next_stacks_private_key,
+            authorization_cycle.into(),
+            &self.config.method,
+            self.chain_id,
+            self.config.period.into(),
+            self.config.max_amount,
+            self.auth_id(authorization_cycle),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    /// Build and sign a `set-signer-key-authorization` transaction, sent from the next key's
+    /// address, which authorizes the stacking operation which registers the next key
+    pub fn make_authorization_tx(
+        &self,
+        authorization_cycle: u64,
+        nonce: u64,
+    ) -> Result<StacksTransaction, ClientError> {
+        let pox_addr = self.config.pox_address.clone().as_clarity_tuple().ok_or(
+            ClientError::TransactionGenerationFailure(
+                "key_rotation.pox_address cannot be used with pox-4".into(),
+            ),
+        )?;
+        let function_args = [
+            ClarityValue::Tuple(pox_addr),
+            ClarityValue::UInt(self.config.period.into()),
+            ClarityValue::UInt(authorization_cycle.into()),
+            ClarityValue::string_ascii_from_bytes(
+                self.config.method.get_name_str().as_bytes().to_vec(),
+            )?,
+            ClarityValue::buff_from(self.next_public_key_bytes())?,
+            ClarityValue::Bool(true),
+            ClarityValue::UInt(self.config.max_amount),
+            ClarityValue::UInt(self.auth_id(authorization_cycle)),
+        ];
+        let tx_version = if self.mainnet {
+            TransactionVersion::Mainnet
+        } else {
+            TransactionVersion::Testnet
+        };
+        let mut unsigned_tx = StacksClient::build_unsigned_contract_call_transaction(
+            &boot_code_addr(self.mainnet),
+            ContractName::from(POX_4_NAME),
+            ClarityName::from("set-signer-key-authorization"),
+            &function_args,
+            &self.config.next_stacks_private_key,
+            tx_version,
+            self.chain_id,
+            nonce,
+        )?;
+        unsigned_tx.set_tx_fee(self.config.tx_fee_ustx);
+
+        let mut tx_signer = StacksTransactionSigner::new(&unsigned_tx);
+        tx_signer
+            .sign_origin(&self.config.next_stacks_private_key)
+            .map_err(|e| ClientError::TransactionGenerationFailure(e.to_string()))?;
+        tx_signer
+            .get_tx()
+            .ok_or(ClientError::TransactionGenerationFailure(
+                "Failed to generate transaction from a transaction signer".to_string(),
+            ))
+    }
+
+    /// Describe the stacking operation the stacker must submit in `current_reward_cycle` to
+    /// register the next key
+    pub fn instructions(&self, current_reward_cycle: u64) -> Result<String, String> {
+        let authorization_cycle = self.authorization_cycle(current_reward_cycle);
+        let signature = self.make_signature(authorization_cycle)?;
+        let function_name = match self.config.method {
+            Pox4SignatureTopic::StackStx => "stack-stx",
+            Pox4SignatureTopic::StackExtend => "stack-extend",
+            Pox4SignatureTopic::StackIncrease => "stack-increase",
+            Pox4SignatureTopic::AggregationCommit => "stack-aggregation-commit-indexed",
+            Pox4SignatureTopic::AggregationIncrease => "stack-aggregation-increase",
+        };
+        Ok(format!(
+            "Call pox-4 `{function_name}` during reward cycle {current_reward_cycle} with \
+             signer-key 0x{signer_key}, pox-addr {pox_addr}, and either signer-sig \
+             (some 0x{signature}) or none if the authorization transaction has been confirmed \
+             (reward-cycle {authorization_cycle}, period {period}, max-amount {max_amount}, \
+             auth-id {auth_id})",
+            signer_key = to_hex(&self.next_public_key_bytes()),
+            pox_addr = self.config.pox_address.clone().to_b58(),
+            signature = to_hex(signature.to_rsv().as_slice()),
+            period = self.config.period,
+            max_amount = self.config.max_amount,
+            auth_id = self.auth_id(authorization_cycle),
+        ))
+    }
+
+    /// Authorize the next key for the stacking operation in `current_reward_cycle`, unless it
+    /// is already registered or was already authorized in this cycle.  Returns the txid of the
+    /// `set-signer-key-authorization` transaction, if one was submitted.
+    pub fn authorize_next_key(
+        &mut self,
+        stacks_client: &StacksClient,
+        current_reward_cycle: u64,
+    ) -> Option<Txid> {
+        if self
+            .activation_cycle
+            .is_some_and(|cycle| cycle <= current_reward_cycle.saturating_add(1))
+            || self.last_authorized_cycle == Some(current_reward_cycle)
+        {
+            return None;
+        }
+
+        match self.instructions(current_reward_cycle) {
+            Ok(instructions) => info!("Signer key rotation: {instructions}"),
+            Err(e) => {
+                warn!("Signer key rotation: failed to sign the next key's authorization: {e}")
+            }
+        }
+        if !self.config.submit_authorization {
+            self.last_authorized_cycle = Some(current_reward_cycle);
+            return None;
+        }
+
+        let authorization_cycle = self.authorization_cycle(current_reward_cycle);
+        let result = stacks_client
+            .get_account_entry(&self.next_address())
+            .and_then(|account| self.make_authorization_tx(authorization_cycle, account.nonce))
+            .and_then(|tx| stacks_client.submit_transaction(&tx));
+        match result {
+            Ok(txid) => {
+                info!(
+                    "Signer key rotation: submitted set-signer-key-authorization for reward cycle {authorization_cycle}";
+                    "txid" => %txid,
+                    "address" => %self.next_address(),
+                );
+                self.last_authorized_cycle = Some(current_reward_cycle);
+                Some(txid)
+            }
+            Err(e) => {
+                warn!("Signer key rotation: failed to submit set-signer-key-authorization: {e}. Will try again later.");
+                None
+            }
+        }
+    }
+
+    fn next_public_key_bytes(&self) -> Vec<u8> {
+        StacksPublicKey::from_private(&self.config.next_stacks_private_key).to_bytes_compressed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blockstack_lib::chainstate::stacks::TransactionPayload;
+    use blockstack_lib::util_lib::signed_structured_data::pox4::make_pox_4_signer_key_message_hash;
+    use stacks_common::types::PublicKey;
+
+    use super::*;
+
+    const NEXT_KEY: &str = "7287ba251d44a4d3fd9276c88ce34c5c52a038955511cccaf77e61068649c17801";
+
+    fn make_rotation(extra: &str) -> KeyRotation {
+        let config_toml = format!(
+            r#"
+stacks_private_key = "2de4e77aab89c0c2570bb8bb90824f5cf2a5204a975905fee450ff9dad0fcf2801"
+node_host = "localhost"
+endpoint = "localhost:30000"
+network = "testnet"
+auth_password = "abcd"
+db_path = ":memory:"
+
+[key_rotation]
+next_stacks_private_key = "{NEXT_KEY}"
+pox_address = "1Xik14zRm29UsyS6DjhYg4iZeZqsDa8D3"
+{extra}
+"#
+        );
+        let config = GlobalConfig::load_from_str(&config_toml).unwrap();
+        KeyRotation::new(&config).unwrap()
+    }
+
+    #[test]
+    fn key_for_cycle_switches_once_registered() {
+        let mut rotation = make_rotation("");
+        let current_key = rotation.current_private_key;
+        let next_key = StacksPrivateKey::from_hex(NEXT_KEY).unwrap();
+        let next_address = rotation.next_address();
+
+        assert_eq!(rotation.key_for_cycle(10, |_| false), current_key);
+        assert!(rotation.activation_cycle().is_none());
+
+        assert_eq!(
+            rotation.key_for_cycle(11, |addr| *addr == next_address),
+            next_key
+        );
+        assert_eq!(rotation.activation_cycle(), Some(11));
+
+        // later cycles do not move the activation cycle
+        assert_eq!(rotation.key_for_cycle(12, |_| true), next_key);
+        assert_eq!(rotation.activation_cycle(), Some(11));
+    }
+
+    #[test]
+    fn authorization_cycle_depends_on_method() {
+        let rotation = make_rotation("");
+        assert_eq!(rotation.authorization_cycle(5), 5);
+        assert_eq!(rotation.auth_id(5), 5);
+
+        let rotation = make_rotation("method = \"aggregation-commit\"\nauth_id = 42");
+        assert_eq!(rotation.authorization_cycle(5), 6);
+        assert_eq!(rotation.auth_id(6), 42);
+    }
+
+    #[test]
+    fn signature_verifies_against_next_key() {
+        let rotation = make_rotation("period = 3\nmax_amount = 1000");
+        let signature = rotation.make_signature(7).unwrap();
+        let msg_hash = make_pox_4_signer_key_message_hash(
+            &rotation.config.pox_address,
+            7,
+            &Pox4SignatureTopic::StackExtend,
+            rotation.chain_id,
+            3,
+            1000,
+            7,
+        );
+        let next_public_key =
+            StacksPublicKey::from_private(&StacksPrivateKey::from_hex(NEXT_KEY).unwrap());
+        assert!(next_public_key
+            .verify(msg_hash.as_bytes(), &signature)
+            .unwrap());
+
+        let instructions = rotation.instructions(7).unwrap();
+        assert!(instructions.contains("`stack-extend` during reward cycle 7"));
+        assert!(instructions.contains(&to_hex(&rotation.next_public_key_bytes())));
+        assert!(instructions.contains(&to_hex(signature.to_rsv().as_slice())));
+    }
+
+    #[test]
+    fn authorization_tx_is_sent_from_next_key() {
+        let rotation = make_rotation("tx_fee_ustx = 1234");
+        let tx = rotation.make_authorization_tx(8, 3).unwrap();
+        assert_eq!(tx.get_origin_nonce(), 3);
+        assert_eq!(tx.get_tx_fee(), 1234);
+        assert_eq!(tx.origin_address(), rotation.next_address());
+        let TransactionPayload::ContractCall(call) = &tx.payload else {
+            panic!("Expected a contract call");
+        };
+        assert_eq!(call.contract_name.as_str(), POX_4_NAME);
+        assert_eq!(call.function_name.as_str(), "set-signer-key-authorization");
+        assert_eq!(call.function_args.len(), 8);
+        assert_eq!(call.function_args[2], ClarityValue::UInt(8));
+        assert_eq!(
+            call.function_args[4],
+            ClarityValue::buff_from(rotation.next_public_key_bytes()).unwrap()
+        );
+        assert_eq!(call.function_args[5], ClarityValue::Bool(true));
+    }
+}
@@ -29,10 +29,14 @@ pub mod cli;
 pub mod client;
 /// The configuration module for the signer
 pub mod config;
+/// Rotation of the signer's key across reward cycles
+pub mod key_rotation;
 /// The signer monitor for observing signer behaviours in the network
 pub mod monitor_signers;
 /// The monitoring server for the signer
 pub mod monitoring;
+/// Signed exports of the signer's decisions on block proposals
+pub mod proposal_audit;
 /// The primary runloop for the signer
 pub mod runloop;
 /// The signer state module
@@ -26,7 +26,8 @@ extern crate serde;
 extern crate serde_json;
 extern crate toml;
 
-use std::io::{self, Write};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 
 use blockstack_lib::util_lib::signed_structured_data::pox4::make_pox_4_signer_key_signature;
 use clap::Parser;
@@ -35,16 +36,19 @@ use clarity::util::sleep_ms;
 use libsigner::{SignerSession, VERSION_STRING};
 use libstackerdb::StackerDBChunkData;
 use slog::{slog_debug, slog_error};
+use stacks_common::util::get_epoch_time_secs;
 use stacks_common::util::hash::to_hex;
 use stacks_common::util::secp256k1::MessageSignature;
 use stacks_common::{debug, error};
 use stacks_signer::cli::{
-    Cli, Command, GenerateStackingSignatureArgs, GenerateVoteArgs, GetChunkArgs,
+    Cli, Command, ExportAuditArgs, GenerateStackingSignatureArgs, GenerateVoteArgs, GetChunkArgs,
     GetLatestChunkArgs, MonitorSignersArgs, PutChunkArgs, RunSignerArgs, StackerDBArgs,
     VerifyVoteArgs,
 };
 use stacks_signer::config::GlobalConfig;
 use stacks_signer::monitor_signers::SignerMonitor;
+use stacks_signer::proposal_audit::export_proposal_audit;
+use stacks_signer::signerdb::SignerDb;
 use stacks_signer::utils::stackerdb_session;
 use stacks_signer::v0::SpawnedSigner;
 use tracing_subscriber::prelude::*;
@@ -184,6 +188,27 @@ fn handle_verify_vote(args: VerifyVoteArgs, do_print: bool) -> bool {
     valid_vote
 }
 
+fn handle_export_audit(args: ExportAuditArgs) {
+    let config = GlobalConfig::try_from(&args.config).unwrap();
+    let signer_db = SignerDb::new(&config.db_path).expect("Failed to open the signer database");
+    let to = args.to.unwrap_or_else(get_epoch_time_secs);
+    let mut out: Box<dyn Write> = match args.output.as_ref() {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).expect("Failed to create the export file"),
+        )),
+        None => Box::new(io::stdout().lock()),
+    };
+    let num_entries = export_proposal_audit(
+        &signer_db,
+        args.from,
+        to,
+        &config.stacks_private_key,
+        &mut out,
+    )
+    .expect("Failed to export the proposal audit log");
+    eprintln!("Exported {num_entries} block proposal decisions");
+}
+
 fn handle_monitor_signers(args: MonitorSignersArgs) {
     // Verify that the host is a valid URL
     let mut signer_monitor = SignerMonitor::new(args);
@@ -237,6 +262,9 @@ fn main() {
         Command::MonitorSigners(args) => {
             handle_monitor_signers(args);
         }
+        Command::ExportAudit(args) => {
+            handle_export_audit(args);
+        }
     }
 }
 
@@ -0,0 +1,190 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signed exports of the signer's proposal audit log (`stacks-signer export-audit`).
+//!
+//! Each line of an export is a JSON-encoded `SignedProposalAuditEntry`: one decision the signer
+//! made on a block proposal, the signer's public key, and the signer's signature over the
+//! SHA-256 hash of the entry's JSON encoding.  Anyone holding an export can check that each
+//! decision was made by the signer that owns the key, e.g. to settle a dispute about a block
+//! rejection.
+
+use std::io::Write;
+
+use blockstack_lib::util_lib::db::Error as DBError;
+use clarity::types::chainstate::{StacksPrivateKey, StacksPublicKey};
+use clarity::types::{PrivateKey, PublicKey};
+use clarity::util::hash::{hex_bytes, to_hex, Sha256Sum};
+use clarity::util::secp256k1::MessageSignature;
+use serde::{Deserialize, Serialize};
+
+use crate::signerdb::{ProposalAuditEntry, SignerDb};
+
+/// A proposal audit log entry, signed by the signer that made the decision
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedProposalAuditEntry {
+    /// The signer's decision on a block proposal
+    pub entry: ProposalAuditEntry,
+    /// The signer's compressed public key, in hex
+    pub signer_public_key: String,
+    /// The signer's signature over `SignedProposalAuditEntry::digest(entry)`
+    pub signature: MessageSignature,
+}
+
+impl SignedProposalAuditEntry {
+    /// Get the digest to sign that authenticates an audit log entry
+    pub fn digest(entry: &ProposalAuditEntry) -> Sha256Sum {
+        let entry_json =
+            serde_json::to_vec(entry).expect("FATAL: failed to serialize audit log entry");
+        Sha256Sum::from_data(&entry_json)
+    }
+
+    /// Sign an audit log entry
+    pub fn sign(
+        entry: ProposalAuditEntry,
+        private_key: &StacksPrivateKey,
+    ) -> Result<Self, &'static str> {
+        let signature = private_key.sign(Self::digest(&entry).as_bytes())?;
+        let public_key = StacksPublicKey::from_private(private_key);
+        Ok(Self {
+            entry,
+            signer_public_key: to_hex(&public_key.to_bytes_compressed()),
+            signature,
+        })
+    }
+
+    /// Verify the signature against the entry and `signer_public_key`
+    pub fn verify(&self) -> Result<bool, &'static str> {
+        let public_key_bytes =
+            hex_bytes(&self.signer_public_key).map_err(|_| "Invalid public key hex")?;
+        let public_key = StacksPublicKey::from_slice(&public_key_bytes)?;
+        public_key.verify(Self::digest(&self.entry).as_bytes(), &self.signature)
+    }
+}
+
+/// Write the decisions the signer made on block proposals between `from` and `to` (epoch time
+/// in seconds, inclusive) to `out` as JSON lines, each signed with `private_key`.  Returns how
+/// many decisions were written.
+pub fn export_proposal_audit(
+    signer_db: &SignerDb,
+    from: u64,
+    to: u64,
+    private_key: &StacksPrivateKey,
+    out: &mut impl Write,
+) -> Result<usize, DBError> {
+    let entries = signer_db.get_proposal_audit_entries(from, to)?;
+    let num_entries = entries.len();
+    for entry in entries {
+        let signed_entry =
+            SignedProposalAuditEntry::sign(entry, private_key).map_err(DBError::Other)?;
+        serde_json::to_writer(&mut *out, &signed_entry).map_err(DBError::SerializationError)?;
+        out.write_all(b"\n").map_err(DBError::IOError)?;
+    }
+    out.flush().map_err(DBError::IOError)?;
+    Ok(num_entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use blockstack_lib::chainstate::nakamoto::{NakamotoBlock, NakamotoBlockHeader};
+    use libsigner::v0::messages::{BlockResponse, RejectCode};
+    use libsigner::BlockProposal;
+    use stacks_common::util::hash::Sha512Trunc256Sum;
+
+    use super::*;
+    use crate::signerdb::{BlockInfo, ProposalDecision};
+
+    #[test]
+    fn export_signed_proposal_audit() {
+        let db_path = std::env::temp_dir().join(format!(
+            "stacks-signer-audit-test-{}.sqlite",
+            rand::random::<u64>()
+        ));
+        let signer_db = SignerDb::new(db_path).unwrap();
+        let private_key = StacksPrivateKey::random();
+        let block_info = BlockInfo::from(BlockProposal {
+            block: NakamotoBlock {
+                header: NakamotoBlockHeader::empty(),
+                txs: vec![],
+            },
+            burn_height: 7,
+            reward_cycle: 42,
+        });
+        let rejection = BlockResponse::rejected(
+            block_info.signer_signature_hash(),
+            RejectCode::SortitionViewMismatch,
+            &private_key,
+            false,
+            0,
+        );
+        let acceptance = BlockResponse::accepted(
+            block_info.signer_signature_hash(),
+            MessageSignature::empty(),
+            0,
+        );
+        for (decided_time, response) in [(100, &rejection), (200, &acceptance)] {
+            let mut entry = ProposalAuditEntry::new(&block_info, response);
+            entry.decided_time = decided_time;
+            signer_db.insert_proposal_audit_entry(&entry).unwrap();
+        }
+
+        let mut out = vec![];
+        let num_entries =
+            export_proposal_audit(&signer_db, 0, 150, &private_key, &mut out).unwrap();
+        assert_eq!(num_entries, 1);
+        let lines: Vec<_> = std::str::from_utf8(&out).unwrap().lines().collect();
+        assert_eq!(lines.len(), 1);
+
+        let signed_entry: SignedProposalAuditEntry = serde_json::from_str(lines[0]).unwrap();
+        assert!(signed_entry.verify().unwrap());
+        assert_eq!(signed_entry.entry.decision, ProposalDecision::Rejected);
+        assert_eq!(signed_entry.entry.decided_time, 100);
+        assert_eq!(
+            signed_entry.entry.reject_code.as_deref(),
+            Some("SortitionViewMismatch")
+        );
+        assert_eq!(
+            signed_entry.signer_public_key,
+            to_hex(&StacksPublicKey::from_private(&private_key).to_bytes_compressed())
+        );
+
+        // a tampered entry no longer verifies
+        let mut tampered = signed_entry.clone();
+        tampered.entry.decision = ProposalDecision::Accepted;
+        assert!(!tampered.verify().unwrap());
+        let mut tampered = signed_entry;
+        tampered.entry.signer_signature_hash = Sha512Trunc256Sum([0x01; 32]);
+        assert!(!tampered.verify().unwrap());
+
+        let mut out = vec![];
+        let num_entries =
+            export_proposal_audit(&signer_db, 0, u64::MAX, &private_key, &mut out).unwrap();
+        assert_eq!(num_entries, 2);
+        let decisions: Vec<_> = std::str::from_utf8(&out)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<SignedProposalAuditEntry>(line)
+                    .unwrap()
+                    .entry
+                    .decision
+            })
+            .collect();
+        assert_eq!(
+            decisions,
+            vec![ProposalDecision::Rejected, ProposalDecision::Accepted]
+        );
+    }
+}
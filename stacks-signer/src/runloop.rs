@@ -21,11 +21,13 @@ use clarity::codec::StacksMessageCodec;
 use hashbrown::HashMap;
 use libsigner::{SignerEntries, SignerEvent, SignerRunLoop};
 use slog::{slog_debug, slog_error, slog_info, slog_warn};
+use stacks_common::types::chainstate::{StacksAddress, StacksPublicKey};
 use stacks_common::{debug, error, info, warn};
 
 use crate::chainstate::SortitionsView;
 use crate::client::{retry_with_exponential_backoff, ClientError, StacksClient};
 use crate::config::{GlobalConfig, SignerConfig, SignerConfigMode};
+use crate::key_rotation::KeyRotation;
 #[cfg(any(test, feature = "testing"))]
 use crate::v0::tests::TEST_SKIP_SIGNER_CLEANUP;
 use crate::Signer as SignerTrait;
@@ -188,12 +190,15 @@ where
     pub current_reward_cycle_info: Option<RewardCycleInfo>,
     /// Cache sortitin data from `stacks-node`
     pub sortition_state: Option<SortitionsView>,
+    /// The rotation to the signer's next key, if one is configured
+    pub key_rotation: Option<KeyRotation>,
 }
 
 impl<Signer: SignerTrait<T>, T: StacksMessageCodec + Clone + Send + Debug> RunLoop<Signer, T> {
     /// Create a new signer runloop from the provided configuration
     pub fn new(config: GlobalConfig) -> Self {
         let stacks_client = StacksClient::from(&config);
+        let key_rotation = KeyRotation::new(&config);
         Self {
             config,
             stacks_client,
@@ -201,6 +206,7 @@ impl<Signer: SignerTrait<T>, T: StacksMessageCodec + Clone + Send + Debug> RunLo
             state: State::Uninitialized,
             current_reward_cycle_info: None,
             sortition_state: None,
+            key_rotation,
         }
     }
     /// Get the registered signers for a specific reward cycle
@@ -263,7 +269,18 @@ impl<Signer: SignerTrait<T>, T: StacksMessageCodec + Clone + Send + Debug> RunLo
             })?;
 
         let dry_run = self.config.dry_run;
-        let current_addr = self.stacks_client.get_signer_address();
+        // Sign with the next key once the reward set registers it, so that the signer switches
+        // keys (and StackerDB slots) at the reward cycle boundary.
+        let stacks_private_key = match self.key_rotation.as_mut() {
+            Some(key_rotation) => key_rotation.key_for_cycle(reward_cycle, |addr| {
+                signer_entries.signer_addr_to_id.contains_key(addr)
+            }),
+            None => self.config.stacks_private_key,
+        };
+        let current_addr = &StacksAddress::p2pkh(
+            self.config.network.is_mainnet(),
+            &StacksPublicKey::from_private(&stacks_private_key),
+        );
 
         let signer_config_mode = if !dry_run {
             let Some(signer_slot_id) = signer_slot_ids.get(current_addr) else {
@@ -306,7 +323,7 @@ impl<Signer: SignerTrait<T>, T: StacksMessageCodec + Clone + Send + Debug> RunLo
             signer_entries,
             signer_slot_ids: signer_slot_ids.into_values().collect(),
             first_proposal_burn_block_timing: self.config.first_proposal_burn_block_timing,
-            stacks_private_key: self.config.stacks_private_key,
+            stacks_private_key,
             node_host: self.config.node_host.to_string(),
             mainnet: self.config.network.is_mainnet(),
             db_path: self.config.db_path.clone(),
@@ -315,6 +332,8 @@ impl<Signer: SignerTrait<T>, T: StacksMessageCodec + Clone + Send + Debug> RunLo
             block_proposal_validation_timeout: self.config.block_proposal_validation_timeout,
             tenure_idle_timeout: self.config.tenure_idle_timeout,
             block_proposal_max_age_secs: self.config.block_proposal_max_age_secs,
+            chain_id: self.config.to_chain_id(),
+            checkpoint_interval: self.config.checkpoint_interval,
         }))
     }
 
@@ -356,6 +375,7 @@ impl<Signer: SignerTrait<T>, T: StacksMessageCodec + Clone + Send + Debug> RunLo
             self.refresh_signer_config(current_reward_cycle.saturating_add(1));
         }
         self.current_reward_cycle_info = Some(reward_cycle_info);
+        self.authorize_next_key(current_reward_cycle);
         if self.stacks_signers.is_empty() {
             self.state = State::NoRegisteredSigners;
         } else {
@@ -418,6 +438,7 @@ impl<Signer: SignerTrait<T>, T: StacksMessageCodec + Clone + Send + Debug> RunLo
             self.refresh_signer_config(next_reward_cycle);
         }
 
+        self.authorize_next_key(current_reward_cycle);
         self.cleanup_stale_signers(current_reward_cycle);
         if self.stacks_signers.is_empty() {
             self.state = State::NoRegisteredSigners;
@@ -427,6 +448,14 @@ impl<Signer: SignerTrait<T>, T: StacksMessageCodec + Clone + Send + Debug> RunLo
         Ok(())
     }
 
+    /// Authorize the signer's next key for the current reward cycle's stacking operation, if a
+    /// key rotation is configured
+    fn authorize_next_key(&mut self, current_reward_cycle: u64) {
+        if let Some(key_rotation) = self.key_rotation.as_mut() {
+            key_rotation.authorize_next_key(&self.stacks_client, current_reward_cycle);
+        }
+    }
+
     fn is_configured_for_cycle(
         stacks_signers: &HashMap<u64, ConfiguredSigner<Signer, T>>,
         reward_cycle: u64,
@@ -22,11 +22,10 @@ use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
 use blockstack_lib::chainstate::stacks::TransactionPayload;
 use blockstack_lib::util_lib::db::{
     query_row, query_rows, sqlite_open, table_exists, tx_begin_immediate, u64_to_sql,
-    Error as DBError,
+    Error as DBError, FromColumn, FromRow,
 };
-#[cfg(any(test, feature = "testing"))]
-use blockstack_lib::util_lib::db::{FromColumn, FromRow};
-use clarity::types::chainstate::{BurnchainHeaderHash, StacksAddress};
+use clarity::types::chainstate::{BurnchainHeaderHash, StacksAddress, StacksBlockId};
+use libsigner::v0::messages::BlockResponse;
 use libsigner::BlockProposal;
 use rusqlite::functions::FunctionFlags;
 use rusqlite::{
@@ -284,6 +283,112 @@ impl BlockInfo {
     }
 }
 
+/// The signer's decision on a block proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProposalDecision {
+    /// The signer accepted (signed) the block
+    Accepted,
+    /// The signer rejected the block
+    Rejected,
+}
+
+impl Display for ProposalDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let decision = match self {
+            ProposalDecision::Accepted => "accepted",
+            ProposalDecision::Rejected => "rejected",
+        };
+        write!(f, "{decision}")
+    }
+}
+
+impl TryFrom<&str> for ProposalDecision {
+    type Error = String;
+    fn try_from(value: &str) -> Result<ProposalDecision, String> {
+        match value {
+            "accepted" => Ok(ProposalDecision::Accepted),
+            "rejected" => Ok(ProposalDecision::Rejected),
+            _ => Err("Unparsable proposal decision".into()),
+        }
+    }
+}
+
+/// A decision this signer made on a block proposal, as recorded in the proposal audit log.
+/// Unlike the `blocks` table, the audit log also records proposals that were rejected before
+/// being submitted for validation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposalAuditEntry {
+    /// The signer signature hash of the proposed block
+    pub signer_signature_hash: Sha512Trunc256Sum,
+    /// The ID of the proposed block
+    pub block_id: StacksBlockId,
+    /// The reward cycle the block belongs to
+    pub reward_cycle: u64,
+    /// The tenure of the proposed block
+    pub consensus_hash: ConsensusHash,
+    /// The height of the proposed block
+    pub stacks_height: u64,
+    /// The burn block height at which the block was proposed
+    pub burn_block_height: u64,
+    /// Time at which the proposal was received by this signer (epoch time in seconds)
+    pub proposed_time: u64,
+    /// Time at which this signer accepted or rejected the block (epoch time in seconds)
+    pub decided_time: u64,
+    /// Whether this signer accepted or rejected the block
+    pub decision: ProposalDecision,
+    /// The reject code sent with the rejection, if the block was rejected
+    pub reject_code: Option<String>,
+    /// The reason sent with the rejection, if the block was rejected
+    pub reason: Option<String>,
+}
+
+impl ProposalAuditEntry {
+    /// Record this signer's `response` to the proposed block in `block_info`
+    pub fn new(block_info: &BlockInfo, response: &BlockResponse) -> Self {
+        let (decision, reject_code, reason) = match response {
+            BlockResponse::Accepted(_) => (ProposalDecision::Accepted, None, None),
+            BlockResponse::Rejected(rejection) => (
+                ProposalDecision::Rejected,
+                Some(format!("{:?}", rejection.reason_code)),
+                Some(rejection.reason.clone()),
+            ),
+        };
+        Self {
+            signer_signature_hash: block_info.signer_signature_hash(),
+            block_id: block_info.block.block_id(),
+            reward_cycle: block_info.reward_cycle,
+            consensus_hash: block_info.block.header.consensus_hash,
+            stacks_height: block_info.block.header.chain_length,
+            burn_block_height: block_info.burn_block_height,
+            proposed_time: block_info.proposed_time,
+            decided_time: get_epoch_time_secs(),
+            decision,
+            reject_code,
+            reason,
+        }
+    }
+}
+
+impl FromRow<ProposalAuditEntry> for ProposalAuditEntry {
+    fn from_row(row: &rusqlite::Row) -> Result<Self, DBError> {
+        let decision: String = row.get("decision")?;
+        Ok(ProposalAuditEntry {
+            signer_signature_hash: Sha512Trunc256Sum::from_column(row, "signer_signature_hash")?,
+            block_id: StacksBlockId::from_column(row, "block_id")?,
+            reward_cycle: u64::from_column(row, "reward_cycle")?,
+            consensus_hash: ConsensusHash::from_column(row, "consensus_hash")?,
+            stacks_height: u64::from_column(row, "stacks_height")?,
+            burn_block_height: u64::from_column(row, "burn_block_height")?,
+            proposed_time: u64::from_column(row, "proposed_time")?,
+            decided_time: u64::from_column(row, "decided_time")?,
+            decision: ProposalDecision::try_from(decision.as_str()).map_err(DBError::Other)?,
+            reject_code: row.get("reject_code")?,
+            reason: row.get("reason")?,
+        })
+    }
+}
+
 /// This struct manages a SQLite database connection
 /// for the signer.
 #[derive(Debug)]
@@ -480,6 +585,31 @@ CREATE TABLE IF NOT EXISTS block_validations_pending (
     PRIMARY KEY (signer_signature_hash)
 ) STRICT;"#;
 
+static CREATE_PROPOSAL_AUDIT_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS proposal_audit (
+    -- the order in which the decisions were made
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signer_signature_hash TEXT NOT NULL,
+    block_id TEXT NOT NULL,
+    reward_cycle INTEGER NOT NULL,
+    consensus_hash TEXT NOT NULL,
+    stacks_height INTEGER NOT NULL,
+    burn_block_height INTEGER NOT NULL,
+    -- the time at which the signer received the proposal
+    proposed_time INTEGER NOT NULL,
+    -- the time at which the signer accepted or rejected the block
+    decided_time INTEGER NOT NULL,
+    -- `accepted` or `rejected`
+    decision TEXT NOT NULL,
+    -- the reject code and the reason given for it, if the block was rejected
+    reject_code TEXT,
+    reason TEXT
+) STRICT;"#;
+
+static CREATE_INDEXES_7: &str = r#"
+CREATE INDEX IF NOT EXISTS proposal_audit_on_decided_time ON proposal_audit(decided_time);
+"#;
+
 static SCHEMA_1: &[&str] = &[
     DROP_SCHEMA_0,
     CREATE_DB_CONFIG,
@@ -534,9 +664,15 @@ static SCHEMA_6: &[&str] = &[
     "INSERT OR REPLACE INTO db_config (version) VALUES (6);",
 ];
 
+static SCHEMA_7: &[&str] = &[
+    CREATE_PROPOSAL_AUDIT_TABLE,
+    CREATE_INDEXES_7,
+    "INSERT OR REPLACE INTO db_config (version) VALUES (7);",
+];
+
 impl SignerDb {
     /// The current schema version used in this build of the signer binary.
-    pub const SCHEMA_VERSION: u32 = 6;
+    pub const SCHEMA_VERSION: u32 = 7;
 
     /// Create a new `SignerState` instance.
     /// This will create a new SQLite database at the given path
@@ -650,6 +786,20 @@ impl SignerDb {
         Ok(())
     }
 
+    /// Migrate from schema 6 to schema 7
+    fn schema_7_migration(tx: &Transaction) -> Result<(), DBError> {
+        if Self::get_schema_version(tx)? >= 7 {
+            // no migration necessary
+            return Ok(());
+        }
+
+        for statement in SCHEMA_7.iter() {
+            tx.execute_batch(statement)?;
+        }
+
+        Ok(())
+    }
+
     /// Register custom scalar functions used by the database
     fn register_scalar_functions(&self) -> Result<(), DBError> {
         // Register helper function for determining if a block is a tenure change transaction
@@ -689,7 +839,8 @@ impl SignerDb {
                 3 => Self::schema_4_migration(&sql_tx)?,
                 4 => Self::schema_5_migration(&sql_tx)?,
                 5 => Self::schema_6_migration(&sql_tx)?,
-                6 => break,
+                6 => Self::schema_7_migration(&sql_tx)?,
+                7 => break,
                 x => return Err(DBError::Other(format!(
                     "Database schema is newer than supported by this binary. Expected version = {}, Database version = {x}",
                     Self::SCHEMA_VERSION,
@@ -1112,6 +1263,39 @@ impl SignerDb {
         self.remove_pending_block_validation(&block_info.signer_signature_hash())?;
         Ok(())
     }
+
+    /// Record a decision this signer made on a block proposal in the proposal audit log
+    pub fn insert_proposal_audit_entry(&self, entry: &ProposalAuditEntry) -> Result<(), DBError> {
+        self.db.execute(
+            "INSERT INTO proposal_audit (signer_signature_hash, block_id, reward_cycle, consensus_hash, stacks_height, burn_block_height, proposed_time, decided_time, decision, reject_code, reason) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                entry.signer_signature_hash.to_string(),
+                entry.block_id.to_hex(),
+                u64_to_sql(entry.reward_cycle)?,
+                entry.consensus_hash.to_hex(),
+                u64_to_sql(entry.stacks_height)?,
+                u64_to_sql(entry.burn_block_height)?,
+                u64_to_sql(entry.proposed_time)?,
+                u64_to_sql(entry.decided_time)?,
+                entry.decision.to_string(),
+                &entry.reject_code,
+                &entry.reason,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get the decisions this signer made on block proposals between `from` and `to` (epoch
+    /// time in seconds, inclusive), in the order in which they were made
+    pub fn get_proposal_audit_entries(
+        &self,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<ProposalAuditEntry>, DBError> {
+        let query = "SELECT signer_signature_hash, block_id, reward_cycle, consensus_hash, stacks_height, burn_block_height, proposed_time, decided_time, decision, reject_code, reason FROM proposal_audit WHERE decided_time >= ?1 AND decided_time <= ?2 ORDER BY id ASC";
+        let to = to.min(i64::MAX as u64);
+        query_rows(&self.db, query, params![u64_to_sql(from)?, u64_to_sql(to)?])
+    }
 }
 
 fn try_deserialize<T>(s: Option<String>) -> Result<Option<T>, DBError>
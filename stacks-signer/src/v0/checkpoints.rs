@@ -0,0 +1,135 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use blockstack_lib::chainstate::nakamoto::checkpoints::SignedChainCheckpoint;
+use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
+use blockstack_lib::chainstate::stacks::boot::CHECKPOINTS_NAME;
+use blockstack_lib::util_lib::boot::boot_code_id;
+use clarity::codec::StacksMessageCodec;
+use clarity::types::chainstate::StacksPrivateKey;
+use clarity::vm::types::QualifiedContractIdentifier;
+use libsigner::MessageSlotID as MessageSlotIDTrait;
+use slog::{slog_info, slog_warn};
+use stacks_common::{info, warn};
+
+use crate::client::StackerDB;
+use crate::config::SignerConfig;
+
+/// The (only) slot of the `.checkpoints` StackerDB a signer writes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CheckpointSlotID {
+    /// The signer's latest signed chain checkpoint
+    Checkpoint,
+}
+
+impl MessageSlotIDTrait for CheckpointSlotID {
+    fn stacker_db_contract(
+        &self,
+        mainnet: bool,
+        _reward_cycle: u64,
+    ) -> QualifiedContractIdentifier {
+        boot_code_id(CHECKPOINTS_NAME, mainnet)
+    }
+
+    fn all() -> &'static [Self] {
+        &[CheckpointSlotID::Checkpoint]
+    }
+}
+
+/// Publishes signed checkpoints of globally accepted blocks to the `.checkpoints` StackerDB,
+/// which nodes in initial block download use to spot a chain the signers never accepted
+#[derive(Debug)]
+pub struct CheckpointPublisher {
+    /// The `.checkpoints` StackerDB client
+    stackerdb: StackerDB<CheckpointSlotID>,
+    /// The signer's private key, which signs the checkpoints
+    private_key: StacksPrivateKey,
+    /// The chain ID of the network
+    chain_id: u32,
+    /// The reward cycle of the signer
+    reward_cycle: u64,
+    /// How many blocks apart checkpoints are (0 to publish none)
+    interval: u64,
+    /// Height of the last block checkpointed
+    last_checkpoint_height: Option<u64>,
+}
+
+impl From<&SignerConfig> for CheckpointPublisher {
+    fn from(signer_config: &SignerConfig) -> Self {
+        Self {
+            stackerdb: StackerDB::from(signer_config),
+            private_key: signer_config.stacks_private_key,
+            chain_id: signer_config.chain_id,
+            reward_cycle: signer_config.reward_cycle,
+            interval: signer_config.checkpoint_interval,
+            last_checkpoint_height: None,
+        }
+    }
+}
+
+impl CheckpointPublisher {
+    /// Whether or not a block at `block_height` should be checkpointed
+    pub fn is_checkpoint_height(&self, block_height: u64) -> bool {
+        if self.interval == 0 || block_height % self.interval != 0 {
+            return false;
+        }
+        match self.last_checkpoint_height {
+            Some(last_height) => block_height > last_height,
+            None => true,
+        }
+    }
+
+    /// Publish a checkpoint of `block`, which has been globally accepted, if its height is a
+    /// checkpoint height
+    pub fn maybe_publish(&mut self, block: &NakamotoBlock) {
+        let block_height = block.header.chain_length;
+        if !self.is_checkpoint_height(block_height) {
+            return;
+        }
+        let block_id = block.block_id();
+        let checkpoint = match SignedChainCheckpoint::new_signed(
+            self.chain_id,
+            self.reward_cycle,
+            block_height,
+            block_id,
+            &self.private_key,
+        ) {
+            Ok(checkpoint) => checkpoint,
+            Err(e) => {
+                warn!("Failed to sign chain checkpoint: {e}"; "block_id" => %block_id);
+                return;
+            }
+        };
+        // don't retry this height whether or not the write succeeds; the next checkpoint
+        // replaces it anyway
+        self.last_checkpoint_height = Some(block_height);
+        match self.stackerdb.send_message_bytes_with_retry(
+            &CheckpointSlotID::Checkpoint,
+            checkpoint.serialize_to_vec(),
+        ) {
+            Ok(_) => info!(
+                "Published chain checkpoint";
+                "block_height" => block_height,
+                "block_id" => %block_id,
+                "reward_cycle" => self.reward_cycle,
+            ),
+            Err(e) => warn!(
+                "Failed to publish chain checkpoint: {e:?}";
+                "block_height" => block_height,
+                "block_id" => %block_id,
+            ),
+        }
+    }
+}
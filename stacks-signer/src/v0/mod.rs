@@ -14,6 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+/// Signed chain checkpoints, published to the `.checkpoints` StackerDB
+pub mod checkpoints;
+
 /// The signer module for processing events
 pub mod signer;
 
@@ -21,6 +24,9 @@ pub mod signer;
 /// Test specific functions for the signer module
 pub mod tests;
 
+/// Cache of the stacks node's verdicts on submitted block proposals
+pub mod verdict_cache;
+
 use libsigner::v0::messages::SignerMessage;
 
 use crate::v0::signer::Signer;
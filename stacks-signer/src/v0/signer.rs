@@ -41,7 +41,9 @@ use crate::chainstate::{ProposalEvalConfig, SortitionsView};
 use crate::client::{ClientError, SignerSlotID, StackerDB, StacksClient};
 use crate::config::{SignerConfig, SignerConfigMode};
 use crate::runloop::SignerResult;
-use crate::signerdb::{BlockInfo, BlockState, SignerDb};
+use crate::signerdb::{BlockInfo, BlockState, ProposalAuditEntry, SignerDb};
+use crate::v0::checkpoints::CheckpointPublisher;
+use crate::v0::verdict_cache::ValidationVerdictCache;
 use crate::Signer as SignerTrait;
 
 /// Signer running mode (whether dry-run or real)
@@ -92,6 +94,10 @@ pub struct Signer {
     pub submitted_block_proposal: Option<(Sha512Trunc256Sum, Instant)>,
     /// Maximum age of a block proposal in seconds before it is dropped without processing
     pub block_proposal_max_age_secs: u64,
+    /// The stacks node's verdicts on the block proposals we submitted for validation
+    pub validation_verdicts: ValidationVerdictCache,
+    /// Publishes signed checkpoints of globally accepted blocks
+    pub checkpoints: CheckpointPublisher,
 }
 
 impl std::fmt::Display for SignerMode {
@@ -279,6 +285,9 @@ impl SignerTrait<SignerMessage> for Signer {
                     if let Err(e) = self.signer_db.insert_block(&block_info) {
                         warn!("{self}: Failed to update block state to globally accepted: {e:?}");
                     }
+                    if block_info.reward_cycle == self.reward_cycle {
+                        self.checkpoints.maybe_publish(&block_info.block);
+                    }
                 }
             }
         }
@@ -298,6 +307,7 @@ impl SignerTrait<SignerMessage> for Signer {
 impl From<SignerConfig> for Signer {
     fn from(signer_config: SignerConfig) -> Self {
         let stackerdb = StackerDB::from(&signer_config);
+        let checkpoints = CheckpointPublisher::from(&signer_config);
         let mode = match signer_config.signer_mode {
             SignerConfigMode::DryRun => SignerMode::DryRun,
             SignerConfigMode::Normal { signer_id, .. } => SignerMode::Normal { signer_id },
@@ -323,6 +333,8 @@ impl From<SignerConfig> for Signer {
             submitted_block_proposal: None,
             block_proposal_validation_timeout: signer_config.block_proposal_validation_timeout,
             block_proposal_max_age_secs: signer_config.block_proposal_max_age_secs,
+            validation_verdicts: ValidationVerdictCache::default(),
+            checkpoints,
         }
     }
 }
@@ -377,6 +389,17 @@ impl Signer {
             ),
         )
     }
+    /// Record this signer's response to a block proposal in the proposal audit log
+    fn record_proposal_decision(&self, block_info: &BlockInfo, block_response: &BlockResponse) {
+        let entry = ProposalAuditEntry::new(block_info, block_response);
+        if let Err(e) = self.signer_db.insert_proposal_audit_entry(&entry) {
+            warn!("{self}: Failed to record block proposal decision in the audit log: {e:?}";
+                "signer_sighash" => %entry.signer_signature_hash,
+                "decision" => %entry.decision,
+            );
+        }
+    }
+
     /// Check if block should be rejected based on sortition state
     /// Will return a BlockResponse::Rejection if the block is invalid, none otherwise.
     fn check_block_against_sortition_state(
@@ -483,6 +506,15 @@ impl Signer {
         let signer_signature_hash = block_proposal.block.header.signer_signature_hash();
         if let Some(block_info) = self.block_lookup_by_reward_cycle(&signer_signature_hash) {
             let Some(block_response) = self.determine_response(&block_info) else {
+                if let Some(verdict) = self
+                    .validation_verdicts
+                    .get(&signer_signature_hash)
+                    .cloned()
+                {
+                    // The node already validated this block, but we did not act on it.
+                    self.apply_block_validate_response(stacks_client, &verdict);
+                    return;
+                }
                 // We are still waiting for a response for this block. Do nothing.
                 debug!("{self}: Received a block proposal for a block we are already validating.";
                     "signer_sighash" => %signer_signature_hash,
@@ -552,6 +584,7 @@ impl Signer {
 
         if let Some(block_response) = block_response {
             // We know proposal is invalid. Send rejection message, do not do further validation and do not store it.
+            self.record_proposal_decision(&block_info, &block_response);
             debug!("{self}: Broadcasting a block response to stacks node: {block_response:?}");
             let res = self
                 .stackerdb
@@ -566,9 +599,20 @@ impl Signer {
                 Ok(_) => debug!("{self}: Block rejection accepted by stacker-db"),
             }
         } else {
+            // Do not store KNOWN invalid blocks as this could DOS the signer. We only store blocks that are valid or unknown.
+            // The block is stored before it is submitted, so that a cached verdict on it can be applied right away.
+            self.signer_db
+                .insert_block(&block_info)
+                .unwrap_or_else(|e| self.handle_insert_block_error(e));
+
             // Just in case check if the last block validation submission timed out.
             self.check_submitted_block_proposal();
-            if self.submitted_block_proposal.is_none() {
+            if self.submitted_block_proposal.is_none()
+                || self
+                    .validation_verdicts
+                    .get(&signer_signature_hash)
+                    .is_some()
+            {
                 // We don't know if proposal is valid, submit to stacks-node for further checks and store it locally.
                 info!(
                     "{self}: submitting block proposal for validation";
@@ -595,11 +639,6 @@ impl Signer {
                         warn!("{self}: Failed to insert pending block validation: {e:?}")
                     });
             }
-
-            // Do not store KNOWN invalid blocks as this could DOS the signer. We only store blocks that are valid or unknown.
-            self.signer_db
-                .insert_block(&block_info)
-                .unwrap_or_else(|e| self.handle_insert_block_error(e));
         }
     }
 
@@ -701,7 +740,6 @@ impl Signer {
         stacks_client: &StacksClient,
         block_validate_ok: &BlockValidateOk,
     ) -> Option<BlockResponse> {
-        crate::monitoring::actions::increment_block_validation_responses(true);
         let signer_signature_hash = block_validate_ok.signer_signature_hash;
         if self
             .submitted_block_proposal
@@ -730,6 +768,7 @@ impl Signer {
                     warn!("{self}: Failed to mark block as locally rejected: {e:?}");
                 }
             };
+            self.record_proposal_decision(&block_info, &block_response);
             debug!("{self}: Broadcasting a block response to stacks node: {block_response:?}");
             let res = self
                 .stackerdb
@@ -768,6 +807,7 @@ impl Signer {
                 .insert_block(&block_info)
                 .unwrap_or_else(|e| self.handle_insert_block_error(e));
             let block_response = self.create_block_acceptance(&block_info.block);
+            self.record_proposal_decision(&block_info, &block_response);
             // have to save the signature _after_ the block info
             self.handle_block_signature(stacks_client, block_response.as_block_accepted()?);
             Some(block_response)
@@ -779,7 +819,6 @@ impl Signer {
         &mut self,
         block_validate_reject: &BlockValidateReject,
     ) -> Option<BlockResponse> {
-        crate::monitoring::actions::increment_block_validation_responses(false);
         let signer_signature_hash = block_validate_reject.signer_signature_hash;
         if self
             .submitted_block_proposal
@@ -817,7 +856,9 @@ impl Signer {
             .insert_block(&block_info)
             .unwrap_or_else(|e| self.handle_insert_block_error(e));
         self.handle_block_rejection(&block_rejection);
-        Some(BlockResponse::Rejected(block_rejection))
+        let block_response = BlockResponse::Rejected(block_rejection);
+        self.record_proposal_decision(&block_info, &block_response);
+        Some(block_response)
     }
 
     /// Handle the block validate response returned from our prior calls to submit a block for validation
@@ -827,11 +868,32 @@ impl Signer {
         block_validate_response: &BlockValidateResponse,
     ) {
         info!("{self}: Received a block validate response: {block_validate_response:?}");
-        let block_response = match block_validate_response {
+        match block_validate_response {
             BlockValidateResponse::Ok(block_validate_ok) => {
+                crate::monitoring::actions::increment_block_validation_responses(true);
                 crate::monitoring::actions::record_block_validation_latency(
                     block_validate_ok.validation_time_ms,
                 );
+            }
+            BlockValidateResponse::Reject(_) => {
+                crate::monitoring::actions::increment_block_validation_responses(false);
+            }
+        };
+        self.validation_verdicts
+            .insert(block_validate_response.clone());
+        self.apply_block_validate_response(stacks_client, block_validate_response);
+    }
+
+    /// Act on the stacks node's verdict on a block proposal, whether it was just returned or
+    /// cached from an earlier submission of the same block, and then submit the next pending
+    /// block validation (if any) to the node.
+    fn apply_block_validate_response(
+        &mut self,
+        stacks_client: &StacksClient,
+        block_validate_response: &BlockValidateResponse,
+    ) {
+        let block_response = match block_validate_response {
+            BlockValidateResponse::Ok(block_validate_ok) => {
                 self.handle_block_validate_ok(stacks_client, block_validate_ok)
             }
             BlockValidateResponse::Reject(block_validate_reject) => {
@@ -871,7 +933,11 @@ impl Signer {
             }
         };
 
-        // Check if there is a pending block validation that we need to submit to the node
+        // Check if there is a pending block validation that we need to submit to the node,
+        // unless we are still waiting on the node to validate another block.
+        if self.submitted_block_proposal.is_some() {
+            return;
+        }
         match self.signer_db.get_and_remove_pending_block_validation() {
             Ok(Some(signer_sig_hash)) => {
                 info!("{self}: Found a pending block validation: {signer_sig_hash:?}");
@@ -942,6 +1008,7 @@ impl Signer {
                 warn!("{self}: Failed to mark block as locally rejected: {e:?}");
             }
         };
+        self.record_proposal_decision(&block_info, &rejection);
         debug!("{self}: Broadcasting a block response to stacks node: {rejection:?}");
         let res = self
             .stackerdb
@@ -1222,9 +1289,23 @@ impl Signer {
     }
 
     /// Submit a block for validation, and mark it as pending if the node
-    /// is busy with a previous request.
+    /// is busy with a previous request.  If the node has already validated
+    /// this block, its cached verdict is used instead.
     fn submit_block_for_validation(&mut self, stacks_client: &StacksClient, block: &NakamotoBlock) {
         let signer_signature_hash = block.header.signer_signature_hash();
+        if let Some(verdict) = self
+            .validation_verdicts
+            .get(&signer_signature_hash)
+            .cloned()
+        {
+            info!(
+                "{self}: Block proposal was already validated by the stacks node. Using its cached verdict.";
+                "signer_signature_hash" => %signer_signature_hash,
+                "block_id" => %block.block_id(),
+            );
+            self.apply_block_validate_response(stacks_client, &verdict);
+            return;
+        }
         match stacks_client.submit_block_for_validation(block.clone()) {
             Ok(_) => {
                 self.submitted_block_proposal = Some((signer_signature_hash, Instant::now()));
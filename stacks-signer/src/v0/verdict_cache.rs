@@ -0,0 +1,160 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{HashMap, VecDeque};
+
+use blockstack_lib::net::api::postblock_proposal::BlockValidateResponse;
+use clarity::util::hash::Sha512Trunc256Sum;
+
+/// Most validation verdicts a signer keeps before evicting the oldest
+pub const MAX_CACHED_VALIDATION_VERDICTS: usize = 256;
+
+/// The verdicts the stacks node returned for the block proposals this signer submitted for
+/// validation, keyed by the proposed block's signer signature hash.  A proposal that is
+/// re-broadcast (by a retrying miner, or one we had queued while the node was busy) is
+/// answered from here instead of being validated again.
+#[derive(Debug)]
+pub struct ValidationVerdictCache {
+    /// Verdicts by signer signature hash
+    verdicts: HashMap<Sha512Trunc256Sum, BlockValidateResponse>,
+    /// Signer signature hashes in insertion order, oldest first
+    order: VecDeque<Sha512Trunc256Sum>,
+    /// Most verdicts to keep
+    capacity: usize,
+}
+
+impl Default for ValidationVerdictCache {
+    fn default() -> Self {
+        Self::new(MAX_CACHED_VALIDATION_VERDICTS)
+    }
+}
+
+impl ValidationVerdictCache {
+    /// Create an empty cache that holds up to `capacity` verdicts
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            verdicts: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Remember the node's verdict on a block proposal, evicting the oldest verdict if the
+    /// cache is full.  A later verdict for the same block replaces the earlier one.
+    pub fn insert(&mut self, verdict: BlockValidateResponse) {
+        if self.capacity == 0 {
+            return;
+        }
+        let signer_signature_hash = verdict.signer_signature_hash();
+        if self
+            .verdicts
+            .insert(signer_signature_hash, verdict)
+            .is_some()
+        {
+            return;
+        }
+        self.order.push_back(signer_signature_hash);
+        while self.order.len() > self.capacity {
+            let Some(evicted) = self.order.pop_front() else {
+                break;
+            };
+            self.verdicts.remove(&evicted);
+        }
+    }
+
+    /// Get the node's verdict on the block with the given signer signature hash, if we have it
+    pub fn get(&self, signer_signature_hash: &Sha512Trunc256Sum) -> Option<&BlockValidateResponse> {
+        self.verdicts.get(signer_signature_hash)
+    }
+
+    /// How many verdicts are cached
+    pub fn len(&self) -> usize {
+        self.verdicts.len()
+    }
+
+    /// Whether or not no verdicts are cached
+    pub fn is_empty(&self) -> bool {
+        self.verdicts.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blockstack_lib::net::api::postblock_proposal::{
+        BlockValidateOk, BlockValidateReject, ValidateRejectCode,
+    };
+    use clarity::vm::costs::ExecutionCost;
+
+    use super::*;
+
+    fn ok_verdict(byte: u8) -> BlockValidateResponse {
+        BlockValidateResponse::Ok(BlockValidateOk {
+            signer_signature_hash: Sha512Trunc256Sum([byte; 32]),
+            cost: ExecutionCost::ZERO,
+            size: 0,
+            validation_time_ms: 0,
+        })
+    }
+
+    fn reject_verdict(byte: u8) -> BlockValidateResponse {
+        BlockValidateResponse::Reject(BlockValidateReject {
+            signer_signature_hash: Sha512Trunc256Sum([byte; 32]),
+            reason: "bad block".into(),
+            reason_code: ValidateRejectCode::InvalidBlock,
+            reason_detail: None,
+        })
+    }
+
+    #[test]
+    fn caches_verdicts_by_signer_signature_hash() {
+        let mut cache = ValidationVerdictCache::default();
+        assert!(cache.is_empty());
+
+        cache.insert(ok_verdict(1));
+        cache.insert(reject_verdict(2));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&Sha512Trunc256Sum([1; 32])), Some(&ok_verdict(1)));
+        assert_eq!(
+            cache.get(&Sha512Trunc256Sum([2; 32])),
+            Some(&reject_verdict(2))
+        );
+        assert!(cache.get(&Sha512Trunc256Sum([3; 32])).is_none());
+
+        // a later verdict on the same block replaces the earlier one
+        cache.insert(reject_verdict(1));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(
+            cache.get(&Sha512Trunc256Sum([1; 32])),
+            Some(&reject_verdict(1))
+        );
+    }
+
+    #[test]
+    fn evicts_oldest_verdicts() {
+        let mut cache = ValidationVerdictCache::new(2);
+        cache.insert(ok_verdict(1));
+        cache.insert(ok_verdict(2));
+        cache.insert(ok_verdict(1));
+        cache.insert(ok_verdict(3));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&Sha512Trunc256Sum([1; 32])).is_none());
+        assert!(cache.get(&Sha512Trunc256Sum([2; 32])).is_some());
+        assert!(cache.get(&Sha512Trunc256Sum([3; 32])).is_some());
+
+        let mut cache = ValidationVerdictCache::new(0);
+        cache.insert(ok_verdict(1));
+        assert!(cache.is_empty());
+    }
+}
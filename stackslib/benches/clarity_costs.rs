@@ -0,0 +1,86 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clarity cost profiling benchmark.
+//!
+//! Times each workload in `blockstack_lib::clarity_vm::cost_profile` at each
+//! profiled epoch, and writes the cost model report (as JSON) to
+//! `$CLARITY_COST_REPORT`, or to `clarity-cost-report.json` if unset. If
+//! `$CLARITY_COST_BASELINE` names a prior report, any cost that differs from it
+//! is printed.
+//!
+//!   cargo bench -p stackslib --features clarity-bench --bench clarity_costs
+
+use std::fs;
+
+use blockstack_lib::clarity_vm::cost_profile::{
+    profile_all_epochs, CostModelReport, CostProfiler, PROFILED_EPOCHS, PROFILE_WORKLOADS,
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const DEFAULT_REPORT_PATH: &str = "clarity-cost-report.json";
+
+fn bench_workloads(c: &mut Criterion) {
+    for epoch in PROFILED_EPOCHS.iter() {
+        let mut profiler = CostProfiler::new(*epoch);
+        let mut group = c.benchmark_group(format!("clarity-costs/{epoch}"));
+        for workload in PROFILE_WORKLOADS.iter() {
+            group.bench_function(workload.name, |b| {
+                b.iter(|| profiler.run_workload(workload))
+            });
+        }
+        group.finish();
+    }
+}
+
+fn write_cost_model_report() {
+    let report = profile_all_epochs();
+    let path =
+        std::env::var("CLARITY_COST_REPORT").unwrap_or_else(|_| DEFAULT_REPORT_PATH.to_string());
+    let json = serde_json::to_string_pretty(&report).expect("FATAL: failed to serialize report");
+    fs::write(&path, json).unwrap_or_else(|e| panic!("FATAL: failed to write {path}: {e:?}"));
+    println!("Wrote Clarity cost model report to {path}");
+
+    let Ok(baseline_path) = std::env::var("CLARITY_COST_BASELINE") else {
+        return;
+    };
+    let baseline_json = fs::read_to_string(&baseline_path)
+        .unwrap_or_else(|e| panic!("FATAL: failed to read {baseline_path}: {e:?}"));
+    let baseline: CostModelReport = serde_json::from_str(&baseline_json)
+        .unwrap_or_else(|e| panic!("FATAL: failed to parse {baseline_path}: {e:?}"));
+    let changes = report.changes_from(&baseline);
+    if changes.is_empty() {
+        println!("Clarity costs match the baseline in {baseline_path}");
+        return;
+    }
+    println!(
+        "{} Clarity cost(s) differ from the baseline in {baseline_path}:",
+        changes.len()
+    );
+    for change in changes.iter() {
+        println!(
+            "  {} @ {}: {:?} -> {:?}",
+            change.workload, change.epoch, change.baseline, change.current
+        );
+    }
+}
+
+fn bench_cost_model(c: &mut Criterion) {
+    write_cost_model_report();
+    bench_workloads(c);
+}
+
+criterion_group!(benches, bench_cost_model);
+criterion_main!(benches);
@@ -245,7 +245,8 @@ use stacks_common::types::chainstate::{
 };
 
 use crate::burnchains::db::{
-    BurnchainBlockData, BurnchainDB, BurnchainDBTransaction, BurnchainHeaderReader,
+    AnchorBlockOverride, BurnchainBlockData, BurnchainDB, BurnchainDBTransaction,
+    BurnchainHeaderReader,
 };
 use crate::burnchains::{Address, Burnchain, BurnchainBlockHeader, Error, PoxConstants, Txid};
 use crate::chainstate::burn::db::sortdb::SortitionDB;
@@ -279,6 +280,15 @@ impl AffirmationMapEntry {
             _ => None,
         }
     }
+
+    /// Human-readable meaning of this entry
+    pub fn description(&self) -> &'static str {
+        match self {
+            AffirmationMapEntry::PoxAnchorBlockPresent => "anchor block present",
+            AffirmationMapEntry::PoxAnchorBlockAbsent => "anchor block absent",
+            AffirmationMapEntry::Nothing => "no anchor block",
+        }
+    }
 }
 
 /// An affirmation map is simply a list of affirmation map entries.  This struct merely wraps the
@@ -499,6 +509,135 @@ impl AffirmationMap {
     }
 }
 
+/// One decoded entry of an affirmation map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecodedAffirmation {
+    /// The reward cycle whose anchor block this entry is about
+    pub reward_cycle: u64,
+    /// The entry's encoding (`p`, `a`, or `n`)
+    pub affirmation: String,
+    pub description: String,
+}
+
+/// An affirmation map, along with its weight and its decoded entries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AffirmationMapReport {
+    pub affirmation_map: AffirmationMap,
+    pub weight: u64,
+    pub entries: Vec<DecodedAffirmation>,
+}
+
+impl AffirmationMapReport {
+    pub fn new(am: &AffirmationMap) -> AffirmationMapReport {
+        // entry i is the status of the anchor block for reward cycle i + 1
+        let entries = am
+            .affirmations
+            .iter()
+            .zip(1u64..)
+            .map(|(entry, reward_cycle)| DecodedAffirmation {
+                reward_cycle,
+                affirmation: entry.to_string(),
+                description: entry.description().to_string(),
+            })
+            .collect();
+        AffirmationMapReport {
+            affirmation_map: am.clone(),
+            weight: am.weight(),
+            entries,
+        }
+    }
+}
+
+/// The affirmation map of a block-commit that was chosen as a PoX anchor block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnchorBlockCommitAffirmation {
+    /// The reward cycle this block-commit is the anchor block for
+    pub reward_cycle: u64,
+    pub burn_block_hash: BurnchainHeaderHash,
+    pub txid: Txid,
+    pub block_height: u64,
+    pub vtxindex: u32,
+    pub affirmation_map: AffirmationMap,
+    pub weight: u64,
+}
+
+/// Where the node stands on each reward cycle's PoX anchor block, for diagnosing divergence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AffirmationsReport {
+    pub burn_block_height: u64,
+    pub reward_cycle: u64,
+    /// Affirmation map of the heaviest anchor block
+    pub heaviest: AffirmationMapReport,
+    /// The heaviest affirmation map, extended with the reward cycles after the heaviest anchor
+    /// block.  This is the affirmation map the node tries to follow.
+    pub canonical: AffirmationMapReport,
+    /// Affirmation map of the canonical sortition
+    pub sortition_tip: AffirmationMapReport,
+    /// Affirmation map of the canonical Stacks tip
+    pub stacks_tip: AffirmationMapReport,
+    /// First reward cycle for which the sortition tip disagrees with the canonical affirmation map
+    pub sortition_tip_divergence: Option<u64>,
+    /// First reward cycle for which the Stacks tip disagrees with the canonical affirmation map
+    pub stacks_tip_divergence: Option<u64>,
+    /// Every anchor block-commit the node knows about, including those of non-canonical forks
+    pub anchor_block_commits: Vec<AnchorBlockCommitAffirmation>,
+    /// Anchor block statuses that the node operator has overridden
+    pub overrides: Vec<AnchorBlockOverride>,
+}
+
+impl AffirmationsReport {
+    /// Build the report from the node's affirmation maps, loading the anchor block-commits and
+    /// overrides from the burnchain DB
+    pub fn new(
+        burnchain: &Burnchain,
+        burnchain_db: &BurnchainDB,
+        burn_block_height: u64,
+        heaviest: &AffirmationMap,
+        canonical: &AffirmationMap,
+        sortition_tip: &AffirmationMap,
+        stacks_tip: &AffirmationMap,
+    ) -> Result<AffirmationsReport, DBError> {
+        let mut anchor_block_commits = vec![];
+        for metadata in BurnchainDB::get_all_anchor_block_commit_metadatas(burnchain_db.conn())? {
+            let Some(reward_cycle) = metadata.anchor_block else {
+                continue;
+            };
+            let affirmation_map =
+                BurnchainDB::get_affirmation_map(burnchain_db.conn(), metadata.affirmation_id)?
+                    .ok_or_else(|| {
+                        DBError::Other(format!(
+                            "No affirmation map {} for anchor block-commit {}",
+                            metadata.affirmation_id, &metadata.txid
+                        ))
+                    })?;
+            anchor_block_commits.push(AnchorBlockCommitAffirmation {
+                reward_cycle,
+                burn_block_hash: metadata.burn_block_hash,
+                txid: metadata.txid,
+                block_height: metadata.block_height,
+                vtxindex: metadata.vtxindex,
+                weight: affirmation_map.weight(),
+                affirmation_map,
+            });
+        }
+
+        Ok(AffirmationsReport {
+            burn_block_height,
+            reward_cycle: burnchain
+                .block_height_to_reward_cycle(burn_block_height)
+                .unwrap_or(0),
+            heaviest: AffirmationMapReport::new(heaviest),
+            canonical: AffirmationMapReport::new(canonical),
+            sortition_tip: AffirmationMapReport::new(sortition_tip),
+            stacks_tip: AffirmationMapReport::new(stacks_tip),
+            sortition_tip_divergence: canonical.find_divergence(sortition_tip).map(|i| i + 1),
+            stacks_tip_divergence: canonical.find_divergence(stacks_tip).map(|i| i + 1),
+            anchor_block_commits,
+            overrides: BurnchainDB::get_anchor_block_overrides(burnchain_db.conn())?,
+        })
+    }
+}
+
 /// Get a parent/child reward cycle.  Only return Some(..) if the reward cycle is known for both --
 /// i.e. their block heights are plausible -- they are at or after the first burnchain block
 /// height.
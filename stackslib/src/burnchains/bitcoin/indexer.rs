@@ -132,6 +132,8 @@ pub struct BitcoinIndexer {
     pub config: BitcoinIndexerConfig,
     pub runtime: BitcoinIndexerRuntime,
     pub should_keep_running: Option<Arc<AtomicBool>>,
+    /// if set, records the progress of header downloads
+    pub header_sync_tracker: Option<HeaderSyncTracker>,
 }
 
 impl BitcoinIndexerConfig {
@@ -213,6 +215,7 @@ impl BitcoinIndexer {
             config,
             runtime,
             should_keep_running,
+            header_sync_tracker: None,
         }
     }
 
@@ -245,6 +248,7 @@ impl BitcoinIndexer {
             ),
             runtime: BitcoinIndexerRuntime::new(BitcoinNetworkType::Regtest),
             should_keep_running: None,
+            header_sync_tracker: None,
         }
     }
 
@@ -253,6 +257,7 @@ impl BitcoinIndexer {
             config: self.config.clone(),
             runtime: BitcoinIndexerRuntime::new(self.runtime.network_id),
             should_keep_running: self.should_keep_running.clone(),
+            header_sync_tracker: self.header_sync_tracker.clone(),
         }
     }
 
@@ -447,9 +452,15 @@ impl BitcoinIndexer {
                 return Ok(cur_height);
             }
         }
-        spv_client
-            .run(self)
-            .map(|_r| spv_client.end_block_height.unwrap())
+        if let Some(tracker) = self.header_sync_tracker.as_ref() {
+            spv_client.set_sync_tracker(tracker.clone());
+        }
+        spv_client.run(self)?;
+        let end_block = spv_client.end_block_height.unwrap();
+        if let Some(tracker) = self.header_sync_tracker.as_ref() {
+            tracker.finish(end_block);
+        }
+        Ok(end_block)
     }
 
     #[cfg(test)]
@@ -844,7 +855,9 @@ impl BitcoinIndexer {
         Ok(new_tip)
     }
 
-    #[cfg(test)]
+    /// Store a mock Bitcoin header (see `mock_bitcoin_header()`) for the given burnchain block
+    /// header, directly after the header at the preceding height.  Used by tests and by
+    /// burnchains whose blocks are synthesized rather than downloaded.
     pub fn raw_store_header(&mut self, header: BurnchainBlockHeader) -> Result<(), btc_error> {
         let mut spv_client = SpvClient::new(
             &self.config.spv_headers_path,
@@ -870,7 +883,7 @@ impl BitcoinIndexer {
         Ok(())
     }
 
-    #[cfg(test)]
+    /// A Bitcoin header with the given parent and timestamp, and no proof-of-work
     pub fn mock_bitcoin_header(
         parent_block_hash: &BurnchainHeaderHash,
         timestamp: u32,
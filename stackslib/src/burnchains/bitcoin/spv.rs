@@ -17,6 +17,7 @@
 use std::collections::VecDeque;
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::ops::Deref;
+use std::sync::{Arc, Mutex};
 use std::{cmp, fs};
 
 use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
@@ -34,11 +35,13 @@ use stacks_common::types::chainstate::BurnchainHeaderHash;
 use stacks_common::types::sqlite::NO_PARAMS;
 use stacks_common::util::hash::{hex_bytes, to_hex};
 use stacks_common::util::uint::Uint256;
-use stacks_common::util::{get_epoch_time_secs, log};
+use stacks_common::util::{get_epoch_time_ms, get_epoch_time_secs, log};
 
 use crate::burnchains::bitcoin::indexer::BitcoinIndexer;
 use crate::burnchains::bitcoin::messages::BitcoinMessageHandler;
 use crate::burnchains::bitcoin::{BitcoinNetworkType, Error as btc_error, PeerMessage};
+use crate::burnchains::db::BurnchainHeaderReader;
+use crate::burnchains::BurnchainBlockHeader;
 use crate::util_lib::db::{
     query_int, query_row, query_rows, sqlite_open, tx_begin_immediate, tx_busy_handler, u64_to_sql,
     DBConn, DBTx, Error as db_error, FromColumn, FromRow,
@@ -113,6 +116,171 @@ const SPV_SCHEMA_3: &[&str] = &[
     "#,
 ];
 
+/// How often to report header download progress to the header sync observer, if any
+const HEADER_SYNC_REPORT_INTERVAL_MS: u128 = 30_000;
+
+/// Progress of the node's Bitcoin header download
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct HeaderSyncStatus {
+    /// Whether or not headers are being downloaded right now
+    pub syncing: bool,
+    /// Height of the highest stored header when the download began
+    pub first_height: u64,
+    /// Height of the highest stored header
+    pub current_height: u64,
+    /// Height of the Bitcoin peer's chain tip, which the download is working towards
+    pub target_height: u64,
+    /// Headers downloaded per second since the download began
+    pub headers_per_second: f64,
+    /// Estimated number of seconds until the download finishes, if known
+    pub eta_secs: Option<u64>,
+    /// When the download began, in seconds since the epoch
+    pub started_at: u64,
+    /// When progress was last recorded, in seconds since the epoch
+    pub updated_at: u64,
+}
+
+/// Receives header download progress reports
+pub trait HeaderSyncObserver: Send + Sync {
+    /// Called when a header download begins, periodically while it makes progress, and when it
+    /// finishes.
+    fn header_sync_progress(&self, status: &HeaderSyncStatus);
+}
+
+struct HeaderSyncState {
+    status: HeaderSyncStatus,
+    started_at_ms: u128,
+    last_report_ms: u128,
+}
+
+/// Tracks the progress of the Bitcoin header download, so it can be reported to the RPC server
+/// and to a header sync observer.  Clones share the same state.
+#[derive(Clone)]
+pub struct HeaderSyncTracker {
+    state: Arc<Mutex<HeaderSyncState>>,
+    observer: Option<Arc<dyn HeaderSyncObserver>>,
+}
+
+impl Default for HeaderSyncTracker {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl HeaderSyncTracker {
+    pub fn new(observer: Option<Arc<dyn HeaderSyncObserver>>) -> HeaderSyncTracker {
+        HeaderSyncTracker {
+            state: Arc::new(Mutex::new(HeaderSyncState {
+                status: HeaderSyncStatus::default(),
+                started_at_ms: 0,
+                last_report_ms: 0,
+            })),
+            observer,
+        }
+    }
+
+    /// Get the current download progress
+    pub fn get_status(&self) -> HeaderSyncStatus {
+        self.state
+            .lock()
+            .expect("FATAL: header sync state lock poisoned")
+            .status
+            .clone()
+    }
+
+    /// Record that a header download towards `target_height` has begun from `first_height`.
+    /// If a previous download was interrupted (e.g. by a peer disconnect), it is resumed
+    /// instead, so the rate and ETA cover the whole download.
+    pub fn begin(&self, first_height: u64, target_height: u64) {
+        self.begin_at(first_height, target_height, get_epoch_time_ms())
+    }
+
+    fn begin_at(&self, first_height: u64, target_height: u64, now_ms: u128) {
+        let mut state = self
+            .state
+            .lock()
+            .expect("FATAL: header sync state lock poisoned");
+        if !state.status.syncing {
+            state.status = HeaderSyncStatus {
+                syncing: true,
+                first_height,
+                current_height: first_height,
+                target_height,
+                headers_per_second: 0.0,
+                eta_secs: None,
+                started_at: Self::ms_to_secs(now_ms),
+                updated_at: Self::ms_to_secs(now_ms),
+            };
+            state.started_at_ms = now_ms;
+        } else {
+            state.status.target_height = target_height;
+            state.status.updated_at = Self::ms_to_secs(now_ms);
+        }
+        state.last_report_ms = now_ms;
+        self.report(&state.status);
+    }
+
+    /// Record that headers up to `current_height` have been stored
+    pub fn record_progress(&self, current_height: u64) {
+        self.record_progress_at(current_height, get_epoch_time_ms())
+    }
+
+    fn record_progress_at(&self, current_height: u64, now_ms: u128) {
+        let mut state = self
+            .state
+            .lock()
+            .expect("FATAL: header sync state lock poisoned");
+        let elapsed_ms = now_ms.saturating_sub(state.started_at_ms);
+        let status = &mut state.status;
+        status.current_height = current_height;
+        status.updated_at = Self::ms_to_secs(now_ms);
+        let downloaded = current_height.saturating_sub(status.first_height);
+        if elapsed_ms > 0 && downloaded > 0 {
+            status.headers_per_second = (downloaded as f64) * 1000.0 / (elapsed_ms as f64);
+            let remaining = status.target_height.saturating_sub(current_height);
+            status.eta_secs = Some((remaining as f64 / status.headers_per_second).ceil() as u64);
+        }
+        if now_ms.saturating_sub(state.last_report_ms) >= HEADER_SYNC_REPORT_INTERVAL_MS {
+            state.last_report_ms = now_ms;
+            self.report(&state.status);
+        }
+    }
+
+    /// Record that all headers up to `current_height` have been downloaded
+    pub fn finish(&self, current_height: u64) {
+        self.finish_at(current_height, get_epoch_time_ms())
+    }
+
+    fn finish_at(&self, current_height: u64, now_ms: u128) {
+        let mut state = self
+            .state
+            .lock()
+            .expect("FATAL: header sync state lock poisoned");
+        let was_syncing = state.status.syncing;
+        let status = &mut state.status;
+        status.syncing = false;
+        status.current_height = current_height;
+        status.target_height = cmp::max(status.target_height, current_height);
+        status.eta_secs = Some(0);
+        status.updated_at = Self::ms_to_secs(now_ms);
+        // only report the end of a download that was reported as begun
+        if was_syncing {
+            state.last_report_ms = now_ms;
+            self.report(&state.status);
+        }
+    }
+
+    fn ms_to_secs(ms: u128) -> u64 {
+        u64::try_from(ms / 1000).unwrap_or(u64::MAX)
+    }
+
+    fn report(&self, status: &HeaderSyncStatus) {
+        if let Some(observer) = self.observer.as_ref() {
+            observer.header_sync_progress(status);
+        }
+    }
+}
+
 pub struct SpvClient {
     pub headers_path: String,
     pub start_block_height: u64,
@@ -123,6 +291,8 @@ pub struct SpvClient {
     reverse_order: bool,
     headers_db: DBConn,
     check_txcount: bool,
+    /// if set, records the progress of this client's header download
+    sync_tracker: Option<HeaderSyncTracker>,
 }
 
 impl FromColumn<Sha256dHash> for Sha256dHash {
@@ -172,6 +342,7 @@ impl SpvClient {
             reverse_order,
             headers_db: conn,
             check_txcount: true,
+            sync_tracker: None,
         };
 
         let empty = client.is_empty()?;
@@ -202,6 +373,7 @@ impl SpvClient {
             reverse_order,
             headers_db: conn,
             check_txcount: true,
+            sync_tracker: None,
         };
 
         if readwrite {
@@ -211,7 +383,6 @@ impl SpvClient {
         Ok(client)
     }
 
-    #[cfg(test)]
     pub fn disable_check_txcount(&mut self) {
         self.check_txcount = false;
     }
@@ -362,6 +533,11 @@ impl SpvClient {
         self.cur_block_height = start_block;
     }
 
+    /// Record the progress of this client's header download in `tracker`
+    pub fn set_sync_tracker(&mut self, tracker: HeaderSyncTracker) {
+        self.sync_tracker = Some(tracker);
+    }
+
     /// go get all the headers.
     /// keep trying forever.
     pub fn run(&mut self, indexer: &mut BitcoinIndexer) -> Result<(), btc_error> {
@@ -1178,6 +1354,47 @@ impl SpvClient {
     }
 }
 
+/// Lets offline tools (e.g. `stacks-inspect`) read the canonical burnchain from a node's headers
+/// DB without a running indexer
+impl BurnchainHeaderReader for SpvClient {
+    fn read_burnchain_headers(
+        &self,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<BurnchainBlockHeader>, db_error> {
+        let hdrs = self
+            .read_block_headers(start_height, end_height)
+            .map_err(|e| db_error::Other(format!("Burnchain error: {:?}", &e)))?;
+
+        Ok(hdrs
+            .into_iter()
+            .zip(start_height..)
+            .map(|(hdr, block_height)| BurnchainBlockHeader {
+                block_height,
+                block_hash: BurnchainHeaderHash::from_bitcoin_hash(&hdr.header.bitcoin_hash()),
+                parent_block_hash: BurnchainHeaderHash::from_bitcoin_hash(
+                    &hdr.header.prev_blockhash,
+                ),
+                num_txs: hdr.tx_count.0,
+                timestamp: hdr.header.time as u64,
+            })
+            .collect())
+    }
+
+    fn get_burnchain_headers_height(&self) -> Result<u64, db_error> {
+        self.get_headers_height()
+            .map_err(|e| db_error::Other(format!("Burnchain error: {:?}", &e)))
+    }
+
+    fn find_burnchain_header_height(
+        &self,
+        burn_header_hash: &BurnchainHeaderHash,
+    ) -> Result<Option<u64>, db_error> {
+        self.find_block_header_height(burn_header_hash)
+            .map_err(|e| db_error::Other(format!("Burnchain error: {:?}", &e)))
+    }
+}
+
 impl BitcoinMessageHandler for SpvClient {
     /// Trait message handler
     /// initiate the conversation with the bitcoin peer
@@ -1196,6 +1413,9 @@ impl BitcoinMessageHandler for SpvClient {
             self.end_block_height.unwrap(),
             self.headers_path
         );
+        if let Some(tracker) = self.sync_tracker.as_ref() {
+            tracker.begin(start_height, indexer.runtime.block_height);
+        }
 
         indexer.runtime.last_getheaders_send_time = get_epoch_time_secs();
         self.send_next_getheaders(indexer, start_height)
@@ -1245,6 +1465,10 @@ impl BitcoinMessageHandler for SpvClient {
                 // clear timeout
                 indexer.runtime.last_getheaders_send_time = 0;
 
+                if let Some(tracker) = self.sync_tracker.as_ref() {
+                    tracker.record_progress(block_height);
+                }
+
                 // if syncing requires to request more than one batch of 2000 headers,
                 // we'll provide some progress in the logs
                 let total = end_block_height - self.start_block_height;
@@ -1801,4 +2025,67 @@ mod test {
 
         spv_client.handle_headers(1, vec![]).unwrap();
     }
+
+    struct TestHeaderSyncObserver {
+        reports: Mutex<Vec<HeaderSyncStatus>>,
+    }
+
+    impl HeaderSyncObserver for TestHeaderSyncObserver {
+        fn header_sync_progress(&self, status: &HeaderSyncStatus) {
+            self.reports.lock().unwrap().push(status.clone());
+        }
+    }
+
+    #[test]
+    fn test_header_sync_tracker() {
+        let observer = Arc::new(TestHeaderSyncObserver {
+            reports: Mutex::new(vec![]),
+        });
+        let tracker = HeaderSyncTracker::new(Some(observer.clone()));
+        assert_eq!(tracker.get_status(), HeaderSyncStatus::default());
+
+        // finishing a download that never began is not reported
+        tracker.finish_at(100, 1_000);
+        assert!(observer.reports.lock().unwrap().is_empty());
+
+        tracker.begin_at(100, 10_100, 10_000);
+        let status = tracker.get_status();
+        assert!(status.syncing);
+        assert_eq!(status.first_height, 100);
+        assert_eq!(status.current_height, 100);
+        assert_eq!(status.target_height, 10_100);
+        assert_eq!(status.started_at, 10);
+        assert_eq!(status.eta_secs, None);
+        assert_eq!(observer.reports.lock().unwrap().len(), 1);
+
+        // 2000 headers in 10 seconds; progress within the report interval isn't reported
+        tracker.record_progress_at(2_100, 20_000);
+        let status = tracker.get_status();
+        assert_eq!(status.current_height, 2_100);
+        assert_eq!(status.headers_per_second, 200.0);
+        assert_eq!(status.eta_secs, Some(40));
+        assert_eq!(observer.reports.lock().unwrap().len(), 1);
+
+        // a reconnect resumes the download instead of restarting it
+        tracker.begin_at(2_100, 10_100, 30_000);
+        assert_eq!(tracker.get_status().first_height, 100);
+        assert_eq!(tracker.get_status().started_at, 10);
+        assert_eq!(observer.reports.lock().unwrap().len(), 2);
+
+        tracker.record_progress_at(4_100, 50_000);
+        assert_eq!(tracker.get_status().eta_secs, Some(60));
+        assert_eq!(observer.reports.lock().unwrap().len(), 2);
+
+        tracker.record_progress_at(6_100, 70_000);
+        assert_eq!(observer.reports.lock().unwrap().len(), 3);
+
+        tracker.finish_at(10_100, 90_000);
+        let status = tracker.get_status();
+        assert!(!status.syncing);
+        assert_eq!(status.current_height, 10_100);
+        assert_eq!(status.eta_secs, Some(0));
+        let reports = observer.reports.lock().unwrap();
+        assert_eq!(reports.len(), 4);
+        assert_eq!(reports.last(), Some(&status));
+    }
 }
@@ -54,7 +54,7 @@ use crate::chainstate::burn::distribution::BurnSamplePoint;
 use crate::chainstate::burn::operations::leader_block_commit::MissedBlockCommit;
 use crate::chainstate::burn::operations::{
     BlockstackOperationType, DelegateStxOp, LeaderBlockCommitOp, LeaderKeyRegisterOp, PreStxOp,
-    StackStxOp, TransferStxOp, VoteForAggregateKeyOp,
+    StackAggregationCommitOp, StackStxOp, TransferStxOp, VoteForAggregateKeyOp,
 };
 use crate::chainstate::burn::{BlockSnapshot, Opcodes};
 use crate::chainstate::coordinator::comm::CoordinatorChannels;
@@ -191,6 +191,9 @@ impl BurnchainStateTransition {
                 BlockstackOperationType::VoteForAggregateKey(_) => {
                     accepted_ops.push(block_ops[i].clone());
                 }
+                BlockstackOperationType::StackAggregationCommit(_) => {
+                    accepted_ops.push(block_ops[i].clone());
+                }
             };
         }
 
@@ -970,6 +973,37 @@ impl Burnchain {
                     None
                 }
             }
+            x if x == Opcodes::StackAggregationCommit as u8
+                && epoch_id.supports_stack_aggregation_commit_op() =>
+            {
+                let pre_stx_txid = StackAggregationCommitOp::get_sender_txid(burn_tx).ok()?;
+                let pre_stx_tx = match pre_stx_op_map.get(pre_stx_txid) {
+                    Some(tx_ref) => Some(BlockstackOperationType::PreStx(tx_ref.clone())),
+                    None => burnchain_db.find_burnchain_op(indexer, pre_stx_txid),
+                };
+                if let Some(BlockstackOperationType::PreStx(pre_stx)) = pre_stx_tx {
+                    let sender = &pre_stx.output;
+                    match StackAggregationCommitOp::from_tx(block_header, burn_tx, sender) {
+                        Ok(op) => Some(BlockstackOperationType::StackAggregationCommit(op)),
+                        Err(e) => {
+                            warn!(
+                                "Failed to parse stack-aggregation-commit tx";
+                                "txid" => %burn_tx.txid(),
+                                "data" => %to_hex(&burn_tx.data()),
+                                "error" => ?e,
+                            );
+                            None
+                        }
+                    }
+                } else {
+                    warn!(
+                        "Failed to find corresponding input to StackAggregationCommitOp";
+                        "txid" => %burn_tx.txid().to_string(),
+                        "pre_stx_txid" => %pre_stx_txid.to_string()
+                    );
+                    None
+                }
+            }
 
             _ => None,
         }
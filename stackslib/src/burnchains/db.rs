@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 use std::{cmp, fmt, fs, io};
 
@@ -23,6 +23,7 @@ use rusqlite::{params, Connection, OpenFlags, OptionalExtension, Row, Transactio
 use serde_json;
 use stacks_common::types::chainstate::BurnchainHeaderHash;
 use stacks_common::types::sqlite::NO_PARAMS;
+use stacks_common::util::get_epoch_time_secs;
 
 use crate::burnchains::affirmation::*;
 use crate::burnchains::{
@@ -33,7 +34,7 @@ use crate::chainstate::burn::BlockSnapshot;
 use crate::chainstate::stacks::index::{ClarityMarfTrieId, MarfTrieId};
 use crate::core::StacksEpochId;
 use crate::util_lib::db::{
-    opt_u64_to_sql, query_row, query_row_panic, query_rows, sql_pragma, sqlite_open,
+    opt_u64_to_sql, query_row, query_row_panic, query_rows, sql_pragma, sqlite_open, table_exists,
     tx_begin_immediate, tx_busy_handler, u64_to_sql, DBConn, Error as DBError, FromColumn, FromRow,
 };
 
@@ -83,6 +84,95 @@ pub struct BlockCommitMetadata {
     pub anchor_block_descendant: Option<u64>,
 }
 
+/// An operator's ruling on a reward cycle's PoX anchor block, which takes precedence over what
+/// the network's affirmations say about it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnchorBlockOverrideStatus {
+    /// Treat the anchor block as present, and wait for it if it is not known
+    Accept,
+    /// Treat the anchor block as absent, even if it is known
+    Reject,
+}
+
+impl AnchorBlockOverrideStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Accept => "accept",
+            Self::Reject => "reject",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "accept" => Some(Self::Accept),
+            "reject" => Some(Self::Reject),
+            _ => None,
+        }
+    }
+
+    /// The affirmation map entry this status forces
+    pub fn affirmation(&self) -> AffirmationMapEntry {
+        match self {
+            Self::Accept => AffirmationMapEntry::PoxAnchorBlockPresent,
+            Self::Reject => AffirmationMapEntry::PoxAnchorBlockAbsent,
+        }
+    }
+}
+
+/// Where an anchor block override came from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnchorBlockOverrideSource {
+    /// `[[burnchain.anchor_block_overrides]]` in the node's config file
+    Config,
+    /// The `/v3/admin/pox/anchor_blocks/:reward_cycle/override` RPC endpoint
+    Rpc,
+}
+
+impl AnchorBlockOverrideSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::Rpc => "rpc",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "config" => Some(Self::Config),
+            "rpc" => Some(Self::Rpc),
+            _ => None,
+        }
+    }
+}
+
+/// A stored override of a reward cycle's PoX anchor block status
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnchorBlockOverride {
+    /// The reward cycle the anchor block was chosen for
+    pub reward_cycle: u64,
+    pub status: AnchorBlockOverrideStatus,
+    pub source: AnchorBlockOverrideSource,
+    /// When the override was set, in seconds since the epoch
+    pub set_at: u64,
+}
+
+impl FromRow<AnchorBlockOverride> for AnchorBlockOverride {
+    fn from_row(row: &Row) -> Result<AnchorBlockOverride, DBError> {
+        let reward_cycle = u64::from_column(row, "reward_cycle")?;
+        let status: String = row.get_unwrap("status");
+        let source: String = row.get_unwrap("source");
+        let set_at = u64::from_column(row, "set_at")?;
+        Ok(AnchorBlockOverride {
+            reward_cycle,
+            status: AnchorBlockOverrideStatus::from_str(&status).ok_or(DBError::ParseError)?,
+            source: AnchorBlockOverrideSource::from_str(&source).ok_or(DBError::ParseError)?,
+            set_at,
+        })
+    }
+}
+
 impl FromColumn<AffirmationMap> for AffirmationMap {
     fn from_column(row: &Row, col_name: &str) -> Result<AffirmationMap, DBError> {
         let txt: String = row.get_unwrap(col_name);
@@ -209,6 +299,20 @@ impl FromRow<BlockstackOperationType> for BlockstackOperationType {
 
 pub const BURNCHAIN_DB_VERSION: &str = "2";
 
+/// Operator overrides of PoX anchor block statuses.  This table is created on demand, so that
+/// existing burnchain DBs get it without a schema migration.
+const ANCHOR_BLOCK_OVERRIDES_SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS anchor_block_overrides (
+    -- the reward cycle the anchor block was chosen for
+    reward_cycle INTEGER PRIMARY KEY NOT NULL,
+    -- "accept" or "reject"
+    status TEXT NOT NULL,
+    -- "config" or "rpc"
+    source TEXT NOT NULL,
+    set_at INTEGER NOT NULL
+);
+"#;
+
 const BURNCHAIN_DB_SCHEMA: &str = r#"
 CREATE TABLE burnchain_db_block_headers (
     -- height of the block (non-negative)
@@ -973,6 +1077,83 @@ impl BurnchainDBTransaction<'_> {
         stmt.execute(args)?;
         Ok(())
     }
+
+    /// Force the status of the anchor block chosen for `reward_cycle`, replacing any prior
+    /// override.  Only for recovering a node whose coordinator is stalled on an anchor block.
+    pub fn set_anchor_block_override(
+        &self,
+        reward_cycle: u64,
+        status: AnchorBlockOverrideStatus,
+        source: AnchorBlockOverrideSource,
+    ) -> Result<(), DBError> {
+        let prior = BurnchainDB::get_anchor_block_override(&self.sql_tx, reward_cycle)?;
+        warn!("Set PoX anchor block override";
+              "reward_cycle" => reward_cycle,
+              "status" => status.as_str(),
+              "source" => source.as_str(),
+              "prior_status" => prior.as_ref().map(|o| o.status.as_str()),
+              "prior_source" => prior.as_ref().map(|o| o.source.as_str()));
+        self.sql_tx.execute(
+            "INSERT OR REPLACE INTO anchor_block_overrides (reward_cycle, status, source, set_at) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                u64_to_sql(reward_cycle)?,
+                status.as_str(),
+                source.as_str(),
+                u64_to_sql(get_epoch_time_secs())?
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Remove the override of the anchor block status for `reward_cycle`.  Returns the removed
+    /// override, if there was one.
+    pub fn clear_anchor_block_override(
+        &self,
+        reward_cycle: u64,
+    ) -> Result<Option<AnchorBlockOverride>, DBError> {
+        let prior = BurnchainDB::get_anchor_block_override(&self.sql_tx, reward_cycle)?;
+        if let Some(prior) = prior.as_ref() {
+            warn!("Cleared PoX anchor block override";
+                  "reward_cycle" => reward_cycle,
+                  "status" => prior.status.as_str(),
+                  "source" => prior.source.as_str());
+            self.sql_tx.execute(
+                "DELETE FROM anchor_block_overrides WHERE reward_cycle = ?1",
+                params![u64_to_sql(reward_cycle)?],
+            )?;
+        }
+        Ok(prior)
+    }
+
+    /// Make the config file's anchor block overrides in the DB match `overrides`.  Configured
+    /// overrides replace RPC overrides of the same reward cycles, and config overrides that are no
+    /// longer configured are cleared.
+    pub fn sync_config_anchor_block_overrides(
+        &self,
+        overrides: &BTreeMap<u64, AnchorBlockOverrideStatus>,
+    ) -> Result<(), DBError> {
+        for existing in BurnchainDB::get_anchor_block_overrides(&self.sql_tx)?.into_iter() {
+            if existing.source == AnchorBlockOverrideSource::Config
+                && !overrides.contains_key(&existing.reward_cycle)
+            {
+                self.clear_anchor_block_override(existing.reward_cycle)?;
+            }
+        }
+        for (reward_cycle, status) in overrides.iter() {
+            let existing = BurnchainDB::get_anchor_block_override(&self.sql_tx, *reward_cycle)?;
+            if existing.map(|o| (o.status, o.source))
+                == Some((*status, AnchorBlockOverrideSource::Config))
+            {
+                continue;
+            }
+            self.set_anchor_block_override(
+                *reward_cycle,
+                *status,
+                AnchorBlockOverrideSource::Config,
+            )?;
+        }
+        Ok(())
+    }
 }
 
 impl BurnchainDB {
@@ -993,6 +1174,11 @@ impl BurnchainDB {
         Ok(())
     }
 
+    fn add_anchor_block_overrides(&mut self) -> Result<(), BurnchainError> {
+        self.conn.execute_batch(ANCHOR_BLOCK_OVERRIDES_SCHEMA)?;
+        Ok(())
+    }
+
     pub fn connect(
         path: &str,
         burnchain: &Burnchain,
@@ -1080,6 +1266,7 @@ impl BurnchainDB {
 
         if readwrite {
             db.add_indexes()?;
+            db.add_anchor_block_overrides()?;
         }
         Ok(db)
     }
@@ -1095,6 +1282,7 @@ impl BurnchainDB {
 
         if readwrite || path == ":memory:" {
             db.add_indexes()?;
+            db.add_anchor_block_overrides()?;
         }
         Ok(db)
     }
@@ -1162,6 +1350,16 @@ impl BurnchainDB {
         })
     }
 
+    /// Get every copy of the operation with the given txid, across all burnchain forks
+    pub fn get_burnchain_ops_by_txid(
+        conn: &DBConn,
+        txid: &Txid,
+    ) -> Result<Vec<BlockstackOperationType>, BurnchainError> {
+        let qry = "SELECT DISTINCT op FROM burnchain_db_block_ops WHERE txid = ?1";
+        let ops = query_rows(conn, qry, params![txid])?;
+        Ok(ops)
+    }
+
     fn inner_get_burnchain_op(
         conn: &DBConn,
         burn_header_hash: &BurnchainHeaderHash,
@@ -1330,6 +1528,15 @@ impl BurnchainDB {
         Ok(metadatas)
     }
 
+    /// Load the metadata of every block-commit chosen as an anchor block, on any burnchain fork,
+    /// in reward cycle order
+    pub fn get_all_anchor_block_commit_metadatas(
+        conn: &DBConn,
+    ) -> Result<Vec<BlockCommitMetadata>, DBError> {
+        let sql = "SELECT * FROM block_commit_metadata WHERE anchor_block IS NOT NULL ORDER BY anchor_block, block_height, vtxindex";
+        query_rows(conn, sql, NO_PARAMS)
+    }
+
     pub fn get_canonical_anchor_block_commit_metadata<B: BurnchainHeaderReader>(
         conn: &DBConn,
         indexer: &B,
@@ -1392,8 +1599,8 @@ impl BurnchainDB {
         }
     }
 
-    // do NOT call directly; only call directly in tests.
-    // This is only `pub` because the tests for it live in a different file.
+    // do NOT call directly, except in tests and for burnchains whose operations are
+    // synthesized rather than parsed from transactions (i.e. the node's mocknet mode).
     pub fn store_new_burnchain_block_ops_unchecked<B: BurnchainHeaderReader>(
         &mut self,
         burnchain: &Burnchain,
@@ -1576,13 +1783,14 @@ impl BurnchainDB {
                     return Ok(am);
                 }
 
-                let am = BurnchainDB::get_affirmation_map(conn, metadata.affirmation_id)?
+                let mut am = BurnchainDB::get_affirmation_map(conn, metadata.affirmation_id)?
                     .unwrap_or_else(|| {
                         panic!(
                             "BUG: failed to load affirmation map {}",
                             metadata.affirmation_id
                         )
                     });
+                BurnchainDB::apply_anchor_block_overrides(conn, &mut am)?;
 
                 if cfg!(test) {
                     let _weight =
@@ -1629,6 +1837,64 @@ impl BurnchainDB {
         Ok(am_opt)
     }
 
+    /// Load the operator's override of the anchor block status for `reward_cycle`, if any
+    pub fn get_anchor_block_override(
+        conn: &DBConn,
+        reward_cycle: u64,
+    ) -> Result<Option<AnchorBlockOverride>, DBError> {
+        if !table_exists(conn, "anchor_block_overrides")? {
+            return Ok(None);
+        }
+        query_row(
+            conn,
+            "SELECT * FROM anchor_block_overrides WHERE reward_cycle = ?1",
+            params![u64_to_sql(reward_cycle)?],
+        )
+    }
+
+    /// Load all of the operator's anchor block status overrides, in reward cycle order
+    pub fn get_anchor_block_overrides(conn: &DBConn) -> Result<Vec<AnchorBlockOverride>, DBError> {
+        if !table_exists(conn, "anchor_block_overrides")? {
+            return Ok(vec![]);
+        }
+        query_rows(
+            conn,
+            "SELECT * FROM anchor_block_overrides ORDER BY reward_cycle",
+            NO_PARAMS,
+        )
+    }
+
+    /// Replace the entries of `am` for reward cycles whose anchor block status the operator has
+    /// overridden.  Entries for reward cycles that chose no anchor block are left alone.
+    pub fn apply_anchor_block_overrides(
+        conn: &DBConn,
+        am: &mut AffirmationMap,
+    ) -> Result<(), DBError> {
+        for anchor_override in BurnchainDB::get_anchor_block_overrides(conn)?.iter() {
+            let Some(index) = anchor_override
+                .reward_cycle
+                .checked_sub(1)
+                .and_then(|index| usize::try_from(index).ok())
+            else {
+                continue;
+            };
+            let Some(entry) = am.affirmations.get_mut(index) else {
+                continue;
+            };
+            let forced = anchor_override.status.affirmation();
+            if *entry == AffirmationMapEntry::Nothing || *entry == forced {
+                continue;
+            }
+            warn!("Overriding affirmed PoX anchor block status";
+                  "reward_cycle" => anchor_override.reward_cycle,
+                  "affirmed" => %entry,
+                  "forced" => %forced,
+                  "source" => anchor_override.source.as_str());
+            *entry = forced;
+        }
+        Ok(())
+    }
+
     /// Get the canonical affirmation map.  This is the heaviest anchor block affirmation map, but
     /// accounting for any subsequent reward cycles whose anchor blocks either aren't on the
     /// heaviest anchor block affirmation map, or which have no anchor blocks.
@@ -1700,6 +1966,8 @@ impl BurnchainDB {
             }
         }
 
+        // cover the reward cycles appended after the heaviest anchor block's
+        BurnchainDB::apply_anchor_block_overrides(conn, &mut heaviest_am)?;
         Ok(heaviest_am)
     }
 }
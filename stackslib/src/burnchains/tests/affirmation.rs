@@ -2931,3 +2931,94 @@ fn test_update_pox_affirmation_fork_duel() {
     assert_eq!(heaviest_am, AffirmationMap::decode("apa").unwrap());
     assert_eq!(canonical_am, AffirmationMap::decode("apap").unwrap());
 }
+
+#[test]
+fn test_affirmations_report() {
+    let first_bhh = BurnchainHeaderHash([0; 32]);
+    let first_timestamp = 0;
+    let first_height = 0;
+
+    let mut burnchain = Burnchain::regtest(":memory:");
+    burnchain.pox_constants = make_test_pox(10, 5, 3, 3);
+    burnchain.first_block_height = first_height;
+    burnchain.first_block_hash = first_bhh.clone();
+    burnchain.first_block_timestamp = first_timestamp;
+
+    let mut burnchain_db = BurnchainDB::connect(":memory:", &burnchain, true).unwrap();
+
+    let first_block_header = burnchain_db.get_canonical_chain_tip().unwrap();
+
+    let mut headers = vec![first_block_header.clone()];
+    let key_register = make_simple_key_register(&first_block_header.block_hash, 0, 1);
+
+    // first reward cycle is all (linear) commits, so it must elect an anchor block
+    let (_next_headers, _commits_0) = make_reward_cycle(
+        &mut burnchain_db,
+        &burnchain,
+        &key_register,
+        &mut headers,
+        vec![None],
+    );
+    update_pox_affirmation_maps(&mut burnchain_db, &headers, 0, &burnchain).unwrap();
+
+    let anchor_block_1 =
+        BurnchainDB::get_canonical_anchor_block_commit(burnchain_db.conn(), &headers, 1)
+            .unwrap()
+            .unwrap()
+            .0;
+    let heaviest_am = BurnchainDB::get_heaviest_anchor_block_affirmation_map(
+        burnchain_db.conn(),
+        &burnchain,
+        &headers,
+    )
+    .unwrap();
+    let canonical_am = BurnchainDB::get_canonical_affirmation_map(
+        burnchain_db.conn(),
+        &burnchain,
+        &headers,
+        |_, _| true,
+    )
+    .unwrap();
+    assert_eq!(canonical_am, AffirmationMap::decode("p").unwrap());
+
+    let tip_height = headers.last().unwrap().block_height;
+    let report = AffirmationsReport::new(
+        &burnchain,
+        &burnchain_db,
+        tip_height,
+        &heaviest_am,
+        &canonical_am,
+        &AffirmationMap::decode("a").unwrap(),
+        &AffirmationMap::empty(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        Some(report.reward_cycle),
+        burnchain.block_height_to_reward_cycle(tip_height)
+    );
+    assert_eq!(report.canonical.weight, 1);
+    assert_eq!(
+        report.canonical.entries,
+        vec![DecodedAffirmation {
+            reward_cycle: 1,
+            affirmation: "p".into(),
+            description: "anchor block present".into(),
+        }]
+    );
+    assert!(report.heaviest.entries.is_empty());
+
+    // the sortition tip disagrees about reward cycle 1's anchor block, but the Stacks tip has
+    // affirmed nothing yet
+    assert_eq!(report.sortition_tip_divergence, Some(1));
+    assert_eq!(report.stacks_tip_divergence, None);
+
+    assert_eq!(report.anchor_block_commits.len(), 1);
+    assert_eq!(report.anchor_block_commits[0].reward_cycle, 1);
+    assert_eq!(report.anchor_block_commits[0].txid, anchor_block_1.txid);
+    assert_eq!(
+        report.anchor_block_commits[0].block_height,
+        anchor_block_1.block_height
+    );
+    assert!(report.overrides.is_empty());
+}
@@ -29,7 +29,9 @@ use crate::burnchains::affirmation::AffirmationMap;
 use crate::burnchains::bitcoin::address::*;
 use crate::burnchains::bitcoin::blocks::*;
 use crate::burnchains::bitcoin::*;
-use crate::burnchains::db::apply_blockstack_txs_safety_checks;
+use crate::burnchains::db::{
+    apply_blockstack_txs_safety_checks, AnchorBlockOverrideSource, AnchorBlockOverrideStatus,
+};
 use crate::burnchains::{Error as BurnchainError, PoxConstants, BLOCKSTACK_MAGIC_MAINNET};
 use crate::chainstate::burn::operations::leader_block_commit::BURN_BLOCK_MINED_AT_MODULUS;
 use crate::chainstate::burn::*;
@@ -509,6 +511,132 @@ fn test_classify_stack_stx() {
     }
 }
 
+#[test]
+fn test_classify_stack_aggregation_commit() {
+    let first_bhh = BurnchainHeaderHash::from_hex(BITCOIN_REGTEST_FIRST_BLOCK_HASH).unwrap();
+
+    let mut burnchain = Burnchain::regtest(":memory:");
+    let mut burnchain_db = BurnchainDB::connect(":memory:", &burnchain, true).unwrap();
+
+    burnchain.pox_constants = PoxConstants::test_default();
+    burnchain.pox_constants.sunset_start = 999;
+    burnchain.pox_constants.sunset_end = 1000;
+
+    let first_block_header = burnchain_db.get_canonical_chain_tip().unwrap();
+    let mut headers = vec![first_block_header.clone()];
+
+    let pre_stx_txid = Txid([5; 32]);
+    let pre_stx = BitcoinTransaction {
+        txid: pre_stx_txid.clone(),
+        vtxindex: 0,
+        opcode: Opcodes::PreStx as u8,
+        data: vec![0; 80],
+        data_amt: 0,
+        inputs: vec![BitcoinTxInputStructured {
+            keys: vec![],
+            num_required: 0,
+            in_type: BitcoinInputType::Standard,
+            tx_ref: (Txid([0; 32]), 1),
+        }
+        .into()],
+        outputs: vec![BitcoinTxOutput {
+            units: 10,
+            address: BitcoinAddress::Legacy(LegacyBitcoinAddress {
+                addrtype: LegacyBitcoinAddressType::PublicKeyHash,
+                network_id: BitcoinNetworkType::Mainnet,
+                bytes: Hash160([1; 20]),
+            }),
+        }],
+    };
+
+    // reward cycle, signer key, max amount, and auth ID
+    let mut agg_commit_data = 7u64.to_be_bytes().to_vec();
+    agg_commit_data.extend_from_slice(&[2; 33]);
+    agg_commit_data.extend_from_slice(&u128::MAX.to_be_bytes());
+    agg_commit_data.extend_from_slice(&1u32.to_be_bytes());
+    let make_agg_commit = |txid: Txid| BitcoinTransaction {
+        txid,
+        vtxindex: 1,
+        opcode: Opcodes::StackAggregationCommit as u8,
+        data: agg_commit_data.clone(),
+        data_amt: 0,
+        inputs: vec![BitcoinTxInputStructured {
+            keys: vec![],
+            num_required: 0,
+            in_type: BitcoinInputType::Standard,
+            tx_ref: (pre_stx_txid.clone(), 1),
+        }
+        .into()],
+        outputs: vec![BitcoinTxOutput {
+            units: 10,
+            address: BitcoinAddress::Legacy(LegacyBitcoinAddress {
+                addrtype: LegacyBitcoinAddressType::PublicKeyHash,
+                network_id: BitcoinNetworkType::Mainnet,
+                bytes: Hash160([2; 20]),
+            }),
+        }],
+    };
+
+    let blocks: Vec<_> = [
+        vec![pre_stx],
+        vec![make_agg_commit(Txid([6; 32]))],
+        vec![make_agg_commit(Txid([7; 32]))],
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, ops)| {
+        let i = i as u64;
+        let parent_hash = headers.last().unwrap().block_hash.clone();
+        let block_hash = BurnchainHeaderHash([i as u8 + 2; 32]);
+        headers.push(BurnchainBlockHeader {
+            block_height: first_block_header.block_height + i + 1,
+            block_hash: block_hash.clone(),
+            parent_block_hash: parent_hash.clone(),
+            num_txs: ops.len() as u64,
+            timestamp: first_block_header.timestamp + i + 1,
+        });
+        BurnchainBlock::Bitcoin(BitcoinBlock::new(
+            501 + i,
+            &block_hash,
+            &parent_hash,
+            ops,
+            350 + i,
+        ))
+    })
+    .collect();
+
+    let processed_ops = burnchain_db
+        .store_new_burnchain_block(&burnchain, &headers, &blocks[0], StacksEpochId::Epoch30)
+        .unwrap();
+    assert_eq!(processed_ops.len(), 1, "The pre-stx op should be accepted");
+
+    // stack-aggregation-commit ops are ignored before epoch 3.1
+    let processed_ops = burnchain_db
+        .store_new_burnchain_block(&burnchain, &headers, &blocks[1], StacksEpochId::Epoch30)
+        .unwrap();
+    assert!(processed_ops.is_empty());
+
+    let processed_ops = burnchain_db
+        .store_new_burnchain_block(&burnchain, &headers, &blocks[2], StacksEpochId::Epoch31)
+        .unwrap();
+    assert_eq!(processed_ops.len(), 1);
+    let BlockstackOperationType::StackAggregationCommit(op) = &processed_ops[0] else {
+        panic!("EXPECTED to parse a stack-aggregation-commit op");
+    };
+    assert_eq!(op.txid, Txid([7; 32]));
+    assert_eq!(
+        op.sender,
+        StacksAddress::from_legacy_bitcoin_address(&LegacyBitcoinAddress {
+            addrtype: LegacyBitcoinAddressType::PublicKeyHash,
+            network_id: BitcoinNetworkType::Mainnet,
+            bytes: Hash160([1; 20]),
+        })
+    );
+    assert_eq!(op.reward_cycle, 7);
+    assert_eq!(op.max_amount, u128::MAX);
+    assert_eq!(op.auth_id, 1);
+}
+
 pub fn make_simple_block_commit(
     burnchain: &Burnchain,
     parent: Option<&LeaderBlockCommitOp>,
@@ -1355,3 +1483,96 @@ fn test_classify_delegate_stx() {
         panic!("EXPECTED to parse a delegate stx op");
     }
 }
+
+#[test]
+fn test_anchor_block_overrides() {
+    let burnchain = Burnchain::regtest(":memory:");
+    let mut burnchain_db = BurnchainDB::connect(":memory:", &burnchain, true).unwrap();
+
+    assert!(BurnchainDB::get_anchor_block_overrides(burnchain_db.conn())
+        .unwrap()
+        .is_empty());
+
+    let tx = burnchain_db.tx_begin().unwrap();
+    tx.set_anchor_block_override(
+        2,
+        AnchorBlockOverrideStatus::Reject,
+        AnchorBlockOverrideSource::Rpc,
+    )
+    .unwrap();
+    tx.set_anchor_block_override(
+        3,
+        AnchorBlockOverrideStatus::Accept,
+        AnchorBlockOverrideSource::Rpc,
+    )
+    .unwrap();
+    tx.set_anchor_block_override(
+        4,
+        AnchorBlockOverrideStatus::Accept,
+        AnchorBlockOverrideSource::Rpc,
+    )
+    .unwrap();
+    tx.commit().unwrap();
+
+    let overrides = BurnchainDB::get_anchor_block_overrides(burnchain_db.conn()).unwrap();
+    assert_eq!(
+        overrides
+            .iter()
+            .map(|o| (o.reward_cycle, o.status))
+            .collect::<Vec<_>>(),
+        vec![
+            (2, AnchorBlockOverrideStatus::Reject),
+            (3, AnchorBlockOverrideStatus::Accept),
+            (4, AnchorBlockOverrideStatus::Accept),
+        ]
+    );
+
+    // overrides replace affirmed anchor block statuses, but not cycles without anchor blocks
+    let mut am = AffirmationMap::decode("ppn").unwrap();
+    BurnchainDB::apply_anchor_block_overrides(burnchain_db.conn(), &mut am).unwrap();
+    assert_eq!(am, AffirmationMap::decode("pan").unwrap());
+
+    // config overrides replace RPC overrides, and unconfigured config overrides are cleared
+    let tx = burnchain_db.tx_begin().unwrap();
+    tx.sync_config_anchor_block_overrides(
+        &[
+            (3, AnchorBlockOverrideStatus::Reject),
+            (5, AnchorBlockOverrideStatus::Reject),
+        ]
+        .into_iter()
+        .collect(),
+    )
+    .unwrap();
+    tx.commit().unwrap();
+    let tx = burnchain_db.tx_begin().unwrap();
+    tx.sync_config_anchor_block_overrides(
+        &[(3, AnchorBlockOverrideStatus::Reject)]
+            .into_iter()
+            .collect(),
+    )
+    .unwrap();
+    let cleared = tx.clear_anchor_block_override(2).unwrap().unwrap();
+    assert_eq!(cleared.status, AnchorBlockOverrideStatus::Reject);
+    assert!(tx.clear_anchor_block_override(2).unwrap().is_none());
+    tx.commit().unwrap();
+
+    let overrides = BurnchainDB::get_anchor_block_overrides(burnchain_db.conn()).unwrap();
+    assert_eq!(
+        overrides
+            .iter()
+            .map(|o| (o.reward_cycle, o.status, o.source))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                3,
+                AnchorBlockOverrideStatus::Reject,
+                AnchorBlockOverrideSource::Config
+            ),
+            (
+                4,
+                AnchorBlockOverrideStatus::Accept,
+                AnchorBlockOverrideSource::Rpc
+            ),
+        ]
+    );
+}
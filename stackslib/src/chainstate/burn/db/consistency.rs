@@ -0,0 +1,376 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Offline consistency checking and repair of the sortition DB.
+//!
+//! The checker walks the canonical sortition fork from its tip back to the first sortition, and
+//! verifies that
+//! * each snapshot links to a valid parent snapshot one burn block lower, with the same burnchain
+//!   header hash that the snapshot records as its parent,
+//! * each snapshot's sortition ID and consensus hash are re-derivable from its burnchain block,
+//!   ops hash, total burn, PoX bit vector, and the consensus hashes of its ancestors,
+//! * each snapshot's burnchain block is stored in the burnchain DB at the same height and with
+//!   the same parent, and
+//! * in epochs 2.1 through 2.5, any PoX anchor block recorded at the start of a reward cycle is
+//!   the one the burnchain DB's block-commit metadata selects.
+//!
+//! Repair rolls the sortition DB back to the last sortition below the lowest inconsistency, by
+//! deleting every sortition above it (in all forks) along with its derived rows and sortition
+//! MARF trie.  The node's coordinator re-derives the deleted sortitions from the raw burnchain
+//! blocks in the burnchain DB when it next starts.
+
+use rusqlite::params;
+use stacks_common::types::chainstate::SortitionId;
+
+use crate::burnchains::db::BurnchainDB;
+use crate::burnchains::{Burnchain, Error as BurnchainError};
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::burn::{BlockSnapshot, ConsensusHash, ConsensusHashExtensions};
+use crate::core::StacksEpochId;
+use crate::util_lib::db::{table_exists, u64_to_sql, Error as db_error};
+
+/// A single inconsistency found in the sortition DB
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortitionDBInconsistency {
+    /// Burn block height of the inconsistent sortition
+    pub block_height: u64,
+    /// ID of the inconsistent sortition
+    pub sortition_id: SortitionId,
+    /// What is wrong with it
+    pub reason: String,
+}
+
+/// Outcome of checking the sortition DB's canonical fork
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SortitionDBCheckReport {
+    /// Number of sortitions checked
+    pub checked_sortitions: u64,
+    /// Inconsistencies found, in ascending order of height
+    pub inconsistencies: Vec<SortitionDBInconsistency>,
+}
+
+impl SortitionDBCheckReport {
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+
+    /// Height of the lowest inconsistent sortition, if there is one
+    pub fn first_inconsistent_height(&self) -> Option<u64> {
+        self.inconsistencies.iter().map(|i| i.block_height).min()
+    }
+
+    fn push(&mut self, sn: &BlockSnapshot, reason: String) {
+        self.inconsistencies.push(SortitionDBInconsistency {
+            block_height: sn.block_height,
+            sortition_id: sn.sortition_id.clone(),
+            reason,
+        });
+    }
+}
+
+impl SortitionDB {
+    /// Check the canonical sortition fork against itself and against the burnchain DB.
+    /// Inconsistencies are reported, not returned as errors; an error means the check itself
+    /// could not be carried out.
+    pub fn check_consistency(
+        &self,
+        burnchain: &Burnchain,
+        burnchain_db: &BurnchainDB,
+    ) -> Result<SortitionDBCheckReport, BurnchainError> {
+        let mut report = SortitionDBCheckReport::default();
+        let fork = self.check_canonical_ancestry(&mut report)?;
+
+        for (i, sn) in fork.iter().enumerate() {
+            report.checked_sortitions += 1;
+            if sn.block_height == self.first_block_height {
+                continue;
+            }
+            self.check_consensus_hash(sn, &fork, &mut report)?;
+            Self::check_burnchain_block(sn, burnchain_db, &mut report)?;
+            if let Some(parent) = i.checked_sub(1).and_then(|j| fork.get(j)) {
+                self.check_pox_anchor(sn, parent, burnchain, burnchain_db, &mut report)?;
+            }
+        }
+
+        report
+            .inconsistencies
+            .sort_by_key(|inconsistency| inconsistency.block_height);
+        Ok(report)
+    }
+
+    /// Walk the canonical fork from its tip back to the first sortition, checking each
+    /// snapshot's link to its parent.  Returns the snapshots walked, in ascending order of
+    /// height.  The walk stops early if a parent snapshot is missing or misplaced, in which case
+    /// the first snapshot returned is not the first sortition.
+    fn check_canonical_ancestry(
+        &self,
+        report: &mut SortitionDBCheckReport,
+    ) -> Result<Vec<BlockSnapshot>, BurnchainError> {
+        let mut cursor = SortitionDB::get_canonical_burn_chain_tip(self.conn())?;
+        let mut fork = vec![];
+        while cursor.block_height > self.first_block_height {
+            let parent_opt =
+                SortitionDB::get_block_snapshot(self.conn(), &cursor.parent_sortition_id)?;
+            let Some(parent) = parent_opt.filter(|p| p.block_height + 1 == cursor.block_height)
+            else {
+                report.push(
+                    &cursor,
+                    format!(
+                        "parent sortition {} is missing or at the wrong height",
+                        &cursor.parent_sortition_id
+                    ),
+                );
+                fork.push(cursor);
+                fork.reverse();
+                return Ok(fork);
+            };
+            if parent.burn_header_hash != cursor.parent_burn_header_hash {
+                report.push(
+                    &cursor,
+                    format!(
+                        "parent burn header hash {} does not match parent sortition's {}",
+                        &cursor.parent_burn_header_hash, &parent.burn_header_hash
+                    ),
+                );
+            }
+            if !parent.pox_valid {
+                report.push(
+                    &cursor,
+                    format!("parent sortition {} is not PoX-valid", &parent.sortition_id),
+                );
+            }
+            fork.push(cursor);
+            cursor = parent;
+        }
+        fork.push(cursor);
+        fork.reverse();
+        Ok(fork)
+    }
+
+    /// Re-derive a snapshot's sortition ID and consensus hash.  `fork` holds the canonical fork
+    /// in ascending order of height; the check is skipped if it does not reach back far enough.
+    fn check_consensus_hash(
+        &self,
+        sn: &BlockSnapshot,
+        fork: &[BlockSnapshot],
+        report: &mut SortitionDBCheckReport,
+    ) -> Result<(), BurnchainError> {
+        let Some(fork_start) = fork.first().map(|first| first.block_height) else {
+            return Ok(());
+        };
+        let pox_id = self.index_handle(&sn.sortition_id).get_pox_id()?;
+        let expected_sortition_id = SortitionId::new(&sn.burn_header_hash, &pox_id);
+        if expected_sortition_id != sn.sortition_id {
+            report.push(
+                sn,
+                format!(
+                    "sortition ID does not match PoX ID {pox_id} (expected {expected_sortition_id})"
+                ),
+            );
+        }
+
+        // same geometric series of ancestors as ConsensusHash::get_prev_consensus_hashes()
+        let parent_height = sn.block_height - 1;
+        let mut prev_chs = vec![];
+        for i in 0..64 {
+            let Some(prev_height) = parent_height
+                .checked_sub((1u64 << i) - 1)
+                .filter(|h| *h >= self.first_block_height)
+            else {
+                break;
+            };
+            if prev_height < fork_start {
+                // the ancestry walk stopped early, so this can't be checked
+                return Ok(());
+            }
+            let prev_ch = fork
+                .get(usize::try_from(prev_height - fork_start).expect("FATAL: fork too long"))
+                .map(|prev| prev.consensus_hash.clone())
+                .unwrap_or(ConsensusHash::empty());
+            prev_chs.push(prev_ch);
+        }
+
+        let expected_ch = ConsensusHash::from_ops(
+            &sn.burn_header_hash,
+            &sn.ops_hash,
+            sn.total_burn,
+            &prev_chs,
+            &pox_id,
+        );
+        if expected_ch != sn.consensus_hash {
+            report.push(
+                sn,
+                format!(
+                    "consensus hash {} does not match derived consensus hash {expected_ch}",
+                    &sn.consensus_hash
+                ),
+            );
+        }
+        Ok(())
+    }
+
+    /// Check that a snapshot's burnchain block is in the burnchain DB, where the snapshot says
+    /// it is
+    fn check_burnchain_block(
+        sn: &BlockSnapshot,
+        burnchain_db: &BurnchainDB,
+        report: &mut SortitionDBCheckReport,
+    ) -> Result<(), BurnchainError> {
+        let header =
+            match BurnchainDB::get_burnchain_block(burnchain_db.conn(), &sn.burn_header_hash) {
+                Ok(block) => block.header,
+                Err(BurnchainError::UnknownBlock(_)) => {
+                    report.push(
+                        sn,
+                        format!(
+                            "burnchain block {} is not in the burnchain DB",
+                            &sn.burn_header_hash
+                        ),
+                    );
+                    return Ok(());
+                }
+                Err(e) => return Err(e),
+            };
+        if header.block_height != sn.block_height {
+            report.push(
+                sn,
+                format!(
+                    "burnchain block {} is at height {} in the burnchain DB",
+                    &sn.burn_header_hash, header.block_height
+                ),
+            );
+        }
+        if header.parent_block_hash != sn.parent_burn_header_hash {
+            report.push(
+                sn,
+                format!(
+                    "burnchain block {} has parent {} in the burnchain DB",
+                    &sn.burn_header_hash, &header.parent_block_hash
+                ),
+            );
+        }
+        Ok(())
+    }
+
+    /// If `sn` starts a reward cycle in epochs 2.1 through 2.5 and recorded a PoX anchor block,
+    /// check that it is the one selected by the burnchain DB's block-commit metadata.
+    fn check_pox_anchor(
+        &self,
+        sn: &BlockSnapshot,
+        parent: &BlockSnapshot,
+        burnchain: &Burnchain,
+        burnchain_db: &BurnchainDB,
+        report: &mut SortitionDBCheckReport,
+    ) -> Result<(), BurnchainError> {
+        if !burnchain.is_reward_cycle_start(sn.block_height) {
+            return Ok(());
+        }
+        let Some(epoch) = SortitionDB::get_stacks_epoch(self.conn(), sn.block_height)? else {
+            report.push(sn, "no epoch is defined at this height".into());
+            return Ok(());
+        };
+        if epoch.epoch_id < StacksEpochId::Epoch21
+            || epoch.epoch_id >= StacksEpochId::Epoch30
+            || burnchain
+                .pox_constants
+                .is_after_pox_sunset_end(sn.block_height, epoch.epoch_id)
+        {
+            return Ok(());
+        }
+
+        let expected_txid = match self
+            .index_handle(&parent.sortition_id)
+            .get_chosen_pox_anchor(
+                Some(burnchain_db.conn()),
+                &sn.parent_burn_header_hash,
+                &burnchain.pox_constants,
+            ) {
+            Ok(anchor_opt) => anchor_opt.map(|(_, _, txid)| txid),
+            Err(e) => {
+                report.push(sn, format!("failed to select PoX anchor block: {e:?}"));
+                return Ok(());
+            }
+        };
+        let recorded_txid = self
+            .index_handle(&sn.sortition_id)
+            .get_last_anchor_block_txid()?;
+        // the coordinator may record no anchor block where the burnchain DB selects one, if the
+        // canonical affirmation map says none was selected, so only a recorded anchor is checked
+        if recorded_txid.is_some() && expected_txid != recorded_txid {
+            report.push(
+                sn,
+                format!(
+                    "recorded PoX anchor block-commit {recorded_txid:?} does not match burnchain DB's {expected_txid:?}"
+                ),
+            );
+        }
+        Ok(())
+    }
+
+    /// Roll the sortition DB back to the sortition at `block_height` on the canonical fork, so
+    /// that the coordinator re-derives everything above it from the burnchain DB.  Every sortition
+    /// above `block_height`, in every fork, is deleted along with its derived rows and its trie
+    /// in the sortition MARF.  The DB must be reopened afterwards.
+    ///
+    /// Returns the number of sortitions deleted.
+    pub fn rollback_to_height(&mut self, block_height: u64) -> Result<u64, BurnchainError> {
+        if block_height < self.first_block_height {
+            return Err(db_error::NotFoundError.into());
+        }
+        let height = u64_to_sql(block_height)?;
+        let db_tx = self.tx_begin()?;
+
+        // tries only back-point into lower tries, so the tries above this height can go
+        db_tx.tx().execute(
+            "DELETE FROM marf_data WHERE block_hash IN (SELECT sortition_id FROM snapshots WHERE block_height > ?1)",
+            params![height],
+        )?;
+        for sql in [
+            "DELETE FROM block_commit_parents WHERE block_commit_sortition_id IN (SELECT sortition_id FROM snapshots WHERE block_height > ?1)",
+            "DELETE FROM block_commits WHERE sortition_id IN (SELECT sortition_id FROM snapshots WHERE block_height > ?1)",
+            "DELETE FROM leader_keys WHERE sortition_id IN (SELECT sortition_id FROM snapshots WHERE block_height > ?1)",
+            "DELETE FROM missed_commits WHERE intended_sortition_id IN (SELECT sortition_id FROM snapshots WHERE block_height > ?1)",
+            "DELETE FROM snapshot_transition_ops WHERE sortition_id IN (SELECT sortition_id FROM snapshots WHERE block_height > ?1)",
+            "DELETE FROM preprocessed_reward_sets WHERE sortition_id IN (SELECT sortition_id FROM snapshots WHERE block_height > ?1)",
+            "DELETE FROM stacks_chain_tips WHERE sortition_id IN (SELECT sortition_id FROM snapshots WHERE block_height > ?1)",
+            "DELETE FROM stack_stx WHERE block_height > ?1",
+            "DELETE FROM transfer_stx WHERE block_height > ?1",
+            "DELETE FROM delegate_stx WHERE block_height > ?1",
+            "DELETE FROM vote_for_aggregate_key WHERE block_height > ?1",
+            "DELETE FROM stack_aggregation_commit WHERE block_height > ?1",
+        ] {
+            db_tx.tx().execute(sql, params![height])?;
+        }
+        if table_exists(db_tx.tx(), "snapshot_burn_distributions")? {
+            // only maintained in tests
+            db_tx.tx().execute(
+                "DELETE FROM snapshot_burn_distributions WHERE sortition_id IN (SELECT sortition_id FROM snapshots WHERE block_height > ?1)",
+                params![height],
+            )?;
+        }
+        let deleted = db_tx.tx().execute(
+            "DELETE FROM snapshots WHERE block_height > ?1",
+            params![height],
+        )?;
+        db_tx.commit()?;
+
+        info!(
+            "Rolled back sortition DB";
+            "block_height" => block_height,
+            "deleted_sortitions" => deleted
+        );
+        Ok(u64::try_from(deleted).expect("FATAL: too many deleted sortitions"))
+    }
+}
@@ -102,6 +102,15 @@ impl SortitionHandleTx<'_> {
                 );
                 BurnchainError::OpError(e)
             }),
+            BlockstackOperationType::StackAggregationCommit(ref op) => {
+                op.check().map_err(|e| {
+                    warn!(
+                        "REJECTED({}) stack aggregation commit op {} at {},{}: {:?}",
+                        op.block_height, &op.txid, op.block_height, op.vtxindex, &e
+                    );
+                    BurnchainError::OpError(e)
+                })
+            }
         }
     }
 
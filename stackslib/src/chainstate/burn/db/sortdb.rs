@@ -58,7 +58,7 @@ use crate::chainstate::burn::operations::leader_block_commit::{
 };
 use crate::chainstate::burn::operations::{
     BlockstackOperationType, DelegateStxOp, LeaderBlockCommitOp, LeaderKeyRegisterOp, PreStxOp,
-    StackStxOp, TransferStxOp, VoteForAggregateKeyOp,
+    StackAggregationCommitOp, StackStxOp, TransferStxOp, VoteForAggregateKeyOp,
 };
 use crate::chainstate::burn::{
     BlockSnapshot, ConsensusHash, ConsensusHashExtensions, Opcodes, OpsHash, SortitionHash,
@@ -447,6 +447,39 @@ impl FromRow<VoteForAggregateKeyOp> for VoteForAggregateKeyOp {
     }
 }
 
+impl FromRow<StackAggregationCommitOp> for StackAggregationCommitOp {
+    fn from_row(row: &Row) -> Result<StackAggregationCommitOp, db_error> {
+        let txid = Txid::from_column(row, "txid")?;
+        let vtxindex: u32 = row.get_unwrap("vtxindex");
+        let block_height = u64::from_column(row, "block_height")?;
+        let burn_header_hash = BurnchainHeaderHash::from_column(row, "burn_header_hash")?;
+
+        let sender = StacksAddress::from_column(row, "sender_addr")?;
+        let reward_addr = PoxAddress::from_column(row, "reward_addr")?;
+        let reward_cycle = u64::from_column(row, "reward_cycle")?;
+        let signer_key_str: String = row.get_unwrap("signer_key");
+        let signer_key: StacksPublicKeyBuffer = serde_json::from_str(&signer_key_str)
+            .expect("CORRUPTION: DB stored bad transition ops");
+        let max_amount_str: String = row.get_unwrap("max_amount");
+        let max_amount = u128::from_str_radix(&max_amount_str, 10)
+            .expect("CORRUPTION: bad u128 written to sortdb");
+        let auth_id: u32 = row.get_unwrap("auth_id");
+
+        Ok(StackAggregationCommitOp {
+            txid,
+            vtxindex,
+            block_height,
+            burn_header_hash,
+            sender,
+            reward_addr,
+            reward_cycle,
+            signer_key,
+            max_amount,
+            auth_id,
+        })
+    }
+}
+
 impl FromColumn<ASTRules> for ASTRules {
     fn from_column(row: &Row, column_name: &str) -> Result<ASTRules, db_error> {
         let x: u8 = row.get_unwrap(column_name);
@@ -513,7 +546,7 @@ impl FromRow<StacksEpoch> for StacksEpoch {
     }
 }
 
-pub const SORTITION_DB_VERSION: &str = "9";
+pub const SORTITION_DB_VERSION: &str = "10";
 
 const SORTITION_DB_INITIAL_SCHEMA: &[&str] = &[
     r#"
@@ -752,7 +785,28 @@ const SORTITION_DB_SCHEMA_8: &[&str] = &[
 static SORTITION_DB_SCHEMA_9: &[&str] =
     &[r#"ALTER TABLE block_commits ADD punished TEXT DEFAULT NULL;"#];
 
-const LAST_SORTITION_DB_INDEX: &str = "index_block_commits_by_sender";
+/// Nodes running an older schema ignored the stack-aggregation-commit opcode like any other
+/// unknown opcode, so no database migrating to this schema has processed one yet, and the new
+/// table does not need a backfill.
+const SORTITION_DB_SCHEMA_10: &[&str] = &[r#"
+    -- table definition for `stack-aggregation-commit` burn op
+    CREATE TABLE stack_aggregation_commit (
+        txid TEXT NOT NULL,
+        vtxindex INTEGER NOT NULL,
+        block_height INTEGER NOT NULL,
+        burn_header_hash TEXT NOT NULL,
+
+        sender_addr TEXT NOT NULL,
+        reward_addr TEXT NOT NULL,
+        reward_cycle INTEGER NOT NULL,
+        signer_key TEXT NOT NULL,
+        max_amount TEXT NOT NULL,
+        auth_id INTEGER NOT NULL,
+
+        PRIMARY KEY(txid,burn_header_hash)
+    );"#];
+
+const LAST_SORTITION_DB_INDEX: &str = "index_stack_aggregation_commit_burn_header_hash";
 const SORTITION_DB_INDEXES: &[&str] = &[
     "CREATE INDEX IF NOT EXISTS snapshots_block_hashes ON snapshots(block_height,index_root,winning_stacks_block_hash);",
     "CREATE INDEX IF NOT EXISTS snapshots_block_stacks_hashes ON snapshots(num_sortitions,index_root,winning_stacks_block_hash);",
@@ -775,7 +829,8 @@ const SORTITION_DB_INDEXES: &[&str] = &[
     "CREATE INDEX IF NOT EXISTS index_delegate_stx_burn_header_hash ON delegate_stx(burn_header_hash);",
     "CREATE INDEX IF NOT EXISTS index_vote_for_aggregate_key_burn_header_hash ON vote_for_aggregate_key(burn_header_hash);",
     "CREATE INDEX IF NOT EXISTS index_block_commits_by_burn_height ON block_commits(block_height);",
-    "CREATE INDEX IF NOT EXISTS index_block_commits_by_sender ON block_commits(apparent_sender);"
+    "CREATE INDEX IF NOT EXISTS index_block_commits_by_sender ON block_commits(apparent_sender);",
+    "CREATE INDEX IF NOT EXISTS index_stack_aggregation_commit_burn_header_hash ON stack_aggregation_commit(burn_header_hash);"
 ];
 
 /// Handle to the sortition database, a MARF'ed sqlite DB on disk.
@@ -2926,6 +2981,7 @@ impl SortitionDB {
 
         let db_tx = SortitionHandleTx::begin(self, &SortitionId::sentinel())?;
         SortitionDB::apply_schema_9(&db_tx, epochs_ref)?;
+        SortitionDB::apply_schema_10(&db_tx, epochs_ref)?;
 
         db_tx.commit()?;
 
@@ -3412,6 +3468,22 @@ impl SortitionDB {
         Ok(())
     }
 
+    #[cfg_attr(test, mutants::skip)]
+    fn apply_schema_10(tx: &DBTx, epochs: &[StacksEpoch]) -> Result<(), db_error> {
+        for sql_exec in SORTITION_DB_SCHEMA_10 {
+            tx.execute_batch(sql_exec)?;
+        }
+
+        SortitionDB::validate_and_replace_epochs(tx, epochs)?;
+
+        tx.execute(
+            "INSERT OR REPLACE INTO db_config (version) VALUES (?1)",
+            &["10"],
+        )?;
+
+        Ok(())
+    }
+
     fn check_schema_version_or_error(&mut self) -> Result<(), db_error> {
         match SortitionDB::get_schema_version(self.conn()) {
             Ok(Some(version)) => {
@@ -3476,6 +3548,10 @@ impl SortitionDB {
                         let tx = self.tx_begin()?;
                         SortitionDB::apply_schema_9(tx.deref(), epochs)?;
                         tx.commit()?;
+                    } else if version == "9" {
+                        let tx = self.tx_begin()?;
+                        SortitionDB::apply_schema_10(tx.deref(), epochs)?;
+                        tx.commit()?;
                     } else if version == expected_version {
                         // this transaction is almost never needed
                         let validated_epochs: &[StacksEpoch] =
@@ -4620,6 +4696,32 @@ impl SortitionDB {
         )
     }
 
+    /// Get every Stack-STX operation sent by `sender`, in any burnchain fork, in the order they
+    /// were mined.
+    pub fn get_stack_stx_ops_by_sender(
+        conn: &Connection,
+        sender: &StacksAddress,
+    ) -> Result<Vec<StackStxOp>, db_error> {
+        query_rows(
+            conn,
+            "SELECT * FROM stack_stx WHERE sender_addr = ?1 ORDER BY block_height, vtxindex",
+            &[sender.to_string()],
+        )
+    }
+
+    /// Get every Delegate-STX operation sent by or delegating to `principal`, in any burnchain
+    /// fork, in the order they were mined.
+    pub fn get_delegate_stx_ops_by_principal(
+        conn: &Connection,
+        principal: &StacksAddress,
+    ) -> Result<Vec<DelegateStxOp>, db_error> {
+        query_rows(
+            conn,
+            "SELECT * FROM delegate_stx WHERE sender_addr = ?1 OR delegate_to = ?1 ORDER BY block_height, vtxindex",
+            &[principal.to_string()],
+        )
+    }
+
     /// Get the list of `vote-for-aggregate-key` operations processed in a given burnchain block.
     /// This will be the same list in each PoX fork; it's up to the Stacks block-processing logic
     /// to reject them.
@@ -4634,6 +4736,20 @@ impl SortitionDB {
         )
     }
 
+    /// Get the list of `stack-aggregation-commit` operations processed in a given burnchain block.
+    /// This will be the same list in each PoX fork; it's up to the Stacks block-processing logic
+    /// to reject them.
+    pub fn get_stack_aggregation_commit_ops(
+        conn: &Connection,
+        burn_header_hash: &BurnchainHeaderHash,
+    ) -> Result<Vec<StackAggregationCommitOp>, db_error> {
+        query_rows(
+            conn,
+            "SELECT * FROM stack_aggregation_commit WHERE burn_header_hash = ? ORDER BY vtxindex",
+            &[burn_header_hash],
+        )
+    }
+
     /// Get the list of Transfer-STX operations processed in a given burnchain block.
     /// This will be the same list in each PoX fork; it's up to the Stacks block-processing logic
     /// to reject them.
@@ -5626,6 +5742,13 @@ impl SortitionHandleTx<'_> {
                 );
                 self.insert_vote_for_aggregate_key(op)
             }
+            BlockstackOperationType::StackAggregationCommit(ref op) => {
+                info!(
+                    "ACCEPTED({}) stack aggregation commit {} at {},{}",
+                    op.block_height, &op.txid, op.block_height, op.vtxindex
+                );
+                self.insert_stack_aggregation_commit(op)
+            }
         }
     }
 
@@ -5719,6 +5842,29 @@ impl SortitionHandleTx<'_> {
         Ok(())
     }
 
+    /// Insert a stack-aggregation-commit op
+    fn insert_stack_aggregation_commit(
+        &mut self,
+        op: &StackAggregationCommitOp,
+    ) -> Result<(), db_error> {
+        let args = params![
+            op.txid,
+            op.vtxindex,
+            u64_to_sql(op.block_height)?,
+            op.burn_header_hash,
+            op.sender.to_string(),
+            op.reward_addr.to_db_string(),
+            u64_to_sql(op.reward_cycle)?,
+            serde_json::to_string(&op.signer_key).unwrap(),
+            op.max_amount.to_string(),
+            op.auth_id,
+        ];
+
+        self.execute("REPLACE INTO stack_aggregation_commit (txid, vtxindex, block_height, burn_header_hash, sender_addr, reward_addr, reward_cycle, signer_key, max_amount, auth_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)", args)?;
+
+        Ok(())
+    }
+
     /// Insert a transfer-stx op
     fn insert_transfer_stx(&mut self, op: &TransferStxOp) -> Result<(), db_error> {
         let args = params![
@@ -10744,6 +10890,22 @@ pub mod tests {
                 block_height,
                 burn_header_hash: first_burn_hash.clone(),
             }),
+            BlockstackOperationType::StackAggregationCommit(StackAggregationCommitOp {
+                sender: StacksAddress::new(7, Hash160([7u8; 20])).unwrap(),
+                reward_addr: PoxAddress::Standard(
+                    StacksAddress::new(8, Hash160([8u8; 20])).unwrap(),
+                    Some(AddressHashMode::SerializeP2PKH),
+                ),
+                reward_cycle: 2,
+                signer_key: vote_key,
+                max_amount: u128::MAX,
+                auth_id: 3,
+
+                txid: Txid([0x06; 32]),
+                vtxindex: 5,
+                block_height,
+                burn_header_hash: first_burn_hash.clone(),
+            }),
         ];
 
         let mut tx = db.tx_begin_at_tip();
@@ -10781,6 +10943,14 @@ pub mod tests {
             good_ops[3]
         );
 
+        let ops =
+            SortitionDB::get_stack_aggregation_commit_ops(db.conn(), &first_burn_hash).unwrap();
+        assert_eq!(ops.len(), 1);
+        assert_eq!(
+            BlockstackOperationType::StackAggregationCommit(ops[0].clone()),
+            good_ops[4]
+        );
+
         // if the same ops get mined in a different burnchain block, they will still be available
         let good_ops_2 = vec![
             BlockstackOperationType::TransferStx(TransferStxOp {
@@ -69,6 +69,7 @@ pub enum Opcodes {
     TransferStx = b'$',
     DelegateStx = b'#',
     VoteForAggregateKey = b'v',
+    StackAggregationCommit = b'a',
 }
 
 // a burnchain block snapshot
@@ -194,6 +195,7 @@ impl Opcodes {
     const HTTP_PEG_OUT_REQUEST: &str = "peg_out_request";
     const HTTP_PEG_OUT_FULFILL: &str = "peg_out_fulfill";
     const HTTP_VOTE_FOR_AGGREGATE_KEY: &str = "vote_for_aggregate_key";
+    const HTTP_STACK_AGGREGATION_COMMIT: &str = "stack_aggregation_commit";
 
     pub fn to_http_str(&self) -> &'static str {
         match self {
@@ -204,6 +206,7 @@ impl Opcodes {
             Opcodes::TransferStx => Self::HTTP_TRANSFER_STX,
             Opcodes::DelegateStx => Self::HTTP_DELEGATE_STX,
             Opcodes::VoteForAggregateKey => Self::HTTP_VOTE_FOR_AGGREGATE_KEY,
+            Opcodes::StackAggregationCommit => Self::HTTP_STACK_AGGREGATION_COMMIT,
         }
     }
 
@@ -216,6 +219,7 @@ impl Opcodes {
             Self::HTTP_TRANSFER_STX => Opcodes::TransferStx,
             Self::HTTP_DELEGATE_STX => Opcodes::DelegateStx,
             Self::HTTP_VOTE_FOR_AGGREGATE_KEY => Opcodes::VoteForAggregateKey,
+            Self::HTTP_STACK_AGGREGATION_COMMIT => Opcodes::StackAggregationCommit,
             _ => return None,
         };
 
@@ -43,6 +43,7 @@ use crate::util_lib::db::{DBConn, DBTx, Error as db_error};
 pub mod delegate_stx;
 pub mod leader_block_commit;
 pub mod leader_key_register;
+pub mod stack_aggregation_commit;
 pub mod stack_stx;
 pub mod transfer_stx;
 pub mod vote_for_aggregate_key;
@@ -314,6 +315,26 @@ pub struct VoteForAggregateKeyOp {
     pub burn_header_hash: BurnchainHeaderHash, // hash of the burn chain block header
 }
 
+#[derive(Debug, PartialEq, Clone, Eq, Serialize, Deserialize)]
+pub struct StackAggregationCommitOp {
+    /// The delegate whose partially-stacked STX are being committed
+    pub sender: StacksAddress,
+    /// the PoX reward address, taken from the first output of the BTC transaction.
+    /// NOTE: the address in .pox-4 will be tagged as either p2pkh or p2sh; it's impossible to tell
+    /// if it's a segwit-p2sh since that looks identical to a p2sh address.
+    pub reward_addr: PoxAddress,
+    pub reward_cycle: u64,
+    pub signer_key: StacksPublicKeyBuffer,
+    pub max_amount: u128,
+    pub auth_id: u32,
+
+    // common to all transactions
+    pub txid: Txid,                            // transaction ID
+    pub vtxindex: u32,                         // index in the block where this tx occurs
+    pub block_height: u64,                     // block height at which this tx occurs
+    pub burn_header_hash: BurnchainHeaderHash, // hash of the burn chain block header
+}
+
 fn hex_ser_memo<S: serde::Serializer>(bytes: &[u8], s: S) -> Result<S::Ok, S::Error> {
     let inst = to_hex(bytes);
     s.serialize_str(inst.as_str())
@@ -357,6 +378,7 @@ pub enum BlockstackOperationType {
     TransferStx(TransferStxOp),
     DelegateStx(DelegateStxOp),
     VoteForAggregateKey(VoteForAggregateKeyOp),
+    StackAggregationCommit(StackAggregationCommitOp),
 }
 
 // serialization helpers for blockstack_op_to_json function
@@ -384,6 +406,7 @@ impl BlockstackOperationType {
             BlockstackOperationType::TransferStx(_) => Opcodes::TransferStx,
             BlockstackOperationType::DelegateStx(_) => Opcodes::DelegateStx,
             BlockstackOperationType::VoteForAggregateKey(_) => Opcodes::VoteForAggregateKey,
+            BlockstackOperationType::StackAggregationCommit(_) => Opcodes::StackAggregationCommit,
         }
     }
 
@@ -400,6 +423,7 @@ impl BlockstackOperationType {
             BlockstackOperationType::TransferStx(ref data) => &data.txid,
             BlockstackOperationType::DelegateStx(ref data) => &data.txid,
             BlockstackOperationType::VoteForAggregateKey(ref data) => &data.txid,
+            BlockstackOperationType::StackAggregationCommit(ref data) => &data.txid,
         }
     }
 
@@ -412,6 +436,7 @@ impl BlockstackOperationType {
             BlockstackOperationType::TransferStx(ref data) => data.vtxindex,
             BlockstackOperationType::DelegateStx(ref data) => data.vtxindex,
             BlockstackOperationType::VoteForAggregateKey(ref data) => data.vtxindex,
+            BlockstackOperationType::StackAggregationCommit(ref data) => data.vtxindex,
         }
     }
 
@@ -424,6 +449,7 @@ impl BlockstackOperationType {
             BlockstackOperationType::TransferStx(ref data) => data.block_height,
             BlockstackOperationType::DelegateStx(ref data) => data.block_height,
             BlockstackOperationType::VoteForAggregateKey(ref data) => data.block_height,
+            BlockstackOperationType::StackAggregationCommit(ref data) => data.block_height,
         }
     }
 
@@ -436,10 +462,12 @@ impl BlockstackOperationType {
             BlockstackOperationType::TransferStx(ref data) => data.burn_header_hash.clone(),
             BlockstackOperationType::DelegateStx(ref data) => data.burn_header_hash.clone(),
             BlockstackOperationType::VoteForAggregateKey(ref data) => data.burn_header_hash.clone(),
+            BlockstackOperationType::StackAggregationCommit(ref data) => {
+                data.burn_header_hash.clone()
+            }
         }
     }
 
-    #[cfg(test)]
     pub fn set_block_height(&mut self, height: u64) {
         match self {
             BlockstackOperationType::LeaderKeyRegister(ref mut data) => data.block_height = height,
@@ -453,10 +481,12 @@ impl BlockstackOperationType {
             BlockstackOperationType::VoteForAggregateKey(ref mut data) => {
                 data.block_height = height
             }
+            BlockstackOperationType::StackAggregationCommit(ref mut data) => {
+                data.block_height = height
+            }
         };
     }
 
-    #[cfg(test)]
     pub fn set_burn_header_hash(&mut self, hash: BurnchainHeaderHash) {
         match self {
             BlockstackOperationType::LeaderKeyRegister(ref mut data) => {
@@ -472,6 +502,37 @@ impl BlockstackOperationType {
             BlockstackOperationType::VoteForAggregateKey(ref mut data) => {
                 data.burn_header_hash = hash
             }
+            BlockstackOperationType::StackAggregationCommit(ref mut data) => {
+                data.burn_header_hash = hash
+            }
+        };
+    }
+
+    pub fn set_txid(&mut self, txid: Txid) {
+        match self {
+            BlockstackOperationType::LeaderKeyRegister(ref mut data) => data.txid = txid,
+            BlockstackOperationType::LeaderBlockCommit(ref mut data) => data.txid = txid,
+            BlockstackOperationType::StackStx(ref mut data) => data.txid = txid,
+            BlockstackOperationType::PreStx(ref mut data) => data.txid = txid,
+            BlockstackOperationType::TransferStx(ref mut data) => data.txid = txid,
+            BlockstackOperationType::DelegateStx(ref mut data) => data.txid = txid,
+            BlockstackOperationType::VoteForAggregateKey(ref mut data) => data.txid = txid,
+            BlockstackOperationType::StackAggregationCommit(ref mut data) => data.txid = txid,
+        };
+    }
+
+    pub fn set_vtxindex(&mut self, vtxindex: u32) {
+        match self {
+            BlockstackOperationType::LeaderKeyRegister(ref mut data) => data.vtxindex = vtxindex,
+            BlockstackOperationType::LeaderBlockCommit(ref mut data) => data.vtxindex = vtxindex,
+            BlockstackOperationType::StackStx(ref mut data) => data.vtxindex = vtxindex,
+            BlockstackOperationType::PreStx(ref mut data) => data.vtxindex = vtxindex,
+            BlockstackOperationType::TransferStx(ref mut data) => data.vtxindex = vtxindex,
+            BlockstackOperationType::DelegateStx(ref mut data) => data.vtxindex = vtxindex,
+            BlockstackOperationType::VoteForAggregateKey(ref mut data) => data.vtxindex = vtxindex,
+            BlockstackOperationType::StackAggregationCommit(ref mut data) => {
+                data.vtxindex = vtxindex
+            }
         };
     }
 
@@ -554,6 +615,23 @@ impl BlockstackOperationType {
         })
     }
 
+    pub fn stack_aggregation_commit_to_json(op: &StackAggregationCommitOp) -> serde_json::Value {
+        json!({
+            "stack_aggregation_commit": {
+                "burn_block_height": op.block_height,
+                "burn_header_hash": &op.burn_header_hash.to_hex(),
+                "reward_addr": op.reward_addr.clone().to_b58(),
+                "reward_cycle": op.reward_cycle,
+                "sender": stacks_addr_serialize(&op.sender),
+                "signer_key": op.signer_key.to_hex(),
+                "max_amount": op.max_amount,
+                "auth_id": op.auth_id,
+                "burn_txid": op.txid,
+                "vtxindex": op.vtxindex,
+            }
+        })
+    }
+
     // An explicit JSON serialization function is used (instead of using the default serialization
     // function) for the Blockstack ops. This is because (a) we wanted the serialization to be
     // more readable, and (b) the serialization used to display PoxAddress as a string is lossy,
@@ -568,9 +646,13 @@ impl BlockstackOperationType {
             BlockstackOperationType::VoteForAggregateKey(op) => {
                 Self::vote_for_aggregate_key_to_json(op)
             }
+            BlockstackOperationType::StackAggregationCommit(op) => {
+                Self::stack_aggregation_commit_to_json(op)
+            }
             // json serialization for the remaining op types is not implemented for now. This function
             // is currently only used to json-ify burnchain ops executed as Stacks transactions (so,
-            // stack_stx, transfer_stx, delegate_stx, and vote_for_aggregate_key).
+            // stack_stx, transfer_stx, delegate_stx, vote_for_aggregate_key, and
+            // stack_aggregation_commit).
             _ => json!(null),
         }
     }
@@ -586,6 +668,7 @@ impl fmt::Display for BlockstackOperationType {
             BlockstackOperationType::TransferStx(ref op) => write!(f, "{:?}", op),
             BlockstackOperationType::DelegateStx(ref op) => write!(f, "{:?}", op),
             BlockstackOperationType::VoteForAggregateKey(ref op) => write!(f, "{:?}", op),
+            BlockstackOperationType::StackAggregationCommit(ref op) => write!(f, "{:?}", op),
         }
     }
 }
@@ -0,0 +1,332 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::io::{Read, Write};
+
+use stacks_common::codec::{write_next, Error as codec_error, StacksMessageCodec};
+use stacks_common::types::chainstate::{BurnchainHeaderHash, StacksAddress};
+use stacks_common::types::StacksPublicKeyBuffer;
+use stacks_common::util::secp256k1::Secp256k1PublicKey;
+
+use crate::burnchains::{BurnchainBlockHeader, BurnchainTransaction, Txid};
+use crate::chainstate::burn::operations::{
+    parse_u128_from_be, parse_u32_from_be, parse_u64_from_be, Error as op_error,
+    StackAggregationCommitOp,
+};
+use crate::chainstate::burn::Opcodes;
+
+struct ParsedData {
+    reward_cycle: u64,
+    signer_key: StacksPublicKeyBuffer,
+    max_amount: u128,
+    auth_id: u32,
+}
+
+impl StackAggregationCommitOp {
+    pub fn from_tx(
+        block_header: &BurnchainBlockHeader,
+        tx: &BurnchainTransaction,
+        sender: &StacksAddress,
+    ) -> Result<StackAggregationCommitOp, op_error> {
+        StackAggregationCommitOp::parse_from_tx(
+            block_header.block_height,
+            &block_header.block_hash,
+            tx,
+            sender,
+        )
+    }
+
+    fn parse_data(data: &[u8]) -> Option<ParsedData> {
+        /*
+           Wire format:
+
+           0     2  3              11           44                 60          64
+           |-----|--|--------------|------------|------------------|-----------|
+           magic  op  reward_cycle   signer_key   max_amount (u128)  auth_id (u32)
+
+           Note that `data` is missing the first 3 bytes -- the magic and op have been stripped
+
+           All integers are in big-endian order.  The PoX address to commit to is the first
+           output of the transaction.
+        */
+
+        if data.len() != 61 {
+            warn!(
+                "StackAggregationCommitOp payload is malformed ({} bytes, expected 61)",
+                data.len()
+            );
+            return None;
+        }
+
+        let reward_cycle = parse_u64_from_be(&data[0..8])?;
+        let signer_key = StacksPublicKeyBuffer::from(&data[8..41]);
+        let max_amount = parse_u128_from_be(&data[41..57])?;
+        let auth_id = parse_u32_from_be(&data[57..61])?;
+
+        Some(ParsedData {
+            reward_cycle,
+            signer_key,
+            max_amount,
+            auth_id,
+        })
+    }
+
+    pub fn get_sender_txid(tx: &BurnchainTransaction) -> Result<&Txid, op_error> {
+        match tx.get_input_tx_ref(0) {
+            Some((ref txid, vout)) => {
+                if *vout != 1 {
+                    warn!("Invalid tx: StackAggregationCommitOp must spend the second output of the PreStxOp");
+                    Err(op_error::InvalidInput)
+                } else {
+                    Ok(txid)
+                }
+            }
+            None => {
+                warn!("Invalid tx: StackAggregationCommitOp must have at least one input");
+                Err(op_error::InvalidInput)
+            }
+        }
+    }
+
+    pub fn parse_from_tx(
+        block_height: u64,
+        block_hash: &BurnchainHeaderHash,
+        tx: &BurnchainTransaction,
+        sender: &StacksAddress,
+    ) -> Result<StackAggregationCommitOp, op_error> {
+        let outputs = tx.get_recipients();
+
+        if tx.num_signers() == 0 || outputs.is_empty() {
+            warn!(
+                "Invalid tx: inputs: {}, outputs: {}",
+                tx.num_signers(),
+                outputs.len()
+            );
+            return Err(op_error::InvalidInput);
+        }
+
+        if tx.opcode() != Opcodes::StackAggregationCommit as u8 {
+            warn!("Invalid tx: invalid opcode {}", tx.opcode());
+            return Err(op_error::InvalidInput);
+        };
+
+        let data = StackAggregationCommitOp::parse_data(&tx.data()).ok_or_else(|| {
+            warn!("Invalid tx data");
+            op_error::ParseError
+        })?;
+
+        let first_output = outputs[0].as_ref().ok_or_else(|| {
+            warn!("Invalid tx: failed to decode first output");
+            op_error::InvalidInput
+        })?;
+
+        // coerce a hash mode for this address if need be, since we'll need it when we feed this
+        // address into the .pox-4 contract
+        let reward_addr = first_output.address.clone().coerce_hash_mode();
+
+        Ok(StackAggregationCommitOp {
+            sender: sender.clone(),
+            reward_addr,
+            reward_cycle: data.reward_cycle,
+            signer_key: data.signer_key,
+            max_amount: data.max_amount,
+            auth_id: data.auth_id,
+            txid: tx.txid(),
+            vtxindex: tx.vtxindex(),
+            block_height,
+            burn_header_hash: block_hash.clone(),
+        })
+    }
+
+    /// Check the payload of a stack-aggregation-commit burn op.
+    /// The signer key must be a valid `Secp256k1PublicKey`.  Everything else is checked by
+    /// .pox-4 when the op is applied.
+    pub fn check(&self) -> Result<(), op_error> {
+        Secp256k1PublicKey::from_slice(self.signer_key.as_bytes())
+            .map_err(|_| op_error::StackStxInvalidKey)?;
+
+        Ok(())
+    }
+}
+
+impl StacksMessageCodec for StackAggregationCommitOp {
+    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), codec_error> {
+        /*
+           Wire format:
+
+           0     2  3              11           44                 60          64
+           |-----|--|--------------|------------|------------------|-----------|
+           magic  op  reward_cycle   signer_key   max_amount (u128)  auth_id (u32)
+        */
+
+        write_next(fd, &(Opcodes::StackAggregationCommit as u8))?;
+        fd.write_all(&self.reward_cycle.to_be_bytes())
+            .map_err(codec_error::WriteError)?;
+        fd.write_all(self.signer_key.as_bytes())
+            .map_err(codec_error::WriteError)?;
+        fd.write_all(&self.max_amount.to_be_bytes())
+            .map_err(codec_error::WriteError)?;
+        fd.write_all(&self.auth_id.to_be_bytes())
+            .map_err(codec_error::WriteError)?;
+
+        Ok(())
+    }
+
+    fn consensus_deserialize<R: Read>(_fd: &mut R) -> Result<Self, codec_error> {
+        // Op deserialized through burchain indexer
+        unimplemented!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stacks_common::address::{AddressHashMode, C32_ADDRESS_VERSION_MAINNET_SINGLESIG};
+    use stacks_common::codec::StacksMessageCodec;
+    use stacks_common::types::chainstate::{BurnchainHeaderHash, StacksAddress};
+    use stacks_common::types::StacksPublicKeyBuffer;
+    use stacks_common::util::hash::*;
+    use stacks_common::util::secp256k1::{Secp256k1PrivateKey, Secp256k1PublicKey};
+
+    use crate::burnchains::bitcoin::address::{
+        BitcoinAddress, LegacyBitcoinAddress, LegacyBitcoinAddressType,
+    };
+    use crate::burnchains::bitcoin::{
+        BitcoinInputType, BitcoinNetworkType, BitcoinTransaction, BitcoinTxInputStructured,
+        BitcoinTxOutput,
+    };
+    use crate::burnchains::{BurnchainTransaction, Txid};
+    use crate::chainstate::burn::operations::{Error as op_error, StackAggregationCommitOp};
+    use crate::chainstate::burn::Opcodes;
+    use crate::chainstate::stacks::address::PoxAddress;
+
+    fn make_tx(data: Vec<u8>, vout: u32) -> BitcoinTransaction {
+        BitcoinTransaction {
+            txid: Txid([0; 32]),
+            vtxindex: 0,
+            opcode: Opcodes::StackAggregationCommit as u8,
+            data,
+            data_amt: 0,
+            inputs: vec![BitcoinTxInputStructured {
+                keys: vec![],
+                num_required: 0,
+                in_type: BitcoinInputType::Standard,
+                tx_ref: (Txid([0; 32]), vout),
+            }
+            .into()],
+            outputs: vec![BitcoinTxOutput {
+                units: 10,
+                address: BitcoinAddress::Legacy(LegacyBitcoinAddress {
+                    addrtype: LegacyBitcoinAddressType::PublicKeyHash,
+                    network_id: BitcoinNetworkType::Mainnet,
+                    bytes: Hash160([1; 20]),
+                }),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_parse_stack_aggregation_commit() {
+        let signer_key = StacksPublicKeyBuffer([0x02; 33]);
+        let reward_cycle: u64 = 12;
+        let max_amount: u128 = u128::MAX;
+        let auth_id: u32 = 7;
+
+        let mut data: Vec<u8> = vec![];
+        data.extend_from_slice(&reward_cycle.to_be_bytes());
+        data.extend_from_slice(signer_key.as_bytes());
+        data.extend_from_slice(&max_amount.to_be_bytes());
+        data.extend_from_slice(&auth_id.to_be_bytes());
+
+        let sender = StacksAddress::new(0, Hash160([0; 20])).unwrap();
+        let tx = BurnchainTransaction::Bitcoin(make_tx(data.clone(), 1));
+        let op = StackAggregationCommitOp::parse_from_tx(
+            1000,
+            &BurnchainHeaderHash([0; 32]),
+            &tx,
+            &sender,
+        )
+        .expect("Failed to parse stack-aggregation-commit tx");
+
+        assert_eq!(&op.sender, &sender);
+        assert_eq!(
+            op.reward_addr,
+            PoxAddress::Standard(
+                StacksAddress::new(C32_ADDRESS_VERSION_MAINNET_SINGLESIG, Hash160([1; 20]))
+                    .unwrap(),
+                Some(AddressHashMode::SerializeP2PKH)
+            )
+        );
+        assert_eq!(op.reward_cycle, reward_cycle);
+        assert_eq!(op.signer_key, signer_key);
+        assert_eq!(op.max_amount, max_amount);
+        assert_eq!(op.auth_id, auth_id);
+        assert_eq!(op.block_height, 1000);
+
+        // the op serializes to its opcode followed by the payload
+        let mut bytes = vec![];
+        op.consensus_serialize(&mut bytes).unwrap();
+        assert_eq!(bytes[0], Opcodes::StackAggregationCommit as u8);
+        assert_eq!(&bytes[1..], data.as_slice());
+
+        // must spend the PreStxOp's second output
+        assert!(StackAggregationCommitOp::get_sender_txid(&tx).is_ok());
+        let bad_input_tx = BurnchainTransaction::Bitcoin(make_tx(data.clone(), 0));
+        assert!(StackAggregationCommitOp::get_sender_txid(&bad_input_tx).is_err());
+
+        // payload must be exactly 61 bytes
+        let mut long_data = data.clone();
+        long_data.push(0);
+        for bad_data in [data[..60].to_vec(), long_data] {
+            let bad_tx = BurnchainTransaction::Bitcoin(make_tx(bad_data, 1));
+            match StackAggregationCommitOp::parse_from_tx(
+                1000,
+                &BurnchainHeaderHash([0; 32]),
+                &bad_tx,
+                &sender,
+            ) {
+                Err(op_error::ParseError) => {}
+                res => panic!("Unexpected parse result: {:?}", res),
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_validation() {
+        let sender =
+            StacksAddress::from_string("ST2QKZ4FKHAH1NQKYKYAYZPY440FEPK7GZ1R5HBP2").unwrap();
+        let mut op = StackAggregationCommitOp {
+            sender: sender.clone(),
+            reward_addr: PoxAddress::Standard(sender, None),
+            reward_cycle: 10,
+            signer_key: StacksPublicKeyBuffer([0x00; 33]),
+            max_amount: 1,
+            auth_id: 0,
+            txid: Txid([10u8; 32]),
+            vtxindex: 10,
+            block_height: 10,
+            burn_header_hash: BurnchainHeaderHash([0x10; 32]),
+        };
+
+        match op.check() {
+            Ok(_) => panic!("Invalid key should not pass validation"),
+            Err(op_error::StackStxInvalidKey) => (),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let signer_key = Secp256k1PublicKey::from_private(&Secp256k1PrivateKey::random());
+        op.signer_key = StacksPublicKeyBuffer::from_public_key(&signer_key);
+        op.check().unwrap();
+    }
+}
@@ -10,8 +10,8 @@ use stacks_common::util::secp256k1::MessageSignature;
 
 use crate::burnchains::Txid;
 use crate::chainstate::burn::operations::{
-    BlockstackOperationType, DelegateStxOp, PreStxOp, StackStxOp, TransferStxOp,
-    VoteForAggregateKeyOp,
+    BlockstackOperationType, DelegateStxOp, PreStxOp, StackAggregationCommitOp, StackStxOp,
+    TransferStxOp, VoteForAggregateKeyOp,
 };
 use crate::chainstate::stacks::address::{PoxAddress, PoxAddressType32};
 
@@ -225,6 +225,51 @@ fn test_serialization_delegate_stx_op() {
     assert_json_diff::assert_json_eq!(serialized_json, constructed_json);
 }
 
+#[test]
+fn test_serialization_stack_aggregation_commit_op() {
+    let sender_addr = "ST2QKZ4FKHAH1NQKYKYAYZPY440FEPK7GZ1R5HBP2";
+    let sender = StacksAddress::from_string(sender_addr).unwrap();
+    let reward_addr = PoxAddress::Standard(
+        StacksAddress::new(C32_ADDRESS_VERSION_MAINNET_SINGLESIG, Hash160([0x01; 20])).unwrap(),
+        None,
+    );
+    let op = StackAggregationCommitOp {
+        sender,
+        reward_addr,
+        reward_cycle: 10,
+        signer_key: StacksPublicKeyBuffer([0x01; 33]),
+        max_amount: 100,
+        auth_id: 3,
+        txid: Txid([10u8; 32]),
+        vtxindex: 10,
+        block_height: 10,
+        burn_header_hash: BurnchainHeaderHash([0x10; 32]),
+    };
+    let serialized_json = BlockstackOperationType::blockstack_op_to_json(
+        &BlockstackOperationType::StackAggregationCommit(op.clone()),
+    );
+    let constructed_json = serde_json::json!({
+        "stack_aggregation_commit": {
+            "burn_block_height": 10,
+            "burn_header_hash": "1010101010101010101010101010101010101010101010101010101010101010",
+            "reward_addr": "16Jswqk47s9PUcyCc88MMVwzgvHPvtEpf",
+            "reward_cycle": 10,
+            "sender": {
+                "address": "ST2QKZ4FKHAH1NQKYKYAYZPY440FEPK7GZ1R5HBP2",
+                "address_hash_bytes": "0xaf3f91f38aa21ade7e9f95efdbc4201eeb4cf0f8",
+                "address_version": 26,
+            },
+            "signer_key": "01".repeat(33),
+            "max_amount": 100,
+            "auth_id": 3,
+            "burn_txid": "0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a",
+            "vtxindex": 10,
+        }
+    });
+
+    assert_json_diff::assert_json_eq!(serialized_json, constructed_json);
+}
+
 #[test]
 fn test_serialization_vote_for_aggregate_key_op() {
     let sender_addr = "ST2QKZ4FKHAH1NQKYKYAYZPY440FEPK7GZ1R5HBP2";
@@ -19,6 +19,34 @@ use std::sync::{Arc, Condvar, LockResult, Mutex, MutexGuard, RwLock, TryLockResu
 use std::time::{Duration, Instant};
 use std::{process, thread};
 
+use stacks_common::types::chainstate::StacksBlockId;
+
+/// A switch of the canonical Stacks tip to a fork deeper than `node.max_reorg_depth` allows.
+/// The chains coordinator stops processing Nakamoto blocks until an operator confirms it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingStacksReorg {
+    /// The canonical Stacks tip when the fork was found
+    pub old_tip: StacksBlockId,
+    pub old_tip_height: u64,
+    /// The block that would become the new canonical Stacks tip
+    pub new_tip: StacksBlockId,
+    pub new_tip_height: u64,
+    /// Height of the last block that both forks have in common
+    pub fork_height: u64,
+    /// Number of blocks on the old tip's fork that would be abandoned
+    pub depth: u64,
+    pub max_depth: u64,
+}
+
+/// Deep Stacks reorgs awaiting, or granted, operator confirmation
+#[derive(Debug, Default)]
+pub struct ReorgGuard {
+    /// The reorg that is holding up block processing, if any
+    pub pending: Option<PendingStacksReorg>,
+    /// The new tip of the last reorg an operator confirmed, which the coordinator may switch to
+    pub confirmed: Option<StacksBlockId>,
+}
+
 /// Trait for use by the ChainsCoordinator
 ///
 pub trait CoordinatorNotices {
@@ -60,6 +88,8 @@ pub struct CoordinatorChannels {
     sortitions_processed: Arc<AtomicU64>,
     /// Does the StackerDB need to be refreshed?
     refresh_stacker_db: Arc<AtomicBool>,
+    /// Deep reorgs awaiting operator confirmation
+    reorg_guard: Arc<Mutex<ReorgGuard>>,
 }
 
 /// Notification struct for communicating to
@@ -85,6 +115,8 @@ pub struct CoordinatorReceivers {
     pub sortitions_processed: Arc<AtomicU64>,
     /// Does the StackerDB need to be refreshed?
     pub refresh_stacker_db: Arc<AtomicBool>,
+    /// Deep reorgs awaiting operator confirmation
+    pub reorg_guard: Arc<Mutex<ReorgGuard>>,
 }
 
 /// Static struct used to hold all the static methods
@@ -172,6 +204,27 @@ impl CoordinatorChannels {
         bools.stop.clone()
     }
 
+    /// Get the deep reorg that the coordinator is waiting on an operator to confirm, if any
+    pub fn get_pending_reorg(&self) -> Option<PendingStacksReorg> {
+        self.reorg_guard.lock().unwrap().pending.clone()
+    }
+
+    /// Let the coordinator switch the canonical Stacks tip to `new_tip`, if that is the pending
+    /// deep reorg's new tip, and wake it up to do so.  Returns the confirmed reorg, or None if
+    /// there is no such pending reorg.
+    pub fn confirm_reorg(&self, new_tip: &StacksBlockId) -> Option<PendingStacksReorg> {
+        let confirmed = {
+            let mut guard = self.reorg_guard.lock().unwrap();
+            if guard.pending.as_ref().map(|reorg| &reorg.new_tip) != Some(new_tip) {
+                return None;
+            }
+            guard.confirmed = Some(new_tip.clone());
+            guard.pending.take()
+        };
+        self.announce_new_stacks_block();
+        confirmed
+    }
+
     pub fn get_stacks_blocks_processed(&self) -> u64 {
         self.stacks_blocks_processed.load(Ordering::SeqCst)
     }
@@ -236,6 +289,7 @@ impl CoordinatorCommunication {
         let stacks_blocks_processed = Arc::new(AtomicU64::new(0));
         let sortitions_processed = Arc::new(AtomicU64::new(0));
         let refresh_stacker_db = Arc::new(AtomicBool::new(false));
+        let reorg_guard = Arc::new(Mutex::new(ReorgGuard::default()));
 
         let senders = CoordinatorChannels {
             signal_bools: signal_bools.clone(),
@@ -244,6 +298,7 @@ impl CoordinatorCommunication {
 
             sortitions_processed: sortitions_processed.clone(),
             refresh_stacker_db: refresh_stacker_db.clone(),
+            reorg_guard: reorg_guard.clone(),
         };
 
         let rcvrs = CoordinatorReceivers {
@@ -252,6 +307,7 @@ impl CoordinatorCommunication {
             stacks_blocks_processed,
             sortitions_processed,
             refresh_stacker_db,
+            reorg_guard,
         };
 
         (rcvrs, senders)
@@ -33,6 +33,9 @@ use stacks_common::types::chainstate::{
 use stacks_common::util::get_epoch_time_secs;
 
 pub use self::comm::CoordinatorCommunication;
+pub use self::reward_set_source::{
+    ConfiguredRewardSetProvider, InjectedRewardSets, RewardSetSource,
+};
 use super::stacks::boot::{RewardSet, RewardSetData};
 use super::stacks::db::blocks::DummyEventDispatcher;
 use crate::burnchains::affirmation::{AffirmationMap, AffirmationMapEntry};
@@ -54,10 +57,15 @@ use crate::chainstate::burn::operations::{BlockstackOperationType, LeaderBlockCo
 use crate::chainstate::burn::{BlockSnapshot, ConsensusHash};
 use crate::chainstate::coordinator::comm::{
     ArcCounterCoordinatorNotices, CoordinatorEvents, CoordinatorNotices, CoordinatorReceivers,
+    PendingStacksReorg, ReorgGuard,
 };
+use crate::chainstate::nakamoto::{header_cache, NakamotoBlock};
 use crate::chainstate::stacks::address::PoxAddress;
 use crate::chainstate::stacks::boot::{POX_3_NAME, POX_4_NAME};
 use crate::chainstate::stacks::db::accounts::MinerReward;
+use crate::chainstate::stacks::db::prune::ClarityPruner;
+use crate::chainstate::stacks::db::receipts::TransactionReceiptDB;
+use crate::chainstate::stacks::db::state_diffs::StateDiffDB;
 use crate::chainstate::stacks::db::{
     ChainStateBootData, ClarityTx, MinerRewardInfo, StacksChainState, StacksEpochReceipt,
     StacksHeaderInfo,
@@ -74,7 +82,11 @@ use crate::chainstate::stacks::{
 use crate::core::{
     StacksEpoch, StacksEpochId, FIRST_BURNCHAIN_CONSENSUS_HASH, FIRST_STACKS_BLOCK_HASH,
 };
+use crate::cost_estimates::contract_costs::ContractCostDB;
 use crate::cost_estimates::{CostEstimator, FeeEstimator, PessimisticEstimator};
+use crate::monitoring::chain_quality::{
+    ChainQualityAlert, ChainQualityMonitor, ChainQualityThresholds,
+};
 use crate::monitoring::{
     increment_contract_calls_processed, increment_stx_blocks_processed_counter,
 };
@@ -82,6 +94,7 @@ use crate::net::atlas::{AtlasConfig, AtlasDB, AttachmentInstance};
 use crate::util_lib::db::{DBConn, DBTx, Error as DBError};
 
 pub mod comm;
+pub mod reward_set_source;
 #[cfg(test)]
 pub mod tests;
 
@@ -183,6 +196,11 @@ pub trait BlockEventDispatcher {
         coinbase_height: u64,
     );
 
+    /// called whenever a Nakamoto block is appended to the chain state, right before
+    ///  `announce_block()`.  Observers that archive blocks receive its consensus-serialized
+    ///  bytes this way, instead of re-assembling them from the `announce_block()` payload.
+    fn announce_raw_nakamoto_block(&self, block: &NakamotoBlock);
+
     /// called whenever a burn block is about to be
     ///  processed for sortition. note, in the event
     ///  of PoX forks, this will be called _multiple_
@@ -196,6 +214,29 @@ pub trait BlockEventDispatcher {
         reward_recipients: Vec<PoxAddress>,
         consensus_hash: &ConsensusHash,
     );
+
+    /// called whenever the first burn block of a reward cycle has been
+    ///  processed for sortition, with the reward cycle's PoX anchor block
+    ///  status (and its reward set, if known).  Like `announce_burn_block()`,
+    ///  this may be called more than once per reward cycle in the event of
+    ///  PoX forks.
+    fn announce_reward_cycle(
+        &self,
+        reward_cycle_info: &RewardCycleInfo,
+        burn_block: &BurnchainHeaderHash,
+        burn_block_height: u64,
+        consensus_hash: &ConsensusHash,
+        pox_constants: &PoxConstants,
+    );
+
+    /// called when the chains coordinator stops processing Nakamoto blocks because the next one
+    ///  would switch the canonical Stacks tip to a fork deeper than `node.max_reorg_depth`.
+    ///  Processing resumes once an operator confirms the reorg.
+    fn announce_deep_reorg(&self, reorg: &PendingStacksReorg);
+
+    /// called when the chain-quality monitor raises an alert about missed sortitions, empty
+    ///  tenures, or low signer participation.
+    fn announce_chain_quality_alert(&self, alert: &ChainQualityAlert);
 }
 
 pub struct ChainsCoordinatorConfig {
@@ -208,6 +249,28 @@ pub struct ChainsCoordinatorConfig {
     /// true: always wait for canonical anchor blocks, even if it stalls the chain
     /// false: proceed to process new chain history even if we're missing an anchor block.
     pub require_affirmed_anchor_blocks: bool,
+    /// the reward sets injected by the node's configured reward set source, shared with the
+    /// rest of the node
+    pub reward_sets: Arc<InjectedRewardSets>,
+    /// if set, record per-contract execution costs in this DB
+    pub contract_cost_db_path: Option<PathBuf>,
+    /// how many Stacks block heights of per-contract costs to keep
+    pub contract_cost_retention: u64,
+    /// if set, record every processed transaction's receipt in this DB
+    pub receipt_db_path: Option<PathBuf>,
+    /// how many Stacks block heights of transaction receipts to keep
+    pub receipt_retention: u64,
+    /// if set, record every processed block's state diff in this DB
+    pub state_diff_db_path: Option<PathBuf>,
+    /// how many Stacks block heights of block state diffs to keep
+    pub state_diff_retention: u64,
+    /// if set, prune Clarity state older than this many Stacks blocks
+    pub prune_horizon: Option<u64>,
+    /// if set, wait for operator confirmation before switching to a Stacks fork that abandons
+    /// more than this many blocks of the canonical fork
+    pub max_reorg_depth: Option<u64>,
+    /// when the chain-quality monitor raises alerts
+    pub chain_quality: ChainQualityThresholds,
 }
 
 impl ChainsCoordinatorConfig {
@@ -216,6 +279,16 @@ impl ChainsCoordinatorConfig {
             always_use_affirmation_maps: true,
             require_affirmed_anchor_blocks: true,
             assume_present_anchor_blocks: true,
+            reward_sets: Arc::new(InjectedRewardSets::default()),
+            contract_cost_db_path: None,
+            contract_cost_retention: 0,
+            receipt_db_path: None,
+            receipt_retention: 0,
+            state_diff_db_path: None,
+            state_diff_retention: 0,
+            prune_horizon: None,
+            max_reorg_depth: None,
+            chain_quality: ChainQualityThresholds::default(),
         }
     }
 
@@ -224,6 +297,16 @@ impl ChainsCoordinatorConfig {
             always_use_affirmation_maps: false,
             require_affirmed_anchor_blocks: false,
             assume_present_anchor_blocks: false,
+            reward_sets: Arc::new(InjectedRewardSets::default()),
+            contract_cost_db_path: None,
+            contract_cost_retention: 0,
+            receipt_db_path: None,
+            receipt_retention: 0,
+            state_diff_db_path: None,
+            state_diff_retention: 0,
+            prune_horizon: None,
+            max_reorg_depth: None,
+            chain_quality: ChainQualityThresholds::default(),
         }
     }
 }
@@ -246,6 +329,15 @@ pub struct ChainsCoordinator<
     pub dispatcher: Option<&'a T>,
     pub cost_estimator: Option<&'a mut CE>,
     pub fee_estimator: Option<&'a mut FE>,
+    pub contract_cost_db: Option<ContractCostDB>,
+    pub receipt_db: Option<TransactionReceiptDB>,
+    pub state_diff_db: Option<StateDiffDB>,
+    /// if set, prunes Clarity state older than its horizon in the background
+    pub clarity_pruner: Option<ClarityPruner>,
+    /// if set, the deepest Stacks reorg to make without operator confirmation
+    pub max_reorg_depth: Option<u64>,
+    /// tracks missed sortitions, empty tenures, and signer participation
+    pub chain_quality: ChainQualityMonitor,
     pub reward_set_provider: R,
     pub notifier: N,
     pub atlas_config: AtlasConfig,
@@ -254,6 +346,8 @@ pub struct ChainsCoordinator<
     /// Used to tell the P2P thread that the stackerdb
     ///  needs to be refreshed.
     pub refresh_stacker_db: Arc<AtomicBool>,
+    /// Deep Stacks reorgs awaiting operator confirmation
+    pub reorg_guard: Arc<Mutex<ReorgGuard>>,
     /// whether or not the canonical tip is now a Nakamoto header
     pub in_nakamoto_epoch: bool,
 }
@@ -274,6 +368,7 @@ pub enum Error {
     RewardSetAlreadyProcessed,
     PoXAnchorBlockRequired,
     PoXNotProcessedYet,
+    RewardSetSourceError(String),
 }
 
 impl From<BurnchainError> for Error {
@@ -391,7 +486,7 @@ impl<T: BlockEventDispatcher> RewardSetProvider for OnChainRewardSetProvider<'_,
         sortdb: &SortitionDB,
         block_id: &StacksBlockId,
     ) -> Result<RewardSet, Error> {
-        self.read_reward_set_nakamoto(chainstate, reward_cycle, sortdb, block_id, false)
+        self.read_cached_reward_set_nakamoto(reward_cycle, chainstate, sortdb, block_id)
     }
 }
 
@@ -498,7 +593,7 @@ impl<
         'a,
         T,
         ArcCounterCoordinatorNotices,
-        OnChainRewardSetProvider<'a, T>,
+        ConfiguredRewardSetProvider<'a, T>,
         CE,
         FE,
         B,
@@ -506,7 +601,7 @@ impl<
 {
     pub fn run(
         config: ChainsCoordinatorConfig,
-        chain_state_db: StacksChainState,
+        mut chain_state_db: StacksChainState,
         burnchain: Burnchain,
         dispatcher: &'a T,
         comms: CoordinatorReceivers,
@@ -539,6 +634,44 @@ impl<
             sortitions_processed,
         };
 
+        let reward_set_provider = ConfiguredRewardSetProvider::new(
+            config.reward_sets.clone(),
+            OnChainRewardSetProvider(Some(dispatcher)),
+        );
+        // everything that loads reward sets through the chainstate sees the same injected sets
+        chain_state_db.injected_reward_sets = config.reward_sets.clone();
+
+        let contract_cost_db = config.contract_cost_db_path.as_ref().and_then(|path| {
+            ContractCostDB::open(path, config.contract_cost_retention)
+                .inspect_err(|e| {
+                    error!("Failed to open contract cost DB, so contract costs will not be recorded";
+                           "path" => %path.display(), "error" => %e);
+                })
+                .ok()
+        });
+
+        let receipt_db = config.receipt_db_path.as_ref().and_then(|path| {
+            TransactionReceiptDB::open(path, config.receipt_retention)
+                .inspect_err(|e| {
+                    error!("Failed to open transaction receipts DB, so receipts will not be recorded";
+                           "path" => %path.display(), "error" => %e);
+                })
+                .ok()
+        });
+
+        let state_diff_db = config.state_diff_db_path.as_ref().and_then(|path| {
+            StateDiffDB::open(path, config.state_diff_retention)
+                .inspect_err(|e| {
+                    error!("Failed to open block state diff DB, so state diffs will not be recorded";
+                           "path" => %path.display(), "error" => %e);
+                })
+                .ok()
+        });
+        // state diffs are computed from the Clarity writes each block makes
+        chain_state_db
+            .clarity_state
+            .set_record_writes(state_diff_db.is_some());
+
         let mut inst = ChainsCoordinator {
             canonical_sortition_tip: Some(canonical_sortition_tip),
             burnchain_blocks_db,
@@ -547,14 +680,21 @@ impl<
             burnchain,
             dispatcher: Some(dispatcher),
             notifier: arc_notices,
-            reward_set_provider: OnChainRewardSetProvider(Some(dispatcher)),
+            reward_set_provider,
             cost_estimator,
             fee_estimator,
+            contract_cost_db,
+            receipt_db,
+            state_diff_db,
+            clarity_pruner: config.prune_horizon.map(ClarityPruner::new),
+            max_reorg_depth: config.max_reorg_depth,
+            chain_quality: ChainQualityMonitor::new(config.chain_quality.clone()),
             atlas_config,
             atlas_db: Some(atlas_db),
             config,
             burnchain_indexer,
             refresh_stacker_db: comms.refresh_stacker_db.clone(),
+            reorg_guard: comms.reorg_guard.clone(),
             in_nakamoto_epoch: false,
         };
 
@@ -709,6 +849,12 @@ impl<T: BlockEventDispatcher, U: RewardSetProvider, B: BurnchainHeaderReader>
             dispatcher,
             cost_estimator: None,
             fee_estimator: None,
+            contract_cost_db: None,
+            receipt_db: None,
+            state_diff_db: None,
+            clarity_pruner: None,
+            max_reorg_depth: None,
+            chain_quality: ChainQualityMonitor::new(ChainQualityThresholds::default()),
             reward_set_provider,
             notifier: (),
             atlas_config,
@@ -716,6 +862,7 @@ impl<T: BlockEventDispatcher, U: RewardSetProvider, B: BurnchainHeaderReader>
             config: ChainsCoordinatorConfig::test_new(),
             burnchain_indexer,
             refresh_stacker_db: Arc::new(AtomicBool::new(false)),
+            reorg_guard: Arc::new(Mutex::new(ReorgGuard::default())),
             in_nakamoto_epoch: false,
         }
     }
@@ -2681,6 +2828,9 @@ impl<
                     }
                     sortition
                 } else {
+                    // only keep a copy of the reward cycle info if there's someone to tell
+                    let announced_reward_cycle_info =
+                        self.dispatcher.and_then(|_| reward_cycle_info.clone());
                     // bind a reference here to avoid tripping up the borrow-checker
                     let dispatcher_ref = &self.dispatcher;
                     let (next_snapshot, _) = self
@@ -2709,6 +2859,19 @@ impl<
                             Error::FailedToProcessSortition(e)
                         })?;
 
+                    if let (Some(dispatcher), Some(rc_info)) =
+                        (self.dispatcher, announced_reward_cycle_info.as_ref())
+                    {
+                        dispatcher.announce_reward_cycle(
+                            rc_info,
+                            &header.block_hash,
+                            header.block_height,
+                            &next_snapshot.consensus_hash,
+                            &self.burnchain.pox_constants,
+                        );
+                    }
+                    self.observe_sortition_quality(&next_snapshot);
+
                     next_snapshot
                 }
             };
@@ -2779,6 +2942,7 @@ impl<
 
             let sortition_id = next_snapshot.sortition_id;
 
+            header_cache::invalidate(&self.chain_state_db.root_path);
             self.notifier.notify_sortition_processed();
             if revalidated_stacks_block {
                 debug!("Bump Stacks block(s) reprocessed");
@@ -2949,6 +3113,7 @@ impl<
                                     &event_data.value,
                                     &contract_id,
                                     block_receipt.header.index_block_hash(),
+                                    block_receipt.header.consensus_hash,
                                     block_receipt.header.stacks_block_height,
                                     receipt.transaction.txid(),
                                     Some(canonical_stacks_tip_height),
@@ -2990,6 +3155,26 @@ impl<
         }
     }
 
+    /// Feed a newly-evaluated sortition to the chain-quality monitor
+    pub fn observe_sortition_quality(&mut self, snapshot: &BlockSnapshot) {
+        let alert_opt = self
+            .chain_quality
+            .observe_sortition(snapshot.block_height, snapshot.sortition);
+        if let Some(alert) = alert_opt {
+            self.announce_chain_quality_alerts(&[alert]);
+        }
+    }
+
+    /// Tell the event dispatcher about chain-quality alerts
+    pub fn announce_chain_quality_alerts(&self, alerts: &[ChainQualityAlert]) {
+        let Some(dispatcher) = self.dispatcher else {
+            return;
+        };
+        for alert in alerts.iter() {
+            dispatcher.announce_chain_quality_alert(alert);
+        }
+    }
+
     /// Replay any existing Stacks blocks we have that arose on a different PoX fork.
     /// This is best-effort -- if a block isn't found or can't be loaded, it's skipped.
     fn replay_stacks_blocks(
@@ -3310,6 +3495,7 @@ impl<
 
                     debug!("Bump blocks processed ({})", &new_canonical_stacks_block);
 
+                    header_cache::invalidate(&self.chain_state_db.root_path);
                     self.notifier.notify_stacks_block_processed();
                     increment_stx_blocks_processed_counter();
 
@@ -3360,6 +3546,46 @@ impl<
                         }
                     }
 
+                    // update per-contract costs
+                    if let Some(ref mut contract_cost_db) = self.contract_cost_db {
+                        if let Err(e) = contract_cost_db.notify_block(&block_receipt) {
+                            warn!("Failed to record contract costs for block receipt";
+                                  "stacks_block" => %block_hash,
+                                  "stacks_height" => %block_receipt.header.stacks_block_height,
+                                  "error" => %e);
+                        }
+                    }
+
+                    // record transaction receipts
+                    if let Some(ref mut receipt_db) = self.receipt_db {
+                        if let Err(e) = receipt_db.notify_block(&block_receipt) {
+                            warn!("Failed to record transaction receipts for block receipt";
+                                  "stacks_block" => %block_hash,
+                                  "stacks_height" => %block_receipt.header.stacks_block_height,
+                                  "error" => %e);
+                        }
+                    }
+
+                    // record the block's state diff
+                    if let Some(ref mut state_diff_db) = self.state_diff_db {
+                        if let Err(e) = state_diff_db.notify_block(&block_receipt) {
+                            warn!("Failed to record state diff for block receipt";
+                                  "stacks_block" => %block_hash,
+                                  "stacks_height" => %block_receipt.header.stacks_block_height,
+                                  "error" => %e);
+                        }
+                    }
+
+                    // discard old Clarity state
+                    if let Some(clarity_pruner) = self.clarity_pruner.as_mut() {
+                        if let Err(e) = clarity_pruner.maybe_start(&self.chain_state_db) {
+                            warn!("Failed to start pruning Clarity state";
+                                  "stacks_block" => %block_hash,
+                                  "stacks_height" => %block_receipt.header.stacks_block_height,
+                                  "error" => ?e);
+                        }
+                    }
+
                     // Was this block sufficiently confirmed by the prepare phase that it was a PoX
                     // anchor block?  And if we're in epoch 2.1, does it match the heaviest-confirmed
                     // block-commit in the burnchain DB, and is it affirmed by the majority of the
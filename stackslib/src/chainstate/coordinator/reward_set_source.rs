@@ -0,0 +1,298 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use std::{fmt, fs};
+
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::net::PeerHost;
+use url::Url;
+
+use crate::burnchains::Burnchain;
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::coordinator::{
+    BlockEventDispatcher, Error, OnChainRewardSetProvider, RewardSetProvider,
+};
+use crate::chainstate::stacks::boot::RewardSet;
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::net::http::HttpRequestContents;
+use crate::net::httpcore::{send_http_request, StacksHttpRequest};
+
+/// How long to wait on an HTTP reward set source before giving up
+pub const REWARD_SET_SOURCE_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where the chains coordinator obtains PoX reward sets from.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum RewardSetSource {
+    /// Compute reward sets from PoX contract state.  This is the only source allowed on mainnet.
+    #[default]
+    OnChain,
+    /// Load reward sets from a JSON file, which maps reward cycle numbers to reward sets.
+    File(PathBuf),
+    /// Fetch reward sets from an HTTP server with `GET <url>/<reward cycle>`.  The server answers
+    /// with a reward set, or with `null` to defer to the on-chain reward set.
+    Http(Url),
+}
+
+impl RewardSetSource {
+    pub fn is_on_chain(&self) -> bool {
+        matches!(self, Self::OnChain)
+    }
+}
+
+impl FromStr for RewardSetSource {
+    type Err = String;
+
+    /// Parse `onchain`, `file:///path/to/reward-sets.json`, or `http://host:port/path`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "onchain" {
+            return Ok(Self::OnChain);
+        }
+        let url = Url::parse(s).map_err(|e| format!("Invalid reward set source '{s}': {e}"))?;
+        match url.scheme() {
+            "file" => {
+                let path = url
+                    .to_file_path()
+                    .map_err(|_| format!("Invalid reward set file path '{s}'"))?;
+                Ok(Self::File(path))
+            }
+            "http" => Ok(Self::Http(url)),
+            scheme => Err(format!(
+                "Unsupported reward set source scheme '{scheme}': expected 'onchain', 'file://', or 'http://'"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for RewardSetSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OnChain => write!(f, "onchain"),
+            Self::File(path) => write!(f, "file://{}", path.display()),
+            Self::Http(url) => write!(f, "{url}"),
+        }
+    }
+}
+
+/// The reward sets injected by a configured `RewardSetSource`.  A node builds this once at
+/// startup and shares it between the chains coordinator and everything else that loads reward
+/// sets (block acceptance, relaying, the RPC endpoints), so they all agree on each cycle's
+/// reward set.
+#[derive(Debug, Default)]
+pub struct InjectedRewardSets {
+    source: RewardSetSource,
+    /// Reward sets obtained from `source`, keyed by reward cycle.  A `None` entry means that the
+    /// source deferred to the on-chain reward set for that cycle.
+    cache: Mutex<HashMap<u64, Option<RewardSet>>>,
+}
+
+impl InjectedRewardSets {
+    /// Instantiate the injected reward sets.  File-backed reward sets are loaded eagerly, so that
+    /// a malformed file is reported at startup; HTTP-backed reward sets are fetched as each cycle
+    /// is needed.
+    pub fn new(source: RewardSetSource) -> Result<Self, Error> {
+        let cache = match &source {
+            RewardSetSource::File(path) => Self::load_reward_set_file(path)?
+                .into_iter()
+                .map(|(cycle, reward_set)| (cycle, Some(reward_set)))
+                .collect(),
+            RewardSetSource::OnChain | RewardSetSource::Http(_) => HashMap::new(),
+        };
+        if !source.is_on_chain() {
+            warn!("Using injected reward sets instead of on-chain PoX state"; "source" => %source);
+        }
+        Ok(Self {
+            source,
+            cache: Mutex::new(cache),
+        })
+    }
+
+    pub fn source(&self) -> &RewardSetSource {
+        &self.source
+    }
+
+    /// Read a JSON object mapping reward cycle numbers to reward sets
+    fn load_reward_set_file(path: &PathBuf) -> Result<HashMap<u64, RewardSet>, Error> {
+        let contents = fs::read_to_string(path).map_err(|e| {
+            Error::RewardSetSourceError(format!(
+                "Failed to read reward set file {}: {e}",
+                path.display()
+            ))
+        })?;
+        serde_json::from_str(&contents).map_err(|e| {
+            Error::RewardSetSourceError(format!(
+                "Failed to parse reward set file {}: {e}",
+                path.display()
+            ))
+        })
+    }
+
+    /// Fetch the reward set for `reward_cycle` from an HTTP reward set source.
+    /// Returns Ok(None) if the server deferred to the on-chain reward set.
+    fn fetch_reward_set(url: &Url, reward_cycle: u64) -> Result<Option<RewardSet>, Error> {
+        let host = url.host_str().ok_or_else(|| {
+            Error::RewardSetSourceError(format!("Reward set source {url} has no host"))
+        })?;
+        let port = url.port_or_known_default().unwrap_or(80);
+        let path = format!("{}/{reward_cycle}", url.path().trim_end_matches('/'));
+        let request = StacksHttpRequest::new_for_peer(
+            PeerHost::from_host_port(host.to_string(), port),
+            "GET".into(),
+            path,
+            HttpRequestContents::new(),
+        )
+        .map_err(|e| Error::RewardSetSourceError(format!("Failed to build request: {e:?}")))?
+        .with_header("Connection".to_string(), "close".to_string());
+
+        let response = send_http_request(host, port, request, REWARD_SET_SOURCE_HTTP_TIMEOUT)
+            .map_err(|e| {
+                Error::RewardSetSourceError(format!(
+                    "Failed to fetch reward set for cycle {reward_cycle} from {url}: {e}"
+                ))
+            })?;
+        let payload = response.get_http_payload_ok().map_err(|e| {
+            Error::RewardSetSourceError(format!(
+                "Invalid reward set response for cycle {reward_cycle} from {url}: {e:?}"
+            ))
+        })?;
+        let payload: serde_json::Value = payload.try_into().map_err(|e| {
+            Error::RewardSetSourceError(format!(
+                "Reward set response for cycle {reward_cycle} from {url} is not JSON: {e:?}"
+            ))
+        })?;
+        serde_json::from_value(payload).map_err(|e| {
+            Error::RewardSetSourceError(format!(
+                "Failed to decode reward set for cycle {reward_cycle} from {url}: {e}"
+            ))
+        })
+    }
+
+    /// Get the injected reward set for `reward_cycle`, if the source provides one.
+    /// Returns Ok(None) if the on-chain reward set should be used instead.
+    pub fn get(&self, reward_cycle: u64) -> Result<Option<RewardSet>, Error> {
+        let mut cache = self
+            .cache
+            .lock()
+            .expect("FATAL: injected reward set lock poisoned");
+        if let Some(reward_set) = cache.get(&reward_cycle) {
+            return Ok(reward_set.clone());
+        }
+        let RewardSetSource::Http(url) = &self.source else {
+            return Ok(None);
+        };
+        // errors are not cached, so the coordinator will try again
+        let reward_set = Self::fetch_reward_set(url, reward_cycle)?;
+        cache.insert(reward_cycle, reward_set.clone());
+        Ok(reward_set)
+    }
+}
+
+/// A reward set provider that serves reward sets from a configured `RewardSetSource`.  Any reward
+/// cycle the source does not cover is computed on-chain, so testnets and replay tooling can
+/// inject custom reward sets for a few cycles without touching `.pox-4` state.
+pub struct ConfiguredRewardSetProvider<'a, T: BlockEventDispatcher> {
+    injected: Arc<InjectedRewardSets>,
+    on_chain: OnChainRewardSetProvider<'a, T>,
+}
+
+impl<'a, T: BlockEventDispatcher> ConfiguredRewardSetProvider<'a, T> {
+    pub fn new(
+        injected: Arc<InjectedRewardSets>,
+        on_chain: OnChainRewardSetProvider<'a, T>,
+    ) -> Self {
+        Self { injected, on_chain }
+    }
+
+    /// Get the injected reward set for `reward_cycle`, if the source provides one.
+    /// Returns Ok(None) if the on-chain reward set should be used instead.
+    pub fn get_injected_reward_set(&self, reward_cycle: u64) -> Result<Option<RewardSet>, Error> {
+        self.injected.get(reward_cycle)
+    }
+
+    /// Read the Nakamoto reward set for `cycle` as of `block_id`: the injected one if the source
+    /// provides it, and otherwise the one written to `.signers`.
+    /// See `OnChainRewardSetProvider::read_reward_set_nakamoto()`.
+    pub fn read_reward_set_nakamoto(
+        &self,
+        chainstate: &mut StacksChainState,
+        cycle: u64,
+        sortdb: &SortitionDB,
+        block_id: &StacksBlockId,
+        debug_log: bool,
+    ) -> Result<RewardSet, Error> {
+        if let Some(reward_set) = self.get_injected_reward_set(cycle)? {
+            return Ok(reward_set);
+        }
+        self.on_chain
+            .read_reward_set_nakamoto(chainstate, cycle, sortdb, block_id, debug_log)
+    }
+}
+
+impl<T: BlockEventDispatcher> RewardSetProvider for ConfiguredRewardSetProvider<'_, T> {
+    fn get_reward_set(
+        &self,
+        cycle_start_burn_height: u64,
+        chainstate: &mut StacksChainState,
+        burnchain: &Burnchain,
+        sortdb: &SortitionDB,
+        block_id: &StacksBlockId,
+    ) -> Result<RewardSet, Error> {
+        let reward_cycle = burnchain
+            .block_height_to_reward_cycle(cycle_start_burn_height)
+            .expect("FATAL: no reward cycle for burn height");
+        if let Some(reward_set) = self.get_injected_reward_set(reward_cycle)? {
+            info!("Using injected reward set";
+                  "reward_cycle" => reward_cycle,
+                  "source" => %self.injected.source(),
+                  "rewarded_addresses" => reward_set.rewarded_addresses.len());
+            return Ok(reward_set);
+        }
+        self.on_chain.get_reward_set(
+            cycle_start_burn_height,
+            chainstate,
+            burnchain,
+            sortdb,
+            block_id,
+        )
+    }
+
+    fn get_reward_set_nakamoto(
+        &self,
+        chainstate: &mut StacksChainState,
+        reward_cycle: u64,
+        sortdb: &SortitionDB,
+        block_id: &StacksBlockId,
+    ) -> Result<RewardSet, Error> {
+        if let Some(reward_set) = self.get_injected_reward_set(reward_cycle)? {
+            if reward_set.signers.is_none() {
+                error!("Injected reward set does not specify a signer set in Nakamoto";
+                       "reward_cycle" => reward_cycle,
+                       "source" => %self.injected.source());
+                return Err(Error::PoXAnchorBlockRequired);
+            }
+            debug!("Using injected Nakamoto reward set";
+                   "reward_cycle" => reward_cycle,
+                   "source" => %self.injected.source());
+            return Ok(reward_set);
+        }
+        self.on_chain
+            .get_reward_set_nakamoto(chainstate, reward_cycle, sortdb, block_id)
+    }
+}
@@ -15,7 +15,7 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::cmp;
-use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::mpsc::sync_channel;
 use std::sync::{Arc, RwLock};
@@ -55,7 +55,9 @@ use crate::chainstate::burn::distribution::BurnSamplePoint;
 use crate::chainstate::burn::operations::leader_block_commit::*;
 use crate::chainstate::burn::operations::*;
 use crate::chainstate::burn::*;
+use crate::chainstate::coordinator::comm::{CoordinatorEvents, PendingStacksReorg};
 use crate::chainstate::coordinator::{Error as CoordError, *};
+use crate::chainstate::nakamoto::NakamotoBlock;
 use crate::chainstate::stacks::address::{PoxAddress, PoxAddressType32};
 use crate::chainstate::stacks::boot::{
     PoxStartCycleInfo, COSTS_2_NAME, POX_1_NAME, POX_2_NAME, POX_3_NAME,
@@ -66,6 +68,7 @@ use crate::chainstate::stacks::miner::BlockBuilder;
 use crate::chainstate::stacks::*;
 use crate::clarity_vm::clarity::ClarityConnection;
 use crate::core::*;
+use crate::monitoring::chain_quality::ChainQualityAlert;
 use crate::monitoring::increment_stx_blocks_processed_counter;
 use crate::util_lib::boot::{boot_code_addr, boot_code_id};
 use crate::util_lib::strings::StacksString;
@@ -439,6 +442,13 @@ impl BlockEventDispatcher for NullEventDispatcher {
         );
     }
 
+    fn announce_raw_nakamoto_block(&self, _block: &NakamotoBlock) {
+        assert!(
+            false,
+            "We should never try to announce to the null dispatcher"
+        );
+    }
+
     fn announce_burn_block(
         &self,
         _burn_block: &BurnchainHeaderHash,
@@ -449,6 +459,20 @@ impl BlockEventDispatcher for NullEventDispatcher {
         _consensus_hash: &ConsensusHash,
     ) {
     }
+
+    fn announce_reward_cycle(
+        &self,
+        _reward_cycle_info: &RewardCycleInfo,
+        _burn_block: &BurnchainHeaderHash,
+        _burn_block_height: u64,
+        _consensus_hash: &ConsensusHash,
+        _pox_constants: &PoxConstants,
+    ) {
+    }
+
+    fn announce_deep_reorg(&self, _reorg: &PendingStacksReorg) {}
+
+    fn announce_chain_quality_alert(&self, _alert: &ChainQualityAlert) {}
 }
 
 pub fn make_coordinator<'a>(
@@ -6860,3 +6884,227 @@ fn test_check_chainstate_db_versions() {
     // should fail in epoch 2.05
     assert!(!check_chainstate_db_versions(&[epoch_2_05], &sortdb_path, &chainstate_path).unwrap());
 }
+
+#[test]
+fn test_reward_set_source_parse() {
+    assert_eq!(
+        "onchain".parse::<RewardSetSource>().unwrap(),
+        RewardSetSource::OnChain
+    );
+    assert_eq!(
+        "file:///tmp/reward-sets.json"
+            .parse::<RewardSetSource>()
+            .unwrap(),
+        RewardSetSource::File("/tmp/reward-sets.json".into())
+    );
+    assert!(matches!(
+        "http://127.0.0.1:20500/reward-sets"
+            .parse::<RewardSetSource>()
+            .unwrap(),
+        RewardSetSource::Http(_)
+    ));
+    assert!("https://127.0.0.1:20500/reward-sets"
+        .parse::<RewardSetSource>()
+        .is_err());
+    assert!("pox-4".parse::<RewardSetSource>().is_err());
+}
+
+#[test]
+fn test_file_reward_set_source() {
+    let path = std::env::temp_dir().join(format!(
+        "test_file_reward_set_source-{}.json",
+        std::process::id()
+    ));
+    let mut reward_set = RewardSet::empty();
+    reward_set.signers = Some(vec![]);
+    let reward_sets: HashMap<u64, RewardSet> = [(5, reward_set.clone())].into_iter().collect();
+    std::fs::write(&path, serde_json::to_string(&reward_sets).unwrap()).unwrap();
+
+    let injected = Arc::new(InjectedRewardSets::new(RewardSetSource::File(path.clone())).unwrap());
+    let provider = ConfiguredRewardSetProvider::new(injected, OnChainRewardSetProvider::new());
+    assert_eq!(
+        provider.get_injected_reward_set(5).unwrap(),
+        Some(reward_set)
+    );
+    // cycles missing from the file are computed on-chain
+    assert_eq!(provider.get_injected_reward_set(6).unwrap(), None);
+
+    // malformed files are rejected up front
+    std::fs::write(&path, "not json").unwrap();
+    assert!(matches!(
+        InjectedRewardSets::new(RewardSetSource::File(path.clone())),
+        Err(CoordError::RewardSetSourceError(_))
+    ));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_sortition_db_check_and_repair() {
+    let path = &test_path("sortition-db-check-and-repair");
+    let _r = std::fs::remove_dir_all(path);
+
+    let vrf_keys: Vec<_> = (0..15).map(|_| VRFPrivateKey::new()).collect();
+    let committers: Vec<_> = (0..15).map(|_| StacksPrivateKey::random()).collect();
+
+    setup_states(
+        &[path],
+        &vrf_keys,
+        &committers,
+        None,
+        None,
+        StacksEpochId::Epoch21,
+    );
+
+    let mut coord = make_coordinator(path, None);
+    coord.handle_new_burnchain_block().unwrap();
+
+    let sort_db = get_sortition_db(path, None);
+    let mut parent = BlockHeaderHash([0; 32]);
+    for (ix, (vrf_key, miner)) in vrf_keys.iter().zip(committers.iter()).enumerate() {
+        let mut burnchain = get_burnchain_db(path, None);
+        let mut chainstate = get_chainstate(path);
+        let b = get_burnchain(path, None);
+        let burnchain_tip = burnchain.get_canonical_chain_tip().unwrap();
+
+        let (op, block) = if ix == 0 {
+            make_genesis_block(
+                &b,
+                &sort_db,
+                &mut chainstate,
+                &parent,
+                miner,
+                10000,
+                vrf_key,
+                ix as u32,
+            )
+        } else {
+            make_stacks_block(
+                &sort_db,
+                &mut chainstate,
+                &b,
+                &parent,
+                burnchain_tip.block_height,
+                miner,
+                10000,
+                vrf_key,
+                ix as u32,
+            )
+        };
+        produce_burn_block(
+            &b,
+            &mut burnchain,
+            &burnchain_tip.block_hash,
+            vec![op],
+            [].iter_mut(),
+        );
+        coord.handle_new_burnchain_block().unwrap();
+
+        let tip = SortitionDB::get_canonical_burn_chain_tip(sort_db.conn()).unwrap();
+        parent = block.header.block_hash();
+        preprocess_block(&mut chainstate, &sort_db, &tip, block);
+        coord.handle_new_stacks_block().unwrap();
+    }
+
+    let burnchain = get_burnchain(path, None);
+    let burnchain_db = get_burnchain_db(path, None);
+    let tip = SortitionDB::get_canonical_burn_chain_tip(sort_db.conn()).unwrap();
+
+    let report = sort_db
+        .check_consistency(&burnchain, &burnchain_db)
+        .unwrap();
+    assert!(report.is_consistent(), "{report:?}");
+    assert_eq!(
+        report.checked_sortitions,
+        tip.block_height - sort_db.first_block_height + 1
+    );
+
+    // corrupt a consensus hash
+    let corrupt_height = tip.block_height - 5;
+    let corrupt_sn = SortitionDB::get_ancestor_snapshot(
+        &sort_db.index_conn(),
+        corrupt_height,
+        &tip.sortition_id,
+    )
+    .unwrap()
+    .unwrap();
+    let mut rw_sort_db = get_rw_sortdb(path, None);
+    rw_sort_db
+        .conn()
+        .execute(
+            "UPDATE snapshots SET consensus_hash = ?1 WHERE sortition_id = ?2",
+            rusqlite::params![ConsensusHash([0xff; 20]), corrupt_sn.sortition_id],
+        )
+        .unwrap();
+
+    let report = sort_db
+        .check_consistency(&burnchain, &burnchain_db)
+        .unwrap();
+    assert!(!report.is_consistent());
+    assert_eq!(report.first_inconsistent_height(), Some(corrupt_height));
+    assert_eq!(
+        report.inconsistencies[0].sortition_id,
+        corrupt_sn.sortition_id
+    );
+
+    // roll back to just below the corruption, and re-derive the rest
+    let deleted = rw_sort_db.rollback_to_height(corrupt_height - 1).unwrap();
+    assert_eq!(deleted, tip.block_height - corrupt_height + 1);
+    let rolled_back_tip = SortitionDB::get_canonical_burn_chain_tip(sort_db.conn()).unwrap();
+    assert_eq!(rolled_back_tip.block_height, corrupt_height - 1);
+
+    let mut coord = make_coordinator(path, None);
+    coord.handle_new_burnchain_block().unwrap();
+
+    let repaired_tip = SortitionDB::get_canonical_burn_chain_tip(sort_db.conn()).unwrap();
+    assert_eq!(repaired_tip.sortition_id, tip.sortition_id);
+    assert_eq!(repaired_tip.consensus_hash, tip.consensus_hash);
+    let repaired_sn = SortitionDB::get_block_snapshot(sort_db.conn(), &corrupt_sn.sortition_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(repaired_sn.consensus_hash, corrupt_sn.consensus_hash);
+
+    let report = sort_db
+        .check_consistency(&burnchain, &burnchain_db)
+        .unwrap();
+    assert!(report.is_consistent(), "{report:?}");
+}
+
+#[test]
+fn test_confirm_pending_reorg() {
+    let (receivers, channels) = CoordinatorCommunication::instantiate();
+    let new_tip = StacksBlockId([0x02; 32]);
+    let reorg = PendingStacksReorg {
+        old_tip: StacksBlockId([0x01; 32]),
+        old_tip_height: 20,
+        new_tip: new_tip.clone(),
+        new_tip_height: 21,
+        fork_height: 10,
+        depth: 10,
+        max_depth: 6,
+    };
+
+    // nothing to confirm yet
+    assert!(channels.get_pending_reorg().is_none());
+    assert!(channels.confirm_reorg(&new_tip).is_none());
+
+    receivers.reorg_guard.lock().unwrap().pending = Some(reorg.clone());
+    assert_eq!(channels.get_pending_reorg(), Some(reorg.clone()));
+
+    // only the pending reorg's new tip can be confirmed
+    assert!(channels.confirm_reorg(&StacksBlockId([0x03; 32])).is_none());
+    assert_eq!(channels.get_pending_reorg(), Some(reorg.clone()));
+    assert!(receivers.reorg_guard.lock().unwrap().confirmed.is_none());
+
+    // confirming it clears it, and wakes up the coordinator to switch tips
+    assert_eq!(channels.confirm_reorg(&new_tip), Some(reorg));
+    assert!(channels.get_pending_reorg().is_none());
+    assert_eq!(
+        receivers.reorg_guard.lock().unwrap().confirmed,
+        Some(new_tip.clone())
+    );
+    assert_eq!(
+        receivers.wait_on() & (CoordinatorEvents::NEW_STACKS_BLOCK as u8),
+        CoordinatorEvents::NEW_STACKS_BLOCK as u8
+    );
+    assert!(channels.confirm_reorg(&new_tip).is_none());
+}
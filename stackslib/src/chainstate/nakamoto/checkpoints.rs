@@ -0,0 +1,271 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signed chain checkpoints.
+//!
+//! Signers periodically write a `SignedChainCheckpoint` -- the height and ID of a block they
+//! have seen accepted -- to their slot in the `.checkpoints` boot StackerDB.  Unlike the
+//! `.signers-*` StackerDBs, `.checkpoints` has no contract behind it: like `.miners`, its
+//! config is generated directly, with one slot for each signer in the reward set that is
+//! active at the node's sortition tip.
+//!
+//! A node that is still in initial block download treats the checkpoints endorsed by a
+//! threshold of the reward set's signing weight as weak subjectivity hints: if the chain it
+//! has synced so far disagrees with one, it is following a history that the signers never
+//! accepted, and it says so instead of only finding out once it reaches the chain tip.
+//! Checkpoints are only checked against the reward set of the node's current sortition tip,
+//! so they are of use to a node whose burnchain view has reached the reward cycle in which
+//! they were signed.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use sha2::{Digest, Sha512_256};
+use stacks_common::codec::{read_next, write_next, Error as CodecError, StacksMessageCodec};
+use stacks_common::types::chainstate::{
+    StacksAddress, StacksBlockId, StacksPrivateKey, StacksPublicKey,
+};
+use stacks_common::types::PrivateKey;
+use stacks_common::util::hash::Sha512Trunc256Sum;
+use stacks_common::util::secp256k1::MessageSignature;
+
+use crate::chainstate::nakamoto::sigcheck::RewardSetSignerKeys;
+use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::stacks::boot::RewardSet;
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::chainstate::stacks::index::marf::MarfConnection;
+use crate::chainstate::stacks::Error as ChainstateError;
+use crate::net::stackerdb::StackerDBConfig;
+
+/// Domain separator for checkpoint signatures, so that they can't be confused with any other
+/// message a signer signs
+const CHECKPOINT_SIGNATURE_DOMAIN: &[u8] = b"stacks-chain-checkpoint-v1";
+
+/// Size of a `.checkpoints` chunk.  A checkpoint is 117 bytes.
+pub const CHECKPOINTS_CHUNK_SIZE: u64 = 1024;
+
+/// A signer's statement that `block_id`, at `block_height`, is part of the canonical chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedChainCheckpoint {
+    /// The chain the block belongs to
+    pub chain_id: u32,
+    /// The reward cycle of the signer that made this checkpoint
+    pub reward_cycle: u64,
+    /// Height of the checkpointed block
+    pub block_height: u64,
+    /// ID of the checkpointed block
+    pub block_id: StacksBlockId,
+    /// The signer's signature over all of the above
+    pub signature: MessageSignature,
+}
+
+impl StacksMessageCodec for SignedChainCheckpoint {
+    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
+        write_next(fd, &self.chain_id)?;
+        write_next(fd, &self.reward_cycle)?;
+        write_next(fd, &self.block_height)?;
+        write_next(fd, &self.block_id)?;
+        write_next(fd, &self.signature)?;
+        Ok(())
+    }
+
+    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<Self, CodecError> {
+        Ok(Self {
+            chain_id: read_next(fd)?,
+            reward_cycle: read_next(fd)?,
+            block_height: read_next(fd)?,
+            block_id: read_next(fd)?,
+            signature: read_next(fd)?,
+        })
+    }
+}
+
+impl SignedChainCheckpoint {
+    /// Make a checkpoint of `block_id` and sign it with the signer's key
+    pub fn new_signed(
+        chain_id: u32,
+        reward_cycle: u64,
+        block_height: u64,
+        block_id: StacksBlockId,
+        private_key: &StacksPrivateKey,
+    ) -> Result<Self, &'static str> {
+        let mut checkpoint = Self {
+            chain_id,
+            reward_cycle,
+            block_height,
+            block_id,
+            signature: MessageSignature::empty(),
+        };
+        checkpoint.signature = private_key.sign(checkpoint.signature_hash().as_bytes())?;
+        Ok(checkpoint)
+    }
+
+    /// The digest the signer signs
+    pub fn signature_hash(&self) -> Sha512Trunc256Sum {
+        let mut hasher = Sha512_256::new();
+        hasher.update(CHECKPOINT_SIGNATURE_DOMAIN);
+        hasher.update(self.chain_id.to_be_bytes());
+        hasher.update(self.reward_cycle.to_be_bytes());
+        hasher.update(self.block_height.to_be_bytes());
+        hasher.update(self.block_id.as_bytes());
+        Sha512Trunc256Sum::from_hasher(hasher)
+    }
+
+    /// Recover the public key of the signer that signed this checkpoint
+    pub fn recover_signer_key(&self) -> Result<StacksPublicKey, &'static str> {
+        StacksPublicKey::recover_to_pubkey(self.signature_hash().as_bytes(), &self.signature)
+    }
+}
+
+/// A checkpoint, and the signing weight behind it
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndorsedCheckpoint {
+    pub block_height: u64,
+    pub block_id: StacksBlockId,
+    /// Total weight of the signers who signed it
+    pub weight: u32,
+}
+
+/// A signed checkpoint that the node's canonical chain disagrees with
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointConflict {
+    pub checkpoint: EndorsedCheckpoint,
+    /// The block the node has at the checkpoint's height
+    pub local_block_id: StacksBlockId,
+}
+
+/// The checkpoints endorsed by a threshold of one reward set's signers
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChainCheckpointHints {
+    /// The reward cycle whose signers endorsed these checkpoints
+    pub reward_cycle: u64,
+    /// Endorsed checkpoints, by ascending height
+    pub checkpoints: Vec<EndorsedCheckpoint>,
+}
+
+impl ChainCheckpointHints {
+    /// Tally the checkpoints in the `.checkpoints` chunks `chunks` (in any slot order).
+    /// Checkpoints for another chain or reward cycle, or not signed by a signer of
+    /// `signer_keys`, are ignored, as is all but one checkpoint per signer.  A checkpoint is
+    /// endorsed if the signers who signed it hold at least the block signing threshold.
+    pub fn tally(
+        chain_id: u32,
+        reward_cycle: u64,
+        signer_keys: &RewardSetSignerKeys,
+        chunks: &[Option<Vec<u8>>],
+    ) -> Self {
+        let mut signed_by = HashMap::new();
+        for data in chunks.iter().flatten() {
+            let Ok(checkpoint) = SignedChainCheckpoint::consensus_deserialize(&mut &data[..])
+            else {
+                continue;
+            };
+            if checkpoint.chain_id != chain_id || checkpoint.reward_cycle != reward_cycle {
+                continue;
+            }
+            let Ok(signer_key) = checkpoint.recover_signer_key() else {
+                continue;
+            };
+            let Ok(signer_key_bytes) = signer_key.to_bytes_compressed().try_into() else {
+                continue;
+            };
+            let Some((signer_index, weight)) = signer_keys.get(&signer_key_bytes) else {
+                continue;
+            };
+            signed_by.insert(
+                signer_index,
+                (checkpoint.block_height, checkpoint.block_id, weight),
+            );
+        }
+
+        let mut weights: HashMap<(u64, StacksBlockId), u32> = HashMap::new();
+        for (block_height, block_id, weight) in signed_by.into_values() {
+            let total = weights.entry((block_height, block_id)).or_insert(0);
+            *total = total.saturating_add(weight);
+        }
+
+        let mut checkpoints: Vec<_> = weights
+            .into_iter()
+            .filter(|(_, weight)| *weight >= signer_keys.threshold())
+            .map(|((block_height, block_id), weight)| EndorsedCheckpoint {
+                block_height,
+                block_id,
+                weight,
+            })
+            .collect();
+        checkpoints.sort_by_key(|checkpoint| checkpoint.block_height);
+        Self {
+            reward_cycle,
+            checkpoints,
+        }
+    }
+
+    /// Find the endorsed checkpoints that the chain ending at `tip` (at `tip_height`)
+    /// disagrees with.  Checkpoints above `tip_height` can't be checked yet.
+    pub fn find_conflicts(
+        &self,
+        chainstate: &mut StacksChainState,
+        tip: &StacksBlockId,
+        tip_height: u64,
+    ) -> Result<Vec<CheckpointConflict>, ChainstateError> {
+        let mut conflicts = vec![];
+        for checkpoint in self.checkpoints.iter() {
+            if checkpoint.block_height > tip_height {
+                break;
+            }
+            let Some(local_block_id) = chainstate
+                .index_conn()
+                .get_ancestor_block_hash(checkpoint.block_height, tip)?
+            else {
+                continue;
+            };
+            if local_block_id != checkpoint.block_id {
+                conflicts.push(CheckpointConflict {
+                    checkpoint: checkpoint.clone(),
+                    local_block_id,
+                });
+            }
+        }
+        Ok(conflicts)
+    }
+}
+
+impl NakamotoChainState {
+    /// Create a StackerDB config for the .checkpoints contract.
+    /// Each signer in `reward_set` gets one slot, in reward set order.
+    pub fn make_checkpoints_stackerdb_config(
+        reward_set: &RewardSet,
+        mainnet: bool,
+    ) -> Result<StackerDBConfig, ChainstateError> {
+        let Some(signers) = reward_set.signers.as_ref() else {
+            return Err(ChainstateError::NoRegisteredSigners(0));
+        };
+        let mut slots = Vec::with_capacity(signers.len());
+        for signer in signers.iter() {
+            let signing_key = StacksPublicKey::from_slice(&signer.signing_key).map_err(|e| {
+                ChainstateError::InvalidStacksBlock(format!("Invalid signer signing key: {e}"))
+            })?;
+            slots.push((StacksAddress::p2pkh(mainnet, &signing_key), 1));
+        }
+        Ok(StackerDBConfig {
+            chunk_size: CHECKPOINTS_CHUNK_SIZE,
+            signers: slots,
+            write_freq: 0,
+            max_writes: u32::MAX,
+            max_neighbors: 200,
+            hint_replicas: vec![],
+        })
+    }
+}
@@ -37,18 +37,19 @@ use crate::chainstate::burn::operations::leader_block_commit::RewardSetInfo;
 use crate::chainstate::burn::BlockSnapshot;
 use crate::chainstate::coordinator::comm::{
     CoordinatorChannels, CoordinatorCommunication, CoordinatorEvents, CoordinatorNotices,
-    CoordinatorReceivers,
+    CoordinatorReceivers, PendingStacksReorg,
 };
 use crate::chainstate::coordinator::{
     calculate_paid_rewards, dispatcher_announce_burn_ops, BlockEventDispatcher, ChainsCoordinator,
     Error, OnChainRewardSetProvider, PaidRewards, PoxAnchorBlockStatus, RewardCycleInfo,
     RewardSetProvider,
 };
-use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::nakamoto::{header_cache, NakamotoChainState};
 use crate::chainstate::stacks::boot::{RewardSet, SIGNERS_NAME};
 use crate::chainstate::stacks::db::{
     StacksBlockHeaderTypes, StacksChainState, StacksDBConn, StacksHeaderInfo,
 };
+use crate::chainstate::stacks::events::TransactionOrigin;
 use crate::chainstate::stacks::index::marf::MarfConnection;
 use crate::chainstate::stacks::miner::{signal_mining_blocked, signal_mining_ready, MinerStatus};
 use crate::chainstate::stacks::Error as ChainstateError;
@@ -145,6 +146,50 @@ impl<T: BlockEventDispatcher> OnChainRewardSetProvider<'_, T> {
         )
     }
 
+    /// Read the reward set for `cycle` at its PoX anchor block `anchor_block_id`, using the
+    /// chainstate's reward set cache.  Reading the reward set from `.signers` needs a Clarity
+    /// query and several MARF lookups, but the result at a given anchor block never changes, so
+    /// it is computed once and then served from the `nakamoto_reward_set_cache` table -- across
+    /// restarts, and to every thread with its own chainstate handle.
+    pub fn read_cached_reward_set_nakamoto(
+        &self,
+        cycle: u64,
+        chainstate: &mut StacksChainState,
+        sortdb: &SortitionDB,
+        anchor_block_id: &StacksBlockId,
+    ) -> Result<RewardSet, Error> {
+        if let Some(reward_set) =
+            NakamotoChainState::get_cached_reward_set(chainstate.db(), cycle, anchor_block_id)?
+        {
+            debug!("Loaded cached reward set";
+                   "cycle_number" => cycle,
+                   "anchor_block_id" => %anchor_block_id);
+            return Ok(reward_set);
+        }
+
+        let reward_set = self.read_reward_set_nakamoto_of_cycle(
+            cycle,
+            chainstate,
+            sortdb,
+            anchor_block_id,
+            false,
+        )?;
+
+        // a failure to cache the reward set only costs us a re-read later
+        let cache_res = chainstate.db_tx_begin().and_then(|tx| {
+            NakamotoChainState::cache_reward_set(&tx, cycle, anchor_block_id, &reward_set)?;
+            tx.commit()
+                .map_err(|e| ChainstateError::DBError(DBError::SqliteError(e)))
+        });
+        if let Err(e) = cache_res {
+            warn!("Failed to cache reward set";
+                  "cycle_number" => cycle,
+                  "anchor_block_id" => %anchor_block_id,
+                  "err" => ?e);
+        }
+        Ok(reward_set)
+    }
+
     pub fn get_height_of_pox_calculation(
         &self,
         cycle: u64,
@@ -593,6 +638,7 @@ pub fn get_nakamoto_next_recipients(
         return Err(Error::BurnchainError(burnchains::Error::NoStacksEpoch));
     };
     let reward_cycle_info = if burnchain.is_reward_cycle_start(next_burn_height) {
+        let reward_set_provider = chain_state.reward_set_provider();
         let Some((reward_set, _)) = load_nakamoto_reward_set(
             reward_cycle,
             &sortition_tip.sortition_id,
@@ -600,7 +646,7 @@ pub fn get_nakamoto_next_recipients(
             chain_state,
             stacks_tip,
             sort_db,
-            &OnChainRewardSetProvider::new(),
+            &reward_set_provider,
         )?
         else {
             return Ok(None);
@@ -781,6 +827,136 @@ impl<
     #[cfg(not(any(test, feature = "testing")))]
     fn fault_injection_pause_nakamoto_block_processing() {}
 
+    /// Find the height of the last block that the Stacks fork tipped at `tip` (at `tip_height`)
+    /// has in common with the fork that includes `other`, a processed block at or above
+    /// `tip_height`.  Returns None if `other`'s ancestry cannot be loaded.
+    fn find_stacks_fork_height(
+        chainstate: &StacksChainState,
+        tip: &StacksBlockId,
+        tip_height: u64,
+        other: &StacksBlockId,
+    ) -> Result<Option<u64>, Error> {
+        let index_conn = chainstate.index_conn();
+        let same_ancestor = |height: u64| -> Result<Option<bool>, Error> {
+            let Some(tip_ancestor) = index_conn.get_ancestor_block_hash(height, tip)? else {
+                return Ok(None);
+            };
+            let Some(other_ancestor) = index_conn.get_ancestor_block_hash(height, other)? else {
+                return Ok(None);
+            };
+            Ok(Some(tip_ancestor == other_ancestor))
+        };
+
+        // forks that share an ancestor share all of its ancestors, so binary-search for the
+        // highest shared one.  Every fork shares the boot block at height 0.
+        let mut low = 0;
+        let mut high = tip_height;
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            let Some(same) = same_ancestor(mid)? else {
+                return Ok(None);
+            };
+            if same {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        Ok(Some(low))
+    }
+
+    /// If `max_reorg_depth` is set, check that processing the next ready Nakamoto block would not
+    /// switch the canonical Stacks tip to a fork that abandons more than that many blocks, unless
+    /// an operator has confirmed the switch.  If it would, record the pending reorg, alert event
+    /// observers (once per reorg), and return false so that block processing pauses.
+    fn check_next_block_reorg_depth(&self) -> Result<bool, Error> {
+        let Some(max_depth) = self.max_reorg_depth else {
+            return Ok(true);
+        };
+        let Some((next_block, _)) = self
+            .chain_state_db
+            .nakamoto_blocks_db()
+            .next_ready_nakamoto_block(self.chain_state_db.db())?
+        else {
+            return Ok(true);
+        };
+        let (tip_ch, tip_bhh, tip_height) =
+            SortitionDB::get_canonical_stacks_chain_tip_hash_and_height(self.sortition_db.conn())?;
+        let tip = StacksBlockId::new(&tip_ch, &tip_bhh);
+        let parent = &next_block.header.parent_block_id;
+        let new_height = next_block.header.chain_length;
+
+        // only a block that would replace the canonical tip, without building on it, can reorg
+        let replaces_tip = new_height > tip_height
+            || (new_height == tip_height && next_block.header.consensus_hash != tip_ch);
+        if !replaces_tip || parent == &tip {
+            return Ok(true);
+        }
+
+        let floor_height = tip_height.saturating_sub(max_depth);
+        let Some(tip_floor) = self
+            .chain_state_db
+            .index_conn()
+            .get_ancestor_block_hash(floor_height, &tip)?
+        else {
+            return Ok(true);
+        };
+        let parent_floor = self
+            .chain_state_db
+            .index_conn()
+            .get_ancestor_block_hash(floor_height, parent)?;
+        if parent_floor.is_none() || parent_floor.as_ref() == Some(&tip_floor) {
+            // either the fork point is within the limit, or the parent is not processed yet (in
+            // which case the block cannot be processed either)
+            return Ok(true);
+        }
+
+        let new_tip = next_block.block_id();
+        let mut guard = self
+            .reorg_guard
+            .lock()
+            .expect("FATAL: reorg guard lock poisoned");
+        if guard.confirmed.as_ref() == Some(&new_tip) {
+            info!("Switching to a deep Stacks fork, as confirmed by the operator";
+                  "old_tip" => %tip,
+                  "new_tip" => %new_tip);
+            guard.confirmed = None;
+            return Ok(true);
+        }
+        if guard.pending.as_ref().map(|reorg| &reorg.new_tip) == Some(&new_tip) {
+            // already alerted
+            return Ok(false);
+        }
+
+        let Some(fork_height) =
+            Self::find_stacks_fork_height(&self.chain_state_db, &tip, tip_height, parent)?
+        else {
+            return Ok(true);
+        };
+        let reorg = PendingStacksReorg {
+            old_tip: tip,
+            old_tip_height: tip_height,
+            new_tip,
+            new_tip_height: new_height,
+            fork_height,
+            depth: tip_height - fork_height,
+            max_depth,
+        };
+        error!("Pausing Stacks block processing: the next block would reorg the canonical Stacks fork deeper than node.max_reorg_depth. Confirm with POST /v3/admin/reorg/confirm/:block_id to proceed.";
+               "old_tip" => %reorg.old_tip,
+               "old_tip_height" => reorg.old_tip_height,
+               "new_tip" => %reorg.new_tip,
+               "new_tip_height" => reorg.new_tip_height,
+               "fork_height" => reorg.fork_height,
+               "depth" => reorg.depth,
+               "max_depth" => reorg.max_depth);
+        if let Some(dispatcher) = self.dispatcher {
+            dispatcher.announce_deep_reorg(&reorg);
+        }
+        guard.pending = Some(reorg);
+        Ok(false)
+    }
+
     /// Handle one or more new Nakamoto Stacks blocks.
     /// If we process a PoX anchor block, then return its block hash.  This unblocks processing the
     /// next reward cycle's burnchain blocks.  Subsequent calls to this function will terminate
@@ -795,6 +971,11 @@ impl<
         loop {
             Self::fault_injection_pause_nakamoto_block_processing();
 
+            if !self.check_next_block_reorg_depth()? {
+                debug!("Stacks block processing is paused on a deep reorg");
+                break;
+            }
+
             // process at most one block per loop pass
             let mut processed_block_receipt = match NakamotoChainState::process_next_nakamoto_block(
                 &mut self.chain_state_db,
@@ -859,6 +1040,7 @@ impl<
 
             debug!("Bump blocks processed ({})", &canonical_stacks_block_id);
 
+            header_cache::invalidate(&self.chain_state_db.root_path);
             self.notifier.notify_stacks_block_processed();
             increment_stx_blocks_processed_counter();
 
@@ -901,6 +1083,50 @@ impl<
                 }
             }
 
+            // update per-contract costs
+            if let Some(ref mut contract_cost_db) = self.contract_cost_db {
+                if let Err(e) = contract_cost_db.notify_block(&block_receipt) {
+                    warn!("Failed to record contract costs for block receipt";
+                        "stacks_block_hash" => %block_hash,
+                        "stacks_block_height" => %block_receipt.header.stacks_block_height,
+                        "error" => %e
+                    );
+                }
+            }
+
+            // record transaction receipts
+            if let Some(ref mut receipt_db) = self.receipt_db {
+                if let Err(e) = receipt_db.notify_block(&block_receipt) {
+                    warn!("Failed to record transaction receipts for block receipt";
+                        "stacks_block_hash" => %block_hash,
+                        "stacks_block_height" => %block_receipt.header.stacks_block_height,
+                        "error" => %e
+                    );
+                }
+            }
+
+            // record the block's state diff
+            if let Some(ref mut state_diff_db) = self.state_diff_db {
+                if let Err(e) = state_diff_db.notify_block(&block_receipt) {
+                    warn!("Failed to record state diff for block receipt";
+                        "stacks_block_hash" => %block_hash,
+                        "stacks_block_height" => %block_receipt.header.stacks_block_height,
+                        "error" => %e
+                    );
+                }
+            }
+
+            // discard old Clarity state
+            if let Some(clarity_pruner) = self.clarity_pruner.as_mut() {
+                if let Err(e) = clarity_pruner.maybe_start(&self.chain_state_db) {
+                    warn!("Failed to start pruning Clarity state";
+                        "stacks_block_hash" => %block_hash,
+                        "stacks_block_height" => %block_receipt.header.stacks_block_height,
+                        "error" => ?e
+                    );
+                }
+            }
+
             let stacks_sn = SortitionDB::get_block_snapshot_consensus(
                 self.sortition_db.conn(),
                 &canonical_stacks_consensus_hash,
@@ -912,6 +1138,34 @@ impl<
                 )
             });
 
+            // update chain-quality statistics.  Shadow blocks aren't signed, so they don't count
+            // towards signer participation.
+            let signers = block_receipt
+                .header
+                .anchored_header
+                .as_stacks_nakamoto()
+                .filter(|header| !header.is_shadow_block())
+                .and_then(|header| {
+                    let num_signers = self
+                        .sortition_db
+                        .get_preprocessed_reward_set_size(&stacks_sn.sortition_id)?;
+                    Some((header.signer_signature.len(), usize::from(num_signers)))
+                });
+            let txs = block_receipt.tx_receipts.iter().filter_map(|receipt| {
+                if let TransactionOrigin::Stacks(tx) = &receipt.transaction {
+                    Some(tx)
+                } else {
+                    None
+                }
+            });
+            let alerts = self.chain_quality.observe_nakamoto_block(
+                &canonical_stacks_block_id,
+                &canonical_stacks_consensus_hash,
+                txs,
+                signers,
+            );
+            self.announce_chain_quality_alerts(&alerts);
+
             // are we in the prepare phase?
             // TODO: this should *not* include the 0 block!
             if !self
@@ -948,7 +1202,7 @@ impl<
                     &mut self.chain_state_db,
                     &canonical_stacks_block_id,
                     &self.sortition_db,
-                    &OnChainRewardSetProvider::new(),
+                    &self.reward_set_provider,
                 )?
                 else {
                     // no anchor block yet, so try processing another block
@@ -996,6 +1250,26 @@ impl<
         )
     }
 
+    /// Drop the cached reward sets whose PoX anchor blocks could be chosen differently after a
+    /// burnchain reorg that replaced the blocks at and above `fork_height`.  A reward cycle's
+    /// anchor block is chosen in the prepare phase before the cycle starts, so only the cycles
+    /// after the one containing `fork_height` are affected.
+    fn invalidate_cached_reward_sets(&mut self, fork_height: u64) -> Result<(), Error> {
+        let Some(fork_reward_cycle) = self.burnchain.block_height_to_reward_cycle(fork_height)
+        else {
+            return Ok(());
+        };
+        let from_reward_cycle = fork_reward_cycle.saturating_add(1);
+        let tx = self.chain_state_db.db_tx_begin()?;
+        let dropped = NakamotoChainState::invalidate_cached_reward_sets(&tx, from_reward_cycle)?;
+        tx.commit().map_err(DBError::SqliteError)?;
+        info!("Burnchain reorg: invalidated cached reward sets";
+              "fork_height" => fork_height,
+              "from_reward_cycle" => from_reward_cycle,
+              "dropped" => dropped);
+        Ok(())
+    }
+
     /// Find sortitions to process.
     /// Returns the last processed ancestor of `cursor`, and any unprocessed burnchain blocks
     fn find_sortitions_to_process(
@@ -1069,6 +1343,17 @@ impl<
             &dbg_burn_header_hashes
         );
 
+        if let (Some(canonical_sortition_tip), Some(first_unprocessed)) = (
+            self.canonical_sortition_tip.as_ref(),
+            sortitions_to_process.front(),
+        ) {
+            if *canonical_sortition_tip != last_processed_ancestor {
+                // the new burnchain blocks do not build on our canonical sortition tip, so
+                // Bitcoin reorged
+                self.invalidate_cached_reward_sets(first_unprocessed.header.block_height)?;
+            }
+        }
+
         // Unlike in Stacks 2.x, there can be neither chain reorgs nor PoX reorgs unless Bitcoin itself
         // reorgs.  But if this happens, then we will have already found the set of
         // (newly-canonical) burnchain blocks which lack sortitions -- they'll be in
@@ -1158,6 +1443,10 @@ impl<
                 None
             };
 
+            // only keep a copy of the reward cycle info if there's someone to tell
+            let announced_reward_cycle_info =
+                self.dispatcher.and_then(|_| reward_cycle_info.clone());
+
             // process next sortition
             let dispatcher_ref = &self.dispatcher;
             let (next_snapshot, _) = self
@@ -1186,6 +1475,19 @@ impl<
                     Error::FailedToProcessSortition(e)
                 })?;
 
+            if let (Some(dispatcher), Some(rc_info)) =
+                (self.dispatcher, announced_reward_cycle_info.as_ref())
+            {
+                dispatcher.announce_reward_cycle(
+                    rc_info,
+                    &header.block_hash,
+                    header.block_height,
+                    &next_snapshot.consensus_hash,
+                    &self.burnchain.pox_constants,
+                );
+            }
+            self.observe_sortition_quality(&next_snapshot);
+
             // mark this burn block as processed in the nakamoto chainstate
             let tx = self.chain_state_db.staging_db_tx_begin()?;
             tx.set_burn_block_processed(&next_snapshot.consensus_hash)?;
@@ -1193,6 +1495,7 @@ impl<
 
             let sortition_id = next_snapshot.sortition_id;
 
+            header_cache::invalidate(&self.chain_state_db.root_path);
             self.notifier.notify_sortition_processed();
 
             debug!(
@@ -25,6 +25,7 @@ use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
 use clarity::vm::{ClarityVersion, Value};
 use rand::prelude::SliceRandom;
 use rand::{thread_rng, Rng, RngCore};
+use rusqlite::Connection;
 use stacks_common::address::{AddressHashMode, C32_ADDRESS_VERSION_TESTNET_SINGLESIG};
 use stacks_common::bitvec::BitVec;
 use stacks_common::consts::{
@@ -33,6 +34,7 @@ use stacks_common::consts::{
 use stacks_common::types::chainstate::{
     BurnchainHeaderHash, StacksAddress, StacksBlockId, StacksPrivateKey, StacksPublicKey,
 };
+use stacks_common::types::sqlite::NO_PARAMS;
 use stacks_common::types::{Address, StacksEpoch, StacksEpochId, StacksPublicKeyBuffer};
 use stacks_common::util::hash::Hash160;
 use stacks_common::util::secp256k1::Secp256k1PrivateKey;
@@ -46,6 +48,7 @@ use crate::chainstate::burn::operations::{
     VoteForAggregateKeyOp,
 };
 use crate::chainstate::coordinator::tests::{p2pkh_from, pox_addr_from};
+use crate::chainstate::coordinator::OnChainRewardSetProvider;
 use crate::chainstate::nakamoto::coordinator::load_nakamoto_reward_set;
 use crate::chainstate::nakamoto::fault_injection::*;
 use crate::chainstate::nakamoto::miner::NakamotoBlockBuilder;
@@ -54,7 +57,9 @@ use crate::chainstate::nakamoto::test_signers::TestSigners;
 use crate::chainstate::nakamoto::tests::get_account;
 use crate::chainstate::nakamoto::tests::node::TestStacker;
 use crate::chainstate::nakamoto::{
-    NakamotoBlock, NakamotoBlockObtainMethod, NakamotoChainState, NakamotoStagingBlocksConnRef,
+    header_cache, keys as nakamoto_keys, NakamotoBlock, NakamotoBlockObtainMethod,
+    NakamotoChainState, NakamotoStagingBlocksConnRef, TenureHeightEntry,
+    NAKAMOTO_CHAINSTATE_SCHEMA_6,
 };
 use crate::chainstate::stacks::address::PoxAddress;
 use crate::chainstate::stacks::boot::pox_4_tests::{get_stacking_minimum, get_tip};
@@ -63,6 +68,7 @@ use crate::chainstate::stacks::boot::test::{
     key_to_stacks_addr, make_pox_4_lockup, make_signer_key_signature, with_sortdb,
 };
 use crate::chainstate::stacks::boot::{MINERS_NAME, SIGNERS_NAME};
+use crate::chainstate::stacks::db::prune::ClarityPruner;
 use crate::chainstate::stacks::db::{MinerPaymentTxFees, StacksAccount, StacksChainState};
 use crate::chainstate::stacks::events::TransactionOrigin;
 use crate::chainstate::stacks::{
@@ -648,6 +654,25 @@ impl TestPeer<'_> {
             &SortitionDB,
             &[(NakamotoBlock, u64, ExecutionCost)],
         ) -> Vec<StacksTransaction>,
+    {
+        self.mine_tenure_and(block_builder, |_| true)
+    }
+
+    /// Produce a tenure like `mine_tenure()`, but call `after_block` right after each block is
+    /// assembled.  Blocks for which it returns false are not processed.
+    pub fn mine_tenure_and<F, G>(
+        &mut self,
+        block_builder: F,
+        after_block: G,
+    ) -> Vec<(NakamotoBlock, u64, ExecutionCost)>
+    where
+        F: FnMut(
+            &mut TestMiner,
+            &mut StacksChainState,
+            &SortitionDB,
+            &[(NakamotoBlock, u64, ExecutionCost)],
+        ) -> Vec<StacksTransaction>,
+        G: FnMut(&mut NakamotoBlock) -> bool,
     {
         let (burn_ops, mut tenure_change, miner_key) =
             self.begin_nakamoto_tenure(TenureChangeCause::BlockFound);
@@ -697,7 +722,7 @@ impl TestPeer<'_> {
             &mut test_signers,
             |_| {},
             block_builder,
-            |_| true,
+            after_block,
         )
         .unwrap()
     }
@@ -2406,6 +2431,441 @@ fn test_nakamoto_coordinator_10_tenures_10_sortitions() {
     simple_nakamoto_coordinator_10_tenures_10_sortitions();
 }
 
+/// Verify that the tenure-height index agrees with the MARF, and that the schema migration's
+/// backfill reproduces it
+#[test]
+fn test_nakamoto_tenure_height_index() {
+    let mut peer = simple_nakamoto_coordinator_10_tenures_10_sortitions();
+    let chainstate = &mut peer.stacks_node.as_mut().unwrap().chainstate;
+    let sort_db = peer.sortdb.as_mut().unwrap();
+
+    let tip = NakamotoChainState::get_canonical_block_header(chainstate.db(), sort_db)
+        .unwrap()
+        .unwrap();
+    let tip_block_id = tip.index_block_hash();
+    let tip_coinbase_height =
+        NakamotoChainState::get_coinbase_height(&mut chainstate.index_conn(), &tip_block_id)
+            .unwrap()
+            .unwrap();
+
+    let load_entries = |conn: &Connection| -> Vec<TenureHeightEntry> {
+        query_rows(
+            conn,
+            "SELECT * FROM nakamoto_tenure_heights ORDER BY coinbase_height",
+            NO_PARAMS,
+        )
+        .unwrap()
+    };
+    let entries = load_entries(chainstate.db());
+    assert_eq!(
+        entries
+            .iter()
+            .filter(|entry| entry.first_nakamoto_tenure)
+            .count(),
+        1
+    );
+    assert_eq!(entries.last().unwrap().coinbase_height, tip_coinbase_height);
+
+    for entry in entries.iter() {
+        let mut index_conn = chainstate.index_conn();
+        let marf_block_id = crate::chainstate::nakamoto::StacksDBIndexed::get(
+            &mut index_conn,
+            &tip_block_id,
+            &nakamoto_keys::ongoing_tenure_coinbase_height(entry.coinbase_height),
+        )
+        .unwrap()
+        .and_then(|id_str| nakamoto_keys::parse_block_id(&id_str));
+        assert_eq!(marf_block_id.as_ref(), Some(&entry.tenure_start_block_id));
+
+        assert_eq!(
+            NakamotoChainState::get_indexed_tenure_start_block_id_at_coinbase_height(
+                chainstate.db(),
+                &tip_block_id,
+                entry.coinbase_height
+            )
+            .unwrap()
+            .as_ref(),
+            Some(&entry.tenure_start_block_id)
+        );
+        assert_eq!(
+            NakamotoChainState::get_indexed_tenure_start_block_id(
+                chainstate.db(),
+                &tip_block_id,
+                &entry.tenure_id_consensus_hash
+            )
+            .unwrap()
+            .as_ref(),
+            Some(&entry.tenure_start_block_id)
+        );
+    }
+
+    // the index can't answer for tenures that the tip hasn't reached
+    assert!(
+        NakamotoChainState::get_indexed_tenure_start_block_id_at_coinbase_height(
+            chainstate.db(),
+            &tip_block_id,
+            tip_coinbase_height + 1
+        )
+        .unwrap()
+        .is_none()
+    );
+
+    // rebuild the index the way the schema migration does
+    let tx = chainstate.db_tx_begin().unwrap();
+    tx.execute("DELETE FROM nakamoto_tenure_heights", NO_PARAMS)
+        .unwrap();
+    tx.execute_batch(NAKAMOTO_CHAINSTATE_SCHEMA_6[2]).unwrap();
+    tx.commit().unwrap();
+    assert_eq!(load_entries(chainstate.db()), entries);
+}
+
+#[test]
+fn test_canonical_header_cache() {
+    let private_key = StacksPrivateKey::from_seed(&[2]);
+    let mut peer = NakamotoBootPlan::new(function_name!())
+        .with_private_key(private_key.clone())
+        .boot_into_nakamoto_peer(vec![], None);
+
+    let load_tips = |peer: &mut TestPeer| {
+        let chainstate = &peer.stacks_node.as_ref().unwrap().chainstate;
+        let sort_db = peer.sortdb.as_ref().unwrap();
+        let uncached = NakamotoChainState::get_canonical_block_header(chainstate.db(), sort_db)
+            .unwrap()
+            .unwrap();
+        let cached = header_cache::get_canonical_block_header(chainstate, sort_db)
+            .unwrap()
+            .unwrap();
+        (uncached, cached, chainstate.root_path.clone())
+    };
+
+    let (tip, cached_tip, root_path) = load_tips(&mut peer);
+    assert_eq!(tip, cached_tip);
+    assert!(header_cache::is_cached(&root_path));
+
+    // served from the cache
+    let (_, cached_tip, _) = load_tips(&mut peer);
+    assert_eq!(tip, cached_tip);
+
+    // processing a tenure invalidates the cached header, and the next read picks up the new tip
+    let (block, ..) = peer.single_block_tenure(&private_key, |_| {}, |_| {}, |_| true);
+    assert!(!header_cache::is_cached(&root_path));
+
+    let (new_tip, new_cached_tip, _) = load_tips(&mut peer);
+    assert_eq!(new_tip.index_block_hash(), block.block_id());
+    assert_eq!(new_tip, new_cached_tip);
+    assert!(header_cache::is_cached(&root_path));
+}
+
+/// Verify that the reward sets loaded by `load_nakamoto_reward_set()` are cached in the
+/// chainstate, served from the cache, and read again once the cache is invalidated
+#[test]
+fn test_nakamoto_reward_set_cache() {
+    let mut peer = simple_nakamoto_coordinator_10_tenures_10_sortitions();
+    let burnchain = peer.config.burnchain.clone();
+    let sort_db = peer.sortdb.as_ref().unwrap();
+    let chainstate = &mut peer.stacks_node.as_mut().unwrap().chainstate;
+
+    let sort_tip = SortitionDB::get_canonical_burn_chain_tip(sort_db.conn()).unwrap();
+    let stacks_tip = NakamotoChainState::get_canonical_block_header(chainstate.db(), sort_db)
+        .unwrap()
+        .unwrap()
+        .index_block_hash();
+    let reward_cycle = burnchain
+        .block_height_to_reward_cycle(sort_tip.block_height)
+        .unwrap();
+
+    let load_reward_set = |chainstate: &mut StacksChainState| {
+        let (rc_info, anchor_block_header) = load_nakamoto_reward_set(
+            reward_cycle,
+            &sort_tip.sortition_id,
+            &burnchain,
+            chainstate,
+            &stacks_tip,
+            sort_db,
+            &OnChainRewardSetProvider::new(),
+        )
+        .unwrap()
+        .unwrap();
+        (
+            rc_info.known_selected_anchor_block_owned().unwrap(),
+            anchor_block_header.index_block_hash(),
+        )
+    };
+    let count_cached = |chainstate: &StacksChainState| -> i64 {
+        chainstate
+            .db()
+            .query_row(
+                "SELECT COUNT(*) FROM nakamoto_reward_set_cache",
+                NO_PARAMS,
+                |row| row.get(0),
+            )
+            .unwrap()
+    };
+
+    // loading the reward set caches it
+    let (reward_set, anchor_block_id) = load_reward_set(chainstate);
+    assert_eq!(
+        NakamotoChainState::get_cached_reward_set(chainstate.db(), reward_cycle, &anchor_block_id)
+            .unwrap()
+            .as_ref(),
+        Some(&reward_set)
+    );
+    let num_cached = count_cached(chainstate);
+    assert!(num_cached > 0);
+
+    // served from the cache
+    let mut doctored = reward_set.clone();
+    doctored.rewarded_addresses.clear();
+    NakamotoChainState::cache_reward_set(
+        chainstate.db(),
+        reward_cycle,
+        &anchor_block_id,
+        &doctored,
+    )
+    .unwrap();
+    assert_eq!(load_reward_set(chainstate).0, doctored);
+    assert_eq!(count_cached(chainstate), num_cached);
+
+    // once invalidated, the reward set is read from `.signers` again, and re-cached
+    let dropped =
+        NakamotoChainState::invalidate_cached_reward_sets(chainstate.db(), reward_cycle).unwrap();
+    assert!(dropped >= 1);
+    assert!(NakamotoChainState::get_cached_reward_set(
+        chainstate.db(),
+        reward_cycle,
+        &anchor_block_id
+    )
+    .unwrap()
+    .is_none());
+    assert_eq!(
+        load_reward_set(chainstate),
+        (reward_set.clone(), anchor_block_id)
+    );
+    assert_eq!(
+        NakamotoChainState::get_cached_reward_set(chainstate.db(), reward_cycle, &anchor_block_id)
+            .unwrap(),
+        Some(reward_set)
+    );
+}
+
+#[test]
+fn test_prune_clarity_state() {
+    let private_key = StacksPrivateKey::from_seed(&[2]);
+    let addr = StacksAddress::from_public_keys(
+        C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+        &AddressHashMode::SerializeP2PKH,
+        1,
+        &vec![StacksPublicKey::from_private(&private_key)],
+    )
+    .unwrap();
+    let mut peer = NakamotoBootPlan::new(function_name!())
+        .with_private_key(private_key.clone())
+        .boot_into_nakamoto_peer(vec![], None);
+    for _ in 0..3 {
+        peer.single_block_tenure(&private_key, |_| {}, |_| {}, |_| true);
+    }
+
+    let count_values = |chainstate: &mut StacksChainState| -> u64 {
+        chainstate.clarity_state.with_marf(|marf| {
+            marf.sqlite_conn()
+                .query_row("SELECT COUNT(*) FROM data_table", NO_PARAMS, |row| {
+                    row.get(0)
+                })
+                .unwrap()
+        })
+    };
+
+    let prune_horizon = 10;
+    let (expected_horizon, account_before) = {
+        let chainstate = &mut peer.stacks_node.as_mut().unwrap().chainstate;
+        let sortdb = peer.sortdb.as_ref().unwrap();
+        let tip = NakamotoChainState::get_canonical_block_header(chainstate.db(), sortdb)
+            .unwrap()
+            .unwrap();
+        assert!(tip.stacks_block_height > 2 * prune_horizon);
+        assert_eq!(
+            StacksChainState::get_clarity_prune_horizon(chainstate.db()).unwrap(),
+            None
+        );
+
+        let account_before = get_account(chainstate, sortdb, &addr);
+        let values_before = count_values(chainstate);
+
+        let mut pruner = ClarityPruner::new(prune_horizon);
+        assert!(pruner.maybe_start(chainstate).unwrap());
+        let stats = pruner.join().unwrap().unwrap().unwrap();
+        let expected_horizon =
+            ((tip.stacks_block_height - prune_horizon) / prune_horizon) * prune_horizon;
+        assert_eq!(stats.horizon_height, expected_horizon);
+        assert!(stats.pruned_values > 0);
+        assert!(stats.retained_tips > 0);
+        assert_eq!(
+            count_values(chainstate),
+            values_before - stats.pruned_values
+        );
+        assert_eq!(
+            StacksChainState::get_clarity_prune_horizon(chainstate.db()).unwrap(),
+            Some(expected_horizon)
+        );
+
+        // no new checkpoint until the chain advances
+        assert!(!pruner.maybe_start(chainstate).unwrap());
+        assert!(pruner.join().is_none());
+
+        // tip state is still readable
+        assert_eq!(get_account(chainstate, sortdb, &addr), account_before);
+        (expected_horizon, account_before)
+    };
+
+    // the node can still process blocks on top of the pruned state
+    let (block, ..) = peer.single_block_tenure(&private_key, |_| {}, |_| {}, |_| true);
+    let chainstate = &mut peer.stacks_node.as_mut().unwrap().chainstate;
+    let sortdb = peer.sortdb.as_ref().unwrap();
+    let tip = NakamotoChainState::get_canonical_block_header(chainstate.db(), sortdb)
+        .unwrap()
+        .unwrap();
+    assert_eq!(tip.index_block_hash(), block.block_id());
+    assert!(get_account(chainstate, sortdb, &addr).nonce >= account_before.nonce);
+    assert_eq!(
+        StacksChainState::get_clarity_prune_horizon(chainstate.db()).unwrap(),
+        Some(expected_horizon)
+    );
+}
+
+/// A node that has pruned the Clarity state that an `at-block` expression reads must refuse to
+/// process the block containing it, instead of treating the block as invalid.
+#[test]
+fn test_prune_clarity_state_at_block() {
+    let private_key = StacksPrivateKey::from_seed(&[2]);
+    let addr = StacksAddress::from_public_keys(
+        C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+        &AddressHashMode::SerializeP2PKH,
+        1,
+        &vec![StacksPublicKey::from_private(&private_key)],
+    )
+    .unwrap();
+    let mut peer = NakamotoBootPlan::new(function_name!())
+        .with_private_key(private_key.clone())
+        .boot_into_nakamoto_peer(vec![], None);
+
+    let contract = r#"
+        (define-data-var marker (string-ascii 40) "marker before the at-block prune test")
+        (define-public (set-marker)
+            (ok (var-set marker "marker after the at-block prune test")))
+        (define-public (get-marker-at (height uint))
+            (ok (at-block (unwrap-panic (get-stacks-block-info? id-header-hash height))
+                (var-get marker))))
+    "#;
+    let chain_id = peer.chainstate().chain_id;
+    let make_call = |function_name: &str, function_args: Vec<Value>, nonce: u64| {
+        let mut tx = StacksTransaction::new(
+            TransactionVersion::Testnet,
+            TransactionAuth::from_p2pkh(&private_key).unwrap(),
+            TransactionPayload::new_contract_call(
+                addr.clone(),
+                "marker",
+                function_name,
+                function_args,
+            )
+            .unwrap(),
+        );
+        tx.chain_id = chain_id;
+        tx.anchor_mode = TransactionAnchorMode::OnChainOnly;
+        tx.set_tx_fee(1000);
+        tx.auth.set_origin_nonce(nonce);
+
+        let mut tx_signer = StacksTransactionSigner::new(&tx);
+        tx_signer.sign_origin(&private_key).unwrap();
+        tx_signer.get_tx().unwrap()
+    };
+    let stacks_tip = |peer: &mut TestPeer| {
+        let chainstate = &mut peer.stacks_node.as_mut().unwrap().chainstate;
+        let sortdb = peer.sortdb.as_ref().unwrap();
+        NakamotoChainState::get_canonical_block_header(chainstate.db(), sortdb)
+            .unwrap()
+            .unwrap()
+            .index_block_hash()
+    };
+    let next_nonce = |peer: &mut TestPeer| {
+        let tip = stacks_tip(peer);
+        peer.get_account(&tip, &addr.to_account_principal()).nonce
+    };
+
+    // deploy the contract, and then change the marker in a later block
+    let nonce = next_nonce(&mut peer);
+    let deploy_blocks = peer.mine_tenure(|_, chainstate, _, blocks_so_far| {
+        if blocks_so_far.is_empty() {
+            vec![make_contract(
+                chainstate,
+                "marker",
+                contract,
+                &private_key,
+                ClarityVersion::Clarity3,
+                nonce,
+                1000,
+            )]
+        } else {
+            vec![]
+        }
+    });
+    let deploy_height = deploy_blocks[0].0.header.chain_length;
+
+    let set_marker = make_call("set-marker", vec![], next_nonce(&mut peer));
+    let set_blocks = peer.mine_tenure(|_, _, _, blocks_so_far| {
+        if blocks_so_far.is_empty() {
+            vec![set_marker.clone()]
+        } else {
+            vec![]
+        }
+    });
+    let set_height = set_blocks[0].0.header.chain_length;
+
+    // mine a block which reads the marker at the deploy block, but don't process it yet
+    let get_marker = make_call(
+        "get-marker-at",
+        vec![Value::UInt(deploy_height.into())],
+        next_nonce(&mut peer),
+    );
+    let get_marker_blocks = peer.mine_tenure_and(
+        |_, _, _, blocks_so_far| {
+            if blocks_so_far.is_empty() {
+                vec![get_marker.clone()]
+            } else {
+                vec![]
+            }
+        },
+        |_| false,
+    );
+    let block = get_marker_blocks[0].0.clone();
+    assert!(block.txs.iter().any(|tx| tx.txid() == get_marker.txid()));
+
+    // prune everything below the block that changed the marker
+    let tip_before = stacks_tip(&mut peer);
+    let stats = peer
+        .chainstate()
+        .prune_clarity_state(set_height)
+        .unwrap()
+        .unwrap();
+    assert!(stats.pruned_values > 0);
+
+    // the block's validity can no longer be determined, so it is neither processed nor orphaned
+    let err = peer.try_process_block(&block).unwrap_err();
+    assert!(err.is_missing_clarity_state(), "{err:?}");
+
+    let (_, processed, orphaned, _) = peer
+        .chainstate()
+        .nakamoto_blocks_db()
+        .get_block_processed_and_signed_weight(
+            &block.header.consensus_hash,
+            &block.header.block_hash(),
+        )
+        .unwrap()
+        .unwrap();
+    assert!(!processed);
+    assert!(!orphaned);
+    assert_eq!(stacks_tip(&mut peer), tip_before);
+}
+
 /// Mine two tenures across three sortitions, using a tenure-extend to allow the first tenure to
 /// cover the time of two sortitions.
 ///
@@ -0,0 +1,94 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Process-wide read-through cache of the canonical Stacks block header.
+//!
+//! RPC handlers load the canonical tip header on almost every request.  The cache holds one
+//! entry per chainstate (keyed by its root path, since tests run many nodes in one process).
+//! An entry is only served if the canonical tip recorded in the sortition DB still matches the
+//! cached header's block ID, so a missed invalidation can cost a cache miss but never return a
+//! stale tip.  The coordinator drops the entry whenever it processes a Stacks block or a
+//! sortition, so headers for non-canonical tips are not retained.
+//!
+//! DO NOT USE IN CONSENSUS CODE.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use stacks_common::types::chainstate::StacksBlockId;
+
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::stacks::db::{StacksChainState, StacksHeaderInfo};
+use crate::chainstate::stacks::Error as ChainstateError;
+
+lazy_static! {
+    static ref CANONICAL_HEADER_CACHE: Mutex<HashMap<String, StacksHeaderInfo>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Load the canonical Stacks block header for `chainstate`, consulting the process-wide cache
+/// first.  Behaves identically to `NakamotoChainState::get_canonical_block_header()`.
+pub fn get_canonical_block_header(
+    chainstate: &StacksChainState,
+    sortdb: &SortitionDB,
+) -> Result<Option<StacksHeaderInfo>, ChainstateError> {
+    let (consensus_hash, block_hash) =
+        SortitionDB::get_canonical_stacks_chain_tip_hash(sortdb.conn())?;
+    let tip_block_id = StacksBlockId::new(&consensus_hash, &block_hash);
+
+    if let Some(header) = get_cached(&chainstate.root_path, &tip_block_id) {
+        return Ok(Some(header));
+    }
+
+    let header_opt = NakamotoChainState::get_block_header(chainstate.db(), &tip_block_id)?;
+    if let Some(header) = header_opt.as_ref() {
+        CANONICAL_HEADER_CACHE
+            .lock()
+            .expect("FATAL: canonical header cache lock poisoned")
+            .insert(chainstate.root_path.clone(), header.clone());
+    }
+    Ok(header_opt)
+}
+
+/// Get the cached header for the chainstate at `root_path`, if it is the header of
+/// `tip_block_id`
+fn get_cached(root_path: &str, tip_block_id: &StacksBlockId) -> Option<StacksHeaderInfo> {
+    CANONICAL_HEADER_CACHE
+        .lock()
+        .expect("FATAL: canonical header cache lock poisoned")
+        .get(root_path)
+        .filter(|header| &header.index_block_hash() == tip_block_id)
+        .cloned()
+}
+
+/// Drop the cached canonical header for the chainstate at `root_path`.  Called by the
+/// coordinator whenever the canonical Stacks tip may have changed.
+pub fn invalidate(root_path: &str) {
+    CANONICAL_HEADER_CACHE
+        .lock()
+        .expect("FATAL: canonical header cache lock poisoned")
+        .remove(root_path);
+}
+
+/// Is there a cached canonical header for the chainstate at `root_path`?
+#[cfg(test)]
+pub fn is_cached(root_path: &str) -> bool {
+    CANONICAL_HEADER_CACHE
+        .lock()
+        .expect("FATAL: canonical header cache lock poisoned")
+        .contains_key(root_path)
+}
@@ -130,6 +130,9 @@ pub struct NakamotoBlockBuilder {
     pub header: NakamotoBlockHeader,
     /// Optional soft limit for this block's budget usage
     soft_limit: Option<ExecutionCost>,
+    /// Whether the transactions given to `try_mine_tx_with_len()` have already had their
+    /// signatures verified and passed `Relayer::static_check_problematic_relayed_tx()`
+    txs_prechecked: bool,
 }
 
 pub struct MinerTenureInfo<'a> {
@@ -150,6 +153,61 @@ pub struct MinerTenureInfo<'a> {
     pub tenure_block_commit_opt: Option<LeaderBlockCommitOp>,
 }
 
+/// How one of an attempt's candidate blocks walks the mempool, when the miner assembles several
+/// candidates and keeps the one that pays the most fees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CandidateStrategy {
+    /// Walk the mempool with the configured settings
+    Configured,
+    /// Consider transactions with fee-rate estimates before any without
+    FeeRateFirst,
+    /// Consider transactions without fee-rate estimates before any with
+    NoEstimateFirst,
+    /// Draw evenly from transactions with and without fee-rate estimates
+    Mixed,
+}
+
+impl CandidateStrategy {
+    const ALL: [CandidateStrategy; 4] = [
+        CandidateStrategy::Configured,
+        CandidateStrategy::FeeRateFirst,
+        CandidateStrategy::NoEstimateFirst,
+        CandidateStrategy::Mixed,
+    ];
+
+    /// Strategy of the `index`-th candidate.  The first candidate always uses the configured
+    /// settings; later candidates cycle through the alternatives.
+    pub fn for_candidate(index: u32) -> CandidateStrategy {
+        let index = usize::try_from(index).unwrap_or(0) % Self::ALL.len();
+        Self::ALL[index]
+    }
+
+    /// Adjust the mempool walk settings for this strategy
+    pub fn apply(&self, mempool_settings: &mut MemPoolWalkSettings) {
+        match self {
+            CandidateStrategy::Configured => {}
+            CandidateStrategy::FeeRateFirst => mempool_settings.consider_no_estimate_tx_prob = 0,
+            CandidateStrategy::NoEstimateFirst => {
+                mempool_settings.consider_no_estimate_tx_prob = 100
+            }
+            CandidateStrategy::Mixed => mempool_settings.consider_no_estimate_tx_prob = 50,
+        }
+    }
+}
+
+/// A candidate block assembled by `NakamotoBlockBuilder::build_nakamoto_block`
+struct CandidateBlock {
+    block: NakamotoBlock,
+    strategy: CandidateStrategy,
+    tenure_consumed: ExecutionCost,
+    tenure_budget: ExecutionCost,
+    tenure_cost_at_start: ExecutionCost,
+    tenure_size: u64,
+    tx_events: Vec<TransactionEvent>,
+    /// Sum of the fees of the block's transactions
+    fee_total: u128,
+}
+
 /// Structure returned from `NakamotoBlockBuilder::build_nakamoto_block` with
 /// information about the block that was built.
 pub struct BlockMetadata {
@@ -161,6 +219,10 @@ pub struct BlockMetadata {
     pub tenure_budget: ExecutionCost,
     /// The size of the blocks in the current tenure in bytes
     pub tenure_size: u64,
+    /// The execution cost consumed by this block alone
+    pub block_consumed: ExecutionCost,
+    /// How long it took to assemble this block, in milliseconds
+    pub assembly_time_ms: u64,
     /// The events emitted by the transactions included in this block
     pub tx_events: Vec<TransactionEvent>,
 }
@@ -181,6 +243,7 @@ impl NakamotoBlockBuilder {
             txs: vec![],
             header: NakamotoBlockHeader::genesis(),
             soft_limit: None,
+            txs_prechecked: false,
         }
     }
 
@@ -250,9 +313,17 @@ impl NakamotoBlockBuilder {
                     .unwrap_or(0),
             ),
             soft_limit,
+            txs_prechecked: false,
         })
     }
 
+    /// Declare that every transaction later given to `try_mine_tx_with_len()` has already had
+    /// its signatures verified and passed `Relayer::static_check_problematic_relayed_tx()`, so
+    /// that neither check is repeated while the transactions are applied.
+    pub fn set_txs_prechecked(&mut self, txs_prechecked: bool) {
+        self.txs_prechecked = txs_prechecked;
+    }
+
     /// This function should be called before `tenure_begin`.
     /// It creates a MinerTenureInfo struct which owns connections to the chainstate and sortition
     /// DBs, so that block-processing is guaranteed to terminate before the lives of these handles
@@ -346,7 +417,7 @@ impl NakamotoBlockBuilder {
                 "Cannot process Nakamoto block: could not load reward set that elected the block";
                 "err" => ?e,
             );
-            Error::NoSuchBlockError
+            Error::NoRegisteredSigners(elected_in_cycle)
         })?;
 
         // must build off of the header's consensus hash as the burnchain view, not the canonical_tip_bhh:
@@ -555,25 +626,169 @@ impl NakamotoBlockBuilder {
 
         let (mut chainstate, _) = chainstate_handle.reopen()?;
 
-        let mut builder = NakamotoBlockBuilder::new(
-            parent_stacks_header,
-            tenure_id_consensus_hash,
-            total_burn,
-            tenure_info.tenure_change_tx(),
-            tenure_info.coinbase_tx(),
-            signer_bitvec_len,
-            None,
-        )?;
+        let make_builder = || {
+            NakamotoBlockBuilder::new(
+                parent_stacks_header,
+                tenure_id_consensus_hash,
+                total_burn,
+                tenure_info.tenure_change_tx(),
+                tenure_info.coinbase_tx(),
+                signer_bitvec_len,
+                None,
+            )
+        };
 
         let ts_start = get_epoch_time_ms();
 
+        // the tenure info is loaded once, and shared by every candidate block
         let mut miner_tenure_info =
-            builder.load_tenure_info(&mut chainstate, burn_dbconn, tenure_info.cause())?;
-        let mut tenure_tx = builder.tenure_begin(burn_dbconn, &mut miner_tenure_info)?;
+            make_builder()?.load_tenure_info(&mut chainstate, burn_dbconn, tenure_info.cause())?;
+
+        let initial_txs: Vec<_> = [
+            tenure_info.tenure_change_tx.clone(),
+            tenure_info.coinbase_tx.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        // A tenure's first block only holds its tenure change and coinbase, so there is nothing
+        // to choose between.
+        let candidate_count = if tenure_info.cause() == Some(TenureChangeCause::BlockFound) {
+            1
+        } else {
+            settings.candidate_count.max(1)
+        };
+
+        // candidates share the attempt's time budget
+        let mut candidate_settings = settings;
+        if candidate_count > 1 {
+            let divisor = u64::from(candidate_count);
+            candidate_settings.max_miner_time_ms /= divisor;
+            candidate_settings.mempool_settings.max_walk_time_ms /= divisor;
+        }
+
+        let mut best_candidate: Option<CandidateBlock> = None;
+        for index in 0..candidate_count {
+            let strategy = CandidateStrategy::for_candidate(index);
+            let mut settings = candidate_settings.clone();
+            strategy.apply(&mut settings.mempool_settings);
+
+            // Only a lone candidate is stored as the mined block.  Otherwise, every candidate is
+            // rolled back so that the next one can be assembled atop the same parent state.
+            let candidate = match Self::build_candidate(
+                make_builder()?,
+                burn_dbconn,
+                &mut miner_tenure_info,
+                mempool,
+                parent_stacks_header,
+                &initial_txs,
+                settings,
+                event_observer,
+                strategy,
+                candidate_count == 1,
+            ) {
+                Ok(candidate) => candidate,
+                Err(Error::NoTransactionsToMine) => continue,
+                Err(e) => return Err(e),
+            };
+
+            debug!(
+                "Miner: assembled candidate Nakamoto block";
+                "candidate" => index,
+                "strategy" => ?strategy,
+                "tx_count" => candidate.block.txs.len(),
+                "fee_total" => candidate.fee_total,
+                "block_size" => candidate.tenure_size,
+            );
+
+            // ties go to the earlier candidate
+            let is_best = match &best_candidate {
+                Some(best) => candidate.fee_total > best.fee_total,
+                None => true,
+            };
+            if is_best {
+                best_candidate = Some(candidate);
+            }
+        }
+
+        let Some(CandidateBlock {
+            block,
+            strategy,
+            tenure_consumed,
+            tenure_budget,
+            tenure_cost_at_start,
+            tenure_size,
+            tx_events,
+            fee_total,
+        }) = best_candidate
+        else {
+            return Err(Error::NoTransactionsToMine);
+        };
+
+        let ts_end = get_epoch_time_ms();
+        let assembly_time_ms: u64 = ts_end
+            .saturating_sub(ts_start)
+            .try_into()
+            .unwrap_or(u64::MAX);
+
+        let mut block_consumed = tenure_consumed.clone();
+        if block_consumed.sub(&tenure_cost_at_start).is_err() {
+            block_consumed = tenure_consumed.clone();
+        }
+
+        set_last_mined_block_transaction_count(block.txs.len() as u64);
+        set_last_mined_execution_cost_observed(&tenure_consumed, &tenure_budget);
+
+        info!(
+            "Miner: mined Nakamoto block";
+            "stacks_block_hash" => %block.header.block_hash(),
+            "stacks_block_id" => %block.header.block_id(),
+            "height" => block.header.chain_length,
+            "tx_count" => block.txs.len(),
+            "parent_block_id" => %block.header.parent_block_id,
+            "block_size" => tenure_size,
+            "execution_consumed" => %tenure_consumed,
+            "percent_full" => tenure_budget.proportion_largest_dimension(&tenure_consumed),
+            "assembly_time_ms" => assembly_time_ms,
+            "candidate_count" => candidate_count,
+            "candidate_strategy" => ?strategy,
+            "fee_total" => fee_total,
+            "consensus_hash" => %block.header.consensus_hash
+        );
+
+        Ok(BlockMetadata {
+            block,
+            tenure_consumed,
+            tenure_budget,
+            tenure_size,
+            block_consumed,
+            assembly_time_ms,
+            tx_events,
+        })
+    }
+
+    /// Assemble one candidate block atop the given tenure info.
+    /// If `store` is true, the candidate's state is stored as the mined block.  Otherwise, it is
+    /// rolled back, so that another candidate can be assembled from the same tenure info.
+    fn build_candidate<'a>(
+        mut builder: NakamotoBlockBuilder,
+        burn_dbconn: &'a SortitionHandleConn,
+        miner_tenure_info: &mut MinerTenureInfo<'a>,
+        mempool: &mut MemPoolDB,
+        parent_stacks_header: &StacksHeaderInfo,
+        initial_txs: &[StacksTransaction],
+        settings: BlockBuilderSettings,
+        event_observer: Option<&dyn MemPoolEventDispatcher>,
+        strategy: CandidateStrategy,
+        store: bool,
+    ) -> Result<CandidateBlock, Error> {
+        let mut tenure_tx = builder.tenure_begin(burn_dbconn, miner_tenure_info)?;
 
         let tenure_budget = tenure_tx
             .block_limit()
             .expect("Failed to obtain block limit from miner's block connection");
+        let tenure_cost_at_start = tenure_tx.cost_so_far();
 
         let mut soft_limit = None;
         if let Some(percentage) = settings
@@ -604,21 +819,13 @@ impl NakamotoBlockBuilder {
 
         builder.soft_limit = soft_limit;
 
-        let initial_txs: Vec<_> = [
-            tenure_info.tenure_change_tx.clone(),
-            tenure_info.coinbase_tx,
-        ]
-        .into_iter()
-        .flatten()
-        .collect();
-
         // TODO: update this mempool check to prioritize signer vote transactions over other transactions
         let (blocked, tx_events) = match StacksBlockBuilder::select_and_apply_transactions(
             &mut tenure_tx,
             &mut builder,
             mempool,
             parent_stacks_header.stacks_block_height,
-            &initial_txs,
+            initial_txs,
             settings,
             event_observer,
             ASTRules::PrecheckSize,
@@ -640,39 +847,31 @@ impl NakamotoBlockBuilder {
         }
 
         if builder.txs.is_empty() {
+            tenure_tx.rollback_block();
             return Err(Error::NoTransactionsToMine);
         }
 
         // save the block so we can build microblocks off of it
         let block = builder.mine_nakamoto_block(&mut tenure_tx);
         let tenure_size = builder.bytes_so_far;
-        let tenure_consumed = builder.tenure_finish(tenure_tx)?;
-
-        let ts_end = get_epoch_time_ms();
-
-        set_last_mined_block_transaction_count(block.txs.len() as u64);
-        set_last_mined_execution_cost_observed(&tenure_consumed, &tenure_budget);
-
-        info!(
-            "Miner: mined Nakamoto block";
-            "stacks_block_hash" => %block.header.block_hash(),
-            "stacks_block_id" => %block.header.block_id(),
-            "height" => block.header.chain_length,
-            "tx_count" => block.txs.len(),
-            "parent_block_id" => %block.header.parent_block_id,
-            "block_size" => tenure_size,
-            "execution_consumed" => %tenure_consumed,
-            "percent_full" => tenure_budget.proportion_largest_dimension(&tenure_consumed),
-            "assembly_time_ms" => ts_end.saturating_sub(ts_start),
-            "consensus_hash" => %block.header.consensus_hash
-        );
+        let tenure_consumed = if store {
+            builder.tenure_finish(tenure_tx)?
+        } else {
+            let tenure_consumed = tenure_tx.cost_so_far();
+            tenure_tx.rollback_block();
+            tenure_consumed
+        };
+        let fee_total = block.txs.iter().map(|tx| u128::from(tx.get_tx_fee())).sum();
 
-        Ok(BlockMetadata {
+        Ok(CandidateBlock {
             block,
+            strategy,
             tenure_consumed,
             tenure_budget,
+            tenure_cost_at_start,
             tenure_size,
             tx_events,
+            fee_total,
         })
     }
 
@@ -723,27 +922,33 @@ impl BlockBuilder for NakamotoBlockBuilder {
         let quiet = !cfg!(test);
         let result = {
             // preemptively skip problematic transactions
-            if let Err(e) = Relayer::static_check_problematic_relayed_tx(
-                clarity_tx.config.mainnet,
-                clarity_tx.get_epoch(),
-                tx,
-                ast_rules,
-            ) {
-                info!(
-                    "Detected problematic tx {} while mining; dropping from mempool",
-                    tx.txid()
-                );
-                return TransactionResult::problematic(tx, Error::NetError(e));
+            if !self.txs_prechecked {
+                if let Err(e) = Relayer::static_check_problematic_relayed_tx(
+                    clarity_tx.config.mainnet,
+                    clarity_tx.get_epoch(),
+                    tx,
+                    ast_rules,
+                ) {
+                    info!(
+                        "Detected problematic tx {} while mining; dropping from mempool",
+                        tx.txid()
+                    );
+                    return TransactionResult::problematic(tx, Error::NetError(e));
+                }
             }
 
             let cost_before = clarity_tx.cost_so_far();
-            let (fee, receipt) =
-                match StacksChainState::process_transaction(clarity_tx, tx, quiet, ast_rules) {
-                    Ok(x) => x,
-                    Err(e) => {
-                        return parse_process_transaction_error(clarity_tx, tx, e);
-                    }
-                };
+            let process_result = if self.txs_prechecked {
+                StacksChainState::process_verified_transaction(clarity_tx, tx, quiet, ast_rules)
+            } else {
+                StacksChainState::process_transaction(clarity_tx, tx, quiet, ast_rules)
+            };
+            let (fee, receipt) = match process_result {
+                Ok(x) => x,
+                Err(e) => {
+                    return parse_process_transaction_error(clarity_tx, tx, e);
+                }
+            };
             let cost_after = clarity_tx.cost_so_far();
             let mut soft_limit_reached = false;
             // We only attempt to apply the soft limit to non-boot code contract calls.
@@ -54,12 +54,15 @@ use stacks_common::util::secp256k1::MessageSignature;
 use stacks_common::util::vrf::{VRFProof, VRFPublicKey, VRF};
 use stacks_common::util::{get_epoch_time_secs, sleep_ms};
 
+use self::sigcheck::{NakamotoBlockSigChecks, RewardSetSignerKeys};
 use self::signer_set::SignerCalculation;
 use super::burn::db::sortdb::{
     get_ancestor_sort_id, get_ancestor_sort_id_tx, get_block_commit_by_txid, SortitionHandle,
     SortitionHandleConn, SortitionHandleTx,
 };
-use super::burn::operations::{DelegateStxOp, StackStxOp, TransferStxOp, VoteForAggregateKeyOp};
+use super::burn::operations::{
+    DelegateStxOp, StackAggregationCommitOp, StackStxOp, TransferStxOp, VoteForAggregateKeyOp,
+};
 use super::stacks::boot::{
     NakamotoSignerEntry, PoxVersions, RawRewardSetEntry, RewardSet, RewardSetData,
     BOOT_TEST_POX_4_AGG_KEY_CONTRACT, BOOT_TEST_POX_4_AGG_KEY_FNAME, SIGNERS_MAX_LIST_SIZE,
@@ -116,10 +119,13 @@ use crate::util_lib::db::{
 };
 use crate::{chainstate, monitoring};
 
+pub mod checkpoints;
 pub mod coordinator;
+pub mod header_cache;
 pub mod keys;
 pub mod miner;
 pub mod shadow;
+pub mod sigcheck;
 pub mod signer_set;
 pub mod staging_blocks;
 pub mod tenure;
@@ -298,6 +304,68 @@ lazy_static! {
     // Add an index for index block hash in nakamoto block headers
     "CREATE INDEX IF NOT EXISTS index_block_hash ON nakamoto_block_headers(index_block_hash);",
     ];
+
+    pub static ref NAKAMOTO_CHAINSTATE_SCHEMA_6: [&'static str; 3] = [
+    r#"
+        UPDATE db_config SET version = "9";
+    "#,
+    // Add a secondary index from tenure heights (coinbase heights) to tenure-start blocks, so that
+    // tenure height lookups can often avoid the MARF.  It is filled in by `advance_tip()`, and
+    // backfilled from `nakamoto_tenure_events` when this migration runs.
+    r#"
+        CREATE TABLE nakamoto_tenure_heights (
+            -- coinbase height (i.e. tenure height) of the tenure
+            coinbase_height INTEGER NOT NULL,
+            -- consensus hash of the sortition that elected the tenure
+            tenure_id_consensus_hash TEXT NOT NULL,
+            -- index block hash of the tenure's first block
+            tenure_start_block_id TEXT NOT NULL,
+            -- 1 if the tenure's parent is an epoch2 block, 0 if not
+            first_nakamoto_tenure INTEGER NOT NULL,
+
+            PRIMARY KEY(tenure_start_block_id)
+        );
+        CREATE INDEX nakamoto_tenure_heights_by_coinbase_height ON nakamoto_tenure_heights(coinbase_height);
+        CREATE INDEX nakamoto_tenure_heights_by_consensus_hash ON nakamoto_tenure_heights(tenure_id_consensus_hash);
+    "#,
+    // Backfill the index from the BlockFound tenure-changes of processed tenure-start blocks
+    r#"
+        INSERT OR REPLACE INTO nakamoto_tenure_heights
+            (coinbase_height, tenure_id_consensus_hash, tenure_start_block_id, first_nakamoto_tenure)
+        SELECT
+            events.coinbase_height,
+            events.tenure_id_consensus_hash,
+            events.block_id,
+            NOT EXISTS (
+                SELECT 1 FROM nakamoto_block_headers parents
+                WHERE parents.index_block_hash = headers.parent_block_id
+            )
+        FROM nakamoto_tenure_events events
+        JOIN nakamoto_block_headers headers ON headers.index_block_hash = events.block_id
+        WHERE events.cause = 0 AND headers.tenure_changed = 1;
+    "#,
+    ];
+
+    pub static ref NAKAMOTO_CHAINSTATE_SCHEMA_7: [&'static str; 2] = [
+    r#"
+        UPDATE db_config SET version = "12";
+    "#,
+    // Cache the reward sets that `load_nakamoto_reward_set()` reads from `.signers`, so they do not
+    // have to be re-read from Clarity state on every call (or after a restart).  Rows are deleted
+    // when a burnchain reorg could have changed a reward cycle's anchor block.
+    r#"
+        CREATE TABLE nakamoto_reward_set_cache (
+            -- reward cycle the reward set is used in
+            reward_cycle INTEGER NOT NULL,
+            -- index block hash of the reward cycle's PoX anchor block
+            anchor_block_id TEXT NOT NULL,
+            -- the reward set, as stored in nakamoto_reward_sets
+            reward_set TEXT NOT NULL,
+
+            PRIMARY KEY(reward_cycle, anchor_block_id)
+        );
+    "#,
+    ];
 }
 
 #[cfg(test)]
@@ -342,6 +410,15 @@ pub trait StacksDBIndexed {
         tip: &StacksBlockId,
         coinbase_height: u64,
     ) -> Result<Option<StacksBlockId>, DBError> {
+        if let Some(block_id) =
+            NakamotoChainState::get_indexed_tenure_start_block_id_at_coinbase_height(
+                self.sqlite(),
+                tip,
+                coinbase_height,
+            )?
+        {
+            return Ok(Some(block_id));
+        }
         Ok(self
             .get(
                 tip,
@@ -357,6 +434,13 @@ pub trait StacksDBIndexed {
         tip: &StacksBlockId,
         tenure_id_consensus_hash: &ConsensusHash,
     ) -> Result<Option<StacksBlockId>, DBError> {
+        if let Some(block_id) = NakamotoChainState::get_indexed_tenure_start_block_id(
+            self.sqlite(),
+            tip,
+            tenure_id_consensus_hash,
+        )? {
+            return Ok(Some(block_id));
+        }
         Ok(self
             .get(
                 tip,
@@ -610,6 +694,8 @@ pub struct SetupBlockResult<'a, 'b> {
     pub signer_set_calc: Option<SignerCalculation>,
     /// vote-for-aggregate-key Stacks-on-Bitcoin txs
     pub burn_vote_for_aggregate_key_ops: Vec<VoteForAggregateKeyOp>,
+    /// stack-aggregation-commit Stacks-on-Bitcoin txs
+    pub burn_stack_aggregation_commit_ops: Vec<StackAggregationCommitOp>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -687,6 +773,32 @@ impl FromRow<NakamotoBlockHeader> for NakamotoBlockHeader {
     }
 }
 
+/// An entry in the tenure-height index (the `nakamoto_tenure_heights` table), which maps Nakamoto
+/// tenure heights (coinbase heights) to tenure-start blocks.  There is one entry for each processed
+/// tenure-start block, across all forks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TenureHeightEntry {
+    pub coinbase_height: u64,
+    pub tenure_id_consensus_hash: ConsensusHash,
+    pub tenure_start_block_id: StacksBlockId,
+    /// Whether or not this tenure's parent is an epoch2 block
+    pub first_nakamoto_tenure: bool,
+}
+
+impl FromRow<TenureHeightEntry> for TenureHeightEntry {
+    fn from_row(row: &rusqlite::Row) -> Result<TenureHeightEntry, DBError> {
+        let coinbase_height_i64: i64 = row.get("coinbase_height")?;
+        let coinbase_height =
+            u64::try_from(coinbase_height_i64).map_err(|_| DBError::ParseError)?;
+        Ok(TenureHeightEntry {
+            coinbase_height,
+            tenure_id_consensus_hash: row.get("tenure_id_consensus_hash")?,
+            tenure_start_block_id: row.get("tenure_start_block_id")?,
+            first_nakamoto_tenure: row.get("first_nakamoto_tenure")?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NakamotoBlock {
     pub header: NakamotoBlockHeader,
@@ -827,81 +939,102 @@ impl NakamotoBlockHeader {
     /// Returns ChainstateError::InvalidStacksBlock on error
     #[cfg_attr(test, mutants::skip)]
     pub fn verify_signer_signatures(&self, reward_set: &RewardSet) -> Result<u32, ChainstateError> {
-        let message = self.signer_signature_hash();
-        let Some(signers) = &reward_set.signers else {
-            return Err(ChainstateError::InvalidStacksBlock(
-                "No signers in the reward set".to_string(),
-            ));
-        };
+        let signer_keys = RewardSetSignerKeys::new(reward_set)?;
+        self.verify_signer_signatures_with_keys(reward_set, &signer_keys)
+    }
+
+    /// Verify the block header against the list of signer signatures, like
+    /// `verify_signer_signatures()`, using the cached signer keys of `reward_cycle`.
+    /// `reward_set` must be the reward set of `reward_cycle`.
+    pub fn verify_signer_signatures_of_cycle(
+        &self,
+        reward_cycle: u64,
+        reward_set: &RewardSet,
+    ) -> Result<u32, ChainstateError> {
+        let signer_keys = NakamotoChainState::get_reward_set_signer_keys(reward_cycle, reward_set)?;
+        self.verify_signer_signatures_with_keys(reward_set, &signer_keys)
+    }
 
+    /// Verify the block header against the list of signer signatures, given the signers of
+    /// `reward_set` indexed by signing key.  The signers' public keys are recovered as a batch.
+    fn verify_signer_signatures_with_keys(
+        &self,
+        reward_set: &RewardSet,
+        signer_keys: &RewardSetSignerKeys,
+    ) -> Result<u32, ChainstateError> {
         // if this is a shadow block, then its signing weight is as if every signer signed it, even
         // though the signature vector is undefined.
         if self.is_shadow_block() {
             return Ok(self.get_shadow_signer_weight(reward_set)?);
         }
 
+        // every signer signs at most once, so don't spend time recovering keys from a vector
+        // that can't be valid
+        if self.signer_signature.len() > signer_keys.num_signers() {
+            return Err(ChainstateError::InvalidStacksBlock(format!(
+                "Too many signatures: got {} but the reward set has {} signers",
+                self.signer_signature.len(),
+                signer_keys.num_signers(),
+            )));
+        }
+
+        let message = self.signer_signature_hash();
+        let public_keys =
+            Secp256k1PublicKey::recover_batch_to_pubkeys(message.bits(), &self.signer_signature);
+
         let mut total_weight_signed: u32 = 0;
         // `last_index` is used to prevent out-of-order signatures
         let mut last_index = None;
 
-        let total_weight = reward_set
-            .total_signing_weight()
-            .map_err(|_| ChainstateError::NoRegisteredSigners(0))?;
+        for (signature, public_key_res) in self.signer_signature.iter().zip(public_keys) {
+            let public_key = public_key_res.map_err(|_| {
+                ChainstateError::InvalidStacksBlock(format!(
+                    "Unable to recover public key from signature {}",
+                    signature.to_hex()
+                ))
+            })?;
 
-        // HashMap of <PublicKey, (Signer, Index)>
-        let signers_by_pk: HashMap<_, _> = signers
-            .iter()
-            .enumerate()
-            .map(|(i, signer)| (&signer.signing_key, (signer, i)))
-            .collect();
+            let mut public_key_bytes = [0u8; 33];
+            public_key_bytes.copy_from_slice(&public_key.to_bytes_compressed()[..]);
 
-        for signature in self.signer_signature.iter() {
-            let public_key = Secp256k1PublicKey::recover_to_pubkey(message.bits(), signature)
-                .map_err(|_| {
+            let (signer_index, signer_weight) =
+                signer_keys.get(&public_key_bytes).ok_or_else(|| {
+                    warn!(
+                        "Found an invalid public key. Reward set has {} signers. Chain length {}. Signatures length {}",
+                        signer_keys.num_signers(),
+                        self.chain_length,
+                        self.signer_signature.len(),
+                    );
                     ChainstateError::InvalidStacksBlock(format!(
-                        "Unable to recover public key from signature {}",
-                        signature.to_hex()
+                        "Public key {} not found in the reward set",
+                        public_key.to_hex()
                     ))
                 })?;
 
-            let mut public_key_bytes = [0u8; 33];
-            public_key_bytes.copy_from_slice(&public_key.to_bytes_compressed()[..]);
-
-            let (signer, signer_index) = signers_by_pk.get(&public_key_bytes).ok_or_else(|| {
-                warn!(
-                    "Found an invalid public key. Reward set has {} signers. Chain length {}. Signatures length {}",
-                    signers.len(),
-                    self.chain_length,
-                    self.signer_signature.len(),
-                );
-                ChainstateError::InvalidStacksBlock(format!(
-                    "Public key {} not found in the reward set",
-                    public_key.to_hex()
-                ))
-            })?;
-
             // Enforce order of signatures
             if let Some(index) = last_index.as_ref() {
-                if *index >= *signer_index {
+                if *index >= signer_index {
                     return Err(ChainstateError::InvalidStacksBlock(
                         "Signatures are out of order".to_string(),
                     ));
                 }
             } else {
-                last_index = Some(*signer_index);
+                last_index = Some(signer_index);
             }
 
             total_weight_signed = total_weight_signed
-                .checked_add(signer.weight)
+                .checked_add(signer_weight)
                 .expect("FATAL: overflow while computing signer set threshold");
         }
 
-        let threshold = Self::compute_voting_weight_threshold(total_weight)?;
+        let threshold = signer_keys.threshold();
 
         if total_weight_signed < threshold {
             return Err(ChainstateError::InvalidStacksBlock(format!(
                 "Not enough signatures. Needed at least {} but got {} (out of {})",
-                threshold, total_weight_signed, total_weight,
+                threshold,
+                total_weight_signed,
+                signer_keys.total_weight(),
             )));
         }
 
@@ -1614,17 +1747,27 @@ impl NakamotoBlock {
     /// -- if this block has a tenure change, then it's consistent with the miner's public key and
     /// self.header.consensus_hash
     /// -- if this block has a coinbase, then that it's VRF proof was generated by this miner
+    ///
+    /// The miner signature and VRF proof checks are skipped if `sig_checks` shows that they
+    /// already passed against the same inputs.
     fn validate_normal_against_burnchain(
         &self,
         tenure_burn_chain_tip: &BlockSnapshot,
         expected_burn: Option<u64>,
         miner_pubkey_hash160: &Hash160,
         vrf_public_key: &VRFPublicKey,
+        sig_checks: Option<&NakamotoBlockSigChecks>,
     ) -> Result<(), ChainstateError> {
         self.common_validate_against_burnchain(tenure_burn_chain_tip, expected_burn)?;
-        self.check_miner_signature(miner_pubkey_hash160)?;
+        if !sig_checks.is_some_and(|checks| checks.miner_signature_matches(miner_pubkey_hash160)) {
+            self.check_miner_signature(miner_pubkey_hash160)?;
+        }
         self.check_tenure_tx()?;
-        self.check_normal_coinbase_tx(vrf_public_key, &tenure_burn_chain_tip.sortition_hash)?;
+        if !sig_checks.is_some_and(|checks| {
+            checks.vrf_proof_verified(vrf_public_key, &tenure_burn_chain_tip.sortition_hash)
+        }) {
+            self.check_normal_coinbase_tx(vrf_public_key, &tenure_burn_chain_tip.sortition_hash)?;
+        }
 
         // not verified by this method:
         // * chain_length       (need parent block header)
@@ -2060,6 +2203,19 @@ impl NakamotoChainState {
             drop(ok_opt);
             drop(chainstate_tx);
 
+            if e.is_missing_clarity_state() {
+                // The block reads Clarity state that this node has pruned, so its validity is
+                // unknown.  Leave it unprocessed rather than orphan a possibly-valid block.
+                error!(
+                    "Cannot process {}/{}: it reads Clarity state that this node has pruned. Resync the node without `prune_mode` to continue.",
+                    &next_ready_block.header.consensus_hash,
+                    &next_ready_block.header.block_hash();
+                    "stacks_block_id" => %next_ready_block.header.block_id(),
+                    "error" => ?e
+                );
+                return Err(e);
+            }
+
             warn!(
                 "Failed to append {}/{}: {:?}",
                 &next_ready_block.header.consensus_hash,
@@ -2149,6 +2305,7 @@ impl NakamotoChainState {
 
         // announce the block, if we're connected to an event dispatcher
         if let Some(dispatcher) = dispatcher_opt {
+            dispatcher.announce_raw_nakamoto_block(&next_ready_block);
             let block_event = (
                 next_ready_block,
                 parent_header_info.anchored_header.block_hash(),
@@ -2300,6 +2457,7 @@ impl NakamotoChainState {
     /// Called before inserting the block into the staging DB.
     /// Wraps `NakamotoBlock::validate_against_burnchain()`, and
     /// verifies that all transactions in the block are allowed in this epoch.
+    /// `sig_checks`, if given, holds signature checks that already passed for this block.
     pub(crate) fn validate_normal_nakamoto_block_burnchain(
         staging_db: NakamotoStagingBlocksConnRef,
         db_handle: &SortitionHandleConn,
@@ -2307,6 +2465,7 @@ impl NakamotoChainState {
         block: &NakamotoBlock,
         mainnet: bool,
         chain_id: u32,
+        sig_checks: Option<&NakamotoBlockSigChecks>,
     ) -> Result<(), ChainstateError> {
         assert!(!block.is_shadow_block());
 
@@ -2359,6 +2518,7 @@ impl NakamotoChainState {
             expected_burn,
             &miner_pubkey_hash160,
             &leader_key.public_key,
+            sig_checks,
         ) {
             warn!(
                 "Invalid Nakamoto block, could not validate on burnchain";
@@ -2458,6 +2618,10 @@ impl NakamotoChainState {
     /// Does nothing if:
     /// * we already have the block
     /// Returns true if we stored the block; false if not.
+    ///
+    /// `sig_checks`, if given, holds the outcome of pre-validating this block's signatures (see
+    /// `sigcheck`), and its `signer_weight` must only be set for `reward_set`.  Checks that it
+    /// shows as passed are not repeated.
     pub fn accept_block(
         config: &ChainstateConfig,
         block: &NakamotoBlock,
@@ -2466,6 +2630,7 @@ impl NakamotoChainState {
         headers_conn: &Connection,
         reward_set: &RewardSet,
         obtain_method: NakamotoBlockObtainMethod,
+        sig_checks: Option<&NakamotoBlockSigChecks>,
     ) -> Result<bool, ChainstateError> {
         test_debug!("Consider Nakamoto block {}", &block.block_id());
         // do nothing if we already have this block
@@ -2526,6 +2691,7 @@ impl NakamotoChainState {
             block,
             config.mainnet,
             config.chain_id,
+            sig_checks,
         ) {
             warn!("Unacceptable Nakamoto block; will not store";
                   "stacks_block_id" => %block.block_id(),
@@ -2534,15 +2700,21 @@ impl NakamotoChainState {
             return Ok(false);
         };
 
-        let signing_weight = match block.header.verify_signer_signatures(reward_set) {
-            Ok(x) => x,
-            Err(e) => {
-                warn!("Received block, but the signer signatures are invalid";
-                      "block_id" => %block.block_id(),
-                      "error" => ?e,
-                );
-                return Err(e);
-            }
+        let prevalidated_weight = sig_checks
+            .and_then(|checks| checks.signer_weight)
+            .map(|(_, weight)| weight);
+        let signing_weight = match prevalidated_weight {
+            Some(x) => x,
+            None => match block.header.verify_signer_signatures(reward_set) {
+                Ok(x) => x,
+                Err(e) => {
+                    warn!("Received block, but the signer signatures are invalid";
+                          "block_id" => %block.block_id(),
+                          "error" => ?e,
+                    );
+                    return Err(e);
+                }
+            },
         };
 
         // if we pass all the tests, then along the way, we will have verified (in
@@ -2565,6 +2737,18 @@ impl NakamotoChainState {
         Ok(ret)
     }
 
+    /// Return the ExecutionCost consumed by `block` alone
+    pub fn get_block_cost_at(
+        chainstate_conn: &Connection,
+        block: &StacksBlockId,
+    ) -> Result<Option<ExecutionCost>, ChainstateError> {
+        let qry = "SELECT cost FROM nakamoto_block_headers WHERE index_block_hash = ?";
+        chainstate_conn
+            .query_row(qry, &[block], |row| row.get(0))
+            .optional()
+            .map_err(ChainstateError::from)
+    }
+
     /// Return the total ExecutionCost consumed during the tenure up to and including
     ///  `block`
     pub fn get_total_tenure_cost_at(
@@ -2632,6 +2816,131 @@ impl NakamotoChainState {
         Ok(None)
     }
 
+    /// Record a processed tenure-start block in the tenure-height index
+    pub(crate) fn insert_tenure_height_entry(
+        tx: &Connection,
+        entry: &TenureHeightEntry,
+    ) -> Result<(), ChainstateError> {
+        let args = params![
+            u64_to_sql(entry.coinbase_height)?,
+            entry.tenure_id_consensus_hash,
+            entry.tenure_start_block_id,
+            entry.first_nakamoto_tenure,
+        ];
+        tx.execute(
+            "INSERT OR REPLACE INTO nakamoto_tenure_heights
+                (coinbase_height, tenure_id_consensus_hash, tenure_start_block_id, first_nakamoto_tenure)
+            VALUES
+                (?1, ?2, ?3, ?4)",
+            args,
+        )?;
+        Ok(())
+    }
+
+    /// Get the tenure-height index entries for all processed tenure-start blocks at a given
+    /// coinbase height, across all forks
+    pub fn get_tenure_height_entries_at(
+        conn: &Connection,
+        coinbase_height: u64,
+    ) -> Result<Vec<TenureHeightEntry>, DBError> {
+        let sql = "SELECT * FROM nakamoto_tenure_heights WHERE coinbase_height = ?1";
+        query_rows(conn, sql, params![u64_to_sql(coinbase_height)?])
+    }
+
+    /// Get the tenure-height index entries for all processed tenure-start blocks of a given
+    /// tenure, across all forks
+    pub fn get_tenure_height_entries_for_tenure(
+        conn: &Connection,
+        tenure_id_consensus_hash: &ConsensusHash,
+    ) -> Result<Vec<TenureHeightEntry>, DBError> {
+        let sql = "SELECT * FROM nakamoto_tenure_heights WHERE tenure_id_consensus_hash = ?1";
+        query_rows(conn, sql, params![tenure_id_consensus_hash])
+    }
+
+    /// Find the tenure-start block at `coinbase_height` in the fork identified by `tip`, using
+    /// only the tenure-height index.
+    ///
+    /// Every processed Nakamoto tenure-start block is indexed.  If `tip` is a Nakamoto block
+    /// whose tenure is at or above `coinbase_height`, and `coinbase_height` is at or above the
+    /// first Nakamoto tenure of every fork, then `tip`'s fork has an indexed tenure at
+    /// `coinbase_height`.  If that is the only tenure indexed at this height, then it must be the
+    /// one in `tip`'s fork.
+    ///
+    /// Returns Ok(Some(..)) if the index alone answers the query.
+    /// Returns Ok(None) if it cannot (e.g. there are forks at this height, or `tip` is not a
+    /// Nakamoto block), in which case the caller must consult the MARF.
+    pub fn get_indexed_tenure_start_block_id_at_coinbase_height(
+        conn: &Connection,
+        tip: &StacksBlockId,
+        coinbase_height: u64,
+    ) -> Result<Option<StacksBlockId>, DBError> {
+        let Some(tip_consensus_hash) = conn
+            .query_row(
+                "SELECT consensus_hash FROM nakamoto_block_headers WHERE index_block_hash = ?1",
+                params![tip],
+                |row| row.get::<_, ConsensusHash>(0),
+            )
+            .optional()?
+        else {
+            return Ok(None);
+        };
+
+        // the tip's tenure must have a single, known coinbase height
+        let tip_entries = Self::get_tenure_height_entries_for_tenure(conn, &tip_consensus_hash)?;
+        let Some(tip_coinbase_height) = tip_entries.first().map(|entry| entry.coinbase_height)
+        else {
+            return Ok(None);
+        };
+        if tip_entries
+            .iter()
+            .any(|entry| entry.coinbase_height != tip_coinbase_height)
+            || coinbase_height > tip_coinbase_height
+        {
+            return Ok(None);
+        }
+
+        // the height must be in the Nakamoto epoch in every fork
+        let highest_first_tenure: Option<i64> = conn.query_row(
+            "SELECT MAX(coinbase_height) FROM nakamoto_tenure_heights WHERE first_nakamoto_tenure = 1",
+            NO_PARAMS,
+            |row| row.get(0),
+        )?;
+        let Some(highest_first_tenure) = highest_first_tenure else {
+            return Ok(None);
+        };
+        if coinbase_height < u64::try_from(highest_first_tenure).map_err(|_| DBError::ParseError)? {
+            return Ok(None);
+        }
+
+        let entries = Self::get_tenure_height_entries_at(conn, coinbase_height)?;
+        let [entry] = entries.as_slice() else {
+            return Ok(None);
+        };
+        Ok(Some(entry.tenure_start_block_id.clone()))
+    }
+
+    /// Find the tenure-start block of the tenure identified by `tenure_id_consensus_hash` in the
+    /// fork identified by `tip`, using only the tenure-height index.
+    ///
+    /// Returns Ok(Some(..)) if the index alone answers the query.
+    /// Returns Ok(None) if it cannot, in which case the caller must consult the MARF.
+    pub fn get_indexed_tenure_start_block_id(
+        conn: &Connection,
+        tip: &StacksBlockId,
+        tenure_id_consensus_hash: &ConsensusHash,
+    ) -> Result<Option<StacksBlockId>, DBError> {
+        let entries = Self::get_tenure_height_entries_for_tenure(conn, tenure_id_consensus_hash)?;
+        let [entry] = entries.as_slice() else {
+            return Ok(None);
+        };
+        let block_id = Self::get_indexed_tenure_start_block_id_at_coinbase_height(
+            conn,
+            tip,
+            entry.coinbase_height,
+        )?;
+        Ok(block_id.filter(|block_id| block_id == &entry.tenure_start_block_id))
+    }
+
     /// Load the block version of a Nakamoto blocok
     pub fn get_nakamoto_block_version(
         chainstate_conn: &Connection,
@@ -3243,6 +3552,7 @@ impl NakamotoChainState {
         burn_transfer_stx_ops: Vec<TransferStxOp>,
         burn_delegate_stx_ops: Vec<DelegateStxOp>,
         burn_vote_for_aggregate_key_ops: Vec<VoteForAggregateKeyOp>,
+        burn_stack_aggregation_commit_ops: Vec<StackAggregationCommitOp>,
         new_tenure: bool,
         coinbase_height: u64,
         block_fees: u128,
@@ -3424,6 +3734,18 @@ impl NakamotoChainState {
             StacksChainState::insert_miner_payment_schedule(headers_tx.deref_mut(), block_reward)?;
         }
 
+        if new_tenure {
+            Self::insert_tenure_height_entry(
+                headers_tx.deref(),
+                &TenureHeightEntry {
+                    coinbase_height,
+                    tenure_id_consensus_hash: new_tip.consensus_hash.clone(),
+                    tenure_start_block_id: index_block_hash.clone(),
+                    first_nakamoto_tenure: matches!(parent_tip, StacksBlockHeaderTypes::Epoch2(..)),
+                },
+            )?;
+        }
+
         // NOTE: this is a no-op if the block isn't a tenure-start block
         if new_tenure {
             StacksChainState::store_burnchain_txids(
@@ -3433,6 +3755,7 @@ impl NakamotoChainState {
                 burn_transfer_stx_ops,
                 burn_delegate_stx_ops,
                 burn_vote_for_aggregate_key_ops,
+                burn_stack_aggregation_commit_ops,
             )?;
         }
 
@@ -3501,6 +3824,53 @@ impl NakamotoChainState {
             .map_err(ChainstateError::from)
     }
 
+    /// Get the cached reward set for `reward_cycle`, as read at its PoX anchor block
+    pub fn get_cached_reward_set(
+        chainstate_db: &Connection,
+        reward_cycle: u64,
+        anchor_block_id: &StacksBlockId,
+    ) -> Result<Option<RewardSet>, ChainstateError> {
+        let sql = "SELECT reward_set FROM nakamoto_reward_set_cache WHERE reward_cycle = ?1 AND anchor_block_id = ?2";
+        let args = params![u64_to_sql(reward_cycle)?, anchor_block_id];
+        chainstate_db
+            .query_row(sql, args, |row| {
+                let reward_set: String = row.get(0)?;
+                let reward_set = RewardSet::metadata_deserialize(&reward_set)
+                    .map_err(|s| FromSqlError::Other(s.into()))?;
+                Ok(reward_set)
+            })
+            .optional()
+            .map_err(ChainstateError::from)
+    }
+
+    /// Cache the reward set for `reward_cycle`, as read at its PoX anchor block
+    pub fn cache_reward_set(
+        tx: &Connection,
+        reward_cycle: u64,
+        anchor_block_id: &StacksBlockId,
+        reward_set: &RewardSet,
+    ) -> Result<(), ChainstateError> {
+        let sql = "INSERT OR REPLACE INTO nakamoto_reward_set_cache (reward_cycle, anchor_block_id, reward_set) VALUES (?1, ?2, ?3)";
+        let args = params![
+            u64_to_sql(reward_cycle)?,
+            anchor_block_id,
+            reward_set.metadata_serialize(),
+        ];
+        tx.execute(sql, args)?;
+        Ok(())
+    }
+
+    /// Drop the cached reward sets of `from_reward_cycle` and every later reward cycle.
+    /// Returns the number of reward sets dropped.
+    pub fn invalidate_cached_reward_sets(
+        tx: &Connection,
+        from_reward_cycle: u64,
+    ) -> Result<usize, ChainstateError> {
+        let sql = "DELETE FROM nakamoto_reward_set_cache WHERE reward_cycle >= ?1";
+        let dropped = tx.execute(sql, params![u64_to_sql(from_reward_cycle)?])?;
+        Ok(dropped)
+    }
+
     /// Keep track of how many blocks each signer is signing
     fn record_block_signers(
         tx: &mut ChainstateTx,
@@ -3581,6 +3951,7 @@ impl NakamotoChainState {
             Vec<TransferStxOp>,
             Vec<DelegateStxOp>,
             Vec<VoteForAggregateKeyOp>,
+            Vec<StackAggregationCommitOp>,
         ),
         ChainstateError,
     > {
@@ -3589,7 +3960,7 @@ impl NakamotoChainState {
 
         // only consider transactions in Stacks 3.0
         if cur_epoch.epoch_id < StacksEpochId::Epoch30 {
-            return Ok((vec![], vec![], vec![], vec![]));
+            return Ok((vec![], vec![], vec![], vec![], vec![]));
         }
 
         let epoch_start_height = cur_epoch.start_height;
@@ -3634,6 +4005,7 @@ impl NakamotoChainState {
         let mut all_transfer_burn_ops = vec![];
         let mut all_delegate_burn_ops = vec![];
         let mut all_vote_for_aggregate_key_ops = vec![];
+        let mut all_stack_aggregation_commit_ops = vec![];
 
         // go from oldest burn header hash to newest
         for ancestor_bhh in ancestor_burnchain_header_hashes.iter().rev() {
@@ -3642,6 +4014,8 @@ impl NakamotoChainState {
             let delegate_ops = SortitionDB::get_delegate_stx_ops(sortdb_conn, ancestor_bhh)?;
             let vote_for_aggregate_key_ops =
                 SortitionDB::get_vote_for_aggregate_key_ops(sortdb_conn, ancestor_bhh)?;
+            let stack_aggregation_commit_ops =
+                SortitionDB::get_stack_aggregation_commit_ops(sortdb_conn, ancestor_bhh)?;
 
             for stacking_op in stacking_ops.into_iter() {
                 if !processed_burnchain_txids.contains(&stacking_op.txid) {
@@ -3666,12 +4040,19 @@ impl NakamotoChainState {
                     all_vote_for_aggregate_key_ops.push(vote_op);
                 }
             }
+
+            for agg_commit_op in stack_aggregation_commit_ops.into_iter() {
+                if !processed_burnchain_txids.contains(&agg_commit_op.txid) {
+                    all_stack_aggregation_commit_ops.push(agg_commit_op);
+                }
+            }
         }
         Ok((
             all_stacking_burn_ops,
             all_transfer_burn_ops,
             all_delegate_burn_ops,
             all_vote_for_aggregate_key_ops,
+            all_stack_aggregation_commit_ops,
         ))
     }
 
@@ -3874,19 +4255,24 @@ impl NakamotoChainState {
             None
         };
 
-        let (stacking_burn_ops, transfer_burn_ops, delegate_burn_ops, vote_for_agg_key_ops) =
-            if new_tenure {
-                NakamotoChainState::get_stacks_on_burnchain_operations(
-                    chainstate_tx.as_tx(),
-                    &parent_consensus_hash,
-                    &parent_header_hash,
-                    sortition_dbconn.sqlite_conn(),
-                    &burn_header_hash,
-                    burn_header_height.into(),
-                )?
-            } else {
-                (vec![], vec![], vec![], vec![])
-            };
+        let (
+            stacking_burn_ops,
+            transfer_burn_ops,
+            delegate_burn_ops,
+            vote_for_agg_key_ops,
+            agg_commit_ops,
+        ) = if new_tenure {
+            NakamotoChainState::get_stacks_on_burnchain_operations(
+                chainstate_tx.as_tx(),
+                &parent_consensus_hash,
+                &parent_header_hash,
+                sortition_dbconn.sqlite_conn(),
+                &burn_header_hash,
+                burn_header_height.into(),
+            )?
+        } else {
+            (vec![], vec![], vec![], vec![], vec![])
+        };
 
         // Nakamoto must load block cost from parent if this block isn't a tenure change.
         // If this is a tenure-extend, then the execution cost is reset.
@@ -4034,6 +4420,13 @@ impl NakamotoChainState {
                 &mut clarity_tx,
                 vote_for_agg_key_ops.clone(),
             ));
+            if evaluated_epoch.supports_stack_aggregation_commit_op() {
+                tx_receipts.extend(StacksChainState::process_stack_aggregation_commit_ops(
+                    &mut clarity_tx,
+                    agg_commit_ops.clone(),
+                    active_pox_contract,
+                ));
+            }
 
             if signer_set_calc.is_some() {
                 debug!("Setup block: computed reward set for the next reward cycle";
@@ -4062,6 +4455,7 @@ impl NakamotoChainState {
             burn_delegate_stx_ops: delegate_burn_ops,
             signer_set_calc,
             burn_vote_for_aggregate_key_ops: vote_for_agg_key_ops,
+            burn_stack_aggregation_commit_ops: agg_commit_ops,
         })
     }
 
@@ -4231,6 +4625,7 @@ impl NakamotoChainState {
             epoch_transition: applied_epoch_transition,
             signers_updated,
             coinbase_height,
+            clarity_writes: vec![],
         };
 
         return Ok((epoch_receipt, clarity_commit, None, phantom_lockup_events));
@@ -4433,6 +4828,7 @@ impl NakamotoChainState {
             mut auto_unlock_events,
             signer_set_calc,
             burn_vote_for_aggregate_key_ops,
+            burn_stack_aggregation_commit_ops,
         } = if block.is_shadow_block() {
             // shadow block
             Self::setup_shadow_block_processing(
@@ -4490,6 +4886,11 @@ impl NakamotoChainState {
             0,
             ast_rules,
         ) {
+            Err(e) if e.is_missing_clarity_state() => {
+                // this node cannot tell whether or not the block is valid
+                clarity_tx.rollback_block();
+                return Err(e);
+            }
             Err(e) => {
                 let msg = format!("Invalid Stacks block {}: {:?}", &block_hash, &e);
                 warn!("{}", &msg);
@@ -4572,6 +4973,7 @@ impl NakamotoChainState {
         let block_limit = clarity_tx
             .block_limit()
             .ok_or_else(|| ChainstateError::InvalidChainstateDB)?;
+        let clarity_writes = clarity_tx.take_write_log();
         let clarity_commit =
             clarity_tx.precommit_to_block(&block.header.consensus_hash, &block_hash);
 
@@ -4634,6 +5036,7 @@ impl NakamotoChainState {
             burn_transfer_stx_ops,
             burn_delegate_stx_ops,
             burn_vote_for_aggregate_key_ops,
+            burn_stack_aggregation_commit_ops,
             new_tenure,
             coinbase_height,
             block_fees,
@@ -4643,6 +5046,11 @@ impl NakamotoChainState {
 
         let new_block_id = new_tip.index_block_hash();
         chainstate_tx.log_transactions_processed(&new_block_id, &tx_receipts);
+        StacksChainState::store_burnchain_op_receipts(
+            chainstate_tx.deref().deref(),
+            &new_block_id,
+            &tx_receipts,
+        )?;
 
         let reward_cycle = pox_constants
             .block_height_to_reward_cycle(first_block_height, chain_tip_burn_header_height.into());
@@ -4721,6 +5129,7 @@ impl NakamotoChainState {
             epoch_transition: applied_epoch_transition,
             signers_updated,
             coinbase_height,
+            clarity_writes,
         };
 
         Ok((
@@ -4893,7 +5302,7 @@ impl NakamotoChainState {
     /// Generate a "phantom" transaction to include STXMintEvents for
     /// lockups that could not be attached to a Coinbase transaction
     /// (because the block doesn't have a Coinbase transaction).
-    fn generate_phantom_unlock_tx(
+    pub(crate) fn generate_phantom_unlock_tx(
         events: Vec<StacksTransactionEvent>,
         config: &ChainstateConfig,
         stacks_block_height: u64,
@@ -0,0 +1,282 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signature pre-validation for batches of Nakamoto blocks.
+//!
+//! Recovering the miner's public key, recovering each signer's public key, and verifying the
+//! coinbase VRF proof are the most expensive parts of
+//! `NakamotoChainState::accept_block()`, and none of them need the chainstate.  When a burst of
+//! blocks arrives, the relayer runs these checks on a pool of worker threads first, and then
+//! stores the blocks one at a time, reusing the results.
+//!
+//! Only successful checks are reused.  If a pre-validated check failed, or was made against
+//! different inputs than the ones `accept_block()` has, the check is simply run again inline so
+//! the block is rejected with the usual error.
+//!
+//! Signer signatures are checked against a `RewardSetSignerKeys`, the reward set's signers
+//! indexed by signing key.  These are cached per reward cycle, so that each block's check does
+//! not have to index the reward set again.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use lazy_static::lazy_static;
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::util::hash::Hash160;
+use stacks_common::util::vrf::{VRFPublicKey, VRF};
+
+use crate::chainstate::burn::SortitionHash;
+use crate::chainstate::nakamoto::{NakamotoBlock, NakamotoBlockHeader, NakamotoChainState};
+use crate::chainstate::stacks::boot::{NakamotoSignerEntry, RewardSet};
+use crate::chainstate::stacks::Error as ChainstateError;
+
+/// Number of reward cycles whose signer keys are cached
+const MAX_CACHED_SIGNER_KEYS: usize = 4;
+
+lazy_static! {
+    static ref SIGNER_KEYS_CACHE: Mutex<HashMap<u64, Arc<RewardSetSignerKeys>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// A reward set's signers, indexed by signing key, along with the signing weight a block needs
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewardSetSignerKeys {
+    /// The signers these keys were computed from
+    signers: Vec<NakamotoSignerEntry>,
+    /// Each signer's position in the reward set, and its weight
+    by_key: HashMap<[u8; 33], (usize, u32)>,
+    total_weight: u32,
+    threshold: u32,
+}
+
+impl RewardSetSignerKeys {
+    /// Index the signers of `reward_set`.
+    /// Fails if the reward set has no signers.
+    pub fn new(reward_set: &RewardSet) -> Result<Self, ChainstateError> {
+        let Some(signers) = &reward_set.signers else {
+            return Err(ChainstateError::InvalidStacksBlock(
+                "No signers in the reward set".to_string(),
+            ));
+        };
+        let total_weight = reward_set
+            .total_signing_weight()
+            .map_err(|_| ChainstateError::NoRegisteredSigners(0))?;
+        let threshold = NakamotoBlockHeader::compute_voting_weight_threshold(total_weight)?;
+        let by_key = signers
+            .iter()
+            .enumerate()
+            .map(|(i, signer)| (signer.signing_key, (i, signer.weight)))
+            .collect();
+        Ok(Self {
+            signers: signers.clone(),
+            by_key,
+            total_weight,
+            threshold,
+        })
+    }
+
+    /// Were these keys computed from `reward_set`?
+    pub fn is_for(&self, reward_set: &RewardSet) -> bool {
+        reward_set.signers.as_ref() == Some(&self.signers)
+    }
+
+    /// Get the position in the reward set, and the weight, of the signer with this signing key
+    pub fn get(&self, signing_key: &[u8; 33]) -> Option<(usize, u32)> {
+        self.by_key.get(signing_key).copied()
+    }
+
+    pub fn num_signers(&self) -> usize {
+        self.signers.len()
+    }
+
+    pub fn total_weight(&self) -> u32 {
+        self.total_weight
+    }
+
+    /// The least signing weight with which a block is signed
+    pub fn threshold(&self) -> u32 {
+        self.threshold
+    }
+}
+
+impl NakamotoChainState {
+    /// Get the indexed signers of `reward_set`, the reward set of `reward_cycle`, from the
+    /// process-wide cache if they are there.  A cached entry is only used if it was computed
+    /// from the same signers, so a reward set from another fork (or another node in the same
+    /// process) just replaces it.
+    pub fn get_reward_set_signer_keys(
+        reward_cycle: u64,
+        reward_set: &RewardSet,
+    ) -> Result<Arc<RewardSetSignerKeys>, ChainstateError> {
+        if let Some(keys) = SIGNER_KEYS_CACHE
+            .lock()
+            .expect("FATAL: signer keys cache lock poisoned")
+            .get(&reward_cycle)
+            .filter(|keys| keys.is_for(reward_set))
+        {
+            return Ok(keys.clone());
+        }
+
+        let keys = Arc::new(RewardSetSignerKeys::new(reward_set)?);
+        let mut cache = SIGNER_KEYS_CACHE
+            .lock()
+            .expect("FATAL: signer keys cache lock poisoned");
+        cache.insert(reward_cycle, keys.clone());
+        if cache.len() > MAX_CACHED_SIGNER_KEYS {
+            // the oldest reward cycle is the least likely to be needed again
+            if let Some(oldest) = cache.keys().min().copied() {
+                cache.remove(&oldest);
+            }
+        }
+        Ok(keys)
+    }
+}
+
+/// The inputs needed to pre-validate one block's signatures
+pub struct NakamotoSigCheckJob<'a> {
+    /// The block to check
+    pub block: &'a NakamotoBlock,
+    /// The reward cycle of the block's tenure, and that cycle's reward set, if known
+    pub reward_set: Option<(u64, &'a RewardSet)>,
+    /// The tenure's leader VRF key and sortition hash, if the block has a coinbase
+    pub vrf_inputs: Option<(VRFPublicKey, SortitionHash)>,
+}
+
+/// The outcome of pre-validating a block's signatures.  Each field is only set if the
+/// corresponding check passed.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NakamotoBlockSigChecks {
+    /// Hash160 of the public key recovered from the miner signature
+    pub miner_pubkh: Option<Hash160>,
+    /// The reward cycle whose signers signed the block, and their total signing weight
+    pub signer_weight: Option<(u64, u32)>,
+    /// The leader VRF key and sortition hash against which the coinbase VRF proof is valid
+    pub vrf_verified: Option<(VRFPublicKey, SortitionHash)>,
+}
+
+impl NakamotoBlockSigChecks {
+    /// Run the signature checks for one block
+    pub fn compute(job: &NakamotoSigCheckJob) -> Self {
+        let block = job.block;
+        if block.is_shadow_block() {
+            // shadow blocks carry no signatures to check
+            return Self::default();
+        }
+
+        let miner_pubkh = block.recover_miner_pubkh().ok();
+
+        let signer_weight = job.reward_set.and_then(|(reward_cycle, reward_set)| {
+            let weight = block
+                .header
+                .verify_signer_signatures_of_cycle(reward_cycle, reward_set)
+                .ok()?;
+            Some((reward_cycle, weight))
+        });
+
+        let vrf_verified = job
+            .vrf_inputs
+            .as_ref()
+            .and_then(|(vrf_key, sortition_hash)| {
+                let coinbase_tx = block.get_coinbase_tx()?;
+                let (_, _, vrf_proof_opt) = coinbase_tx.try_as_coinbase()?;
+                let valid = VRF::verify(vrf_key, vrf_proof_opt?, sortition_hash.as_bytes()).ok()?;
+                valid.then(|| (vrf_key.clone(), sortition_hash.clone()))
+            });
+
+        Self {
+            miner_pubkh,
+            signer_weight,
+            vrf_verified,
+        }
+    }
+
+    /// Run the signature checks for a batch of blocks on up to `num_workers` threads.
+    /// Returns the outcome for each block, keyed by block ID.
+    pub fn compute_batch(
+        jobs: &[NakamotoSigCheckJob],
+        num_workers: usize,
+    ) -> HashMap<StacksBlockId, Self> {
+        if jobs.is_empty() {
+            return HashMap::new();
+        }
+        let num_workers = num_workers.clamp(1, jobs.len());
+        if num_workers == 1 {
+            return jobs
+                .iter()
+                .map(|job| (job.block.block_id(), Self::compute(job)))
+                .collect();
+        }
+
+        let chunk_size = jobs.len().div_ceil(num_workers);
+        thread::scope(|s| {
+            let workers: Vec<_> = jobs
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    s.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|job| (job.block.block_id(), Self::compute(job)))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .flat_map(|worker| {
+                    worker
+                        .join()
+                        .expect("FATAL: Nakamoto block signature check worker panicked")
+                })
+                .collect()
+        })
+    }
+
+    /// Get these checks with the signer weight dropped unless it was computed against the
+    /// signers of `reward_cycle`
+    pub fn for_reward_cycle(&self, reward_cycle: u64) -> Self {
+        Self {
+            signer_weight: self
+                .signer_weight_for(reward_cycle)
+                .map(|weight| (reward_cycle, weight)),
+            ..self.clone()
+        }
+    }
+
+    /// Get the pre-validated signing weight, if the block was checked against the signers of
+    /// `reward_cycle`
+    pub fn signer_weight_for(&self, reward_cycle: u64) -> Option<u32> {
+        self.signer_weight
+            .filter(|(rc, _)| *rc == reward_cycle)
+            .map(|(_, weight)| weight)
+    }
+
+    /// Did the miner signature pre-validate against `miner_pubkh`?
+    pub fn miner_signature_matches(&self, miner_pubkh: &Hash160) -> bool {
+        self.miner_pubkh.as_ref() == Some(miner_pubkh)
+    }
+
+    /// Did the coinbase VRF proof pre-validate against this VRF key and sortition hash?
+    pub fn vrf_proof_verified(
+        &self,
+        vrf_key: &VRFPublicKey,
+        sortition_hash: &SortitionHash,
+    ) -> bool {
+        self.vrf_verified
+            .as_ref()
+            .is_some_and(|(key, hash)| key == vrf_key && hash == sortition_hash)
+    }
+}
@@ -59,6 +59,16 @@ impl fmt::Display for NakamotoBlockObtainMethod {
     }
 }
 
+/// A stored Nakamoto block that has not been processed yet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnprocessedStagingBlock {
+    pub block_id: StacksBlockId,
+    pub parent_block_id: StacksBlockId,
+    pub height: u64,
+    /// Has the burnchain block that started this block's tenure been processed?
+    pub burn_attachable: bool,
+}
+
 pub const NAKAMOTO_STAGING_DB_SCHEMA_1: &[&str] = &[
     r#"
   -- Table for staging nakamoto blocks
@@ -497,6 +507,34 @@ impl<'a> NakamotoStagingBlocksConnRef<'a> {
             })
     }
 
+    /// Get up to `limit` stored Nakamoto blocks above `height` that have been neither processed
+    /// nor orphaned, lowest first.
+    ///
+    /// Used to diagnose a Stacks tip that has stopped advancing
+    pub fn get_unprocessed_blocks_above(
+        &self,
+        height: u64,
+        limit: u32,
+    ) -> Result<Vec<UnprocessedStagingBlock>, ChainstateError> {
+        let sql = "SELECT index_block_hash, parent_block_id, height, burn_attachable FROM nakamoto_staging_blocks
+                   WHERE processed = 0 AND orphaned = 0 AND height > ?1
+                   ORDER BY height ASC LIMIT ?2";
+        let args = params![u64_to_sql(height)?, limit];
+        let mut stmt = self.deref().prepare(sql)?;
+        let mut rows = stmt.query(args)?;
+        let mut blocks = vec![];
+        while let Some(row) = rows.next()? {
+            let height: i64 = row.get(2)?;
+            blocks.push(UnprocessedStagingBlock {
+                block_id: row.get(0)?,
+                parent_block_id: row.get(1)?,
+                height: u64::try_from(height).map_err(|_| DBError::ParseError)?,
+                burn_attachable: row.get(3)?,
+            });
+        }
+        Ok(blocks)
+    }
+
     /// Given a consensus hash, determine if the burn block has been processed.
     /// Because this is stored in a denormalized way, we'll want to do this whenever we store a
     /// block (so we can set `burn_attachable` accordingly)
@@ -249,6 +249,58 @@ pub struct NakamotoTenureEventId {
     pub block_id: StacksBlockId,
 }
 
+/// How much of its execution budget a tenure has consumed, as of one of its blocks.
+/// The budget is the epoch's block limit.  Each tenure change and tenure extension refreshes it,
+/// so only the blocks since the most recent one count towards `consumed`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TenureBudgetUsage {
+    /// consensus hash of the tenure
+    pub consensus_hash: ConsensusHash,
+    /// block as of which the usage is measured
+    pub tip_block_id: StacksBlockId,
+    /// height of `tip_block_id`
+    pub tip_height: u64,
+    /// cost consumed since the most recent tenure change or extension, including `tip_block_id`
+    pub consumed: ExecutionCost,
+    /// cost of `tip_block_id` alone
+    pub tip_block_cost: ExecutionCost,
+    /// the tenure's execution budget
+    pub budget: ExecutionCost,
+    /// percentage of the budget consumed, in its most-consumed dimension
+    pub percent_used: u64,
+    /// percentage of the budget that will have been consumed after one more block as costly as
+    /// `tip_block_id`, in its most-consumed dimension
+    pub projected_percent_used: u64,
+}
+
+impl TenureBudgetUsage {
+    pub fn new(
+        consensus_hash: ConsensusHash,
+        tip_block_id: StacksBlockId,
+        tip_height: u64,
+        consumed: ExecutionCost,
+        tip_block_cost: ExecutionCost,
+        budget: ExecutionCost,
+    ) -> Self {
+        let percent_used = budget.proportion_largest_dimension(&consumed);
+        let mut projected = consumed.clone();
+        let projected_percent_used = match projected.add(&tip_block_cost) {
+            Ok(()) => budget.proportion_largest_dimension(&projected),
+            Err(_) => u64::MAX,
+        };
+        Self {
+            consensus_hash,
+            tip_block_id,
+            tip_height,
+            consumed,
+            tip_block_cost,
+            budget,
+            percent_used,
+            projected_percent_used,
+        }
+    }
+}
+
 /// Nakamto tenure event.  Something happened to the tenure stream, and this struct encodes it (be
 /// it a new tenure was started, or the current tenure was extended).
 #[derive(Debug, Clone, PartialEq)]
@@ -530,6 +582,44 @@ impl NakamotoChainState {
         Ok(count)
     }
 
+    /// Get how much of its execution budget the tenure of `tip_block_id` has consumed, as of
+    /// that block.  The budget is the block limit of the epoch in which the tenure started.
+    /// Returns None if `tip_block_id` is not a processed Nakamoto block.
+    pub fn get_tenure_budget_usage(
+        chainstate_conn: &Connection,
+        sortdb_conn: &DBConn,
+        tip_block_id: &StacksBlockId,
+    ) -> Result<Option<TenureBudgetUsage>, ChainstateError> {
+        let Some(header) = Self::get_block_header_nakamoto(chainstate_conn, tip_block_id)? else {
+            return Ok(None);
+        };
+        let sql =
+            "SELECT cost, total_tenure_cost FROM nakamoto_block_headers WHERE index_block_hash = ?1";
+        let Some((tip_block_cost, consumed)): Option<(ExecutionCost, ExecutionCost)> =
+            chainstate_conn
+                .query_row(sql, params![tip_block_id], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })
+                .optional()?
+        else {
+            return Ok(None);
+        };
+        let burn_height = u64::from(header.burn_header_height);
+        let Some(epoch) = SortitionDB::get_stacks_epoch(sortdb_conn, burn_height)? else {
+            return Err(ChainstateError::InvalidStacksBlock(format!(
+                "No epoch at burn height {burn_height}"
+            )));
+        };
+        Ok(Some(TenureBudgetUsage::new(
+            header.consensus_hash,
+            tip_block_id.clone(),
+            header.stacks_block_height,
+            consumed,
+            tip_block_cost,
+            epoch.block_limit,
+        )))
+    }
+
     /// Get a Nakamoto tenure change by its ID
     pub fn get_nakamoto_tenure_change(
         headers_conn: &Connection,
@@ -541,6 +631,23 @@ impl NakamotoChainState {
         Ok(query_row(headers_conn, sql, args)?)
     }
 
+    /// Get every tenure-change and tenure-extend processed in the given tenure, on any fork, in
+    /// block height order
+    pub fn get_nakamoto_tenure_changes(
+        headers_conn: &Connection,
+        tenure_id_consensus_hash: &ConsensusHash,
+    ) -> Result<Vec<NakamotoTenureEvent>, ChainstateError> {
+        let sql = "SELECT events.* FROM nakamoto_tenure_events events
+            JOIN nakamoto_block_headers headers ON headers.index_block_hash = events.block_id
+            WHERE events.tenure_id_consensus_hash = ?1
+            ORDER BY headers.block_height, events.block_id";
+        Ok(query_rows(
+            headers_conn,
+            sql,
+            params![tenure_id_consensus_hash],
+        )?)
+    }
+
     /// Get the tenure-change most recently processed in the history tipped by the given block.
     /// This can be a block-found or an extended tenure change.
     /// Returns None if this tip is an epoch2x block ID
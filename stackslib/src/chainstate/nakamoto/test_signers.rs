@@ -17,6 +17,7 @@
 use std::cell::RefCell;
 use std::collections::{HashSet, VecDeque};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use std::{fs, io};
 
 use clarity::util::hash::MerkleHashFunc;
@@ -34,7 +35,7 @@ use stacks_common::consts::{FIRST_BURNCHAIN_CONSENSUS_HASH, FIRST_STACKS_BLOCK_H
 use stacks_common::types::chainstate::{
     BlockHeaderHash, SortitionId, StacksAddress, StacksBlockId, VRFSeed,
 };
-use stacks_common::util::hash::Hash160;
+use stacks_common::util::hash::{Hash160, Sha256Sum};
 use stacks_common::util::sleep_ms;
 use stacks_common::util::vrf::{VRFProof, VRFPublicKey};
 
@@ -65,6 +66,21 @@ use crate::net::relay::Relayer;
 use crate::util_lib::boot::boot_code_addr;
 use crate::util_lib::db::Error as db_error;
 
+/// How a test signer responds when it is asked to sign a block
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignerParticipation {
+    /// Sign every block
+    AlwaysSign,
+    /// Sign no block
+    NeverSign,
+    /// Sign each block with this probability, in `[0, 1]`. The outcome is drawn from the
+    /// block's signer sighash and the signer's key, so re-signing the same block gives the
+    /// same outcome.
+    SignWithProbability(f64),
+    /// Sign every block, but only after this delay has passed
+    SignAfterDelay(Duration),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct TestSigners {
     /// The number of signatures required to validate a block
@@ -75,6 +91,12 @@ pub struct TestSigners {
     pub aggregate_public_key: Vec<u8>,
     /// The cycle for which the aggregate public key was generated
     pub cycle: u64,
+    /// How each signer responds to signing requests, by compressed public key.
+    /// Signers without an entry always sign.
+    pub participation: HashMap<Vec<u8>, SignerParticipation>,
+    /// The weight of each signer in [`Self::synthesize_reward_set()`], by compressed public
+    /// key. Signers without an entry have weight 1.
+    pub signer_weights: HashMap<Vec<u8>, u32>,
 }
 
 impl Default for TestSigners {
@@ -93,6 +115,8 @@ impl Default for TestSigners {
             signer_keys,
             aggregate_public_key,
             cycle: 0,
+            participation: HashMap::new(),
+            signer_weights: HashMap::new(),
         }
     }
 }
@@ -114,9 +138,106 @@ impl TestSigners {
             signer_keys,
             aggregate_public_key,
             cycle: 0,
+            participation: HashMap::new(),
+            signer_weights: HashMap::new(),
+        }
+    }
+
+    /// Set how `signer_key` responds to signing requests
+    pub fn with_participation(
+        mut self,
+        signer_key: &Secp256k1PrivateKey,
+        participation: SignerParticipation,
+    ) -> Self {
+        self.participation.insert(
+            Secp256k1PublicKey::from_private(signer_key).to_bytes_compressed(),
+            participation,
+        );
+        self
+    }
+
+    /// Set the weight of `signer_key` in [`Self::synthesize_reward_set()`]
+    pub fn with_signer_weight(mut self, signer_key: &Secp256k1PrivateKey, weight: u32) -> Self {
+        self.signer_weights.insert(
+            Secp256k1PublicKey::from_private(signer_key).to_bytes_compressed(),
+            weight,
+        );
+        self
+    }
+
+    /// The weight of `signer_key` in [`Self::synthesize_reward_set()`]
+    pub fn signer_weight(&self, signer_key: &Secp256k1PrivateKey) -> u32 {
+        self.signer_weights
+            .get(&Secp256k1PublicKey::from_private(signer_key).to_bytes_compressed())
+            .copied()
+            .unwrap_or(1)
+    }
+
+    /// The signing weight a block needs to be accepted by the signers in
+    /// [`Self::synthesize_reward_set()`]
+    pub fn weight_threshold(&self) -> u32 {
+        let total_weight = self
+            .signer_keys
+            .iter()
+            .map(|key| self.signer_weight(key))
+            .fold(0u32, |total, weight| {
+                total
+                    .checked_add(weight)
+                    .expect("FATAL: overflow while computing signer set weight")
+            });
+        NakamotoBlockHeader::compute_voting_weight_threshold(total_weight)
+            .expect("FATAL: overflow while computing signer set threshold")
+    }
+
+    /// Whether `signer_key` signs the block with the given signer sighash, and if so, how long
+    /// it waits before signing
+    fn signing_delay(
+        &self,
+        signer_key: &Secp256k1PrivateKey,
+        signer_sighash: &[u8],
+    ) -> Option<Duration> {
+        let public_key = Secp256k1PublicKey::from_private(signer_key).to_bytes_compressed();
+        match self.participation.get(&public_key) {
+            None | Some(SignerParticipation::AlwaysSign) => Some(Duration::ZERO),
+            Some(SignerParticipation::NeverSign) => None,
+            Some(SignerParticipation::SignWithProbability(probability)) => {
+                let mut seed = signer_sighash.to_vec();
+                seed.extend_from_slice(&public_key);
+                let mut rng = ChaCha20Rng::from_seed(Sha256Sum::from_data(&seed).0);
+                rng.gen_bool(*probability).then_some(Duration::ZERO)
+            }
+            Some(SignerParticipation::SignAfterDelay(delay)) => Some(*delay),
         }
     }
 
+    /// Sign `msg` with each of `keys` whose participation policy has it sign `msg`, in order.
+    /// Returns once the slowest participating signer has signed.
+    fn sign_with_participating_keys<'a>(
+        &self,
+        keys: impl Iterator<Item = &'a Secp256k1PrivateKey>,
+        msg: &[u8],
+    ) -> Vec<MessageSignature> {
+        let mut max_delay = Duration::ZERO;
+        let mut num_declined = 0;
+        let mut signatures = vec![];
+        for key in keys {
+            let Some(delay) = self.signing_delay(key, msg) else {
+                num_declined += 1;
+                continue;
+            };
+            max_delay = max_delay.max(delay);
+            signatures.push(key.sign(msg).unwrap());
+        }
+        if num_declined > 0 {
+            info!("TestSigners: {num_declined} signers declined to sign");
+        }
+        if !max_delay.is_zero() {
+            info!("TestSigners: waiting {max_delay:?} for delayed signers");
+            std::thread::sleep(max_delay);
+        }
+        signatures
+    }
+
     /// Sign a Nakamoto block using [`Self::signer_keys`].
     ///
     /// N.B. If any of [`Self::signer_keys`] are not in the reward set, the resulting
@@ -162,7 +283,7 @@ impl TestSigners {
             let nakamoto_signer_entry = NakamotoSignerEntry {
                 signing_key,
                 stacked_amt: 100_000_000_000,
-                weight: 1,
+                weight: self.signer_weight(key),
             };
             let pox_addr = PoxAddress::Standard(
                 StacksAddress::new(
@@ -197,7 +318,7 @@ impl TestSigners {
             let b = Secp256k1PublicKey::from_private(b).to_bytes_compressed();
             a.cmp(&b)
         });
-        keys.iter().map(|key| key.sign(&msg).unwrap()).collect()
+        self.sign_with_participating_keys(keys.iter(), &msg)
     }
 
     /// Generate an list of signatures for a block. Only
@@ -233,14 +354,13 @@ impl TestSigners {
             reward_set_keys.len(),
         );
 
-        let mut signatures = Vec::with_capacity(reward_set_keys.len());
+        let mut signer_keys = Vec::with_capacity(reward_set_keys.len());
 
         let mut missing_keys = 0;
 
         for key in reward_set_keys {
             if let Some(signer_key) = test_signers_by_pk.get(key) {
-                let signature = signer_key.sign(&msg).unwrap();
-                signatures.push(signature);
+                signer_keys.push(signer_key);
             } else {
                 missing_keys += 1;
             }
@@ -252,7 +372,7 @@ impl TestSigners {
             );
         }
 
-        signatures
+        self.sign_with_participating_keys(signer_keys.into_iter(), &msg)
     }
 
     // Generate and assign a new aggregate public key
@@ -17,6 +17,8 @@
 use std::borrow::BorrowMut;
 use std::collections::HashMap;
 use std::fs;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use clarity::types::chainstate::{PoxId, SortitionId, StacksBlockId};
 use clarity::util::secp256k1::Secp256k1PrivateKey;
@@ -60,13 +62,14 @@ use crate::chainstate::coordinator::tests::{
     pox_addr_from, setup_states_with_epochs,
 };
 use crate::chainstate::nakamoto::coordinator::tests::boot_nakamoto;
-use crate::chainstate::nakamoto::miner::NakamotoBlockBuilder;
+use crate::chainstate::nakamoto::miner::{CandidateStrategy, NakamotoBlockBuilder};
+use crate::chainstate::nakamoto::sigcheck::{NakamotoBlockSigChecks, NakamotoSigCheckJob};
 use crate::chainstate::nakamoto::signer_set::NakamotoSigners;
 use crate::chainstate::nakamoto::staging_blocks::{
     NakamotoBlockObtainMethod, NakamotoStagingBlocksConnRef,
 };
 use crate::chainstate::nakamoto::tenure::NakamotoTenureEvent;
-use crate::chainstate::nakamoto::test_signers::TestSigners;
+use crate::chainstate::nakamoto::test_signers::{SignerParticipation, TestSigners};
 use crate::chainstate::nakamoto::tests::node::TestStacker;
 use crate::chainstate::nakamoto::{
     query_row, NakamotoBlock, NakamotoBlockHeader, NakamotoChainState, SortitionHandle,
@@ -87,6 +90,7 @@ use crate::chainstate::stacks::{
     TransactionPostConditionMode, TransactionSmartContract, TransactionVersion,
 };
 use crate::core;
+use crate::core::mempool::MemPoolWalkSettings;
 use crate::core::{StacksEpochExtension, STACKS_EPOCH_3_0_MARKER};
 use crate::net::codec::test::check_codec_and_corruption;
 use crate::net::stackerdb::MINER_SLOT_COUNT;
@@ -1963,6 +1967,42 @@ fn test_nakamoto_block_static_verification() {
         .check_normal_coinbase_tx(&vrf_alt_pubkey, &sortition_hash)
         .is_err());
 
+    // pre-validated signature checks agree with the inline checks
+    let sig_check_jobs = [
+        NakamotoSigCheckJob {
+            block: &nakamoto_block,
+            reward_set: None,
+            vrf_inputs: Some((vrf_pubkey.clone(), sortition_hash.clone())),
+        },
+        NakamotoSigCheckJob {
+            block: &nakamoto_block_bad_miner_sig,
+            reward_set: None,
+            vrf_inputs: Some((vrf_alt_pubkey.clone(), sortition_hash.clone())),
+        },
+    ];
+    let sig_checks = NakamotoBlockSigChecks::compute_batch(&sig_check_jobs, 2);
+    assert_eq!(sig_checks.len(), 2);
+    assert_eq!(
+        sig_checks[&nakamoto_block.block_id()],
+        NakamotoBlockSigChecks::compute(&sig_check_jobs[0])
+    );
+
+    let block_checks = &sig_checks[&nakamoto_block.block_id()];
+    assert!(block_checks.miner_signature_matches(&tenure_change_payload.pubkey_hash));
+    assert!(
+        !block_checks.miner_signature_matches(&Hash160::from_node_public_key(
+            &StacksPublicKey::from_private(&private_key_2)
+        ))
+    );
+    assert!(block_checks.vrf_proof_verified(&vrf_pubkey, &sortition_hash));
+    assert!(!block_checks.vrf_proof_verified(&vrf_pubkey, &SortitionHash([0x02; 32])));
+    assert!(!block_checks.vrf_proof_verified(&vrf_alt_pubkey, &sortition_hash));
+    assert_eq!(block_checks.signer_weight_for(0), None);
+
+    // a VRF proof which doesn't verify is not recorded
+    let bad_block_checks = &sig_checks[&nakamoto_block_bad_miner_sig.block_id()];
+    assert!(bad_block_checks.vrf_verified.is_none());
+
     let mut shadow_block = nakamoto_shadow_recipient_block.clone();
     shadow_block.header.version |= 0x80;
 
@@ -3041,6 +3081,40 @@ fn filter_one_transaction_per_signer_duplicate_nonces() {
     assert!(filtered_txs.contains(txs.first().expect("failed to get first tx")));
 }
 
+#[test]
+fn candidate_strategies_vary_mempool_walk() {
+    // the first candidate always walks the mempool as configured
+    assert_eq!(
+        CandidateStrategy::for_candidate(0),
+        CandidateStrategy::Configured
+    );
+    let mut configured = MemPoolWalkSettings::default();
+    configured.consider_no_estimate_tx_prob = 25;
+    let mut settings = configured.clone();
+    CandidateStrategy::for_candidate(0).apply(&mut settings);
+    assert_eq!(settings.consider_no_estimate_tx_prob, 25);
+
+    // later candidates walk it differently
+    let probs: Vec<_> = (1..4)
+        .map(|index| {
+            let mut settings = configured.clone();
+            CandidateStrategy::for_candidate(index).apply(&mut settings);
+            settings.consider_no_estimate_tx_prob
+        })
+        .collect();
+    assert_eq!(probs, vec![0, 100, 50]);
+
+    // and strategies repeat past the last one
+    assert_eq!(
+        CandidateStrategy::for_candidate(4),
+        CandidateStrategy::Configured
+    );
+    assert_eq!(
+        CandidateStrategy::for_candidate(5),
+        CandidateStrategy::FeeRateFirst
+    );
+}
+
 pub mod nakamoto_block_signatures {
     use super::*;
 
@@ -3427,4 +3501,331 @@ pub mod nakamoto_block_signatures {
             358_u32,
         );
     }
+
+    #[test]
+    /// Test that a weighted test signer set signs a block with exactly the threshold weight,
+    /// and fails to sign it when one more signer declines
+    pub fn test_test_signers_weighted_partial_participation() {
+        let signer_keys: Vec<_> = (0..4).map(|_| Secp256k1PrivateKey::random()).collect();
+        let signers = TestSigners::new(signer_keys.clone())
+            .with_signer_weight(&signer_keys[0], 35)
+            .with_signer_weight(&signer_keys[1], 35)
+            .with_signer_weight(&signer_keys[2], 20)
+            .with_signer_weight(&signer_keys[3], 10)
+            .with_participation(&signer_keys[2], SignerParticipation::NeverSign)
+            .with_participation(&signer_keys[3], SignerParticipation::NeverSign);
+        let reward_set = signers.synthesize_reward_set();
+        assert_eq!(signers.weight_threshold(), 70);
+
+        let mut block = NakamotoBlock {
+            header: NakamotoBlockHeader::empty(),
+            txs: vec![],
+        };
+        signers.sign_block_with_reward_set(&mut block, &reward_set);
+        assert_eq!(block.header.signer_signature.len(), 2);
+        assert_eq!(
+            block.header.verify_signer_signatures(&reward_set).unwrap(),
+            70
+        );
+
+        let signers = signers.with_participation(&signer_keys[1], SignerParticipation::NeverSign);
+        signers.sign_block_with_reward_set(&mut block, &reward_set);
+        assert_eq!(block.header.signer_signature.len(), 1);
+        match block.header.verify_signer_signatures(&reward_set) {
+            Ok(_) => panic!("Expected insufficient signatures to fail"),
+            Err(ChainstateError::InvalidStacksBlock(msg)) => {
+                assert!(msg.contains("Not enough signatures"));
+            }
+            _ => panic!("Expected InvalidStacksBlock error"),
+        }
+    }
+
+    #[test]
+    /// Test that probabilistic signers are reproducible, and that delayed signers sign
+    pub fn test_test_signers_probabilistic_and_delayed_participation() {
+        let signer_keys: Vec<_> = (0..10).map(|_| Secp256k1PrivateKey::random()).collect();
+        let signers =
+            signer_keys
+                .iter()
+                .fold(TestSigners::new(signer_keys.clone()), |signers, key| {
+                    signers.with_participation(key, SignerParticipation::SignWithProbability(0.5))
+                });
+        let reward_set = signers.synthesize_reward_set();
+        let mut block = NakamotoBlock {
+            header: NakamotoBlockHeader::empty(),
+            txs: vec![],
+        };
+        signers.sign_block_with_reward_set(&mut block, &reward_set);
+        let first_signatures = block.header.signer_signature.clone();
+        signers.sign_block_with_reward_set(&mut block, &reward_set);
+        assert_eq!(block.header.signer_signature, first_signatures);
+
+        let signers = signer_keys.iter().fold(signers, |signers, key| {
+            signers.with_participation(
+                key,
+                SignerParticipation::SignAfterDelay(Duration::from_millis(10)),
+            )
+        });
+        let start = Instant::now();
+        signers.sign_block_with_reward_set(&mut block, &reward_set);
+        assert!(start.elapsed() >= Duration::from_millis(10));
+        assert_eq!(
+            block.header.verify_signer_signatures(&reward_set).unwrap(),
+            10
+        );
+    }
+
+    #[test]
+    /// Test that a large signature vector is verified as a batch, against cached signer keys
+    fn test_verify_signer_signatures_of_cycle() {
+        // far from any reward cycle other tests cache signer keys for
+        let reward_cycle = u64::MAX - 1;
+        let signers: Vec<_> = (0..40)
+            .map(|_| (Secp256k1PrivateKey::random(), 10))
+            .collect();
+        let reward_set = make_reward_set(&signers);
+
+        let mut header = NakamotoBlockHeader::empty();
+        let message = header.signer_signature_hash().0;
+        header.signer_signature = signers
+            .iter()
+            .take(30)
+            .map(|(s, _)| s.sign(&message).expect("Failed to sign block sighash"))
+            .collect();
+
+        assert_eq!(header.verify_signer_signatures(&reward_set).unwrap(), 300);
+        assert_eq!(
+            header
+                .verify_signer_signatures_of_cycle(reward_cycle, &reward_set)
+                .unwrap(),
+            300
+        );
+        let keys =
+            NakamotoChainState::get_reward_set_signer_keys(reward_cycle, &reward_set).unwrap();
+        assert!(Arc::ptr_eq(
+            &keys,
+            &NakamotoChainState::get_reward_set_signer_keys(reward_cycle, &reward_set).unwrap()
+        ));
+        assert_eq!(keys.total_weight(), 400);
+        assert_eq!(keys.threshold(), 280);
+
+        // a signature that does not recover is found wherever it is in the batch
+        let mut bad_header = header.clone();
+        bad_header.signer_signature[25].0[0] = 0x02;
+        match bad_header.verify_signer_signatures_of_cycle(reward_cycle, &reward_set) {
+            Err(ChainstateError::InvalidStacksBlock(msg)) => {
+                assert!(msg.contains("Unable to recover public key"), "{msg}");
+            }
+            x => panic!("Expected InvalidStacksBlock error, got {x:?}"),
+        }
+
+        // another reward set for the same cycle replaces the cached keys
+        let other_reward_set = make_reward_set(&signers[..35]);
+        assert_eq!(
+            header
+                .verify_signer_signatures_of_cycle(reward_cycle, &other_reward_set)
+                .unwrap(),
+            300
+        );
+        let other_keys =
+            NakamotoChainState::get_reward_set_signer_keys(reward_cycle, &other_reward_set)
+                .unwrap();
+        assert!(other_keys.is_for(&other_reward_set));
+        assert!(!other_keys.is_for(&reward_set));
+        assert_eq!(other_keys.total_weight(), 350);
+    }
+
+    #[test]
+    /// Test that a signature vector longer than the reward set is rejected before any key is
+    /// recovered from it
+    fn test_verify_signer_signatures_oversized() {
+        let signers: Vec<_> = (0..3)
+            .map(|_| (Secp256k1PrivateKey::random(), 10))
+            .collect();
+        let reward_set = make_reward_set(&signers);
+
+        let mut header = NakamotoBlockHeader::empty();
+        let message = header.signer_signature_hash().0;
+        header.signer_signature = signers
+            .iter()
+            .map(|(s, _)| s.sign(&message).expect("Failed to sign block sighash"))
+            .collect();
+        assert_eq!(header.verify_signer_signatures(&reward_set).unwrap(), 30);
+
+        // a single extra signature, even one that doesn't recover, rejects the whole vector
+        header.signer_signature.push(MessageSignature::empty());
+        match header.verify_signer_signatures(&reward_set) {
+            Err(ChainstateError::InvalidStacksBlock(msg)) => {
+                assert!(msg.contains("Too many signatures"), "{msg}");
+            }
+            x => panic!("Expected InvalidStacksBlock error, got {x:?}"),
+        }
+
+        // and so does a huge one
+        header.signer_signature = vec![MessageSignature::empty(); 4096];
+        match header.verify_signer_signatures(&reward_set) {
+            Err(ChainstateError::InvalidStacksBlock(msg)) => {
+                assert!(msg.contains("Too many signatures"), "{msg}");
+            }
+            x => panic!("Expected InvalidStacksBlock error, got {x:?}"),
+        }
+    }
+}
+
+pub mod chain_checkpoints {
+    use super::*;
+    use crate::chainstate::nakamoto::checkpoints::{
+        ChainCheckpointHints, EndorsedCheckpoint, SignedChainCheckpoint,
+    };
+    use crate::chainstate::nakamoto::sigcheck::RewardSetSignerKeys;
+
+    fn make_reward_set(signers: &[(Secp256k1PrivateKey, u32)]) -> RewardSet {
+        let mut reward_set = RewardSet::empty();
+        reward_set.signers = Some(
+            signers
+                .iter()
+                .map(|(s, w)| NakamotoSignerEntry {
+                    signing_key: Secp256k1PublicKey::from_private(s)
+                        .to_bytes_compressed()
+                        .try_into()
+                        .unwrap(),
+                    stacked_amt: 100_u128,
+                    weight: *w,
+                })
+                .collect(),
+        );
+        reward_set
+    }
+
+    fn checkpoint_chunk(
+        signer: &Secp256k1PrivateKey,
+        chain_id: u32,
+        reward_cycle: u64,
+        block_height: u64,
+        block_id: StacksBlockId,
+    ) -> Option<Vec<u8>> {
+        Some(
+            SignedChainCheckpoint::new_signed(
+                chain_id,
+                reward_cycle,
+                block_height,
+                block_id,
+                signer,
+            )
+            .unwrap()
+            .serialize_to_vec(),
+        )
+    }
+
+    #[test]
+    fn test_checkpoint_codec_and_signature() {
+        let signer = Secp256k1PrivateKey::random();
+        let checkpoint = SignedChainCheckpoint::new_signed(
+            0x80000000,
+            12,
+            3456,
+            StacksBlockId([0x11; 32]),
+            &signer,
+        )
+        .unwrap();
+
+        let bytes = checkpoint.serialize_to_vec();
+        assert_eq!(bytes.len(), 117);
+        check_codec_and_corruption(&checkpoint, &bytes);
+
+        assert_eq!(
+            checkpoint.recover_signer_key().unwrap(),
+            Secp256k1PublicKey::from_private(&signer)
+        );
+
+        // the signature covers every field
+        let mut tampered = checkpoint.clone();
+        tampered.block_height += 1;
+        assert_ne!(
+            tampered.recover_signer_key().ok(),
+            Some(Secp256k1PublicKey::from_private(&signer))
+        );
+        let mut tampered = checkpoint;
+        tampered.chain_id = 1;
+        assert_ne!(
+            tampered.recover_signer_key().ok(),
+            Some(Secp256k1PublicKey::from_private(&signer))
+        );
+    }
+
+    #[test]
+    fn test_tally_checkpoints() {
+        let signers = [
+            (Secp256k1PrivateKey::random(), 35),
+            (Secp256k1PrivateKey::random(), 35),
+            (Secp256k1PrivateKey::random(), 20),
+            (Secp256k1PrivateKey::random(), 10),
+        ];
+        let outsider = Secp256k1PrivateKey::random();
+        let reward_set = make_reward_set(&signers);
+        let signer_keys = RewardSetSignerKeys::new(&reward_set).unwrap();
+        let chain_id = 0x80000000;
+
+        // 70% of the weight endorses the block at height 100, and 90% the one at 50
+        let chunks = vec![
+            checkpoint_chunk(&signers[0].0, chain_id, 7, 100, StacksBlockId([1; 32])),
+            checkpoint_chunk(&signers[1].0, chain_id, 7, 100, StacksBlockId([1; 32])),
+            checkpoint_chunk(&signers[2].0, chain_id, 7, 50, StacksBlockId([2; 32])),
+            None,
+            // not in the reward set
+            checkpoint_chunk(&outsider, chain_id, 7, 50, StacksBlockId([2; 32])),
+            // wrong reward cycle, or wrong chain
+            checkpoint_chunk(&signers[3].0, chain_id, 6, 50, StacksBlockId([2; 32])),
+            checkpoint_chunk(&signers[3].0, 1, 7, 50, StacksBlockId([2; 32])),
+            Some(vec![0xff; 4]),
+        ];
+        let hints = ChainCheckpointHints::tally(chain_id, 7, &signer_keys, &chunks);
+        assert_eq!(
+            hints,
+            ChainCheckpointHints {
+                reward_cycle: 7,
+                checkpoints: vec![EndorsedCheckpoint {
+                    block_height: 100,
+                    block_id: StacksBlockId([1; 32]),
+                    weight: 70,
+                }],
+            }
+        );
+
+        // a block endorsed by less than the threshold is not a hint
+        let chunks = vec![
+            checkpoint_chunk(&signers[0].0, chain_id, 7, 100, StacksBlockId([1; 32])),
+            checkpoint_chunk(&signers[1].0, chain_id, 7, 100, StacksBlockId([3; 32])),
+            checkpoint_chunk(&signers[2].0, chain_id, 7, 100, StacksBlockId([1; 32])),
+        ];
+        let hints = ChainCheckpointHints::tally(chain_id, 7, &signer_keys, &chunks);
+        assert!(hints.checkpoints.is_empty());
+    }
+
+    #[test]
+    fn test_make_checkpoints_stackerdb_config() {
+        let signers = [
+            (Secp256k1PrivateKey::random(), 1),
+            (Secp256k1PrivateKey::random(), 2),
+        ];
+        let reward_set = make_reward_set(&signers);
+        let config =
+            NakamotoChainState::make_checkpoints_stackerdb_config(&reward_set, false).unwrap();
+        assert_eq!(
+            config.signers,
+            signers
+                .iter()
+                .map(|(s, _)| (
+                    StacksAddress::p2pkh(false, &Secp256k1PublicKey::from_private(s)),
+                    1
+                ))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(config.num_slots(), 2);
+
+        assert!(
+            NakamotoChainState::make_checkpoints_stackerdb_config(&RewardSet::empty(), false)
+                .is_err()
+        );
+    }
 }
@@ -15,10 +15,11 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::{HashMap, HashSet};
-use std::io;
 use std::io::prelude::*;
 use std::io::{Read, Write};
+use std::{fmt, io};
 
+use clarity::vm::ClarityVersion;
 use sha2::{Digest, Sha512_256};
 use stacks_common::codec::{
     read_next, read_next_at_most, write_next, Error as codec_error, StacksMessageCodec,
@@ -375,6 +376,80 @@ impl StacksMessageCodec for StacksBlock {
     }
 }
 
+/// Why a transaction cannot be mined in a particular epoch
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionEpochError {
+    /// Coinbases with VRF proofs require Stacks 3.0
+    CoinbaseProofNotSupported,
+    /// Coinbases must have VRF proofs in Stacks 3.0 and later
+    CoinbaseProofRequired,
+    /// Coinbases that pay an alternative recipient require Stacks 2.1
+    CoinbaseAltRecipientNotSupported,
+    /// Smart contracts that name their Clarity version require Stacks 2.1
+    VersionedSmartContractNotSupported,
+    /// The smart contract asks for a Clarity version that is newer than the epoch's
+    ClarityVersionNotSupported(ClarityVersion),
+    /// Tenure changes require Stacks 3.0
+    TenureChangeNotSupported,
+    /// The origin or sponsor uses an authentication mode that the epoch does not support
+    AuthModeNotSupported,
+}
+
+impl TransactionEpochError {
+    /// Machine-readable name of the rule that the transaction breaks
+    pub fn reason_code(&self) -> &'static str {
+        match self {
+            Self::CoinbaseProofNotSupported => "CoinbaseProofNotSupported",
+            Self::CoinbaseProofRequired => "CoinbaseProofRequired",
+            Self::CoinbaseAltRecipientNotSupported => "CoinbaseAltRecipientNotSupported",
+            Self::VersionedSmartContractNotSupported => "VersionedSmartContractNotSupported",
+            Self::ClarityVersionNotSupported(_) => "ClarityVersionNotSupported",
+            Self::TenureChangeNotSupported => "TenureChangeNotSupported",
+            Self::AuthModeNotSupported => "AuthModeNotSupported",
+        }
+    }
+}
+
+impl fmt::Display for TransactionEpochError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::CoinbaseProofNotSupported => {
+                write!(f, "Coinbase with VRF proof not supported before Stacks 3.0")
+            }
+            Self::CoinbaseProofRequired => {
+                write!(
+                    f,
+                    "Coinbase with VRF proof is required in Stacks 3.0 and later"
+                )
+            }
+            Self::CoinbaseAltRecipientNotSupported => {
+                write!(
+                    f,
+                    "Coinbase pay-to-alt-recipient not supported before Stacks 2.1"
+                )
+            }
+            Self::VersionedSmartContractNotSupported => {
+                write!(
+                    f,
+                    "Versioned smart contracts not supported before Stacks 2.1"
+                )
+            }
+            Self::ClarityVersionNotSupported(version) => {
+                write!(f, "{version} is not supported in this epoch")
+            }
+            Self::TenureChangeNotSupported => {
+                write!(
+                    f,
+                    "TenureChange transaction not supported before Stacks 3.0"
+                )
+            }
+            Self::AuthModeNotSupported => {
+                write!(f, "Authentication mode not supported in this epoch")
+            }
+        }
+    }
+}
+
 impl StacksBlock {
     pub fn from_parent(
         parent_header: &StacksBlockHeader,
@@ -573,41 +648,44 @@ impl StacksBlock {
         tx: &StacksTransaction,
         epoch_id: StacksEpochId,
     ) -> bool {
+        if let Err(e) = StacksBlock::check_transaction_static_epoch(tx, epoch_id) {
+            error!("Transaction not supported in Epoch {epoch_id}: {e}"; "txid" => %tx.txid());
+            return false;
+        }
+        return true;
+    }
+
+    /// Check that one transaction is supported in the given epoch, as indicated by `epoch_id`,
+    /// and if not, say why.
+    pub fn check_transaction_static_epoch(
+        tx: &StacksTransaction,
+        epoch_id: StacksEpochId,
+    ) -> Result<(), TransactionEpochError> {
         if let TransactionPayload::Coinbase(_, ref recipient_opt, ref proof_opt) = &tx.payload {
             if proof_opt.is_some() && epoch_id < StacksEpochId::Epoch30 {
-                // not supported
-                error!("Coinbase with VRF proof not supported before Stacks 3.0"; "txid" => %tx.txid());
-                return false;
+                return Err(TransactionEpochError::CoinbaseProofNotSupported);
             }
             if proof_opt.is_none() && epoch_id >= StacksEpochId::Epoch30 {
-                // not supported
-                error!("Coinbase with VRF proof is required in Stacks 3.0 and later"; "txid" => %tx.txid());
-                return false;
+                return Err(TransactionEpochError::CoinbaseProofRequired);
             }
             if recipient_opt.is_some() && epoch_id < StacksEpochId::Epoch21 {
-                // not supported
-                error!("Coinbase pay-to-alt-recipient not supported before Stacks 2.1"; "txid" => %tx.txid());
-                return false;
+                return Err(TransactionEpochError::CoinbaseAltRecipientNotSupported);
             }
         }
         if let TransactionPayload::SmartContract(_, ref version_opt) = &tx.payload {
             if version_opt.is_some() && epoch_id < StacksEpochId::Epoch21 {
-                // not supported
-                error!("Versioned smart contracts not supported before Stacks 2.1");
-                return false;
+                return Err(TransactionEpochError::VersionedSmartContractNotSupported);
             }
         }
         if let TransactionPayload::TenureChange(..) = &tx.payload {
             if epoch_id < StacksEpochId::Epoch30 {
-                error!("TenureChange transaction not supported before Stacks 3.0"; "txid" => %tx.txid());
-                return false;
+                return Err(TransactionEpochError::TenureChangeNotSupported);
             }
         }
         if !tx.auth.is_supported_in_epoch(epoch_id) {
-            error!("Authentication mode not supported in Epoch {epoch_id}");
-            return false;
+            return Err(TransactionEpochError::AuthModeNotSupported);
         }
-        return true;
+        Ok(())
     }
 
     /// static sanity checks on transactions.
@@ -2037,6 +2115,52 @@ mod test {
         assert!(!StacksBlock::validate_anchor_mode(&offchain_txs, true));
         assert!(!StacksBlock::validate_coinbase(&no_coinbase, true));
 
+        // each epoch rule has its own rejection reason
+        for (tx, epoch_id, expected) in [
+            (
+                &versioned_contract[0],
+                StacksEpochId::Epoch2_05,
+                TransactionEpochError::VersionedSmartContractNotSupported,
+            ),
+            (
+                &coinbase_contract[0],
+                StacksEpochId::Epoch2_05,
+                TransactionEpochError::CoinbaseAltRecipientNotSupported,
+            ),
+            (
+                &coinbase_contract[0],
+                StacksEpochId::Epoch30,
+                TransactionEpochError::CoinbaseProofRequired,
+            ),
+            (
+                &nakamoto_coinbase[0],
+                StacksEpochId::Epoch25,
+                TransactionEpochError::CoinbaseProofNotSupported,
+            ),
+            (
+                &tenure_change_tx[0],
+                StacksEpochId::Epoch25,
+                TransactionEpochError::TenureChangeNotSupported,
+            ),
+            (
+                &order_independent_multisig_txs[0],
+                StacksEpochId::Epoch25,
+                TransactionEpochError::AuthModeNotSupported,
+            ),
+        ] {
+            assert_eq!(
+                StacksBlock::check_transaction_static_epoch(tx, epoch_id),
+                Err(expected)
+            );
+        }
+        assert_eq!(
+            StacksBlock::check_transaction_static_epoch(
+                &versioned_contract[0],
+                StacksEpochId::Epoch21
+            ),
+            Ok(())
+        );
+
         verify_block_epoch_validation(
             &versioned_contract,
             Some(tx_coinbase.clone()),
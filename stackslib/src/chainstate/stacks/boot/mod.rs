@@ -103,6 +103,8 @@ pub const BOOT_TEST_POX_4_AGG_KEY_CONTRACT: &str = "pox-4-agg-test-booter";
 pub const BOOT_TEST_POX_4_AGG_KEY_FNAME: &str = "aggregate-key";
 
 pub const MINERS_NAME: &str = "miners";
+/// The boot StackerDB to which signers write signed chain checkpoints
+pub const CHECKPOINTS_NAME: &str = "checkpoints";
 
 pub mod docs;
 
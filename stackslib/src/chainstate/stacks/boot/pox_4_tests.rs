@@ -40,14 +40,14 @@ use stacks_common::address::AddressHashMode;
 use stacks_common::types::chainstate::{
     BlockHeaderHash, BurnchainHeaderHash, StacksAddress, StacksBlockId, VRFSeed,
 };
-use stacks_common::types::{Address, PrivateKey};
+use stacks_common::types::{Address, PrivateKey, StacksPublicKeyBuffer};
 use stacks_common::util::hash::{hex_bytes, to_hex, Sha256Sum, Sha512Trunc256Sum};
 use stacks_common::util::secp256k1::{Secp256k1PrivateKey, Secp256k1PublicKey};
 use stdext::num::integer::Integer;
 
 use super::test::*;
 use super::RawRewardSetEntry;
-use crate::burnchains::{Burnchain, PoxConstants};
+use crate::burnchains::{Burnchain, PoxConstants, Txid};
 use crate::chainstate::burn::db::sortdb::{SortitionDB, SortitionHandle};
 use crate::chainstate::burn::operations::*;
 use crate::chainstate::burn::{BlockSnapshot, ConsensusHash};
@@ -5533,6 +5533,247 @@ fn stack_agg_commit_signer_auth(use_nakamoto: bool) {
         .expect("Expected ok result from stack-agg-commit tx");
 }
 
+#[test]
+/// Test that a `stack-aggregation-commit` burnchain op is ignored before epoch 3.1, and that in
+/// epoch 3.1 it commits the delegate's partially-stacked STX to the reward set
+fn stack_agg_commit_burn_op() {
+    let observer = TestEventObserver::new();
+
+    let boot_stacker_key = StacksPrivateKey::from_seed(&[3]);
+    let stacker_key = StacksPrivateKey::from_seed(&[10]);
+    let stacker_addr = PrincipalData::from(key_to_stacks_addr(&stacker_key));
+    let signer_sk = StacksPrivateKey::from_seed(&[11]);
+    let signer_pk = StacksPublicKey::from_private(&signer_sk);
+    let delegate_key = StacksPrivateKey::from_seed(&[12]);
+    let delegate_addr = key_to_stacks_addr(&delegate_key);
+    let pox_addr = pox_addr_from(&delegate_key);
+
+    let mut pox_constants = TestPeerConfig::default().burnchain.pox_constants;
+    pox_constants.reward_cycle_length = 10;
+    pox_constants.v2_unlock_height = 21;
+    pox_constants.pox_3_activation_height = 26;
+    pox_constants.v3_unlock_height = 27;
+    pox_constants.pox_4_activation_height = 41;
+    pox_constants.prepare_length = 5;
+
+    // the peer boots into epoch 3.0 at burn height 52, then reaches 3.1 after two tenures
+    let epoch_3_0_height =
+        u64::from(pox_constants.pox_4_activation_height + pox_constants.reward_cycle_length + 1);
+    let mut epochs = StacksEpoch::unit_test_3_0_only(epoch_3_0_height).to_vec();
+    let mut epoch_3_0 = epochs.pop().unwrap();
+    epoch_3_0.end_height = epoch_3_0_height + 2;
+    let mut epoch_3_1 = epoch_3_0.clone();
+    epoch_3_1.epoch_id = StacksEpochId::Epoch31;
+    epoch_3_1.start_height = epoch_3_0_height + 2;
+    epoch_3_1.end_height = STACKS_EPOCH_MAX;
+    epoch_3_1.network_epoch = PEER_VERSION_EPOCH_3_1;
+    epochs.extend([epoch_3_0, epoch_3_1]);
+    let epochs = EpochList::new(&epochs);
+
+    let test_stackers = vec![TestStacker {
+        signer_private_key: boot_stacker_key.clone(),
+        stacker_private_key: boot_stacker_key.clone(),
+        amount: 1024 * POX_THRESHOLD_STEPS_USTX,
+        pox_addr: Some(pox_addr_from(&boot_stacker_key)),
+        max_amount: None,
+    }];
+    let test_signers = TestSigners::new(vec![boot_stacker_key.clone()]);
+    let mut boot_plan = NakamotoBootPlan::new(function_name!())
+        .with_test_stackers(test_stackers)
+        .with_test_signers(test_signers.clone())
+        .with_private_key(StacksPrivateKey::from_seed(&[2]))
+        .with_epochs(epochs.clone());
+    boot_plan.add_default_balance = false;
+    boot_plan.initial_balances = [&boot_stacker_key, &stacker_key, &signer_sk, &delegate_key]
+        .into_iter()
+        .map(|key| {
+            (
+                key_to_stacks_addr(key).into(),
+                (1024 * POX_THRESHOLD_STEPS_USTX) as u64,
+            )
+        })
+        .collect();
+    boot_plan.pox_constants = pox_constants.clone();
+    let mut burnchain = Burnchain::default_unittest(
+        0,
+        &BurnchainHeaderHash::from_hex(BITCOIN_REGTEST_FIRST_BLOCK_HASH).unwrap(),
+    );
+    burnchain.pox_constants = pox_constants;
+
+    let mut peer = boot_plan.boot_into_nakamoto_peer(vec![], Some(&observer));
+    let mut test_signers = Some(test_signers);
+    let latest_block = peer
+        .sortdb()
+        .index_handle_at_tip()
+        .get_nakamoto_tip_block_id()
+        .unwrap()
+        .unwrap();
+    let block_height = get_tip(peer.sortdb.as_ref()).block_height;
+    let min_ustx = get_stacking_minimum(&mut peer, &latest_block);
+
+    let lock_period = 3;
+    let reward_cycle = burnchain
+        .block_height_to_reward_cycle(block_height)
+        .unwrap();
+    let commit_cycle = reward_cycle + 2;
+    let auth_id = 1;
+
+    // Setup: delegate-stx, delegate-stack-stx, and the signer's authorization of the commit
+    let delegate_tx = make_pox_4_delegate_stx(
+        &stacker_key,
+        0,
+        min_ustx,
+        delegate_addr.clone().into(),
+        None,
+        None,
+    );
+    let delegate_stack_stx_tx = make_pox_4_delegate_stack_stx(
+        &delegate_key,
+        0,
+        stacker_addr,
+        min_ustx,
+        pox_addr.clone(),
+        block_height.into(),
+        lock_period,
+    );
+    let enable_auth_tx = make_pox_4_set_signer_key_auth(
+        &pox_addr,
+        &signer_sk,
+        commit_cycle.into(),
+        &Pox4SignatureTopic::AggregationCommit,
+        1,
+        true,
+        0,
+        None,
+        u128::MAX,
+        auth_id.into(),
+    );
+    let latest_block = tenure_with_txs(
+        &mut peer,
+        &[delegate_tx, delegate_stack_stx_tx, enable_auth_tx],
+        &mut 0,
+        &mut test_signers,
+    );
+    assert_eq!(
+        get_partially_stacked_state_pox_4(
+            &mut peer,
+            &latest_block,
+            &pox_addr,
+            commit_cycle,
+            &delegate_addr
+        ),
+        Some(min_ustx)
+    );
+
+    let make_agg_commit_op = |peer: &TestPeer, txid: Txid| {
+        BlockstackOperationType::StackAggregationCommit(StackAggregationCommitOp {
+            sender: delegate_addr.clone(),
+            reward_addr: pox_addr.clone(),
+            reward_cycle: commit_cycle,
+            signer_key: StacksPublicKeyBuffer::from_public_key(&signer_pk),
+            max_amount: u128::MAX,
+            auth_id,
+
+            // mocked
+            txid,
+            vtxindex: 11,
+            block_height: peer.get_burn_block_height() + 1,
+            burn_header_hash: BurnchainHeaderHash([0x00; 32]),
+        })
+    };
+
+    // In epoch 3.0, the op is ignored
+    let early_txid = Txid([0x01; 32]);
+    let early_op = make_agg_commit_op(&peer, early_txid.clone());
+    let latest_block = tenure_with_txs_and_burn_ops(
+        &mut peer,
+        &[],
+        vec![early_op],
+        test_signers.as_mut().unwrap(),
+    );
+    assert_eq!(
+        peer.get_burn_block_height(),
+        epochs[StacksEpochId::Epoch30].start_height + 1
+    );
+    assert_eq!(
+        get_partially_stacked_state_pox_4(
+            &mut peer,
+            &latest_block,
+            &pox_addr,
+            commit_cycle,
+            &delegate_addr
+        ),
+        Some(min_ustx)
+    );
+
+    // In epoch 3.1, the op commits the partially-stacked STX
+    let txid = Txid([0x02; 32]);
+    let op = make_agg_commit_op(&peer, txid.clone());
+    let latest_block =
+        tenure_with_txs_and_burn_ops(&mut peer, &[], vec![op], test_signers.as_mut().unwrap());
+    assert_eq!(
+        peer.get_burn_block_height(),
+        epochs[StacksEpochId::Epoch31].start_height
+    );
+
+    let burn_op_receipts: Vec<_> = observer
+        .get_blocks()
+        .into_iter()
+        .flat_map(|block| block.receipts)
+        .filter_map(|receipt| match &receipt.transaction {
+            TransactionOrigin::Burn(op) => Some((op.txid(), receipt.result.clone())),
+            TransactionOrigin::Stacks(_) => None,
+        })
+        .collect();
+    assert!(!burn_op_receipts
+        .iter()
+        .any(|(receipt_txid, _)| receipt_txid == &early_txid));
+    let (_, result) = burn_op_receipts
+        .iter()
+        .find(|(receipt_txid, _)| receipt_txid == &txid)
+        .expect("No receipt for the epoch 3.1 stack-aggregation-commit op");
+    result
+        .clone()
+        .expect_result_ok()
+        .expect("Expected ok result from the stack-aggregation-commit op");
+
+    // the partially-stacked STX for the committed cycle are now in its reward set
+    assert_eq!(
+        get_partially_stacked_state_pox_4(
+            &mut peer,
+            &latest_block,
+            &pox_addr,
+            commit_cycle,
+            &delegate_addr
+        ),
+        None
+    );
+    assert_eq!(
+        get_partially_stacked_state_pox_4(
+            &mut peer,
+            &latest_block,
+            &pox_addr,
+            commit_cycle - 1,
+            &delegate_addr
+        ),
+        Some(min_ustx)
+    );
+    let reward_set_entries = get_reward_set_entries_at(
+        &mut peer,
+        &latest_block,
+        burnchain.reward_cycle_to_block_height(commit_cycle),
+    );
+    let entry = reward_set_entries
+        .iter()
+        .find(|entry| entry.reward_address == pox_addr)
+        .expect("No reward set entry for the delegate's PoX address");
+    assert_eq!(entry.amount_stacked, min_ustx);
+    assert_eq!(
+        entry.signer,
+        Some(signer_pk.to_bytes_compressed().try_into().unwrap())
+    );
+}
+
 #[apply(nakamoto_cases)]
 /// Test `stack-extend` using signer key authorization
 /// instead of signatures
@@ -9296,48 +9537,60 @@ pub fn tenure_with_txs(
     test_signers: &mut Option<TestSigners>,
 ) -> StacksBlockId {
     if let Some(test_signers) = test_signers {
-        let (burn_ops, mut tenure_change, miner_key) =
-            peer.begin_nakamoto_tenure(TenureChangeCause::BlockFound);
-        let (_, _, consensus_hash) = peer.next_burnchain_block(burn_ops);
-        let vrf_proof = peer.make_nakamoto_vrf_proof(miner_key);
-
-        tenure_change.tenure_consensus_hash = consensus_hash.clone();
-        tenure_change.burn_view_consensus_hash = consensus_hash.clone();
-
-        let tenure_change_tx = peer.miner.make_nakamoto_tenure_change(tenure_change);
-        let coinbase_tx = peer.miner.make_nakamoto_coinbase(None, vrf_proof);
-
-        let blocks_and_sizes = peer.make_nakamoto_tenure(
-            tenure_change_tx,
-            coinbase_tx,
-            test_signers,
-            |_miner, _chainstate, _sort_dbconn, _blocks| {
-                info!("Building nakamoto block. Blocks len {}", _blocks.len());
-                if _blocks.is_empty() {
-                    txs.to_vec()
-                } else {
-                    vec![]
-                }
-            },
-        );
-        let blocks: Vec<_> = blocks_and_sizes
-            .into_iter()
-            .map(|(block, _, _)| block)
-            .collect();
-
-        let chainstate = &mut peer.stacks_node.as_mut().unwrap().chainstate;
-        let sort_db = peer.sortdb.as_mut().unwrap();
-        let latest_block = sort_db
-            .index_handle_at_tip()
-            .get_nakamoto_tip_block_id()
-            .unwrap()
-            .unwrap();
-        latest_block
+        tenure_with_txs_and_burn_ops(peer, txs, vec![], test_signers)
     } else {
         peer.tenure_with_txs(txs, coinbase_nonce)
     }
 }
 
+/// Mine a Nakamoto tenure whose sortition also carries `extra_burn_ops`, and whose first block
+/// contains `txs`
+pub fn tenure_with_txs_and_burn_ops(
+    peer: &mut TestPeer,
+    txs: &[StacksTransaction],
+    extra_burn_ops: Vec<BlockstackOperationType>,
+    test_signers: &mut TestSigners,
+) -> StacksBlockId {
+    let (mut burn_ops, mut tenure_change, miner_key) =
+        peer.begin_nakamoto_tenure(TenureChangeCause::BlockFound);
+    burn_ops.extend(extra_burn_ops);
+    let (_, _, consensus_hash) = peer.next_burnchain_block(burn_ops);
+    let vrf_proof = peer.make_nakamoto_vrf_proof(miner_key);
+
+    tenure_change.tenure_consensus_hash = consensus_hash.clone();
+    tenure_change.burn_view_consensus_hash = consensus_hash.clone();
+
+    let tenure_change_tx = peer.miner.make_nakamoto_tenure_change(tenure_change);
+    let coinbase_tx = peer.miner.make_nakamoto_coinbase(None, vrf_proof);
+
+    let blocks_and_sizes = peer.make_nakamoto_tenure(
+        tenure_change_tx,
+        coinbase_tx,
+        test_signers,
+        |_miner, _chainstate, _sort_dbconn, _blocks| {
+            info!("Building nakamoto block. Blocks len {}", _blocks.len());
+            if _blocks.is_empty() {
+                txs.to_vec()
+            } else {
+                vec![]
+            }
+        },
+    );
+    let blocks: Vec<_> = blocks_and_sizes
+        .into_iter()
+        .map(|(block, _, _)| block)
+        .collect();
+
+    let chainstate = &mut peer.stacks_node.as_mut().unwrap().chainstate;
+    let sort_db = peer.sortdb.as_mut().unwrap();
+    let latest_block = sort_db
+        .index_handle_at_tip()
+        .get_nakamoto_tip_block_id()
+        .unwrap()
+        .unwrap();
+    latest_block
+}
+
 pub fn get_last_block_sender_transactions(
     observer: &TestEventObserver,
     address: StacksAddress,
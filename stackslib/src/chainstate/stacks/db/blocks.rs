@@ -33,6 +33,7 @@ use clarity::vm::types::{
     StacksAddressExtensions as ClarityStacksAddressExtensions, StandardPrincipalData, TupleData,
     TypeSignature, Value,
 };
+use clarity::vm::ClarityVersion;
 use rand::{thread_rng, Rng, RngCore};
 use rusqlite::types::ToSql;
 use rusqlite::{
@@ -55,10 +56,12 @@ use crate::burnchains::db::{BurnchainDB, BurnchainHeaderReader};
 use crate::chainstate::burn::db::sortdb::*;
 use crate::chainstate::burn::operations::*;
 use crate::chainstate::burn::BlockSnapshot;
-use crate::chainstate::coordinator::BlockEventDispatcher;
+use crate::chainstate::coordinator::comm::PendingStacksReorg;
+use crate::chainstate::coordinator::{BlockEventDispatcher, RewardCycleInfo};
 use crate::chainstate::nakamoto::signer_set::{NakamotoSigners, SignerCalculation};
-use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::nakamoto::{NakamotoBlock, NakamotoChainState};
 use crate::chainstate::stacks::address::{PoxAddress, StacksAddressExtensions};
+use crate::chainstate::stacks::block::TransactionEpochError;
 use crate::chainstate::stacks::db::accounts::MinerReward;
 use crate::chainstate::stacks::db::transactions::TransactionNonceMismatch;
 use crate::chainstate::stacks::db::*;
@@ -71,9 +74,12 @@ use crate::chainstate::stacks::{
 };
 use crate::clarity_vm::clarity::{ClarityBlockConnection, ClarityConnection, ClarityInstance};
 use crate::clarity_vm::database::SortitionDBRef;
-use crate::core::mempool::{MemPoolDB, MAXIMUM_MEMPOOL_TX_CHAINING};
+use crate::core::mempool::{
+    MemPoolDB, MAXIMUM_MEMPOOL_SPONSOR_TX_CHAINING, MAXIMUM_MEMPOOL_TX_CHAINING,
+};
 use crate::core::*;
 use crate::cost_estimates::EstimatorError;
+use crate::monitoring::chain_quality::ChainQualityAlert;
 use crate::monitoring::{set_last_block_transaction_count, set_last_execution_cost_observed};
 use crate::net::relay::Relayer;
 use crate::net::{BlocksInvData, Error as net_error};
@@ -144,9 +150,16 @@ pub enum MemPoolRejection {
     BadTransactionVersion,
     TransferRecipientIsSender(PrincipalData),
     TransferAmountMustBePositive,
+    /// The transaction breaks a rule of the current epoch
+    NotSupportedInEpoch(StacksEpochId, TransactionEpochError),
     DBError(db_error),
     EstimatorError(EstimatorError),
     TemporarilyBlacklisted,
+    /// The node's mempool admission policy rejected the transaction
+    PolicyRejected {
+        policy: String,
+        reason: String,
+    },
     Other(String),
 }
 
@@ -166,6 +179,7 @@ pub struct SetupBlockResult<'a, 'b> {
     pub auto_unlock_events: Vec<StacksTransactionEvent>,
     pub burn_delegate_stx_ops: Vec<DelegateStxOp>,
     pub burn_vote_for_aggregate_key_ops: Vec<VoteForAggregateKeyOp>,
+    pub burn_stack_aggregation_commit_ops: Vec<StackAggregationCommitOp>,
     /// Result of a signer set calculation if one occurred
     pub signer_set_calc: Option<SignerCalculation>,
 }
@@ -199,6 +213,13 @@ impl BlockEventDispatcher for DummyEventDispatcher {
         );
     }
 
+    fn announce_raw_nakamoto_block(&self, _block: &NakamotoBlock) {
+        assert!(
+            false,
+            "We should never try to announce to the dummy dispatcher"
+        );
+    }
+
     fn announce_burn_block(
         &self,
         _burn_block: &BurnchainHeaderHash,
@@ -213,6 +234,34 @@ impl BlockEventDispatcher for DummyEventDispatcher {
             "We should never try to announce to the dummy dispatcher"
         );
     }
+
+    fn announce_reward_cycle(
+        &self,
+        _reward_cycle_info: &RewardCycleInfo,
+        _burn_block: &BurnchainHeaderHash,
+        _burn_block_height: u64,
+        _consensus_hash: &ConsensusHash,
+        _pox_constants: &PoxConstants,
+    ) {
+        assert!(
+            false,
+            "We should never try to announce to the dummy dispatcher"
+        );
+    }
+
+    fn announce_deep_reorg(&self, _reorg: &PendingStacksReorg) {
+        assert!(
+            false,
+            "We should never try to announce to the dummy dispatcher"
+        );
+    }
+
+    fn announce_chain_quality_alert(&self, _alert: &ChainQualityAlert) {
+        assert!(
+            false,
+            "We should never try to announce to the dummy dispatcher"
+        );
+    }
 }
 
 impl MemPoolRejection {
@@ -256,6 +305,14 @@ impl MemPoolRejection {
                 Some(json!({"recipient": recipient.to_string()})),
             ),
             TransferAmountMustBePositive => ("TransferAmountMustBePositive", None),
+            NotSupportedInEpoch(epoch_id, e) => (
+                "NotSupportedInEpoch",
+                Some(json!({
+                    "epoch": epoch_id.to_string(),
+                    "rule": e.reason_code(),
+                    "message": e.to_string()
+                })),
+            ),
             BadNonces(TransactionNonceMismatch {
                 expected,
                 actual,
@@ -302,6 +359,10 @@ impl MemPoolRejection {
                 Some(json!({"message": e.to_string()})),
             ),
             TemporarilyBlacklisted => ("TemporarilyBlacklisted", None),
+            PolicyRejected { policy, reason } => (
+                "PolicyRejected",
+                Some(json!({ "policy": policy, "message": reason })),
+            ),
             Other(s) => ("ServerFailureOther", Some(json!({ "message": s }))),
         };
         let mut result = json!({
@@ -4558,6 +4619,119 @@ impl StacksChainState {
         all_receipts
     }
 
+    /// Process any `stack-aggregation-commit` bitcoin operations
+    ///  that haven't been processed in this Stacks fork yet.
+    /// Each op calls `stack-aggregation-commit-indexed` in .pox-4 on behalf of its sender, with no
+    /// signer signature, so the signer must have authorized the commit beforehand with
+    /// `set-signer-key-authorization`.  Ops are skipped if .pox-4 is not the active PoX contract.
+    pub fn process_stack_aggregation_commit_ops(
+        clarity_tx: &mut ClarityTx,
+        operations: Vec<StackAggregationCommitOp>,
+        active_pox_contract: &str,
+    ) -> Vec<StacksTransactionReceipt> {
+        let mut all_receipts = vec![];
+        let mainnet = clarity_tx.config.mainnet;
+        let cost_so_far = clarity_tx.cost_so_far();
+        for agg_commit_op in operations.into_iter() {
+            let StackAggregationCommitOp {
+                sender,
+                reward_addr,
+                reward_cycle,
+                signer_key,
+                max_amount,
+                auth_id,
+                block_height,
+                txid,
+                burn_header_hash,
+                ..
+            } = &agg_commit_op;
+            if active_pox_contract != PoxVersions::Pox4.get_name() {
+                info!("Skipping StackAggregationCommit burn op, since .pox-4 is not active";
+                      "active_pox_contract" => active_pox_contract,
+                      "txid" => %txid,
+                      "burn_block_hash" => %burn_header_hash);
+                continue;
+            }
+            let args = [
+                // this .expect() should be unreachable since we coerce the hash mode when
+                // we parse the StackAggregationCommitOp from a burnchain transaction
+                reward_addr
+                    .as_clarity_tuple()
+                    .expect("FATAL: stack-aggregation-commit operation has no hash mode")
+                    .into(),
+                Value::UInt(u128::from(*reward_cycle)),
+                Value::none(),
+                Value::buff_from(signer_key.as_bytes().to_vec())
+                    .expect("FATAL: signer key should fit in a Clarity buffer"),
+                Value::UInt(*max_amount),
+                Value::UInt(u128::from(*auth_id)),
+            ];
+            let result = clarity_tx.connection().as_transaction(|tx| {
+                tx.run_contract_call(
+                    &sender.clone().into(),
+                    None,
+                    &boot_code_id(active_pox_contract, mainnet),
+                    "stack-aggregation-commit-indexed",
+                    &args,
+                    |_, _| false,
+                )
+            });
+            match result {
+                Ok((value, _, events)) => {
+                    if let Value::Response(ref resp) = value {
+                        if !resp.committed {
+                            info!("StackAggregationCommit burn op rejected by PoX contract.";
+                                   "txid" => %txid,
+                                   "burn_block_hash" => %burn_header_hash,
+                                   "contract_call_ecode" => %resp.data);
+                        } else {
+                            info!("Processed StackAggregationCommit burnchain op";
+                                "resp" => %resp.data,
+                                "reward_cycle" => reward_cycle,
+                                "signer_key" => signer_key.to_hex(),
+                                "burn_block_height" => block_height,
+                                "sender" => %sender,
+                                "reward_addr" => %reward_addr,
+                                "txid" => %txid);
+                        }
+                        let mut execution_cost = clarity_tx.cost_so_far();
+                        execution_cost
+                            .sub(&cost_so_far)
+                            .expect("BUG: cost declined between executions");
+
+                        let receipt = StacksTransactionReceipt {
+                            transaction: TransactionOrigin::Burn(
+                                BlockstackOperationType::StackAggregationCommit(agg_commit_op),
+                            ),
+                            events,
+                            result: value,
+                            post_condition_aborted: false,
+                            stx_burned: 0,
+                            contract_analysis: None,
+                            execution_cost,
+                            microblock_header: None,
+                            tx_index: 0,
+                            vm_error: None,
+                        };
+
+                        all_receipts.push(receipt);
+                    } else {
+                        unreachable!(
+                            "BUG: Non-response value returned by StackAggregationCommit burnchain op"
+                        )
+                    }
+                }
+                Err(e) => {
+                    info!("StackAggregationCommit burn op processing error.";
+                           "error" => %format!("{:?}", e),
+                           "txid" => %txid,
+                           "burn_block_hash" => %burn_header_hash);
+                }
+            };
+        }
+        all_receipts
+    }
+
     /// Process a single anchored block.
     /// Return the fees and burns.
     pub fn process_block_transactions(
@@ -4770,6 +4944,7 @@ impl StacksChainState {
             Vec<TransferStxOp>,
             Vec<DelegateStxOp>,
             Vec<VoteForAggregateKeyOp>,
+            Vec<StackAggregationCommitOp>,
         ),
         Error,
     > {
@@ -4812,6 +4987,7 @@ impl StacksChainState {
         let mut all_transfer_burn_ops = vec![];
         let mut all_delegate_burn_ops = vec![];
         let mut all_vote_for_aggregate_key_ops = vec![];
+        let mut all_stack_aggregation_commit_ops = vec![];
 
         // go from oldest burn header hash to newest
         for ancestor_bhh in ancestor_burnchain_header_hashes.iter().rev() {
@@ -4820,6 +4996,8 @@ impl StacksChainState {
             let delegate_ops = SortitionDB::get_delegate_stx_ops(sortdb_conn, ancestor_bhh)?;
             let vote_for_aggregate_key_ops =
                 SortitionDB::get_vote_for_aggregate_key_ops(sortdb_conn, ancestor_bhh)?;
+            let stack_aggregation_commit_ops =
+                SortitionDB::get_stack_aggregation_commit_ops(sortdb_conn, ancestor_bhh)?;
 
             for stacking_op in stacking_ops.into_iter() {
                 if !processed_burnchain_txids.contains(&stacking_op.txid) {
@@ -4844,12 +5022,19 @@ impl StacksChainState {
                     all_vote_for_aggregate_key_ops.push(vote_op);
                 }
             }
+
+            for agg_commit_op in stack_aggregation_commit_ops.into_iter() {
+                if !processed_burnchain_txids.contains(&agg_commit_op.txid) {
+                    all_stack_aggregation_commit_ops.push(agg_commit_op);
+                }
+            }
         }
         Ok((
             all_stacking_burn_ops,
             all_transfer_burn_ops,
             all_delegate_burn_ops,
             all_vote_for_aggregate_key_ops,
+            all_stack_aggregation_commit_ops,
         ))
     }
 
@@ -4878,7 +5063,8 @@ impl StacksChainState {
     /// the first time -- the choice of K is significantly bigger than the length of short-lived
     /// forks or periods of time with no sortition than have been observed in practice.
     ///
-    /// In epoch 2.5+, the vote-for-aggregate-key op is included
+    /// In epoch 2.5+, the vote-for-aggregate-key ops are included, and in epoch 3.1+, the
+    /// stack-aggregation-commit ops are included as well
     pub fn get_stacking_and_transfer_and_delegate_burn_ops(
         chainstate_tx: &mut ChainstateTx,
         parent_index_hash: &StacksBlockId,
@@ -4891,6 +5077,7 @@ impl StacksChainState {
             Vec<TransferStxOp>,
             Vec<DelegateStxOp>,
             Vec<VoteForAggregateKeyOp>,
+            Vec<StackAggregationCommitOp>,
         ),
         Error,
     > {
@@ -4908,13 +5095,13 @@ impl StacksChainState {
                         burn_tip,
                     )?;
                 // The DelegateStx bitcoin wire format does not exist before Epoch 2.1.
-                Ok((stack_ops, transfer_ops, vec![], vec![]))
+                Ok((stack_ops, transfer_ops, vec![], vec![], vec![]))
             }
             StacksEpochId::Epoch21
             | StacksEpochId::Epoch22
             | StacksEpochId::Epoch23
             | StacksEpochId::Epoch24 => {
-                let (stack_ops, transfer_ops, delegate_ops, _) =
+                let (stack_ops, transfer_ops, delegate_ops, _, _) =
                     StacksChainState::get_stacking_and_transfer_and_delegate_burn_ops_v210(
                         chainstate_tx,
                         parent_index_hash,
@@ -4923,9 +5110,22 @@ impl StacksChainState {
                         burn_tip_height,
                         cur_epoch.start_height,
                     )?;
-                Ok((stack_ops, transfer_ops, delegate_ops, vec![]))
+                Ok((stack_ops, transfer_ops, delegate_ops, vec![], vec![]))
             }
-            StacksEpochId::Epoch25 | StacksEpochId::Epoch30 | StacksEpochId::Epoch31 => {
+            StacksEpochId::Epoch25 | StacksEpochId::Epoch30 => {
+                let (stack_ops, transfer_ops, delegate_ops, vote_ops, _) =
+                    StacksChainState::get_stacking_and_transfer_and_delegate_burn_ops_v210(
+                        chainstate_tx,
+                        parent_index_hash,
+                        sortdb_conn,
+                        burn_tip,
+                        burn_tip_height,
+                        cur_epoch.start_height,
+                    )?;
+                // The StackAggregationCommit bitcoin wire format does not exist before Epoch 3.1.
+                Ok((stack_ops, transfer_ops, delegate_ops, vote_ops, vec![]))
+            }
+            StacksEpochId::Epoch31 => {
                 StacksChainState::get_stacking_and_transfer_and_delegate_burn_ops_v210(
                     chainstate_tx,
                     parent_index_hash,
@@ -5083,14 +5283,19 @@ impl StacksChainState {
             (latest_miners, parent_miner)
         };
 
-        let (stacking_burn_ops, transfer_burn_ops, delegate_burn_ops, vote_for_agg_key_burn_ops) =
-            StacksChainState::get_stacking_and_transfer_and_delegate_burn_ops(
-                chainstate_tx,
-                &parent_index_hash,
-                conn,
-                &burn_tip,
-                burn_tip_height.into(),
-            )?;
+        let (
+            stacking_burn_ops,
+            transfer_burn_ops,
+            delegate_burn_ops,
+            vote_for_agg_key_burn_ops,
+            agg_commit_burn_ops,
+        ) = StacksChainState::get_stacking_and_transfer_and_delegate_burn_ops(
+            chainstate_tx,
+            &parent_index_hash,
+            conn,
+            &burn_tip,
+            burn_tip_height.into(),
+        )?;
 
         // load the execution cost of the parent block if the executor is the follower.
         // otherwise, if the executor is the miner, only load the parent cost if the parent
@@ -5168,6 +5373,13 @@ impl StacksChainState {
                 microblock_ast_rules,
             ) {
                 Ok((fees, burns, events)) => (fees, burns, events),
+                Err((e, _)) if e.is_missing_clarity_state() => {
+                    // this node cannot tell whether or not the microblocks are valid
+                    if miner_id_opt.is_none() {
+                        clarity_tx.rollback_block();
+                    }
+                    return Err(e);
+                }
                 Err((e, mblock_header_hash)) => {
                     let msg = format!(
                         "Invalid Stacks microblocks {},{} (offender {}): {:?}",
@@ -5299,6 +5511,14 @@ impl StacksChainState {
                 vote_for_agg_key_burn_ops.clone(),
             ));
         }
+        // Stack-aggregation-commit ops are allowed from epoch 3.1 onward, against .pox-4
+        if evaluated_epoch.supports_stack_aggregation_commit_op() {
+            tx_receipts.extend(StacksChainState::process_stack_aggregation_commit_ops(
+                &mut clarity_tx,
+                agg_commit_burn_ops.clone(),
+                active_pox_contract,
+            ));
+        }
 
         debug!(
             "Setup block: ready to go for {}/{}",
@@ -5320,6 +5540,7 @@ impl StacksChainState {
             auto_unlock_events,
             burn_delegate_stx_ops: delegate_burn_ops,
             burn_vote_for_aggregate_key_ops: vote_for_agg_key_burn_ops,
+            burn_stack_aggregation_commit_ops: agg_commit_burn_ops,
             signer_set_calc,
         })
     }
@@ -5525,6 +5746,7 @@ impl StacksChainState {
             burn_delegate_stx_ops,
             signer_set_calc,
             burn_vote_for_aggregate_key_ops,
+            burn_stack_aggregation_commit_ops,
         } = StacksChainState::setup_block(
             chainstate_tx,
             clarity_instance,
@@ -5556,6 +5778,7 @@ impl StacksChainState {
             parent_burn_block_height,
             parent_burn_block_timestamp,
             clarity_commit,
+            clarity_writes,
         ) = {
             // get previous burn block stats
             let (parent_burn_block_hash, parent_burn_block_height, parent_burn_block_timestamp) =
@@ -5634,6 +5857,11 @@ impl StacksChainState {
                         .expect("more than 2^32 tx receipts"),
                     ast_rules,
                 ) {
+                    Err(e) if e.is_missing_clarity_state() => {
+                        // this node cannot tell whether or not the block is valid
+                        clarity_tx.rollback_block();
+                        return Err(e);
+                    }
                     Err(e) => {
                         let msg = format!("Invalid Stacks block {}: {:?}", block.block_hash(), &e);
                         warn!("{}", &msg);
@@ -5731,6 +5959,7 @@ impl StacksChainState {
                    "block cost" => %block_cost);
 
             // good to go!
+            let clarity_writes = clarity_tx.take_write_log();
             let clarity_commit =
                 clarity_tx.precommit_to_block(chain_tip_consensus_hash, &block.block_hash());
 
@@ -5785,6 +6014,7 @@ impl StacksChainState {
                 parent_burn_block_height,
                 parent_burn_block_timestamp,
                 clarity_commit,
+                clarity_writes,
             )
         };
 
@@ -5814,6 +6044,7 @@ impl StacksChainState {
                 epoch_transition: applied_epoch_transition,
                 signers_updated: false,
                 coinbase_height,
+                clarity_writes,
             };
 
             return Ok((epoch_receipt, clarity_commit, None));
@@ -5843,11 +6074,17 @@ impl StacksChainState {
             burn_transfer_stx_ops,
             burn_delegate_stx_ops,
             burn_vote_for_aggregate_key_ops,
+            burn_stack_aggregation_commit_ops,
             affirmation_weight,
         )
         .expect("FATAL: failed to advance chain tip");
 
         chainstate_tx.log_transactions_processed(&new_tip.index_block_hash(), &tx_receipts);
+        StacksChainState::store_burnchain_op_receipts(
+            chainstate_tx.deref().deref(),
+            &new_tip.index_block_hash(),
+            &tx_receipts,
+        )?;
 
         // store the reward set calculated during this block if it happened
         // NOTE: miner and proposal evaluation should not invoke this because
@@ -5907,6 +6144,7 @@ impl StacksChainState {
             epoch_transition: applied_epoch_transition,
             signers_updated,
             coinbase_height,
+            clarity_writes,
         };
 
         Ok((epoch_receipt, clarity_commit, reward_set_data))
@@ -6286,6 +6524,17 @@ impl StacksChainState {
             false,
         ) {
             Ok(next_chain_tip_info) => next_chain_tip_info,
+            Err(e) if e.is_missing_clarity_state() => {
+                // The block reads Clarity state that this node has pruned, so its validity is
+                // unknown.  Leave it unprocessed rather than orphan a possibly-valid block.
+                error!(
+                    "Cannot process {}/{}: it reads Clarity state that this node has pruned. Resync the node without `prune_mode` to continue.",
+                    &next_staging_block.consensus_hash,
+                    &block.block_hash();
+                    "error" => ?e
+                );
+                return Err(e);
+            }
             Err(e) => {
                 // something's wrong with this epoch -- either a microblock was invalid, or the
                 // anchored block was invalid.  Either way, the anchored block will _never be_
@@ -6666,6 +6915,39 @@ impl StacksChainState {
         }
     }
 
+    /// Check that a transaction's origin or sponsor nonce, `tx_nonce`, can be admitted to the
+    /// mempool given its account's current nonce: it must not have been used already, and it
+    /// must not be more than `max_chaining` nonces ahead of the next one.
+    fn check_mempool_nonce_chaining(
+        tx: &StacksTransaction,
+        account: &StacksAccount,
+        tx_nonce: u64,
+        max_chaining: u64,
+        is_origin: bool,
+    ) -> Result<(), MemPoolRejection> {
+        if tx_nonce < account.nonce {
+            return Err(TransactionNonceMismatch {
+                expected: account.nonce,
+                actual: tx_nonce,
+                txid: tx.txid(),
+                principal: account.principal.clone(),
+                is_origin,
+                quiet: true,
+            }
+            .into());
+        }
+        let max_nonce = account.nonce + 1 + max_chaining;
+        if max_nonce < tx_nonce {
+            return Err(MemPoolRejection::TooMuchChaining {
+                max_nonce,
+                actual_nonce: tx_nonce,
+                principal: account.principal.clone(),
+                is_origin,
+            });
+        }
+        Ok(())
+    }
+
     /// Given an outstanding clarity connection, can we append the tx to the chain state?
     /// Used when determining whether a transaction can be added to the mempool.
     fn can_include_tx<T: ClarityConnection>(
@@ -6677,13 +6959,18 @@ impl StacksChainState {
     ) -> Result<(), MemPoolRejection> {
         // 1: must parse (done)
 
-        // 2: it must be validly signed.
+        // 2: it must be valid in the current epoch.  This is checked before the signature, so
+        // that a transaction using a newer authentication mode gets an epoch-specific reason.
         let epoch = clarity_connection.get_epoch().clone();
 
+        StacksBlock::check_transaction_static_epoch(tx, epoch)
+            .map_err(|e| MemPoolRejection::NotSupportedInEpoch(epoch, e))?;
+
+        // 3: it must be validly signed.
         StacksChainState::process_transaction_precheck(chainstate_config, tx, epoch)
             .map_err(MemPoolRejection::FailedToValidate)?;
 
-        // 3: it must pay a tx fee
+        // 4: it must pay a tx fee
         let fee = tx.get_tx_fee();
 
         if fee < MINIMUM_TX_FEE || fee / tx_size < MINIMUM_TX_FEE_RATE_PER_BYTE {
@@ -6693,47 +6980,28 @@ impl StacksChainState {
             ));
         }
 
-        // 4: check if transaction is valid in the current epoch
-        if !StacksBlock::validate_transaction_static_epoch(tx, epoch) {
-            return Err(MemPoolRejection::Other(
-                "Transaction is not supported in this epoch".to_string(),
-            ));
-        }
-
-        // 5: the account nonces must be correct
+        // 5: the account nonces must be correct.  The origin and sponsor nonces are checked
+        // separately: each may run ahead of its account's nonce, up to its own chaining limit, to
+        // follow transactions from the same origin or sponsor that are still in the mempool.
         let (origin, payer) =
             match StacksChainState::check_transaction_nonces(clarity_connection, tx, true) {
                 Ok(x) => x,
-                // if errored, check if MEMPOOL_TX_CHAINING would admit this TX
-                Err((e, (origin, payer))) => {
-                    // if the nonce is less than expected, then TX_CHAINING would not allow in any case
-                    if e.actual < e.expected {
-                        return Err(e.into());
-                    }
-
-                    let tx_origin_nonce = tx.get_origin().nonce();
-
-                    let origin_max_nonce = origin.nonce + 1 + MAXIMUM_MEMPOOL_TX_CHAINING;
-                    if origin_max_nonce < tx_origin_nonce {
-                        return Err(MemPoolRejection::TooMuchChaining {
-                            max_nonce: origin_max_nonce,
-                            actual_nonce: tx_origin_nonce,
-                            principal: tx.origin_address().into(),
-                            is_origin: true,
-                        });
-                    }
-
-                    if let Some(sponsor_addr) = tx.sponsor_address() {
-                        let tx_sponsor_nonce = tx.get_payer().nonce();
-                        let sponsor_max_nonce = payer.nonce + 1 + MAXIMUM_MEMPOOL_TX_CHAINING;
-                        if sponsor_max_nonce < tx_sponsor_nonce {
-                            return Err(MemPoolRejection::TooMuchChaining {
-                                max_nonce: sponsor_max_nonce,
-                                actual_nonce: tx_sponsor_nonce,
-                                principal: sponsor_addr.into(),
-                                is_origin: false,
-                            });
-                        }
+                Err((_e, (origin, payer))) => {
+                    StacksChainState::check_mempool_nonce_chaining(
+                        tx,
+                        &origin,
+                        tx.get_origin().nonce(),
+                        MAXIMUM_MEMPOOL_TX_CHAINING,
+                        true,
+                    )?;
+                    if tx.sponsor_address().is_some() {
+                        StacksChainState::check_mempool_nonce_chaining(
+                            tx,
+                            &payer,
+                            tx.get_payer().nonce(),
+                            MAXIMUM_MEMPOOL_SPONSOR_TX_CHAINING,
+                            false,
+                        )?;
                     }
                     (origin, payer)
                 }
@@ -6890,11 +7158,12 @@ impl StacksChainState {
                     return Err(MemPoolRejection::ContractAlreadyExists(contract_identifier));
                 }
 
-                if let Some(_version) = version_opt.as_ref() {
-                    if epoch < StacksEpochId::Epoch21 {
-                        return Err(MemPoolRejection::Other(
-                            "Versioned smart contract transactions are not supported in this epoch"
-                                .to_string(),
+                // don't admit contracts that ask for a Clarity version from a later epoch
+                if let Some(version) = version_opt {
+                    if *version > ClarityVersion::default_for_epoch(epoch) {
+                        return Err(MemPoolRejection::NotSupportedInEpoch(
+                            epoch,
+                            TransactionEpochError::ClarityVersionNotSupported(*version),
                         ));
                     }
                 }
@@ -11213,16 +11482,21 @@ pub mod test {
                 let chainstate = peer.chainstate();
                 let (mut chainstate_tx, clarity_instance) =
                     chainstate.chainstate_tx_begin().unwrap();
-                let (stack_stx_ops, transfer_stx_ops, delegate_stx_ops, vote_for_aggregate_key_ops) =
-                    StacksChainState::get_stacking_and_transfer_and_delegate_burn_ops_v210(
-                        &mut chainstate_tx,
-                        &last_block_id,
-                        sortdb.conn(),
-                        &tip.burn_header_hash,
-                        tip.block_height,
-                        0,
-                    )
-                    .unwrap();
+                let (
+                    stack_stx_ops,
+                    transfer_stx_ops,
+                    delegate_stx_ops,
+                    vote_for_aggregate_key_ops,
+                    _,
+                ) = StacksChainState::get_stacking_and_transfer_and_delegate_burn_ops_v210(
+                    &mut chainstate_tx,
+                    &last_block_id,
+                    sortdb.conn(),
+                    &tip.burn_header_hash,
+                    tip.block_height,
+                    0,
+                )
+                .unwrap();
 
                 assert_eq!(transfer_stx_ops.len(), expected_transfer_ops.len());
                 assert_eq!(delegate_stx_ops.len(), expected_del_ops.len());
@@ -11893,7 +12167,7 @@ pub mod test {
                 let chainstate = peer.chainstate();
                 let (mut chainstate_tx, clarity_instance) =
                     chainstate.chainstate_tx_begin().unwrap();
-                let (stack_stx_ops, transfer_stx_ops, delegate_stx_ops, _) =
+                let (stack_stx_ops, transfer_stx_ops, delegate_stx_ops, _, _) =
                     StacksChainState::get_stacking_and_transfer_and_delegate_burn_ops_v210(
                         &mut chainstate_tx,
                         &last_block_id,
@@ -19,6 +19,7 @@ use std::collections::{BTreeMap, HashSet};
 use std::io::prelude::*;
 use std::ops::{Deref, DerefMut};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::{fmt, fs, io};
 
 use clarity::vm::analysis::analysis_db::AnalysisDatabase;
@@ -49,18 +50,23 @@ use crate::burnchains::bitcoin::address::{BitcoinAddress, LegacyBitcoinAddress};
 use crate::burnchains::{Address, Burnchain, BurnchainParameters, PoxConstants};
 use crate::chainstate::burn::db::sortdb::{BlockHeaderCache, SortitionDB, SortitionDBConn, *};
 use crate::chainstate::burn::operations::{
-    DelegateStxOp, StackStxOp, TransferStxOp, VoteForAggregateKeyOp,
+    DelegateStxOp, StackAggregationCommitOp, StackStxOp, TransferStxOp, VoteForAggregateKeyOp,
 };
 use crate::chainstate::burn::{ConsensusHash, ConsensusHashExtensions};
+use crate::chainstate::coordinator::{
+    ConfiguredRewardSetProvider, InjectedRewardSets, OnChainRewardSetProvider,
+};
 use crate::chainstate::nakamoto::{
     HeaderTypeNames, NakamotoBlock, NakamotoBlockHeader, NakamotoChainState,
     NakamotoStagingBlocksConn, NAKAMOTO_CHAINSTATE_SCHEMA_1, NAKAMOTO_CHAINSTATE_SCHEMA_2,
     NAKAMOTO_CHAINSTATE_SCHEMA_3, NAKAMOTO_CHAINSTATE_SCHEMA_4, NAKAMOTO_CHAINSTATE_SCHEMA_5,
+    NAKAMOTO_CHAINSTATE_SCHEMA_6, NAKAMOTO_CHAINSTATE_SCHEMA_7,
 };
 use crate::chainstate::stacks::address::StacksAddressExtensions;
 use crate::chainstate::stacks::boot::*;
 use crate::chainstate::stacks::db::accounts::*;
 use crate::chainstate::stacks::db::blocks::*;
+use crate::chainstate::stacks::db::read_replica::ReadReplicaWorkers;
 use crate::chainstate::stacks::db::unconfirmed::UnconfirmedState;
 use crate::chainstate::stacks::events::*;
 use crate::chainstate::stacks::index::marf::{
@@ -86,14 +92,18 @@ use crate::net::atlas::BNS_CHARS_REGEX;
 use crate::net::Error as net_error;
 use crate::util_lib::boot::{boot_code_acc, boot_code_addr, boot_code_id, boot_code_tx_auth};
 use crate::util_lib::db::{
-    query_count, query_row, tx_begin_immediate, tx_busy_handler, DBConn, DBTx, Error as db_error,
-    FromColumn, FromRow, IndexDBConn, IndexDBTx,
+    query_count, query_row, query_rows, tx_begin_immediate, tx_busy_handler, DBConn, DBTx,
+    Error as db_error, FromColumn, FromRow, IndexDBConn, IndexDBTx,
 };
 
 pub mod accounts;
 pub mod blocks;
 pub mod contracts;
 pub mod headers;
+pub mod prune;
+pub mod read_replica;
+pub mod receipts;
+pub mod state_diffs;
 pub mod transactions;
 pub mod unconfirmed;
 
@@ -128,6 +138,11 @@ pub struct StacksChainState {
     pub unconfirmed_state: Option<UnconfirmedState>,
     pub fault_injection: StacksChainStateFaults,
     marf_opts: Option<MARFOpenOpts>,
+    /// Read replica workers that serve read-heavy RPC queries, if enabled
+    read_replicas: Option<Arc<ReadReplicaWorkers>>,
+    /// Reward sets injected by the node's configured reward set source.  Everything that loads
+    /// reward sets through `reward_set_provider()` sees the same ones as the chains coordinator.
+    pub injected_reward_sets: Arc<InjectedRewardSets>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -231,6 +246,9 @@ pub struct StacksEpochReceipt {
     /// Was .signers updated during this block?
     pub signers_updated: bool,
     pub coinbase_height: u64,
+    /// The Clarity key/value pairs the block wrote, in the order they were written.  Only
+    /// recorded if the chainstate's Clarity instance was set to record writes.
+    pub clarity_writes: Vec<(String, String)>,
 }
 
 /// Headers we serve over the network
@@ -476,6 +494,36 @@ impl FromRow<StacksHeaderInfo> for StacksHeaderInfo {
     }
 }
 
+/// The outcome of applying an on-burnchain STX operation, as recorded in `burnchain_op_receipts`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BurnchainOpReceipt {
+    pub txid: Txid,
+    /// The Stacks block that applied the operation
+    pub index_block_hash: StacksBlockId,
+    /// Clarity result of the operation
+    pub result: Value,
+    /// Events the operation emitted, in the event observer's JSON encoding
+    pub events: Vec<serde_json::Value>,
+}
+
+impl FromRow<BurnchainOpReceipt> for BurnchainOpReceipt {
+    fn from_row(row: &Row) -> Result<BurnchainOpReceipt, db_error> {
+        let txid = Txid::from_column(row, "txid")?;
+        let index_block_hash = StacksBlockId::from_column(row, "index_block_hash")?;
+        let result_hex: String = row.get_unwrap("result");
+        let result =
+            Value::try_deserialize_hex_untyped(&result_hex).map_err(|_| db_error::ParseError)?;
+        let events_json: String = row.get_unwrap("events");
+        let events = serde_json::from_str(&events_json).map_err(db_error::SerializationError)?;
+        Ok(BurnchainOpReceipt {
+            txid,
+            index_block_hash,
+            result,
+            events,
+        })
+    }
+}
+
 pub type StacksDBTx<'a> = IndexDBTx<'a, (), StacksBlockId>;
 pub type StacksDBConn<'a> = IndexDBConn<'a, (), StacksBlockId>;
 
@@ -544,6 +592,11 @@ impl<'a, 'b> ClarityTx<'a, 'b> {
         self.block.seal()
     }
 
+    /// Take the Clarity key/value pairs committed to this block so far
+    pub fn take_write_log(&mut self) -> Vec<(String, String)> {
+        self.block.take_write_log()
+    }
+
     #[cfg(test)]
     pub fn commit_block(self) {
         self.block.commit_block();
@@ -676,7 +729,7 @@ impl<'a> DerefMut for ChainstateTx<'a> {
     }
 }
 
-pub const CHAINSTATE_VERSION: &str = "8";
+pub const CHAINSTATE_VERSION: &str = "12";
 
 const CHAINSTATE_INITIAL_SCHEMA: &[&str] = &[
     "PRAGMA foreign_keys = ON;",
@@ -875,6 +928,42 @@ const CHAINSTATE_SCHEMA_3: &[&str] = &[
     "#,
 ];
 
+const CHAINSTATE_SCHEMA_4: &[&str] = &[
+    // track the horizons below which Clarity state has been pruned (see `prune.rs`)
+    r#"
+    CREATE TABLE clarity_prune_checkpoints(
+        -- blocks at or above this height kept all of their Clarity state
+        horizon_height INTEGER PRIMARY KEY,
+        -- number of Clarity values discarded by this checkpoint
+        pruned_values INTEGER NOT NULL,
+        -- number of Stacks tips whose state was retained
+        retained_tips INTEGER NOT NULL,
+        -- when the checkpoint was taken (seconds since the epoch)
+        pruned_at INTEGER NOT NULL
+    );"#,
+    r#"
+    UPDATE db_config SET version = "10";
+    "#,
+];
+
+const CHAINSTATE_SCHEMA_5: &[&str] = &[
+    // outcome of each on-burnchain STX operation, so it can be queried after the fact
+    r#"
+    CREATE TABLE burnchain_op_receipts(
+        txid TEXT NOT NULL,
+        -- the Stacks block that applied the operation
+        index_block_hash TEXT NOT NULL,
+        -- hex-encoded, consensus-serialized Clarity result
+        result TEXT NOT NULL,
+        -- JSON-encoded list of the events the operation emitted
+        events TEXT NOT NULL,
+        PRIMARY KEY(txid,index_block_hash)
+    );"#,
+    r#"
+    UPDATE db_config SET version = "11";
+    "#,
+];
+
 const CHAINSTATE_INDEXES: &[&str] = &[
     "CREATE INDEX IF NOT EXISTS index_block_hash_to_primary_key ON block_headers(index_block_hash,consensus_hash,block_hash);",
     "CREATE INDEX IF NOT EXISTS block_headers_hash_index ON block_headers(block_hash,block_height);",
@@ -1126,6 +1215,38 @@ impl StacksChainState {
                         tx.execute_batch(cmd)?;
                     }
                 }
+                "8" => {
+                    info!(
+                        "Migrating chainstate schema from version 8 to 9: add nakamoto tenure height index"
+                    );
+                    for cmd in NAKAMOTO_CHAINSTATE_SCHEMA_6.iter() {
+                        tx.execute_batch(cmd)?;
+                    }
+                }
+                "9" => {
+                    info!(
+                        "Migrating chainstate schema from version 9 to 10: add clarity prune checkpoints"
+                    );
+                    for cmd in CHAINSTATE_SCHEMA_4.iter() {
+                        tx.execute_batch(cmd)?;
+                    }
+                }
+                "10" => {
+                    info!(
+                        "Migrating chainstate schema from version 10 to 11: add burnchain op receipts"
+                    );
+                    for cmd in CHAINSTATE_SCHEMA_5.iter() {
+                        tx.execute_batch(cmd)?;
+                    }
+                }
+                "11" => {
+                    info!(
+                        "Migrating chainstate schema from version 11 to 12: add nakamoto reward set cache"
+                    );
+                    for cmd in NAKAMOTO_CHAINSTATE_SCHEMA_7.iter() {
+                        tx.execute_batch(cmd)?;
+                    }
+                }
                 _ => {
                     error!(
                         "Invalid chain state database: expected version = {}, got {}",
@@ -1873,6 +1994,8 @@ impl StacksChainState {
             unconfirmed_state: None,
             fault_injection: StacksChainStateFaults::new(),
             marf_opts,
+            read_replicas: None,
+            injected_reward_sets: Arc::new(InjectedRewardSets::default()),
         };
 
         let mut receipts = vec![];
@@ -2222,6 +2345,43 @@ impl StacksChainState {
         }
     }
 
+    /// Start a pool of `num_replicas` worker threads, each owning a read replica of this
+    /// chainstate, to serve read-heavy RPC queries of the Clarity state.  Passing 0 stops the
+    /// pool, if any.
+    pub fn enable_read_replicas(
+        &mut self,
+        sortdb: &SortitionDB,
+        num_replicas: usize,
+    ) -> Result<(), Error> {
+        self.read_replicas = if num_replicas > 0 {
+            Some(Arc::new(ReadReplicaWorkers::spawn(
+                self,
+                sortdb,
+                num_replicas,
+            )?))
+        } else {
+            None
+        };
+        Ok(())
+    }
+
+    /// Get the read replica workers, if read replicas are enabled
+    pub fn read_replicas(&self) -> Option<&ReadReplicaWorkers> {
+        self.read_replicas.as_deref()
+    }
+
+    /// The reward set provider to use when loading reward sets outside of the chains coordinator,
+    /// e.g. to validate signer signatures on incoming blocks.  It serves the node's injected
+    /// reward sets, and computes the rest on-chain.
+    pub fn reward_set_provider(
+        &self,
+    ) -> ConfiguredRewardSetProvider<'static, DummyEventDispatcher> {
+        ConfiguredRewardSetProvider::new(
+            self.injected_reward_sets.clone(),
+            OnChainRewardSetProvider::new(),
+        )
+    }
+
     fn get_parent_index_block(
         parent_consensus_hash: &ConsensusHash,
         parent_block: &BlockHeaderHash,
@@ -2542,6 +2702,7 @@ impl StacksChainState {
         burn_transfer_stx_ops: Vec<TransferStxOp>,
         burn_delegate_stx_ops: Vec<DelegateStxOp>,
         burn_vote_for_aggregate_key_ops: Vec<VoteForAggregateKeyOp>,
+        burn_stack_aggregation_commit_ops: Vec<StackAggregationCommitOp>,
     ) -> Result<(), Error> {
         let mut txids: Vec<_> = burn_stack_stx_ops
             .into_iter()
@@ -2578,6 +2739,16 @@ impl StacksChainState {
 
         txids.append(&mut vote_txids);
 
+        let mut agg_commit_txids =
+            burn_stack_aggregation_commit_ops
+                .into_iter()
+                .fold(vec![], |mut txids, op| {
+                    txids.push(op.txid);
+                    txids
+                });
+
+        txids.append(&mut agg_commit_txids);
+
         let txids_json =
             serde_json::to_string(&txids).expect("FATAL: could not serialize Vec<Txid>");
         let sql = "INSERT INTO burnchain_txids (index_block_hash, txids) VALUES (?1, ?2)";
@@ -2586,6 +2757,49 @@ impl StacksChainState {
         Ok(())
     }
 
+    /// Record the outcome of each on-burnchain STX operation applied by `index_block_hash`.
+    /// Receipts for Stacks transactions are skipped.
+    pub fn store_burnchain_op_receipts(
+        tx: &DBTx,
+        index_block_hash: &StacksBlockId,
+        receipts: &[StacksTransactionReceipt],
+    ) -> Result<(), Error> {
+        let sql = "INSERT OR REPLACE INTO burnchain_op_receipts (txid, index_block_hash, result, events) VALUES (?1, ?2, ?3, ?4)";
+        for receipt in receipts.iter() {
+            let TransactionOrigin::Burn(op) = &receipt.transaction else {
+                continue;
+            };
+            let txid = op.txid();
+            let committed = matches!(&receipt.result, Value::Response(data) if data.committed);
+            let encoded = receipt.result.serialize_to_hex().ok().and_then(|result| {
+                let events = receipt
+                    .events
+                    .iter()
+                    .enumerate()
+                    .map(|(i, event)| event.json_serialize(i, &txid, committed))
+                    .collect::<Result<Vec<_>, _>>()
+                    .ok()?;
+                Some((result, serde_json::Value::Array(events).to_string()))
+            });
+            let Some((result, events_json)) = encoded else {
+                warn!("Failed to encode burnchain op receipt"; "txid" => %txid);
+                continue;
+            };
+            tx.execute(sql, params![txid, index_block_hash, result, events_json])?;
+        }
+        Ok(())
+    }
+
+    /// Get the recorded outcomes of the on-burnchain STX operation `txid` -- one for each Stacks
+    /// block, in any fork, that applied it.
+    pub fn get_burnchain_op_receipts(
+        conn: &Connection,
+        txid: &Txid,
+    ) -> Result<Vec<BurnchainOpReceipt>, Error> {
+        let sql = "SELECT * FROM burnchain_op_receipts WHERE txid = ?1";
+        Ok(query_rows(conn, sql, params![txid])?)
+    }
+
     /// Append a Stacks block to an existing Stacks block, and grant the miner the block reward.
     /// Return the new Stacks header info.
     pub fn advance_tip(
@@ -2607,6 +2821,7 @@ impl StacksChainState {
         burn_transfer_stx_ops: Vec<TransferStxOp>,
         burn_delegate_stx_ops: Vec<DelegateStxOp>,
         burn_vote_for_aggregate_key_ops: Vec<VoteForAggregateKeyOp>,
+        burn_stack_aggregation_commit_ops: Vec<StackAggregationCommitOp>,
         affirmation_weight: u64,
     ) -> Result<StacksHeaderInfo, Error> {
         if new_tip.parent_block != FIRST_STACKS_BLOCK_HASH {
@@ -2673,6 +2888,7 @@ impl StacksChainState {
             burn_transfer_stx_ops,
             burn_delegate_stx_ops,
             burn_vote_for_aggregate_key_ops,
+            burn_stack_aggregation_commit_ops,
         )?;
 
         if let Some((miner_payout, user_payouts, parent_payout, reward_info)) = mature_miner_payouts
@@ -2729,9 +2945,11 @@ pub mod test {
     use std::{env, fs};
 
     use clarity::vm::test_util::TEST_BURN_STATE_DB;
+    use clarity::vm::types::BuffData;
     use stx_genesis::GenesisData;
 
     use super::*;
+    use crate::chainstate::burn::operations::BlockstackOperationType;
     use crate::chainstate::stacks::db::*;
     use crate::chainstate::stacks::*;
     use crate::util_lib::boot::boot_code_test_addr;
@@ -2990,6 +3208,65 @@ pub mod test {
         assert!(db.supports_epoch(StacksEpochId::latest()));
     }
 
+    #[test]
+    fn test_burnchain_op_receipts() {
+        let mut chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
+        let sender =
+            StacksAddress::new(C32_ADDRESS_VERSION_TESTNET_SINGLESIG, Hash160([0x01; 20])).unwrap();
+        let recipient =
+            StacksAddress::new(C32_ADDRESS_VERSION_TESTNET_SINGLESIG, Hash160([0x02; 20])).unwrap();
+        let transfer_op = TransferStxOp {
+            sender: sender.clone(),
+            recipient: recipient.clone(),
+            transfered_ustx: 123,
+            memo: vec![],
+            txid: Txid([0x11; 32]),
+            vtxindex: 1,
+            block_height: 100,
+            burn_header_hash: BurnchainHeaderHash([0x22; 32]),
+        };
+        let burn_receipt = StacksTransactionReceipt {
+            transaction: TransactionOrigin::Burn(BlockstackOperationType::TransferStx(transfer_op)),
+            events: vec![StacksTransactionEvent::STXEvent(
+                STXEventType::STXTransferEvent(STXTransferEventData {
+                    sender: sender.into(),
+                    recipient: recipient.into(),
+                    amount: 123,
+                    memo: BuffData { data: vec![] },
+                }),
+            )],
+            post_condition_aborted: false,
+            result: Value::okay_true(),
+            stx_burned: 0,
+            contract_analysis: None,
+            execution_cost: ExecutionCost::ZERO,
+            microblock_header: None,
+            tx_index: 0,
+            vm_error: None,
+        };
+        let block_id = StacksBlockId([0x33; 32]);
+
+        let tx = chainstate.db_tx_begin().unwrap();
+        StacksChainState::store_burnchain_op_receipts(&tx, &block_id, &[burn_receipt]).unwrap();
+        tx.commit().unwrap();
+
+        let receipts =
+            StacksChainState::get_burnchain_op_receipts(chainstate.db(), &Txid([0x11; 32]))
+                .unwrap();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].index_block_hash, block_id);
+        assert_eq!(receipts[0].result, Value::okay_true());
+        assert_eq!(receipts[0].events.len(), 1);
+        assert_eq!(receipts[0].events[0]["type"], "stx_transfer_event");
+        assert_eq!(receipts[0].events[0]["committed"], true);
+
+        assert!(
+            StacksChainState::get_burnchain_op_receipts(chainstate.db(), &Txid([0x44; 32]))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_sqlite_version() {
         let chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
@@ -0,0 +1,368 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Checkpointed pruning of historical Clarity state (`node.prune_mode`).
+//!
+//! Clarity values are stored in the `data_table` side store of the Clarity MARF's sqlite DB,
+//! keyed by the `MARFValue` that the MARF's leaves hold for them.  Pruning marks every value
+//! reachable from the tries of the Stacks blocks at or above a horizon height, and sweeps the
+//! rest out of the side store.  Reads of the current state and of recent tips keep working, but
+//! reads of Clarity state at blocks below the horizon will fail.
+//!
+//! The MARF's trie nodes are *not* pruned, since the tries of later blocks back-point into the
+//! tries of earlier blocks.  Only the values are discarded, which is where the bulk of the
+//! Clarity state's disk usage is.
+//!
+//! Pruning happens in checkpoints: with a horizon of `K` blocks, the node prunes everything
+//! below the highest multiple of `K` that is at least `K` blocks below the highest known Stacks
+//! block, so between `K` and `2K` blocks of history are retained at any time.  `K` must be
+//! larger than any reorg the node may have to process, since a block whose parent's state was
+//! pruned cannot be processed.
+//!
+//! The chains coordinator runs checkpoints on a background thread (see `ClarityPruner`), with
+//! its own connections to the chainstate.  Blocks keep getting processed while a checkpoint
+//! runs, so the sweep only considers values that were already stored when it started.
+//!
+//! A block whose transactions read pruned state (e.g. via `at-block`) cannot be evaluated.  A
+//! pruned node refuses to process such a block instead of treating it as invalid, and stops
+//! advancing its Stacks tip; it must be resynced without `prune_mode` to get past it.
+
+use std::collections::HashSet;
+use std::thread::{self, JoinHandle};
+
+use clarity::vm::clarity::Error as clarity_error;
+use clarity::vm::errors::{Error as ClarityVmError, InterpreterError};
+use rusqlite::params;
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::sqlite::NO_PARAMS;
+use stacks_common::util::get_epoch_time_secs;
+
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::chainstate::stacks::index::marf::{MARFOpenOpts, MarfConnection, MARF};
+use crate::chainstate::stacks::index::Error as MarfError;
+use crate::chainstate::stacks::Error;
+use crate::clarity_vm::database::marf::MISSING_SIDE_STORE_VALUE;
+use crate::util_lib::db::{query_rows, u64_to_sql, DBConn, Error as db_error};
+
+/// How many side store rows to consider per sweep transaction, so that the coordinator is
+/// never locked out of the Clarity DB for long
+const SWEEP_BATCH_ROWS: i64 = 10_000;
+
+/// Outcome of a Clarity state pruning checkpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClarityPruneStats {
+    /// Blocks at or above this height retained all of their Clarity state
+    pub horizon_height: u64,
+    /// Number of Clarity values discarded from the side store
+    pub pruned_values: u64,
+    /// Number of Stacks blocks whose Clarity state was retained
+    pub retained_tips: u64,
+}
+
+impl Error {
+    /// Did this error happen because Clarity state that the node needed is missing from the
+    /// side store, e.g. because it was pruned?  Such an error says nothing about the validity of
+    /// the block being processed.
+    pub fn is_missing_clarity_state(&self) -> bool {
+        match self {
+            Error::ClarityError(clarity_error::Interpreter(ClarityVmError::Interpreter(
+                InterpreterError::Expect(msg),
+            ))) => msg.starts_with(MISSING_SIDE_STORE_VALUE),
+            _ => false,
+        }
+    }
+}
+
+/// Runs Clarity state pruning checkpoints on a background thread, so that the chains
+/// coordinator does not stop processing blocks while the side store is swept.  At most one
+/// checkpoint runs at a time.
+pub struct ClarityPruner {
+    /// How many Stacks blocks of Clarity state to keep
+    prune_horizon: u64,
+    /// The running checkpoint, if any
+    handle: Option<JoinHandle<Result<Option<ClarityPruneStats>, Error>>>,
+}
+
+impl ClarityPruner {
+    pub fn new(prune_horizon: u64) -> Self {
+        Self {
+            prune_horizon,
+            handle: None,
+        }
+    }
+
+    /// Is a checkpoint currently running?
+    pub fn is_running(&self) -> bool {
+        self.handle
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+    }
+
+    /// Start a checkpoint on a background thread if a new one has been reached since the last
+    /// time state was pruned, and no checkpoint is already running.  Called by the coordinator
+    /// after it processes a Stacks block.
+    ///
+    /// Returns Ok(true) if a checkpoint was started.
+    pub fn maybe_start(&mut self, chainstate: &StacksChainState) -> Result<bool, Error> {
+        if self.is_running() {
+            return Ok(false);
+        }
+        self.join();
+
+        let Some(horizon_height) =
+            StacksChainState::next_clarity_prune_checkpoint(chainstate.db(), self.prune_horizon)?
+        else {
+            return Ok(false);
+        };
+
+        let index_conn = chainstate.reopen_db()?;
+        let clarity_marf_path = chainstate.clarity_state_index_path.clone();
+        let mut marf_opts = chainstate
+            .marf_opts
+            .clone()
+            .unwrap_or_else(MARFOpenOpts::default);
+        marf_opts.external_blobs = true;
+
+        let handle = thread::Builder::new()
+            .name("clarity-pruner".into())
+            .spawn(move || {
+                let mut marf = MARF::from_path(&clarity_marf_path, marf_opts)?;
+                StacksChainState::run_clarity_prune_checkpoint(
+                    &index_conn,
+                    &mut marf,
+                    horizon_height,
+                )
+            })
+            .expect("FATAL: failed to spawn the Clarity pruner thread");
+        self.handle = Some(handle);
+
+        debug!("Started Clarity state pruning checkpoint"; "horizon_height" => horizon_height);
+        Ok(true)
+    }
+
+    /// Wait for the running checkpoint, if any, to finish, and log its outcome.
+    ///
+    /// Returns None if no checkpoint was started since the last call.
+    pub fn join(&mut self) -> Option<Result<Option<ClarityPruneStats>, Error>> {
+        let handle = self.handle.take()?;
+        let result = handle
+            .join()
+            .expect("FATAL: Clarity pruner thread panicked");
+        if let Err(e) = result.as_ref() {
+            warn!("Failed to prune Clarity state"; "error" => ?e);
+        }
+        Some(result)
+    }
+}
+
+impl StacksChainState {
+    /// Get the height below which Clarity state has been pruned, if it has been pruned at all
+    pub fn get_clarity_prune_horizon(conn: &DBConn) -> Result<Option<u64>, Error> {
+        let horizon: Option<i64> = conn.query_row(
+            "SELECT MAX(horizon_height) FROM clarity_prune_checkpoints",
+            NO_PARAMS,
+            |row| row.get(0),
+        )?;
+        horizon
+            .map(|h| u64::try_from(h).map_err(|_| Error::DBError(db_error::ParseError)))
+            .transpose()
+    }
+
+    /// Get the height of the highest Stacks block this node has processed, in any fork
+    fn get_highest_block_height(conn: &DBConn) -> Result<Option<u64>, Error> {
+        let height: Option<i64> = conn.query_row(
+            "SELECT MAX(h) FROM (
+                SELECT MAX(block_height) AS h FROM block_headers
+                UNION ALL
+                SELECT MAX(block_height) AS h FROM nakamoto_block_headers
+            )",
+            NO_PARAMS,
+            |row| row.get(0),
+        )?;
+        height
+            .map(|h| u64::try_from(h).map_err(|_| Error::DBError(db_error::ParseError)))
+            .transpose()
+    }
+
+    /// Get the horizon height of the next pruning checkpoint for a horizon of `prune_horizon`
+    /// blocks, if a new checkpoint has been reached since the last time state was pruned.
+    pub fn next_clarity_prune_checkpoint(
+        conn: &DBConn,
+        prune_horizon: u64,
+    ) -> Result<Option<u64>, Error> {
+        if prune_horizon == 0 {
+            return Ok(None);
+        }
+        let Some(tip_height) = Self::get_highest_block_height(conn)? else {
+            return Ok(None);
+        };
+        let checkpoint = (tip_height.saturating_sub(prune_horizon) / prune_horizon) * prune_horizon;
+        if checkpoint == 0 {
+            return Ok(None);
+        }
+        if let Some(last_horizon) = Self::get_clarity_prune_horizon(conn)? {
+            if checkpoint <= last_horizon {
+                return Ok(None);
+            }
+        }
+        Ok(Some(checkpoint))
+    }
+
+    /// Discard all Clarity values that are not reachable from the state of a Stacks block at or
+    /// above `horizon_height`, and record the checkpoint.  This runs on the calling thread; the
+    /// coordinator uses a `ClarityPruner` instead.
+    ///
+    /// Returns Ok(None) without pruning anything if the Clarity MARF holds unconfirmed state,
+    /// since its values may not be reachable from any processed block.
+    pub fn prune_clarity_state(
+        &mut self,
+        horizon_height: u64,
+    ) -> Result<Option<ClarityPruneStats>, Error> {
+        let index_conn = self.state_index.sqlite_conn();
+        self.clarity_state
+            .with_marf(|marf| Self::run_clarity_prune_checkpoint(index_conn, marf, horizon_height))
+    }
+
+    /// Prune the Clarity state in `marf` below `horizon_height`, and record the checkpoint in
+    /// the chainstate DB `index_conn`.
+    fn run_clarity_prune_checkpoint(
+        index_conn: &DBConn,
+        marf: &mut MARF<StacksBlockId>,
+        horizon_height: u64,
+    ) -> Result<Option<ClarityPruneStats>, Error> {
+        let retained_tips: Vec<StacksBlockId> = query_rows(
+            index_conn,
+            "SELECT index_block_hash FROM block_headers WHERE block_height >= ?1
+             UNION
+             SELECT index_block_hash FROM nakamoto_block_headers WHERE block_height >= ?1",
+            params![u64_to_sql(horizon_height)?],
+        )?;
+
+        let Some((pruned_values, retained_tips)) =
+            Self::sweep_clarity_side_store(marf, &retained_tips)?
+        else {
+            info!(
+                "Will not prune Clarity state while there is unconfirmed state";
+                "horizon_height" => horizon_height
+            );
+            return Ok(None);
+        };
+
+        index_conn.execute(
+            "INSERT OR REPLACE INTO clarity_prune_checkpoints (horizon_height, pruned_values, retained_tips, pruned_at) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                u64_to_sql(horizon_height)?,
+                u64_to_sql(pruned_values)?,
+                u64_to_sql(retained_tips)?,
+                u64_to_sql(get_epoch_time_secs())?,
+            ],
+        )?;
+
+        info!(
+            "Pruned Clarity state";
+            "horizon_height" => horizon_height,
+            "pruned_values" => pruned_values,
+            "retained_tips" => retained_tips,
+        );
+
+        Ok(Some(ClarityPruneStats {
+            horizon_height,
+            pruned_values,
+            retained_tips,
+        }))
+    }
+
+    /// Mark the side store values reachable from each of `tips` in the Clarity MARF, and sweep
+    /// out the rest.  Tips that have no trie are skipped.
+    ///
+    /// Only rows that were already in the side store when the sweep started are candidates for
+    /// deletion.  Blocks processed concurrently store their values with `REPLACE`, which gives
+    /// the row a new rowid, so any value that such a block writes or rewrites is retained.  Any
+    /// other value such a block can reach is reachable from its parent, which is a retained tip.
+    ///
+    /// Returns Ok(Some((pruned values, retained tips))) on success, and Ok(None) if the MARF
+    /// holds unconfirmed state.
+    fn sweep_clarity_side_store(
+        marf: &mut MARF<StacksBlockId>,
+        tips: &[StacksBlockId],
+    ) -> Result<Option<(u64, u64)>, Error> {
+        let has_unconfirmed: bool = marf.sqlite_conn().query_row(
+            "SELECT EXISTS (SELECT 1 FROM marf_data WHERE unconfirmed = 1)",
+            NO_PARAMS,
+            |row| row.get(0),
+        )?;
+        if has_unconfirmed {
+            return Ok(None);
+        }
+
+        // The highest rowid is never a candidate, since `REPLACE`-ing it can re-use its rowid.
+        let sweep_end: i64 = marf.sqlite_conn().query_row(
+            "SELECT IFNULL(MAX(rowid), 0) FROM data_table",
+            NO_PARAMS,
+            |row| row.get(0),
+        )?;
+
+        let mut live = HashSet::new();
+        let mut visited = HashSet::new();
+        let mut retained_tips = 0u64;
+        for tip in tips.iter() {
+            let res = marf.with_conn(|conn| {
+                MARF::walk_leaves(conn, tip, &mut visited, |leaf| {
+                    live.insert(leaf.data.to_hex());
+                })
+            });
+            match res {
+                Ok(()) => retained_tips += 1,
+                Err(MarfError::NotFoundError) => {
+                    debug!("No Clarity trie for retained tip {tip}");
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        // the live set is only visible to this connection, so it needs no write lock
+        let conn = marf.sqlite_conn();
+        conn.execute(
+            "CREATE TEMP TABLE IF NOT EXISTS clarity_prune_live (key TEXT PRIMARY KEY)",
+            NO_PARAMS,
+        )?;
+        conn.execute("DELETE FROM clarity_prune_live", NO_PARAMS)?;
+        {
+            let mut stmt =
+                conn.prepare("INSERT OR IGNORE INTO clarity_prune_live (key) VALUES (?1)")?;
+            for key in live.iter() {
+                stmt.execute(params![key])?;
+            }
+        }
+
+        let mut pruned_values = 0u64;
+        let mut batch_start = 0i64;
+        while batch_start < sweep_end {
+            let batch_end = batch_start.saturating_add(SWEEP_BATCH_ROWS).min(sweep_end);
+            let tx = marf.storage_tx()?;
+            let pruned = tx.execute(
+                "DELETE FROM data_table WHERE rowid >= ?1 AND rowid < ?2 AND key NOT IN (SELECT key FROM clarity_prune_live)",
+                params![batch_start, batch_end],
+            )?;
+            tx.commit()?;
+            pruned_values += u64::try_from(pruned).expect("FATAL: too many pruned values");
+            batch_start = batch_end;
+        }
+        marf.sqlite_conn()
+            .execute("DROP TABLE clarity_prune_live", NO_PARAMS)?;
+
+        Ok(Some((pruned_values, retained_tips)))
+    }
+}
@@ -0,0 +1,276 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Read replicas of the chainstate for serving read-heavy RPC queries.
+//!
+//! A read replica is a read-only view of the Clarity MARF and the headers index, opened on its
+//! own SQLite connections.  Each replica is owned by a worker thread in a `ReadReplicaWorkers`
+//! pool, which also gives the worker its own sortition DB connection.  RPC handlers for
+//! read-heavy endpoints (read-only calls, contract analysis, and the like) send their Clarity
+//! queries to the pool and hold their requests until a worker answers, so the queries run
+//! neither on the peer thread nor on the connections used for block processing.
+//!
+//! Each query reads the headers index in a single read transaction, so it sees one snapshot of
+//! which blocks have been processed.  The Clarity state of a processed block is never rewritten,
+//! so the query's reads of it at the requested tip are consistent as well.
+
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::{self, JoinHandle};
+
+use clarity::vm::database::BurnStateDB;
+use stacks_common::types::chainstate::{SortitionId, StacksBlockId};
+
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::chainstate::stacks::index::marf::{MarfConnection, MARF};
+use crate::chainstate::stacks::Error;
+use crate::clarity_vm::clarity::{ClarityInstance, ClarityReadOnlyConnection};
+use crate::util_lib::db::{tx_begin_deferred, Error as db_error};
+
+/// Most queries that may wait in the pool's queue, per worker
+pub const MAX_QUEUED_QUERIES_PER_WORKER: usize = 16;
+/// How long an RPC request may be held while its query waits for a worker
+pub const READ_REPLICA_QUERY_TIMEOUT_MS: u128 = 30_000;
+
+/// A query for a read replica worker.  It is given the worker's replica and sortition DB.
+type ReadReplicaJob = Box<dyn FnOnce(&mut ChainStateReadReplica, &SortitionDB) + Send>;
+
+/// A read-only view of the chainstate on its own database connections
+pub struct ChainStateReadReplica {
+    /// Read-only Clarity state
+    clarity_state: ClarityInstance,
+    /// Read-only headers index
+    state_index: MARF<StacksBlockId>,
+}
+
+impl ChainStateReadReplica {
+    /// Open a read replica of `chainstate`
+    pub fn open(chainstate: &StacksChainState) -> Result<Self, Error> {
+        let clarity_state = chainstate.clarity_state.reopen_readonly()?;
+        let state_index = chainstate
+            .state_index
+            .reopen_readonly()
+            .map_err(|e| Error::DBError(db_error::IndexError(e)))?;
+        Ok(Self {
+            clarity_state,
+            state_index,
+        })
+    }
+
+    /// Run `to_do` on the state of the Clarity VM at `tip`, as of a single snapshot of the
+    /// headers index.
+    /// Returns Ok(Some(x: R)) if `tip` has been processed.
+    /// Returns Ok(None) if not.
+    pub fn with_read_only_clarity_tx<F, R>(
+        &mut self,
+        burn_dbconn: &dyn BurnStateDB,
+        tip: &StacksBlockId,
+        to_do: F,
+    ) -> Result<Option<R>, Error>
+    where
+        F: FnOnce(&mut ClarityReadOnlyConnection) -> R,
+    {
+        // rolled back when dropped
+        let _headers_tx = tx_begin_deferred(self.state_index.sqlite_conn())?;
+        if NakamotoChainState::get_block_header(self.state_index.sqlite_conn(), tip)?.is_none() {
+            return Ok(None);
+        }
+        let mut conn = match self.clarity_state.read_only_connection_checked(
+            tip,
+            &self.state_index,
+            burn_dbconn,
+        ) {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!("Failed to load read replica connection"; "tip" => %tip, "err" => %e);
+                return Ok(None);
+            }
+        };
+        Ok(Some(to_do(&mut conn)))
+    }
+}
+
+/// The eventual result of a query sent to a `ReadReplicaWorkers` pool
+pub struct ReadReplicaQuery<R> {
+    /// Set by the worker once the query has run
+    result: Arc<Mutex<Option<R>>>,
+    /// Kept alive by the worker until it is done with the query, whether or not the query
+    /// produced a result
+    pending: Weak<()>,
+}
+
+impl<R> Clone for ReadReplicaQuery<R> {
+    fn clone(&self) -> Self {
+        Self {
+            result: self.result.clone(),
+            pending: self.pending.clone(),
+        }
+    }
+}
+
+impl<R> ReadReplicaQuery<R> {
+    /// A query that is already answered with `result`
+    pub fn answered(result: R) -> Self {
+        Self {
+            result: Arc::new(Mutex::new(Some(result))),
+            pending: Weak::new(),
+        }
+    }
+
+    /// A query that the pool refused to run
+    pub fn refused() -> Self {
+        Self {
+            result: Arc::new(Mutex::new(None)),
+            pending: Weak::new(),
+        }
+    }
+
+    /// Is the query done?  A query that was refused, or whose worker died before answering it,
+    /// is done but has no result.
+    pub fn is_done(&self) -> bool {
+        self.result
+            .lock()
+            .expect("FATAL: read replica query lock poisoned")
+            .is_some()
+            || self.pending.strong_count() == 0
+    }
+
+    /// Take the query's result.
+    /// Returns None if the query is not done, or if it is done but has no result.
+    pub fn take_result(&self) -> Option<R> {
+        self.result
+            .lock()
+            .expect("FATAL: read replica query lock poisoned")
+            .take()
+    }
+}
+
+/// A pool of worker threads, each of which owns a read replica of the chainstate and its own
+/// sortition DB connection, and runs the queries sent to the pool
+pub struct ReadReplicaWorkers {
+    /// Queue of queries for the workers.  Dropped to stop them.
+    queue: Option<SyncSender<ReadReplicaJob>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl ReadReplicaWorkers {
+    /// Start `num_workers` workers, each with a read replica of `chainstate` and a reopened
+    /// `sortdb`
+    pub fn spawn(
+        chainstate: &StacksChainState,
+        sortdb: &SortitionDB,
+        num_workers: usize,
+    ) -> Result<Self, Error> {
+        let (queue, queue_rx) =
+            mpsc::sync_channel(num_workers.saturating_mul(MAX_QUEUED_QUERIES_PER_WORKER));
+        let queue_rx = Arc::new(Mutex::new(queue_rx));
+        let mut workers = Vec::with_capacity(num_workers);
+        for i in 0..num_workers {
+            let replica = ChainStateReadReplica::open(chainstate)?;
+            let sortdb = sortdb.reopen()?;
+            let queue_rx = queue_rx.clone();
+            let worker = thread::Builder::new()
+                .name(format!("read-replica-{i}"))
+                .spawn(move || Self::run_worker(replica, sortdb, queue_rx))
+                .map_err(|e| {
+                    db_error::Other(format!("Failed to spawn read replica worker: {e}"))
+                })?;
+            workers.push(worker);
+        }
+        Ok(Self {
+            queue: Some(queue),
+            workers,
+        })
+    }
+
+    /// Run queries from `queue_rx` until the pool is dropped
+    fn run_worker(
+        mut replica: ChainStateReadReplica,
+        sortdb: SortitionDB,
+        queue_rx: Arc<Mutex<Receiver<ReadReplicaJob>>>,
+    ) {
+        loop {
+            let job_res = queue_rx
+                .lock()
+                .expect("FATAL: read replica queue lock poisoned")
+                .recv();
+            let Ok(job) = job_res else {
+                return;
+            };
+            job(&mut replica, &sortdb);
+        }
+    }
+
+    /// Queue `to_do`, a query of the Clarity state at `tip`, whose burnchain view is the
+    /// sortition `sortition_id`.  Its result is None if `tip` has not been processed, or if the
+    /// query failed.
+    /// If the queue is full, the query is refused.
+    pub fn submit<F, R>(
+        &self,
+        sortition_id: SortitionId,
+        tip: StacksBlockId,
+        to_do: F,
+    ) -> ReadReplicaQuery<Option<R>>
+    where
+        F: FnOnce(&mut ClarityReadOnlyConnection) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let Some(queue) = self.queue.as_ref() else {
+            return ReadReplicaQuery::refused();
+        };
+        let result = Arc::new(Mutex::new(None));
+        let pending = Arc::new(());
+        let query = ReadReplicaQuery {
+            result: result.clone(),
+            pending: Arc::downgrade(&pending),
+        };
+        let job: ReadReplicaJob = Box::new(move |replica, sortdb| {
+            let burn_dbconn = sortdb.index_handle(&sortition_id);
+            let answer = replica
+                .with_read_only_clarity_tx(&burn_dbconn, &tip, to_do)
+                .unwrap_or_else(|e| {
+                    warn!("Read replica query failed"; "tip" => %tip, "err" => ?e);
+                    None
+                });
+            *result
+                .lock()
+                .expect("FATAL: read replica query lock poisoned") = Some(answer);
+            drop(pending);
+        });
+        if queue.try_send(job).is_err() {
+            return ReadReplicaQuery::refused();
+        }
+        query
+    }
+
+    /// Number of worker threads in the pool
+    pub fn num_workers(&self) -> usize {
+        self.workers.len()
+    }
+}
+
+impl Drop for ReadReplicaWorkers {
+    fn drop(&mut self) {
+        // closing the queue stops each worker once it finishes its current query
+        self.queue.take();
+        for worker in self.workers.drain(..) {
+            if worker.join().is_err() {
+                warn!("Read replica worker panicked");
+            }
+        }
+    }
+}
@@ -0,0 +1,357 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A rolling store of transaction receipts.
+//!
+//! Transaction receipts are otherwise only available to event observers, at the time a block is
+//! processed.  When receipt tracking is enabled, the chains coordinator records the receipt of
+//! every transaction in each block it processes, so that a transaction's result, events, and
+//! execution cost can be looked up afterwards (see `/v3/transactions/:txid/receipt`).
+//!
+//! Receipts are kept for the most recent `retention` Stacks block heights.  Receipts from every
+//! processed fork are kept; readers decide which of them is on the fork they care about.
+
+use std::path::Path;
+
+use clarity::types::sqlite::NO_PARAMS;
+use clarity::vm::costs::ExecutionCost;
+use rusqlite::{params, Connection, Error as SqliteError, OpenFlags};
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::util::hash::to_hex;
+
+use crate::burnchains::Txid;
+use crate::chainstate::stacks::db::StacksEpochReceipt;
+use crate::util_lib::db::{
+    query_rows, sqlite_open, table_exists, tx_begin_immediate_sqlite, DBTx, Error as db_error,
+};
+
+/// The outcome of a processed transaction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredTransactionReceipt {
+    pub txid: Txid,
+    /// The block that included the transaction
+    pub index_block_hash: StacksBlockId,
+    pub block_height: u64,
+    pub tx_index: u32,
+    /// Hex-encoded consensus serialization of the transaction's Clarity result
+    pub result: String,
+    pub vm_error: Option<String>,
+    pub post_condition_aborted: bool,
+    pub stx_burned: u128,
+    pub execution_cost: ExecutionCost,
+    /// The transaction's events, as sent to event observers in `new_block` payloads
+    pub events: Vec<serde_json::Value>,
+}
+
+const CREATE_TABLE: &str = "
+CREATE TABLE transaction_receipts (
+    txid TEXT NOT NULL,
+    index_block_hash TEXT NOT NULL,
+    block_height INTEGER NOT NULL,
+    receipt TEXT NOT NULL,
+    PRIMARY KEY(txid, index_block_hash)
+)";
+const CREATE_INDEX: &str = "CREATE INDEX IF NOT EXISTS index_transaction_receipts_by_height ON transaction_receipts(block_height)";
+const CREATE_BLOCK_INDEX: &str = "CREATE INDEX IF NOT EXISTS index_transaction_receipts_by_block ON transaction_receipts(index_block_hash)";
+
+/// Heights are compared in SQL, so they are stored as (saturated) i64s
+fn saturating_u64_to_sql(x: u64) -> i64 {
+    i64::try_from(x).unwrap_or(i64::MAX)
+}
+
+pub struct TransactionReceiptDB {
+    db: Connection,
+    retention: u64,
+}
+
+impl TransactionReceiptDB {
+    pub fn open(p: &Path, retention: u64) -> Result<TransactionReceiptDB, db_error> {
+        let mut db = sqlite_open(p, OpenFlags::SQLITE_OPEN_READ_WRITE, false).or_else(|e| {
+            if let SqliteError::SqliteFailure(ref internal, _) = e {
+                if let rusqlite::ErrorCode::CannotOpen = internal.code {
+                    let mut db = sqlite_open(
+                        p,
+                        OpenFlags::SQLITE_OPEN_CREATE | OpenFlags::SQLITE_OPEN_READ_WRITE,
+                        false,
+                    )?;
+                    let tx = tx_begin_immediate_sqlite(&mut db)?;
+                    TransactionReceiptDB::instantiate_db(&tx)?;
+                    tx.commit()?;
+                    Ok(db)
+                } else {
+                    Err(e)
+                }
+            } else {
+                Err(e)
+            }
+        })?;
+
+        // DBs created before receipts were looked up by block lack the block index
+        let tx = tx_begin_immediate_sqlite(&mut db)?;
+        tx.execute(CREATE_BLOCK_INDEX, NO_PARAMS)?;
+        tx.commit()?;
+
+        Ok(TransactionReceiptDB { db, retention })
+    }
+
+    /// Create the table, unless another thread that opened the DB at the same time already did
+    fn instantiate_db(tx: &DBTx) -> Result<(), SqliteError> {
+        if !table_exists(tx, "transaction_receipts")? {
+            tx.execute(CREATE_TABLE, NO_PARAMS)?;
+            tx.execute(CREATE_INDEX, NO_PARAMS)?;
+        }
+        Ok(())
+    }
+
+    /// Number of Stacks block heights for which receipts are kept
+    pub fn retention(&self) -> u64 {
+        self.retention
+    }
+
+    /// Convert a newly-processed block's transaction receipts into their stored form
+    pub fn make_stored_receipts(
+        receipt: &StacksEpochReceipt,
+    ) -> Result<Vec<StoredTransactionReceipt>, db_error> {
+        let index_block_hash = receipt.header.index_block_hash();
+        // events are numbered across the whole block, as they are for event observers
+        let mut event_index = 0;
+        let mut stored_receipts = Vec::with_capacity(receipt.tx_receipts.len());
+        for tx_receipt in receipt.tx_receipts.iter() {
+            let txid = tx_receipt.transaction.txid();
+            let committed = !tx_receipt.post_condition_aborted;
+            let mut events = Vec::with_capacity(tx_receipt.events.len());
+            for event in tx_receipt.events.iter() {
+                let event_json = event
+                    .json_serialize(event_index, &txid, committed)
+                    .map_err(|e| db_error::Other(format!("Failed to serialize event: {e:?}")))?;
+                events.push(event_json);
+                event_index += 1;
+            }
+            let result = tx_receipt
+                .result
+                .serialize_to_vec()
+                .map_err(|e| db_error::Other(format!("Failed to serialize result: {e:?}")))?;
+            stored_receipts.push(StoredTransactionReceipt {
+                txid,
+                index_block_hash: index_block_hash.clone(),
+                block_height: receipt.header.stacks_block_height,
+                tx_index: tx_receipt.tx_index,
+                result: format!("0x{}", to_hex(&result)),
+                vm_error: tx_receipt.vm_error.clone(),
+                post_condition_aborted: tx_receipt.post_condition_aborted,
+                stx_burned: tx_receipt.stx_burned,
+                execution_cost: tx_receipt.execution_cost.clone(),
+                events,
+            });
+        }
+        Ok(stored_receipts)
+    }
+
+    /// Record the transaction receipts of a newly-processed block, and drop receipts from blocks
+    /// that are now older than the retention window.
+    pub fn notify_block(&mut self, receipt: &StacksEpochReceipt) -> Result<(), db_error> {
+        let block_height = receipt.header.stacks_block_height;
+        let stored_receipts = Self::make_stored_receipts(receipt)?;
+
+        let tx = tx_begin_immediate_sqlite(&mut self.db)?;
+        for stored_receipt in stored_receipts.iter() {
+            let receipt_json =
+                serde_json::to_string(stored_receipt).map_err(db_error::SerializationError)?;
+            tx.execute(
+                "INSERT OR REPLACE INTO transaction_receipts
+                    (txid, index_block_hash, block_height, receipt)
+                    VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    stored_receipt.txid,
+                    stored_receipt.index_block_hash,
+                    saturating_u64_to_sql(block_height),
+                    receipt_json,
+                ],
+            )?;
+        }
+        if block_height >= self.retention {
+            let oldest_height = saturating_u64_to_sql(block_height - self.retention);
+            tx.execute(
+                "DELETE FROM transaction_receipts WHERE block_height <= ?1",
+                params![oldest_height],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Get every stored receipt of a transaction, one for each processed block that included it,
+    /// with the highest blocks first
+    pub fn get_receipts(&self, txid: &Txid) -> Result<Vec<StoredTransactionReceipt>, db_error> {
+        let receipts_json: Vec<String> = query_rows(
+            &self.db,
+            "SELECT receipt FROM transaction_receipts WHERE txid = ?1 ORDER BY block_height DESC",
+            params![txid],
+        )?;
+        receipts_json
+            .iter()
+            .map(|receipt_json| {
+                serde_json::from_str(receipt_json).map_err(db_error::SerializationError)
+            })
+            .collect()
+    }
+
+    /// Get the stored receipts of the transactions in a block, in block order.  Returns an empty
+    /// list if the block had no transactions, or if its receipts are no longer retained.
+    pub fn get_block_receipts(
+        &self,
+        index_block_hash: &StacksBlockId,
+    ) -> Result<Vec<StoredTransactionReceipt>, db_error> {
+        let receipts_json: Vec<String> = query_rows(
+            &self.db,
+            "SELECT receipt FROM transaction_receipts WHERE index_block_hash = ?1",
+            params![index_block_hash],
+        )?;
+        let mut receipts = receipts_json
+            .iter()
+            .map(|receipt_json| {
+                serde_json::from_str(receipt_json).map_err(db_error::SerializationError)
+            })
+            .collect::<Result<Vec<StoredTransactionReceipt>, _>>()?;
+        receipts.sort_by_key(|receipt| receipt.tx_index);
+        Ok(receipts)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use clarity::vm::Value;
+    use rand::Rng;
+    use stacks_common::types::chainstate::{ConsensusHash, StacksAddress};
+    use stacks_common::util::hash::Hash160;
+
+    use super::*;
+    use crate::chainstate::stacks::events::StacksTransactionReceipt;
+    use crate::chainstate::stacks::{
+        StacksTransaction, TransactionAuth, TransactionContractCall, TransactionPayload,
+        TransactionSpendingCondition, TransactionVersion,
+    };
+    use crate::cost_estimates::tests::common::make_block_receipt;
+
+    fn instantiate_test_db(retention: u64) -> TransactionReceiptDB {
+        let mut path = env::temp_dir();
+        let random_bytes = rand::thread_rng().gen::<[u8; 32]>();
+        path.push(format!("receipts_{}.sqlite", &to_hex(&random_bytes)[0..8]));
+        TransactionReceiptDB::open(&path, retention)
+            .expect("Test failure: could not open transaction receipts DB")
+    }
+
+    fn make_tx_receipt(function_name: &str) -> StacksTransactionReceipt {
+        let tx = StacksTransaction::new(
+            TransactionVersion::Mainnet,
+            TransactionAuth::Standard(TransactionSpendingCondition::new_initial_sighash()),
+            TransactionPayload::ContractCall(TransactionContractCall {
+                address: StacksAddress::new(0, Hash160([0; 20])).unwrap(),
+                contract_name: "contract".into(),
+                function_name: function_name.into(),
+                function_args: vec![],
+            }),
+        );
+        StacksTransactionReceipt::from_contract_call(
+            tx,
+            vec![],
+            Value::okay(Value::UInt(1)).unwrap(),
+            0,
+            ExecutionCost::ZERO,
+        )
+    }
+
+    fn record_block(
+        db: &mut TransactionReceiptDB,
+        height: u64,
+        fork: u8,
+        tx_receipts: Vec<StacksTransactionReceipt>,
+    ) -> StacksBlockId {
+        let mut receipt = make_block_receipt(tx_receipts);
+        receipt.header.stacks_block_height = height;
+        receipt.header.consensus_hash = ConsensusHash([fork; 20]);
+        db.notify_block(&receipt).unwrap();
+        receipt.header.index_block_hash()
+    }
+
+    #[test]
+    fn test_receipts_across_forks_and_retention() {
+        let mut db = instantiate_test_db(2);
+        let tx_receipt = make_tx_receipt("f");
+        let txid = tx_receipt.transaction.txid();
+        assert!(db.get_receipts(&txid).unwrap().is_empty());
+
+        // mined at the same height in two forks, then again in a higher block
+        let fork_a = record_block(&mut db, 1, 1, vec![tx_receipt.clone()]);
+        let fork_b = record_block(&mut db, 1, 2, vec![tx_receipt.clone()]);
+        let mut later_tx_receipt = tx_receipt.clone();
+        later_tx_receipt.tx_index = 1;
+        let later = record_block(&mut db, 2, 1, vec![make_tx_receipt("g"), later_tx_receipt]);
+
+        let receipts = db.get_receipts(&txid).unwrap();
+        assert_eq!(receipts.len(), 3);
+        assert_eq!(receipts[0].index_block_hash, later);
+        assert_eq!(receipts[0].tx_index, 1);
+        assert_eq!(receipts[0].block_height, 2);
+        let fork_blocks: Vec<_> = receipts[1..]
+            .iter()
+            .map(|receipt| receipt.index_block_hash.clone())
+            .collect();
+        assert!(fork_blocks.contains(&fork_a));
+        assert!(fork_blocks.contains(&fork_b));
+        let result = Value::okay(Value::UInt(1)).unwrap();
+        assert_eq!(
+            receipts[0].result,
+            format!("0x{}", to_hex(&result.serialize_to_vec().unwrap()))
+        );
+        assert!(!receipts[0].post_condition_aborted);
+
+        // receipts at or below `height - retention` are dropped
+        record_block(&mut db, 3, 1, vec![]);
+        let receipts = db.get_receipts(&txid).unwrap();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].index_block_hash, later);
+    }
+
+    #[test]
+    fn test_block_receipts() {
+        let mut db = instantiate_test_db(2);
+        let mut second_tx_receipt = make_tx_receipt("g");
+        second_tx_receipt.tx_index = 1;
+        let block = record_block(
+            &mut db,
+            1,
+            1,
+            vec![second_tx_receipt.clone(), make_tx_receipt("f")],
+        );
+        let other_block = record_block(&mut db, 1, 2, vec![make_tx_receipt("h")]);
+
+        let receipts = db.get_block_receipts(&block).unwrap();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0].tx_index, 0);
+        assert_eq!(receipts[1].tx_index, 1);
+        assert_eq!(receipts[1].txid, second_tx_receipt.transaction.txid());
+        assert!(receipts
+            .iter()
+            .all(|receipt| receipt.index_block_hash == block));
+        assert_eq!(db.get_block_receipts(&other_block).unwrap().len(), 1);
+
+        // pruned blocks have no receipts
+        record_block(&mut db, 3, 1, vec![]);
+        assert!(db.get_block_receipts(&block).unwrap().is_empty());
+    }
+}
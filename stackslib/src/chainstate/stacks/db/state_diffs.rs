@@ -0,0 +1,551 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A rolling store of per-block state diffs.
+//!
+//! Indexers that want to follow contract state otherwise have to replay every block's
+//! transactions, or query each data var and map entry they care about after every block.  When
+//! state diff tracking is enabled, the chains coordinator records, for every block it processes,
+//! the data vars and map entries the block wrote and the net change it made to each token
+//! balance (see `/v3/blocks/:block_id/state-diff`).
+//!
+//! Data var and map writes come from the Clarity key/value pairs the block committed, so they
+//! only include writes from transactions that were not rolled back.  Token balance deltas are
+//! computed from the block's committed STX and fungible token events, its transaction fees, and
+//! the miner rewards that matured in it.
+//!
+//! Diffs are kept for the most recent `retention` Stacks block heights.  Diffs from every
+//! processed fork are kept; readers decide which of them is on the fork they care about.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use clarity::types::sqlite::NO_PARAMS;
+use clarity::vm::database::StoreType;
+use clarity::vm::events::{FTEventType, STXEventType, StacksTransactionEvent};
+use clarity::vm::types::PrincipalData;
+use clarity::vm::Value;
+use rusqlite::{params, Connection, Error as SqliteError, OpenFlags};
+use stacks_common::types::chainstate::StacksBlockId;
+
+use crate::chainstate::stacks::db::StacksEpochReceipt;
+use crate::chainstate::stacks::events::TransactionOrigin;
+use crate::util_lib::db::{
+    query_row, sqlite_open, table_exists, tx_begin_immediate_sqlite, DBTx, Error as db_error,
+};
+
+/// The asset name used for STX in token balance deltas
+pub const STX_ASSET: &str = "stx";
+
+/// A data var that a block set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataVarDiff {
+    pub contract_id: String,
+    pub name: String,
+    /// Hex-encoded consensus serialization of the var's new value
+    pub value: String,
+}
+
+/// A map entry that a block set or deleted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapEntryDiff {
+    pub contract_id: String,
+    pub map: String,
+    /// Hex-encoded consensus serialization of the entry's key
+    pub key: String,
+    /// Hex-encoded consensus serialization of the entry's new value, or `None` if it was deleted
+    pub value: Option<String>,
+}
+
+/// The net change a block made to a principal's balance of one token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenBalanceDelta {
+    /// `"stx"`, or the fungible token's asset identifier
+    pub asset: String,
+    pub principal: String,
+    /// Signed change in the principal's balance.  For STX, locking and unlocking do not change
+    /// the balance.  Encoded as a string, since it can exceed the range of a JSON number.
+    pub delta: String,
+}
+
+/// The state changes made by a processed block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockStateDiff {
+    pub index_block_hash: StacksBlockId,
+    pub block_height: u64,
+    /// Each data var the block set, with its value at the end of the block
+    pub data_vars: Vec<DataVarDiff>,
+    /// Each map entry the block set or deleted, with its value at the end of the block
+    pub map_entries: Vec<MapEntryDiff>,
+    /// Each nonzero token balance change the block made
+    pub token_deltas: Vec<TokenBalanceDelta>,
+}
+
+impl BlockStateDiff {
+    /// Compute the state diff of a newly-processed block from its receipt.  The receipt's
+    /// Clarity writes are only present if the block was processed with write recording enabled.
+    pub fn from_receipt(receipt: &StacksEpochReceipt) -> Result<BlockStateDiff, db_error> {
+        // only the last write to each var or entry matters; sorted for a stable output
+        let mut data_vars = BTreeMap::new();
+        let mut map_entries = BTreeMap::new();
+        for (key, value) in receipt.clarity_writes.iter() {
+            let Some(rest) = key.strip_prefix("vm::") else {
+                continue;
+            };
+            let mut parts = rest.splitn(4, "::");
+            let (Some(contract_id), Some(store_type), Some(name)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+            match (StoreType::try_from(store_type), parts.next()) {
+                (Ok(StoreType::Variable), None) => {
+                    data_vars.insert((contract_id, name), format!("0x{value}"));
+                }
+                (Ok(StoreType::DataMap), Some(map_key)) => {
+                    // map entries are stored as optionals, with `none` marking a deleted entry
+                    let entry_value = match Value::try_deserialize_hex_untyped(value) {
+                        Ok(Value::Optional(entry)) => entry.data,
+                        Ok(v) => {
+                            return Err(db_error::Other(format!(
+                                "Map entry {key} is not an optional: {v}"
+                            )))
+                        }
+                        Err(e) => {
+                            return Err(db_error::Other(format!(
+                                "Failed to deserialize map entry {key}: {e:?}"
+                            )))
+                        }
+                    };
+                    let entry_value = entry_value
+                        .map(|v| v.serialize_to_hex())
+                        .transpose()
+                        .map_err(|e| {
+                            db_error::Other(format!("Failed to serialize map entry {key}: {e:?}"))
+                        })?
+                        .map(|hex| format!("0x{hex}"));
+                    map_entries.insert((contract_id, name, map_key), entry_value);
+                }
+                _ => {}
+            }
+        }
+
+        let data_vars = data_vars
+            .into_iter()
+            .map(|((contract_id, name), value)| DataVarDiff {
+                contract_id: contract_id.to_string(),
+                name: name.to_string(),
+                value,
+            })
+            .collect();
+        let map_entries = map_entries
+            .into_iter()
+            .map(|((contract_id, map, key), value)| MapEntryDiff {
+                contract_id: contract_id.to_string(),
+                map: map.to_string(),
+                key: format!("0x{key}"),
+                value,
+            })
+            .collect();
+
+        Ok(BlockStateDiff {
+            index_block_hash: receipt.header.index_block_hash(),
+            block_height: receipt.header.stacks_block_height,
+            data_vars,
+            map_entries,
+            token_deltas: Self::make_token_deltas(receipt),
+        })
+    }
+
+    /// Sum up the token balance changes made by a block
+    fn make_token_deltas(receipt: &StacksEpochReceipt) -> Vec<TokenBalanceDelta> {
+        let mut deltas: BTreeMap<(String, String), i128> = BTreeMap::new();
+        let mut add = |asset: String, principal: &PrincipalData, amount: u128, credit: bool| {
+            let amount = i128::try_from(amount).unwrap_or(i128::MAX);
+            let delta = deltas.entry((asset, principal.to_string())).or_insert(0);
+            *delta = if credit {
+                delta.saturating_add(amount)
+            } else {
+                delta.saturating_sub(amount)
+            };
+        };
+
+        for reward in receipt.matured_rewards.iter() {
+            add(STX_ASSET.into(), &reward.recipient, reward.total(), true);
+        }
+
+        for tx_receipt in receipt.tx_receipts.iter() {
+            // fees are paid even if the transaction's post-conditions aborted it
+            if let TransactionOrigin::Stacks(tx) = &tx_receipt.transaction {
+                let payer = tx.sponsor_address().unwrap_or_else(|| tx.origin_address());
+                add(
+                    STX_ASSET.into(),
+                    &payer.to_account_principal(),
+                    tx.get_tx_fee().into(),
+                    false,
+                );
+            }
+            if tx_receipt.post_condition_aborted {
+                continue;
+            }
+            for event in tx_receipt.events.iter() {
+                match event {
+                    StacksTransactionEvent::STXEvent(STXEventType::STXTransferEvent(data)) => {
+                        add(STX_ASSET.into(), &data.sender, data.amount, false);
+                        add(STX_ASSET.into(), &data.recipient, data.amount, true);
+                    }
+                    StacksTransactionEvent::STXEvent(STXEventType::STXMintEvent(data)) => {
+                        add(STX_ASSET.into(), &data.recipient, data.amount, true);
+                    }
+                    StacksTransactionEvent::STXEvent(STXEventType::STXBurnEvent(data)) => {
+                        add(STX_ASSET.into(), &data.sender, data.amount, false);
+                    }
+                    StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(data)) => {
+                        let asset = data.asset_identifier.to_string();
+                        add(asset.clone(), &data.sender, data.amount, false);
+                        add(asset, &data.recipient, data.amount, true);
+                    }
+                    StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(data)) => {
+                        add(
+                            data.asset_identifier.to_string(),
+                            &data.recipient,
+                            data.amount,
+                            true,
+                        );
+                    }
+                    StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(data)) => {
+                        add(
+                            data.asset_identifier.to_string(),
+                            &data.sender,
+                            data.amount,
+                            false,
+                        );
+                    }
+                    StacksTransactionEvent::STXEvent(STXEventType::STXLockEvent(_))
+                    | StacksTransactionEvent::NFTEvent(_)
+                    | StacksTransactionEvent::SmartContractEvent(_) => {}
+                }
+            }
+        }
+
+        deltas
+            .into_iter()
+            .filter(|(_, delta)| *delta != 0)
+            .map(|((asset, principal), delta)| TokenBalanceDelta {
+                asset,
+                principal,
+                delta: delta.to_string(),
+            })
+            .collect()
+    }
+}
+
+const CREATE_TABLE: &str = "
+CREATE TABLE block_state_diffs (
+    index_block_hash TEXT PRIMARY KEY NOT NULL,
+    block_height INTEGER NOT NULL,
+    diff TEXT NOT NULL
+)";
+const CREATE_INDEX: &str = "CREATE INDEX IF NOT EXISTS index_block_state_diffs_by_height ON block_state_diffs(block_height)";
+
+/// Heights are compared in SQL, so they are stored as (saturated) i64s
+fn saturating_u64_to_sql(x: u64) -> i64 {
+    i64::try_from(x).unwrap_or(i64::MAX)
+}
+
+pub struct StateDiffDB {
+    db: Connection,
+    retention: u64,
+}
+
+impl StateDiffDB {
+    pub fn open(p: &Path, retention: u64) -> Result<StateDiffDB, db_error> {
+        let db = sqlite_open(p, OpenFlags::SQLITE_OPEN_READ_WRITE, false).or_else(|e| {
+            if let SqliteError::SqliteFailure(ref internal, _) = e {
+                if let rusqlite::ErrorCode::CannotOpen = internal.code {
+                    let mut db = sqlite_open(
+                        p,
+                        OpenFlags::SQLITE_OPEN_CREATE | OpenFlags::SQLITE_OPEN_READ_WRITE,
+                        false,
+                    )?;
+                    let tx = tx_begin_immediate_sqlite(&mut db)?;
+                    StateDiffDB::instantiate_db(&tx)?;
+                    tx.commit()?;
+                    Ok(db)
+                } else {
+                    Err(e)
+                }
+            } else {
+                Err(e)
+            }
+        })?;
+
+        Ok(StateDiffDB { db, retention })
+    }
+
+    /// Create the table, unless another thread that opened the DB at the same time already did
+    fn instantiate_db(tx: &DBTx) -> Result<(), SqliteError> {
+        if !table_exists(tx, "block_state_diffs")? {
+            tx.execute(CREATE_TABLE, NO_PARAMS)?;
+            tx.execute(CREATE_INDEX, NO_PARAMS)?;
+        }
+        Ok(())
+    }
+
+    /// Number of Stacks block heights for which diffs are kept
+    pub fn retention(&self) -> u64 {
+        self.retention
+    }
+
+    /// Record the state diff of a newly-processed block, and drop diffs of blocks that are now
+    /// older than the retention window.
+    pub fn notify_block(&mut self, receipt: &StacksEpochReceipt) -> Result<(), db_error> {
+        let diff = BlockStateDiff::from_receipt(receipt)?;
+        let diff_json = serde_json::to_string(&diff).map_err(db_error::SerializationError)?;
+
+        let tx = tx_begin_immediate_sqlite(&mut self.db)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO block_state_diffs (index_block_hash, block_height, diff)
+                VALUES (?1, ?2, ?3)",
+            params![
+                diff.index_block_hash,
+                saturating_u64_to_sql(diff.block_height),
+                diff_json,
+            ],
+        )?;
+        if diff.block_height >= self.retention {
+            let oldest_height = saturating_u64_to_sql(diff.block_height - self.retention);
+            tx.execute(
+                "DELETE FROM block_state_diffs WHERE block_height <= ?1",
+                params![oldest_height],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Get the state diff of a block, if it was recorded and is still retained
+    pub fn get_block_state_diff(
+        &self,
+        index_block_hash: &StacksBlockId,
+    ) -> Result<Option<BlockStateDiff>, db_error> {
+        let diff_json: Option<String> = query_row(
+            &self.db,
+            "SELECT diff FROM block_state_diffs WHERE index_block_hash = ?1",
+            params![index_block_hash],
+        )?;
+        diff_json
+            .map(|diff_json| serde_json::from_str(&diff_json).map_err(db_error::SerializationError))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use clarity::vm::costs::ExecutionCost;
+    use clarity::vm::database::ClarityDatabase;
+    use clarity::vm::events::{FTTransferEventData, STXTransferEventData};
+    use clarity::vm::types::{AssetIdentifier, BuffData, QualifiedContractIdentifier};
+    use rand::Rng;
+    use stacks_common::types::chainstate::{ConsensusHash, StacksAddress};
+    use stacks_common::util::hash::{to_hex, Hash160};
+
+    use super::*;
+    use crate::chainstate::stacks::events::StacksTransactionReceipt;
+    use crate::chainstate::stacks::{
+        StacksTransaction, TransactionAuth, TransactionContractCall, TransactionPayload,
+        TransactionSpendingCondition, TransactionVersion,
+    };
+    use crate::cost_estimates::tests::common::make_block_receipt;
+
+    fn instantiate_test_db(retention: u64) -> StateDiffDB {
+        let mut path = env::temp_dir();
+        let random_bytes = rand::thread_rng().gen::<[u8; 32]>();
+        path.push(format!(
+            "state_diffs_{}.sqlite",
+            &to_hex(&random_bytes)[0..8]
+        ));
+        StateDiffDB::open(&path, retention).expect("Test failure: could not open state diff DB")
+    }
+
+    fn principal(byte: u8) -> PrincipalData {
+        StacksAddress::new(26, Hash160([byte; 20]))
+            .unwrap()
+            .to_account_principal()
+    }
+
+    fn make_tx_receipt(
+        events: Vec<StacksTransactionEvent>,
+        post_condition_aborted: bool,
+    ) -> StacksTransactionReceipt {
+        let mut tx = StacksTransaction::new(
+            TransactionVersion::Testnet,
+            TransactionAuth::Standard(TransactionSpendingCondition::new_initial_sighash()),
+            TransactionPayload::ContractCall(TransactionContractCall {
+                address: StacksAddress::new(26, Hash160([0; 20])).unwrap(),
+                contract_name: "contract".into(),
+                function_name: "f".into(),
+                function_args: vec![],
+            }),
+        );
+        tx.set_tx_fee(10);
+        let mut receipt = StacksTransactionReceipt::from_contract_call(
+            tx,
+            events,
+            Value::okay_true(),
+            0,
+            ExecutionCost::ZERO,
+        );
+        receipt.post_condition_aborted = post_condition_aborted;
+        receipt
+    }
+
+    fn record_block(db: &mut StateDiffDB, height: u64, fork: u8) -> StacksBlockId {
+        let mut receipt = make_block_receipt(vec![]);
+        receipt.header.stacks_block_height = height;
+        receipt.header.consensus_hash = ConsensusHash([fork; 20]);
+        db.notify_block(&receipt).unwrap();
+        receipt.header.index_block_hash()
+    }
+
+    #[test]
+    fn test_diff_from_receipt() {
+        let contract_id =
+            QualifiedContractIdentifier::parse("ST000000000000000000002AMW42H.contract").unwrap();
+        let var_key =
+            ClarityDatabase::make_key_for_trip(&contract_id, StoreType::Variable, "counter");
+        let entry_key =
+            ClarityDatabase::make_key_for_data_map_entry(&contract_id, "names", &Value::UInt(1))
+                .unwrap();
+        let deleted_key =
+            ClarityDatabase::make_key_for_data_map_entry(&contract_id, "names", &Value::UInt(2))
+                .unwrap();
+        let some_value = Value::some(Value::Int(5))
+            .unwrap()
+            .serialize_to_hex()
+            .unwrap();
+
+        let asset = AssetIdentifier {
+            contract_identifier: contract_id.clone(),
+            asset_name: "token".into(),
+        };
+        let stx_transfer = StacksTransactionEvent::STXEvent(STXEventType::STXTransferEvent(
+            STXTransferEventData {
+                sender: principal(0),
+                recipient: principal(1),
+                amount: 100,
+                memo: BuffData::empty(),
+            },
+        ));
+        let ft_transfer =
+            StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(FTTransferEventData {
+                asset_identifier: asset.clone(),
+                sender: principal(1),
+                recipient: principal(2),
+                amount: 7,
+            }));
+
+        let mut receipt = make_block_receipt(vec![
+            make_tx_receipt(vec![stx_transfer.clone(), ft_transfer], false),
+            // only the fee of an aborted transaction is counted
+            make_tx_receipt(vec![stx_transfer], true),
+        ]);
+        receipt.clarity_writes = vec![
+            (var_key.clone(), Value::UInt(1).serialize_to_hex().unwrap()),
+            (entry_key, some_value),
+            (var_key, Value::UInt(2).serialize_to_hex().unwrap()),
+            (deleted_key, Value::none().serialize_to_hex().unwrap()),
+            // account and metadata writes are not part of the diff
+            (
+                ClarityDatabase::make_key_for_account_nonce(&principal(0)),
+                "00".into(),
+            ),
+            (
+                ClarityDatabase::make_metadata_key(StoreType::Contract, "contract"),
+                "00".into(),
+            ),
+        ];
+
+        let diff = BlockStateDiff::from_receipt(&receipt).unwrap();
+        assert_eq!(diff.index_block_hash, receipt.header.index_block_hash());
+        assert_eq!(
+            diff.data_vars,
+            vec![DataVarDiff {
+                contract_id: contract_id.to_string(),
+                name: "counter".into(),
+                value: format!("0x{}", Value::UInt(2).serialize_to_hex().unwrap()),
+            }]
+        );
+        assert_eq!(
+            diff.map_entries,
+            vec![
+                MapEntryDiff {
+                    contract_id: contract_id.to_string(),
+                    map: "names".into(),
+                    key: format!("0x{}", Value::UInt(1).serialize_to_hex().unwrap()),
+                    value: Some(format!("0x{}", Value::Int(5).serialize_to_hex().unwrap())),
+                },
+                MapEntryDiff {
+                    contract_id: contract_id.to_string(),
+                    map: "names".into(),
+                    key: format!("0x{}", Value::UInt(2).serialize_to_hex().unwrap()),
+                    value: None,
+                },
+            ]
+        );
+
+        let delta_of = |asset: &str, principal: &PrincipalData| {
+            diff.token_deltas
+                .iter()
+                .find(|delta| delta.asset == asset && delta.principal == principal.to_string())
+                .map(|delta| delta.delta.clone())
+        };
+        // principal(0) is the origin of both transactions, and paid both fees
+        assert_eq!(delta_of(STX_ASSET, &principal(0)), Some("-120".into()));
+        assert_eq!(delta_of(STX_ASSET, &principal(1)), Some("100".into()));
+        assert_eq!(
+            delta_of(&asset.to_string(), &principal(1)),
+            Some("-7".into())
+        );
+        assert_eq!(
+            delta_of(&asset.to_string(), &principal(2)),
+            Some("7".into())
+        );
+    }
+
+    #[test]
+    fn test_diffs_across_forks_and_retention() {
+        let mut db = instantiate_test_db(2);
+        let fork_a = record_block(&mut db, 1, 1);
+        let fork_b = record_block(&mut db, 1, 2);
+        assert!(db
+            .get_block_state_diff(&StacksBlockId([0; 32]))
+            .unwrap()
+            .is_none());
+
+        let diff = db.get_block_state_diff(&fork_a).unwrap().unwrap();
+        assert_eq!(diff.index_block_hash, fork_a);
+        assert_eq!(diff.block_height, 1);
+        assert!(db.get_block_state_diff(&fork_b).unwrap().is_some());
+
+        // diffs at or below `height - retention` are dropped
+        let later = record_block(&mut db, 3, 1);
+        assert!(db.get_block_state_diff(&fork_a).unwrap().is_none());
+        assert!(db.get_block_state_diff(&fork_b).unwrap().is_none());
+        assert!(db.get_block_state_diff(&later).unwrap().is_some());
+    }
+}
@@ -523,6 +523,16 @@ impl StacksChainState {
         config: &DBConfig,
         tx: &StacksTransaction,
         epoch_id: StacksEpochId,
+    ) -> Result<(), Error> {
+        StacksChainState::inner_process_transaction_precheck(config, tx, epoch_id, true)
+    }
+
+    /// Pre-check a transaction, optionally without verifying its signatures
+    fn inner_process_transaction_precheck(
+        config: &DBConfig,
+        tx: &StacksTransaction,
+        epoch_id: StacksEpochId,
+        verify_signatures: bool,
     ) -> Result<(), Error> {
         // valid auth?
         if !tx.auth.is_supported_in_epoch(epoch_id) {
@@ -534,7 +544,9 @@ impl StacksChainState {
 
             return Err(Error::InvalidStacksTransaction(msg, false));
         }
-        tx.verify().map_err(Error::NetError)?;
+        if verify_signatures {
+            tx.verify().map_err(Error::NetError)?;
+        }
 
         // destined for us?
         if config.chain_id != tx.chain_id {
@@ -1471,11 +1483,50 @@ impl StacksChainState {
         tx: &StacksTransaction,
         quiet: bool,
         ast_rules: ASTRules,
+    ) -> Result<(u64, StacksTransactionReceipt), Error> {
+        StacksChainState::inner_process_transaction(clarity_block, tx, quiet, ast_rules, true)
+    }
+
+    /// Process a transaction whose signatures the caller has already verified with
+    /// `StacksTransaction::verify()`.  Return the fee and the transaction receipt.
+    pub fn process_verified_transaction(
+        clarity_block: &mut ClarityTx,
+        tx: &StacksTransaction,
+        quiet: bool,
+        ast_rules: ASTRules,
+    ) -> Result<(u64, StacksTransactionReceipt), Error> {
+        StacksChainState::inner_process_transaction(clarity_block, tx, quiet, ast_rules, false)
+    }
+
+    /// Process a transaction without verifying its signatures.  Return the fee and the
+    /// transaction receipt.
+    ///
+    /// This is only for simulating transactions in a block that gets rolled back afterwards.
+    /// Blocks must never be processed this way.
+    pub fn process_unsigned_transaction(
+        clarity_block: &mut ClarityTx,
+        tx: &StacksTransaction,
+        ast_rules: ASTRules,
+    ) -> Result<(u64, StacksTransactionReceipt), Error> {
+        StacksChainState::inner_process_transaction(clarity_block, tx, true, ast_rules, false)
+    }
+
+    fn inner_process_transaction(
+        clarity_block: &mut ClarityTx,
+        tx: &StacksTransaction,
+        quiet: bool,
+        ast_rules: ASTRules,
+        verify_signatures: bool,
     ) -> Result<(u64, StacksTransactionReceipt), Error> {
         debug!("Process transaction {} ({})", tx.txid(), tx.payload.name());
         let epoch = clarity_block.get_epoch();
 
-        StacksChainState::process_transaction_precheck(&clarity_block.config, tx, epoch)?;
+        StacksChainState::inner_process_transaction_precheck(
+            &clarity_block.config,
+            tx,
+            epoch,
+            verify_signatures,
+        )?;
 
         // what version of Clarity did the transaction caller want? And, is it valid now?
         let clarity_version = StacksChainState::get_tx_clarity_version(clarity_block, tx)?;
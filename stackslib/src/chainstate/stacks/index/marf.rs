@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::HashSet;
 use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 use std::ops::DerefMut;
 use std::path::PathBuf;
@@ -71,6 +72,9 @@ pub struct MARFOpenOpts {
     pub external_blobs: bool,
     /// unconditionally do a DB migration (used for testing)
     pub force_db_migrate: bool,
+    /// remember the ancestor trie root hashes looked up while committing a trie, so that a run of
+    /// consecutive tries (such as the blocks of a tenure) can reuse them
+    pub batch_ancestor_hashes: bool,
 }
 
 impl MARFOpenOpts {
@@ -80,6 +84,7 @@ impl MARFOpenOpts {
             cache_strategy: "noop".to_string(),
             external_blobs: false,
             force_db_migrate: false,
+            batch_ancestor_hashes: false,
         }
     }
 
@@ -93,6 +98,7 @@ impl MARFOpenOpts {
             cache_strategy: cache_strategy.to_string(),
             external_blobs,
             force_db_migrate: false,
+            batch_ancestor_hashes: false,
         }
     }
 
@@ -1227,6 +1233,73 @@ impl<T: MarfTrieId> MARF<T> {
         result.map(|option_result| option_result.map(|leaf| leaf.data))
     }
 
+    /// Visit every leaf reachable from the trie of `block_hash`, including the leaves of ancestor
+    /// tries that it reaches through back-pointers.  Nodes are identified by their trie's local
+    /// block ID and their pointer within that trie.  Nodes already in `visited` are skipped along
+    /// with their children, so walks that share a `visited` set visit common subtries only once.
+    pub fn walk_leaves<F>(
+        storage: &mut TrieStorageConnection<T>,
+        block_hash: &T,
+        visited: &mut HashSet<(u32, u32)>,
+        mut visitor: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&TrieLeaf),
+    {
+        let (cur_block_hash, cur_block_id) = storage.get_cur_block_and_id();
+
+        let result = MARF::inner_walk_leaves(storage, block_hash, visited, &mut visitor);
+
+        // restore
+        storage
+            .open_block_maybe_id(&cur_block_hash, cur_block_id)
+            .inspect_err(|e| {
+                warn!("Failed to re-open {cur_block_hash} {cur_block_id:?}: {e:?}");
+                warn!("Result of failed leaf walk from {block_hash}: {result:?}");
+            })?;
+
+        result
+    }
+
+    fn inner_walk_leaves<F>(
+        storage: &mut TrieStorageConnection<T>,
+        block_hash: &T,
+        visited: &mut HashSet<(u32, u32)>,
+        visitor: &mut F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&TrieLeaf),
+    {
+        storage.open_block(block_hash)?;
+        let mut frontier = vec![(storage.get_cur_block_identifier()?, storage.root_trieptr())];
+
+        while let Some((block_id, ptr)) = frontier.pop() {
+            if !visited.insert((block_id, ptr.ptr())) {
+                continue;
+            }
+
+            let node_block_hash = storage.get_block_from_local_id(block_id)?.clone();
+            storage.open_block_known_id(&node_block_hash, block_id)?;
+            let (node, _) = storage.read_nodetype(&ptr)?;
+            if let TrieNodeType::Leaf(leaf) = &node {
+                visitor(leaf);
+                continue;
+            }
+
+            for child in node.ptrs() {
+                if child.id() == TrieNodeID::Empty as u8 {
+                    continue;
+                }
+                if is_backptr(child.id()) {
+                    frontier.push((child.back_block(), child.from_backptr()));
+                } else {
+                    frontier.push((block_id, child.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_block_height_miner_tip(
         storage: &mut TrieStorageConnection<T>,
         block_hash: &T,
@@ -1255,6 +1255,13 @@ pub struct TrieStorageTransientData<T: MarfTrieId> {
     /// query more than once.
     trie_ancestor_hash_bytes_cache: Option<(T, Vec<TrieHash>)>,
 
+    /// Should ancestor trie root hashes be remembered across consecutive trie commits?
+    batch_ancestor_hashes: bool,
+
+    /// Ancestor trie root hashes of the most recently committed trie, if
+    /// `batch_ancestor_hashes` is set.
+    trie_ancestor_batch: Option<TrieAncestorBatch<T>>,
+
     /// Is the trie opened read-only?
     readonly: bool,
 
@@ -1262,6 +1269,40 @@ pub struct TrieStorageTransientData<T: MarfTrieId> {
     unconfirmed: bool,
 }
 
+/// Maximum number of ancestor root hashes to remember in a `TrieAncestorBatch` before starting
+/// over.
+const TRIE_ANCESTOR_BATCH_MAX: usize = 4096;
+
+///
+///  TrieAncestorBatch remembers the ancestor trie root hashes looked up while sealing a run of
+///   consecutive tries, such as the blocks of a tenure.  Each trie's root hash mixes in the root
+///   hashes of the tries 1, 2, 4, 8, ... blocks before it, and finding each of those ancestors
+///   requires a MARF lookup.  Consecutive tries share most of their ancestry, so once a trie is
+///   committed, its child can reuse the ancestors found so far instead of looking them up again.
+///   The root hash of a sealed trie is remembered when it is committed, so its child does not
+///   read it back from storage either.  Committed trie root hashes never change, so reusing them
+///   does not alter any root hash.
+///
+struct TrieAncestorBatch<T: MarfTrieId> {
+    /// The most recently committed trie in the run
+    tip: T,
+    /// The height of `tip`
+    tip_height: u32,
+    /// Ancestor trie and its root hash at each height looked up so far.  All of them are
+    /// ancestors of (or equal to) `tip`.
+    ancestors: HashMap<u32, (T, TrieHash)>,
+}
+
+impl<T: MarfTrieId> TrieAncestorBatch<T> {
+    fn new(tip: T, tip_height: u32) -> Self {
+        Self {
+            tip,
+            tip_height,
+            ancestors: HashMap::new(),
+        }
+    }
+}
+
 // disk-backed Trie.
 // Keeps the last-extended Trie in-RAM and flushes it to disk on either a call to flush() or a call
 // to extend_to_block() with a different block header hash.
@@ -1458,6 +1499,9 @@ impl<T: MarfTrieId> TrieFileStorage<T> {
 
                 trie_ancestor_hash_bytes_cache: None,
 
+                batch_ancestor_hashes: marf_opts.batch_ancestor_hashes,
+                trie_ancestor_batch: None,
+
                 readonly,
                 unconfirmed,
             },
@@ -1547,6 +1591,9 @@ impl<T: MarfTrieId> TrieFileStorage<T> {
 
                 trie_ancestor_hash_bytes_cache: None,
 
+                batch_ancestor_hashes: false,
+                trie_ancestor_batch: None,
+
                 readonly: true,
                 unconfirmed: self.unconfirmed(),
             },
@@ -1616,6 +1663,9 @@ impl<'a, T: MarfTrieId> TrieStorageTransaction<'a, T> {
 
                 trie_ancestor_hash_bytes_cache: None,
 
+                batch_ancestor_hashes: false,
+                trie_ancestor_batch: None,
+
                 readonly: true,
                 unconfirmed: self.unconfirmed(),
             },
@@ -1653,6 +1703,11 @@ impl<'a, T: MarfTrieId> TrieStorageTransaction<'a, T> {
         }
         if let Some((bhh, trie_ram)) = self.data.uncommitted_writes.take() {
             trace!("Buffering block flush started.");
+            let parent = trie_ram.trie_ram_ref().parent.clone();
+            let sealed_root_hash = match trie_ram {
+                UncommittedState::Sealed(_, ref root_hash) => Some(root_hash.clone()),
+                UncommittedState::RW(_) => None,
+            };
             let mut buffer = Cursor::new(Vec::new());
             trie_ram.dump(self, &mut buffer, &bhh)?;
 
@@ -1667,13 +1722,15 @@ impl<'a, T: MarfTrieId> TrieStorageTransaction<'a, T> {
                     if self.unconfirmed() {
                         return Err(Error::UnconfirmedError);
                     }
-                    self.with_trie_blobs(|db, blobs| match blobs {
+                    let block_id = self.with_trie_blobs(|db, blobs| match blobs {
                         Some(blobs) => blobs.store_trie_blob(db, &bhh, &buffer),
                         None => {
                             test_debug!("Stored trie blob {bhh} to db");
                             trie_sql::write_trie_blob(db, &bhh, &buffer)
                         }
-                    })?
+                    })?;
+                    self.advance_ancestor_batch(&parent, &bhh, sealed_root_hash);
+                    block_id
                 }
                 FlushOptions::NewHeader(real_bhh) => {
                     // If we opened a block with a given hash, but want to store it as a block with a *different*
@@ -1692,13 +1749,15 @@ impl<'a, T: MarfTrieId> TrieStorageTransaction<'a, T> {
                         // switch over state
                         self.data.retarget_block(real_bhh.clone());
                     }
-                    self.with_trie_blobs(|db, blobs| match blobs {
+                    let block_id = self.with_trie_blobs(|db, blobs| match blobs {
                         Some(blobs) => blobs.store_trie_blob(db, real_bhh, &buffer),
                         None => {
                             test_debug!("Stored trie blob {} to db", real_bhh);
                             trie_sql::write_trie_blob(db, real_bhh, &buffer)
                         }
-                    })?
+                    })?;
+                    self.advance_ancestor_batch(&parent, real_bhh, sealed_root_hash);
+                    block_id
                 }
                 FlushOptions::MinedTable(real_bhh) => {
                     if self.unconfirmed() {
@@ -1972,6 +2031,98 @@ impl<T: MarfTrieId> TrieStorageConnection<'_, T> {
         root_hash_res
     }
 
+    /// Get the ancestor batch to use while computing the ancestor root hashes of the trie `bhh`
+    /// at height `bhh_height`.  This is only available if batching is enabled and `bhh` is the
+    /// trie being extended.  If the current batch was built for a chain tip other than `bhh`'s
+    /// parent, then a new batch is started from the parent.
+    fn ancestor_batch_mut(
+        &mut self,
+        bhh: &T,
+        bhh_height: u32,
+    ) -> Option<&mut TrieAncestorBatch<T>> {
+        if !self.data.batch_ancestor_hashes || bhh_height == 0 {
+            return None;
+        }
+        let parent = match self.data.uncommitted_writes {
+            Some((ref uncommitted_bhh, ref uncommitted)) if uncommitted_bhh == bhh => {
+                uncommitted.trie_ram_ref().parent.clone()
+            }
+            _ => {
+                return None;
+            }
+        };
+        let tip_height = bhh_height - 1;
+        let reusable = self.data.trie_ancestor_batch.as_ref().is_some_and(|batch| {
+            batch.tip == parent
+                && batch.tip_height == tip_height
+                && batch.ancestors.len() < TRIE_ANCESTOR_BATCH_MAX
+        });
+        if !reusable {
+            self.data.trie_ancestor_batch = Some(TrieAncestorBatch::new(parent, tip_height));
+        }
+        self.data.trie_ancestor_batch.as_mut()
+    }
+
+    /// Get the block and root hash of the ancestor at `height` of the trie `bhh` (at height
+    /// `bhh_height`), if it was already looked up while committing a preceding trie.
+    pub fn get_batched_ancestor(
+        &mut self,
+        bhh: &T,
+        bhh_height: u32,
+        height: u32,
+    ) -> Option<(T, TrieHash)> {
+        self.ancestor_batch_mut(bhh, bhh_height)?
+            .ancestors
+            .get(&height)
+            .cloned()
+    }
+
+    /// Remember the block and root hash of the ancestor at `height` of the trie `bhh` (at height
+    /// `bhh_height`), so subsequent tries can reuse it.  Does nothing unless batching is enabled.
+    pub fn set_batched_ancestor(
+        &mut self,
+        bhh: &T,
+        bhh_height: u32,
+        height: u32,
+        ancestor: T,
+        root_hash: TrieHash,
+    ) {
+        if let Some(batch) = self.ancestor_batch_mut(bhh, bhh_height) {
+            batch.ancestors.insert(height, (ancestor, root_hash));
+        }
+    }
+
+    /// Advance the ancestor batch once the child of its tip, `parent`, has been committed as
+    /// `bhh`.  If `bhh` was sealed, its root hash is `root_hash`, which its own child will need.
+    /// Any other commit starts a new run, so the batch is dropped.
+    fn advance_ancestor_batch(&mut self, parent: &T, bhh: &T, root_hash: Option<TrieHash>) {
+        let Some(mut batch) = self.data.trie_ancestor_batch.take() else {
+            return;
+        };
+        if &batch.tip != parent {
+            return;
+        }
+        batch.tip = bhh.clone();
+        batch.tip_height += 1;
+        if let Some(root_hash) = root_hash {
+            if batch.ancestors.len() < TRIE_ANCESTOR_BATCH_MAX {
+                batch
+                    .ancestors
+                    .insert(batch.tip_height, (bhh.clone(), root_hash));
+            }
+        }
+        self.data.trie_ancestor_batch = Some(batch);
+    }
+
+    #[cfg(test)]
+    pub fn batched_ancestors_len(&self) -> usize {
+        self.data
+            .trie_ancestor_batch
+            .as_ref()
+            .map(|batch| batch.ancestors.len())
+            .unwrap_or(0)
+    }
+
     pub fn check_cached_ancestor_hashes_bytes(&mut self, bhh: &T) -> Option<Vec<TrieHash>> {
         if let Some((ref cached_bhh, ref cached_bytes)) = self.data.trie_ancestor_hash_bytes_cache {
             if cached_bhh == bhh {
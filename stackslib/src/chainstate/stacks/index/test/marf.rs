@@ -17,6 +17,7 @@
 #![allow(unused_variables)]
 #![allow(unused_assignments)]
 
+use std::collections::HashSet;
 use std::fs;
 use std::io::Cursor;
 
@@ -2268,3 +2269,139 @@ fn test_marf_unconfirmed() {
         assert!(false);
     }
 }
+
+#[test]
+fn test_marf_walk_leaves() {
+    let marf_opts = MARFOpenOpts::default();
+    let f = TrieFileStorage::new_memory(marf_opts).unwrap();
+    let block_header_1 = BlockHeaderHash::from_bytes(&[1u8; 32]).unwrap();
+    let block_header_2 = BlockHeaderHash::from_bytes(&[2u8; 32]).unwrap();
+    let block_header_3 = BlockHeaderHash::from_bytes(&[3u8; 32]).unwrap();
+    let mut marf = MARF::from_storage(f);
+
+    marf.begin(&BlockHeaderHash::sentinel(), &block_header_1)
+        .unwrap();
+    marf.insert("a", MARFValue::from(0x1a)).unwrap();
+    marf.insert("b", MARFValue::from(0x1b)).unwrap();
+    marf.insert("c", MARFValue::from(0x1c)).unwrap();
+    marf.commit().unwrap();
+
+    // overwrite a
+    marf.begin(&block_header_1, &block_header_2).unwrap();
+    marf.insert("a", MARFValue::from(0x2a)).unwrap();
+    marf.commit().unwrap();
+
+    // add d
+    marf.begin(&block_header_2, &block_header_3).unwrap();
+    marf.insert("d", MARFValue::from(0x3d)).unwrap();
+    marf.commit().unwrap();
+
+    fn walk(
+        marf: &mut MARF<BlockHeaderHash>,
+        block_hash: &BlockHeaderHash,
+        visited: &mut HashSet<(u32, u32)>,
+    ) -> HashSet<MARFValue> {
+        let mut values = HashSet::new();
+        MARF::walk_leaves(
+            &mut marf.borrow_storage_backend(),
+            block_hash,
+            visited,
+            |leaf| {
+                values.insert(leaf.data.clone());
+            },
+        )
+        .unwrap();
+        values
+    }
+
+    let mut visited = HashSet::new();
+
+    // the tip reaches exactly the live values, including those in ancestor tries
+    let expected: HashSet<_> = [0x2a, 0x1b, 0x1c, 0x3d]
+        .into_iter()
+        .map(MARFValue::from)
+        .collect();
+    assert_eq!(walk(&mut marf, &block_header_3, &mut visited), expected);
+
+    // everything reachable from block 2 was already reachable from block 3
+    assert!(walk(&mut marf, &block_header_2, &mut visited).is_empty());
+
+    // only the overwritten value is new in block 1
+    let expected: HashSet<_> = [MARFValue::from(0x1a)].into_iter().collect();
+    assert_eq!(walk(&mut marf, &block_header_1, &mut visited), expected);
+
+    // a fresh walk from block 1 sees all of its values
+    let expected: HashSet<_> = [0x1a, 0x1b, 0x1c]
+        .into_iter()
+        .map(MARFValue::from)
+        .collect();
+    assert_eq!(
+        walk(&mut marf, &block_header_1, &mut HashSet::new()),
+        expected
+    );
+
+    // walking leaves the MARF's open block alone
+    assert_eq!(
+        marf.get(&block_header_3, "a").unwrap(),
+        Some(MARFValue::from(0x2a))
+    );
+}
+
+#[test]
+fn test_marf_batch_ancestor_hashes() {
+    let block_header = |i: u32| {
+        let mut bytes = [0u8; 32];
+        bytes[0..4].copy_from_slice(&i.to_be_bytes());
+        bytes[31] = 1;
+        BlockHeaderHash(bytes)
+    };
+    let path = |i: u32| {
+        let mut bytes = [0u8; 32];
+        bytes[0..4].copy_from_slice(&i.to_be_bytes());
+        TrieHash(bytes)
+    };
+
+    for mut marf_opts in MARFOpenOpts::all().into_iter() {
+        let mut root_hashes = vec![];
+        for batch_ancestor_hashes in [false, true] {
+            marf_opts.batch_ancestor_hashes = batch_ancestor_hashes;
+            test_debug!("With {:?}", &marf_opts);
+            let f = TrieFileStorage::new_memory(marf_opts.clone()).unwrap();
+            let mut marf = MARF::from_storage(f);
+
+            // a run of consecutive blocks
+            let mut last_block_header = BlockHeaderHash::sentinel();
+            for i in 0..64 {
+                marf.begin(&last_block_header, &block_header(i)).unwrap();
+                marf.insert_raw(path(i), TrieLeaf::new(&[], &[i as u8; 40]))
+                    .unwrap();
+                marf.commit().unwrap();
+                last_block_header = block_header(i);
+            }
+            if batch_ancestor_hashes {
+                assert!(marf.borrow_storage_backend().batched_ancestors_len() > 0);
+            } else {
+                assert_eq!(marf.borrow_storage_backend().batched_ancestors_len(), 0);
+            }
+
+            // a fork off of the middle of the run, which must not reuse the run's ancestors
+            let mut last_block_header = block_header(31);
+            for i in 64..72 {
+                marf.begin(&last_block_header, &block_header(i)).unwrap();
+                marf.insert_raw(path(i), TrieLeaf::new(&[], &[i as u8; 40]))
+                    .unwrap();
+                marf.commit().unwrap();
+                last_block_header = block_header(i);
+            }
+
+            root_hashes.push(
+                marf.borrow_storage_backend()
+                    .read_root_to_block_table()
+                    .unwrap(),
+            );
+        }
+
+        // batching does not change any trie's root hash
+        assert_eq!(root_hashes[0], root_hashes[1]);
+    }
+}
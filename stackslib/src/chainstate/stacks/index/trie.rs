@@ -768,22 +768,34 @@ impl Trie {
 
         let mut log_depth = 0;
         while log_depth < 32 && (1u32 << log_depth) <= cur_block_height {
-            let prev_block_header = MARF::get_block_at_height(
-                storage,
-                cur_block_height - (1u32 << log_depth),
-                &cur_block_header,
-            )?
-            .ok_or_else(|| {
-                Error::CorruptionError(format!(
-                    "Could not obtain block hash at block height {}",
-                    cur_block_height - (1u32 << log_depth)
-                ))
-            })?;
-
-            storage.open_block(&prev_block_header)?;
-
-            let root_ptr = storage.root_trieptr();
-            let ancestor_hash = storage.read_node_hash_bytes(&root_ptr)?;
+            let prev_block_height = cur_block_height - (1u32 << log_depth);
+            let (prev_block_header, ancestor_hash) = if let Some(batched) =
+                storage.get_batched_ancestor(&cur_block_header, cur_block_height, prev_block_height)
+            {
+                batched
+            } else {
+                let prev_block_header =
+                    MARF::get_block_at_height(storage, prev_block_height, &cur_block_header)?
+                        .ok_or_else(|| {
+                            Error::CorruptionError(format!(
+                                "Could not obtain block hash at block height {}",
+                                prev_block_height
+                            ))
+                        })?;
+
+                storage.open_block(&prev_block_header)?;
+
+                let root_ptr = storage.root_trieptr();
+                let ancestor_hash = storage.read_node_hash_bytes(&root_ptr)?;
+                storage.set_batched_ancestor(
+                    &cur_block_header,
+                    cur_block_height,
+                    prev_block_height,
+                    prev_block_header.clone(),
+                    ancestor_hash.clone(),
+                );
+                (prev_block_header, ancestor_hash)
+            };
 
             trace!(
                 "Include root hash {} from block {} in ancestor #{}",
@@ -195,6 +195,9 @@ pub struct BlockBuilderSettings {
     pub miner_status: Arc<Mutex<MinerStatus>>,
     /// Should the builder attempt to confirm any parent microblocks
     pub confirm_microblocks: bool,
+    /// Number of candidate blocks the Nakamoto miner assembles per attempt, keeping the one with
+    /// the highest fee total
+    pub candidate_count: u32,
 }
 
 impl BlockBuilderSettings {
@@ -206,6 +209,7 @@ impl BlockBuilderSettings {
             mempool_settings: MemPoolWalkSettings::default(),
             miner_status: Arc::new(Mutex::new(MinerStatus::make_ready(0))),
             confirm_microblocks: true,
+            candidate_count: 1,
         }
     }
 
@@ -217,6 +221,7 @@ impl BlockBuilderSettings {
             mempool_settings: MemPoolWalkSettings::zero(),
             miner_status: Arc::new(Mutex::new(MinerStatus::make_ready(0))),
             confirm_microblocks: true,
+            candidate_count: 1,
         }
     }
 }
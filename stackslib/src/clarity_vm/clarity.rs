@@ -259,10 +259,22 @@ impl ClarityInstance {
         f(self.datastore.get_marf())
     }
 
+    /// Open a read-only view of this instance's state on its own database connection
+    pub fn reopen_readonly(&self) -> Result<ClarityInstance, Error> {
+        let datastore = self.datastore.reopen_readonly()?;
+        Ok(ClarityInstance::new(self.mainnet, self.chain_id, datastore))
+    }
+
     pub fn is_mainnet(&self) -> bool {
         self.mainnet
     }
 
+    /// Set whether blocks begun from now on log the Clarity key/value pairs they write (see
+    /// `ClarityBlockConnection::take_write_log()`)
+    pub fn set_record_writes(&mut self, record_writes: bool) {
+        self.datastore.set_record_writes(record_writes);
+    }
+
     /// Returns the Stacks epoch of the burn block that elected `stacks_block`
     fn get_epoch_of(
         stacks_block: &StacksBlockId,
@@ -1628,6 +1640,12 @@ impl<'a> ClarityBlockConnection<'a, '_> {
         self.datastore.seal()
     }
 
+    /// Take the Clarity key/value pairs committed to this block so far, in the order they were
+    /// written.  Empty unless the block was begun with write recording enabled.
+    pub fn take_write_log(&mut self) -> Vec<(String, String)> {
+        self.datastore.take_write_log()
+    }
+
     pub fn destruct(self) -> WritableMarfStore<'a> {
         self.datastore
     }
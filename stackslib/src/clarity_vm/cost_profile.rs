@@ -0,0 +1,435 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clarity cost profiling harness.
+//!
+//! Runs a fixed set of representative Clarity workloads through the full
+//! `ClarityInstance` -> `ClarityBlockConnection` -> `ClarityTransactionConnection`
+//! stack at each epoch, and records the execution cost of each one. The
+//! resulting `CostModelReport` is machine-readable, so that a report produced
+//! by a release candidate can be diffed against a baseline report. The
+//! `clarity_costs` benchmark (feature `clarity-bench`) drives this module.
+
+use clarity::vm::ast::ASTRules;
+use clarity::vm::clarity::TransactionConnection;
+use clarity::vm::costs::ExecutionCost;
+use clarity::vm::test_util::{generate_test_burn_state_db, UnitTestBurnStateDB, TEST_HEADER_DB};
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use clarity::vm::ClarityVersion;
+use stacks_common::consts::CHAIN_ID_TESTNET;
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::StacksEpochId;
+
+use crate::chainstate::stacks::boot::{BOOT_CODE_COSTS_2, BOOT_CODE_COSTS_3};
+use crate::chainstate::stacks::index::ClarityMarfTrieId;
+use crate::clarity_vm::clarity::ClarityInstance;
+use crate::clarity_vm::database::marf::MarfedKV;
+use crate::util_lib::boot::boot_code_id;
+
+/// Version of the `CostModelReport` format. Bump this when the report layout changes.
+pub const COST_MODEL_REPORT_VERSION: u32 = 1;
+
+/// Epochs which the harness profiles
+pub const PROFILED_EPOCHS: &[StacksEpochId] = &[
+    StacksEpochId::Epoch20,
+    StacksEpochId::Epoch2_05,
+    StacksEpochId::Epoch21,
+    StacksEpochId::Epoch22,
+    StacksEpochId::Epoch23,
+    StacksEpochId::Epoch24,
+    StacksEpochId::Epoch25,
+    StacksEpochId::Epoch30,
+    StacksEpochId::Epoch31,
+];
+
+/// A representative Clarity workload. Each workload is a contract with a
+/// public `run` function, which the harness calls once per measurement.
+/// Workloads must be valid Clarity1, since they run in every profiled epoch.
+pub struct ProfileWorkload {
+    /// Name of the workload, and of the contract that implements it
+    pub name: &'static str,
+    /// Contract source
+    pub contract: &'static str,
+}
+
+/// Contract called by the `contract-call` workload
+const CALLEE_CONTRACT_NAME: &str = "callee";
+const CALLEE_CONTRACT: &str = "
+(define-data-var counter uint u0)
+(define-public (bump (amount uint))
+  (begin
+    (var-set counter (+ (var-get counter) amount))
+    (ok (var-get counter))))
+";
+
+pub const PROFILE_WORKLOADS: &[ProfileWorkload] = &[
+    ProfileWorkload {
+        name: "arithmetic",
+        contract: "
+(define-private (step (x int) (acc int))
+  (+ (* acc 3) (/ (- x 7) 2) (mod x 5)))
+(define-public (run)
+  (ok (fold step (list 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20) 0)))
+",
+    },
+    ProfileWorkload {
+        name: "list-ops",
+        contract: "
+(define-private (double (x uint)) (* x u2))
+(define-private (is-even (x uint)) (is-eq (mod x u2) u0))
+(define-public (run)
+  (let ((items (list u1 u2 u3 u4 u5 u6 u7 u8 u9 u10 u11 u12 u13 u14 u15 u16)))
+    (ok (len (concat (map double items) (filter is-even items))))))
+",
+    },
+    ProfileWorkload {
+        name: "hashing",
+        contract: "
+(define-private (rehash (x uint) (acc (buff 32)))
+  (sha256 (concat acc (sha512/256 acc))))
+(define-public (run)
+  (ok (fold rehash (list u1 u2 u3 u4 u5 u6 u7 u8) 0x00)))
+",
+    },
+    ProfileWorkload {
+        name: "data-var",
+        contract: "
+(define-data-var total uint u0)
+(define-private (add (x uint) (acc bool))
+  (var-set total (+ (var-get total) x)))
+(define-public (run)
+  (begin
+    (fold add (list u1 u2 u3 u4 u5 u6 u7 u8 u9 u10) true)
+    (ok (var-get total))))
+",
+    },
+    ProfileWorkload {
+        name: "map-ops",
+        contract: "
+(define-map balances uint uint)
+(define-private (store (x uint) (acc bool))
+  (map-set balances x (* x x)))
+(define-private (load (x uint) (acc uint))
+  (+ acc (default-to u0 (map-get? balances x))))
+(define-public (run)
+  (let ((keys (list u1 u2 u3 u4 u5 u6 u7 u8 u9 u10)))
+    (fold store keys true)
+    (map-delete balances u1)
+    (ok (fold load keys u0))))
+",
+    },
+    ProfileWorkload {
+        name: "fungible-token",
+        contract: "
+(define-fungible-token bench-token)
+(define-public (run)
+  (begin
+    (try! (ft-mint? bench-token u1000000 tx-sender))
+    (try! (ft-transfer? bench-token u1000 tx-sender 'SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G))
+    (try! (ft-burn? bench-token u500 tx-sender))
+    (ok (ft-get-balance bench-token tx-sender))))
+",
+    },
+    ProfileWorkload {
+        name: "contract-call",
+        contract: "
+(define-private (call-callee (x uint) (acc uint))
+  (unwrap-panic (contract-call? .callee bump x)))
+(define-public (run)
+  (ok (fold call-callee (list u1 u2 u3 u4 u5) u0)))
+",
+    },
+];
+
+/// The measured cost of one workload in one epoch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostModelEntry {
+    pub epoch: String,
+    pub workload: String,
+    pub cost: ExecutionCost,
+}
+
+/// A difference between a report and its baseline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostModelChange {
+    pub epoch: String,
+    pub workload: String,
+    /// Cost in the baseline report, if the baseline measured this workload
+    pub baseline: Option<ExecutionCost>,
+    /// Cost in this report, if this report measured this workload
+    pub current: Option<ExecutionCost>,
+}
+
+/// Machine-readable report of the measured cost of each workload at each epoch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostModelReport {
+    pub version: u32,
+    pub entries: Vec<CostModelEntry>,
+}
+
+impl CostModelReport {
+    pub fn new() -> Self {
+        Self {
+            version: COST_MODEL_REPORT_VERSION,
+            entries: vec![],
+        }
+    }
+
+    pub fn get(&self, epoch: &str, workload: &str) -> Option<&ExecutionCost> {
+        self.entries
+            .iter()
+            .find(|entry| entry.epoch == epoch && entry.workload == workload)
+            .map(|entry| &entry.cost)
+    }
+
+    /// Find every (epoch, workload) whose cost differs from `baseline`, including
+    /// workloads that only one of the two reports measured.
+    pub fn changes_from(&self, baseline: &CostModelReport) -> Vec<CostModelChange> {
+        let mut changes = vec![];
+        for entry in self.entries.iter() {
+            let baseline_cost = baseline.get(&entry.epoch, &entry.workload);
+            if baseline_cost != Some(&entry.cost) {
+                changes.push(CostModelChange {
+                    epoch: entry.epoch.clone(),
+                    workload: entry.workload.clone(),
+                    baseline: baseline_cost.cloned(),
+                    current: Some(entry.cost.clone()),
+                });
+            }
+        }
+        for entry in baseline.entries.iter() {
+            if self.get(&entry.epoch, &entry.workload).is_none() {
+                changes.push(CostModelChange {
+                    epoch: entry.epoch.clone(),
+                    workload: entry.workload.clone(),
+                    baseline: Some(entry.cost.clone()),
+                    current: None,
+                });
+            }
+        }
+        changes
+    }
+}
+
+impl Default for CostModelReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A Clarity instance at a given epoch, with every workload deployed
+pub struct CostProfiler {
+    epoch: StacksEpochId,
+    clarity: ClarityInstance,
+    burn_state_db: UnitTestBurnStateDB,
+    tip: StacksBlockId,
+    sender: PrincipalData,
+}
+
+impl CostProfiler {
+    /// Instantiate a fresh Clarity instance at `epoch`, with its cost contract and all
+    /// workloads deployed.
+    pub fn new(epoch: StacksEpochId) -> Self {
+        let mut clarity = ClarityInstance::new(false, CHAIN_ID_TESTNET, MarfedKV::temporary());
+        let burn_state_db = generate_test_burn_state_db(epoch);
+        let sender =
+            PrincipalData::parse_standard_principal("SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR")
+                .expect("FATAL: invalid profiler sender")
+                .into();
+
+        let genesis = StacksBlockId([0xfe; 32]);
+        let mut block = clarity.begin_test_genesis_block(
+            &StacksBlockId::sentinel(),
+            &genesis,
+            &TEST_HEADER_DB,
+            &burn_state_db,
+        );
+        block.as_transaction(|tx| {
+            tx.with_clarity_db(|db| {
+                db.set_clarity_epoch_version(epoch)
+                    .expect("FATAL: failed to set Clarity epoch");
+                Ok(())
+            })
+            .expect("FATAL: failed to set Clarity epoch");
+
+            // the genesis block only has the epoch 2.0 cost contract
+            let cost_contract = match epoch {
+                StacksEpochId::Epoch10 | StacksEpochId::Epoch20 => None,
+                StacksEpochId::Epoch2_05 => {
+                    Some(("costs-2", ClarityVersion::Clarity1, BOOT_CODE_COSTS_2))
+                }
+                StacksEpochId::Epoch21
+                | StacksEpochId::Epoch22
+                | StacksEpochId::Epoch23
+                | StacksEpochId::Epoch24
+                | StacksEpochId::Epoch25
+                | StacksEpochId::Epoch30
+                | StacksEpochId::Epoch31 => {
+                    Some(("costs-3", ClarityVersion::Clarity2, BOOT_CODE_COSTS_3))
+                }
+            };
+            if let Some((name, version, code)) = cost_contract {
+                let contract_id = boot_code_id(name, false);
+                let (ast, _analysis) = tx
+                    .analyze_smart_contract(&contract_id, version, code, ASTRules::PrecheckSize)
+                    .expect("FATAL: failed to analyze cost contract");
+                tx.initialize_smart_contract(&contract_id, version, &ast, code, None, |_, _| false)
+                    .expect("FATAL: failed to instantiate cost contract");
+            }
+        });
+        block.commit_to_block(&genesis);
+
+        let mut profiler = Self {
+            epoch,
+            clarity,
+            burn_state_db,
+            tip: genesis,
+            sender,
+        };
+        profiler.deploy_workloads();
+        profiler
+    }
+
+    pub fn epoch(&self) -> StacksEpochId {
+        self.epoch
+    }
+
+    fn contract_id(&self, name: &str) -> QualifiedContractIdentifier {
+        let PrincipalData::Standard(issuer) = &self.sender else {
+            unreachable!("profiler sender is a standard principal");
+        };
+        QualifiedContractIdentifier::new(issuer.clone(), name.into())
+    }
+
+    /// Deploy the callee contract and every workload in their own block
+    fn deploy_workloads(&mut self) {
+        let version = ClarityVersion::default_for_epoch(self.epoch);
+        let contracts: Vec<_> = [(CALLEE_CONTRACT_NAME, CALLEE_CONTRACT)]
+            .into_iter()
+            .chain(PROFILE_WORKLOADS.iter().map(|w| (w.name, w.contract)))
+            .map(|(name, code)| (self.contract_id(name), code))
+            .collect();
+
+        let next_tip = StacksBlockId([0x01; 32]);
+        let mut block =
+            self.clarity
+                .begin_block(&self.tip, &next_tip, &TEST_HEADER_DB, &self.burn_state_db);
+        for (contract_id, code) in contracts.iter() {
+            block.as_transaction(|tx| {
+                let (ast, analysis) = tx
+                    .analyze_smart_contract(contract_id, version, code, ASTRules::PrecheckSize)
+                    .unwrap_or_else(|e| panic!("FATAL: failed to analyze {contract_id}: {e:?}"));
+                tx.initialize_smart_contract(contract_id, version, &ast, code, None, |_, _| false)
+                    .unwrap_or_else(|e| panic!("FATAL: failed to deploy {contract_id}: {e:?}"));
+                tx.save_analysis(contract_id, &analysis)
+                    .unwrap_or_else(|e| panic!("FATAL: failed to save {contract_id}: {e:?}"));
+            });
+        }
+        block.commit_to_block(&next_tip);
+        self.tip = next_tip;
+    }
+
+    /// Call the workload's `run` function in a fresh block, and return the block's
+    /// total cost. The block is rolled back, so repeated runs measure the same state.
+    pub fn run_workload(&mut self, workload: &ProfileWorkload) -> ExecutionCost {
+        let contract_id = self.contract_id(workload.name);
+        let mut block = self.clarity.begin_block(
+            &self.tip,
+            &StacksBlockId([0x02; 32]),
+            &TEST_HEADER_DB,
+            &self.burn_state_db,
+        );
+        block.as_transaction(|tx| {
+            tx.run_contract_call(&self.sender, None, &contract_id, "run", &[], |_, _| false)
+                .unwrap_or_else(|e| panic!("FATAL: workload {} failed: {e:?}", workload.name));
+        });
+        let cost = block.cost_so_far();
+        block.rollback_block();
+        cost
+    }
+
+    /// Measure every workload at this profiler's epoch
+    pub fn profile(&mut self, report: &mut CostModelReport) {
+        for workload in PROFILE_WORKLOADS.iter() {
+            let cost = self.run_workload(workload);
+            report.entries.push(CostModelEntry {
+                epoch: self.epoch.to_string(),
+                workload: workload.name.to_string(),
+                cost,
+            });
+        }
+    }
+}
+
+/// Measure every workload at every profiled epoch
+pub fn profile_all_epochs() -> CostModelReport {
+    let mut report = CostModelReport::new();
+    for epoch in PROFILED_EPOCHS.iter() {
+        CostProfiler::new(*epoch).profile(&mut report);
+    }
+    report
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn profile_workloads_at_every_epoch() {
+        let report = profile_all_epochs();
+        assert_eq!(report.version, COST_MODEL_REPORT_VERSION);
+        assert_eq!(
+            report.entries.len(),
+            PROFILED_EPOCHS.len() * PROFILE_WORKLOADS.len()
+        );
+        for entry in report.entries.iter() {
+            assert!(
+                entry.cost.runtime > 0,
+                "{} at {} has no runtime cost",
+                entry.workload,
+                entry.epoch
+            );
+        }
+
+        // measurements are deterministic
+        let mut profiler = CostProfiler::new(StacksEpochId::Epoch31);
+        let workload = &PROFILE_WORKLOADS[0];
+        assert_eq!(
+            profiler.run_workload(workload),
+            profiler.run_workload(workload)
+        );
+        assert_eq!(
+            Some(&profiler.run_workload(workload)),
+            report.get("3.1", workload.name)
+        );
+
+        // the report round-trips through json
+        let json = serde_json::to_string(&report).unwrap();
+        let decoded: CostModelReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, report);
+        assert!(report.changes_from(&decoded).is_empty());
+
+        // changes against a baseline are detected
+        let mut baseline = report.clone();
+        baseline.entries[0].cost.runtime += 1;
+        let removed = baseline.entries.pop().unwrap();
+        let changes = report.changes_from(&baseline);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].epoch, report.entries[0].epoch);
+        assert_eq!(changes[0].current, Some(report.entries[0].cost.clone()));
+        assert_eq!(changes[1].workload, removed.workload);
+        assert_eq!(changes[1].baseline, None);
+    }
+}
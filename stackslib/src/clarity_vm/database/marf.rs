@@ -27,6 +27,15 @@ use crate::clarity_vm::special::handle_contract_call_special_cases;
 use crate::core::{FIRST_BURNCHAIN_CONSENSUS_HASH, FIRST_STACKS_BLOCK_HASH};
 use crate::util_lib::db::{Error as DatabaseError, IndexDBConn};
 
+/// Prefix of the error raised when the MARF holds a value hash whose value is not in the side
+/// store, which is what reading Clarity state discarded by `node.prune_mode` looks like
+pub const MISSING_SIDE_STORE_VALUE: &str =
+    "ERROR: MARF contained value_hash not found in side storage";
+
+fn missing_side_store_value(side_key: &str) -> InterpreterError {
+    InterpreterError::Expect(format!("{MISSING_SIDE_STORE_VALUE}: {side_key}"))
+}
+
 /// The MarfedKV struct is used to wrap a MARF data structure and side-storage
 ///   for use as a K/V store for ClarityDB or the AnalysisDB.
 /// The Clarity VM and type checker do not "know" to begin/commit the block they are currently processing:
@@ -37,6 +46,8 @@ use crate::util_lib::db::{Error as DatabaseError, IndexDBConn};
 pub struct MarfedKV {
     chain_tip: StacksBlockId,
     marf: MARF<StacksBlockId>,
+    /// Whether blocks begun with `begin()` keep a log of the key/value pairs written to them
+    record_writes: bool,
 }
 
 impl MarfedKV {
@@ -94,7 +105,11 @@ impl MarfedKV {
             None => StacksBlockId::sentinel(),
         };
 
-        Ok(MarfedKV { marf, chain_tip })
+        Ok(MarfedKV {
+            marf,
+            chain_tip,
+            record_writes: false,
+        })
     }
 
     pub fn open_unconfirmed(
@@ -108,7 +123,24 @@ impl MarfedKV {
             None => StacksBlockId::sentinel(),
         };
 
-        Ok(MarfedKV { marf, chain_tip })
+        Ok(MarfedKV {
+            marf,
+            chain_tip,
+            record_writes: false,
+        })
+    }
+
+    /// Reopen this K/V store's MARF on a new, read-only database connection
+    pub fn reopen_readonly(&self) -> InterpreterResult<MarfedKV> {
+        let marf = self
+            .marf
+            .reopen_readonly()
+            .map_err(|err| InterpreterError::MarfFailure(err.to_string()))?;
+        Ok(MarfedKV {
+            marf,
+            chain_tip: self.chain_tip.clone(),
+            record_writes: false,
+        })
     }
 
     // used by benchmarks
@@ -139,7 +171,11 @@ impl MarfedKV {
 
         let chain_tip = StacksBlockId::sentinel();
 
-        MarfedKV { marf, chain_tip }
+        MarfedKV {
+            marf,
+            chain_tip,
+            record_writes: false,
+        }
     }
 
     pub fn begin_read_only<'a>(
@@ -221,6 +257,7 @@ impl MarfedKV {
         WritableMarfStore {
             chain_tip,
             marf: tx,
+            write_log: self.record_writes.then(Vec::new),
         }
     }
 
@@ -246,9 +283,15 @@ impl MarfedKV {
         WritableMarfStore {
             chain_tip,
             marf: tx,
+            write_log: None,
         }
     }
 
+    /// Set whether blocks begun from now on keep a log of the key/value pairs written to them
+    pub fn set_record_writes(&mut self, record_writes: bool) {
+        self.record_writes = record_writes;
+    }
+
     pub fn get_chain_tip(&self) -> &StacksBlockId {
         &self.chain_tip
     }
@@ -273,6 +316,9 @@ impl MarfedKV {
 pub struct WritableMarfStore<'a> {
     chain_tip: StacksBlockId,
     marf: MarfTransaction<'a, StacksBlockId>,
+    /// Key/value pairs committed to this block so far, in the order they were written, if the
+    /// block was begun with write recording enabled
+    write_log: Option<Vec<(String, String)>>,
 }
 
 pub struct ReadOnlyMarfStore<'a> {
@@ -408,13 +454,8 @@ impl ClarityBackingStore for ReadOnlyMarfStore<'_> {
             .map_err(|_| InterpreterError::Expect("ERROR: Unexpected MARF Failure on GET".into()))?
             .map(|(marf_value, proof)| {
                 let side_key = marf_value.to_hex();
-                let data =
-                    SqliteConnection::get(self.get_side_store(), &side_key)?.ok_or_else(|| {
-                        InterpreterError::Expect(format!(
-                            "ERROR: MARF contained value_hash not found in side storage: {}",
-                            side_key
-                        ))
-                    })?;
+                let data = SqliteConnection::get(self.get_side_store(), &side_key)?
+                    .ok_or_else(|| missing_side_store_value(&side_key))?;
                 Ok((data, proof.serialize_to_vec()))
             })
             .transpose()
@@ -433,13 +474,8 @@ impl ClarityBackingStore for ReadOnlyMarfStore<'_> {
             .map_err(|_| InterpreterError::Expect("ERROR: Unexpected MARF Failure on GET".into()))?
             .map(|(marf_value, proof)| {
                 let side_key = marf_value.to_hex();
-                let data =
-                    SqliteConnection::get(self.get_side_store(), &side_key)?.ok_or_else(|| {
-                        InterpreterError::Expect(format!(
-                            "ERROR: MARF contained value_hash not found in side storage: {}",
-                            side_key
-                        ))
-                    })?;
+                let data = SqliteConnection::get(self.get_side_store(), &side_key)?
+                    .ok_or_else(|| missing_side_store_value(&side_key))?;
                 Ok((data, proof.serialize_to_vec()))
             })
             .transpose()
@@ -464,13 +500,8 @@ impl ClarityBackingStore for ReadOnlyMarfStore<'_> {
             .map(|marf_value| {
                 let side_key = marf_value.to_hex();
                 trace!("MarfedKV get side-key for {:?}: {:?}", key, &side_key);
-                SqliteConnection::get(self.get_side_store(), &side_key)?.ok_or_else(|| {
-                    InterpreterError::Expect(format!(
-                        "ERROR: MARF contained value_hash not found in side storage: {}",
-                        side_key
-                    ))
-                    .into()
-                })
+                SqliteConnection::get(self.get_side_store(), &side_key)?
+                    .ok_or_else(|| missing_side_store_value(&side_key).into())
             })
             .transpose()
     }
@@ -494,13 +525,8 @@ impl ClarityBackingStore for ReadOnlyMarfStore<'_> {
             .map(|marf_value| {
                 let side_key = marf_value.to_hex();
                 trace!("MarfedKV get side-key for {:?}: {:?}", hash, &side_key);
-                SqliteConnection::get(self.get_side_store(), &side_key)?.ok_or_else(|| {
-                    InterpreterError::Expect(format!(
-                        "ERROR: MARF contained value_hash not found in side storage: {}",
-                        side_key
-                    ))
-                    .into()
-                })
+                SqliteConnection::get(self.get_side_store(), &side_key)?
+                    .ok_or_else(|| missing_side_store_value(&side_key).into())
             })
             .transpose()
     }
@@ -621,6 +647,15 @@ impl WritableMarfStore<'_> {
     pub fn seal(&mut self) -> TrieHash {
         self.marf.seal().expect("FATAL: failed to .seal() MARF")
     }
+
+    /// Take the key/value pairs written to this block so far.  Empty unless the block was begun
+    /// with write recording enabled.
+    pub fn take_write_log(&mut self) -> Vec<(String, String)> {
+        self.write_log
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
 }
 
 impl ClarityBackingStore for WritableMarfStore<'_> {
@@ -673,13 +708,8 @@ impl ClarityBackingStore for WritableMarfStore<'_> {
             .map(|marf_value| {
                 let side_key = marf_value.to_hex();
                 trace!("MarfedKV get side-key for {:?}: {:?}", key, &side_key);
-                SqliteConnection::get(self.marf.sqlite_tx(), &side_key)?.ok_or_else(|| {
-                    InterpreterError::Expect(format!(
-                        "ERROR: MARF contained value_hash not found in side storage: {}",
-                        side_key
-                    ))
-                    .into()
-                })
+                SqliteConnection::get(self.marf.sqlite_tx(), &side_key)?
+                    .ok_or_else(|| missing_side_store_value(&side_key).into())
             })
             .transpose()
     }
@@ -703,13 +733,8 @@ impl ClarityBackingStore for WritableMarfStore<'_> {
             .map(|marf_value| {
                 let side_key = marf_value.to_hex();
                 trace!("MarfedKV get side-key for {:?}: {:?}", hash, &side_key);
-                SqliteConnection::get(self.marf.sqlite_tx(), &side_key)?.ok_or_else(|| {
-                    InterpreterError::Expect(format!(
-                        "ERROR: MARF contained value_hash not found in side storage: {}",
-                        side_key
-                    ))
-                    .into()
-                })
+                SqliteConnection::get(self.marf.sqlite_tx(), &side_key)?
+                    .ok_or_else(|| missing_side_store_value(&side_key).into())
             })
             .transpose()
     }
@@ -724,13 +749,8 @@ impl ClarityBackingStore for WritableMarfStore<'_> {
             .map_err(|_| InterpreterError::Expect("ERROR: Unexpected MARF Failure on GET".into()))?
             .map(|(marf_value, proof)| {
                 let side_key = marf_value.to_hex();
-                let data =
-                    SqliteConnection::get(self.marf.sqlite_tx(), &side_key)?.ok_or_else(|| {
-                        InterpreterError::Expect(format!(
-                            "ERROR: MARF contained value_hash not found in side storage: {}",
-                            side_key
-                        ))
-                    })?;
+                let data = SqliteConnection::get(self.marf.sqlite_tx(), &side_key)?
+                    .ok_or_else(|| missing_side_store_value(&side_key))?;
                 Ok((data, proof.serialize_to_vec()))
             })
             .transpose()
@@ -749,13 +769,8 @@ impl ClarityBackingStore for WritableMarfStore<'_> {
             .map_err(|_| InterpreterError::Expect("ERROR: Unexpected MARF Failure on GET".into()))?
             .map(|(marf_value, proof)| {
                 let side_key = marf_value.to_hex();
-                let data =
-                    SqliteConnection::get(self.marf.sqlite_tx(), &side_key)?.ok_or_else(|| {
-                        InterpreterError::Expect(format!(
-                            "ERROR: MARF contained value_hash not found in side storage: {}",
-                            side_key
-                        ))
-                    })?;
+                let data = SqliteConnection::get(self.marf.sqlite_tx(), &side_key)?
+                    .ok_or_else(|| missing_side_store_value(&side_key))?;
                 Ok((data, proof.serialize_to_vec()))
             })
             .transpose()
@@ -831,6 +846,9 @@ impl ClarityBackingStore for WritableMarfStore<'_> {
             trace!("MarfedKV put '{}' = '{}'", &key, &value);
             let marf_value = MARFValue::from_value(&value);
             SqliteConnection::put(self.get_side_store(), &marf_value.to_hex(), &value)?;
+            if let Some(write_log) = self.write_log.as_mut() {
+                write_log.push((key.clone(), value));
+            }
             keys.push(key);
             values.push(marf_value);
         }
@@ -65,6 +65,13 @@ impl GetTenureStartId for StacksDBConn<'_> {
         tip: &StacksBlockId,
         tenure_id_consensus_hash: &ConsensusHash,
     ) -> Result<Option<TenureBlockId>, DBError> {
+        if let Some(block_id) = NakamotoChainState::get_indexed_tenure_start_block_id(
+            self.sqlite(),
+            tip,
+            tenure_id_consensus_hash,
+        )? {
+            return Ok(Some(TenureBlockId::from(block_id)));
+        }
         Ok(self
             .get_indexed(
                 tip,
@@ -79,6 +86,15 @@ impl GetTenureStartId for StacksDBConn<'_> {
         tip: &StacksBlockId,
         coinbase_height: u64,
     ) -> Result<Option<StacksBlockId>, DBError> {
+        if let Some(block_id) =
+            NakamotoChainState::get_indexed_tenure_start_block_id_at_coinbase_height(
+                self.sqlite(),
+                tip,
+                coinbase_height,
+            )?
+        {
+            return Ok(Some(block_id));
+        }
         let opt_out = self
             .get_indexed(
                 tip,
@@ -99,6 +115,13 @@ impl GetTenureStartId for StacksDBTx<'_> {
         tip: &StacksBlockId,
         tenure_id_consensus_hash: &ConsensusHash,
     ) -> Result<Option<TenureBlockId>, DBError> {
+        if let Some(block_id) = NakamotoChainState::get_indexed_tenure_start_block_id(
+            self.sqlite(),
+            tip,
+            tenure_id_consensus_hash,
+        )? {
+            return Ok(Some(TenureBlockId::from(block_id)));
+        }
         Ok(self
             .get_indexed_ref(
                 tip,
@@ -113,6 +136,15 @@ impl GetTenureStartId for StacksDBTx<'_> {
         tip: &StacksBlockId,
         coinbase_height: u64,
     ) -> Result<Option<StacksBlockId>, DBError> {
+        if let Some(block_id) =
+            NakamotoChainState::get_indexed_tenure_start_block_id_at_coinbase_height(
+                self.sqlite(),
+                tip,
+                coinbase_height,
+            )?
+        {
+            return Ok(Some(block_id));
+        }
         let opt_out = self
             .get_indexed_ref(
                 tip,
@@ -6,5 +6,9 @@ pub mod special;
 /// Stacks blockchain specific Clarity database implementations and wrappers
 pub mod database;
 
+/// Cost profiling harness for representative Clarity workloads
+#[cfg(any(test, feature = "clarity-bench"))]
+pub mod cost_profile;
+
 #[cfg(test)]
 mod tests;
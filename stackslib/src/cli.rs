@@ -18,32 +18,51 @@
 
 use std::any::type_name;
 use std::cell::LazyCell;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 use std::{env, fs, io, process, thread};
 
 use clarity::types::chainstate::SortitionId;
+use clarity::vm::clarity::ClarityConnection;
+use clarity::vm::costs::ExecutionCost;
+use clarity::vm::types::{QualifiedContractIdentifier, StacksAddressExtensions};
 use db::blocks::DummyEventDispatcher;
 use db::ChainstateTx;
 use regex::Regex;
-use rusqlite::{Connection, OpenFlags};
-use stacks_common::types::chainstate::{BlockHeaderHash, BurnchainHeaderHash, StacksBlockId};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use serde::Serialize;
+use stacks_common::codec::StacksMessageCodec;
+use stacks_common::types::chainstate::{
+    BlockHeaderHash, BurnchainHeaderHash, StacksAddress, StacksBlockId,
+};
 use stacks_common::types::sqlite::NO_PARAMS;
 use stacks_common::util::get_epoch_time_ms;
-use stacks_common::util::hash::Hash160;
+use stacks_common::util::hash::{to_hex, Hash160};
+use stacks_common::util::secp256k1::Secp256k1PublicKey;
 use stacks_common::util::vrf::VRFProof;
 
+use crate::burnchains::affirmation::AffirmationsReport;
+use crate::burnchains::bitcoin::spv::SpvClient;
 use crate::burnchains::db::BurnchainDB;
-use crate::burnchains::{Burnchain, PoxConstants};
+use crate::burnchains::{Burnchain, PoxConstants, Txid};
 use crate::chainstate::burn::db::sortdb::{
-    get_ancestor_sort_id, SortitionDB, SortitionHandle, SortitionHandleContext,
+    get_ancestor_sort_id, SortitionDB, SortitionHandle, SortitionHandleConn, SortitionHandleContext,
 };
 use crate::chainstate::burn::{BlockSnapshot, ConsensusHash};
-use crate::chainstate::coordinator::OnChainRewardSetProvider;
+use crate::chainstate::coordinator::{
+    static_get_canonical_affirmation_map, static_get_heaviest_affirmation_map,
+    static_get_stacks_tip_affirmation_map, BlockEventDispatcher, OnChainRewardSetProvider,
+};
 use crate::chainstate::nakamoto::miner::{BlockMetadata, NakamotoBlockBuilder, NakamotoTenureInfo};
 use crate::chainstate::nakamoto::{NakamotoBlock, NakamotoChainState};
+use crate::chainstate::stacks::boot::RewardSet;
 use crate::chainstate::stacks::db::blocks::StagingBlock;
-use crate::chainstate::stacks::db::{StacksBlockHeaderTypes, StacksChainState, StacksHeaderInfo};
+use crate::chainstate::stacks::db::receipts::TransactionReceiptDB;
+use crate::chainstate::stacks::db::{
+    StacksBlockHeaderTypes, StacksChainState, StacksEpochReceipt, StacksHeaderInfo,
+};
 use crate::chainstate::stacks::miner::*;
 use crate::chainstate::stacks::{Error as ChainstateError, *};
 use crate::clarity_vm::clarity::ClarityInstance;
@@ -51,7 +70,11 @@ use crate::config::{Config, ConfigFile, DEFAULT_MAINNET_CONFIG};
 use crate::core::*;
 use crate::cost_estimates::metrics::UnitMetric;
 use crate::cost_estimates::UnitEstimator;
-use crate::util_lib::db::IndexDBTx;
+use crate::net::api::getblocksummary::payload_type_name;
+use crate::util_lib::db::{u64_to_sql, Error as DBError, FromColumn, IndexDBTx};
+use crate::util_lib::export::{
+    create_table_writer, Column, ColumnType, ExportFormat, ExportValue, TableWriter,
+};
 
 /// Options common to many `stacks-inspect` subcommands
 /// Returned by `process_common_opts()`
@@ -191,7 +214,15 @@ pub fn command_replay_block(argv: &[String], conf: Option<&Config>) {
         if i % 100 == 0 {
             println!("Checked {i}...");
         }
-        replay_staging_block(db_path, index_block_hash, conf);
+        if let Err(e) = replay_staging_block(
+            db_path,
+            index_block_hash,
+            conf,
+            None::<&DummyEventDispatcher>,
+        ) {
+            println!("Failed processing block! block = {index_block_hash}, error = {e:?}");
+            process::exit(1);
+        }
     }
     println!("Finished. run_time_seconds = {}", start.elapsed().as_secs());
 }
@@ -279,7 +310,13 @@ pub fn command_replay_block_nakamoto(argv: &[String], conf: Option<&Config>) {
         if i % 100 == 0 {
             println!("Checked {i}...");
         }
-        replay_naka_staging_block(db_path, index_block_hash, conf);
+        replay_naka_staging_block(
+            db_path,
+            index_block_hash,
+            conf,
+            None::<&DummyEventDispatcher>,
+        )
+        .unwrap();
     }
     println!("Finished. run_time_seconds = {}", start.elapsed().as_secs());
 }
@@ -374,7 +411,10 @@ pub fn command_replay_mock_mining(argv: &[String], conf: Option<&Config>) {
             "block_height" => bh,
             "block" => ?block
         );
-        replay_mock_mined_block(db_path, block, conf);
+        if let Err(e) = replay_mock_mined_block(db_path, block, conf) {
+            println!("Failed processing block from {filepath:?}! error = {e:?}");
+            process::exit(1);
+        }
     }
 }
 
@@ -550,8 +590,1391 @@ pub fn command_try_mine(argv: &[String], conf: Option<&Config>) {
     process::exit(code);
 }
 
+/// The outcome of one replayed transaction, in a form that can be saved and diffed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayedTxRecord {
+    pub txid: Txid,
+    pub tx_index: u32,
+    pub result: String,
+    pub vm_error: Option<String>,
+    pub post_condition_aborted: bool,
+    pub stx_burned: u128,
+    pub execution_cost: ExecutionCost,
+    pub events: Vec<serde_json::Value>,
+}
+
+/// The outcome of one replayed block, in a form that can be saved and diffed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayedBlockRecord {
+    pub block_id: StacksBlockId,
+    pub height: u64,
+    pub cost: ExecutionCost,
+    pub txs: Vec<ReplayedTxRecord>,
+}
+
+impl ReplayedBlockRecord {
+    pub fn from_receipt(
+        block_id: &StacksBlockId,
+        height: u64,
+        receipt: &StacksEpochReceipt,
+    ) -> Self {
+        let txs = receipt
+            .tx_receipts
+            .iter()
+            .map(|tx_receipt| {
+                let txid = tx_receipt.transaction.txid();
+                let events = tx_receipt
+                    .events
+                    .iter()
+                    .enumerate()
+                    .map(|(event_index, event)| {
+                        event
+                            .json_serialize(event_index, &txid, true)
+                            .unwrap_or_else(|e| serde_json::Value::String(format!("{e:?}")))
+                    })
+                    .collect();
+                ReplayedTxRecord {
+                    txid,
+                    tx_index: tx_receipt.tx_index,
+                    result: tx_receipt.result.to_string(),
+                    vm_error: tx_receipt.vm_error.clone(),
+                    post_condition_aborted: tx_receipt.post_condition_aborted,
+                    stx_burned: tx_receipt.stx_burned,
+                    execution_cost: tx_receipt.execution_cost.clone(),
+                    events,
+                }
+            })
+            .collect();
+        Self {
+            block_id: block_id.clone(),
+            height,
+            cost: receipt.anchored_block_cost.clone(),
+            txs,
+        }
+    }
+}
+
+/// A mismatch between a replayed block and what was expected of it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayDiff {
+    pub block_id: StacksBlockId,
+    pub height: u64,
+    /// Where the expected value came from: `chainstate` or `baseline`
+    pub source: String,
+    /// Which part of the outcome differs, e.g. `cost` or `txs[2].events`
+    pub field: String,
+    pub expected: serde_json::Value,
+    pub actual: serde_json::Value,
+}
+
+impl ReplayDiff {
+    fn new<T: Serialize>(
+        record: &ReplayedBlockRecord,
+        source: &str,
+        field: String,
+        expected: &T,
+        actual: &T,
+    ) -> Self {
+        Self {
+            block_id: record.block_id.clone(),
+            height: record.height,
+            source: source.into(),
+            field,
+            expected: serde_json::to_value(expected).expect("FATAL: failed to serialize"),
+            actual: serde_json::to_value(actual).expect("FATAL: failed to serialize"),
+        }
+    }
+}
+
+/// Compare a replayed block against a previously-recorded replay of the same block
+pub fn diff_replayed_blocks(
+    expected: &ReplayedBlockRecord,
+    actual: &ReplayedBlockRecord,
+) -> Vec<ReplayDiff> {
+    let source = "baseline";
+    let mut diffs = vec![];
+    if expected.cost != actual.cost {
+        diffs.push(ReplayDiff::new(
+            actual,
+            source,
+            "cost".into(),
+            &expected.cost,
+            &actual.cost,
+        ));
+    }
+    if expected.txs.len() != actual.txs.len() {
+        diffs.push(ReplayDiff::new(
+            actual,
+            source,
+            "txs.len".into(),
+            &expected.txs.len(),
+            &actual.txs.len(),
+        ));
+    }
+    for (i, (expected_tx, actual_tx)) in expected.txs.iter().zip(actual.txs.iter()).enumerate() {
+        macro_rules! diff_field {
+            ($field:ident) => {
+                if expected_tx.$field != actual_tx.$field {
+                    diffs.push(ReplayDiff::new(
+                        actual,
+                        source,
+                        format!("txs[{i}].{}", stringify!($field)),
+                        &expected_tx.$field,
+                        &actual_tx.$field,
+                    ));
+                }
+            };
+        }
+        diff_field!(txid);
+        diff_field!(result);
+        diff_field!(vm_error);
+        diff_field!(post_condition_aborted);
+        diff_field!(stx_burned);
+        diff_field!(execution_cost);
+        diff_field!(events);
+    }
+    diffs
+}
+
+/// Load the processed blocks with heights in `[start, end]`, in height order
+fn load_replay_range(
+    chainstate: &StacksChainState,
+    start: u64,
+    end: u64,
+) -> Result<Vec<(StacksBlockId, u64, bool)>, ChainstateError> {
+    let mut blocks = vec![];
+    for (table, is_nakamoto) in [("block_headers", false), ("nakamoto_block_headers", true)] {
+        let qry = format!(
+            "SELECT index_block_hash, block_height FROM {table} WHERE block_height >= ?1 AND block_height <= ?2"
+        );
+        let mut stmt = chainstate.db().prepare(&qry).map_err(DBError::from)?;
+        let mut rows = stmt
+            .query(params![u64_to_sql(start)?, u64_to_sql(end)?])
+            .map_err(DBError::from)?;
+        while let Some(row) = rows.next().map_err(DBError::from)? {
+            let block_id: StacksBlockId = row.get(0).map_err(DBError::from)?;
+            let height: u64 = u64::from_column(row, "block_height")?;
+            blocks.push((block_id, height, is_nakamoto));
+        }
+    }
+    blocks.sort_by_key(|(_, height, _)| *height);
+    Ok(blocks)
+}
+
+/// Load the cost recorded in the headers DB for a processed block
+fn load_stored_block_cost(
+    chainstate: &StacksChainState,
+    block_id: &StacksBlockId,
+    is_nakamoto: bool,
+) -> Result<Option<ExecutionCost>, ChainstateError> {
+    if !is_nakamoto {
+        return StacksChainState::get_stacks_block_anchored_cost(chainstate.db(), block_id);
+    }
+    chainstate
+        .db()
+        .query_row(
+            "SELECT cost FROM nakamoto_block_headers WHERE index_block_hash = ?1",
+            params![block_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| DBError::from(e).into())
+}
+
+/// Deterministically re-execute a range of processed blocks, and report every difference between
+/// the replayed outcome and (1) the costs stored in chainstate, and (2) optionally, a baseline
+/// saved by an earlier run with `--save`.  Replayed blocks are never committed, so the chainstate
+/// is left untouched.  Diffs are printed to stdout as JSON lines.
+/// Terminates with a non-zero exit code if any differences were found.
+///
+/// Arguments:
+///  - `argv`: Args in CLI format: `<command-name> [args...]`
+///  - `conf`: Optional config for running on non-mainnet chainstate
+pub fn command_replay_range(argv: &[String], conf: Option<&Config>) {
+    let print_help_and_exit = || -> ! {
+        let n = &argv[0];
+        eprintln!("Usage:");
+        eprintln!("  {n} <database-path> <start-height> <end-height> [--save <records-path>] [--baseline <records-path>]");
+        process::exit(1);
+    };
+    let start_time = Instant::now();
+    let db_path = argv.get(1).unwrap_or_else(|| print_help_and_exit());
+    let (Some(Ok(start)), Some(Ok(end))) = (
+        argv.get(2).map(|s| s.parse::<u64>()),
+        argv.get(3).map(|s| s.parse::<u64>()),
+    ) else {
+        print_help_and_exit();
+    };
+    let mut save_path = None;
+    let mut baseline_path = None;
+    let mut i = 4;
+    while i < argv.len() {
+        let value = argv.get(i + 1).unwrap_or_else(|| print_help_and_exit());
+        match argv[i].as_str() {
+            "--save" => save_path = Some(value.clone()),
+            "--baseline" => baseline_path = Some(value.clone()),
+            _ => print_help_and_exit(),
+        }
+        i += 2;
+    }
+
+    let conf = conf.unwrap_or(&DEFAULT_MAINNET_CONFIG);
+    let chain_state_path = format!("{db_path}/chainstate/");
+    let (chainstate, _) = StacksChainState::open(
+        conf.is_mainnet(),
+        conf.burnchain.chain_id,
+        &chain_state_path,
+        None,
+    )
+    .unwrap();
+
+    let baseline: HashMap<StacksBlockId, ReplayedBlockRecord> = baseline_path
+        .map(|path| {
+            let contents = fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("Failed to read baseline {path}: {e}"));
+            contents
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| {
+                    let record: ReplayedBlockRecord = serde_json::from_str(line)
+                        .unwrap_or_else(|e| panic!("Failed to parse baseline {path}: {e}"));
+                    (record.block_id.clone(), record)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut save_file = save_path.map(|path| {
+        fs::File::create(&path).unwrap_or_else(|e| panic!("Failed to create {path}: {e}"))
+    });
+
+    let blocks = load_replay_range(&chainstate, start, end).unwrap();
+    eprintln!("Will replay {} blocks", blocks.len());
+
+    let mut num_diffs = 0;
+    for (i, (block_id, height, is_nakamoto)) in blocks.iter().enumerate() {
+        if i % 100 == 0 {
+            eprintln!("Replayed {i}...");
+        }
+        let block_id_hex = block_id.to_hex();
+        let replay_res = if *is_nakamoto {
+            replay_naka_staging_block(db_path, &block_id_hex, conf, None::<&DummyEventDispatcher>)
+        } else {
+            replay_staging_block(
+                db_path,
+                &block_id_hex,
+                Some(conf),
+                None::<&DummyEventDispatcher>,
+            )
+        };
+        let receipt = match replay_res {
+            Ok(Some(receipt)) => receipt,
+            Ok(None) => {
+                eprintln!("Skipped block {block_id}: it could not be evaluated");
+                continue;
+            }
+            Err(e) => {
+                let diff = ReplayDiff {
+                    block_id: block_id.clone(),
+                    height: *height,
+                    source: "chainstate".into(),
+                    field: "processed".into(),
+                    expected: serde_json::Value::Bool(true),
+                    actual: serde_json::Value::String(format!("{e:?}")),
+                };
+                println!("{}", serde_json::to_string(&diff).unwrap());
+                num_diffs += 1;
+                continue;
+            }
+        };
+
+        let record = ReplayedBlockRecord::from_receipt(block_id, *height, &receipt);
+        let mut diffs = vec![];
+        if let Some(stored_cost) =
+            load_stored_block_cost(&chainstate, block_id, *is_nakamoto).unwrap()
+        {
+            if stored_cost != record.cost {
+                diffs.push(ReplayDiff::new(
+                    &record,
+                    "chainstate",
+                    "cost".into(),
+                    &stored_cost,
+                    &record.cost,
+                ));
+            }
+        }
+        if let Some(expected) = baseline.get(block_id) {
+            diffs.extend(diff_replayed_blocks(expected, &record));
+        }
+        for diff in diffs.iter() {
+            println!("{}", serde_json::to_string(diff).unwrap());
+        }
+        num_diffs += diffs.len();
+
+        if let Some(file) = save_file.as_mut() {
+            writeln!(file, "{}", serde_json::to_string(&record).unwrap())
+                .unwrap_or_else(|e| panic!("Failed to save replay record: {e}"));
+        }
+    }
+    eprintln!(
+        "Finished. blocks = {}, diffs = {num_diffs}, run_time_seconds = {}",
+        blocks.len(),
+        start_time.elapsed().as_secs()
+    );
+    if num_diffs > 0 {
+        process::exit(1);
+    }
+}
+
+/// Load the canonical Stacks fork's blocks with heights in `[start, end]`, in height order.
+/// Each block is returned with its height and whether it is a Nakamoto block.
+pub fn load_canonical_block_range(
+    db_path: &str,
+    conf: &Config,
+    start: u64,
+    end: u64,
+) -> Result<Vec<(StacksBlockId, u64, bool)>, ChainstateError> {
+    let chain_state_path = format!("{db_path}/chainstate/");
+    let sort_db_path = format!("{db_path}/burnchain/sortition");
+    let (chainstate, _) = StacksChainState::open(
+        conf.is_mainnet(),
+        conf.burnchain.chain_id,
+        &chain_state_path,
+        None,
+    )?;
+    let sortdb = SortitionDB::open(&sort_db_path, false, conf.get_burnchain().pox_constants)?;
+    let (tip_consensus_hash, tip_block_hash) =
+        SortitionDB::get_canonical_stacks_chain_tip_hash(sortdb.conn())?;
+    let tip = StacksBlockId::new(&tip_consensus_hash, &tip_block_hash);
+
+    let mut blocks = vec![];
+    for height in start..=end {
+        let Some(block_id) = chainstate
+            .index_conn()
+            .get_ancestor_block_hash(height, &tip)?
+        else {
+            break;
+        };
+        let header = NakamotoChainState::get_block_header(chainstate.db(), &block_id)?
+            .ok_or(ChainstateError::NoSuchBlockError)?;
+        let is_nakamoto = matches!(header.anchored_header, StacksBlockHeaderTypes::Nakamoto(_));
+        blocks.push((block_id, height, is_nakamoto));
+    }
+    Ok(blocks)
+}
+
+/// Re-execute a processed block without committing it, and announce it to `dispatcher` the way
+/// the node did when it first processed the block.
+/// Returns the block's receipt, or `None` if the block could not be evaluated.
+pub fn replay_block_events<T: BlockEventDispatcher>(
+    db_path: &str,
+    block_id: &StacksBlockId,
+    is_nakamoto: bool,
+    conf: &Config,
+    dispatcher: &T,
+) -> Result<Option<StacksEpochReceipt>, ChainstateError> {
+    let block_id_hex = block_id.to_hex();
+    if is_nakamoto {
+        replay_naka_staging_block(db_path, &block_id_hex, conf, Some(dispatcher))
+    } else {
+        replay_staging_block(db_path, &block_id_hex, Some(conf), Some(dispatcher))
+    }
+}
+
+/// Resolve which reward set signers produced each of a block's signatures.
+/// Returns the signers' indexes in the reward set, or an error string for each signature which
+/// could not be resolved.
+fn resolve_block_signers(
+    block: &NakamotoBlock,
+    reward_set: &RewardSet,
+) -> Vec<Result<usize, String>> {
+    let message = block.header.signer_signature_hash();
+    let signers = reward_set.signers.as_deref().unwrap_or_default();
+    block
+        .header
+        .signer_signature
+        .iter()
+        .map(|signature| {
+            let public_key = Secp256k1PublicKey::recover_to_pubkey(message.bits(), signature)
+                .map_err(|e| format!("Unrecoverable signature {}: {e}", signature.to_hex()))?;
+            let public_key_bytes = public_key.to_bytes_compressed();
+            signers
+                .iter()
+                .position(|signer| signer.signing_key[..] == public_key_bytes[..])
+                .ok_or_else(|| {
+                    format!(
+                        "Public key {} is not in the reward set",
+                        public_key.to_hex()
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Print a forensic report of a Nakamoto tenure as JSON: each block's header, its PoX treatment
+/// bitvec, which signers signed it (resolved against the reward set), its tenure-change and
+/// tenure-extend transactions, and the block-commits in the tenure's sortition.
+///
+/// Arguments:
+///  - `argv`: Args in CLI format: `<command-name> [args...]`
+///  - `conf`: Optional config for running on non-mainnet chainstate
+pub fn command_dump_tenure(argv: &[String], conf: Option<&Config>) {
+    let print_help_and_exit = || -> ! {
+        let n = &argv[0];
+        eprintln!("Usage:");
+        eprintln!("  {n} <database-path> <consensus-hash>");
+        process::exit(1);
+    };
+    let db_path = argv.get(1).unwrap_or_else(|| print_help_and_exit());
+    let consensus_hash = argv
+        .get(2)
+        .and_then(|ch| ConsensusHash::from_hex(ch).ok())
+        .unwrap_or_else(|| print_help_and_exit());
+
+    let conf = conf.unwrap_or(&DEFAULT_MAINNET_CONFIG);
+    let chain_state_path = format!("{db_path}/chainstate/");
+    let sort_db_path = format!("{db_path}/burnchain/sortition");
+
+    let (mut chainstate, _) = StacksChainState::open(
+        conf.is_mainnet(),
+        conf.burnchain.chain_id,
+        &chain_state_path,
+        None,
+    )
+    .unwrap();
+    let burnchain = conf.get_burnchain();
+    let sortdb = SortitionDB::open(&sort_db_path, false, burnchain.pox_constants.clone())
+        .unwrap_or_else(|e| panic!("Failed to open {sort_db_path}: {e:?}"));
+
+    let Some(snapshot) =
+        SortitionDB::get_block_snapshot_consensus(sortdb.conn(), &consensus_hash).unwrap()
+    else {
+        eprintln!("No sortition with consensus hash {consensus_hash}");
+        process::exit(1);
+    };
+
+    // every copy of every block in this tenure that we know about, including orphans
+    let mut block_ids = vec![];
+    {
+        let staging_conn = chainstate.nakamoto_blocks_db();
+        let mut stmt = staging_conn
+            .prepare(
+                "SELECT index_block_hash, processed, orphaned FROM nakamoto_staging_blocks WHERE consensus_hash = ?1 ORDER BY height ASC",
+            )
+            .unwrap();
+        let mut rows = stmt.query(params![consensus_hash]).unwrap();
+        while let Some(row) = rows.next().unwrap() {
+            let block_id: StacksBlockId = row.get(0).unwrap();
+            let processed: bool = row.get(1).unwrap();
+            let orphaned: bool = row.get(2).unwrap();
+            block_ids.push((block_id, processed, orphaned));
+        }
+    }
+
+    let mut blocks = vec![];
+    for (block_id, processed, orphaned) in block_ids {
+        let Some((block, _)) = chainstate
+            .nakamoto_blocks_db()
+            .get_nakamoto_block(&block_id)
+            .unwrap()
+        else {
+            continue;
+        };
+        blocks.push((block, processed, orphaned));
+    }
+
+    // the signer set that signed off on this tenure's blocks
+    let reward_cycle = burnchain.block_height_to_reward_cycle(snapshot.block_height);
+    let reward_set = match (reward_cycle, blocks.first()) {
+        (Some(reward_cycle), Some((first_block, ..))) => {
+            OnChainRewardSetProvider::<DummyEventDispatcher>(None)
+                .read_reward_set_nakamoto_of_cycle(
+                    reward_cycle,
+                    &mut chainstate,
+                    &sortdb,
+                    &first_block.header.parent_block_id,
+                    true,
+                )
+                .map_err(|e| eprintln!("Failed to load reward set for cycle {reward_cycle}: {e:?}"))
+                .ok()
+        }
+        _ => None,
+    };
+
+    let block_reports: Vec<_> = blocks
+        .iter()
+        .map(|(block, processed, orphaned)| {
+            let signers: Vec<_> = match reward_set.as_ref() {
+                Some(reward_set) => {
+                    let signer_entries = reward_set.signers.as_deref().unwrap_or_default();
+                    resolve_block_signers(block, reward_set)
+                        .into_iter()
+                        .map(|resolved| match resolved {
+                            Ok(signer_index) => serde_json::json!({
+                                "signer_index": signer_index,
+                                "signing_key": to_hex(&signer_entries[signer_index].signing_key),
+                                "weight": signer_entries[signer_index].weight,
+                            }),
+                            Err(e) => serde_json::json!({ "error": e }),
+                        })
+                        .collect()
+                }
+                None => vec![],
+            };
+            let signed_weight = reward_set
+                .as_ref()
+                .map(|reward_set| block.header.verify_signer_signatures(reward_set))
+                .map(|res| match res {
+                    Ok(weight) => serde_json::json!(weight),
+                    Err(e) => serde_json::json!({ "error": format!("{e:?}") }),
+                });
+            let tenure_txs: Vec<_> = block
+                .txs
+                .iter()
+                .filter_map(|tx| match &tx.payload {
+                    TransactionPayload::TenureChange(payload) => Some(serde_json::json!({
+                        "txid": tx.txid(),
+                        "payload": payload,
+                    })),
+                    _ => None,
+                })
+                .collect();
+            serde_json::json!({
+                "block_id": block.header.block_id(),
+                "block_hash": block.header.block_hash(),
+                "parent_block_id": block.header.parent_block_id,
+                "height": block.header.chain_length,
+                "timestamp": block.header.timestamp,
+                "miner_signature": block.header.miner_signature,
+                "processed": processed,
+                "orphaned": orphaned,
+                "num_txs": block.txs.len(),
+                "pox_treatment": block.header.pox_treatment.binary_str(),
+                "num_signatures": block.header.signer_signature.len(),
+                "signers": signers,
+                "signed_weight": signed_weight,
+                "tenure_txs": tenure_txs,
+            })
+        })
+        .collect();
+
+    let commits: Vec<_> =
+        SortitionDB::get_block_commits_by_block(sortdb.conn(), &snapshot.sortition_id)
+            .unwrap()
+            .into_iter()
+            .map(|commit| {
+                serde_json::json!({
+                    "won": commit.txid == snapshot.winning_block_txid,
+                    "commit": commit,
+                })
+            })
+            .collect();
+
+    let report = serde_json::json!({
+        "consensus_hash": consensus_hash,
+        "burn_block_hash": snapshot.burn_header_hash,
+        "burn_block_height": snapshot.block_height,
+        "sortition": snapshot.sortition,
+        "winning_block_txid": snapshot.winning_block_txid,
+        "reward_cycle": reward_cycle,
+        "total_signing_weight": reward_set.as_ref().and_then(|reward_set| reward_set.total_signing_weight().ok()),
+        "blocks": block_reports,
+        "block_commits": commits,
+    });
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+}
+
+/// Whether a transaction included on one fork can be mined on another
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum Replayability {
+    /// Can be mined on the other fork as-is
+    Replayable,
+    /// Can be mined on the other fork once the sender's (or sponsor's) transactions with nonces
+    /// from `next_nonce` up are
+    AwaitingEarlierNonces { account: String, next_nonce: u64 },
+    /// The other fork already used the sender's (or sponsor's) nonce for a different transaction
+    NonceConflict { account: String, next_nonce: u64 },
+    /// The other fork already has a contract with the same name
+    ContractExists,
+    /// Tenure changes and coinbases are only valid in the tenure that mined them
+    TenureBound,
+}
+
+/// Decide whether `tx` can be mined on a fork, given the next nonces of its origin and sponsor
+/// on that fork and whether the contract it deploys (if any) already exists there
+pub fn classify_replayable_tx(
+    tx: &StacksTransaction,
+    origin_next_nonce: u64,
+    sponsor_next_nonce: Option<u64>,
+    contract_exists: bool,
+) -> Replayability {
+    if matches!(
+        tx.payload,
+        TransactionPayload::TenureChange(..) | TransactionPayload::Coinbase(..)
+    ) {
+        return Replayability::TenureBound;
+    }
+    let mut nonces = vec![(
+        tx.origin_address(),
+        tx.get_origin_nonce(),
+        origin_next_nonce,
+    )];
+    if let (Some(sponsor), Some(nonce), Some(next_nonce)) = (
+        tx.sponsor_address(),
+        tx.get_sponsor_nonce(),
+        sponsor_next_nonce,
+    ) {
+        nonces.push((sponsor, nonce, next_nonce));
+    }
+    if let Some((account, _, next_nonce)) = nonces
+        .iter()
+        .find(|(_, nonce, next_nonce)| nonce < next_nonce)
+    {
+        return Replayability::NonceConflict {
+            account: account.to_string(),
+            next_nonce: *next_nonce,
+        };
+    }
+    if contract_exists {
+        return Replayability::ContractExists;
+    }
+    if let Some((account, _, next_nonce)) = nonces
+        .iter()
+        .find(|(_, nonce, next_nonce)| nonce > next_nonce)
+    {
+        return Replayability::AwaitingEarlierNonces {
+            account: account.to_string(),
+            next_nonce: *next_nonce,
+        };
+    }
+    Replayability::Replayable
+}
+
+/// A transaction included on one fork but not the other
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForkedTxRecord {
+    pub txid: Txid,
+    /// The block on this transaction's fork that includes it
+    pub block_id: StacksBlockId,
+    pub block_height: u64,
+    pub origin: String,
+    pub origin_nonce: u64,
+    pub sponsor: Option<String>,
+    pub sponsor_nonce: Option<u64>,
+    /// Whether it can be mined on the other fork
+    pub replayability: Replayability,
+}
+
+/// Walk back from two Nakamoto blocks to their common ancestor.  Returns the ancestor's ID and
+/// the blocks on each fork after it, in height order.  Gives up after `max_depth` blocks.
+fn load_fork_branches(
+    chainstate: &StacksChainState,
+    tip_a: &StacksBlockId,
+    tip_b: &StacksBlockId,
+    max_depth: u64,
+) -> Result<(StacksBlockId, Vec<NakamotoBlock>, Vec<NakamotoBlock>), String> {
+    let load_block = |block_id: &StacksBlockId| -> Result<NakamotoBlock, String> {
+        chainstate
+            .nakamoto_blocks_db()
+            .get_nakamoto_block(block_id)
+            .map_err(|e| format!("Failed to load block {block_id}: {e:?}"))?
+            .map(|(block, _size)| block)
+            .ok_or_else(|| {
+                format!("No Nakamoto block {block_id}; is the fork point before Nakamoto?")
+            })
+    };
+    let mut block_a = load_block(tip_a)?;
+    let mut block_b = load_block(tip_b)?;
+    let mut branch_a = vec![];
+    let mut branch_b = vec![];
+    while block_a.block_id() != block_b.block_id() {
+        if (branch_a.len() + branch_b.len()) as u64 >= max_depth {
+            return Err(format!(
+                "No common ancestor within {max_depth} blocks of the fork tips"
+            ));
+        }
+        if block_a.header.chain_length >= block_b.header.chain_length {
+            let parent = load_block(&block_a.header.parent_block_id)?;
+            branch_a.push(std::mem::replace(&mut block_a, parent));
+        } else {
+            let parent = load_block(&block_b.header.parent_block_id)?;
+            branch_b.push(std::mem::replace(&mut block_b, parent));
+        }
+    }
+    branch_a.reverse();
+    branch_b.reverse();
+    Ok((block_a.block_id(), branch_a, branch_b))
+}
+
+/// Get the next nonce of `addr` on top of `tip`, from `next_nonces` if it has been looked up
+/// already
+fn load_next_nonce(
+    chainstate: &mut StacksChainState,
+    handle: &SortitionHandleConn,
+    tip: &StacksBlockId,
+    next_nonces: &mut HashMap<StacksAddress, u64>,
+    addr: &StacksAddress,
+) -> Result<u64, String> {
+    if let Some(nonce) = next_nonces.get(addr) {
+        return Ok(*nonce);
+    }
+    let nonce = chainstate
+        .with_read_only_clarity_tx(handle, tip, |conn| {
+            StacksChainState::get_account(conn, &addr.to_account_principal()).nonce
+        })
+        .ok_or_else(|| format!("Block {tip} has not been processed"))?;
+    next_nonces.insert(addr.clone(), nonce);
+    Ok(nonce)
+}
+
+/// Classify the transactions in `branch` that are not in `other_branch` by whether they can be
+/// mined on top of `other_tip`.  Each replayable transaction advances its sender's (and sponsor's)
+/// nonce, so that a sender's orphaned transactions are considered in order.
+fn find_replayable_txs(
+    chainstate: &mut StacksChainState,
+    sortdb: &SortitionDB,
+    branch: &[NakamotoBlock],
+    other_branch: &[NakamotoBlock],
+    other_tip: &StacksBlockId,
+) -> Result<Vec<ForkedTxRecord>, String> {
+    let other_txids: HashSet<_> = other_branch
+        .iter()
+        .flat_map(|block| block.txs.iter().map(|tx| tx.txid()))
+        .collect();
+    let handle = sortdb
+        .index_handle_at_block(chainstate, other_tip)
+        .map_err(|e| format!("Failed to load sortition of block {other_tip}: {e:?}"))?;
+
+    let mut next_nonces: HashMap<StacksAddress, u64> = HashMap::new();
+
+    let mut records = vec![];
+    for block in branch {
+        for tx in block.txs.iter() {
+            let txid = tx.txid();
+            if other_txids.contains(&txid) {
+                continue;
+            }
+            let origin = tx.origin_address();
+            let sponsor = tx.sponsor_address();
+            let origin_next_nonce =
+                load_next_nonce(chainstate, &handle, other_tip, &mut next_nonces, &origin)?;
+            let sponsor_next_nonce = sponsor
+                .as_ref()
+                .map(|sponsor| {
+                    load_next_nonce(chainstate, &handle, other_tip, &mut next_nonces, sponsor)
+                })
+                .transpose()?;
+            let contract_exists = match &tx.payload {
+                TransactionPayload::SmartContract(contract, _) => {
+                    let contract_id = QualifiedContractIdentifier::new(
+                        origin.clone().into(),
+                        contract.name.clone(),
+                    );
+                    chainstate
+                        .with_read_only_clarity_tx(&handle, other_tip, |conn| {
+                            conn.with_clarity_db_readonly(|db| db.has_contract(&contract_id))
+                        })
+                        .ok_or_else(|| format!("Block {other_tip} has not been processed"))?
+                }
+                _ => false,
+            };
+
+            let replayability =
+                classify_replayable_tx(tx, origin_next_nonce, sponsor_next_nonce, contract_exists);
+            if replayability == Replayability::Replayable {
+                next_nonces.insert(origin.clone(), tx.get_origin_nonce() + 1);
+                if let (Some(sponsor), Some(nonce)) = (sponsor.as_ref(), tx.get_sponsor_nonce()) {
+                    next_nonces.insert(sponsor.clone(), nonce + 1);
+                }
+            }
+            records.push(ForkedTxRecord {
+                txid,
+                block_id: block.block_id(),
+                block_height: block.header.chain_length,
+                origin: origin.to_string(),
+                origin_nonce: tx.get_origin_nonce(),
+                sponsor: sponsor.map(|sponsor| sponsor.to_string()),
+                sponsor_nonce: tx.get_sponsor_nonce(),
+                replayability,
+            });
+        }
+    }
+    Ok(records)
+}
+
+/// Given the tips of two forks, print as JSON the transactions included on each fork but not the
+/// other, and whether each could be replayed on the other fork.  For incident response when a
+/// short fork orphans user transactions.
+///
+/// Arguments:
+///  - `argv`: Args in CLI format: `<command-name> [args...]`
+///  - `conf`: Optional config for running on non-mainnet chainstate
+pub fn command_find_replayable(argv: &[String], conf: Option<&Config>) {
+    let print_help_and_exit = || -> ! {
+        let n = &argv[0];
+        eprintln!("Usage:");
+        eprintln!("  {n} <database-path> <fork-a-tip-block-id> <fork-b-tip-block-id> [max-depth]");
+        process::exit(1);
+    };
+    let db_path = argv.get(1).unwrap_or_else(|| print_help_and_exit());
+    let tip_a = argv
+        .get(2)
+        .and_then(|tip| StacksBlockId::from_hex(tip).ok())
+        .unwrap_or_else(|| print_help_and_exit());
+    let tip_b = argv
+        .get(3)
+        .and_then(|tip| StacksBlockId::from_hex(tip).ok())
+        .unwrap_or_else(|| print_help_and_exit());
+    let max_depth = argv
+        .get(4)
+        .map(|depth| {
+            depth
+                .parse::<u64>()
+                .unwrap_or_else(|_| print_help_and_exit())
+        })
+        .unwrap_or(1000);
+
+    let conf = conf.unwrap_or(&DEFAULT_MAINNET_CONFIG);
+    let chain_state_path = format!("{db_path}/chainstate/");
+    let sort_db_path = format!("{db_path}/burnchain/sortition");
+
+    let (mut chainstate, _) = StacksChainState::open(
+        conf.is_mainnet(),
+        conf.burnchain.chain_id,
+        &chain_state_path,
+        None,
+    )
+    .unwrap();
+    let burnchain = conf.get_burnchain();
+    let sortdb = SortitionDB::open(&sort_db_path, false, burnchain.pox_constants.clone())
+        .unwrap_or_else(|e| panic!("Failed to open {sort_db_path}: {e:?}"));
+
+    let (ancestor, branch_a, branch_b) = load_fork_branches(&chainstate, &tip_a, &tip_b, max_depth)
+        .unwrap_or_else(|e| {
+            eprintln!("{e}");
+            process::exit(1);
+        });
+    let ancestor_height = branch_a
+        .first()
+        .or(branch_b.first())
+        .map(|block| block.header.chain_length.saturating_sub(1));
+
+    let a_only = find_replayable_txs(&mut chainstate, &sortdb, &branch_a, &branch_b, &tip_b);
+    let b_only = find_replayable_txs(&mut chainstate, &sortdb, &branch_b, &branch_a, &tip_a);
+    let (a_only, b_only) = match (a_only, b_only) {
+        (Ok(a_only), Ok(b_only)) => (a_only, b_only),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    };
+
+    let report = serde_json::json!({
+        "common_ancestor": ancestor,
+        "common_ancestor_height": ancestor_height,
+        "fork_a": {
+            "tip": tip_a,
+            "num_blocks": branch_a.len(),
+            "txs_not_in_fork_b": a_only,
+        },
+        "fork_b": {
+            "tip": tip_b,
+            "num_blocks": branch_b.len(),
+            "txs_not_in_fork_a": b_only,
+        },
+    });
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+}
+
+/// Check the sortition DB's canonical fork for broken ancestry links, underivable consensus
+/// hashes, and PoX anchor blocks that disagree with the burnchain DB.  With `--repair`, roll the
+/// sortition DB back to the last consistent sortition so the node re-derives the rest from the
+/// burnchain DB when it next starts.
+///
+/// Exits with status 1 if inconsistencies remain.
+pub fn command_check_sortition_db(argv: &[String], conf: Option<&Config>) {
+    let print_help_and_exit = || -> ! {
+        let n = &argv[0];
+        eprintln!("Usage:");
+        eprintln!("  {n} <database-path> [--repair]");
+        eprintln!();
+        eprintln!("  --repair  Delete every sortition at or above the lowest inconsistency. The");
+        eprintln!("            node re-derives them from its burnchain DB when it next starts.");
+        process::exit(1);
+    };
+    let db_path = argv.get(1).unwrap_or_else(|| print_help_and_exit());
+    let repair = match argv.get(2).map(String::as_str) {
+        None => false,
+        Some("--repair") => true,
+        Some(_) => print_help_and_exit(),
+    };
+
+    let conf = conf.unwrap_or(&DEFAULT_MAINNET_CONFIG);
+    let sort_db_path = format!("{db_path}/burnchain/sortition");
+    let burn_db_path = format!("{db_path}/burnchain/burnchain.sqlite");
+
+    let burnchain = conf.get_burnchain();
+    let burnchain_db = BurnchainDB::open(&burn_db_path, false)
+        .unwrap_or_else(|e| panic!("Failed to open {burn_db_path}: {e:?}"));
+    let mut sortdb = SortitionDB::open(&sort_db_path, repair, burnchain.pox_constants.clone())
+        .unwrap_or_else(|e| panic!("Failed to open {sort_db_path}: {e:?}"));
+
+    let check = sortdb
+        .check_consistency(&burnchain, &burnchain_db)
+        .unwrap_or_else(|e| panic!("Failed to check {sort_db_path}: {e:?}"));
+    let inconsistencies: Vec<_> = check
+        .inconsistencies
+        .iter()
+        .map(|inconsistency| {
+            serde_json::json!({
+                "burn_block_height": inconsistency.block_height,
+                "sortition_id": inconsistency.sortition_id,
+                "reason": inconsistency.reason,
+            })
+        })
+        .collect();
+
+    let rollback_height = check
+        .first_inconsistent_height()
+        .map(|height| height.saturating_sub(1).max(sortdb.first_block_height));
+    let deleted_sortitions = match rollback_height {
+        Some(rollback_height) if repair => Some(
+            sortdb
+                .rollback_to_height(rollback_height)
+                .unwrap_or_else(|e| panic!("Failed to repair {sort_db_path}: {e:?}")),
+        ),
+        _ => None,
+    };
+
+    let report = serde_json::json!({
+        "checked_sortitions": check.checked_sortitions,
+        "consistent": check.is_consistent(),
+        "inconsistencies": inconsistencies,
+        "rollback_height": rollback_height,
+        "deleted_sortitions": deleted_sortitions,
+    });
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+
+    if !check.is_consistent() && deleted_sortitions.is_none() {
+        process::exit(1);
+    }
+}
+
+/// Tables that `stacks-inspect export` can write
+const EXPORT_TABLES: &[&str] = &["blocks", "txs", "events"];
+
+/// Columns of the exported `blocks` table
+const EXPORT_BLOCK_COLUMNS: &[Column] = &[
+    Column::new("block_id", ColumnType::Text),
+    Column::new("block_hash", ColumnType::Text),
+    Column::new("parent_block_id", ColumnType::Text),
+    Column::new("consensus_hash", ColumnType::Text),
+    Column::new("height", ColumnType::Int),
+    Column::new("burn_block_hash", ColumnType::Text),
+    Column::new("burn_block_height", ColumnType::Int),
+    Column::new("burn_block_timestamp", ColumnType::Int),
+    // the block's own timestamp for Nakamoto blocks, and the burn block's for epoch 2.x blocks
+    Column::new("timestamp", ColumnType::Int),
+    Column::new("is_nakamoto", ColumnType::Bool),
+    Column::new("block_size", ColumnType::Int),
+    Column::new("tx_count", ColumnType::Int),
+];
+
+/// Columns of the exported `txs` table.  `sponsor`, `contract_id` and `function_name` are empty
+/// when they do not apply.
+const EXPORT_TX_COLUMNS: &[Column] = &[
+    Column::new("txid", ColumnType::Text),
+    Column::new("block_id", ColumnType::Text),
+    Column::new("block_height", ColumnType::Int),
+    Column::new("tx_index", ColumnType::Int),
+    Column::new("tx_type", ColumnType::Text),
+    Column::new("sender", ColumnType::Text),
+    Column::new("sponsor", ColumnType::Text),
+    Column::new("nonce", ColumnType::Int),
+    Column::new("fee", ColumnType::Int),
+    Column::new("contract_id", ColumnType::Text),
+    Column::new("function_name", ColumnType::Text),
+    Column::new("raw_tx", ColumnType::Text),
+];
+
+/// Columns of the exported `events` table.  `event` is the event's JSON, as sent to event
+/// observers.
+const EXPORT_EVENT_COLUMNS: &[Column] = &[
+    Column::new("txid", ColumnType::Text),
+    Column::new("block_id", ColumnType::Text),
+    Column::new("block_height", ColumnType::Int),
+    Column::new("tx_index", ColumnType::Int),
+    Column::new("event_index", ColumnType::Int),
+    Column::new("event_type", ColumnType::Text),
+    Column::new("committed", ColumnType::Bool),
+    Column::new("event", ColumnType::Text),
+];
+
+/// The `txs` table row of the `tx_index`th transaction in a block
+fn export_tx_row(
+    tx: &StacksTransaction,
+    block_id: &StacksBlockId,
+    block_height: u64,
+    tx_index: usize,
+) -> Vec<ExportValue> {
+    let (contract_id, function_name) = match &tx.payload {
+        TransactionPayload::ContractCall(cc) => (
+            cc.contract_identifier().to_string(),
+            cc.function_name.to_string(),
+        ),
+        TransactionPayload::SmartContract(sc, _) => (
+            format!("{}.{}", tx.origin_address(), &sc.name),
+            String::new(),
+        ),
+        _ => (String::new(), String::new()),
+    };
+    vec![
+        tx.txid().to_hex().into(),
+        block_id.to_hex().into(),
+        ExportValue::uint(block_height),
+        ExportValue::uint(u64::try_from(tx_index).unwrap_or(u64::MAX)),
+        payload_type_name(&tx.payload).into(),
+        tx.origin_address().to_string().into(),
+        tx.sponsor_address()
+            .map(|addr| addr.to_string())
+            .unwrap_or_default()
+            .into(),
+        ExportValue::uint(tx.get_origin_nonce()),
+        ExportValue::uint(tx.get_tx_fee()),
+        contract_id.into(),
+        function_name.into(),
+        to_hex(&tx.serialize_to_vec()).into(),
+    ]
+}
+
+/// Write the canonical Stacks fork's blocks, transactions and transaction events to columnar
+/// files (Parquet or CSV) in an output directory, one file per table, for analytics with tools
+/// like DuckDB or Athena.
+///
+/// Events are only available if the node kept transaction receipts (`node.receipt_tracking`),
+/// and only for the blocks still within `node.receipt_retention`.  Transactions mined in epoch
+/// 2.x microblocks are not exported.
+///
+/// Arguments:
+///  - `argv`: Args in CLI format: `<command-name> [args...]`
+///  - `conf`: Optional config for running on non-mainnet chainstate
+pub fn command_export(argv: &[String], conf: Option<&Config>) {
+    let print_help_and_exit = || -> ! {
+        let n = &argv[0];
+        eprintln!("Usage:");
+        eprintln!("  {n} <database-path> <output-dir> [--format parquet|csv] [--tables blocks,txs,events] [--from-height <height>] [--to-height <height>]");
+        eprintln!();
+        eprintln!(
+            "  --format       Defaults to parquet, which needs a build with the `parquet-export`"
+        );
+        eprintln!("                 feature.");
+        eprintln!("  --tables       Defaults to blocks and txs, plus events if the node kept");
+        eprintln!("                 transaction receipts (node.receipt_tracking).");
+        eprintln!("  --from-height  First Stacks block height to export.  Defaults to 0.");
+        eprintln!("  --to-height    Last Stacks block height to export.  Defaults to the tip.");
+        process::exit(1);
+    };
+    let db_path = argv.get(1).unwrap_or_else(|| print_help_and_exit());
+    let out_dir = argv.get(2).unwrap_or_else(|| print_help_and_exit());
+    let mut format = ExportFormat::Parquet;
+    let mut tables: Option<Vec<String>> = None;
+    let mut from_height = 0;
+    let mut to_height = u64::MAX;
+    let mut i = 3;
+    while i < argv.len() {
+        let value = argv.get(i + 1).unwrap_or_else(|| print_help_and_exit());
+        match argv[i].as_str() {
+            "--format" => {
+                format = ExportFormat::from_str(value).unwrap_or_else(|| print_help_and_exit())
+            }
+            "--tables" => tables = Some(value.split(',').map(|t| t.trim().to_string()).collect()),
+            "--from-height" => {
+                from_height = value.parse().unwrap_or_else(|_| print_help_and_exit())
+            }
+            "--to-height" => to_height = value.parse().unwrap_or_else(|_| print_help_and_exit()),
+            _ => print_help_and_exit(),
+        }
+        i += 2;
+    }
+    if !format.is_supported() {
+        eprintln!("This build of stacks-inspect cannot write Parquet files. Rebuild it with `--features parquet-export`, or pass `--format csv`.");
+        process::exit(1);
+    }
+
+    let conf = conf.unwrap_or(&DEFAULT_MAINNET_CONFIG);
+    let chain_state_path = format!("{db_path}/chainstate/");
+    let receipt_db_path = PathBuf::from(format!("{chain_state_path}receipts.sqlite"));
+    let tables = tables.unwrap_or_else(|| {
+        EXPORT_TABLES
+            .iter()
+            .filter(|table| **table != "events" || receipt_db_path.exists())
+            .map(|table| table.to_string())
+            .collect()
+    });
+    if let Some(table) = tables.iter().find(|t| !EXPORT_TABLES.contains(&t.as_str())) {
+        eprintln!(
+            "Unknown table {table}. Expected any of {}",
+            EXPORT_TABLES.join(",")
+        );
+        process::exit(1);
+    }
+    let export_table = |table: &str| tables.iter().any(|t| t == table);
+
+    let receipt_db = if export_table("events") {
+        if !receipt_db_path.exists() {
+            eprintln!(
+                "No transaction receipts at {}: events can only be exported if the node kept receipts (node.receipt_tracking)",
+                receipt_db_path.display()
+            );
+            process::exit(1);
+        }
+        Some(
+            TransactionReceiptDB::open(&receipt_db_path, conf.node.receipt_retention)
+                .unwrap_or_else(|e| panic!("Failed to open {}: {e:?}", receipt_db_path.display())),
+        )
+    } else {
+        None
+    };
+
+    let (chainstate, _) = StacksChainState::open(
+        conf.is_mainnet(),
+        conf.burnchain.chain_id,
+        &chain_state_path,
+        None,
+    )
+    .unwrap();
+    let blocks = load_canonical_block_range(db_path, conf, from_height, to_height)
+        .unwrap_or_else(|e| panic!("Failed to load the canonical Stacks fork: {e:?}"));
+    eprintln!("Will export {} blocks", blocks.len());
+
+    fs::create_dir_all(out_dir).unwrap_or_else(|e| panic!("Failed to create {out_dir}: {e}"));
+    let open_writer =
+        |table: &str, columns: &'static [Column]| -> Option<(PathBuf, Box<dyn TableWriter>)> {
+            if !export_table(table) {
+                return None;
+            }
+            let path = Path::new(out_dir).join(format!("{table}.{}", format.extension()));
+            let writer = create_table_writer(format, &path, table, columns)
+                .unwrap_or_else(|e| panic!("Failed to create {}: {e}", path.display()));
+            Some((path, writer))
+        };
+    let mut block_writer = open_writer("blocks", EXPORT_BLOCK_COLUMNS);
+    let mut tx_writer = open_writer("txs", EXPORT_TX_COLUMNS);
+    let mut event_writer = open_writer("events", EXPORT_EVENT_COLUMNS);
+    let write_row = |writer: &mut Option<(PathBuf, Box<dyn TableWriter>)>, row| {
+        if let Some((path, writer)) = writer.as_mut() {
+            writer
+                .write_row(row)
+                .unwrap_or_else(|e| panic!("Failed to write to {}: {e}", path.display()));
+        }
+    };
+
+    let mut missing_blocks = 0;
+    for (i, (block_id, height, is_nakamoto)) in blocks.iter().enumerate() {
+        if i % 1000 == 0 {
+            eprintln!("Exported {i}...");
+        }
+        let header = NakamotoChainState::get_block_header(chainstate.db(), block_id)
+            .unwrap()
+            .unwrap_or_else(|| panic!("No header for canonical block {block_id}"));
+
+        let (parent_block_id, timestamp, txs) = match &header.anchored_header {
+            StacksBlockHeaderTypes::Nakamoto(nakamoto_header) => {
+                let txs = chainstate
+                    .nakamoto_blocks_db()
+                    .get_nakamoto_block(block_id)
+                    .unwrap()
+                    .map(|(block, _)| block.txs);
+                (
+                    nakamoto_header.parent_block_id.clone(),
+                    nakamoto_header.timestamp,
+                    txs,
+                )
+            }
+            StacksBlockHeaderTypes::Epoch2(epoch2_header) => {
+                let parent_block_id =
+                    StacksChainState::get_parent_block_id(chainstate.db(), block_id)
+                        .unwrap()
+                        .unwrap_or_else(|| panic!("No parent for canonical block {block_id}"));
+                let txs = StacksChainState::load_block(
+                    &chainstate.blocks_path,
+                    &header.consensus_hash,
+                    &epoch2_header.block_hash(),
+                )
+                .ok()
+                .flatten()
+                .map(|block| block.txs);
+                (parent_block_id, header.burn_header_timestamp, txs)
+            }
+        };
+        let txs = txs.unwrap_or_else(|| {
+            eprintln!("Block {block_id} at height {height} is missing from the chainstate; exporting its header only");
+            missing_blocks += 1;
+            vec![]
+        });
+
+        write_row(
+            &mut block_writer,
+            vec![
+                block_id.to_hex().into(),
+                header.anchored_header.block_hash().to_hex().into(),
+                parent_block_id.to_hex().into(),
+                header.consensus_hash.to_hex().into(),
+                ExportValue::uint(*height),
+                header.burn_header_hash.to_hex().into(),
+                ExportValue::uint(header.burn_header_height.into()),
+                ExportValue::uint(header.burn_header_timestamp),
+                ExportValue::uint(timestamp),
+                (*is_nakamoto).into(),
+                ExportValue::uint(header.anchored_block_size),
+                ExportValue::uint(u64::try_from(txs.len()).unwrap_or(u64::MAX)),
+            ],
+        );
+        for (tx_index, tx) in txs.iter().enumerate() {
+            write_row(
+                &mut tx_writer,
+                export_tx_row(tx, block_id, *height, tx_index),
+            );
+        }
+
+        let Some(receipt_db) = receipt_db.as_ref() else {
+            continue;
+        };
+        let receipts = receipt_db
+            .get_block_receipts(block_id)
+            .unwrap_or_else(|e| panic!("Failed to load receipts of block {block_id}: {e:?}"));
+        for receipt in receipts.iter() {
+            for event in receipt.events.iter() {
+                write_row(
+                    &mut event_writer,
+                    vec![
+                        receipt.txid.to_hex().into(),
+                        block_id.to_hex().into(),
+                        ExportValue::uint(*height),
+                        ExportValue::uint(receipt.tx_index.into()),
+                        ExportValue::uint(event["event_index"].as_u64().unwrap_or_default()),
+                        event["type"].as_str().unwrap_or_default().into(),
+                        event["committed"].as_bool().unwrap_or_default().into(),
+                        event.to_string().into(),
+                    ],
+                );
+            }
+        }
+    }
+
+    let mut report = serde_json::Map::new();
+    for writer in [block_writer, tx_writer, event_writer] {
+        let Some((path, writer)) = writer else {
+            continue;
+        };
+        let rows = writer
+            .finish()
+            .unwrap_or_else(|e| panic!("Failed to write {}: {e}", path.display()));
+        report.insert(
+            path.display().to_string(),
+            serde_json::json!({ "rows": rows }),
+        );
+    }
+    let report = serde_json::json!({
+        "format": format.extension(),
+        "blocks": blocks.len(),
+        "missing_blocks": missing_blocks,
+        "files": report,
+    });
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+}
+
+/// Print the node's PoX anchor block affirmation maps (heaviest, canonical, sortition tip, and
+/// Stacks tip) with each reward cycle's entry decoded, along with the affirmation maps of all
+/// anchor block-commits and any anchor block status overrides.  The maps are computed the same
+/// way the node computes them at its canonical sortition.
+///
+/// Arguments:
+///  - `argv`: Args in CLI format: `<command-name> [args...]`
+///  - `conf`: Optional config for running on non-mainnet chainstate
+pub fn command_affirmations(argv: &[String], conf: Option<&Config>) {
+    let print_help_and_exit = || -> ! {
+        let n = &argv[0];
+        eprintln!("Usage:");
+        eprintln!("  {n} <database-path>");
+        process::exit(1);
+    };
+    let db_path = argv.get(1).unwrap_or_else(|| print_help_and_exit());
+
+    let conf = conf.unwrap_or(&DEFAULT_MAINNET_CONFIG);
+    let chain_state_path = format!("{db_path}/chainstate/");
+    let sort_db_path = format!("{db_path}/burnchain/sortition");
+    let burn_db_path = format!("{db_path}/burnchain/burnchain.sqlite");
+    let headers_path = format!("{db_path}/headers.sqlite");
+
+    let burnchain = conf.get_burnchain();
+    let (_, network_id) = conf.burnchain.get_bitcoin_network();
+    let headers =
+        SpvClient::new_without_migration(&headers_path, 0, None, network_id, false, false)
+            .unwrap_or_else(|e| panic!("Failed to open {headers_path}: {e:?}"));
+    let burnchain_db = BurnchainDB::open(&burn_db_path, false)
+        .unwrap_or_else(|e| panic!("Failed to open {burn_db_path}: {e:?}"));
+    let sortdb = SortitionDB::open(&sort_db_path, false, burnchain.pox_constants.clone())
+        .unwrap_or_else(|e| panic!("Failed to open {sort_db_path}: {e:?}"));
+    let (chainstate, _) = StacksChainState::open(
+        conf.is_mainnet(),
+        conf.burnchain.chain_id,
+        &chain_state_path,
+        None,
+    )
+    .unwrap();
+
+    let tip = SortitionDB::get_canonical_burn_chain_tip(sortdb.conn()).unwrap();
+    let heaviest_am = static_get_heaviest_affirmation_map(
+        &burnchain,
+        &headers,
+        &burnchain_db,
+        &sortdb,
+        &tip.sortition_id,
+    )
+    .unwrap_or_else(|e| panic!("Failed to load heaviest affirmation map: {e:?}"));
+    let canonical_am = static_get_canonical_affirmation_map(
+        &burnchain,
+        &headers,
+        &burnchain_db,
+        &sortdb,
+        &chainstate,
+        &tip.sortition_id,
+    )
+    .unwrap_or_else(|e| panic!("Failed to load canonical affirmation map: {e:?}"));
+    let sortition_am = sortdb
+        .find_sortition_tip_affirmation_map(&tip.sortition_id)
+        .unwrap_or_else(|e| panic!("Failed to load sortition tip affirmation map: {e:?}"));
+    let stacks_am = static_get_stacks_tip_affirmation_map(
+        &burnchain_db,
+        &sortdb,
+        &tip.sortition_id,
+        &tip.canonical_stacks_tip_consensus_hash,
+        &tip.canonical_stacks_tip_hash,
+    )
+    .unwrap_or_else(|e| panic!("Failed to load Stacks tip affirmation map: {e:?}"));
+
+    let report = AffirmationsReport::new(
+        &burnchain,
+        &burnchain_db,
+        tip.block_height,
+        &heaviest_am,
+        &canonical_am,
+        &sortition_am,
+        &stacks_am,
+    )
+    .unwrap_or_else(|e| panic!("Failed to load anchor block-commits: {e:?}"));
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+}
+
 /// Fetch and process a `StagingBlock` from database and call `replay_block()` to validate
-fn replay_staging_block(db_path: &str, index_block_hash_hex: &str, conf: Option<&Config>) {
+fn replay_staging_block<T: BlockEventDispatcher>(
+    db_path: &str,
+    index_block_hash_hex: &str,
+    conf: Option<&Config>,
+    dispatcher_opt: Option<&T>,
+) -> Result<Option<StacksEpochReceipt>, ChainstateError> {
     let block_id = StacksBlockId::from_hex(index_block_hash_hex).unwrap();
     let chain_state_path = format!("{db_path}/chainstate/");
     let sort_db_path = format!("{db_path}/burnchain/sortition");
@@ -604,7 +2027,7 @@ fn replay_staging_block(db_path: &str, index_block_hash_hex: &str, conf: Option<
         StacksChainState::get_parent_header_info(&mut chainstate_tx, &next_staging_block).unwrap()
     else {
         println!("Failed to load parent head info for block: {index_block_hash_hex}");
-        return;
+        return Ok(None);
     };
 
     let block =
@@ -626,11 +2049,16 @@ fn replay_staging_block(db_path: &str, index_block_hash_hex: &str, conf: Option<
         &next_staging_block.anchored_block_hash,
         next_staging_block.commit_burn,
         next_staging_block.sortition_burn,
-    );
+        dispatcher_opt,
+    )
 }
 
 /// Process a mock mined block and call `replay_block()` to validate
-fn replay_mock_mined_block(db_path: &str, block: AssembledAnchorBlock, conf: Option<&Config>) {
+fn replay_mock_mined_block(
+    db_path: &str,
+    block: AssembledAnchorBlock,
+    conf: Option<&Config>,
+) -> Result<Option<StacksEpochReceipt>, ChainstateError> {
     let chain_state_path = format!("{db_path}/chainstate/");
     let sort_db_path = format!("{db_path}/burnchain/sortition");
     let burn_db_path = format!("{db_path}/burnchain/burnchain.sqlite");
@@ -682,7 +2110,7 @@ fn replay_mock_mined_block(db_path: &str, block: AssembledAnchorBlock, conf: Opt
     )
     .unwrap() else {
         println!("Failed to load parent head info for block: {block_hash}");
-        return;
+        return Ok(None);
     };
 
     replay_block(
@@ -701,11 +2129,13 @@ fn replay_mock_mined_block(db_path: &str, block: AssembledAnchorBlock, conf: Opt
         // I think the burn is used for miner rewards but not necessary for validation
         0,
         0,
-    );
+        None::<&DummyEventDispatcher>,
+    )
 }
 
-/// Validate a block against chainstate
-fn replay_block(
+/// Validate a block against chainstate, and announce it to `dispatcher_opt` if given.
+/// Returns the block's receipt, or `None` if the block could not be evaluated.
+fn replay_block<T: BlockEventDispatcher>(
     mut sort_tx: IndexDBTx<SortitionHandleContext, SortitionId>,
     mut chainstate_tx: ChainstateTx,
     clarity_instance: &mut ClarityInstance,
@@ -720,7 +2150,8 @@ fn replay_block(
     block_hash: &BlockHeaderHash,
     block_commit_burn: u64,
     block_sortition_burn: u64,
-) {
+    dispatcher_opt: Option<&T>,
+) -> Result<Option<StacksEpochReceipt>, ChainstateError> {
     let parent_block_header = match &parent_header_info.anchored_header {
         StacksBlockHeaderTypes::Epoch2(bh) => bh,
         StacksBlockHeaderTypes::Nakamoto(_) => panic!("Nakamoto blocks not supported yet"),
@@ -737,10 +2168,10 @@ fn replay_block(
     )
     .unwrap() else {
         println!("No microblock stream found for {block_id}");
-        return;
+        return Ok(None);
     };
 
-    let (burn_header_hash, burn_header_height, burn_header_timestamp, _winning_block_txid) =
+    let (burn_header_hash, burn_header_height, burn_header_timestamp, winning_block_txid) =
         match SortitionDB::get_block_snapshot_consensus(&sort_tx, block_consensus_hash).unwrap() {
             Some(sn) => (
                 sn.burn_header_hash,
@@ -768,7 +2199,7 @@ fn replay_block(
             &parent_header_info.consensus_hash
         );
         println!("{msg}");
-        return;
+        return Ok(None);
     }
 
     // validation check -- validate parent microblocks and find the ones that connect the
@@ -804,7 +2235,7 @@ fn replay_block(
 
     let pox_constants = sort_tx.context.pox_constants.clone();
 
-    match StacksChainState::append_block(
+    let (receipt, _, reward_set_data) = StacksChainState::append_block(
         &mut chainstate_tx,
         clarity_instance,
         &mut sort_tx,
@@ -821,19 +2252,40 @@ fn replay_block(
         block_sortition_burn,
         block_am.weight(),
         true,
-    ) {
-        Ok((_receipt, _, _)) => {
-            info!("Block processed successfully! block = {block_id}");
-        }
-        Err(e) => {
-            println!("Failed processing block! block = {block_id}, error = {e:?}");
-            process::exit(1);
-        }
-    };
+    )?;
+    info!("Block processed successfully! block = {block_id}");
+
+    if let Some(dispatcher) = dispatcher_opt {
+        dispatcher.announce_block(
+            &block.clone().into(),
+            &receipt.header,
+            &receipt.tx_receipts,
+            &parent_header_info.index_block_hash(),
+            winning_block_txid,
+            &receipt.matured_rewards,
+            receipt.matured_rewards_info.as_ref(),
+            receipt.parent_burn_block_hash.clone(),
+            receipt.parent_burn_block_height,
+            receipt.parent_burn_block_timestamp,
+            &receipt.anchored_block_cost,
+            &receipt.parent_microblocks_cost,
+            &pox_constants,
+            &reward_set_data,
+            &None,
+            None,
+            receipt.header.stacks_block_height,
+        );
+    }
+    Ok(Some(receipt))
 }
 
 /// Fetch and process a NakamotoBlock from database and call `replay_block_nakamoto()` to validate
-fn replay_naka_staging_block(db_path: &str, index_block_hash_hex: &str, conf: &Config) {
+fn replay_naka_staging_block<T: BlockEventDispatcher>(
+    db_path: &str,
+    index_block_hash_hex: &str,
+    conf: &Config,
+    dispatcher_opt: Option<&T>,
+) -> Result<Option<StacksEpochReceipt>, ChainstateError> {
     let block_id = StacksBlockId::from_hex(index_block_hash_hex).unwrap();
     let chain_state_path = format!("{db_path}/chainstate/");
     let sort_db_path = format!("{db_path}/burnchain/sortition");
@@ -862,18 +2314,26 @@ fn replay_naka_staging_block(db_path: &str, index_block_hash_hex: &str, conf: &C
 
     let (block, block_size) = chainstate
         .nakamoto_blocks_db()
-        .get_nakamoto_block(&block_id)
-        .unwrap()
-        .unwrap();
-    replay_block_nakamoto(&mut sortdb, &mut chainstate, &block, block_size).unwrap();
+        .get_nakamoto_block(&block_id)?
+        .ok_or(ChainstateError::NoSuchBlockError)?;
+    replay_block_nakamoto(
+        &mut sortdb,
+        &mut chainstate,
+        &block,
+        block_size,
+        dispatcher_opt,
+    )
 }
 
-fn replay_block_nakamoto(
+/// Validate a Nakamoto block against chainstate, and announce it to `dispatcher_opt` if given.
+/// Returns the block's receipt, or `None` if the block could not be evaluated.
+fn replay_block_nakamoto<T: BlockEventDispatcher>(
     sort_db: &mut SortitionDB,
     stacks_chain_state: &mut StacksChainState,
     block: &NakamotoBlock,
     block_size: u64,
-) -> Result<(), ChainstateError> {
+    dispatcher_opt: Option<&T>,
+) -> Result<Option<StacksEpochReceipt>, ChainstateError> {
     // find corresponding snapshot
     let next_ready_block_snapshot =
         SortitionDB::get_block_snapshot_consensus(sort_db.conn(), &block.header.consensus_hash)?
@@ -922,6 +2382,7 @@ fn replay_block_nakamoto(
             );
             ChainstateError::NoSuchBlockError
         })?;
+    let chainstate_config = stacks_chain_state.config();
     let (mut chainstate_tx, clarity_instance) = stacks_chain_state.chainstate_tx_begin()?;
 
     // find parent header
@@ -935,7 +2396,7 @@ fn replay_block_nakamoto(
                "stacks_block_id" => %block.header.block_id(),
                "parent_block_id" => %block.header.parent_block_id
         );
-        return Ok(());
+        return Ok(None);
     };
 
     // sanity check -- must attach to parent
@@ -1041,7 +2502,7 @@ fn replay_block_nakamoto(
             "stacks_block_id" => %block.header.block_id(),
             "burn_view_consensus_hash" => %burnchain_view,
         );
-        return Ok(());
+        return Ok(None);
     };
 
     // find commit and sortition burns if this is a tenure-start block
@@ -1127,7 +2588,47 @@ fn replay_block_nakamoto(
         return Err(e);
     };
 
-    Ok(())
+    let Some((receipt, clarity_commit, reward_set_data, phantom_unlock_events)) = ok_opt else {
+        return Ok(None);
+    };
+    // the replayed block is never committed
+    drop(clarity_commit);
+
+    if let Some(dispatcher) = dispatcher_opt {
+        let mut tx_receipts = receipt.tx_receipts.clone();
+        if let Some(unlock_receipt) = NakamotoChainState::generate_phantom_unlock_tx(
+            phantom_unlock_events,
+            &chainstate_config,
+            block.header.chain_length,
+        ) {
+            tx_receipts.push(unlock_receipt);
+        }
+        dispatcher.announce_raw_nakamoto_block(block);
+        dispatcher.announce_block(
+            &(
+                block.clone(),
+                parent_header_info.anchored_header.block_hash(),
+            )
+                .into(),
+            &receipt.header,
+            &tx_receipts,
+            &parent_block_id,
+            next_ready_block_snapshot.winning_block_txid.clone(),
+            &receipt.matured_rewards,
+            receipt.matured_rewards_info.as_ref(),
+            receipt.parent_burn_block_hash.clone(),
+            receipt.parent_burn_block_height,
+            receipt.parent_burn_block_timestamp,
+            &receipt.anchored_block_cost,
+            &receipt.parent_microblocks_cost,
+            &pox_constants,
+            &reward_set_data,
+            &Some(block.header.pox_treatment.clone()),
+            Some(block.header.timestamp),
+            receipt.coinbase_height,
+        );
+    }
+    Ok(Some(receipt))
 }
 
 #[cfg(test)]
@@ -1161,4 +2662,110 @@ pub mod test {
         assert_eq!(argv, argv_expected);
         assert!(opts.config.is_some());
     }
+
+    #[test]
+    pub fn test_diff_replayed_blocks() {
+        let tx = ReplayedTxRecord {
+            txid: Txid([0x01; 32]),
+            tx_index: 0,
+            result: "(ok true)".into(),
+            vm_error: None,
+            post_condition_aborted: false,
+            stx_burned: 0,
+            execution_cost: ExecutionCost::ZERO,
+            events: vec![],
+        };
+        let expected = ReplayedBlockRecord {
+            block_id: StacksBlockId([0x02; 32]),
+            height: 10,
+            cost: ExecutionCost::ZERO,
+            txs: vec![tx.clone()],
+        };
+        assert!(diff_replayed_blocks(&expected, &expected).is_empty());
+
+        let mut actual = expected.clone();
+        actual.cost.runtime = 100;
+        actual.txs[0].result = "(err u1)".into();
+        actual.txs.push(tx);
+
+        let diffs = diff_replayed_blocks(&expected, &actual);
+        let fields: Vec<_> = diffs.iter().map(|diff| diff.field.as_str()).collect();
+        assert_eq!(fields, vec!["cost", "txs.len", "txs[0].result"]);
+        assert_eq!(diffs[2].expected, serde_json::json!("(ok true)"));
+        assert_eq!(diffs[2].actual, serde_json::json!("(err u1)"));
+        assert!(diffs.iter().all(|diff| diff.height == 10));
+    }
+
+    #[test]
+    pub fn test_classify_replayable_tx() {
+        let origin_auth = TransactionAuth::from_p2pkh(&StacksPrivateKey::from_seed(&[1])).unwrap();
+        let sponsor_auth = TransactionAuth::from_p2pkh(&StacksPrivateKey::from_seed(&[2])).unwrap();
+        let transfer = TransactionPayload::TokenTransfer(
+            StacksAddress::burn_address(true).into(),
+            100,
+            TokenTransferMemo([0; 34]),
+        );
+
+        let mut tx = StacksTransaction::new(
+            TransactionVersion::Mainnet,
+            origin_auth.clone(),
+            transfer.clone(),
+        );
+        tx.set_origin_nonce(5);
+        let origin = tx.origin_address().to_string();
+
+        assert_eq!(
+            classify_replayable_tx(&tx, 5, None, false),
+            Replayability::Replayable
+        );
+        assert_eq!(
+            classify_replayable_tx(&tx, 6, None, false),
+            Replayability::NonceConflict {
+                account: origin.clone(),
+                next_nonce: 6
+            }
+        );
+        assert_eq!(
+            classify_replayable_tx(&tx, 3, None, false),
+            Replayability::AwaitingEarlierNonces {
+                account: origin,
+                next_nonce: 3
+            }
+        );
+        assert_eq!(
+            classify_replayable_tx(&tx, 5, None, true),
+            Replayability::ContractExists
+        );
+
+        // a sponsor's used nonce conflicts too
+        let mut sponsored_tx = StacksTransaction::new(
+            TransactionVersion::Mainnet,
+            origin_auth.into_sponsored(sponsor_auth).unwrap(),
+            transfer,
+        );
+        sponsored_tx.set_origin_nonce(5);
+        sponsored_tx.set_sponsor_nonce(2).unwrap();
+        assert_eq!(
+            classify_replayable_tx(&sponsored_tx, 5, Some(2), false),
+            Replayability::Replayable
+        );
+        assert_eq!(
+            classify_replayable_tx(&sponsored_tx, 5, Some(3), false),
+            Replayability::NonceConflict {
+                account: sponsored_tx.sponsor_address().unwrap().to_string(),
+                next_nonce: 3
+            }
+        );
+
+        // coinbases never replay, whatever the nonces
+        let coinbase = StacksTransaction::new(
+            TransactionVersion::Mainnet,
+            TransactionAuth::from_p2pkh(&StacksPrivateKey::from_seed(&[1])).unwrap(),
+            TransactionPayload::Coinbase(CoinbasePayload([0; 32]), None, None),
+        );
+        assert_eq!(
+            classify_replayable_tx(&coinbase, 0, None, false),
+            Replayability::TenureBound
+        );
+    }
 }
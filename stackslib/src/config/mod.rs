@@ -18,11 +18,11 @@ pub mod chain_data;
 
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::net::{Ipv4Addr, SocketAddr, ToSocketAddrs};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::{Arc, LazyLock, Mutex};
 use std::time::Duration;
-use std::{cmp, fs, thread};
+use std::{cmp, fmt, fs, thread};
 
 use clarity::vm::costs::ExecutionCost;
 use clarity::vm::types::{AssetIdentifier, PrincipalData, QualifiedContractIdentifier};
@@ -32,20 +32,26 @@ use stacks_common::consts::SIGNER_SLOTS_PER_USER;
 use stacks_common::types::chainstate::StacksAddress;
 use stacks_common::types::net::PeerAddress;
 use stacks_common::types::Address;
-use stacks_common::util::get_epoch_time_ms;
 use stacks_common::util::hash::hex_bytes;
 use stacks_common::util::secp256k1::{Secp256k1PrivateKey, Secp256k1PublicKey};
+use stacks_common::util::{get_epoch_time_ms, get_epoch_time_secs};
 
 use crate::burnchains::affirmation::AffirmationMap;
+use crate::burnchains::bitcoin::address::{BitcoinAddress, LegacyBitcoinAddressType};
 use crate::burnchains::bitcoin::BitcoinNetworkType;
+use crate::burnchains::db::AnchorBlockOverrideStatus;
 use crate::burnchains::{Burnchain, MagicBytes, PoxConstants, BLOCKSTACK_MAGIC_MAINNET};
+use crate::chainstate::coordinator::{InjectedRewardSets, RewardSetSource};
 use crate::chainstate::nakamoto::signer_set::NakamotoSigners;
-use crate::chainstate::stacks::boot::MINERS_NAME;
+use crate::chainstate::stacks::boot::{CHECKPOINTS_NAME, MINERS_NAME};
+use crate::chainstate::stacks::db::receipts::TransactionReceiptDB;
+use crate::chainstate::stacks::db::state_diffs::StateDiffDB;
 use crate::chainstate::stacks::index::marf::MARFOpenOpts;
 use crate::chainstate::stacks::index::storage::TrieHashCalculationMode;
 use crate::chainstate::stacks::miner::{BlockBuilderSettings, MinerStatus};
 use crate::chainstate::stacks::MAX_BLOCK_LEN;
 use crate::config::chain_data::MinerStats;
+use crate::core::admission::ContractRulesPolicy;
 use crate::core::mempool::{MemPoolWalkSettings, MemPoolWalkTxTypes};
 use crate::core::{
     MemPoolDB, StacksEpoch, StacksEpochExtension, StacksEpochId,
@@ -53,14 +59,20 @@ use crate::core::{
     BITCOIN_TESTNET_STACKS_25_REORGED_HEIGHT, CHAIN_ID_MAINNET, CHAIN_ID_TESTNET,
     PEER_VERSION_MAINNET, PEER_VERSION_TESTNET, STACKS_EPOCHS_REGTEST, STACKS_EPOCHS_TESTNET,
 };
+use crate::cost_estimates::contract_costs::ContractCostDB;
 use crate::cost_estimates::fee_medians::WeightedMedianFeeRateEstimator;
 use crate::cost_estimates::fee_rate_fuzzer::FeeRateFuzzer;
 use crate::cost_estimates::fee_scalar::ScalarFeeRateEstimator;
 use crate::cost_estimates::metrics::{CostMetric, ProportionalDotProduct, UnitMetric};
 use crate::cost_estimates::{CostEstimator, FeeEstimator, PessimisticEstimator, UnitEstimator};
+use crate::monitoring::chain_quality::ChainQualityThresholds;
 use crate::net::atlas::AtlasConfig;
 use crate::net::connection::{ConnectionOptions, DEFAULT_BLOCK_PROPOSAL_MAX_AGE_SECS};
-use crate::net::{Neighbor, NeighborAddress, NeighborKey};
+use crate::net::httpcore::{AdminAuth, AdminRole, AdminToken, HttpCorsPolicy, HttpRateLimitConfig};
+use crate::net::nat::NatProtocol;
+use crate::net::neighbors::seeds::{parse_operator_key, SignedSeedList};
+use crate::net::tls::{TlsPeerConfig, TlsServerConfig};
+use crate::net::{Neighbor, NeighborAddress, NeighborKey, NodeConfigReloader};
 use crate::types::chainstate::BurnchainHeaderHash;
 use crate::types::EpochList;
 use crate::util::hash::to_hex;
@@ -105,6 +117,9 @@ const DEFAULT_FIRST_REJECTION_PAUSE_MS: u64 = 5_000;
 /// Default time in milliseconds to pause after receiving subsequent threshold
 /// rejections, before proposing a new block.
 const DEFAULT_SUBSEQUENT_REJECTION_PAUSE_MS: u64 = 10_000;
+/// Default number of times in a row the miner reproposes a block without pausing,
+/// after signers reject its proposal because of specific transactions.
+const DEFAULT_MAX_REPROPOSALS: u32 = 3;
 /// Default time in milliseconds to wait for a Nakamoto block after seeing a
 /// burnchain block before submitting a block commit.
 const DEFAULT_BLOCK_COMMIT_DELAY_MS: u64 = 20_000;
@@ -121,6 +136,20 @@ const DEFAULT_TENURE_TIMEOUT_SECS: u64 = 180;
 /// Default percentage of block budget that must be used before attempting a
 /// time-based tenure extend
 const DEFAULT_TENURE_EXTEND_COST_THRESHOLD: u64 = 50;
+/// Default minimum number of UTXOs the miner must hold before it consolidates them at a low
+/// fee rate
+const DEFAULT_UTXO_CONSOLIDATION_MIN_COUNT: u64 = 10;
+/// Default time for which a redundant miner holds the miner lease after its last block-commit
+const DEFAULT_FAILOVER_LEASE_TTL_SECS: u64 = 1800;
+/// Default lowest amount (in sats) the burn fee auto-bidder will burn in a block-commit
+const DEFAULT_COMMIT_BID_MIN_SATS: u64 = 5_000;
+/// Default number of recent sortitions the burn fee auto-bidder considers
+const DEFAULT_COMMIT_BID_WINDOW: u64 = 6;
+/// Default share of the total burn, in percent, that the burn fee auto-bidder aims for
+const DEFAULT_COMMIT_BID_TARGET_WIN_PCT: u8 = 25;
+/// Default time before, and between, the miner's checks of the staging DB and burnchain tip
+/// while it waits for signatures on a block proposal
+const DEFAULT_SIGNATURE_POLL_INTERVAL_MS: u64 = 500;
 
 static HELIUM_DEFAULT_CONNECTION_OPTIONS: LazyLock<ConnectionOptions> =
     LazyLock::new(|| ConnectionOptions {
@@ -159,34 +188,87 @@ pub static DEFAULT_MAINNET_CONFIG: LazyLock<Config> = LazyLock::new(|| {
         .expect("Failed to create default mainnet config")
 });
 
+/// The config file table of named profiles
+const CONFIG_PROFILES_KEY: &str = "profile";
+/// The key naming the profile that a profile inherits from
+const CONFIG_PROFILE_INHERITS_KEY: &str = "inherits";
+
+/// Merge `overrides` into `base`.  Tables are merged key by key; any other value replaces the
+/// value in `base`.
+fn merge_toml_tables(base: &mut toml::value::Table, overrides: toml::value::Table) {
+    for (key, value) in overrides {
+        let toml::Value::Table(override_table) = value else {
+            base.insert(key, value);
+            continue;
+        };
+        if let Some(toml::Value::Table(base_table)) = base.get_mut(&key) {
+            merge_toml_tables(base_table, override_table);
+            continue;
+        }
+        base.insert(key, toml::Value::Table(override_table));
+    }
+}
+
 #[derive(Clone, Deserialize, Default, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct ConfigFile {
-    pub __path: Option<String>, // Only used for config file reloads
+    pub __path: Option<String>,    // Only used for config file reloads
+    pub __profile: Option<String>, // Only used for config file reloads
     pub burnchain: Option<BurnchainConfigFile>,
     pub node: Option<NodeConfigFile>,
     pub ustx_balance: Option<Vec<InitialBalanceFile>>,
     /// Deprecated: use `ustx_balance` instead
     pub mstx_balance: Option<Vec<InitialBalanceFile>>,
     pub events_observer: Option<HashSet<EventObserverConfigFile>>,
+    pub webhook: Option<Vec<WebhookConfigFile>>,
+    pub stackerdb_standby: Option<Vec<StackerDBStandbyConfigFile>>,
     pub connection_options: Option<ConnectionOptionsFile>,
     pub fee_estimation: Option<FeeEstimationConfigFile>,
     pub miner: Option<MinerConfigFile>,
     pub atlas: Option<AtlasConfigFile>,
+    pub dns_seeds: Option<DNSSeedsConfigFile>,
+    pub mempool_admission: Option<MempoolAdmissionConfigFile>,
 }
 
 impl ConfigFile {
     pub fn from_path(path: &str) -> Result<ConfigFile, String> {
+        Self::from_path_with_profile(path, None)
+    }
+
+    /// Load the config file at `path`, applying the overrides of `profile` if given.  See
+    /// `ConfigFile::from_str_with_profile`.
+    pub fn from_path_with_profile(path: &str, profile: Option<&str>) -> Result<ConfigFile, String> {
         let content = fs::read_to_string(path).map_err(|e| format!("Invalid path: {e}"))?;
-        let mut f = Self::from_str(&content)?;
+        let mut f = Self::from_str_with_profile(&content, profile)?;
         f.__path = Some(path.to_string());
+        f.__profile = profile.map(String::from);
         Ok(f)
     }
 
     #[allow(clippy::should_implement_trait)]
     pub fn from_str(content: &str) -> Result<ConfigFile, String> {
-        let mut config: ConfigFile =
-            toml::from_str(content).map_err(|e| format!("Invalid toml: {e}"))?;
+        Self::from_str_with_profile(content, None)
+    }
+
+    /// Parse a config file.  Besides the top-level settings, a config file can define named
+    /// profiles (e.g. `[profile.mainnet.node]`, `[profile.testnet.burnchain]`), so that one file
+    /// can configure nodes on several networks.  A profile overrides the top-level settings, and
+    /// can inherit the overrides of another profile with `inherits = "<name>"`.
+    ///
+    /// If `profile` is given, its overrides (and those of the profiles it inherits from) are
+    /// merged into the top-level settings: tables are merged key by key, and any other value,
+    /// including an array such as `[[events_observer]]`, replaces the inherited value.
+    /// Otherwise, only the top-level settings are used.
+    pub fn from_str_with_profile(
+        content: &str,
+        profile: Option<&str>,
+    ) -> Result<ConfigFile, String> {
+        let mut config: ConfigFile = match Self::resolve_profile(content, profile)? {
+            Some(table) => toml::Value::Table(table)
+                .try_into()
+                .map_err(|e| format!("Invalid toml: {e}"))?,
+            None => toml::from_str(content).map_err(|e| format!("Invalid toml: {e}"))?,
+        };
         if let Some(mstx_balance) = config.mstx_balance.take() {
             warn!("'mstx_balance' in the config is deprecated; please use 'ustx_balance' instead.");
             match config.ustx_balance {
@@ -201,6 +283,60 @@ impl ConfigFile {
         Ok(config)
     }
 
+    /// Merge the overrides of `profile` into the top-level settings of `content`.  Returns None
+    /// if `content` defines no profiles and no profile is selected, in which case `content` can
+    /// be used as is.
+    fn resolve_profile(
+        content: &str,
+        profile: Option<&str>,
+    ) -> Result<Option<toml::value::Table>, String> {
+        let mut table: toml::value::Table =
+            toml::from_str(content).map_err(|e| format!("Invalid toml: {e}"))?;
+        let profiles = match table.remove(CONFIG_PROFILES_KEY) {
+            Some(toml::Value::Table(profiles)) => profiles,
+            Some(_) => {
+                return Err(format!(
+                    "Invalid toml: `{CONFIG_PROFILES_KEY}` must be a table of profiles"
+                ))
+            }
+            None if profile.is_none() => return Ok(None),
+            None => toml::value::Table::new(),
+        };
+        let Some(profile) = profile else {
+            return Ok(Some(table));
+        };
+
+        // the selected profile, followed by the profiles it inherits from
+        let mut lineage: Vec<(String, toml::value::Table)> = vec![];
+        let mut next = Some(profile.to_string());
+        while let Some(name) = next.take() {
+            if lineage.iter().any(|(ancestor, _)| *ancestor == name) {
+                return Err(format!(
+                    "Invalid profile `{profile}`: profile `{name}` inherits from itself"
+                ));
+            }
+            let mut overrides = match profiles.get(&name) {
+                Some(toml::Value::Table(overrides)) => overrides.clone(),
+                Some(_) => return Err(format!("Invalid profile `{name}`: expected a table")),
+                None => return Err(format!("No such profile: `{name}`")),
+            };
+            next = match overrides.remove(CONFIG_PROFILE_INHERITS_KEY) {
+                Some(toml::Value::String(parent)) => Some(parent),
+                Some(_) => {
+                    return Err(format!(
+                        "Invalid profile `{name}`: `{CONFIG_PROFILE_INHERITS_KEY}` must be a profile name"
+                    ))
+                }
+                None => None,
+            };
+            lineage.push((name, overrides));
+        }
+        for (_, overrides) in lineage.into_iter().rev() {
+            merge_toml_tables(&mut table, overrides);
+        }
+        Ok(Some(table))
+    }
+
     pub fn xenon() -> ConfigFile {
         let mut burnchain = BurnchainConfigFile {
             mode: Some("xenon".to_string()),
@@ -366,14 +502,50 @@ impl ConfigFile {
 #[derive(Clone, Debug)]
 pub struct Config {
     pub config_path: Option<String>,
+    /// The config file profile that was applied, if any
+    pub config_profile: Option<String>,
     pub burnchain: BurnchainConfig,
     pub node: NodeConfig,
     pub initial_balances: Vec<InitialBalance>,
     pub events_observers: HashSet<EventObserverConfig>,
+    pub webhooks: Vec<WebhookConfig>,
+    pub stackerdb_standbys: Vec<StackerDBStandbyConfig>,
     pub connection_options: ConnectionOptions,
     pub miner: MinerConfig,
     pub estimation: FeeEstimationConfig,
     pub atlas: AtlasConfig,
+    /// Rules for the mempool admission policy
+    pub mempool_admission: ContractRulesPolicy,
+}
+
+/// Re-reads the node's config file for the `/v3/admin/config/reload` endpoint
+#[derive(Clone, Debug)]
+pub struct ConfigFileReloader {
+    /// Path to the config file, if the node was started from one
+    config_path: Option<String>,
+    /// The config file profile that was applied, if any
+    config_profile: Option<String>,
+}
+
+impl ConfigFileReloader {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            config_path: config.config_path.clone(),
+            config_profile: config.config_profile.clone(),
+        }
+    }
+}
+
+impl NodeConfigReloader for ConfigFileReloader {
+    fn reload_connection_options(&self) -> Result<ConnectionOptions, String> {
+        let Some(path) = &self.config_path else {
+            return Err("The node was not started from a config file".into());
+        };
+        let config_file =
+            ConfigFile::from_path_with_profile(path.as_str(), self.config_profile.as_deref())?;
+        let config = Config::from_config_file(config_file, false)?;
+        Ok(config.connection_options)
+    }
 }
 
 impl Config {
@@ -383,7 +555,9 @@ impl Config {
         let Some(path) = &self.config_path else {
             return self.burnchain.clone();
         };
-        let Ok(config_file) = ConfigFile::from_path(path.as_str()) else {
+        let Ok(config_file) =
+            ConfigFile::from_path_with_profile(path.as_str(), self.config_profile.as_deref())
+        else {
             return self.burnchain.clone();
         };
         let Ok(config) = Config::from_config_file(config_file, false) else {
@@ -398,7 +572,9 @@ impl Config {
         let Some(path) = &self.config_path else {
             return self.miner.clone();
         };
-        let Ok(config_file) = ConfigFile::from_path(path.as_str()) else {
+        let Ok(config_file) =
+            ConfigFile::from_path_with_profile(path.as_str(), self.config_profile.as_deref())
+        else {
             return self.miner.clone();
         };
         let Ok(config) = Config::from_config_file(config_file, false) else {
@@ -411,7 +587,9 @@ impl Config {
         let Some(path) = &self.config_path else {
             return self.node.clone();
         };
-        let Ok(config_file) = ConfigFile::from_path(path.as_str()) else {
+        let Ok(config_file) =
+            ConfigFile::from_path_with_profile(path.as_str(), self.config_profile.as_deref())
+        else {
             return self.node.clone();
         };
         let Ok(config) = Config::from_config_file(config_file, resolve_bootstrap_nodes) else {
@@ -488,42 +666,64 @@ impl Config {
             }
 
             if let Some(epoch) = epochs.get(StacksEpochId::Epoch21) {
-                // Override v1_unlock_height to the start_height of epoch2.1
+                // Override v1_unlock_height to the start_height of epoch2.1, unless configured
+                let v1_unlock_height = self
+                    .burnchain
+                    .get_epoch_pox(StacksEpochId::Epoch21)
+                    .unlock_height
+                    .unwrap_or(epoch.start_height as u32 + 1);
                 debug!(
-                    "Override v2_unlock_height from {} to {}",
-                    burnchain.pox_constants.v1_unlock_height,
-                    epoch.start_height + 1
+                    "Override v1_unlock_height from {} to {v1_unlock_height}",
+                    burnchain.pox_constants.v1_unlock_height
                 );
-                burnchain.pox_constants.v1_unlock_height = epoch.start_height as u32 + 1;
+                burnchain.pox_constants.v1_unlock_height = v1_unlock_height;
             }
 
             if let Some(epoch) = epochs.get(StacksEpochId::Epoch22) {
-                // Override v2_unlock_height to the start_height of epoch2.2
+                // Override v2_unlock_height to the start_height of epoch2.2, unless configured
+                let v2_unlock_height = self
+                    .burnchain
+                    .get_epoch_pox(StacksEpochId::Epoch22)
+                    .unlock_height
+                    .unwrap_or(epoch.start_height as u32 + 1);
                 debug!(
-                    "Override v2_unlock_height from {} to {}",
-                    burnchain.pox_constants.v2_unlock_height,
-                    epoch.start_height + 1
+                    "Override v2_unlock_height from {} to {v2_unlock_height}",
+                    burnchain.pox_constants.v2_unlock_height
                 );
-                burnchain.pox_constants.v2_unlock_height = epoch.start_height as u32 + 1;
+                burnchain.pox_constants.v2_unlock_height = v2_unlock_height;
             }
 
             if let Some(epoch) = epochs.get(StacksEpochId::Epoch24) {
-                // Override pox_3_activation_height to the start_height of epoch2.4
+                // Override pox_3_activation_height to the start_height of epoch2.4, unless
+                // configured
+                let pox_3_activation_height = self
+                    .burnchain
+                    .get_epoch_pox(StacksEpochId::Epoch24)
+                    .activation_height
+                    .unwrap_or(epoch.start_height as u32);
                 debug!(
-                    "Override pox_3_activation_height from {} to {}",
-                    burnchain.pox_constants.pox_3_activation_height, epoch.start_height
+                    "Override pox_3_activation_height from {} to {pox_3_activation_height}",
+                    burnchain.pox_constants.pox_3_activation_height
                 );
-                burnchain.pox_constants.pox_3_activation_height = epoch.start_height as u32;
+                burnchain.pox_constants.pox_3_activation_height = pox_3_activation_height;
             }
 
             if let Some(epoch) = epochs.get(StacksEpochId::Epoch25) {
-                // Override pox_4_activation_height to the start_height of epoch2.5
+                // Override pox_4_activation_height to the start_height of epoch2.5, unless
+                // configured
+                let epoch_pox = self.burnchain.get_epoch_pox(StacksEpochId::Epoch25);
+                let pox_4_activation_height = epoch_pox
+                    .activation_height
+                    .unwrap_or(epoch.start_height as u32);
+                let v3_unlock_height = epoch_pox
+                    .unlock_height
+                    .unwrap_or(epoch.start_height as u32 + 1);
                 debug!(
-                    "Override pox_4_activation_height from {} to {}",
-                    burnchain.pox_constants.pox_4_activation_height, epoch.start_height
+                    "Override pox_4_activation_height from {} to {pox_4_activation_height}",
+                    burnchain.pox_constants.pox_4_activation_height
                 );
-                burnchain.pox_constants.pox_4_activation_height = epoch.start_height as u32;
-                burnchain.pox_constants.v3_unlock_height = epoch.start_height as u32 + 1;
+                burnchain.pox_constants.pox_4_activation_height = pox_4_activation_height;
+                burnchain.pox_constants.v3_unlock_height = v3_unlock_height;
             }
         }
 
@@ -588,13 +788,32 @@ impl Config {
             .make_cost_metric()
             .unwrap_or_else(|| Box::new(UnitMetric));
 
-        MemPoolDB::open(
+        let mempool = MemPoolDB::open(
             self.is_mainnet(),
             self.burnchain.chain_id,
             &self.get_chainstate_path_str(),
             cost_estimator,
             metric,
-        )
+        )?;
+        Ok(self.with_mempool_admission_policy(mempool))
+    }
+
+    /// Install the configured mempool admission policy, if there are any rules
+    #[cfg(feature = "mempool-admission-policy")]
+    fn with_mempool_admission_policy(&self, mut mempool: MemPoolDB) -> MemPoolDB {
+        if !self.mempool_admission.is_empty() {
+            mempool.set_admission_policy(Box::new(self.mempool_admission.clone()));
+        }
+        mempool
+    }
+
+    /// Mempool admission policies need the `mempool-admission-policy` feature
+    #[cfg(not(feature = "mempool-admission-policy"))]
+    fn with_mempool_admission_policy(&self, mempool: MemPoolDB) -> MemPoolDB {
+        if !self.mempool_admission.is_empty() {
+            warn!("Ignoring [mempool_admission] rules: this node was built without the `mempool-admission-policy` feature");
+        }
+        mempool
     }
 
     /// Load up a Burnchain and apply config settings to it.
@@ -670,30 +889,7 @@ impl Config {
         }?;
         let mut matched_epochs = vec![];
         for configured_epoch in conf_epochs.iter() {
-            let epoch_name = &configured_epoch.epoch_name;
-            let epoch_id = if epoch_name == EPOCH_CONFIG_1_0_0 {
-                Ok(StacksEpochId::Epoch10)
-            } else if epoch_name == EPOCH_CONFIG_2_0_0 {
-                Ok(StacksEpochId::Epoch20)
-            } else if epoch_name == EPOCH_CONFIG_2_0_5 {
-                Ok(StacksEpochId::Epoch2_05)
-            } else if epoch_name == EPOCH_CONFIG_2_1_0 {
-                Ok(StacksEpochId::Epoch21)
-            } else if epoch_name == EPOCH_CONFIG_2_2_0 {
-                Ok(StacksEpochId::Epoch22)
-            } else if epoch_name == EPOCH_CONFIG_2_3_0 {
-                Ok(StacksEpochId::Epoch23)
-            } else if epoch_name == EPOCH_CONFIG_2_4_0 {
-                Ok(StacksEpochId::Epoch24)
-            } else if epoch_name == EPOCH_CONFIG_2_5_0 {
-                Ok(StacksEpochId::Epoch25)
-            } else if epoch_name == EPOCH_CONFIG_3_0_0 {
-                Ok(StacksEpochId::Epoch30)
-            } else if epoch_name == EPOCH_CONFIG_3_1_0 {
-                Ok(StacksEpochId::Epoch31)
-            } else {
-                Err(format!("Unknown epoch name specified: {epoch_name}"))
-            }?;
+            let epoch_id = configured_epoch.epoch_id()?;
             matched_epochs.push((epoch_id, configured_epoch.start_height));
         }
 
@@ -765,6 +961,18 @@ impl Config {
             }
         }
 
+        for configured_epoch in conf_epochs.iter() {
+            let Some(block_limit) = configured_epoch.block_limit.as_ref() else {
+                continue;
+            };
+            let epoch_id = configured_epoch.epoch_id()?;
+            let epoch = out_epochs
+                .iter_mut()
+                .find(|e| e.epoch_id == epoch_id)
+                .ok_or_else(|| format!("Epoch {epoch_id} is not configured"))?;
+            block_limit.apply_to(&mut epoch.block_limit);
+        }
+
         if let Some(pox_2_activation) = pox_2_activation {
             let last_epoch = out_epochs
                 .iter()
@@ -778,6 +986,66 @@ impl Config {
         Ok(EpochList::new(&out_epochs))
     }
 
+    /// Gather and check the PoX heights configured alongside the epochs in `conf_epochs`.
+    /// `epochs` is the epoch list made from `conf_epochs` by `make_epochs()`.
+    fn make_epoch_pox(
+        conf_epochs: &[StacksEpochConfigFile],
+        epochs: &EpochList<ExecutionCost>,
+    ) -> Result<HashMap<StacksEpochId, EpochPoxConfig>, String> {
+        let mut epoch_pox = HashMap::new();
+        for configured_epoch in conf_epochs.iter() {
+            let epoch_pox_config = EpochPoxConfig {
+                activation_height: configured_epoch.pox_activation_height,
+                unlock_height: configured_epoch.pox_unlock_height,
+            };
+            if epoch_pox_config == EpochPoxConfig::default() {
+                continue;
+            }
+            let epoch_id = configured_epoch.epoch_id()?;
+            let epoch = epochs
+                .get(epoch_id)
+                .ok_or_else(|| format!("Epoch {epoch_id} is not configured"))?;
+
+            if let Some(activation_height) = epoch_pox_config.activation_height {
+                if !matches!(epoch_id, StacksEpochId::Epoch24 | StacksEpochId::Epoch25) {
+                    return Err(format!(
+                        "pox_activation_height can only be set for epochs 2.4 and 2.5, not {epoch_id}"
+                    ));
+                }
+                let activation_height = u64::from(activation_height);
+                if activation_height < epoch.start_height || activation_height >= epoch.end_height {
+                    return Err(format!(
+                        "Epoch {epoch_id} pox_activation_height must be within the epoch. pox_activation_height = {activation_height}, epoch start height = {}, epoch end height = {}",
+                        epoch.start_height, epoch.end_height
+                    ));
+                }
+            }
+
+            if let Some(unlock_height) = epoch_pox_config.unlock_height {
+                if !matches!(
+                    epoch_id,
+                    StacksEpochId::Epoch21 | StacksEpochId::Epoch22 | StacksEpochId::Epoch25
+                ) {
+                    return Err(format!(
+                        "pox_unlock_height can only be set for epochs 2.1, 2.2, and 2.5, not {epoch_id}"
+                    ));
+                }
+                let activation_height = epoch_pox_config
+                    .activation_height
+                    .map(u64::from)
+                    .unwrap_or(epoch.start_height);
+                if u64::from(unlock_height) <= activation_height {
+                    return Err(format!(
+                        "Epoch {epoch_id} pox_unlock_height must be after the PoX activation height. pox_unlock_height = {unlock_height}, activation height = {activation_height}"
+                    ));
+                }
+            }
+
+            epoch_pox.insert(epoch_id, epoch_pox_config);
+        }
+        Ok(epoch_pox)
+    }
+
     pub fn from_config_file(
         config_file: ConfigFile,
         resolve_bootstrap_nodes: bool,
@@ -859,11 +1127,19 @@ impl Config {
         if is_mainnet && node.use_test_genesis_chainstate == Some(true) {
             return Err("Attempted to run mainnet node with `use_test_genesis_chainstate`".into());
         }
+        if is_mainnet && !node.reward_sets.source().is_on_chain() {
+            return Err(
+                "Attempted to run mainnet node with an off-chain `reward_set_source`".into(),
+            );
+        }
 
         if node.stacker || node.miner {
             node.add_miner_stackerdb(is_mainnet);
             node.add_signers_stackerdbs(is_mainnet);
         }
+        if node.stacker || node.miner || node.checkpoint_hints {
+            node.add_checkpoints_stackerdb(is_mainnet);
+        }
 
         let miner = match config_file.miner {
             Some(mut miner) => {
@@ -929,10 +1205,30 @@ impl Config {
             });
         };
 
-        let connection_options = match config_file.connection_options {
+        let webhooks = config_file
+            .webhook
+            .unwrap_or_default()
+            .into_iter()
+            .map(WebhookConfigFile::into_config)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let stackerdb_standbys = config_file
+            .stackerdb_standby
+            .unwrap_or_default()
+            .into_iter()
+            .map(StackerDBStandbyConfigFile::into_config)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut connection_options = match config_file.connection_options {
             Some(opts) => opts.into_config(is_mainnet)?,
             None => HELIUM_DEFAULT_CONNECTION_OPTIONS.clone(),
         };
+        node.load_tls_config(&mut connection_options)?;
+        node.load_extra_p2p_addresses(&mut connection_options);
+        connection_options.archival = node.archival;
+        if let Some(dns_seeds) = config_file.dns_seeds.as_ref() {
+            dns_seeds.load_into(&mut connection_options)?;
+        }
 
         let estimation = match config_file.fee_estimation {
             Some(f) => FeeEstimationConfig::from(f),
@@ -948,20 +1244,47 @@ impl Config {
             .validate()
             .map_err(|e| format!("Atlas config error: {e}"))?;
 
+        let mempool_admission = match config_file.mempool_admission {
+            Some(f) => f.into_config()?,
+            None => ContractRulesPolicy::default(),
+        };
+
         if miner.mining_key.is_none() && miner.pre_nakamoto_mock_signing {
             return Err("Cannot use pre_nakamoto_mock_signing without a mining_key".to_string());
         }
 
+        if node.prune_mode && node.prune_horizon == 0 {
+            return Err("node.prune_horizon must be positive when node.prune_mode is set".into());
+        }
+
+        if node.archival && node.prune_mode {
+            return Err("node.archival cannot be combined with node.prune_mode".into());
+        }
+
+        if node.backup_retention == 0 {
+            return Err("node.backup_retention must be positive".into());
+        }
+
+        if !(0.0..=1.0).contains(&node.chain_quality_min_signer_participation) {
+            return Err(
+                "node.chain_quality_min_signer_participation must be between 0 and 1".into(),
+            );
+        }
+
         Ok(Config {
             config_path: config_file.__path,
+            config_profile: config_file.__profile,
             node,
             burnchain,
             initial_balances,
             events_observers,
+            webhooks,
+            stackerdb_standbys,
             connection_options,
             estimation,
             miner,
             atlas,
+            mempool_admission,
         })
     }
 
@@ -1004,6 +1327,63 @@ impl Config {
         path
     }
 
+    /// Returns the path of the per-contract cost DB, if contract cost tracking is enabled
+    pub fn get_contract_cost_db_path(&self) -> Option<PathBuf> {
+        if !self.node.contract_cost_tracking {
+            return None;
+        }
+        let mut path = self.get_estimates_path();
+        path.push("contract_costs.sqlite");
+        Some(path)
+    }
+
+    /// Returns the path of the transaction receipts DB, if receipt tracking is enabled
+    pub fn get_receipt_db_path(&self) -> Option<PathBuf> {
+        if !self.node.receipt_tracking {
+            return None;
+        }
+        let mut path = self.get_chainstate_path();
+        path.push("receipts.sqlite");
+        Some(path)
+    }
+
+    /// Returns the path of the block state diff DB, if state diff tracking is enabled
+    pub fn get_state_diff_db_path(&self) -> Option<PathBuf> {
+        if !self.node.state_diff_tracking {
+            return None;
+        }
+        let mut path = self.get_chainstate_path();
+        path.push("state_diffs.sqlite");
+        Some(path)
+    }
+
+    /// Returns the number of Stacks blocks of Clarity state to keep, if pruning is enabled
+    pub fn get_prune_horizon(&self) -> Option<u64> {
+        self.node.prune_mode.then_some(self.node.prune_horizon)
+    }
+
+    /// Returns when the chain-quality monitor raises alerts
+    pub fn get_chain_quality_thresholds(&self) -> ChainQualityThresholds {
+        ChainQualityThresholds {
+            missed_sortitions: self.node.chain_quality_missed_sortitions,
+            empty_tenures: self.node.chain_quality_empty_tenures,
+            min_signer_participation: self.node.chain_quality_min_signer_participation,
+        }
+    }
+
+    /// Returns the directory into which chainstate backups are rotated
+    pub fn get_backup_dir(&self) -> PathBuf {
+        match self.node.backup_dir.as_ref() {
+            Some(dir) => PathBuf::from(dir),
+            None => {
+                let mut path = PathBuf::from(&self.node.working_dir);
+                path.push(&self.burnchain.mode);
+                path.push("backups");
+                path
+            }
+        }
+    }
+
     pub fn get_chainstate_path_str(&self) -> String {
         self.get_chainstate_path()
             .to_str()
@@ -1097,9 +1477,11 @@ impl Config {
                 filter_origins: miner_config.filter_origins,
                 tenure_cost_limit_per_block_percentage: miner_config
                     .tenure_cost_limit_per_block_percentage,
+                nonce_chain_fee_rate: miner_config.nonce_chain_fee_rate,
             },
             miner_status,
             confirm_microblocks: false,
+            candidate_count: miner_config.candidate_count,
         }
     }
 
@@ -1139,9 +1521,11 @@ impl Config {
                 filter_origins: miner_config.filter_origins,
                 tenure_cost_limit_per_block_percentage: miner_config
                     .tenure_cost_limit_per_block_percentage,
+                nonce_chain_fee_rate: miner_config.nonce_chain_fee_rate,
             },
             miner_status,
             confirm_microblocks: true,
+            candidate_count: 1,
         }
     }
 
@@ -1181,14 +1565,18 @@ impl std::default::Default for Config {
 
         Config {
             config_path: None,
+            config_profile: None,
             burnchain,
             node,
             initial_balances: vec![],
             events_observers: HashSet::new(),
+            webhooks: vec![],
+            stackerdb_standbys: vec![],
             connection_options,
             estimation,
             miner: MinerConfig::default(),
             atlas: AtlasConfig::new(mainnet),
+            mempool_admission: ContractRulesPolicy::default(),
         }
     }
 }
@@ -1228,6 +1616,10 @@ pub struct BurnchainConfig {
     /// Custom override for the definitions of the epochs. This will only be applied for testnet and
     /// regtest nodes.
     pub epochs: Option<EpochList<ExecutionCost>>,
+    /// PoX activation and unlock heights configured alongside `epochs`, which override the
+    /// defaults derived from the epochs' start heights.  This will only be applied for testnet
+    /// and regtest nodes.
+    pub epoch_pox: HashMap<StacksEpochId, EpochPoxConfig>,
     pub pox_2_activation: Option<u32>,
     pub pox_reward_length: Option<u32>,
     pub pox_prepare_length: Option<u32>,
@@ -1236,6 +1628,9 @@ pub struct BurnchainConfig {
     pub wallet_name: String,
     pub ast_precheck_size_height: Option<u64>,
     pub affirmation_overrides: HashMap<u64, AffirmationMap>,
+    /// Operator rulings on the PoX anchor blocks of the given reward cycles, which take
+    /// precedence over the network's affirmations.  Written to the burnchain DB at startup.
+    pub anchor_block_overrides: BTreeMap<u64, AnchorBlockOverrideStatus>,
     /// fault injection to simulate a slow burnchain peer.
     /// Delay burnchain block downloads by the given number of millseconds
     pub fault_injection_burnchain_block_delay: u64,
@@ -1243,6 +1638,48 @@ pub struct BurnchainConfig {
     /// This value is passed as the `maximumCount` query option to the
     /// `listunspent` RPC call.
     pub max_unspent_utxos: Option<u64>,
+    /// How the miner's block-commit and leader key register transactions are funded and signed
+    pub wallet_mode: BitcoinWalletMode,
+    /// The address in `wallet_name` which funds the miner's transactions, and receives their
+    /// change, if `wallet_mode` is not `local`
+    pub wallet_funding_address: Option<String>,
+    /// In `external` wallet mode, the command which signs the miner's transactions.  It is given
+    /// a base64-encoded PSBT on stdin, and must print the signed PSBT to stdout.
+    pub wallet_signer_command: Option<String>,
+    /// In `mocknet` mode, when the node runs the Nakamoto run loop, the node mines a synthetic
+    /// burnchain block at most once every this many milliseconds (and at most once every
+    /// `poll_time_secs`).
+    pub mocknet_block_time_ms: u64,
+}
+
+/// Where the keys that fund the miner's burnchain transactions are held
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum BitcoinWalletMode {
+    /// The node funds transactions from the address of its burnchain op key, and signs them
+    /// itself
+    #[default]
+    Local,
+    /// bitcoind's descriptor wallet `wallet_name` funds transactions from
+    /// `wallet_funding_address`, and signs them
+    Descriptor,
+    /// Transactions are funded from `wallet_funding_address`, which `wallet_name` watches
+    /// without holding its keys, and are signed by `wallet_signer_command` (e.g. an HSM)
+    External,
+}
+
+impl FromStr for BitcoinWalletMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "local" => Ok(Self::Local),
+            "descriptor" => Ok(Self::Descriptor),
+            "external" => Ok(Self::External),
+            other => Err(format!(
+                "Invalid burnchain.wallet_mode '{other}': expected 'local', 'descriptor', or 'external'"
+            )),
+        }
+    }
 }
 
 impl BurnchainConfig {
@@ -1274,6 +1711,7 @@ impl BurnchainConfig {
             first_burn_block_timestamp: None,
             first_burn_block_hash: None,
             epochs: None,
+            epoch_pox: HashMap::new(),
             pox_2_activation: None,
             pox_prepare_length: None,
             pox_reward_length: None,
@@ -1282,8 +1720,13 @@ impl BurnchainConfig {
             wallet_name: "".to_string(),
             ast_precheck_size_height: None,
             affirmation_overrides: HashMap::new(),
+            anchor_block_overrides: BTreeMap::new(),
             fault_injection_burnchain_block_delay: 0,
             max_unspent_utxos: Some(1024),
+            wallet_mode: BitcoinWalletMode::Local,
+            wallet_funding_address: None,
+            wallet_signer_command: None,
+            mocknet_block_time_ms: 10_000,
         }
     }
     pub fn get_rpc_url(&self, wallet: Option<String>) -> String {
@@ -1317,15 +1760,118 @@ impl BurnchainConfig {
         }
     }
 
+    /// Whether the node runs on a synthetic burnchain instead of a bitcoind
+    pub fn is_mocknet(&self) -> bool {
+        self.mode == "mocknet"
+    }
+
     pub fn get_epoch_list(&self) -> EpochList<ExecutionCost> {
         StacksEpoch::get_epochs(self.get_bitcoin_network().1, self.epochs.as_ref())
     }
+
+    /// Get the PoX heights configured alongside the given epoch
+    pub fn get_epoch_pox(&self, epoch_id: StacksEpochId) -> EpochPoxConfig {
+        self.epoch_pox.get(&epoch_id).cloned().unwrap_or_default()
+    }
+
+    /// Get the address which funds the miner's transactions, if they are not funded by the
+    /// node's own key
+    pub fn get_wallet_funding_address(&self) -> Option<BitcoinAddress> {
+        if self.wallet_mode == BitcoinWalletMode::Local {
+            return None;
+        }
+        self.wallet_funding_address
+            .as_deref()
+            .and_then(BitcoinAddress::from_string)
+    }
 }
 
 #[derive(Clone, Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
 pub struct StacksEpochConfigFile {
     epoch_name: String,
     start_height: i64,
+    /// Override for this epoch's block limit.  Unset dimensions keep the node's default limit
+    /// for this epoch.
+    block_limit: Option<ExecutionCostConfigFile>,
+    /// Height at which this epoch's PoX contract activates.  Only applies to epochs 2.4 (pox-3)
+    /// and 2.5 (pox-4), and defaults to the epoch's start height.
+    pox_activation_height: Option<u32>,
+    /// Height at which STX locked in the previous PoX contract unlock.  Only applies to epochs
+    /// 2.1 (pox-1), 2.2 (pox-2), and 2.5 (pox-3), and defaults to the block after the epoch's
+    /// start height.
+    pox_unlock_height: Option<u32>,
+}
+
+impl StacksEpochConfigFile {
+    /// Get the ID of the epoch this entry configures
+    fn epoch_id(&self) -> Result<StacksEpochId, String> {
+        let epoch_name = &self.epoch_name;
+        if epoch_name == EPOCH_CONFIG_1_0_0 {
+            Ok(StacksEpochId::Epoch10)
+        } else if epoch_name == EPOCH_CONFIG_2_0_0 {
+            Ok(StacksEpochId::Epoch20)
+        } else if epoch_name == EPOCH_CONFIG_2_0_5 {
+            Ok(StacksEpochId::Epoch2_05)
+        } else if epoch_name == EPOCH_CONFIG_2_1_0 {
+            Ok(StacksEpochId::Epoch21)
+        } else if epoch_name == EPOCH_CONFIG_2_2_0 {
+            Ok(StacksEpochId::Epoch22)
+        } else if epoch_name == EPOCH_CONFIG_2_3_0 {
+            Ok(StacksEpochId::Epoch23)
+        } else if epoch_name == EPOCH_CONFIG_2_4_0 {
+            Ok(StacksEpochId::Epoch24)
+        } else if epoch_name == EPOCH_CONFIG_2_5_0 {
+            Ok(StacksEpochId::Epoch25)
+        } else if epoch_name == EPOCH_CONFIG_3_0_0 {
+            Ok(StacksEpochId::Epoch30)
+        } else if epoch_name == EPOCH_CONFIG_3_1_0 {
+            Ok(StacksEpochId::Epoch31)
+        } else {
+            Err(format!("Unknown epoch name specified: {epoch_name}"))
+        }
+    }
+}
+
+/// An execution cost, as set in the config file
+#[derive(Clone, Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ExecutionCostConfigFile {
+    pub write_length: Option<u64>,
+    pub write_count: Option<u64>,
+    pub read_length: Option<u64>,
+    pub read_count: Option<u64>,
+    pub runtime: Option<u64>,
+}
+
+impl ExecutionCostConfigFile {
+    /// Overwrite the dimensions of `cost` that are set in this config
+    fn apply_to(&self, cost: &mut ExecutionCost) {
+        if let Some(write_length) = self.write_length {
+            cost.write_length = write_length;
+        }
+        if let Some(write_count) = self.write_count {
+            cost.write_count = write_count;
+        }
+        if let Some(read_length) = self.read_length {
+            cost.read_length = read_length;
+        }
+        if let Some(read_count) = self.read_count {
+            cost.read_count = read_count;
+        }
+        if let Some(runtime) = self.runtime {
+            cost.runtime = runtime;
+        }
+    }
+}
+
+/// PoX heights configured alongside an epoch
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EpochPoxConfig {
+    /// Height at which the epoch's PoX contract activates
+    pub activation_height: Option<u32>,
+    /// Height at which STX locked in the previous PoX contract unlock
+    pub unlock_height: Option<u32>,
 }
 
 pub const EPOCH_CONFIG_1_0_0: &str = "1.0";
@@ -1345,6 +1891,14 @@ pub struct AffirmationOverride {
     pub affirmation: String,
 }
 
+#[derive(Clone, Deserialize, Default, Debug)]
+pub struct AnchorBlockOverrideEntry {
+    /// The reward cycle the anchor block was chosen for
+    pub reward_cycle: u64,
+    /// "accept" or "reject"
+    pub status: String,
+}
+
 #[derive(Clone, Deserialize, Default, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct BurnchainConfigFile {
@@ -1382,8 +1936,13 @@ pub struct BurnchainConfigFile {
     pub wallet_name: Option<String>,
     pub ast_precheck_size_height: Option<u64>,
     pub affirmation_overrides: Option<Vec<AffirmationOverride>>,
+    pub anchor_block_overrides: Option<Vec<AnchorBlockOverrideEntry>>,
     pub fault_injection_burnchain_block_delay: Option<u64>,
     pub max_unspent_utxos: Option<u64>,
+    pub wallet_mode: Option<String>,
+    pub wallet_funding_address: Option<String>,
+    pub wallet_signer_command: Option<String>,
+    pub mocknet_block_time_ms: Option<u64>,
 }
 
 impl BurnchainConfigFile {
@@ -1496,6 +2055,30 @@ impl BurnchainConfigFile {
             }
         }
 
+        let mut anchor_block_overrides = BTreeMap::new();
+        for abo in self.anchor_block_overrides.unwrap_or_default() {
+            let Some(status) = AnchorBlockOverrideStatus::from_str(&abo.status) else {
+                return Err(format!(
+                    "Invalid anchor block override for reward cycle {}: status must be \"accept\" or \"reject\", not \"{}\"",
+                    abo.reward_cycle, abo.status
+                ));
+            };
+            if abo.reward_cycle == 0 {
+                return Err(
+                    "Invalid anchor block override: reward cycle 0 has no anchor block".into(),
+                );
+            }
+            if anchor_block_overrides
+                .insert(abo.reward_cycle, status)
+                .is_some()
+            {
+                return Err(format!(
+                    "Duplicate anchor block override for reward cycle {}",
+                    abo.reward_cycle
+                ));
+            }
+        }
+
         let mut config = BurnchainConfig {
             chain: self.chain.unwrap_or(default_burnchain_config.chain),
             chain_id: match self.chain_id {
@@ -1585,6 +2168,7 @@ impl BurnchainConfigFile {
                 .or(default_burnchain_config.first_burn_block_hash.clone()),
             // will be overwritten below
             epochs: default_burnchain_config.epochs,
+            epoch_pox: default_burnchain_config.epoch_pox,
             ast_precheck_size_height: self.ast_precheck_size_height,
             pox_2_activation: self
                 .pox_2_activation
@@ -1601,6 +2185,7 @@ impl BurnchainConfigFile {
                 .pox_prepare_length
                 .or(default_burnchain_config.pox_prepare_length),
             affirmation_overrides,
+            anchor_block_overrides,
             fault_injection_burnchain_block_delay: self
                 .fault_injection_burnchain_block_delay
                 .unwrap_or(default_burnchain_config.fault_injection_burnchain_block_delay),
@@ -1610,8 +2195,69 @@ impl BurnchainConfigFile {
                     assert!(val <= 1024, "Value for max_unspent_utxos should be <= 1024");
                 })
                 .or(default_burnchain_config.max_unspent_utxos),
+            wallet_mode: self
+                .wallet_mode
+                .as_deref()
+                .map(BitcoinWalletMode::from_str)
+                .transpose()?
+                .unwrap_or(default_burnchain_config.wallet_mode),
+            wallet_funding_address: self
+                .wallet_funding_address
+                .or(default_burnchain_config.wallet_funding_address),
+            wallet_signer_command: self
+                .wallet_signer_command
+                .or(default_burnchain_config.wallet_signer_command),
+            mocknet_block_time_ms: self
+                .mocknet_block_time_ms
+                .unwrap_or(default_burnchain_config.mocknet_block_time_ms),
         };
 
+        if config.wallet_mode != BitcoinWalletMode::Local {
+            if config.wallet_name.is_empty() {
+                return Err(
+                    "burnchain.wallet_name is required unless wallet_mode is 'local'".into(),
+                );
+            }
+            let Some(funding_address) = config.wallet_funding_address.as_ref() else {
+                return Err(
+                    "burnchain.wallet_funding_address is required unless wallet_mode is 'local'"
+                        .into(),
+                );
+            };
+            let address = BitcoinAddress::from_string(funding_address).ok_or_else(|| {
+                format!("Invalid burnchain.wallet_funding_address '{funding_address}'")
+            })?;
+            let is_supported = match &address {
+                BitcoinAddress::Legacy(legacy) => {
+                    legacy.addrtype == LegacyBitcoinAddressType::PublicKeyHash
+                }
+                BitcoinAddress::Segwit(_) => address.is_segwit_p2wpkh() || address.is_segwit_p2tr(),
+            };
+            if !is_supported {
+                return Err(
+                    "burnchain.wallet_funding_address must be a p2pkh, p2wpkh, or p2tr address"
+                        .into(),
+                );
+            }
+            let is_mainnet_address = match &address {
+                BitcoinAddress::Legacy(legacy) => legacy.network_id == BitcoinNetworkType::Mainnet,
+                BitcoinAddress::Segwit(segwit) => segwit.is_mainnet(),
+            };
+            if is_mainnet_address != (config.get_bitcoin_network().1 == BitcoinNetworkType::Mainnet)
+            {
+                return Err(format!(
+                    "burnchain.wallet_funding_address '{funding_address}' is for the wrong network"
+                ));
+            }
+        }
+        if config.wallet_mode == BitcoinWalletMode::External
+            && config.wallet_signer_command.is_none()
+        {
+            return Err(
+                "burnchain.wallet_signer_command is required when wallet_mode is 'external'".into(),
+            );
+        }
+
         if let BitcoinNetworkType::Mainnet = config.get_bitcoin_network().1 {
             // check that pox_2_activation hasn't been set in mainnet
             if config.pox_2_activation.is_some()
@@ -1630,12 +2276,14 @@ impl BurnchainConfigFile {
         }
 
         if let Some(ref conf_epochs) = self.epochs {
-            config.epochs = Some(Config::make_epochs(
+            let epochs = Config::make_epochs(
                 conf_epochs,
                 &config.mode,
                 config.get_bitcoin_network().1,
                 self.pox_2_activation,
-            )?);
+            )?;
+            config.epoch_pox = Config::make_epoch_pox(conf_epochs, &epochs)?;
+            config.epochs = Some(epochs);
         }
 
         Ok(config)
@@ -1674,11 +2322,62 @@ pub struct NodeConfig {
     pub prometheus_bind: Option<String>,
     pub marf_cache_strategy: Option<String>,
     pub marf_defer_hashing: bool,
+    /// Reuse the ancestor trie root hashes looked up while committing each block's MARF trie, and
+    /// the block's own sealed root hash, for the next block, so that consecutive blocks of a
+    /// tenure do not repeat the same ancestor lookups.  Each block's trie is still committed to
+    /// disk on its own, and root hashes are unaffected.
+    pub marf_batch_ancestor_hashes: bool,
     pub pox_sync_sample_secs: u64,
     pub use_test_genesis_chainstate: Option<bool>,
     pub always_use_affirmation_maps: bool,
     pub require_affirmed_anchor_blocks: bool,
     pub assume_present_anchor_blocks: bool,
+    /// The reward sets injected by the configured reward set source, shared by everything in the
+    /// node that loads reward sets.  Anything other than the on-chain source is only permitted
+    /// off of mainnet.
+    pub reward_sets: Arc<InjectedRewardSets>,
+    /// Whether to record the execution cost charged to each contract, for
+    /// `/v3/metrics/contract-costs`
+    pub contract_cost_tracking: bool,
+    /// How many Stacks block heights of per-contract costs to keep
+    pub contract_cost_retention: u64,
+    /// Whether to record the receipt of every processed transaction, for
+    /// `/v3/transactions/:txid/receipt` and the event counts of `/v3/blocks/:block_id/summary`
+    pub receipt_tracking: bool,
+    /// How many Stacks block heights of transaction receipts to keep
+    pub receipt_retention: u64,
+    /// Whether to record the data vars, map entries, and token balances changed by each
+    /// processed block, for `/v3/blocks/:block_id/state-diff`
+    pub state_diff_tracking: bool,
+    /// How many Stacks block heights of block state diffs to keep
+    pub state_diff_retention: u64,
+    /// Whether to discard Clarity state older than `prune_horizon` blocks
+    pub prune_mode: bool,
+    /// How many Stacks block heights of Clarity state to keep in prune mode
+    pub prune_horizon: u64,
+    /// If set, the deepest Stacks reorg the node follows without operator confirmation
+    pub max_reorg_depth: Option<u64>,
+    /// Consecutive sortitions without a winner before raising an alert (0 disables the alert)
+    pub chain_quality_missed_sortitions: u64,
+    /// Consecutive tenures without transactions before raising an alert (0 disables the alert)
+    pub chain_quality_empty_tenures: u64,
+    /// Average signer participation below which to raise an alert (0 disables the alert)
+    pub chain_quality_min_signer_participation: f64,
+    /// How often, in seconds, to back up the node's databases (0 disables periodic backups)
+    pub backup_interval: u64,
+    /// Where to write backups, if not the default `backups` directory in the working dir
+    pub backup_dir: Option<String>,
+    /// How many backups to keep
+    pub backup_retention: u64,
+    /// Whether to revalidate mempool transactions against the chain tip when the node starts
+    pub mempool_revalidate_on_start: bool,
+    /// Maximum number of mempool transactions to revalidate on startup
+    pub mempool_revalidate_max_txs: u64,
+    /// Whether to keep every block and serve tenure history from the `/v3/archive` endpoints
+    pub archival: bool,
+    /// Whether to replicate the signers' `.checkpoints` StackerDB, and check the chain against
+    /// its signed checkpoints during initial block download
+    pub checkpoint_hints: bool,
     /// Fault injection for failing to push blocks
     pub fault_injection_block_push_fail_probability: Option<u8>,
     // fault injection for hiding blocks.
@@ -1689,6 +2388,20 @@ pub struct NodeConfig {
     pub chain_liveness_poll_time_secs: u64,
     /// stacker DBs we replicate
     pub stacker_dbs: Vec<QualifiedContractIdentifier>,
+    /// PEM-encoded certificate chain for serving RPC requests over TLS
+    pub tls_cert: Option<PathBuf>,
+    /// PEM-encoded private key for `tls_cert`
+    pub tls_key: Option<PathBuf>,
+    /// PEM-encoded CA certificate which signs every node's `tls_cert`.  If set, p2p sessions use
+    /// mutual TLS, and peers without a certificate from this CA are refused.
+    pub p2p_tls_ca: Option<PathBuf>,
+    /// Addresses to accept RPC connections on in addition to `rpc_bind`
+    pub extra_rpc_binds: Vec<SocketAddr>,
+    /// Addresses to accept p2p connections on in addition to `p2p_bind`
+    pub extra_p2p_binds: Vec<SocketAddr>,
+    /// Public p2p addresses to advertise to peers whose address family differs from
+    /// `p2p_address`'s (e.g. an IPv6 address for a node whose `p2p_address` is IPv4)
+    pub extra_p2p_addresses: Vec<SocketAddr>,
 }
 
 #[derive(Clone, Debug)]
@@ -1856,6 +2569,36 @@ impl Config {
 
         Some(fee_estimator)
     }
+
+    /// Open the per-contract cost DB, if contract cost tracking is enabled
+    pub fn make_contract_cost_db(&self) -> Option<ContractCostDB> {
+        let path = self.get_contract_cost_db_path()?;
+        ContractCostDB::open(&path, self.node.contract_cost_retention)
+            .inspect_err(|e| {
+                error!("Failed to open contract cost DB"; "path" => %path.display(), "error" => %e);
+            })
+            .ok()
+    }
+
+    /// Open the transaction receipts DB, if receipt tracking is enabled
+    pub fn make_receipt_db(&self) -> Option<TransactionReceiptDB> {
+        let path = self.get_receipt_db_path()?;
+        TransactionReceiptDB::open(&path, self.node.receipt_retention)
+            .inspect_err(|e| {
+                error!("Failed to open transaction receipts DB"; "path" => %path.display(), "error" => %e);
+            })
+            .ok()
+    }
+
+    /// Open the block state diff DB, if state diff tracking is enabled
+    pub fn make_state_diff_db(&self) -> Option<StateDiffDB> {
+        let path = self.get_state_diff_db_path()?;
+        StateDiffDB::open(&path, self.node.state_diff_retention)
+            .inspect_err(|e| {
+                error!("Failed to open block state diff DB"; "path" => %path.display(), "error" => %e);
+            })
+            .ok()
+    }
 }
 
 impl FeeEstimationConfig {
@@ -1958,20 +2701,104 @@ impl Default for NodeConfig {
             prometheus_bind: None,
             marf_cache_strategy: None,
             marf_defer_hashing: true,
+            marf_batch_ancestor_hashes: false,
             pox_sync_sample_secs: 30,
             use_test_genesis_chainstate: None,
             always_use_affirmation_maps: true,
             require_affirmed_anchor_blocks: true,
             assume_present_anchor_blocks: true,
+            reward_sets: Arc::new(InjectedRewardSets::default()),
+            contract_cost_tracking: false,
+            contract_cost_retention: 10_000,
+            receipt_tracking: false,
+            receipt_retention: 10_000,
+            state_diff_tracking: false,
+            state_diff_retention: 10_000,
+            prune_mode: false,
+            prune_horizon: 10_000,
+            max_reorg_depth: None,
+            chain_quality_missed_sortitions: ChainQualityThresholds::default().missed_sortitions,
+            chain_quality_empty_tenures: ChainQualityThresholds::default().empty_tenures,
+            chain_quality_min_signer_participation: ChainQualityThresholds::default()
+                .min_signer_participation,
+            backup_interval: 0,
+            backup_dir: None,
+            backup_retention: 3,
+            mempool_revalidate_on_start: false,
+            mempool_revalidate_max_txs: 5_000,
+            archival: false,
+            checkpoint_hints: false,
             fault_injection_block_push_fail_probability: None,
             fault_injection_hide_blocks: false,
             chain_liveness_poll_time_secs: 300,
             stacker_dbs: vec![],
+            tls_cert: None,
+            tls_key: None,
+            p2p_tls_ca: None,
+            extra_rpc_binds: vec![],
+            extra_p2p_binds: vec![],
+            extra_p2p_addresses: vec![],
         }
     }
 }
 
 impl NodeConfig {
+    /// Load the TLS certificates named in this config into the connection options
+    fn load_tls_config(&self, connection_options: &mut ConnectionOptions) -> Result<(), String> {
+        let (tls_cert, tls_key) = match (&self.tls_cert, &self.tls_key) {
+            (Some(tls_cert), Some(tls_key)) => (tls_cert, tls_key),
+            (None, None) => {
+                if self.p2p_tls_ca.is_some() {
+                    return Err("node.p2p_tls_ca requires node.tls_cert and node.tls_key".into());
+                }
+                return Ok(());
+            }
+            _ => return Err("node.tls_cert and node.tls_key must be set together".into()),
+        };
+        connection_options.rpc_tls = Some(
+            TlsServerConfig::load(tls_cert, tls_key)
+                .map_err(|e| format!("Failed to load RPC TLS config: {e}"))?,
+        );
+        if let Some(p2p_tls_ca) = &self.p2p_tls_ca {
+            connection_options.p2p_tls = Some(
+                TlsPeerConfig::load(tls_cert, tls_key, p2p_tls_ca)
+                    .map_err(|e| format!("Failed to load p2p TLS config: {e}"))?,
+            );
+        }
+        Ok(())
+    }
+
+    /// Load the extra advertised p2p addresses into the connection options
+    fn load_extra_p2p_addresses(&self, connection_options: &mut ConnectionOptions) {
+        connection_options.extra_p2p_addresses = self
+            .extra_p2p_addresses
+            .iter()
+            .map(|addr| (PeerAddress::from_socketaddr(addr), addr.port()))
+            .collect();
+    }
+
+    /// Get every address the p2p server binds to.  The first is `p2p_bind`.
+    pub fn get_p2p_bind_addrs(&self) -> Vec<SocketAddr> {
+        let p2p_sock: SocketAddr = self
+            .p2p_bind
+            .parse()
+            .unwrap_or_else(|_| panic!("Failed to parse socket: {}", &self.p2p_bind));
+        let mut addrs = vec![p2p_sock];
+        addrs.extend_from_slice(&self.extra_p2p_binds);
+        addrs
+    }
+
+    /// Get every address the RPC server binds to.  The first is `rpc_bind`.
+    pub fn get_rpc_bind_addrs(&self) -> Vec<SocketAddr> {
+        let rpc_sock: SocketAddr = self
+            .rpc_bind
+            .parse()
+            .unwrap_or_else(|_| panic!("Failed to parse socket: {}", &self.rpc_bind));
+        let mut addrs = vec![rpc_sock];
+        addrs.extend_from_slice(&self.extra_rpc_binds);
+        addrs
+    }
+
     /// Get a SocketAddr for this node's RPC endpoint which uses the loopback address
     pub fn get_rpc_loopback(&self) -> Option<SocketAddr> {
         let rpc_port = SocketAddr::from_str(&self.rpc_bind)
@@ -2002,6 +2829,13 @@ impl NodeConfig {
         }
     }
 
+    pub fn add_checkpoints_stackerdb(&mut self, is_mainnet: bool) {
+        let checkpoints_contract_id = boot_code_id(CHECKPOINTS_NAME, is_mainnet);
+        if !self.stacker_dbs.contains(&checkpoints_contract_id) {
+            self.stacker_dbs.push(checkpoints_contract_id);
+        }
+    }
+
     fn default_neighbor(
         addr: SocketAddr,
         pubk: Secp256k1PublicKey,
@@ -2109,11 +2943,13 @@ impl NodeConfig {
             TrieHashCalculationMode::Immediate
         };
 
-        MARFOpenOpts::new(
+        let mut marf_opts = MARFOpenOpts::new(
             hash_mode,
             self.marf_cache_strategy.as_deref().unwrap_or("noop"),
             false,
-        )
+        );
+        marf_opts.batch_ancestor_hashes = self.marf_batch_ancestor_hashes;
+        marf_opts
     }
 }
 
@@ -2173,19 +3009,82 @@ pub struct MinerConfig {
     pub first_rejection_pause_ms: u64,
     /// Time in milliseconds to pause after receiving subsequent threshold rejections, before proposing a new block.
     pub subsequent_rejection_pause_ms: u64,
+    /// Maximum number of times in a row to repropose a block right away, without the rejection pause, after
+    /// signers reject a proposal and blame specific transactions in it. The reproposed block leaves those
+    /// transactions out. Past this many reproposals, the miner pauses as it does for any other rejection.
+    pub max_reproposals: u32,
     /// Duration to wait for a Nakamoto block after seeing a burnchain block before submitting a block commit.
     pub block_commit_delay: Duration,
     /// The percentage of the remaining tenure cost limit to consume each block.
     pub tenure_cost_limit_per_block_percentage: Option<u8>,
+    /// Whether to consider an origin's chained mempool transactions by the aggregate fee rate of
+    /// the chain, so that a low-fee transaction ahead of higher-fee ones from the same sender is
+    /// not left behind them.  Only applies to transactions with a fee-rate estimate.
+    pub nonce_chain_fee_rate: bool,
+    /// Number of candidate blocks to assemble per Nakamoto mining attempt, each walking the
+    /// mempool in a different order.  The candidate with the highest fee total is proposed.
+    /// Candidates share `nakamoto_attempt_time_ms`.
+    pub candidate_count: u32,
     /// The number of seconds to wait in-between polling the sortition DB to see if we need to
     /// extend the ongoing tenure (e.g. because the current sortition is empty or invalid).
     pub tenure_extend_poll_secs: Duration,
     /// Duration to wait before attempting to issue a tenure extend
     pub tenure_timeout: Duration,
-    /// Percentage of block budget that must be used before attempting a time-based tenure extend
+    /// Percentage of block budget that must be used before attempting a time-based tenure extend.
+    /// The miner also attempts one if another block as costly as the last one would exhaust the budget.
     pub tenure_extend_cost_threshold: u64,
+    /// Highest fee rate (sats/vbyte) to pay for a block commit, including RBF bumps.  If set,
+    /// the miner watches the bitcoin mempool for competing commits and outbids them up to this
+    /// rate.  If not set, RBF bumps stop at `burnchain.max_rbf` percent of
+    /// `burnchain.satoshis_per_byte`, and competing commits are not tracked.
+    pub max_commit_fee_rate: Option<u64>,
+    /// How much to raise a block commit's fee rate (sats/vbyte) each time it is replaced by fee.
+    /// If not set, `burnchain.rbf_fee_increment` is used.
+    pub commit_rbf_increment: Option<u64>,
+    /// How to choose which of the miner's UTXOs fund its block commits and leader key registers
+    pub utxo_selection: CoinSelectionStrategy,
+    /// Fee rate (sats/vbyte) at or below which the miner consolidates its UTXOs, by spending as
+    /// many of its smallest UTXOs as it can in its next block commit.  If not set, the miner
+    /// only consolidates when `utxo_selection` is `consolidate`.
+    pub utxo_consolidation_fee_rate: Option<u64>,
+    /// Minimum number of UTXOs the miner must hold before it consolidates them at a low fee rate
+    pub utxo_consolidation_min_count: u64,
+    /// Path to a lease file shared by redundant nodes with the same `mining_key`.  If set, only
+    /// the node holding the lease submits block-commits and mines, and a standby takes over once
+    /// the active node has not renewed the lease for `failover_lease_ttl`.
+    pub failover_lease_path: Option<String>,
+    /// This node's name in the miner lease.  Must be unique among the redundant nodes.
+    pub failover_node_id: Option<String>,
+    /// How long the miner lease lasts after the active node's last block-commit
+    pub failover_lease_ttl: Duration,
+    /// Whether to choose the amount to burn in each block-commit from the block-commits in recent
+    /// sortitions, instead of always burning `burnchain.burn_fee_cap`
+    pub commit_bid_auto: bool,
+    /// Lowest amount (in sats) to burn in a block-commit when auto-bidding
+    pub commit_bid_min_sats: u64,
+    /// Highest amount (in sats) to burn in a block-commit when auto-bidding.  If not set,
+    /// `burnchain.burn_fee_cap` is used.
+    pub commit_bid_max_sats: Option<u64>,
+    /// Number of recent sortitions whose block-commits the auto-bidder considers
+    pub commit_bid_window: u64,
+    /// Share of the total burn in recent sortitions, in percent, that the auto-bidder's bid
+    /// would have won.  This is roughly the miner's chance of winning each sortition.
+    pub commit_bid_target_win_pct: u8,
     /// Define the timeout to apply while waiting for signers responses, based on the amount of rejections
     pub block_rejection_timeout_steps: HashMap<u32, Duration>,
+    /// Whether the miner's signature-wait timings are fixed or learned from signer latency
+    pub signature_wait_mode: SignatureWaitMode,
+    /// How long to wait for signatures on a block proposal before first checking the staging DB
+    /// and burnchain tip
+    pub signature_initial_wait: Duration,
+    /// Time between checks of the staging DB and burnchain tip while waiting for signatures
+    pub signature_poll_interval: Duration,
+    /// Longest time to wait for signatures on a block proposal, whatever the number of
+    /// rejections.  If not set, only `block_rejection_timeout_steps` applies.
+    pub signature_max_wait: Option<Duration>,
+    /// What to do with a proposal that reaches `signature_max_wait` with some, but not enough,
+    /// signing weight
+    pub signature_partial_threshold: PartialThresholdAction,
 }
 
 impl Default for MinerConfig {
@@ -2218,13 +3117,29 @@ impl Default for MinerConfig {
             min_time_between_blocks_ms: DEFAULT_MIN_TIME_BETWEEN_BLOCKS_MS,
             first_rejection_pause_ms: DEFAULT_FIRST_REJECTION_PAUSE_MS,
             subsequent_rejection_pause_ms: DEFAULT_SUBSEQUENT_REJECTION_PAUSE_MS,
+            max_reproposals: DEFAULT_MAX_REPROPOSALS,
             block_commit_delay: Duration::from_millis(DEFAULT_BLOCK_COMMIT_DELAY_MS),
             tenure_cost_limit_per_block_percentage: Some(
                 DEFAULT_TENURE_COST_LIMIT_PER_BLOCK_PERCENTAGE,
             ),
+            nonce_chain_fee_rate: false,
+            candidate_count: 1,
             tenure_extend_poll_secs: Duration::from_secs(DEFAULT_TENURE_EXTEND_POLL_SECS),
             tenure_timeout: Duration::from_secs(DEFAULT_TENURE_TIMEOUT_SECS),
             tenure_extend_cost_threshold: DEFAULT_TENURE_EXTEND_COST_THRESHOLD,
+            max_commit_fee_rate: None,
+            commit_rbf_increment: None,
+            utxo_selection: CoinSelectionStrategy::NewestFirst,
+            utxo_consolidation_fee_rate: None,
+            utxo_consolidation_min_count: DEFAULT_UTXO_CONSOLIDATION_MIN_COUNT,
+            failover_lease_path: None,
+            failover_node_id: None,
+            failover_lease_ttl: Duration::from_secs(DEFAULT_FAILOVER_LEASE_TTL_SECS),
+            commit_bid_auto: false,
+            commit_bid_min_sats: DEFAULT_COMMIT_BID_MIN_SATS,
+            commit_bid_max_sats: None,
+            commit_bid_window: DEFAULT_COMMIT_BID_WINDOW,
+            commit_bid_target_win_pct: DEFAULT_COMMIT_BID_TARGET_WIN_PCT,
 
             block_rejection_timeout_steps: {
                 let mut rejections_timeouts_default_map = HashMap::<u32, Duration>::new();
@@ -2234,6 +3149,11 @@ impl Default for MinerConfig {
                 rejections_timeouts_default_map.insert(30, Duration::from_secs(0));
                 rejections_timeouts_default_map
             },
+            signature_wait_mode: SignatureWaitMode::Fixed,
+            signature_initial_wait: Duration::from_millis(DEFAULT_SIGNATURE_POLL_INTERVAL_MS),
+            signature_poll_interval: Duration::from_millis(DEFAULT_SIGNATURE_POLL_INTERVAL_MS),
+            signature_max_wait: None,
+            signature_partial_threshold: PartialThresholdAction::Abandon,
         }
     }
 }
@@ -2271,6 +3191,8 @@ pub struct ConnectionOptionsFile {
     pub read_only_call_limit_write_count: Option<u64>,
     pub read_only_call_limit_read_count: Option<u64>,
     pub read_only_call_limit_runtime: Option<u64>,
+    pub read_only_map_entry_limit_read_length: Option<u64>,
+    pub read_only_map_entry_limit_read_count: Option<u64>,
     pub maximum_call_argument_size: Option<u32>,
     pub download_interval: Option<u64>,
     pub inv_sync_interval: Option<u64>,
@@ -2284,13 +3206,141 @@ pub struct ConnectionOptionsFile {
     pub antientropy_public: Option<bool>,
     pub private_neighbors: Option<bool>,
     pub auth_token: Option<String>,
+    /// Named operator tokens for the `/v3/admin/*` endpoints, each limited to some roles
+    pub admin_tokens: Option<Vec<AdminTokenConfigFile>>,
     pub antientropy_retry: Option<u64>,
     pub reject_blocks_pushed: Option<bool>,
     pub stackerdb_hint_replicas: Option<String>,
     pub block_proposal_max_age_secs: Option<u64>,
+    /// Sustained requests per second each source may make to cheap read endpoints (0 = unlimited)
+    pub rpc_rate_limit_read: Option<u64>,
+    /// Sustained requests per second each source may make to expensive read endpoints, like block
+    /// downloads and read-only calls (0 = unlimited)
+    pub rpc_rate_limit_read_heavy: Option<u64>,
+    /// Sustained requests per second each source may make to write endpoints (0 = unlimited)
+    pub rpc_rate_limit_write: Option<u64>,
+    /// Sustained requests per second each source may make to authenticated endpoints (0 = unlimited)
+    pub rpc_rate_limit_admin: Option<u64>,
+    /// How many seconds' worth of requests a source may send in a burst
+    pub rpc_rate_limit_burst_secs: Option<u64>,
+    /// IPv4 sources sharing this prefix length share a rate limit
+    pub rpc_rate_limit_ipv4_prefix: Option<u8>,
+    /// IPv6 sources sharing this prefix length share a rate limit
+    pub rpc_rate_limit_ipv6_prefix: Option<u8>,
+    /// How many requests to long-polling endpoints like `/v3/tip/wait` may be held at once.
+    /// Further requests are answered with a 503 until some are released.
+    pub rpc_max_held_requests: Option<u64>,
+    /// Browser origins (e.g. `"https://wallet.example.com"`) that may read RPC responses, or
+    /// `["*"]` for any origin.  An empty list disables CORS.  Defaults to `["*"]`.
+    pub cors_origins: Option<Vec<String>>,
+    /// How many burnchain blocks the node may lag behind its bitcoind before `/v3/health/ready`
+    /// reports it as not ready.  Defaults to 2.
+    pub health_max_burn_block_lag: Option<u64>,
+    /// How many tenures the Stacks tip may lag behind the burnchain tip before
+    /// `/v3/health/ready` reports the node as not ready.  Defaults to 2.
+    pub health_max_tenure_lag: Option<u64>,
+    /// How many seconds the Stacks tip may go without advancing while neighbors report later
+    /// tenures before the node re-downloads them and `/v3/health/ready` reports the tip as
+    /// stale.  0 disables the check.  Defaults to 180.
+    pub stale_tip_timeout_secs: Option<u64>,
+    /// Most outbound neighbors to forward each new transaction to.  Defaults to 8.
+    pub tx_broadcast_max_outbound: Option<usize>,
+    /// Most inbound neighbors to forward each new transaction to.  Defaults to 16.
+    pub tx_broadcast_max_inbound: Option<usize>,
+    /// Most miner neighbors (peers advertising the miner service bit in their handshake) to
+    /// eagerly push each new transaction to, in addition to the neighbors above, so it reaches
+    /// miners before their next mempool sync.  0 disables eager pushes.  Defaults to 8.
+    pub tx_push_max_miner_neighbors: Option<usize>,
+    /// Most threads to use to check the signatures of downloaded and pushed Nakamoto blocks
+    /// before they are stored, and of the transactions in block proposals before they are
+    /// validated.  Defaults to 4.
+    pub nakamoto_sigcheck_threads: Option<usize>,
+    /// Number of worker threads, each with its own read-only chainstate connections, from which
+    /// to serve read-only function calls and contract analyses.  0 disables read replicas.
+    /// Defaults to 0.
+    pub rpc_read_replicas: Option<usize>,
+    /// Whether to zstd-compress large p2p payloads (blocks and StackerDB chunks) sent to peers
+    /// that support it, and to advertise support for it in the handshake.  Defaults to false.
+    pub p2p_compression: Option<bool>,
+    /// Smallest p2p payload, in bytes, to compress.  Defaults to 4096.
+    pub p2p_compression_min_len: Option<usize>,
+    /// Whether to map the p2p port on the NAT gateway with UPnP or NAT-PMP, and check that it is
+    /// reachable from the Internet.  Defaults to false.
+    pub nat_traversal: Option<bool>,
+    /// Protocol with which to map the p2p port: "upnp", "natpmp", or "auto" to try UPnP and then
+    /// NAT-PMP.  Defaults to "auto".
+    pub nat_protocol: Option<String>,
+    /// IPv4 address of the gateway to send NAT-PMP requests to.  Defaults to the default route's
+    /// gateway.
+    pub nat_gateway: Option<String>,
+    /// How long, in seconds, the gateway should keep the port mapping.  It is renewed before it
+    /// expires.  Defaults to 3600.
+    pub nat_mapping_lifetime: Option<u64>,
+}
+
+#[derive(Clone, Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AdminTokenConfigFile {
+    /// Name of the token, for logs
+    pub name: String,
+    /// Secret to send in the `authorization` header
+    pub token: String,
+    /// What the token may do: any of "peers", "backup", "mempool", "mining", "logging",
+    /// "chainstate", "stackerdb", "blocks", "simulation", and "config"
+    pub roles: Vec<String>,
+}
+
+impl AdminTokenConfigFile {
+    fn into_config(self) -> Result<AdminToken, String> {
+        let roles = self
+            .roles
+            .iter()
+            .map(|role| AdminRole::from_str(role))
+            .collect::<Result<_, _>>()?;
+        Ok(AdminToken {
+            name: self.name,
+            token: self.token,
+            roles,
+        })
+    }
 }
 
 impl ConnectionOptionsFile {
+    fn rpc_rate_limits(&self) -> Result<HttpRateLimitConfig, String> {
+        let default = HttpRateLimitConfig::default();
+        let ipv4_prefix_len = self
+            .rpc_rate_limit_ipv4_prefix
+            .unwrap_or(default.ipv4_prefix_len);
+        if ipv4_prefix_len > 32 {
+            return Err(format!(
+                "Invalid connection_options.rpc_rate_limit_ipv4_prefix: {ipv4_prefix_len} is greater than 32"
+            ));
+        }
+        let ipv6_prefix_len = self
+            .rpc_rate_limit_ipv6_prefix
+            .unwrap_or(default.ipv6_prefix_len);
+        if ipv6_prefix_len > 128 {
+            return Err(format!(
+                "Invalid connection_options.rpc_rate_limit_ipv6_prefix: {ipv6_prefix_len} is greater than 128"
+            ));
+        }
+        Ok(HttpRateLimitConfig {
+            read: self.rpc_rate_limit_read.filter(|limit| *limit > 0),
+            read_heavy: self.rpc_rate_limit_read_heavy.filter(|limit| *limit > 0),
+            write: self.rpc_rate_limit_write.filter(|limit| *limit > 0),
+            admin: self.rpc_rate_limit_admin.filter(|limit| *limit > 0),
+            burst_secs: self
+                .rpc_rate_limit_burst_secs
+                .unwrap_or(default.burst_secs)
+                .max(1),
+            ipv4_prefix_len,
+            ipv6_prefix_len,
+            max_held_requests: self
+                .rpc_max_held_requests
+                .unwrap_or(default.max_held_requests),
+        })
+    }
+
     fn into_config(self, is_mainnet: bool) -> Result<ConnectionOptions, String> {
         let ip_addr = self
             .public_ip_address
@@ -2319,9 +3369,53 @@ impl ConnectionOptionsFile {
         if let Some(x) = self.read_only_call_limit_runtime {
             read_only_call_limit.runtime = x;
         };
+        let mut read_only_map_entry_limit = HELIUM_DEFAULT_CONNECTION_OPTIONS
+            .read_only_map_entry_limit
+            .clone();
+        if let Some(x) = self.read_only_map_entry_limit_read_length {
+            read_only_map_entry_limit.read_length = x;
+        }
+        if let Some(x) = self.read_only_map_entry_limit_read_count {
+            read_only_map_entry_limit.read_count = x;
+        }
+        let rpc_rate_limits = self.rpc_rate_limits()?;
+        let rpc_cors_policy = self
+            .cors_origins
+            .as_deref()
+            .map(HttpCorsPolicy::from_origins)
+            .transpose()
+            .map_err(|e| format!("Invalid connection_options.cors_origins: {e}"))?
+            .unwrap_or_default();
+        let admin_tokens = self
+            .admin_tokens
+            .unwrap_or_default()
+            .into_iter()
+            .map(AdminTokenConfigFile::into_config)
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(|admin_tokens| {
+                AdminAuth::validate_tokens(self.auth_token.as_deref(), &admin_tokens)?;
+                Ok(admin_tokens)
+            })
+            .map_err(|e| format!("Invalid connection_options.admin_tokens: {e}"))?;
+        let nat_protocol = match self.nat_protocol.as_deref() {
+            None | Some("auto") => None,
+            Some(protocol) => Some(
+                NatProtocol::from_str(protocol)
+                    .map_err(|e| format!("Invalid connection_options.nat_protocol: {e}"))?,
+            ),
+        };
+        let nat_gateway = self
+            .nat_gateway
+            .map(|gateway| {
+                gateway
+                    .parse::<Ipv4Addr>()
+                    .map_err(|e| format!("Invalid connection_options.nat_gateway: {e}"))
+            })
+            .transpose()?;
         let default = ConnectionOptions::default();
         Ok(ConnectionOptions {
             read_only_call_limit,
+            read_only_map_entry_limit,
             inbox_maxlen: self
                 .inbox_maxlen
                 .unwrap_or_else(|| HELIUM_DEFAULT_CONNECTION_OPTIONS.inbox_maxlen),
@@ -2419,6 +3513,7 @@ impl ConnectionOptionsFile {
             antientropy_public: self.antientropy_public.unwrap_or(true),
             private_neighbors: self.private_neighbors.unwrap_or(false),
             auth_token: self.auth_token,
+            admin_tokens,
             antientropy_retry: self.antientropy_retry.unwrap_or(default.antientropy_retry),
             reject_blocks_pushed: self
                 .reject_blocks_pushed
@@ -2439,6 +3534,42 @@ impl ConnectionOptionsFile {
             block_proposal_max_age_secs: self
                 .block_proposal_max_age_secs
                 .unwrap_or(DEFAULT_BLOCK_PROPOSAL_MAX_AGE_SECS),
+            rpc_rate_limits,
+            rpc_cors_policy,
+            health_max_burn_block_lag: self
+                .health_max_burn_block_lag
+                .unwrap_or(default.health_max_burn_block_lag),
+            health_max_tenure_lag: self
+                .health_max_tenure_lag
+                .unwrap_or(default.health_max_tenure_lag),
+            stale_tip_timeout_secs: self
+                .stale_tip_timeout_secs
+                .unwrap_or(default.stale_tip_timeout_secs),
+            tx_broadcast_max_outbound: self
+                .tx_broadcast_max_outbound
+                .unwrap_or(default.tx_broadcast_max_outbound),
+            tx_broadcast_max_inbound: self
+                .tx_broadcast_max_inbound
+                .unwrap_or(default.tx_broadcast_max_inbound),
+            tx_push_max_miner_neighbors: self
+                .tx_push_max_miner_neighbors
+                .unwrap_or(default.tx_push_max_miner_neighbors),
+            nakamoto_sigcheck_threads: self
+                .nakamoto_sigcheck_threads
+                .unwrap_or(default.nakamoto_sigcheck_threads)
+                .max(1),
+            rpc_read_replicas: self.rpc_read_replicas.unwrap_or(default.rpc_read_replicas),
+            p2p_compression: self.p2p_compression.unwrap_or(default.p2p_compression),
+            p2p_compression_min_len: self
+                .p2p_compression_min_len
+                .unwrap_or(default.p2p_compression_min_len),
+            nat_traversal: self.nat_traversal.unwrap_or(default.nat_traversal),
+            nat_protocol,
+            nat_gateway,
+            nat_mapping_lifetime: self
+                .nat_mapping_lifetime
+                .unwrap_or(default.nat_mapping_lifetime)
+                .max(1),
             ..default
         })
     }
@@ -2451,8 +3582,15 @@ pub struct NodeConfigFile {
     pub seed: Option<String>,
     pub deny_nodes: Option<String>,
     pub working_dir: Option<String>,
+    /// Address to accept RPC connections on, or a comma-separated list of addresses (e.g.
+    /// `"0.0.0.0:20443,[::]:20443"` for a dual-stack node).  The first is the primary address.
     pub rpc_bind: Option<String>,
+    /// Address to accept p2p connections on, or a comma-separated list of addresses.  The first
+    /// is the primary address.
     pub p2p_bind: Option<String>,
+    /// Public p2p address, or a comma-separated list of public addresses.  The first is
+    /// advertised to peers by default; the others are advertised to peers of a different address
+    /// family.
     pub p2p_address: Option<String>,
     pub data_url: Option<String>,
     pub bootstrap_node: Option<String>,
@@ -2470,11 +3608,87 @@ pub struct NodeConfigFile {
     pub prometheus_bind: Option<String>,
     pub marf_cache_strategy: Option<String>,
     pub marf_defer_hashing: Option<bool>,
+    /// Reuse ancestor trie root hashes across consecutive MARF commits.  Defaults to false.
+    pub marf_batch_ancestor_hashes: Option<bool>,
     pub pox_sync_sample_secs: Option<u64>,
     pub use_test_genesis_chainstate: Option<bool>,
     pub always_use_affirmation_maps: Option<bool>,
     pub require_affirmed_anchor_blocks: Option<bool>,
     pub assume_present_anchor_blocks: Option<bool>,
+    /// Where to obtain PoX reward sets from: `onchain` (the default), a `file://` path to a JSON
+    /// object mapping reward cycles to reward sets, or an `http://` URL serving
+    /// `GET <url>/<reward cycle>`
+    pub reward_set_source: Option<String>,
+    /// Record the execution cost charged to each contract in each block, and serve the totals
+    /// from `/v3/metrics/contract-costs`.  Defaults to false.
+    pub contract_cost_tracking: Option<bool>,
+    /// How many Stacks block heights of per-contract costs to keep.  Defaults to 10,000.
+    pub contract_cost_retention: Option<u64>,
+    /// Record the receipt (result, events, and execution cost) of every processed transaction,
+    /// and serve them from `/v3/transactions/:txid/receipt`.  Also enables the event counts of
+    /// `/v3/blocks/:block_id/summary`.  Defaults to false.
+    pub receipt_tracking: Option<bool>,
+    /// How many Stacks block heights of transaction receipts to keep.  Defaults to 10,000.
+    pub receipt_retention: Option<u64>,
+    /// Record the data vars and map entries written by each processed block, and the net change
+    /// it made to each STX and fungible token balance, and serve them from
+    /// `/v3/blocks/:block_id/state-diff`.  Defaults to false.
+    pub state_diff_tracking: Option<bool>,
+    /// How many Stacks block heights of block state diffs to keep.  Defaults to 10,000.
+    pub state_diff_retention: Option<u64>,
+    /// Discard Clarity state older than `prune_horizon` Stacks blocks, keeping only the current
+    /// state and that of recent tips.  The node will not be able to answer queries about older
+    /// state, nor process reorgs deeper than `prune_horizon`.  It will also stop at any block
+    /// that reads discarded state (e.g. with `at-block`), since it cannot tell whether or not
+    /// that block is valid.  Defaults to false.
+    pub prune_mode: Option<bool>,
+    /// How many Stacks block heights of Clarity state to keep when `prune_mode` is set.  Defaults
+    /// to 10,000.
+    pub prune_horizon: Option<u64>,
+    /// The most blocks of its canonical Stacks fork that the node abandons for a better fork
+    /// without operator confirmation.  If a deeper reorg comes along, the node stops processing
+    /// Nakamoto blocks, sends an `alert` event to `alerts` event observers, and waits for
+    /// `POST /v3/admin/reorg/confirm/:block_id`.  Defaults to unset (no limit).
+    pub max_reorg_depth: Option<u64>,
+    /// How many consecutive sortitions without a winning block-commit it takes to send an
+    /// `alert` event to `alerts` event observers.  0 disables the alert.  Defaults to 3.
+    pub chain_quality_missed_sortitions: Option<u64>,
+    /// How many consecutive Nakamoto tenures without any transactions besides their
+    /// tenure-change and coinbase it takes to send an `alert` event.  0 disables the alert.
+    /// Defaults to 3.
+    pub chain_quality_empty_tenures: Option<u64>,
+    /// Send an `alert` event when, on average over the last 10 Nakamoto blocks, the fraction of
+    /// the reward set's signers whose signatures appear in a block falls below this.  Miners stop
+    /// collecting signatures once 70% of the signing weight has signed, so values much higher
+    /// than 0.7 will raise false alarms.  0 disables the alert.  Defaults to 0.7.
+    pub chain_quality_min_signer_participation: Option<f64>,
+    /// How often, in seconds, to snapshot the sortition DB, burnchain DB, chainstate headers DB
+    /// and StackerDB into `backup_dir`.  Backups can also be requested with
+    /// `POST /v3/admin/backup`.  Defaults to 0 (no periodic backups).
+    pub backup_interval: Option<u64>,
+    /// Directory into which backups are rotated.  Defaults to `backups` in the node's working
+    /// directory.
+    pub backup_dir: Option<String>,
+    /// How many backups to keep in `backup_dir`; older ones are deleted.  Defaults to 3.
+    pub backup_retention: Option<u64>,
+    /// On startup, revalidate the mempool transactions that were admitted against a chain tip
+    /// other than the current one, and drop those that are no longer valid.  This delays the
+    /// start of mining by up to `mempool_revalidate_max_txs` admission checks.  Defaults to false.
+    pub mempool_revalidate_on_start: Option<bool>,
+    /// Maximum number of mempool transactions to revalidate on startup, oldest first.  The rest
+    /// are left as they are.  Defaults to 5000.
+    pub mempool_revalidate_max_txs: Option<u64>,
+    /// Run as an archival node: keep every Stacks block, serve whole tenures and ranges of blocks
+    /// from `/v3/archive/tenures/:consensus_hash` and `/v3/archive/blocks`, and advertise this
+    /// to peers so that bootstrapping nodes download from this node first.  Cannot be combined
+    /// with `prune_mode`.  Defaults to false.
+    pub archival: Option<bool>,
+    /// Replicate the `.checkpoints` StackerDB, to which signers write signed (block height,
+    /// block ID) checkpoints, and during initial block download, log an error if the chain
+    /// synced so far disagrees with a checkpoint signed by 70% of the signing weight.  Nodes
+    /// running with `stacker = true` or `miner = true` always replicate `.checkpoints`.
+    /// Defaults to false.
+    pub checkpoint_hints: Option<bool>,
     /// At most, how often should the chain-liveness thread
     ///  wake up the chains-coordinator. Defaults to 300s (5 min).
     pub chain_liveness_poll_time_secs: Option<u64>,
@@ -2482,11 +3696,54 @@ pub struct NodeConfigFile {
     pub stacker_dbs: Option<Vec<String>>,
     /// fault injection: fail to push blocks with this probability (0-100)
     pub fault_injection_block_push_fail_probability: Option<u8>,
+    /// Path to a PEM-encoded certificate chain.  If set along with `tls_key`, the RPC server only
+    /// accepts TLS connections.  Other nodes download blocks from the RPC server over plain HTTP,
+    /// so this is meant for nodes that serve RPC clients rather than the peer network.
+    pub tls_cert: Option<String>,
+    /// Path to the PEM-encoded private key for `tls_cert`
+    pub tls_key: Option<String>,
+    /// Path to a PEM-encoded CA certificate.  If set, p2p sessions use mutual TLS with
+    /// `tls_cert`, and every peer must present a certificate signed by this CA.
+    pub p2p_tls_ca: Option<String>,
+}
+
+/// Split a comma-separated list of socket addresses into its first address, and the parsed
+/// remaining addresses
+fn split_addr_list(setting: &str, addrs: &str) -> Result<(String, Vec<SocketAddr>), String> {
+    let mut addrs = addrs.split(',').map(str::trim);
+    let first = addrs
+        .next()
+        .filter(|addr| !addr.is_empty())
+        .ok_or_else(|| format!("node.{setting} must name at least one address"))?;
+    let rest = addrs
+        .map(|addr| {
+            addr.parse()
+                .map_err(|e| format!("Invalid node.{setting} address '{addr}': {e}"))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((first.to_string(), rest))
 }
 
 impl NodeConfigFile {
     fn into_config_default(self, default_node_config: NodeConfig) -> Result<NodeConfig, String> {
-        let rpc_bind = self.rpc_bind.unwrap_or(default_node_config.rpc_bind);
+        let (rpc_bind, extra_rpc_binds) = match self.rpc_bind {
+            Some(rpc_bind) => split_addr_list("rpc_bind", &rpc_bind)?,
+            None => (
+                default_node_config.rpc_bind,
+                default_node_config.extra_rpc_binds,
+            ),
+        };
+        let (p2p_bind, extra_p2p_binds) = match self.p2p_bind {
+            Some(p2p_bind) => split_addr_list("p2p_bind", &p2p_bind)?,
+            None => (
+                default_node_config.p2p_bind,
+                default_node_config.extra_p2p_binds,
+            ),
+        };
+        let (p2p_address, extra_p2p_addresses) = match self.p2p_address {
+            Some(p2p_address) => split_addr_list("p2p_address", &p2p_address)?,
+            None => (rpc_bind.clone(), default_node_config.extra_p2p_addresses),
+        };
         let miner = self.miner.unwrap_or(default_node_config.miner);
         let stacker = self.stacker.unwrap_or(default_node_config.stacker);
         let node_config = NodeConfig {
@@ -2499,8 +3756,8 @@ impl NodeConfigFile {
             working_dir: std::env::var("STACKS_WORKING_DIR")
                 .unwrap_or(self.working_dir.unwrap_or(default_node_config.working_dir)),
             rpc_bind: rpc_bind.clone(),
-            p2p_bind: self.p2p_bind.unwrap_or(default_node_config.p2p_bind),
-            p2p_address: self.p2p_address.unwrap_or(rpc_bind.clone()),
+            p2p_bind,
+            p2p_address,
             bootstrap_node: vec![],
             deny_nodes: vec![],
             data_url: self
@@ -2546,6 +3803,9 @@ impl NodeConfigFile {
             marf_defer_hashing: self
                 .marf_defer_hashing
                 .unwrap_or(default_node_config.marf_defer_hashing),
+            marf_batch_ancestor_hashes: self
+                .marf_batch_ancestor_hashes
+                .unwrap_or(default_node_config.marf_batch_ancestor_hashes),
             pox_sync_sample_secs: self
                 .pox_sync_sample_secs
                 .unwrap_or(default_node_config.pox_sync_sample_secs),
@@ -2560,6 +3820,64 @@ impl NodeConfigFile {
             // at the start of epoch 3.0, the chain stalls without anchor blocks.
             // only set this to false if you're doing some very extreme testing.
             assume_present_anchor_blocks: true,
+            reward_sets: match self.reward_set_source.as_deref() {
+                Some(source) => {
+                    let source = RewardSetSource::from_str(source)?;
+                    let reward_sets = InjectedRewardSets::new(source)
+                        .map_err(|e| format!("Failed to load node.reward_set_source: {e:?}"))?;
+                    Arc::new(reward_sets)
+                }
+                None => default_node_config.reward_sets,
+            },
+            contract_cost_tracking: self
+                .contract_cost_tracking
+                .unwrap_or(default_node_config.contract_cost_tracking),
+            contract_cost_retention: self
+                .contract_cost_retention
+                .unwrap_or(default_node_config.contract_cost_retention),
+            receipt_tracking: self
+                .receipt_tracking
+                .unwrap_or(default_node_config.receipt_tracking),
+            receipt_retention: self
+                .receipt_retention
+                .unwrap_or(default_node_config.receipt_retention),
+            state_diff_tracking: self
+                .state_diff_tracking
+                .unwrap_or(default_node_config.state_diff_tracking),
+            state_diff_retention: self
+                .state_diff_retention
+                .unwrap_or(default_node_config.state_diff_retention),
+            prune_mode: self.prune_mode.unwrap_or(default_node_config.prune_mode),
+            prune_horizon: self
+                .prune_horizon
+                .unwrap_or(default_node_config.prune_horizon),
+            max_reorg_depth: self.max_reorg_depth.or(default_node_config.max_reorg_depth),
+            chain_quality_missed_sortitions: self
+                .chain_quality_missed_sortitions
+                .unwrap_or(default_node_config.chain_quality_missed_sortitions),
+            chain_quality_empty_tenures: self
+                .chain_quality_empty_tenures
+                .unwrap_or(default_node_config.chain_quality_empty_tenures),
+            chain_quality_min_signer_participation: self
+                .chain_quality_min_signer_participation
+                .unwrap_or(default_node_config.chain_quality_min_signer_participation),
+            backup_interval: self
+                .backup_interval
+                .unwrap_or(default_node_config.backup_interval),
+            backup_dir: self.backup_dir,
+            backup_retention: self
+                .backup_retention
+                .unwrap_or(default_node_config.backup_retention),
+            mempool_revalidate_on_start: self
+                .mempool_revalidate_on_start
+                .unwrap_or(default_node_config.mempool_revalidate_on_start),
+            mempool_revalidate_max_txs: self
+                .mempool_revalidate_max_txs
+                .unwrap_or(default_node_config.mempool_revalidate_max_txs),
+            archival: self.archival.unwrap_or(default_node_config.archival),
+            checkpoint_hints: self
+                .checkpoint_hints
+                .unwrap_or(default_node_config.checkpoint_hints),
             // chainstate fault_injection activation for hide_blocks.
             // you can't set this in the config file.
             fault_injection_hide_blocks: false,
@@ -2580,6 +3898,21 @@ impl NodeConfigFile {
             } else {
                 default_node_config.fault_injection_block_push_fail_probability
             },
+            tls_cert: self
+                .tls_cert
+                .map(PathBuf::from)
+                .or(default_node_config.tls_cert),
+            tls_key: self
+                .tls_key
+                .map(PathBuf::from)
+                .or(default_node_config.tls_key),
+            p2p_tls_ca: self
+                .p2p_tls_ca
+                .map(PathBuf::from)
+                .or(default_node_config.p2p_tls_ca),
+            extra_rpc_binds,
+            extra_p2p_binds,
+            extra_p2p_addresses,
         };
         Ok(node_config)
     }
@@ -2597,6 +3930,102 @@ pub struct FeeEstimationConfigFile {
     pub fee_rate_window_size: Option<u64>,
 }
 
+/// How the miner chooses which of its UTXOs to spend
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CoinSelectionStrategy {
+    /// Only fetch UTXOs which can each fund the transaction alone, and spend the most recent
+    /// first.  This leaves smaller UTXOs (such as old commit change) unspent.
+    #[default]
+    NewestFirst,
+    /// Spend the most recent UTXO, then the largest ones, until the transaction is funded
+    LargestFirst,
+    /// Search for a set of UTXOs whose total is just enough to fund the transaction, so that
+    /// little or no change is left over.  Falls back to `LargestFirst` if there is no such set.
+    BranchAndBound,
+    /// Spend as many of the smallest UTXOs as the transaction can hold, merging them into a
+    /// single change output
+    Consolidate,
+}
+
+impl FromStr for CoinSelectionStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "newest-first" => Ok(Self::NewestFirst),
+            "largest-first" => Ok(Self::LargestFirst),
+            "branch-and-bound" => Ok(Self::BranchAndBound),
+            "consolidate" => Ok(Self::Consolidate),
+            other => Err(format!(
+                "Invalid miner.utxo_selection '{other}': expected 'newest-first', 'largest-first', 'branch-and-bound', or 'consolidate'"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CoinSelectionStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::NewestFirst => "newest-first",
+            Self::LargestFirst => "largest-first",
+            Self::BranchAndBound => "branch-and-bound",
+            Self::Consolidate => "consolidate",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// How the miner paces its wait for signatures on a block proposal
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SignatureWaitMode {
+    /// Use `signature_initial_wait` and `signature_poll_interval` as configured
+    #[default]
+    Fixed,
+    /// Wait about as long as the signers have recently taken to sign a block before the first
+    /// check, and poll more often when they are fast.  Falls back to `Fixed` until there is
+    /// enough history.
+    Adaptive,
+}
+
+impl FromStr for SignatureWaitMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fixed" => Ok(Self::Fixed),
+            "adaptive" => Ok(Self::Adaptive),
+            other => Err(format!(
+                "Invalid miner.signature_wait_mode '{other}': expected 'fixed' or 'adaptive'"
+            )),
+        }
+    }
+}
+
+/// What the miner does with a block proposal that reaches `signature_max_wait` with some, but
+/// not enough, signing weight
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PartialThresholdAction {
+    /// Give up on the proposal
+    #[default]
+    Abandon,
+    /// Keep waiting, until the rejection timeout or a new burnchain tip
+    KeepWaiting,
+}
+
+impl FromStr for PartialThresholdAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "abandon" => Ok(Self::Abandon),
+            "keep-waiting" => Ok(Self::KeepWaiting),
+            other => Err(format!(
+                "Invalid miner.signature_partial_threshold '{other}': expected 'abandon' or 'keep-waiting'"
+            )),
+        }
+    }
+}
+
 #[derive(Clone, Deserialize, Default, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct MinerConfigFile {
@@ -2626,12 +4055,33 @@ pub struct MinerConfigFile {
     pub min_time_between_blocks_ms: Option<u64>,
     pub first_rejection_pause_ms: Option<u64>,
     pub subsequent_rejection_pause_ms: Option<u64>,
+    pub max_reproposals: Option<u32>,
     pub block_commit_delay_ms: Option<u64>,
     pub tenure_cost_limit_per_block_percentage: Option<u8>,
+    pub nonce_chain_fee_rate: Option<bool>,
+    pub candidate_count: Option<u32>,
     pub tenure_extend_poll_secs: Option<u64>,
     pub tenure_timeout_secs: Option<u64>,
     pub tenure_extend_cost_threshold: Option<u64>,
     pub block_rejection_timeout_steps: Option<HashMap<String, u64>>,
+    pub max_commit_fee_rate: Option<u64>,
+    pub commit_rbf_increment: Option<u64>,
+    pub utxo_selection: Option<String>,
+    pub utxo_consolidation_fee_rate: Option<u64>,
+    pub utxo_consolidation_min_count: Option<u64>,
+    pub failover_lease_path: Option<String>,
+    pub failover_node_id: Option<String>,
+    pub failover_lease_ttl_secs: Option<u64>,
+    pub commit_bid_auto: Option<bool>,
+    pub commit_bid_min_sats: Option<u64>,
+    pub commit_bid_max_sats: Option<u64>,
+    pub commit_bid_window: Option<u64>,
+    pub commit_bid_target_win_pct: Option<u8>,
+    pub signature_wait_mode: Option<String>,
+    pub signature_initial_wait_ms: Option<u64>,
+    pub signature_poll_interval_ms: Option<u64>,
+    pub signature_max_wait_ms: Option<u64>,
+    pub signature_partial_threshold: Option<String>,
 }
 
 impl MinerConfigFile {
@@ -2665,6 +4115,55 @@ impl MinerConfigFile {
             } else {
                 miner_default_config.tenure_cost_limit_per_block_percentage
             };
+        if self.candidate_count == Some(0) {
+            return Err("miner.candidate_count must be at least 1".to_string());
+        }
+        if self.max_commit_fee_rate == Some(0) {
+            return Err("miner.max_commit_fee_rate must be positive".to_string());
+        }
+        if self.commit_rbf_increment == Some(0) {
+            return Err("miner.commit_rbf_increment must be positive".to_string());
+        }
+        if self
+            .utxo_consolidation_min_count
+            .is_some_and(|count| count < 2)
+        {
+            return Err("miner.utxo_consolidation_min_count must be at least 2".to_string());
+        }
+        if self.failover_lease_path.is_some()
+            && self.failover_node_id.as_deref().unwrap_or("").is_empty()
+        {
+            return Err(
+                "miner.failover_node_id must be set when miner.failover_lease_path is set"
+                    .to_string(),
+            );
+        }
+        if self.failover_lease_ttl_secs == Some(0) {
+            return Err("miner.failover_lease_ttl_secs must be positive".to_string());
+        }
+        let commit_bid_min_sats = self
+            .commit_bid_min_sats
+            .unwrap_or(miner_default_config.commit_bid_min_sats);
+        if self
+            .commit_bid_max_sats
+            .is_some_and(|max_sats| max_sats < commit_bid_min_sats)
+        {
+            return Err(
+                "miner.commit_bid_max_sats must be at least miner.commit_bid_min_sats".to_string(),
+            );
+        }
+        if self.commit_bid_window == Some(0) {
+            return Err("miner.commit_bid_window must be positive".to_string());
+        }
+        if self
+            .commit_bid_target_win_pct
+            .is_some_and(|pct| pct == 0 || pct >= 100)
+        {
+            return Err("miner.commit_bid_target_win_pct must be between 1 and 99".to_string());
+        }
+        if self.signature_poll_interval_ms == Some(0) {
+            return Err("miner.signature_poll_interval_ms must be positive".to_string());
+        }
         Ok(MinerConfig {
             first_attempt_time_ms: self
                 .first_attempt_time_ms
@@ -2770,11 +4269,27 @@ impl MinerConfigFile {
             }).unwrap_or(miner_default_config.min_time_between_blocks_ms),
             first_rejection_pause_ms: self.first_rejection_pause_ms.unwrap_or(miner_default_config.first_rejection_pause_ms),
             subsequent_rejection_pause_ms: self.subsequent_rejection_pause_ms.unwrap_or(miner_default_config.subsequent_rejection_pause_ms),
+            max_reproposals: self.max_reproposals.unwrap_or(miner_default_config.max_reproposals),
             block_commit_delay: self.block_commit_delay_ms.map(Duration::from_millis).unwrap_or(miner_default_config.block_commit_delay),
             tenure_cost_limit_per_block_percentage,
+            nonce_chain_fee_rate: self.nonce_chain_fee_rate.unwrap_or(miner_default_config.nonce_chain_fee_rate),
+            candidate_count: self.candidate_count.unwrap_or(miner_default_config.candidate_count),
             tenure_extend_poll_secs: self.tenure_extend_poll_secs.map(Duration::from_secs).unwrap_or(miner_default_config.tenure_extend_poll_secs),
             tenure_timeout: self.tenure_timeout_secs.map(Duration::from_secs).unwrap_or(miner_default_config.tenure_timeout),
             tenure_extend_cost_threshold: self.tenure_extend_cost_threshold.unwrap_or(miner_default_config.tenure_extend_cost_threshold),
+            max_commit_fee_rate: self.max_commit_fee_rate.or(miner_default_config.max_commit_fee_rate),
+            commit_rbf_increment: self.commit_rbf_increment.or(miner_default_config.commit_rbf_increment),
+            utxo_selection: self.utxo_selection.as_deref().map(CoinSelectionStrategy::from_str).transpose()?.unwrap_or(miner_default_config.utxo_selection),
+            utxo_consolidation_fee_rate: self.utxo_consolidation_fee_rate.or(miner_default_config.utxo_consolidation_fee_rate),
+            utxo_consolidation_min_count: self.utxo_consolidation_min_count.unwrap_or(miner_default_config.utxo_consolidation_min_count),
+            failover_lease_path: self.failover_lease_path.or(miner_default_config.failover_lease_path),
+            failover_node_id: self.failover_node_id.or(miner_default_config.failover_node_id),
+            failover_lease_ttl: self.failover_lease_ttl_secs.map(Duration::from_secs).unwrap_or(miner_default_config.failover_lease_ttl),
+            commit_bid_auto: self.commit_bid_auto.unwrap_or(miner_default_config.commit_bid_auto),
+            commit_bid_min_sats,
+            commit_bid_max_sats: self.commit_bid_max_sats.or(miner_default_config.commit_bid_max_sats),
+            commit_bid_window: self.commit_bid_window.unwrap_or(miner_default_config.commit_bid_window),
+            commit_bid_target_win_pct: self.commit_bid_target_win_pct.unwrap_or(miner_default_config.commit_bid_target_win_pct),
 
             block_rejection_timeout_steps: {
                 if let Some(block_rejection_timeout_items) = self.block_rejection_timeout_steps {
@@ -2792,7 +4307,12 @@ impl MinerConfigFile {
                 } else{
                     miner_default_config.block_rejection_timeout_steps
                 }
-            }
+            },
+            signature_wait_mode: self.signature_wait_mode.as_deref().map(SignatureWaitMode::from_str).transpose()?.unwrap_or(miner_default_config.signature_wait_mode),
+            signature_initial_wait: self.signature_initial_wait_ms.map(Duration::from_millis).unwrap_or(miner_default_config.signature_initial_wait),
+            signature_poll_interval: self.signature_poll_interval_ms.map(Duration::from_millis).unwrap_or(miner_default_config.signature_poll_interval),
+            signature_max_wait: self.signature_max_wait_ms.map(Duration::from_millis).or(miner_default_config.signature_max_wait),
+            signature_partial_threshold: self.signature_partial_threshold.as_deref().map(PartialThresholdAction::from_str).transpose()?.unwrap_or(miner_default_config.signature_partial_threshold),
         })
     }
 }
@@ -2826,6 +4346,61 @@ impl AtlasConfigFile {
     }
 }
 
+#[derive(Clone, Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DNSSeedsConfigFile {
+    /// Seed nodes, as `PUBKEY@HOST:PORT`
+    pub hosts: Option<Vec<String>>,
+    /// Paths to JSON seed lists signed by a seed operator
+    pub seed_lists: Option<Vec<String>>,
+    /// Hex-encoded ed25519 public keys of the seed operators whose lists we trust
+    pub operator_keys: Option<Vec<String>>,
+    /// How often to re-resolve the seeds, in seconds
+    pub refresh_interval_secs: Option<u64>,
+}
+
+impl DNSSeedsConfigFile {
+    /// Add the configured seeds to `connection_options`.  Seed lists must be signed by one of
+    /// `operator_keys`; expired lists are skipped with a warning.
+    fn load_into(&self, connection_options: &mut ConnectionOptions) -> Result<(), String> {
+        for host in self.hosts.iter().flatten() {
+            connection_options.dns_seeds.push(host.parse()?);
+        }
+
+        let seed_lists = self.seed_lists.as_deref().unwrap_or_default();
+        let operator_keys = self
+            .operator_keys
+            .iter()
+            .flatten()
+            .map(|key| parse_operator_key(key))
+            .collect::<Result<Vec<_>, _>>()?;
+        if !seed_lists.is_empty() && operator_keys.is_empty() {
+            return Err(
+                "dns_seeds.seed_lists requires at least one dns_seeds.operator_keys".into(),
+            );
+        }
+
+        let now = get_epoch_time_secs();
+        for path in seed_lists.iter() {
+            let seed_list = SignedSeedList::load(Path::new(path))?;
+            if seed_list.expires_at <= now {
+                warn!("Ignoring expired DNS seed list";
+                      "path" => path, "expires_at" => seed_list.expires_at);
+                continue;
+            }
+            let seeds = seed_list
+                .verify(&operator_keys, now)
+                .map_err(|e| format!("Invalid DNS seed list {path}: {e}"))?;
+            connection_options.dns_seeds.extend(seeds);
+        }
+
+        if let Some(val) = self.refresh_interval_secs {
+            connection_options.dns_seed_interval = val;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Deserialize, Default, Debug, Hash, PartialEq, Eq, PartialOrd)]
 #[serde(deny_unknown_fields)]
 pub struct EventObserverConfigFile {
@@ -2854,6 +4429,11 @@ pub enum EventKeyType {
     MinedMicroblocks,
     StackerDBChunks,
     BlockProposal,
+    RawBlocks,
+    RewardCycles,
+    Alerts,
+    BurnchainSync,
+    MinerReports,
 }
 
 impl EventKeyType {
@@ -2886,6 +4466,26 @@ impl EventKeyType {
             return Some(EventKeyType::BlockProposal);
         }
 
+        if raw_key == "raw_blocks" {
+            return Some(EventKeyType::RawBlocks);
+        }
+
+        if raw_key == "reward_cycles" {
+            return Some(EventKeyType::RewardCycles);
+        }
+
+        if raw_key == "alerts" {
+            return Some(EventKeyType::Alerts);
+        }
+
+        if raw_key == "burnchain_sync" {
+            return Some(EventKeyType::BurnchainSync);
+        }
+
+        if raw_key == "miner_reports" {
+            return Some(EventKeyType::MinerReports);
+        }
+
         let comps: Vec<_> = raw_key.split("::").collect();
         if comps.len() == 1 {
             let split: Vec<_> = comps[0].split('.').collect();
@@ -2923,6 +4523,211 @@ impl EventKeyType {
     }
 }
 
+#[derive(Clone, Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfigFile {
+    /// URL template to POST each event to.  Occurrences of `{event}`, `{burn_block_height}`,
+    /// `{burn_block_hash}` and `{consensus_hash}` are replaced with the event's values.  Only
+    /// `http://` URLs are supported.
+    pub url: String,
+    /// Which events to fire on: any of `burn_block`, `new_tenure`, `missed_sortition`, `reorg`
+    /// and `block_accepted`.  Defaults to all of the burnchain events (i.e. all but
+    /// `block_accepted`, which only a miner fires).
+    pub events: Option<Vec<String>>,
+    /// If set, each payload is signed with HMAC-SHA256 under this secret, and the hex-encoded
+    /// signature is sent in the `X-Stacks-Signature` header as `sha256=<signature>`.
+    pub secret: Option<String>,
+    /// How long to wait for the webhook to respond.  Defaults to 1,000ms.
+    pub timeout_ms: Option<u64>,
+}
+
+impl WebhookConfigFile {
+    fn into_config(self) -> Result<WebhookConfig, String> {
+        if !self.url.starts_with("http://") {
+            return Err(format!("webhook.url must be an http:// URL: {}", &self.url));
+        }
+        let events = match self.events {
+            Some(events) => events
+                .iter()
+                .map(|e| {
+                    WebhookEventType::from_str(e)
+                        .ok_or_else(|| format!("Unknown webhook event type: {e}"))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => WebhookEventType::BURNCHAIN.to_vec(),
+        };
+        Ok(WebhookConfig {
+            url: self.url,
+            events,
+            secret: self.secret,
+            timeout_ms: self.timeout_ms.unwrap_or(1_000),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WebhookConfig {
+    pub url: String,
+    pub events: Vec<WebhookEventType>,
+    pub secret: Option<String>,
+    pub timeout_ms: u64,
+}
+
+/// Events that can fire a webhook
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum WebhookEventType {
+    /// A new burnchain block was processed
+    BurnBlock,
+    /// A burnchain block selected a miner, starting a new tenure
+    NewTenure,
+    /// A burnchain block did not select a miner
+    MissedSortition,
+    /// The canonical burnchain fork changed
+    Reorg,
+    /// A block proposed by this node's miner gathered the signature threshold
+    BlockAccepted,
+}
+
+impl WebhookEventType {
+    pub const ALL: [WebhookEventType; 5] = [
+        WebhookEventType::BurnBlock,
+        WebhookEventType::NewTenure,
+        WebhookEventType::MissedSortition,
+        WebhookEventType::Reorg,
+        WebhookEventType::BlockAccepted,
+    ];
+
+    /// The events fired by the webhook thread as the burnchain tip changes
+    pub const BURNCHAIN: [WebhookEventType; 4] = [
+        WebhookEventType::BurnBlock,
+        WebhookEventType::NewTenure,
+        WebhookEventType::MissedSortition,
+        WebhookEventType::Reorg,
+    ];
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(raw_key: &str) -> Option<WebhookEventType> {
+        Self::ALL.into_iter().find(|e| e.as_str() == raw_key)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::BurnBlock => "burn_block",
+            WebhookEventType::NewTenure => "new_tenure",
+            WebhookEventType::MissedSortition => "missed_sortition",
+            WebhookEventType::Reorg => "reorg",
+            WebhookEventType::BlockAccepted => "block_accepted",
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct StackerDBStandbyConfigFile {
+    /// RPC URL of the standby node, e.g. `"http://standby.local:20443"`.  Only `http://` URLs are
+    /// supported.
+    pub url: String,
+    /// The standby node's `connection_options.auth_token`
+    pub auth_token: String,
+    /// StackerDB contracts whose chunk writes are replicated to the standby
+    pub contracts: Vec<String>,
+    /// How long to wait for the standby to store each chunk.  Defaults to 1,000ms.
+    pub timeout_ms: Option<u64>,
+}
+
+impl StackerDBStandbyConfigFile {
+    fn into_config(self) -> Result<StackerDBStandbyConfig, String> {
+        if !self.url.starts_with("http://") {
+            return Err(format!(
+                "stackerdb_standby.url must be an http:// URL: {}",
+                &self.url
+            ));
+        }
+        if self.contracts.is_empty() {
+            return Err(format!(
+                "stackerdb_standby.contracts must not be empty for {}",
+                &self.url
+            ));
+        }
+        let contracts = self
+            .contracts
+            .iter()
+            .map(|contract| {
+                QualifiedContractIdentifier::parse(contract)
+                    .map_err(|e| format!("Invalid stackerdb_standby contract {contract}: {e:?}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(StackerDBStandbyConfig {
+            url: self.url,
+            auth_token: self.auth_token,
+            contracts,
+            timeout_ms: self.timeout_ms.unwrap_or(1_000),
+        })
+    }
+}
+
+#[derive(Clone, Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ContractFeeFloorConfigFile {
+    /// The contract, e.g. `"SP000000000000000000002Q6VF78.pox-4"`
+    pub contract: String,
+    /// The lowest fee (in uSTX) a transaction that calls or deploys `contract` may pay
+    pub min_tx_fee: u64,
+}
+
+#[derive(Clone, Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MempoolAdmissionConfigFile {
+    /// Transactions that call or deploy these contracts are rejected
+    pub deny_contracts: Option<Vec<String>>,
+    /// Transactions that call or deploy these contracts are admitted with a zero fee rate, so
+    /// that miners consider them after every other transaction
+    pub deprioritize_contracts: Option<Vec<String>>,
+    /// Per-contract minimum transaction fees
+    pub contract_fee_floor: Option<Vec<ContractFeeFloorConfigFile>>,
+}
+
+impl MempoolAdmissionConfigFile {
+    fn into_config(self) -> Result<ContractRulesPolicy, String> {
+        let parse_contract = |contract: &String| {
+            QualifiedContractIdentifier::parse(contract)
+                .map_err(|e| format!("Invalid mempool_admission contract {contract}: {e:?}"))
+        };
+        let deny_contracts = self
+            .deny_contracts
+            .unwrap_or_default()
+            .iter()
+            .map(parse_contract)
+            .collect::<Result<HashSet<_>, _>>()?;
+        let deprioritize_contracts = self
+            .deprioritize_contracts
+            .unwrap_or_default()
+            .iter()
+            .map(parse_contract)
+            .collect::<Result<HashSet<_>, _>>()?;
+        let contract_fee_floors = self
+            .contract_fee_floor
+            .unwrap_or_default()
+            .iter()
+            .map(|floor| Ok((parse_contract(&floor.contract)?, floor.min_tx_fee)))
+            .collect::<Result<HashMap<_, _>, String>>()?;
+        Ok(ContractRulesPolicy {
+            deny_contracts,
+            deprioritize_contracts,
+            contract_fee_floors,
+        })
+    }
+}
+
+/// A standby node to which StackerDB chunk writes are replicated
+#[derive(Clone, Debug, PartialEq)]
+pub struct StackerDBStandbyConfig {
+    pub url: String,
+    pub auth_token: String,
+    pub contracts: Vec<QualifiedContractIdentifier>,
+    pub timeout_ms: u64,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct InitialBalance {
     pub address: PrincipalData,
@@ -2938,6 +4743,7 @@ pub struct InitialBalanceFile {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
     use std::path::Path;
 
     use super::*;
@@ -2955,6 +4761,102 @@ mod tests {
         assert!(ConfigFile::from_str("").is_ok());
     }
 
+    #[test]
+    fn test_config_profiles() {
+        let content = r#"
+            [node]
+            rpc_bind = "0.0.0.0:20443"
+            p2p_bind = "0.0.0.0:20444"
+
+            [burnchain]
+            mode = "mainnet"
+            peer_host = "127.0.0.1"
+
+            [[events_observer]]
+            endpoint = "localhost:3700"
+            events_keys = ["*"]
+
+            [profile.testnet.node]
+            bootstrap_node = "029266faff4c8e0ca4f934f34996a96af481df94a89b0c9bd515f3536a95682ddc@seed.testnet.hiro.so:30444"
+
+            [profile.testnet.burnchain]
+            mode = "krypton"
+            peer_port = 18444
+
+            [profile.custom]
+            inherits = "testnet"
+
+            [profile.custom.node]
+            rpc_bind = "0.0.0.0:30443"
+
+            [[profile.custom.events_observer]]
+            endpoint = "127.0.0.1:30000"
+            events_keys = ["stackerdb"]
+        "#;
+
+        // without a profile, only the top-level settings apply
+        let config = ConfigFile::from_str(content).unwrap();
+        let burnchain = config.burnchain.unwrap();
+        assert_eq!(burnchain.mode.as_deref(), Some("mainnet"));
+        assert_eq!(burnchain.peer_port, None);
+        assert!(config.node.unwrap().bootstrap_node.is_none());
+
+        let config = ConfigFile::from_str_with_profile(content, Some("testnet")).unwrap();
+        let burnchain = config.burnchain.unwrap();
+        assert_eq!(burnchain.mode.as_deref(), Some("krypton"));
+        assert_eq!(burnchain.peer_port, Some(18444));
+        // settings the profile does not override are inherited from the top level
+        assert_eq!(burnchain.peer_host.as_deref(), Some("127.0.0.1"));
+        let node = config.node.unwrap();
+        assert_eq!(node.rpc_bind.as_deref(), Some("0.0.0.0:20443"));
+        assert!(node.bootstrap_node.is_some());
+        let observers = config.events_observer.unwrap();
+        assert_eq!(observers.len(), 1);
+        assert!(observers.iter().any(|o| o.endpoint == "localhost:3700"));
+
+        let config = ConfigFile::from_str_with_profile(content, Some("custom")).unwrap();
+        assert_eq!(config.burnchain.unwrap().mode.as_deref(), Some("krypton"));
+        let node = config.node.unwrap();
+        assert_eq!(node.rpc_bind.as_deref(), Some("0.0.0.0:30443"));
+        assert_eq!(node.p2p_bind.as_deref(), Some("0.0.0.0:20444"));
+        assert!(node.bootstrap_node.is_some());
+        // arrays are replaced, not appended to
+        let observers = config.events_observer.unwrap();
+        assert_eq!(observers.len(), 1);
+        assert!(observers.iter().any(|o| o.endpoint == "127.0.0.1:30000"));
+
+        assert_eq!(
+            ConfigFile::from_str_with_profile(content, Some("regtest")).unwrap_err(),
+            "No such profile: `regtest`"
+        );
+        assert_eq!(
+            ConfigFile::from_str_with_profile("", Some("testnet")).unwrap_err(),
+            "No such profile: `testnet`"
+        );
+
+        let cyclic = r#"
+            [profile.a]
+            inherits = "b"
+            [profile.b]
+            inherits = "a"
+        "#;
+        assert_eq!(
+            ConfigFile::from_str_with_profile(cyclic, Some("a")).unwrap_err(),
+            "Invalid profile `a`: profile `a` inherits from itself"
+        );
+
+        let unknown_field = r#"
+            [profile.testnet.node]
+            unknown_field = "test"
+        "#;
+        assert!(ConfigFile::from_str(unknown_field).is_ok());
+        assert!(
+            ConfigFile::from_str_with_profile(unknown_field, Some("testnet"))
+                .unwrap_err()
+                .starts_with("Invalid toml: unknown field `unknown_field`")
+        );
+    }
+
     #[test]
     fn test_config() {
         assert_eq!(
@@ -3213,88 +5115,1132 @@ mod tests {
     }
 
     #[test]
-    fn should_load_affirmation_map() {
-        let affirmation_string = "nnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnppnnnnnnnnnnnnnnnnnnnnnnnnpppppnnnnnnnnnnnnnnnnnnnnnnnpppppppppppppppnnnnnnnnnnnnnnnnnnnnnnnppppppppppnnnnnnnnnnnnnnnnnnnppppnnnnnnnnnnnnnnnnnnnnnnnppppppppnnnnnnnnnnnnnnnnnnnnnnnppnppnnnnnnnnnnnnnnnnnnnnnnnppppnnnnnnnnnnnnnnnnnnnnnnnnnppppppnnnnnnnnnnnnnnnnnnnnnnnnnppnnnnnnnnnnnnnnnnnnnnnnnnnpppppppnnnnnnnnnnnnnnnnnnnnnnnnnnpnnnnnnnnnnnnnnnnnnnnnnnnnpppnppppppppppppppnnppppnpa";
-        let affirmation =
-            AffirmationMap::decode(affirmation_string).expect("Failed to decode affirmation map");
+    fn should_load_rpc_rate_limits() {
         let config = Config::from_config_file(
-            ConfigFile::from_str(&format!(
+            ConfigFile::from_str(
                 r#"
-                    [[burnchain.affirmation_overrides]]
-                    reward_cycle = 413
-                    affirmation = "{affirmation_string}"
-                "#
-            ))
-            .expect("Expected to be able to parse config file from string"),
+                [connection_options]
+                rpc_rate_limit_read = 50
+                rpc_rate_limit_read_heavy = 5
+                rpc_rate_limit_write = 0
+                rpc_rate_limit_burst_secs = 20
+                rpc_rate_limit_ipv4_prefix = 24
+                rpc_max_held_requests = 16
+                "#,
+            )
+            .unwrap(),
             false,
         )
-        .expect("Expected to be able to parse affirmation map from file");
+        .expect("Expected to be able to parse RPC rate limits from file");
 
-        assert_eq!(config.burnchain.affirmation_overrides.len(), 1);
-        assert_eq!(config.burnchain.affirmation_overrides.get(&0), None);
         assert_eq!(
-            config.burnchain.affirmation_overrides.get(&413),
-            Some(&affirmation)
+            config.connection_options.rpc_rate_limits,
+            HttpRateLimitConfig {
+                read: Some(50),
+                read_heavy: Some(5),
+                write: None,
+                admin: None,
+                burst_secs: 20,
+                ipv4_prefix_len: 24,
+                ipv6_prefix_len: 64,
+                max_held_requests: 16,
+            }
         );
-    }
-
-    #[test]
-    fn should_fail_to_load_invalid_affirmation_map() {
-        let bad_affirmation_string = "bad_map";
-        let file = ConfigFile::from_str(&format!(
-            r#"
-                    [[burnchain.affirmation_overrides]]
-                    reward_cycle = 1
-                    affirmation = "{bad_affirmation_string}"
-                "#
-        ))
-        .expect("Expected to be able to parse config file from string");
 
-        assert!(Config::from_config_file(file, false).is_err());
+        let err = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [connection_options]
+                rpc_rate_limit_ipv6_prefix = 129
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.contains("rpc_rate_limit_ipv6_prefix"));
     }
 
     #[test]
-    fn should_load_empty_affirmation_map() {
-        let config = Config::from_config_file(
-            ConfigFile::from_str(r#""#)
-                .expect("Expected to be able to parse config file from string"),
+    fn should_reject_unloadable_reward_set_source() {
+        let err = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [node]
+                reward_set_source = "file:///nonexistent/reward-sets.json"
+                "#,
+            )
+            .unwrap(),
             false,
         )
-        .expect("Expected to be able to parse affirmation map from file");
+        .unwrap_err();
+        assert!(err.contains("reward_set_source"));
 
-        assert!(config.burnchain.affirmation_overrides.is_empty());
+        let config = Config::from_config_file(ConfigFile::from_str("").unwrap(), false).unwrap();
+        assert!(config.node.reward_sets.source().is_on_chain());
     }
 
     #[test]
-    fn should_include_xenon_default_affirmation_overrides() {
+    fn should_load_cors_origins() {
+        let config = Config::from_config_file(ConfigFile::from_str("").unwrap(), false)
+            .expect("Expected to be able to parse an empty config");
+        assert_eq!(
+            config.connection_options.rpc_cors_policy,
+            HttpCorsPolicy::default()
+        );
+
         let config = Config::from_config_file(
             ConfigFile::from_str(
                 r#"
-                [burnchain]
-                chain = "bitcoin"
-                mode = "xenon"
+                [connection_options]
+                cors_origins = ["https://Wallet.example.com/", "http://localhost:3000"]
                 "#,
             )
-            .expect("Expected to be able to parse config file from string"),
+            .unwrap(),
             false,
         )
-        .expect("Expected to be able to parse affirmation map from file");
-        // Should default add xenon affirmation overrides
-        assert_eq!(config.burnchain.affirmation_overrides.len(), 5);
+        .expect("Expected to be able to parse CORS origins from file");
+        assert_eq!(
+            config.connection_options.rpc_cors_policy.allowed_origins,
+            vec![
+                "https://wallet.example.com".to_string(),
+                "http://localhost:3000".to_string()
+            ]
+        );
+
+        let err = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [connection_options]
+                cors_origins = ["wallet.example.com"]
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.contains("cors_origins"));
     }
 
     #[test]
-    fn should_override_xenon_default_affirmation_overrides() {
-        let affirmation_string = "aaapnnnnnnnnnnnnnnnnnnnnnnnnnnnnppnnnnnnnnnnnnnnnnnnnnnnnnpppppnnnnnnnnnnnnnnnnnnnnnnnpppppppppppppppnnnnnnnnnnnnnnnnnnnnnnnppppppppppnnnnnnnnnnnnnnnnnnnppppnnnnnnnnnnnnnnnnnnnnnnnppppppppnnnnnnnnnnnnnnnnnnnnnnnppnppnnnnnnnnnnnnnnnnnnnnnnnppppnnnnnnnnnnnnnnnnnnnnnnnnnppppppnnnnnnnnnnnnnnnnnnnnnnnnnppnnnnnnnnnnnnnnnnnnnnnnnnnpppppppnnnnnnnnnnnnnnnnnnnnnnnnnnpnnnnnnnnnnnnnnnnnnnnnnnnnpppnppppppppppppppnnppppnpa";
-        let affirmation =
-            AffirmationMap::decode(affirmation_string).expect("Failed to decode affirmation map");
-
+    fn should_load_admin_tokens() {
         let config = Config::from_config_file(
-            ConfigFile::from_str(&format!(
+            ConfigFile::from_str(
                 r#"
-                [burnchain]
-                chain = "bitcoin"
-                mode = "xenon"
+                [connection_options]
+                auth_token = "root"
+
+                [[connection_options.admin_tokens]]
+                name = "backups"
+                token = "hunter2"
+                roles = ["backup"]
+
+                [[connection_options.admin_tokens]]
+                name = "ops"
+                token = "correct-horse"
+                roles = ["peers", "mempool", "mining"]
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse admin tokens from file");
+        let admin_tokens = &config.connection_options.admin_tokens;
+        assert_eq!(admin_tokens.len(), 2);
+        assert_eq!(admin_tokens[0].name, "backups");
+        assert_eq!(
+            admin_tokens[0].roles,
+            BTreeSet::from_iter([AdminRole::Backup])
+        );
+        assert_eq!(
+            admin_tokens[1].roles,
+            BTreeSet::from_iter([AdminRole::Peers, AdminRole::Mempool, AdminRole::Mining])
+        );
+
+        for (tokens, expected_err) in [
+            (
+                r#"
+                [[connection_options.admin_tokens]]
+                name = "ops"
+                token = "hunter2"
+                roles = ["root"]
+                "#,
+                "unknown admin role 'root'",
+            ),
+            (
+                r#"
+                [[connection_options.admin_tokens]]
+                name = "ops"
+                token = "hunter2"
+                roles = []
+                "#,
+                "has no roles",
+            ),
+            (
+                r#"
+                [[connection_options.admin_tokens]]
+                name = "ops"
+                token = "hunter2"
+                roles = ["peers"]
+
+                [[connection_options.admin_tokens]]
+                name = "ops"
+                token = "correct-horse"
+                roles = ["mining"]
+                "#,
+                "duplicate token name 'ops'",
+            ),
+            (
+                r#"
+                [[connection_options.admin_tokens]]
+                name = "ops"
+                token = "root"
+                roles = ["peers"]
+                "#,
+                "reuses the secret",
+            ),
+        ] {
+            let err = Config::from_config_file(
+                ConfigFile::from_str(&format!(
+                    r#"
+                    [connection_options]
+                    auth_token = "root"
+                    {tokens}
+                    "#
+                ))
+                .unwrap(),
+                false,
+            )
+            .unwrap_err();
+            assert!(err.contains("admin_tokens"), "{err}");
+            assert!(err.contains(expected_err), "{err}");
+        }
+    }
+
+    #[test]
+    fn should_reload_connection_options() {
+        let path = std::env::temp_dir().join(format!(
+            "should_reload_connection_options-{}.toml",
+            rand::random::<u64>()
+        ));
+        let config_path = path.display().to_string();
+        fs::write(
+            &path,
+            r#"
+            [connection_options]
+            auth_token = "root"
+            "#,
+        )
+        .unwrap();
+        let config =
+            Config::from_config_file(ConfigFile::from_path(&config_path).unwrap(), false).unwrap();
+        let reloader = ConfigFileReloader::new(&config);
+        assert!(config.connection_options.admin_tokens.is_empty());
+
+        // the reloader sees the file as it is now
+        fs::write(
+            &path,
+            r#"
+            [connection_options]
+            auth_token = "root"
+
+            [[connection_options.admin_tokens]]
+            name = "ops"
+            token = "hunter2"
+            roles = ["config"]
+            "#,
+        )
+        .unwrap();
+        let conn_opts = reloader.reload_connection_options().unwrap();
+        assert_eq!(conn_opts.auth_token.as_deref(), Some("root"));
+        assert_eq!(conn_opts.admin_tokens.len(), 1);
+        assert_eq!(conn_opts.admin_tokens[0].name, "ops");
+        assert_eq!(
+            conn_opts.admin_tokens[0].roles,
+            BTreeSet::from_iter([AdminRole::Config])
+        );
+
+        // an invalid file is refused
+        fs::write(
+            &path,
+            r#"
+            [[connection_options.admin_tokens]]
+            name = "ops"
+            token = "hunter2"
+            roles = ["root"]
+            "#,
+        )
+        .unwrap();
+        let err = reloader.reload_connection_options().unwrap_err();
+        assert!(err.contains("unknown admin role 'root'"), "{err}");
+
+        // a node not started from a config file has nothing to reload
+        let reloader = ConfigFileReloader::new(&Config::default());
+        assert!(reloader.reload_connection_options().is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn should_load_read_only_map_entry_limit() {
+        let config = Config::from_config_file(ConfigFile::from_str("").unwrap(), false)
+            .expect("Expected to be able to parse an empty config");
+        assert_eq!(
+            config.connection_options.read_only_map_entry_limit,
+            ConnectionOptions::default().read_only_map_entry_limit
+        );
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [connection_options]
+                read_only_call_limit_read_count = 50
+                read_only_map_entry_limit_read_length = 4096
+                read_only_map_entry_limit_read_count = 1
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse read-only limits from file");
+        assert_eq!(
+            config.connection_options.read_only_map_entry_limit,
+            ExecutionCost {
+                write_length: 0,
+                write_count: 0,
+                read_length: 4096,
+                read_count: 1,
+                runtime: 0,
+            }
+        );
+        // the call-read budget is configured separately
+        assert_eq!(
+            config.connection_options.read_only_call_limit.read_count,
+            50
+        );
+    }
+
+    #[test]
+    fn should_load_health_thresholds() {
+        let config = Config::from_config_file(ConfigFile::from_str("").unwrap(), false)
+            .expect("Expected to be able to parse an empty config");
+        let default = ConnectionOptions::default();
+        assert_eq!(
+            config.connection_options.health_max_burn_block_lag,
+            default.health_max_burn_block_lag
+        );
+        assert_eq!(
+            config.connection_options.health_max_tenure_lag,
+            default.health_max_tenure_lag
+        );
+        assert_eq!(
+            config.connection_options.stale_tip_timeout_secs,
+            default.stale_tip_timeout_secs
+        );
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [connection_options]
+                health_max_burn_block_lag = 6
+                health_max_tenure_lag = 1
+                stale_tip_timeout_secs = 0
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse health thresholds from file");
+        assert_eq!(config.connection_options.health_max_burn_block_lag, 6);
+        assert_eq!(config.connection_options.health_max_tenure_lag, 1);
+        assert_eq!(config.connection_options.stale_tip_timeout_secs, 0);
+    }
+
+    #[test]
+    fn should_load_tx_broadcast_options() {
+        let default = ConnectionOptions::default();
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [connection_options]
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse default config");
+        assert_eq!(
+            config.connection_options.tx_broadcast_max_outbound,
+            default.tx_broadcast_max_outbound
+        );
+        assert_eq!(
+            config.connection_options.tx_broadcast_max_inbound,
+            default.tx_broadcast_max_inbound
+        );
+        assert_eq!(
+            config.connection_options.tx_push_max_miner_neighbors,
+            default.tx_push_max_miner_neighbors
+        );
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [connection_options]
+                tx_broadcast_max_outbound = 4
+                tx_broadcast_max_inbound = 2
+                tx_push_max_miner_neighbors = 0
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse tx broadcast options from file");
+        assert_eq!(config.connection_options.tx_broadcast_max_outbound, 4);
+        assert_eq!(config.connection_options.tx_broadcast_max_inbound, 2);
+        assert_eq!(config.connection_options.tx_push_max_miner_neighbors, 0);
+    }
+
+    #[test]
+    fn test_nakamoto_sigcheck_threads() {
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [connection_options]
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse default config");
+        assert_eq!(config.connection_options.nakamoto_sigcheck_threads, 4);
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [connection_options]
+                nakamoto_sigcheck_threads = 0
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse nakamoto_sigcheck_threads from file");
+        assert_eq!(config.connection_options.nakamoto_sigcheck_threads, 1);
+    }
+
+    #[test]
+    fn test_marf_batch_ancestor_hashes() {
+        let config = Config::from_config_file(ConfigFile::from_str("").unwrap(), false)
+            .expect("Expected to be able to parse an empty config");
+        assert!(!config.node.marf_batch_ancestor_hashes);
+        assert!(!config.node.get_marf_opts().batch_ancestor_hashes);
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [node]
+                marf_batch_ancestor_hashes = true
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse marf_batch_ancestor_hashes from file");
+        assert!(config.node.marf_batch_ancestor_hashes);
+        assert!(config.node.get_marf_opts().batch_ancestor_hashes);
+    }
+
+    #[test]
+    fn test_rpc_read_replicas() {
+        let config = Config::from_config_file(ConfigFile::from_str("").unwrap(), false)
+            .expect("Expected to be able to parse an empty config");
+        assert_eq!(config.connection_options.rpc_read_replicas, 0);
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [connection_options]
+                rpc_read_replicas = 3
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse rpc_read_replicas from file");
+        assert_eq!(config.connection_options.rpc_read_replicas, 3);
+    }
+
+    #[test]
+    fn test_p2p_compression() {
+        let config = Config::from_config_file(ConfigFile::from_str("").unwrap(), false)
+            .expect("Expected to be able to parse an empty config");
+        assert!(!config.connection_options.p2p_compression);
+        assert_eq!(config.connection_options.p2p_compression_min_len, 4096);
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [connection_options]
+                p2p_compression = true
+                p2p_compression_min_len = 1024
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse p2p compression options from file");
+        assert!(config.connection_options.p2p_compression);
+        assert_eq!(config.connection_options.p2p_compression_min_len, 1024);
+    }
+
+    #[test]
+    fn test_nat_traversal() {
+        let config = Config::from_config_file(ConfigFile::from_str("").unwrap(), false)
+            .expect("Expected to be able to parse an empty config");
+        assert!(!config.connection_options.nat_traversal);
+        assert_eq!(config.connection_options.nat_protocol, None);
+        assert_eq!(config.connection_options.nat_gateway, None);
+        assert_eq!(config.connection_options.nat_mapping_lifetime, 3600);
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [connection_options]
+                nat_traversal = true
+                nat_protocol = "natpmp"
+                nat_gateway = "192.168.1.1"
+                nat_mapping_lifetime = 600
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse NAT traversal options from file");
+        assert!(config.connection_options.nat_traversal);
+        assert_eq!(
+            config.connection_options.nat_protocol,
+            Some(NatProtocol::Natpmp)
+        );
+        assert_eq!(
+            config.connection_options.nat_gateway,
+            Some(Ipv4Addr::new(192, 168, 1, 1))
+        );
+        assert_eq!(config.connection_options.nat_mapping_lifetime, 600);
+
+        let err = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [connection_options]
+                nat_protocol = "pcp"
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.contains("Invalid connection_options.nat_protocol"));
+    }
+
+    #[test]
+    fn test_epoch_schedule_config() {
+        let epochs_conf = r#"
+            [burnchain]
+            mode = "krypton"
+            pox_reward_length = 20
+            pox_prepare_length = 5
+
+            [[burnchain.epochs]]
+            epoch_name = "1.0"
+            start_height = 0
+
+            [[burnchain.epochs]]
+            epoch_name = "2.0"
+            start_height = 0
+
+            [[burnchain.epochs]]
+            epoch_name = "2.05"
+            start_height = 1
+
+            [[burnchain.epochs]]
+            epoch_name = "2.1"
+            start_height = 2
+            pox_unlock_height = 4
+
+            [[burnchain.epochs]]
+            epoch_name = "2.2"
+            start_height = 3
+
+            [[burnchain.epochs]]
+            epoch_name = "2.3"
+            start_height = 4
+
+            [[burnchain.epochs]]
+            epoch_name = "2.4"
+            start_height = 5
+
+            [[burnchain.epochs]]
+            epoch_name = "2.5"
+            start_height = 6
+            pox_activation_height = 7
+            pox_unlock_height = 9
+
+            [[burnchain.epochs]]
+            epoch_name = "3.0"
+            start_height = 41
+
+            [[burnchain.epochs]]
+            epoch_name = "3.1"
+            start_height = 50
+            block_limit = { runtime = 1000, write_count = 10 }
+            "#;
+        let config = Config::from_config_file(ConfigFile::from_str(epochs_conf).unwrap(), false)
+            .expect("Expected to be able to parse the epoch schedule from file");
+
+        let epochs = config.burnchain.epochs.as_ref().unwrap();
+        let epoch_30 = epochs.get(StacksEpochId::Epoch30).unwrap();
+        assert_eq!(epoch_30.start_height, 41);
+        assert_eq!(epoch_30.end_height, 50);
+        let epoch_31 = epochs.get(StacksEpochId::Epoch31).unwrap();
+        assert_eq!(epoch_31.start_height, 50);
+        assert_eq!(epoch_31.block_limit.runtime, 1000);
+        assert_eq!(epoch_31.block_limit.write_count, 10);
+        assert_eq!(
+            epoch_31.block_limit.read_count,
+            epoch_30.block_limit.read_count
+        );
+
+        assert_eq!(
+            config.burnchain.get_epoch_pox(StacksEpochId::Epoch21),
+            EpochPoxConfig {
+                activation_height: None,
+                unlock_height: Some(4),
+            }
+        );
+        assert_eq!(
+            config.burnchain.get_epoch_pox(StacksEpochId::Epoch22),
+            EpochPoxConfig::default()
+        );
+
+        let burnchain = config.get_burnchain();
+        assert_eq!(burnchain.pox_constants.v1_unlock_height, 4);
+        assert_eq!(burnchain.pox_constants.v2_unlock_height, 4);
+        assert_eq!(burnchain.pox_constants.pox_3_activation_height, 5);
+        assert_eq!(burnchain.pox_constants.pox_4_activation_height, 7);
+        assert_eq!(burnchain.pox_constants.v3_unlock_height, 9);
+
+        // PoX heights may only be set on epochs that define them
+        let bad_conf = epochs_conf.replace(
+            "epoch_name = \"2.3\"\n            start_height = 4",
+            "epoch_name = \"2.3\"\n            start_height = 4\n            pox_unlock_height = 5",
+        );
+        assert!(Config::from_config_file(ConfigFile::from_str(&bad_conf).unwrap(), false).is_err());
+
+        // PoX activation heights must fall within their epoch
+        let bad_conf =
+            epochs_conf.replace("pox_activation_height = 7", "pox_activation_height = 45");
+        assert!(Config::from_config_file(ConfigFile::from_str(&bad_conf).unwrap(), false).is_err());
+
+        // Unknown block limit dimensions are rejected
+        let bad_conf = epochs_conf.replace("write_count = 10", "write_counts = 10");
+        assert!(ConfigFile::from_str(&bad_conf).is_err());
+    }
+
+    #[test]
+    fn should_load_mempool_revalidate_on_start() {
+        let config = Config::from_config_file(ConfigFile::from_str("").unwrap(), false)
+            .expect("Expected to be able to parse an empty config");
+        assert!(!config.node.mempool_revalidate_on_start);
+        assert_eq!(config.node.mempool_revalidate_max_txs, 5_000);
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [node]
+                mempool_revalidate_on_start = true
+                mempool_revalidate_max_txs = 100
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse mempool_revalidate_on_start from file");
+        assert!(config.node.mempool_revalidate_on_start);
+        assert_eq!(config.node.mempool_revalidate_max_txs, 100);
+    }
+
+    #[test]
+    fn should_load_checkpoint_hints() {
+        let checkpoints_contract_id = boot_code_id(CHECKPOINTS_NAME, false);
+        let config = Config::from_config_file(ConfigFile::from_str("").unwrap(), false)
+            .expect("Expected to be able to parse an empty config");
+        assert!(!config.node.checkpoint_hints);
+        assert!(!config.node.stacker_dbs.contains(&checkpoints_contract_id));
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [node]
+                checkpoint_hints = true
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse checkpoint_hints from file");
+        assert!(config.node.checkpoint_hints);
+        assert!(config.node.stacker_dbs.contains(&checkpoints_contract_id));
+
+        // stacker nodes replicate .checkpoints regardless, so that signers can write to it
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [node]
+                stacker = true
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse stacker from file");
+        assert!(!config.node.checkpoint_hints);
+        assert!(config.node.stacker_dbs.contains(&checkpoints_contract_id));
+    }
+
+    #[test]
+    fn should_load_mempool_admission() {
+        let config = Config::from_config_file(ConfigFile::from_str("").unwrap(), false)
+            .expect("Expected to be able to parse an empty config");
+        assert!(config.mempool_admission.is_empty());
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [mempool_admission]
+                deny_contracts = ["ST000000000000000000002AMW42H.denied"]
+                deprioritize_contracts = ["ST000000000000000000002AMW42H.spammy"]
+
+                [[mempool_admission.contract_fee_floor]]
+                contract = "ST000000000000000000002AMW42H.pricey"
+                min_tx_fee = 10000
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse mempool_admission from file");
+        let contract = |name| {
+            QualifiedContractIdentifier::parse(&format!("ST000000000000000000002AMW42H.{name}"))
+                .unwrap()
+        };
+        assert_eq!(
+            config.mempool_admission.deny_contracts,
+            HashSet::from([contract("denied")])
+        );
+        assert_eq!(
+            config.mempool_admission.deprioritize_contracts,
+            HashSet::from([contract("spammy")])
+        );
+        assert_eq!(
+            config.mempool_admission.contract_fee_floors,
+            HashMap::from([(contract("pricey"), 10000)])
+        );
+
+        let err = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [mempool_admission]
+                deny_contracts = ["not-a-contract"]
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.contains("Invalid mempool_admission contract"));
+    }
+
+    #[test]
+    fn should_load_archival_mode() {
+        let config = Config::from_config_file(ConfigFile::from_str("").unwrap(), false)
+            .expect("Expected to be able to parse an empty config");
+        assert!(!config.node.archival);
+        assert!(!config.connection_options.archival);
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [node]
+                archival = true
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse archival from file");
+        assert!(config.node.archival);
+        assert!(config.connection_options.archival);
+
+        let err = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [node]
+                archival = true
+                prune_mode = true
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.contains("node.archival"));
+    }
+
+    #[test]
+    fn should_load_tls_config() {
+        let tls_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/net/tests/tls");
+        let config = Config::from_config_file(
+            ConfigFile::from_str(&format!(
+                r#"
+                [node]
+                tls_cert = "{cert}"
+                tls_key = "{key}"
+                p2p_tls_ca = "{ca}"
+                "#,
+                cert = tls_dir.join("node.pem").display(),
+                key = tls_dir.join("node.key").display(),
+                ca = tls_dir.join("ca.pem").display(),
+            ))
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to load TLS certificates");
+        assert!(config.connection_options.rpc_tls.is_some());
+        assert!(config.connection_options.p2p_tls.is_some());
+
+        let err = Config::from_config_file(
+            ConfigFile::from_str(&format!(
+                r#"
+                [node]
+                tls_cert = "{cert}"
+                "#,
+                cert = tls_dir.join("node.pem").display(),
+            ))
+            .unwrap(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.contains("must be set together"), "{err}");
+    }
+
+    #[test]
+    fn should_load_multiple_bind_addresses() {
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [node]
+                rpc_bind = "0.0.0.0:20443, [::]:20443"
+                p2p_bind = "0.0.0.0:20444,[::]:20444"
+                p2p_address = "1.2.3.4:20444,[2001:db8::1]:20444"
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to load multiple bind addresses");
+        assert_eq!(config.node.rpc_bind, "0.0.0.0:20443");
+        assert_eq!(config.node.p2p_bind, "0.0.0.0:20444");
+        assert_eq!(config.node.p2p_address, "1.2.3.4:20444");
+        assert_eq!(config.node.data_url, "http://0.0.0.0:20443");
+        assert_eq!(
+            config.node.get_rpc_bind_addrs(),
+            vec![
+                "0.0.0.0:20443".parse::<SocketAddr>().unwrap(),
+                "[::]:20443".parse().unwrap()
+            ]
+        );
+        assert_eq!(
+            config.node.get_p2p_bind_addrs(),
+            vec![
+                "0.0.0.0:20444".parse::<SocketAddr>().unwrap(),
+                "[::]:20444".parse().unwrap()
+            ]
+        );
+        assert_eq!(
+            config.connection_options.extra_p2p_addresses,
+            vec![(
+                PeerAddress::from_socketaddr(&"[2001:db8::1]:20444".parse().unwrap()),
+                20444
+            )]
+        );
+
+        let err = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [node]
+                p2p_bind = "0.0.0.0:20444,localhost"
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.contains("Invalid node.p2p_bind address"), "{err}");
+    }
+
+    #[test]
+    fn should_load_dns_seeds() {
+        let operator_key = ed25519_dalek::SigningKey::from_bytes(&[0x11; 32]);
+        let untrusted_key = ed25519_dalek::SigningKey::from_bytes(&[0x22; 32]);
+        let seed_pubkey = Secp256k1PublicKey::from_private(&Secp256k1PrivateKey::random());
+        let now = get_epoch_time_secs();
+
+        let dir =
+            std::env::temp_dir().join(format!("should_load_dns_seeds-{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        let write_list = |name: &str, list: &SignedSeedList| {
+            let path = dir.join(name);
+            fs::write(&path, serde_json::to_string(list).unwrap()).unwrap();
+            path.display().to_string()
+        };
+        let good_list = write_list(
+            "good.json",
+            &SignedSeedList::sign(
+                vec![format!("{}@seed.example.com:20444", seed_pubkey.to_hex())],
+                now + 3600,
+                &operator_key,
+            ),
+        );
+        let expired_list = write_list(
+            "expired.json",
+            &SignedSeedList::sign(
+                vec![format!(
+                    "{}@expired.example.com:20444",
+                    seed_pubkey.to_hex()
+                )],
+                now - 1,
+                &operator_key,
+            ),
+        );
+        let untrusted_list = write_list(
+            "untrusted.json",
+            &SignedSeedList::sign(
+                vec![format!("{}@evil.example.com:20444", seed_pubkey.to_hex())],
+                now + 3600,
+                &untrusted_key,
+            ),
+        );
+        let operator_key_hex = to_hex(operator_key.verifying_key().as_bytes());
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(&format!(
+                r#"
+                [dns_seeds]
+                hosts = ["{}@[2001:db8::1]:20444"]
+                seed_lists = ["{good_list}", "{expired_list}"]
+                operator_keys = ["{operator_key_hex}"]
+                refresh_interval_secs = 600
+                "#,
+                seed_pubkey.to_hex()
+            ))
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to load DNS seeds");
+        let hosts: Vec<_> = config
+            .connection_options
+            .dns_seeds
+            .iter()
+            .map(|seed| (seed.host.as_str(), seed.port))
+            .collect();
+        assert_eq!(
+            hosts,
+            vec![("2001:db8::1", 20444), ("seed.example.com", 20444)]
+        );
+        assert!(config
+            .connection_options
+            .dns_seeds
+            .iter()
+            .all(|seed| seed.public_key == seed_pubkey));
+        assert_eq!(config.connection_options.dns_seed_interval, 600);
+
+        let err = Config::from_config_file(
+            ConfigFile::from_str(&format!(
+                r#"
+                [dns_seeds]
+                seed_lists = ["{untrusted_list}"]
+                operator_keys = ["{operator_key_hex}"]
+                "#
+            ))
+            .unwrap(),
+            false,
+        )
+        .unwrap_err();
+        assert!(
+            err.contains("not signed by a trusted operator key"),
+            "{err}"
+        );
+
+        let err = Config::from_config_file(
+            ConfigFile::from_str(&format!(
+                r#"
+                [dns_seeds]
+                seed_lists = ["{good_list}"]
+                "#
+            ))
+            .unwrap(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.contains("requires at least one"), "{err}");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn should_load_affirmation_map() {
+        let affirmation_string = "nnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnppnnnnnnnnnnnnnnnnnnnnnnnnpppppnnnnnnnnnnnnnnnnnnnnnnnpppppppppppppppnnnnnnnnnnnnnnnnnnnnnnnppppppppppnnnnnnnnnnnnnnnnnnnppppnnnnnnnnnnnnnnnnnnnnnnnppppppppnnnnnnnnnnnnnnnnnnnnnnnppnppnnnnnnnnnnnnnnnnnnnnnnnppppnnnnnnnnnnnnnnnnnnnnnnnnnppppppnnnnnnnnnnnnnnnnnnnnnnnnnppnnnnnnnnnnnnnnnnnnnnnnnnnpppppppnnnnnnnnnnnnnnnnnnnnnnnnnnpnnnnnnnnnnnnnnnnnnnnnnnnnpppnppppppppppppppnnppppnpa";
+        let affirmation =
+            AffirmationMap::decode(affirmation_string).expect("Failed to decode affirmation map");
+        let config = Config::from_config_file(
+            ConfigFile::from_str(&format!(
+                r#"
+                    [[burnchain.affirmation_overrides]]
+                    reward_cycle = 413
+                    affirmation = "{affirmation_string}"
+                "#
+            ))
+            .expect("Expected to be able to parse config file from string"),
+            false,
+        )
+        .expect("Expected to be able to parse affirmation map from file");
+
+        assert_eq!(config.burnchain.affirmation_overrides.len(), 1);
+        assert_eq!(config.burnchain.affirmation_overrides.get(&0), None);
+        assert_eq!(
+            config.burnchain.affirmation_overrides.get(&413),
+            Some(&affirmation)
+        );
+    }
+
+    #[test]
+    fn should_fail_to_load_invalid_affirmation_map() {
+        let bad_affirmation_string = "bad_map";
+        let file = ConfigFile::from_str(&format!(
+            r#"
+                    [[burnchain.affirmation_overrides]]
+                    reward_cycle = 1
+                    affirmation = "{bad_affirmation_string}"
+                "#
+        ))
+        .expect("Expected to be able to parse config file from string");
+
+        assert!(Config::from_config_file(file, false).is_err());
+    }
+
+    #[test]
+    fn should_load_empty_affirmation_map() {
+        let config = Config::from_config_file(
+            ConfigFile::from_str(r#""#)
+                .expect("Expected to be able to parse config file from string"),
+            false,
+        )
+        .expect("Expected to be able to parse affirmation map from file");
+
+        assert!(config.burnchain.affirmation_overrides.is_empty());
+    }
+
+    #[test]
+    fn should_load_anchor_block_overrides() {
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [[burnchain.anchor_block_overrides]]
+                reward_cycle = 413
+                status = "reject"
+
+                [[burnchain.anchor_block_overrides]]
+                reward_cycle = 414
+                status = "accept"
+                "#,
+            )
+            .expect("Expected to be able to parse config file from string"),
+            false,
+        )
+        .expect("Expected to be able to parse anchor block overrides from file");
+
+        assert_eq!(config.burnchain.anchor_block_overrides.len(), 2);
+        assert_eq!(
+            config.burnchain.anchor_block_overrides.get(&413),
+            Some(&AnchorBlockOverrideStatus::Reject)
+        );
+        assert_eq!(
+            config.burnchain.anchor_block_overrides.get(&414),
+            Some(&AnchorBlockOverrideStatus::Accept)
+        );
+    }
+
+    #[test]
+    fn should_fail_to_load_invalid_anchor_block_overrides() {
+        for overrides in [
+            // unknown status
+            r#"
+            [[burnchain.anchor_block_overrides]]
+            reward_cycle = 413
+            status = "present"
+            "#,
+            // no anchor block in reward cycle 0
+            r#"
+            [[burnchain.anchor_block_overrides]]
+            reward_cycle = 0
+            status = "reject"
+            "#,
+            // conflicting overrides
+            r#"
+            [[burnchain.anchor_block_overrides]]
+            reward_cycle = 413
+            status = "reject"
+
+            [[burnchain.anchor_block_overrides]]
+            reward_cycle = 413
+            status = "accept"
+            "#,
+        ] {
+            let file = ConfigFile::from_str(overrides)
+                .expect("Expected to be able to parse config file from string");
+            assert!(Config::from_config_file(file, false).is_err());
+        }
+    }
+
+    #[test]
+    fn should_include_xenon_default_affirmation_overrides() {
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [burnchain]
+                chain = "bitcoin"
+                mode = "xenon"
+                "#,
+            )
+            .expect("Expected to be able to parse config file from string"),
+            false,
+        )
+        .expect("Expected to be able to parse affirmation map from file");
+        // Should default add xenon affirmation overrides
+        assert_eq!(config.burnchain.affirmation_overrides.len(), 5);
+    }
+
+    #[test]
+    fn should_override_xenon_default_affirmation_overrides() {
+        let affirmation_string = "aaapnnnnnnnnnnnnnnnnnnnnnnnnnnnnppnnnnnnnnnnnnnnnnnnnnnnnnpppppnnnnnnnnnnnnnnnnnnnnnnnpppppppppppppppnnnnnnnnnnnnnnnnnnnnnnnppppppppppnnnnnnnnnnnnnnnnnnnppppnnnnnnnnnnnnnnnnnnnnnnnppppppppnnnnnnnnnnnnnnnnnnnnnnnppnppnnnnnnnnnnnnnnnnnnnnnnnppppnnnnnnnnnnnnnnnnnnnnnnnnnppppppnnnnnnnnnnnnnnnnnnnnnnnnnppnnnnnnnnnnnnnnnnnnnnnnnnnpppppppnnnnnnnnnnnnnnnnnnnnnnnnnnpnnnnnnnnnnnnnnnnnnnnnnnnnpppnppppppppppppppnnppppnpa";
+        let affirmation =
+            AffirmationMap::decode(affirmation_string).expect("Failed to decode affirmation map");
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(&format!(
+                r#"
+                [burnchain]
+                chain = "bitcoin"
+                mode = "xenon"
 
                 [[burnchain.affirmation_overrides]]
                 reward_cycle = 413
@@ -3310,6 +6256,489 @@ mod tests {
         assert_eq!(config.burnchain.affirmation_overrides[&413], affirmation);
     }
 
+    #[test]
+    fn test_stackerdb_standby_config() {
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [[stackerdb_standby]]
+                url = "http://standby.local:20443"
+                auth_token = "hunter2"
+                contracts = ["SP000000000000000000002Q6VF78.signers-0-0", "SP000000000000000000002Q6VF78.signers-1-0"]
+
+                [[stackerdb_standby]]
+                url = "http://127.0.0.1:30443"
+                auth_token = "password"
+                contracts = ["SP000000000000000000002Q6VF78.miners"]
+                timeout_ms = 500
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            config.stackerdb_standbys,
+            vec![
+                StackerDBStandbyConfig {
+                    url: "http://standby.local:20443".into(),
+                    auth_token: "hunter2".into(),
+                    contracts: vec![
+                        QualifiedContractIdentifier::parse(
+                            "SP000000000000000000002Q6VF78.signers-0-0"
+                        )
+                        .unwrap(),
+                        QualifiedContractIdentifier::parse(
+                            "SP000000000000000000002Q6VF78.signers-1-0"
+                        )
+                        .unwrap(),
+                    ],
+                    timeout_ms: 1_000,
+                },
+                StackerDBStandbyConfig {
+                    url: "http://127.0.0.1:30443".into(),
+                    auth_token: "password".into(),
+                    contracts: vec![QualifiedContractIdentifier::parse(
+                        "SP000000000000000000002Q6VF78.miners"
+                    )
+                    .unwrap()],
+                    timeout_ms: 500,
+                },
+            ]
+        );
+
+        let err = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [[stackerdb_standby]]
+                url = "http://standby.local:20443"
+                auth_token = "hunter2"
+                contracts = []
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.contains("stackerdb_standby.contracts"));
+
+        let err = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [[stackerdb_standby]]
+                url = "https://standby.local:20443"
+                auth_token = "hunter2"
+                contracts = ["SP000000000000000000002Q6VF78.miners"]
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.contains("stackerdb_standby.url"));
+    }
+
+    #[test]
+    fn test_webhook_config() {
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [[webhook]]
+                url = "http://localhost:9000/{event}?height={burn_block_height}"
+
+                [[webhook]]
+                url = "http://localhost:9001/alerts"
+                events = ["missed_sortition", "reorg"]
+                secret = "hunter2"
+                timeout_ms = 500
+
+                [[webhook]]
+                url = "http://localhost:9002/blocks"
+                events = ["block_accepted"]
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            config.webhooks,
+            vec![
+                WebhookConfig {
+                    url: "http://localhost:9000/{event}?height={burn_block_height}".into(),
+                    events: WebhookEventType::BURNCHAIN.to_vec(),
+                    secret: None,
+                    timeout_ms: 1_000,
+                },
+                WebhookConfig {
+                    url: "http://localhost:9001/alerts".into(),
+                    events: vec![WebhookEventType::MissedSortition, WebhookEventType::Reorg],
+                    secret: Some("hunter2".into()),
+                    timeout_ms: 500,
+                },
+                WebhookConfig {
+                    url: "http://localhost:9002/blocks".into(),
+                    events: vec![WebhookEventType::BlockAccepted],
+                    secret: None,
+                    timeout_ms: 1_000,
+                },
+            ]
+        );
+
+        let err = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [[webhook]]
+                url = "http://localhost:9000"
+                events = ["new_block"]
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(err, "Unknown webhook event type: new_block");
+
+        let err = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [[webhook]]
+                url = "https://example.com/hook"
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "webhook.url must be an http:// URL: https://example.com/hook"
+        );
+    }
+
+    #[test]
+    fn test_wallet_mode_config() {
+        let load = |burnchain: &str| {
+            Config::from_config_file(
+                ConfigFile::from_str(&format!("[burnchain]\n{burnchain}")).unwrap(),
+                false,
+            )
+        };
+
+        let config = load("").unwrap();
+        assert_eq!(config.burnchain.wallet_mode, BitcoinWalletMode::Local);
+        assert!(config.burnchain.get_wallet_funding_address().is_none());
+
+        let config = load(
+            r#"
+            wallet_mode = "descriptor"
+            wallet_name = "miner"
+            wallet_funding_address = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.burnchain.wallet_mode, BitcoinWalletMode::Descriptor);
+        assert!(config
+            .burnchain
+            .get_wallet_funding_address()
+            .unwrap()
+            .is_segwit_p2wpkh());
+
+        let err = load(r#"wallet_mode = "hot""#).unwrap_err();
+        assert_eq!(
+            err,
+            "Invalid burnchain.wallet_mode 'hot': expected 'local', 'descriptor', or 'external'"
+        );
+
+        let err = load(
+            r#"
+            wallet_mode = "descriptor"
+            wallet_funding_address = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"
+            "#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "burnchain.wallet_name is required unless wallet_mode is 'local'"
+        );
+
+        let err = load(
+            r#"
+            wallet_mode = "descriptor"
+            wallet_name = "miner"
+            wallet_funding_address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+            "#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "burnchain.wallet_funding_address 'bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4' is for the wrong network"
+        );
+
+        let err = load(
+            r#"
+            wallet_mode = "external"
+            wallet_name = "miner"
+            wallet_funding_address = "mgbpit8FvkVJ9kuXY8QSM5P7eibnhcEMBk"
+            "#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "burnchain.wallet_signer_command is required when wallet_mode is 'external'"
+        );
+
+        let config = load(
+            r#"
+            wallet_mode = "external"
+            wallet_name = "miner"
+            wallet_funding_address = "mgbpit8FvkVJ9kuXY8QSM5P7eibnhcEMBk"
+            wallet_signer_command = "hsm-signer --key miner"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.burnchain.wallet_mode, BitcoinWalletMode::External);
+        assert_eq!(
+            config.burnchain.wallet_signer_command.as_deref(),
+            Some("hsm-signer --key miner")
+        );
+    }
+
+    #[test]
+    fn test_utxo_selection_config() {
+        let load = |miner: &str| {
+            Config::from_config_file(
+                ConfigFile::from_str(&format!(
+                    "[miner]\nmining_key = \"{}\"\n{miner}",
+                    "01".repeat(32)
+                ))
+                .unwrap(),
+                false,
+            )
+        };
+
+        let config = load("").unwrap();
+        assert_eq!(
+            config.miner.utxo_selection,
+            CoinSelectionStrategy::NewestFirst
+        );
+        assert!(config.miner.utxo_consolidation_fee_rate.is_none());
+        assert_eq!(
+            config.miner.utxo_consolidation_min_count,
+            DEFAULT_UTXO_CONSOLIDATION_MIN_COUNT
+        );
+
+        let config = load(
+            r#"
+            utxo_selection = "branch-and-bound"
+            utxo_consolidation_fee_rate = 5
+            utxo_consolidation_min_count = 4
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.miner.utxo_selection,
+            CoinSelectionStrategy::BranchAndBound
+        );
+        assert_eq!(config.miner.utxo_consolidation_fee_rate, Some(5));
+        assert_eq!(config.miner.utxo_consolidation_min_count, 4);
+
+        for strategy in [
+            CoinSelectionStrategy::NewestFirst,
+            CoinSelectionStrategy::LargestFirst,
+            CoinSelectionStrategy::BranchAndBound,
+            CoinSelectionStrategy::Consolidate,
+        ] {
+            assert_eq!(
+                CoinSelectionStrategy::from_str(&strategy.to_string()),
+                Ok(strategy)
+            );
+        }
+
+        let err = load(r#"utxo_selection = "random""#).unwrap_err();
+        assert_eq!(
+            err,
+            "Invalid miner.utxo_selection 'random': expected 'newest-first', 'largest-first', 'branch-and-bound', or 'consolidate'"
+        );
+
+        let err = load("utxo_consolidation_min_count = 1").unwrap_err();
+        assert_eq!(err, "miner.utxo_consolidation_min_count must be at least 2");
+    }
+
+    #[test]
+    fn test_miner_failover_config() {
+        let load = |miner: &str| {
+            Config::from_config_file(
+                ConfigFile::from_str(&format!(
+                    "[miner]\nmining_key = \"{}\"\n{miner}",
+                    "01".repeat(32)
+                ))
+                .unwrap(),
+                false,
+            )
+        };
+
+        let config = load("").unwrap();
+        assert!(config.miner.failover_lease_path.is_none());
+        assert!(config.miner.failover_node_id.is_none());
+        assert_eq!(
+            config.miner.failover_lease_ttl,
+            Duration::from_secs(DEFAULT_FAILOVER_LEASE_TTL_SECS)
+        );
+
+        let config = load(
+            r#"
+            failover_lease_path = "/mnt/shared/miner-lease.sqlite"
+            failover_node_id = "miner-a"
+            failover_lease_ttl_secs = 900
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.miner.failover_lease_path.as_deref(),
+            Some("/mnt/shared/miner-lease.sqlite")
+        );
+        assert_eq!(config.miner.failover_node_id.as_deref(), Some("miner-a"));
+        assert_eq!(config.miner.failover_lease_ttl, Duration::from_secs(900));
+
+        let err = load(r#"failover_lease_path = "/mnt/shared/miner-lease.sqlite""#).unwrap_err();
+        assert_eq!(
+            err,
+            "miner.failover_node_id must be set when miner.failover_lease_path is set"
+        );
+
+        let err = load("failover_lease_ttl_secs = 0").unwrap_err();
+        assert_eq!(err, "miner.failover_lease_ttl_secs must be positive");
+    }
+
+    #[test]
+    fn test_miner_commit_bid_config() {
+        let load = |miner: &str| {
+            Config::from_config_file(
+                ConfigFile::from_str(&format!(
+                    "[miner]\nmining_key = \"{}\"\n{miner}",
+                    "01".repeat(32)
+                ))
+                .unwrap(),
+                false,
+            )
+        };
+
+        let config = load("").unwrap();
+        assert!(!config.miner.commit_bid_auto);
+        assert_eq!(
+            config.miner.commit_bid_min_sats,
+            DEFAULT_COMMIT_BID_MIN_SATS
+        );
+        assert!(config.miner.commit_bid_max_sats.is_none());
+        assert_eq!(config.miner.commit_bid_window, DEFAULT_COMMIT_BID_WINDOW);
+        assert_eq!(
+            config.miner.commit_bid_target_win_pct,
+            DEFAULT_COMMIT_BID_TARGET_WIN_PCT
+        );
+
+        let config = load(
+            r#"
+            commit_bid_auto = true
+            commit_bid_min_sats = 10000
+            commit_bid_max_sats = 50000
+            commit_bid_window = 12
+            commit_bid_target_win_pct = 40
+            "#,
+        )
+        .unwrap();
+        assert!(config.miner.commit_bid_auto);
+        assert_eq!(config.miner.commit_bid_min_sats, 10_000);
+        assert_eq!(config.miner.commit_bid_max_sats, Some(50_000));
+        assert_eq!(config.miner.commit_bid_window, 12);
+        assert_eq!(config.miner.commit_bid_target_win_pct, 40);
+
+        let err = load("commit_bid_min_sats = 10000\ncommit_bid_max_sats = 5000").unwrap_err();
+        assert_eq!(
+            err,
+            "miner.commit_bid_max_sats must be at least miner.commit_bid_min_sats"
+        );
+
+        let err = load("commit_bid_window = 0").unwrap_err();
+        assert_eq!(err, "miner.commit_bid_window must be positive");
+
+        let err = load("commit_bid_target_win_pct = 100").unwrap_err();
+        assert_eq!(
+            err,
+            "miner.commit_bid_target_win_pct must be between 1 and 99"
+        );
+    }
+
+    #[test]
+    fn test_miner_signature_wait_config() {
+        let load = |miner: &str| {
+            Config::from_config_file(
+                ConfigFile::from_str(&format!(
+                    "[miner]\nmining_key = \"{}\"\n{miner}",
+                    "01".repeat(32)
+                ))
+                .unwrap(),
+                false,
+            )
+        };
+
+        let config = load("").unwrap();
+        assert_eq!(config.miner.signature_wait_mode, SignatureWaitMode::Fixed);
+        assert_eq!(
+            config.miner.signature_initial_wait,
+            Duration::from_millis(DEFAULT_SIGNATURE_POLL_INTERVAL_MS)
+        );
+        assert_eq!(
+            config.miner.signature_poll_interval,
+            Duration::from_millis(DEFAULT_SIGNATURE_POLL_INTERVAL_MS)
+        );
+        assert!(config.miner.signature_max_wait.is_none());
+        assert_eq!(
+            config.miner.signature_partial_threshold,
+            PartialThresholdAction::Abandon
+        );
+
+        let config = load(
+            r#"
+            signature_wait_mode = "adaptive"
+            signature_initial_wait_ms = 2000
+            signature_poll_interval_ms = 100
+            signature_max_wait_ms = 60000
+            signature_partial_threshold = "keep-waiting"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.miner.signature_wait_mode,
+            SignatureWaitMode::Adaptive
+        );
+        assert_eq!(
+            config.miner.signature_initial_wait,
+            Duration::from_millis(2000)
+        );
+        assert_eq!(
+            config.miner.signature_poll_interval,
+            Duration::from_millis(100)
+        );
+        assert_eq!(
+            config.miner.signature_max_wait,
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            config.miner.signature_partial_threshold,
+            PartialThresholdAction::KeepWaiting
+        );
+
+        let err = load(r#"signature_wait_mode = "eager""#).unwrap_err();
+        assert_eq!(
+            err,
+            "Invalid miner.signature_wait_mode 'eager': expected 'fixed' or 'adaptive'"
+        );
+
+        let err = load("signature_poll_interval_ms = 0").unwrap_err();
+        assert_eq!(err, "miner.signature_poll_interval_ms must be positive");
+    }
+
     #[test]
     fn test_into_config_default_chain_id() {
         // Helper function to create BurnchainConfigFile with mode and optional chain_id
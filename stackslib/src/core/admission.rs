@@ -0,0 +1,112 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Operator-defined mempool admission policies.
+//!
+//! A `MemPoolAdmissionPolicy` is consulted by `MemPoolDB::submit` for every transaction
+//! submitted to the node.  It can admit the transaction, admit it with a zero fee rate so that
+//! miners consider it last, or reject it.  Rejections are returned to the submitter as
+//! `MemPoolRejection::PolicyRejected`.  `submit` only consults a policy in builds with the
+//! `mempool-admission-policy` feature.
+
+use std::collections::{HashMap, HashSet};
+
+use clarity::vm::types::QualifiedContractIdentifier;
+
+use crate::chainstate::stacks::{StacksTransaction, TransactionPayload};
+
+/// What a policy decided to do with a transaction
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdmissionDecision {
+    /// Admit the transaction as usual
+    Admit,
+    /// Admit the transaction, but consider it after every other transaction.  Carries the
+    /// reason, for logging.
+    Deprioritize(String),
+    /// Don't admit the transaction.  Carries the reason, which is reported to the submitter.
+    Reject(String),
+}
+
+/// An operator-defined rule set for transactions entering the mempool
+pub trait MemPoolAdmissionPolicy: Send {
+    /// Name of the policy, for logs and rejection responses
+    fn name(&self) -> &str;
+    /// Decide what to do with `tx`
+    fn evaluate(&self, tx: &StacksTransaction) -> AdmissionDecision;
+}
+
+/// The contract a transaction calls or deploys, if any
+fn target_contract(tx: &StacksTransaction) -> Option<QualifiedContractIdentifier> {
+    match &tx.payload {
+        TransactionPayload::ContractCall(call) => Some(call.to_clarity_contract_id()),
+        TransactionPayload::SmartContract(smart_contract, _) => {
+            Some(QualifiedContractIdentifier::new(
+                tx.origin_address().into(),
+                smart_contract.name.clone(),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// The policy built from the `[mempool_admission]` config section: per-contract deny lists,
+/// deprioritization lists, and fee floors
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContractRulesPolicy {
+    /// Transactions that call or deploy these contracts are rejected
+    pub deny_contracts: HashSet<QualifiedContractIdentifier>,
+    /// Transactions that call or deploy these contracts are deprioritized
+    pub deprioritize_contracts: HashSet<QualifiedContractIdentifier>,
+    /// Calls to these contracts must pay at least this much (in uSTX)
+    pub contract_fee_floors: HashMap<QualifiedContractIdentifier, u64>,
+}
+
+impl ContractRulesPolicy {
+    /// Whether or not this policy has no rules at all
+    pub fn is_empty(&self) -> bool {
+        self.deny_contracts.is_empty()
+            && self.deprioritize_contracts.is_empty()
+            && self.contract_fee_floors.is_empty()
+    }
+}
+
+impl MemPoolAdmissionPolicy for ContractRulesPolicy {
+    fn name(&self) -> &str {
+        "contract_rules"
+    }
+
+    fn evaluate(&self, tx: &StacksTransaction) -> AdmissionDecision {
+        let Some(contract_id) = target_contract(tx) else {
+            return AdmissionDecision::Admit;
+        };
+        if self.deny_contracts.contains(&contract_id) {
+            return AdmissionDecision::Reject(format!("Contract {contract_id} is denied"));
+        }
+        if let Some(min_tx_fee) = self.contract_fee_floors.get(&contract_id) {
+            if tx.get_tx_fee() < *min_tx_fee {
+                return AdmissionDecision::Reject(format!(
+                    "Transactions for contract {contract_id} must pay a fee of at least {min_tx_fee} uSTX, but this one pays {}",
+                    tx.get_tx_fee()
+                ));
+            }
+        }
+        if self.deprioritize_contracts.contains(&contract_id) {
+            return AdmissionDecision::Deprioritize(format!(
+                "Contract {contract_id} is deprioritized"
+            ));
+        }
+        AdmissionDecision::Admit
+    }
+}
@@ -38,7 +38,7 @@ use stacks_common::codec::{
 use stacks_common::types::chainstate::{BlockHeaderHash, StacksAddress, StacksBlockId};
 use stacks_common::types::sqlite::NO_PARAMS;
 use stacks_common::types::MempoolCollectionBehavior;
-use stacks_common::util::hash::{to_hex, Sha512Trunc256Sum};
+use stacks_common::util::hash::{hex_bytes, to_hex, Sha512Trunc256Sum};
 use stacks_common::util::retry::{BoundReader, RetryReader};
 use stacks_common::util::{get_epoch_time_ms, get_epoch_time_secs};
 
@@ -55,6 +55,8 @@ use crate::chainstate::stacks::{
     Error as ChainstateError, StacksBlock, StacksMicroblock, StacksTransaction, TransactionPayload,
 };
 use crate::clarity_vm::clarity::ClarityConnection;
+#[cfg(feature = "mempool-admission-policy")]
+use crate::core::admission::{AdmissionDecision, MemPoolAdmissionPolicy};
 use crate::core::{
     ExecutionCost, StacksEpochId, FIRST_BURNCHAIN_CONSENSUS_HASH, FIRST_STACKS_BLOCK_HASH,
 };
@@ -74,12 +76,19 @@ use crate::{cost_estimates, monitoring};
 // maximum number of confirmations a transaction can have before it's garbage-collected
 pub static MEMPOOL_MAX_TRANSACTION_AGE: u64 = 256;
 pub static MAXIMUM_MEMPOOL_TX_CHAINING: u64 = 25;
+// maximum number of nonces a sponsored transaction's sponsor nonce can be ahead of the sponsor
+// account's nonce.  This is higher than the origin limit, since a sponsor typically pays for many
+// users' transactions at once.
+pub static MAXIMUM_MEMPOOL_SPONSOR_TX_CHAINING: u64 = 100;
 pub static MEMPOOL_NAKAMOTO_MAX_TRANSACTION_AGE: Duration =
     Duration::from_secs(MEMPOOL_MAX_TRANSACTION_AGE * 10 * 60);
 
 // name of table for storing the counting bloom filter
 pub const BLOOM_COUNTER_TABLE: &str = "txid_bloom_counter";
 
+// name of the directory, next to the mempool DB, that drained transactions are saved in
+pub const MEMPOOL_DRAIN_DIR: &str = "mempool_drains";
+
 // bloom filter error rate
 pub const BLOOM_COUNTER_ERROR_RATE: f64 = 0.001;
 
@@ -94,6 +103,11 @@ pub const BLOOM_COUNTER_DEPTH: usize = 2;
 pub const DEFAULT_BLACKLIST_TIMEOUT: u64 = 24 * 60 * 60 * 2;
 pub const DEFAULT_BLACKLIST_MAX_SIZE: u64 = 134217728; // 2**27 -- the blacklist table can reach at most 4GB at 128 bytes per record
 
+// how long will the miner skip a transaction after signers rejected a block proposal over it?
+// the penalty doubles with each rejection, up to the maximum
+pub const PROPOSAL_REJECTION_BASE_PENALTY: u64 = 60;
+pub const PROPOSAL_REJECTION_MAX_PENALTY: u64 = 60 * 60;
+
 // maximum many tx tags we'll send before sending a bloom filter instead.
 // The parameter choice here is due to performance -- calculating a tag set can be slower than just
 // loading the bloom filter, even though the bloom filter is larger.
@@ -302,6 +316,20 @@ pub fn decode_tx_stream<R: Read>(
     Ok((txs, page_id))
 }
 
+/// Outcome of `MemPoolDB::replay_admissions()`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdmissionReplayStats {
+    /// Transactions that were last admitted against the current tip, and were kept as-is
+    pub unchanged: u64,
+    /// Transactions that passed the admission checks again, and were kept
+    pub revalidated: u64,
+    /// Transactions that no longer pass the admission checks, and were dropped
+    pub dropped: u64,
+    /// Transactions admitted against another tip that were left for a later pass, because this
+    /// pass reached its limit
+    pub deferred: u64,
+}
+
 pub struct MemPoolAdmitter {
     cur_block: BlockHeaderHash,
     cur_consensus_hash: ConsensusHash,
@@ -355,6 +383,7 @@ pub enum MemPoolDropReason {
     STALE_COLLECT,
     TOO_EXPENSIVE,
     PROBLEMATIC,
+    DRAINED,
 }
 
 pub struct ConsiderTransaction {
@@ -380,6 +409,7 @@ impl std::fmt::Display for MemPoolDropReason {
             MemPoolDropReason::REPLACE_ACROSS_FORK => write!(f, "ReplaceAcrossFork"),
             MemPoolDropReason::REPLACE_BY_FEE => write!(f, "ReplaceByFee"),
             MemPoolDropReason::PROBLEMATIC => write!(f, "Problematic"),
+            MemPoolDropReason::DRAINED => write!(f, "Drained"),
         }
     }
 }
@@ -535,6 +565,11 @@ pub struct MemPoolWalkSettings {
     /// What percentage of the remaining cost limit should we consume before stopping the walk
     /// None means we consume the entire cost limit ASAP
     pub tenure_cost_limit_per_block_percentage: Option<u8>,
+    /// Whether to order transactions with a fee-rate estimate by the aggregate fee rate of the
+    /// origin's nonce chain that each one starts, instead of by their own fee rates.  This lets
+    /// a cheap transaction that unlocks more expensive ones from the same origin be considered
+    /// as early as those.
+    pub nonce_chain_fee_rate: bool,
 }
 
 impl Default for MemPoolWalkSettings {
@@ -547,6 +582,7 @@ impl Default for MemPoolWalkSettings {
             txs_to_consider: MemPoolWalkTxTypes::all(),
             filter_origins: HashSet::new(),
             tenure_cost_limit_per_block_percentage: None,
+            nonce_chain_fee_rate: false,
         }
     }
 }
@@ -560,6 +596,7 @@ impl MemPoolWalkSettings {
             txs_to_consider: MemPoolWalkTxTypes::all(),
             filter_origins: HashSet::new(),
             tenure_cost_limit_per_block_percentage: None,
+            nonce_chain_fee_rate: false,
         }
     }
 }
@@ -803,6 +840,39 @@ const MEMPOOL_SCHEMA_7_TIME_ESTIMATES: &[&str] = &[
     "#,
 ];
 
+const MEMPOOL_SCHEMA_8_PROPOSAL_REJECTIONS: &[&str] = &[
+    r#"
+    CREATE TABLE tx_proposal_rejections(
+        txid TEXT PRIMARY KEY NOT NULL,
+        rejection_count INTEGER NOT NULL,
+        reason TEXT NOT NULL,
+        penalized_until INTEGER NOT NULL
+    );
+    "#,
+    r#"
+    INSERT INTO schema_version (version) VALUES (8)
+    "#,
+];
+
+const MEMPOOL_SCHEMA_9_ADMISSIONS: &[&str] = &[
+    r#"
+    -- The chain tip against which each transaction last passed the admission checks, and its
+    -- estimated execution cost at the time.  On restart, only transactions whose tip is no longer
+    -- the canonical tip need to be revalidated.
+    CREATE TABLE tx_admissions(
+        txid TEXT PRIMARY KEY NOT NULL,
+        consensus_hash TEXT NOT NULL,
+        block_header_hash TEXT NOT NULL,
+        -- JSON-encoded ExecutionCost, or NULL if no estimate was available
+        estimated_cost TEXT,
+        FOREIGN KEY(txid) REFERENCES mempool(txid) ON DELETE CASCADE
+    );
+    "#,
+    r#"
+    INSERT INTO schema_version (version) VALUES (9)
+    "#,
+];
+
 const MEMPOOL_INDEXES: &[&str] = &[
     "CREATE INDEX IF NOT EXISTS by_txid ON mempool(txid);",
     "CREATE INDEX IF NOT EXISTS by_height ON mempool(height);",
@@ -827,6 +897,11 @@ pub struct MemPoolDB {
     metric: Box<dyn CostMetric>,
     pub blacklist_timeout: u64,
     pub blacklist_max_size: u64,
+    /// Whether the node operator has paused the admission of new transactions
+    admission_paused: bool,
+    /// Operator-defined policy consulted by `submit`, if any
+    #[cfg(feature = "mempool-admission-policy")]
+    admission_policy: Option<Box<dyn MemPoolAdmissionPolicy>>,
 }
 
 pub struct MemPoolTx<'a> {
@@ -913,8 +988,8 @@ impl<'a> MemPoolTx<'a> {
         Ok(())
     }
 
-    /// Add the txid to the bloom counter in the mempool DB, optionally replacing a prior
-    /// transaction (identified by prior_txid) if the bloom counter is full.
+    /// Add the txid to the bloom counter in the mempool DB, removing any prior transactions
+    /// (identified by prior_txids) that it replaces.
     /// If this is the first txid at this coinbase height, then also garbage-collect the bloom counter to remove no-longer-recent transactions.
     /// If the bloom counter is saturated -- i.e. it represents more than MAX_BLOOM_COUNTER_TXS
     /// transactions -- then pick another transaction to evict from the bloom filter and return its txid.
@@ -924,7 +999,7 @@ impl<'a> MemPoolTx<'a> {
         &mut self,
         coinbase_height: u64,
         txid: &Txid,
-        prior_txid: Option<Txid>,
+        prior_txids: &[Txid],
     ) -> Result<Option<Txid>, MemPoolRejection> {
         // is this the first-ever txid at this coinbase height?
         let sql = "SELECT 1 FROM mempool WHERE height = ?1";
@@ -938,8 +1013,8 @@ impl<'a> MemPoolTx<'a> {
         }
 
         MemPoolTx::with_bloom_state(self, |ref mut dbtx, ref mut bloom_counter| {
-            // remove replaced transaction
-            if let Some(prior_txid) = prior_txid {
+            // remove replaced transactions
+            for prior_txid in prior_txids.iter() {
                 bloom_counter.remove_raw(dbtx, &prior_txid.0)?;
             }
 
@@ -1291,6 +1366,12 @@ impl MemPoolDB {
                     MemPoolDB::instantiate_schema_7(tx)?;
                 }
                 7 => {
+                    MemPoolDB::instantiate_schema_8(tx)?;
+                }
+                8 => {
+                    MemPoolDB::instantiate_schema_9(tx)?;
+                }
+                9 => {
                     break;
                 }
                 _ => {
@@ -1377,6 +1458,26 @@ impl MemPoolDB {
         Ok(())
     }
 
+    /// Add the proposal rejection penalty table
+    #[cfg_attr(test, mutants::skip)]
+    fn instantiate_schema_8(tx: &DBTx) -> Result<(), db_error> {
+        for sql_exec in MEMPOOL_SCHEMA_8_PROPOSAL_REJECTIONS {
+            tx.execute_batch(sql_exec)?;
+        }
+
+        Ok(())
+    }
+
+    /// Add the table that records the tip each transaction was admitted against
+    #[cfg_attr(test, mutants::skip)]
+    fn instantiate_schema_9(tx: &DBTx) -> Result<(), db_error> {
+        for sql_exec in MEMPOOL_SCHEMA_9_ADMISSIONS {
+            tx.execute_batch(sql_exec)?;
+        }
+
+        Ok(())
+    }
+
     #[cfg_attr(test, mutants::skip)]
     pub fn db_path(chainstate_root_path: &str) -> Result<String, db_error> {
         let mut path = PathBuf::from(chainstate_root_path);
@@ -1439,9 +1540,63 @@ impl MemPoolDB {
             metric,
             blacklist_timeout: DEFAULT_BLACKLIST_TIMEOUT,
             blacklist_max_size: DEFAULT_BLACKLIST_MAX_SIZE,
+            admission_paused: false,
+            #[cfg(feature = "mempool-admission-policy")]
+            admission_policy: None,
         })
     }
 
+    /// Pause or resume the admission of new transactions, e.g. for a maintenance window.
+    /// `submit()` does not check this, so that drained transactions can still be re-imported;
+    /// callers that accept transactions from users or peers must check `is_admission_paused()`.
+    pub fn set_admission_paused(&mut self, paused: bool) {
+        self.admission_paused = paused;
+    }
+
+    /// Has the node operator paused the admission of new transactions?
+    pub fn is_admission_paused(&self) -> bool {
+        self.admission_paused
+    }
+
+    /// Set the policy that `submit` consults before admitting a transaction
+    #[cfg(feature = "mempool-admission-policy")]
+    pub fn set_admission_policy(&mut self, policy: Box<dyn MemPoolAdmissionPolicy>) {
+        self.admission_policy = Some(policy);
+    }
+
+    /// Run `tx` past the admission policy, if there is one.  Returns the fee rate to store for
+    /// it: `fee_rate` if the policy admits it, or 0 if the policy deprioritizes it.
+    #[cfg(feature = "mempool-admission-policy")]
+    pub fn check_admission_policy(
+        &self,
+        tx: &StacksTransaction,
+        fee_rate: Option<f64>,
+    ) -> Result<Option<f64>, MemPoolRejection> {
+        let Some(policy) = self.admission_policy.as_ref() else {
+            return Ok(fee_rate);
+        };
+        match policy.evaluate(tx) {
+            AdmissionDecision::Admit => Ok(fee_rate),
+            AdmissionDecision::Deprioritize(reason) => {
+                info!("Mempool admission policy deprioritized transaction";
+                      "txid" => %tx.txid(),
+                      "policy" => policy.name(),
+                      "reason" => %reason);
+                Ok(Some(0.0))
+            }
+            AdmissionDecision::Reject(reason) => {
+                info!("Mempool admission policy rejected transaction";
+                      "txid" => %tx.txid(),
+                      "policy" => policy.name(),
+                      "reason" => %reason);
+                Err(MemPoolRejection::PolicyRejected {
+                    policy: policy.name().to_string(),
+                    reason,
+                })
+            }
+        }
+    }
+
     pub fn reopen(&self, readwrite: bool) -> Result<DBConn, db_error> {
         if let Err(e) = fs::metadata(&self.path) {
             return Err(db_error::IOError(e));
@@ -1583,6 +1738,56 @@ impl MemPoolDB {
         }
     }
 
+    /// Get the aggregate fee rate of the nonce chain that each of the given transactions starts.
+    /// `txs` must be sorted by origin address, then by origin nonce.  A transaction's nonce chain
+    /// is itself plus the transactions with the next consecutive nonces from the same origin (up
+    /// to `MAXIMUM_MEMPOOL_TX_CHAINING` of them), and its aggregate fee rate is the highest mean
+    /// fee rate of any prefix of that chain.  A transaction is never rated below its own fee rate.
+    fn nonce_chain_fee_rates(txs: &[MemPoolTxInfoPartial]) -> Vec<f64> {
+        let max_chain_len = usize::try_from(MAXIMUM_MEMPOOL_TX_CHAINING).unwrap_or(usize::MAX);
+        (0..txs.len())
+            .map(|i| {
+                let mut total = 0.0;
+                let mut best: f64 = 0.0;
+                for (len, tx) in txs[i..].iter().take(max_chain_len).enumerate() {
+                    if tx.origin_address != txs[i].origin_address
+                        || tx.origin_nonce != txs[i].origin_nonce + len as u64
+                    {
+                        break;
+                    }
+                    total += tx.fee_rate.unwrap_or_default();
+                    best = best.max(total / (len + 1) as f64);
+                }
+                best
+            })
+            .collect()
+    }
+
+    /// Get the transactions with a fee-rate estimate, in descending order of the aggregate fee
+    /// rate of the nonce chain each one starts (see `nonce_chain_fee_rates()`).  Ties go to the
+    /// transaction with the lower nonce.
+    fn get_candidates_by_nonce_chain_fee_rate(
+        conn: &DBConn,
+    ) -> Result<Vec<MemPoolTxInfoPartial>, db_error> {
+        let sql = "
+            SELECT txid, origin_nonce, origin_address, sponsor_nonce, sponsor_address, fee_rate
+            FROM mempool
+            WHERE fee_rate IS NOT NULL
+            ORDER BY origin_address ASC, origin_nonce ASC
+            ";
+        let txs: Vec<MemPoolTxInfoPartial> = query_rows(conn, sql, NO_PARAMS)?;
+        let mut candidates: Vec<_> = Self::nonce_chain_fee_rates(&txs)
+            .into_iter()
+            .zip(txs)
+            .collect();
+        candidates.sort_by(|(rate_a, tx_a), (rate_b, tx_b)| {
+            rate_b
+                .total_cmp(rate_a)
+                .then_with(|| tx_a.origin_nonce.cmp(&tx_b.origin_nonce))
+        });
+        Ok(candidates.into_iter().map(|(_, tx)| tx).collect())
+    }
+
     /// Iterate over candidates in the mempool
     /// `todo` will be called once for each transaction that is a valid
     /// candidate for inclusion in the next block, meaning its origin and
@@ -1591,6 +1796,8 @@ impl MemPoolDB {
     /// That is, transactions will be processed in fee-rate order until the
     /// candidate cache is full, at which point, transactions with a lower
     /// fee-rate may be considered before those with a higher fee-rate.
+    /// If `settings.nonce_chain_fee_rate` is set, the fee-rate order is that of
+    /// the aggregate fee rates of the origins' nonce chains instead.
     /// When the candidate cache fills, a subsequent call to
     /// `iterate_candidates` will be needed to reconsider transactions which
     /// were skipped on the first pass, but become valid after some lower
@@ -1638,6 +1845,9 @@ impl MemPoolDB {
         // single transaction.  This cannot grow to more than `settings.nonce_cache_size` entries.
         let mut retry_store = HashMap::new();
 
+        // transactions which recently got one of our block proposals rejected
+        let penalized_txids = MemPoolDB::get_penalized_txids(self.conn(), get_epoch_time_secs())?;
+
         let sql = "
              SELECT txid, origin_nonce, origin_address, sponsor_nonce, sponsor_address, fee_rate
              FROM mempool
@@ -1659,6 +1869,23 @@ impl MemPoolDB {
             .query(NO_PARAMS)
             .map_err(Error::SqliteError)?;
 
+        // transactions with a fee-rate estimate, in nonce-chain fee-rate order, if so configured
+        let mut nonce_chain_candidates = if settings.nonce_chain_fee_rate {
+            Some(Self::get_candidates_by_nonce_chain_fee_rate(self.conn())?.into_iter())
+        } else {
+            None
+        };
+        let mut next_estimated_candidate = || -> Result<Option<MemPoolTxInfoPartial>, db_error> {
+            if let Some(candidates) = nonce_chain_candidates.as_mut() {
+                return Ok(candidates.next());
+            }
+            fee_iterator
+                .next()
+                .map_err(Error::SqliteError)?
+                .map(MemPoolTxInfoPartial::from_row)
+                .transpose()
+        };
+
         let stop_reason = loop {
             if start_time.elapsed().as_millis() > settings.max_walk_time_ms as u128 {
                 debug!("Mempool iteration deadline exceeded";
@@ -1680,23 +1907,28 @@ impl MemPoolDB {
                     // randomly selecting from either the null fee-rate transactions
                     // or those with fee-rate estimates.
                     let opt_tx = if start_with_no_estimate {
-                        null_iterator.next().map_err(Error::SqliteError)?
+                        null_iterator
+                            .next()
+                            .map_err(Error::SqliteError)?
+                            .map(MemPoolTxInfoPartial::from_row)
+                            .transpose()?
                     } else {
-                        fee_iterator.next().map_err(Error::SqliteError)?
+                        next_estimated_candidate()?
                     };
                     match opt_tx {
-                        Some(row) => (MemPoolTxInfoPartial::from_row(row)?, start_with_no_estimate),
+                        Some(tx) => (tx, start_with_no_estimate),
                         None => {
                             // If the selected iterator is empty, check the other
                             match if start_with_no_estimate {
-                                fee_iterator.next().map_err(Error::SqliteError)?
+                                next_estimated_candidate()?
                             } else {
-                                null_iterator.next().map_err(Error::SqliteError)?
+                                null_iterator
+                                    .next()
+                                    .map_err(Error::SqliteError)?
+                                    .map(MemPoolTxInfoPartial::from_row)
+                                    .transpose()?
                             } {
-                                Some(row) => (
-                                    MemPoolTxInfoPartial::from_row(row)?,
-                                    !start_with_no_estimate,
-                                ),
+                                Some(tx) => (tx, !start_with_no_estimate),
                                 None => {
                                     debug!("No more transactions to consider in mempool");
                                     break MempoolIterationStopReason::NoMoreCandidates;
@@ -1707,6 +1939,16 @@ impl MemPoolDB {
                 }
             };
 
+            if penalized_txids.contains(&candidate.txid) {
+                debug!(
+                    "Mempool: skip tx penalized by a block proposal rejection";
+                    "txid" => %candidate.txid,
+                    "tx_origin_addr" => %candidate.origin_address,
+                    "tx_origin_nonce" => candidate.origin_nonce,
+                );
+                continue;
+            }
+
             // Check the nonces.
             let (expected_origin_nonce, retry_store_origin_nonce) =
                 nonce_cache.get(&candidate.origin_address, clarity_tx, self.conn());
@@ -2000,6 +2242,19 @@ impl MemPoolDB {
         query_row(conn, &sql, args)
     }
 
+    /// Get the metadata of all transactions that use the given address and nonce, either as their
+    /// origin or as their sponsor.  An account's nonces are shared between both roles, so any of
+    /// these transactions conflicts with a new transaction that uses this address and nonce.
+    pub fn get_txs_metadata_by_account_nonce(
+        conn: &DBConn,
+        addr: &StacksAddress,
+        nonce: u64,
+    ) -> Result<Vec<MemPoolTxMetadata>, db_error> {
+        let sql = "SELECT * FROM mempool WHERE (origin_address = ?1 AND origin_nonce = ?2) OR (sponsor_address = ?1 AND sponsor_nonce = ?2)";
+        let args = params![addr.to_string(), u64_to_sql(nonce)?];
+        query_rows(conn, sql, args)
+    }
+
     /// Are the given fully-qualified blocks, identified by their (consensus-hash, block-header-hash) pairs, in the same fork?
     /// That is, is one block an ancestor of another?
     /// TODO: Nakamoto-ize
@@ -2096,31 +2351,32 @@ impl MemPoolDB {
             (tip_consensus_hash.clone(), tip_block_header_hash.clone())
         };
 
-        // do we already have txs with either the same origin nonce or sponsor nonce ?
-        let prior_tx = {
-            match MemPoolDB::get_tx_metadata_by_address(tx, true, origin_address, origin_nonce)? {
-                Some(prior_tx) => Some(prior_tx),
-                None => MemPoolDB::get_tx_metadata_by_address(
-                    tx,
-                    false,
-                    sponsor_address,
-                    sponsor_nonce,
-                )?,
+        // do we already have txs that use either the origin's nonce or the sponsor's nonce?
+        // Note that an account's nonce can be used either as an origin nonce or as a sponsor
+        // nonce, so a sponsored transaction can conflict with more than one prior transaction.
+        let mut prior_txs =
+            MemPoolDB::get_txs_metadata_by_account_nonce(tx, origin_address, origin_nonce)?;
+        if (sponsor_address, sponsor_nonce) != (origin_address, origin_nonce) {
+            for prior_tx in
+                MemPoolDB::get_txs_metadata_by_account_nonce(tx, sponsor_address, sponsor_nonce)?
+            {
+                if !prior_txs.iter().any(|tx| tx.txid == prior_tx.txid) {
+                    prior_txs.push(prior_tx);
+                }
             }
-        };
-
-        let mut replace_reason = MemPoolDropReason::REPLACE_BY_FEE;
+        }
 
-        // if so, is this a replace-by-fee? or a replace-in-chain-tip?
-        let add_tx = if let Some(ref prior_tx) = prior_tx {
+        // if so, is each of them a replace-by-fee? or a replace-in-chain-tip?
+        let mut replaced_by_fee = vec![];
+        let mut replaced_across_fork = vec![];
+        for prior_tx in prior_txs.iter() {
             if tx_fee > prior_tx.tx_fee {
                 // is this a replace-by-fee ?
                 debug!(
                     "Can replace {} with {} for {},{} by fee ({} < {})",
                     &prior_tx.txid, &txid, origin_address, origin_nonce, &prior_tx.tx_fee, &tx_fee
                 );
-                replace_reason = MemPoolDropReason::REPLACE_BY_FEE;
-                true
+                replaced_by_fee.push(prior_tx.txid.clone());
             } else if !MemPoolDB::are_blocks_in_same_fork(
                 chainstate,
                 &prior_tx.tenure_consensus_hash,
@@ -2133,8 +2389,7 @@ impl MemPoolDB {
                     "Can replace {} with {} for {},{} across fork",
                     &prior_tx.txid, &txid, origin_address, origin_nonce
                 );
-                replace_reason = MemPoolDropReason::REPLACE_ACROSS_FORK;
-                true
+                replaced_across_fork.push(prior_tx.txid.clone());
             } else {
                 // there's a >= fee tx in this fork, cannot add
                 info!("TX conflicts with sponsor/origin nonce in same fork with >= fee";
@@ -2146,22 +2401,16 @@ impl MemPoolDB {
                       "sponsor_nonce" => sponsor_nonce,
                       "new_fee" => tx_fee,
                       "old_fee" => prior_tx.tx_fee);
-                false
+                return Err(MemPoolRejection::ConflictingNonceInMempool);
             }
-        } else {
-            // no conflicting TX with this origin/sponsor, go ahead and add
-            true
-        };
-
-        if !add_tx {
-            return Err(MemPoolRejection::ConflictingNonceInMempool);
         }
 
-        tx.update_bloom_counter(
-            coinbase_height,
-            &txid,
-            prior_tx.as_ref().map(|tx| tx.txid.clone()),
-        )?;
+        let prior_txids: Vec<_> = prior_txs.into_iter().map(|tx| tx.txid).collect();
+        tx.update_bloom_counter(coinbase_height, &txid, &prior_txids)?;
+
+        // drop the replaced transactions explicitly, since the insert below only replaces the
+        // ones that share its origin or sponsor columns
+        MemPoolDB::inner_drop_txs(tx, &prior_txids)?;
 
         let sql = "INSERT OR REPLACE INTO mempool (
             txid,
@@ -2198,10 +2447,23 @@ impl MemPoolDB {
 
         tx.update_mempool_pager(&txid)?;
 
-        // broadcast drop event if a tx is being replaced
-        if let (Some(prior_tx), Some(event_observer)) = (prior_tx, event_observer) {
-            event_observer.mempool_txs_dropped(vec![prior_tx.txid], Some(txid), replace_reason);
-        };
+        // broadcast drop events for the txs being replaced
+        if let Some(event_observer) = event_observer {
+            if !replaced_by_fee.is_empty() {
+                event_observer.mempool_txs_dropped(
+                    replaced_by_fee,
+                    Some(txid.clone()),
+                    MemPoolDropReason::REPLACE_BY_FEE,
+                );
+            }
+            if !replaced_across_fork.is_empty() {
+                event_observer.mempool_txs_dropped(
+                    replaced_across_fork,
+                    Some(txid),
+                    MemPoolDropReason::REPLACE_ACROSS_FORK,
+                );
+            }
+        }
 
         Ok(())
     }
@@ -2231,6 +2493,7 @@ impl MemPoolDB {
                 )?;
             }
         };
+        Self::garbage_collect_tx_proposal_rejections(&tx)?;
         tx.commit()
     }
 
@@ -2299,6 +2562,7 @@ impl MemPoolDB {
         do_admission_checks: bool,
         event_observer: Option<&dyn MemPoolEventDispatcher>,
         fee_rate_estimate: Option<f64>,
+        estimated_cost: Option<&ExecutionCost>,
     ) -> Result<(), MemPoolRejection> {
         test_debug!(
             "Mempool submit {} at {}/{}",
@@ -2378,6 +2642,16 @@ impl MemPoolDB {
             )
             .map_err(db_error::from)?;
 
+        if do_admission_checks {
+            MemPoolDB::record_admission(
+                mempool_tx,
+                &txid,
+                consensus_hash,
+                block_hash,
+                estimated_cost,
+            )?;
+        }
+
         if let Err(e) = monitoring::mempool_accepted(&txid, &chainstate.root_path) {
             warn!("Failed to monitor TX receive: {:?}", e; "txid" => %txid);
         }
@@ -2416,17 +2690,10 @@ impl MemPoolDB {
             return Err(MemPoolRejection::TemporarilyBlacklisted);
         }
 
-        let estimator_result = cost_estimates::estimate_fee_rate(
-            tx,
-            self.cost_estimator.as_ref(),
-            self.metric.as_ref(),
-            block_limit,
-            stacks_epoch_id,
-        );
-
-        let mut mempool_tx = self.tx_begin().map_err(MemPoolRejection::DBError)?;
-
-        let fee_rate = match estimator_result {
+        let estimated_cost = match self
+            .cost_estimator
+            .estimate_cost(&tx.payload, stacks_epoch_id)
+        {
             Ok(x) => Some(x),
             Err(EstimatorError::NoEstimateAvailable) => None,
             Err(e) => {
@@ -2436,6 +2703,13 @@ impl MemPoolDB {
                 return Err(MemPoolRejection::EstimatorError(e));
             }
         };
+        let fee_rate = estimated_cost.as_ref().map(|cost| {
+            cost_estimates::fee_rate_for_cost(tx, cost, self.metric.as_ref(), block_limit)
+        });
+        #[cfg(feature = "mempool-admission-policy")]
+        let fee_rate = self.check_admission_policy(tx, fee_rate)?;
+
+        let mut mempool_tx = self.tx_begin().map_err(MemPoolRejection::DBError)?;
 
         MemPoolDB::tx_submit(
             &mut mempool_tx,
@@ -2447,6 +2721,7 @@ impl MemPoolDB {
             true,
             event_observer,
             fee_rate,
+            estimated_cost.as_ref(),
         )?;
         mempool_tx.commit().map_err(MemPoolRejection::DBError)?;
         Ok(())
@@ -2477,6 +2752,7 @@ impl MemPoolDB {
             false,
             event_observer,
             fee_estimate,
+            None,
         )?;
         mempool_tx.commit().map_err(MemPoolRejection::DBError)?;
         Ok(())
@@ -2536,6 +2812,7 @@ impl MemPoolDB {
             false,
             None,
             fee_rate,
+            None,
         )?;
         mempool_tx.commit().map_err(MemPoolRejection::DBError)?;
         Ok(())
@@ -2614,6 +2891,85 @@ impl MemPoolDB {
         )
     }
 
+    /// Record that signers rejected a block proposal because of this transaction, and penalize it
+    /// so that the miner skips it until `now` plus the penalty.  Each subsequent rejection doubles
+    /// the penalty, up to `PROPOSAL_REJECTION_MAX_PENALTY`.
+    /// Returns the time until which the transaction is penalized.
+    /// Do not call directly; it's `pub` only for testing
+    pub fn inner_penalize_rejected_tx(
+        tx: &DBTx<'_>,
+        txid: &Txid,
+        reason: &str,
+        now: u64,
+    ) -> Result<u64, db_error> {
+        let sql = "SELECT rejection_count FROM tx_proposal_rejections WHERE txid = ?1";
+        let prior_count: Option<u64> = query_row(tx, sql, params![txid])?;
+        let rejection_count = prior_count.unwrap_or(0).saturating_add(1);
+
+        let shift = u32::try_from(rejection_count - 1).unwrap_or(u32::MAX);
+        let penalty = PROPOSAL_REJECTION_BASE_PENALTY
+            .checked_shl(shift)
+            .filter(|penalty| *penalty <= PROPOSAL_REJECTION_MAX_PENALTY)
+            .unwrap_or(PROPOSAL_REJECTION_MAX_PENALTY);
+        let penalized_until = now.saturating_add(penalty);
+
+        let sql = "INSERT OR REPLACE INTO tx_proposal_rejections (txid, rejection_count, reason, penalized_until) VALUES (?1, ?2, ?3, ?4)";
+        let args = params![
+            txid,
+            u64_to_sql(rejection_count)?,
+            reason,
+            u64_to_sql(penalized_until)?
+        ];
+        tx.execute(sql, args)?;
+        Ok(penalized_until)
+    }
+
+    /// Penalize transactions which caused signers to reject a block proposal, so that subsequent
+    /// mempool walks skip them until their penalty expires.
+    /// `txs` pairs each offending txid with the signers' rejection reason.
+    pub fn penalize_rejected_txs(&mut self, txs: &[(Txid, String)]) -> Result<(), db_error> {
+        let now = get_epoch_time_secs();
+        let mempool_tx = self.tx_begin()?;
+        for (txid, reason) in txs.iter() {
+            let penalized_until =
+                MemPoolDB::inner_penalize_rejected_tx(&mempool_tx, txid, reason, now)?;
+            debug!("Mempool: penalized tx rejected by signers";
+                   "txid" => %txid,
+                   "reason" => reason,
+                   "penalized_until" => penalized_until);
+        }
+        mempool_tx.commit()?;
+        Ok(())
+    }
+
+    /// Until when is a tx penalized for causing a block proposal rejection, if at all?
+    pub fn get_tx_penalized_until(conn: &DBConn, txid: &Txid) -> Result<Option<u64>, db_error> {
+        let sql = "SELECT penalized_until FROM tx_proposal_rejections WHERE txid = ?1";
+        query_row(conn, sql, params![txid])
+    }
+
+    /// is a tx currently penalized for causing a block proposal rejection?
+    pub fn is_tx_penalized(&self, txid: &Txid) -> Result<bool, db_error> {
+        match MemPoolDB::get_tx_penalized_until(self.conn(), txid)? {
+            None => Ok(false),
+            Some(penalized_until) => Ok(get_epoch_time_secs() < penalized_until),
+        }
+    }
+
+    /// Get the set of txids whose proposal rejection penalty has not yet expired as of `now`
+    fn get_penalized_txids(conn: &DBConn, now: u64) -> Result<HashSet<Txid>, db_error> {
+        let sql = "SELECT txid FROM tx_proposal_rejections WHERE penalized_until > ?1";
+        let txids: Vec<Txid> = query_rows(conn, sql, params![u64_to_sql(now)?])?;
+        Ok(txids.into_iter().collect())
+    }
+
+    /// Forget the proposal rejection history of transactions which are no longer in the mempool
+    fn garbage_collect_tx_proposal_rejections(tx: &DBTx<'_>) -> Result<(), db_error> {
+        let sql = "DELETE FROM tx_proposal_rejections WHERE txid NOT IN (SELECT txid FROM mempool)";
+        tx.execute(sql, NO_PARAMS)?;
+        Ok(())
+    }
+
     /// Inner code body for dropping transactions.
     /// Note that the bloom filter will *NOT* be updated.  That's the caller's job, if desired.
     fn inner_drop_txs(tx: &DBTx<'_>, txids: &[Txid]) -> Result<(), db_error> {
@@ -2633,6 +2989,257 @@ impl MemPoolDB {
         Ok(())
     }
 
+    /// The directory that `drain_to_file()` saves transactions in
+    pub fn drain_dir(&self) -> PathBuf {
+        Path::new(&self.path)
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(MEMPOOL_DRAIN_DIR)
+    }
+
+    /// Create a new, empty file in the drain directory for `drain_to_file()` to save
+    /// transactions in
+    fn create_drain_file(&self) -> Result<(PathBuf, fs::File), db_error> {
+        let dir = self.drain_dir();
+        fs::create_dir_all(&dir).map_err(db_error::IOError)?;
+        let now = get_epoch_time_ms();
+        for i in 0u32.. {
+            let file_name = if i == 0 {
+                format!("drain-{now}.txs")
+            } else {
+                format!("drain-{now}-{i}.txs")
+            };
+            let path = dir.join(file_name);
+            // never overwrite an earlier drain
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(file) => return Ok((path, file)),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(db_error::IOError(e)),
+            }
+        }
+        Err(db_error::Overflow)
+    }
+
+    /// Drop every transaction from the mempool, at the node operator's request.  Like
+    /// `drop_txs()`, this does not update the bloom filter, so peers will not sync the
+    /// transactions back.  Returns the dropped transactions' IDs.
+    pub fn drain(
+        &mut self,
+        event_observer: Option<&dyn MemPoolEventDispatcher>,
+    ) -> Result<Vec<Txid>, db_error> {
+        self.inner_drain(event_observer, None)
+    }
+
+    /// Like `drain()`, but first save the transactions to a new file in `drain_dir()`, one
+    /// hex-encoded transaction per line in origin nonce order, so they can be re-imported with
+    /// `load_drained_txs()`.  Nothing is dropped if they cannot be saved.  Returns the dropped
+    /// transactions' IDs and the file they were saved to.
+    pub fn drain_to_file(
+        &mut self,
+        event_observer: Option<&dyn MemPoolEventDispatcher>,
+    ) -> Result<(Vec<Txid>, PathBuf), db_error> {
+        let (save_path, mut file) = self.create_drain_file()?;
+        let txids = self.inner_drain(event_observer, Some(&mut file))?;
+        Ok((txids, save_path))
+    }
+
+    /// Drop every transaction from the mempool, after saving them to `save_file` if given
+    fn inner_drain(
+        &mut self,
+        event_observer: Option<&dyn MemPoolEventDispatcher>,
+        save_file: Option<&mut fs::File>,
+    ) -> Result<Vec<Txid>, db_error> {
+        let mempool_tx = self.tx_begin()?;
+        let mut stmt = mempool_tx
+            .prepare("SELECT txid, tx FROM mempool ORDER BY origin_address, origin_nonce")?;
+        let mut rows = stmt.query(NO_PARAMS)?;
+        let mut txids = vec![];
+        let mut contents = String::new();
+        while let Some(row) = rows.next()? {
+            let txid = Txid::from_column(row, "txid")?;
+            txids.push(txid);
+            if save_file.is_some() {
+                let tx_bytes: Vec<u8> = row.get_unwrap("tx");
+                contents.push_str(&to_hex(&tx_bytes));
+                contents.push('\n');
+            }
+        }
+        drop(rows);
+        drop(stmt);
+
+        if let Some(file) = save_file {
+            file.write_all(contents.as_bytes())
+                .and_then(|_| file.sync_all())
+                .map_err(db_error::IOError)?;
+        }
+
+        MemPoolDB::inner_drop_txs(&mempool_tx, &txids)?;
+        mempool_tx.commit()?;
+        if let Some(event_observer) = event_observer {
+            event_observer.mempool_txs_dropped(txids.clone(), None, MemPoolDropReason::DRAINED);
+        }
+        Ok(txids)
+    }
+
+    /// Load the transactions that `drain_to_file()` saved to `path`
+    pub fn load_drained_txs(path: &Path) -> Result<Vec<StacksTransaction>, db_error> {
+        let contents = fs::read_to_string(path).map_err(db_error::IOError)?;
+        let mut txs = vec![];
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let tx = hex_bytes(line)
+                .ok()
+                .and_then(|bytes| StacksTransaction::consensus_deserialize(&mut &bytes[..]).ok())
+                .ok_or_else(|| {
+                    db_error::Other(format!(
+                        "{}:{}: not a hex-encoded transaction",
+                        path.display(),
+                        i + 1
+                    ))
+                })?;
+            txs.push(tx);
+        }
+        Ok(txs)
+    }
+
+    /// Record that `txid` passed the admission checks against the chain tip
+    /// `consensus_hash`/`block_hash`, along with its estimated execution cost (if known).
+    pub(crate) fn record_admission(
+        tx: &DBTx,
+        txid: &Txid,
+        consensus_hash: &ConsensusHash,
+        block_hash: &BlockHeaderHash,
+        estimated_cost: Option<&ExecutionCost>,
+    ) -> Result<(), db_error> {
+        let estimated_cost = estimated_cost
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(db_error::SerializationError)?;
+        let sql = "INSERT OR REPLACE INTO tx_admissions (txid, consensus_hash, block_header_hash, estimated_cost) VALUES (?1, ?2, ?3, ?4)";
+        tx.execute(
+            sql,
+            params![txid, consensus_hash, block_hash, estimated_cost],
+        )?;
+        Ok(())
+    }
+
+    /// Load the execution cost that was estimated for `txid` when it was admitted, if any
+    pub(crate) fn get_admission_estimated_cost(
+        conn: &DBConn,
+        txid: &Txid,
+    ) -> Result<Option<ExecutionCost>, db_error> {
+        let sql = "SELECT estimated_cost FROM tx_admissions WHERE txid = ?1";
+        let estimated_cost: Option<Option<String>> = conn
+            .query_row(sql, params![txid], |row| row.get(0))
+            .optional()?;
+        estimated_cost
+            .flatten()
+            .map(|json| serde_json::from_str(&json).map_err(|_e| db_error::ParseError))
+            .transpose()
+    }
+
+    /// Revalidate the mempool against the chain tip `consensus_hash`/`block_hash`, e.g. after a
+    /// restart.  Transactions that last passed the admission checks against this tip are kept
+    /// as-is.  The rest are checked again, and dropped if they no longer pass (e.g. because they
+    /// were mined while the node was down).  Transactions that still pass get their fee rate
+    /// recomputed against `block_limit` from the execution cost estimated when they were first
+    /// admitted, instead of asking the cost estimator again.  At most `max_txs` transactions are
+    /// checked again, oldest first; the rest are left as-is for a later pass.
+    pub fn replay_admissions(
+        &mut self,
+        chainstate: &mut StacksChainState,
+        sortdb: &SortitionDB,
+        consensus_hash: &ConsensusHash,
+        block_hash: &BlockHeaderHash,
+        block_limit: &ExecutionCost,
+        max_txs: u64,
+    ) -> Result<AdmissionReplayStats, db_error> {
+        let num_txs = MemPoolDB::get_num_txs(self.conn())?;
+        let stale = "FROM mempool LEFT JOIN tx_admissions ON mempool.txid = tx_admissions.txid WHERE tx_admissions.txid IS NULL OR tx_admissions.consensus_hash != ?1 OR tx_admissions.block_header_hash != ?2";
+        let num_stale = query_int(
+            self.conn(),
+            &format!("SELECT COUNT(mempool.txid) {stale}"),
+            params![consensus_hash, block_hash],
+        )? as u64;
+        let stale_txids: Vec<Txid> = query_rows(
+            self.conn(),
+            &format!("SELECT mempool.txid {stale} ORDER BY mempool.accept_time ASC LIMIT ?3"),
+            params![consensus_hash, block_hash, u64_to_sql(max_txs)?],
+        )?;
+
+        let mut stats = AdmissionReplayStats {
+            unchanged: num_txs.saturating_sub(num_stale),
+            deferred: num_stale.saturating_sub(stale_txids.len() as u64),
+            ..AdmissionReplayStats::default()
+        };
+        let mut admitted = vec![];
+        let mut dropped = vec![];
+
+        self.admitter.set_block(block_hash, consensus_hash.clone());
+        for txid in stale_txids.into_iter() {
+            let Some(tx_info) = MemPoolDB::get_tx(self.conn(), &txid)? else {
+                continue;
+            };
+            match self
+                .admitter
+                .will_admit_tx(chainstate, sortdb, &tx_info.tx, tx_info.metadata.len)
+            {
+                Ok(()) => {
+                    let estimated_cost =
+                        MemPoolDB::get_admission_estimated_cost(self.conn(), &txid)?;
+                    let fee_rate = estimated_cost.as_ref().map(|cost| {
+                        cost_estimates::fee_rate_for_cost(
+                            &tx_info.tx,
+                            cost,
+                            self.metric.as_ref(),
+                            block_limit,
+                        )
+                    });
+                    admitted.push((txid, estimated_cost, fee_rate));
+                }
+                Err(MemPoolRejection::DBError(e)) => {
+                    return Err(e);
+                }
+                Err(e) => {
+                    debug!("Mempool: dropping transaction that no longer passes admission checks";
+                           "txid" => %txid,
+                           "reason" => ?e);
+                    dropped.push(txid);
+                }
+            }
+        }
+
+        let mempool_tx = self.tx_begin()?;
+        for (txid, estimated_cost, fee_rate) in admitted.iter() {
+            MemPoolDB::record_admission(
+                &mempool_tx,
+                txid,
+                consensus_hash,
+                block_hash,
+                estimated_cost.as_ref(),
+            )?;
+            if fee_rate.is_some() {
+                mempool_tx.execute(
+                    "UPDATE mempool SET fee_rate = ? WHERE txid = ?",
+                    params![fee_rate, txid],
+                )?;
+            }
+        }
+        MemPoolDB::inner_drop_txs(&mempool_tx, &dropped)?;
+        mempool_tx.commit()?;
+
+        stats.revalidated = admitted.len() as u64;
+        stats.dropped = dropped.len() as u64;
+        Ok(stats)
+    }
+
     /// Update the time estimates for the supplied txs in the mempool db
     pub fn update_tx_time_estimates(&mut self, txs: &[(Txid, u64)]) -> Result<(), db_error> {
         let sql = "UPDATE mempool SET time_estimate_ms = ? WHERE txid = ?";
@@ -2756,6 +3363,12 @@ impl MemPoolDB {
         query_int(conn, sql, args).map(|cnt| cnt as u64)
     }
 
+    /// How many transactions are in the mempool?
+    pub fn get_num_txs(conn: &DBConn) -> Result<u64, db_error> {
+        let sql = "SELECT COUNT(txid) FROM mempool";
+        query_int(conn, sql, NO_PARAMS).map(|cnt| cnt as u64)
+    }
+
     /// Make a mempool sync request.
     /// If sufficiently sparse, use a MemPoolSyncData::TxTags variant
     /// Otherwise, use a MemPoolSyncData::BloomFilter variant
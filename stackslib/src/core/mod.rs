@@ -29,6 +29,7 @@ use crate::burnchains::bitcoin::indexer::get_bitcoin_stacks_epochs;
 use crate::burnchains::bitcoin::BitcoinNetworkType;
 use crate::burnchains::{Burnchain, Error as burnchain_error};
 use crate::chainstate::burn::ConsensusHash;
+pub mod admission;
 pub mod mempool;
 
 #[cfg(test)]
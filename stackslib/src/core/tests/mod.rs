@@ -60,9 +60,11 @@ use crate::chainstate::stacks::{
     TransactionVersion, C32_ADDRESS_VERSION_MAINNET_SINGLESIG,
     C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
 };
+use crate::core::admission::{AdmissionDecision, ContractRulesPolicy, MemPoolAdmissionPolicy};
 use crate::core::mempool::{
     db_get_all_nonces, MemPoolSyncData, MemPoolWalkSettings, MemPoolWalkTxTypes, TxTag,
     BLOOM_COUNTER_DEPTH, BLOOM_COUNTER_ERROR_RATE, MAX_BLOOM_COUNTER_TXS,
+    PROPOSAL_REJECTION_BASE_PENALTY, PROPOSAL_REJECTION_MAX_PENALTY,
 };
 use crate::core::{FIRST_BURNCHAIN_CONSENSUS_HASH, FIRST_STACKS_BLOCK_HASH};
 use crate::net::Error as NetError;
@@ -2777,3 +2779,490 @@ fn test_filter_txs_by_type() {
         },
     );
 }
+
+#[test]
+fn test_penalize_rejected_txs() {
+    let mut chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+
+    let addr = StacksAddress::new(1, Hash160([0xff; 20])).unwrap();
+    let block_height = 10;
+
+    let b_1 = make_block(
+        &mut chainstate,
+        ConsensusHash([0x1; 20]),
+        &(
+            FIRST_BURNCHAIN_CONSENSUS_HASH.clone(),
+            FIRST_STACKS_BLOCK_HASH.clone(),
+        ),
+        1,
+        1,
+    );
+    let b_2 = make_block(&mut chainstate, ConsensusHash([0x2; 20]), &b_1, 2, 2);
+
+    let mut txids = vec![];
+    let mut mempool_tx = mempool.tx_begin().unwrap();
+    for _ in 0..4 {
+        let pk = StacksPrivateKey::random();
+        let mut tx = StacksTransaction {
+            version: TransactionVersion::Testnet,
+            chain_id: 0x80000000,
+            auth: TransactionAuth::from_p2pkh(&pk).unwrap(),
+            anchor_mode: TransactionAnchorMode::Any,
+            post_condition_mode: TransactionPostConditionMode::Allow,
+            post_conditions: vec![],
+            payload: TransactionPayload::TokenTransfer(
+                addr.to_account_principal(),
+                123,
+                TokenTransferMemo([0u8; 34]),
+            ),
+        };
+        tx.set_tx_fee(1000);
+        tx.set_origin_nonce(0);
+
+        let txid = tx.txid();
+        let tx_bytes = tx.serialize_to_vec();
+        let origin_addr = tx.origin_address();
+        let origin_nonce = tx.get_origin_nonce();
+        let sponsor_addr = tx.sponsor_address().unwrap_or(origin_addr.clone());
+        let sponsor_nonce = tx.get_sponsor_nonce().unwrap_or(origin_nonce);
+        let tx_fee = tx.get_tx_fee();
+
+        MemPoolDB::try_add_tx(
+            &mut mempool_tx,
+            &mut chainstate,
+            &b_2.0,
+            &b_2.1,
+            true,
+            txid.clone(),
+            tx_bytes,
+            tx_fee,
+            block_height as u64,
+            &origin_addr,
+            origin_nonce,
+            &sponsor_addr,
+            sponsor_nonce,
+            None,
+        )
+        .unwrap();
+        txids.push(txid);
+    }
+    mempool_tx.commit().unwrap();
+
+    // penalty doubles with each rejection, up to the max
+    let now = get_epoch_time_secs();
+    let mempool_tx = mempool.tx_begin().unwrap();
+    let mut expected_penalty = PROPOSAL_REJECTION_BASE_PENALTY;
+    for _ in 0..10 {
+        let penalized_until =
+            MemPoolDB::inner_penalize_rejected_tx(&mempool_tx, &txids[0], "bad tx", now).unwrap();
+        assert_eq!(penalized_until, now + expected_penalty);
+        expected_penalty = (expected_penalty * 2).min(PROPOSAL_REJECTION_MAX_PENALTY);
+    }
+    // an expired penalty
+    MemPoolDB::inner_penalize_rejected_tx(
+        &mempool_tx,
+        &txids[1],
+        "bad tx",
+        now - PROPOSAL_REJECTION_BASE_PENALTY - 1,
+    )
+    .unwrap();
+    mempool_tx.commit().unwrap();
+
+    mempool
+        .penalize_rejected_txs(&[(txids[2].clone(), "Problematic tx 2".into())])
+        .unwrap();
+
+    assert!(mempool.is_tx_penalized(&txids[0]).unwrap());
+    assert!(!mempool.is_tx_penalized(&txids[1]).unwrap());
+    assert!(mempool.is_tx_penalized(&txids[2]).unwrap());
+    assert!(!mempool.is_tx_penalized(&txids[3]).unwrap());
+
+    // the mempool walk skips the penalized txs
+    let mut tx_events = Vec::new();
+    chainstate.with_read_only_clarity_tx(
+        &TEST_BURN_STATE_DB,
+        &StacksBlockHeader::make_index_block_hash(&b_2.0, &b_2.1),
+        |clarity_conn| {
+            let mut considered = HashSet::new();
+            mempool
+                .iterate_candidates::<_, ChainstateError, _>(
+                    clarity_conn,
+                    &mut tx_events,
+                    MemPoolWalkSettings::default(),
+                    |_, available_tx, _| {
+                        considered.insert(available_tx.tx.tx.txid());
+                        Ok(Some(
+                            TransactionResult::skipped(&available_tx.tx.tx, "test".into())
+                                .convert_to_event(),
+                        ))
+                    },
+                )
+                .unwrap();
+            let expected: HashSet<_> = [txids[1].clone(), txids[3].clone()].into_iter().collect();
+            assert_eq!(considered, expected);
+        },
+    );
+
+    // penalty records are garbage-collected along with their txs
+    mempool.drop_txs(&[txids[0].clone()]).unwrap();
+    mempool
+        .garbage_collect(0, &MempoolCollectionBehavior::ByReceiveTime, None)
+        .unwrap();
+    assert!(MemPoolDB::get_tx_penalized_until(mempool.conn(), &txids[0])
+        .unwrap()
+        .is_none());
+    assert!(MemPoolDB::get_tx_penalized_until(mempool.conn(), &txids[2])
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn test_iterate_candidates_nonce_chain_fee_rate() {
+    let mut chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+
+    let addr = StacksAddress::new(1, Hash160([0xff; 20])).unwrap();
+    let b_1 = make_block(
+        &mut chainstate,
+        ConsensusHash([0x1; 20]),
+        &(
+            FIRST_BURNCHAIN_CONSENSUS_HASH.clone(),
+            FIRST_STACKS_BLOCK_HASH.clone(),
+        ),
+        1,
+        1,
+    );
+    let b_2 = make_block(&mut chainstate, ConsensusHash([0x2; 20]), &b_1, 2, 2);
+
+    // origin A has a cheap transaction ahead of an expensive one; origin B has a single
+    // transaction whose fee rate is between the two, but below their mean.
+    let pk_a = StacksPrivateKey::random();
+    let pk_b = StacksPrivateKey::random();
+    let mut txids = vec![];
+    let mut mempool_tx = mempool.tx_begin().unwrap();
+    for (pk, nonce, fee_rate) in [(&pk_a, 0, 10.0), (&pk_a, 1, 1000.0), (&pk_b, 0, 500.0)] {
+        let mut tx = StacksTransaction {
+            version: TransactionVersion::Testnet,
+            chain_id: 0x80000000,
+            auth: TransactionAuth::from_p2pkh(pk).unwrap(),
+            anchor_mode: TransactionAnchorMode::Any,
+            post_condition_mode: TransactionPostConditionMode::Allow,
+            post_conditions: vec![],
+            payload: TransactionPayload::TokenTransfer(
+                addr.to_account_principal(),
+                123,
+                TokenTransferMemo([0u8; 34]),
+            ),
+        };
+        tx.set_tx_fee(1000);
+        tx.set_origin_nonce(nonce);
+
+        let txid = tx.txid();
+        let tx_bytes = tx.serialize_to_vec();
+        let origin_addr = tx.origin_address();
+        let tx_fee = tx.get_tx_fee();
+
+        MemPoolDB::try_add_tx(
+            &mut mempool_tx,
+            &mut chainstate,
+            &b_1.0,
+            &b_1.1,
+            true,
+            txid.clone(),
+            tx_bytes,
+            tx_fee,
+            10,
+            &origin_addr,
+            nonce,
+            &origin_addr,
+            nonce,
+            None,
+        )
+        .unwrap();
+        mempool_tx
+            .execute(
+                "UPDATE mempool SET fee_rate = ? WHERE txid = ?",
+                params![Some(fee_rate), txid],
+            )
+            .unwrap();
+        txids.push(txid);
+    }
+    mempool_tx.commit().unwrap();
+
+    let mut walk_order = |nonce_chain_fee_rate: bool| {
+        let mut mempool_settings = MemPoolWalkSettings::default();
+        mempool_settings.consider_no_estimate_tx_prob = 0;
+        mempool_settings.nonce_chain_fee_rate = nonce_chain_fee_rate;
+        mempool.reset_nonce_cache().unwrap();
+
+        let mut considered = vec![];
+        let mut tx_events = Vec::new();
+        chainstate.with_read_only_clarity_tx(
+            &TEST_BURN_STATE_DB,
+            &StacksBlockHeader::make_index_block_hash(&b_2.0, &b_2.1),
+            |clarity_conn| {
+                mempool
+                    .iterate_candidates::<_, ChainstateError, _>(
+                        clarity_conn,
+                        &mut tx_events,
+                        mempool_settings,
+                        |_, available_tx, _| {
+                            considered.push(available_tx.tx.tx.txid());
+                            Ok(Some(
+                                TransactionResult::success(
+                                    &available_tx.tx.tx,
+                                    available_tx.tx.metadata.tx_fee,
+                                    StacksTransactionReceipt::from_stx_transfer(
+                                        available_tx.tx.tx.clone(),
+                                        vec![],
+                                        Value::okay(Value::Bool(true)).unwrap(),
+                                        ExecutionCost::ZERO,
+                                    ),
+                                )
+                                .convert_to_event(),
+                            ))
+                        },
+                    )
+                    .unwrap();
+            },
+        );
+        considered
+    };
+
+    // by their own fee rates, B's transaction goes before A's cheap one
+    assert_eq!(
+        walk_order(false),
+        vec![txids[2].clone(), txids[0].clone(), txids[1].clone()]
+    );
+    // by the fee rate of A's whole chain, A's transactions go first
+    assert_eq!(
+        walk_order(true),
+        vec![txids[0].clone(), txids[1].clone(), txids[2].clone()]
+    );
+}
+
+#[test]
+fn test_tx_admissions() {
+    let mut chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+
+    let addr = StacksAddress::new(1, Hash160([0xff; 20])).unwrap();
+    let consensus_hash = ConsensusHash([0x11; 20]);
+    let block_hash = BlockHeaderHash([0x22; 32]);
+    let mut txids = vec![];
+
+    let mut mempool_tx = mempool.tx_begin().unwrap();
+    for _ in 0..2 {
+        let pk = StacksPrivateKey::random();
+        let mut tx = StacksTransaction {
+            version: TransactionVersion::Testnet,
+            chain_id: 0x80000000,
+            auth: TransactionAuth::from_p2pkh(&pk).unwrap(),
+            anchor_mode: TransactionAnchorMode::Any,
+            post_condition_mode: TransactionPostConditionMode::Allow,
+            post_conditions: vec![],
+            payload: TransactionPayload::TokenTransfer(
+                addr.to_account_principal(),
+                123,
+                TokenTransferMemo([0u8; 34]),
+            ),
+        };
+        tx.set_tx_fee(1000);
+        tx.set_origin_nonce(0);
+
+        let txid = tx.txid();
+        let origin_addr = tx.origin_address();
+        MemPoolDB::try_add_tx(
+            &mut mempool_tx,
+            &mut chainstate,
+            &consensus_hash,
+            &block_hash,
+            false, // don't resolve the above chain tip since it doesn't exist
+            txid.clone(),
+            tx.serialize_to_vec(),
+            tx.get_tx_fee(),
+            10,
+            &origin_addr,
+            0,
+            &origin_addr,
+            0,
+            None,
+        )
+        .unwrap();
+        txids.push(txid);
+    }
+
+    let cost = ExecutionCost {
+        write_length: 1,
+        write_count: 2,
+        read_length: 3,
+        read_count: 4,
+        runtime: 5,
+    };
+    MemPoolDB::record_admission(
+        &mempool_tx,
+        &txids[0],
+        &consensus_hash,
+        &block_hash,
+        Some(&cost),
+    )
+    .unwrap();
+    MemPoolDB::record_admission(&mempool_tx, &txids[1], &consensus_hash, &block_hash, None)
+        .unwrap();
+    mempool_tx.commit().unwrap();
+
+    assert_eq!(
+        MemPoolDB::get_admission_estimated_cost(mempool.conn(), &txids[0]).unwrap(),
+        Some(cost)
+    );
+    assert_eq!(
+        MemPoolDB::get_admission_estimated_cost(mempool.conn(), &txids[1]).unwrap(),
+        None
+    );
+
+    // dropping a transaction drops its admission record too
+    mempool.drop_txs(&txids[0..1]).unwrap();
+    let num_admissions: i64 = mempool
+        .conn()
+        .query_row("SELECT COUNT(*) FROM tx_admissions", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(num_admissions, 1);
+}
+
+fn make_contract_call_tx(
+    contract_address: StacksAddress,
+    contract_name: &str,
+    fee: u64,
+) -> StacksTransaction {
+    let pk = StacksPrivateKey::random();
+    let mut tx = StacksTransaction {
+        version: TransactionVersion::Testnet,
+        chain_id: 0x80000000,
+        auth: TransactionAuth::from_p2pkh(&pk).unwrap(),
+        anchor_mode: TransactionAnchorMode::Any,
+        post_condition_mode: TransactionPostConditionMode::Allow,
+        post_conditions: vec![],
+        payload: TransactionPayload::new_contract_call(
+            contract_address,
+            contract_name,
+            "foo",
+            vec![],
+        )
+        .unwrap(),
+    };
+    tx.set_tx_fee(fee);
+    tx
+}
+
+#[test]
+fn test_contract_rules_policy() {
+    let addr = StacksAddress::new(1, Hash160([0xff; 20])).unwrap();
+    let contract =
+        |name: &str| QualifiedContractIdentifier::new(addr.into(), ContractName::from(name));
+    let policy = ContractRulesPolicy {
+        deny_contracts: HashSet::from([contract("denied")]),
+        deprioritize_contracts: HashSet::from([contract("spammy"), contract("pricey")]),
+        contract_fee_floors: HashMap::from([(contract("pricey"), 1000)]),
+    };
+    assert!(!policy.is_empty());
+    assert!(ContractRulesPolicy::default().is_empty());
+
+    let decision = |name, fee| policy.evaluate(&make_contract_call_tx(addr, name, fee));
+    assert_eq!(decision("other", 1), AdmissionDecision::Admit);
+    assert!(matches!(
+        decision("denied", 1_000_000),
+        AdmissionDecision::Reject(_)
+    ));
+    assert!(matches!(
+        decision("spammy", 1),
+        AdmissionDecision::Deprioritize(_)
+    ));
+    // the fee floor is checked before deprioritization
+    assert!(matches!(
+        decision("pricey", 999),
+        AdmissionDecision::Reject(_)
+    ));
+    assert!(matches!(
+        decision("pricey", 1000),
+        AdmissionDecision::Deprioritize(_)
+    ));
+
+    // deploying a denied contract is denied too
+    let pk = StacksPrivateKey::random();
+    let mut deploy = StacksTransaction {
+        version: TransactionVersion::Testnet,
+        chain_id: 0x80000000,
+        auth: TransactionAuth::from_p2pkh(&pk).unwrap(),
+        anchor_mode: TransactionAnchorMode::Any,
+        post_condition_mode: TransactionPostConditionMode::Allow,
+        post_conditions: vec![],
+        payload: TransactionPayload::new_smart_contract("denied", "(+ 1 1)", None).unwrap(),
+    };
+    deploy.set_tx_fee(1);
+    let deployer_policy = ContractRulesPolicy {
+        deny_contracts: HashSet::from([QualifiedContractIdentifier::new(
+            deploy.origin_address().into(),
+            ContractName::from("denied"),
+        )]),
+        ..ContractRulesPolicy::default()
+    };
+    assert!(matches!(
+        deployer_policy.evaluate(&deploy),
+        AdmissionDecision::Reject(_)
+    ));
+    assert_eq!(policy.evaluate(&deploy), AdmissionDecision::Admit);
+}
+
+#[cfg(feature = "mempool-admission-policy")]
+#[test]
+fn test_check_admission_policy() {
+    let _chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+
+    let addr = StacksAddress::new(1, Hash160([0xff; 20])).unwrap();
+    let contract =
+        |name: &str| QualifiedContractIdentifier::new(addr.into(), ContractName::from(name));
+    let denied_tx = make_contract_call_tx(addr, "denied", 1);
+    let spammy_tx = make_contract_call_tx(addr, "spammy", 1);
+    let other_tx = make_contract_call_tx(addr, "other", 1);
+
+    // no policy, no change
+    assert_eq!(
+        mempool
+            .check_admission_policy(&denied_tx, Some(1.5))
+            .unwrap(),
+        Some(1.5)
+    );
+
+    mempool.set_admission_policy(Box::new(ContractRulesPolicy {
+        deny_contracts: HashSet::from([contract("denied")]),
+        deprioritize_contracts: HashSet::from([contract("spammy")]),
+        ..ContractRulesPolicy::default()
+    }));
+    let rejection = mempool
+        .check_admission_policy(&denied_tx, Some(1.5))
+        .unwrap_err();
+    assert!(matches!(
+        &rejection,
+        MemPoolRejection::PolicyRejected { policy, .. } if policy == "contract_rules"
+    ));
+    let json = rejection.into_json(&denied_tx.txid());
+    assert_eq!(json["reason"], "PolicyRejected");
+    assert_eq!(json["reason_data"]["policy"], "contract_rules");
+
+    assert_eq!(
+        mempool
+            .check_admission_policy(&spammy_tx, Some(1.5))
+            .unwrap(),
+        Some(0.0)
+    );
+    assert_eq!(
+        mempool.check_admission_policy(&other_tx, None).unwrap(),
+        None
+    );
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use clarity::types::sqlite::NO_PARAMS;
+use clarity::vm::costs::ExecutionCost;
+use clarity::vm::types::{QualifiedContractIdentifier, StandardPrincipalData};
+use rusqlite::{
+    params, Connection, Error as SqliteError, OpenFlags, Transaction as SqliteTransaction,
+};
+
+use super::EstimatorError;
+use crate::chainstate::stacks::db::StacksEpochReceipt;
+use crate::chainstate::stacks::events::{StacksTransactionReceipt, TransactionOrigin};
+use crate::chainstate::stacks::TransactionPayload;
+use crate::util_lib::db::{sqlite_open, table_exists, tx_begin_immediate_sqlite};
+
+/// This struct accumulates the Clarity execution cost charged to each contract, block by block,
+/// so operators can see which contracts dominate block budgets.
+///
+/// A contract is charged for calls into it and for its own deployment.  Rows are kept for the
+/// most recent `retention` Stacks block heights.  If more than one block is processed at a height
+/// (i.e. in a fork), the most recently processed one replaces the others.
+pub struct ContractCostDB {
+    db: Connection,
+    retention: u64,
+}
+
+/// Cumulative cost charged to a single contract
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractCostEntry {
+    pub contract_id: String,
+    /// Number of transactions that called or deployed this contract
+    pub num_txs: u64,
+    pub cost: ExecutionCost,
+}
+
+const CREATE_TABLE: &str = "
+CREATE TABLE contract_costs (
+    contract_id TEXT NOT NULL,
+    block_height INTEGER NOT NULL,
+    num_txs INTEGER NOT NULL,
+    runtime INTEGER NOT NULL,
+    read_count INTEGER NOT NULL,
+    read_length INTEGER NOT NULL,
+    write_count INTEGER NOT NULL,
+    write_length INTEGER NOT NULL,
+    PRIMARY KEY(contract_id, block_height)
+)";
+const CREATE_INDEX: &str =
+    "CREATE INDEX IF NOT EXISTS index_contract_costs_by_height ON contract_costs(block_height)";
+
+/// Costs are summed in SQL, so they are stored as (saturated) i64s
+fn saturating_u64_to_sql(x: u64) -> i64 {
+    i64::try_from(x).unwrap_or(i64::MAX)
+}
+
+impl ContractCostDB {
+    pub fn open(p: &Path, retention: u64) -> Result<ContractCostDB, EstimatorError> {
+        let db = sqlite_open(p, OpenFlags::SQLITE_OPEN_READ_WRITE, false).or_else(|e| {
+            if let SqliteError::SqliteFailure(ref internal, _) = e {
+                if let rusqlite::ErrorCode::CannotOpen = internal.code {
+                    let mut db = sqlite_open(
+                        p,
+                        OpenFlags::SQLITE_OPEN_CREATE | OpenFlags::SQLITE_OPEN_READ_WRITE,
+                        false,
+                    )?;
+                    let tx = tx_begin_immediate_sqlite(&mut db)?;
+                    ContractCostDB::instantiate_db(&tx)?;
+                    tx.commit()?;
+                    Ok(db)
+                } else {
+                    Err(e)
+                }
+            } else {
+                Err(e)
+            }
+        })?;
+
+        Ok(ContractCostDB { db, retention })
+    }
+
+    /// Check if the SQL database was already created. Necessary to avoid races if
+    ///  different threads open the DB at the same time.
+    fn db_already_instantiated(tx: &SqliteTransaction) -> Result<bool, SqliteError> {
+        table_exists(tx, "contract_costs")
+    }
+
+    fn instantiate_db(tx: &SqliteTransaction) -> Result<(), SqliteError> {
+        if !Self::db_already_instantiated(tx)? {
+            tx.execute(CREATE_TABLE, NO_PARAMS)?;
+            tx.execute(CREATE_INDEX, NO_PARAMS)?;
+        }
+
+        Ok(())
+    }
+
+    /// Number of Stacks block heights for which costs are kept
+    pub fn retention(&self) -> u64 {
+        self.retention
+    }
+
+    /// Which contract, if any, should be charged for this transaction's execution cost?
+    fn get_charged_contract(
+        receipt: &StacksTransactionReceipt,
+    ) -> Option<QualifiedContractIdentifier> {
+        let TransactionOrigin::Stacks(ref tx) = receipt.transaction else {
+            return None;
+        };
+        match &tx.payload {
+            TransactionPayload::ContractCall(cc) => Some(cc.to_clarity_contract_id()),
+            TransactionPayload::SmartContract(sc, _) => Some(QualifiedContractIdentifier::new(
+                StandardPrincipalData::from(tx.origin_address()),
+                sc.name.clone(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Record the costs charged to each contract in a newly-processed block, and drop costs
+    /// from blocks that are now older than the retention window.
+    pub fn notify_block(&mut self, receipt: &StacksEpochReceipt) -> Result<(), EstimatorError> {
+        let block_height = receipt.header.stacks_block_height;
+
+        let mut block_costs: HashMap<QualifiedContractIdentifier, (u64, ExecutionCost)> =
+            HashMap::new();
+        for tx_receipt in receipt.tx_receipts.iter() {
+            let Some(contract_id) = Self::get_charged_contract(tx_receipt) else {
+                continue;
+            };
+            let (num_txs, cost) = block_costs
+                .entry(contract_id)
+                .or_insert_with(|| (0, ExecutionCost::ZERO));
+            *num_txs += 1;
+            if cost.add(&tx_receipt.execution_cost).is_err() {
+                *cost = ExecutionCost::max_value();
+            }
+        }
+
+        let tx = tx_begin_immediate_sqlite(&mut self.db)?;
+        let height_sql = saturating_u64_to_sql(block_height);
+        tx.execute(
+            "DELETE FROM contract_costs WHERE block_height = ?",
+            params![height_sql],
+        )?;
+        for (contract_id, (num_txs, cost)) in block_costs.iter() {
+            tx.execute(
+                "INSERT INTO contract_costs
+                    (contract_id, block_height, num_txs, runtime, read_count, read_length, write_count, write_length)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    contract_id.to_string(),
+                    height_sql,
+                    saturating_u64_to_sql(*num_txs),
+                    saturating_u64_to_sql(cost.runtime),
+                    saturating_u64_to_sql(cost.read_count),
+                    saturating_u64_to_sql(cost.read_length),
+                    saturating_u64_to_sql(cost.write_count),
+                    saturating_u64_to_sql(cost.write_length),
+                ],
+            )?;
+        }
+        if block_height >= self.retention {
+            let oldest_height = saturating_u64_to_sql(block_height - self.retention);
+            tx.execute(
+                "DELETE FROM contract_costs WHERE block_height <= ?",
+                params![oldest_height],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Get the highest Stacks block height for which costs have been recorded
+    pub fn get_tip_height(&self) -> Result<Option<u64>, EstimatorError> {
+        let height: Option<i64> = self.db.query_row(
+            "SELECT MAX(block_height) FROM contract_costs",
+            NO_PARAMS,
+            |row| row.get(0),
+        )?;
+        Ok(height.map(|h| u64::try_from(h).expect("DB corrupt: negative block height")))
+    }
+
+    /// Get the cumulative cost charged to each contract over the last `window` recorded Stacks
+    /// block heights (capped at the retention window), with the contracts that used the most
+    /// runtime first.  At most `limit` contracts are returned.
+    pub fn get_contract_costs(
+        &self,
+        window: u64,
+        limit: u64,
+    ) -> Result<Vec<ContractCostEntry>, EstimatorError> {
+        let Some(tip_height) = self.get_tip_height()? else {
+            return Ok(vec![]);
+        };
+        let window = window.min(self.retention);
+        let min_height = saturating_u64_to_sql((tip_height + 1).saturating_sub(window));
+
+        let sql =
+            "SELECT contract_id, SUM(num_txs), SUM(runtime), SUM(read_count), SUM(read_length),
+                          SUM(write_count), SUM(write_length)
+                   FROM contract_costs WHERE block_height >= ?
+                   GROUP BY contract_id ORDER BY SUM(runtime) DESC, contract_id ASC LIMIT ?";
+        let mut stmt = self.db.prepare(sql)?;
+        let rows = stmt.query_map(params![min_height, saturating_u64_to_sql(limit)], |row| {
+            let get_u64 = |idx: usize| -> Result<u64, SqliteError> {
+                let value: i64 = row.get(idx)?;
+                Ok(u64::try_from(value).unwrap_or(0))
+            };
+            Ok(ContractCostEntry {
+                contract_id: row.get(0)?,
+                num_txs: get_u64(1)?,
+                cost: ExecutionCost {
+                    runtime: get_u64(2)?,
+                    read_count: get_u64(3)?,
+                    read_length: get_u64(4)?,
+                    write_count: get_u64(5)?,
+                    write_length: get_u64(6)?,
+                },
+            })
+        })?;
+        let entries = rows.collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+}
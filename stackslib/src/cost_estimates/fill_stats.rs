@@ -0,0 +1,319 @@
+use std::collections::VecDeque;
+
+use clarity::vm::costs::ExecutionCost;
+
+use crate::chainstate::stacks::MAX_BLOCK_LEN;
+
+/// Default number of recently-mined blocks to keep fill statistics for
+pub const DEFAULT_FILL_STATS_MAX_BLOCKS: usize = 1000;
+
+/// Lower bound on a recommended `nakamoto_attempt_time_ms`
+pub const MIN_RECOMMENDED_ATTEMPT_TIME_MS: u64 = 1_000;
+/// Upper bound on a recommended `nakamoto_attempt_time_ms`
+pub const MAX_RECOMMENDED_ATTEMPT_TIME_MS: u64 = 60_000;
+
+/// A block's assembly is considered cut short by the attempt timeout if it took at least this
+/// percentage of `nakamoto_attempt_time_ms`
+const TIME_BOUND_PCT: u64 = 90;
+/// A block is considered capped by the soft limit if it used at least this percentage of it
+const SOFT_LIMIT_BOUND_PCT: u64 = 90;
+/// Step by which to recommend raising `tenure_cost_limit_per_block_percentage`
+const SOFT_LIMIT_STEP_PCT: u8 = 10;
+
+/// How full a single mined block was, along with the miner settings it was mined with
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockFillRecord {
+    pub stacks_block_height: u64,
+    pub tx_count: u64,
+    /// Size of the block in bytes
+    pub size: u64,
+    /// Execution cost consumed by this block alone
+    pub block_consumed: ExecutionCost,
+    /// Execution cost consumed by the tenure, including this block
+    pub tenure_consumed: ExecutionCost,
+    /// Execution cost budget of the tenure
+    pub tenure_budget: ExecutionCost,
+    pub assembly_time_ms: u64,
+    /// The miner's `nakamoto_attempt_time_ms` when this block was mined
+    pub attempt_time_ms: u64,
+    /// The miner's `tenure_cost_limit_per_block_percentage` when this block was mined
+    pub soft_limit_pct: Option<u8>,
+}
+
+/// Percentage of some budget used in each cost dimension
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct CostFillPercentages {
+    pub runtime: u64,
+    pub read_count: u64,
+    pub read_length: u64,
+    pub write_count: u64,
+    pub write_length: u64,
+}
+
+fn percent_of(numerator: u64, denominator: u64) -> u64 {
+    let pct = u128::from(numerator) * 100 / u128::from(denominator.max(1));
+    u64::try_from(pct).unwrap_or(u64::MAX)
+}
+
+impl CostFillPercentages {
+    pub fn from_costs(consumed: &ExecutionCost, budget: &ExecutionCost) -> Self {
+        Self {
+            runtime: percent_of(consumed.runtime, budget.runtime),
+            read_count: percent_of(consumed.read_count, budget.read_count),
+            read_length: percent_of(consumed.read_length, budget.read_length),
+            write_count: percent_of(consumed.write_count, budget.write_count),
+            write_length: percent_of(consumed.write_length, budget.write_length),
+        }
+    }
+
+    /// The percentage used by the fullest dimension
+    pub fn max_dimension(&self) -> u64 {
+        self.runtime
+            .max(self.read_count)
+            .max(self.read_length)
+            .max(self.write_count)
+            .max(self.write_length)
+    }
+
+    fn saturating_add(&mut self, other: &CostFillPercentages) {
+        self.runtime = self.runtime.saturating_add(other.runtime);
+        self.read_count = self.read_count.saturating_add(other.read_count);
+        self.read_length = self.read_length.saturating_add(other.read_length);
+        self.write_count = self.write_count.saturating_add(other.write_count);
+        self.write_length = self.write_length.saturating_add(other.write_length);
+    }
+
+    fn divide(&mut self, n: u64) {
+        let n = n.max(1);
+        self.runtime /= n;
+        self.read_count /= n;
+        self.read_length /= n;
+        self.write_count /= n;
+        self.write_length /= n;
+    }
+}
+
+impl BlockFillRecord {
+    /// How much of the tenure budget this block used, per dimension
+    pub fn cost_fill(&self) -> CostFillPercentages {
+        CostFillPercentages::from_costs(&self.block_consumed, &self.tenure_budget)
+    }
+
+    /// How much of the tenure budget that was left when this block was started it used, in its
+    /// fullest dimension.  This is what `tenure_cost_limit_per_block_percentage` is applied to.
+    pub fn remaining_budget_fill_pct(&self) -> u64 {
+        let mut cost_at_start = self.tenure_consumed.clone();
+        if cost_at_start.sub(&self.block_consumed).is_err() {
+            cost_at_start = ExecutionCost::ZERO;
+        }
+        let mut remaining = self.tenure_budget.clone();
+        if remaining.sub(&cost_at_start).is_err() {
+            return 100;
+        }
+        CostFillPercentages::from_costs(&self.block_consumed, &remaining).max_dimension()
+    }
+
+    /// How much of the maximum block size this block used
+    pub fn size_fill_pct(&self) -> u64 {
+        percent_of(self.size, u64::from(MAX_BLOCK_LEN))
+    }
+
+    /// Was this block's assembly cut short by the attempt timeout?
+    pub fn is_time_bound(&self) -> bool {
+        percent_of(self.assembly_time_ms, self.attempt_time_ms) >= TIME_BOUND_PCT
+    }
+
+    /// Was this block capped by the per-block soft limit?
+    pub fn is_soft_limit_bound(&self) -> bool {
+        let Some(soft_limit_pct) = self.soft_limit_pct else {
+            return false;
+        };
+        percent_of(self.remaining_budget_fill_pct(), u64::from(soft_limit_pct))
+            >= SOFT_LIMIT_BOUND_PCT
+    }
+}
+
+/// Suggested miner settings, derived from how full recently-mined blocks were
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FillRecommendation {
+    pub nakamoto_attempt_time_ms: u64,
+    pub tenure_cost_limit_per_block_percentage: Option<u8>,
+    /// Human-readable explanation of the suggestion
+    pub reasons: Vec<String>,
+}
+
+/// Summary of how full recently-mined blocks were
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FillStatsSummary {
+    pub num_blocks: u64,
+    /// Lowest and highest Stacks block heights summarized
+    pub first_block_height: Option<u64>,
+    pub last_block_height: Option<u64>,
+    /// Average percentage of the tenure budget used by each block, per dimension
+    pub avg_cost_fill_pct: CostFillPercentages,
+    /// Highest percentage of the tenure budget used by any block, in its fullest dimension
+    pub max_cost_fill_pct: u64,
+    /// Average percentage of the maximum block size used by each block
+    pub avg_size_fill_pct: u64,
+    pub avg_assembly_time_ms: u64,
+    pub max_assembly_time_ms: u64,
+    /// Number of blocks whose assembly was cut short by `nakamoto_attempt_time_ms`
+    pub num_time_bound_blocks: u64,
+    /// Number of blocks capped by `tenure_cost_limit_per_block_percentage`
+    pub num_soft_limit_bound_blocks: u64,
+    /// Suggested settings, if any blocks have been recorded
+    pub recommendation: Option<FillRecommendation>,
+}
+
+/// In-memory record of how full each recently-mined block was.  The miner thread records each
+/// block it assembles, and the RPC server summarizes them for operators tuning
+/// `nakamoto_attempt_time_ms` and `tenure_cost_limit_per_block_percentage`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinerFillStats {
+    max_blocks: usize,
+    blocks: VecDeque<BlockFillRecord>,
+}
+
+impl Default for MinerFillStats {
+    fn default() -> Self {
+        Self::new(DEFAULT_FILL_STATS_MAX_BLOCKS)
+    }
+}
+
+impl MinerFillStats {
+    pub fn new(max_blocks: usize) -> Self {
+        Self {
+            max_blocks: max_blocks.max(1),
+            blocks: VecDeque::new(),
+        }
+    }
+
+    /// Record a newly-mined block, dropping the oldest one if we're at capacity
+    pub fn record_block(&mut self, record: BlockFillRecord) {
+        while self.blocks.len() >= self.max_blocks {
+            self.blocks.pop_front();
+        }
+        self.blocks.push_back(record);
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Summarize the last `window` recorded blocks
+    pub fn summarize(&self, window: usize) -> FillStatsSummary {
+        let skip = self.blocks.len().saturating_sub(window);
+        let blocks: Vec<_> = self.blocks.iter().skip(skip).collect();
+        let num_blocks = u64::try_from(blocks.len()).unwrap_or(u64::MAX);
+
+        let mut avg_cost_fill_pct = CostFillPercentages::default();
+        let mut max_cost_fill_pct = 0;
+        let mut total_size_fill_pct: u64 = 0;
+        let mut total_assembly_time_ms: u64 = 0;
+        let mut max_assembly_time_ms = 0;
+        let mut num_time_bound_blocks = 0;
+        let mut num_soft_limit_bound_blocks = 0;
+        for block in blocks.iter() {
+            let cost_fill = block.cost_fill();
+            max_cost_fill_pct = max_cost_fill_pct.max(cost_fill.max_dimension());
+            avg_cost_fill_pct.saturating_add(&cost_fill);
+            total_size_fill_pct = total_size_fill_pct.saturating_add(block.size_fill_pct());
+            total_assembly_time_ms = total_assembly_time_ms.saturating_add(block.assembly_time_ms);
+            max_assembly_time_ms = max_assembly_time_ms.max(block.assembly_time_ms);
+            if block.is_time_bound() {
+                num_time_bound_blocks += 1;
+            }
+            if block.is_soft_limit_bound() {
+                num_soft_limit_bound_blocks += 1;
+            }
+        }
+        avg_cost_fill_pct.divide(num_blocks);
+
+        let mut summary = FillStatsSummary {
+            num_blocks,
+            first_block_height: blocks.first().map(|b| b.stacks_block_height),
+            last_block_height: blocks.last().map(|b| b.stacks_block_height),
+            avg_cost_fill_pct,
+            max_cost_fill_pct,
+            avg_size_fill_pct: total_size_fill_pct / num_blocks.max(1),
+            avg_assembly_time_ms: total_assembly_time_ms / num_blocks.max(1),
+            max_assembly_time_ms,
+            num_time_bound_blocks,
+            num_soft_limit_bound_blocks,
+            recommendation: None,
+        };
+        summary.recommendation = blocks
+            .last()
+            .map(|latest| Self::recommend(&summary, latest.attempt_time_ms, latest.soft_limit_pct));
+        summary
+    }
+
+    /// Suggest settings based on a summary, given the settings currently in use.
+    ///
+    /// * If most blocks were cut short by the attempt timeout, then the miner is running out of
+    ///   time before it runs out of budget, so suggest a longer attempt time.
+    /// * If no block came close to the attempt timeout, suggest a shorter one so blocks are
+    ///   proposed sooner.
+    /// * If most blocks were capped by the per-block soft limit, suggest raising it.
+    fn recommend(
+        summary: &FillStatsSummary,
+        attempt_time_ms: u64,
+        soft_limit_pct: Option<u8>,
+    ) -> FillRecommendation {
+        let mut reasons = vec![];
+        let majority = summary.num_blocks / 2 + 1;
+
+        let mut nakamoto_attempt_time_ms = attempt_time_ms;
+        if summary.num_time_bound_blocks >= majority {
+            nakamoto_attempt_time_ms = attempt_time_ms.saturating_add(attempt_time_ms / 2).clamp(
+                MIN_RECOMMENDED_ATTEMPT_TIME_MS,
+                MAX_RECOMMENDED_ATTEMPT_TIME_MS,
+            );
+            reasons.push(format!(
+                "{} of {} blocks used the full attempt time; allow more time to fill them",
+                summary.num_time_bound_blocks, summary.num_blocks
+            ));
+        } else if summary.max_assembly_time_ms.saturating_mul(2) < attempt_time_ms {
+            nakamoto_attempt_time_ms = summary
+                .max_assembly_time_ms
+                .saturating_mul(2)
+                .clamp(
+                    MIN_RECOMMENDED_ATTEMPT_TIME_MS,
+                    MAX_RECOMMENDED_ATTEMPT_TIME_MS,
+                )
+                .min(attempt_time_ms);
+            if nakamoto_attempt_time_ms < attempt_time_ms {
+                reasons.push(format!(
+                    "no block took more than {}ms to assemble; a shorter attempt time proposes blocks sooner",
+                    summary.max_assembly_time_ms
+                ));
+            }
+        }
+
+        let mut tenure_cost_limit_per_block_percentage = soft_limit_pct;
+        if let Some(pct) = soft_limit_pct {
+            if pct < 100 && summary.num_soft_limit_bound_blocks >= majority {
+                let new_pct = pct.saturating_add(SOFT_LIMIT_STEP_PCT).min(100);
+                tenure_cost_limit_per_block_percentage = Some(new_pct);
+                reasons.push(format!(
+                    "{} of {} blocks were capped by the {pct}% per-block soft limit",
+                    summary.num_soft_limit_bound_blocks, summary.num_blocks
+                ));
+            }
+        }
+
+        if reasons.is_empty() {
+            reasons.push("current settings look appropriate for recent blocks".into());
+        }
+
+        FillRecommendation {
+            nakamoto_attempt_time_ms,
+            tenure_cost_limit_per_block_percentage,
+            reasons,
+        }
+    }
+}
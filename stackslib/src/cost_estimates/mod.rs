@@ -14,9 +14,11 @@ use crate::chainstate::stacks::db::StacksEpochReceipt;
 use crate::chainstate::stacks::events::{StacksTransactionReceipt, TransactionOrigin};
 use crate::chainstate::stacks::{StacksBlock, TransactionPayload};
 
+pub mod contract_costs;
 pub mod fee_medians;
 pub mod fee_rate_fuzzer;
 pub mod fee_scalar;
+pub mod fill_stats;
 pub mod metrics;
 pub mod pessimistic;
 
@@ -112,8 +114,18 @@ pub fn estimate_fee_rate<CE: CostEstimator + ?Sized, CM: CostMetric + ?Sized>(
     stacks_epoch_id: &StacksEpochId,
 ) -> Result<f64, EstimatorError> {
     let cost_estimate = estimator.estimate_cost(&tx.payload, stacks_epoch_id)?;
-    let metric_estimate = metric.from_cost_and_len(&cost_estimate, block_limit, tx.tx_len());
-    Ok(tx.get_tx_fee() as f64 / metric_estimate as f64)
+    Ok(fee_rate_for_cost(tx, &cost_estimate, metric, block_limit))
+}
+
+/// Compute the fee rate of `tx`, given an estimate of its execution cost
+pub fn fee_rate_for_cost<CM: CostMetric + ?Sized>(
+    tx: &StacksTransaction,
+    cost_estimate: &ExecutionCost,
+    metric: &CM,
+    block_limit: &ExecutionCost,
+) -> f64 {
+    let metric_estimate = metric.from_cost_and_len(cost_estimate, block_limit, tx.tx_len());
+    tx.get_tx_fee() as f64 / metric_estimate as f64
 }
 
 /// This trait is for implementation of *execution cost* estimation. CostEstimators
@@ -0,0 +1,177 @@
+use std::env;
+
+use clarity::vm::costs::ExecutionCost;
+use clarity::vm::types::{PrincipalData, StandardPrincipalData};
+use clarity::vm::Value;
+use rand::Rng;
+use stacks_common::types::chainstate::StacksAddress;
+use stacks_common::util::hash::{to_hex, Hash160};
+
+use crate::chainstate::stacks::events::{StacksTransactionReceipt, TransactionOrigin};
+use crate::chainstate::stacks::{
+    StacksTransaction, TokenTransferMemo, TransactionAuth, TransactionContractCall,
+    TransactionPayload, TransactionSmartContract, TransactionSpendingCondition, TransactionVersion,
+};
+use crate::cost_estimates::contract_costs::ContractCostDB;
+use crate::cost_estimates::tests::common::make_block_receipt;
+use crate::util_lib::strings::StacksString;
+
+fn instantiate_test_db(retention: u64) -> ContractCostDB {
+    let mut path = env::temp_dir();
+    let random_bytes = rand::thread_rng().gen::<[u8; 32]>();
+    path.push(&format!(
+        "contract_costs_{}.sqlite",
+        &to_hex(&random_bytes)[0..8]
+    ));
+
+    ContractCostDB::open(&path, retention).expect("Test failure: could not open contract cost DB")
+}
+
+fn make_cost(runtime: u64) -> ExecutionCost {
+    ExecutionCost {
+        write_length: 1,
+        write_count: 1,
+        read_length: 1,
+        read_count: 1,
+        runtime,
+    }
+}
+
+fn make_receipt(payload: TransactionPayload, cost: ExecutionCost) -> StacksTransactionReceipt {
+    let tx = StacksTransaction::new(
+        TransactionVersion::Mainnet,
+        TransactionAuth::Standard(TransactionSpendingCondition::new_initial_sighash()),
+        payload,
+    );
+    StacksTransactionReceipt::from_contract_call(
+        tx,
+        vec![],
+        Value::okay(Value::Bool(true)).unwrap(),
+        0,
+        cost,
+    )
+}
+
+fn contract_address() -> StacksAddress {
+    StacksAddress::new(0, Hash160([0; 20])).unwrap()
+}
+
+fn make_cc_receipt(contract_name: &str, runtime: u64) -> StacksTransactionReceipt {
+    make_receipt(
+        TransactionPayload::ContractCall(TransactionContractCall {
+            address: contract_address(),
+            contract_name: contract_name.into(),
+            function_name: "f".into(),
+            function_args: vec![],
+        }),
+        make_cost(runtime),
+    )
+}
+
+fn make_transfer_receipt() -> StacksTransactionReceipt {
+    make_receipt(
+        TransactionPayload::TokenTransfer(
+            PrincipalData::Standard(StandardPrincipalData::new(0, [0; 20]).unwrap()),
+            1,
+            TokenTransferMemo([0; 34]),
+        ),
+        make_cost(1000),
+    )
+}
+
+fn record_block(db: &mut ContractCostDB, height: u64, tx_receipts: Vec<StacksTransactionReceipt>) {
+    let mut receipt = make_block_receipt(tx_receipts);
+    receipt.header.stacks_block_height = height;
+    db.notify_block(&receipt).unwrap();
+}
+
+#[test]
+fn test_empty_contract_cost_db() {
+    let db = instantiate_test_db(10);
+    assert_eq!(db.get_tip_height().unwrap(), None);
+    assert!(db.get_contract_costs(10, 10).unwrap().is_empty());
+}
+
+#[test]
+fn test_contract_costs_accumulate() {
+    let mut db = instantiate_test_db(10);
+
+    let busy_id = format!("{}.busy", contract_address());
+    let idle_id = format!("{}.idle", contract_address());
+    let deploy = make_receipt(
+        TransactionPayload::SmartContract(
+            TransactionSmartContract {
+                name: "deployed".into(),
+                code_body: StacksString::from_str("(define-constant a 1)").unwrap(),
+            },
+            None,
+        ),
+        make_cost(5),
+    );
+    let TransactionOrigin::Stacks(ref deploy_tx) = deploy.transaction else {
+        panic!("not a Stacks transaction");
+    };
+    let deployed_id = format!("{}.deployed", deploy_tx.origin_address());
+
+    record_block(
+        &mut db,
+        1,
+        vec![
+            make_cc_receipt("busy", 100),
+            make_cc_receipt("idle", 1),
+            make_transfer_receipt(),
+            deploy,
+        ],
+    );
+    record_block(
+        &mut db,
+        2,
+        vec![make_cc_receipt("busy", 200), make_cc_receipt("busy", 300)],
+    );
+
+    let costs = db.get_contract_costs(10, 10).unwrap();
+    assert_eq!(costs.len(), 3);
+
+    // most runtime first, and transfers are not charged to any contract
+    assert_eq!(costs[0].contract_id, busy_id);
+    assert_eq!(costs[0].num_txs, 3);
+    assert_eq!(costs[0].cost.runtime, 600);
+    assert_eq!(costs[0].cost.read_count, 3);
+    assert_eq!(costs[1].contract_id, deployed_id);
+    assert_eq!(costs[1].num_txs, 1);
+    assert_eq!(costs[2].contract_id, idle_id);
+    assert_eq!(costs[2].cost.runtime, 1);
+
+    // window of 1 only covers the tip
+    let costs = db.get_contract_costs(1, 10).unwrap();
+    assert_eq!(costs.len(), 1);
+    assert_eq!(costs[0].num_txs, 2);
+    assert_eq!(costs[0].cost.runtime, 500);
+
+    // limit
+    let costs = db.get_contract_costs(10, 1).unwrap();
+    assert_eq!(costs.len(), 1);
+    assert_eq!(costs[0].cost.runtime, 600);
+
+    // reprocessing a height (i.e. a fork) replaces it
+    record_block(&mut db, 2, vec![make_cc_receipt("idle", 50)]);
+    let costs = db.get_contract_costs(1, 10).unwrap();
+    assert_eq!(costs.len(), 1);
+    assert_eq!(costs[0].contract_id, idle_id);
+    assert_eq!(costs[0].cost.runtime, 50);
+}
+
+#[test]
+fn test_contract_costs_retention() {
+    let mut db = instantiate_test_db(3);
+    for height in 1..=5 {
+        record_block(&mut db, height, vec![make_cc_receipt("busy", height)]);
+    }
+    assert_eq!(db.get_tip_height().unwrap(), Some(5));
+
+    // only heights 3, 4, and 5 are kept, no matter how large the window
+    let costs = db.get_contract_costs(100, 10).unwrap();
+    assert_eq!(costs.len(), 1);
+    assert_eq!(costs[0].num_txs, 3);
+    assert_eq!(costs[0].cost.runtime, 3 + 4 + 5);
+}
@@ -0,0 +1,119 @@
+use clarity::vm::costs::ExecutionCost;
+
+use crate::chainstate::stacks::MAX_BLOCK_LEN;
+use crate::cost_estimates::fill_stats::{BlockFillRecord, MinerFillStats};
+
+fn make_cost(x: u64) -> ExecutionCost {
+    ExecutionCost {
+        write_length: x,
+        write_count: x,
+        read_length: x,
+        read_count: x,
+        runtime: x,
+    }
+}
+
+/// A block that used `block_pct`% of the tenure budget, on top of `prior_pct`% used by earlier
+/// blocks in the tenure
+fn make_record(
+    height: u64,
+    prior_pct: u64,
+    block_pct: u64,
+    assembly_time_ms: u64,
+    soft_limit_pct: Option<u8>,
+) -> BlockFillRecord {
+    BlockFillRecord {
+        stacks_block_height: height,
+        tx_count: 1,
+        size: u64::from(MAX_BLOCK_LEN) / 4,
+        block_consumed: make_cost(block_pct * 100),
+        tenure_consumed: make_cost((prior_pct + block_pct) * 100),
+        tenure_budget: make_cost(10_000),
+        assembly_time_ms,
+        attempt_time_ms: 5_000,
+        soft_limit_pct,
+    }
+}
+
+#[test]
+fn test_empty_fill_stats() {
+    let stats = MinerFillStats::new(10);
+    let summary = stats.summarize(10);
+    assert_eq!(summary.num_blocks, 0);
+    assert_eq!(summary.last_block_height, None);
+    assert!(summary.recommendation.is_none());
+}
+
+#[test]
+fn test_fill_stats_summary() {
+    let mut stats = MinerFillStats::new(3);
+    for height in 1..=4 {
+        stats.record_block(make_record(height, 0, height * 10, height * 1000, None));
+    }
+
+    // only the last 3 blocks are kept
+    assert_eq!(stats.len(), 3);
+    let summary = stats.summarize(10);
+    assert_eq!(summary.num_blocks, 3);
+    assert_eq!(summary.first_block_height, Some(2));
+    assert_eq!(summary.last_block_height, Some(4));
+    assert_eq!(summary.avg_cost_fill_pct.runtime, 30);
+    assert_eq!(summary.max_cost_fill_pct, 40);
+    assert_eq!(summary.avg_size_fill_pct, 25);
+    assert_eq!(summary.avg_assembly_time_ms, 3000);
+    assert_eq!(summary.max_assembly_time_ms, 4000);
+    assert_eq!(summary.num_time_bound_blocks, 0);
+
+    // window only covers the latest block
+    let summary = stats.summarize(1);
+    assert_eq!(summary.num_blocks, 1);
+    assert_eq!(summary.first_block_height, Some(4));
+    assert_eq!(summary.max_cost_fill_pct, 40);
+}
+
+#[test]
+fn test_fill_stats_recommendations() {
+    // blocks that use up the attempt time without filling up should get more time
+    let mut stats = MinerFillStats::new(10);
+    for height in 1..=3 {
+        stats.record_block(make_record(height, 0, 10, 4_900, None));
+    }
+    let summary = stats.summarize(10);
+    assert_eq!(summary.num_time_bound_blocks, 3);
+    let recommendation = summary.recommendation.unwrap();
+    assert_eq!(recommendation.nakamoto_attempt_time_ms, 7_500);
+    assert_eq!(recommendation.tenure_cost_limit_per_block_percentage, None);
+
+    // blocks that are assembled quickly should get less time
+    let mut stats = MinerFillStats::new(10);
+    for height in 1..=3 {
+        stats.record_block(make_record(height, 0, 10, 1_000, None));
+    }
+    let recommendation = stats.summarize(10).recommendation.unwrap();
+    assert_eq!(recommendation.nakamoto_attempt_time_ms, 2_000);
+
+    // blocks capped by the soft limit should get a higher one.  Each of these blocks used 25% of
+    // the budget that was left, and the soft limit is 25%.
+    let mut stats = MinerFillStats::new(10);
+    stats.record_block(make_record(1, 0, 25, 3_000, Some(25)));
+    stats.record_block(make_record(2, 20, 20, 3_000, Some(25)));
+    stats.record_block(make_record(3, 60, 10, 3_000, Some(25)));
+    let summary = stats.summarize(10);
+    assert_eq!(summary.num_soft_limit_bound_blocks, 3);
+    let recommendation = summary.recommendation.unwrap();
+    assert_eq!(recommendation.nakamoto_attempt_time_ms, 5_000);
+    assert_eq!(
+        recommendation.tenure_cost_limit_per_block_percentage,
+        Some(35)
+    );
+
+    // nothing to change
+    let mut stats = MinerFillStats::new(10);
+    stats.record_block(make_record(1, 0, 5, 3_000, Some(25)));
+    let recommendation = stats.summarize(10).recommendation.unwrap();
+    assert_eq!(recommendation.nakamoto_attempt_time_ms, 5_000);
+    assert_eq!(
+        recommendation.tenure_cost_limit_per_block_percentage,
+        Some(25)
+    );
+}
@@ -1,10 +1,12 @@
 use crate::cost_estimates::FeeRateEstimate;
 
 pub mod common;
+pub mod contract_costs;
 pub mod cost_estimators;
 pub mod fee_medians;
 pub mod fee_rate_fuzzer;
 pub mod fee_scalar;
+pub mod fill_stats;
 pub mod metrics;
 
 #[test]
@@ -1608,6 +1608,36 @@ check if the associated microblocks can be downloaded
         process::exit(0);
     }
 
+    if argv[1] == "replay-range" {
+        cli::command_replay_range(&argv[1..], common_opts.config.as_ref());
+        process::exit(0);
+    }
+
+    if argv[1] == "dump-tenure" {
+        cli::command_dump_tenure(&argv[1..], common_opts.config.as_ref());
+        process::exit(0);
+    }
+
+    if argv[1] == "find-replayable" {
+        cli::command_find_replayable(&argv[1..], common_opts.config.as_ref());
+        process::exit(0);
+    }
+
+    if argv[1] == "affirmations" {
+        cli::command_affirmations(&argv[1..], common_opts.config.as_ref());
+        process::exit(0);
+    }
+
+    if argv[1] == "export" {
+        cli::command_export(&argv[1..], common_opts.config.as_ref());
+        process::exit(0);
+    }
+
+    if argv[1] == "check-sortition-db" {
+        cli::command_check_sortition_db(&argv[1..], common_opts.config.as_ref());
+        process::exit(0);
+    }
+
     if argv[1] == "replay-mock-mining" {
         cli::command_replay_mock_mining(&argv[1..], common_opts.config.as_ref());
         process::exit(0);
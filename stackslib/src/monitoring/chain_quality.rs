@@ -0,0 +1,442 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Chain-quality monitoring.
+//!
+//! The chains coordinator feeds every sortition and every processed Nakamoto block to a
+//! `ChainQualityMonitor`, which tracks runs of missed sortitions (burnchain blocks without a
+//! winning block-commit), runs of empty tenures (tenures whose blocks hold no transactions besides
+//! their tenure-change and coinbase), and the fraction of the reward set's signers whose
+//! signatures appear in recent blocks.  It exports these as Prometheus metrics, and raises a
+//! `ChainQualityAlert` when one of them crosses its configured threshold.
+
+use std::collections::VecDeque;
+
+use stacks_common::types::chainstate::{ConsensusHash, StacksBlockId};
+
+use crate::chainstate::stacks::{StacksTransaction, TransactionPayload};
+use crate::monitoring;
+
+/// Number of recent Nakamoto blocks over which signer participation is averaged
+pub const SIGNER_PARTICIPATION_WINDOW: usize = 10;
+
+/// When the chain-quality monitor raises alerts.  A threshold of 0 disables its alert.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainQualityThresholds {
+    /// Alert after this many consecutive sortitions without a winner
+    pub missed_sortitions: u64,
+    /// Alert after this many consecutive Nakamoto tenures without any transactions
+    pub empty_tenures: u64,
+    /// Alert when the average fraction of signers whose signatures appear in the last
+    /// `SIGNER_PARTICIPATION_WINDOW` Nakamoto blocks falls below this
+    pub min_signer_participation: f64,
+}
+
+impl Default for ChainQualityThresholds {
+    fn default() -> Self {
+        Self {
+            missed_sortitions: 3,
+            empty_tenures: 3,
+            min_signer_participation: 0.7,
+        }
+    }
+}
+
+/// A chain-quality problem, raised when it first crosses its threshold
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChainQualityAlert {
+    /// `count` consecutive sortitions, up to and including `burn_height`, had no winner
+    MissedSortitions { burn_height: u64, count: u64 },
+    /// `count` consecutive Nakamoto tenures, up to and including the one started by
+    /// `consensus_hash`, had no transactions besides their tenure-change and coinbase
+    EmptyTenures {
+        consensus_hash: ConsensusHash,
+        count: u64,
+    },
+    /// The average signer participation over the last `SIGNER_PARTICIPATION_WINDOW` blocks, up
+    /// to and including `block_id`, fell below `threshold`
+    LowSignerParticipation {
+        block_id: StacksBlockId,
+        participation: f64,
+        threshold: f64,
+    },
+}
+
+impl ChainQualityAlert {
+    /// Name of the alert, as used in `alert` events and Prometheus labels
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChainQualityAlert::MissedSortitions { .. } => "missed_sortitions",
+            ChainQualityAlert::EmptyTenures { .. } => "empty_tenures",
+            ChainQualityAlert::LowSignerParticipation { .. } => "low_signer_participation",
+        }
+    }
+
+    /// Human-readable description of the alert
+    pub fn message(&self) -> String {
+        match self {
+            ChainQualityAlert::MissedSortitions { burn_height, count } => format!(
+                "{count} consecutive sortitions up to burn block {burn_height} had no winner"
+            ),
+            ChainQualityAlert::EmptyTenures {
+                consensus_hash,
+                count,
+            } => format!(
+                "{count} consecutive tenures up to the tenure of {consensus_hash} had no transactions"
+            ),
+            ChainQualityAlert::LowSignerParticipation {
+                block_id,
+                participation,
+                threshold,
+            } => format!(
+                "Signer participation over the last {SIGNER_PARTICIPATION_WINDOW} blocks up to {block_id} fell to {:.1}%, below {:.1}%",
+                participation * 100.0,
+                threshold * 100.0
+            ),
+        }
+    }
+}
+
+/// Tracks chain-quality statistics across sortitions and Nakamoto blocks
+#[derive(Debug, Clone)]
+pub struct ChainQualityMonitor {
+    thresholds: ChainQualityThresholds,
+    /// Consecutive sortitions without a winner
+    missed_sortitions: u64,
+    /// Consecutive completed tenures without transactions
+    empty_tenures: u64,
+    /// The tenure of the most recently processed block, and its transaction count so far
+    current_tenure: Option<(ConsensusHash, u64)>,
+    /// Signer participation of the most recently processed blocks
+    signer_participation: VecDeque<f64>,
+    /// Whether or not the signer participation alert has been raised since participation was
+    /// last above the threshold
+    low_participation_alerted: bool,
+}
+
+impl ChainQualityMonitor {
+    pub fn new(thresholds: ChainQualityThresholds) -> ChainQualityMonitor {
+        ChainQualityMonitor {
+            thresholds,
+            missed_sortitions: 0,
+            empty_tenures: 0,
+            current_tenure: None,
+            signer_participation: VecDeque::with_capacity(SIGNER_PARTICIPATION_WINDOW),
+            low_participation_alerted: false,
+        }
+    }
+
+    /// Number of consecutive sortitions without a winner
+    pub fn missed_sortitions(&self) -> u64 {
+        self.missed_sortitions
+    }
+
+    /// Number of consecutive completed tenures without transactions
+    pub fn empty_tenures(&self) -> u64 {
+        self.empty_tenures
+    }
+
+    /// Average signer participation over the recent blocks, if any blocks have been seen
+    pub fn signer_participation(&self) -> Option<f64> {
+        if self.signer_participation.is_empty() {
+            return None;
+        }
+        let total: f64 = self.signer_participation.iter().sum();
+        Some(total / (self.signer_participation.len() as f64))
+    }
+
+    /// Record a new sortition at `burn_height`, which either had a winner or didn't
+    pub fn observe_sortition(
+        &mut self,
+        burn_height: u64,
+        has_winner: bool,
+    ) -> Option<ChainQualityAlert> {
+        if has_winner {
+            self.missed_sortitions = 0;
+        } else {
+            self.missed_sortitions += 1;
+        }
+        monitoring::update_consecutive_missed_sortitions(self.missed_sortitions);
+
+        if self.thresholds.missed_sortitions > 0
+            && self.missed_sortitions == self.thresholds.missed_sortitions
+        {
+            return Some(self.raise(ChainQualityAlert::MissedSortitions {
+                burn_height,
+                count: self.missed_sortitions,
+            }));
+        }
+        None
+    }
+
+    /// Record a newly-processed Nakamoto block with the given transactions.  `signers` is the
+    /// number of signatures the block carries and the number of signers in its reward set, if
+    /// known.  Shadow blocks have no signatures, and should be recorded with `signers` set to
+    /// `None`.
+    pub fn observe_nakamoto_block<'a>(
+        &mut self,
+        block_id: &StacksBlockId,
+        consensus_hash: &ConsensusHash,
+        txs: impl Iterator<Item = &'a StacksTransaction>,
+        signers: Option<(usize, usize)>,
+    ) -> Vec<ChainQualityAlert> {
+        let mut alerts = vec![];
+
+        let mut tenure_start = false;
+        let mut num_txs = 0;
+        for tx in txs {
+            match tx.payload {
+                TransactionPayload::Coinbase(..) => {
+                    tenure_start = true;
+                }
+                TransactionPayload::TenureChange(..) => {}
+                _ => {
+                    num_txs += 1;
+                }
+            }
+        }
+
+        match self.current_tenure.as_mut() {
+            Some((tenure_ch, tenure_txs)) if !tenure_start && tenure_ch == consensus_hash => {
+                *tenure_txs += num_txs;
+            }
+            _ => {
+                // a new tenure began, so the last one is complete.  If this block isn't in the
+                // last tenure, but doesn't start a new one either, then the blocks before it in
+                // its tenure were processed before this monitor started, so all we can do is
+                // start tracking it.
+                if tenure_start {
+                    if let Some((tenure_ch, tenure_txs)) = self.current_tenure.take() {
+                        if tenure_txs == 0 {
+                            self.empty_tenures += 1;
+                        } else {
+                            self.empty_tenures = 0;
+                        }
+                        monitoring::update_consecutive_empty_tenures(self.empty_tenures);
+                        if self.thresholds.empty_tenures > 0
+                            && self.empty_tenures == self.thresholds.empty_tenures
+                        {
+                            alerts.push(self.raise(ChainQualityAlert::EmptyTenures {
+                                consensus_hash: tenure_ch,
+                                count: self.empty_tenures,
+                            }));
+                        }
+                    }
+                }
+                self.current_tenure = Some((consensus_hash.clone(), num_txs));
+            }
+        }
+
+        if let Some((num_signatures, num_signers)) = signers {
+            if num_signers > 0 {
+                if self.signer_participation.len() >= SIGNER_PARTICIPATION_WINDOW {
+                    self.signer_participation.pop_front();
+                }
+                self.signer_participation
+                    .push_back((num_signatures as f64) / (num_signers as f64));
+            }
+        }
+        if let Some(participation) = self.signer_participation() {
+            monitoring::update_signer_participation(participation);
+            let threshold = self.thresholds.min_signer_participation;
+            if participation >= threshold {
+                self.low_participation_alerted = false;
+            } else if threshold > 0.0
+                && self.signer_participation.len() >= SIGNER_PARTICIPATION_WINDOW
+                && !self.low_participation_alerted
+            {
+                self.low_participation_alerted = true;
+                alerts.push(self.raise(ChainQualityAlert::LowSignerParticipation {
+                    block_id: block_id.clone(),
+                    participation,
+                    threshold,
+                }));
+            }
+        }
+        alerts
+    }
+
+    fn raise(&self, alert: ChainQualityAlert) -> ChainQualityAlert {
+        warn!("Chain quality alert: {}", alert.message(); "alert" => alert.name());
+        monitoring::increment_chain_quality_alerts_counter(alert.name());
+        alert
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use clarity::vm::types::PrincipalData;
+    use stacks_common::types::chainstate::StacksAddress;
+    use stacks_common::util::hash::Hash160;
+
+    use super::*;
+    use crate::chainstate::stacks::{
+        CoinbasePayload, TenureChangeCause, TenureChangePayload, TokenTransferMemo,
+        TransactionAuth, TransactionSpendingCondition, TransactionVersion,
+    };
+
+    fn make_tx(payload: TransactionPayload) -> StacksTransaction {
+        StacksTransaction::new(
+            TransactionVersion::Testnet,
+            TransactionAuth::Standard(TransactionSpendingCondition::new_initial_sighash()),
+            payload,
+        )
+    }
+
+    /// The tenure-change and coinbase that start the tenure of `ch`
+    fn tenure_start_txs(ch: &ConsensusHash) -> Vec<StacksTransaction> {
+        vec![
+            make_tx(TransactionPayload::TenureChange(TenureChangePayload {
+                tenure_consensus_hash: ch.clone(),
+                prev_tenure_consensus_hash: ConsensusHash([0; 20]),
+                burn_view_consensus_hash: ch.clone(),
+                previous_tenure_end: StacksBlockId([0; 32]),
+                previous_tenure_blocks: 1,
+                cause: TenureChangeCause::BlockFound,
+                pubkey_hash: Hash160([0; 20]),
+            })),
+            make_tx(TransactionPayload::Coinbase(
+                CoinbasePayload([0; 32]),
+                None,
+                None,
+            )),
+        ]
+    }
+
+    fn make_transfer() -> StacksTransaction {
+        make_tx(TransactionPayload::TokenTransfer(
+            PrincipalData::from(StacksAddress::new(26, Hash160([1; 20])).unwrap()),
+            1,
+            TokenTransferMemo([0; 34]),
+        ))
+    }
+
+    #[test]
+    fn test_missed_sortitions() {
+        let mut monitor = ChainQualityMonitor::new(ChainQualityThresholds::default());
+        assert!(monitor.observe_sortition(1, false).is_none());
+        assert!(monitor.observe_sortition(2, false).is_none());
+        assert_eq!(
+            monitor.observe_sortition(3, false),
+            Some(ChainQualityAlert::MissedSortitions {
+                burn_height: 3,
+                count: 3
+            })
+        );
+        // only raised when the threshold is first crossed
+        assert!(monitor.observe_sortition(4, false).is_none());
+        assert_eq!(monitor.missed_sortitions(), 4);
+
+        assert!(monitor.observe_sortition(5, true).is_none());
+        assert_eq!(monitor.missed_sortitions(), 0);
+
+        let mut monitor = ChainQualityMonitor::new(ChainQualityThresholds {
+            missed_sortitions: 0,
+            ..ChainQualityThresholds::default()
+        });
+        for height in 0..10 {
+            assert!(monitor.observe_sortition(height, false).is_none());
+        }
+    }
+
+    #[test]
+    fn test_empty_tenures() {
+        let mut monitor = ChainQualityMonitor::new(ChainQualityThresholds {
+            empty_tenures: 2,
+            ..ChainQualityThresholds::default()
+        });
+        let block_id = StacksBlockId([0; 32]);
+        let tenure = |i: u8| ConsensusHash([i; 20]);
+
+        // tenure 1 has a transfer in its second block
+        let txs = tenure_start_txs(&tenure(1));
+        assert!(monitor
+            .observe_nakamoto_block(&block_id, &tenure(1), txs.iter(), None)
+            .is_empty());
+        let txs = [make_transfer()];
+        assert!(monitor
+            .observe_nakamoto_block(&block_id, &tenure(1), txs.iter(), None)
+            .is_empty());
+
+        // tenures 2 and 3 are empty, which is only known once tenures 3 and 4 start
+        for i in 2..4 {
+            let txs = tenure_start_txs(&tenure(i));
+            assert!(monitor
+                .observe_nakamoto_block(&block_id, &tenure(i), txs.iter(), None)
+                .is_empty());
+        }
+        assert_eq!(monitor.empty_tenures(), 1);
+        let txs = tenure_start_txs(&tenure(4));
+        assert_eq!(
+            monitor.observe_nakamoto_block(&block_id, &tenure(4), txs.iter(), None),
+            vec![ChainQualityAlert::EmptyTenures {
+                consensus_hash: tenure(3),
+                count: 2
+            }]
+        );
+
+        // tenure 4 gets a transaction, which resets the count when tenure 5 starts
+        let txs = [make_transfer()];
+        monitor.observe_nakamoto_block(&block_id, &tenure(4), txs.iter(), None);
+        let txs = tenure_start_txs(&tenure(5));
+        assert!(monitor
+            .observe_nakamoto_block(&block_id, &tenure(5), txs.iter(), None)
+            .is_empty());
+        assert_eq!(monitor.empty_tenures(), 0);
+    }
+
+    #[test]
+    fn test_signer_participation() {
+        let mut monitor = ChainQualityMonitor::new(ChainQualityThresholds::default());
+        let ch = ConsensusHash([1; 20]);
+        assert!(monitor.signer_participation().is_none());
+
+        // no alert until the window is full
+        for i in 0..(SIGNER_PARTICIPATION_WINDOW - 1) {
+            let block_id = StacksBlockId([i as u8; 32]);
+            assert!(monitor
+                .observe_nakamoto_block(&block_id, &ch, [].iter(), Some((5, 10)))
+                .is_empty());
+        }
+        let block_id = StacksBlockId([0xff; 32]);
+        assert_eq!(
+            monitor.observe_nakamoto_block(&block_id, &ch, [].iter(), Some((5, 10))),
+            vec![ChainQualityAlert::LowSignerParticipation {
+                block_id: block_id.clone(),
+                participation: 0.5,
+                threshold: 0.7
+            }]
+        );
+        // not raised again until participation recovers
+        assert!(monitor
+            .observe_nakamoto_block(&block_id, &ch, [].iter(), Some((5, 10)))
+            .is_empty());
+
+        // blocks without signer information don't count
+        monitor.observe_nakamoto_block(&block_id, &ch, [].iter(), None);
+        assert_eq!(monitor.signer_participation(), Some(0.5));
+
+        for _ in 0..SIGNER_PARTICIPATION_WINDOW {
+            assert!(monitor
+                .observe_nakamoto_block(&block_id, &ch, [].iter(), Some((10, 10)))
+                .is_empty());
+        }
+        assert_eq!(monitor.signer_participation(), Some(1.0));
+        for _ in 0..SIGNER_PARTICIPATION_WINDOW {
+            monitor.observe_nakamoto_block(&block_id, &ch, [].iter(), Some((1, 10)));
+        }
+        assert!((monitor.signer_participation().unwrap() - 0.1).abs() < 1e-9);
+    }
+}
@@ -34,6 +34,7 @@ use crate::net::rpc::ConversationHttp;
 use crate::net::Error as net_error;
 use crate::util_lib::db::{sqlite_open, tx_busy_handler, DBConn, Error as DatabaseError};
 
+pub mod chain_quality;
 #[cfg(feature = "monitoring_prom")]
 mod prometheus;
 
@@ -70,6 +71,14 @@ where
     res
 }
 
+#[allow(unused_variables)]
+pub fn increment_rpc_rate_limited_counter(class: &str) {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::RPC_RATE_LIMITED_COUNTER_VEC
+        .with_label_values(&[class])
+        .inc();
+}
+
 pub fn increment_stx_blocks_received_counter() {
     #[cfg(feature = "monitoring_prom")]
     prometheus::STX_BLOCKS_RECEIVED_COUNTER.inc();
@@ -170,6 +179,22 @@ pub fn increment_btc_ops_sent_counter() {
     prometheus::BTC_OPS_SENT_COUNTER.inc();
 }
 
+pub fn increment_miner_commit_outbid_counter() {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::MINER_COMMIT_OUTBID_COUNTER.inc();
+}
+
+pub fn increment_miner_commit_fee_cap_reached_counter() {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::MINER_COMMIT_FEE_CAP_REACHED_COUNTER.inc();
+}
+
+#[allow(unused_variables)]
+pub fn update_miner_commit_fee_rate(value: i64) {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::MINER_COMMIT_FEE_RATE_GAUGE.set(value);
+}
+
 pub fn increment_stx_blocks_processed_counter() {
     #[cfg(feature = "monitoring_prom")]
     prometheus::STX_BLOCKS_PROCESSED_COUNTER.inc();
@@ -442,6 +467,52 @@ pub fn update_miner_current_median_commitment(value: u128) {
     }
 }
 
+#[allow(unused_variables)]
+pub fn update_consecutive_missed_sortitions(value: u64) {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::CONSECUTIVE_MISSED_SORTITIONS.set(i64::try_from(value).unwrap_or(i64::MAX));
+}
+
+#[allow(unused_variables)]
+pub fn update_consecutive_empty_tenures(value: u64) {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::CONSECUTIVE_EMPTY_TENURES.set(i64::try_from(value).unwrap_or(i64::MAX));
+}
+
+#[allow(unused_variables)]
+pub fn update_signer_participation(value: f64) {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::SIGNER_PARTICIPATION.set(value);
+}
+
+#[allow(unused_variables)]
+pub fn increment_chain_quality_alerts_counter(name: &str) {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::CHAIN_QUALITY_ALERTS_COUNTER_VEC
+        .with_label_values(&[name])
+        .inc();
+}
+
+#[allow(unused_variables)]
+pub fn update_commit_bid(
+    bid: u64,
+    target_bid: u64,
+    avg_competitor_burn: u64,
+    median_winner_burn: u64,
+    avg_competitors: f64,
+) {
+    #[cfg(feature = "monitoring_prom")]
+    {
+        prometheus::COMMIT_BID_SATS.set(i64::try_from(bid).unwrap_or(i64::MAX));
+        prometheus::COMMIT_BID_TARGET_SATS.set(i64::try_from(target_bid).unwrap_or(i64::MAX));
+        prometheus::COMMIT_BID_COMPETITOR_BURN_SATS
+            .set(i64::try_from(avg_competitor_burn).unwrap_or(i64::MAX));
+        prometheus::COMMIT_BID_WINNER_BURN_SATS
+            .set(i64::try_from(median_winner_burn).unwrap_or(i64::MAX));
+        prometheus::COMMIT_BID_COMPETITORS.set(avg_competitors);
+    }
+}
+
 /// Function sets the global variable `GLOBAL_BURNCHAIN_SIGNER`.
 /// Fails if there are multiple attempts to set this variable.
 #[allow(unused_variables)]
@@ -74,6 +74,21 @@ lazy_static! {
         "Total number of ops (key registrations, block commits, user burn supports) submitted to the burnchain"
     )).unwrap();
 
+    pub static ref MINER_COMMIT_OUTBID_COUNTER: IntCounter = register_int_counter!(opts!(
+        "stacks_node_miner_commit_outbid_total",
+        "Total number of times a competing block commit in the bitcoin mempool paid a higher fee rate than this miner's pending commit"
+    )).unwrap();
+
+    pub static ref MINER_COMMIT_FEE_CAP_REACHED_COUNTER: IntCounter = register_int_counter!(opts!(
+        "stacks_node_miner_commit_fee_cap_reached_total",
+        "Total number of block commit fee bumps abandoned because they would exceed the maximum commit fee rate"
+    )).unwrap();
+
+    pub static ref MINER_COMMIT_FEE_RATE_GAUGE: IntGauge = register_int_gauge!(opts!(
+        "stacks_node_miner_commit_fee_rate",
+        "Fee rate (sats/vbyte) of the last block commit submitted by this miner"
+    )).unwrap();
+
     pub static ref STX_BLOCKS_PROCESSED_COUNTER: IntCounter = register_int_counter!(opts!(
         "stacks_node_stx_blocks_processed_total",
         "Total number of stacks blocks processed"
@@ -200,6 +215,12 @@ lazy_static! {
         "Total RPC outbound bandwidth in bytes"
     )).unwrap();
 
+    pub static ref RPC_RATE_LIMITED_COUNTER_VEC: IntCounterVec = register_int_counter_vec!(
+        "stacks_node_rpc_rate_limited_requests_total",
+        "Total number of RPC requests rejected by the rate limiter, by endpoint class",
+        &["class"]
+    ).unwrap();
+
     pub static ref MSG_COUNTER_VEC: IntCounterVec = register_int_counter_vec!(
         "stacks_node_message_count",
         "Stacks message count by type of message",
@@ -254,6 +275,52 @@ lazy_static! {
         "stacks_node_miner_current_median_commitment_low",
         "Low 64 bits of a miner's median commitment over the mining commitment window."
     )).unwrap();
+
+    pub static ref CONSECUTIVE_MISSED_SORTITIONS: IntGauge = register_int_gauge!(opts!(
+        "stacks_node_consecutive_missed_sortitions",
+        "Number of consecutive sortitions, up to the latest one, without a winner"
+    )).unwrap();
+
+    pub static ref CONSECUTIVE_EMPTY_TENURES: IntGauge = register_int_gauge!(opts!(
+        "stacks_node_consecutive_empty_tenures",
+        "Number of consecutive completed tenures, up to the latest one, without any transactions besides their tenure-change and coinbase"
+    )).unwrap();
+
+    pub static ref SIGNER_PARTICIPATION: Gauge = register_gauge!(opts!(
+        "stacks_node_signer_participation",
+        "Average fraction of the reward set's signers whose signatures appear in the most recent Nakamoto blocks"
+    )).unwrap();
+
+    pub static ref CHAIN_QUALITY_ALERTS_COUNTER_VEC: IntCounterVec = register_int_counter_vec!(
+        "stacks_node_chain_quality_alerts_total",
+        "Total number of chain-quality alerts raised, by alert",
+        &["alert"]
+    ).unwrap();
+
+    pub static ref COMMIT_BID_SATS: IntGauge = register_int_gauge!(opts!(
+        "stacks_node_commit_bid_sats",
+        "Amount the miner chose to burn in its block-commits, when burn fee auto-bidding is enabled"
+    )).unwrap();
+
+    pub static ref COMMIT_BID_TARGET_SATS: IntGauge = register_int_gauge!(opts!(
+        "stacks_node_commit_bid_target_sats",
+        "Block-commit bid which would have won the target share of the total burn in recent sortitions, before the configured bounds are applied"
+    )).unwrap();
+
+    pub static ref COMMIT_BID_COMPETITOR_BURN_SATS: IntGauge = register_int_gauge!(opts!(
+        "stacks_node_commit_bid_competitor_burn_sats",
+        "Average amount burnt by other miners per sortition, over the burn fee auto-bidding window"
+    )).unwrap();
+
+    pub static ref COMMIT_BID_WINNER_BURN_SATS: IntGauge = register_int_gauge!(opts!(
+        "stacks_node_commit_bid_winner_burn_sats",
+        "Median amount burnt by sortition winners, over the burn fee auto-bidding window"
+    )).unwrap();
+
+    pub static ref COMMIT_BID_COMPETITORS: Gauge = register_gauge!(opts!(
+        "stacks_node_commit_bid_competitors",
+        "Average number of other miners per sortition, over the burn fee auto-bidding window"
+    )).unwrap();
 }
 
 pub fn new_rpc_call_timer(path: &str) -> HistogramTimer {
@@ -39,17 +39,22 @@ use stacks_common::util::hash::{to_hex, Sha256Sum};
 
 use crate::burnchains::Burnchain;
 use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::stacks::db::read_replica::{
+    ReadReplicaQuery, READ_REPLICA_QUERY_TIMEOUT_MS,
+};
 use crate::chainstate::stacks::db::StacksChainState;
 use crate::chainstate::stacks::Error as ChainError;
+use crate::clarity_vm::clarity::ClarityReadOnlyConnection;
 use crate::core::mempool::MemPoolDB;
 use crate::net::http::{
     parse_json, Error, HttpBadRequest, HttpContentType, HttpNotFound, HttpRequest,
     HttpRequestContents, HttpRequestPayload, HttpRequestPreamble, HttpResponse,
     HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+    HttpServiceUnavailable,
 };
 use crate::net::httpcore::{
-    request, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
-    StacksHttpRequest, StacksHttpResponse,
+    request, EndpointClass, HttpPreambleExtensions, HttpRequestContentsExtensions,
+    RPCRequestHandler, StacksHttp, StacksHttpRequest, StacksHttpResponse,
 };
 use crate::net::p2p::PeerNetwork;
 use crate::net::{Error as NetError, StacksNodeState, TipRequest};
@@ -75,17 +80,123 @@ pub struct CallReadOnlyResponse {
     pub cause: Option<String>,
 }
 
+/// The arguments of a read-only function call
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallReadOnlyArgs {
+    pub contract_identifier: QualifiedContractIdentifier,
+    pub function: ClarityName,
+    pub sender: PrincipalData,
+    pub sponsor: Option<PrincipalData>,
+    pub arguments: Vec<Value>,
+}
+
+/// Run the read-only function call `args` on `clarity_tx`, within `cost_limit`.  The call may
+/// not write.
+fn execute_read_only(
+    clarity_tx: &mut ClarityReadOnlyConnection,
+    mainnet: bool,
+    chain_id: u32,
+    mut cost_limit: ExecutionCost,
+    args: CallReadOnlyArgs,
+) -> Result<Value, ClarityRuntimeError> {
+    cost_limit.write_length = 0;
+    cost_limit.write_count = 0;
+
+    let epoch = clarity_tx.get_epoch();
+    let cost_track = clarity_tx
+        .with_clarity_db_readonly(|clarity_db| {
+            LimitedCostTracker::new_mid_block(mainnet, chain_id, cost_limit, clarity_db, epoch)
+        })
+        .map_err(|_| ClarityRuntimeError::from(InterpreterError::CostContractLoadFailure))?;
+
+    let clarity_version = clarity_tx
+        .with_analysis_db_readonly(|analysis_db| {
+            analysis_db.get_clarity_version(&args.contract_identifier)
+        })
+        .map_err(|_| {
+            ClarityRuntimeError::from(CheckErrors::NoSuchContract(format!(
+                "{}",
+                &args.contract_identifier
+            )))
+        })?;
+
+    let call_args: Vec<_> = args
+        .arguments
+        .into_iter()
+        .map(SymbolicExpression::atom_value)
+        .collect();
+
+    clarity_tx.with_readonly_clarity_env(
+        mainnet,
+        chain_id,
+        clarity_version,
+        args.sender,
+        args.sponsor,
+        cost_track,
+        |env| {
+            // we want to execute any function as long as no actual writes are made as
+            // opposed to be limited to purely calling `define-read-only` functions,
+            // so use `read_only = false`.  This broadens the number of functions that
+            // can be called, and also circumvents limitations on `define-read-only`
+            // functions that can not use `contrac-call?`, even when calling other
+            // read-only functions
+            env.execute_contract(
+                &args.contract_identifier,
+                args.function.as_str(),
+                &call_args,
+                false,
+            )
+        },
+    )
+}
+
+/// Run the read-only function call `args` on `clarity_tx`, within `cost_limit`, and make the
+/// response to it.
+/// Returns Err(..) with the reason if the call's result could not be encoded.
+pub fn call_read_only(
+    clarity_tx: &mut ClarityReadOnlyConnection,
+    mainnet: bool,
+    chain_id: u32,
+    cost_limit: ExecutionCost,
+    args: CallReadOnlyArgs,
+) -> Result<CallReadOnlyResponse, String> {
+    let data_resp = match execute_read_only(clarity_tx, mainnet, chain_id, cost_limit, args) {
+        Ok(data) => {
+            let hex_result = data.serialize_to_hex().map_err(|e| format!("{:?}", &e))?;
+
+            CallReadOnlyResponse {
+                okay: true,
+                result: Some(format!("0x{}", hex_result)),
+                cause: None,
+            }
+        }
+        Err(e) => match e {
+            Unchecked(CheckErrors::CostBalanceExceeded(actual_cost, _))
+                if actual_cost.write_count > 0 =>
+            {
+                CallReadOnlyResponse {
+                    okay: false,
+                    result: None,
+                    cause: Some("NotReadOnly".to_string()),
+                }
+            }
+            _ => CallReadOnlyResponse {
+                okay: false,
+                result: None,
+                cause: Some(e.to_string()),
+            },
+        },
+    };
+    Ok(data_resp)
+}
+
 #[derive(Clone)]
 pub struct RPCCallReadOnlyRequestHandler {
     maximum_call_argument_size: u32,
     read_only_call_limit: ExecutionCost,
 
-    /// Runtime fields
-    pub contract_identifier: Option<QualifiedContractIdentifier>,
-    pub function: Option<ClarityName>,
-    pub sender: Option<PrincipalData>,
-    pub sponsor: Option<PrincipalData>,
-    pub arguments: Option<Vec<Value>>,
+    /// The call, if it was sent to the read replica workers
+    query: Option<ReadReplicaQuery<Option<Result<CallReadOnlyResponse, String>>>>,
 }
 
 impl RPCCallReadOnlyRequestHandler {
@@ -93,13 +204,55 @@ impl RPCCallReadOnlyRequestHandler {
         Self {
             maximum_call_argument_size,
             read_only_call_limit,
-            contract_identifier: None,
-            function: None,
-            sender: None,
-            sponsor: None,
-            arguments: None,
+            query: None,
         }
     }
+
+    /// Get the call's arguments.  These are read from the request contents, since requests are
+    /// held after this handler has moved on to others.
+    pub fn parse_args(contents: &HttpRequestContents) -> Result<CallReadOnlyArgs, Error> {
+        let contract_identifier = contents
+            .get_path_arg(&"contract".to_string())
+            .and_then(|contract| QualifiedContractIdentifier::parse(contract).ok())
+            .ok_or_else(|| Error::DecodeError("Failed to parse contract identifier".into()))?;
+        let function = contents
+            .get_path_arg(&"function".to_string())
+            .and_then(|function| ClarityName::try_from(function.clone()).ok())
+            .ok_or_else(|| Error::DecodeError("Failed to parse function name".into()))?;
+        let HttpRequestPayload::JSON(body) = contents.get_payload() else {
+            return Err(Error::DecodeError("Missing JSON body".into()));
+        };
+        let body: CallReadOnlyRequestBody = serde_json::from_value(body.clone())
+            .map_err(|_e| Error::DecodeError("Failed to parse JSON body".into()))?;
+
+        let sender = PrincipalData::parse(&body.sender)
+            .map_err(|_e| Error::DecodeError("Failed to parse sender principal".into()))?;
+
+        let sponsor = if let Some(sponsor) = body.sponsor {
+            Some(
+                PrincipalData::parse(&sponsor)
+                    .map_err(|_e| Error::DecodeError("Failed to parse sponsor principal".into()))?,
+            )
+        } else {
+            None
+        };
+
+        // arguments must be valid Clarity values
+        let arguments = body
+            .arguments
+            .into_iter()
+            .map(|hex| Value::try_deserialize_hex_untyped(&hex).ok())
+            .collect::<Option<Vec<Value>>>()
+            .ok_or_else(|| Error::DecodeError("Failed to deserialize argument value".into()))?;
+
+        Ok(CallReadOnlyArgs {
+            contract_identifier,
+            function,
+            sender,
+            sponsor,
+            arguments,
+        })
+    }
 }
 
 /// Decode the HTTP request
@@ -128,6 +281,9 @@ impl HttpRequest for RPCCallReadOnlyRequestHandler {
         query: Option<&str>,
         body: &[u8],
     ) -> Result<HttpRequestContents, Error> {
+        // a query left over from a request that was never answered
+        self.query = None;
+
         let content_len = preamble.get_content_length();
         if !(content_len > 0 && content_len < self.maximum_call_argument_size) {
             return Err(Error::DecodeError(format!(
@@ -144,48 +300,52 @@ impl HttpRequest for RPCCallReadOnlyRequestHandler {
 
         let contract_identifier = request::get_contract_address(captures, "address", "contract")?;
         let function = request::get_clarity_name(captures, "function")?;
-        let body: CallReadOnlyRequestBody = serde_json::from_slice(body)
+        let body: serde_json::Value = serde_json::from_slice(body)
             .map_err(|_e| Error::DecodeError("Failed to parse JSON body".into()))?;
 
-        let sender = PrincipalData::parse(&body.sender)
-            .map_err(|_e| Error::DecodeError("Failed to parse sender principal".into()))?;
-
-        let sponsor = if let Some(sponsor) = body.sponsor {
-            Some(
-                PrincipalData::parse(&sponsor)
-                    .map_err(|_e| Error::DecodeError("Failed to parse sponsor principal".into()))?,
-            )
-        } else {
-            None
-        };
-
-        // arguments must be valid Clarity values
-        let arguments = body
-            .arguments
-            .into_iter()
-            .map(|hex| Value::try_deserialize_hex_untyped(&hex).ok())
-            .collect::<Option<Vec<Value>>>()
-            .ok_or_else(|| Error::DecodeError("Failed to deserialize argument value".into()))?;
-
-        self.contract_identifier = Some(contract_identifier);
-        self.function = Some(function);
-        self.sender = Some(sender);
-        self.sponsor = sponsor;
-        self.arguments = Some(arguments);
-
-        Ok(HttpRequestContents::new().query_string(query))
+        let contents = HttpRequestContents::new()
+            .query_string(query)
+            .path_arg("contract".into(), contract_identifier.to_string())
+            .path_arg("function".into(), function.to_string())
+            .payload_json(body);
+        Self::parse_args(&contents)?;
+        Ok(contents)
     }
 }
 
 /// Handle the HTTP request
 impl RPCRequestHandler for RPCCallReadOnlyRequestHandler {
+    /// Read-only calls execute Clarity code
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
     /// Reset internal state
-    fn restart(&mut self) {
-        self.contract_identifier = None;
-        self.function = None;
-        self.sender = None;
-        self.sponsor = None;
-        self.arguments = None;
+    fn restart(&mut self) {}
+
+    /// Hold the request while a read replica worker runs the call, if read replicas are enabled
+    fn defer_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        contents: &HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Option<u128> {
+        if let Some(query) = self.query.as_ref() {
+            return (!query.is_done()).then_some(READ_REPLICA_QUERY_TIMEOUT_MS);
+        }
+        let args = Self::parse_args(contents).ok()?;
+        let tip = node.load_stacks_chain_tip(preamble, contents).ok()?;
+        let (mainnet, chain_id) =
+            node.with_node_state(|_network, _sortdb, chainstate, _mempool, _rpc_args| {
+                (chainstate.mainnet, chainstate.chain_id)
+            });
+        let cost_limit = self.read_only_call_limit.clone();
+        let query = node.submit_read_replica_query(&tip, move |clarity_tx| {
+            call_read_only(clarity_tx, mainnet, chain_id, cost_limit, args)
+        })?;
+        let max_wait_ms = (!query.is_done()).then_some(READ_REPLICA_QUERY_TIMEOUT_MS);
+        self.query = Some(query);
+        max_wait_ms
     }
 
     /// Make the response
@@ -201,121 +361,45 @@ impl RPCRequestHandler for RPCCallReadOnlyRequestHandler {
                 return error_resp.try_into_contents().map_err(NetError::from);
             }
         };
+        let args = Self::parse_args(&contents)?;
 
-        let contract_identifier = self
-            .contract_identifier
-            .take()
-            .ok_or(NetError::SendError("Missing `contract_identifier`".into()))?;
-        let function = self
-            .function
-            .take()
-            .ok_or(NetError::SendError("Missing `function`".into()))?;
-        let sender = self
-            .sender
-            .take()
-            .ok_or(NetError::SendError("Missing `sender`".into()))?;
-        let sponsor = self.sponsor.clone();
-        let arguments = self
-            .arguments
-            .take()
-            .ok_or(NetError::SendError("Missing `arguments`".into()))?;
-
-        // run the read-only call
-        let data_resp =
+        let data_resp = if let Some(query) = self.query.take() {
+            // the call ran (or is still running) on a read replica worker
+            let Some(data_resp) = query.take_result() else {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpServiceUnavailable::new(
+                        "Read replicas are too busy; try again later".to_string(),
+                    ),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            };
+            data_resp
+        } else {
+            // run the read-only call
             node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
-                let args: Vec<_> = arguments
-                    .iter()
-                    .map(|x| SymbolicExpression::atom_value(x.clone()))
-                    .collect();
-
                 let mainnet = chainstate.mainnet;
                 let chain_id = chainstate.chain_id;
-                let mut cost_limit = self.read_only_call_limit.clone();
-                cost_limit.write_length = 0;
-                cost_limit.write_count = 0;
+                let cost_limit = self.read_only_call_limit.clone();
 
                 chainstate.maybe_read_only_clarity_tx(
                     &sortdb.index_handle_at_block(chainstate, &tip)?,
                     &tip,
-                    |clarity_tx| {
-                        let epoch = clarity_tx.get_epoch();
-                        let cost_track = clarity_tx
-                            .with_clarity_db_readonly(|clarity_db| {
-                                LimitedCostTracker::new_mid_block(
-                                    mainnet, chain_id, cost_limit, clarity_db, epoch,
-                                )
-                            })
-                            .map_err(|_| {
-                                ClarityRuntimeError::from(InterpreterError::CostContractLoadFailure)
-                            })?;
-
-                        let clarity_version = clarity_tx
-                            .with_analysis_db_readonly(|analysis_db| {
-                                analysis_db.get_clarity_version(&contract_identifier)
-                            })
-                            .map_err(|_| {
-                                ClarityRuntimeError::from(CheckErrors::NoSuchContract(format!(
-                                    "{}",
-                                    &contract_identifier
-                                )))
-                            })?;
-
-                        clarity_tx.with_readonly_clarity_env(
-                            mainnet,
-                            chain_id,
-                            clarity_version,
-                            sender,
-                            sponsor,
-                            cost_track,
-                            |env| {
-                                // we want to execute any function as long as no actual writes are made as
-                                // opposed to be limited to purely calling `define-read-only` functions,
-                                // so use `read_only = false`.  This broadens the number of functions that
-                                // can be called, and also circumvents limitations on `define-read-only`
-                                // functions that can not use `contrac-call?`, even when calling other
-                                // read-only functions
-                                env.execute_contract(
-                                    &contract_identifier,
-                                    function.as_str(),
-                                    &args,
-                                    false,
-                                )
-                            },
-                        )
-                    },
+                    |clarity_tx| call_read_only(clarity_tx, mainnet, chain_id, cost_limit, args),
                 )
-            });
+            })
+            .ok()
+            .flatten()
+        };
 
         // decode the response
         let data_resp = match data_resp {
-            Ok(Some(Ok(data))) => {
-                let hex_result = data
-                    .serialize_to_hex()
-                    .map_err(|e| NetError::SerializeError(format!("{:?}", &e)))?;
-
-                CallReadOnlyResponse {
-                    okay: true,
-                    result: Some(format!("0x{}", hex_result)),
-                    cause: None,
-                }
+            Some(Ok(data_resp)) => data_resp,
+            Some(Err(msg)) => {
+                return Err(NetError::SerializeError(msg));
             }
-            Ok(Some(Err(e))) => match e {
-                Unchecked(CheckErrors::CostBalanceExceeded(actual_cost, _))
-                    if actual_cost.write_count > 0 =>
-                {
-                    CallReadOnlyResponse {
-                        okay: false,
-                        result: None,
-                        cause: Some("NotReadOnly".to_string()),
-                    }
-                }
-                _ => CallReadOnlyResponse {
-                    okay: false,
-                    result: None,
-                    cause: Some(e.to_string()),
-                },
-            },
-            Ok(None) | Err(_) => {
+            None => {
                 return StacksHttpResponse::new_error(
                     &preamble,
                     &HttpNotFound::new("Chain tip not found".to_string()),
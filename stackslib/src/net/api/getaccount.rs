@@ -60,6 +60,92 @@ pub struct AccountEntryResponse {
     pub nonce_proof: Option<String>,
 }
 
+/// Read an account's balance and nonce as of `tip`.
+/// Returns Ok(None) if the tip's state is not available.
+pub(crate) fn load_account_entry(
+    sortdb: &SortitionDB,
+    chainstate: &mut StacksChainState,
+    tip: &StacksBlockId,
+    account: &PrincipalData,
+    with_proof: bool,
+) -> Result<Option<AccountEntryResponse>, ChainError> {
+    let account_opt = chainstate.maybe_read_only_clarity_tx(
+        &sortdb.index_handle_at_block(chainstate, tip)?,
+        tip,
+        |clarity_tx| {
+            clarity_tx.with_clarity_db_readonly(|clarity_db| {
+                let key = ClarityDatabase::make_key_for_account_balance(account);
+                let burn_block_height =
+                    clarity_db.get_current_burnchain_block_height().ok()? as u64;
+                let v1_unlock_height = clarity_db.get_v1_unlock_height();
+                let v2_unlock_height = clarity_db.get_v2_unlock_height().ok()?;
+                let v3_unlock_height = clarity_db.get_v3_unlock_height().ok()?;
+                let (balance, balance_proof) = if with_proof {
+                    clarity_db
+                        .get_data_with_proof::<STXBalance>(&key)
+                        .ok()
+                        .flatten()
+                        .map(|(a, b)| (a, Some(format!("0x{}", to_hex(&b)))))
+                        .unwrap_or_else(|| (STXBalance::zero(), Some("".into())))
+                } else {
+                    clarity_db
+                        .get_data::<STXBalance>(&key)
+                        .ok()
+                        .flatten()
+                        .map(|a| (a, None))
+                        .unwrap_or_else(|| (STXBalance::zero(), None))
+                };
+
+                let key = ClarityDatabase::make_key_for_account_nonce(account);
+                let (nonce, nonce_proof) = if with_proof {
+                    clarity_db
+                        .get_data_with_proof(&key)
+                        .ok()
+                        .flatten()
+                        .map(|(a, b)| (a, Some(format!("0x{}", to_hex(&b)))))
+                        .unwrap_or_else(|| (0, Some("".into())))
+                } else {
+                    clarity_db
+                        .get_data(&key)
+                        .ok()
+                        .flatten()
+                        .map(|a| (a, None))
+                        .unwrap_or_else(|| (0, None))
+                };
+
+                let unlocked = balance
+                    .get_available_balance_at_burn_block(
+                        burn_block_height,
+                        v1_unlock_height,
+                        v2_unlock_height,
+                        v3_unlock_height,
+                    )
+                    .ok()?;
+
+                let (locked, unlock_height) = balance.get_locked_balance_at_burn_block(
+                    burn_block_height,
+                    v1_unlock_height,
+                    v2_unlock_height,
+                    v3_unlock_height,
+                );
+
+                let balance = format!("0x{}", to_hex(&unlocked.to_be_bytes()));
+                let locked = format!("0x{}", to_hex(&locked.to_be_bytes()));
+
+                Some(AccountEntryResponse {
+                    balance,
+                    locked,
+                    unlock_height,
+                    nonce,
+                    balance_proof,
+                    nonce_proof,
+                })
+            })
+        },
+    )?;
+    Ok(account_opt.flatten())
+}
+
 #[derive(Clone)]
 pub struct RPCGetAccountRequestHandler {
     pub account: Option<PrincipalData>,
@@ -146,80 +232,7 @@ impl RPCRequestHandler for RPCGetAccountRequestHandler {
 
         let account_opt_res =
             node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
-                chainstate.maybe_read_only_clarity_tx(
-                    &sortdb.index_handle_at_block(chainstate, &tip)?,
-                    &tip,
-                    |clarity_tx| {
-                        clarity_tx.with_clarity_db_readonly(|clarity_db| {
-                            let key = ClarityDatabase::make_key_for_account_balance(&account);
-                            let burn_block_height =
-                                clarity_db.get_current_burnchain_block_height().ok()? as u64;
-                            let v1_unlock_height = clarity_db.get_v1_unlock_height();
-                            let v2_unlock_height = clarity_db.get_v2_unlock_height().ok()?;
-                            let v3_unlock_height = clarity_db.get_v3_unlock_height().ok()?;
-                            let (balance, balance_proof) = if with_proof {
-                                clarity_db
-                                    .get_data_with_proof::<STXBalance>(&key)
-                                    .ok()
-                                    .flatten()
-                                    .map(|(a, b)| (a, Some(format!("0x{}", to_hex(&b)))))
-                                    .unwrap_or_else(|| (STXBalance::zero(), Some("".into())))
-                            } else {
-                                clarity_db
-                                    .get_data::<STXBalance>(&key)
-                                    .ok()
-                                    .flatten()
-                                    .map(|a| (a, None))
-                                    .unwrap_or_else(|| (STXBalance::zero(), None))
-                            };
-
-                            let key = ClarityDatabase::make_key_for_account_nonce(&account);
-                            let (nonce, nonce_proof) = if with_proof {
-                                clarity_db
-                                    .get_data_with_proof(&key)
-                                    .ok()
-                                    .flatten()
-                                    .map(|(a, b)| (a, Some(format!("0x{}", to_hex(&b)))))
-                                    .unwrap_or_else(|| (0, Some("".into())))
-                            } else {
-                                clarity_db
-                                    .get_data(&key)
-                                    .ok()
-                                    .flatten()
-                                    .map(|a| (a, None))
-                                    .unwrap_or_else(|| (0, None))
-                            };
-
-                            let unlocked = balance
-                                .get_available_balance_at_burn_block(
-                                    burn_block_height,
-                                    v1_unlock_height,
-                                    v2_unlock_height,
-                                    v3_unlock_height,
-                                )
-                                .ok()?;
-
-                            let (locked, unlock_height) = balance.get_locked_balance_at_burn_block(
-                                burn_block_height,
-                                v1_unlock_height,
-                                v2_unlock_height,
-                                v3_unlock_height,
-                            );
-
-                            let balance = format!("0x{}", to_hex(&unlocked.to_be_bytes()));
-                            let locked = format!("0x{}", to_hex(&locked.to_be_bytes()));
-
-                            Some(AccountEntryResponse {
-                                balance,
-                                locked,
-                                unlock_height,
-                                nonce,
-                                balance_proof,
-                                nonce_proof,
-                            })
-                        })
-                    },
-                )
+                load_account_entry(sortdb, chainstate, &tip, &account, with_proof)
             });
 
         let account = if let Ok(Some(account)) = account_opt_res {
@@ -0,0 +1,334 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::representations::PRINCIPAL_DATA_REGEX_STRING;
+use clarity::vm::types::{PrincipalData, StacksAddressExtensions};
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::hash::to_hex;
+
+use crate::chainstate::nakamoto::{NakamotoBlock, NakamotoChainState};
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::chainstate::stacks::{Error as ChainError, TransactionPayload};
+use crate::net::api::getaccount::{load_account_entry, AccountEntryResponse};
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttpRequest,
+    StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+/// An account's state, along with the block it was read from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountEntryV3Response {
+    #[serde(flatten)]
+    pub account: AccountEntryResponse,
+    /// Index block hash of the block the account was read from
+    pub index_block_hash: StacksBlockId,
+    /// Height of the block the account was read from
+    pub block_height: u64,
+}
+
+/// Maximum number of stored but unprocessed blocks past the canonical tip to apply to an
+/// account with `include_unconfirmed_tenure`
+pub const MAX_UNCONFIRMED_TENURE_BLOCKS: u32 = 64;
+
+#[derive(Clone)]
+pub struct RPCGetAccountV3RequestHandler {
+    pub account: Option<PrincipalData>,
+    /// Also apply the blocks of the ongoing tenure that this node has stored but not yet
+    /// processed
+    pub include_unconfirmed_tenure: bool,
+}
+
+impl RPCGetAccountV3RequestHandler {
+    pub fn new() -> Self {
+        Self {
+            account: None,
+            include_unconfirmed_tenure: false,
+        }
+    }
+
+    /// Get the blocks this node has stored but not yet processed that build on `tip`, lowest
+    /// first.  These blocks have been signed, but the node has not caught up to them yet.  At
+    /// most `MAX_UNCONFIRMED_TENURE_BLOCKS` blocks are returned.
+    pub fn get_unconfirmed_tenure_blocks(
+        chainstate: &StacksChainState,
+        tip: &StacksBlockId,
+        tip_height: u64,
+    ) -> Result<Vec<NakamotoBlock>, ChainError> {
+        let staging_db = chainstate.nakamoto_blocks_db();
+        let unprocessed =
+            staging_db.get_unprocessed_blocks_above(tip_height, MAX_UNCONFIRMED_TENURE_BLOCKS)?;
+        let mut blocks = vec![];
+        let mut parent_block_id = tip.clone();
+        // pick the first stored child at each height; forks are resolved once processed
+        for staging_block in unprocessed.iter() {
+            if staging_block.parent_block_id != parent_block_id {
+                continue;
+            }
+            let Some((block, _size)) = staging_db.get_nakamoto_block(&staging_block.block_id)?
+            else {
+                break;
+            };
+            parent_block_id = staging_block.block_id.clone();
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+
+    /// Apply the transactions in `blocks` to `principal`'s `account`, as if the blocks had been
+    /// processed.  Only nonces, fees and STX token transfers are applied; other STX movements
+    /// (such as by contract calls) show up once the blocks are processed.
+    pub fn apply_unconfirmed_txs(
+        account: &mut AccountEntryResponse,
+        principal: &PrincipalData,
+        blocks: &[NakamotoBlock],
+    ) -> Result<(), ChainError> {
+        let mut balance = u128::from_str_radix(account.balance.trim_start_matches("0x"), 16)
+            .map_err(|_| ChainError::InvalidStacksBlock("Unparseable account balance".into()))?;
+        for tx in blocks.iter().flat_map(|block| block.txs.iter()) {
+            let is_origin = &tx.origin_address().to_account_principal() == principal;
+            let sponsor = tx.sponsor_address().map(|addr| addr.to_account_principal());
+            let is_sponsor = sponsor.as_ref() == Some(principal);
+            if is_origin {
+                account.nonce = account.nonce.max(tx.get_origin_nonce().saturating_add(1));
+            }
+            if is_sponsor {
+                if let Some(sponsor_nonce) = tx.get_sponsor_nonce() {
+                    account.nonce = account.nonce.max(sponsor_nonce.saturating_add(1));
+                }
+            }
+            if is_sponsor || (is_origin && sponsor.is_none()) {
+                balance = balance.saturating_sub(u128::from(tx.get_tx_fee()));
+            }
+            if let TransactionPayload::TokenTransfer(recipient, amount, _) = &tx.payload {
+                if is_origin {
+                    balance = balance.saturating_sub(u128::from(*amount));
+                }
+                if recipient == principal {
+                    balance = balance.saturating_add(u128::from(*amount));
+                }
+            }
+        }
+        account.balance = format!("0x{}", to_hex(&balance.to_be_bytes()));
+        Ok(())
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetAccountV3RequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!(
+            "^/v3/accounts/(?P<principal>{})$",
+            *PRINCIPAL_DATA_REGEX_STRING
+        ))
+        .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/accounts/:principal"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let account = if let Some(value) = captures.name("principal") {
+            PrincipalData::parse(value.into())
+                .map_err(|_e| Error::DecodeError("Failed to parse `principal` field".to_string()))?
+        } else {
+            return Err(Error::DecodeError(
+                "Missing in request path: `principal`".into(),
+            ));
+        };
+
+        let contents = HttpRequestContents::new().query_string(query);
+        let include_unconfirmed_tenure = match contents.get_query_arg("include_unconfirmed_tenure")
+        {
+            None => false,
+            Some(value) if value == "true" || value == "1" => true,
+            Some(value) if value == "false" || value == "0" => false,
+            Some(_) => {
+                return Err(Error::DecodeError(
+                    "Invalid `include_unconfirmed_tenure`: expected `true` or `false`".into(),
+                ));
+            }
+        };
+        if include_unconfirmed_tenure
+            && matches!(contents.tip_request(), TipRequest::SpecificTip(_))
+        {
+            return Err(Error::DecodeError(
+                "`include_unconfirmed_tenure` cannot be combined with a specific `tip`".into(),
+            ));
+        }
+
+        self.account = Some(account);
+        self.include_unconfirmed_tenure = include_unconfirmed_tenure;
+
+        Ok(contents)
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetAccountV3RequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.account = None;
+        self.include_unconfirmed_tenure = false;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let account = self
+            .account
+            .take()
+            .ok_or(NetError::SendError("Missing `account`".into()))?;
+        let with_proof = contents.get_with_proof();
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+        let include_unconfirmed_tenure = self.include_unconfirmed_tenure;
+
+        let account_opt_res =
+            node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+                let Some(mut entry) =
+                    load_account_entry(sortdb, chainstate, &tip, &account, with_proof)?
+                else {
+                    return Ok(None);
+                };
+                let Some(block_height) =
+                    NakamotoChainState::get_block_header(chainstate.db(), &tip)?
+                        .map(|hdr| hdr.stacks_block_height)
+                else {
+                    return Ok(None);
+                };
+                let unconfirmed_blocks = if include_unconfirmed_tenure {
+                    Self::get_unconfirmed_tenure_blocks(chainstate, &tip, block_height)?
+                } else {
+                    vec![]
+                };
+                let Some(unconfirmed_tip) = unconfirmed_blocks.last() else {
+                    return Ok(Some(AccountEntryV3Response {
+                        account: entry,
+                        index_block_hash: tip.clone(),
+                        block_height,
+                    }));
+                };
+                Self::apply_unconfirmed_txs(&mut entry, &account, &unconfirmed_blocks)?;
+                // the proofs are against the processed tip's state
+                entry.balance_proof = None;
+                entry.nonce_proof = None;
+                Ok::<_, ChainError>(Some(AccountEntryV3Response {
+                    account: entry,
+                    index_block_hash: unconfirmed_tip.block_id(),
+                    block_height: unconfirmed_tip.header.chain_length,
+                }))
+            });
+
+        let account = if let Ok(Some(account)) = account_opt_res {
+            account
+        } else {
+            return StacksHttpResponse::new_error(
+                &preamble,
+                &HttpNotFound::new(format!("Chain tip '{}' not found", &tip)),
+            )
+            .try_into_contents()
+            .map_err(NetError::from);
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&account)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetAccountV3RequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let account: AccountEntryV3Response = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(account)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for an account, optionally including the blocks of the ongoing tenure
+    /// that the node has not processed yet
+    pub fn new_getaccount_v3(
+        host: PeerHost,
+        principal: PrincipalData,
+        tip_req: TipRequest,
+        with_proof: bool,
+        include_unconfirmed_tenure: bool,
+    ) -> StacksHttpRequest {
+        let mut contents = HttpRequestContents::new()
+            .for_tip(tip_req)
+            .query_arg("proof".into(), if with_proof { "1" } else { "0" }.into());
+        if include_unconfirmed_tenure {
+            contents = contents.query_arg("include_unconfirmed_tenure".into(), "true".into());
+        }
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v3/accounts/{}", &principal),
+            contents,
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_account_entry_v3_response(self) -> Result<AccountEntryV3Response, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: AccountEntryV3Response = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::burnchains::affirmation::AffirmationsReport;
+use crate::net::http::{
+    parse_json, Error, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    EndpointClass, HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+pub static PATH: &str = "/v3/burnchain/affirmations";
+
+#[derive(Clone)]
+pub struct RPCGetAffirmationsRequestHandler {}
+
+impl RPCGetAffirmationsRequestHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetAffirmationsRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetAffirmationsRequestHandler {
+    /// The report reads every anchor block-commit from the burnchain DB
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response.
+    /// The affirmation maps are the ones the p2p network last computed for the canonical burnchain
+    /// tip, so they stop changing once the node is in epoch 3.0.
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let report_res =
+            node.with_node_state(|network, _sortdb, _chainstate, _mempool, _rpc_args| {
+                AffirmationsReport::new(
+                    &network.burnchain,
+                    &network.burnchain_db,
+                    network.burnchain_tip.block_height,
+                    &network.heaviest_affirmation_map,
+                    &network.tentative_best_affirmation_map,
+                    &network.sortition_tip_affirmation_map,
+                    &network.stacks_tip_affirmation_map,
+                )
+            });
+
+        let report = match report_res {
+            Ok(report) => report,
+            Err(e) => {
+                let msg = format!("Failed to load affirmation maps: {e:?}");
+                warn!("{msg}");
+                return StacksHttpResponse::new_error(&preamble, &HttpServerError::new(msg))
+                    .try_into_contents()
+                    .map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&report)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetAffirmationsRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let report: AffirmationsReport = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(report)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the node's PoX anchor block affirmation maps
+    pub fn new_get_affirmations(host: PeerHost) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(host, "GET".into(), PATH.into(), HttpRequestContents::new())
+            .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_affirmations(self) -> Result<AffirmationsReport, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let report: AffirmationsReport = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(report)
+    }
+}
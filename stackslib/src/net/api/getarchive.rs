@@ -0,0 +1,623 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Archival node endpoints (`node.archival` in the config file).
+//!
+//! * `GET /v3/archive/tenures/:consensus_hash` streams every block in a tenure, oldest first.
+//! * `GET /v3/archive/blocks?start_height=..&end_height=..` streams a range of Nakamoto blocks on
+//!   the canonical fork (or the fork of `tip=`), oldest first.
+//!
+//! Both endpoints return the blocks concatenated with no length prefix, like `/v3/tenures`.  A
+//! response holds at most `MAX_ARCHIVE_BLOCKS` blocks and `MAX_PAYLOAD_LEN` bytes; if the range
+//! does not fit, the response carries an `X-Archive-Next-Height` header with the height to pass
+//! as `start_height` to resume the download.
+
+use regex::{Captures, Regex};
+use stacks_common::codec::{StacksMessageCodec, MAX_PAYLOAD_LEN};
+use stacks_common::types::chainstate::{ConsensusHash, StacksBlockId};
+use stacks_common::types::net::PeerHost;
+
+use crate::chainstate::nakamoto::{NakamotoBlock, NakamotoChainState};
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::chainstate::stacks::Error as ChainError;
+use crate::net::api::getblock_v3::NakamotoBlockStream;
+use crate::net::http::{
+    parse_bytes, Error, HttpChunkGenerator, HttpContentType, HttpNotFound, HttpRequest,
+    HttpRequestContents, HttpRequestPreamble, HttpResponse, HttpResponseContents,
+    HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    EndpointClass, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttpRequest,
+    StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+/// Response header giving the height to resume a truncated archive download from
+pub const ARCHIVE_NEXT_HEIGHT_HEADER: &str = "X-Archive-Next-Height";
+
+/// Most blocks to send in a single archive response
+pub const MAX_ARCHIVE_BLOCKS: u64 = 4096;
+
+/// A block to send in an archive response
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveBlock {
+    pub block_id: StacksBlockId,
+    pub consensus_hash: ConsensusHash,
+    pub parent_block_id: StacksBlockId,
+}
+
+/// The blocks to send in an archive response, oldest first, and the height to resume from if
+/// they did not all fit
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveRange {
+    pub blocks: Vec<ArchiveBlock>,
+    pub next_height: Option<u64>,
+}
+
+impl ArchiveRange {
+    /// Find the Nakamoto blocks at heights `start_height` through `end_height` (inclusive) that
+    /// are ancestors of (or are) `tip`.  Stops early at the first height with no Nakamoto block,
+    /// or once the response would hold more than `max_blocks` blocks or `MAX_PAYLOAD_LEN` bytes.
+    /// At least one block is always included if one exists.
+    pub fn load(
+        chainstate: &mut StacksChainState,
+        tip: &StacksBlockId,
+        start_height: u64,
+        end_height: u64,
+        max_blocks: u64,
+    ) -> Result<Self, ChainError> {
+        let mut blocks = vec![];
+        let mut total_size: u64 = 0;
+        for height in start_height..=end_height {
+            let Some(block_id) = chainstate
+                .index_conn()
+                .get_ancestor_block_hash(height, tip)?
+            else {
+                break;
+            };
+            // epoch 2.x blocks are not served
+            let Some((consensus_hash, parent_block_id)) = chainstate
+                .nakamoto_blocks_db()
+                .get_tenure_and_parent_block_id(&block_id)?
+            else {
+                break;
+            };
+            let size = chainstate
+                .nakamoto_blocks_db()
+                .get_nakamoto_block_size(&block_id)?
+                .ok_or(ChainError::NoSuchBlockError)?;
+
+            if !blocks.is_empty()
+                && (blocks.len() as u64 >= max_blocks
+                    || total_size.saturating_add(size) > MAX_PAYLOAD_LEN.into())
+            {
+                return Ok(Self {
+                    blocks,
+                    next_height: Some(height),
+                });
+            }
+            total_size = total_size.saturating_add(size);
+            blocks.push(ArchiveBlock {
+                block_id,
+                consensus_hash,
+                parent_block_id,
+            });
+        }
+        Ok(Self {
+            blocks,
+            next_height: None,
+        })
+    }
+}
+
+/// Streams a list of Nakamoto blocks, one after the other
+pub struct NakamotoArchiveStream {
+    /// stream for the current block
+    pub block_stream: NakamotoBlockStream,
+    /// blocks to send after the current one, in reverse order
+    pub remaining: Vec<ArchiveBlock>,
+}
+
+impl NakamotoArchiveStream {
+    pub fn new(
+        chainstate: &StacksChainState,
+        blocks: Vec<ArchiveBlock>,
+    ) -> Result<Self, ChainError> {
+        let mut remaining = blocks;
+        remaining.reverse();
+        let first = remaining.pop().ok_or(ChainError::NoSuchBlockError)?;
+        let block_stream = NakamotoBlockStream::new(
+            chainstate,
+            first.block_id,
+            first.consensus_hash,
+            first.parent_block_id,
+        )?;
+        Ok(Self {
+            block_stream,
+            remaining,
+        })
+    }
+
+    /// Start streaming the next block.
+    /// Return Ok(true) if there is one, and Ok(false) if all blocks have been sent.
+    pub fn next_block(&mut self) -> Result<bool, ChainError> {
+        let Some(next) = self.remaining.pop() else {
+            return Ok(false);
+        };
+        self.block_stream
+            .reset(next.block_id, next.parent_block_id)?;
+        self.block_stream.consensus_hash = next.consensus_hash;
+        Ok(true)
+    }
+}
+
+/// Stream implementation for a list of Nakamoto blocks
+impl HttpChunkGenerator for NakamotoArchiveStream {
+    #[cfg(test)]
+    fn hint_chunk_size(&self) -> usize {
+        // make this hurt
+        32
+    }
+
+    #[cfg(not(test))]
+    fn hint_chunk_size(&self) -> usize {
+        4096
+    }
+
+    fn generate_next_chunk(&mut self) -> Result<Vec<u8>, String> {
+        let next_block_chunk = self.block_stream.generate_next_chunk()?;
+        if !next_block_chunk.is_empty() {
+            // have block data to send
+            return Ok(next_block_chunk);
+        }
+
+        let send_more = self.next_block().map_err(|e| {
+            let msg = format!("Failed to load next archived block: {:?}", &e);
+            warn!("{}", &msg);
+            msg
+        })?;
+
+        if !send_more {
+            return Ok(vec![]);
+        }
+
+        self.block_stream.generate_next_chunk()
+    }
+}
+
+/// Parse an optional height from the query string
+fn parse_height_arg(contents: &HttpRequestContents, key: &str) -> Result<Option<u64>, Error> {
+    contents
+        .get_query_arg(key)
+        .map(|height| height.parse::<u64>())
+        .transpose()
+        .map_err(|e| Error::DecodeError(format!("Failed to parse {key}= query parameter: {e}")))
+}
+
+/// Make the response for an archive request: 404 if this node is not an archival node or has
+/// none of the requested blocks, and the stream of blocks otherwise.
+fn make_archive_response<F>(
+    preamble: &HttpRequestPreamble,
+    contents: &HttpRequestContents,
+    node: &mut StacksNodeState,
+    what: &str,
+    load_range: F,
+) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError>
+where
+    F: FnOnce(&mut StacksChainState, &StacksBlockId) -> Result<ArchiveRange, ChainError>,
+{
+    let archival = node.with_node_state(|network, _sortdb, _chainstate, _mempool, _rpc_args| {
+        network.connection_opts.archival
+    });
+    if !archival {
+        return StacksHttpResponse::new_error(
+            preamble,
+            &HttpNotFound::new("This node is not an archival node\n".into()),
+        )
+        .try_into_contents()
+        .map_err(NetError::from);
+    }
+
+    let tip = match node.load_stacks_chain_tip(preamble, contents) {
+        Ok(tip) => tip,
+        Err(error_resp) => {
+            return error_resp.try_into_contents().map_err(NetError::from);
+        }
+    };
+
+    let stream_res = node.with_node_state(|_network, _sortdb, chainstate, _mempool, _rpc_args| {
+        let range = load_range(chainstate, &tip)?;
+        if range.blocks.is_empty() {
+            return Err(ChainError::NoSuchBlockError);
+        }
+        let next_height = range.next_height;
+        let stream = NakamotoArchiveStream::new(chainstate, range.blocks)?;
+        Ok((stream, next_height))
+    });
+
+    let (stream, next_height) = match stream_res {
+        Ok(res) => res,
+        Err(ChainError::NoSuchBlockError) => {
+            return StacksHttpResponse::new_error(
+                preamble,
+                &HttpNotFound::new(format!("No Nakamoto blocks for {what}\n")),
+            )
+            .try_into_contents()
+            .map_err(NetError::from)
+        }
+        Err(e) => {
+            let msg = format!("Failed to load blocks for {what}: {:?}\n", &e);
+            warn!("{}", &msg);
+            return StacksHttpResponse::new_error(preamble, &HttpServerError::new(msg))
+                .try_into_contents()
+                .map_err(NetError::from);
+        }
+    };
+
+    let mut resp_preamble = HttpResponsePreamble::from_http_request_preamble(
+        preamble,
+        200,
+        "OK",
+        None,
+        HttpContentType::Bytes,
+    );
+    if let Some(next_height) = next_height {
+        resp_preamble.add_header(
+            ARCHIVE_NEXT_HEIGHT_HEADER.to_string(),
+            next_height.to_string(),
+        );
+    }
+
+    Ok((
+        resp_preamble,
+        HttpResponseContents::from_stream(Box::new(stream)),
+    ))
+}
+
+#[derive(Clone)]
+pub struct RPCGetArchiveTenureRequestHandler {
+    pub consensus_hash: Option<ConsensusHash>,
+    /// Skip the blocks in the tenure below this height (used to resume a download)
+    pub start_height: Option<u64>,
+}
+
+impl RPCGetArchiveTenureRequestHandler {
+    pub fn new() -> Self {
+        Self {
+            consensus_hash: None,
+            start_height: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetArchiveTenureRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/archive/tenures/(?P<consensus_hash>[0-9a-f]{40})$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/archive/tenures/:consensus_hash"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let consensus_hash_str = captures
+            .name("consensus_hash")
+            .ok_or_else(|| {
+                Error::DecodeError("Failed to match path to consensus hash group".to_string())
+            })?
+            .as_str();
+
+        let consensus_hash = ConsensusHash::from_hex(consensus_hash_str).map_err(|_| {
+            Error::DecodeError("Invalid path: unparseable consensus hash".to_string())
+        })?;
+
+        let req_contents = HttpRequestContents::new().query_string(query);
+        self.start_height = parse_height_arg(&req_contents, "start_height")?;
+        self.consensus_hash = Some(consensus_hash);
+
+        Ok(req_contents)
+    }
+}
+
+impl RPCRequestHandler for RPCGetArchiveTenureRequestHandler {
+    /// Tenures are streamed from disk
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.consensus_hash = None;
+        self.start_height = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let consensus_hash = self
+            .consensus_hash
+            .take()
+            .ok_or(NetError::SendError("Missing `consensus_hash`".into()))?;
+        let start_height = self.start_height.take().unwrap_or(0);
+
+        make_archive_response(
+            &preamble,
+            &contents,
+            node,
+            &format!("tenure {consensus_hash}"),
+            |chainstate, tip| {
+                let Some(start_header) =
+                    NakamotoChainState::get_nakamoto_tenure_start_block_header(
+                        &mut chainstate.index_conn(),
+                        tip,
+                        &consensus_hash,
+                    )?
+                else {
+                    return Err(ChainError::NoSuchBlockError);
+                };
+                let Some(end_header) = NakamotoChainState::get_highest_block_header_in_tenure(
+                    &mut chainstate.index_conn(),
+                    tip,
+                    &consensus_hash,
+                )?
+                else {
+                    return Err(ChainError::NoSuchBlockError);
+                };
+                ArchiveRange::load(
+                    chainstate,
+                    &end_header.index_block_hash(),
+                    start_height.max(start_header.stacks_block_height),
+                    end_header.stacks_block_height,
+                    MAX_ARCHIVE_BLOCKS,
+                )
+            },
+        )
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetArchiveTenureRequestHandler {
+    /// Decode this response from a byte stream.  This is called by the client to decode this
+    /// message
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let bytes = parse_bytes(preamble, body, MAX_PAYLOAD_LEN.into())?;
+        Ok(HttpResponsePayload::Bytes(bytes))
+    }
+}
+
+#[derive(Clone)]
+pub struct RPCGetArchiveBlocksRequestHandler {
+    pub start_height: Option<u64>,
+    pub end_height: Option<u64>,
+}
+
+impl RPCGetArchiveBlocksRequestHandler {
+    pub fn new() -> Self {
+        Self {
+            start_height: None,
+            end_height: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetArchiveBlocksRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/archive/blocks$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/archive/blocks"
+    }
+
+    /// Try to decode this request.
+    /// Both ends of the range are required, and the range must not be empty.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let req_contents = HttpRequestContents::new().query_string(query);
+        let start_height = parse_height_arg(&req_contents, "start_height")?.ok_or_else(|| {
+            Error::DecodeError("Missing start_height= query parameter".to_string())
+        })?;
+        let end_height = parse_height_arg(&req_contents, "end_height")?
+            .ok_or_else(|| Error::DecodeError("Missing end_height= query parameter".to_string()))?;
+        if end_height < start_height {
+            return Err(Error::DecodeError(
+                "end_height must not be less than start_height".to_string(),
+            ));
+        }
+
+        self.start_height = Some(start_height);
+        self.end_height = Some(end_height);
+
+        Ok(req_contents)
+    }
+}
+
+impl RPCRequestHandler for RPCGetArchiveBlocksRequestHandler {
+    /// Blocks are streamed from disk
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.start_height = None;
+        self.end_height = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let start_height = self
+            .start_height
+            .take()
+            .ok_or(NetError::SendError("Missing `start_height`".into()))?;
+        let end_height = self
+            .end_height
+            .take()
+            .ok_or(NetError::SendError("Missing `end_height`".into()))?;
+
+        make_archive_response(
+            &preamble,
+            &contents,
+            node,
+            &format!("heights {start_height}-{end_height}"),
+            |chainstate, tip| {
+                ArchiveRange::load(
+                    chainstate,
+                    tip,
+                    start_height,
+                    end_height,
+                    MAX_ARCHIVE_BLOCKS,
+                )
+            },
+        )
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetArchiveBlocksRequestHandler {
+    /// Decode this response from a byte stream.  This is called by the client to decode this
+    /// message
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let bytes = parse_bytes(preamble, body, MAX_PAYLOAD_LEN.into())?;
+        Ok(HttpResponsePayload::Bytes(bytes))
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the blocks in a tenure, starting at `start_height` if given.
+    /// Decode the response with `decode_archive_blocks()`.
+    pub fn new_get_archive_tenure(
+        host: PeerHost,
+        consensus_hash: &ConsensusHash,
+        start_height: Option<u64>,
+        tip: TipRequest,
+    ) -> StacksHttpRequest {
+        let mut contents = HttpRequestContents::new().for_tip(tip);
+        if let Some(start_height) = start_height {
+            contents = contents.query_arg("start_height".into(), start_height.to_string());
+        }
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v3/archive/tenures/{consensus_hash}"),
+            contents,
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+
+    /// Make a new request for the blocks at heights `start_height` through `end_height`.
+    /// Decode the response with `decode_archive_blocks()`.
+    pub fn new_get_archive_blocks(
+        host: PeerHost,
+        start_height: u64,
+        end_height: u64,
+        tip: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            "/v3/archive/blocks".into(),
+            HttpRequestContents::new()
+                .for_tip(tip)
+                .query_arg("start_height".into(), start_height.to_string())
+                .query_arg("end_height".into(), end_height.to_string()),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    /// Decode an archive response into its blocks, oldest first, and the height to resume the
+    /// download from if the response was truncated.
+    /// If it fails, return Self::Error(..)
+    pub fn decode_archive_blocks(self) -> Result<(Vec<NakamotoBlock>, Option<u64>), NetError> {
+        let next_height = self
+            .preamble()
+            .get_header(ARCHIVE_NEXT_HEIGHT_HEADER.to_string())
+            .map(|height| height.parse::<u64>())
+            .transpose()
+            .map_err(|e| {
+                NetError::DeserializeError(format!(
+                    "Failed to parse {ARCHIVE_NEXT_HEIGHT_HEADER} header: {e}"
+                ))
+            })?;
+
+        let contents = self.get_http_payload_ok()?;
+
+        // contents will be raw bytes
+        let blocks_bytes: Vec<u8> = contents.try_into()?;
+        let ptr = &mut blocks_bytes.as_slice();
+
+        let mut blocks = vec![];
+        while !ptr.is_empty() {
+            let block = NakamotoBlock::consensus_deserialize(ptr)?;
+            blocks.push(block);
+        }
+
+        Ok((blocks, next_height))
+    }
+}
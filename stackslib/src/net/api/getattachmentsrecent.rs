@@ -0,0 +1,192 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+use url::form_urlencoded;
+
+use crate::net::atlas::{GetRecentAttachmentsResponse, MAX_RECENT_ATTACHMENTS_PER_REQUEST};
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+#[derive(Clone)]
+pub struct RPCGetRecentAttachmentsRequestHandler {
+    pub limit: Option<u32>,
+    pub min_height: Option<u64>,
+}
+
+impl RPCGetRecentAttachmentsRequestHandler {
+    pub fn new() -> Self {
+        Self {
+            limit: None,
+            min_height: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetRecentAttachmentsRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new("^/v3/attachments/recent$").unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/attachments/recent"
+    }
+
+    /// Try to decode this request.
+    /// The optional `limit` and `min_height` query arguments bound the response.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let mut limit = None;
+        let mut min_height = None;
+        for (key, value) in form_urlencoded::parse(query.unwrap_or("").as_bytes()) {
+            if key == "limit" {
+                let value = value.parse::<u32>().map_err(|_| {
+                    Error::DecodeError("Invalid Http request: invalid `limit`".to_string())
+                })?;
+                limit = Some(value);
+            } else if key == "min_height" {
+                let value = value.parse::<u64>().map_err(|_| {
+                    Error::DecodeError("Invalid Http request: invalid `min_height`".to_string())
+                })?;
+                min_height = Some(value);
+            }
+        }
+
+        self.limit = limit;
+        self.min_height = min_height;
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetRecentAttachmentsRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.limit = None;
+        self.min_height = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let limit = self
+            .limit
+            .take()
+            .unwrap_or(MAX_RECENT_ATTACHMENTS_PER_REQUEST)
+            .min(MAX_RECENT_ATTACHMENTS_PER_REQUEST);
+        let min_height = self.min_height.take().unwrap_or(0);
+
+        let instances_res =
+            node.with_node_state(|network, _sortdb, _chainstate, _mempool, _rpc_args| {
+                network
+                    .get_atlasdb()
+                    .find_recent_attachment_instances(min_height, limit)
+            });
+
+        let instances = match instances_res {
+            Ok(instances) => instances,
+            Err(e) => {
+                let msg = format!("Unable to read Atlas DB - {}", e);
+                warn!("{}", msg);
+                return StacksHttpResponse::new_error(&preamble, &HttpNotFound::new(msg))
+                    .try_into_contents()
+                    .map_err(NetError::from);
+            }
+        };
+
+        let content = GetRecentAttachmentsResponse::from_instances(instances);
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&content)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetRecentAttachmentsRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let recent: GetRecentAttachmentsResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(recent)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the most recent attachment instances
+    pub fn new_getattachmentsrecent(
+        host: PeerHost,
+        limit: Option<u32>,
+        min_height: Option<u64>,
+    ) -> StacksHttpRequest {
+        let mut contents = HttpRequestContents::new();
+        if let Some(limit) = limit {
+            contents = contents.query_arg("limit".into(), format!("{}", limit));
+        }
+        if let Some(min_height) = min_height {
+            contents = contents.query_arg("min_height".into(), format!("{}", min_height));
+        }
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            "/v3/attachments/recent".into(),
+            contents,
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_atlas_recent_attachments_response(
+        self,
+    ) -> Result<GetRecentAttachmentsResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: GetRecentAttachmentsResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -35,7 +35,7 @@ use crate::net::http::{
     HttpResponsePayload, HttpResponsePreamble, HttpServerError, HttpVersion,
 };
 use crate::net::httpcore::{
-    HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+    EndpointClass, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
     StacksHttpResponse,
 };
 use crate::net::{Error as NetError, StacksNodeState, TipRequest, MAX_HEADERS};
@@ -162,6 +162,16 @@ impl HttpRequest for RPCNakamotoBlockRequestHandler {
 }
 
 impl RPCRequestHandler for RPCNakamotoBlockRequestHandler {
+    /// Blocks are streamed from disk
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// Blocks are immutable, so their ID identifies their contents
+    fn immutable_etag(&self) -> Option<String> {
+        self.block_id.as_ref().map(|block_id| block_id.to_hex())
+    }
+
     /// Reset internal state
     fn restart(&mut self) {
         self.block_id = None;
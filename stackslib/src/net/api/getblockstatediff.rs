@@ -0,0 +1,183 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::net::PeerHost;
+
+use crate::chainstate::stacks::db::state_diffs::BlockStateDiff;
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpNotFound, HttpRequest, HttpRequestContents,
+    HttpRequestPreamble, HttpResponse, HttpResponseContents, HttpResponsePayload,
+    HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    EndpointClass, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+#[derive(Clone)]
+pub struct RPCGetBlockStateDiffRequestHandler {
+    pub block_id: Option<StacksBlockId>,
+}
+
+impl RPCGetBlockStateDiffRequestHandler {
+    pub fn new() -> Self {
+        Self { block_id: None }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetBlockStateDiffRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/blocks/(?P<block_id>[0-9a-f]{64})/state-diff$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/blocks/:block_id/state-diff"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let block_id_str = captures
+            .name("block_id")
+            .ok_or_else(|| {
+                Error::DecodeError("Failed to match path to block ID group".to_string())
+            })?
+            .as_str();
+
+        let block_id = StacksBlockId::from_hex(block_id_str)
+            .map_err(|_| Error::DecodeError("Invalid path: unparseable block ID".to_string()))?;
+        self.block_id = Some(block_id);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetBlockStateDiffRequestHandler {
+    /// Loads and decodes the block's whole state diff
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.block_id = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let block_id = self
+            .block_id
+            .take()
+            .ok_or(NetError::SendError("Missing `block_id`".into()))?;
+
+        let diff_resp =
+            node.with_node_state(|_network, _sortdb, _chainstate, _mempool, rpc_args| {
+                let Some(state_diff_db) = rpc_args.state_diff_db else {
+                    debug!("State diff tracking not configured on this stacks node");
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpBadRequest::new(
+                            "State diff tracking not enabled on this node".to_string(),
+                        ),
+                    ));
+                };
+                let diff_opt = state_diff_db.get_block_state_diff(&block_id).map_err(|e| {
+                    let msg = format!("Failed to load state diff of block {block_id}: {e}");
+                    warn!("{msg}");
+                    StacksHttpResponse::new_error(&preamble, &HttpServerError::new(msg))
+                })?;
+                diff_opt.ok_or_else(|| {
+                    StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpNotFound::new(format!(
+                            "No state diff for block {block_id} in the last {} blocks",
+                            state_diff_db.retention()
+                        )),
+                    )
+                })
+            });
+
+        let diff = match diff_resp {
+            Ok(diff) => diff,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&diff)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetBlockStateDiffRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let diff: BlockStateDiff = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(diff)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the state diff of a processed block
+    pub fn new_get_block_state_diff(host: PeerHost, block_id: StacksBlockId) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v3/blocks/{block_id}/state-diff"),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_block_state_diff(self) -> Result<BlockStateDiff, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let diff: BlockStateDiff = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(diff)
+    }
+}
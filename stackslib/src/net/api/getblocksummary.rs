@@ -0,0 +1,307 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use clarity::vm::costs::ExecutionCost;
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::{ConsensusHash, StacksBlockId};
+use stacks_common::types::net::PeerHost;
+
+use crate::chainstate::nakamoto::{NakamotoBlock, NakamotoChainState};
+use crate::chainstate::stacks::db::receipts::StoredTransactionReceipt;
+use crate::chainstate::stacks::{Error as ChainError, TransactionPayload};
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    EndpointClass, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+/// Counts of the events a block's transactions emitted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockEventCounts {
+    pub total: u64,
+    /// Keyed by event type, as sent to event observers (e.g. `stx_transfer_event`)
+    pub by_type: BTreeMap<String, u64>,
+}
+
+/// Aggregate statistics of a processed Nakamoto block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockSummaryResponse {
+    pub index_block_hash: StacksBlockId,
+    pub consensus_hash: ConsensusHash,
+    pub parent_block_id: StacksBlockId,
+    pub height: u64,
+    pub timestamp: u64,
+    /// Execution cost of the block's transactions
+    pub execution_cost: ExecutionCost,
+    pub tx_count: u64,
+    /// Keyed by payload type (`token_transfer`, `contract_call`, `smart_contract`,
+    /// `poison_microblock`, `coinbase`, `tenure_change`)
+    pub tx_count_by_type: BTreeMap<String, u64>,
+    /// Sum of the fees of the block's transactions, in microSTX
+    pub total_fees: u128,
+    pub signer_bitvec_len: u16,
+    /// Number of bits set in the signer bitvec
+    pub signer_bitvec_set: u16,
+    /// Percentage of bits set in the signer bitvec
+    pub signer_bitvec_participation: f64,
+    pub signer_signature_count: u64,
+    /// Only known if this node keeps transaction receipts and still has the block's receipts
+    pub events: Option<BlockEventCounts>,
+}
+
+/// Name of a transaction payload type, as reported in `tx_count_by_type`
+pub fn payload_type_name(payload: &TransactionPayload) -> &'static str {
+    match payload {
+        TransactionPayload::TokenTransfer(..) => "token_transfer",
+        TransactionPayload::ContractCall(..) => "contract_call",
+        TransactionPayload::SmartContract(..) => "smart_contract",
+        TransactionPayload::PoisonMicroblock(..) => "poison_microblock",
+        TransactionPayload::Coinbase(..) => "coinbase",
+        TransactionPayload::TenureChange(..) => "tenure_change",
+    }
+}
+
+impl BlockSummaryResponse {
+    /// Summarize a processed block.  `receipts` are the block's stored transaction receipts, if
+    /// this node keeps them.
+    pub fn from_block(
+        block: &NakamotoBlock,
+        execution_cost: ExecutionCost,
+        receipts: Option<Vec<StoredTransactionReceipt>>,
+    ) -> Self {
+        let mut tx_count_by_type = BTreeMap::new();
+        let mut total_fees = 0u128;
+        for tx in block.txs.iter() {
+            *tx_count_by_type
+                .entry(payload_type_name(&tx.payload).to_string())
+                .or_insert(0) += 1;
+            total_fees = total_fees.saturating_add(u128::from(tx.get_tx_fee()));
+        }
+
+        let signer_bitvec_len = block.header.pox_treatment.len();
+        let signer_bitvec_set = block.header.pox_treatment.iter().filter(|bit| *bit).count();
+        let signer_bitvec_set = u16::try_from(signer_bitvec_set).unwrap_or(u16::MAX);
+        let signer_bitvec_participation = if signer_bitvec_len == 0 {
+            0.0
+        } else {
+            f64::from(signer_bitvec_set) * 100.0 / f64::from(signer_bitvec_len)
+        };
+
+        // a block with transactions but no stored receipts has had its receipts pruned
+        let events = receipts.and_then(|receipts| {
+            if receipts.is_empty() && !block.txs.is_empty() {
+                return None;
+            }
+            let mut by_type = BTreeMap::new();
+            let mut total = 0;
+            for event in receipts.iter().flat_map(|receipt| receipt.events.iter()) {
+                let event_type = event
+                    .get("type")
+                    .and_then(|event_type| event_type.as_str())
+                    .unwrap_or("unknown");
+                *by_type.entry(event_type.to_string()).or_insert(0) += 1;
+                total += 1;
+            }
+            Some(BlockEventCounts { total, by_type })
+        });
+
+        Self {
+            index_block_hash: block.block_id(),
+            consensus_hash: block.header.consensus_hash.clone(),
+            parent_block_id: block.header.parent_block_id.clone(),
+            height: block.header.chain_length,
+            timestamp: block.header.timestamp,
+            execution_cost,
+            tx_count: u64::try_from(block.txs.len()).expect("FATAL: more than u64::MAX txs"),
+            tx_count_by_type,
+            total_fees,
+            signer_bitvec_len,
+            signer_bitvec_set,
+            signer_bitvec_participation,
+            signer_signature_count: u64::try_from(block.header.signer_signature.len())
+                .expect("FATAL: more than u64::MAX signatures"),
+            events,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RPCGetBlockSummaryRequestHandler {
+    pub block_id: Option<StacksBlockId>,
+}
+
+impl RPCGetBlockSummaryRequestHandler {
+    pub fn new() -> Self {
+        Self { block_id: None }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetBlockSummaryRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/blocks/(?P<block_id>[0-9a-f]{64})/summary$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/blocks/:block_id/summary"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let block_id_str = captures
+            .name("block_id")
+            .ok_or_else(|| {
+                Error::DecodeError("Failed to match path to block ID group".to_string())
+            })?
+            .as_str();
+
+        let block_id = StacksBlockId::from_hex(block_id_str)
+            .map_err(|_| Error::DecodeError("Invalid path: unparseable block ID".to_string()))?;
+        self.block_id = Some(block_id);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetBlockSummaryRequestHandler {
+    /// Loads the block and walks its stored transaction receipts
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.block_id = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let block_id = self
+            .block_id
+            .take()
+            .ok_or(NetError::SendError("Missing `block_id`".into()))?;
+
+        let summary_res =
+            node.with_node_state(|_network, _sortdb, chainstate, _mempool, rpc_args| {
+                let Some((block, _size)) = chainstate
+                    .nakamoto_blocks_db()
+                    .get_nakamoto_block(&block_id)?
+                else {
+                    return Err(ChainError::NoSuchBlockError);
+                };
+                // only processed blocks have a recorded cost
+                let Some(execution_cost) =
+                    NakamotoChainState::get_block_cost_at(chainstate.db(), &block_id)?
+                else {
+                    return Err(ChainError::NoSuchBlockError);
+                };
+                let receipts = rpc_args
+                    .receipt_db
+                    .map(|receipt_db| receipt_db.get_block_receipts(&block_id))
+                    .transpose()?;
+                Ok(BlockSummaryResponse::from_block(
+                    &block,
+                    execution_cost,
+                    receipts,
+                ))
+            });
+
+        let summary = match summary_res {
+            Ok(summary) => summary,
+            Err(ChainError::NoSuchBlockError) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new(format!("No such processed block {block_id}")),
+                )
+                .try_into_contents()
+                .map_err(NetError::from)
+            }
+            Err(e) => {
+                let msg = format!("Failed to summarize block {block_id}: {e:?}");
+                warn!("{msg}");
+                return StacksHttpResponse::new_error(&preamble, &HttpServerError::new(msg))
+                    .try_into_contents()
+                    .map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&summary)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetBlockSummaryRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let summary: BlockSummaryResponse = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(summary)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the summary of a processed Nakamoto block
+    pub fn new_get_block_summary(host: PeerHost, block_id: StacksBlockId) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v3/blocks/{block_id}/summary"),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_block_summary(self) -> Result<BlockSummaryResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let summary: BlockSummaryResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(summary)
+    }
+}
@@ -0,0 +1,264 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::{BurnchainHeaderHash, StacksBlockId};
+use stacks_common::types::net::PeerHost;
+
+use crate::burnchains::db::BurnchainDB;
+use crate::burnchains::Txid;
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::net::api::getburnops::{load_burn_ops, BurnOpEntry};
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    request, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler,
+    StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+/// The STX lock applied by a stack-stx or delegate operation, taken from its `stx_lock_event`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurnOpStxLock {
+    pub locked_address: String,
+    pub locked_amount: u128,
+    pub unlock_height: u64,
+}
+
+impl BurnOpStxLock {
+    /// Find the STX lock among an operation's events, encoded as for event observers
+    pub fn from_events(events: &[serde_json::Value]) -> Option<Self> {
+        let lock = events
+            .iter()
+            .filter(|event| event.get("committed") != Some(&serde_json::Value::Bool(false)))
+            .find_map(|event| event.get("stx_lock_event"))?;
+        Some(Self {
+            locked_address: lock.get("locked_address")?.as_str()?.to_string(),
+            locked_amount: lock.get("locked_amount")?.as_str()?.parse().ok()?,
+            unlock_height: lock.get("unlock_height")?.as_str()?.parse().ok()?,
+        })
+    }
+}
+
+/// The response to GET /v3/burn-ops/receipt/:txid
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurnOpReceiptResponse {
+    pub burn_block_height: u64,
+    pub burn_header_hash: BurnchainHeaderHash,
+    /// Index block hash of the Stacks tip whose fork `status` refers to
+    pub index_block_hash: StacksBlockId,
+    #[serde(flatten)]
+    pub entry: BurnOpEntry,
+    /// Height of the Stacks block that applied the operation, if any
+    pub applied_in_height: Option<u64>,
+    /// The STX lock the operation applied, if any
+    pub stx_lock: Option<BurnOpStxLock>,
+}
+
+/// Load the receipt for the Blockstack operation with the given burnchain txid, as processed
+/// on the fork of `tip`.
+/// Returns Ok(None) if no canonical burnchain block holds such an operation, or if it is a
+/// block-commit or key registration.
+pub fn load_burn_op_receipt(
+    burnchain_db: &BurnchainDB,
+    sortdb: &SortitionDB,
+    chainstate: &mut StacksChainState,
+    tip: &StacksBlockId,
+    txid: &Txid,
+) -> Result<Option<BurnOpReceiptResponse>, NetError> {
+    // the same op can be mined in several burnchain forks; only the canonical one counts
+    let handle = sortdb.index_handle_at_tip();
+    let mut snapshot = None;
+    for op in BurnchainDB::get_burnchain_ops_by_txid(burnchain_db.conn(), txid)? {
+        if let Some(sn) = handle.get_block_snapshot(&op.burn_header_hash())? {
+            snapshot = Some(sn);
+            break;
+        }
+    }
+    let Some(snapshot) = snapshot else {
+        return Ok(None);
+    };
+
+    let Some(burn_ops) =
+        load_burn_ops(burnchain_db, sortdb, chainstate, tip, snapshot.block_height)?
+    else {
+        return Ok(None);
+    };
+    let Some(entry) = burn_ops.ops.into_iter().find(|entry| &entry.txid == txid) else {
+        return Ok(None);
+    };
+
+    let applied_in_height = match entry.applied_in.as_ref() {
+        Some(applied_in) => NakamotoChainState::get_block_header(chainstate.db(), applied_in)?
+            .map(|header| header.stacks_block_height),
+        None => None,
+    };
+    let stx_lock = BurnOpStxLock::from_events(&entry.events);
+
+    Ok(Some(BurnOpReceiptResponse {
+        burn_block_height: burn_ops.burn_block_height,
+        burn_header_hash: burn_ops.burn_header_hash,
+        index_block_hash: burn_ops.index_block_hash,
+        entry,
+        applied_in_height,
+        stx_lock,
+    }))
+}
+
+#[derive(Clone)]
+pub struct RPCGetBurnOpReceiptRequestHandler {
+    pub txid: Option<Txid>,
+}
+
+impl RPCGetBurnOpReceiptRequestHandler {
+    pub fn new() -> Self {
+        Self { txid: None }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetBurnOpReceiptRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/burn-ops/receipt/(?P<txid>[0-9a-f]{64})$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/burn-ops/receipt/:txid"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let txid = request::get_txid(captures, "txid")?;
+        self.txid = Some(txid);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetBurnOpReceiptRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.txid = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let txid = self
+            .txid
+            .take()
+            .ok_or(NetError::SendError("Missing `txid`".into()))?;
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let receipt_res =
+            node.with_node_state(|network, sortdb, chainstate, _mempool, _rpc_args| {
+                load_burn_op_receipt(&network.burnchain_db, sortdb, chainstate, &tip, &txid)
+            });
+
+        let receipt = match receipt_res {
+            Ok(Some(receipt)) => receipt,
+            Ok(None) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new(format!("No burnchain operation with txid {txid}\n")),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Err(e) => {
+                let msg = format!("Failed to load burnchain op receipt for {txid}: {e:?}\n");
+                warn!("{msg}");
+                return StacksHttpResponse::new_error(&preamble, &HttpServerError::new(msg))
+                    .try_into_contents()
+                    .map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&receipt)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetBurnOpReceiptRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let receipt: BurnOpReceiptResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(receipt)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the receipt of the Blockstack operation with a burnchain txid
+    pub fn new_get_burn_op_receipt(
+        host: PeerHost,
+        txid: &Txid,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v3/burn-ops/receipt/{txid}"),
+            HttpRequestContents::new().for_tip(tip_req),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_burn_op_receipt(self) -> Result<BurnOpReceiptResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: BurnOpReceiptResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
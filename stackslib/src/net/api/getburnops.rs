@@ -0,0 +1,340 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashSet;
+
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::{BurnchainHeaderHash, StacksBlockId};
+use stacks_common::types::net::PeerHost;
+
+use crate::burnchains::db::BurnchainDB;
+use crate::burnchains::{Error as BurnchainError, Txid};
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::burn::operations::BlockstackOperationType;
+use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttpRequest,
+    StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+/// Where a burnchain operation stands, as seen from a Stacks tip
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BurnOpStatus {
+    /// A pre-stx operation.  These are never applied themselves; they authorize the sender of
+    /// a later operation.
+    Accepted,
+    /// The operation failed validation, and will never be applied
+    Rejected,
+    /// The operation is valid, but no Stacks block on this fork has applied it
+    Pending,
+    /// A Stacks block on this fork applied the operation
+    Applied,
+}
+
+/// A Blockstack operation mined in a burnchain block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurnOpEntry {
+    pub txid: Txid,
+    pub vtxindex: u32,
+    /// The operation, encoded as in the event observer's `burnchain_op` field
+    pub op: serde_json::Value,
+    pub status: BurnOpStatus,
+    /// Index block hash of the Stacks block that applied the operation, if any
+    pub applied_in: Option<StacksBlockId>,
+    /// Hex-encoded Clarity result of applying the operation, if it was applied
+    pub result: Option<String>,
+    /// Events emitted while applying the operation, encoded as for event observers
+    pub events: Vec<serde_json::Value>,
+}
+
+/// The response to GET /v3/burn-ops/:burn_height
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurnOpsResponse {
+    pub burn_block_height: u64,
+    pub burn_header_hash: BurnchainHeaderHash,
+    /// Index block hash of the Stacks tip whose fork each `status` refers to
+    pub index_block_hash: StacksBlockId,
+    /// The burn block's stack-stx, delegate-stx, transfer-stx, vote-for-aggregate-key,
+    /// stack-aggregation-commit, and pre-stx operations, in block order
+    pub ops: Vec<BurnOpEntry>,
+}
+
+/// Load the Blockstack operations mined in the canonical burnchain block at `burn_height`, and
+/// whether each was applied on the fork of `tip`.
+/// Returns Ok(None) if there is no such burnchain block.
+pub fn load_burn_ops(
+    burnchain_db: &BurnchainDB,
+    sortdb: &SortitionDB,
+    chainstate: &mut StacksChainState,
+    tip: &StacksBlockId,
+    burn_height: u64,
+) -> Result<Option<BurnOpsResponse>, NetError> {
+    let Some(snapshot) = sortdb
+        .index_handle_at_tip()
+        .get_block_snapshot_by_height(burn_height)?
+    else {
+        return Ok(None);
+    };
+    let burn_header_hash = snapshot.burn_header_hash;
+
+    let block_ops = match BurnchainDB::get_burnchain_block(burnchain_db.conn(), &burn_header_hash) {
+        Ok(block) => block.ops,
+        // e.g. the first burnchain block, which is never downloaded
+        Err(BurnchainError::UnknownBlock(_)) => vec![],
+        Err(e) => return Err(e.into()),
+    };
+
+    // ops that passed validation are stored in the sortition DB
+    let sort_conn = sortdb.conn();
+    let mut valid_txids = HashSet::new();
+    valid_txids.extend(
+        SortitionDB::get_stack_stx_ops(sort_conn, &burn_header_hash)?
+            .into_iter()
+            .map(|op| op.txid),
+    );
+    valid_txids.extend(
+        SortitionDB::get_transfer_stx_ops(sort_conn, &burn_header_hash)?
+            .into_iter()
+            .map(|op| op.txid),
+    );
+    valid_txids.extend(
+        SortitionDB::get_delegate_stx_ops(sort_conn, &burn_header_hash)?
+            .into_iter()
+            .map(|op| op.txid),
+    );
+    valid_txids.extend(
+        SortitionDB::get_vote_for_aggregate_key_ops(sort_conn, &burn_header_hash)?
+            .into_iter()
+            .map(|op| op.txid),
+    );
+    valid_txids.extend(
+        SortitionDB::get_stack_aggregation_commit_ops(sort_conn, &burn_header_hash)?
+            .into_iter()
+            .map(|op| op.txid),
+    );
+
+    let mut ops = vec![];
+    for op in block_ops.into_iter() {
+        let txid = op.txid();
+        let status = match op {
+            BlockstackOperationType::LeaderKeyRegister(_)
+            | BlockstackOperationType::LeaderBlockCommit(_) => {
+                continue;
+            }
+            BlockstackOperationType::PreStx(_) => BurnOpStatus::Accepted,
+            _ if !valid_txids.contains(&txid) => BurnOpStatus::Rejected,
+            _ => BurnOpStatus::Pending,
+        };
+        let mut entry = BurnOpEntry {
+            txid: txid.clone(),
+            vtxindex: op.vtxindex(),
+            op: op.blockstack_op_to_json(),
+            status,
+            applied_in: None,
+            result: None,
+            events: vec![],
+        };
+
+        if status == BurnOpStatus::Pending {
+            // find the receipt left by the block on this fork that applied it, if any
+            for receipt in StacksChainState::get_burnchain_op_receipts(chainstate.db(), &txid)? {
+                let Some(header) = NakamotoChainState::get_block_header(
+                    chainstate.db(),
+                    &receipt.index_block_hash,
+                )?
+                else {
+                    continue;
+                };
+                let ancestor = chainstate
+                    .index_conn()
+                    .get_ancestor_block_hash(header.stacks_block_height, tip)?;
+                if ancestor.as_ref() != Some(&receipt.index_block_hash) {
+                    continue;
+                }
+                entry.status = BurnOpStatus::Applied;
+                entry.result = Some(format!("0x{}", receipt.result.serialize_to_hex()?));
+                entry.applied_in = Some(receipt.index_block_hash);
+                entry.events = receipt.events;
+                break;
+            }
+        }
+        ops.push(entry);
+    }
+    ops.sort_by_key(|entry| entry.vtxindex);
+
+    Ok(Some(BurnOpsResponse {
+        burn_block_height: burn_height,
+        burn_header_hash,
+        index_block_hash: tip.clone(),
+        ops,
+    }))
+}
+
+#[derive(Clone)]
+pub struct RPCGetBurnOpsRequestHandler {
+    pub burn_height: Option<u64>,
+}
+
+impl RPCGetBurnOpsRequestHandler {
+    pub fn new() -> Self {
+        Self { burn_height: None }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetBurnOpsRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/burn-ops/(?P<burn_height>[0-9]{1,20})$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/burn-ops/:burn_height"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let burn_height = captures
+            .name("burn_height")
+            .ok_or_else(|| {
+                Error::DecodeError("Failed to match path to burn height group".to_string())
+            })?
+            .as_str()
+            .parse::<u64>()
+            .map_err(|_| Error::DecodeError("Invalid path: unparseable burn height".to_string()))?;
+        self.burn_height = Some(burn_height);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetBurnOpsRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.burn_height = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let burn_height = self
+            .burn_height
+            .take()
+            .ok_or(NetError::SendError("Missing `burn_height`".into()))?;
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let burn_ops_res =
+            node.with_node_state(|network, sortdb, chainstate, _mempool, _rpc_args| {
+                load_burn_ops(&network.burnchain_db, sortdb, chainstate, &tip, burn_height)
+            });
+
+        let burn_ops = match burn_ops_res {
+            Ok(Some(burn_ops)) => burn_ops,
+            Ok(None) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new(format!("No burnchain block at height {burn_height}\n")),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Err(e) => {
+                let msg = format!("Failed to load burnchain ops at height {burn_height}: {e:?}\n");
+                warn!("{msg}");
+                return StacksHttpResponse::new_error(&preamble, &HttpServerError::new(msg))
+                    .try_into_contents()
+                    .map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&burn_ops)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetBurnOpsRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let burn_ops: BurnOpsResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(burn_ops)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the Blockstack operations mined at a burnchain height
+    pub fn new_get_burn_ops(
+        host: PeerHost,
+        burn_height: u64,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v3/burn-ops/{burn_height}"),
+            HttpRequestContents::new().for_tip(tip_req),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_burn_ops(self) -> Result<BurnOpsResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: BurnOpsResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,274 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::analysis::contract_interface_builder::{
+    build_contract_interface, ContractInterface, ContractInterfaceAtomType,
+};
+use clarity::vm::analysis::ContractAnalysis;
+use clarity::vm::clarity::ClarityConnection;
+use clarity::vm::representations::{CONTRACT_NAME_REGEX_STRING, STANDARD_PRINCIPAL_REGEX_STRING};
+use clarity::vm::types::QualifiedContractIdentifier;
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    request, EndpointClass, HttpPreambleExtensions, HttpRequestContentsExtensions,
+    RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+/// A function that a trait requires
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractInterfaceTraitFunction {
+    pub name: String,
+    pub args: Vec<ContractInterfaceAtomType>,
+    pub returns: ContractInterfaceAtomType,
+}
+
+/// A trait that a contract defines
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractInterfaceTrait {
+    pub name: String,
+    pub functions: Vec<ContractInterfaceTraitFunction>,
+}
+
+/// A deployed contract's interface, including the traits it defines and implements
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractInterfaceV3Response {
+    #[serde(flatten)]
+    pub interface: ContractInterface,
+    /// Traits the contract defines with `define-trait`
+    pub defined_traits: Vec<ContractInterfaceTrait>,
+    /// Traits the contract declares with `impl-trait`, as `<contract identifier>.<trait name>`
+    pub implemented_traits: Vec<String>,
+    /// Index block hash of the block the interface was read from
+    pub index_block_hash: StacksBlockId,
+}
+
+impl ContractInterfaceV3Response {
+    /// Build the response from a stored contract analysis.  Contracts whose analysis was stored
+    /// without an interface get one built from the analysis.
+    pub fn from_analysis(
+        contract: ContractAnalysis,
+        index_block_hash: StacksBlockId,
+    ) -> Option<Self> {
+        let interface = match contract.contract_interface.clone() {
+            Some(interface) => interface,
+            None => build_contract_interface(&contract).ok()?,
+        };
+        let defined_traits = contract
+            .defined_traits
+            .iter()
+            .map(|(trait_name, functions)| ContractInterfaceTrait {
+                name: trait_name.to_string(),
+                functions: functions
+                    .iter()
+                    .map(|(name, signature)| ContractInterfaceTraitFunction {
+                        name: name.to_string(),
+                        args: signature
+                            .args
+                            .iter()
+                            .map(ContractInterfaceAtomType::from_type_signature)
+                            .collect(),
+                        returns: ContractInterfaceAtomType::from_type_signature(&signature.returns),
+                    })
+                    .collect(),
+            })
+            .collect();
+        let implemented_traits = contract
+            .implemented_traits
+            .iter()
+            .map(|trait_id| trait_id.to_string())
+            .collect();
+        Some(Self {
+            interface,
+            defined_traits,
+            implemented_traits,
+            index_block_hash,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct RPCGetContractAbiV3RequestHandler {
+    pub contract_identifier: Option<QualifiedContractIdentifier>,
+}
+
+impl RPCGetContractAbiV3RequestHandler {
+    pub fn new() -> Self {
+        Self {
+            contract_identifier: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetContractAbiV3RequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!(
+            "^/v3/contracts/(?P<address>{})\\.(?P<contract>{})/interface$",
+            *STANDARD_PRINCIPAL_REGEX_STRING, *CONTRACT_NAME_REGEX_STRING
+        ))
+        .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/contracts/:contract_id/interface"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let contract_identifier = request::get_contract_address(captures, "address", "contract")?;
+
+        self.contract_identifier = Some(contract_identifier);
+
+        let contents = HttpRequestContents::new().query_string(query);
+        Ok(contents)
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetContractAbiV3RequestHandler {
+    /// Loads and decodes the contract's whole analysis to build its interface
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.contract_identifier = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let contract_identifier = self.contract_identifier.take().ok_or(NetError::SendError(
+            "`contract_identifier` not set".to_string(),
+        ))?;
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let data_resp =
+            node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+                chainstate.maybe_read_only_clarity_tx(
+                    &sortdb.index_handle_at_block(chainstate, &tip)?,
+                    &tip,
+                    |clarity_tx| {
+                        let epoch = clarity_tx.get_epoch();
+                        clarity_tx.with_analysis_db_readonly(|db| {
+                            db.load_contract(&contract_identifier, &epoch).ok()?
+                        })
+                    },
+                )
+            });
+
+        let data_resp = match data_resp {
+            Ok(Some(Some(contract))) => {
+                ContractInterfaceV3Response::from_analysis(contract, tip.clone())
+            }
+            Ok(Some(None)) => None,
+            Ok(None) | Err(_) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("Chain tip not found".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+        let Some(data_resp) = data_resp else {
+            return StacksHttpResponse::new_error(
+                &preamble,
+                &HttpNotFound::new("No contract interface data found".to_string()),
+            )
+            .try_into_contents()
+            .map_err(NetError::from);
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetContractAbiV3RequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let contract_abi: ContractInterfaceV3Response = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(contract_abi)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for a contract's interface, including its traits
+    pub fn new_getcontractabi_v3(
+        host: PeerHost,
+        contract_identifier: &QualifiedContractIdentifier,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v3/contracts/{}/interface", contract_identifier),
+            HttpRequestContents::new().for_tip(tip_req),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_contract_abi_v3_response(self) -> Result<ContractInterfaceV3Response, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: ContractInterfaceV3Response = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
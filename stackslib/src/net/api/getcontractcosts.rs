@@ -0,0 +1,225 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::cost_estimates::contract_costs::ContractCostEntry;
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    EndpointClass, HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+pub static PATH: &str = "/v3/metrics/contract-costs";
+
+/// Default number of Stacks blocks to sum costs over
+pub const DEFAULT_WINDOW: u64 = 100;
+/// Default number of contracts to return
+pub const DEFAULT_LIMIT: u64 = 100;
+/// Maximum number of contracts to return
+pub const MAX_LIMIT: u64 = 1000;
+
+/// The contracts that were charged the most execution cost over a window of recent Stacks blocks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractCostsResponse {
+    /// Highest Stacks block height for which costs were recorded, if any
+    pub tip_height: Option<u64>,
+    /// Number of Stacks block heights the costs were summed over
+    pub window: u64,
+    /// Contracts, with the most runtime used first
+    pub contracts: Vec<ContractCostEntry>,
+}
+
+#[derive(Clone)]
+pub struct RPCGetContractCostsRequestHandler {
+    pub window: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+impl RPCGetContractCostsRequestHandler {
+    pub fn new() -> Self {
+        Self {
+            window: None,
+            limit: None,
+        }
+    }
+
+    fn parse_u64_arg(contents: &HttpRequestContents, key: &str) -> Result<Option<u64>, Error> {
+        contents
+            .get_query_arg(key)
+            .map(|value| {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| Error::DecodeError(format!("Failed to parse `{key}`")))
+            })
+            .transpose()
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetContractCostsRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let contents = HttpRequestContents::new().query_string(query);
+        let window = Self::parse_u64_arg(&contents, "window")?;
+        if window == Some(0) {
+            return Err(Error::DecodeError("`window` must be positive".to_string()));
+        }
+        let limit = Self::parse_u64_arg(&contents, "limit")?;
+        if limit.is_some_and(|limit| limit > MAX_LIMIT) {
+            return Err(Error::DecodeError(format!(
+                "`limit` must be at most {MAX_LIMIT}"
+            )));
+        }
+
+        self.window = window;
+        self.limit = limit;
+        Ok(contents)
+    }
+}
+
+impl RPCRequestHandler for RPCGetContractCostsRequestHandler {
+    /// Summing costs scans every recorded block in the window
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.window = None;
+        self.limit = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let window = self.window.take().unwrap_or(DEFAULT_WINDOW);
+        let limit = self.limit.take().unwrap_or(DEFAULT_LIMIT);
+
+        let data_resp =
+            node.with_node_state(|_network, _sortdb, _chainstate, _mempool, rpc_args| {
+                let Some(contract_cost_db) = rpc_args.contract_cost_db else {
+                    debug!("Contract cost tracking not configured on this stacks node");
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpBadRequest::new(
+                            "Contract cost tracking not enabled on this node".to_string(),
+                        ),
+                    ));
+                };
+                let window = window.min(contract_cost_db.retention());
+                let result = contract_cost_db.get_tip_height().and_then(|tip_height| {
+                    let contracts = contract_cost_db.get_contract_costs(window, limit)?;
+                    Ok(ContractCostsResponse {
+                        tip_height,
+                        window,
+                        contracts,
+                    })
+                });
+                result.map_err(|e| {
+                    StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpServerError::new(format!("Failed to load contract costs: {e}")),
+                    )
+                })
+            });
+
+        let data_resp = match data_resp {
+            Ok(data) => data,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetContractCostsRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let data: ContractCostsResponse = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(data)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the contracts that used the most execution cost recently
+    pub fn new_get_contract_costs(
+        host: PeerHost,
+        window: Option<u64>,
+        limit: Option<u64>,
+    ) -> StacksHttpRequest {
+        let mut contents = HttpRequestContents::new();
+        if let Some(window) = window {
+            contents = contents.query_arg("window".into(), window.to_string());
+        }
+        if let Some(limit) = limit {
+            contents = contents.query_arg("limit".into(), limit.to_string());
+        }
+        StacksHttpRequest::new_for_peer(host, "GET".into(), PATH.into(), contents)
+            .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_contract_costs(self) -> Result<ContractCostsResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: ContractCostsResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,293 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::ast::parser::v1::CLARITY_NAME_REGEX;
+use clarity::vm::clarity::ClarityConnection;
+use clarity::vm::costs::{CostErrors, ExecutionCost};
+use clarity::vm::database::ClarityDatabase;
+use clarity::vm::representations::{CONTRACT_NAME_REGEX_STRING, STANDARD_PRINCIPAL_REGEX_STRING};
+use clarity::vm::types::{QualifiedContractIdentifier, BOUND_VALUE_SERIALIZATION_HEX};
+use clarity::vm::{ClarityName, Value};
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::{StacksBlockId, TrieHash};
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::hash::to_hex;
+
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpNotFound, HttpRequest, HttpRequestContents,
+    HttpRequestPreamble, HttpResponse, HttpResponseContents, HttpResponsePayload,
+    HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    request, EndpointClass, HttpPreambleExtensions, HttpRequestContentsExtensions,
+    RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+/// A Clarity data map entry, along with the MARF inclusion proof that binds it to a Stacks
+/// block.  A client that trusts the block's index root hash can verify `data` without trusting
+/// the node that served it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataProofResponse {
+    /// Hex serialization of the stored value, which is a `(some ...)` Clarity value
+    pub data: String,
+    /// Hex serialization of the MARF inclusion proof for `marf_key`
+    pub proof: String,
+    /// The MARF key under which the entry is stored
+    pub marf_key: String,
+    /// The hash of `marf_key`, which is the path the proof commits to
+    pub marf_key_hash: TrieHash,
+    /// The block the proof was generated against
+    pub index_block_hash: StacksBlockId,
+}
+
+#[derive(Clone)]
+pub struct RPCGetDataProofRequestHandler {
+    pub contract_identifier: Option<QualifiedContractIdentifier>,
+    pub map_name: Option<ClarityName>,
+    pub key: Option<Value>,
+    /// Read budget for serving a map entry and its proof
+    pub read_only_map_entry_limit: ExecutionCost,
+}
+impl RPCGetDataProofRequestHandler {
+    pub fn new(read_only_map_entry_limit: ExecutionCost) -> Self {
+        Self {
+            read_only_map_entry_limit,
+            contract_identifier: None,
+            map_name: None,
+            key: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetDataProofRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!(
+            "^/v3/proofs/data/(?P<address>{})\\.(?P<contract>{})/(?P<map>{})/(?P<key>(0x)?[0-9a-fA-F]+)$",
+            *STANDARD_PRINCIPAL_REGEX_STRING, *CONTRACT_NAME_REGEX_STRING, *CLARITY_NAME_REGEX
+        ))
+        .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/proofs/data/:contract_identifier/:map_name/:key"
+    }
+
+    /// Try to decode this request.
+    /// The key is a hex-encoded serialized Clarity value, optionally prefixed with `0x`.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let contract_identifier = request::get_contract_address(captures, "address", "contract")?;
+        let map_name = request::get_clarity_name(captures, "map")?;
+
+        let key_hex = captures
+            .name("key")
+            .ok_or_else(|| Error::DecodeError("Missing `key`".into()))?
+            .as_str();
+        let key_hex = key_hex.strip_prefix("0x").unwrap_or(key_hex);
+        if key_hex.len() >= BOUND_VALUE_SERIALIZATION_HEX as usize {
+            return Err(Error::DecodeError(format!(
+                "Invalid Http request: key is too long ({})",
+                key_hex.len()
+            )));
+        }
+        let key = Value::try_deserialize_hex_untyped(key_hex)
+            .map_err(|_e| Error::DecodeError("Failed to deserialize key value".into()))?;
+
+        self.contract_identifier = Some(contract_identifier);
+        self.map_name = Some(map_name);
+        self.key = Some(key);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetDataProofRequestHandler {
+    /// Building the MARF proof walks the trie from the tip
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.contract_identifier = None;
+        self.map_name = None;
+        self.key = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let contract_identifier = self
+            .contract_identifier
+            .take()
+            .ok_or(NetError::SendError("`contract_identifier` not set".into()))?;
+        let map_name = self
+            .map_name
+            .take()
+            .ok_or(NetError::SendError("`map_name` not set".into()))?;
+        let key = self
+            .key
+            .take()
+            .ok_or(NetError::SendError("`key` not set".into()))?;
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+        let marf_key =
+            ClarityDatabase::make_key_for_data_map_entry(&contract_identifier, &map_name, &key)
+                .map_err(|e| NetError::SerializeError(format!("{:?}", &e)))?;
+
+        let limit = &self.read_only_map_entry_limit;
+        let data_resp =
+            node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+                chainstate.maybe_read_only_clarity_tx(
+                    &sortdb.index_handle_at_block(chainstate, &tip)?,
+                    &tip,
+                    |clarity_tx| {
+                        clarity_tx.with_clarity_db_readonly_limited(
+                            limit,
+                            |clarity_db, charge| -> Result<_, CostErrors> {
+                                let Some((value_hex, proof)) = clarity_db
+                                    .get_data_with_proof::<String>(&marf_key)
+                                    .ok()
+                                    .flatten()
+                                else {
+                                    return Ok(None);
+                                };
+                                let read_length = value_hex.len() / 2 + proof.len();
+                                charge(&ExecutionCost::reads(1, read_length as u64))?;
+                                Ok(Some(DataProofResponse {
+                                    data: format!("0x{value_hex}"),
+                                    proof: format!("0x{}", to_hex(&proof)),
+                                    marf_key_hash: TrieHash::from_key(&marf_key),
+                                    marf_key: marf_key.clone(),
+                                    index_block_hash: tip.clone(),
+                                }))
+                            },
+                        )
+                    },
+                )
+            });
+
+        let data_resp = match data_resp {
+            Ok(Some(Ok(Some(data)))) => data,
+            Ok(Some(Err(e))) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpBadRequest::new(format!(
+                        "Map entry exceeds this node's read-only map entry budget: {e:?}"
+                    )),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Ok(Some(Ok(None))) => {
+                // MARF proofs are inclusion proofs only, so there is nothing to prove here
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("Data entry not found".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Ok(None) | Err(_) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("Chain tip not found".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetDataProofRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let data_proof: DataProofResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(data_proof)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for a data map entry and its MARF proof
+    pub fn new_getdataproof(
+        host: PeerHost,
+        contract_identifier: &QualifiedContractIdentifier,
+        map_name: ClarityName,
+        key: Value,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!(
+                "/v3/proofs/data/{}/{}/{}",
+                contract_identifier,
+                &map_name,
+                key.serialize_to_hex()
+                    .expect("FATAL: invalid key could not be serialized")
+            ),
+            HttpRequestContents::new().for_tip(tip_req),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_data_proof_response(self) -> Result<DataProofResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: DataProofResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -31,8 +31,8 @@ use crate::net::http::{
     HttpResponsePayload, HttpResponsePreamble, HttpServerError,
 };
 use crate::net::httpcore::{
-    request, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
-    StacksHttpResponse,
+    request, EndpointClass, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
+    StacksHttpRequest, StacksHttpResponse,
 };
 use crate::net::{Error as NetError, StacksNodeState, TipRequest, MAX_HEADERS};
 use crate::util_lib::db::{DBConn, Error as DBError};
@@ -138,6 +138,11 @@ impl HttpRequest for RPCHeadersRequestHandler {
 }
 
 impl RPCRequestHandler for RPCHeadersRequestHandler {
+    /// Headers are streamed from disk
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
     /// Reset internal state
     fn restart(&mut self) {
         self.quantity = None;
@@ -0,0 +1,342 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::ConsensusHash;
+use stacks_common::types::net::PeerHost;
+
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::burn::BlockSnapshot;
+use crate::net::http::{
+    http_reason, parse_json, Error, HttpContentType, HttpRequest, HttpRequestContents,
+    HttpRequestPreamble, HttpResponse, HttpResponseContents, HttpResponsePayload,
+    HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{RPCRequestHandler, StacksHttpRequest, StacksHttpResponse};
+use crate::net::p2p::PeerNetwork;
+use crate::net::stale_tip::StaleTipDiagnostic;
+use crate::net::{Error as NetError, StacksNodeState};
+use crate::util_lib::db::Error as DBError;
+
+pub static LIVE_PATH: &str = "/v3/health/live";
+pub static READY_PATH: &str = "/v3/health/ready";
+
+/// The request to GET /v3/health/live
+#[derive(Clone, Default)]
+pub struct RPCGetHealthLiveRequestHandler {}
+
+impl RPCGetHealthLiveRequestHandler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// The request to GET /v3/health/ready
+#[derive(Clone, Default)]
+pub struct RPCGetHealthReadyRequestHandler {}
+
+impl RPCGetHealthReadyRequestHandler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// The response to GET /v3/health/live
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPCHealthLiveData {
+    pub status: String,
+}
+
+/// The response to GET /v3/health/ready.  It is sent with 200 if the node is ready, and 503 if
+/// not.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPCHealthReadyData {
+    pub ready: bool,
+    /// Height of the highest processed burnchain block
+    pub burn_block_height: u64,
+    /// Height of the highest burnchain header downloaded from bitcoind
+    pub burnchain_headers_height: u64,
+    /// How many burnchain blocks this node has yet to process
+    pub burn_block_lag: u64,
+    pub max_burn_block_lag: u64,
+    pub stacks_tip_height: u64,
+    /// How many tenures have started since the Stacks tip's tenure.  This is only counted up to
+    /// `max_tenure_lag + 1`.
+    pub tenure_lag: u64,
+    pub max_tenure_lag: u64,
+    /// Set if the Stacks tip has not advanced for `stale_tip_timeout_secs` while neighbors report
+    /// later tenures.  The node is not ready while this is set.
+    pub stale_tip: Option<StaleTipDiagnostic>,
+}
+
+impl RPCHealthReadyData {
+    pub fn from_network(network: &PeerNetwork, sortdb: &SortitionDB) -> Result<Self, DBError> {
+        let max_burn_block_lag = network.connection_opts.health_max_burn_block_lag;
+        let max_tenure_lag = network.connection_opts.health_max_tenure_lag;
+        let burn_block_height = network.burnchain_tip.block_height;
+        let burnchain_headers_height = network.burnchain_headers_height.max(burn_block_height);
+        let burn_block_lag = burnchain_headers_height - burn_block_height;
+        let tenure_lag = Self::count_tenure_lag(
+            sortdb,
+            &network.burnchain_tip,
+            &network.stacks_tip.consensus_hash,
+            max_tenure_lag,
+        )?;
+        let stale_tip = network.stale_tip.diagnostic().cloned();
+        Ok(Self {
+            ready: burn_block_lag <= max_burn_block_lag
+                && tenure_lag <= max_tenure_lag
+                && stale_tip.is_none(),
+            burn_block_height,
+            burnchain_headers_height,
+            burn_block_lag,
+            max_burn_block_lag,
+            stacks_tip_height: network.stacks_tip.height,
+            tenure_lag,
+            max_tenure_lag,
+            stale_tip,
+        })
+    }
+
+    /// Count the winning sortitions on the canonical burnchain fork that come after the one which
+    /// started the Stacks tip's tenure.  Stops counting once the count exceeds `limit`, so a
+    /// syncing node does not walk the whole sortition history on every probe.
+    fn count_tenure_lag(
+        sortdb: &SortitionDB,
+        burnchain_tip: &BlockSnapshot,
+        tenure_consensus_hash: &ConsensusHash,
+        limit: u64,
+    ) -> Result<u64, DBError> {
+        let Some(tenure_sn) =
+            SortitionDB::get_block_snapshot_consensus(sortdb.conn(), tenure_consensus_hash)?
+        else {
+            // the Stacks tip's tenure has not been processed yet, so it is arbitrarily far behind
+            return Ok(limit.saturating_add(1));
+        };
+        let mut lag = 0;
+        let mut cursor = burnchain_tip.clone();
+        while cursor.block_height > tenure_sn.block_height && lag <= limit {
+            if cursor.sortition {
+                lag += 1;
+            }
+            let Some(parent_sn) =
+                SortitionDB::get_block_snapshot(sortdb.conn(), &cursor.parent_sortition_id)?
+            else {
+                break;
+            };
+            cursor = parent_sn;
+        }
+        Ok(lag)
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetHealthLiveRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{LIVE_PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        LIVE_PATH
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetHealthLiveRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response.  If the RPC server can answer at all, the node is alive.
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        _node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&RPCHealthLiveData {
+            status: "ok".to_string(),
+        })?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetHealthLiveRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let live: RPCHealthLiveData = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(live)?)
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetHealthReadyRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{READY_PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        READY_PATH
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetHealthReadyRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response.  A node that is too far behind the burnchain or the Stacks network
+    /// answers with 503, so load balancers stop routing traffic to it.
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let ready_res =
+            node.with_node_state(|network, sortdb, _chainstate, _mempool, _rpc_args| {
+                RPCHealthReadyData::from_network(network, sortdb).map_err(|e| {
+                    StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpServerError::new(format!("Failed to load sortition state: {e:?}")),
+                    )
+                })
+            });
+
+        let ready = match ready_res {
+            Ok(ready) => ready,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let status = if ready.ready { 200 } else { 503 };
+        let preamble = HttpResponsePreamble::from_http_request_preamble(
+            &preamble,
+            status,
+            http_reason(status),
+            None,
+            HttpContentType::JSON,
+        );
+        let body = HttpResponseContents::try_from_json(&ready)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetHealthReadyRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let ready: RPCHealthReadyData = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(ready)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new liveness probe request
+    pub fn new_get_health_live(host: PeerHost) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            LIVE_PATH.into(),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+
+    /// Make a new readiness probe request
+    pub fn new_get_health_ready(host: PeerHost) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            READY_PATH.into(),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_health_live(self) -> Result<RPCHealthLiveData, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let response_json: serde_json::Value = contents.try_into()?;
+        let live: RPCHealthLiveData = serde_json::from_value(response_json)
+            .map_err(|_e| Error::DecodeError("Failed to decode JSON".to_string()))?;
+        Ok(live)
+    }
+
+    /// Decode a readiness probe response.  A node that is not ready sends the same body with a
+    /// 503, so both statuses decode.
+    pub fn decode_health_ready(self) -> Result<RPCHealthReadyData, NetError> {
+        let (preamble, contents) = self.destruct();
+        if preamble.status_code != 200 && preamble.status_code != 503 {
+            return Err(NetError::RecvError(format!(
+                "HTTP status {}",
+                &preamble.status_code
+            )));
+        }
+        let response_json: serde_json::Value = contents.try_into()?;
+        let ready: RPCHealthReadyData = serde_json::from_value(response_json)
+            .map_err(|_e| Error::DecodeError("Failed to decode JSON".to_string()))?;
+        Ok(ready)
+    }
+}
@@ -0,0 +1,348 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::{StacksBlockId, StacksPublicKey};
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::get_epoch_time_secs;
+use stacks_common::util::hash::to_hex;
+
+use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::core::mempool::MemPoolDB;
+use crate::core::STACKS_EPOCH_MAX;
+use crate::net::http::{
+    parse_json, Error, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::nat::NatTraversalStatus;
+use crate::net::p2p::PeerNetwork;
+use crate::net::{Error as NetError, StacksNodeState};
+use crate::version_string;
+
+pub static PATH: &str = "/v3/info";
+
+/// The request to GET /v3/info
+#[derive(Clone, Default)]
+pub struct RPCPeerInfoV3RequestHandler {
+    /// Signer key to look for in the current reward set, if any
+    pub signer_key: Option<StacksPublicKey>,
+}
+
+impl RPCPeerInfoV3RequestHandler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// The current epoch, and when the next one starts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPCEpochInfo {
+    /// The current epoch (e.g. "3.1")
+    pub epoch_id: String,
+    /// The burnchain height at which the current epoch started
+    pub start_height: u64,
+    /// The next epoch, if one is scheduled
+    pub next_epoch_id: Option<String>,
+    /// The burnchain height at which the next epoch starts, if one is scheduled
+    pub next_epoch_height: Option<u64>,
+}
+
+/// The current reward cycle's signer set, as cached by the node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPCSignerSetInfo {
+    pub reward_cycle: u64,
+    /// Number of signers in the reward set, or None if the reward set is not known
+    pub num_signers: Option<u64>,
+    /// Whether the `signer_key` query argument is in the reward set.  None if no key was given or
+    /// the reward set is not known.
+    pub in_reward_set: Option<bool>,
+    /// The `signer_key` query argument's signing weight, if it is in the reward set
+    pub signer_weight: Option<u32>,
+}
+
+/// This node's p2p connections
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPCP2PHealthInfo {
+    pub num_inbound: u64,
+    pub num_outbound: u64,
+    /// Connected neighbors we have not heard from in more than a heartbeat interval
+    pub num_stale: u64,
+    /// Port mapping and reachability of the p2p port, if `connection_options.nat_traversal` is
+    /// set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub nat: Option<NatTraversalStatus>,
+}
+
+/// How current this node's view of the chain is
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPCTipHealthInfo {
+    pub burn_block_height: u64,
+    pub stacks_tip_height: u64,
+    pub stacks_tip: StacksBlockId,
+    /// Seconds since the Stacks tip was produced, if its header could be loaded
+    pub stacks_tip_age_secs: Option<u64>,
+    pub is_fully_synced: bool,
+}
+
+/// The response to GET /v3/info
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPCPeerInfoV3Data {
+    pub server_version: String,
+    pub network_id: u32,
+    pub epoch: RPCEpochInfo,
+    pub signers: RPCSignerSetInfo,
+    pub p2p: RPCP2PHealthInfo,
+    pub tip: RPCTipHealthInfo,
+    /// Number of transactions in the mempool
+    pub mempool_size: u64,
+}
+
+impl RPCEpochInfo {
+    pub fn from_network(network: &PeerNetwork) -> Self {
+        let epoch = network.get_current_epoch();
+        let next_epoch = (epoch.end_height != STACKS_EPOCH_MAX)
+            .then(|| network.epochs.epoch_at_height(epoch.end_height))
+            .flatten();
+        Self {
+            epoch_id: epoch.epoch_id.to_string(),
+            start_height: epoch.start_height,
+            next_epoch_id: next_epoch.as_ref().map(|e| e.epoch_id.to_string()),
+            next_epoch_height: next_epoch.as_ref().map(|e| e.start_height),
+        }
+    }
+}
+
+impl RPCSignerSetInfo {
+    pub fn from_network(network: &PeerNetwork, signer_key: Option<&StacksPublicKey>) -> Self {
+        let reward_cycle = network
+            .burnchain
+            .block_height_to_reward_cycle(network.burnchain_tip.block_height)
+            .expect("FATAL: burnchain tip before system start");
+        let signers = network
+            .current_reward_sets
+            .get(&reward_cycle)
+            .and_then(|rc_info| rc_info.reward_set())
+            .and_then(|reward_set| reward_set.signers.as_ref());
+        let signer_entry = signer_key.zip(signers).and_then(|(key, signers)| {
+            let key_bytes = key.to_bytes_compressed();
+            signers
+                .iter()
+                .find(|entry| entry.signing_key.as_slice() == key_bytes.as_slice())
+        });
+        Self {
+            reward_cycle,
+            num_signers: signers.map(|signers| signers.len() as u64),
+            in_reward_set: signer_key.and(signers).map(|_| signer_entry.is_some()),
+            signer_weight: signer_entry.map(|entry| entry.weight),
+        }
+    }
+}
+
+impl RPCP2PHealthInfo {
+    pub fn from_network(network: &PeerNetwork, now: u64) -> Self {
+        let mut info = Self {
+            num_inbound: 0,
+            num_outbound: 0,
+            num_stale: 0,
+            nat: network.get_nat_status(),
+        };
+        for (_, convo) in network.iter_peer_convos() {
+            if convo.is_outbound() {
+                info.num_outbound += 1;
+            } else {
+                info.num_inbound += 1;
+            }
+            let heartbeat = u64::from(convo.peer_heartbeat.max(convo.heartbeat));
+            if convo.stats.last_recv_time.saturating_add(heartbeat) < now {
+                info.num_stale += 1;
+            }
+        }
+        info
+    }
+}
+
+impl RPCTipHealthInfo {
+    pub fn from_network(
+        network: &PeerNetwork,
+        chainstate: &StacksChainState,
+        ibd: bool,
+        now: u64,
+    ) -> Self {
+        let stacks_tip = network.stacks_tip.block_id();
+        let stacks_tip_age_secs = match NakamotoChainState::get_block_header(
+            chainstate.db(),
+            &stacks_tip,
+        ) {
+            Ok(Some(header)) => {
+                let produced_at = header
+                    .anchored_header
+                    .as_stacks_nakamoto()
+                    .map(|header| header.timestamp)
+                    .unwrap_or(header.burn_header_timestamp);
+                Some(now.saturating_sub(produced_at))
+            }
+            Ok(None) => None,
+            Err(e) => {
+                warn!("Failed to load Stacks tip header"; "stacks_tip" => %stacks_tip, "err" => ?e);
+                None
+            }
+        };
+        Self {
+            burn_block_height: network.burnchain_tip.block_height,
+            stacks_tip_height: network.stacks_tip.height,
+            stacks_tip,
+            stacks_tip_age_secs,
+            is_fully_synced: !ibd,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPeerInfoV3RequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    /// The only thing to load is the optional `signer_key` query argument.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+        let contents = HttpRequestContents::new().query_string(query);
+        self.signer_key = contents
+            .get_query_arg("signer_key")
+            .map(|key_hex| {
+                StacksPublicKey::from_hex(key_hex.trim_start_matches("0x"))
+                    .map_err(|e| Error::DecodeError(format!("Invalid signer_key: {e}")))
+            })
+            .transpose()?;
+        Ok(contents)
+    }
+}
+
+impl RPCRequestHandler for RPCPeerInfoV3RequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.signer_key = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let ibd = node.ibd;
+        let signer_key = self.signer_key.take();
+
+        let info_res = node.with_node_state(|network, _sortdb, chainstate, mempool, _rpc_args| {
+            let now = get_epoch_time_secs();
+            let mempool_size = MemPoolDB::get_num_txs(mempool.conn()).map_err(|e| {
+                StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpServerError::new(format!("Failed to count mempool transactions: {e:?}")),
+                )
+            })?;
+            Ok(RPCPeerInfoV3Data {
+                server_version: version_string(
+                    "stacks-node",
+                    option_env!("STACKS_NODE_VERSION")
+                        .or(option_env!("CARGO_PKG_VERSION"))
+                        .unwrap_or("0.0.0.0"),
+                ),
+                network_id: network.local_peer.network_id,
+                epoch: RPCEpochInfo::from_network(network),
+                signers: RPCSignerSetInfo::from_network(network, signer_key.as_ref()),
+                p2p: RPCP2PHealthInfo::from_network(network, now),
+                tip: RPCTipHealthInfo::from_network(network, chainstate, ibd, now),
+                mempool_size,
+            })
+        });
+
+        let info = match info_res {
+            Ok(info) => info,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&info)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPeerInfoV3RequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let info: RPCPeerInfoV3Data = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(info)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the node's epoch, signer, and health summary.
+    /// If `signer_key` is given, the response says whether it is in the current reward set.
+    pub fn new_getinfo_v3(
+        host: PeerHost,
+        signer_key: Option<&StacksPublicKey>,
+    ) -> StacksHttpRequest {
+        let contents = match signer_key {
+            Some(key) => HttpRequestContents::new()
+                .query_arg("signer_key".into(), to_hex(&key.to_bytes_compressed())),
+            None => HttpRequestContents::new(),
+        };
+        StacksHttpRequest::new_for_peer(host, "GET".into(), PATH.into(), contents)
+            .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_peer_info_v3(self) -> Result<RPCPeerInfoV3Data, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let response_json: serde_json::Value = contents.try_into()?;
+        let info: RPCPeerInfoV3Data = serde_json::from_value(response_json)
+            .map_err(|_e| Error::DecodeError("Failed to decode JSON".to_string()))?;
+        Ok(info)
+    }
+}
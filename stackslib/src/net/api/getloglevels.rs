@@ -0,0 +1,153 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::log::{get_component_loglevels, ComponentLogLevel};
+
+use crate::net::http::{
+    parse_json, Error, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    AdminAuth, AdminRole, EndpointClass, HttpPreambleExtensions, RPCRequestHandler,
+    StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+pub static PATH: &str = "/v3/admin/log_levels";
+
+/// Per-component log levels, as `component=level` pairs separated by commas
+/// (e.g. `net=debug,chainstate=info,miner=trace`).  A bare level overrides the default level.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogLevelsData {
+    pub log_levels: String,
+}
+
+impl LogLevelsData {
+    pub fn from_levels(levels: &[ComponentLogLevel]) -> Self {
+        Self {
+            log_levels: levels
+                .iter()
+                .map(|l| l.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct RPCGetLogLevelsRequestHandler {
+    pub auth: AdminAuth,
+}
+
+impl RPCGetLogLevelsRequestHandler {
+    pub fn new(auth: AdminAuth) -> Self {
+        Self { auth }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetLogLevelsRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        self.auth.authorize(preamble, AdminRole::Logging)?;
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetLogLevelsRequestHandler {
+    /// Log levels are only available with an admin token
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::Admin
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        _node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let data = LogLevelsData::from_levels(&get_component_loglevels());
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&data)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetLogLevelsRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let data: LogLevelsData = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(data)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the node's per-component log levels
+    pub fn new_get_log_levels(host: PeerHost, auth: &str) -> StacksHttpRequest {
+        let mut request = StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            PATH.into(),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data");
+        request.add_header("authorization".into(), auth.into());
+        request
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_log_levels(self) -> Result<LogLevelsData, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: LogLevelsData = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -18,7 +18,7 @@ use std::io::{Read, Write};
 
 use clarity::vm::ast::parser::v1::CLARITY_NAME_REGEX;
 use clarity::vm::clarity::ClarityConnection;
-use clarity::vm::costs::LimitedCostTracker;
+use clarity::vm::costs::{CostErrors, ExecutionCost, LimitedCostTracker};
 use clarity::vm::database::{ClarityDatabase, STXBalance, StoreType};
 use clarity::vm::representations::{
     CONTRACT_NAME_REGEX_STRING, PRINCIPAL_DATA_REGEX_STRING, STANDARD_PRINCIPAL_REGEX_STRING,
@@ -40,9 +40,9 @@ use crate::chainstate::stacks::db::StacksChainState;
 use crate::chainstate::stacks::Error as ChainError;
 use crate::core::mempool::MemPoolDB;
 use crate::net::http::{
-    parse_json, Error, HttpContentType, HttpNotFound, HttpRequest, HttpRequestContents,
-    HttpRequestPayload, HttpRequestPreamble, HttpResponse, HttpResponseContents,
-    HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+    parse_json, Error, HttpBadRequest, HttpContentType, HttpNotFound, HttpRequest,
+    HttpRequestContents, HttpRequestPayload, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
 };
 use crate::net::httpcore::{
     request, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
@@ -67,10 +67,13 @@ pub struct RPCGetMapEntryRequestHandler {
     pub contract_identifier: Option<QualifiedContractIdentifier>,
     pub map_name: Option<ClarityName>,
     pub key: Option<Value>,
+    /// Read budget for serving a map entry
+    pub read_only_map_entry_limit: ExecutionCost,
 }
 impl RPCGetMapEntryRequestHandler {
-    pub fn new() -> Self {
+    pub fn new(read_only_map_entry_limit: ExecutionCost) -> Self {
         Self {
+            read_only_map_entry_limit,
             contract_identifier: None,
             map_name: None,
             key: None,
@@ -181,44 +184,63 @@ impl RPCRequestHandler for RPCGetMapEntryRequestHandler {
             .serialize_to_hex()
             .map_err(|e| NetError::SerializeError(format!("{:?}", &e)))?;
 
+        let limit = &self.read_only_map_entry_limit;
         let data_resp =
             node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
                 chainstate.maybe_read_only_clarity_tx(
                     &sortdb.index_handle_at_block(chainstate, &tip)?,
                     &tip,
                     |clarity_tx| {
-                        clarity_tx.with_clarity_db_readonly(|clarity_db| {
-                            let (value_hex, marf_proof): (String, _) = if with_proof {
-                                clarity_db
-                                    .get_data_with_proof(&key)
-                                    .ok()
-                                    .flatten()
-                                    .map(|(a, b)| (a, Some(format!("0x{}", to_hex(&b)))))
-                                    .unwrap_or_else(|| {
-                                        test_debug!("No value for '{}' in {}", &key, tip);
-                                        (none_response, Some("".into()))
-                                    })
-                            } else {
-                                clarity_db
-                                    .get_data(&key)
-                                    .ok()
-                                    .flatten()
-                                    .map(|a| (a, None))
-                                    .unwrap_or_else(|| {
-                                        test_debug!("No value for '{}' in {}", &key, tip);
-                                        (none_response, None)
-                                    })
-                            };
+                        clarity_tx.with_clarity_db_readonly_limited(
+                            limit,
+                            |clarity_db, charge| -> Result<_, CostErrors> {
+                                let (value_hex, marf_proof): (String, _) = if with_proof {
+                                    clarity_db
+                                        .get_data_with_proof(&key)
+                                        .ok()
+                                        .flatten()
+                                        .map(|(a, b)| (a, Some(format!("0x{}", to_hex(&b)))))
+                                        .unwrap_or_else(|| {
+                                            test_debug!("No value for '{}' in {}", &key, tip);
+                                            (none_response, Some("".into()))
+                                        })
+                                } else {
+                                    clarity_db
+                                        .get_data(&key)
+                                        .ok()
+                                        .flatten()
+                                        .map(|a| (a, None))
+                                        .unwrap_or_else(|| {
+                                            test_debug!("No value for '{}' in {}", &key, tip);
+                                            (none_response, None)
+                                        })
+                                };
 
-                            let data = format!("0x{}", value_hex);
-                            MapEntryResponse { data, marf_proof }
-                        })
+                                let read_length = (value_hex.len()
+                                    + marf_proof.as_ref().map(|proof| proof.len()).unwrap_or(0))
+                                    / 2;
+                                charge(&ExecutionCost::reads(1, read_length as u64))?;
+
+                                let data = format!("0x{}", value_hex);
+                                Ok(MapEntryResponse { data, marf_proof })
+                            },
+                        )
                     },
                 )
             });
 
         let data_resp = match data_resp {
-            Ok(Some(data)) => data,
+            Ok(Some(Ok(data))) => data,
+            Ok(Some(Err(e))) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpBadRequest::new(format!(
+                        "Map entry exceeds this node's read-only map entry budget: {e:?}"
+                    )),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
             Ok(None) | Err(_) => {
                 return StacksHttpResponse::new_error(
                     &preamble,
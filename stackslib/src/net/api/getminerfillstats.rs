@@ -0,0 +1,172 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::cost_estimates::fill_stats::{FillStatsSummary, DEFAULT_FILL_STATS_MAX_BLOCKS};
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+pub static PATH: &str = "/v3/miner/fill-stats";
+
+#[derive(Clone)]
+pub struct RPCGetMinerFillStatsRequestHandler {
+    pub window: Option<u64>,
+}
+
+impl RPCGetMinerFillStatsRequestHandler {
+    pub fn new() -> Self {
+        Self { window: None }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetMinerFillStatsRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let contents = HttpRequestContents::new().query_string(query);
+        let window = contents
+            .get_query_arg("window")
+            .map(|value| {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| Error::DecodeError("Failed to parse `window`".to_string()))
+            })
+            .transpose()?;
+        if window == Some(0) {
+            return Err(Error::DecodeError("`window` must be positive".to_string()));
+        }
+
+        self.window = window;
+        Ok(contents)
+    }
+}
+
+impl RPCRequestHandler for RPCGetMinerFillStatsRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.window = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let window = self
+            .window
+            .take()
+            .and_then(|window| usize::try_from(window).ok())
+            .unwrap_or(DEFAULT_FILL_STATS_MAX_BLOCKS);
+
+        let data_resp =
+            node.with_node_state(|_network, _sortdb, _chainstate, _mempool, rpc_args| {
+                let Some(miner_fill_stats) = rpc_args.miner_fill_stats else {
+                    debug!("Miner fill statistics not available on this stacks node");
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpBadRequest::new(
+                            "Miner fill statistics not available on this node".to_string(),
+                        ),
+                    ));
+                };
+                let summary = miner_fill_stats
+                    .lock()
+                    .expect("FATAL: miner fill stats mutex poisoned")
+                    .summarize(window);
+                Ok(summary)
+            });
+
+        let data_resp = match data_resp {
+            Ok(data) => data,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetMinerFillStatsRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let data: FillStatsSummary = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(data)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for how full this node's recently-mined blocks were
+    pub fn new_get_miner_fill_stats(host: PeerHost, window: Option<u64>) -> StacksHttpRequest {
+        let mut contents = HttpRequestContents::new();
+        if let Some(window) = window {
+            contents = contents.query_arg("window".into(), window.to_string());
+        }
+        StacksHttpRequest::new_for_peer(host, "GET".into(), PATH.into(), contents)
+            .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_miner_fill_stats(self) -> Result<FillStatsSummary, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: FillStatsSummary = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,256 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::VecDeque;
+
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::ConsensusHash;
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+pub static PATH: &str = "/v3/miner/reports";
+
+/// Default number of tenure reports the miner keeps
+pub const DEFAULT_MINER_REPORTS_MAX_TENURES: usize = 100;
+
+/// What the miner did in one of its tenures (or tenure extensions), emitted when it stops
+/// mining it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MinerTenureReport {
+    /// Consensus hash of the sortition which elected this miner
+    pub tenure_consensus_hash: ConsensusHash,
+    /// Consensus hash of the burnchain view the miner mined against
+    pub burn_view_consensus_hash: ConsensusHash,
+    /// Burnchain block height of the burnchain view
+    pub burn_block_height: u64,
+    /// Whether this report covers a tenure extension rather than a newly-won tenure
+    pub tenure_extend: bool,
+    /// Unix timestamps of when the miner started and stopped mining
+    pub started_at: u64,
+    pub ended_at: u64,
+    /// Number of blocks the miner got signed and broadcast
+    pub blocks_mined: u64,
+    /// Lowest and highest Stacks block heights mined
+    pub first_block_height: Option<u64>,
+    pub last_block_height: Option<u64>,
+    /// Number of transactions included in the mined blocks, excluding tenure changes and
+    /// coinbases
+    pub txs_included: u64,
+    /// Sum of the fees of the included transactions, in microSTX
+    pub fees_earned: u64,
+    /// Amount the miner burnt on the winning block commit, in satoshis.  This is `None` for
+    /// tenure extensions, which do not require a new commit.
+    pub commit_cost_sats: Option<u64>,
+    /// Number of block proposals the signers rejected
+    pub proposals_rejected: u64,
+    /// Milliseconds between the start of the tenure and the first signed block, if any
+    pub time_to_first_signature_ms: Option<u64>,
+}
+
+/// In-memory history of the miner's most recent tenure reports.  The miner thread records a
+/// report each time it stops mining a tenure, and the RPC server serves them to operators
+/// tracking profitability.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MinerReports {
+    max_tenures: usize,
+    reports: VecDeque<MinerTenureReport>,
+}
+
+impl Default for MinerReports {
+    fn default() -> Self {
+        Self::new(DEFAULT_MINER_REPORTS_MAX_TENURES)
+    }
+}
+
+impl MinerReports {
+    pub fn new(max_tenures: usize) -> Self {
+        Self {
+            max_tenures: max_tenures.max(1),
+            reports: VecDeque::new(),
+        }
+    }
+
+    /// Record a tenure report, dropping the oldest one if we're at capacity
+    pub fn record(&mut self, report: MinerTenureReport) {
+        while self.reports.len() >= self.max_tenures {
+            self.reports.pop_front();
+        }
+        self.reports.push_back(report);
+    }
+
+    pub fn len(&self) -> usize {
+        self.reports.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reports.is_empty()
+    }
+
+    /// Get up to `limit` of the most recent reports, newest first
+    pub fn latest(&self, limit: usize) -> Vec<MinerTenureReport> {
+        self.reports.iter().rev().take(limit).cloned().collect()
+    }
+}
+
+#[derive(Clone)]
+pub struct RPCGetMinerReportsRequestHandler {
+    pub limit: Option<u64>,
+}
+
+impl RPCGetMinerReportsRequestHandler {
+    pub fn new() -> Self {
+        Self { limit: None }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetMinerReportsRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let contents = HttpRequestContents::new().query_string(query);
+        let limit = contents
+            .get_query_arg("limit")
+            .map(|value| {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| Error::DecodeError("Failed to parse `limit`".to_string()))
+            })
+            .transpose()?;
+        if limit == Some(0) {
+            return Err(Error::DecodeError("`limit` must be positive".to_string()));
+        }
+
+        self.limit = limit;
+        Ok(contents)
+    }
+}
+
+impl RPCRequestHandler for RPCGetMinerReportsRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.limit = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let limit = self
+            .limit
+            .take()
+            .and_then(|limit| usize::try_from(limit).ok())
+            .unwrap_or(DEFAULT_MINER_REPORTS_MAX_TENURES);
+
+        let data_resp =
+            node.with_node_state(|_network, _sortdb, _chainstate, _mempool, rpc_args| {
+                let Some(miner_reports) = rpc_args.miner_reports else {
+                    debug!("Miner tenure reports not available on this stacks node");
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpBadRequest::new(
+                            "Miner tenure reports not available on this node".to_string(),
+                        ),
+                    ));
+                };
+                let reports = miner_reports
+                    .lock()
+                    .expect("FATAL: miner reports mutex poisoned")
+                    .latest(limit);
+                Ok(reports)
+            });
+
+        let data_resp = match data_resp {
+            Ok(data) => data,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetMinerReportsRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let data: Vec<MinerTenureReport> = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(data)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the miner's most recent tenure reports
+    pub fn new_get_miner_reports(host: PeerHost, limit: Option<u64>) -> StacksHttpRequest {
+        let mut contents = HttpRequestContents::new();
+        if let Some(limit) = limit {
+            contents = contents.query_arg("limit".into(), limit.to_string());
+        }
+        StacksHttpRequest::new_for_peer(host, "GET".into(), PATH.into(), contents)
+            .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_miner_reports(self) -> Result<Vec<MinerTenureReport>, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: Vec<MinerTenureReport> = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
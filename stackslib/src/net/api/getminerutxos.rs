@@ -0,0 +1,186 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+pub static PATH: &str = "/v3/miner/utxos";
+
+/// One of the miner's unspent burnchain outputs
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MinerUtxo {
+    pub txid: String,
+    pub vout: u32,
+    /// Value in satoshis
+    pub amount: u64,
+    pub confirmations: u32,
+}
+
+/// The UTXOs chosen to fund the miner's most recent burnchain transaction
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MinerUtxoSelection {
+    /// Coin-selection strategy used.  This is `consolidate` if the miner consolidated its UTXOs
+    /// because fees were low, regardless of the configured strategy.
+    pub strategy: String,
+    pub num_inputs: u64,
+    /// Total value of the inputs, in satoshis
+    pub total_input: u64,
+    /// Fee paid by the transaction, in satoshis
+    pub fee: u64,
+}
+
+/// The miner's view of its burnchain UTXOs, as of the last time it fetched them
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MinerUtxoStatus {
+    /// Address which holds the UTXOs
+    pub address: Option<String>,
+    /// Configured coin-selection strategy (`[miner] utxo_selection`)
+    pub strategy: String,
+    /// Burnchain fee rate (sats/vbyte) when the UTXOs were fetched
+    pub fee_rate: u64,
+    /// Whether the miner will consolidate its UTXOs in its next transaction
+    pub consolidating: bool,
+    /// UTXOs available to the miner, from newest to oldest
+    pub utxos: Vec<MinerUtxo>,
+    /// Total value of `utxos`, in satoshis
+    pub total_amount: u64,
+    /// The most recent selection of UTXOs the miner spent, if any
+    pub last_selection: Option<MinerUtxoSelection>,
+    /// Unix timestamp of the last time the UTXOs were fetched, or 0 if they never were
+    pub updated_at: u64,
+}
+
+#[derive(Clone)]
+pub struct RPCGetMinerUtxosRequestHandler {}
+
+impl RPCGetMinerUtxosRequestHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetMinerUtxosRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetMinerUtxosRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let data_resp =
+            node.with_node_state(|_network, _sortdb, _chainstate, _mempool, rpc_args| {
+                let Some(miner_utxos) = rpc_args.miner_utxos else {
+                    debug!("Miner UTXO status not available on this stacks node");
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpBadRequest::new(
+                            "Miner UTXO status not available on this node".to_string(),
+                        ),
+                    ));
+                };
+                let status = miner_utxos
+                    .lock()
+                    .expect("FATAL: miner UTXO status mutex poisoned")
+                    .clone();
+                Ok(status)
+            });
+
+        let data_resp = match data_resp {
+            Ok(data) => data,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetMinerUtxosRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let data: MinerUtxoStatus = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(data)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the miner's burnchain UTXOs
+    pub fn new_get_miner_utxos(host: PeerHost) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(host, "GET".into(), PATH.into(), HttpRequestContents::new())
+            .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_miner_utxos(self) -> Result<MinerUtxoStatus, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: MinerUtxoStatus = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
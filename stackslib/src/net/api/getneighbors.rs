@@ -60,7 +60,7 @@ pub struct RPCNeighbor {
 
 /// Serialize and deserialize `Option<Vec<QualifiedContractIdentifier>>`
 ///  using the `to_string()` and `parse()` implementations of `QualifiedContractIdentifier`.
-mod serde_opt_vec_qci {
+pub(crate) mod serde_opt_vec_qci {
     use clarity::vm::types::QualifiedContractIdentifier;
     use serde::{Deserialize, Serialize};
 
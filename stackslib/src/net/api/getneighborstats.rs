@@ -0,0 +1,222 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::types::QualifiedContractIdentifier;
+use regex::{Captures, Regex};
+use stacks_common::types::net::{PeerAddress, PeerHost};
+use stacks_common::util::hash::Hash160;
+
+use crate::net::chat::ConversationP2P;
+use crate::net::http::{
+    parse_json, Error, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{RPCRequestHandler, StacksHttpRequest, StacksHttpResponse};
+use crate::net::p2p::PeerNetwork;
+use crate::net::{Error as NetError, StacksNodeState};
+
+pub static PATH: &str = "/v3/neighbors/stats";
+
+#[derive(Clone)]
+pub struct RPCNeighborStatsRequestHandler {}
+
+impl RPCNeighborStatsRequestHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// How well we are syncing with one connected neighbor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPCNeighborStats {
+    pub network_id: u32,
+    pub peer_version: u32,
+    #[serde(rename = "ip")]
+    pub addrbytes: PeerAddress,
+    pub port: u16,
+    pub public_key_hash: Hash160,
+    pub authenticated: bool,
+    pub outbound: bool,
+    /// Seconds since the connection was established
+    pub age: u64,
+    /// When we last completed a handshake with this peer (0 if never)
+    pub last_handshake_time: u64,
+    /// When we last received a message from this peer (0 if never)
+    pub last_contact_time: u64,
+    /// The peer's burnchain tip height, as of its last handshake
+    pub burn_block_height: u64,
+    /// The highest burnchain block height covered by this peer's epoch 2.x block inventory, if
+    /// we have synced it
+    pub inv_block_height: Option<u64>,
+    /// The highest reward cycle covered by this peer's Nakamoto tenure inventory, if we have
+    /// synced it
+    pub inv_reward_cycle: Option<u64>,
+    /// Bytes per second recently downloaded from this peer's data URL
+    pub download_throughput: f64,
+    pub bytes_rx: u64,
+    pub bytes_tx: u64,
+    pub msgs_rx: u64,
+    pub msgs_tx: u64,
+    /// Number of messages from this peer that we could not handle
+    pub msgs_err: u64,
+    /// The StackerDB replicas this peer says it has
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "super::getneighbors::serde_opt_vec_qci")]
+    pub stackerdbs: Option<Vec<QualifiedContractIdentifier>>,
+    /// Fraction of recent requests to this peer that it answered.  Peers with low scores are the
+    /// first to be pruned.
+    pub health_score: f64,
+}
+
+/// Struct given back from a call to `/v3/neighbors/stats`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPCNeighborStatsInfo {
+    pub neighbors: Vec<RPCNeighborStats>,
+}
+
+impl RPCNeighborStats {
+    pub fn from_conversation(network: &PeerNetwork, convo: &ConversationP2P) -> Self {
+        let nk = convo.to_neighbor_key();
+        let naddr = convo.to_neighbor_address();
+        let inv_block_height = network
+            .inv_state
+            .as_ref()
+            .and_then(|inv_state| inv_state.get_stats(&nk))
+            .map(|stats| stats.inv.get_block_height());
+        let inv_reward_cycle = network
+            .inv_state_nakamoto
+            .as_ref()
+            .and_then(|inv_state| inv_state.inventories.get(&naddr))
+            .map(|inv| inv.highest_reward_cycle());
+
+        Self {
+            network_id: nk.network_id,
+            peer_version: nk.peer_version,
+            addrbytes: nk.addrbytes,
+            port: nk.port,
+            public_key_hash: naddr.public_key_hash,
+            authenticated: convo.is_authenticated(),
+            outbound: convo.is_outbound(),
+            age: convo.age(),
+            last_handshake_time: convo.stats.last_handshake_time,
+            last_contact_time: convo.stats.last_contact_time,
+            burn_block_height: convo.burnchain_tip_height,
+            inv_block_height,
+            inv_reward_cycle,
+            download_throughput: convo.stats.get_http_download_throughput(),
+            bytes_rx: convo.stats.bytes_rx,
+            bytes_tx: convo.stats.bytes_tx,
+            msgs_rx: convo.stats.msgs_rx,
+            msgs_tx: convo.stats.msgs_tx,
+            msgs_err: convo.stats.msgs_err,
+            stackerdbs: Some(convo.get_stackerdb_contract_ids().to_vec()),
+            health_score: convo.stats.get_health_score(),
+        }
+    }
+}
+
+impl RPCNeighborStatsInfo {
+    /// Load the stats of every connected neighbor from the peer network
+    pub fn from_p2p(network: &PeerNetwork) -> Self {
+        let neighbors = network
+            .iter_peer_convos()
+            .map(|(_, convo)| RPCNeighborStats::from_conversation(network, convo))
+            .collect();
+        Self { neighbors }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCNeighborStatsRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCNeighborStatsRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let stats = node.with_node_state(|network, _sortdb, _chainstate, _mempool, _rpc_args| {
+            RPCNeighborStatsInfo::from_p2p(network)
+        });
+
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&stats)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCNeighborStatsRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let stats: RPCNeighborStatsInfo = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(stats)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the sync stats of each connected neighbor
+    pub fn new_get_neighbor_stats(host: PeerHost) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(host, "GET".into(), PATH.into(), HttpRequestContents::new())
+            .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_neighbor_stats(self) -> Result<RPCNeighborStatsInfo, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let response_json: serde_json::Value = contents.try_into()?;
+        let stats: RPCNeighborStatsInfo = serde_json::from_value(response_json)
+            .map_err(|_e| Error::DecodeError("Failed to decode JSON".to_string()))?;
+        Ok(stats)
+    }
+}
@@ -0,0 +1,544 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::clarity::ClarityConnection;
+use clarity::vm::database::{ClarityDatabase, STXBalance};
+use clarity::vm::representations::PRINCIPAL_DATA_REGEX_STRING;
+use clarity::vm::types::{PrincipalData, TupleData, Value};
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::{BurnchainHeaderHash, StacksAddress, StacksBlockId};
+use stacks_common::types::net::PeerHost;
+
+use crate::burnchains::Burnchain;
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::burn::operations::{DelegateStxOp, StackStxOp};
+use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::stacks::address::PoxAddress;
+use crate::chainstate::stacks::boot::{POX_2_NAME, POX_3_NAME, POX_4_NAME};
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::chainstate::stacks::Error as ChainError;
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    EndpointClass, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler,
+    StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+use crate::util_lib::boot::boot_code_id;
+
+/// A lockup, as recorded in a PoX contract's `stacking-state` map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoxStackingStateEntry {
+    /// PoX reward address, if it has a b58 or bech32 encoding
+    pub pox_addr: Option<String>,
+    pub first_reward_cycle: u64,
+    /// Number of reward cycles the STX are locked for, including any extensions
+    pub lock_period: u64,
+    /// First reward cycle in which the STX are no longer locked
+    pub unlock_reward_cycle: u64,
+    /// Burnchain height at which the STX unlock
+    pub unlock_height: u64,
+    /// Delegate which stacked on the principal's behalf, if any
+    pub delegated_to: Option<String>,
+}
+
+/// A delegation, as recorded in a PoX contract's `delegation-state` map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoxDelegationStateEntry {
+    /// Most uSTX the delegate may stack, as a decimal string
+    pub amount_ustx: String,
+    pub delegated_to: String,
+    /// Burnchain height after which the delegation expires, if any
+    pub until_burn_ht: Option<u64>,
+    /// PoX reward address the delegate must use, if any
+    pub pox_addr: Option<String>,
+}
+
+/// A principal's state in one PoX contract
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoxContractHistory {
+    /// Name of the boot contract (e.g. `pox-4`)
+    pub contract: String,
+    pub stacking_state: Option<PoxStackingStateEntry>,
+    pub delegation_state: Option<PoxDelegationStateEntry>,
+}
+
+/// A PoX operation sent on the burnchain, on the canonical burnchain fork.  These are reported as
+/// mined; PoX may still have rejected them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PoxBurnchainOpEntry {
+    StackStx {
+        txid: String,
+        burn_block_height: u64,
+        burn_header_hash: String,
+        sender: String,
+        reward_addr: Option<String>,
+        /// uSTX to lock, as a decimal string
+        stacked_ustx: String,
+        num_cycles: u8,
+        signer_key: Option<String>,
+        /// Most uSTX the signer key authorized, as a decimal string
+        max_amount: Option<String>,
+        auth_id: Option<u32>,
+    },
+    DelegateStx {
+        txid: String,
+        burn_block_height: u64,
+        burn_header_hash: String,
+        sender: String,
+        delegate_to: String,
+        reward_addr: Option<String>,
+        /// uSTX delegated, as a decimal string
+        delegated_ustx: String,
+        until_burn_height: Option<u64>,
+    },
+}
+
+impl PoxBurnchainOpEntry {
+    fn from_stack_stx(op: StackStxOp) -> Self {
+        Self::StackStx {
+            txid: op.txid.to_string(),
+            burn_block_height: op.block_height,
+            burn_header_hash: op.burn_header_hash.to_string(),
+            sender: op.sender.to_string(),
+            reward_addr: Some(op.reward_addr.to_b58()),
+            stacked_ustx: op.stacked_ustx.to_string(),
+            num_cycles: op.num_cycles,
+            signer_key: op.signer_key.map(|key| key.to_hex()),
+            max_amount: op.max_amount.map(|amount| amount.to_string()),
+            auth_id: op.auth_id,
+        }
+    }
+
+    fn from_delegate_stx(op: DelegateStxOp) -> Self {
+        Self::DelegateStx {
+            txid: op.txid.to_string(),
+            burn_block_height: op.block_height,
+            burn_header_hash: op.burn_header_hash.to_string(),
+            sender: op.sender.to_string(),
+            delegate_to: op.delegate_to.to_string(),
+            reward_addr: op.reward_addr.map(|(_, addr)| addr.to_b58()),
+            delegated_ustx: op.delegated_ustx.to_string(),
+            until_burn_height: op.until_burn_height,
+        }
+    }
+}
+
+/// A principal's PoX lockups and delegations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoxHistoryResponse {
+    pub principal: String,
+    /// Index block hash of the block the PoX state was read from
+    pub index_block_hash: StacksBlockId,
+    /// Height of the block the PoX state was read from
+    pub block_height: u64,
+    /// uSTX currently locked, as a decimal string
+    pub locked: String,
+    /// Burnchain height at which the currently-locked uSTX unlock, or 0 if none are locked
+    pub unlock_height: u64,
+    /// State in each instantiated PoX contract, newest contract first
+    pub contracts: Vec<PoxContractHistory>,
+    /// Stack-STX and Delegate-STX burnchain operations involving the principal, oldest first
+    pub burnchain_ops: Vec<PoxBurnchainOpEntry>,
+}
+
+impl PoxStackingStateEntry {
+    /// Decode a `stacking-state` map entry.  All of pox-2, pox-3, and pox-4 share its layout.
+    pub fn try_from_tuple(tuple: &TupleData, mainnet: bool, burnchain: &Burnchain) -> Option<Self> {
+        let first_reward_cycle = u64::try_from(
+            tuple
+                .get("first-reward-cycle")
+                .ok()?
+                .clone()
+                .expect_u128()
+                .ok()?,
+        )
+        .ok()?;
+        let lock_period =
+            u64::try_from(tuple.get("lock-period").ok()?.clone().expect_u128().ok()?).ok()?;
+        let unlock_reward_cycle = first_reward_cycle.checked_add(lock_period)?;
+        let pox_addr = PoxAddress::try_from_pox_tuple(mainnet, tuple.get("pox-addr").ok()?)
+            .map(|addr| addr.to_b58());
+        let delegated_to = tuple
+            .get("delegated-to")
+            .ok()?
+            .clone()
+            .expect_optional()
+            .ok()?
+            .map(|principal| principal.expect_principal().map(|p| p.to_string()))
+            .transpose()
+            .ok()?;
+        Some(Self {
+            pox_addr,
+            first_reward_cycle,
+            lock_period,
+            unlock_reward_cycle,
+            unlock_height: burnchain.reward_cycle_to_block_height(unlock_reward_cycle),
+            delegated_to,
+        })
+    }
+}
+
+impl PoxDelegationStateEntry {
+    /// Decode a `delegation-state` map entry.  All of pox-2, pox-3, and pox-4 share its layout.
+    pub fn try_from_tuple(tuple: &TupleData, mainnet: bool) -> Option<Self> {
+        let amount_ustx = tuple.get("amount-ustx").ok()?.clone().expect_u128().ok()?;
+        let delegated_to = tuple
+            .get("delegated-to")
+            .ok()?
+            .clone()
+            .expect_principal()
+            .ok()?;
+        let until_burn_ht = tuple
+            .get("until-burn-ht")
+            .ok()?
+            .clone()
+            .expect_optional()
+            .ok()?
+            .map(|height| {
+                height
+                    .expect_u128()
+                    .map(|h| u64::try_from(h).unwrap_or(u64::MAX))
+            })
+            .transpose()
+            .ok()?;
+        let pox_addr = tuple
+            .get("pox-addr")
+            .ok()?
+            .clone()
+            .expect_optional()
+            .ok()?
+            .and_then(|addr| PoxAddress::try_from_pox_tuple(mainnet, &addr))
+            .map(|addr| addr.to_b58());
+        Some(Self {
+            amount_ustx: amount_ustx.to_string(),
+            delegated_to: delegated_to.to_string(),
+            until_burn_ht,
+            pox_addr,
+        })
+    }
+}
+
+/// Read `principal`'s entry in a PoX contract's map, as a tuple
+fn fetch_pox_map_entry(
+    clarity_db: &mut ClarityDatabase,
+    pox_contract: &str,
+    map_name: &str,
+    principal: &PrincipalData,
+    mainnet: bool,
+) -> Option<TupleData> {
+    let epoch = clarity_db.get_clarity_epoch_version().ok()?;
+    let key = Value::Tuple(
+        TupleData::from_data(vec![("stacker".into(), principal.clone().into())]).ok()?,
+    );
+    clarity_db
+        .fetch_entry_unknown_descriptor(
+            &boot_code_id(pox_contract, mainnet),
+            map_name,
+            &key,
+            &epoch,
+        )
+        .ok()?
+        .expect_optional()
+        .ok()??
+        .expect_tuple()
+        .ok()
+}
+
+/// Read `principal`'s PoX state and burnchain PoX operations as of `tip`.
+/// Returns Ok(None) if the tip's state is not available.
+pub fn load_pox_history(
+    burnchain: &Burnchain,
+    sortdb: &SortitionDB,
+    chainstate: &mut StacksChainState,
+    tip: &StacksBlockId,
+    principal: &PrincipalData,
+) -> Result<Option<PoxHistoryResponse>, ChainError> {
+    let Some(block_height) = NakamotoChainState::get_block_header(chainstate.db(), tip)?
+        .map(|hdr| hdr.stacks_block_height)
+    else {
+        return Ok(None);
+    };
+    let mainnet = chainstate.mainnet;
+
+    let state_opt = chainstate.maybe_read_only_clarity_tx(
+        &sortdb.index_handle_at_block(chainstate, tip)?,
+        tip,
+        |clarity_tx| {
+            clarity_tx.with_clarity_db_readonly(|clarity_db| {
+                let burn_block_height =
+                    clarity_db.get_current_burnchain_block_height().ok()? as u64;
+                let v1_unlock_height = clarity_db.get_v1_unlock_height();
+                let v2_unlock_height = clarity_db.get_v2_unlock_height().ok()?;
+                let v3_unlock_height = clarity_db.get_v3_unlock_height().ok()?;
+                let balance = clarity_db
+                    .get_data::<STXBalance>(&ClarityDatabase::make_key_for_account_balance(
+                        principal,
+                    ))
+                    .ok()
+                    .flatten()
+                    .unwrap_or_else(STXBalance::zero);
+                let (locked, unlock_height) = balance.get_locked_balance_at_burn_block(
+                    burn_block_height,
+                    v1_unlock_height,
+                    v2_unlock_height,
+                    v3_unlock_height,
+                );
+
+                let mut contracts = vec![];
+                for pox_contract in [POX_4_NAME, POX_3_NAME, POX_2_NAME] {
+                    if !clarity_db.has_contract(&boot_code_id(pox_contract, mainnet)) {
+                        continue;
+                    }
+                    let stacking_state = fetch_pox_map_entry(
+                        clarity_db,
+                        pox_contract,
+                        "stacking-state",
+                        principal,
+                        mainnet,
+                    )
+                    .and_then(|tuple| {
+                        PoxStackingStateEntry::try_from_tuple(&tuple, mainnet, burnchain)
+                    });
+                    let delegation_state = fetch_pox_map_entry(
+                        clarity_db,
+                        pox_contract,
+                        "delegation-state",
+                        principal,
+                        mainnet,
+                    )
+                    .and_then(|tuple| PoxDelegationStateEntry::try_from_tuple(&tuple, mainnet));
+                    contracts.push(PoxContractHistory {
+                        contract: pox_contract.to_string(),
+                        stacking_state,
+                        delegation_state,
+                    });
+                }
+                Some((locked, unlock_height, contracts))
+            })
+        },
+    )?;
+    let Some((locked, unlock_height, contracts)) = state_opt.flatten() else {
+        return Ok(None);
+    };
+
+    // Burnchain operations can only be sent by standard principals.  Only report the ones on the
+    // canonical burnchain fork.
+    let mut burnchain_ops = vec![];
+    if let PrincipalData::Standard(standard) = principal {
+        let address: StacksAddress = standard.clone().into();
+        let sort_handle = sortdb.index_handle_at_tip();
+        let is_canonical =
+            |height: u64, burn_header_hash: &BurnchainHeaderHash| -> Result<bool, ChainError> {
+                Ok(sort_handle
+                    .get_block_snapshot_by_height(height)?
+                    .is_some_and(|sn| &sn.burn_header_hash == burn_header_hash))
+            };
+
+        let mut ops = vec![];
+        for op in SortitionDB::get_stack_stx_ops_by_sender(sortdb.conn(), &address)? {
+            if is_canonical(op.block_height, &op.burn_header_hash)? {
+                ops.push((
+                    op.block_height,
+                    op.vtxindex,
+                    PoxBurnchainOpEntry::from_stack_stx(op),
+                ));
+            }
+        }
+        for op in SortitionDB::get_delegate_stx_ops_by_principal(sortdb.conn(), &address)? {
+            if is_canonical(op.block_height, &op.burn_header_hash)? {
+                ops.push((
+                    op.block_height,
+                    op.vtxindex,
+                    PoxBurnchainOpEntry::from_delegate_stx(op),
+                ));
+            }
+        }
+        ops.sort_by_key(|(height, vtxindex, _)| (*height, *vtxindex));
+        burnchain_ops = ops.into_iter().map(|(_, _, op)| op).collect();
+    }
+
+    Ok(Some(PoxHistoryResponse {
+        principal: principal.to_string(),
+        index_block_hash: tip.clone(),
+        block_height,
+        locked: locked.to_string(),
+        unlock_height,
+        contracts,
+        burnchain_ops,
+    }))
+}
+
+#[derive(Clone)]
+pub struct RPCGetPoxHistoryRequestHandler {
+    pub principal: Option<PrincipalData>,
+}
+
+impl RPCGetPoxHistoryRequestHandler {
+    pub fn new() -> Self {
+        Self { principal: None }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetPoxHistoryRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!(
+            "^/v3/pox/(?P<principal>{})/history$",
+            *PRINCIPAL_DATA_REGEX_STRING
+        ))
+        .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/pox/:principal/history"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let principal = if let Some(value) = captures.name("principal") {
+            PrincipalData::parse(value.into())
+                .map_err(|_e| Error::DecodeError("Failed to parse `principal` field".to_string()))?
+        } else {
+            return Err(Error::DecodeError(
+                "Missing in request path: `principal`".into(),
+            ));
+        };
+
+        self.principal = Some(principal);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetPoxHistoryRequestHandler {
+    /// Scans the PoX contracts' state and the sortition DB's stacking operations
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.principal = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let principal = self
+            .principal
+            .take()
+            .ok_or(NetError::SendError("Missing `principal`".into()))?;
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let history_res =
+            node.with_node_state(|network, sortdb, chainstate, _mempool, _rpc_args| {
+                load_pox_history(
+                    network.get_burnchain(),
+                    sortdb,
+                    chainstate,
+                    &tip,
+                    &principal,
+                )
+            });
+
+        let history = match history_res {
+            Ok(Some(history)) => history,
+            Ok(None) | Err(_) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new(format!("Chain tip '{}' not found", &tip)),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&history)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetPoxHistoryRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let history: PoxHistoryResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(history)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for a principal's PoX lockups and delegations
+    pub fn new_getpoxhistory(
+        host: PeerHost,
+        principal: PrincipalData,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v3/pox/{}/history", &principal),
+            HttpRequestContents::new().for_tip(tip_req),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_pox_history_response(self) -> Result<PoxHistoryResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: PoxHistoryResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
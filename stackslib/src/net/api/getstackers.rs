@@ -20,7 +20,6 @@ use stacks_common::util::hash::Sha256Sum;
 
 use crate::burnchains::Burnchain;
 use crate::chainstate::burn::db::sortdb::SortitionDB;
-use crate::chainstate::coordinator::OnChainRewardSetProvider;
 use crate::chainstate::stacks::boot::{
     PoxVersions, RewardSet, POX_1_NAME, POX_2_NAME, POX_3_NAME, POX_4_NAME,
 };
@@ -104,7 +103,7 @@ impl GetStackersResponse {
             );
         }
 
-        let provider = OnChainRewardSetProvider::new();
+        let provider = chainstate.reward_set_provider();
         let stacker_set = provider
             .read_reward_set_nakamoto(chainstate, cycle_number, sortdb, tip, true)
             .map_err(GetStackersErrors::NotAvailableYet)?;
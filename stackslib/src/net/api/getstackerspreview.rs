@@ -0,0 +1,245 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use serde_json::json;
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::net::PeerHost;
+use stacks_common::types::StacksEpochId;
+
+use crate::burnchains::Burnchain;
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::stacks::boot::{PoxVersions, RewardSet};
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::net::api::getstackers::GetStackersErrors;
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    EndpointClass, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttpRequest,
+    StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+pub static PATH: &str = "/v3/stacker_set/next/preview";
+
+#[derive(Clone, Default)]
+pub struct GetStackersPreviewRequestHandler {}
+
+/// The reward set the next reward cycle would have if its prepare phase started now
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetStackersPreviewResponse {
+    /// The next reward cycle
+    pub reward_cycle: u64,
+    /// Whether or not the prepare phase has already fixed the reward set, in which case
+    /// `stacker_set` is the reward set the cycle will use
+    pub is_final: bool,
+    /// Total uSTX locked for the cycle so far
+    pub total_ustx_stacked: u128,
+    /// Total liquid uSTX at the chain tip
+    pub liquid_ustx: u128,
+    pub stacker_set: RewardSet,
+}
+
+impl GetStackersPreviewResponse {
+    /// Compute the next reward cycle's reward set from the pox-4 state at `tip`, or load it if
+    /// the prepare phase has already computed it
+    pub fn load(
+        sortdb: &SortitionDB,
+        chainstate: &mut StacksChainState,
+        tip: &StacksBlockId,
+        burnchain: &Burnchain,
+    ) -> Result<Self, GetStackersErrors> {
+        let burn_tip = SortitionDB::get_canonical_burn_chain_tip(sortdb.conn())
+            .map_err(|e| GetStackersErrors::Other(format!("Failed to load burnchain tip: {e}")))?;
+        let reward_cycle = burnchain
+            .block_height_to_reward_cycle(burn_tip.block_height)
+            .ok_or("Burnchain tip is before the first reward cycle")?
+            .saturating_add(1);
+
+        let cycle_start_height = burnchain.reward_cycle_to_block_height(reward_cycle);
+        let pox_contract_name = burnchain
+            .pox_constants
+            .active_pox_contract(cycle_start_height);
+        let pox_version = PoxVersions::lookup_by_name(pox_contract_name)
+            .ok_or("Failed to lookup PoX contract version for the next reward cycle")?;
+        if !matches!(pox_version, PoxVersions::Pox4) {
+            return Err(
+                "Active PoX contract version in the next reward cycle is Pre-PoX-4, the signer set is not fetchable"
+                    .into(),
+            );
+        }
+
+        let liquid_ustx = chainstate.get_liquid_ustx(tip);
+        let provider = chainstate.reward_set_provider();
+        if let Ok(stacker_set) =
+            provider.read_reward_set_nakamoto(chainstate, reward_cycle, sortdb, tip, false)
+        {
+            let total_ustx_stacked = stacker_set
+                .signers
+                .iter()
+                .flatten()
+                .map(|signer| signer.stacked_amt)
+                .sum();
+            return Ok(Self {
+                reward_cycle,
+                is_final: true,
+                total_ustx_stacked,
+                liquid_ustx,
+                stacker_set,
+            });
+        }
+
+        let reward_slots = chainstate
+            .get_reward_addresses_in_cycle(burnchain, sortdb, reward_cycle, tip)
+            .map_err(|e| {
+                GetStackersErrors::Other(format!("Failed to read pox-4 reward slots: {e}"))
+            })?;
+        let (threshold, total_ustx_stacked) =
+            StacksChainState::get_reward_threshold_and_participation(
+                &burnchain.pox_constants,
+                &reward_slots,
+                liquid_ustx,
+            );
+        let stacker_set =
+            StacksChainState::make_reward_set(threshold, reward_slots, StacksEpochId::Epoch30);
+
+        Ok(Self {
+            reward_cycle,
+            is_final: false,
+            total_ustx_stacked,
+            liquid_ustx,
+            stacker_set,
+        })
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for GetStackersPreviewRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".into(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for GetStackersPreviewRequestHandler {
+    /// Computes the next reward set by running the pox contract's stacker selection
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let preview_response =
+            node.with_node_state(|network, sortdb, chainstate, _mempool, _rpc_args| {
+                GetStackersPreviewResponse::load(sortdb, chainstate, &tip, network.get_burnchain())
+            });
+
+        let response = match preview_response {
+            Ok(response) => response,
+            Err(error) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpBadRequest::new_json(json!({
+                        "response": "error",
+                        "err_type": error.error_type_string(),
+                        "err_msg": error.to_string()})),
+                )
+                .try_into_contents()
+                .map_err(NetError::from)
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&response)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for GetStackersPreviewRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let response: GetStackersPreviewResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(response)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for a preview of the next reward cycle's signer set
+    pub fn new_getstackers_preview(host: PeerHost, tip_req: TipRequest) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            PATH.into(),
+            HttpRequestContents::new().for_tip(tip_req),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_stacker_set_preview(self) -> Result<GetStackersPreviewResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let response_json: serde_json::Value = contents.try_into()?;
+        let response: GetStackersPreviewResponse = serde_json::from_value(response_json)
+            .map_err(|_e| Error::DecodeError("Failed to decode JSON".to_string()))?;
+        Ok(response)
+    }
+}
@@ -35,7 +35,7 @@ use crate::net::http::{
     HttpResponsePayload, HttpResponsePreamble, HttpServerError, HttpVersion,
 };
 use crate::net::httpcore::{
-    HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+    EndpointClass, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
     StacksHttpResponse,
 };
 use crate::net::{Error as NetError, StacksNodeState, TipRequest, MAX_HEADERS};
@@ -202,6 +202,20 @@ impl HttpRequest for RPCNakamotoTenureRequestHandler {
 }
 
 impl RPCRequestHandler for RPCNakamotoTenureRequestHandler {
+    /// Tenures are streamed from disk
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// The blocks streamed back from a given block (and stopping point) never change
+    fn immutable_etag(&self) -> Option<String> {
+        let block_id = self.block_id.as_ref()?;
+        match self.last_block_id.as_ref() {
+            Some(last_block_id) => Some(format!("{block_id}-{last_block_id}")),
+            None => Some(block_id.to_hex()),
+        }
+    }
+
     /// Reset internal state
     fn restart(&mut self) {
         self.block_id = None;
@@ -0,0 +1,156 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::net::PeerHost;
+
+use crate::chainstate::nakamoto::tenure::TenureBudgetUsage;
+use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{RPCRequestHandler, StacksHttpRequest, StacksHttpResponse};
+use crate::net::{Error as NetError, StacksNodeState};
+
+pub static PATH: &str = "/v3/tenures/current/budget";
+
+#[derive(Clone)]
+pub struct RPCGetTenureBudgetRequestHandler {}
+
+impl RPCGetTenureBudgetRequestHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetTenureBudgetRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetTenureBudgetRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let usage_resp =
+            node.with_node_state(|network, sortdb, chainstate, _mempool, _rpc_args| {
+                let tip_block_id = StacksBlockId::new(
+                    &network.stacks_tip.consensus_hash,
+                    &network.stacks_tip.block_hash,
+                );
+                match NakamotoChainState::get_tenure_budget_usage(
+                    chainstate.db(),
+                    sortdb.conn(),
+                    &tip_block_id,
+                ) {
+                    Ok(Some(usage)) => Ok(usage),
+                    Ok(None) => {
+                        let msg = format!("Stacks tip {tip_block_id} is not a Nakamoto block");
+                        debug!("{msg}");
+                        Err(StacksHttpResponse::new_error(
+                            &preamble,
+                            &HttpNotFound::new(msg),
+                        ))
+                    }
+                    Err(e) => {
+                        let msg =
+                            format!("Failed to load tenure budget usage at {tip_block_id}: {e:?}");
+                        error!("{msg}");
+                        Err(StacksHttpResponse::new_error(
+                            &preamble,
+                            &HttpServerError::new(msg),
+                        ))
+                    }
+                }
+            });
+
+        let usage = match usage_resp {
+            Ok(usage) => usage,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&usage)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetTenureBudgetRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let usage: TenureBudgetUsage = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(usage)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the current tenure's budget usage
+    pub fn new_get_tenure_budget(host: PeerHost) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(host, "GET".into(), PATH.into(), HttpRequestContents::new())
+            .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_tenure_budget(self) -> Result<TenureBudgetUsage, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let response_json: serde_json::Value = contents.try_into()?;
+        let usage: TenureBudgetUsage = serde_json::from_value(response_json)
+            .map_err(|_e| Error::DecodeError("Failed to decode JSON".to_string()))?;
+        Ok(usage)
+    }
+}
@@ -0,0 +1,247 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::{BlockHeaderHash, ConsensusHash, StacksBlockId};
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::hash::Hash160;
+
+use crate::burnchains::Txid;
+use crate::chainstate::nakamoto::tenure::NakamotoTenureEvent;
+use crate::chainstate::nakamoto::{NakamotoBlock, NakamotoChainState};
+use crate::chainstate::stacks::{Error as ChainError, TenureChangeCause};
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{request, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse};
+use crate::net::{Error as NetError, StacksNodeState};
+
+/// A tenure-change or tenure-extend transaction processed in a tenure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TenureChangeInfo {
+    pub txid: Txid,
+    pub cause: TenureChangeCause,
+    /// The block that contains the transaction
+    pub block_id: StacksBlockId,
+    pub block_hash: BlockHeaderHash,
+    pub block_height: u64,
+    /// The sortition tip the miner had seen when it made the transaction
+    pub burn_view_consensus_hash: ConsensusHash,
+    pub prev_tenure_consensus_hash: ConsensusHash,
+    /// The last block of the previous tenure (for a tenure-change) or of this tenure so far
+    /// (for a tenure-extend)
+    pub previous_tenure_end: StacksBlockId,
+    /// Number of blocks in the previous tenure (for a tenure-change) or in this tenure so far
+    /// (for a tenure-extend)
+    pub previous_tenure_blocks: u32,
+    pub coinbase_height: u64,
+    /// Hash160 of the miner's public key
+    pub pubkey_hash: Hash160,
+}
+
+impl TenureChangeInfo {
+    /// Combine a processed tenure-change with the block that contains it.
+    /// Returns None if the block has no tenure-change or tenure-extend transaction.
+    pub fn from_block(event: NakamotoTenureEvent, block: &NakamotoBlock) -> Option<Self> {
+        let tx = block.txs.first()?;
+        let payload = block.get_tenure_tx_payload()?;
+        Some(Self {
+            txid: tx.txid(),
+            cause: event.cause,
+            block_id: event.block_id,
+            block_hash: event.block_hash,
+            block_height: block.header.chain_length,
+            burn_view_consensus_hash: event.burn_view_consensus_hash,
+            prev_tenure_consensus_hash: event.prev_tenure_id_consensus_hash,
+            previous_tenure_end: payload.previous_tenure_end.clone(),
+            previous_tenure_blocks: event.num_blocks_confirmed,
+            coinbase_height: event.coinbase_height,
+            pubkey_hash: payload.pubkey_hash.clone(),
+        })
+    }
+}
+
+/// The response to GET /v3/tenures/:consensus_hash/changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TenureChangesResponse {
+    pub consensus_hash: ConsensusHash,
+    /// Every tenure-change and tenure-extend processed in the tenure, on any fork, in block
+    /// height order
+    pub changes: Vec<TenureChangeInfo>,
+}
+
+#[derive(Clone)]
+pub struct RPCGetTenureChangesRequestHandler {
+    pub(crate) consensus_hash: Option<ConsensusHash>,
+}
+
+impl RPCGetTenureChangesRequestHandler {
+    pub fn new() -> Self {
+        Self {
+            consensus_hash: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetTenureChangesRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/tenures/(?P<consensus_hash>[0-9a-f]{40})/changes$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/tenures/:consensus_hash/changes"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+        let consensus_hash = request::get_consensus_hash(captures, "consensus_hash")?;
+        self.consensus_hash = Some(consensus_hash);
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetTenureChangesRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.consensus_hash = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let consensus_hash = self
+            .consensus_hash
+            .take()
+            .ok_or(NetError::SendError("`consensus_hash` not set".into()))?;
+
+        let changes_res =
+            node.with_node_state(|_network, _sortdb, chainstate, _mempool, _rpc_args| {
+                let events = NakamotoChainState::get_nakamoto_tenure_changes(
+                    chainstate.db(),
+                    &consensus_hash,
+                )?;
+                let mut changes = Vec::with_capacity(events.len());
+                for event in events {
+                    let block_id = event.block_id.clone();
+                    let Some((block, _size)) = chainstate
+                        .nakamoto_blocks_db()
+                        .get_nakamoto_block(&block_id)?
+                    else {
+                        return Err(ChainError::InvalidStacksBlock(format!(
+                            "Processed block {block_id} is not stored"
+                        )));
+                    };
+                    let Some(change) = TenureChangeInfo::from_block(event, &block) else {
+                        return Err(ChainError::InvalidStacksBlock(format!(
+                            "Block {block_id} has no tenure-change transaction"
+                        )));
+                    };
+                    changes.push(change);
+                }
+                Ok(changes)
+            });
+
+        let changes = match changes_res {
+            Ok(changes) if changes.is_empty() => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new(format!(
+                        "No tenure changes processed in tenure {consensus_hash}"
+                    )),
+                )
+                .try_into_contents()
+                .map_err(NetError::from)
+            }
+            Ok(changes) => changes,
+            Err(e) => {
+                let msg =
+                    format!("Failed to load tenure changes in tenure {consensus_hash}: {e:?}");
+                warn!("{msg}");
+                return StacksHttpResponse::new_error(&preamble, &HttpServerError::new(msg))
+                    .try_into_contents()
+                    .map_err(NetError::from);
+            }
+        };
+
+        let response = TenureChangesResponse {
+            consensus_hash,
+            changes,
+        };
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&response)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetTenureChangesRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let response: TenureChangesResponse = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(response)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the tenure-changes and tenure-extends in a tenure
+    pub fn new_get_tenure_changes(
+        host: PeerHost,
+        consensus_hash: &ConsensusHash,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v3/tenures/{consensus_hash}/changes"),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_tenure_changes(self) -> Result<TenureChangesResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let response: TenureChangesResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,225 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::{ConsensusHash, StacksBlockId};
+use stacks_common::types::net::PeerHost;
+
+use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::stacks::db::StacksBlockHeaderTypes;
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    EndpointClass, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+/// How long to wait for a new tip if the request does not say
+pub const DEFAULT_TIP_WAIT_SECS: u64 = 30;
+/// Longest a request may wait for a new tip
+pub const MAX_TIP_WAIT_SECS: u64 = 60;
+
+/// The request to GET /v3/tip/wait.  This is a long-polling endpoint: the node holds the request
+/// until its Stacks tip differs from `since=`, or until `timeout=` seconds have passed, and then
+/// answers with its current tip.
+#[derive(Clone, Default)]
+pub struct RPCGetTipWaitRequestHandler {}
+
+impl RPCGetTipWaitRequestHandler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the tip the caller already knows about, and how many seconds it is willing to wait for
+    /// a new one.  These are read from the request contents, since requests are held after this
+    /// handler has moved on to others.
+    fn parse_args(contents: &HttpRequestContents) -> Result<(StacksBlockId, u64), Error> {
+        let since = contents
+            .get_query_arg("since")
+            .ok_or_else(|| Error::DecodeError("Missing since= query parameter".to_string()))?;
+        let since = StacksBlockId::from_hex(since).map_err(|e| {
+            Error::DecodeError(format!("Failed to parse since= query parameter: {e:?}"))
+        })?;
+        let timeout_secs = contents
+            .get_query_arg("timeout")
+            .map(|timeout| timeout.parse::<u64>())
+            .transpose()
+            .map_err(|e| {
+                Error::DecodeError(format!("Failed to parse timeout= query parameter: {e}"))
+            })?
+            .unwrap_or(DEFAULT_TIP_WAIT_SECS)
+            .min(MAX_TIP_WAIT_SECS);
+        Ok((since, timeout_secs))
+    }
+}
+
+/// The response to GET /v3/tip/wait
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPCTipWaitData {
+    /// Whether the tip differs from `since=`.  False if the request timed out.
+    pub changed: bool,
+    pub index_block_hash: StacksBlockId,
+    pub consensus_hash: ConsensusHash,
+    pub block_height: u64,
+    pub burn_block_height: u32,
+    pub header: StacksBlockHeaderTypes,
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetTipWaitRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/tip/wait$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/tip/wait"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+        let contents = HttpRequestContents::new().query_string(query);
+        Self::parse_args(&contents)?;
+        Ok(contents)
+    }
+}
+
+impl RPCRequestHandler for RPCGetTipWaitRequestHandler {
+    /// Each request ties up its connection for as long as it is held
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Hold the request while the tip is still the one the caller knows about
+    fn defer_request(
+        &mut self,
+        _preamble: &HttpRequestPreamble,
+        contents: &HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Option<u128> {
+        let (since, timeout_secs) = Self::parse_args(contents).ok()?;
+        let tip = node.with_node_state(|network, _sortdb, _chainstate, _mempool, _rpc_args| {
+            network.stacks_tip.block_id()
+        });
+        (tip == since).then(|| u128::from(timeout_secs) * 1000)
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let (since, _) = Self::parse_args(&contents)?;
+
+        let tip_res = node.with_node_state(|network, _sortdb, chainstate, _mempool, _rpc_args| {
+            let tip = network.stacks_tip.block_id();
+            NakamotoChainState::get_block_header(chainstate.db(), &tip)
+                .map(|header_opt| header_opt.map(|header| (tip, header)))
+        });
+
+        let (tip, header) = match tip_res {
+            Ok(Some(tip)) => tip,
+            Ok(None) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("No Stacks tip yet\n".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Err(e) => {
+                let msg = format!("Failed to load Stacks tip: {e:?}\n");
+                warn!("{msg}");
+                return StacksHttpResponse::new_error(&preamble, &HttpServerError::new(msg))
+                    .try_into_contents()
+                    .map_err(NetError::from);
+            }
+        };
+
+        let data = RPCTipWaitData {
+            changed: tip != since,
+            index_block_hash: tip,
+            consensus_hash: header.consensus_hash,
+            block_height: header.stacks_block_height,
+            burn_block_height: header.burn_header_height,
+            header: header.anchored_header,
+        };
+
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&data)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetTipWaitRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let data: RPCTipWaitData = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(data)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to wait for the Stacks tip to move past `since`, for up to
+    /// `timeout_secs` seconds
+    pub fn new_get_tip_wait(
+        host: PeerHost,
+        since: &StacksBlockId,
+        timeout_secs: Option<u64>,
+    ) -> StacksHttpRequest {
+        let mut contents = HttpRequestContents::new().query_arg("since".into(), since.to_hex());
+        if let Some(timeout_secs) = timeout_secs {
+            contents = contents.query_arg("timeout".into(), timeout_secs.to_string());
+        }
+        StacksHttpRequest::new_for_peer(host, "GET".into(), "/v3/tip/wait".into(), contents)
+            .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_tip_wait(self) -> Result<RPCTipWaitData, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let response_json: serde_json::Value = contents.try_into()?;
+        let data: RPCTipWaitData = serde_json::from_value(response_json)
+            .map_err(|_e| Error::DecodeError("Failed to decode JSON".to_string()))?;
+        Ok(data)
+    }
+}
@@ -38,46 +38,87 @@ use crate::stacks_common::codec::StacksMessageCodec;
 pub mod callreadonly;
 pub mod get_tenures_fork_info;
 pub mod getaccount;
+pub mod getaccount_v3;
+pub mod getaffirmations;
+pub mod getarchive;
 pub mod getattachment;
 pub mod getattachmentsinv;
+pub mod getattachmentsrecent;
 pub mod getblock;
 pub mod getblock_v3;
 pub mod getblockbyheight;
+pub mod getblockstatediff;
+pub mod getblocksummary;
+pub mod getburnchainsyncstatus;
+pub mod getburnopreceipt;
+pub mod getburnops;
 pub mod getclaritymarfvalue;
 pub mod getclaritymetadata;
 pub mod getconstantval;
 pub mod getcontractabi;
+pub mod getcontractabi_v3;
+pub mod getcontractcosts;
 pub mod getcontractsrc;
+pub mod getdataproof;
 pub mod getdatavar;
 pub mod getheaders;
+pub mod gethealth;
 pub mod getinfo;
+pub mod getinfo_v3;
 pub mod getistraitimplemented;
+pub mod getloglevels;
 pub mod getmapentry;
 pub mod getmicroblocks_confirmed;
 pub mod getmicroblocks_indexed;
 pub mod getmicroblocks_unconfirmed;
+pub mod getminerfillstats;
+pub mod getminerreports;
+pub mod getminerutxos;
 pub mod getneighbors;
+pub mod getneighborstats;
+pub mod getpoxhistory;
 pub mod getpoxinfo;
 pub mod getsigner;
 pub mod getsortition;
 pub mod getstackerdbchunk;
 pub mod getstackerdbmetadata;
 pub mod getstackers;
+pub mod getstackerspreview;
 pub mod getstxtransfercost;
 pub mod gettenure;
+pub mod gettenurebudget;
+pub mod gettenurechanges;
 pub mod gettenureinfo;
 pub mod gettenuretip;
+pub mod gettipwait;
 pub mod gettransaction_unconfirmed;
+pub mod gettransactionreceipt;
 pub mod liststackerdbreplicas;
+pub mod postbackup;
 pub mod postblock;
 pub mod postblock_proposal;
 #[warn(unused_imports)]
 pub mod postblock_v3;
+pub mod postconfigreload;
+pub mod postcontractanalyze;
 pub mod postfeerate;
+pub mod postloglevels;
+pub mod postmempooladmission;
+pub mod postmempooldrain;
+pub mod postmempooldraintofile;
+pub mod postmempoolimport;
 pub mod postmempoolquery;
 pub mod postmicroblock;
+pub mod postminingpause;
+pub mod postpeerban;
+pub mod postpoxanchoroverride;
+pub mod postreorgconfirm;
 pub mod poststackerdbchunk;
+pub mod poststackerdbreplica;
 pub mod posttransaction;
+pub mod posttransactionsimulate;
+pub mod postverifysignersignature;
+pub mod preflight;
 
 #[cfg(test)]
 mod tests;
@@ -91,23 +132,50 @@ impl StacksHttp {
             self.read_only_call_limit.clone(),
         ));
         self.register_rpc_endpoint(getaccount::RPCGetAccountRequestHandler::new());
+        self.register_rpc_endpoint(getaccount_v3::RPCGetAccountV3RequestHandler::new());
+        self.register_rpc_endpoint(getaffirmations::RPCGetAffirmationsRequestHandler::new());
+        self.register_rpc_endpoint(getarchive::RPCGetArchiveTenureRequestHandler::new());
+        self.register_rpc_endpoint(getarchive::RPCGetArchiveBlocksRequestHandler::new());
         self.register_rpc_endpoint(getattachment::RPCGetAttachmentRequestHandler::new());
         self.register_rpc_endpoint(getattachmentsinv::RPCGetAttachmentsInvRequestHandler::new());
+        self.register_rpc_endpoint(
+            getattachmentsrecent::RPCGetRecentAttachmentsRequestHandler::new(),
+        );
         self.register_rpc_endpoint(getblock::RPCBlocksRequestHandler::new());
         self.register_rpc_endpoint(getblock_v3::RPCNakamotoBlockRequestHandler::new());
         self.register_rpc_endpoint(getblockbyheight::RPCNakamotoBlockByHeightRequestHandler::new());
+        self.register_rpc_endpoint(getblockstatediff::RPCGetBlockStateDiffRequestHandler::new());
+        self.register_rpc_endpoint(getblocksummary::RPCGetBlockSummaryRequestHandler::new());
+        self.register_rpc_endpoint(
+            getburnchainsyncstatus::RPCGetBurnchainSyncStatusRequestHandler::new(),
+        );
+        self.register_rpc_endpoint(getburnopreceipt::RPCGetBurnOpReceiptRequestHandler::new());
+        self.register_rpc_endpoint(getburnops::RPCGetBurnOpsRequestHandler::new());
         self.register_rpc_endpoint(getclaritymarfvalue::RPCGetClarityMarfRequestHandler::new());
         self.register_rpc_endpoint(getclaritymetadata::RPCGetClarityMetadataRequestHandler::new());
         self.register_rpc_endpoint(getconstantval::RPCGetConstantValRequestHandler::new());
         self.register_rpc_endpoint(getcontractabi::RPCGetContractAbiRequestHandler::new());
+        self.register_rpc_endpoint(getcontractabi_v3::RPCGetContractAbiV3RequestHandler::new());
         self.register_rpc_endpoint(getcontractsrc::RPCGetContractSrcRequestHandler::new());
+        self.register_rpc_endpoint(getcontractcosts::RPCGetContractCostsRequestHandler::new());
+        self.register_rpc_endpoint(getdataproof::RPCGetDataProofRequestHandler::new(
+            self.read_only_map_entry_limit.clone(),
+        ));
         self.register_rpc_endpoint(getdatavar::RPCGetDataVarRequestHandler::new());
         self.register_rpc_endpoint(getheaders::RPCHeadersRequestHandler::new());
         self.register_rpc_endpoint(getinfo::RPCPeerInfoRequestHandler::new());
+        self.register_rpc_endpoint(getinfo_v3::RPCPeerInfoV3RequestHandler::new());
+        self.register_rpc_endpoint(gethealth::RPCGetHealthLiveRequestHandler::new());
+        self.register_rpc_endpoint(gethealth::RPCGetHealthReadyRequestHandler::new());
+        self.register_rpc_endpoint(getloglevels::RPCGetLogLevelsRequestHandler::new(
+            self.admin_auth.clone(),
+        ));
         self.register_rpc_endpoint(
             getistraitimplemented::RPCGetIsTraitImplementedRequestHandler::new(),
         );
-        self.register_rpc_endpoint(getmapentry::RPCGetMapEntryRequestHandler::new());
+        self.register_rpc_endpoint(getmapentry::RPCGetMapEntryRequestHandler::new(
+            self.read_only_map_entry_limit.clone(),
+        ));
         self.register_rpc_endpoint(
             getmicroblocks_confirmed::RPCMicroblocksConfirmedRequestHandler::new(),
         );
@@ -117,38 +185,108 @@ impl StacksHttp {
         self.register_rpc_endpoint(
             getmicroblocks_unconfirmed::RPCMicroblocksUnconfirmedRequestHandler::new(),
         );
+        self.register_rpc_endpoint(getminerfillstats::RPCGetMinerFillStatsRequestHandler::new());
+        self.register_rpc_endpoint(getminerreports::RPCGetMinerReportsRequestHandler::new());
+        self.register_rpc_endpoint(getminerutxos::RPCGetMinerUtxosRequestHandler::new());
         self.register_rpc_endpoint(getneighbors::RPCNeighborsRequestHandler::new());
+        self.register_rpc_endpoint(getneighborstats::RPCNeighborStatsRequestHandler::new());
         self.register_rpc_endpoint(getstxtransfercost::RPCGetStxTransferCostRequestHandler::new());
         self.register_rpc_endpoint(getstackerdbchunk::RPCGetStackerDBChunkRequestHandler::new());
+        self.register_rpc_endpoint(getpoxhistory::RPCGetPoxHistoryRequestHandler::new());
         self.register_rpc_endpoint(getpoxinfo::RPCPoxInfoRequestHandler::new());
         self.register_rpc_endpoint(
             getstackerdbmetadata::RPCGetStackerDBMetadataRequestHandler::new(),
         );
         self.register_rpc_endpoint(getstackers::GetStackersRequestHandler::default());
+        self.register_rpc_endpoint(getstackerspreview::GetStackersPreviewRequestHandler::default());
         self.register_rpc_endpoint(getsortition::GetSortitionHandler::new());
         self.register_rpc_endpoint(gettenure::RPCNakamotoTenureRequestHandler::new());
+        self.register_rpc_endpoint(gettenurebudget::RPCGetTenureBudgetRequestHandler::new());
+        self.register_rpc_endpoint(gettenurechanges::RPCGetTenureChangesRequestHandler::new());
         self.register_rpc_endpoint(gettenureinfo::RPCNakamotoTenureInfoRequestHandler::new());
         self.register_rpc_endpoint(gettenuretip::RPCNakamotoTenureTipRequestHandler::new());
+        self.register_rpc_endpoint(gettipwait::RPCGetTipWaitRequestHandler::new());
         self.register_rpc_endpoint(get_tenures_fork_info::GetTenuresForkInfo::default());
         self.register_rpc_endpoint(
             gettransaction_unconfirmed::RPCGetTransactionUnconfirmedRequestHandler::new(),
         );
+        self.register_rpc_endpoint(
+            gettransactionreceipt::RPCGetTransactionReceiptRequestHandler::new(),
+        );
         self.register_rpc_endpoint(getsigner::GetSignerRequestHandler::default());
         self.register_rpc_endpoint(
             liststackerdbreplicas::RPCListStackerDBReplicasRequestHandler::new(),
         );
+        self.register_rpc_endpoint(postbackup::RPCPostBackupRequestHandler::new(
+            self.admin_auth.clone(),
+        ));
         self.register_rpc_endpoint(postblock::RPCPostBlockRequestHandler::new());
         self.register_rpc_endpoint(postblock_proposal::RPCBlockProposalRequestHandler::new(
-            self.auth_token.clone(),
+            self.admin_auth.clone(),
         ));
         self.register_rpc_endpoint(postblock_v3::RPCPostBlockRequestHandler::new(
-            self.auth_token.clone(),
+            self.admin_auth.clone(),
         ));
+        self.register_rpc_endpoint(postconfigreload::RPCPostConfigReloadRequestHandler::new(
+            self.admin_auth.clone(),
+        ));
+        self.register_rpc_endpoint(
+            postcontractanalyze::RPCPostContractAnalyzeRequestHandler::new(),
+        );
         self.register_rpc_endpoint(postfeerate::RPCPostFeeRateRequestHandler::new());
+        self.register_rpc_endpoint(postloglevels::RPCPostLogLevelsRequestHandler::new(
+            self.admin_auth.clone(),
+        ));
+        self.register_rpc_endpoint(
+            postmempooladmission::RPCPostMempoolAdmissionRequestHandler::new(
+                self.admin_auth.clone(),
+            ),
+        );
+        self.register_rpc_endpoint(postmempooldrain::RPCPostMempoolDrainRequestHandler::new(
+            self.admin_auth.clone(),
+        ));
+        self.register_rpc_endpoint(
+            postmempooldraintofile::RPCPostMempoolDrainToFileRequestHandler::new(
+                self.admin_auth.clone(),
+            ),
+        );
+        self.register_rpc_endpoint(postmempoolimport::RPCPostMempoolImportRequestHandler::new(
+            self.admin_auth.clone(),
+        ));
         self.register_rpc_endpoint(postmempoolquery::RPCMempoolQueryRequestHandler::new());
         self.register_rpc_endpoint(postmicroblock::RPCPostMicroblockRequestHandler::new());
+        self.register_rpc_endpoint(postminingpause::RPCPostMiningPauseRequestHandler::new(
+            self.admin_auth.clone(),
+        ));
+        self.register_rpc_endpoint(postpeerban::RPCPostPeerBanRequestHandler::new(
+            self.admin_auth.clone(),
+        ));
+        self.register_rpc_endpoint(
+            postpoxanchoroverride::RPCPostPoxAnchorOverrideRequestHandler::new(
+                self.admin_auth.clone(),
+            ),
+        );
+        self.register_rpc_endpoint(postreorgconfirm::RPCPostReorgConfirmRequestHandler::new(
+            self.admin_auth.clone(),
+        ));
         self.register_rpc_endpoint(poststackerdbchunk::RPCPostStackerDBChunkRequestHandler::new());
+        self.register_rpc_endpoint(
+            poststackerdbreplica::RPCPostStackerDBReplicaRequestHandler::new(
+                self.admin_auth.clone(),
+            ),
+        );
         self.register_rpc_endpoint(posttransaction::RPCPostTransactionRequestHandler::new());
+        self.register_rpc_endpoint(
+            posttransactionsimulate::RPCPostTransactionSimulateRequestHandler::new(
+                self.admin_auth.clone(),
+            ),
+        );
+        self.register_rpc_endpoint(
+            postverifysignersignature::RPCVerifySignerSignatureRequestHandler::new(
+                self.read_only_call_limit.clone(),
+            ),
+        );
+        self.register_rpc_endpoint(preflight::RPCPreflightRequestHandler::new());
     }
 }
 
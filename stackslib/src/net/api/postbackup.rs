@@ -0,0 +1,152 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    AdminAuth, AdminRole, EndpointClass, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+use crate::util_lib::backup::{get_backup_service, BackupStatus};
+
+pub const PATH: &str = "/v3/admin/backup";
+
+#[derive(Clone, Default)]
+pub struct RPCPostBackupRequestHandler {
+    pub auth: AdminAuth,
+}
+
+impl RPCPostBackupRequestHandler {
+    pub fn new(auth: AdminAuth) -> Self {
+        Self { auth }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPostBackupRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    /// There is nothing to decode beyond the authorization header.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        self.auth.authorize(preamble, AdminRole::Backup)?;
+
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCPostBackupRequestHandler {
+    /// Triggering a backup requires an admin token
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::Admin
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response.
+    /// The backup is taken asynchronously by the node's backup thread; the response reports the
+    /// outcome of the last backup.
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let root_path =
+            node.with_node_state(|_network, _sortdb, chainstate, _mempool, _rpc_args| {
+                chainstate.root_path.clone()
+            });
+        let Some(backups) = get_backup_service(&root_path) else {
+            debug!("Backup service not running on this stacks node");
+            return StacksHttpResponse::new_error(
+                &preamble,
+                &HttpNotFound::new("Backups are not enabled on this node".to_string()),
+            )
+            .try_into_contents()
+            .map_err(NetError::from);
+        };
+        let status = backups.request();
+        info!("Requested a backup of the node's databases");
+
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&status)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPostBackupRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let data: BackupStatus = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(data)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to back up the node's databases
+    pub fn new_post_backup(host: PeerHost, auth: &str) -> StacksHttpRequest {
+        let mut request = StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            PATH.into(),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data");
+        request.add_header("authorization".into(), auth.into());
+        request
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_backup_status(self) -> Result<BackupStatus, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: BackupStatus = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::HashMap;
 use std::io::{Read, Write};
 #[cfg(any(test, feature = "testing"))]
 use std::sync::LazyLock;
@@ -34,7 +35,7 @@ use stacks_common::types::chainstate::{
     BlockHeaderHash, BurnchainHeaderHash, ConsensusHash, StacksBlockId, StacksPublicKey,
 };
 use stacks_common::types::net::PeerHost;
-use stacks_common::types::StacksPublicKeyBuffer;
+use stacks_common::types::{StacksEpochId, StacksPublicKeyBuffer};
 use stacks_common::util::hash::{hex_bytes, to_hex, Hash160, Sha256Sum, Sha512Trunc256Sum};
 use stacks_common::util::retry::BoundReader;
 #[cfg(any(test, feature = "testing"))]
@@ -60,8 +61,8 @@ use crate::net::http::{
     HttpResponsePayload, HttpResponsePreamble, HttpServerError,
 };
 use crate::net::httpcore::{
-    request, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler,
-    StacksHttpRequest, StacksHttpResponse,
+    request, AdminAuth, AdminRole, EndpointClass, HttpPreambleExtensions,
+    HttpRequestContentsExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
 };
 use crate::net::p2p::PeerNetwork;
 use crate::net::relay::Relayer;
@@ -90,6 +91,18 @@ define_u8_enum![ValidateRejectCode {
     NoSuchTenure = 6
 }];
 
+// This enum is used to supply an optional `reason_detail` for validation
+//  rejection responses, which narrows down the `reason_code`.  It is a
+//  separate field so that the set of reason codes stays decodable by
+//  signers that predate these details.
+define_u8_enum![ValidateRejectDetail {
+    CostExceeded = 0,
+    BadNonce = 1,
+    SignerSetMismatch = 2,
+    TenureChangeInvalid = 3,
+    ReplayedTransaction = 4
+}];
+
 pub static TOO_MANY_REQUESTS_STATUS: u16 = 429;
 
 impl TryFrom<u8> for ValidateRejectCode {
@@ -100,6 +113,29 @@ impl TryFrom<u8> for ValidateRejectCode {
     }
 }
 
+impl TryFrom<u8> for ValidateRejectDetail {
+    type Error = CodecError;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::from_u8(value)
+            .ok_or_else(|| CodecError::DeserializeError(format!("Unknown type prefix: {value}")))
+    }
+}
+
+impl ValidateRejectDetail {
+    /// The detail, if any, for a chainstate error hit while validating a block
+    pub fn from_chainstate_error(e: &ChainError) -> Option<Self> {
+        match e {
+            ChainError::BlockTooBigError
+            | ChainError::TransactionTooBigError(_)
+            | ChainError::BlockCostExceeded
+            | ChainError::CostOverflowError(..) => Some(Self::CostExceeded),
+            ChainError::ExpectedTenureChange => Some(Self::TenureChangeInvalid),
+            ChainError::NoRegisteredSigners(_) => Some(Self::SignerSetMismatch),
+            _ => None,
+        }
+    }
+}
+
 fn hex_ser_block<S: serde::Serializer>(b: &NakamotoBlock, s: S) -> Result<S::Ok, S::Error> {
     let inst = to_hex(&b.serialize_to_vec());
     s.serialize_str(inst.as_str())
@@ -118,12 +154,15 @@ pub struct BlockValidateReject {
     pub signer_signature_hash: Sha512Trunc256Sum,
     pub reason: String,
     pub reason_code: ValidateRejectCode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason_detail: Option<ValidateRejectDetail>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct BlockValidateRejectReason {
     pub reason: String,
     pub reason_code: ValidateRejectCode,
+    pub reason_detail: Option<ValidateRejectDetail>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -145,8 +184,9 @@ where
     fn from(value: T) -> Self {
         let ce: ChainError = value.into();
         Self {
-            reason: format!("Chainstate Error: {ce}"),
             reason_code: ValidateRejectCode::ChainstateError,
+            reason_detail: ValidateRejectDetail::from_chainstate_error(&ce),
+            reason: format!("Chainstate Error: {ce}"),
         }
     }
 }
@@ -215,17 +255,19 @@ impl NakamotoBlockProposal {
         sortdb: SortitionDB,
         mut chainstate: StacksChainState,
         receiver: Box<dyn ProposalCallbackReceiver>,
+        num_workers: usize,
     ) -> Result<JoinHandle<()>, std::io::Error> {
         thread::Builder::new()
             .name("block-proposal".into())
             .spawn(move || {
-                let result =
-                    self.validate(&sortdb, &mut chainstate)
-                        .map_err(|reason| BlockValidateReject {
-                            signer_signature_hash: self.block.header.signer_signature_hash(),
-                            reason_code: reason.reason_code,
-                            reason: reason.reason,
-                        });
+                let result = self
+                    .validate(&sortdb, &mut chainstate, num_workers)
+                    .map_err(|reason| BlockValidateReject {
+                        signer_signature_hash: self.block.header.signer_signature_hash(),
+                        reason_code: reason.reason_code,
+                        reason_detail: reason.reason_detail,
+                        reason: reason.reason,
+                    });
                 receiver.notify_proposal_result(result);
             })
     }
@@ -247,6 +289,7 @@ impl NakamotoBlockProposal {
         )
         .map_err(|e| BlockValidateRejectReason {
             reason_code: ValidateRejectCode::ChainstateError,
+            reason_detail: None,
             reason: format!("Failed to query highest block in tenure ID: {:?}", &e),
         })?
         else {
@@ -257,6 +300,7 @@ impl NakamotoBlockProposal {
             );
             return Err(BlockValidateRejectReason {
                 reason_code: ValidateRejectCode::NoSuchTenure,
+                reason_detail: None,
                 reason: "Block is not a tenure-start block, and has an unrecognized tenure consensus hash".into(),
             });
         };
@@ -264,6 +308,7 @@ impl NakamotoBlockProposal {
             NakamotoChainState::get_block_header(chainstate.db(), parent_block_id).map_err(
                 |e| BlockValidateRejectReason {
                     reason_code: ValidateRejectCode::ChainstateError,
+                    reason_detail: None,
                     reason: format!("Failed to query block header by block ID: {:?}", &e),
                 },
             )?
@@ -275,6 +320,7 @@ impl NakamotoBlockProposal {
             );
             return Err(BlockValidateRejectReason {
                 reason_code: ValidateRejectCode::UnknownParent,
+                reason_detail: None,
                 reason: "Block has no parent".into(),
             });
         };
@@ -288,12 +334,97 @@ impl NakamotoBlockProposal {
             );
             return Err(BlockValidateRejectReason {
                 reason_code: ValidateRejectCode::InvalidBlock,
+                reason_detail: None,
                 reason: "Block is not higher than the highest block in its tenure".into(),
             });
         }
         Ok(())
     }
 
+    /// Run the checks of a transaction that do not depend on chainstate: signature
+    /// verification, and screening out problematic transactions.
+    /// Returns the reason for rejecting the block if the `i`th transaction fails.
+    fn precheck_transaction(
+        i: usize,
+        tx: &StacksTransaction,
+        mainnet: bool,
+        epoch_id: StacksEpochId,
+        ast_rules: ASTRules,
+    ) -> Option<String> {
+        if let Err(e) = tx.verify() {
+            return Some(format!(
+                "Error processing tx {i}: {}",
+                ChainError::NetError(e)
+            ));
+        }
+        if let Err(e) =
+            Relayer::static_check_problematic_relayed_tx(mainnet, epoch_id, tx, ast_rules)
+        {
+            return Some(format!("Problematic tx {i}: {}", ChainError::NetError(e)));
+        }
+        None
+    }
+
+    /// Run `precheck_transaction()` on each of the block's transactions, split across up to
+    /// `num_workers` threads, so that only the Clarity execution of the transactions is serial.
+    /// If more than one transaction fails, the first one in block order is reported.
+    /// Implemented as a static function to facilitate testing.
+    pub(crate) fn precheck_transactions(
+        txs: &[StacksTransaction],
+        mainnet: bool,
+        epoch_id: StacksEpochId,
+        ast_rules: ASTRules,
+        num_workers: usize,
+    ) -> Result<(), BlockValidateRejectReason> {
+        let precheck_chunk = |offset: usize, chunk: &[StacksTransaction]| {
+            chunk.iter().enumerate().find_map(|(j, tx)| {
+                let i = offset + j;
+                Self::precheck_transaction(i, tx, mainnet, epoch_id, ast_rules)
+                    .map(|reason| (i, reason))
+            })
+        };
+
+        let num_workers = num_workers.clamp(1, txs.len().max(1));
+        let failure = if num_workers == 1 {
+            precheck_chunk(0, txs)
+        } else {
+            let chunk_size = txs.len().div_ceil(num_workers);
+            thread::scope(|s| {
+                let workers: Vec<_> = txs
+                    .chunks(chunk_size)
+                    .enumerate()
+                    .map(|(chunk_index, chunk)| {
+                        s.spawn(move || precheck_chunk(chunk_index * chunk_size, chunk))
+                    })
+                    .collect();
+
+                // join every worker, but keep the failure that comes first in the block
+                workers
+                    .into_iter()
+                    .map(|worker| {
+                        worker
+                            .join()
+                            .expect("FATAL: block proposal transaction check worker panicked")
+                    })
+                    .fold(None, |first, failure| first.or(failure))
+            })
+        };
+
+        let Some((i, reason)) = failure else {
+            return Ok(());
+        };
+        warn!(
+            "Rejected block proposal";
+            "reason" => %reason,
+            "tx" => ?txs[i],
+        );
+        Err(BlockValidateRejectReason {
+            reason,
+            reason_code: ValidateRejectCode::BadTransaction,
+            reason_detail: None,
+        })
+    }
+
     /// Verify that the block we received builds upon a valid tenure.
     /// Implemented as a static function to facilitate testing.
     pub(crate) fn check_block_has_valid_tenure(
@@ -309,6 +440,7 @@ impl NakamotoBlockProposal {
             );
             return Err(BlockValidateRejectReason {
                 reason_code: ValidateRejectCode::NonCanonicalTenure,
+                reason_detail: None,
                 reason: "Tenure consensus hash is not on the canonical Bitcoin fork".into(),
             });
         }
@@ -330,6 +462,7 @@ impl NakamotoBlockProposal {
                 .is_wellformed_tenure_start_block()
                 .map_err(|_| BlockValidateRejectReason {
                     reason_code: ValidateRejectCode::InvalidBlock,
+                    reason_detail: Some(ValidateRejectDetail::TenureChangeInvalid),
                     reason: "Block is not well-formed".into(),
                 })?;
 
@@ -350,6 +483,7 @@ impl NakamotoBlockProposal {
             )?
             .ok_or_else(|| BlockValidateRejectReason {
                 reason_code: ValidateRejectCode::UnknownParent,
+                reason_detail: None,
                 reason: "No parent block".into(),
             })?;
 
@@ -371,11 +505,13 @@ impl NakamotoBlockProposal {
     ///   - Transactions are well-formed
     ///   - Miner signature is valid
     /// - Validation of transactions by executing them agains current chainstate.
-    ///   This is resource intensive, and therefore done only if previous checks pass
+    ///   This is resource intensive, and therefore done only if previous checks pass.
+    ///   The transactions' signatures are checked up front on up to `num_workers` threads.
     pub fn validate(
         &self,
         sortdb: &SortitionDB,
         chainstate: &mut StacksChainState, // not directly used; used as a handle to open other chainstates
+        num_workers: usize,
     ) -> Result<BlockValidateOk, BlockValidateRejectReason> {
         #[cfg(any(test, feature = "testing"))]
         {
@@ -406,6 +542,7 @@ impl NakamotoBlockProposal {
             );
             return Err(BlockValidateRejectReason {
                 reason_code: ValidateRejectCode::InvalidBlock,
+                reason_detail: None,
                 reason: "Wrong network/chain_id".into(),
             });
         }
@@ -429,6 +566,7 @@ impl NakamotoBlockProposal {
         )?
         .ok_or_else(|| BlockValidateRejectReason {
             reason_code: ValidateRejectCode::InvalidBlock,
+            reason_detail: None,
             reason: "Invalid parent block".into(),
         })?;
 
@@ -438,6 +576,7 @@ impl NakamotoBlockProposal {
             SortitionDB::get_block_snapshot_consensus(sortdb.conn(), &burn_view_consensus_hash)?
                 .ok_or_else(|| BlockValidateRejectReason {
                     reason_code: ValidateRejectCode::NoSuchTenure,
+                    reason_detail: None,
                     reason: "Failed to find sortition for block tenure".to_string(),
                 })?;
 
@@ -464,6 +603,7 @@ impl NakamotoBlockProposal {
             );
             return Err(BlockValidateRejectReason {
                 reason_code: ValidateRejectCode::UnknownParent,
+                reason_detail: None,
                 reason: "Failed to find parent expected burns".into(),
             });
         };
@@ -476,6 +616,7 @@ impl NakamotoBlockProposal {
             &self.block,
             mainnet,
             self.chain_id,
+            None,
         )?;
 
         // Validate txs against chainstate
@@ -495,6 +636,7 @@ impl NakamotoBlockProposal {
                 );
                 return Err(BlockValidateRejectReason {
                     reason_code: ValidateRejectCode::InvalidBlock,
+                    reason_detail: None,
                     reason: "Block timestamp is not greater than parent block".into(),
                 });
             }
@@ -508,6 +650,7 @@ impl NakamotoBlockProposal {
             );
             return Err(BlockValidateRejectReason {
                 reason_code: ValidateRejectCode::InvalidBlock,
+                reason_detail: None,
                 reason: "Block timestamp is too far into the future".into(),
             });
         }
@@ -541,6 +684,32 @@ impl NakamotoBlockProposal {
             builder.load_tenure_info(chainstate, &burn_dbconn, tenure_cause)?;
         let mut tenure_tx = builder.tenure_begin(&burn_dbconn, &mut miner_tenure_info)?;
 
+        Self::precheck_transactions(
+            &self.block.txs,
+            mainnet,
+            tenure_tx.get_epoch(),
+            ASTRules::PrecheckSize,
+            num_workers,
+        )?;
+        builder.set_txs_prechecked(true);
+
+        let mut seen_txids = HashMap::with_capacity(self.block.txs.len());
+        for (i, tx) in self.block.txs.iter().enumerate() {
+            if let Some(j) = seen_txids.insert(tx.txid(), i) {
+                let reason = format!("tx {i} is a replay of tx {j}");
+                warn!(
+                    "Rejected block proposal";
+                    "reason" => %reason,
+                    "tx" => ?tx,
+                );
+                return Err(BlockValidateRejectReason {
+                    reason,
+                    reason_code: ValidateRejectCode::BadTransaction,
+                    reason_detail: Some(ValidateRejectDetail::ReplayedTransaction),
+                });
+            }
+        }
+
         for (i, tx) in self.block.txs.iter().enumerate() {
             let tx_len = tx.tx_len();
             let tx_result = builder.try_mine_tx_with_len(
@@ -550,27 +719,41 @@ impl NakamotoBlockProposal {
                 &BlockLimitFunction::NO_LIMIT_HIT,
                 ASTRules::PrecheckSize,
             );
-            let err = match tx_result {
-                TransactionResult::Success(_) => Ok(()),
-                TransactionResult::Skipped(s) => Err(format!("tx {i} skipped: {}", s.error)),
+            let (reason, error) = match tx_result {
+                TransactionResult::Success(_) => continue,
+                TransactionResult::Skipped(s) => (format!("tx {i} skipped: {}", s.error), s.error),
                 TransactionResult::ProcessingError(e) => {
-                    Err(format!("Error processing tx {i}: {}", e.error))
+                    (format!("Error processing tx {i}: {}", e.error), e.error)
                 }
                 TransactionResult::Problematic(p) => {
-                    Err(format!("Problematic tx {i}: {}", p.error))
+                    (format!("Problematic tx {i}: {}", p.error), p.error)
                 }
             };
-            if let Err(reason) = err {
-                warn!(
-                    "Rejected block proposal";
-                    "reason" => %reason,
-                    "tx" => ?tx,
-                );
-                return Err(BlockValidateRejectReason {
-                    reason,
-                    reason_code: ValidateRejectCode::BadTransaction,
-                });
-            }
+            let reason_detail = match ValidateRejectDetail::from_chainstate_error(&error) {
+                detail @ Some(ValidateRejectDetail::CostExceeded) => detail,
+                _ if matches!(tx.payload, TransactionPayload::TenureChange(..)) => {
+                    Some(ValidateRejectDetail::TenureChangeInvalid)
+                }
+                _ => match StacksChainState::check_transaction_nonces(&mut tenure_tx, tx, true) {
+                    // the account already used this nonce
+                    Err((mismatch, _)) if mismatch.actual < mismatch.expected => {
+                        Some(ValidateRejectDetail::ReplayedTransaction)
+                    }
+                    Err(_) => Some(ValidateRejectDetail::BadNonce),
+                    Ok(_) => None,
+                },
+            };
+            warn!(
+                "Rejected block proposal";
+                "reason" => %reason,
+                "reason_detail" => ?reason_detail,
+                "tx" => ?tx,
+            );
+            return Err(BlockValidateRejectReason {
+                reason,
+                reason_code: ValidateRejectCode::BadTransaction,
+                reason_detail,
+            });
         }
 
         let mut block = builder.mine_nakamoto_block(&mut tenure_tx);
@@ -608,6 +791,7 @@ impl NakamotoBlockProposal {
             return Err(BlockValidateRejectReason {
                 reason: "Block hash is not as expected".into(),
                 reason_code: ValidateRejectCode::BadBlockHash,
+                reason_detail: None,
             });
         }
 
@@ -639,11 +823,11 @@ impl NakamotoBlockProposal {
 #[derive(Clone, Default)]
 pub struct RPCBlockProposalRequestHandler {
     pub block_proposal: Option<NakamotoBlockProposal>,
-    pub auth: Option<String>,
+    pub auth: AdminAuth,
 }
 
 impl RPCBlockProposalRequestHandler {
-    pub fn new(auth: Option<String>) -> Self {
+    pub fn new(auth: AdminAuth) -> Self {
         Self {
             block_proposal: None,
             auth,
@@ -680,16 +864,7 @@ impl HttpRequest for RPCBlockProposalRequestHandler {
         query: Option<&str>,
         body: &[u8],
     ) -> Result<HttpRequestContents, Error> {
-        // If no authorization is set, then the block proposal endpoint is not enabled
-        let Some(password) = &self.auth else {
-            return Err(Error::Http(400, "Bad Request.".into()));
-        };
-        let Some(auth_header) = preamble.headers.get("authorization") else {
-            return Err(Error::Http(401, "Unauthorized".into()));
-        };
-        if auth_header != password {
-            return Err(Error::Http(401, "Unauthorized".into()));
-        }
+        self.auth.authorize(preamble, AdminRole::Blocks)?;
         if preamble.get_content_length() == 0 {
             return Err(Error::DecodeError(
                 "Invalid Http request: expected non-zero-length body for block proposal endpoint"
@@ -734,6 +909,11 @@ struct ProposalThreadInfo {
 }
 
 impl RPCRequestHandler for RPCBlockProposalRequestHandler {
+    /// Block proposals require an admin token, and are expensive to validate
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::Admin
+    }
+
     /// Reset internal state
     fn restart(&mut self) {
         self.block_proposal = None
@@ -794,8 +974,9 @@ impl RPCRequestHandler for RPCBlockProposalRequestHandler {
                         ),
                     )
                 })?;
+            let num_workers = network.get_connection_opts().nakamoto_sigcheck_threads;
             let thread_info = block_proposal
-                .spawn_validation_thread(sortdb, chainstate, receiver)
+                .spawn_validation_thread(sortdb, chainstate, receiver, num_workers)
                 .map_err(|_e| {
                     (
                         TOO_MANY_REQUESTS_STATUS,
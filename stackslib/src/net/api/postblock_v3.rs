@@ -27,7 +27,8 @@ use crate::net::http::{
     HttpResponsePreamble,
 };
 use crate::net::httpcore::{
-    HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+    AdminAuth, AdminRole, HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest,
+    StacksHttpResponse,
 };
 use crate::net::relay::Relayer;
 use crate::net::{Error as NetError, NakamotoBlocksData, StacksMessageType, StacksNodeState};
@@ -37,12 +38,12 @@ pub static PATH: &str = "/v3/blocks/upload/";
 #[derive(Clone, Default)]
 pub struct RPCPostBlockRequestHandler {
     pub block: Option<NakamotoBlock>,
-    pub auth: Option<String>,
+    pub auth: AdminAuth,
     pub broadcast: Option<bool>,
 }
 
 impl RPCPostBlockRequestHandler {
-    pub fn new(auth: Option<String>) -> Self {
+    pub fn new(auth: AdminAuth) -> Self {
         Self {
             block: None,
             auth,
@@ -103,13 +104,9 @@ impl HttpRequest for RPCPostBlockRequestHandler {
         let mut authenticated = false;
 
         // look for authorization header
-        if let Some(password) = &self.auth {
-            if let Some(auth_header) = preamble.headers.get("authorization") {
-                if auth_header != password {
-                    return Err(Error::Http(401, "Unauthorized".into()));
-                }
-                authenticated = true;
-            }
+        if self.auth.allows(AdminRole::Blocks) && preamble.headers.contains_key("authorization") {
+            self.auth.authorize(preamble, AdminRole::Blocks)?;
+            authenticated = true;
         }
 
         // see if broadcast=1 is set
@@ -172,6 +169,7 @@ impl RPCRequestHandler for RPCPostBlockRequestHandler {
                     rpc_args.coord_comms,
                     NakamotoBlockObtainMethod::Uploaded,
                     self.broadcast.unwrap_or(false),
+                    None,
                 )
             })
             .map_err(|e| {
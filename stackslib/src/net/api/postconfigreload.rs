@@ -0,0 +1,180 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpNotFound, HttpRequest, HttpRequestContents,
+    HttpRequestPreamble, HttpResponse, HttpResponseContents, HttpResponsePayload,
+    HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    AdminAuth, AdminRole, EndpointClass, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+pub static PATH: &str = "/v3/admin/config/reload";
+
+/// The response to POST /v3/admin/config/reload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigReloadResponse {
+    /// Names of the admin tokens accepted from now on
+    pub admin_tokens: Vec<String>,
+}
+
+/// Re-reads the node's config file.  The admin tokens are replaced, and take effect on HTTP
+/// connections opened afterwards.  The miner and burnchain settings are already re-read from the
+/// file whenever they are used.  If the file is not a valid config, nothing is changed.
+#[derive(Clone, Default)]
+pub struct RPCPostConfigReloadRequestHandler {
+    pub auth: AdminAuth,
+}
+
+impl RPCPostConfigReloadRequestHandler {
+    pub fn new(auth: AdminAuth) -> Self {
+        Self { auth }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPostConfigReloadRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    /// There is nothing to decode beyond the authorization header.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        self.auth.authorize(preamble, AdminRole::Config)?;
+
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCPostConfigReloadRequestHandler {
+    /// Reloading the config requires an admin token
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::Admin
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let reload_res =
+            node.with_node_state(|network, _sortdb, _chainstate, _mempool, rpc_args| {
+                let reloader = rpc_args.config_reloader?;
+                let reloaded = reloader.reload_connection_options().map(|conn_opts| {
+                    let auth =
+                        AdminAuth::new(conn_opts.auth_token.as_deref(), &conn_opts.admin_tokens);
+                    network.set_admin_tokens(conn_opts.auth_token, conn_opts.admin_tokens);
+                    auth.token_names()
+                });
+                Some(reloaded)
+            });
+        let admin_tokens = match reload_res {
+            Some(Ok(admin_tokens)) => admin_tokens,
+            Some(Err(msg)) => {
+                warn!("Failed to reload the config file"; "err" => %msg);
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpBadRequest::new(format!("Failed to reload the config file: {msg}")),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            None => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("The config cannot be reloaded on this node".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+        info!(
+            "Reloaded the config file at the node operator's request";
+            "admin_tokens" => ?admin_tokens
+        );
+
+        let response = ConfigReloadResponse { admin_tokens };
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&response)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPostConfigReloadRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let response: ConfigReloadResponse = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(response)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to reload the node's config file
+    pub fn new_post_config_reload(host: PeerHost, auth: &str) -> StacksHttpRequest {
+        let mut request = StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            PATH.into(),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data");
+        request.add_header("authorization".into(), auth.into());
+        request
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_config_reload(self) -> Result<ConfigReloadResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let response: ConfigReloadResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,617 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::analysis::contract_interface_builder::ContractInterface;
+use clarity::vm::ast::ASTRules;
+use clarity::vm::clarity::{ClarityConnection, Error as ClarityError};
+use clarity::vm::contexts::OwnedEnvironment;
+use clarity::vm::costs::{ExecutionCost, LimitedCostTracker};
+use clarity::vm::diagnostic::Diagnostic;
+use clarity::vm::types::QualifiedContractIdentifier;
+use clarity::vm::{analysis, ast, ClarityVersion};
+use regex::{Captures, Regex};
+use stacks_common::codec::StacksMessageCodec;
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::net::PeerHost;
+use stacks_common::types::StacksEpochId;
+
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::stacks::db::read_replica::{
+    ReadReplicaQuery, READ_REPLICA_QUERY_TIMEOUT_MS,
+};
+use crate::chainstate::stacks::{
+    Error as ChainError, TransactionPayload, TransactionSmartContract, MAX_TRANSACTION_LEN,
+};
+use crate::core::StacksEpoch;
+use crate::net::api::postfeerate::{RPCFeeEstimateResponse, RPCPostFeeRateRequestHandler};
+use crate::net::http::{
+    parse_json, Error, HttpContentType, HttpNotFound, HttpRequest, HttpRequestContents,
+    HttpRequestPayload, HttpRequestPreamble, HttpResponse, HttpResponseContents,
+    HttpResponsePayload, HttpResponsePreamble, HttpServerError, HttpServiceUnavailable,
+};
+use crate::net::httpcore::{
+    EndpointClass, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler,
+    StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+use crate::util_lib::strings::StacksString;
+
+/// A contract to analyze
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractAnalyzeRequestBody {
+    /// Clarity source code
+    pub source: String,
+    /// Clarity version (1, 2, or 3).  Defaults to the default version of the tip's epoch.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clarity_version: Option<u32>,
+    /// Identifier the contract would be deployed as.  Defaults to the transient contract
+    /// identifier, which suffices for contracts that do not refer to themselves.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_identifier: Option<String>,
+}
+
+/// A parsed contract to analyze
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractAnalyzeRequest {
+    pub source: StacksString,
+    pub clarity_version: Option<ClarityVersion>,
+    pub contract_identifier: QualifiedContractIdentifier,
+}
+
+impl TryFrom<ContractAnalyzeRequestBody> for ContractAnalyzeRequest {
+    type Error = Error;
+
+    fn try_from(body: ContractAnalyzeRequestBody) -> Result<Self, Self::Error> {
+        let source = StacksString::from_string(&body.source).ok_or_else(|| {
+            Error::DecodeError("`source` must contain only printable ASCII characters".into())
+        })?;
+        let clarity_version = body
+            .clarity_version
+            .map(|version| match version {
+                1 => Ok(ClarityVersion::Clarity1),
+                2 => Ok(ClarityVersion::Clarity2),
+                3 => Ok(ClarityVersion::Clarity3),
+                _ => Err(Error::DecodeError("Unknown `clarity_version`".into())),
+            })
+            .transpose()?;
+        let contract_identifier = body
+            .contract_identifier
+            .map(|id| QualifiedContractIdentifier::parse(&id))
+            .transpose()
+            .map_err(|_e| Error::DecodeError("Failed to parse `contract_identifier`".into()))?
+            .unwrap_or_else(QualifiedContractIdentifier::transient);
+        Ok(Self {
+            source,
+            clarity_version,
+            contract_identifier,
+        })
+    }
+}
+
+impl From<ContractAnalyzeRequest> for ContractAnalyzeRequestBody {
+    fn from(request: ContractAnalyzeRequest) -> Self {
+        Self {
+            source: request.source.to_string(),
+            clarity_version: request.clarity_version.map(|version| match version {
+                ClarityVersion::Clarity1 => 1,
+                ClarityVersion::Clarity2 => 2,
+                ClarityVersion::Clarity3 => 3,
+            }),
+            contract_identifier: Some(request.contract_identifier.to_string()),
+        }
+    }
+}
+
+/// Why a contract would fail to deploy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractAnalysisError {
+    /// One of `unsupported_version`, `duplicate_contract`, `parse`, `analysis`,
+    /// `initialization`, or `cost`
+    pub kind: String,
+    pub message: String,
+    /// Source location and suggestion, for parse and analysis errors
+    pub diagnostic: Option<Diagnostic>,
+}
+
+impl ContractAnalysisError {
+    fn new(kind: &str, message: String) -> Self {
+        Self {
+            kind: kind.into(),
+            message,
+            diagnostic: None,
+        }
+    }
+}
+
+impl From<ClarityError> for ContractAnalysisError {
+    fn from(e: ClarityError) -> Self {
+        let message = e.to_string();
+        match e {
+            ClarityError::CostError(..) => Self::new("cost", message),
+            ClarityError::Parse(e) => Self {
+                kind: "parse".into(),
+                message,
+                diagnostic: Some(e.diagnostic),
+            },
+            ClarityError::Analysis(e) => Self {
+                kind: "analysis".into(),
+                message,
+                diagnostic: Some(e.diagnostic),
+            },
+            _ => Self::new("initialization", message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractAnalyzeResponse {
+    /// Whether a transaction deploying this contract at the queried tip would succeed
+    pub valid: bool,
+    pub error: Option<ContractAnalysisError>,
+    pub contract_identifier: String,
+    pub clarity_version: ClarityVersion,
+    pub epoch: StacksEpochId,
+    /// The contract's interface, if it passed analysis
+    pub interface: Option<ContractInterface>,
+    /// Traits the contract defines
+    pub defined_traits: Vec<String>,
+    /// Traits the contract declares that it implements
+    pub implemented_traits: Vec<String>,
+    /// Cost of parsing and analyzing the contract
+    pub analysis_cost: ExecutionCost,
+    /// Cost of evaluating the contract's top-level expressions and storing it
+    pub initialization_cost: ExecutionCost,
+    /// Total execution cost of the deployment
+    pub estimated_cost: ExecutionCost,
+    /// Length of the deployment's transaction payload
+    pub estimated_len: u64,
+    /// Fee estimates for the deployment, if this node estimates fees
+    pub fee_estimate: Option<RPCFeeEstimateResponse>,
+    /// Index block hash of the block the analysis ran against
+    pub index_block_hash: StacksBlockId,
+}
+
+/// Check a contract deployment the way a block processing it would -- parse, analyze, and
+/// initialize the contract with `block_limit` as its budget -- without storing anything.
+/// Returns Err only if the cost tracker could not be instantiated.
+pub fn analyze_contract<C: ClarityConnection>(
+    conn: &mut C,
+    mainnet: bool,
+    chain_id: u32,
+    block_limit: ExecutionCost,
+    ast_rules: ASTRules,
+    request: &ContractAnalyzeRequest,
+    tip: StacksBlockId,
+) -> Result<ContractAnalyzeResponse, ClarityError> {
+    let epoch = conn.get_epoch();
+    let clarity_version = request
+        .clarity_version
+        .unwrap_or_else(|| ClarityVersion::default_for_epoch(epoch));
+    let contract_id = &request.contract_identifier;
+    let source = request.source.to_string();
+
+    let payload = TransactionPayload::SmartContract(
+        TransactionSmartContract {
+            name: contract_id.name.clone(),
+            code_body: request.source.clone(),
+        },
+        (epoch >= StacksEpochId::Epoch21).then_some(clarity_version),
+    );
+    let mut resp = ContractAnalyzeResponse {
+        valid: false,
+        error: None,
+        contract_identifier: contract_id.to_string(),
+        clarity_version,
+        epoch,
+        interface: None,
+        defined_traits: vec![],
+        implemented_traits: vec![],
+        analysis_cost: ExecutionCost::ZERO,
+        initialization_cost: ExecutionCost::ZERO,
+        estimated_cost: ExecutionCost::ZERO,
+        estimated_len: payload.serialize_to_vec().len() as u64,
+        fee_estimate: None,
+        index_block_hash: tip,
+    };
+
+    // same static checks as `StacksBlock::check_transaction_static_epoch()` and the
+    // duplicate-contract check in `StacksChainState::process_transaction_payload()`
+    if clarity_version > ClarityVersion::default_for_epoch(epoch) {
+        resp.error = Some(ContractAnalysisError::new(
+            "unsupported_version",
+            format!("{clarity_version} is not supported in epoch {epoch}"),
+        ));
+        return Ok(resp);
+    }
+    if conn.with_clarity_db_readonly(|db| db.has_contract(contract_id)) {
+        resp.error = Some(ContractAnalysisError::new(
+            "duplicate_contract",
+            format!("Duplicate contract '{contract_id}'"),
+        ));
+        return Ok(resp);
+    }
+
+    let cost_track = conn.with_clarity_db_readonly(|db| {
+        LimitedCostTracker::new_mid_block(mainnet, chain_id, block_limit, db, epoch)
+    })?;
+
+    // analysis pass
+    let (cost_track, analysis_result) = conn.with_analysis_db_readonly(|db| {
+        let mut cost_track = cost_track;
+        let contract_ast = match ast::build_ast_with_rules(
+            contract_id,
+            &source,
+            &mut cost_track,
+            clarity_version,
+            epoch,
+            ast_rules,
+        ) {
+            Ok(contract_ast) => contract_ast,
+            Err(e) => return (cost_track, Err(ClarityError::from(e))),
+        };
+        match analysis::run_analysis(
+            contract_id,
+            &contract_ast.expressions,
+            db,
+            false,
+            cost_track,
+            epoch,
+            clarity_version,
+            false,
+        ) {
+            Ok(mut contract_analysis) => {
+                let cost_track = contract_analysis.take_contract_cost_tracker();
+                (cost_track, Ok((contract_ast, contract_analysis)))
+            }
+            Err((e, cost_track)) => (cost_track, Err(ClarityError::from(e))),
+        }
+    });
+    resp.analysis_cost = cost_track.get_total();
+    resp.estimated_cost = resp.analysis_cost.clone();
+
+    let (contract_ast, mut contract_analysis) = match analysis_result {
+        Ok(x) => x,
+        Err(e) => {
+            resp.error = Some(e.into());
+            return Ok(resp);
+        }
+    };
+    resp.interface = contract_analysis.contract_interface.take();
+    resp.defined_traits = contract_analysis
+        .defined_traits
+        .keys()
+        .map(|name| name.to_string())
+        .collect();
+    resp.implemented_traits = contract_analysis
+        .implemented_traits
+        .iter()
+        .map(|trait_id| trait_id.to_string())
+        .collect();
+
+    // initialization pass.  The read-only connection rolls back whatever it stores.
+    let (cost_track, init_result) = conn.with_clarity_db_readonly_owned(|clarity_db| {
+        let mut vm_env =
+            OwnedEnvironment::new_cost_limited(mainnet, chain_id, clarity_db, cost_track, epoch);
+        let result = vm_env.initialize_contract_from_ast(
+            contract_id.clone(),
+            clarity_version,
+            &contract_ast,
+            &source,
+            None,
+        );
+        let (db, cost_track) = vm_env
+            .destruct()
+            .expect("Failed to recover database reference after initializing contract");
+        ((cost_track, result), db)
+    });
+    resp.estimated_cost = cost_track.get_total();
+    resp.initialization_cost = resp.estimated_cost.clone();
+    resp.initialization_cost
+        .sub(&resp.analysis_cost)
+        .expect("BUG: total contract cost decreased");
+
+    match init_result {
+        Ok(_) => resp.valid = true,
+        Err(e) => resp.error = Some(ClarityError::from(e).into()),
+    }
+    Ok(resp)
+}
+
+#[derive(Clone)]
+pub struct RPCPostContractAnalyzeRequestHandler {
+    /// The analysis, if it was sent to the read replica workers
+    query: Option<ReadReplicaQuery<Option<Result<ContractAnalyzeResponse, String>>>>,
+}
+
+impl RPCPostContractAnalyzeRequestHandler {
+    pub fn new() -> Self {
+        Self { query: None }
+    }
+
+    /// Get the contract to analyze.  This is read from the request contents, since requests are
+    /// held after this handler has moved on to others.
+    pub fn parse_request(contents: &HttpRequestContents) -> Result<ContractAnalyzeRequest, Error> {
+        let HttpRequestPayload::JSON(body) = contents.get_payload() else {
+            return Err(Error::DecodeError("Missing JSON body".into()));
+        };
+        let body: ContractAnalyzeRequestBody = serde_json::from_value(body.clone())
+            .map_err(|_e| Error::DecodeError("Failed to parse JSON body".into()))?;
+        body.try_into()
+    }
+
+    /// Get the epoch whose block limit bounds the analysis, and the AST rules to parse the
+    /// contract with, as of the canonical burnchain tip
+    fn load_analysis_rules(
+        &self,
+        preamble: &HttpRequestPreamble,
+        sortdb: &SortitionDB,
+    ) -> Result<(StacksEpoch, ASTRules), StacksHttpResponse> {
+        let burn_tip = self.get_canonical_burn_chain_tip(preamble, sortdb)?;
+        let stacks_epoch = self.get_stacks_epoch(preamble, sortdb, burn_tip.block_height)?;
+        let ast_rules =
+            SortitionDB::get_ast_rules(sortdb.conn(), burn_tip.block_height).map_err(|e| {
+                StacksHttpResponse::new_error(
+                    preamble,
+                    &HttpServerError::new(format!("Failed to load AST rules: {:?}", &e)),
+                )
+            })?;
+        Ok((stacks_epoch, ast_rules))
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPostContractAnalyzeRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/contracts/analyze$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/contracts/analyze"
+    }
+
+    /// Try to decode this request.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        // a query left over from a request that was never answered
+        self.query = None;
+
+        // the source is JSON-escaped, so leave room for the escapes
+        let content_len = preamble.get_content_length();
+        if !(content_len > 0 && content_len < 2 * MAX_TRANSACTION_LEN) {
+            return Err(Error::DecodeError(format!(
+                "Invalid Http request: invalid body length for ContractAnalyze ({})",
+                content_len
+            )));
+        }
+
+        if preamble.content_type != Some(HttpContentType::JSON) {
+            return Err(Error::DecodeError(
+                "Invalid content-type: expected application/json".to_string(),
+            ));
+        }
+
+        let body: serde_json::Value = serde_json::from_slice(body)
+            .map_err(|_e| Error::DecodeError("Failed to parse JSON body".into()))?;
+        let contents = HttpRequestContents::new()
+            .query_string(query)
+            .payload_json(body);
+        Self::parse_request(&contents)?;
+        Ok(contents)
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCPostContractAnalyzeRequestHandler {
+    /// Analysis and initialization execute Clarity code
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Hold the request while a read replica worker analyzes the contract, if read replicas are
+    /// enabled
+    fn defer_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        contents: &HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Option<u128> {
+        if let Some(query) = self.query.as_ref() {
+            return (!query.is_done()).then_some(READ_REPLICA_QUERY_TIMEOUT_MS);
+        }
+        let request = Self::parse_request(contents).ok()?;
+        let tip = node.load_stacks_chain_tip(preamble, contents).ok()?;
+        let (mainnet, chain_id, stacks_epoch, ast_rules) =
+            node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+                let (stacks_epoch, ast_rules) = self.load_analysis_rules(preamble, sortdb).ok()?;
+                Some((
+                    chainstate.mainnet,
+                    chainstate.chain_id,
+                    stacks_epoch,
+                    ast_rules,
+                ))
+            })?;
+        let analysis_tip = tip.clone();
+        let query = node.submit_read_replica_query(&tip, move |clarity_tx| {
+            analyze_contract(
+                clarity_tx,
+                mainnet,
+                chain_id,
+                stacks_epoch.block_limit,
+                ast_rules,
+                &request,
+                analysis_tip,
+            )
+            .map_err(|e| e.to_string())
+        })?;
+        let max_wait_ms = (!query.is_done()).then_some(READ_REPLICA_QUERY_TIMEOUT_MS);
+        self.query = Some(query);
+        max_wait_ms
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let request = Self::parse_request(&contents)?;
+        let query = self.query.take();
+
+        let data_resp = node.with_node_state(|_network, sortdb, chainstate, _mempool, rpc_args| {
+            let (stacks_epoch, ast_rules) = self.load_analysis_rules(&preamble, sortdb)?;
+            let mainnet = chainstate.mainnet;
+            let chain_id = chainstate.chain_id;
+
+            let result = if let Some(query) = query {
+                // the analysis ran (or is still running) on a read replica worker
+                let Some(result) = query.take_result() else {
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpServiceUnavailable::new(
+                            "Read replicas are too busy; try again later".to_string(),
+                        ),
+                    ));
+                };
+                result
+            } else {
+                sortdb
+                    .index_handle_at_block(chainstate, &tip)
+                    .map_err(ChainError::from)
+                    .and_then(|sort_handle| {
+                        chainstate.maybe_read_only_clarity_tx(&sort_handle, &tip, |clarity_tx| {
+                            analyze_contract(
+                                clarity_tx,
+                                mainnet,
+                                chain_id,
+                                stacks_epoch.block_limit.clone(),
+                                ast_rules,
+                                &request,
+                                tip.clone(),
+                            )
+                            .map_err(|e| e.to_string())
+                        })
+                    })
+                    .ok()
+                    .flatten()
+            };
+
+            let mut analyze_resp = match result {
+                Some(Ok(analyze_resp)) => analyze_resp,
+                Some(Err(e)) => {
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpServerError::new(format!("Failed to analyze contract: {e}")),
+                    ));
+                }
+                None => {
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpNotFound::new(format!("Chain tip '{}' not found", &tip)),
+                    ));
+                }
+            };
+
+            if let Some((_cost_estimator, fee_estimator, metric)) = rpc_args.get_estimators_ref() {
+                analyze_resp.fee_estimate =
+                    RPCPostFeeRateRequestHandler::estimate_tx_fee_from_cost_and_length(
+                        &preamble,
+                        fee_estimator,
+                        metric,
+                        analyze_resp.estimated_cost.clone(),
+                        analyze_resp.estimated_len,
+                        stacks_epoch,
+                    )
+                    .ok();
+            }
+            Ok(analyze_resp)
+        });
+
+        let analyze_resp = match data_resp {
+            Ok(analyze_resp) => analyze_resp,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&analyze_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPostContractAnalyzeRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let analyze_resp: ContractAnalyzeResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(analyze_resp)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to analyze a contract without deploying it
+    pub fn new_analyze_contract(
+        host: PeerHost,
+        request: ContractAnalyzeRequest,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            "/v3/contracts/analyze".into(),
+            HttpRequestContents::new().for_tip(tip_req).payload_json(
+                serde_json::to_value(ContractAnalyzeRequestBody::from(request))
+                    .expect("FATAL: failed to encode infallible data"),
+            ),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_analyze_contract_response(self) -> Result<ContractAnalyzeResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: ContractAnalyzeResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -48,11 +48,19 @@ use crate::net::httpcore::{
 use crate::net::p2p::PeerNetwork;
 use crate::net::{Error as NetError, StacksNodeState};
 
+/// Length of a single-signature sponsor spending condition: hash mode (1), signer (20), nonce
+/// (8), fee (8), key encoding (1), and signature (65).
+pub const SINGLESIG_SPONSOR_SPENDING_CONDITION_LEN: u64 = 103;
+
 #[derive(Serialize, Deserialize)]
 pub struct FeeRateEstimateRequestBody {
     #[serde(default)]
     pub estimated_len: Option<u64>,
     pub transaction_payload: String,
+    /// If true, the transaction will be sponsored, so its minimum length accounts for the
+    /// sponsor's spending condition as well as the payload.
+    #[serde(default)]
+    pub sponsored: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -197,8 +205,11 @@ impl HttpRequest for RPCPostFeeRateRequestHandler {
         })?;
 
         let tx = TransactionPayload::consensus_deserialize(&mut payload_data.as_slice())?;
-        let estimated_len =
-            std::cmp::max(body.estimated_len.unwrap_or(0), payload_data.len() as u64);
+        let mut min_len = payload_data.len() as u64;
+        if body.sponsored {
+            min_len += SINGLESIG_SPONSOR_SPENDING_CONDITION_LEN;
+        }
+        let estimated_len = std::cmp::max(body.estimated_len.unwrap_or(0), min_len);
 
         self.transaction_payload = Some(tx);
         self.estimated_len = Some(estimated_len);
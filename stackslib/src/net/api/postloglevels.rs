@@ -0,0 +1,161 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::codec::MAX_PAYLOAD_LEN;
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::log::{
+    get_component_loglevels, parse_component_loglevels, set_component_loglevels, ComponentLogLevel,
+};
+
+use crate::net::api::getloglevels::{LogLevelsData, PATH};
+use crate::net::http::{
+    parse_json, Error, HttpContentType, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    AdminAuth, AdminRole, EndpointClass, HttpPreambleExtensions, RPCRequestHandler,
+    StacksHttpRequest,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+#[derive(Clone, Default)]
+pub struct RPCPostLogLevelsRequestHandler {
+    pub log_levels: Option<Vec<ComponentLogLevel>>,
+    pub auth: AdminAuth,
+}
+
+impl RPCPostLogLevelsRequestHandler {
+    pub fn new(auth: AdminAuth) -> Self {
+        Self {
+            log_levels: None,
+            auth,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPostLogLevelsRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    /// The new log levels are only validated here; they are applied when the request is handled.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        self.auth.authorize(preamble, AdminRole::Logging)?;
+
+        let content_len = preamble.get_content_length();
+        if !(content_len > 0 && content_len < MAX_PAYLOAD_LEN) {
+            return Err(Error::DecodeError(format!(
+                "Invalid Http request: invalid body length for log levels ({content_len})"
+            )));
+        }
+        if preamble.content_type != Some(HttpContentType::JSON) {
+            return Err(Error::DecodeError(
+                "Invalid content-type: expected application/json".to_string(),
+            ));
+        }
+
+        let body: LogLevelsData = serde_json::from_slice(body)
+            .map_err(|e| Error::DecodeError(format!("Failed to parse JSON body: {e}")))?;
+        let log_levels = parse_component_loglevels(&body.log_levels).map_err(Error::DecodeError)?;
+
+        self.log_levels = Some(log_levels);
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCPostLogLevelsRequestHandler {
+    /// Changing log levels requires an admin token
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::Admin
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.log_levels = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        _node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let log_levels = self
+            .log_levels
+            .take()
+            .ok_or(NetError::SendError("`log_levels` not set".into()))?;
+
+        let old_levels = LogLevelsData::from_levels(&get_component_loglevels());
+        let new_levels = LogLevelsData::from_levels(&log_levels);
+        set_component_loglevels(log_levels);
+        info!("Changed log levels";
+              "old_log_levels" => %old_levels.log_levels,
+              "new_log_levels" => %new_levels.log_levels);
+
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&new_levels)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPostLogLevelsRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let data: LogLevelsData = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(data)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to replace the node's per-component log levels
+    pub fn new_post_log_levels(host: PeerHost, log_levels: &str, auth: &str) -> StacksHttpRequest {
+        let mut request = StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            PATH.into(),
+            HttpRequestContents::new().payload_json(
+                serde_json::to_value(LogLevelsData {
+                    log_levels: log_levels.to_string(),
+                })
+                .expect("FATAL: failed to encode log levels to JSON"),
+            ),
+        )
+        .expect("FATAL: failed to construct request from infallible data");
+        request.add_header("authorization".into(), auth.into());
+        request
+    }
+}
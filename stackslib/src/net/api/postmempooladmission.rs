@@ -0,0 +1,178 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::{
+    parse_json, Error, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    AdminAuth, AdminRole, EndpointClass, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+/// The response to POST /v3/admin/mempool/admission/pause and
+/// /v3/admin/mempool/admission/resume
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempoolAdmissionResponse {
+    /// Whether the admission of new transactions is now paused
+    pub paused: bool,
+}
+
+/// Pauses or resumes the admission of new transactions into the mempool.  While admission is
+/// paused, transactions posted over RPC are refused with a 503, and transactions pushed or synced
+/// by peers are discarded.  Transactions already in the mempool can still be mined, drained and
+/// re-imported.
+#[derive(Clone, Default)]
+pub struct RPCPostMempoolAdmissionRequestHandler {
+    /// Whether to pause (true) or resume (false) admission
+    pub pause: Option<bool>,
+    pub auth: AdminAuth,
+}
+
+impl RPCPostMempoolAdmissionRequestHandler {
+    pub fn new(auth: AdminAuth) -> Self {
+        Self { pause: None, auth }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPostMempoolAdmissionRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/admin/mempool/admission/(?P<action>pause|resume)$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/admin/mempool/admission/:action"
+    }
+
+    /// Try to decode this request.
+    /// There is nothing to decode beyond the authorization header and the action.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        self.auth.authorize(preamble, AdminRole::Mempool)?;
+
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let pause = match captures.name("action").map(|action| action.as_str()) {
+            Some("pause") => true,
+            Some("resume") => false,
+            _ => {
+                return Err(Error::DecodeError(
+                    "Invalid Http request: expected `pause` or `resume`".to_string(),
+                ));
+            }
+        };
+        self.pause = Some(pause);
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCPostMempoolAdmissionRequestHandler {
+    /// Pausing mempool admission requires an admin token
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::Admin
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.pause = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let pause = self
+            .pause
+            .take()
+            .ok_or(NetError::SendError("`pause` not set".into()))?;
+
+        node.with_node_state(|_network, _sortdb, _chainstate, mempool, _rpc_args| {
+            if mempool.is_admission_paused() != pause {
+                info!(
+                    "{} mempool admission at the node operator's request",
+                    if pause { "Paused" } else { "Resumed" }
+                );
+            }
+            mempool.set_admission_paused(pause);
+        });
+
+        let response = MempoolAdmissionResponse { paused: pause };
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&response)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPostMempoolAdmissionRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let response: MempoolAdmissionResponse = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(response)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to pause (`pause == true`) or resume mempool admission
+    pub fn new_post_mempool_admission(
+        host: PeerHost,
+        pause: bool,
+        auth: &str,
+    ) -> StacksHttpRequest {
+        let action = if pause { "pause" } else { "resume" };
+        let mut request = StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            format!("/v3/admin/mempool/admission/{action}"),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data");
+        request.add_header("authorization".into(), auth.into());
+        request
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_mempool_admission(self) -> Result<MempoolAdmissionResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let response: MempoolAdmissionResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::{
+    parse_json, Error, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    AdminAuth, AdminRole, EndpointClass, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+pub const PATH: &str = "/v3/admin/mempool/drain_to_file";
+
+/// The response to POST /v3/admin/mempool/drain_to_file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempoolDrainToFileResponse {
+    /// How many transactions were dropped
+    pub dropped: u64,
+    /// The file in the node's mempool drain directory that the transactions were saved to.
+    /// Pass it to POST /v3/admin/mempool/import to re-admit them.
+    pub file: String,
+}
+
+/// Saves every mempool transaction to a file in the mempool drain directory, and then drops
+/// them from the mempool.  Unlike POST /v3/admin/mempool/drain, the transactions can be
+/// re-admitted afterwards.
+#[derive(Clone, Default)]
+pub struct RPCPostMempoolDrainToFileRequestHandler {
+    pub auth: AdminAuth,
+}
+
+impl RPCPostMempoolDrainToFileRequestHandler {
+    pub fn new(auth: AdminAuth) -> Self {
+        Self { auth }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPostMempoolDrainToFileRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    /// There is nothing to decode beyond the authorization header.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        self.auth.authorize(preamble, AdminRole::Mempool)?;
+
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCPostMempoolDrainToFileRequestHandler {
+    /// Draining the mempool requires an admin token
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::Admin
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let drain_res =
+            node.with_node_state(|_network, _sortdb, _chainstate, mempool, rpc_args| {
+                mempool.drain_to_file(rpc_args.event_observer)
+            });
+        let (txids, save_path) = match drain_res {
+            Ok(res) => res,
+            Err(e) => {
+                let msg = format!("Failed to drain the mempool to a file: {e:?}");
+                warn!("{msg}");
+                return StacksHttpResponse::new_error(&preamble, &HttpServerError::new(msg))
+                    .try_into_contents()
+                    .map_err(NetError::from);
+            }
+        };
+        info!("Drained the mempool to a file"; "dropped" => txids.len(), "saved_to" => %save_path.display());
+
+        let response = MempoolDrainToFileResponse {
+            dropped: u64::try_from(txids.len()).unwrap_or(u64::MAX),
+            file: save_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        };
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&response)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPostMempoolDrainToFileRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let response: MempoolDrainToFileResponse = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(response)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to save every transaction in the node's mempool to a file and then
+    /// drop them
+    pub fn new_post_mempool_drain_to_file(host: PeerHost, auth: &str) -> StacksHttpRequest {
+        let mut request = StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            PATH.into(),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data");
+        request.add_header("authorization".into(), auth.into());
+        request
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_mempool_drain_to_file(self) -> Result<MempoolDrainToFileResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let response: MempoolDrainToFileResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(response)
+    }
+}
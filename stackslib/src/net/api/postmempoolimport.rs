@@ -0,0 +1,268 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::io::ErrorKind;
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::core::mempool::MemPoolDB;
+use crate::net::http::{
+    parse_json, Error, HttpContentType, HttpNotFound, HttpRequest, HttpRequestContents,
+    HttpRequestPreamble, HttpResponse, HttpResponseContents, HttpResponsePayload,
+    HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    AdminAuth, AdminRole, EndpointClass, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+use crate::util_lib::db::Error as db_error;
+
+pub const PATH: &str = "/v3/admin/mempool/import";
+
+/// The body of POST /v3/admin/mempool/import
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempoolImportRequest {
+    /// A file in the node's mempool drain directory, as reported by POST
+    /// /v3/admin/mempool/drain_to_file
+    pub file: String,
+}
+
+/// The response to POST /v3/admin/mempool/import
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempoolImportResponse {
+    pub file: String,
+    /// How many transactions were admitted to the mempool
+    pub imported: u64,
+    /// How many transactions were already in the mempool
+    pub already_present: u64,
+    /// How many transactions failed the admission checks (e.g. because they were mined, or
+    /// their nonces were used, in the meantime)
+    pub rejected: u64,
+}
+
+/// Re-admits the transactions that POST /v3/admin/mempool/drain_to_file saved to a file.  Each
+/// transaction goes through the usual admission checks against the canonical Stacks tip, even
+/// while mempool admission is paused.  Imported transactions are not relayed, since peers already
+/// saw them before they were drained.
+#[derive(Clone, Default)]
+pub struct RPCPostMempoolImportRequestHandler {
+    pub file: Option<String>,
+    pub auth: AdminAuth,
+}
+
+impl RPCPostMempoolImportRequestHandler {
+    pub fn new(auth: AdminAuth) -> Self {
+        Self { file: None, auth }
+    }
+
+    /// Drained transaction files are always read from the mempool drain directory, so the file
+    /// name cannot name a path.
+    fn is_valid_file_name(file: &str) -> bool {
+        !file.is_empty() && !file.starts_with('.') && !file.contains(['/', '\\'])
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPostMempoolImportRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        self.auth.authorize(preamble, AdminRole::Mempool)?;
+
+        if preamble.get_content_length() == 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected non-empty body".to_string(),
+            ));
+        }
+        if preamble.content_type != Some(HttpContentType::JSON) {
+            return Err(Error::DecodeError(
+                "Invalid content-type: expected application/json".to_string(),
+            ));
+        }
+
+        let body: MempoolImportRequest = serde_json::from_slice(body)
+            .map_err(|e| Error::DecodeError(format!("Failed to parse JSON body: {e}")))?;
+        if !Self::is_valid_file_name(&body.file) {
+            return Err(Error::DecodeError(format!(
+                "Invalid file name '{}': expected a file in the mempool drain directory",
+                &body.file
+            )));
+        }
+
+        self.file = Some(body.file);
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCPostMempoolImportRequestHandler {
+    /// Importing transactions requires an admin token
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::Admin
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.file = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let file = self
+            .file
+            .take()
+            .ok_or(NetError::SendError("`file` not set".into()))?;
+
+        let import_res = node.with_node_state(|_network, sortdb, chainstate, mempool, rpc_args| {
+            let path = mempool.drain_dir().join(&file);
+            let txs = match MemPoolDB::load_drained_txs(&path) {
+                Ok(txs) => txs,
+                Err(db_error::IOError(e)) if e.kind() == ErrorKind::NotFound => {
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpNotFound::new(format!("No such drained mempool file: {file}")),
+                    ));
+                }
+                Err(e) => {
+                    let msg = format!("Failed to load drained transactions from {file}: {e:?}");
+                    warn!("{msg}");
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpServerError::new(msg),
+                    ));
+                }
+            };
+
+            let burn_tip = self.get_canonical_burn_chain_tip(&preamble, sortdb)?;
+            let stacks_epoch = self.get_stacks_epoch(&preamble, sortdb, burn_tip.block_height)?;
+            let stacks_tip = self.get_stacks_chain_tip(&preamble, sortdb, chainstate)?;
+            let event_observer = rpc_args.event_observer.as_deref();
+
+            let mut response = MempoolImportResponse {
+                file: file.clone(),
+                imported: 0,
+                already_present: 0,
+                rejected: 0,
+            };
+            for tx in txs.iter() {
+                let txid = tx.txid();
+                if mempool.has_tx(&txid) {
+                    response.already_present += 1;
+                    continue;
+                }
+                match mempool.submit(
+                    chainstate,
+                    sortdb,
+                    &stacks_tip.consensus_hash,
+                    &stacks_tip.anchored_header.block_hash(),
+                    tx,
+                    event_observer,
+                    &stacks_epoch.block_limit,
+                    &stacks_epoch.epoch_id,
+                ) {
+                    Ok(()) => response.imported += 1,
+                    Err(e) => {
+                        info!(
+                            "Drained transaction rejected from mempool, {}",
+                            &e.into_json(&txid)
+                        );
+                        response.rejected += 1;
+                    }
+                }
+            }
+            Ok(response)
+        });
+
+        let response = match import_res {
+            Ok(response) => response,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+        info!("Imported drained mempool transactions";
+              "file" => &response.file,
+              "imported" => response.imported,
+              "already_present" => response.already_present,
+              "rejected" => response.rejected);
+
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&response)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPostMempoolImportRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let response: MempoolImportResponse = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(response)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to re-admit the transactions drained to `file`
+    pub fn new_post_mempool_import(host: PeerHost, file: &str, auth: &str) -> StacksHttpRequest {
+        let mut request = StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            PATH.into(),
+            HttpRequestContents::new().payload_json(
+                serde_json::to_value(MempoolImportRequest {
+                    file: file.to_string(),
+                })
+                .expect("FATAL: failed to encode import request to JSON"),
+            ),
+        )
+        .expect("FATAL: failed to construct request from infallible data");
+        request.add_header("authorization".into(), auth.into());
+        request
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_mempool_import(self) -> Result<MempoolImportResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let response: MempoolImportResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(response)
+    }
+}
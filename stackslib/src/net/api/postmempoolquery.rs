@@ -39,7 +39,7 @@ use crate::net::http::{
     HttpResponsePayload, HttpResponsePreamble, HttpServerError,
 };
 use crate::net::httpcore::{
-    HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+    EndpointClass, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
     StacksHttpResponse,
 };
 use crate::net::{Error as NetError, StacksNodeState, TipRequest, MAX_HEADERS};
@@ -256,6 +256,11 @@ impl HttpRequest for RPCMempoolQueryRequestHandler {
 }
 
 impl RPCRequestHandler for RPCMempoolQueryRequestHandler {
+    /// Mempool queries stream back many transactions
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
     /// Reset internal state
     fn restart(&mut self) {
         self.mempool_query = None;
@@ -0,0 +1,189 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::SocketAddr;
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::{
+    parse_json, Error, HttpContentType, HttpNotFound, HttpRequest, HttpRequestContents,
+    HttpRequestPreamble, HttpResponse, HttpResponseContents, HttpResponsePayload,
+    HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    AdminAuth, AdminRole, EndpointClass, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+pub const PATH: &str = "/v3/admin/peers/ban";
+
+/// The body of POST /v3/admin/peers/ban
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BanPeerRequest {
+    /// The neighbor's address, as `ip:port`
+    pub address: SocketAddr,
+}
+
+/// The response to POST /v3/admin/peers/ban
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BanPeerResponse {
+    pub address: SocketAddr,
+    /// The connected neighbors that will be banned
+    pub banned: Vec<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct RPCPostPeerBanRequestHandler {
+    pub address: Option<SocketAddr>,
+    pub auth: AdminAuth,
+}
+
+impl RPCPostPeerBanRequestHandler {
+    pub fn new(auth: AdminAuth) -> Self {
+        Self {
+            address: None,
+            auth,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPostPeerBanRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        self.auth.authorize(preamble, AdminRole::Peers)?;
+
+        if preamble.get_content_length() == 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected non-empty body".to_string(),
+            ));
+        }
+        if preamble.content_type != Some(HttpContentType::JSON) {
+            return Err(Error::DecodeError(
+                "Invalid content-type: expected application/json".to_string(),
+            ));
+        }
+
+        let body: BanPeerRequest = serde_json::from_slice(body)
+            .map_err(|e| Error::DecodeError(format!("Failed to parse JSON body: {e}")))?;
+
+        self.address = Some(body.address);
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCPostPeerBanRequestHandler {
+    /// Banning peers requires an admin token
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::Admin
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.address = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let address = self
+            .address
+            .take()
+            .ok_or(NetError::SendError("`address` not set".into()))?;
+
+        let banned = node.with_node_state(|network, _sortdb, _chainstate, _mempool, _rpc_args| {
+            network.ban_neighbors_at(&address)
+        });
+        if banned.is_empty() {
+            return StacksHttpResponse::new_error(
+                &preamble,
+                &HttpNotFound::new(format!("No neighbor is connected from {address}")),
+            )
+            .try_into_contents()
+            .map_err(NetError::from);
+        }
+
+        let response = BanPeerResponse {
+            address,
+            banned: banned.iter().map(|nk| nk.to_string()).collect(),
+        };
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&response)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPostPeerBanRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let response: BanPeerResponse = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(response)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to ban the neighbors connected from `address`
+    pub fn new_post_peer_ban(host: PeerHost, address: SocketAddr, auth: &str) -> StacksHttpRequest {
+        let mut request = StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            PATH.into(),
+            HttpRequestContents::new().payload_json(
+                serde_json::to_value(BanPeerRequest { address })
+                    .expect("FATAL: failed to encode ban request to JSON"),
+            ),
+        )
+        .expect("FATAL: failed to construct request from infallible data");
+        request.add_header("authorization".into(), auth.into());
+        request
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_peer_ban(self) -> Result<BanPeerResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let response: BanPeerResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,290 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::codec::MAX_PAYLOAD_LEN;
+use stacks_common::types::net::PeerHost;
+
+use crate::burnchains::db::{
+    AnchorBlockOverride, AnchorBlockOverrideSource, AnchorBlockOverrideStatus, BurnchainDB,
+};
+use crate::net::http::{
+    parse_json, Error, HttpContentType, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    AdminAuth, AdminRole, EndpointClass, HttpPreambleExtensions, RPCRequestHandler,
+    StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+/// What to do with a reward cycle's anchor block status
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnchorBlockOverrideAction {
+    /// Treat the anchor block as present
+    Accept,
+    /// Treat the anchor block as absent
+    Reject,
+    /// Go back to what the network affirms
+    Clear,
+}
+
+impl AnchorBlockOverrideAction {
+    /// The status to force, or None to clear the override
+    pub fn status(&self) -> Option<AnchorBlockOverrideStatus> {
+        match self {
+            Self::Accept => Some(AnchorBlockOverrideStatus::Accept),
+            Self::Reject => Some(AnchorBlockOverrideStatus::Reject),
+            Self::Clear => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnchorBlockOverrideRequestBody {
+    pub status: AnchorBlockOverrideAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnchorBlockOverrideResponse {
+    pub reward_cycle: u64,
+    /// The override that was replaced or cleared, if any
+    pub prior: Option<AnchorBlockOverride>,
+    /// All overrides now in effect
+    pub overrides: Vec<AnchorBlockOverride>,
+}
+
+#[derive(Clone, Default)]
+pub struct RPCPostPoxAnchorOverrideRequestHandler {
+    pub auth: AdminAuth,
+    pub reward_cycle: Option<u64>,
+    pub action: Option<AnchorBlockOverrideAction>,
+}
+
+impl RPCPostPoxAnchorOverrideRequestHandler {
+    pub fn new(auth: AdminAuth) -> Self {
+        Self {
+            auth,
+            reward_cycle: None,
+            action: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPostPoxAnchorOverrideRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/admin/pox/anchor_blocks/(?P<reward_cycle>[0-9]{1,20})/override$"#)
+            .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/admin/pox/anchor_blocks/:reward_cycle/override"
+    }
+
+    /// Try to decode this request.
+    /// The override is only validated here; it is stored when the request is handled.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        self.auth.authorize(preamble, AdminRole::Chainstate)?;
+
+        let content_len = preamble.get_content_length();
+        if !(content_len > 0 && content_len < MAX_PAYLOAD_LEN) {
+            return Err(Error::DecodeError(format!(
+                "Invalid Http request: invalid body length for anchor block override ({content_len})"
+            )));
+        }
+        if preamble.content_type != Some(HttpContentType::JSON) {
+            return Err(Error::DecodeError(
+                "Invalid content-type: expected application/json".to_string(),
+            ));
+        }
+
+        let reward_cycle = captures
+            .name("reward_cycle")
+            .ok_or_else(|| Error::DecodeError("Failed to match path to reward cycle".into()))?
+            .as_str()
+            .parse::<u64>()
+            .map_err(|_| Error::DecodeError("Invalid path: unparseable reward cycle".into()))?;
+        if reward_cycle == 0 {
+            return Err(Error::DecodeError(
+                "Invalid path: reward cycle 0 has no anchor block".into(),
+            ));
+        }
+
+        let body: AnchorBlockOverrideRequestBody = serde_json::from_slice(body)
+            .map_err(|e| Error::DecodeError(format!("Failed to parse JSON body: {e}")))?;
+
+        self.reward_cycle = Some(reward_cycle);
+        self.action = Some(body.status);
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCPostPoxAnchorOverrideRequestHandler {
+    /// Overriding anchor block statuses requires an admin token
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::Admin
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.reward_cycle = None;
+        self.action = None;
+    }
+
+    /// Make the response.
+    /// The override is stored in the burnchain DB, and the chains coordinator is woken up to
+    /// re-evaluate the affected reward cycles.
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let reward_cycle = self
+            .reward_cycle
+            .take()
+            .ok_or(NetError::SendError("`reward_cycle` not set".into()))?;
+        let action = self
+            .action
+            .take()
+            .ok_or(NetError::SendError("`action` not set".into()))?;
+
+        let override_resp =
+            node.with_node_state(|network, _sortdb, _chainstate, _mempool, rpc_args| {
+                let server_error = |msg: String| {
+                    error!("{msg}");
+                    StacksHttpResponse::new_error(&preamble, &HttpServerError::new(msg))
+                };
+
+                // the p2p thread's burnchain DB handle is read-only
+                let mut burnchain_db =
+                    BurnchainDB::open(&network.burnchain.get_burnchaindb_path(), true)
+                        .map_err(|e| server_error(format!("Failed to open burnchain DB: {e:?}")))?;
+                let tx = burnchain_db
+                    .tx_begin()
+                    .map_err(|e| server_error(format!("Failed to begin burnchain DB tx: {e:?}")))?;
+                let prior = match action.status() {
+                    Some(status) => {
+                        let prior = BurnchainDB::get_anchor_block_override(tx.conn(), reward_cycle)
+                            .map_err(|e| {
+                                server_error(format!("Failed to load anchor block override: {e:?}"))
+                            })?;
+                        tx.set_anchor_block_override(
+                            reward_cycle,
+                            status,
+                            AnchorBlockOverrideSource::Rpc,
+                        )
+                        .map_err(|e| {
+                            server_error(format!("Failed to store anchor block override: {e:?}"))
+                        })?;
+                        prior
+                    }
+                    None => tx.clear_anchor_block_override(reward_cycle).map_err(|e| {
+                        server_error(format!("Failed to clear anchor block override: {e:?}"))
+                    })?,
+                };
+                tx.commit().map_err(|e| {
+                    server_error(format!("Failed to commit burnchain DB tx: {e:?}"))
+                })?;
+                let overrides = BurnchainDB::get_anchor_block_overrides(burnchain_db.conn())
+                    .map_err(|e| {
+                        server_error(format!("Failed to load anchor block overrides: {e:?}"))
+                    })?;
+
+                // have the coordinator re-evaluate the burnchain with the new override
+                if let Some(coord_comms) = rpc_args.coord_comms {
+                    coord_comms.announce_new_burn_block();
+                }
+                Ok(AnchorBlockOverrideResponse {
+                    reward_cycle,
+                    prior,
+                    overrides,
+                })
+            });
+
+        let resp = match override_resp {
+            Ok(resp) => resp,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+        warn!("Operator overrode PoX anchor block status via RPC";
+              "reward_cycle" => reward_cycle,
+              "action" => ?action,
+              "prior_status" => resp.prior.as_ref().map(|o| o.status.as_str()));
+
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPostPoxAnchorOverrideRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let resp: AnchorBlockOverrideResponse = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(resp)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to override (or clear the override of) the status of the PoX anchor
+    /// block chosen for `reward_cycle`
+    pub fn new_post_pox_anchor_override(
+        host: PeerHost,
+        reward_cycle: u64,
+        action: AnchorBlockOverrideAction,
+        auth: &str,
+    ) -> StacksHttpRequest {
+        let mut request = StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            format!("/v3/admin/pox/anchor_blocks/{reward_cycle}/override"),
+            HttpRequestContents::new().payload_json(
+                serde_json::to_value(AnchorBlockOverrideRequestBody { status: action })
+                    .expect("FATAL: failed to encode infallible data"),
+            ),
+        )
+        .expect("FATAL: failed to construct request from infallible data");
+        request.add_header("authorization".into(), auth.into());
+        request
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_pox_anchor_override(self) -> Result<AnchorBlockOverrideResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: AnchorBlockOverrideResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
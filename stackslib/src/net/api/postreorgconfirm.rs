@@ -0,0 +1,186 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::net::PeerHost;
+
+use crate::chainstate::coordinator::comm::PendingStacksReorg;
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpNotFound, HttpRequest, HttpRequestContents,
+    HttpRequestPreamble, HttpResponse, HttpResponseContents, HttpResponsePayload,
+    HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    request, AdminAuth, AdminRole, EndpointClass, RPCRequestHandler, StacksHttpRequest,
+    StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+#[derive(Clone, Default)]
+pub struct RPCPostReorgConfirmRequestHandler {
+    pub auth: AdminAuth,
+    pub block_id: Option<StacksBlockId>,
+}
+
+impl RPCPostReorgConfirmRequestHandler {
+    pub fn new(auth: AdminAuth) -> Self {
+        Self {
+            auth,
+            block_id: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPostReorgConfirmRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/admin/reorg/confirm/(?P<block_id>[0-9a-f]{64})$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/admin/reorg/confirm/:block_id"
+    }
+
+    /// Try to decode this request.
+    /// There is nothing to decode beyond the authorization header and the new tip's block ID.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        self.auth.authorize(preamble, AdminRole::Chainstate)?;
+
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let block_id = request::get_block_hash(captures, "block_id")?;
+        self.block_id = Some(block_id);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCPostReorgConfirmRequestHandler {
+    /// Confirming a reorg requires an admin token
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::Admin
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.block_id = None;
+    }
+
+    /// Make the response.
+    /// The coordinator switches to the new tip asynchronously; the response is the reorg that was
+    /// confirmed.
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let block_id = self
+            .block_id
+            .take()
+            .ok_or(NetError::SendError("`block_id` not set".into()))?;
+
+        let confirm_resp =
+            node.with_node_state(|_network, _sortdb, _chainstate, _mempool, rpc_args| {
+                let Some(coord_comms) = rpc_args.coord_comms else {
+                    debug!("No chains coordinator reachable from this stacks node's RPC server");
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpBadRequest::new(
+                            "Reorg confirmation is not available on this node".to_string(),
+                        ),
+                    ));
+                };
+                coord_comms.confirm_reorg(&block_id).ok_or_else(|| {
+                    StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpNotFound::new(format!("No pending reorg to {block_id}")),
+                    )
+                })
+            });
+
+        let reorg = match confirm_resp {
+            Ok(reorg) => reorg,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+        info!("Operator confirmed deep Stacks reorg";
+              "old_tip" => %reorg.old_tip,
+              "new_tip" => %reorg.new_tip,
+              "depth" => reorg.depth,
+              "max_depth" => reorg.max_depth);
+
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&reorg)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPostReorgConfirmRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let reorg: PendingStacksReorg = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(reorg)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to confirm a pending deep reorg to `block_id`
+    pub fn new_post_reorg_confirm(
+        host: PeerHost,
+        block_id: &StacksBlockId,
+        auth: &str,
+    ) -> StacksHttpRequest {
+        let mut request = StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            format!("/v3/admin/reorg/confirm/{block_id}"),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data");
+        request.add_header("authorization".into(), auth.into());
+        request
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_confirmed_reorg(self) -> Result<PendingStacksReorg, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let reorg: PendingStacksReorg = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(reorg)
+    }
+}
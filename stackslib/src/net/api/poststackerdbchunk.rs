@@ -185,7 +185,7 @@ impl RPCRequestHandler for RPCPostStackerDBChunkRequestHandler {
             .ok_or(NetError::SendError("`chunk` not set".into()))?;
 
         let ack_resp =
-            node.with_node_state(|network, _sortdb, _chainstate, _mempool, _rpc_args| {
+            node.with_node_state(|network, _sortdb, _chainstate, _mempool, handler_args| {
                 let tx = if let Ok(tx) = network.stackerdbs_tx_begin(&contract_identifier) {
                     tx
                 } else {
@@ -267,6 +267,22 @@ impl RPCRequestHandler for RPCPostStackerDBChunkRequestHandler {
                     ));
                 };
 
+                // queue the chunk for the standby nodes before it is committed, so that every
+                // acknowledged write is replicated.  If the queue is unavailable, the write is
+                // refused and rolled back.
+                if let Some(replication) = handler_args.stackerdb_replication {
+                    if let Err(e) =
+                        replication.queue_stackerdb_chunk(&contract_identifier, &stackerdb_chunk)
+                    {
+                        return Err(StacksHttpResponse::new_error(
+                            &preamble,
+                            &HttpServerError::new(format!(
+                                "Failed to queue StackerDB chunk for replication: {e}"
+                            )),
+                        ));
+                    }
+                }
+
                 if let Err(e) = tx.commit() {
                     return Err(StacksHttpResponse::new_error(
                         &preamble,
@@ -0,0 +1,146 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::representations::{CONTRACT_NAME_REGEX_STRING, STANDARD_PRINCIPAL_REGEX_STRING};
+use clarity::vm::types::QualifiedContractIdentifier;
+use libstackerdb::{StackerDBChunkAckData, StackerDBChunkData};
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::net::api::poststackerdbchunk::RPCPostStackerDBChunkRequestHandler;
+use crate::net::http::{
+    parse_json, Error, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    AdminAuth, AdminRole, EndpointClass, RPCRequestHandler, StacksHttpRequest,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+/// Stores a StackerDB chunk replicated from a primary node.  This is the same as
+/// POST /v2/stackerdb/:principal/:contract_name/chunks, except that it requires an admin token
+/// with the `stackerdb` role, so it is rate-limited as an operator endpoint instead of competing
+/// with public writes.
+#[derive(Clone)]
+pub struct RPCPostStackerDBReplicaRequestHandler {
+    pub auth: AdminAuth,
+    pub chunk_handler: RPCPostStackerDBChunkRequestHandler,
+}
+
+impl RPCPostStackerDBReplicaRequestHandler {
+    pub fn new(auth: AdminAuth) -> Self {
+        Self {
+            auth,
+            chunk_handler: RPCPostStackerDBChunkRequestHandler::new(),
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPostStackerDBReplicaRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!(
+            r#"^/v3/admin/stackerdb/(?P<address>{})/(?P<contract>{})/chunks$"#,
+            *STANDARD_PRINCIPAL_REGEX_STRING, *CONTRACT_NAME_REGEX_STRING
+        ))
+        .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/admin/stackerdb/:principal/:contract_name/chunks"
+    }
+
+    /// Try to decode this request.
+    /// Check the authorization header, and then decode the chunk.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        self.auth.authorize(preamble, AdminRole::StackerDB)?;
+
+        self.chunk_handler
+            .try_parse_request(preamble, captures, query, body)
+    }
+}
+
+impl RPCRequestHandler for RPCPostStackerDBReplicaRequestHandler {
+    /// Replicating chunks requires an admin token
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::Admin
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.chunk_handler.restart();
+    }
+
+    /// Make the response.
+    /// The chunk is stored and relayed exactly as if it had been posted to the public endpoint.
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        self.chunk_handler
+            .try_handle_request(preamble, contents, node)
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPostStackerDBReplicaRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let ack: StackerDBChunkAckData = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(ack)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to store a replicated StackerDB chunk on a standby node.
+    /// Decode the response with `decode_stackerdb_chunk_ack()`.
+    pub fn new_post_stackerdb_replica(
+        host: PeerHost,
+        stackerdb_contract_id: &QualifiedContractIdentifier,
+        chunk: &StackerDBChunkData,
+        auth: &str,
+    ) -> StacksHttpRequest {
+        let mut request = StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            format!(
+                "/v3/admin/stackerdb/{}/{}/chunks",
+                &stackerdb_contract_id.issuer, &stackerdb_contract_id.name
+            ),
+            HttpRequestContents::new().payload_json(
+                serde_json::to_value(chunk)
+                    .expect("FATAL: failed to construct JSON from infallible structure"),
+            ),
+        )
+        .expect("FATAL: failed to construct request from infallible data");
+        request.add_header("authorization".into(), auth.into());
+        request
+    }
+}
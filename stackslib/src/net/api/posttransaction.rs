@@ -38,7 +38,8 @@ use crate::cost_estimates::FeeRateEstimate;
 use crate::net::http::{
     parse_json, Error, HttpBadRequest, HttpContentType, HttpNotFound, HttpRequest,
     HttpRequestContents, HttpRequestPreamble, HttpResponse, HttpResponseContents,
-    HttpResponsePayload, HttpResponsePreamble, HttpServerError, HttpVersion,
+    HttpResponsePayload, HttpResponsePreamble, HttpServerError, HttpServiceUnavailable,
+    HttpVersion,
 };
 use crate::net::httpcore::{
     HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
@@ -198,6 +199,16 @@ impl RPCRequestHandler for RPCPostTransactionRequestHandler {
         let txid = tx.txid();
 
         let data_resp = node.with_node_state(|network, sortdb, chainstate, mempool, rpc_args| {
+            if mempool.is_admission_paused() {
+                debug!("Mempool admission is paused; refusing POSTed transaction {txid}");
+                return Err(StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpServiceUnavailable::new(
+                        "Mempool admission is paused for maintenance; try again later".to_string(),
+                    ),
+                ));
+            }
+
             if mempool.has_tx(&txid) {
                 // will not accept
                 debug!("Mempool already has POSTed transaction {}", &txid);
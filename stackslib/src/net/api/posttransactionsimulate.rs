@@ -0,0 +1,560 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use clarity::vm::ast::ASTRules;
+use clarity::vm::costs::ExecutionCost;
+use clarity::vm::events::{STXEventType, StacksTransactionEvent};
+use clarity::vm::types::PrincipalData;
+use regex::{Captures, Regex};
+use stacks_common::address::{
+    C32_ADDRESS_VERSION_MAINNET_SINGLESIG, C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+};
+use stacks_common::codec::StacksMessageCodec;
+use stacks_common::types::chainstate::{StacksAddress, StacksBlockId};
+use stacks_common::types::net::PeerHost;
+use stacks_common::types::Address;
+use stacks_common::util::hash::{hex_bytes, to_hex};
+use stacks_common::util::secp256k1::MessageSignature;
+
+use crate::burnchains::Txid;
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::nakamoto::miner::NakamotoBlockBuilder;
+use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::stacks::db::blocks::SetupBlockResult;
+use crate::chainstate::stacks::db::{
+    ClarityTx, StacksAccount, StacksBlockHeaderTypes, StacksChainState,
+};
+use crate::chainstate::stacks::events::StacksTransactionReceipt;
+use crate::chainstate::stacks::{
+    Error as ChainError, SinglesigHashMode, SinglesigSpendingCondition, StacksTransaction,
+    TransactionAuth, TransactionPublicKeyEncoding, TransactionSpendingCondition,
+    MAX_TRANSACTION_LEN,
+};
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpContentType, HttpNotFound, HttpRequest,
+    HttpRequestContents, HttpRequestPreamble, HttpResponse, HttpResponseContents,
+    HttpResponsePayload, HttpResponsePreamble, HttpServerError, HttpServiceUnavailable,
+};
+use crate::net::httpcore::{
+    AdminAuth, AdminRole, EndpointClass, HttpPreambleExtensions, HttpRequestContentsExtensions,
+    RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+use crate::util_lib::db::Error as db_error;
+
+pub static PATH: &str = "/v3/transactions/simulate";
+
+/// How long the RPC thread waits for a simulation to finish before giving up on it
+pub const SIMULATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionSimulateRequestBody {
+    /// Hex-encoded transaction
+    pub tx: String,
+    /// If given, the transaction is simulated as if this address signed it, and its signatures
+    /// are not checked
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+}
+
+/// An account's STX balance and nonce
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedAccountState {
+    pub balance: u128,
+    pub locked: u128,
+    pub unlock_height: u64,
+    pub nonce: u64,
+}
+
+impl From<&StacksAccount> for SimulatedAccountState {
+    fn from(account: &StacksAccount) -> Self {
+        Self {
+            balance: account.stx_balance.amount_unlocked(),
+            locked: account.stx_balance.amount_locked(),
+            unlock_height: account.stx_balance.unlock_height(),
+            nonce: account.nonce,
+        }
+    }
+}
+
+/// How the transaction would change an account it touches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedAccountDiff {
+    pub principal: String,
+    pub before: SimulatedAccountState,
+    pub after: SimulatedAccountState,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionSimulateResponse {
+    pub txid: Txid,
+    /// Index block hash of the block the transaction was simulated on top of
+    pub index_block_hash: StacksBlockId,
+    /// Hex-encoded Clarity value the transaction evaluated to
+    pub result: String,
+    pub vm_error: Option<String>,
+    pub post_condition_aborted: bool,
+    pub fee: u64,
+    pub stx_burned: u128,
+    pub execution_cost: ExecutionCost,
+    /// Events in the same format event observers receive them
+    pub events: Vec<serde_json::Value>,
+    /// STX balances and nonces of the origin, the sponsor, and every account in an STX event
+    pub account_diffs: Vec<SimulatedAccountDiff>,
+}
+
+/// Make `tx` spend from `sender` instead of whatever its origin spending condition says.
+/// The nonce and fee are kept; the signature is cleared, since it will not be checked.
+pub fn set_simulated_sender(tx: &mut StacksTransaction, sender: &StacksAddress) {
+    let origin = match &mut tx.auth {
+        TransactionAuth::Standard(origin) => origin,
+        TransactionAuth::Sponsored(origin, _) => origin,
+    };
+    let hash_mode = match sender.version() {
+        C32_ADDRESS_VERSION_MAINNET_SINGLESIG | C32_ADDRESS_VERSION_TESTNET_SINGLESIG => {
+            SinglesigHashMode::P2PKH
+        }
+        _ => SinglesigHashMode::P2WPKH,
+    };
+    *origin = TransactionSpendingCondition::Singlesig(SinglesigSpendingCondition {
+        hash_mode,
+        signer: sender.bytes().clone(),
+        nonce: origin.nonce(),
+        tx_fee: origin.tx_fee(),
+        key_encoding: TransactionPublicKeyEncoding::Compressed,
+        signature: MessageSignature::empty(),
+    });
+}
+
+/// Process `tx` in the freshly-opened block `clarity_tx`, and then roll the block back.
+/// Returns the fee, the receipt, and the state of each of `principals` just after the
+/// transaction.  Every account in an STX event is added to `principals`.
+fn simulate_in_block(
+    mut clarity_tx: ClarityTx,
+    tx: &StacksTransaction,
+    check_signatures: bool,
+    ast_rules: ASTRules,
+    principals: &mut Vec<PrincipalData>,
+) -> Result<(u64, StacksTransactionReceipt, Vec<StacksAccount>), ChainError> {
+    let process_result = if check_signatures {
+        StacksChainState::process_transaction(&mut clarity_tx, tx, true, ast_rules)
+    } else {
+        StacksChainState::process_unsigned_transaction(&mut clarity_tx, tx, ast_rules)
+    };
+    let (fee, receipt) = match process_result {
+        Ok(x) => x,
+        Err(e) => {
+            clarity_tx.rollback_block();
+            return Err(e);
+        }
+    };
+
+    for event in receipt.events.iter() {
+        let StacksTransactionEvent::STXEvent(stx_event) = event else {
+            continue;
+        };
+        match stx_event {
+            STXEventType::STXTransferEvent(data) => {
+                principals.push(data.sender.clone());
+                principals.push(data.recipient.clone());
+            }
+            STXEventType::STXMintEvent(data) => principals.push(data.recipient.clone()),
+            STXEventType::STXBurnEvent(data) => principals.push(data.sender.clone()),
+            STXEventType::STXLockEvent(data) => principals.push(data.locked_address.clone()),
+        }
+    }
+    let mut seen = HashSet::new();
+    principals.retain(|principal| seen.insert(principal.clone()));
+
+    let after = principals
+        .iter()
+        .map(|principal| StacksChainState::get_account(&mut clarity_tx, principal))
+        .collect();
+    clarity_tx.rollback_block();
+    Ok((fee, receipt, after))
+}
+
+/// Process `tx` on top of `tip` in a block that is rolled back afterwards, and report what it
+/// would have done.  If `check_signatures` is false, the transaction's signatures are not
+/// verified.
+///
+/// The block is opened the way a miner building on `tip` would open it, so the transaction
+/// sees whatever epoch transition, PoX unlocks, and tenure bookkeeping would precede it.
+/// Nothing is ever committed, but the chainstate is written to while the block is open, so
+/// this should run on its own chainstate and sortition DB connections (see
+/// `spawn_simulation()`).
+pub fn simulate_transaction(
+    chainstate: &mut StacksChainState,
+    sortdb: &SortitionDB,
+    tip: &StacksBlockId,
+    tx: &StacksTransaction,
+    check_signatures: bool,
+) -> Result<TransactionSimulateResponse, ChainError> {
+    let header = NakamotoChainState::get_block_header(chainstate.db(), tip)?
+        .ok_or(ChainError::NoSuchBlockError)?;
+    let ast_rules = SortitionDB::get_ast_rules(sortdb.conn(), header.burn_header_height.into())?;
+    let burn_dbconn = sortdb.index_handle_at_block(chainstate, tip)?;
+    let mainnet = chainstate.mainnet;
+
+    let mut principals: Vec<PrincipalData> = vec![tx.origin_address().into()];
+    principals.extend(tx.sponsor_address().map(PrincipalData::from));
+
+    let (fee, receipt, after) = match &header.anchored_header {
+        StacksBlockHeaderTypes::Nakamoto(parent_header) => {
+            // build a block in the tip's tenure, whose block-commit already accepted the tip's
+            // PoX treatment
+            let mut builder = NakamotoBlockBuilder::new(
+                &header,
+                &header.consensus_hash,
+                parent_header.burn_spent,
+                None,
+                None,
+                parent_header.pox_treatment.len(),
+                None,
+            )?;
+            builder.header.pox_treatment = parent_header.pox_treatment.clone();
+            let mut tenure_info = builder.load_tenure_info(chainstate, &burn_dbconn, None)?;
+            let clarity_tx = builder.tenure_begin(&burn_dbconn, &mut tenure_info)?;
+            simulate_in_block(clarity_tx, tx, check_signatures, ast_rules, &mut principals)?
+        }
+        StacksBlockHeaderTypes::Epoch2(_) => {
+            let burn_tip = SortitionDB::get_canonical_chain_tip_bhh(burn_dbconn.conn())?;
+            let burn_tip_height = u32::try_from(
+                SortitionDB::get_canonical_burn_chain_tip(burn_dbconn.conn())?.block_height,
+            )
+            .expect("FATAL: more than 2^32 sortitions");
+            let (mut chainstate_tx, clarity_instance) = chainstate.chainstate_tx_begin()?;
+            let SetupBlockResult { clarity_tx, .. } = StacksChainState::setup_block(
+                &mut chainstate_tx,
+                clarity_instance,
+                &burn_dbconn,
+                &burn_dbconn,
+                burn_dbconn.conn(),
+                &burn_dbconn.context.pox_constants,
+                &header,
+                burn_tip,
+                burn_tip_height + 1,
+                header.consensus_hash.clone(),
+                header.anchored_header.block_hash(),
+                &[],
+                mainnet,
+                None,
+            )?;
+            simulate_in_block(clarity_tx, tx, check_signatures, ast_rules, &mut principals)?
+        }
+    };
+
+    let before = chainstate
+        .with_read_only_clarity_tx(&burn_dbconn, tip, |clarity_conn| {
+            principals
+                .iter()
+                .map(|principal| StacksChainState::get_account(clarity_conn, principal))
+                .collect::<Vec<_>>()
+        })
+        .ok_or(ChainError::NoSuchBlockError)?;
+
+    let txid = tx.txid();
+    let committed = !receipt.post_condition_aborted;
+    let events = receipt
+        .events
+        .iter()
+        .enumerate()
+        .map(|(event_index, event)| event.json_serialize(event_index, &txid, committed))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| db_error::Other(format!("Failed to serialize event: {e:?}")))?;
+    let result = receipt
+        .result
+        .serialize_to_vec()
+        .map_err(|e| db_error::Other(format!("Failed to serialize result: {e:?}")))?;
+    let account_diffs = before
+        .iter()
+        .zip(after.iter())
+        .map(|(before, after)| SimulatedAccountDiff {
+            principal: before.principal.to_string(),
+            before: before.into(),
+            after: after.into(),
+        })
+        .collect();
+
+    Ok(TransactionSimulateResponse {
+        txid,
+        index_block_hash: tip.clone(),
+        result: format!("0x{}", to_hex(&result)),
+        vm_error: receipt.vm_error,
+        post_condition_aborted: receipt.post_condition_aborted,
+        fee,
+        stx_burned: receipt.stx_burned,
+        execution_cost: receipt.execution_cost,
+        events,
+        account_diffs,
+    })
+}
+
+/// Run `simulate_transaction()` on a worker thread with its own chainstate and sortition DB
+/// connections, so that a simulation which waits on the chainstate's write lock, or which
+/// fails outright, cannot stall or take down the RPC thread.
+/// Returns a receiver for the simulation's result.
+pub fn spawn_simulation(
+    chainstate: &StacksChainState,
+    sortdb: &SortitionDB,
+    tip: StacksBlockId,
+    tx: StacksTransaction,
+    check_signatures: bool,
+) -> Result<Receiver<Result<TransactionSimulateResponse, ChainError>>, ChainError> {
+    let (mut chainstate, _) = chainstate.reopen()?;
+    let sortdb = sortdb.reopen()?;
+    let (sender, receiver) = mpsc::sync_channel(1);
+    thread::Builder::new()
+        .name("tx-simulate".into())
+        .spawn(move || {
+            let result =
+                simulate_transaction(&mut chainstate, &sortdb, &tip, &tx, check_signatures);
+            // the RPC thread may have stopped waiting
+            let _ = sender.send(result);
+        })
+        .map_err(|e| db_error::Other(format!("Failed to spawn simulation thread: {e}")))?;
+    Ok(receiver)
+}
+
+#[derive(Clone, Default)]
+pub struct RPCPostTransactionSimulateRequestHandler {
+    pub auth: AdminAuth,
+    pub tx: Option<StacksTransaction>,
+    pub check_signatures: bool,
+}
+
+impl RPCPostTransactionSimulateRequestHandler {
+    pub fn new(auth: AdminAuth) -> Self {
+        Self {
+            auth,
+            tx: None,
+            check_signatures: true,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPostTransactionSimulateRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!("^{PATH}$")).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        PATH
+    }
+
+    /// Try to decode this request.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        self.auth.authorize(preamble, AdminRole::Simulation)?;
+
+        // the transaction is hex-encoded
+        let content_len = preamble.get_content_length();
+        if !(content_len > 0 && content_len < 2 * MAX_TRANSACTION_LEN) {
+            return Err(Error::DecodeError(format!(
+                "Invalid Http request: invalid body length for TransactionSimulate ({content_len})"
+            )));
+        }
+        if preamble.content_type != Some(HttpContentType::JSON) {
+            return Err(Error::DecodeError(
+                "Invalid content-type: expected application/json".to_string(),
+            ));
+        }
+
+        let body: TransactionSimulateRequestBody = serde_json::from_slice(body)
+            .map_err(|_e| Error::DecodeError("Failed to parse JSON body".into()))?;
+        let tx_bytes =
+            hex_bytes(&body.tx).map_err(|_e| Error::DecodeError("Failed to parse tx".into()))?;
+        let mut tx = StacksTransaction::consensus_deserialize(&mut &tx_bytes[..])
+            .map_err(|e| Error::DecodeError(format!("Failed to deserialize tx: {e}")))?;
+
+        self.check_signatures = true;
+        if let Some(sender) = body.sender {
+            let sender = StacksAddress::from_string(&sender)
+                .ok_or_else(|| Error::DecodeError("Failed to parse `sender`".into()))?;
+            if sender.is_mainnet() != tx.is_mainnet() {
+                return Err(Error::DecodeError(
+                    "`sender` is not on the transaction's network".into(),
+                ));
+            }
+            set_simulated_sender(&mut tx, &sender);
+            self.check_signatures = false;
+        }
+        self.tx = Some(tx);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCPostTransactionSimulateRequestHandler {
+    /// Simulations execute arbitrary transactions, so they require an admin token
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::Admin
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.tx = None;
+        self.check_signatures = true;
+    }
+
+    /// Make the response.
+    /// Nothing is stored or relayed.  A transaction that could not be mined at this tip (e.g.
+    /// because of a bad nonce or an unaffordable fee) is a 400.
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let tx = self
+            .tx
+            .take()
+            .ok_or(NetError::SendError("`tx` not set".into()))?;
+        let check_signatures = self.check_signatures;
+
+        let receiver_res =
+            node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+                spawn_simulation(chainstate, sortdb, tip.clone(), tx, check_signatures)
+            });
+        let simulate_result = match receiver_res.map(|rx| rx.recv_timeout(SIMULATION_TIMEOUT)) {
+            Ok(Ok(simulate_result)) => simulate_result,
+            Ok(Err(RecvTimeoutError::Timeout)) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpServiceUnavailable::new(
+                        "Timed out waiting for the chainstate; try again later".into(),
+                    ),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Ok(Err(RecvTimeoutError::Disconnected)) => {
+                let msg = "Simulation thread exited without a result".to_string();
+                warn!("{msg}"; "tip" => %tip);
+                return StacksHttpResponse::new_error(&preamble, &HttpServerError::new(msg))
+                    .try_into_contents()
+                    .map_err(NetError::from);
+            }
+            Err(e) => Err(e),
+        };
+
+        let simulate_resp = match simulate_result {
+            Ok(simulate_resp) => simulate_resp,
+            Err(ChainError::NoSuchBlockError) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new(format!("Chain tip '{tip}' not found")),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Err(e @ (ChainError::DBError(_) | ChainError::MARFError(_))) => {
+                let msg = format!("Failed to simulate transaction: {e:?}");
+                warn!("{msg}");
+                return StacksHttpResponse::new_error(&preamble, &HttpServerError::new(msg))
+                    .try_into_contents()
+                    .map_err(NetError::from);
+            }
+            Err(e) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpBadRequest::new(format!("Transaction rejected: {e}")),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&simulate_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPostTransactionSimulateRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let simulate_resp: TransactionSimulateResponse = parse_json(preamble, body)?;
+        HttpResponsePayload::try_from_json(simulate_resp)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to simulate a transaction without broadcasting it.  If `sender` is
+    /// given, the transaction is simulated as if that address signed it.
+    pub fn new_simulate_transaction(
+        host: PeerHost,
+        tx: &StacksTransaction,
+        sender: Option<&StacksAddress>,
+        tip_req: TipRequest,
+        auth: &str,
+    ) -> StacksHttpRequest {
+        let body = TransactionSimulateRequestBody {
+            tx: to_hex(&tx.serialize_to_vec()),
+            sender: sender.map(|addr| addr.to_string()),
+        };
+        let mut request = StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            PATH.into(),
+            HttpRequestContents::new().for_tip(tip_req).payload_json(
+                serde_json::to_value(body).expect("FATAL: failed to encode infallible data"),
+            ),
+        )
+        .expect("FATAL: failed to construct request from infallible data");
+        request.add_header("authorization".into(), auth.into());
+        request
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_simulate_transaction_response(
+        self,
+    ) -> Result<TransactionSimulateResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: TransactionSimulateResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,444 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::clarity::ClarityConnection;
+use clarity::vm::costs::{ExecutionCost, LimitedCostTracker};
+use clarity::vm::types::{BuffData, OptionalData, PrincipalData, SequenceData};
+use clarity::vm::{ClarityVersion, SymbolicExpression, Value};
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::{StacksBlockId, StacksPublicKey};
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::hash::to_hex;
+use stacks_common::util::secp256k1::MessageSignature;
+
+use crate::chainstate::stacks::address::PoxAddress;
+use crate::chainstate::stacks::boot::POX_4_NAME;
+use crate::chainstate::stacks::Error as ChainError;
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpContentType, HttpNotFound, HttpRequest,
+    HttpRequestContents, HttpRequestPreamble, HttpResponse, HttpResponseContents,
+    HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    EndpointClass, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler,
+    StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+use crate::util_lib::boot::{boot_code_addr, boot_code_id};
+use crate::util_lib::signed_structured_data::pox4::{
+    make_pox_4_signer_key_message_hash, Pox4SignatureTopic,
+};
+
+/// Largest request body we will accept
+const MAX_VERIFY_SIGNER_SIGNATURE_BODY_LEN: u32 = 4096;
+
+/// A pox-4 signer key authorization to check.  uSTX amounts and the auth ID are decimal strings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerifySignerSignatureRequestBody {
+    /// PoX reward address, as a b58 or bech32 string
+    pub pox_address: String,
+    pub reward_cycle: u64,
+    /// One of `stack-stx`, `stack-extend`, `stack-increase`, `agg-commit`, or `agg-increase`
+    pub topic: String,
+    pub period: u64,
+    /// Hex-encoded compressed signer public key
+    pub signer_key: String,
+    /// Hex-encoded recoverable signature.  If omitted, checks for an authorization made with
+    /// `set-signer-key-authorization` instead.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signer_signature: Option<String>,
+    pub max_amount: String,
+    /// Amount the stacker intends to lock.  Defaults to `max_amount`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<String>,
+    pub auth_id: String,
+}
+
+/// A parsed pox-4 signer key authorization
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignerSignatureRequest {
+    pub pox_address: PoxAddress,
+    pub reward_cycle: u64,
+    pub topic: Pox4SignatureTopic,
+    pub period: u64,
+    pub signer_key: StacksPublicKey,
+    pub signer_signature: Option<MessageSignature>,
+    pub max_amount: u128,
+    pub amount: u128,
+    pub auth_id: u128,
+}
+
+impl TryFrom<VerifySignerSignatureRequestBody> for SignerSignatureRequest {
+    type Error = Error;
+
+    fn try_from(body: VerifySignerSignatureRequestBody) -> Result<Self, Self::Error> {
+        let pox_address = PoxAddress::from_b58(&body.pox_address)
+            .ok_or_else(|| Error::DecodeError("Failed to parse `pox_address`".into()))?;
+        let topic = Pox4SignatureTopic::lookup_by_name(&body.topic)
+            .ok_or_else(|| Error::DecodeError("Unknown signature `topic`".into()))?;
+        let signer_key = StacksPublicKey::from_hex(&body.signer_key)
+            .map_err(|_e| Error::DecodeError("Failed to parse `signer_key`".into()))?;
+        if !signer_key.compressed() {
+            return Err(Error::DecodeError(
+                "`signer_key` must be a compressed public key".into(),
+            ));
+        }
+        let signer_signature = body
+            .signer_signature
+            .map(|sig| MessageSignature::from_hex(&sig))
+            .transpose()
+            .map_err(|_e| Error::DecodeError("Failed to parse `signer_signature`".into()))?;
+        let max_amount = body
+            .max_amount
+            .parse()
+            .map_err(|_e| Error::DecodeError("Failed to parse `max_amount`".into()))?;
+        let amount = body
+            .amount
+            .map(|amount| amount.parse())
+            .transpose()
+            .map_err(|_e| Error::DecodeError("Failed to parse `amount`".into()))?
+            .unwrap_or(max_amount);
+        let auth_id = body
+            .auth_id
+            .parse()
+            .map_err(|_e| Error::DecodeError("Failed to parse `auth_id`".into()))?;
+        Ok(Self {
+            pox_address,
+            reward_cycle: body.reward_cycle,
+            topic,
+            period: body.period,
+            signer_key,
+            signer_signature,
+            max_amount,
+            amount,
+            auth_id,
+        })
+    }
+}
+
+impl From<SignerSignatureRequest> for VerifySignerSignatureRequestBody {
+    fn from(request: SignerSignatureRequest) -> Self {
+        Self {
+            pox_address: request.pox_address.to_b58(),
+            reward_cycle: request.reward_cycle,
+            topic: request.topic.get_name(),
+            period: request.period,
+            signer_key: to_hex(&request.signer_key.to_bytes_compressed()),
+            signer_signature: request.signer_signature.map(|sig| sig.to_hex()),
+            max_amount: request.max_amount.to_string(),
+            amount: Some(request.amount.to_string()),
+            auth_id: request.auth_id.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerifySignerSignatureResponse {
+    /// Whether pox-4 would accept this authorization at the queried tip
+    pub valid: bool,
+    /// pox-4 error code, if it would be rejected
+    pub error_code: Option<u128>,
+    /// Name of the pox-4 error, if it would be rejected
+    pub reason: Option<String>,
+    /// Hex-encoded message hash the signer key is expected to sign
+    pub message_hash: String,
+    /// Index block hash of the block the check ran against
+    pub index_block_hash: StacksBlockId,
+}
+
+/// Name of the pox-4 error codes that `verify-signer-key-sig` can return
+fn signer_key_sig_error_name(code: u128) -> Option<&'static str> {
+    match code {
+        19 => Some("ERR_NOT_ALLOWED"),
+        35 => Some("ERR_INVALID_SIGNATURE_PUBKEY"),
+        36 => Some("ERR_INVALID_SIGNATURE_RECOVER"),
+        38 => Some("ERR_SIGNER_AUTH_AMOUNT_TOO_HIGH"),
+        39 => Some("ERR_SIGNER_AUTH_USED"),
+        _ => None,
+    }
+}
+
+#[derive(Clone)]
+pub struct RPCVerifySignerSignatureRequestHandler {
+    read_only_call_limit: ExecutionCost,
+    pub request: Option<SignerSignatureRequest>,
+}
+
+impl RPCVerifySignerSignatureRequestHandler {
+    pub fn new(read_only_call_limit: ExecutionCost) -> Self {
+        Self {
+            read_only_call_limit,
+            request: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCVerifySignerSignatureRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/pox/verify-signer-signature$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/pox/verify-signer-signature"
+    }
+
+    /// Try to decode this request.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        let content_len = preamble.get_content_length();
+        if !(content_len > 0 && content_len < MAX_VERIFY_SIGNER_SIGNATURE_BODY_LEN) {
+            return Err(Error::DecodeError(format!(
+                "Invalid Http request: invalid body length for VerifySignerSignature ({})",
+                content_len
+            )));
+        }
+
+        if preamble.content_type != Some(HttpContentType::JSON) {
+            return Err(Error::DecodeError(
+                "Invalid content-type: expected application/json".to_string(),
+            ));
+        }
+
+        let body: VerifySignerSignatureRequestBody = serde_json::from_slice(body)
+            .map_err(|_e| Error::DecodeError("Failed to parse JSON body".into()))?;
+        self.request = Some(body.try_into()?);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCVerifySignerSignatureRequestHandler {
+    /// Verification executes Clarity code
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::ReadHeavy
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.request = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let request = self
+            .request
+            .take()
+            .ok_or(NetError::SendError("Missing `request`".into()))?;
+
+        let Some(pox_addr_tuple) = request.pox_address.as_clarity_tuple() else {
+            return StacksHttpResponse::new_error(
+                &preamble,
+                &HttpBadRequest::new("`pox_address` cannot be used with pox-4".to_string()),
+            )
+            .try_into_contents()
+            .map_err(NetError::from);
+        };
+
+        let (chain_id, data_resp) =
+            node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+                let mainnet = chainstate.mainnet;
+                let chain_id = chainstate.chain_id;
+                let pox_4_id = boot_code_id(POX_4_NAME, mainnet);
+                let mut cost_limit = self.read_only_call_limit.clone();
+                cost_limit.write_length = 0;
+                cost_limit.write_count = 0;
+
+                // mirror the arguments pox-4 passes to `verify-signer-key-sig`
+                let args: Vec<_> = [
+                    Value::Tuple(pox_addr_tuple),
+                    Value::UInt(request.reward_cycle.into()),
+                    Value::string_ascii_from_bytes(request.topic.get_name_str().into())
+                        .expect("FATAL: signature topics are ASCII"),
+                    Value::UInt(request.period.into()),
+                    Value::Optional(OptionalData {
+                        data: request.signer_signature.as_ref().map(|sig| {
+                            Box::new(Value::Sequence(SequenceData::Buffer(BuffData {
+                                data: sig.as_bytes().to_vec(),
+                            })))
+                        }),
+                    }),
+                    Value::Sequence(SequenceData::Buffer(BuffData {
+                        data: request.signer_key.to_bytes_compressed(),
+                    })),
+                    Value::UInt(request.amount),
+                    Value::UInt(request.max_amount),
+                    Value::UInt(request.auth_id),
+                ]
+                .into_iter()
+                .map(SymbolicExpression::atom_value)
+                .collect();
+
+                let result = sortdb
+                    .index_handle_at_block(chainstate, &tip)
+                    .map_err(ChainError::from)
+                    .and_then(|sort_handle| {
+                        chainstate.maybe_read_only_clarity_tx(&sort_handle, &tip, |clarity_tx| {
+                            let has_pox_4 = clarity_tx.with_clarity_db_readonly(|clarity_db| {
+                                clarity_db.has_contract(&pox_4_id)
+                            });
+                            if !has_pox_4 {
+                                return None;
+                            }
+                            let epoch = clarity_tx.get_epoch();
+                            let cost_track = clarity_tx
+                                .with_clarity_db_readonly(|clarity_db| {
+                                    LimitedCostTracker::new_mid_block(
+                                        mainnet, chain_id, cost_limit, clarity_db, epoch,
+                                    )
+                                })
+                                .ok()?;
+                            clarity_tx
+                                .with_readonly_clarity_env(
+                                    mainnet,
+                                    chain_id,
+                                    ClarityVersion::Clarity2,
+                                    PrincipalData::from(boot_code_addr(mainnet)),
+                                    None,
+                                    cost_track,
+                                    |env| {
+                                        env.execute_contract(
+                                            &pox_4_id,
+                                            "verify-signer-key-sig",
+                                            &args,
+                                            true,
+                                        )
+                                    },
+                                )
+                                .ok()
+                        })
+                    });
+                (chain_id, result)
+            });
+
+        let result = match data_resp {
+            Ok(Some(Some(result))) => result,
+            Ok(Some(None)) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new(format!("pox-4 is not instantiated at '{}'", &tip)),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Ok(None) | Err(_) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new(format!("Chain tip '{}' not found", &tip)),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let (valid, error_code) = match result.expect_result() {
+            Ok(Ok(_)) => (true, None),
+            Ok(Err(err)) => (false, err.expect_u128().ok()),
+            Err(_) => (false, None),
+        };
+        let message_hash = make_pox_4_signer_key_message_hash(
+            &request.pox_address,
+            request.reward_cycle.into(),
+            &request.topic,
+            chain_id,
+            request.period.into(),
+            request.max_amount,
+            request.auth_id,
+        );
+
+        let verify_resp = VerifySignerSignatureResponse {
+            valid,
+            error_code,
+            reason: error_code
+                .and_then(signer_key_sig_error_name)
+                .map(String::from),
+            message_hash: to_hex(message_hash.as_bytes()),
+            index_block_hash: tip,
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&verify_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCVerifySignerSignatureRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let verify_resp: VerifySignerSignatureResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(verify_resp)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to check a pox-4 signer key authorization
+    pub fn new_verify_signer_signature(
+        host: PeerHost,
+        request: SignerSignatureRequest,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            "/v3/pox/verify-signer-signature".into(),
+            HttpRequestContents::new().for_tip(tip_req).payload_json(
+                serde_json::to_value(VerifySignerSignatureRequestBody::from(request))
+                    .expect("FATAL: failed to encode infallible data"),
+            ),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_verify_signer_signature_response(
+        self,
+    ) -> Result<VerifySignerSignatureResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: VerifySignerSignatureResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
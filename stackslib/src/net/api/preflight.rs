@@ -0,0 +1,123 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::{
+    http_reason, Error, HttpContentType, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{EndpointClass, HttpCorsPolicy, RPCRequestHandler, StacksHttpRequest};
+use crate::net::{Error as NetError, StacksNodeState};
+
+/// Answers CORS preflight (`OPTIONS`) requests for any path.  The CORS headers themselves are
+/// added by `StacksHttp`, according to its CORS policy.
+#[derive(Clone, Default)]
+pub struct RPCPreflightRequestHandler {}
+
+impl RPCPreflightRequestHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPreflightRequestHandler {
+    fn verb(&self) -> &'static str {
+        "OPTIONS"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/.*$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "OPTIONS"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCPreflightRequestHandler {
+    /// Preflight requests are cheap
+    fn endpoint_class(&self) -> EndpointClass {
+        EndpointClass::Read
+    }
+
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        _node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let mut preamble = HttpResponsePreamble::from_http_request_preamble(
+            &preamble,
+            204,
+            http_reason(204),
+            Some(0),
+            HttpContentType::Text,
+        );
+        preamble.add_header(
+            "Access-Control-Max-Age".into(),
+            HttpCorsPolicy::MAX_AGE_SECS.to_string(),
+        );
+        Ok((preamble, HttpResponseContents::from_ram(vec![])))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPreflightRequestHandler {
+    fn try_parse_response(
+        &self,
+        _preamble: &HttpResponsePreamble,
+        _body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        Ok(HttpResponsePayload::Empty)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new CORS preflight request for a path, from a browser page at `origin`
+    pub fn new_preflight(host: PeerHost, path: &str, origin: &str) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "OPTIONS".into(),
+            path.into(),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+        .with_header("Origin".into(), origin.into())
+    }
+}
@@ -15,15 +15,17 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::thread;
+use std::time::Duration;
 
 use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, StacksAddressExtensions};
 use clarity::vm::{ClarityName, ContractName};
 use stacks_common::codec::StacksMessageCodec;
-use stacks_common::types::chainstate::StacksAddress;
+use stacks_common::types::chainstate::{StacksAddress, StacksBlockId};
 use stacks_common::types::net::PeerHost;
 use stacks_common::types::Address;
 
-use super::test_rpc;
+use super::{test_rpc, TestRPC};
 use crate::core::BLOCK_LIMIT_MAINNET_21;
 use crate::net::api::*;
 use crate::net::connection::ConnectionOptions;
@@ -31,7 +33,7 @@ use crate::net::httpcore::{
     HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
     StacksHttpRequest,
 };
-use crate::net::{ProtocolFamily, TipRequest};
+use crate::net::{ProtocolFamily, RPCHandlerArgs, StacksNodeState, TipRequest};
 
 #[test]
 fn test_try_parse_request() {
@@ -71,36 +73,28 @@ fn test_try_parse_request() {
         .unwrap();
 
     // consumed path args and body
+    let args =
+        callreadonly::RPCCallReadOnlyRequestHandler::parse_args(parsed_request.contents()).unwrap();
     assert_eq!(
-        handler.contract_identifier,
-        Some(
-            QualifiedContractIdentifier::parse(
-                "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world-unconfirmed"
-            )
-            .unwrap()
+        args.contract_identifier,
+        QualifiedContractIdentifier::parse(
+            "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world-unconfirmed"
         )
+        .unwrap()
     );
-    assert_eq!(handler.function, Some("ro-test".into()));
+    assert_eq!(args.function, "ro-test".into());
     assert_eq!(
-        handler.sender,
-        Some(PrincipalData::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap())
+        args.sender,
+        PrincipalData::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap()
     );
-    assert_eq!(handler.sponsor, None);
-    assert_eq!(handler.arguments, Some(vec![]));
+    assert_eq!(args.sponsor, None);
+    assert_eq!(args.arguments, vec![]);
 
     // parsed request consumes headers that would not be in a constructed reqeuest
     parsed_request.clear_headers();
     let (preamble, contents) = parsed_request.destruct();
 
     assert_eq!(&preamble, request.preamble());
-
-    // restart clears the handler state
-    handler.restart();
-    assert!(handler.contract_identifier.is_none());
-    assert!(handler.function.is_none());
-    assert!(handler.sender.is_none());
-    assert!(handler.sponsor.is_none());
-    assert!(handler.arguments.is_none());
 }
 
 #[test]
@@ -271,3 +265,112 @@ fn test_try_make_response() {
     let (preamble, payload) = response.destruct();
     assert_eq!(preamble.status_code, 404);
 }
+
+#[test]
+fn test_defer_request_read_replicas() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let mut rpc_test = TestRPC::setup(function_name!());
+    let peer = &mut rpc_test.peer_2;
+    let sortdb = peer.sortdb.take().unwrap();
+    let mut stacks_node = peer.stacks_node.take().unwrap();
+    let mut mempool = peer.mempool.take().unwrap();
+    stacks_node
+        .chainstate
+        .enable_read_replicas(&sortdb, 1)
+        .unwrap();
+    assert_eq!(
+        stacks_node
+            .chainstate
+            .read_replicas()
+            .unwrap()
+            .num_workers(),
+        1
+    );
+    let mainnet = stacks_node.chainstate.mainnet;
+    let chain_id = stacks_node.chainstate.chain_id;
+
+    let rpc_args = RPCHandlerArgs::default();
+    let mut node_state = StacksNodeState::new(
+        &mut peer.network,
+        &sortdb,
+        &mut stacks_node.chainstate,
+        &mut mempool,
+        &rpc_args,
+        false,
+    );
+
+    let request = StacksHttpRequest::new_callreadonlyfunction(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        "hello-world".try_into().unwrap(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R")
+            .unwrap()
+            .to_account_principal(),
+        None,
+        "ro-confirmed".try_into().unwrap(),
+        vec![],
+        TipRequest::UseLatestAnchoredTip,
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler =
+        callreadonly::RPCCallReadOnlyRequestHandler::new(4096, BLOCK_LIMIT_MAINNET_21);
+    let parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+    let (preamble, contents) = parsed_request.destruct();
+
+    // the call runs on a read replica worker
+    let args = callreadonly::RPCCallReadOnlyRequestHandler::parse_args(&contents).unwrap();
+    let Ok(tip) = node_state.load_stacks_chain_tip(&preamble, &contents) else {
+        panic!("No chain tip");
+    };
+    let query = node_state
+        .submit_read_replica_query(&tip, move |clarity_tx| {
+            callreadonly::call_read_only(
+                clarity_tx,
+                mainnet,
+                chain_id,
+                BLOCK_LIMIT_MAINNET_21,
+                args,
+            )
+        })
+        .unwrap();
+    while !query.is_done() {
+        thread::sleep(Duration::from_millis(10));
+    }
+    let resp = query.take_result().unwrap().unwrap().unwrap();
+    assert!(resp.okay);
+    // u1
+    assert_eq!(resp.result.unwrap(), "0x0100000000000000000000000000000001");
+
+    // a tip that was never processed
+    let query = node_state
+        .submit_read_replica_query(&StacksBlockId([0x11; 32]), |_clarity_tx| ())
+        .unwrap();
+    while !query.is_done() {
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(query.take_result(), Some(None));
+
+    // the request is held until the worker has run the call, and is answered from its result
+    let mut num_passes = 0;
+    while handler
+        .defer_request(&preamble, &contents, &mut node_state)
+        .is_some()
+    {
+        num_passes += 1;
+        assert!(num_passes < 1000, "Read replica query never finished");
+        thread::sleep(Duration::from_millis(10));
+    }
+    let (response_preamble, _body) = handler
+        .try_handle_request(preamble, contents, &mut node_state)
+        .unwrap();
+    assert_eq!(response_preamble.status_code, 200);
+}
@@ -0,0 +1,305 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clarity::vm::types::{PrincipalData, StacksAddressExtensions};
+use stacks_common::types::chainstate::{StacksAddress, StacksBlockId, StacksPrivateKey};
+use stacks_common::types::Address;
+use stacks_common::util::hash::Hash160;
+
+use super::{test_rpc, TestRPC};
+use crate::chainstate::nakamoto::staging_blocks::NakamotoBlockObtainMethod;
+use crate::chainstate::stacks::{
+    StacksTransaction, StacksTransactionSigner, TokenTransferMemo, TransactionAuth,
+    TransactionPayload, TransactionVersion,
+};
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+};
+use crate::net::test::TestEventObserver;
+use crate::net::TipRequest;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+    let principal = StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R")
+        .unwrap()
+        .to_account_principal();
+
+    for include_unconfirmed_tenure in [false, true] {
+        let request = StacksHttpRequest::new_getaccount_v3(
+            addr.into(),
+            principal.clone(),
+            TipRequest::UseLatestAnchoredTip,
+            false,
+            include_unconfirmed_tenure,
+        );
+        let bytes = request.try_serialize().unwrap();
+
+        debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+        let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+        let mut handler = getaccount_v3::RPCGetAccountV3RequestHandler::new();
+        let mut parsed_request = http
+            .handle_try_parse_request(
+                &mut handler,
+                &parsed_preamble.expect_request(),
+                &bytes[offset..],
+            )
+            .unwrap();
+
+        // parsed request consumes headers that would not be in a constructed reqeuest
+        parsed_request.clear_headers();
+        let (preamble, _contents) = parsed_request.destruct();
+
+        // consumed path args
+        assert_eq!(handler.account, Some(principal.clone()));
+        assert_eq!(
+            handler.include_unconfirmed_tenure,
+            include_unconfirmed_tenure
+        );
+
+        assert_eq!(&preamble, request.preamble());
+
+        // reset works
+        handler.restart();
+        assert!(handler.account.is_none());
+        assert!(!handler.include_unconfirmed_tenure);
+    }
+
+    // can't ask for the ongoing tenure at a specific tip
+    let request = StacksHttpRequest::new_getaccount_v3(
+        addr.into(),
+        principal.clone(),
+        TipRequest::SpecificTip(StacksBlockId([0x11; 32])),
+        false,
+        true,
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getaccount_v3::RPCGetAccountV3RequestHandler::new();
+    assert!(http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .is_err());
+
+    // bad flag
+    let request = StacksHttpRequest::new_for_peer(
+        addr.into(),
+        "GET".into(),
+        format!("/v3/accounts/{principal}"),
+        crate::net::http::HttpRequestContents::new()
+            .query_arg("include_unconfirmed_tenure".into(), "maybe".into()),
+    )
+    .unwrap();
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getaccount_v3::RPCGetAccountV3RequestHandler::new();
+    assert!(http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .is_err());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let principal: PrincipalData =
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R")
+            .unwrap()
+            .to_account_principal();
+
+    let mut requests = vec![];
+
+    // query existing account at the canonical tip
+    let request = StacksHttpRequest::new_getaccount_v3(
+        addr.into(),
+        principal.clone(),
+        TipRequest::UseLatestAnchoredTip,
+        false,
+        false,
+    );
+    requests.push(request);
+
+    // query existing account in the ongoing tenure
+    let request = StacksHttpRequest::new_getaccount_v3(
+        addr.into(),
+        principal.clone(),
+        TipRequest::UseLatestAnchoredTip,
+        true,
+        true,
+    );
+    requests.push(request);
+
+    // query nonexistant tip
+    let request = StacksHttpRequest::new_getaccount_v3(
+        addr.into(),
+        principal,
+        TipRequest::SpecificTip(StacksBlockId([0x11; 32])),
+        false,
+        false,
+    );
+    requests.push(request);
+
+    let mut responses = test_rpc(function_name!(), requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    assert_eq!(
+        response.preamble().get_canonical_stacks_tip_height(),
+        Some(1)
+    );
+
+    let resp = response.decode_account_entry_v3_response().unwrap();
+
+    assert_eq!(resp.account.balance, "0x0000000000000000000000003b9aca00");
+    assert_eq!(resp.account.locked, "0x00000000000000000000000000000000");
+    assert_eq!(resp.account.nonce, 2);
+    assert!(resp.account.balance_proof.is_none());
+    assert!(resp.account.nonce_proof.is_none());
+    assert_eq!(resp.block_height, 1);
+    let canonical_tip = resp.index_block_hash;
+
+    // the ongoing tenure has no blocks past the canonical tip
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_account_entry_v3_response().unwrap();
+
+    assert_eq!(resp.account.balance, "0x0000000000000000000000003b9aca00");
+    assert_eq!(resp.account.nonce, 2);
+    assert!(resp.account.balance_proof.is_some());
+    assert!(resp.account.nonce_proof.is_some());
+    assert_eq!(resp.block_height, 1);
+    assert_eq!(resp.index_block_hash, canonical_tip);
+
+    // no such tip
+    let response = responses.remove(0);
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+}
+
+#[test]
+fn test_try_make_response_unconfirmed_tenure() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let test_observer = TestEventObserver::new();
+    let mut rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+    let canonical_tip = rpc_test.canonical_tip.clone();
+
+    let sender_privk = StacksPrivateKey::random();
+    let auth = TransactionAuth::from_p2pkh(&sender_privk).unwrap();
+    let sender: PrincipalData = auth.origin().address_testnet().to_account_principal();
+    let recipient: PrincipalData = StacksAddress::new(26, Hash160([0xfe; 20]))
+        .unwrap()
+        .to_account_principal();
+
+    let mut tx = StacksTransaction::new(
+        TransactionVersion::Testnet,
+        auth,
+        TransactionPayload::TokenTransfer(recipient.clone(), 123, TokenTransferMemo([0u8; 34])),
+    );
+    tx.chain_id = 0x80000000;
+    tx.set_tx_fee(180);
+    let mut tx_signer = StacksTransactionSigner::new(&tx);
+    tx_signer.sign_origin(&sender_privk).unwrap();
+    let tx = tx_signer.get_tx().unwrap();
+
+    // store a child of the canonical tip that this node has not processed yet
+    let chainstate = rpc_test.peer_2.chainstate();
+    let (mut unconfirmed_block, _size) = chainstate
+        .nakamoto_blocks_db()
+        .get_nakamoto_block(&canonical_tip)
+        .unwrap()
+        .unwrap();
+    unconfirmed_block.header.parent_block_id = canonical_tip.clone();
+    unconfirmed_block.header.chain_length += 1;
+    unconfirmed_block.txs = vec![tx];
+    let unconfirmed_tip = unconfirmed_block.block_id();
+    let staging_tx = chainstate.staging_db_tx_begin().unwrap();
+    staging_tx
+        .store_block(
+            &unconfirmed_block,
+            true,
+            0,
+            NakamotoBlockObtainMethod::Pushed,
+        )
+        .unwrap();
+    staging_tx.commit().unwrap();
+
+    let mut requests = vec![];
+    for principal in [&sender, &recipient] {
+        for include_unconfirmed_tenure in [false, true] {
+            requests.push(StacksHttpRequest::new_getaccount_v3(
+                addr.into(),
+                principal.clone(),
+                TipRequest::UseLatestAnchoredTip,
+                false,
+                include_unconfirmed_tenure,
+            ));
+        }
+    }
+
+    let mut responses = rpc_test.run(requests);
+
+    // the sender's transfer is only visible with `include_unconfirmed_tenure`
+    let resp = responses
+        .remove(0)
+        .decode_account_entry_v3_response()
+        .unwrap();
+    assert_eq!(resp.index_block_hash, canonical_tip);
+    assert_eq!(resp.account.nonce, 0);
+
+    let resp = responses
+        .remove(0)
+        .decode_account_entry_v3_response()
+        .unwrap();
+    assert_eq!(resp.index_block_hash, unconfirmed_tip);
+    assert_eq!(resp.block_height, unconfirmed_block.header.chain_length);
+    assert_eq!(resp.account.nonce, 1);
+
+    let resp = responses
+        .remove(0)
+        .decode_account_entry_v3_response()
+        .unwrap();
+    assert_eq!(resp.index_block_hash, canonical_tip);
+    assert_eq!(resp.account.balance, "0x00000000000000000000000000000000");
+
+    let resp = responses
+        .remove(0)
+        .decode_account_entry_v3_response()
+        .unwrap();
+    assert_eq!(resp.index_block_hash, unconfirmed_tip);
+    assert_eq!(resp.account.balance, "0x0000000000000000000000000000007b");
+    assert_eq!(resp.account.nonce, 0);
+}
@@ -0,0 +1,261 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use stacks_common::types::chainstate::ConsensusHash;
+
+use super::TestRPC;
+use crate::net::api::getarchive::ArchiveRange;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+use crate::net::test::TestEventObserver;
+use crate::net::TipRequest;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_get_archive_tenure(
+        addr.into(),
+        &ConsensusHash([0x11; 20]),
+        Some(123),
+        TipRequest::UseLatestAnchoredTip,
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getarchive::RPCGetArchiveTenureRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed request
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(handler.consensus_hash, Some(ConsensusHash([0x11; 20])));
+    assert_eq!(handler.start_height, Some(123));
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.consensus_hash.is_none());
+    assert!(handler.start_height.is_none());
+
+    let request = StacksHttpRequest::new_get_archive_blocks(
+        addr.into(),
+        100,
+        200,
+        TipRequest::UseLatestAnchoredTip,
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getarchive::RPCGetArchiveBlocksRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(handler.start_height, Some(100));
+    assert_eq!(handler.end_height, Some(200));
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.start_height.is_none());
+    assert!(handler.end_height.is_none());
+
+    // empty ranges are rejected
+    let request = StacksHttpRequest::new_get_archive_blocks(
+        addr.into(),
+        200,
+        100,
+        TipRequest::UseLatestAnchoredTip,
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getarchive::RPCGetArchiveBlocksRequestHandler::new();
+    assert!(http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .is_err());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let test_observer = TestEventObserver::new();
+    let mut rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+    rpc_test.peer_2.network.connection_opts.archival = true;
+
+    let nakamoto_chain_tip = rpc_test.canonical_tip.clone();
+    let consensus_hash = rpc_test.consensus_hash.clone();
+    let tip_height = rpc_test.tip_height;
+
+    // the whole tip tenure, then a suffix of it
+    let mut requests = vec![
+        StacksHttpRequest::new_get_archive_tenure(
+            addr.into(),
+            &consensus_hash,
+            None,
+            TipRequest::UseLatestAnchoredTip,
+        ),
+        StacksHttpRequest::new_get_archive_tenure(
+            addr.into(),
+            &consensus_hash,
+            Some(tip_height - 4),
+            TipRequest::UseLatestAnchoredTip,
+        ),
+    ];
+
+    // a range of blocks ending at the tip, and a range past the tip
+    requests.push(StacksHttpRequest::new_get_archive_blocks(
+        addr.into(),
+        tip_height - 2,
+        tip_height + 100,
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // non-existent tenure and range
+    requests.push(StacksHttpRequest::new_get_archive_tenure(
+        addr.into(),
+        &ConsensusHash([0x11; 20]),
+        None,
+        TipRequest::UseLatestAnchoredTip,
+    ));
+    requests.push(StacksHttpRequest::new_get_archive_blocks(
+        addr.into(),
+        tip_height + 1,
+        tip_height + 100,
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    let (blocks, next_height) = response.decode_archive_blocks().unwrap();
+    assert_eq!(blocks.len(), 10);
+    assert_eq!(blocks.last().unwrap().header.block_id(), nakamoto_chain_tip);
+    assert!(blocks
+        .iter()
+        .all(|block| block.header.consensus_hash == consensus_hash));
+    for pair in blocks.windows(2) {
+        assert_eq!(pair[1].header.parent_block_id, pair[0].header.block_id());
+    }
+    assert!(next_height.is_none());
+
+    let response = responses.remove(0);
+    let (blocks, next_height) = response.decode_archive_blocks().unwrap();
+    assert_eq!(blocks.len(), 5);
+    assert_eq!(blocks.last().unwrap().header.block_id(), nakamoto_chain_tip);
+    assert!(next_height.is_none());
+
+    let response = responses.remove(0);
+    let (blocks, next_height) = response.decode_archive_blocks().unwrap();
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks.last().unwrap().header.block_id(), nakamoto_chain_tip);
+    assert!(next_height.is_none());
+
+    let response = responses.remove(0);
+    assert_eq!(response.preamble().status_code, 404);
+
+    let response = responses.remove(0);
+    assert_eq!(response.preamble().status_code, 404);
+}
+
+#[test]
+fn test_archive_disabled() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let test_observer = TestEventObserver::new();
+    let rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+    let consensus_hash = rpc_test.consensus_hash.clone();
+    let tip_height = rpc_test.tip_height;
+
+    let requests = vec![
+        StacksHttpRequest::new_get_archive_tenure(
+            addr.into(),
+            &consensus_hash,
+            None,
+            TipRequest::UseLatestAnchoredTip,
+        ),
+        StacksHttpRequest::new_get_archive_blocks(
+            addr.into(),
+            tip_height,
+            tip_height,
+            TipRequest::UseLatestAnchoredTip,
+        ),
+    ];
+
+    for response in rpc_test.run(requests) {
+        assert_eq!(response.preamble().status_code, 404);
+    }
+}
+
+#[test]
+fn test_archive_range_resumes() {
+    let test_observer = TestEventObserver::new();
+    let mut rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+    let nakamoto_chain_tip = rpc_test.canonical_tip.clone();
+    let tip_height = rpc_test.tip_height;
+    let chainstate = rpc_test.peer_1.chainstate();
+
+    // a truncated range says where to pick up from
+    let range = ArchiveRange::load(
+        chainstate,
+        &nakamoto_chain_tip,
+        tip_height - 5,
+        tip_height,
+        4,
+    )
+    .unwrap();
+    assert_eq!(range.blocks.len(), 4);
+    assert_eq!(range.next_height, Some(tip_height - 1));
+
+    let rest = ArchiveRange::load(
+        chainstate,
+        &nakamoto_chain_tip,
+        range.next_height.unwrap(),
+        tip_height,
+        4,
+    )
+    .unwrap();
+    assert_eq!(rest.blocks.len(), 2);
+    assert_eq!(rest.next_height, None);
+    assert_eq!(rest.blocks.last().unwrap().block_id, nakamoto_chain_tip);
+    assert_eq!(
+        rest.blocks[0].parent_block_id,
+        range.blocks.last().unwrap().block_id
+    );
+}
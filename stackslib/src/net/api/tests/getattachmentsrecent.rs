@@ -0,0 +1,132 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::TestRPC;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+};
+use crate::net::Attachment;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_getattachmentsrecent(addr.into(), Some(10), Some(5));
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getattachmentsrecent::RPCGetRecentAttachmentsRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(handler.limit, Some(10));
+    assert_eq!(handler.min_height, Some(5));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.limit.is_none());
+    assert!(handler.min_height.is_none());
+
+    // query arguments are optional
+    let request = StacksHttpRequest::new_getattachmentsrecent(addr.into(), None, None);
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    )
+    .unwrap();
+    assert!(handler.limit.is_none());
+    assert!(handler.min_height.is_none());
+}
+
+#[test]
+fn test_try_make_response() {
+    let attachment = Attachment {
+        content: vec![0, 1, 2, 3, 4],
+    };
+    let attachment_hash = attachment.hash();
+
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let stacks_chain_tip = rpc_test.canonical_tip.clone();
+    let consensus_hash = rpc_test.consensus_hash.clone();
+
+    let mut requests = vec![];
+
+    // query all recent attachments
+    let request = StacksHttpRequest::new_getattachmentsrecent(addr.into(), None, None);
+    requests.push(request);
+
+    // query attachments above the chain tip
+    let request = StacksHttpRequest::new_getattachmentsrecent(addr.into(), None, Some(2));
+    requests.push(request);
+
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    assert_eq!(
+        response.preamble().get_canonical_stacks_tip_height(),
+        Some(1)
+    );
+
+    let resp = response.decode_atlas_recent_attachments_response().unwrap();
+
+    // the attachment instance is reported under its tenure
+    assert_eq!(resp.tenures.len(), 1);
+    assert_eq!(resp.tenures[0].consensus_hash, Some(consensus_hash));
+    assert_eq!(resp.tenures[0].attachments.len(), 1);
+
+    let recent = &resp.tenures[0].attachments[0];
+    assert_eq!(recent.content_hash, attachment_hash);
+    assert_eq!(recent.attachment_index, 123);
+    assert_eq!(recent.index_block_hash, stacks_chain_tip);
+    assert_eq!(recent.stacks_block_height, 1);
+    assert!(recent.is_available);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_atlas_recent_attachments_response().unwrap();
+    assert!(resp.tenures.is_empty());
+}
@@ -36,7 +36,8 @@ use crate::chainstate::stacks::{
 use crate::net::api::getblock_v3::NakamotoBlockStream;
 use crate::net::api::*;
 use crate::net::connection::ConnectionOptions;
-use crate::net::http::HttpChunkGenerator;
+use crate::net::http::common::IMMUTABLE_CACHE_CONTROL;
+use crate::net::http::{HttpChunkGenerator, HttpResponsePayload};
 use crate::net::httpcore::{
     HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
     StacksHttpRequest,
@@ -100,10 +101,37 @@ fn test_try_make_response() {
     let request = StacksHttpRequest::new_get_nakamoto_block(addr.into(), StacksBlockId([0x11; 32]));
     requests.push(request);
 
+    // conditional query for a block we already have
+    let request =
+        StacksHttpRequest::new_get_nakamoto_block(addr.into(), nakamoto_chain_tip.clone())
+            .with_header(
+                "If-None-Match".into(),
+                format!("W/\"abcd\", \"{nakamoto_chain_tip}\""),
+            );
+    requests.push(request);
+
+    // conditional query for a different version
+    let request =
+        StacksHttpRequest::new_get_nakamoto_block(addr.into(), nakamoto_chain_tip.clone())
+            .with_header("If-None-Match".into(), "\"abcd\"".into());
+    requests.push(request);
+
     let mut responses = rpc_test.run(requests);
 
     // got the block
     let response = responses.remove(0);
+    let expected_etag = format!("\"{nakamoto_chain_tip}\"");
+    assert_eq!(
+        response.preamble().get_header("ETag".into()),
+        Some(expected_etag.clone())
+    );
+    assert_eq!(
+        response
+            .preamble()
+            .get_header("Cache-Control".into())
+            .as_deref(),
+        Some(IMMUTABLE_CACHE_CONTROL)
+    );
     let resp = response.decode_nakamoto_block().unwrap();
 
     assert_eq!(
@@ -116,6 +144,26 @@ fn test_try_make_response() {
     let (preamble, body) = response.destruct();
 
     assert_eq!(preamble.status_code, 404);
+    assert!(preamble.get_header("ETag".into()).is_none());
+
+    // not modified
+    let response = responses.remove(0);
+    let (preamble, body) = response.destruct();
+    assert_eq!(preamble.status_code, 304);
+    assert_eq!(
+        preamble.get_header("ETag".into()),
+        Some(expected_etag.clone())
+    );
+    assert_eq!(body, HttpResponsePayload::Empty);
+
+    // etag mismatch, so got the block
+    let response = responses.remove(0);
+    assert_eq!(response.preamble().status_code, 200);
+    let resp = response.decode_nakamoto_block().unwrap();
+    assert_eq!(
+        StacksBlockHeader::make_index_block_hash(&consensus_hash, &resp.header.block_hash()),
+        nakamoto_chain_tip
+    );
 }
 
 #[test]
@@ -0,0 +1,146 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clarity::vm::costs::ExecutionCost;
+use serde_json::json;
+use stacks_common::types::chainstate::StacksBlockId;
+
+use super::TestRPC;
+use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::stacks::db::receipts::StoredTransactionReceipt;
+use crate::net::api::getblocksummary::{BlockSummaryResponse, RPCGetBlockSummaryRequestHandler};
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{EndpointClass, RPCRequestHandler, StacksHttp, StacksHttpRequest};
+use crate::net::test::TestEventObserver;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_get_block_summary(addr.into(), StacksBlockId([0x11; 32]));
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = RPCGetBlockSummaryRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(handler.block_id, Some(StacksBlockId([0x11; 32])));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    assert_eq!(handler.endpoint_class(), EndpointClass::ReadHeavy);
+
+    handler.restart();
+    assert!(handler.block_id.is_none());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let test_observer = TestEventObserver::new();
+    let mut rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+    let nakamoto_chain_tip = rpc_test.canonical_tip.clone();
+
+    let chainstate = rpc_test.peer_2.chainstate();
+    let (block, _size) = chainstate
+        .nakamoto_blocks_db()
+        .get_nakamoto_block(&nakamoto_chain_tip)
+        .unwrap()
+        .unwrap();
+    let block_cost = NakamotoChainState::get_block_cost_at(chainstate.db(), &nakamoto_chain_tip)
+        .unwrap()
+        .unwrap();
+
+    let mut requests = vec![];
+
+    // query existing block
+    requests.push(StacksHttpRequest::new_get_block_summary(
+        addr.into(),
+        nakamoto_chain_tip.clone(),
+    ));
+
+    // query non-existant block
+    requests.push(StacksHttpRequest::new_get_block_summary(
+        addr.into(),
+        StacksBlockId([0x11; 32]),
+    ));
+
+    let mut responses = rpc_test.run(requests);
+
+    let summary = responses.remove(0).decode_block_summary().unwrap();
+    assert_eq!(
+        summary,
+        BlockSummaryResponse::from_block(&block, block_cost.clone(), None)
+    );
+    assert_eq!(summary.index_block_hash, nakamoto_chain_tip);
+    assert_eq!(summary.execution_cost, block_cost);
+    assert_eq!(summary.tx_count, block.txs.len() as u64);
+    assert_eq!(
+        summary.tx_count_by_type.values().sum::<u64>(),
+        summary.tx_count
+    );
+    let total_fees: u128 = block.txs.iter().map(|tx| u128::from(tx.get_tx_fee())).sum();
+    assert_eq!(summary.total_fees, total_fees);
+    assert_eq!(summary.signer_bitvec_len, block.header.pox_treatment.len());
+    assert!(summary.signer_bitvec_participation <= 100.0);
+    // receipt tracking is not enabled on the test peer
+    assert!(summary.events.is_none());
+
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 404);
+
+    // events are counted from stored receipts
+    let receipt = StoredTransactionReceipt {
+        txid: block.txs[0].txid(),
+        index_block_hash: nakamoto_chain_tip.clone(),
+        block_height: block.header.chain_length,
+        tx_index: 0,
+        result: "0x03".into(),
+        vm_error: None,
+        post_condition_aborted: false,
+        stx_burned: 0,
+        execution_cost: ExecutionCost::ZERO,
+        events: vec![
+            json!({"type": "stx_transfer_event"}),
+            json!({"type": "contract_event"}),
+            json!({"type": "stx_transfer_event"}),
+        ],
+    };
+    let summary = BlockSummaryResponse::from_block(&block, block_cost.clone(), Some(vec![receipt]));
+    let events = summary.events.unwrap();
+    assert_eq!(events.total, 3);
+    assert_eq!(events.by_type.get("stx_transfer_event"), Some(&2));
+    assert_eq!(events.by_type.get("contract_event"), Some(&1));
+
+    // pruned receipts are unknown rather than empty
+    let summary = BlockSummaryResponse::from_block(&block, block_cost, Some(vec![]));
+    assert!(summary.events.is_none());
+}
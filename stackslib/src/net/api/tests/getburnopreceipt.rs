@@ -0,0 +1,113 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::TestRPC;
+use crate::burnchains::Txid;
+use crate::net::api::getburnopreceipt::BurnOpStxLock;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+use crate::net::test::TestEventObserver;
+use crate::net::TipRequest;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let txid = Txid([0x11; 32]);
+    let request = StacksHttpRequest::new_get_burn_op_receipt(
+        addr.into(),
+        &txid,
+        TipRequest::UseLatestAnchoredTip,
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getburnopreceipt::RPCGetBurnOpReceiptRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed request
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(handler.txid, Some(txid));
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.txid.is_none());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let test_observer = TestEventObserver::new();
+    let mut rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+
+    let requests = vec![
+        // no such burnchain op
+        StacksHttpRequest::new_get_burn_op_receipt(
+            addr.into(),
+            &Txid([0x11; 32]),
+            TipRequest::UseLatestAnchoredTip,
+        ),
+    ];
+
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    assert_eq!(response.preamble().status_code, 404);
+}
+
+#[test]
+fn test_stx_lock_from_events() {
+    let lock_event = serde_json::json!({
+        "txid": "0x1111111111111111111111111111111111111111111111111111111111111111",
+        "event_index": 0,
+        "committed": true,
+        "type": "stx_lock_event",
+        "stx_lock_event": {
+            "locked_amount": "1000000000",
+            "unlock_height": "250",
+            "locked_address": "ST2QKZ4FKHAH1NQKYKYAYZPY440FEPK7GZ1R5HBP2",
+        }
+    });
+    let lock = BurnOpStxLock::from_events(&[lock_event.clone()]).unwrap();
+    assert_eq!(
+        lock,
+        BurnOpStxLock {
+            locked_address: "ST2QKZ4FKHAH1NQKYKYAYZPY440FEPK7GZ1R5HBP2".into(),
+            locked_amount: 1_000_000_000,
+            unlock_height: 250,
+        }
+    );
+
+    // rolled-back locks don't count
+    let mut rolled_back = lock_event;
+    rolled_back["committed"] = serde_json::Value::Bool(false);
+    assert!(BurnOpStxLock::from_events(&[rolled_back]).is_none());
+    assert!(BurnOpStxLock::from_events(&[]).is_none());
+}
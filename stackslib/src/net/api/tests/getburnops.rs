@@ -0,0 +1,95 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::TestRPC;
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+use crate::net::test::TestEventObserver;
+use crate::net::TipRequest;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request =
+        StacksHttpRequest::new_get_burn_ops(addr.into(), 123, TipRequest::UseLatestAnchoredTip);
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getburnops::RPCGetBurnOpsRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed request
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(handler.burn_height, Some(123));
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.burn_height.is_none());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let test_observer = TestEventObserver::new();
+    let mut rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+    let nakamoto_chain_tip = rpc_test.canonical_tip.clone();
+    let burn_tip =
+        SortitionDB::get_canonical_burn_chain_tip(rpc_test.peer_2.sortdb_ref().conn()).unwrap();
+
+    let requests = vec![
+        StacksHttpRequest::new_get_burn_ops(
+            addr.into(),
+            burn_tip.block_height,
+            TipRequest::UseLatestAnchoredTip,
+        ),
+        // no such burnchain block
+        StacksHttpRequest::new_get_burn_ops(
+            addr.into(),
+            burn_tip.block_height + 1000,
+            TipRequest::UseLatestAnchoredTip,
+        ),
+    ];
+
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    let burn_ops = response.decode_burn_ops().unwrap();
+    assert_eq!(burn_ops.burn_block_height, burn_tip.block_height);
+    assert_eq!(burn_ops.burn_header_hash, burn_tip.burn_header_hash);
+    assert_eq!(burn_ops.index_block_hash, nakamoto_chain_tip);
+    // block-commits and key registrations are not reported
+    assert!(burn_ops.ops.iter().all(|entry| !entry.op.is_null()));
+
+    let response = responses.remove(0);
+    assert_eq!(response.preamble().status_code, 404);
+}
@@ -0,0 +1,117 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::test_rpc;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_get_health_live(addr.into());
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = gethealth::RPCGetHealthLiveRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed request
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+    assert_eq!(&preamble, request.preamble());
+
+    let request = StacksHttpRequest::new_get_health_ready(addr.into());
+    let bytes = request.try_serialize().unwrap();
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = gethealth::RPCGetHealthReadyRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+    assert_eq!(&preamble, request.preamble());
+
+    // reset works
+    handler.restart();
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let requests = vec![
+        StacksHttpRequest::new_get_health_live(addr.into()),
+        StacksHttpRequest::new_get_health_ready(addr.into()),
+    ];
+
+    let mut responses = test_rpc(function_name!(), requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    assert_eq!(response.preamble().status_code, 200);
+    let resp = response.decode_health_live().unwrap();
+    assert_eq!(resp.status, "ok");
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let status_code = response.preamble().status_code;
+    let resp = response.decode_health_ready().unwrap();
+    let default_opts = ConnectionOptions::default();
+    assert_eq!(
+        resp.max_burn_block_lag,
+        default_opts.health_max_burn_block_lag
+    );
+    assert_eq!(resp.max_tenure_lag, default_opts.health_max_tenure_lag);
+    assert_eq!(resp.stacks_tip_height, 1);
+    assert!(resp.burnchain_headers_height >= resp.burn_block_height);
+    assert_eq!(
+        resp.burn_block_lag,
+        resp.burnchain_headers_height - resp.burn_block_height
+    );
+    // the test peer has not been running long enough for its tip to be considered stale
+    assert!(resp.stale_tip.is_none());
+    assert_eq!(
+        resp.ready,
+        resp.burn_block_lag <= resp.max_burn_block_lag
+            && resp.tenure_lag <= resp.max_tenure_lag
+            && resp.stale_tip.is_none()
+    );
+    assert_eq!(status_code, if resp.ready { 200 } else { 503 });
+}
@@ -0,0 +1,117 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use stacks_common::types::chainstate::{StacksPrivateKey, StacksPublicKey};
+
+use super::test_rpc;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let signer_key = StacksPublicKey::from_private(&StacksPrivateKey::random());
+    let request = StacksHttpRequest::new_getinfo_v3(addr.into(), Some(&signer_key));
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getinfo_v3::RPCPeerInfoV3RequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed request
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+    assert_eq!(handler.signer_key, Some(signer_key));
+
+    // reset works
+    handler.restart();
+    assert!(handler.signer_key.is_none());
+
+    // bad signer keys are rejected
+    let request = StacksHttpRequest::new_for_peer(
+        addr.into(),
+        "GET".into(),
+        "/v3/info".into(),
+        crate::net::http::HttpRequestContents::new()
+            .query_arg("signer_key".into(), "not-a-key".into()),
+    )
+    .unwrap();
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    assert!(http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .is_err());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let signer_key = StacksPublicKey::from_private(&StacksPrivateKey::random());
+    let requests = vec![
+        StacksHttpRequest::new_getinfo_v3(addr.into(), None),
+        StacksHttpRequest::new_getinfo_v3(addr.into(), Some(&signer_key)),
+    ];
+
+    let mut responses = test_rpc(function_name!(), requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    assert_eq!(
+        response.preamble().get_canonical_stacks_tip_height(),
+        Some(1)
+    );
+    let resp = response.decode_peer_info_v3().unwrap();
+    assert_eq!(resp.tip.stacks_tip_height, 1);
+    assert!(resp.tip.stacks_tip_age_secs.is_some());
+    assert!(resp.epoch.start_height <= resp.tip.burn_block_height);
+    if let Some(next_epoch_height) = resp.epoch.next_epoch_height {
+        assert!(next_epoch_height > resp.tip.burn_block_height);
+        assert!(resp.epoch.next_epoch_id.is_some());
+    }
+    assert!(resp.signers.in_reward_set.is_none());
+    assert!(resp.signers.signer_weight.is_none());
+    // NAT traversal is off by default
+    assert!(resp.p2p.nat.is_none());
+
+    // epoch 2.x reward sets have no signers, so membership is unknown
+    let resp = responses.remove(0).decode_peer_info_v3().unwrap();
+    assert!(resp.signers.num_signers.is_none());
+    assert!(resp.signers.in_reward_set.is_none());
+}
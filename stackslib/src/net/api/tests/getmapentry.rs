@@ -58,7 +58,9 @@ fn test_try_parse_request() {
     debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
 
     let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
-    let mut handler = getmapentry::RPCGetMapEntryRequestHandler::new();
+    let mut handler = getmapentry::RPCGetMapEntryRequestHandler::new(
+        ConnectionOptions::default().read_only_map_entry_limit,
+    );
     let mut parsed_request = http
         .handle_try_parse_request(
             &mut handler,
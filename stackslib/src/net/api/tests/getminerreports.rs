@@ -0,0 +1,134 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use stacks_common::types::chainstate::ConsensusHash;
+
+use super::test_rpc;
+use crate::net::api::getminerreports::{
+    MinerReports, MinerTenureReport, RPCGetMinerReportsRequestHandler,
+};
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+
+fn make_report(burn_block_height: u64) -> MinerTenureReport {
+    MinerTenureReport {
+        tenure_consensus_hash: ConsensusHash([0x01; 20]),
+        burn_view_consensus_hash: ConsensusHash([0x01; 20]),
+        burn_block_height,
+        tenure_extend: false,
+        started_at: 1_700_000_000,
+        ended_at: 1_700_000_600,
+        blocks_mined: 3,
+        first_block_height: Some(100),
+        last_block_height: Some(102),
+        txs_included: 10,
+        fees_earned: 50_000,
+        commit_cost_sats: Some(20_000),
+        proposals_rejected: 0,
+        time_to_first_signature_ms: Some(1_500),
+    }
+}
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_get_miner_reports(addr.into(), Some(5));
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = RPCGetMinerReportsRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(handler.limit, Some(5));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.limit.is_none());
+
+    // a zero limit is rejected
+    let request = StacksHttpRequest::new_get_miner_reports(addr.into(), Some(0));
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = RPCGetMinerReportsRequestHandler::new();
+    assert!(http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .is_err());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let requests = vec![StacksHttpRequest::new_get_miner_reports(addr.into(), None)];
+    let mut responses = test_rpc(function_name!(), requests);
+
+    // the test peer is not a miner, so it has no tenure reports
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 400);
+}
+
+#[test]
+fn test_miner_reports_history() {
+    let mut reports = MinerReports::new(3);
+    assert!(reports.is_empty());
+    assert!(reports.latest(10).is_empty());
+
+    for burn_block_height in 1..=5 {
+        reports.record(make_report(burn_block_height));
+    }
+    assert_eq!(reports.len(), 3);
+
+    // newest first, and only the most recent reports are kept
+    let heights: Vec<_> = reports
+        .latest(10)
+        .iter()
+        .map(|report| report.burn_block_height)
+        .collect();
+    assert_eq!(heights, vec![5, 4, 3]);
+
+    let heights: Vec<_> = reports
+        .latest(2)
+        .iter()
+        .map(|report| report.burn_block_height)
+        .collect();
+    assert_eq!(heights, vec![5, 4]);
+}
@@ -0,0 +1,110 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use stacks_common::types::chainstate::ConsensusHash;
+
+use super::TestRPC;
+use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::stacks::TenureChangeCause;
+use crate::net::api::gettenurechanges::{RPCGetTenureChangesRequestHandler, TenureChangeInfo};
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+use crate::net::test::TestEventObserver;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request =
+        StacksHttpRequest::new_get_tenure_changes(addr.into(), &ConsensusHash([0x01; 20]));
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = RPCGetTenureChangesRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+    assert_eq!(handler.consensus_hash, Some(ConsensusHash([0x01; 20])));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.consensus_hash.is_none());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let test_observer = TestEventObserver::new();
+    let mut rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+    let consensus_hash = rpc_test.consensus_hash.clone();
+
+    let chainstate = rpc_test.peer_2.chainstate();
+    let events =
+        NakamotoChainState::get_nakamoto_tenure_changes(chainstate.db(), &consensus_hash).unwrap();
+    assert!(!events.is_empty());
+    let expected: Vec<_> = events
+        .into_iter()
+        .map(|event| {
+            let (block, _size) = chainstate
+                .nakamoto_blocks_db()
+                .get_nakamoto_block(&event.block_id)
+                .unwrap()
+                .unwrap();
+            TenureChangeInfo::from_block(event, &block).unwrap()
+        })
+        .collect();
+
+    // epoch2 tenures have no tenure changes
+    let all_sortitions = rpc_test.peer_1.sortdb().get_all_snapshots().unwrap();
+    assert!(all_sortitions[30].sortition);
+    let epoch2_consensus_hash = all_sortitions[30].consensus_hash.clone();
+
+    let requests = vec![
+        StacksHttpRequest::new_get_tenure_changes(addr.into(), &consensus_hash),
+        StacksHttpRequest::new_get_tenure_changes(addr.into(), &epoch2_consensus_hash),
+        StacksHttpRequest::new_get_tenure_changes(addr.into(), &ConsensusHash([0x01; 20])),
+    ];
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0).decode_tenure_changes().unwrap();
+    assert_eq!(response.consensus_hash, consensus_hash);
+    assert_eq!(response.changes, expected);
+    // the tenure starts with a block-found tenure change
+    assert_eq!(response.changes[0].cause, TenureChangeCause::BlockFound);
+    assert!(response
+        .changes
+        .windows(2)
+        .all(|pair| pair[0].block_height <= pair[1].block_height));
+
+    for _ in 0..2 {
+        let (preamble, _body) = responses.remove(0).destruct();
+        assert_eq!(preamble.status_code, 404);
+    }
+}
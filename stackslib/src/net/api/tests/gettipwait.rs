@@ -0,0 +1,134 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use stacks_common::types::chainstate::StacksBlockId;
+
+use super::TestRPC;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{EndpointClass, RPCRequestHandler, StacksHttp, StacksHttpRequest};
+use crate::net::test::TestEventObserver;
+use crate::net::{RPCHandlerArgs, StacksNodeState};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request =
+        StacksHttpRequest::new_get_tip_wait(addr.into(), &StacksBlockId([0x11; 32]), Some(10));
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = gettipwait::RPCGetTipWaitRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed request
+    parsed_request.clear_headers();
+    let (preamble, contents) = parsed_request.destruct();
+    assert_eq!(&preamble, request.preamble());
+    assert_eq!(
+        contents.get_query_arg("since"),
+        Some(&StacksBlockId([0x11; 32]).to_hex())
+    );
+    assert_eq!(contents.get_query_arg("timeout"), Some(&"10".to_string()));
+
+    assert_eq!(handler.endpoint_class(), EndpointClass::ReadHeavy);
+
+    handler.restart();
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let test_observer = TestEventObserver::new();
+    let rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+    let nakamoto_chain_tip = rpc_test.canonical_tip.clone();
+    let tip_height = rpc_test.tip_height;
+
+    let requests = vec![
+        // the caller is behind, so this is answered right away
+        StacksHttpRequest::new_get_tip_wait(addr.into(), &StacksBlockId([0x11; 32]), None),
+        // the caller is caught up, but is not willing to wait
+        StacksHttpRequest::new_get_tip_wait(addr.into(), &nakamoto_chain_tip, Some(0)),
+    ];
+
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    let resp = response.decode_tip_wait().unwrap();
+    assert!(resp.changed);
+    assert_eq!(resp.index_block_hash, nakamoto_chain_tip);
+    assert_eq!(resp.block_height, tip_height);
+
+    let response = responses.remove(0);
+    let resp = response.decode_tip_wait().unwrap();
+    assert!(!resp.changed);
+    assert_eq!(resp.index_block_hash, nakamoto_chain_tip);
+}
+
+#[test]
+fn test_defer_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let test_observer = TestEventObserver::new();
+    let mut rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+    let peer = &mut rpc_test.peer_2;
+    peer.refresh_burnchain_view();
+    let tip = peer.network.stacks_tip.block_id();
+    assert_eq!(tip, rpc_test.canonical_tip);
+
+    let sortdb = peer.sortdb.take().unwrap();
+    let mut stacks_node = peer.stacks_node.take().unwrap();
+    let mut mempool = peer.mempool.take().unwrap();
+    let rpc_args = RPCHandlerArgs::default();
+    let mut node_state = StacksNodeState::new(
+        &mut peer.network,
+        &sortdb,
+        &mut stacks_node.chainstate,
+        &mut mempool,
+        &rpc_args,
+        false,
+    );
+
+    // held while the tip is the one the caller knows about
+    let request = StacksHttpRequest::new_get_tip_wait(addr.into(), &tip, None);
+    assert!(http.should_defer_request(&request, &mut node_state));
+
+    // ...but not past its timeout
+    let request = StacksHttpRequest::new_get_tip_wait(addr.into(), &tip, Some(0));
+    assert!(!http.should_defer_request(&request, &mut node_state));
+
+    // answered right away if the caller is behind
+    let request =
+        StacksHttpRequest::new_get_tip_wait(addr.into(), &StacksBlockId([0x11; 32]), None);
+    assert!(!http.should_defer_request(&request, &mut node_state));
+
+    // other endpoints are never held
+    let request = StacksHttpRequest::new_get_health_live(addr.into());
+    assert!(!http.should_defer_request(&request, &mut node_state));
+}
@@ -43,7 +43,9 @@ use crate::chainstate::stacks::{
 use crate::core::MemPoolDB;
 use crate::net::api::{prefix_hex, prefix_opt_hex};
 use crate::net::db::PeerDB;
-use crate::net::httpcore::{StacksHttpRequest, StacksHttpResponse};
+use crate::net::httpcore::{
+    HttpRateLimitConfig, HttpRateLimiter, StacksHttpRequest, StacksHttpResponse,
+};
 use crate::net::relay::Relayer;
 use crate::net::rpc::ConversationHttp;
 use crate::net::test::{TestEventObserver, TestPeer, TestPeerConfig};
@@ -56,44 +58,85 @@ use crate::net::{
 mod callreadonly;
 mod get_tenures_fork_info;
 mod getaccount;
+mod getaccount_v3;
+mod getaffirmations;
+mod getarchive;
 mod getattachment;
 mod getattachmentsinv;
+mod getattachmentsrecent;
 mod getblock;
 mod getblock_v3;
 mod getblockbyheight;
+mod getblockstatediff;
+mod getblocksummary;
+mod getburnchainsyncstatus;
+mod getburnopreceipt;
+mod getburnops;
 mod getclaritymarfvalue;
 mod getclaritymetadata;
 mod getconstantval;
 mod getcontractabi;
+mod getcontractabi_v3;
+mod getcontractcosts;
 mod getcontractsrc;
+mod getdataproof;
 mod getdatavar;
 mod getheaders;
+mod gethealth;
 mod getinfo;
+mod getinfo_v3;
 mod getistraitimplemented;
+mod getloglevels;
 mod getmapentry;
 mod getmicroblocks_confirmed;
 mod getmicroblocks_indexed;
 mod getmicroblocks_unconfirmed;
+mod getminerfillstats;
+mod getminerreports;
+mod getminerutxos;
 mod getneighbors;
+mod getneighborstats;
+mod getpoxhistory;
 mod getpoxinfo;
 mod getsigner;
 mod getsortition;
 mod getstackerdbchunk;
 mod getstackerdbmetadata;
+mod getstackerspreview;
 mod getstxtransfercost;
 mod gettenure;
+mod gettenurebudget;
+mod gettenurechanges;
 mod gettenureinfo;
 mod gettenuretip;
+mod gettipwait;
 mod gettransaction_unconfirmed;
+mod gettransactionreceipt;
 mod liststackerdbreplicas;
+mod postbackup;
 mod postblock;
 mod postblock_proposal;
 mod postblock_v3;
+mod postconfigreload;
+mod postcontractanalyze;
 mod postfeerate;
+mod postloglevels;
+mod postmempooladmission;
+mod postmempooldrain;
+mod postmempooldraintofile;
+mod postmempoolimport;
 mod postmempoolquery;
 mod postmicroblock;
+mod postminingpause;
+mod postpeerban;
+mod postpoxanchoroverride;
+mod postreorgconfirm;
 mod poststackerdbchunk;
+mod poststackerdbreplica;
 mod posttransaction;
+mod posttransactionsimulate;
+mod postverifysignersignature;
+mod preflight;
 
 const TEST_CONTRACT: &str = "
     (define-trait test-trait
@@ -668,6 +711,7 @@ impl<'a> TestRPC<'a> {
                 .unwrap(),
             tx_id: Txid([0x22; 32]),
             canonical_stacks_tip_height: Some(1),
+            consensus_hash: Some(consensus_hash.clone()),
         };
 
         peer_1
@@ -986,7 +1030,12 @@ impl<'a> TestRPC<'a> {
                     &rpc_args,
                     false,
                 );
-                convo_1.chat(&mut node_state).unwrap();
+                convo_1
+                    .chat(
+                        &mut node_state,
+                        &mut HttpRateLimiter::new(HttpRateLimitConfig::default()),
+                    )
+                    .unwrap();
             }
 
             peer_1.sortdb = Some(peer_1_sortdb);
@@ -1030,7 +1079,12 @@ impl<'a> TestRPC<'a> {
                     &rpc_args,
                     false,
                 );
-                convo_2.chat(&mut node_state).unwrap();
+                convo_2
+                    .chat(
+                        &mut node_state,
+                        &mut HttpRateLimiter::new(HttpRateLimitConfig::default()),
+                    )
+                    .unwrap();
             }
 
             peer_2.sortdb = Some(peer_2_sortdb);
@@ -1086,7 +1140,12 @@ impl<'a> TestRPC<'a> {
                         false,
                     );
 
-                    convo_1.chat(&mut node_state).unwrap();
+                    convo_1
+                        .chat(
+                            &mut node_state,
+                            &mut HttpRateLimiter::new(HttpRateLimitConfig::default()),
+                        )
+                        .unwrap();
 
                     peer_1.sortdb = Some(peer_1_sortdb);
                     peer_1.stacks_node = Some(peer_1_stacks_node);
@@ -0,0 +1,137 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use super::{test_rpc, TestRPC};
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{AdminAuth, RPCRequestHandler, StacksHttp, StacksHttpRequest};
+use crate::util_lib::backup::{register_backup_service, unregister_backup_service, BackupRequests};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_post_backup(addr.into(), "password");
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler =
+        postbackup::RPCPostBackupRequestHandler::new(AdminAuth::new(Some("password"), &[]));
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    parsed_request.add_header("authorization".into(), "password".into());
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+
+    // wrong password
+    let request = StacksHttpRequest::new_post_backup(addr.into(), "nope");
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    match http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    ) {
+        Err(crate::net::Error::Http(crate::net::http::Error::Http(err_code, message))) => {
+            assert_eq!(err_code, 401);
+            assert_eq!(message, "Unauthorized");
+        }
+        x => {
+            error!("Expected HTTP 401, got {:?}", &x);
+            panic!("expected error");
+        }
+    }
+
+    // admin endpoints are disabled without an auth token
+    let mut handler = postbackup::RPCPostBackupRequestHandler::new(AdminAuth::default());
+    let request = StacksHttpRequest::new_post_backup(addr.into(), "password");
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    match http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    ) {
+        Err(crate::net::Error::Http(crate::net::http::Error::Http(err_code, _))) => {
+            assert_eq!(err_code, 400);
+        }
+        x => {
+            error!("Expected HTTP 400, got {:?}", &x);
+            panic!("expected error");
+        }
+    }
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    // no backup service is running
+    let mut responses = test_rpc(
+        function_name!(),
+        vec![StacksHttpRequest::new_post_backup(addr.into(), "password")],
+    );
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 404);
+
+    let rpc_test = TestRPC::setup(&format!("{}-enabled", function_name!()));
+    let root_paths = [
+        rpc_test.peer_1.chainstate_ref().root_path.clone(),
+        rpc_test.peer_2.chainstate_ref().root_path.clone(),
+    ];
+    let backups = Arc::new(BackupRequests::new());
+    for root_path in root_paths.iter() {
+        register_backup_service(root_path, backups.clone());
+    }
+
+    let mut responses = rpc_test.run(vec![
+        StacksHttpRequest::new_post_backup(addr.into(), "password"),
+        StacksHttpRequest::new_post_backup(addr.into(), "wrong password"),
+    ]);
+    for root_path in root_paths.iter() {
+        unregister_backup_service(root_path);
+    }
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let status = response.decode_backup_status().unwrap();
+    assert!(status.pending);
+    assert!(status.last_backup.is_none());
+    assert!(backups.status().pending);
+
+    let response = responses.remove(0);
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 401);
+}
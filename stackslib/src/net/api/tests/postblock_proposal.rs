@@ -26,7 +26,10 @@ use clarity::vm::ast::ASTRules;
 use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, StacksAddressExtensions};
 use clarity::vm::{ClarityName, ContractName, Value};
 use mempool::{MemPoolDB, MemPoolEventDispatcher, ProposalCallbackReceiver};
-use postblock_proposal::{NakamotoBlockProposal, ValidateRejectCode};
+use postblock_proposal::{
+    BlockValidateReject, BlockValidateRejectReason, NakamotoBlockProposal, ValidateRejectCode,
+    ValidateRejectDetail,
+};
 use stacks_common::bitvec::BitVec;
 use stacks_common::types::chainstate::{ConsensusHash, StacksAddress};
 use stacks_common::types::net::PeerHost;
@@ -50,12 +53,133 @@ use crate::core::BLOCK_LIMIT_MAINNET_21;
 use crate::net::api::*;
 use crate::net::connection::ConnectionOptions;
 use crate::net::httpcore::{
-    HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+    AdminAuth, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
 };
 use crate::net::relay::Relayer;
 use crate::net::test::TestEventObserver;
 use crate::net::{ProtocolFamily, TipRequest};
 
+#[test]
+fn test_precheck_transactions() {
+    let mut txs: Vec<StacksTransaction> = (0..8)
+        .map(|nonce| {
+            let privk = StacksPrivateKey::random();
+            let mut tx = StacksTransaction::new(
+                TransactionVersion::Testnet,
+                TransactionAuth::from_p2pkh(&privk).unwrap(),
+                TransactionPayload::TokenTransfer(
+                    StacksAddress::new(26, Hash160([0xff; 20])).unwrap().into(),
+                    123,
+                    TokenTransferMemo([0u8; 34]),
+                ),
+            );
+            tx.chain_id = 0x80000000;
+            tx.set_tx_fee(300);
+            tx.set_origin_nonce(nonce);
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+            tx_signer.sign_origin(&privk).unwrap();
+            tx_signer.get_tx().unwrap()
+        })
+        .collect();
+
+    // the result must not depend on how the transactions are split across workers
+    for num_workers in [1, 3, 16] {
+        NakamotoBlockProposal::precheck_transactions(
+            &txs,
+            false,
+            StacksEpochId::Epoch30,
+            ASTRules::PrecheckSize,
+            num_workers,
+        )
+        .unwrap();
+    }
+
+    // changing a signed transaction invalidates its signature.  The first bad transaction in
+    // block order is reported, even if a later one is checked by an earlier-finishing worker.
+    txs[5].set_tx_fee(301);
+    txs[2].set_tx_fee(301);
+    for num_workers in [1, 3, 16] {
+        let reject = NakamotoBlockProposal::precheck_transactions(
+            &txs,
+            false,
+            StacksEpochId::Epoch30,
+            ASTRules::PrecheckSize,
+            num_workers,
+        )
+        .unwrap_err();
+        assert_eq!(reject.reason_code, ValidateRejectCode::BadTransaction);
+        assert!(
+            reject.reason.starts_with("Error processing tx 2:"),
+            "{}",
+            reject.reason
+        );
+    }
+}
+
+#[test]
+fn test_reject_detail_from_chainstate_error() {
+    use crate::chainstate::stacks::Error as ChainError;
+
+    for (error, expected) in [
+        (
+            ChainError::BlockTooBigError,
+            Some(ValidateRejectDetail::CostExceeded),
+        ),
+        (
+            ChainError::BlockCostExceeded,
+            Some(ValidateRejectDetail::CostExceeded),
+        ),
+        (
+            ChainError::TransactionTooBigError(None),
+            Some(ValidateRejectDetail::CostExceeded),
+        ),
+        (
+            ChainError::ExpectedTenureChange,
+            Some(ValidateRejectDetail::TenureChangeInvalid),
+        ),
+        (
+            ChainError::NoRegisteredSigners(3),
+            Some(ValidateRejectDetail::SignerSetMismatch),
+        ),
+        (ChainError::NoSuchBlockError, None),
+    ] {
+        assert_eq!(
+            ValidateRejectDetail::from_chainstate_error(&error),
+            expected
+        );
+        let reason = BlockValidateRejectReason::from(error);
+        assert_eq!(reason.reason_code, ValidateRejectCode::ChainstateError);
+        assert_eq!(reason.reason_detail, expected);
+    }
+}
+
+#[test]
+fn test_reject_detail_is_optional() {
+    let reject = BlockValidateReject {
+        signer_signature_hash: Sha512Trunc256Sum([1; 32]),
+        reason: "tx 1 is a replay of tx 0".into(),
+        reason_code: ValidateRejectCode::BadTransaction,
+        reason_detail: Some(ValidateRejectDetail::ReplayedTransaction),
+    };
+    let json = serde_json::to_value(&reject).unwrap();
+    assert_eq!(json["reason_code"], "BadTransaction");
+    assert_eq!(json["reason_detail"], "ReplayedTransaction");
+
+    // a response from a node that does not send details still decodes
+    let mut legacy_json = json.clone();
+    legacy_json.as_object_mut().unwrap().remove("reason_detail");
+    let legacy: BlockValidateReject = serde_json::from_value(legacy_json).unwrap();
+    assert_eq!(legacy.reason_code, ValidateRejectCode::BadTransaction);
+    assert_eq!(legacy.reason_detail, None);
+
+    let reject = BlockValidateReject {
+        reason_detail: None,
+        ..reject
+    };
+    let json = serde_json::to_value(&reject).unwrap();
+    assert!(json.get("reason_detail").is_none());
+}
+
 #[test]
 fn test_try_parse_request() {
     let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
@@ -76,8 +200,10 @@ fn test_try_parse_request() {
     let bytes = request.try_serialize().unwrap();
 
     let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
-    let mut handler =
-        postblock_proposal::RPCBlockProposalRequestHandler::new(Some("password".into()));
+    let mut handler = postblock_proposal::RPCBlockProposalRequestHandler::new(AdminAuth::new(
+        Some("password"),
+        &[],
+    ));
 
     // missing authorization header
     let bad_request = http.handle_try_parse_request(
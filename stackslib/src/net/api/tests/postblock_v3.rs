@@ -28,7 +28,8 @@ use crate::core::BLOCK_LIMIT_MAINNET_21;
 use crate::net::api::*;
 use crate::net::connection::ConnectionOptions;
 use crate::net::httpcore::{
-    HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+    AdminAuth, AdminRole, AdminToken, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
+    StacksHttpRequest,
 };
 use crate::net::test::TestEventObserver;
 use crate::net::{ProtocolFamily, TipRequest};
@@ -44,7 +45,15 @@ fn parse_request() {
     let bytes = request.try_serialize().unwrap();
 
     let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
-    let mut handler = postblock_v3::RPCPostBlockRequestHandler::new(Some("12345".to_string()));
+    let auth = AdminAuth::new(
+        Some("12345"),
+        &[AdminToken {
+            name: "backups".into(),
+            token: "hunter2".into(),
+            roles: [AdminRole::Backup].into_iter().collect(),
+        }],
+    );
+    let mut handler = postblock_v3::RPCPostBlockRequestHandler::new(auth);
     let mut parsed_request = http
         .handle_try_parse_request(
             &mut handler,
@@ -161,6 +170,28 @@ fn parse_request() {
             panic!("expected error");
         }
     }
+
+    handler.restart();
+
+    // a token without the blocks role may not broadcast
+    let request = StacksHttpRequest::new_post_block_v3_broadcast(addr.into(), &block, "hunter2");
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let bad_response = http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    );
+    match bad_response {
+        Err(crate::net::Error::Http(crate::net::http::Error::Http(err_code, message))) => {
+            assert_eq!(err_code, 403);
+            assert_eq!(message, "Forbidden");
+        }
+        x => {
+            error!("Expected HTTP 403, got {:?}", &x);
+            panic!("expected error");
+        }
+    }
 }
 
 #[test]
@@ -0,0 +1,99 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::test_rpc;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    AdminAuth, AdminRole, AdminToken, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let auth = AdminAuth::new(
+        Some("password"),
+        &[AdminToken {
+            name: "ops".into(),
+            token: "hunter2".into(),
+            roles: [AdminRole::Mining].into_iter().collect(),
+        }],
+    );
+    let mut handler = postconfigreload::RPCPostConfigReloadRequestHandler::new(auth);
+    let request = StacksHttpRequest::new_post_config_reload(addr.into(), "password");
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    parsed_request.add_header("authorization".into(), "password".into());
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+
+    // a token without the config role
+    let request = StacksHttpRequest::new_post_config_reload(addr.into(), "hunter2");
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    match http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    ) {
+        Err(crate::net::Error::Http(crate::net::http::Error::Http(err_code, _))) => {
+            assert_eq!(err_code, 403);
+        }
+        x => {
+            error!("Expected HTTP 403, got {:?}", &x);
+            panic!("expected error");
+        }
+    }
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    // the test peers were not started from a config file
+    let mut responses = test_rpc(
+        function_name!(),
+        vec![
+            StacksHttpRequest::new_post_config_reload(addr.into(), "password"),
+            StacksHttpRequest::new_post_config_reload(addr.into(), "wrong password"),
+        ],
+    );
+
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 404);
+
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 401);
+}
@@ -0,0 +1,251 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clarity::vm::types::QualifiedContractIdentifier;
+use clarity::vm::ClarityVersion;
+use stacks_common::types::chainstate::StacksBlockId;
+
+use super::TestRPC;
+use crate::net::api::postcontractanalyze::{
+    ContractAnalyzeRequest, RPCPostContractAnalyzeRequestHandler,
+};
+use crate::net::connection::ConnectionOptions;
+use crate::net::http::HttpRequestContents;
+use crate::net::httpcore::{StacksHttp, StacksHttpRequest};
+use crate::net::test::TestEventObserver;
+use crate::net::TipRequest;
+use crate::util_lib::boot::boot_code_id;
+use crate::util_lib::strings::StacksString;
+
+fn make_analyze_request(
+    source: &str,
+    contract_identifier: QualifiedContractIdentifier,
+) -> ContractAnalyzeRequest {
+    ContractAnalyzeRequest {
+        source: StacksString::from_str(source).unwrap(),
+        clarity_version: None,
+        contract_identifier,
+    }
+}
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let mut analyze_request = make_analyze_request(
+        "(define-read-only (hello) u1)",
+        QualifiedContractIdentifier::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello")
+            .unwrap(),
+    );
+    analyze_request.clarity_version = Some(ClarityVersion::Clarity2);
+    let request = StacksHttpRequest::new_analyze_contract(
+        addr.into(),
+        analyze_request.clone(),
+        TipRequest::UseLatestAnchoredTip,
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = RPCPostContractAnalyzeRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, contents) = parsed_request.destruct();
+    assert_eq!(&preamble, request.preamble());
+    assert_eq!(
+        RPCPostContractAnalyzeRequestHandler::parse_request(&contents).unwrap(),
+        analyze_request
+    );
+
+    // the contract identifier defaults to the transient one
+    let request = StacksHttpRequest::new_for_peer(
+        addr.into(),
+        "POST".into(),
+        "/v3/contracts/analyze".into(),
+        HttpRequestContents::new().payload_json(serde_json::json!({
+            "source": "(define-read-only (hello) u1)",
+        })),
+    )
+    .unwrap();
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+    let parsed =
+        RPCPostContractAnalyzeRequestHandler::parse_request(parsed_request.contents()).unwrap();
+    assert_eq!(
+        parsed.contract_identifier,
+        QualifiedContractIdentifier::transient()
+    );
+    assert!(parsed.clarity_version.is_none());
+
+    // unknown Clarity versions are rejected
+    let bad_request = StacksHttpRequest::new_for_peer(
+        addr.into(),
+        "POST".into(),
+        "/v3/contracts/analyze".into(),
+        HttpRequestContents::new().payload_json(serde_json::json!({
+            "source": "(define-read-only (hello) u1)",
+            "clarity_version": 9,
+        })),
+    )
+    .unwrap();
+    let bytes = bad_request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    assert!(http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .is_err());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let test_observer = TestEventObserver::new();
+    let mut rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+    let mainnet = rpc_test.peer_2.chainstate().mainnet;
+    let nakamoto_chain_tip = rpc_test.canonical_tip.clone();
+    let contract_id =
+        QualifiedContractIdentifier::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello")
+            .unwrap();
+
+    let mut requests = vec![];
+
+    // valid contract
+    requests.push(StacksHttpRequest::new_analyze_contract(
+        addr.into(),
+        make_analyze_request(
+            "(define-trait greeter ((greet () (response uint uint))))
+             (define-public (greet) (ok u1))
+             (define-read-only (hello) u1)",
+            contract_id.clone(),
+        ),
+        TipRequest::SpecificTip(nakamoto_chain_tip.clone()),
+    ));
+
+    // type error
+    requests.push(StacksHttpRequest::new_analyze_contract(
+        addr.into(),
+        make_analyze_request("(define-read-only (hello) (+ u1 1))", contract_id.clone()),
+        TipRequest::SpecificTip(nakamoto_chain_tip.clone()),
+    ));
+
+    // runtime error while initializing
+    requests.push(StacksHttpRequest::new_analyze_contract(
+        addr.into(),
+        make_analyze_request("(define-data-var x uint (/ u1 u0))", contract_id.clone()),
+        TipRequest::SpecificTip(nakamoto_chain_tip.clone()),
+    ));
+
+    // already deployed
+    requests.push(StacksHttpRequest::new_analyze_contract(
+        addr.into(),
+        make_analyze_request(
+            "(define-read-only (hello) u1)",
+            boot_code_id("pox-4", mainnet),
+        ),
+        TipRequest::SpecificTip(nakamoto_chain_tip.clone()),
+    ));
+
+    // no such tip
+    requests.push(StacksHttpRequest::new_analyze_contract(
+        addr.into(),
+        make_analyze_request("(define-read-only (hello) u1)", contract_id.clone()),
+        TipRequest::SpecificTip(StacksBlockId([0x11; 32])),
+    ));
+
+    let mut responses = rpc_test.run(requests);
+
+    let resp = responses
+        .remove(0)
+        .decode_analyze_contract_response()
+        .unwrap();
+    assert!(resp.valid);
+    assert!(resp.error.is_none());
+    assert_eq!(resp.contract_identifier, contract_id.to_string());
+    assert_eq!(
+        resp.clarity_version,
+        ClarityVersion::default_for_epoch(resp.epoch)
+    );
+    assert_eq!(resp.index_block_hash, nakamoto_chain_tip);
+    assert_eq!(resp.defined_traits, vec!["greeter".to_string()]);
+    assert!(resp.implemented_traits.is_empty());
+    let interface = resp.interface.unwrap();
+    let mut function_names: Vec<_> = interface
+        .functions
+        .iter()
+        .map(|func| func.name.clone())
+        .collect();
+    function_names.sort();
+    assert_eq!(
+        function_names,
+        vec!["greet".to_string(), "hello".to_string()]
+    );
+    assert!(resp.analysis_cost.runtime > 0);
+    assert!(resp.initialization_cost.write_count > 0);
+    let mut total_cost = resp.analysis_cost.clone();
+    total_cost.add(&resp.initialization_cost).unwrap();
+    assert_eq!(resp.estimated_cost, total_cost);
+    assert!(resp.estimated_len > 0);
+
+    let resp = responses
+        .remove(0)
+        .decode_analyze_contract_response()
+        .unwrap();
+    assert!(!resp.valid);
+    let error = resp.error.unwrap();
+    assert_eq!(error.kind, "analysis");
+    assert!(error.diagnostic.is_some());
+    assert!(resp.interface.is_none());
+
+    let resp = responses
+        .remove(0)
+        .decode_analyze_contract_response()
+        .unwrap();
+    assert!(!resp.valid);
+    assert_eq!(resp.error.unwrap().kind, "initialization");
+    assert!(resp.interface.is_some());
+
+    let resp = responses
+        .remove(0)
+        .decode_analyze_contract_response()
+        .unwrap();
+    assert!(!resp.valid);
+    assert_eq!(resp.error.unwrap().kind, "duplicate_contract");
+
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 404);
+}
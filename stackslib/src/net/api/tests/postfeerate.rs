@@ -49,6 +49,7 @@ fn test_try_parse_request() {
         postfeerate::FeeRateEstimateRequestBody {
             estimated_len: Some(123),
             transaction_payload: to_hex(&tx_payload.serialize_to_vec()),
+            sponsored: false,
         },
     );
     let bytes = request.try_serialize().unwrap();
@@ -77,6 +78,32 @@ fn test_try_parse_request() {
     handler.restart();
     assert!(handler.estimated_len.is_none());
     assert!(handler.transaction_payload.is_none());
+
+    // a sponsored transaction's length includes the sponsor's spending condition
+    let request = StacksHttpRequest::new_post_fee_rate(
+        addr.into(),
+        postfeerate::FeeRateEstimateRequestBody {
+            estimated_len: None,
+            transaction_payload: to_hex(&tx_payload.serialize_to_vec()),
+            sponsored: true,
+        },
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    )
+    .unwrap();
+
+    assert_eq!(
+        handler.estimated_len,
+        Some(
+            tx_payload.serialize_to_vec().len() as u64
+                + postfeerate::SINGLESIG_SPONSOR_SPENDING_CONDITION_LEN
+        )
+    );
 }
 
 #[test]
@@ -95,6 +122,7 @@ fn test_try_make_response() {
         postfeerate::FeeRateEstimateRequestBody {
             estimated_len: Some(123),
             transaction_payload: to_hex(&tx_payload.serialize_to_vec()),
+            sponsored: false,
         },
     );
     requests.push(request);
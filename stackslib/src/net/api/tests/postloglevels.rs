@@ -0,0 +1,134 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use stacks_common::util::log::{
+    get_component_loglevels, parse_component_loglevels, set_component_loglevels,
+};
+
+use super::test_rpc;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{AdminAuth, RPCRequestHandler, StacksHttp, StacksHttpRequest};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_post_log_levels(
+        addr.into(),
+        "net=debug,chainstate=info,miner=trace",
+        "password",
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler =
+        postloglevels::RPCPostLogLevelsRequestHandler::new(AdminAuth::new(Some("password"), &[]));
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(
+        handler.log_levels,
+        Some(parse_component_loglevels("net=debug,chainstate=info,miner=trace").unwrap())
+    );
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    parsed_request.add_header("authorization".into(), "password".into());
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.log_levels.is_none());
+
+    // invalid level
+    let request = StacksHttpRequest::new_post_log_levels(addr.into(), "net=loud", "password");
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    match http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    ) {
+        Err(crate::net::Error::Http(crate::net::http::Error::DecodeError(..))) => {}
+        x => {
+            error!("Expected decode error, got {:?}", &x);
+            panic!("expected error");
+        }
+    }
+
+    // wrong password
+    let request = StacksHttpRequest::new_post_log_levels(addr.into(), "net=debug", "nope");
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    match http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    ) {
+        Err(crate::net::Error::Http(crate::net::http::Error::Http(err_code, message))) => {
+            assert_eq!(err_code, 401);
+            assert_eq!(message, "Unauthorized");
+        }
+        x => {
+            error!("Expected HTTP 401, got {:?}", &x);
+            panic!("expected error");
+        }
+    }
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let old_levels = get_component_loglevels();
+
+    let mut requests = vec![];
+    requests.push(StacksHttpRequest::new_post_log_levels(
+        addr.into(),
+        "net=debug,chainstate=info,net::p2p=trace",
+        "password",
+    ));
+    requests.push(StacksHttpRequest::new_post_log_levels(
+        addr.into(),
+        "net=debug",
+        "wrong password",
+    ));
+
+    let mut responses = test_rpc(function_name!(), requests);
+    set_component_loglevels(old_levels);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let data = response.decode_log_levels().unwrap();
+    assert_eq!(data.log_levels, "net=debug,chainstate=info,net::p2p=trace");
+
+    let response = responses.remove(0);
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 401);
+}
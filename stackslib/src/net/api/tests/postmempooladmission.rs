@@ -0,0 +1,99 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::TestRPC;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{AdminAuth, RPCRequestHandler, StacksHttp, StacksHttpRequest};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let mut handler = postmempooladmission::RPCPostMempoolAdmissionRequestHandler::new(
+        AdminAuth::new(Some("password"), &[]),
+    );
+    for pause in [true, false] {
+        let request = StacksHttpRequest::new_post_mempool_admission(addr.into(), pause, "password");
+        let bytes = request.try_serialize().unwrap();
+
+        debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+        let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+        let mut parsed_request = http
+            .handle_try_parse_request(
+                &mut handler,
+                &parsed_preamble.expect_request(),
+                &bytes[offset..],
+            )
+            .unwrap();
+        assert_eq!(handler.pause, Some(pause));
+
+        // parsed request consumes headers that would not be in a constructed reqeuest
+        parsed_request.clear_headers();
+        parsed_request.add_header("authorization".into(), "password".into());
+        let (preamble, _contents) = parsed_request.destruct();
+
+        assert_eq!(&preamble, request.preamble());
+
+        handler.restart();
+        assert!(handler.pause.is_none());
+    }
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let sendable_txs = rpc_test.sendable_txs.clone();
+
+    let mut responses = rpc_test.run(vec![
+        StacksHttpRequest::new_post_mempool_admission(addr.into(), true, "password"),
+        StacksHttpRequest::new_post_transaction(addr.into(), sendable_txs[0].clone()),
+        StacksHttpRequest::new_post_mempool_admission(addr.into(), false, "password"),
+        StacksHttpRequest::new_post_transaction(addr.into(), sendable_txs[0].clone()),
+        StacksHttpRequest::new_post_mempool_admission(addr.into(), true, "wrong password"),
+    ]);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    assert!(response.decode_mempool_admission().unwrap().paused);
+
+    // refused while admission is paused
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 503);
+
+    assert!(
+        !responses
+            .remove(0)
+            .decode_mempool_admission()
+            .unwrap()
+            .paused
+    );
+
+    // accepted once it resumes
+    let txid = responses.remove(0).decode_txid().unwrap();
+    assert_eq!(txid, sendable_txs[0].txid());
+
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 401);
+}
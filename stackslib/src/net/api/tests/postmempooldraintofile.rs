@@ -0,0 +1,126 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::TestRPC;
+use crate::core::mempool::MemPoolDB;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    AdminAuth, AdminRole, AdminToken, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_post_mempool_drain_to_file(addr.into(), "password");
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let auth = AdminAuth::new(
+        Some("password"),
+        &[AdminToken {
+            name: "backups".into(),
+            token: "hunter2".into(),
+            roles: [AdminRole::Backup].into_iter().collect(),
+        }],
+    );
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = postmempooldraintofile::RPCPostMempoolDrainToFileRequestHandler::new(auth);
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    parsed_request.add_header("authorization".into(), "password".into());
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+
+    // a token without the mempool role
+    let request = StacksHttpRequest::new_post_mempool_drain_to_file(addr.into(), "hunter2");
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    match http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    ) {
+        Err(crate::net::Error::Http(crate::net::http::Error::Http(err_code, message))) => {
+            assert_eq!(err_code, 403);
+            assert_eq!(message, "Forbidden");
+        }
+        x => {
+            error!("Expected HTTP 403, got {:?}", &x);
+            panic!("expected error");
+        }
+    }
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let mempool_txids = rpc_test.mempool_txids.clone();
+    let num_mempool_txs = u64::try_from(mempool_txids.len()).unwrap();
+    assert!(num_mempool_txs > 0);
+
+    // peer 2 serves the requests
+    let drain_dir = rpc_test.peer_2.mempool.as_ref().unwrap().drain_dir();
+
+    let mut responses = rpc_test.run(vec![
+        StacksHttpRequest::new_post_mempool_drain_to_file(addr.into(), "password"),
+        StacksHttpRequest::new_post_mempool_drain_to_file(addr.into(), "password"),
+        StacksHttpRequest::new_post_mempool_drain_to_file(addr.into(), "wrong password"),
+    ]);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let drained = response.decode_mempool_drain_to_file().unwrap();
+    assert_eq!(drained.dropped, num_mempool_txs);
+    assert!(drained.file.starts_with("drain-"));
+
+    // the file holds every dropped transaction
+    let saved_txids: Vec<_> = MemPoolDB::load_drained_txs(&drain_dir.join(&drained.file))
+        .unwrap()
+        .iter()
+        .map(|tx| tx.txid())
+        .collect();
+    assert_eq!(saved_txids.len(), mempool_txids.len());
+    assert!(saved_txids.iter().all(|txid| mempool_txids.contains(txid)));
+
+    // nothing left to drain, but each drain gets its own file
+    let drained_again = responses.remove(0).decode_mempool_drain_to_file().unwrap();
+    assert_eq!(drained_again.dropped, 0);
+    assert_ne!(drained_again.file, drained.file);
+
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 401);
+}
@@ -0,0 +1,123 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::TestRPC;
+use crate::core::mempool::MemPoolDB;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{AdminAuth, RPCRequestHandler, StacksHttp, StacksHttpRequest};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let mut handler = postmempoolimport::RPCPostMempoolImportRequestHandler::new(AdminAuth::new(
+        Some("password"),
+        &[],
+    ));
+
+    let request =
+        StacksHttpRequest::new_post_mempool_import(addr.into(), "drain-1234.txs", "password");
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+    assert_eq!(handler.file, Some("drain-1234.txs".to_string()));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    parsed_request.add_header("authorization".into(), "password".into());
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.file.is_none());
+
+    // only files in the drain directory can be imported
+    for file in ["", "../mempool.sqlite", "/etc/passwd", ".hidden"] {
+        let request = StacksHttpRequest::new_post_mempool_import(addr.into(), file, "password");
+        let bytes = request.try_serialize().unwrap();
+        let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+        assert!(http
+            .handle_try_parse_request(
+                &mut handler,
+                &parsed_preamble.expect_request(),
+                &bytes[offset..],
+            )
+            .is_err());
+    }
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let mut rpc_test = TestRPC::setup(function_name!());
+
+    // peer 2 serves the requests
+    let mempool = rpc_test.peer_2.mempool.as_mut().unwrap();
+    let (drained, save_path) = mempool.drain_to_file(None).unwrap();
+    let file = save_path.file_name().unwrap().to_str().unwrap().to_string();
+    assert_eq!(drained.len(), rpc_test.mempool_txids.len());
+    let saved_txids: Vec<_> = MemPoolDB::load_drained_txs(&save_path)
+        .unwrap()
+        .iter()
+        .map(|tx| tx.txid())
+        .collect();
+    assert_eq!(saved_txids.len(), drained.len());
+    assert!(saved_txids.iter().all(|txid| drained.contains(txid)));
+    let num_drained = u64::try_from(drained.len()).unwrap();
+
+    let mut responses = rpc_test.run(vec![
+        StacksHttpRequest::new_post_mempool_import(addr.into(), &file, "password"),
+        StacksHttpRequest::new_post_mempool_import(addr.into(), &file, "password"),
+        StacksHttpRequest::new_post_mempool_import(addr.into(), "drain-missing.txs", "password"),
+        StacksHttpRequest::new_post_mempool_import(addr.into(), &file, "wrong password"),
+    ]);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let imported = response.decode_mempool_import().unwrap();
+    assert_eq!(imported.file, file);
+    assert_eq!(imported.already_present, 0);
+    assert_eq!(imported.imported + imported.rejected, num_drained);
+    assert!(imported.imported > 0);
+
+    // importing the file again finds the imported transactions already present
+    let reimported = responses.remove(0).decode_mempool_import().unwrap();
+    assert_eq!(reimported.imported, 0);
+    assert_eq!(reimported.already_present, imported.imported);
+
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 404);
+
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 401);
+}
@@ -0,0 +1,204 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::test_rpc;
+use crate::burnchains::db::{AnchorBlockOverrideSource, AnchorBlockOverrideStatus};
+use crate::net::api::postpoxanchoroverride::{
+    AnchorBlockOverrideAction, RPCPostPoxAnchorOverrideRequestHandler,
+};
+use crate::net::connection::ConnectionOptions;
+use crate::net::http::HttpRequestContents;
+use crate::net::httpcore::{AdminAuth, RPCRequestHandler, StacksHttp, StacksHttpRequest};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_post_pox_anchor_override(
+        addr.into(),
+        413,
+        AnchorBlockOverrideAction::Reject,
+        "password",
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler =
+        RPCPostPoxAnchorOverrideRequestHandler::new(AdminAuth::new(Some("password"), &[]));
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+    assert_eq!(handler.reward_cycle, Some(413));
+    assert_eq!(handler.action, Some(AnchorBlockOverrideAction::Reject));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    parsed_request.add_header("authorization".into(), "password".into());
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.reward_cycle.is_none());
+    assert!(handler.action.is_none());
+
+    // reward cycle 0 has no anchor block, and unknown statuses are rejected
+    for (path, body) in [
+        (
+            "/v3/admin/pox/anchor_blocks/0/override",
+            serde_json::json!({"status": "accept"}),
+        ),
+        (
+            "/v3/admin/pox/anchor_blocks/413/override",
+            serde_json::json!({"status": "present"}),
+        ),
+    ] {
+        let mut request = StacksHttpRequest::new_for_peer(
+            addr.into(),
+            "POST".into(),
+            path.into(),
+            HttpRequestContents::new().payload_json(body),
+        )
+        .unwrap();
+        request.add_header("authorization".into(), "password".into());
+        let bytes = request.try_serialize().unwrap();
+        let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+        assert!(http
+            .handle_try_parse_request(
+                &mut handler,
+                &parsed_preamble.expect_request(),
+                &bytes[offset..],
+            )
+            .is_err());
+    }
+
+    // wrong password
+    let request = StacksHttpRequest::new_post_pox_anchor_override(
+        addr.into(),
+        413,
+        AnchorBlockOverrideAction::Reject,
+        "nope",
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    match http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    ) {
+        Err(crate::net::Error::Http(crate::net::http::Error::Http(err_code, message))) => {
+            assert_eq!(err_code, 401);
+            assert_eq!(message, "Unauthorized");
+        }
+        x => {
+            error!("Expected HTTP 401, got {:?}", &x);
+            panic!("expected error");
+        }
+    }
+
+    // admin endpoints are disabled without an auth token
+    let mut handler = RPCPostPoxAnchorOverrideRequestHandler::new(AdminAuth::default());
+    let request = StacksHttpRequest::new_post_pox_anchor_override(
+        addr.into(),
+        413,
+        AnchorBlockOverrideAction::Reject,
+        "password",
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    match http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    ) {
+        Err(crate::net::Error::Http(crate::net::http::Error::Http(err_code, _))) => {
+            assert_eq!(err_code, 400);
+        }
+        x => {
+            error!("Expected HTTP 400, got {:?}", &x);
+            panic!("expected error");
+        }
+    }
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let mut responses = test_rpc(
+        function_name!(),
+        vec![
+            StacksHttpRequest::new_post_pox_anchor_override(
+                addr.into(),
+                413,
+                AnchorBlockOverrideAction::Reject,
+                "password",
+            ),
+            StacksHttpRequest::new_post_pox_anchor_override(
+                addr.into(),
+                413,
+                AnchorBlockOverrideAction::Accept,
+                "password",
+            ),
+            StacksHttpRequest::new_post_pox_anchor_override(
+                addr.into(),
+                413,
+                AnchorBlockOverrideAction::Clear,
+                "password",
+            ),
+            StacksHttpRequest::new_post_pox_anchor_override(
+                addr.into(),
+                413,
+                AnchorBlockOverrideAction::Clear,
+                "wrong password",
+            ),
+        ],
+    );
+
+    let resp = responses.remove(0).decode_pox_anchor_override().unwrap();
+    assert_eq!(resp.reward_cycle, 413);
+    assert!(resp.prior.is_none());
+    assert_eq!(resp.overrides.len(), 1);
+    assert_eq!(resp.overrides[0].reward_cycle, 413);
+    assert_eq!(resp.overrides[0].status, AnchorBlockOverrideStatus::Reject);
+    assert_eq!(resp.overrides[0].source, AnchorBlockOverrideSource::Rpc);
+
+    let resp = responses.remove(0).decode_pox_anchor_override().unwrap();
+    assert_eq!(
+        resp.prior.unwrap().status,
+        AnchorBlockOverrideStatus::Reject
+    );
+    assert_eq!(resp.overrides.len(), 1);
+    assert_eq!(resp.overrides[0].status, AnchorBlockOverrideStatus::Accept);
+
+    let resp = responses.remove(0).decode_pox_anchor_override().unwrap();
+    assert_eq!(
+        resp.prior.unwrap().status,
+        AnchorBlockOverrideStatus::Accept
+    );
+    assert!(resp.overrides.is_empty());
+
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 401);
+}
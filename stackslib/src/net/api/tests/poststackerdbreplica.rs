@@ -0,0 +1,200 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clarity::vm::types::QualifiedContractIdentifier;
+use libstackerdb::{SlotMetadata, StackerDBChunkData};
+use stacks_common::util::hash::Sha512Trunc256Sum;
+use stacks_common::util::secp256k1::MessageSignature;
+
+use super::TestRPC;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{AdminAuth, RPCRequestHandler, StacksHttp, StacksHttpRequest};
+
+fn hello_world_contract() -> QualifiedContractIdentifier {
+    QualifiedContractIdentifier::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world")
+        .unwrap()
+}
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let chunk = StackerDBChunkData {
+        slot_id: 0,
+        slot_version: 1,
+        sig: MessageSignature::empty(),
+        data: vec![0, 1, 2, 3, 4],
+    };
+    let request = StacksHttpRequest::new_post_stackerdb_replica(
+        addr.into(),
+        &hello_world_contract(),
+        &chunk,
+        "password",
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = poststackerdbreplica::RPCPostStackerDBReplicaRequestHandler::new(
+        AdminAuth::new(Some("password"), &[]),
+    );
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(
+        handler.chunk_handler.contract_identifier,
+        Some(hello_world_contract())
+    );
+    assert_eq!(handler.chunk_handler.chunk, Some(chunk.clone()));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    parsed_request.add_header("authorization".into(), "password".into());
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.chunk_handler.contract_identifier.is_none());
+    assert!(handler.chunk_handler.chunk.is_none());
+
+    // wrong password
+    let request = StacksHttpRequest::new_post_stackerdb_replica(
+        addr.into(),
+        &hello_world_contract(),
+        &chunk,
+        "nope",
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    match http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    ) {
+        Err(crate::net::Error::Http(crate::net::http::Error::Http(err_code, message))) => {
+            assert_eq!(err_code, 401);
+            assert_eq!(message, "Unauthorized");
+        }
+        x => {
+            error!("Expected HTTP 401, got {:?}", &x);
+            panic!("expected error");
+        }
+    }
+
+    // admin endpoints are disabled without an auth token
+    let mut handler =
+        poststackerdbreplica::RPCPostStackerDBReplicaRequestHandler::new(AdminAuth::default());
+    let request = StacksHttpRequest::new_post_stackerdb_replica(
+        addr.into(),
+        &hello_world_contract(),
+        &chunk,
+        "password",
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    match http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    ) {
+        Err(crate::net::Error::Http(crate::net::http::Error::Http(err_code, _message))) => {
+            assert_eq!(err_code, 400);
+        }
+        x => {
+            error!("Expected HTTP 400, got {:?}", &x);
+            panic!("expected error");
+        }
+    }
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let mut requests = vec![];
+
+    // replicate a new chunk
+    let data = "replicated chunk".as_bytes();
+    let mut slot_metadata = SlotMetadata::new_unsigned(1, 1, Sha512Trunc256Sum::from_data(data));
+    slot_metadata.sign(&rpc_test.privk1).unwrap();
+    let chunk = StackerDBChunkData {
+        slot_id: slot_metadata.slot_id,
+        slot_version: slot_metadata.slot_version,
+        sig: slot_metadata.signature.clone(),
+        data: data.to_vec(),
+    };
+    requests.push(StacksHttpRequest::new_post_stackerdb_replica(
+        addr.into(),
+        &hello_world_contract(),
+        &chunk,
+        "password",
+    ));
+
+    // replicating it again is harmless, but not accepted
+    requests.push(StacksHttpRequest::new_post_stackerdb_replica(
+        addr.into(),
+        &hello_world_contract(),
+        &chunk,
+        "password",
+    ));
+
+    // replicas signed by the wrong key are rejected, just like public writes
+    let data = "forged chunk".as_bytes();
+    let mut slot_metadata = SlotMetadata::new_unsigned(1, 2, Sha512Trunc256Sum::from_data(data));
+    slot_metadata.sign(&rpc_test.privk2).unwrap();
+    requests.push(StacksHttpRequest::new_post_stackerdb_replica(
+        addr.into(),
+        &hello_world_contract(),
+        &StackerDBChunkData {
+            slot_id: slot_metadata.slot_id,
+            slot_version: slot_metadata.slot_version,
+            sig: slot_metadata.signature.clone(),
+            data: data.to_vec(),
+        },
+        "password",
+    ));
+
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let resp = response.decode_stackerdb_chunk_ack().unwrap();
+    assert!(resp.accepted);
+    assert_eq!(resp.metadata.as_ref().unwrap().slot_id, 1);
+    assert_eq!(resp.metadata.as_ref().unwrap().slot_version, 1);
+
+    let resp = responses.remove(0).decode_stackerdb_chunk_ack().unwrap();
+    assert!(!resp.accepted);
+    assert_eq!(resp.metadata.as_ref().unwrap().slot_version, 1);
+
+    let resp = responses.remove(0).decode_stackerdb_chunk_ack().unwrap();
+    assert!(!resp.accepted);
+    assert!(resp.reason.is_some());
+}
@@ -0,0 +1,416 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clarity::vm::types::StacksAddressExtensions;
+use stacks_common::address::{
+    AddressHashMode, C32_ADDRESS_VERSION_MAINNET_SINGLESIG, C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+};
+use stacks_common::types::chainstate::{StacksAddress, StacksPrivateKey, StacksPublicKey};
+
+use super::TestRPC;
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::chainstate::stacks::{
+    StacksTransaction, StacksTransactionSigner, TokenTransferMemo, TransactionAuth,
+    TransactionPayload, TransactionVersion,
+};
+use crate::net::api::posttransactionsimulate::RPCPostTransactionSimulateRequestHandler;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    AdminAuth, AdminRole, AdminToken, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+};
+use crate::net::test::TestEventObserver;
+use crate::net::TipRequest;
+
+fn make_stx_transfer(
+    auth_privk: &StacksPrivateKey,
+    recipient: &StacksAddress,
+    nonce: u64,
+    sign: bool,
+) -> StacksTransaction {
+    let mut tx = StacksTransaction::new(
+        TransactionVersion::Testnet,
+        TransactionAuth::from_p2pkh(auth_privk).unwrap(),
+        TransactionPayload::TokenTransfer(
+            recipient.to_account_principal(),
+            123,
+            TokenTransferMemo([0u8; 34]),
+        ),
+    );
+    tx.chain_id = 0x80000000;
+    tx.set_tx_fee(1000);
+    tx.set_origin_nonce(nonce);
+    if !sign {
+        return tx;
+    }
+    let mut tx_signer = StacksTransactionSigner::new(&tx);
+    tx_signer.sign_origin(auth_privk).unwrap();
+    tx_signer.get_tx().unwrap()
+}
+
+fn p2pkh_testnet(privk: &StacksPrivateKey) -> StacksAddress {
+    StacksAddress::from_public_keys(
+        C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+        &AddressHashMode::SerializeP2PKH,
+        1,
+        &vec![StacksPublicKey::from_private(privk)],
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let privk = StacksPrivateKey::random();
+    let sender = p2pkh_testnet(&StacksPrivateKey::random());
+    let recipient = p2pkh_testnet(&StacksPrivateKey::random());
+    let tx = make_stx_transfer(&privk, &recipient, 3, false);
+
+    let request = StacksHttpRequest::new_simulate_transaction(
+        addr.into(),
+        &tx,
+        Some(&sender),
+        TipRequest::UseLatestAnchoredTip,
+        "password",
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let auth = AdminAuth::new(
+        Some("password"),
+        &[AdminToken {
+            name: "signer".into(),
+            token: "hunter2".into(),
+            roles: [AdminRole::Blocks].into_iter().collect(),
+        }],
+    );
+    let mut handler = RPCPostTransactionSimulateRequestHandler::new(auth);
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // the origin is replaced by the sender, and its nonce and fee are kept
+    let parsed_tx = handler.tx.clone().unwrap();
+    assert_eq!(parsed_tx.origin_address(), sender);
+    assert_eq!(parsed_tx.get_origin_nonce(), 3);
+    assert_eq!(parsed_tx.get_tx_fee(), 1000);
+    assert_eq!(parsed_tx.payload, tx.payload);
+    assert!(!handler.check_signatures);
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    parsed_request.add_header("authorization".into(), "password".into());
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.tx.is_none());
+    assert!(handler.check_signatures);
+
+    // without a sender, the transaction is taken as-is and its signatures are checked
+    let request = StacksHttpRequest::new_simulate_transaction(
+        addr.into(),
+        &tx,
+        None,
+        TipRequest::UseLatestAnchoredTip,
+        "password",
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    )
+    .unwrap();
+    assert_eq!(handler.tx, Some(tx.clone()));
+    assert!(handler.check_signatures);
+    handler.restart();
+
+    // the sender must be on the transaction's network
+    let mainnet_sender = StacksAddress::new(
+        C32_ADDRESS_VERSION_MAINNET_SINGLESIG,
+        sender.bytes().clone(),
+    )
+    .unwrap();
+    let request = StacksHttpRequest::new_simulate_transaction(
+        addr.into(),
+        &tx,
+        Some(&mainnet_sender),
+        TipRequest::UseLatestAnchoredTip,
+        "password",
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    assert!(http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .is_err());
+
+    // a token without the simulation role
+    let request = StacksHttpRequest::new_simulate_transaction(
+        addr.into(),
+        &tx,
+        None,
+        TipRequest::UseLatestAnchoredTip,
+        "hunter2",
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    match http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    ) {
+        Err(crate::net::Error::Http(crate::net::http::Error::Http(err_code, _))) => {
+            assert_eq!(err_code, 403);
+        }
+        x => {
+            error!("Expected HTTP 403, got {:?}", &x);
+            panic!("expected error");
+        }
+    }
+
+    // admin endpoints are disabled without an auth token
+    let mut handler = RPCPostTransactionSimulateRequestHandler::new(AdminAuth::default());
+    let request = StacksHttpRequest::new_simulate_transaction(
+        addr.into(),
+        &tx,
+        None,
+        TipRequest::UseLatestAnchoredTip,
+        "password",
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    match http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    ) {
+        Err(crate::net::Error::Http(crate::net::http::Error::Http(err_code, _))) => {
+            assert_eq!(err_code, 400);
+        }
+        x => {
+            error!("Expected HTTP 400, got {:?}", &x);
+            panic!("expected error");
+        }
+    }
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let mut rpc_test = TestRPC::setup(function_name!());
+    let tip = rpc_test.canonical_tip.clone();
+    let privk1 = rpc_test.privk1.clone();
+    let addr1 = p2pkh_testnet(&privk1);
+    let recipient = p2pkh_testnet(&StacksPrivateKey::random());
+
+    let account = rpc_test
+        .peer_1
+        .with_dbs(|_peer, sortdb, stacks_node, _mempool| {
+            let burn_dbconn = sortdb
+                .index_handle_at_block(&stacks_node.chainstate, &tip)
+                .unwrap();
+            stacks_node
+                .chainstate
+                .with_read_only_clarity_tx(&burn_dbconn, &tip, |clarity_conn| {
+                    StacksChainState::get_account(clarity_conn, &addr1.to_account_principal())
+                })
+                .unwrap()
+        });
+    let nonce = account.nonce;
+    let balance = account.stx_balance.amount_unlocked();
+
+    let signed_tx = make_stx_transfer(&privk1, &recipient, nonce, true);
+    let unsigned_tx = make_stx_transfer(&StacksPrivateKey::random(), &recipient, nonce, false);
+    let bad_nonce_tx = make_stx_transfer(&privk1, &recipient, nonce + 5, true);
+
+    let tip_req = TipRequest::SpecificTip(tip.clone());
+    let requests = vec![
+        // simulating the same transaction twice gives the same answer, since nothing is stored
+        StacksHttpRequest::new_simulate_transaction(
+            addr.into(),
+            &signed_tx,
+            None,
+            tip_req.clone(),
+            "password",
+        ),
+        StacksHttpRequest::new_simulate_transaction(
+            addr.into(),
+            &signed_tx,
+            None,
+            tip_req.clone(),
+            "password",
+        ),
+        // unsigned, but spending from addr1
+        StacksHttpRequest::new_simulate_transaction(
+            addr.into(),
+            &unsigned_tx,
+            Some(&addr1),
+            tip_req.clone(),
+            "password",
+        ),
+        // unsigned, and without a sender
+        StacksHttpRequest::new_simulate_transaction(
+            addr.into(),
+            &unsigned_tx,
+            None,
+            tip_req.clone(),
+            "password",
+        ),
+        // could not be mined at this tip
+        StacksHttpRequest::new_simulate_transaction(
+            addr.into(),
+            &bad_nonce_tx,
+            None,
+            tip_req.clone(),
+            "password",
+        ),
+        StacksHttpRequest::new_simulate_transaction(
+            addr.into(),
+            &signed_tx,
+            None,
+            tip_req,
+            "wrong password",
+        ),
+    ];
+    let mut responses = rpc_test.run(requests);
+
+    let resp = responses
+        .remove(0)
+        .decode_simulate_transaction_response()
+        .unwrap();
+    assert_eq!(resp.txid, signed_tx.txid());
+    assert_eq!(resp.index_block_hash, tip);
+    // (ok true)
+    assert_eq!(resp.result, "0x0703");
+    assert!(resp.vm_error.is_none());
+    assert!(!resp.post_condition_aborted);
+    assert_eq!(resp.fee, 1000);
+    assert_eq!(resp.events.len(), 1);
+    assert_eq!(resp.events[0]["type"], "stx_transfer_event");
+    assert_eq!(resp.events[0]["stx_transfer_event"]["amount"], "123");
+
+    assert_eq!(resp.account_diffs.len(), 2);
+    let origin_diff = &resp.account_diffs[0];
+    assert_eq!(origin_diff.principal, addr1.to_string());
+    assert_eq!(origin_diff.before.nonce, nonce);
+    assert_eq!(origin_diff.after.nonce, nonce + 1);
+    assert_eq!(origin_diff.before.balance, balance);
+    assert_eq!(origin_diff.after.balance, balance - 123 - 1000);
+    let recipient_diff = &resp.account_diffs[1];
+    assert_eq!(recipient_diff.principal, recipient.to_string());
+    assert_eq!(recipient_diff.before.balance, 0);
+    assert_eq!(recipient_diff.after.balance, 123);
+
+    let resp_again = responses
+        .remove(0)
+        .decode_simulate_transaction_response()
+        .unwrap();
+    assert_eq!(resp_again, resp);
+
+    let resp = responses
+        .remove(0)
+        .decode_simulate_transaction_response()
+        .unwrap();
+    assert_eq!(resp.result, "0x0703");
+    assert_eq!(resp.account_diffs[0].principal, addr1.to_string());
+    assert_eq!(resp.account_diffs[0].after.nonce, nonce + 1);
+    assert_eq!(resp.account_diffs[0].after.balance, balance - 123 - 1000);
+
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 400);
+
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 400);
+
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 401);
+}
+
+#[test]
+fn test_try_make_response_nakamoto() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let test_observer = TestEventObserver::new();
+    let rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+    let tip = rpc_test.canonical_tip.clone();
+    let sender = p2pkh_testnet(&StacksPrivateKey::random());
+
+    // a fee-less deploy from a fresh account, so the sender needs no balance
+    let mut deploy_tx = StacksTransaction::new(
+        TransactionVersion::Testnet,
+        TransactionAuth::from_p2pkh(&StacksPrivateKey::random()).unwrap(),
+        TransactionPayload::new_smart_contract("hello", "(define-read-only (hello) u1)", None)
+            .unwrap(),
+    );
+    deploy_tx.chain_id = 0x80000000;
+    deploy_tx.set_origin_nonce(0);
+
+    let tip_req = TipRequest::SpecificTip(tip.clone());
+    let requests = vec![
+        StacksHttpRequest::new_simulate_transaction(
+            addr.into(),
+            &deploy_tx,
+            Some(&sender),
+            tip_req.clone(),
+            "password",
+        ),
+        // the first deploy was rolled back, so this one does not collide with it
+        StacksHttpRequest::new_simulate_transaction(
+            addr.into(),
+            &deploy_tx,
+            Some(&sender),
+            tip_req,
+            "password",
+        ),
+    ];
+    let mut responses = rpc_test.run(requests);
+
+    let resp = responses
+        .remove(0)
+        .decode_simulate_transaction_response()
+        .unwrap();
+    assert_eq!(resp.index_block_hash, tip);
+    // (ok true)
+    assert_eq!(resp.result, "0x0703");
+    assert!(resp.vm_error.is_none());
+    assert_eq!(resp.fee, 0);
+    assert_eq!(resp.account_diffs.len(), 1);
+    assert_eq!(resp.account_diffs[0].principal, sender.to_string());
+    assert_eq!(resp.account_diffs[0].before.nonce, 0);
+    assert_eq!(resp.account_diffs[0].after.nonce, 1);
+
+    let resp_again = responses
+        .remove(0)
+        .decode_simulate_transaction_response()
+        .unwrap();
+    assert_eq!(resp_again, resp);
+}
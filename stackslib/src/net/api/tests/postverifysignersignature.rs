@@ -0,0 +1,244 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clarity::vm::costs::ExecutionCost;
+use stacks_common::address::AddressHashMode;
+use stacks_common::types::chainstate::{StacksBlockId, StacksPrivateKey, StacksPublicKey};
+use stacks_common::util::hash::{to_hex, Hash160};
+
+use super::TestRPC;
+use crate::chainstate::stacks::address::PoxAddress;
+use crate::net::api::postverifysignersignature::{
+    RPCVerifySignerSignatureRequestHandler, SignerSignatureRequest,
+};
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+use crate::net::test::TestEventObserver;
+use crate::net::TipRequest;
+use crate::util_lib::signed_structured_data::pox4::{
+    make_pox_4_signer_key_message_hash, make_pox_4_signer_key_signature, Pox4SignatureTopic,
+};
+
+fn make_signer_signature_request(
+    signer_key: &StacksPrivateKey,
+    chain_id: u32,
+    amount: u128,
+) -> SignerSignatureRequest {
+    let pox_address = PoxAddress::from_legacy(AddressHashMode::SerializeP2PKH, Hash160([0x01; 20]));
+    let topic = Pox4SignatureTopic::StackStx;
+    let signer_signature = make_pox_4_signer_key_signature(
+        &pox_address,
+        signer_key,
+        5,
+        &topic,
+        chain_id,
+        2,
+        1_000_000,
+        7,
+    )
+    .unwrap();
+    SignerSignatureRequest {
+        pox_address,
+        reward_cycle: 5,
+        topic,
+        period: 2,
+        signer_key: StacksPublicKey::from_private(signer_key),
+        signer_signature: Some(signer_signature),
+        max_amount: 1_000_000,
+        amount,
+        auth_id: 7,
+    }
+}
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let signer_key = StacksPrivateKey::random();
+    let signer_request = make_signer_signature_request(&signer_key, 0x80000000, 1_000);
+    let request = StacksHttpRequest::new_verify_signer_signature(
+        addr.into(),
+        signer_request.clone(),
+        TipRequest::UseLatestAnchoredTip,
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = RPCVerifySignerSignatureRequestHandler::new(ExecutionCost::max_value());
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+    assert_eq!(&preamble, request.preamble());
+
+    let parsed = handler.request.clone().unwrap();
+    assert_eq!(
+        parsed.pox_address.to_b58(),
+        signer_request.pox_address.clone().to_b58()
+    );
+    assert_eq!(parsed.reward_cycle, signer_request.reward_cycle);
+    assert_eq!(parsed.topic, signer_request.topic);
+    assert_eq!(parsed.period, signer_request.period);
+    assert_eq!(parsed.signer_key, signer_request.signer_key);
+    assert_eq!(parsed.signer_signature, signer_request.signer_signature);
+    assert_eq!(parsed.max_amount, signer_request.max_amount);
+    assert_eq!(parsed.amount, signer_request.amount);
+    assert_eq!(parsed.auth_id, signer_request.auth_id);
+
+    // reset works
+    handler.restart();
+    assert!(handler.request.is_none());
+
+    // unknown topics are rejected
+    let bad_request = StacksHttpRequest::new_for_peer(
+        addr.into(),
+        "POST".into(),
+        "/v3/pox/verify-signer-signature".into(),
+        crate::net::http::HttpRequestContents::new().payload_json(serde_json::json!({
+            "pox_address": signer_request.pox_address.clone().to_b58(),
+            "reward_cycle": 5,
+            "topic": "stack-everything",
+            "period": 2,
+            "signer_key": to_hex(&signer_request.signer_key.to_bytes_compressed()),
+            "max_amount": "1000000",
+            "auth_id": "7",
+        })),
+    )
+    .unwrap();
+    let bytes = bad_request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    assert!(http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .is_err());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let test_observer = TestEventObserver::new();
+    let mut rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+    let chain_id = rpc_test.peer_2.chainstate().chain_id;
+    let nakamoto_chain_tip = rpc_test.canonical_tip.clone();
+
+    let signer_key = StacksPrivateKey::random();
+    let mut requests = vec![];
+
+    // well-formed signature
+    let signer_request = make_signer_signature_request(&signer_key, chain_id, 1_000);
+    requests.push(StacksHttpRequest::new_verify_signer_signature(
+        addr.into(),
+        signer_request.clone(),
+        TipRequest::SpecificTip(nakamoto_chain_tip.clone()),
+    ));
+
+    // amount above the signed max-amount
+    let over_max_request = make_signer_signature_request(&signer_key, chain_id, 1_000_001);
+    requests.push(StacksHttpRequest::new_verify_signer_signature(
+        addr.into(),
+        over_max_request,
+        TipRequest::SpecificTip(nakamoto_chain_tip.clone()),
+    ));
+
+    // signed by a different key
+    let mut wrong_key_request = signer_request.clone();
+    wrong_key_request.signer_key = StacksPublicKey::from_private(&StacksPrivateKey::random());
+    requests.push(StacksHttpRequest::new_verify_signer_signature(
+        addr.into(),
+        wrong_key_request,
+        TipRequest::SpecificTip(nakamoto_chain_tip.clone()),
+    ));
+
+    // no signature and no on-chain authorization
+    let mut unauthorized_request = signer_request.clone();
+    unauthorized_request.signer_signature = None;
+    requests.push(StacksHttpRequest::new_verify_signer_signature(
+        addr.into(),
+        unauthorized_request,
+        TipRequest::SpecificTip(nakamoto_chain_tip.clone()),
+    ));
+
+    // no such tip
+    requests.push(StacksHttpRequest::new_verify_signer_signature(
+        addr.into(),
+        signer_request.clone(),
+        TipRequest::SpecificTip(StacksBlockId([0x11; 32])),
+    ));
+
+    let mut responses = rpc_test.run(requests);
+
+    let resp = responses
+        .remove(0)
+        .decode_verify_signer_signature_response()
+        .unwrap();
+    assert!(resp.valid);
+    assert!(resp.error_code.is_none());
+    assert!(resp.reason.is_none());
+    assert_eq!(resp.index_block_hash, nakamoto_chain_tip);
+    let message_hash = make_pox_4_signer_key_message_hash(
+        &signer_request.pox_address,
+        signer_request.reward_cycle.into(),
+        &signer_request.topic,
+        chain_id,
+        signer_request.period.into(),
+        signer_request.max_amount,
+        signer_request.auth_id,
+    );
+    assert_eq!(resp.message_hash, to_hex(message_hash.as_bytes()));
+
+    let resp = responses
+        .remove(0)
+        .decode_verify_signer_signature_response()
+        .unwrap();
+    assert!(!resp.valid);
+    assert_eq!(resp.error_code, Some(38));
+    assert_eq!(
+        resp.reason.as_deref(),
+        Some("ERR_SIGNER_AUTH_AMOUNT_TOO_HIGH")
+    );
+
+    let resp = responses
+        .remove(0)
+        .decode_verify_signer_signature_response()
+        .unwrap();
+    assert!(!resp.valid);
+    assert_eq!(resp.reason.as_deref(), Some("ERR_INVALID_SIGNATURE_PUBKEY"));
+
+    let resp = responses
+        .remove(0)
+        .decode_verify_signer_signature_response()
+        .unwrap();
+    assert!(!resp.valid);
+    assert_eq!(resp.reason.as_deref(), Some("ERR_NOT_ALLOWED"));
+
+    let (preamble, _body) = responses.remove(0).destruct();
+    assert_eq!(preamble.status_code, 404);
+}
@@ -50,12 +50,13 @@ use stacks_common::util::secp256k1::{Secp256k1PrivateKey, Secp256k1PublicKey};
 
 use super::{AtlasConfig, Attachment, AttachmentInstance};
 use crate::burnchains::Txid;
+use crate::chainstate::burn::ConsensusHash;
 use crate::util_lib::db::{
     query_count, query_int, query_row, query_rows, sqlite_open, tx_begin_immediate, u64_to_sql,
     DBConn, Error as db_error, FromColumn, FromRow,
 };
 
-pub const ATLASDB_VERSION: &str = "2";
+pub const ATLASDB_VERSION: &str = "3";
 
 /// The maximum number of atlas attachment instances that should be
 /// checked at once (this is used to limit the return size of
@@ -105,9 +106,20 @@ const ATLASDB_SCHEMA_2: &[&str] = &[
     "#,
 ];
 
+const ATLASDB_SCHEMA_3: &[&str] = &[
+    // Record the tenure in which each attachment instance was emitted, so that
+    //  the downloader can batch instances per tenure. Instances written before
+    //  this migration keep a NULL tenure, and are batched per block instead.
+    r#"
+    ALTER TABLE attachment_instances
+    ADD consensus_hash TEXT
+    ;"#,
+];
+
 const ATLASDB_INDEXES: &[&str] = &[
     "CREATE INDEX IF NOT EXISTS index_was_instantiated ON attachments(was_instantiated);",
     "CREATE INDEX IF NOT EXISTS index_instance_status ON attachment_instances(status);",
+    "CREATE INDEX IF NOT EXISTS index_instance_block_height ON attachment_instances(block_height);",
 ];
 
 /// Attachment instances pass through different states once written to the AtlasDB.
@@ -145,6 +157,7 @@ impl FromRow<AttachmentInstance> for AttachmentInstance {
         let contract_id = QualifiedContractIdentifier::from_column(row, "contract_id")?;
         let hex_tx_id: String = row.get_unwrap("tx_id");
         let tx_id = Txid::from_hex(&hex_tx_id).map_err(|_| db_error::TypeError)?;
+        let consensus_hash: Option<ConsensusHash> = row.get("consensus_hash")?;
 
         Ok(AttachmentInstance {
             content_hash,
@@ -155,10 +168,19 @@ impl FromRow<AttachmentInstance> for AttachmentInstance {
             contract_id,
             tx_id,
             canonical_stacks_tip_height: None,
+            consensus_hash,
         })
     }
 }
 
+impl FromRow<(AttachmentInstance, bool)> for (AttachmentInstance, bool) {
+    fn from_row(row: &Row) -> Result<(AttachmentInstance, bool), db_error> {
+        let instance = AttachmentInstance::from_row(row)?;
+        let is_available: bool = row.get_unwrap("is_available");
+        Ok((instance, is_available))
+    }
+}
+
 impl FromRow<(u32, u32)> for (u32, u32) {
     fn from_row(row: &Row) -> Result<(u32, u32), db_error> {
         let t1: u32 = row.get_unwrap(0);
@@ -224,6 +246,9 @@ impl AtlasDB {
         for row_text in ATLASDB_SCHEMA_2 {
             tx.execute_batch(row_text)?;
         }
+        for row_text in ATLASDB_SCHEMA_3 {
+            tx.execute_batch(row_text)?;
+        }
 
         tx.execute(
             "INSERT INTO db_config (version) VALUES (?1)",
@@ -351,6 +376,19 @@ impl AtlasDB {
         Ok(())
     }
 
+    fn apply_schema_3(db_conn: &Connection) -> Result<(), db_error> {
+        for row_text in ATLASDB_SCHEMA_3 {
+            db_conn.execute_batch(row_text)?;
+        }
+
+        db_conn.execute(
+            "INSERT OR REPLACE INTO db_config (version) VALUES (?1)",
+            params!["3"],
+        )?;
+
+        Ok(())
+    }
+
     fn check_schema_version_and_update(&mut self) -> Result<(), db_error> {
         let tx = self.tx_begin()?;
         match AtlasDB::get_schema_version(&tx) {
@@ -361,6 +399,11 @@ impl AtlasDB {
                 }
                 if version == "1" {
                     Self::apply_schema_2(&tx)?;
+                    Self::apply_schema_3(&tx)?;
+                    tx.commit()?;
+                    Ok(())
+                } else if version == "2" {
+                    Self::apply_schema_3(&tx)?;
                     tx.commit()?;
                     Ok(())
                 } else {
@@ -682,6 +725,26 @@ impl AtlasDB {
         )
     }
 
+    /// Return the most recent checked attachment instances, along with whether or not their
+    ///  content is available, ordered by decreasing block height. At most `limit` instances
+    ///  are returned, and only instances at or above `min_block_height` are considered.
+    pub fn find_recent_attachment_instances(
+        &self,
+        min_block_height: u64,
+        limit: u32,
+    ) -> Result<Vec<(AttachmentInstance, bool)>, db_error> {
+        query_rows(
+            &self.conn,
+            "SELECT * FROM attachment_instances WHERE status = ?1 AND block_height >= ?2
+              ORDER BY block_height DESC, attachment_index DESC LIMIT ?3",
+            params![
+                AttachmentInstanceStatus::Checked,
+                u64_to_sql(min_block_height)?,
+                limit
+            ],
+        )
+    }
+
     /// Update a queued attachment to "checked", setting the `is_available` field.
     pub fn mark_attachment_instance_checked(
         &mut self,
@@ -715,8 +778,8 @@ impl AtlasDB {
             "INSERT OR REPLACE INTO attachment_instances (
                content_hash, created_at, index_block_hash,
                attachment_index, block_height, is_available,
-                metadata, contract_id, tx_id, status)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                metadata, contract_id, tx_id, status, consensus_hash)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             params![
                 attachment.content_hash,
                 now,
@@ -727,7 +790,8 @@ impl AtlasDB {
                 attachment.metadata,
                 attachment.contract_id.to_string(),
                 attachment.tx_id,
-                status
+                status,
+                attachment.consensus_hash,
             ],
         )?;
         sql_tx.commit()?;
@@ -238,7 +238,8 @@ impl AttachmentsDownloader {
         F: Fn(&mut AtlasDB, &AttachmentInstance) -> Result<(), DBError>,
         G: Fn(&mut AtlasDB, &AttachmentInstance) -> Result<(), DBError>,
     {
-        let mut attachments_batches: HashMap<StacksBlockId, AttachmentsBatch> = HashMap::new();
+        let mut attachments_batches: HashMap<AttachmentsBatchKey, AttachmentsBatch> =
+            HashMap::new();
         let mut resolved_attachments = vec![];
         for attachment_instance in iterator {
             if attachment_instance.content_hash == Hash160::empty() {
@@ -266,7 +267,7 @@ impl AttachmentsDownloader {
             } else {
                 // This attachment refers to an unknown attachment.
                 // Let's append it to the batch being constructed in this routine.
-                match attachments_batches.entry(attachment_instance.index_block_hash) {
+                match attachments_batches.entry(AttachmentsBatchKey::from(&attachment_instance)) {
                     Entry::Occupied(entry) => {
                         entry.into_mut().track_attachment(&attachment_instance);
                     }
@@ -381,23 +382,33 @@ impl AttachmentsBatchStateContext {
     ) -> BinaryHeap<AttachmentsInventoryRequest> {
         let mut queue = BinaryHeap::new();
         for (contract_id, _) in self.attachments_batch.attachments_instances.iter() {
-            let pages_batches = self
+            // Inventories are served per block, so a batch spanning a tenure
+            // needs one set of inventory requests per block.
+            for (index_block_hash, stacks_block_height) in self
                 .attachments_batch
-                .get_paginated_missing_pages_for_contract_id(contract_id);
-            for (peer_url, reliability_report) in self.peers.iter() {
-                for pages in pages_batches.iter() {
-                    let request = AttachmentsInventoryRequest {
-                        url: peer_url.clone(),
-                        reliability_report: reliability_report.clone(),
-                        contract_id: contract_id.clone(),
-                        pages: pages.clone(),
-                        stacks_block_height: self.attachments_batch.stacks_block_height,
-                        index_block_hash: self.attachments_batch.index_block_hash,
-                        canonical_stacks_tip_height: self
-                            .attachments_batch
-                            .canonical_stacks_tip_height,
-                    };
-                    queue.push(request);
+                .get_missing_blocks_for_contract_id(contract_id)
+            {
+                let pages_batches = self
+                    .attachments_batch
+                    .get_paginated_missing_pages_for_contract_id_in_block(
+                        contract_id,
+                        &index_block_hash,
+                    );
+                for (peer_url, reliability_report) in self.peers.iter() {
+                    for pages in pages_batches.iter() {
+                        let request = AttachmentsInventoryRequest {
+                            url: peer_url.clone(),
+                            reliability_report: reliability_report.clone(),
+                            contract_id: contract_id.clone(),
+                            pages: pages.clone(),
+                            stacks_block_height,
+                            index_block_hash,
+                            canonical_stacks_tip_height: self
+                                .attachments_batch
+                                .canonical_stacks_tip_height,
+                        };
+                        queue.push(request);
+                    }
                 }
             }
         }
@@ -407,7 +418,7 @@ impl AttachmentsBatchStateContext {
     pub fn get_prioritized_attachments_requests(&self) -> BinaryHeap<AttachmentRequest> {
         let mut queue = BinaryHeap::new();
         let mut enqueued = HashSet::new();
-        for ((contract_id, pages, _), peers_responses) in self.inventories.iter() {
+        for ((contract_id, pages, index_block_hash), peers_responses) in self.inventories.iter() {
             let missing_attachments = match self
                 .attachments_batch
                 .attachments_instances
@@ -425,6 +436,15 @@ impl AttachmentsBatchStateContext {
                     continue;
                 }
 
+                // Other blocks of the tenure can share this page, but this inventory
+                // only covers attachments emitted in `index_block_hash`.
+                let attachment_block = self
+                    .attachments_batch
+                    .get_attachment_block(contract_id, *attachment_index);
+                if attachment_block.is_some_and(|block| block != *index_block_hash) {
+                    continue;
+                }
+
                 if enqueued.contains(content_hash) {
                     debug!("Atlas: {} already enqueued", content_hash);
                     continue;
@@ -471,7 +491,12 @@ impl AttachmentsBatchStateContext {
                 let request = AttachmentRequest {
                     sources,
                     content_hash: content_hash.clone(),
-                    stacks_block_height: self.attachments_batch.stacks_block_height,
+                    stacks_block_height: self
+                        .attachments_batch
+                        .block_heights
+                        .get(index_block_hash)
+                        .copied()
+                        .unwrap_or(self.attachments_batch.stacks_block_height),
                     canonical_stacks_tip_height: self.attachments_batch.canonical_stacks_tip_height,
                 };
                 enqueued.insert(content_hash);
@@ -1129,12 +1154,37 @@ impl std::fmt::Display for AttachmentRequest {
     }
 }
 
+/// Attachment instances are downloaded in batches, one per tenure. Instances which were stored
+/// without a tenure are batched per block.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AttachmentsBatchKey {
+    Tenure(ConsensusHash),
+    Block(StacksBlockId),
+}
+
+impl From<&AttachmentInstance> for AttachmentsBatchKey {
+    fn from(attachment: &AttachmentInstance) -> Self {
+        match attachment.consensus_hash {
+            Some(consensus_hash) => AttachmentsBatchKey::Tenure(consensus_hash),
+            None => AttachmentsBatchKey::Block(attachment.index_block_hash),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct AttachmentsBatch {
+    /// Height of the earliest block in the batch
     pub stacks_block_height: u64,
     pub canonical_stacks_tip_height: Option<u64>,
+    /// Earliest block in the batch
     pub index_block_hash: StacksBlockId,
+    /// Tenure of the batch, if known. In Nakamoto, a tenure can span many blocks.
+    pub consensus_hash: Option<ConsensusHash>,
     pub attachments_instances: HashMap<QualifiedContractIdentifier, HashMap<u32, Hash160>>,
+    /// Block in which each tracked attachment instance was emitted
+    pub attachments_blocks: HashMap<QualifiedContractIdentifier, HashMap<u32, StacksBlockId>>,
+    /// Heights of the blocks spanned by this batch
+    pub block_heights: HashMap<StacksBlockId, u64>,
     pub retry_count: u64,
     pub retry_deadline: u64,
 }
@@ -1145,27 +1195,64 @@ impl AttachmentsBatch {
             stacks_block_height: 0,
             canonical_stacks_tip_height: None,
             index_block_hash: StacksBlockId([0u8; 32]),
+            consensus_hash: None,
             attachments_instances: HashMap::new(),
+            attachments_blocks: HashMap::new(),
+            block_heights: HashMap::new(),
             retry_count: 0,
             retry_deadline: 0,
         }
     }
 
+    /// Can `attachment` be tracked by this (non-empty) batch?
+    /// Instances from any block of the batch's tenure are related; instances without a tenure
+    /// are only related to instances from the same block.
+    fn is_related(&self, attachment: &AttachmentInstance) -> bool {
+        match (&self.consensus_hash, &attachment.consensus_hash) {
+            (Some(consensus_hash), Some(other)) => consensus_hash == other,
+            (None, None) => {
+                self.stacks_block_height == attachment.stacks_block_height
+                    && self.index_block_hash == attachment.index_block_hash
+            }
+            _ => false,
+        }
+    }
+
     pub fn track_attachment(&mut self, attachment: &AttachmentInstance) {
         if self.attachments_instances.is_empty() {
             self.stacks_block_height = attachment.stacks_block_height.clone();
             self.index_block_hash = attachment.index_block_hash.clone();
             self.canonical_stacks_tip_height = attachment.canonical_stacks_tip_height;
-        } else if self.stacks_block_height != attachment.stacks_block_height
-            || self.index_block_hash != attachment.index_block_hash
-        {
+            self.consensus_hash = attachment.consensus_hash.clone();
+        } else if !self.is_related(attachment) {
             warn!(
                 "Atlas: attempt to add unrelated AttachmentInstance ({}, {}) to AttachmentsBatch",
                 attachment.attachment_index, attachment.index_block_hash
             );
             return;
+        } else {
+            if attachment.stacks_block_height < self.stacks_block_height {
+                self.stacks_block_height = attachment.stacks_block_height;
+                self.index_block_hash = attachment.index_block_hash.clone();
+            }
+            self.canonical_stacks_tip_height = cmp::max(
+                self.canonical_stacks_tip_height,
+                attachment.canonical_stacks_tip_height,
+            );
         }
 
+        self.block_heights.insert(
+            attachment.index_block_hash.clone(),
+            attachment.stacks_block_height,
+        );
+        self.attachments_blocks
+            .entry(attachment.contract_id.clone())
+            .or_default()
+            .insert(
+                attachment.attachment_index,
+                attachment.index_block_hash.clone(),
+            );
+
         let inner_key = attachment.attachment_index;
         match self
             .attachments_instances
@@ -1215,6 +1302,76 @@ impl AttachmentsBatch {
         pages_indexes.into_iter().collect()
     }
 
+    /// Get the block in which the attachment instance `(contract_id, attachment_index)` was emitted
+    pub fn get_attachment_block(
+        &self,
+        contract_id: &QualifiedContractIdentifier,
+        attachment_index: u32,
+    ) -> Option<StacksBlockId> {
+        self.attachments_blocks
+            .get(contract_id)
+            .and_then(|blocks| blocks.get(&attachment_index))
+            .copied()
+    }
+
+    /// Get the blocks (and their heights) with attachments still missing for `contract_id`,
+    /// ordered by height.
+    pub fn get_missing_blocks_for_contract_id(
+        &self,
+        contract_id: &QualifiedContractIdentifier,
+    ) -> Vec<(StacksBlockId, u64)> {
+        let mut blocks = HashSet::new();
+        if let Some(missing_attachments) = self.attachments_instances.get(contract_id) {
+            for attachment_index in missing_attachments.keys() {
+                let block_id = self
+                    .get_attachment_block(contract_id, *attachment_index)
+                    .unwrap_or(self.index_block_hash);
+                blocks.insert(block_id);
+            }
+        }
+        let mut blocks: Vec<_> = blocks
+            .into_iter()
+            .map(|block_id| {
+                let height = self
+                    .block_heights
+                    .get(&block_id)
+                    .copied()
+                    .unwrap_or(self.stacks_block_height);
+                (block_id, height)
+            })
+            .collect();
+        blocks.sort_by_key(|(block_id, height)| (*height, *block_id));
+        blocks
+    }
+
+    /// Get the missing pages for `contract_id` in the block `index_block_hash`, split into
+    /// chunks of at most `MAX_ATTACHMENT_INV_PAGES_PER_REQUEST` pages.
+    pub fn get_paginated_missing_pages_for_contract_id_in_block(
+        &self,
+        contract_id: &QualifiedContractIdentifier,
+        index_block_hash: &StacksBlockId,
+    ) -> Vec<Vec<u32>> {
+        let mut pages_indexes = HashSet::new();
+        if let Some(missing_attachments) = self.attachments_instances.get(contract_id) {
+            for attachment_index in missing_attachments.keys() {
+                let block_id = self
+                    .get_attachment_block(contract_id, *attachment_index)
+                    .unwrap_or(self.index_block_hash);
+                if block_id != *index_block_hash {
+                    continue;
+                }
+                pages_indexes
+                    .insert(attachment_index / AttachmentInstance::ATTACHMENTS_INV_PAGE_SIZE);
+            }
+        }
+        let mut pages_indexes: Vec<u32> = pages_indexes.into_iter().collect();
+        pages_indexes.sort();
+        pages_indexes
+            .chunks(MAX_ATTACHMENT_INV_PAGES_PER_REQUEST)
+            .map(|page| page.to_vec())
+            .collect()
+    }
+
     pub fn get_paginated_missing_pages_for_contract_id(
         &self,
         contract_id: &QualifiedContractIdentifier,
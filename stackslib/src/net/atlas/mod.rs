@@ -41,6 +41,8 @@ pub mod db;
 pub mod download;
 
 pub const MAX_ATTACHMENT_INV_PAGES_PER_REQUEST: usize = 8;
+/// Maximum number of attachment instances reported by `GET /v3/attachments/recent`
+pub const MAX_RECENT_ATTACHMENTS_PER_REQUEST: u32 = 256;
 pub const MAX_RETRY_DELAY: u64 = 600; // seconds
 /// This is the maximum number of pending attachments batches allowed
 ///  in the synchronized channel before the coordinator will stall
@@ -90,6 +92,64 @@ pub struct AttachmentPage {
     pub inventory: Vec<u8>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetRecentAttachmentsResponse {
+    /// Tenures with recent attachment instances, most recent first
+    pub tenures: Vec<RecentAttachmentsTenure>,
+}
+
+/// The attachment instances emitted during a single tenure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentAttachmentsTenure {
+    /// `None` for attachment instances stored before tenures were recorded
+    pub consensus_hash: Option<ConsensusHash>,
+    pub attachments: Vec<RecentAttachment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentAttachment {
+    pub content_hash: Hash160,
+    pub attachment_index: u32,
+    pub contract_id: String,
+    pub index_block_hash: StacksBlockId,
+    pub stacks_block_height: u64,
+    pub tx_id: Txid,
+    pub is_available: bool,
+}
+
+impl GetRecentAttachmentsResponse {
+    /// Group attachment instances by tenure, preserving the order in which each tenure first
+    /// appears in `instances`.
+    pub fn from_instances(instances: Vec<(AttachmentInstance, bool)>) -> Self {
+        let mut tenures: Vec<RecentAttachmentsTenure> = vec![];
+        for (instance, is_available) in instances.into_iter() {
+            let attachment = RecentAttachment {
+                content_hash: instance.content_hash,
+                attachment_index: instance.attachment_index,
+                contract_id: instance.contract_id.to_string(),
+                index_block_hash: instance.index_block_hash,
+                stacks_block_height: instance.stacks_block_height,
+                tx_id: instance.tx_id,
+                is_available,
+            };
+            // instances from legacy rows have no tenure, so each one is its own group
+            let position = instance.consensus_hash.as_ref().and_then(|ch| {
+                tenures
+                    .iter()
+                    .position(|tenure| tenure.consensus_hash.as_ref() == Some(ch))
+            });
+            match position {
+                Some(i) => tenures[i].attachments.push(attachment),
+                None => tenures.push(RecentAttachmentsTenure {
+                    consensus_hash: instance.consensus_hash,
+                    attachments: vec![attachment],
+                }),
+            }
+        }
+        GetRecentAttachmentsResponse { tenures }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AtlasConfig {
     pub contracts: HashSet<QualifiedContractIdentifier>,
@@ -180,6 +240,9 @@ pub struct AttachmentInstance {
     pub contract_id: QualifiedContractIdentifier,
     pub tx_id: Txid,
     pub canonical_stacks_tip_height: Option<u64>,
+    /// Consensus hash of the tenure in which this instance was emitted.  This is `None` for
+    /// instances stored before tenures were recorded in the AtlasDB.
+    pub consensus_hash: Option<ConsensusHash>,
 }
 
 impl AttachmentInstance {
@@ -189,6 +252,7 @@ impl AttachmentInstance {
         value: &Value,
         contract_id: &QualifiedContractIdentifier,
         index_block_hash: StacksBlockId,
+        consensus_hash: ConsensusHash,
         stacks_block_height: u64,
         tx_id: Txid,
         canonical_stacks_tip_height: Option<u64>,
@@ -229,6 +293,7 @@ impl AttachmentInstance {
                         contract_id: contract_id.clone(),
                         tx_id,
                         canonical_stacks_tip_height,
+                        consensus_hash: Some(consensus_hash),
                     };
                     return Some(instance);
                 }
@@ -28,7 +28,8 @@ use super::download::{
     BatchedRequestsResult, ReliabilityReport,
 };
 use super::{
-    AtlasConfig, AtlasDB, Attachment, AttachmentInstance, AttachmentPage, GetAttachmentsInvResponse,
+    AtlasConfig, AtlasDB, Attachment, AttachmentInstance, AttachmentPage,
+    GetAttachmentsInvResponse, GetRecentAttachmentsResponse,
 };
 use crate::burnchains::Txid;
 use crate::chainstate::burn::ConsensusHash;
@@ -62,6 +63,7 @@ fn new_attachment_instance_from(
         contract_id: QualifiedContractIdentifier::transient(),
         tx_id: Txid([0; 32]),
         canonical_stacks_tip_height: Some(block_height),
+        consensus_hash: None,
     }
 }
 
@@ -178,6 +180,7 @@ fn test_attachment_instance_parsing() {
         &value_1,
         &contract_id,
         index_block_hash.clone(),
+        ConsensusHash([0x00; 20]),
         stacks_block_height,
         Txid([0; 32]),
         Some(stacks_block_height),
@@ -205,6 +208,7 @@ fn test_attachment_instance_parsing() {
         &value_2,
         &contract_id,
         index_block_hash.clone(),
+        ConsensusHash([0x00; 20]),
         stacks_block_height,
         Txid([0; 32]),
         Some(stacks_block_height),
@@ -232,6 +236,7 @@ fn test_attachment_instance_parsing() {
         &value_3,
         &contract_id,
         index_block_hash.clone(),
+        ConsensusHash([0x00; 20]),
         stacks_block_height,
         Txid([0; 32]),
         Some(stacks_block_height),
@@ -290,6 +295,7 @@ fn test_attachment_instance_parsing() {
             value,
             &contract_id,
             index_block_hash.clone(),
+            ConsensusHash([0x00; 20]),
             stacks_block_height,
             Txid([0; 32]),
             Some(stacks_block_height)
@@ -596,6 +602,94 @@ fn test_attachments_batch_pages() {
     );
 }
 
+#[test]
+fn test_attachments_batch_per_tenure() {
+    let page_size = AttachmentInstance::ATTACHMENTS_INV_PAGE_SIZE;
+    let tenure = ConsensusHash([0x01; 20]);
+    let other_tenure = ConsensusHash([0x02; 20]);
+
+    let mut attachment_instance_1 =
+        new_attachment_instance_from(&new_attachment_from("facade11"), page_size * 0, 1);
+    attachment_instance_1.consensus_hash = Some(tenure.clone());
+    let mut attachment_instance_2 =
+        new_attachment_instance_from(&new_attachment_from("facade12"), page_size * 0 + 1, 2);
+    attachment_instance_2.consensus_hash = Some(tenure.clone());
+    let mut attachment_instance_3 =
+        new_attachment_instance_from(&new_attachment_from("facade13"), page_size * 1, 3);
+    attachment_instance_3.consensus_hash = Some(tenure.clone());
+    let mut attachment_instance_4 =
+        new_attachment_instance_from(&new_attachment_from("facade14"), page_size * 1 + 1, 4);
+    attachment_instance_4.consensus_hash = Some(other_tenure);
+    let attachment_instance_5 =
+        new_attachment_instance_from(&new_attachment_from("facade15"), page_size * 1 + 2, 3);
+
+    // instances from the blocks of a tenure are batched together, in any order
+    let mut attachments_batch = AttachmentsBatch::new();
+    attachments_batch.track_attachment(&attachment_instance_2);
+    attachments_batch.track_attachment(&attachment_instance_1);
+    attachments_batch.track_attachment(&attachment_instance_3);
+
+    // instances from other tenures, or without a tenure, are not
+    attachments_batch.track_attachment(&attachment_instance_4);
+    attachments_batch.track_attachment(&attachment_instance_5);
+
+    let default_contract_id = QualifiedContractIdentifier::transient();
+
+    assert_eq!(attachments_batch.attachments_instances_count(), 3);
+    assert_eq!(attachments_batch.consensus_hash, Some(tenure));
+    assert_eq!(attachments_batch.stacks_block_height, 1);
+    assert_eq!(
+        attachments_batch.index_block_hash,
+        attachment_instance_1.index_block_hash
+    );
+    assert_eq!(
+        attachments_batch.get_missing_blocks_for_contract_id(&default_contract_id),
+        vec![
+            (attachment_instance_1.index_block_hash, 1),
+            (attachment_instance_2.index_block_hash, 2),
+            (attachment_instance_3.index_block_hash, 3),
+        ]
+    );
+
+    // pages are requested per block
+    assert_eq!(
+        attachments_batch.get_paginated_missing_pages_for_contract_id_in_block(
+            &default_contract_id,
+            &attachment_instance_2.index_block_hash
+        ),
+        vec![vec![0]]
+    );
+    assert_eq!(
+        attachments_batch.get_paginated_missing_pages_for_contract_id_in_block(
+            &default_contract_id,
+            &attachment_instance_3.index_block_hash
+        ),
+        vec![vec![1]]
+    );
+
+    attachments_batch.resolve_attachment(&attachment_instance_1.content_hash);
+    assert_eq!(
+        attachments_batch.get_missing_blocks_for_contract_id(&default_contract_id),
+        vec![
+            (attachment_instance_2.index_block_hash, 2),
+            (attachment_instance_3.index_block_hash, 3),
+        ]
+    );
+
+    // one inventory request per block and peer
+    let peers = new_peers(vec![("http://localhost:20443", 2, 2)]);
+    let context =
+        AttachmentsBatchStateContext::new(attachments_batch, peers, &ConnectionOptions::default());
+    let requests = context.get_prioritized_attachments_inventory_requests();
+    assert_eq!(requests.len(), 2);
+    let requested_blocks: HashSet<_> = requests
+        .iter()
+        .map(|request| (request.index_block_hash, request.stacks_block_height))
+        .collect();
+    assert!(requested_blocks.contains(&(attachment_instance_2.index_block_hash, 2)));
+    assert!(requested_blocks.contains(&(attachment_instance_3.index_block_hash, 3)));
+}
+
 #[test]
 fn test_downloader_context_attachment_inventories_requests() {
     let localhost = PeerHost::from_host_port("127.0.0.1".to_string(), 1024);
@@ -806,6 +900,7 @@ fn schema_2_migration() {
             contract_id: QualifiedContractIdentifier::transient(),
             tx_id: Txid([0x2f; 32]),
             canonical_stacks_tip_height: None,
+            consensus_hash: None,
         },
         AttachmentInstance {
             content_hash: Hash160([0x00; 20]),
@@ -816,6 +911,7 @@ fn schema_2_migration() {
             contract_id: QualifiedContractIdentifier::transient(),
             tx_id: Txid([0x0b; 32]),
             canonical_stacks_tip_height: None,
+            consensus_hash: None,
         },
     ];
 
@@ -877,6 +973,86 @@ fn schema_2_migration() {
     );
 }
 
+#[test]
+fn test_find_recent_attachment_instances() {
+    let atlas_config = AtlasConfig {
+        contracts: HashSet::new(),
+        attachments_max_size: 1024,
+        max_uninstantiated_attachments: 10,
+        uninstantiated_attachments_expire_after: 0,
+        unresolved_attachment_instances_expire_after: 10,
+        genesis_attachments: None,
+    };
+
+    let mut atlas_db = AtlasDB::connect_memory(atlas_config).unwrap();
+
+    let mut attachment_instances = vec![];
+    for height in 1..=4 {
+        let mut attachment_instance = new_attachment_instance_from(
+            &new_attachment_from(&format!("facade0{}", height)),
+            height as u32,
+            height,
+        );
+        attachment_instance.consensus_hash = Some(ConsensusHash([(height as u8 + 1) / 2; 20]));
+        attachment_instances.push(attachment_instance);
+    }
+
+    atlas_db
+        .insert_initial_attachment_instance(&attachment_instances[0])
+        .unwrap();
+    for attachment_instance in attachment_instances[1..].iter() {
+        atlas_db
+            .queue_attachment_instance(attachment_instance)
+            .unwrap();
+    }
+
+    // queued instances are not reported until checked
+    let recent = atlas_db.find_recent_attachment_instances(0, 10).unwrap();
+    assert_eq!(recent.len(), 1);
+    assert_eq!(
+        recent[0].0.content_hash,
+        attachment_instances[0].content_hash
+    );
+    assert!(recent[0].1);
+
+    for attachment_instance in attachment_instances[1..].iter() {
+        atlas_db
+            .mark_attachment_instance_checked(attachment_instance, false)
+            .unwrap();
+    }
+
+    // most recent first
+    let recent = atlas_db.find_recent_attachment_instances(0, 10).unwrap();
+    let heights: Vec<_> = recent
+        .iter()
+        .map(|(instance, _)| instance.stacks_block_height)
+        .collect();
+    assert_eq!(heights, vec![4, 3, 2, 1]);
+    assert_eq!(recent[0].0.consensus_hash, Some(ConsensusHash([2; 20])));
+
+    let recent = atlas_db.find_recent_attachment_instances(0, 2).unwrap();
+    assert_eq!(recent.len(), 2);
+    let recent = atlas_db.find_recent_attachment_instances(3, 10).unwrap();
+    assert_eq!(recent.len(), 2);
+
+    // instances are grouped by tenure
+    let recent = atlas_db.find_recent_attachment_instances(0, 10).unwrap();
+    let response = GetRecentAttachmentsResponse::from_instances(recent);
+    assert_eq!(response.tenures.len(), 2);
+    assert_eq!(
+        response.tenures[0].consensus_hash,
+        Some(ConsensusHash([2; 20]))
+    );
+    assert_eq!(response.tenures[0].attachments.len(), 2);
+    assert!(!response.tenures[0].attachments[0].is_available);
+    assert_eq!(
+        response.tenures[1].consensus_hash,
+        Some(ConsensusHash([1; 20]))
+    );
+    assert_eq!(response.tenures[1].attachments.len(), 2);
+    assert!(response.tenures[1].attachments[1].is_available);
+}
+
 #[test]
 fn test_evict_k_oldest_uninstantiated_attachments() {
     let atlas_config = AtlasConfig {
@@ -130,6 +130,8 @@ pub struct NeighborStats {
     pub stackerdb_push_rx_counts: VecDeque<(u64, u64)>,
     /// (timestamp, num bytes)
     pub nakamoto_block_push_rx_counts: VecDeque<(u64, u64)>,
+    /// (num bytes, milliseconds taken) of HTTP requests made to this peer's data URL
+    pub http_download_counts: VecDeque<(u64, u64)>,
     pub relayed_messages: HashMap<NeighborAddress, RelayStats>,
 }
 
@@ -155,6 +157,7 @@ impl NeighborStats {
             transaction_push_rx_counts: VecDeque::new(),
             stackerdb_push_rx_counts: VecDeque::new(),
             nakamoto_block_push_rx_counts: VecDeque::new(),
+            http_download_counts: VecDeque::new(),
             relayed_messages: HashMap::new(),
         }
     }
@@ -228,6 +231,17 @@ impl NeighborStats {
         }
     }
 
+    /// Record that an HTTP request to this peer's data URL returned `num_bytes` of data after
+    /// `elapsed_ms` milliseconds.
+    /// Keeps track of the last `NUM_BANDWIDTH_POINTS` such events, so we can estimate how fast we
+    /// can download from this peer.
+    pub fn add_http_download(&mut self, num_bytes: u64, elapsed_ms: u64) {
+        self.http_download_counts.push_back((num_bytes, elapsed_ms));
+        while self.http_download_counts.len() > NUM_BANDWIDTH_POINTS {
+            self.http_download_counts.pop_front();
+        }
+    }
+
     pub fn add_relayer(&mut self, addr: &NeighborAddress, num_bytes: u64) {
         if let Some(stats) = self.relayed_messages.get_mut(addr) {
             stats.num_messages += 1;
@@ -320,6 +334,22 @@ impl NeighborStats {
         )
     }
 
+    /// Get the rate, in bytes per second, at which we have recently downloaded data from this
+    /// peer's data URL
+    pub fn get_http_download_throughput(&self) -> f64 {
+        let mut total_bytes: u64 = 0;
+        let mut total_ms: u64 = 0;
+        for (num_bytes, elapsed_ms) in self.http_download_counts.iter() {
+            total_bytes = total_bytes.saturating_add(*num_bytes);
+            total_ms = total_ms.saturating_add(*elapsed_ms);
+        }
+        if total_ms == 0 {
+            total_bytes as f64
+        } else {
+            (total_bytes as f64) * 1000.0 / (total_ms as f64)
+        }
+    }
+
     /// Determine how many of a particular message this peer has received
     pub fn get_message_recv_count(&self, msg_id: StacksMessageID) -> u64 {
         *(self.msg_rx_counts.get(&msg_id).unwrap_or(&0))
@@ -706,6 +736,46 @@ impl ConversationP2P {
         (peer_services & (ServiceFlags::STACKERDB as u16)) != 0
     }
 
+    /// Does the given services bitfield serve Nakamoto tenure downloads?  It will if it has the
+    /// RPC and TENURES bits set.  Peers that predate the TENURES and ARCHIVAL bits set neither,
+    /// so they are assumed to serve tenures if they serve RPC at all.
+    pub fn serves_tenure_downloads(peer_services: u16) -> bool {
+        if (peer_services & (ServiceFlags::RPC as u16)) == 0 {
+            return false;
+        }
+        let capability_bits = (ServiceFlags::TENURES as u16) | (ServiceFlags::ARCHIVAL as u16);
+        (peer_services & capability_bits) == 0
+            || (peer_services & (ServiceFlags::TENURES as u16)) != 0
+    }
+
+    /// Does the given services bitfield advertise an archival node?  It will if it has the
+    /// ARCHIVAL bit set
+    pub fn is_archival(peer_services: u16) -> bool {
+        (peer_services & (ServiceFlags::ARCHIVAL as u16)) != 0
+    }
+
+    /// Does the given services bitfield advertise a miner?  It will if it has the MINER bit set
+    pub fn advertises_miner(peer_services: u16) -> bool {
+        (peer_services & (ServiceFlags::MINER as u16)) != 0
+    }
+
+    /// Does the given services bitfield accept compressed payloads?  It will if it has the
+    /// COMPRESSION bit set
+    pub fn supports_compression(peer_services: u16) -> bool {
+        (peer_services & (ServiceFlags::COMPRESSION as u16)) != 0
+    }
+
+    /// Compress an outbound payload if compression is enabled, the remote peer accepts it, and the
+    /// payload is big enough (`connection_options.p2p_compression_min_len`) to be worth it.
+    fn compress_payload(&self, payload: StacksMessageType) -> StacksMessageType {
+        if !self.connection.options.p2p_compression
+            || !Self::supports_compression(self.peer_services)
+        {
+            return payload;
+        }
+        payload.compress(self.connection.options.p2p_compression_min_len)
+    }
+
     /// Does this remote neighbor support a particular StackerDB?
     pub fn replicates_stackerdb(&self, db: &QualifiedContractIdentifier) -> bool {
         for cid in self.db_smart_contracts.iter() {
@@ -890,6 +960,7 @@ impl ConversationP2P {
         seq: u32,
         payload: StacksMessageType,
     ) -> Result<StacksMessage, net_error> {
+        let payload = self.compress_payload(payload);
         let mut msg =
             StacksMessage::from_chain_view(self.version, self.network_id, chain_view, payload);
         msg.sign(seq, private_key)?;
@@ -916,6 +987,7 @@ impl ConversationP2P {
         mut relay_hints: Vec<RelayData>,
         payload: StacksMessageType,
     ) -> Result<StacksMessage, net_error> {
+        let payload = self.compress_payload(payload);
         let mut msg =
             StacksMessage::from_chain_view(self.version, self.network_id, chain_view, payload);
         msg.relayers.append(&mut relay_hints);
@@ -935,6 +1007,7 @@ impl ConversationP2P {
         payload: StacksMessageType,
         seq: u32,
     ) -> Result<StacksMessage, net_error> {
+        let payload = self.compress_payload(payload);
         let mut msg =
             StacksMessage::from_chain_view(self.version, self.network_id, chain_view, payload);
         msg.sign(seq, private_key)?;
@@ -1270,6 +1343,10 @@ impl ConversationP2P {
              "services" => &to_hex(&handshake_data.services.to_be_bytes()),
              "expires_block_height" => handshake_data.expire_block_height,
              "supports_mempool_query" => Self::supports_mempool_query(handshake_data.services),
+             "serves_tenure_downloads" => Self::serves_tenure_downloads(handshake_data.services),
+             "archival" => Self::is_archival(handshake_data.services),
+             "miner" => Self::advertises_miner(handshake_data.services),
+             "compression" => Self::supports_compression(handshake_data.services),
         );
 
         if updated {
@@ -1282,6 +1359,7 @@ impl ConversationP2P {
                 &handshake_data,
             )?;
             neighbor.save_update(&tx, None)?;
+            neighbor.save_services(&tx, handshake_data.services)?;
             tx.commit()
                 .map_err(|e| net_error::DBError(db_error::SqliteError(e)))?;
 
@@ -1294,7 +1372,10 @@ impl ConversationP2P {
             );
         }
 
-        let accept_data = HandshakeAcceptData::new(network.get_local_peer(), self.heartbeat);
+        let accept_data = HandshakeAcceptData {
+            handshake: network.handshake_data_for(&self.peer_addrbytes),
+            heartbeat_interval: self.heartbeat,
+        };
         let stacks_message =
             if ConversationP2P::supports_stackerdb(network.get_local_peer().services)
                 && ConversationP2P::supports_stackerdb(self.peer_services)
@@ -2931,6 +3012,9 @@ impl ConversationP2P {
         dns_client_opt: &mut Option<&mut DNSClient>,
         ibd: bool,
     ) -> Result<Vec<StacksMessage>, net_error> {
+        #[cfg(test)]
+        network.network_faults.filter_inbox(self);
+
         let num_inbound = self.connection.inbox_len();
         debug!("{:?}: {} messages pending", &self, num_inbound);
 
@@ -6597,6 +6681,23 @@ mod test {
         assert_eq!(bw_stats.get_transaction_push_bandwidth(), 110.0);
     }
 
+    #[test]
+    fn test_neighbor_stats_http_download_throughput() {
+        let mut stats = NeighborStats::new(true);
+        assert_eq!(stats.get_http_download_throughput(), 0.0);
+
+        // 4000 bytes in 2 seconds
+        stats.add_http_download(1000, 500);
+        stats.add_http_download(3000, 1500);
+        assert_eq!(stats.get_http_download_throughput(), 2000.0);
+
+        // only the most recent downloads count
+        for _ in 0..NUM_BANDWIDTH_POINTS {
+            stats.add_http_download(100, 1000);
+        }
+        assert_eq!(stats.get_http_download_throughput(), 100.0);
+    }
+
     #[test]
     fn test_neighbor_stats_microblocks_push_bandwidth() {
         let mut stats = NeighborStats::new(false);
@@ -7439,4 +7540,84 @@ mod test {
             .is_some());
         assert_eq!(convo_1.stats.msgs_err, err_before);
     }
+
+    #[test]
+    /// Large payloads are only compressed if compression is enabled and the remote peer
+    /// advertises the COMPRESSION service bit
+    fn convo_compress_only_for_advertising_peers() {
+        let burnchain = testing_burnchain_config("convo_compress_only_for_advertising_peers");
+        let socketaddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 8081);
+        let mut chain_view = BurnchainView {
+            burn_block_height: 12348,
+            burn_block_hash: BurnchainHeaderHash([0x11; 32]),
+            burn_stable_block_height: 12341,
+            burn_stable_block_hash: BurnchainHeaderHash([0x22; 32]),
+            last_burn_block_hashes: HashMap::new(),
+            rc_consensus_hash: ConsensusHash([0x33; 20]),
+        };
+        chain_view.make_test_data();
+        let privkey = Secp256k1PrivateKey::random();
+
+        let payload = StacksMessageType::StackerDBPushChunk(StackerDBPushChunkData {
+            contract_id: QualifiedContractIdentifier::parse(
+                "SP8QPP8TVXYAXS1VFSERG978A6WKBF59NSYJQEMN.foo",
+            )
+            .unwrap(),
+            rc_consensus_hash: ConsensusHash([0x01; 20]),
+            chunk_data: StackerDBChunkData {
+                slot_id: 2,
+                slot_version: 3,
+                sig: MessageSignature::from_raw(&[0x44; 65]),
+                data: vec![0x55; 8192],
+            },
+        });
+
+        let enabled_opts = ConnectionOptions {
+            p2p_compression: true,
+            ..ConnectionOptions::default()
+        };
+        for (conn_opts, peer_services, expect_compressed) in [
+            // off by default
+            (
+                ConnectionOptions::default(),
+                DEFAULT_SERVICES | (ServiceFlags::COMPRESSION as u16),
+                false,
+            ),
+            (enabled_opts.clone(), DEFAULT_SERVICES, false),
+            (
+                enabled_opts.clone(),
+                DEFAULT_SERVICES | (ServiceFlags::COMPRESSION as u16),
+                true,
+            ),
+        ] {
+            let mut convo = ConversationP2P::new(
+                123,
+                456,
+                &burnchain,
+                &socketaddr,
+                &conn_opts,
+                true,
+                0,
+                StacksEpoch::unit_test_pre_2_05(0),
+            );
+            convo.peer_services = peer_services;
+
+            let msgs = [
+                convo
+                    .sign_message(&chain_view, &privkey, payload.clone())
+                    .unwrap(),
+                convo
+                    .sign_reply(&chain_view, &privkey, payload.clone(), 1)
+                    .unwrap(),
+            ];
+            for msg in msgs {
+                assert_eq!(
+                    matches!(msg.payload, StacksMessageType::Compressed(_)),
+                    expect_compressed,
+                    "peer_services = {peer_services:#x}, p2p_compression = {}",
+                    conn_opts.p2p_compression
+                );
+            }
+        }
+    }
 }
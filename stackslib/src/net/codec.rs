@@ -605,7 +605,38 @@ impl HandshakeData {
             Some((ref public_addrbytes, ref port)) => (public_addrbytes.clone(), *port),
             None => (local_peer.addrbytes.clone(), local_peer.port),
         };
+        HandshakeData::from_local_peer_at(local_peer, addrbytes, port)
+    }
+
+    /// Make handshake data for the peer at `peer_addrbytes`.  If our address is not in the same
+    /// address family as the peer's, then advertise the first of `extra_addresses` that is.
+    pub fn from_local_peer_for(
+        local_peer: &LocalPeer,
+        extra_addresses: &[(PeerAddress, u16)],
+        peer_addrbytes: &PeerAddress,
+    ) -> HandshakeData {
+        let (mut addrbytes, mut port) = match local_peer.public_ip_address {
+            Some((ref public_addrbytes, ref port)) => (public_addrbytes.clone(), *port),
+            None => (local_peer.addrbytes.clone(), local_peer.port),
+        };
+        if addrbytes.is_ipv4() != peer_addrbytes.is_ipv4() {
+            if let Some((extra_addrbytes, extra_port)) = extra_addresses
+                .iter()
+                .find(|(extra_addrbytes, _)| extra_addrbytes.is_ipv4() == peer_addrbytes.is_ipv4())
+            {
+                addrbytes = extra_addrbytes.clone();
+                port = *extra_port;
+            }
+        }
+        HandshakeData::from_local_peer_at(local_peer, addrbytes, port)
+    }
 
+    /// Make handshake data which advertises `addrbytes:port` as our address
+    fn from_local_peer_at(
+        local_peer: &LocalPeer,
+        addrbytes: PeerAddress,
+        port: u16,
+    ) -> HandshakeData {
         // transmit the empty string if our data URL compels us to bind to the anynet address
         let data_url = if local_peer.data_url.has_routable_host() {
             local_peer.data_url.clone()
@@ -909,6 +940,28 @@ impl StacksMessageCodec for StackerDBPushChunkData {
     }
 }
 
+impl StacksMessageCodec for CompressedData {
+    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), codec_error> {
+        write_next(fd, &self.uncompressed_len)?;
+        write_next(fd, &self.data)?;
+        Ok(())
+    }
+
+    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<CompressedData, codec_error> {
+        let uncompressed_len: u32 = read_next(fd)?;
+        if uncompressed_len > MAX_MESSAGE_LEN {
+            return Err(codec_error::DeserializeError(
+                "Compressed payload would be too big".to_string(),
+            ));
+        }
+        let data: Vec<u8> = read_next_at_most::<_, u8>(fd, MAX_MESSAGE_LEN)?;
+        Ok(CompressedData {
+            uncompressed_len,
+            data,
+        })
+    }
+}
+
 impl StacksMessageCodec for RelayData {
     fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), codec_error> {
         write_next(fd, &self.peer)?;
@@ -960,6 +1013,7 @@ impl StacksMessageType {
             StacksMessageType::GetNakamotoInv(ref _m) => StacksMessageID::GetNakamotoInv,
             StacksMessageType::NakamotoInv(ref _m) => StacksMessageID::NakamotoInv,
             StacksMessageType::NakamotoBlocks(ref _m) => StacksMessageID::NakamotoBlocks,
+            StacksMessageType::Compressed(ref _m) => StacksMessageID::Compressed,
         }
     }
 
@@ -995,6 +1049,7 @@ impl StacksMessageType {
             StacksMessageType::GetNakamotoInv(ref _m) => "GetNakamotoInv",
             StacksMessageType::NakamotoInv(ref _m) => "NakamotoInv",
             StacksMessageType::NakamotoBlocks(ref _m) => "NakamotoBlocks",
+            StacksMessageType::Compressed(ref _m) => "Compressed",
         }
     }
 
@@ -1111,7 +1166,92 @@ impl StacksMessageType {
                         .collect::<Vec<_>>()
                 )
             }
+            StacksMessageType::Compressed(ref m) => {
+                format!(
+                    "Compressed(sz={},uncompressed_sz={})",
+                    m.data.len(),
+                    m.uncompressed_len
+                )
+            }
+        }
+    }
+
+    /// Can this payload be sent compressed?  Only the payloads that carry blocks or StackerDB
+    /// chunks can; the rest are too small to benefit.
+    pub fn is_compressible(&self) -> bool {
+        matches!(
+            self,
+            StacksMessageType::Blocks(_)
+                | StacksMessageType::Microblocks(_)
+                | StacksMessageType::NakamotoBlocks(_)
+                | StacksMessageType::StackerDBChunk(_)
+                | StacksMessageType::StackerDBPushChunk(_)
+        )
+    }
+
+    /// Compress this payload if it is compressible, it encodes to at least `min_len` bytes, and
+    /// compressing it makes it smaller.  Otherwise, return it unchanged.
+    pub fn compress(self, min_len: usize) -> StacksMessageType {
+        if !self.is_compressible() {
+            return self;
+        }
+        let bytes = self.serialize_to_vec();
+        if bytes.len() < min_len {
+            return self;
+        }
+        let Ok(uncompressed_len) = u32::try_from(bytes.len()) else {
+            return self;
+        };
+        let data = match zstd::bulk::compress(&bytes, P2P_COMPRESSION_LEVEL) {
+            Ok(data) => data,
+            Err(e) => {
+                warn!(
+                    "Failed to compress {} payload: {e:?}",
+                    self.get_message_name()
+                );
+                return self;
+            }
+        };
+        if data.len() >= bytes.len() {
+            return self;
+        }
+        StacksMessageType::Compressed(CompressedData {
+            uncompressed_len,
+            data,
+        })
+    }
+
+    /// Decompress a `Compressed` payload.  Any other payload is returned unchanged.
+    /// Fails if the compressed data does not hold exactly one compressible payload.
+    pub fn decompress(self) -> Result<StacksMessageType, codec_error> {
+        let StacksMessageType::Compressed(compressed) = self else {
+            return Ok(self);
+        };
+        let uncompressed_len = compressed.uncompressed_len as usize;
+        let bytes = zstd::bulk::decompress(&compressed.data, uncompressed_len).map_err(|e| {
+            codec_error::DeserializeError(format!("Failed to decompress payload: {e:?}"))
+        })?;
+        if bytes.len() != uncompressed_len {
+            return Err(codec_error::DeserializeError(format!(
+                "Decompressed payload is {} bytes, but expected {uncompressed_len}",
+                bytes.len()
+            )));
+        }
+
+        let mut cursor = io::Cursor::new(&bytes[..]);
+        let payload: StacksMessageType = read_next(&mut cursor)?;
+        if cursor.position() != bytes.len() as u64 {
+            return Err(codec_error::DeserializeError(
+                "Decompressed payload has trailing bytes".to_string(),
+            ));
+        }
+        if !payload.is_compressible() {
+            return Err(codec_error::DeserializeError(format!(
+                "Payload {} cannot be sent compressed",
+                payload.get_message_name()
+            )));
         }
+        Ok(payload)
     }
 }
 
@@ -1163,6 +1303,7 @@ impl StacksMessageCodec for StacksMessageID {
             x if x == StacksMessageID::GetNakamotoInv as u8 => StacksMessageID::GetNakamotoInv,
             x if x == StacksMessageID::NakamotoInv as u8 => StacksMessageID::NakamotoInv,
             x if x == StacksMessageID::NakamotoBlocks as u8 => StacksMessageID::NakamotoBlocks,
+            x if x == StacksMessageID::Compressed as u8 => StacksMessageID::Compressed,
             _ => {
                 return Err(codec_error::DeserializeError(
                     "Unknown message ID".to_string(),
@@ -1208,6 +1349,7 @@ impl StacksMessageCodec for StacksMessageType {
             StacksMessageType::GetNakamotoInv(ref m) => write_next(fd, m)?,
             StacksMessageType::NakamotoInv(ref m) => write_next(fd, m)?,
             StacksMessageType::NakamotoBlocks(ref m) => write_next(fd, m)?,
+            StacksMessageType::Compressed(ref m) => write_next(fd, m)?,
         }
         Ok(())
     }
@@ -1322,6 +1464,10 @@ impl StacksMessageCodec for StacksMessageType {
                 let m: NakamotoBlocksData = read_next(fd)?;
                 StacksMessageType::NakamotoBlocks(m)
             }
+            StacksMessageID::Compressed => {
+                let m: CompressedData = read_next(fd)?;
+                StacksMessageType::Compressed(m)
+            }
             StacksMessageID::Reserved => {
                 return Err(codec_error::DeserializeError(
                     "Unsupported message ID 'reserved'".to_string(),
@@ -1567,6 +1713,9 @@ impl ProtocolFamily for StacksP2P {
 
         let mut cursor = io::Cursor::new(&bytes[0..(preamble.payload_len as usize)]);
         let (relayers, payload) = StacksMessage::deserialize_body(&mut cursor)?;
+
+        // the signature covers the compressed payload, so the bytes can still be verified
+        let payload = payload.decompress()?;
         let message = StacksMessage {
             preamble: preamble.clone(),
             relayers,
@@ -2406,6 +2555,111 @@ pub mod test {
         check_codec_and_corruption::<StackerDBPushChunkData>(&push_data, &bytes);
     }
 
+    #[test]
+    fn codec_CompressedData() {
+        let data = CompressedData {
+            uncompressed_len: 0x01020304,
+            data: vec![0x55, 0x66, 0x77],
+        };
+        let bytes = vec![
+            // uncompressed len
+            0x01, 0x02, 0x03, 0x04, // length
+            0x00, 0x00, 0x00, 0x03, // data
+            0x55, 0x66, 0x77,
+        ];
+        check_codec_and_corruption::<CompressedData>(&data, &bytes);
+
+        // can't claim to decompress to more than a message's worth of bytes
+        let too_big = CompressedData {
+            uncompressed_len: MAX_MESSAGE_LEN + 1,
+            data: vec![0x55, 0x66, 0x77],
+        };
+        assert!(
+            CompressedData::consensus_deserialize(&mut &too_big.serialize_to_vec()[..]).is_err()
+        );
+    }
+
+    #[test]
+    fn codec_compress_payload() {
+        let chunk = StackerDBPushChunkData {
+            contract_id: QualifiedContractIdentifier::parse(
+                "SP8QPP8TVXYAXS1VFSERG978A6WKBF59NSYJQEMN.foo",
+            )
+            .unwrap(),
+            rc_consensus_hash: ConsensusHash([0x01; 20]),
+            chunk_data: StackerDBChunkData {
+                slot_id: 2,
+                slot_version: 3,
+                sig: MessageSignature::from_raw(&[0x44; 65]),
+                data: vec![0x55; 8192],
+            },
+        };
+        let payload = StacksMessageType::StackerDBPushChunk(chunk);
+        let payload_len = payload.serialize_to_vec().len();
+
+        let compressed = payload.clone().compress(4096);
+        let StacksMessageType::Compressed(ref compressed_data) = compressed else {
+            panic!("Expected a compressed payload, got {compressed:?}");
+        };
+        assert_eq!(compressed_data.uncompressed_len as usize, payload_len);
+        assert!(compressed.serialize_to_vec().len() < payload_len);
+        assert_eq!(compressed.clone().decompress().unwrap(), payload);
+
+        // small and non-bulk payloads are left alone
+        assert_eq!(payload.clone().compress(payload_len + 1), payload);
+        let ping = StacksMessageType::Ping(PingData { nonce: 0x01020304 });
+        assert_eq!(ping.clone().compress(0), ping);
+        assert_eq!(ping.clone().decompress().unwrap(), ping);
+
+        // compressed data must hold a single compressible payload
+        for bad_payload in [ping.serialize_to_vec(), compressed.serialize_to_vec(), {
+            let mut bytes = payload.serialize_to_vec();
+            bytes.push(0x00);
+            bytes
+        }] {
+            let bad = StacksMessageType::Compressed(CompressedData {
+                uncompressed_len: bad_payload.len() as u32,
+                data: zstd::bulk::compress(&bad_payload, P2P_COMPRESSION_LEVEL).unwrap(),
+            });
+            assert!(bad.decompress().is_err());
+        }
+
+        // the uncompressed length must be right
+        let mut wrong_len = compressed_data.clone();
+        wrong_len.uncompressed_len -= 1;
+        assert!(StacksMessageType::Compressed(wrong_len)
+            .decompress()
+            .is_err());
+
+        // a signed, compressed message is verified as sent, and read back decompressed
+        let privkey = Secp256k1PrivateKey::random();
+        let mut msg = StacksMessage::new(
+            PEER_VERSION_TESTNET,
+            0x9abcdef0,
+            12345,
+            &BurnchainHeaderHash([0x11; 32]),
+            12339,
+            &BurnchainHeaderHash([0x22; 32]),
+            compressed,
+        );
+        msg.sign(444, &privkey).unwrap();
+        let bytes = msg.serialize_to_vec();
+
+        let mut protocol = StacksP2P::new();
+        let (preamble, preamble_len) = protocol.read_preamble(&bytes).unwrap();
+        protocol
+            .verify_payload_bytes(
+                &Secp256k1PublicKey::from_private(&privkey),
+                &preamble,
+                &bytes[preamble_len..],
+            )
+            .unwrap();
+        let (read_msg, _) = protocol
+            .read_payload(&preamble, &bytes[preamble_len..])
+            .unwrap();
+        assert_eq!(read_msg.payload, payload);
+    }
+
     #[test]
     fn codec_GetNakamotoInv() {
         let get_nakamoto_inv = GetNakamotoInvData {
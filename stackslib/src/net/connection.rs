@@ -16,6 +16,7 @@
 
 use std::collections::{HashMap, VecDeque};
 use std::io::{Read, Write};
+use std::net::Ipv4Addr;
 use std::ops::{Deref, DerefMut};
 use std::sync::mpsc::{
     sync_channel, Receiver, RecvError, RecvTimeoutError, SyncSender, TryRecvError, TrySendError,
@@ -37,15 +38,19 @@ use crate::core::mempool::MAX_BLOOM_COUNTER_TXS;
 use crate::monitoring::{update_inbound_bandwidth, update_outbound_bandwidth};
 use crate::net::codec::*;
 use crate::net::download::BLOCK_DOWNLOAD_INTERVAL;
+use crate::net::httpcore::{AdminToken, HttpCorsPolicy, HttpRateLimitConfig};
 use crate::net::inv::{INV_REWARD_CYCLES, INV_SYNC_INTERVAL};
+use crate::net::nat::NatProtocol;
+use crate::net::neighbors::seeds::DNSSeed;
 use crate::net::neighbors::{
     MAX_NEIGHBOR_AGE, NEIGHBOR_REQUEST_TIMEOUT, NEIGHBOR_WALK_INTERVAL, NUM_INITIAL_WALKS,
     WALK_MAX_DURATION, WALK_MIN_DURATION, WALK_RESET_INTERVAL, WALK_RESET_PROB, WALK_RETRY_COUNT,
     WALK_SEED_PROBABILITY, WALK_STATE_TIMEOUT,
 };
+use crate::net::tls::{TlsPeerConfig, TlsServerConfig};
 use crate::net::{
     Error as net_error, MessageSequence, NeighborAddress, Preamble, ProtocolFamily, RelayData,
-    StacksHttp, StacksP2P,
+    StacksHttp, StacksP2P, MAX_BROADCAST_INBOUND_RECEIVERS, MAX_BROADCAST_OUTBOUND_RECEIVERS,
 };
 
 /// The default maximum age in seconds of a block that can be validated by the block proposal endpoint
@@ -388,6 +393,8 @@ pub struct ConnectionOptions {
     pub max_inflight_attachments: u64,
     pub max_attachment_retry_count: u64,
     pub read_only_call_limit: ExecutionCost,
+    /// read budget for serving map entries (and their proofs) over RPC
+    pub read_only_map_entry_limit: ExecutionCost,
     pub maximum_call_argument_size: u32,
     pub max_block_push_bandwidth: u64,
     pub max_microblocks_push_bandwidth: u64,
@@ -396,6 +403,14 @@ pub struct ConnectionOptions {
     pub max_nakamoto_block_push_bandwidth: u64,
     pub max_sockets: usize,
     pub public_ip_address: Option<(PeerAddress, u16)>,
+    /// Public addresses to advertise in handshakes with peers whose address family differs from
+    /// our primary address's
+    pub extra_p2p_addresses: Vec<(PeerAddress, u16)>,
+    /// Seed nodes to resolve and add to the frontier, so the neighbor walk can bootstrap without
+    /// any `bootstrap_node` entries
+    pub dns_seeds: Vec<DNSSeed>,
+    /// How often to re-resolve the DNS seeds, in seconds
+    pub dns_seed_interval: u64,
     pub public_ip_request_timeout: u64,
     pub public_ip_timeout: u64,
     pub public_ip_max_retries: u64,
@@ -432,16 +447,69 @@ pub struct ConnectionOptions {
     pub max_nakamoto_block_relay_age: u64,
     /// minimum amount of time between requests to push nakamoto blocks (millis)
     pub nakamoto_push_interval_ms: u128,
+    /// how long to remember that a peer has a nakamoto block, because it pushed it to us or we
+    /// pushed it to it, so we don't push it to that peer again (millis)
+    pub nakamoto_push_dedup_window_ms: u128,
     /// minimum amount of time between requests to push nakamoto blocks (millis)
     pub nakamoto_inv_sync_burst_interval_ms: u128,
     /// time between unconfirmed downloader runs
     pub nakamoto_unconfirmed_downloader_interval_ms: u128,
     /// The authorization token to enable privileged RPC endpoints
     pub auth_token: Option<String>,
+    /// Named operator tokens for the `/v3/admin/*` endpoints, each limited to some roles.  The
+    /// `auth_token` is accepted in every role as well.
+    pub admin_tokens: Vec<AdminToken>,
     /// The maximum age in seconds of a block that can be validated by the block proposal endpoint
     pub block_proposal_max_age_secs: u64,
     /// StackerDB replicas to talk to for a particular smart contract
     pub stackerdb_hint_replicas: HashMap<QualifiedContractIdentifier, Vec<NeighborAddress>>,
+    /// Per-source request rate limits for the RPC server
+    pub rpc_rate_limits: HttpRateLimitConfig,
+    /// Browser origins that may read RPC responses
+    pub rpc_cors_policy: HttpCorsPolicy,
+    /// The readiness probe fails if the processed burnchain tip is more than this many blocks
+    /// behind the highest burnchain block known to the node
+    pub health_max_burn_block_lag: u64,
+    /// The readiness probe fails if the Stacks tip is more than this many tenures behind the
+    /// processed burnchain tip
+    pub health_max_tenure_lag: u64,
+    /// If the Stacks tip has not changed for this many seconds while neighbors report later
+    /// tenures, the node re-downloads the missing tenures and reports the stale tip in the
+    /// readiness probe.  0 disables the check.
+    pub stale_tip_timeout_secs: u64,
+    /// Most outbound neighbors to forward each new transaction to
+    pub tx_broadcast_max_outbound: usize,
+    /// Most inbound neighbors to forward each new transaction to
+    pub tx_broadcast_max_inbound: usize,
+    /// Most neighbors advertising the MINER service bit to eagerly push each new transaction to,
+    /// on top of the regular broadcast sample.  0 disables eager pushes.
+    pub tx_push_max_miner_neighbors: usize,
+    /// Most threads to use to check the signatures of a batch of downloaded or pushed Nakamoto
+    /// blocks before storing them, and of the transactions in a block proposal before
+    /// validating it
+    pub nakamoto_sigcheck_threads: usize,
+    /// Number of read replica workers to serve read-only function calls and contract analyses
+    /// from, instead of the peer thread.  0 disables read replicas.
+    pub rpc_read_replicas: usize,
+    /// Whether this node is an archival node, and serves the `/v3/archive` endpoints
+    pub archival: bool,
+    /// Certificate for serving RPC requests over TLS, if any
+    pub rpc_tls: Option<TlsServerConfig>,
+    /// Mutual TLS settings for p2p sessions, if any.  If set, every peer must use TLS.
+    pub p2p_tls: Option<TlsPeerConfig>,
+    /// Whether to advertise the COMPRESSION service bit, and zstd-compress large payloads sent to
+    /// peers that advertise it too.  Off by default.
+    pub p2p_compression: bool,
+    /// Smallest encoded payload, in bytes, that is worth compressing
+    pub p2p_compression_min_len: usize,
+    /// Whether to map the p2p port on the NAT gateway and check that it is reachable
+    pub nat_traversal: bool,
+    /// Protocol with which to map the p2p port, or None to try UPnP and then NAT-PMP
+    pub nat_protocol: Option<NatProtocol>,
+    /// Gateway to send NAT-PMP requests to, or None to use the default route's
+    pub nat_gateway: Option<Ipv4Addr>,
+    /// How long the gateway should keep the port mapping, in seconds
+    pub nat_mapping_lifetime: u64,
 
     // fault injection
     /// Disable neighbor walk and discovery
@@ -542,6 +610,13 @@ impl std::default::Default for ConnectionOptions {
                 read_count: 30,
                 runtime: 1_000_000_000,
             },
+            read_only_map_entry_limit: ExecutionCost {
+                write_length: 0,
+                write_count: 0,
+                read_length: 10_000_000, // enough for the largest Clarity value, with its proof
+                read_count: 30,
+                runtime: 0,
+            },
             maximum_call_argument_size: 20 * BOUND_VALUE_SERIALIZATION_HEX,
             max_block_push_bandwidth: 0, // infinite upload bandwidth allowed
             max_microblocks_push_bandwidth: 0, // infinite upload bandwidth allowed
@@ -550,6 +625,9 @@ impl std::default::Default for ConnectionOptions {
             max_nakamoto_block_push_bandwidth: 0, // infinite upload bandwidth allowed
             max_sockets: 800,            // maximum number of client sockets we'll ever register
             public_ip_address: None,     // resolve it at runtime by default
+            extra_p2p_addresses: vec![],
+            dns_seeds: vec![],
+            dns_seed_interval: 3600,       // re-resolve DNS seeds every hour
             public_ip_request_timeout: 60, // how often we can attempt to look up our public IP address
             public_ip_timeout: 3600,       // re-learn the public IP ever hour, if it's not given
             public_ip_max_retries: 3, // maximum number of retries before self-throttling for $public_ip_timeout
@@ -571,11 +649,32 @@ impl std::default::Default for ConnectionOptions {
             private_neighbors: true,
             max_nakamoto_block_relay_age: 6,
             nakamoto_push_interval_ms: 30_000, // re-send a block no more than once every 30 seconds
+            nakamoto_push_dedup_window_ms: 600_000, // don't push a block to a peer that has had it in the last 10 minutes
             nakamoto_inv_sync_burst_interval_ms: 1_000, // wait 1 second after a sortition before running inventory sync
             nakamoto_unconfirmed_downloader_interval_ms: 5_000, // run unconfirmed downloader once every 5 seconds
             auth_token: None,
+            admin_tokens: vec![],
             block_proposal_max_age_secs: DEFAULT_BLOCK_PROPOSAL_MAX_AGE_SECS,
             stackerdb_hint_replicas: HashMap::new(),
+            rpc_rate_limits: HttpRateLimitConfig::default(),
+            rpc_cors_policy: HttpCorsPolicy::default(),
+            health_max_burn_block_lag: 2,
+            health_max_tenure_lag: 2,
+            stale_tip_timeout_secs: 180,
+            tx_broadcast_max_outbound: MAX_BROADCAST_OUTBOUND_RECEIVERS,
+            tx_broadcast_max_inbound: MAX_BROADCAST_INBOUND_RECEIVERS,
+            tx_push_max_miner_neighbors: 8,
+            nakamoto_sigcheck_threads: 4,
+            rpc_read_replicas: 0,
+            archival: false,
+            rpc_tls: None,
+            p2p_tls: None,
+            p2p_compression: false,
+            p2p_compression_min_len: 4096,
+            nat_traversal: false,
+            nat_protocol: None,
+            nat_gateway: None,
+            nat_mapping_lifetime: 3600,
 
             // no faults on by default
             disable_neighbor_walk: false,
@@ -1079,6 +1178,12 @@ impl<P: ProtocolFamily> ConnectionInbox<P> {
     pub fn num_messages(&self) -> usize {
         self.inbox.len()
     }
+
+    /// Enqueue an already-decoded message (used for fault injection in tests)
+    #[cfg(test)]
+    pub fn push_message(&mut self, message: P::Message) {
+        self.inbox.push_back(message);
+    }
 }
 
 impl<P: ProtocolFamily> ConnectionOutbox<P> {
@@ -1468,6 +1573,12 @@ impl<P: ProtocolFamily + Clone> NetworkConnection<P> {
         self.inbox.next_message()
     }
 
+    /// put a message at the back of the inbox (used for fault injection in tests)
+    #[cfg(test)]
+    pub fn push_inbox_message(&mut self, message: P::Message) {
+        self.inbox.push_message(message)
+    }
+
     /// set the public key
     pub fn set_public_key(&mut self, pubk: Option<Secp256k1PublicKey>) {
         self.inbox.public_key = pubk;
@@ -1514,8 +1625,7 @@ mod test {
     use std::sync::{Arc, Mutex};
     use std::{io, thread};
 
-    use rand;
-    use rand::RngCore;
+    use rand::{self, RngCore};
     use stacks_common::util::pipe::*;
     use stacks_common::util::secp256k1::*;
     use stacks_common::util::*;
@@ -45,7 +45,7 @@ use crate::util_lib::db::{
 };
 use crate::util_lib::strings::UrlString;
 
-pub const PEERDB_VERSION: &str = "3";
+pub const PEERDB_VERSION: &str = "4";
 
 const NUM_SLOTS: usize = 8;
 
@@ -408,6 +408,14 @@ const PEERDB_SCHEMA_3: &[&str] = &[
     "UPDATE db_config SET version = 3;",
 ];
 
+const PEERDB_SCHEMA_4: &[&str] = &[
+    r#"
+    -- the services bitfield each peer last advertised in its handshake (0 if not yet known)
+    ALTER TABLE frontier ADD COLUMN services INTEGER NOT NULL DEFAULT 0;
+    "#,
+    "UPDATE db_config SET version = 4;",
+];
+
 #[derive(Debug)]
 pub struct PeerDB {
     pub conn: Connection,
@@ -554,6 +562,15 @@ impl PeerDB {
         Ok(())
     }
 
+    #[cfg_attr(test, mutants::skip)]
+    fn apply_schema_4(tx: &Transaction) -> Result<(), db_error> {
+        test_debug!("Apply schema 4 to peer DB");
+        for row_text in PEERDB_SCHEMA_4 {
+            tx.execute_batch(row_text).map_err(db_error::SqliteError)?;
+        }
+        Ok(())
+    }
+
     fn apply_schema_migrations(tx: &Transaction) -> Result<String, db_error> {
         test_debug!("Apply any schema migrations");
         let expected_version = PEERDB_VERSION.to_string();
@@ -568,6 +585,8 @@ impl PeerDB {
                         PeerDB::apply_schema_2(tx)?;
                     } else if version == "2" {
                         PeerDB::apply_schema_3(tx)?;
+                    } else if version == "3" {
+                        PeerDB::apply_schema_4(tx)?;
                     } else if version == expected_version {
                         return Ok(ret.expect("unreachable"));
                     } else {
@@ -1307,6 +1326,37 @@ impl PeerDB {
         Ok(())
     }
 
+    /// Record the services bitfield a peer advertised in its handshake.  Does nothing if the peer
+    /// is not present.
+    pub fn set_peer_services(
+        tx: &Transaction,
+        network_id: u32,
+        peer_addr: &PeerAddress,
+        peer_port: u16,
+        services: u16,
+    ) -> Result<(), db_error> {
+        tx.execute(
+            "UPDATE frontier SET services = ?1 WHERE network_id = ?2 AND addrbytes = ?3 AND port = ?4",
+            params![services, network_id, peer_addr.to_bin(), peer_port],
+        )
+        .map_err(db_error::SqliteError)?;
+        Ok(())
+    }
+
+    /// Get the services bitfield a peer last advertised, if the peer is present.  This is 0 if
+    /// the peer has not handshaked with us since it was added.
+    pub fn get_peer_services(
+        conn: &DBConn,
+        network_id: u32,
+        peer_addr: &PeerAddress,
+        peer_port: u16,
+    ) -> Result<Option<u16>, db_error> {
+        let qry =
+            "SELECT services FROM frontier WHERE network_id = ?1 AND addrbytes = ?2 AND port = ?3";
+        let args = params![network_id, peer_addr.to_bin(), peer_port];
+        query_row(conn, qry, args)
+    }
+
     /// Update an existing peer's entries.  Does nothing if the peer is not present.
     pub fn update_peer(tx: &Transaction, neighbor: &Neighbor) -> Result<(), db_error> {
         let old_peer_opt = PeerDB::get_peer(
@@ -1859,13 +1909,16 @@ impl PeerDB {
         if max_count == 0 {
             return Ok(vec![]);
         }
-        let qry = "SELECT frontier.* FROM frontier JOIN stackerdb_peers ON stackerdb_peers.peer_slot = frontier.slot WHERE stackerdb_peers.smart_contract_id = ?1 AND frontier.network_id = ?2 AND frontier.last_contact_time >= ?3 ORDER BY RANDOM() LIMIT ?4";
+        // skip peers that have since stopped advertising StackerDB support, but keep the ones
+        // whose services we don't know yet
+        let qry = "SELECT frontier.* FROM frontier JOIN stackerdb_peers ON stackerdb_peers.peer_slot = frontier.slot WHERE stackerdb_peers.smart_contract_id = ?1 AND frontier.network_id = ?2 AND frontier.last_contact_time >= ?3 AND (frontier.services = 0 OR (frontier.services & ?5) != 0) ORDER BY RANDOM() LIMIT ?4";
         let max_count_u32 = u32::try_from(max_count).unwrap_or(u32::MAX);
         let args = params![
             smart_contract.to_string(),
             network_id,
             u64_to_sql(min_age)?,
             max_count_u32,
+            ServiceFlags::STACKERDB as u16,
         ];
         Self::query_peers(conn, qry, args)
     }
@@ -2480,6 +2533,136 @@ mod test {
         assert_eq!(deleted_stackerdbs.len(), 0);
     }
 
+    /// Verifies that a peer's advertised services are stored, and that StackerDB replicas which
+    /// stop advertising StackerDB support are no longer returned.
+    #[test]
+    fn test_peer_services() {
+        let neighbor = Neighbor {
+            addr: NeighborKey {
+                peer_version: 0x12345678,
+                network_id: 0x9abcdef0,
+                addrbytes: PeerAddress([
+                    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+                    0x0d, 0x0e, 0x0f,
+                ]),
+                port: 12345,
+            },
+            public_key: Secp256k1PublicKey::from_hex(
+                "02fa66b66f8971a8cd4d20ffded09674e030f0f33883f337f34b95ad4935bac0e3",
+            )
+            .unwrap(),
+            expire_block: 23456,
+            last_contact_time: 1552509642,
+            allowed: -1,
+            denied: -1,
+            asn: 34567,
+            org: 45678,
+            in_degree: 1,
+            out_degree: 1,
+        };
+
+        let path = "/tmp/test-peerdb-peer_services.db".to_string();
+        if fs::metadata(&path).is_ok() {
+            fs::remove_file(&path).unwrap();
+        }
+        let mut db = PeerDB::connect(
+            &path,
+            true,
+            0x9abcdef0,
+            12345,
+            Some(Secp256k1PrivateKey::random()),
+            i64::MAX as u64,
+            PeerAddress::from_ipv4(127, 0, 0, 1),
+            12345,
+            UrlString::try_from("http://foo.com").unwrap(),
+            &[],
+            None,
+            &[],
+        )
+        .unwrap();
+
+        let stackerdbs = vec![QualifiedContractIdentifier::new(
+            StandardPrincipalData::new(0x01, [0x02; 20]).unwrap(),
+            "db-1".into(),
+        )];
+
+        // unknown peer
+        assert_eq!(
+            PeerDB::get_peer_services(
+                &db.conn,
+                0x9abcdef0,
+                &neighbor.addr.addrbytes,
+                neighbor.addr.port
+            )
+            .unwrap(),
+            None
+        );
+
+        let tx = db.tx_begin().unwrap();
+        assert!(PeerDB::try_insert_peer(&tx, &neighbor, &stackerdbs).unwrap());
+        tx.commit().unwrap();
+
+        // services aren't known until the peer handshakes, but it's still a replica candidate
+        assert_eq!(
+            PeerDB::get_peer_services(
+                &db.conn,
+                0x9abcdef0,
+                &neighbor.addr.addrbytes,
+                neighbor.addr.port
+            )
+            .unwrap(),
+            Some(0)
+        );
+        let replicas =
+            PeerDB::find_stacker_db_replicas(&db.conn, 0x9abcdef0, &stackerdbs[0], 0, 1).unwrap();
+        assert_eq!(replicas, vec![neighbor.clone()]);
+
+        let services = (ServiceFlags::RELAY as u16)
+            | (ServiceFlags::RPC as u16)
+            | (ServiceFlags::STACKERDB as u16)
+            | (ServiceFlags::TENURES as u16);
+        let tx = db.tx_begin().unwrap();
+        PeerDB::set_peer_services(
+            &tx,
+            0x9abcdef0,
+            &neighbor.addr.addrbytes,
+            neighbor.addr.port,
+            services,
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(
+            PeerDB::get_peer_services(
+                &db.conn,
+                0x9abcdef0,
+                &neighbor.addr.addrbytes,
+                neighbor.addr.port
+            )
+            .unwrap(),
+            Some(services)
+        );
+        let replicas =
+            PeerDB::find_stacker_db_replicas(&db.conn, 0x9abcdef0, &stackerdbs[0], 0, 1).unwrap();
+        assert_eq!(replicas, vec![neighbor.clone()]);
+
+        // peer stops serving StackerDB
+        let tx = db.tx_begin().unwrap();
+        PeerDB::set_peer_services(
+            &tx,
+            0x9abcdef0,
+            &neighbor.addr.addrbytes,
+            neighbor.addr.port,
+            (ServiceFlags::RELAY as u16) | (ServiceFlags::RPC as u16),
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        let replicas =
+            PeerDB::find_stacker_db_replicas(&db.conn, 0x9abcdef0, &stackerdbs[0], 0, 1).unwrap();
+        assert!(replicas.is_empty());
+    }
+
     /// Test PeerDB::find_stacker_db_replicas().  Verifies that we can find a list of neighbors
     /// that serve a particular stacker DB, given their contract IDs
     #[test]
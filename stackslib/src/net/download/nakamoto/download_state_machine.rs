@@ -13,6 +13,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::borrow::Cow;
 use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::convert::TryFrom;
 use std::fmt;
@@ -1408,6 +1409,56 @@ impl NakamotoDownloadStateMachine {
         tenure_blocks
     }
 
+    /// What services does this neighbor offer?  This uses the services it advertised on its
+    /// current connection, or the ones it last advertised according to the peer DB if it's not
+    /// connected.  Returns None if they are not known.
+    fn neighbor_services(network: &PeerNetwork, naddr: &NeighborAddress) -> Option<u16> {
+        let nk = naddr.to_neighbor_key(network);
+        if let Some(convo) = network.get_neighbor_convo(&nk) {
+            return Some(convo.peer_services);
+        }
+        match PeerDB::get_peer_services(
+            network.peerdb_conn(),
+            nk.network_id,
+            &nk.addrbytes,
+            nk.port,
+        ) {
+            Ok(Some(services)) if services != 0 => Some(services),
+            _ => None,
+        }
+    }
+
+    /// Can we download tenures from this neighbor?  Neighbors whose services are unknown are
+    /// assumed to serve tenures.
+    fn serves_tenure_downloads(network: &PeerNetwork, naddr: &NeighborAddress) -> bool {
+        Self::neighbor_services(network, naddr)
+            .map(ConversationP2P::serves_tenure_downloads)
+            .unwrap_or(true)
+    }
+
+    /// Is this neighbor an archival node?  Neighbors whose services are unknown are assumed not
+    /// to be.
+    fn is_archival_neighbor(network: &PeerNetwork, naddr: &NeighborAddress) -> bool {
+        Self::neighbor_services(network, naddr)
+            .map(ConversationP2P::is_archival)
+            .unwrap_or(false)
+    }
+
+    /// While in initial block download, try archival neighbors first for each tenure.  They
+    /// keep every block, so they are the most likely to be able to serve old tenures.
+    /// Tenure downloaders take neighbors from the back of each list, so archival neighbors are
+    /// moved to the back.
+    fn prefer_archival_neighbors(&mut self, network: &PeerNetwork) {
+        let mut archival = HashMap::new();
+        for neighbors in self.available_tenures.values_mut() {
+            neighbors.sort_by_key(|naddr| {
+                *archival
+                    .entry(naddr.clone())
+                    .or_insert_with(|| Self::is_archival_neighbor(network, naddr))
+            });
+        }
+    }
+
     /// Top-level download state machine execution.
     ///
     /// The downloader transitions between two states in perpetuity: obtaining confirmed tenures,
@@ -1440,12 +1491,32 @@ impl NakamotoDownloadStateMachine {
             return HashMap::new();
         };
 
+        // only download from neighbors that serve tenures
+        let inventories = if invs
+            .inventories
+            .keys()
+            .all(|naddr| Self::serves_tenure_downloads(network, naddr))
+        {
+            Cow::Borrowed(&invs.inventories)
+        } else {
+            Cow::Owned(
+                invs.inventories
+                    .iter()
+                    .filter(|(naddr, _)| Self::serves_tenure_downloads(network, naddr))
+                    .map(|(naddr, inv)| (naddr.clone(), inv.clone()))
+                    .collect(),
+            )
+        };
+
         self.update_available_tenures(
-            &invs.inventories,
+            &inventories,
             &sortdb.pox_constants,
             sortdb.first_block_height,
             ibd,
         );
+        if ibd {
+            self.prefer_archival_neighbors(network);
+        }
 
         // check this now, since we mutate self.available
         self.fetch_unconfirmed_tenures = if self
@@ -1549,6 +1620,36 @@ impl NakamotoDownloadStateMachine {
         }
     }
 
+    /// Fetch every wanted tenure that has not been processed again, including the ones that were
+    /// already downloaded, and restart the unconfirmed tenure downloads.  The p2p state machine
+    /// calls this when the Stacks tip has stopped advancing even though neighbors have later
+    /// tenures, in case downloaded blocks were lost before they could be processed.
+    ///
+    /// Returns the number of unprocessed tenures that will be fetched again.
+    pub(crate) fn redownload_unprocessed_tenures(&mut self) -> usize {
+        let unprocessed: HashSet<ConsensusHash> = self
+            .prev_wanted_tenures
+            .iter()
+            .flatten()
+            .chain(self.wanted_tenures.iter())
+            .filter(|wt| !wt.processed)
+            .map(|wt| wt.tenure_id_consensus_hash.clone())
+            .collect();
+        let forgotten = self.tenure_downloads.forget_tenures(&unprocessed);
+        debug!(
+            "Downloader: will fetch {} unprocessed tenure(s) again ({forgotten} already downloaded)",
+            unprocessed.len()
+        );
+
+        // recompute availability and the download schedule on the next pass
+        self.tenure_download_schedule.clear();
+        self.unconfirmed_tenure_download_schedule.clear();
+        self.unconfirmed_tenure_downloads.clear();
+        self.last_unconfirmed_download_check_ms = 0;
+        self.state = NakamotoDownloadState::Confirmed;
+        unprocessed.len()
+    }
+
     /// Go and get tenures. Returns list of blocks per tenure, identified by consensus hash.
     /// The blocks will be sorted by height, but may not be contiguous.
     pub fn run(
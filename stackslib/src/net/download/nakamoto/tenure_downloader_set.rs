@@ -144,6 +144,19 @@ impl NakamotoTenureDownloaderSet {
         }
     }
 
+    /// Forget that the given tenures were downloaded or attempted, so they will be fetched again.
+    /// Returns the number of downloaded tenures that were forgotten.
+    pub(crate) fn forget_tenures(&mut self, tenure_ids: &HashSet<ConsensusHash>) -> usize {
+        let num_completed = self.completed_tenures.len();
+        self.completed_tenures
+            .retain(|completed| !tenure_ids.contains(&completed.tenure_id));
+        for tenure_id in tenure_ids.iter() {
+            self.attempted_tenures.remove(tenure_id);
+            self.attempt_failed_tenures.remove(tenure_id);
+        }
+        num_completed - self.completed_tenures.len()
+    }
+
     /// Mark a peer as deprioritized
     /// Implemented statically to appease the borrow checker.
     fn mark_deprioritized(
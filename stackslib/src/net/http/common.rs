@@ -74,6 +74,10 @@ pub const HTTP_PREAMBLE_MAX_ENCODED_SIZE: u32 = 4096;
 /// Maximum number of headers in an HTTP request or response
 pub const HTTP_PREAMBLE_MAX_NUM_HEADERS: usize = 64;
 
+/// Cache-Control value for responses that will never change, such as blocks by ID.
+/// Caches may hold these for up to a year without revalidating.
+pub const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
 /// Helper function to parse a SIP-003 bytestream.  The first 4 bytes are a big-endian length prefix
 pub fn parse_bytestream<R: Read, T: StacksMessageCodec>(
     preamble: &HttpResponsePreamble,
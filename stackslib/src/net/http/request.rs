@@ -198,6 +198,25 @@ impl HttpRequestPreamble {
         }
     }
 
+    /// Does this request's `If-None-Match` header match the given (unquoted) entity tag?
+    /// Per RFC 9110, `If-None-Match` uses the weak comparison function, so a `W/` prefix on a
+    /// listed tag is ignored, and `*` matches any tag.
+    pub fn if_none_match(&self, etag: &str) -> bool {
+        let Some(if_none_match) = self.headers.get("if-none-match") else {
+            return false;
+        };
+        if_none_match.split(',').any(|tag| {
+            let tag = tag.trim();
+            if tag == "*" {
+                return true;
+            }
+            let tag = tag.strip_prefix("W/").unwrap_or(tag);
+            tag.strip_prefix('"')
+                .and_then(|tag| tag.strip_suffix('"'))
+                .is_some_and(|tag| tag == etag)
+        })
+    }
+
     /// Content-Length for this request.
     /// If there is no valid Content-Length header, then
     /// the Content-Length is 0
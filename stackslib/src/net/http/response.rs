@@ -31,6 +31,7 @@ use {serde, serde_json};
 
 use crate::net::http::common::{
     HttpReservedHeader, HTTP_PREAMBLE_MAX_ENCODED_SIZE, HTTP_PREAMBLE_MAX_NUM_HEADERS,
+    IMMUTABLE_CACHE_CONTROL,
 };
 use crate::net::http::request::{HttpRequestContents, HttpRequestPreamble};
 use crate::net::http::stream::HttpChunkGenerator;
@@ -252,6 +253,28 @@ impl HttpResponsePreamble {
         )
     }
 
+    /// A `304 Not Modified` response to a conditional request for an immutable resource
+    pub fn not_modified(preamble: &HttpRequestPreamble, etag: &str) -> Self {
+        let mut response = HttpResponsePreamble::new(
+            preamble.version,
+            304,
+            http_reason(304).to_string(),
+            Some(0),
+            HttpContentType::Bytes,
+            preamble.keep_alive,
+        );
+        response.set_immutable_etag(etag);
+        response
+    }
+
+    /// Mark this response as an immutable resource with the given (unquoted) strong entity tag,
+    /// so that caches may serve it indefinitely and revalidate it with `If-None-Match`.
+    pub fn set_immutable_etag(&mut self, etag: &str) {
+        self.headers.insert("etag".into(), format!("\"{etag}\""));
+        self.headers
+            .insert("cache-control".into(), IMMUTABLE_CACHE_CONTROL.into());
+    }
+
     #[cfg(test)]
     pub fn from_headers(
         status_code: u16,
@@ -336,9 +359,19 @@ impl HttpResponsePreamble {
         }
     }
 
+    /// Let any origin read this response.
+    /// The RPC server sets these headers according to its CORS policy instead.
     pub fn add_CORS_headers(&mut self) {
         self.headers
-            .insert("Access-Control-Allow-Origin".to_string(), "*".to_string());
+            .insert("access-control-allow-origin".to_string(), "*".to_string());
+        self.headers.insert(
+            "access-control-allow-headers".to_string(),
+            "origin, content-type".to_string(),
+        );
+        self.headers.insert(
+            "access-control-allow-methods".to_string(),
+            "POST, GET, OPTIONS".to_string(),
+        );
     }
 
     // do we have Transfer-Encoding: chunked?
@@ -396,21 +429,6 @@ impl StacksMessageCodec for HttpResponsePreamble {
                 .map_err(CodecError::WriteError)?;
         }
 
-        if !self.headers.contains_key("access-control-allow-origin") {
-            fd.write_all("Access-Control-Allow-Origin: *\r\n".as_bytes())
-                .map_err(CodecError::WriteError)?;
-        }
-
-        if !self.headers.contains_key("access-control-allow-headers") {
-            fd.write_all("Access-Control-Allow-Headers: origin, content-type\r\n".as_bytes())
-                .map_err(CodecError::WriteError)?;
-        }
-
-        if !self.headers.contains_key("access-control-allow-methods") {
-            fd.write_all("Access-Control-Allow-Methods: POST, GET, OPTIONS\r\n".as_bytes())
-                .map_err(CodecError::WriteError)?;
-        }
-
         // content type (reserved header)
         fd.write_all("Content-Type: ".as_bytes())
             .map_err(CodecError::WriteError)?;
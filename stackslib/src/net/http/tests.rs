@@ -257,6 +257,40 @@ fn test_http_request_preamble_headers() {
     );
 }
 
+#[test]
+fn test_http_request_preamble_if_none_match() {
+    let mut req = HttpRequestPreamble::new(
+        HttpVersion::Http11,
+        "GET".to_string(),
+        "/foo".to_string(),
+        "localhost".to_string(),
+        6270,
+        true,
+    );
+    assert!(!req.if_none_match("abcd"));
+
+    req.add_header("If-None-Match".to_string(), "\"abcd\"".to_string());
+    assert!(req.if_none_match("abcd"));
+    assert!(!req.if_none_match("abc"));
+
+    // weak tags and lists of tags
+    req.add_header(
+        "If-None-Match".to_string(),
+        "\"1234\", W/\"abcd\"".to_string(),
+    );
+    assert!(req.if_none_match("abcd"));
+    assert!(req.if_none_match("1234"));
+    assert!(!req.if_none_match("5678"));
+
+    // tags must be quoted
+    req.add_header("If-None-Match".to_string(), "abcd".to_string());
+    assert!(!req.if_none_match("abcd"));
+
+    // wildcard
+    req.add_header("If-None-Match".to_string(), "*".to_string());
+    assert!(req.if_none_match("abcd"));
+}
+
 #[test]
 fn test_parse_http_response_preamble_ok() {
     let tests = vec![
@@ -339,16 +373,16 @@ fn test_http_response_preamble_headers() {
     assert!(txt.find("Date: ").is_some(), "Date header is missing");
     assert!(txt.find("foo: bar\r\n").is_some(), "foo header is missing");
     assert!(
-        txt.find("Access-Control-Allow-Origin: *\r\n").is_some(),
+        txt.find("access-control-allow-origin: *\r\n").is_some(),
         "CORS header is missing"
     );
     assert!(
-        txt.find("Access-Control-Allow-Headers: origin, content-type\r\n")
+        txt.find("access-control-allow-headers: origin, content-type\r\n")
             .is_some(),
         "CORS header is missing"
     );
     assert!(
-        txt.find("Access-Control-Allow-Methods: POST, GET, OPTIONS\r\n")
+        txt.find("access-control-allow-methods: POST, GET, OPTIONS\r\n")
             .is_some(),
         "CORS header is missing"
     );
@@ -15,9 +15,10 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 /// This module binds the http library to Stacks as a `ProtocolFamily` implementation
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::io::{Read, Write};
-use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::str::FromStr;
 use std::time::{Duration, Instant};
 use std::{fmt, io, mem};
 
@@ -41,7 +42,7 @@ use super::rpc::ConversationHttp;
 use crate::burnchains::Txid;
 use crate::chainstate::burn::db::sortdb::SortitionDB;
 use crate::chainstate::burn::BlockSnapshot;
-use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::nakamoto::header_cache;
 use crate::chainstate::stacks::db::{StacksChainState, StacksHeaderInfo};
 use crate::core::{MemPoolDB, StacksEpoch};
 use crate::net::connection::{ConnectionOptions, NetworkConnection};
@@ -362,6 +363,509 @@ impl Clone for Box<dyn RPCRequestHandler> {
     }
 }
 
+/// Rate-limiting class of an RPC endpoint.  Each class has its own request budget per source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointClass {
+    /// Cheap reads, like `/v2/info` or account lookups
+    Read,
+    /// Reads that stream a lot of data from disk or run Clarity code
+    ReadHeavy,
+    /// Requests that submit data to the node, like transactions and blocks
+    Write,
+    /// Operator endpoints which require an admin token
+    Admin,
+}
+
+impl EndpointClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::ReadHeavy => "read_heavy",
+            Self::Write => "write",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for EndpointClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Per-source RPC rate limits.  Each source (an IP address, or a subnet if the prefix lengths are
+/// shorter than a full address) gets a token bucket per endpoint class, which refills at the
+/// class's sustained rate and holds up to `burst_secs` seconds' worth of requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRateLimitConfig {
+    /// Sustained requests per second for `EndpointClass::Read`.  None means unlimited.
+    pub read: Option<u64>,
+    /// Sustained requests per second for `EndpointClass::ReadHeavy`.  None means unlimited.
+    pub read_heavy: Option<u64>,
+    /// Sustained requests per second for `EndpointClass::Write`.  None means unlimited.
+    pub write: Option<u64>,
+    /// Sustained requests per second for `EndpointClass::Admin`.  None means unlimited.
+    pub admin: Option<u64>,
+    /// How many seconds' worth of requests a source may send in a burst
+    pub burst_secs: u64,
+    /// IPv4 sources sharing this many leading bits share a budget
+    pub ipv4_prefix_len: u8,
+    /// IPv6 sources sharing this many leading bits share a budget
+    pub ipv6_prefix_len: u8,
+    /// How many requests to long-polling endpoints may be held at once, across all sources
+    pub max_held_requests: u64,
+}
+
+impl Default for HttpRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            read: None,
+            read_heavy: None,
+            write: None,
+            admin: None,
+            burst_secs: 10,
+            ipv4_prefix_len: 32,
+            ipv6_prefix_len: 64,
+            max_held_requests: 100,
+        }
+    }
+}
+
+impl HttpRateLimitConfig {
+    /// Sustained requests per second allowed for a class, if it is limited at all
+    pub fn limit_for(&self, class: EndpointClass) -> Option<u64> {
+        match class {
+            EndpointClass::Read => self.read,
+            EndpointClass::ReadHeavy => self.read_heavy,
+            EndpointClass::Write => self.write,
+            EndpointClass::Admin => self.admin,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.read.is_some()
+            || self.read_heavy.is_some()
+            || self.write.is_some()
+            || self.admin.is_some()
+    }
+}
+
+/// Which browser origins may read RPC responses (cross-origin resource sharing).  Each allowed
+/// origin is a `scheme://host[:port]` string, or `*` to allow any origin.  An empty list disables
+/// CORS, so browsers will refuse to hand responses to scripts from other origins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpCorsPolicy {
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for HttpCorsPolicy {
+    /// Any origin may read RPC responses
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec!["*".into()],
+        }
+    }
+}
+
+impl HttpCorsPolicy {
+    /// Methods a cross-origin request may use
+    pub const ALLOWED_METHODS: &'static str = "POST, GET, OPTIONS";
+    /// Request headers a cross-origin request may set
+    pub const ALLOWED_HEADERS: &'static str = "origin, content-type";
+    /// How long, in seconds, a browser may cache a preflight response
+    pub const MAX_AGE_SECS: u64 = 86400;
+
+    /// Build a policy from a list of origins, normalizing and validating each one
+    pub fn from_origins(origins: &[String]) -> Result<Self, String> {
+        let mut allowed_origins = Vec::with_capacity(origins.len());
+        for origin in origins.iter() {
+            let origin = origin.trim().trim_end_matches('/').to_ascii_lowercase();
+            if origin != "*" && !Self::is_valid_origin(&origin) {
+                return Err(format!("'{origin}' is not `*` or `scheme://host[:port]`"));
+            }
+            allowed_origins.push(origin);
+        }
+        Ok(Self { allowed_origins })
+    }
+
+    /// Is this a serialized origin (`scheme://host[:port]`, with no path, query, or userinfo)?
+    fn is_valid_origin(origin: &str) -> bool {
+        let Some((scheme, host)) = origin.split_once("://") else {
+            return false;
+        };
+        !scheme.is_empty()
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+            && !host.is_empty()
+            && !host.contains(['/', '?', '#', '@'])
+    }
+
+    /// Does this policy let any origin through?
+    pub fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.iter().any(|origin| origin == "*")
+    }
+
+    /// The `Access-Control-Allow-Origin` value to send to a request from `origin`, if it is
+    /// allowed at all
+    pub fn allowed_origin(&self, origin: Option<&str>) -> Option<String> {
+        if self.allows_any_origin() {
+            return Some("*".into());
+        }
+        let origin = origin?;
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(origin))
+            .then(|| origin.to_string())
+    }
+
+    /// Add the CORS headers for a request from `origin` to its response
+    pub fn apply(&self, origin: Option<&str>, response: &mut HttpResponsePreamble) {
+        if !self.allows_any_origin() && !self.allowed_origins.is_empty() {
+            // the response depends on the origin, so caches must not share it across origins
+            response.add_header("Vary".into(), "Origin".into());
+        }
+        let Some(allow_origin) = self.allowed_origin(origin) else {
+            return;
+        };
+        response.add_header("Access-Control-Allow-Origin".into(), allow_origin);
+        response.add_header(
+            "Access-Control-Allow-Headers".into(),
+            Self::ALLOWED_HEADERS.into(),
+        );
+        response.add_header(
+            "Access-Control-Allow-Methods".into(),
+            Self::ALLOWED_METHODS.into(),
+        );
+    }
+}
+
+/// What an operator token may do on the `/v3/admin/*` endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AdminRole {
+    /// Ban peers
+    Peers,
+    /// Take database backups
+    Backup,
+    /// Drain the mempool
+    Mempool,
+    /// Pause and resume mining
+    Mining,
+    /// Read and change log levels
+    Logging,
+    /// Override PoX anchor block statuses and confirm deep reorgs
+    Chainstate,
+    /// Replicate StackerDB chunks from a primary node
+    StackerDB,
+    /// Validate block proposals and broadcast uploaded blocks, as signers do
+    Blocks,
+    /// Simulate transactions
+    Simulation,
+    /// Reload the node's config file
+    Config,
+}
+
+impl AdminRole {
+    pub const ALL: [AdminRole; 10] = [
+        AdminRole::Peers,
+        AdminRole::Backup,
+        AdminRole::Mempool,
+        AdminRole::Mining,
+        AdminRole::Logging,
+        AdminRole::Chainstate,
+        AdminRole::StackerDB,
+        AdminRole::Blocks,
+        AdminRole::Simulation,
+        AdminRole::Config,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdminRole::Peers => "peers",
+            AdminRole::Backup => "backup",
+            AdminRole::Mempool => "mempool",
+            AdminRole::Mining => "mining",
+            AdminRole::Logging => "logging",
+            AdminRole::Chainstate => "chainstate",
+            AdminRole::StackerDB => "stackerdb",
+            AdminRole::Blocks => "blocks",
+            AdminRole::Simulation => "simulation",
+            AdminRole::Config => "config",
+        }
+    }
+}
+
+impl fmt::Display for AdminRole {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for AdminRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AdminRole::ALL
+            .into_iter()
+            .find(|role| role.as_str() == s)
+            .ok_or_else(|| format!("unknown admin role '{s}'"))
+    }
+}
+
+/// A named operator token, and the roles in which it may use the `/v3/admin/*` endpoints
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminToken {
+    /// Name of the token, for logs
+    pub name: String,
+    /// Secret sent in the `authorization` header
+    pub token: String,
+    pub roles: BTreeSet<AdminRole>,
+}
+
+/// The operator tokens accepted by the `/v3/admin/*` endpoints.  The node's `auth_token`, if
+/// set, is accepted in every role.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdminAuth {
+    tokens: Vec<AdminToken>,
+}
+
+impl AdminAuth {
+    /// Name under which the node's `auth_token` is logged
+    pub const AUTH_TOKEN_NAME: &'static str = "auth_token";
+
+    pub fn new(auth_token: Option<&str>, admin_tokens: &[AdminToken]) -> Self {
+        let mut tokens = Vec::with_capacity(admin_tokens.len() + 1);
+        if let Some(auth_token) = auth_token {
+            tokens.push(AdminToken {
+                name: Self::AUTH_TOKEN_NAME.into(),
+                token: auth_token.into(),
+                roles: AdminRole::ALL.into_iter().collect(),
+            });
+        }
+        tokens.extend(admin_tokens.iter().cloned());
+        Self { tokens }
+    }
+
+    /// Make sure each token has a unique name and secret, and at least one role
+    pub fn validate_tokens(
+        auth_token: Option<&str>,
+        admin_tokens: &[AdminToken],
+    ) -> Result<(), String> {
+        let mut names = BTreeSet::new();
+        let mut secrets: BTreeSet<&str> = auth_token.into_iter().collect();
+        for admin_token in admin_tokens.iter() {
+            if admin_token.name.is_empty() || admin_token.name == Self::AUTH_TOKEN_NAME {
+                return Err(format!("invalid token name '{}'", admin_token.name));
+            }
+            if !names.insert(admin_token.name.as_str()) {
+                return Err(format!("duplicate token name '{}'", admin_token.name));
+            }
+            if admin_token.token.is_empty() {
+                return Err(format!("token '{}' has an empty secret", admin_token.name));
+            }
+            if !secrets.insert(admin_token.token.as_str()) {
+                return Err(format!(
+                    "token '{}' reuses the secret of another token",
+                    admin_token.name
+                ));
+            }
+            if admin_token.roles.is_empty() {
+                return Err(format!("token '{}' has no roles", admin_token.name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Names of the tokens, for logs
+    pub fn token_names(&self) -> Vec<String> {
+        self.tokens.iter().map(|token| token.name.clone()).collect()
+    }
+
+    /// Can any token act in `role`?  If not, the endpoints for that role are disabled.
+    pub fn allows(&self, role: AdminRole) -> bool {
+        self.tokens.iter().any(|token| token.roles.contains(&role))
+    }
+
+    /// Check the request's `authorization` header against the tokens that may act in `role`.
+    /// Returns the name of the token used.
+    pub fn authorize(
+        &self,
+        preamble: &HttpRequestPreamble,
+        role: AdminRole,
+    ) -> Result<&str, HttpError> {
+        // If no token has this role, then its admin endpoints are not enabled
+        if !self.allows(role) {
+            return Err(HttpError::Http(400, "Bad Request.".into()));
+        }
+        let Some(auth_header) = preamble.headers.get("authorization") else {
+            return Err(HttpError::Http(401, "Unauthorized".into()));
+        };
+        let Some(token) = self.tokens.iter().find(|token| &token.token == auth_header) else {
+            return Err(HttpError::Http(401, "Unauthorized".into()));
+        };
+        if !token.roles.contains(&role) {
+            warn!("Admin token lacks the role for this request";
+                  "token" => &token.name, "role" => %role, "path" => &preamble.path_and_query_str);
+            return Err(HttpError::Http(403, "Forbidden".into()));
+        }
+        Ok(&token.name)
+    }
+}
+
+/// A token bucket holding up to `capacity` requests, refilled at `refill_per_sec`
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    tokens: f64,
+    capacity: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(refill_per_sec: u64, burst_secs: u64, now: Instant) -> Self {
+        let capacity = refill_per_sec.saturating_mul(burst_secs.max(1)) as f64;
+        Self {
+            tokens: capacity,
+            capacity,
+            refill_per_sec: refill_per_sec as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    fn is_full(&self) -> bool {
+        self.tokens >= self.capacity
+    }
+
+    /// Take a token.  If the bucket is empty, return the number of seconds until a token will be
+    /// available.
+    fn try_take(&mut self, now: Instant) -> Result<(), u64> {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        let wait_secs = ((1.0 - self.tokens) / self.refill_per_sec).ceil() as u64;
+        Err(wait_secs.max(1))
+    }
+}
+
+/// How often idle token buckets get dropped
+const RATE_LIMITER_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Tracks how many RPC requests each source has made, per endpoint class
+#[derive(Debug, Clone)]
+pub struct HttpRateLimiter {
+    config: HttpRateLimitConfig,
+    buckets: HashMap<(IpAddr, EndpointClass), TokenBucket>,
+    last_prune: Instant,
+    /// Number of requests currently held by long-polling endpoints
+    held_requests: u64,
+}
+
+impl HttpRateLimiter {
+    pub fn new(config: HttpRateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: HashMap::new(),
+            last_prune: Instant::now(),
+            held_requests: 0,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_enabled()
+    }
+
+    /// Map an address to the source whose budget it draws from: IPv4-mapped IPv6 addresses are
+    /// treated as IPv4, and the address is truncated to the configured prefix length.
+    fn source_key(&self, addr: &IpAddr) -> IpAddr {
+        let addr = match addr {
+            IpAddr::V6(v6) => v6
+                .to_ipv4_mapped()
+                .map(IpAddr::V4)
+                .unwrap_or(IpAddr::V6(*v6)),
+            IpAddr::V4(v4) => IpAddr::V4(*v4),
+        };
+        match addr {
+            IpAddr::V4(v4) => {
+                let prefix_len = u32::from(self.config.ipv4_prefix_len.min(32));
+                let mask = u32::MAX.checked_shl(32 - prefix_len).unwrap_or(0);
+                IpAddr::V4(Ipv4Addr::from(u32::from(v4) & mask))
+            }
+            IpAddr::V6(v6) => {
+                let prefix_len = u32::from(self.config.ipv6_prefix_len.min(128));
+                let mask = u128::MAX.checked_shl(128 - prefix_len).unwrap_or(0);
+                IpAddr::V6(Ipv6Addr::from(u128::from(v6) & mask))
+            }
+        }
+    }
+
+    /// Charge a request of the given class to the source at `addr`.
+    /// Returns Err(retry_after_secs) if the source has exhausted its budget.
+    pub fn check(&mut self, addr: &IpAddr, class: EndpointClass) -> Result<(), u64> {
+        self.check_at(addr, class, Instant::now())
+    }
+
+    pub(crate) fn check_at(
+        &mut self,
+        addr: &IpAddr,
+        class: EndpointClass,
+        now: Instant,
+    ) -> Result<(), u64> {
+        let Some(limit) = self.config.limit_for(class) else {
+            return Ok(());
+        };
+        if now.saturating_duration_since(self.last_prune) >= RATE_LIMITER_PRUNE_INTERVAL {
+            self.prune(now);
+        }
+        let key = (self.source_key(addr), class);
+        let burst_secs = self.config.burst_secs;
+        self.buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::new(limit, burst_secs, now))
+            .try_take(now)
+    }
+
+    /// Record how many requests the conversations are holding at the start of a network pass
+    pub fn set_held_requests(&mut self, held_requests: u64) {
+        self.held_requests = held_requests;
+    }
+
+    /// Try to take a slot for holding one more long-polling request.  Unlike the per-source
+    /// budgets, this applies even if rate limiting is disabled, since each held request ties up a
+    /// connection until it is answered.
+    /// Returns false if `max_held_requests` requests are already held.
+    pub fn try_hold_request(&mut self) -> bool {
+        if self.held_requests >= self.config.max_held_requests {
+            return false;
+        }
+        self.held_requests += 1;
+        true
+    }
+
+    /// Drop buckets which have refilled completely, since they carry no state
+    fn prune(&mut self, now: Instant) {
+        self.buckets.retain(|_, bucket| {
+            bucket.refill(now);
+            !bucket.is_full()
+        });
+        self.last_prune = now;
+    }
+
+    #[cfg(test)]
+    pub(crate) fn num_buckets(&self) -> usize {
+        self.buckets.len()
+    }
+}
+
 /// Trait that every HTTP round-trip request type must implement.
 pub trait RPCRequestHandler: HttpRequest + HttpResponse + RPCRequestHandlerClone {
     /// Reset the RPC handler.  This clears any internal state this handler stored between calls to
@@ -375,6 +879,41 @@ pub trait RPCRequestHandler: HttpRequest + HttpResponse + RPCRequestHandlerClone
         state: &mut StacksNodeState,
     ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError>;
 
+    /// Which rate-limiting class this endpoint belongs to.  By default, `GET` requests are
+    /// cheap reads and everything else is a write.  Handlers that stream large amounts of data,
+    /// run Clarity code, or require authorization should override this.
+    fn endpoint_class(&self) -> EndpointClass {
+        if self.verb() == "GET" {
+            EndpointClass::Read
+        } else {
+            EndpointClass::Write
+        }
+    }
+
+    /// If the parsed request names an immutable resource (e.g. a block by its ID), return the
+    /// strong entity tag of its representation, without quotes.  Successful responses will carry
+    /// this as their `ETag`, and requests whose `If-None-Match` matches it are answered with
+    /// `304 Not Modified` without calling `try_handle_request()`.  By default, resources are
+    /// mutable and have no entity tag.
+    fn immutable_etag(&self) -> Option<String> {
+        None
+    }
+
+    /// Long-polling endpoints can hold a request until they have something new to report.  Return
+    /// `Some(max_wait_ms)` if the response to this request is not ready yet.  The request is then
+    /// held, and this is asked again on each pass of the network loop, until it returns None or
+    /// the request is `max_wait_ms` milliseconds old; only then is `try_handle_request()` called.
+    /// Other requests may be parsed while one is held, so handlers that hold requests must take
+    /// their arguments from `contents` instead of from state set by `try_parse_request()`.
+    fn defer_request(
+        &mut self,
+        _preamble: &HttpRequestPreamble,
+        _contents: &HttpRequestContents,
+        _node: &mut StacksNodeState,
+    ) -> Option<u128> {
+        None
+    }
+
     /// Helper to get the canonical sortition tip
     fn get_canonical_burn_chain_tip(
         &self,
@@ -417,7 +956,7 @@ pub trait RPCRequestHandler: HttpRequest + HttpResponse + RPCRequestHandlerClone
         sortdb: &SortitionDB,
         chainstate: &StacksChainState,
     ) -> Result<StacksHeaderInfo, StacksHttpResponse> {
-        NakamotoChainState::get_canonical_block_header(chainstate.db(), sortdb)
+        header_cache::get_canonical_block_header(chainstate, sortdb)
             .map_err(|e| {
                 let msg = format!("Failed to load stacks chain tip header: {:?}", &e);
                 warn!("{}", &msg);
@@ -934,8 +1473,12 @@ pub struct StacksHttp {
     pub maximum_call_argument_size: u32,
     /// Maximum execution budget of a read-only call
     pub read_only_call_limit: ExecutionCost,
-    /// The authorization token to enable access to privileged features, such as the block proposal RPC endpoint
-    pub auth_token: Option<String>,
+    /// Maximum read budget for serving a map entry
+    pub read_only_map_entry_limit: ExecutionCost,
+    /// The operator tokens accepted by the `/v3/admin/*` endpoints
+    pub admin_auth: AdminAuth,
+    /// Which browser origins may read our responses
+    pub cors_policy: HttpCorsPolicy,
     /// Allow arbitrary responses to be handled in addition to request handlers
     allow_arbitrary_response: bool,
 }
@@ -955,7 +1498,9 @@ impl StacksHttp {
             request_handlers: vec![],
             maximum_call_argument_size: conn_opts.maximum_call_argument_size,
             read_only_call_limit: conn_opts.read_only_call_limit.clone(),
-            auth_token: conn_opts.auth_token.clone(),
+            read_only_map_entry_limit: conn_opts.read_only_map_entry_limit.clone(),
+            admin_auth: AdminAuth::new(conn_opts.auth_token.as_deref(), &conn_opts.admin_tokens),
+            cors_policy: conn_opts.rpc_cors_policy.clone(),
             allow_arbitrary_response: false,
         };
         http.register_rpc_methods();
@@ -976,7 +1521,9 @@ impl StacksHttp {
             request_handlers: vec![],
             maximum_call_argument_size: conn_opts.maximum_call_argument_size,
             read_only_call_limit: conn_opts.read_only_call_limit.clone(),
-            auth_token: conn_opts.auth_token.clone(),
+            read_only_map_entry_limit: conn_opts.read_only_map_entry_limit.clone(),
+            admin_auth: AdminAuth::new(conn_opts.auth_token.as_deref(), &conn_opts.admin_tokens),
+            cors_policy: conn_opts.rpc_cors_policy.clone(),
             allow_arbitrary_response: true,
         }
     }
@@ -1149,6 +1696,13 @@ impl StacksHttp {
         if preamble.status_code >= 400 {
             return Self::try_parse_error_response(preamble, body);
         }
+        if preamble.status_code == 304 {
+            // conditional request for an immutable resource we already have
+            return Ok(StacksHttpResponse::new(
+                preamble.clone(),
+                HttpResponsePayload::Empty,
+            ));
+        }
 
         let (_, _, parser) = self
             .request_handlers
@@ -1159,7 +1713,17 @@ impl StacksHttp {
         return Ok(response);
     }
 
-    /// Handle an HTTP request by generating an HTTP response.
+    /// Add the CORS headers our policy calls for to the response to a request
+    pub fn apply_cors_policy(
+        &self,
+        request_preamble: &HttpRequestPreamble,
+        response_preamble: &mut HttpResponsePreamble,
+    ) {
+        let origin = request_preamble.get_header("origin".into());
+        self.cors_policy.apply(origin.as_deref(), response_preamble);
+    }
+
+    /// Handle an HTTP request by generating an HTTP response, with CORS headers.
     /// Returns Ok((preamble, contents)) on success.  Note that this could be an HTTP error
     /// message.
     /// Returns Err(..) on failure to decode or generate the response.
@@ -1167,6 +1731,19 @@ impl StacksHttp {
         &mut self,
         request: StacksHttpRequest,
         node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let request_preamble = request.preamble().clone();
+        let (mut response_preamble, response_contents) =
+            self.inner_try_handle_request(request, node)?;
+        self.apply_cors_policy(&request_preamble, &mut response_preamble);
+        Ok((response_preamble, response_contents))
+    }
+
+    /// Handle an HTTP request by dispatching it to its request handler.
+    fn inner_try_handle_request(
+        &mut self,
+        request: StacksHttpRequest,
+        node: &mut StacksNodeState,
     ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
         let (decoded_path, _) = decode_request_path(&request.preamble().path_and_query_str)?;
         let Some(response_handler_index) = request
@@ -1190,11 +1767,21 @@ impl StacksHttp {
             .get_mut(response_handler_index)
             .expect("FATAL: request points to a nonexistent handler");
         let request_preamble = request.preamble.clone();
+        let etag = request_handler.immutable_etag();
+        if let Some(etag) = etag.as_ref() {
+            if request_preamble.if_none_match(etag) {
+                request_handler.restart();
+                return Ok((
+                    HttpResponsePreamble::not_modified(&request_preamble, etag),
+                    HttpResponseContents::from_ram(vec![]),
+                ));
+            }
+        }
         let request_result =
             request_handler.try_handle_request(request.preamble, request.contents, node);
         request_handler.restart();
 
-        let (response_preamble, response_contents) = match request_result {
+        let (mut response_preamble, response_contents) = match request_result {
             Ok((rp, rc)) => (rp, rc),
             Err(NetError::Http(e)) => {
                 debug!("RPC handler for {} failed: {:?}", decoded_path, &e);
@@ -1206,9 +1793,41 @@ impl StacksHttp {
                 return Err(e);
             }
         };
+        if let Some(etag) = etag.as_ref() {
+            if response_preamble.status_code == 200 {
+                response_preamble.set_immutable_etag(etag);
+            }
+        }
         Ok((response_preamble, response_contents))
     }
 
+    /// Should this request be held instead of answered now?  True if its handler is a
+    /// long-polling endpoint whose response is not ready yet, and the request has not been held
+    /// for as long as the handler allows.
+    pub fn should_defer_request(
+        &mut self,
+        request: &StacksHttpRequest,
+        node: &mut StacksNodeState,
+    ) -> bool {
+        let Ok((decoded_path, _)) = decode_request_path(&request.preamble().path_and_query_str)
+        else {
+            return false;
+        };
+        let Some(response_handler_index) = request
+            .response_handler_index
+            .or_else(|| self.find_response_handler(&request.preamble().verb, &decoded_path))
+        else {
+            return false;
+        };
+        let Some((_, _, request_handler)) = self.request_handlers.get_mut(response_handler_index)
+        else {
+            return false;
+        };
+        request_handler
+            .defer_request(request.preamble(), request.contents(), node)
+            .is_some_and(|max_wait_ms| request.duration_ms() < max_wait_ms)
+    }
+
     #[cfg(test)]
     pub fn num_pending(&self) -> usize {
         self.reply.as_ref().map(|_| 1).unwrap_or(0)
@@ -1348,6 +1967,23 @@ impl StacksHttp {
         request_handler.metrics_identifier()
     }
 
+    /// Get the rate-limiting class of the handler for this request.
+    /// Returns None if no handler matches the request, in which case it will be rejected anyway.
+    pub fn endpoint_class(&self, req: &mut StacksHttpRequest) -> Option<EndpointClass> {
+        let (decoded_path, _) = decode_request_path(req.request_path()).ok()?;
+        let response_handler_index = req
+            .response_handler_index
+            .or_else(|| self.find_response_handler(&req.preamble().verb, &decoded_path))?;
+        req.response_handler_index = Some(response_handler_index);
+
+        let (_, _, request_handler) = self
+            .request_handlers
+            .get(response_handler_index)
+            .expect("FATAL: request points to a nonexistent handler");
+
+        Some(request_handler.endpoint_class())
+    }
+
     /// Given a fully-formed single HTTP response, parse it (used by clients).
     #[cfg(test)]
     pub fn parse_response(
@@ -1582,10 +2218,11 @@ impl ProtocolFamily for StacksHttp {
                     Ok(data_request) => Ok((StacksHttpMessage::Request(data_request), len)),
                     Err(NetError::Http(http_error)) => {
                         // convert into a response
-                        let resp = StacksHttpResponse::new_error(
+                        let mut resp = StacksHttpResponse::new_error(
                             http_request_preamble,
                             &*http_error.into_http_error(),
                         );
+                        self.apply_cors_policy(http_request_preamble, &mut resp.preamble);
                         self.reset();
                         return Ok((
                             StacksHttpMessage::Error(
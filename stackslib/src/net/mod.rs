@@ -23,6 +23,8 @@ use std::io::{Read, Write};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::ops::Deref;
 use std::str::FromStr;
+use std::sync::atomic::AtomicBool;
+use std::sync::Mutex;
 use std::{error, fmt, io};
 
 use clarity::vm::analysis::contract_interface_builder::ContractInterface;
@@ -62,28 +64,37 @@ use {rusqlite, serde_json, url};
 
 use self::dns::*;
 use crate::burnchains::affirmation::AffirmationMap;
+use crate::burnchains::bitcoin::spv::HeaderSyncTracker;
 use crate::burnchains::{Error as burnchain_error, Txid};
 use crate::chainstate::burn::db::sortdb::SortitionDB;
 use crate::chainstate::burn::{ConsensusHash, Opcodes};
 use crate::chainstate::coordinator::comm::CoordinatorChannels;
 use crate::chainstate::coordinator::Error as coordinator_error;
-use crate::chainstate::nakamoto::{NakamotoBlock, NakamotoChainState};
+use crate::chainstate::nakamoto::{header_cache, NakamotoBlock, NakamotoChainState};
 use crate::chainstate::stacks::boot::{
     BOOT_TEST_POX_4_AGG_KEY_CONTRACT, BOOT_TEST_POX_4_AGG_KEY_FNAME,
 };
 use crate::chainstate::stacks::db::blocks::MemPoolRejection;
+use crate::chainstate::stacks::db::read_replica::ReadReplicaQuery;
+use crate::chainstate::stacks::db::receipts::TransactionReceiptDB;
+use crate::chainstate::stacks::db::state_diffs::StateDiffDB;
 use crate::chainstate::stacks::db::StacksChainState;
 use crate::chainstate::stacks::index::Error as marf_error;
 use crate::chainstate::stacks::{
     Error as chainstate_error, Error as chain_error, StacksBlock, StacksBlockHeader,
     StacksMicroblock, StacksPublicKey, StacksTransaction, TransactionPayload,
 };
-use crate::clarity_vm::clarity::Error as clarity_error;
+use crate::clarity_vm::clarity::{ClarityReadOnlyConnection, Error as clarity_error};
 use crate::core::mempool::*;
 use crate::core::{StacksEpoch, POX_REWARD_CYCLE_LENGTH};
+use crate::cost_estimates::contract_costs::ContractCostDB;
+use crate::cost_estimates::fill_stats::MinerFillStats;
 use crate::cost_estimates::metrics::CostMetric;
 use crate::cost_estimates::{CostEstimator, FeeEstimator, FeeRateEstimate};
+use crate::net::api::getminerreports::MinerReports;
+use crate::net::api::getminerutxos::MinerUtxoStatus;
 use crate::net::atlas::{Attachment, AttachmentInstance};
+use crate::net::connection::ConnectionOptions;
 use crate::net::dns::*;
 use crate::net::http::error::{HttpNotFound, HttpServerError};
 use crate::net::http::{
@@ -127,6 +138,9 @@ pub mod http;
 pub mod httpcore;
 pub mod inv;
 pub mod mempool;
+/// Implements `NatTraversal`, which maps the p2p port on a NAT gateway with UPnP or NAT-PMP and
+/// checks that it is reachable.
+pub mod nat;
 pub mod neighbors;
 pub mod p2p;
 /// Implements wrapper around `mio` crate, which itself is a wrapper around Linux's `epoll(2)` syscall.
@@ -139,10 +153,16 @@ pub mod relay;
 pub mod rpc;
 pub mod server;
 pub mod stackerdb;
+/// Implements `StaleTipState`, which detects a Stacks tip that has stopped advancing while
+/// neighbors report later tenures.
+pub mod stale_tip;
+pub mod tls;
 pub mod unsolicited;
 
 pub use crate::net::neighbors::{NeighborComms, PeerNetworkComms};
-use crate::net::stackerdb::{StackerDBConfig, StackerDBSync, StackerDBSyncResult, StackerDBs};
+use crate::net::stackerdb::{
+    StackerDBConfig, StackerDBReplicationQueue, StackerDBSync, StackerDBSyncResult, StackerDBs,
+};
 
 #[cfg(test)]
 pub mod tests;
@@ -618,6 +638,13 @@ impl PeerHostExtensions for PeerHost {
     }
 }
 
+/// Re-reads the node's config file at the node operator's request
+pub trait NodeConfigReloader {
+    /// Load the connection options from the node's config file as it is now.
+    /// Returns an error message if the file cannot be loaded, or is not a valid config.
+    fn reload_connection_options(&self) -> Result<ConnectionOptions, String>;
+}
+
 /// Runtime arguments to an RPC handler
 #[derive(Default)]
 pub struct RPCHandlerArgs<'a> {
@@ -627,14 +654,35 @@ pub struct RPCHandlerArgs<'a> {
     pub genesis_chainstate_hash: Sha256Sum,
     /// event observer for the mempool
     pub event_observer: Option<&'a dyn MemPoolEventDispatcher>,
+    /// durably queues StackerDB chunks written over RPC for replication to standby nodes, before
+    /// the writes are committed
+    pub stackerdb_replication: Option<&'a dyn StackerDBReplicationQueue>,
     /// tx runtime cost estimator
     pub cost_estimator: Option<&'a dyn CostEstimator>,
     /// tx fee estimator
     pub fee_estimator: Option<&'a dyn FeeEstimator>,
     /// tx runtime cost metric
     pub cost_metric: Option<&'a dyn CostMetric>,
+    /// per-contract execution costs
+    pub contract_cost_db: Option<&'a ContractCostDB>,
+    /// recently-processed transaction receipts
+    pub receipt_db: Option<&'a TransactionReceiptDB>,
+    /// recently-processed block state diffs
+    pub state_diff_db: Option<&'a StateDiffDB>,
+    /// fill rates of recently-mined blocks
+    pub miner_fill_stats: Option<&'a Mutex<MinerFillStats>>,
+    /// the miner's burnchain UTXOs
+    pub miner_utxos: Option<&'a Mutex<MinerUtxoStatus>>,
+    /// reports of the miner's recent tenures
+    pub miner_reports: Option<&'a Mutex<MinerReports>>,
+    /// set while the node operator has paused mining
+    pub mining_paused: Option<&'a AtomicBool>,
+    /// re-reads the node's config file
+    pub config_reloader: Option<&'a dyn NodeConfigReloader>,
     /// coordinator channels
     pub coord_comms: Option<&'a CoordinatorChannels>,
+    /// progress of the Bitcoin header download
+    pub header_sync: Option<&'a HeaderSyncTracker>,
 }
 
 impl RPCHandlerArgs<'_> {
@@ -783,10 +831,7 @@ impl<'a> StacksNodeState<'a> {
                     if let Some(unconfirmed_chain_tip) = unconfirmed_chain_tip_opt {
                         Ok(unconfirmed_chain_tip)
                     } else {
-                        match NakamotoChainState::get_canonical_block_header(
-                            chainstate.db(),
-                            sortdb,
-                        ) {
+                        match header_cache::get_canonical_block_header(chainstate, sortdb) {
                             Ok(Some(tip)) => Ok(StacksBlockId::new(
                                 &tip.consensus_hash,
                                 &tip.anchored_header.block_hash(),
@@ -811,7 +856,7 @@ impl<'a> StacksNodeState<'a> {
                 }
                 TipRequest::SpecificTip(tip) => Ok(tip.clone()),
                 TipRequest::UseLatestAnchoredTip => {
-                    match NakamotoChainState::get_canonical_block_header(chainstate.db(), sortdb) {
+                    match header_cache::get_canonical_block_header(chainstate, sortdb) {
                         Ok(Some(tip)) => Ok(StacksBlockId::new(
                             &tip.consensus_hash,
                             &tip.anchored_header.block_hash(),
@@ -838,6 +883,36 @@ impl<'a> StacksNodeState<'a> {
             }
         })
     }
+
+    /// Send `to_do`, a query of the Clarity state at `tip`, to the chainstate's read replica
+    /// workers, so that it runs off of this thread.
+    /// Returns None if read replicas are not enabled, or if `tip` is the unconfirmed tip (which
+    /// the read replicas cannot see).  The caller should then run the query itself.
+    /// The query's result is None if `tip` is not a processed block.
+    pub fn submit_read_replica_query<F, R>(
+        &mut self,
+        tip: &StacksBlockId,
+        to_do: F,
+    ) -> Option<ReadReplicaQuery<Option<R>>>
+    where
+        F: FnOnce(&mut ClarityReadOnlyConnection) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+            let read_replicas = chainstate.read_replicas()?;
+            if chainstate
+                .unconfirmed_state
+                .as_ref()
+                .is_some_and(|unconfirmed_state| &unconfirmed_state.unconfirmed_chain_tip == tip)
+            {
+                return None;
+            }
+            let Ok(burn_dbconn) = sortdb.index_handle_at_block(chainstate, tip) else {
+                return Some(ReadReplicaQuery::answered(None));
+            };
+            Some(read_replicas.submit(burn_dbconn.context.chain_tip.clone(), tip.clone(), to_do))
+        })
+    }
 }
 
 pub const STACKS_PUBLIC_KEY_ENCODED_SIZE: u32 = 33;
@@ -1027,6 +1102,16 @@ pub enum ServiceFlags {
     RELAY = 0x01,
     RPC = 0x02,
     STACKERDB = 0x04,
+    /// This node mines, so transactions pushed to it can be included sooner
+    MINER = 0x08,
+    /// This node serves Nakamoto tenures over its RPC interface
+    TENURES = 0x10,
+    /// This node is an archival node (`node.archival`): it keeps every block, and serves whole
+    /// tenures and block ranges over its RPC interface
+    ARCHIVAL = 0x20,
+    /// This node accepts zstd-compressed payloads (`StacksMessageType::Compressed`), and
+    /// compresses large payloads to peers that set this bit too
+    COMPRESSION = 0x40,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -1135,6 +1220,19 @@ pub struct StackerDBPushChunkData {
     pub chunk_data: StackerDBChunkData,
 }
 
+/// zstd level used to compress p2p payloads
+pub const P2P_COMPRESSION_LEVEL: i32 = 3;
+
+/// A zstd-compressed payload, sent in place of a large payload to a peer that negotiated
+/// compression in its handshake.  The message signature covers the compressed form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressedData {
+    /// length of the encoded payload
+    pub uncompressed_len: u32,
+    /// zstd frame holding the encoded payload (message ID included)
+    pub data: Vec<u8>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RelayData {
     pub peer: NeighborAddress,
@@ -1174,6 +1272,8 @@ pub enum StacksMessageType {
     GetNakamotoInv(GetNakamotoInvData),
     NakamotoInv(NakamotoInvData),
     NakamotoBlocks(NakamotoBlocksData),
+    // compression
+    Compressed(CompressedData),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -1209,6 +1309,8 @@ pub enum StacksMessageID {
     GetNakamotoInv = 26,
     NakamotoInv = 27,
     NakamotoBlocks = 28,
+    // compression
+    Compressed = 29,
     // reserved
     Reserved = 255,
 }
@@ -2231,6 +2333,7 @@ pub mod test {
     use clarity::vm::ast::ASTRules;
     use clarity::vm::costs::ExecutionCost;
     use clarity::vm::database::STXBalance;
+    use clarity::vm::events::{FTEventType, NFTEventType, STXEventType, StacksTransactionEvent};
     use clarity::vm::types::*;
     use clarity::vm::ClarityVersion;
     use rand::{Rng, RngCore};
@@ -2261,6 +2364,7 @@ pub mod test {
     use crate::chainstate::burn::db::sortdb::*;
     use crate::chainstate::burn::operations::*;
     use crate::chainstate::burn::*;
+    use crate::chainstate::coordinator::comm::PendingStacksReorg;
     use crate::chainstate::coordinator::tests::*;
     use crate::chainstate::coordinator::*;
     use crate::chainstate::nakamoto::tests::node::TestStacker;
@@ -2277,6 +2381,7 @@ pub mod test {
     use crate::chainstate::*;
     use crate::clarity::vm::clarity::TransactionConnection;
     use crate::core::{EpochList, StacksEpoch, StacksEpochExtension, NETWORK_P2P_PORT};
+    use crate::monitoring::chain_quality::ChainQualityAlert;
     use crate::net::asn::*;
     use crate::net::atlas::*;
     use crate::net::chat::*;
@@ -2287,6 +2392,7 @@ pub mod test {
     use crate::net::p2p::*;
     use crate::net::poll::*;
     use crate::net::relay::*;
+    use crate::net::tests::faults::{LinkFault, NetworkFaults};
     use crate::net::Error as net_error;
     use crate::util_lib::boot::boot_code_test_addr;
     use crate::util_lib::strings::*;
@@ -2300,6 +2406,7 @@ pub mod test {
                 | BlockstackOperationType::DelegateStx(_)
                 | BlockstackOperationType::PreStx(_)
                 | BlockstackOperationType::VoteForAggregateKey(_)
+                | BlockstackOperationType::StackAggregationCommit(_)
                 | BlockstackOperationType::StackStx(_) => Ok(()),
             }
         }
@@ -2490,20 +2597,271 @@ pub mod test {
         pub reward_set_data: Option<RewardSetData>,
     }
 
+    /// A transaction event seen by a `TestEventObserver`, along with the receipt of the
+    /// transaction that emitted it
+    #[derive(Debug, Clone)]
+    pub struct TestObservedEvent {
+        /// index block hash of the block that contained the transaction
+        pub block_id: StacksBlockId,
+        pub receipt: StacksTransactionReceipt,
+        /// index of the event in `receipt.events`
+        pub event_index: usize,
+        pub event: StacksTransactionEvent,
+    }
+
+    impl TestObservedEvent {
+        /// Get the (contract, name) key and value of this event, if it's a smart contract event
+        pub fn contract_event(&self) -> Option<(&QualifiedContractIdentifier, &str, &Value)> {
+            match &self.event {
+                StacksTransactionEvent::SmartContractEvent(data) => {
+                    Some((&data.key.0, data.key.1.as_str(), &data.value))
+                }
+                _ => None,
+            }
+        }
+    }
+
+    /// Location of an event: (block index, receipt index, event index)
+    type TestEventLocation = (usize, usize, usize);
+
+    /// Observed blocks, and indexes over their transactions and events
+    #[derive(Default)]
+    struct TestEventObserverState {
+        blocks: Vec<TestEventObserverBlock>,
+        /// txid to (block index, receipt index)
+        txs: HashMap<Txid, (usize, usize)>,
+        /// contract that emitted an event, or that defines the asset in an FT/NFT event
+        contract_events: HashMap<QualifiedContractIdentifier, Vec<TestEventLocation>>,
+        /// event type, as named by the event observer interface (e.g. "contract_event")
+        typed_events: HashMap<&'static str, Vec<TestEventLocation>>,
+    }
+
+    impl TestEventObserverState {
+        fn add_block(&mut self, block: TestEventObserverBlock) {
+            let block_idx = self.blocks.len();
+            for (receipt_idx, receipt) in block.receipts.iter().enumerate() {
+                self.txs
+                    .insert(receipt.transaction.txid(), (block_idx, receipt_idx));
+                for (event_idx, event) in receipt.events.iter().enumerate() {
+                    let loc = (block_idx, receipt_idx, event_idx);
+                    if let Some(contract_id) = TestEventObserver::event_contract_id(event) {
+                        self.contract_events
+                            .entry(contract_id.clone())
+                            .or_default()
+                            .push(loc);
+                    }
+                    self.typed_events
+                        .entry(TestEventObserver::event_type(event))
+                        .or_default()
+                        .push(loc);
+                }
+            }
+            self.blocks.push(block);
+        }
+
+        fn get_events(&self, locs: Option<&Vec<TestEventLocation>>) -> Vec<TestObservedEvent> {
+            let Some(locs) = locs else {
+                return vec![];
+            };
+            locs.iter()
+                .map(|(block_idx, receipt_idx, event_idx)| {
+                    let block = &self.blocks[*block_idx];
+                    let receipt = &block.receipts[*receipt_idx];
+                    TestObservedEvent {
+                        block_id: block.metadata.index_block_hash(),
+                        receipt: receipt.clone(),
+                        event_index: *event_idx,
+                        event: receipt.events[*event_idx].clone(),
+                    }
+                })
+                .collect()
+        }
+    }
+
     pub struct TestEventObserver {
-        blocks: Mutex<Vec<TestEventObserverBlock>>,
+        state: Mutex<TestEventObserverState>,
     }
 
     impl TestEventObserver {
         pub fn get_blocks(&self) -> Vec<TestEventObserverBlock> {
-            self.blocks.lock().unwrap().deref().to_vec()
+            self.state.lock().unwrap().blocks.to_vec()
         }
 
         pub fn new() -> TestEventObserver {
             TestEventObserver {
-                blocks: Mutex::new(vec![]),
+                state: Mutex::new(TestEventObserverState::default()),
             }
         }
+
+        /// The event type name used by the event observer interface
+        pub fn event_type(event: &StacksTransactionEvent) -> &'static str {
+            match event {
+                StacksTransactionEvent::SmartContractEvent(_) => "contract_event",
+                StacksTransactionEvent::STXEvent(STXEventType::STXTransferEvent(_)) => {
+                    "stx_transfer_event"
+                }
+                StacksTransactionEvent::STXEvent(STXEventType::STXMintEvent(_)) => "stx_mint_event",
+                StacksTransactionEvent::STXEvent(STXEventType::STXBurnEvent(_)) => "stx_burn_event",
+                StacksTransactionEvent::STXEvent(STXEventType::STXLockEvent(_)) => "stx_lock_event",
+                StacksTransactionEvent::NFTEvent(NFTEventType::NFTTransferEvent(_)) => {
+                    "nft_transfer_event"
+                }
+                StacksTransactionEvent::NFTEvent(NFTEventType::NFTMintEvent(_)) => "nft_mint_event",
+                StacksTransactionEvent::NFTEvent(NFTEventType::NFTBurnEvent(_)) => "nft_burn_event",
+                StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(_)) => {
+                    "ft_transfer_event"
+                }
+                StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(_)) => "ft_mint_event",
+                StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(_)) => "ft_burn_event",
+            }
+        }
+
+        /// The contract an event is indexed under: the emitting contract for smart contract
+        /// events and STX lock events, and the asset's contract for FT/NFT events.
+        fn event_contract_id(
+            event: &StacksTransactionEvent,
+        ) -> Option<&QualifiedContractIdentifier> {
+            match event {
+                StacksTransactionEvent::SmartContractEvent(data) => Some(&data.key.0),
+                StacksTransactionEvent::STXEvent(STXEventType::STXLockEvent(data)) => {
+                    Some(&data.contract_identifier)
+                }
+                StacksTransactionEvent::STXEvent(_) => None,
+                StacksTransactionEvent::NFTEvent(NFTEventType::NFTTransferEvent(data)) => {
+                    Some(&data.asset_identifier.contract_identifier)
+                }
+                StacksTransactionEvent::NFTEvent(NFTEventType::NFTMintEvent(data)) => {
+                    Some(&data.asset_identifier.contract_identifier)
+                }
+                StacksTransactionEvent::NFTEvent(NFTEventType::NFTBurnEvent(data)) => {
+                    Some(&data.asset_identifier.contract_identifier)
+                }
+                StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(data)) => {
+                    Some(&data.asset_identifier.contract_identifier)
+                }
+                StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(data)) => {
+                    Some(&data.asset_identifier.contract_identifier)
+                }
+                StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(data)) => {
+                    Some(&data.asset_identifier.contract_identifier)
+                }
+            }
+        }
+
+        /// Get the receipt for a transaction, if it was observed
+        pub fn get_tx_receipt(&self, txid: &Txid) -> Option<StacksTransactionReceipt> {
+            let state = self.state.lock().unwrap();
+            let (block_idx, receipt_idx) = state.txs.get(txid)?;
+            Some(state.blocks[*block_idx].receipts[*receipt_idx].clone())
+        }
+
+        /// Get the observed block that contained a transaction
+        pub fn get_tx_block(&self, txid: &Txid) -> Option<TestEventObserverBlock> {
+            let state = self.state.lock().unwrap();
+            let (block_idx, _) = state.txs.get(txid)?;
+            Some(state.blocks[*block_idx].clone())
+        }
+
+        /// Get all events associated with a contract, in the order they were observed
+        pub fn get_contract_events(
+            &self,
+            contract_id: &QualifiedContractIdentifier,
+        ) -> Vec<TestObservedEvent> {
+            let state = self.state.lock().unwrap();
+            state.get_events(state.contract_events.get(contract_id))
+        }
+
+        /// Get all events of the given type (e.g. "contract_event" or "stx_lock_event"), in the
+        /// order they were observed
+        pub fn get_events_by_type(&self, event_type: &str) -> Vec<TestObservedEvent> {
+            let state = self.state.lock().unwrap();
+            state.get_events(state.typed_events.get(event_type))
+        }
+
+        /// Get the values of all smart contract events emitted by `contract_id` under `name`
+        /// (e.g. "print"), in the order they were observed
+        pub fn get_contract_event_values(
+            &self,
+            contract_id: &QualifiedContractIdentifier,
+            name: &str,
+        ) -> Vec<Value> {
+            self.get_contract_events(contract_id)
+                .iter()
+                .filter_map(|event| {
+                    let (_, event_name, value) = event.contract_event()?;
+                    (event_name == name).then(|| value.clone())
+                })
+                .collect()
+        }
+
+        /// Assert that `contract_id` emitted at least one smart contract event named `name` whose
+        /// value matches `predicate`, and return the first such event
+        pub fn assert_event_emitted<F>(
+            &self,
+            contract_id: &QualifiedContractIdentifier,
+            name: &str,
+            predicate: F,
+        ) -> TestObservedEvent
+        where
+            F: Fn(&Value) -> bool,
+        {
+            let events = self.get_contract_events(contract_id);
+            let found = events.iter().find(|event| {
+                event
+                    .contract_event()
+                    .is_some_and(|(_, event_name, value)| event_name == name && predicate(value))
+            });
+            match found {
+                Some(event) => event.clone(),
+                None => panic!(
+                    "No matching '{}' event from {}; observed values: {:?}",
+                    name,
+                    contract_id,
+                    self.get_contract_event_values(contract_id, name)
+                ),
+            }
+        }
+
+        /// Assert that no smart contract event named `name` from `contract_id` matches
+        /// `predicate`
+        pub fn assert_event_not_emitted<F>(
+            &self,
+            contract_id: &QualifiedContractIdentifier,
+            name: &str,
+            predicate: F,
+        ) where
+            F: Fn(&Value) -> bool,
+        {
+            for value in self.get_contract_event_values(contract_id, name) {
+                assert!(
+                    !predicate(&value),
+                    "Unexpected '{}' event from {}: {:?}",
+                    name,
+                    contract_id,
+                    &value
+                );
+            }
+        }
+
+        /// Assert that a transaction was observed, ran to completion without a runtime error,
+        /// and was not aborted by its post-conditions.  Returns its receipt.
+        pub fn assert_tx_succeeded(&self, txid: &Txid) -> StacksTransactionReceipt {
+            let Some(receipt) = self.get_tx_receipt(txid) else {
+                panic!("Transaction {} was not observed", txid);
+            };
+            assert!(
+                receipt.vm_error.is_none(),
+                "Transaction {} failed: {:?}",
+                txid,
+                &receipt.vm_error
+            );
+            assert!(
+                !receipt.post_condition_aborted,
+                "Transaction {} was aborted by its post-conditions",
+                txid
+            );
+            receipt
+        }
     }
 
     impl BlockEventDispatcher for TestEventObserver {
@@ -2527,7 +2885,8 @@ pub mod test {
             _block_timestamp: Option<u64>,
             _coinbase_height: u64,
         ) {
-            self.blocks.lock().unwrap().push(TestEventObserverBlock {
+            let mut state = self.state.lock().unwrap();
+            state.add_block(TestEventObserverBlock {
                 block: block.clone(),
                 metadata: metadata.clone(),
                 receipts: receipts.to_owned(),
@@ -2539,6 +2898,10 @@ pub mod test {
             })
         }
 
+        fn announce_raw_nakamoto_block(&self, _block: &NakamotoBlock) {
+            // pass
+        }
+
         fn announce_burn_block(
             &self,
             _burn_block: &BurnchainHeaderHash,
@@ -2550,6 +2913,25 @@ pub mod test {
         ) {
             // pass
         }
+
+        fn announce_reward_cycle(
+            &self,
+            _reward_cycle_info: &RewardCycleInfo,
+            _burn_block: &BurnchainHeaderHash,
+            _burn_block_height: u64,
+            _consensus_hash: &ConsensusHash,
+            _pox_constants: &PoxConstants,
+        ) {
+            // pass
+        }
+
+        fn announce_deep_reorg(&self, _reorg: &PendingStacksReorg) {
+            // pass
+        }
+
+        fn announce_chain_quality_alert(&self, _alert: &ChainQualityAlert) {
+            // pass
+        }
     }
 
     // describes a peer's initial configuration
@@ -2592,6 +2974,10 @@ pub mod test {
         pub aggregate_public_key: Option<Vec<u8>>,
         pub test_stackers: Option<Vec<TestStacker>>,
         pub test_signers: Option<TestSigners>,
+        /// Seed for the RNGs used to inject network faults
+        pub network_faults_seed: u64,
+        /// Faults to apply to p2p messages received from other peers, keyed by their p2p port
+        pub link_faults: HashMap<u16, LinkFault>,
     }
 
     impl TestPeerConfig {
@@ -2644,6 +3030,8 @@ pub mod test {
                 aggregate_public_key: None,
                 test_stackers: None,
                 test_signers: None,
+                network_faults_seed: 0,
+                link_faults: HashMap::new(),
             }
         }
 
@@ -3140,6 +3528,8 @@ pub mod test {
                 epochs,
             );
             peer_network.set_stacker_db_configs(config.get_stacker_db_configs());
+            peer_network.network_faults =
+                NetworkFaults::from_links(config.network_faults_seed, &config.link_faults);
 
             peer_network.bind(&local_addr, &http_local_addr).unwrap();
             let relayer = Relayer::from_p2p(&mut peer_network, relayer_stacker_dbs);
@@ -3242,6 +3632,31 @@ pub mod test {
             tx.commit().unwrap();
         }
 
+        /// Apply `fault` to all p2p messages this peer subsequently receives from `other`
+        pub fn set_link_fault(&mut self, other: &TestPeer, fault: LinkFault) {
+            self.network
+                .network_faults
+                .set_link_fault(other.config.server_port, fault);
+        }
+
+        /// Drop all p2p messages between this peer and `other`, in both directions
+        pub fn partition(&mut self, other: &mut TestPeer) {
+            self.set_link_fault(other, LinkFault::partition());
+            other.set_link_fault(self, LinkFault::partition());
+        }
+
+        /// Stop injecting faults between this peer and `other`, in both directions.  Messages
+        /// that were delayed are still delivered when due.
+        pub fn heal(&mut self, other: &mut TestPeer) {
+            self.network
+                .network_faults
+                .clear_link_fault(other.config.server_port);
+            other
+                .network
+                .network_faults
+                .clear_link_fault(self.config.server_port);
+        }
+
         // TODO: DRY up from PoxSyncWatchdog
         pub fn infer_initial_burnchain_block_download(
             burnchain: &Burnchain,
@@ -4306,12 +4721,13 @@ pub mod test {
             let leader_key_op = stacks_node.add_key_register(&mut burn_block, &mut self.miner);
 
             // patch in reward set info
+            let reward_set_provider = stacks_node.chainstate.reward_set_provider();
             match get_next_recipients(
                 &tip,
                 &mut stacks_node.chainstate,
                 &mut sortdb,
                 &self.config.burnchain,
-                &OnChainRewardSetProvider::new(),
+                &reward_set_provider,
                 true,
             ) {
                 Ok(recipients) => {
@@ -0,0 +1,934 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Opt-in NAT traversal for the p2p port (`connection_options.nat_traversal`).
+//!
+//! Nodes run from home are often behind a NAT gateway that drops inbound connections, so no one
+//! can connect to them and their operators rarely notice.  The `NatTraversal` worker asks the
+//! gateway to forward the p2p port, with UPnP IGD or NAT-PMP, and renews the mapping before it
+//! expires.  It then checks that the port is reachable at the node's external address -- as
+//! reported by the gateway, or as learned from a public peer's NAT punch reply -- by dialing it.
+//! An inbound connection from a public peer also counts as proof of reachability.
+//!
+//! Like the DNS resolver, these are blocking operations, so the worker runs in its own thread.
+//! The `PeerNetwork` shares state with it through a `NatHandle`, and reports its status in
+//! `/v3/info`.
+
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4, TcpStream, ToSocketAddrs, UdpSocket};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use std::{fmt, fs};
+
+use stacks_common::util::{get_epoch_time_secs, sleep_ms};
+use url::Url;
+
+use crate::net::connection::ConnectionOptions;
+
+/// Port on which NAT-PMP gateways listen
+pub const NATPMP_PORT: u16 = 5351;
+/// Multicast address to which SSDP searches are sent
+pub const SSDP_MULTICAST_ADDR: SocketAddrV4 =
+    SocketAddrV4::new(Ipv4Addr::new(239, 255, 255, 250), 1900);
+/// Most bytes to read in an HTTP response from a UPnP gateway
+pub const MAX_UPNP_RESPONSE_LEN: u64 = 1 << 20;
+/// Description of the port mapping, as shown in the gateway's admin interface
+pub const PORT_MAPPING_DESCRIPTION: &str = "stacks-node p2p";
+/// Shortest time between two passes of the NAT traversal worker, in seconds
+pub const MIN_NAT_REFRESH_INTERVAL: u64 = 60;
+/// Longest time between two passes of the NAT traversal worker, in seconds
+pub const MAX_NAT_REFRESH_INTERVAL: u64 = 600;
+
+const NATPMP_OP_EXTERNAL_ADDRESS: u8 = 0;
+const NATPMP_OP_MAP_TCP: u8 = 2;
+const NATPMP_RESPONSE_BIT: u8 = 0x80;
+
+/// Protocol used to map the p2p port on the NAT gateway
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NatProtocol {
+    /// UPnP Internet Gateway Device protocol
+    Upnp,
+    /// NAT Port Mapping Protocol (RFC 6886)
+    Natpmp,
+}
+
+impl fmt::Display for NatProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NatProtocol::Upnp => write!(f, "upnp"),
+            NatProtocol::Natpmp => write!(f, "natpmp"),
+        }
+    }
+}
+
+impl FromStr for NatProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "upnp" => Ok(NatProtocol::Upnp),
+            "natpmp" => Ok(NatProtocol::Natpmp),
+            _ => Err(format!("unknown NAT protocol '{s}'")),
+        }
+    }
+}
+
+/// A port mapping made on the NAT gateway
+#[derive(Debug, Clone, PartialEq)]
+pub struct NatPortMapping {
+    pub protocol: NatProtocol,
+    /// The gateway's external address, if it told us
+    pub external_ip: Option<IpAddr>,
+    /// The port forwarded to the p2p port
+    pub external_port: u16,
+    /// How long the gateway will keep the mapping, in seconds
+    pub lifetime: u64,
+}
+
+/// What the NAT traversal worker knows about this node's reachability
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NatTraversalStatus {
+    /// Protocol with which the p2p port is mapped on the gateway, if it is mapped
+    pub protocol: Option<NatProtocol>,
+    /// The address at which peers can reach the p2p port, if known
+    pub external_address: Option<SocketAddr>,
+    /// When the port mapping expires, in seconds since the epoch
+    pub mapping_expires_at: Option<u64>,
+    /// Whether the p2p port is reachable at `external_address`, or None if not yet checked
+    pub reachable: Option<bool>,
+    /// When reachability was last checked, in seconds since the epoch
+    pub checked_at: Option<u64>,
+    /// Why the last port mapping or reachability check failed, if it did
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct NatShared {
+    status: NatTraversalStatus,
+    /// Our IP address, as learned from a public peer
+    peer_public_ip: Option<IpAddr>,
+    /// Whether or not a peer with a public address is connected to us
+    inbound_public_peer: bool,
+}
+
+/// The `PeerNetwork`'s handle to the NAT traversal worker
+#[derive(Debug, Clone)]
+pub struct NatHandle {
+    shared: Arc<Mutex<NatShared>>,
+}
+
+impl NatHandle {
+    /// Get the worker's latest status
+    pub fn status(&self) -> NatTraversalStatus {
+        self.shared
+            .lock()
+            .expect("FATAL: NAT traversal state lock poisoned")
+            .status
+            .clone()
+    }
+
+    /// Tell the worker what public peers think our IP address is, and whether or not a peer with
+    /// a public address is connected to us
+    pub fn update_from_network(&self, peer_public_ip: Option<IpAddr>, inbound_public_peer: bool) {
+        let mut shared = self
+            .shared
+            .lock()
+            .expect("FATAL: NAT traversal state lock poisoned");
+        shared.peer_public_ip = peer_public_ip;
+        shared.inbound_public_peer = inbound_public_peer;
+    }
+}
+
+/// Maps the p2p port on the NAT gateway and checks that it is reachable.
+/// Runs in its own thread; see `thread_main()`.
+#[derive(Debug)]
+pub struct NatTraversal {
+    /// The p2p port to map
+    port: u16,
+    /// The protocol to use, or None to try UPnP and then NAT-PMP
+    protocol: Option<NatProtocol>,
+    /// The gateway to send NAT-PMP requests to, or None to use the default route's
+    gateway: Option<Ipv4Addr>,
+    /// How long to ask the gateway to keep the mapping, in seconds
+    lifetime: u64,
+    /// Timeout for each request to the gateway and for the reachability dial
+    timeout: Duration,
+    handle: NatHandle,
+}
+
+impl NatTraversal {
+    /// Make a worker that maps `port`, and the handle the `PeerNetwork` uses to talk to it
+    pub fn new(port: u16, opts: &ConnectionOptions) -> (NatTraversal, NatHandle) {
+        let handle = NatHandle {
+            shared: Arc::new(Mutex::new(NatShared::default())),
+        };
+        let worker = NatTraversal {
+            port,
+            protocol: opts.nat_protocol,
+            gateway: opts.nat_gateway,
+            lifetime: opts.nat_mapping_lifetime,
+            timeout: Duration::from_secs(opts.connect_timeout.max(1)),
+            handle: handle.clone(),
+        };
+        (worker, handle)
+    }
+
+    /// Map the p2p port with the configured protocol, or with whichever one works
+    pub fn map_port(&self) -> Result<NatPortMapping, String> {
+        match self.protocol {
+            Some(NatProtocol::Upnp) => self.map_port_upnp(),
+            Some(NatProtocol::Natpmp) => self.map_port_natpmp(),
+            None => self.map_port_upnp().or_else(|upnp_err| {
+                self.map_port_natpmp()
+                    .map_err(|natpmp_err| format!("{upnp_err}; {natpmp_err}"))
+            }),
+        }
+    }
+
+    fn map_port_upnp(&self) -> Result<NatPortMapping, String> {
+        let location = ssdp_discover(self.timeout)?;
+        let gateway = UpnpGateway::from_location(&location, self.timeout)?;
+        let local_ip = local_ip_towards(gateway.addr()?)?;
+        gateway.add_port_mapping(self.port, local_ip, self.lifetime, self.timeout)?;
+        let external_ip = gateway
+            .get_external_ip(self.timeout)
+            .inspect_err(|e| debug!("Failed to get external IP address over UPnP: {e}"))
+            .ok();
+        Ok(NatPortMapping {
+            protocol: NatProtocol::Upnp,
+            external_ip,
+            external_port: self.port,
+            lifetime: self.lifetime,
+        })
+    }
+
+    fn map_port_natpmp(&self) -> Result<NatPortMapping, String> {
+        let gateway = self
+            .gateway
+            .or_else(default_gateway_ipv4)
+            .ok_or("NAT-PMP: no gateway configured or found".to_string())?;
+        natpmp_map_port(
+            SocketAddr::from((gateway, NATPMP_PORT)),
+            self.port,
+            self.lifetime,
+            self.timeout,
+        )
+    }
+
+    /// Map the port, then check that it is reachable.  Returns the new status.
+    pub fn run_once(&mut self) -> NatTraversalStatus {
+        let (peer_public_ip, inbound_public_peer) = {
+            let shared = self
+                .handle
+                .shared
+                .lock()
+                .expect("FATAL: NAT traversal state lock poisoned");
+            (shared.peer_public_ip, shared.inbound_public_peer)
+        };
+
+        let now = get_epoch_time_secs();
+        let mut status = NatTraversalStatus::default();
+        let mut external_port = self.port;
+        match self.map_port() {
+            Ok(mapping) => {
+                info!(
+                    "Mapped p2p port {} to external port {} with {}",
+                    self.port, mapping.external_port, mapping.protocol;
+                    "external_ip" => ?mapping.external_ip,
+                    "lifetime" => mapping.lifetime
+                );
+                status.protocol = Some(mapping.protocol);
+                status.mapping_expires_at = Some(now.saturating_add(mapping.lifetime));
+                status.external_address = mapping
+                    .external_ip
+                    .map(|ip| SocketAddr::new(ip, mapping.external_port));
+                external_port = mapping.external_port;
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to map p2p port {} on the NAT gateway: {e}",
+                    self.port
+                );
+                status.error = Some(e);
+            }
+        }
+        if status.external_address.is_none() {
+            status.external_address = peer_public_ip.map(|ip| SocketAddr::new(ip, external_port));
+        }
+
+        if inbound_public_peer {
+            status.reachable = Some(true);
+            status.checked_at = Some(now);
+        } else if let Some(external_address) = status.external_address {
+            let reachable = check_reachability(&external_address, self.timeout);
+            if let Err(e) = reachable.as_ref() {
+                warn!(
+                    "p2p port is not reachable at {external_address}; other nodes will not be able to connect to this node: {e}"
+                );
+                status.error.get_or_insert_with(|| e.clone());
+            }
+            status.reachable = Some(reachable.is_ok());
+            status.checked_at = Some(now);
+        }
+
+        self.handle
+            .shared
+            .lock()
+            .expect("FATAL: NAT traversal state lock poisoned")
+            .status = status.clone();
+        status
+    }
+
+    /// Seconds to wait before the next pass, given the last one's status
+    fn refresh_interval(&self, status: &NatTraversalStatus) -> u64 {
+        if status.reachable.is_none() {
+            // don't know our external address yet
+            return MIN_NAT_REFRESH_INTERVAL;
+        }
+        (self.lifetime / 2).clamp(MIN_NAT_REFRESH_INTERVAL, MAX_NAT_REFRESH_INTERVAL)
+    }
+
+    /// Main loop of the NAT traversal thread.  Exits once the `PeerNetwork`'s handle is dropped.
+    pub fn thread_main(&mut self) {
+        debug!("NAT traversal start");
+        loop {
+            let status = self.run_once();
+            let deadline = get_epoch_time_secs() + self.refresh_interval(&status);
+            while get_epoch_time_secs() < deadline {
+                if Arc::strong_count(&self.handle.shared) <= 1 {
+                    debug!("NAT traversal handle dropped; exiting");
+                    return;
+                }
+                sleep_ms(1000);
+            }
+        }
+    }
+}
+
+/// Check that a p2p port is reachable by connecting to it
+pub fn check_reachability(addr: &SocketAddr, timeout: Duration) -> Result<(), String> {
+    TcpStream::connect_timeout(addr, timeout)
+        .map(|_| ())
+        .map_err(|e| format!("failed to connect to {addr}: {e}"))
+}
+
+/// Find the IPv4 default gateway.  Only implemented on Linux.
+pub fn default_gateway_ipv4() -> Option<Ipv4Addr> {
+    let routes = fs::read_to_string("/proc/net/route").ok()?;
+    parse_default_gateway(&routes)
+}
+
+/// Find the default route's gateway in the contents of `/proc/net/route`
+fn parse_default_gateway(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let fields: Vec<_> = line.split_whitespace().collect();
+        let (destination, gateway) = (fields.get(1)?, fields.get(2)?);
+        if *destination != "00000000" {
+            return None;
+        }
+        // little-endian hex
+        let gateway = u32::from_str_radix(gateway, 16).ok()?;
+        Some(Ipv4Addr::from(gateway.swap_bytes()))
+    })
+}
+
+/// Find the local IP address that packets to `remote` are sent from
+fn local_ip_towards(remote: SocketAddr) -> Result<IpAddr, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| format!("UDP bind failed: {e}"))?;
+    socket
+        .connect(remote)
+        .map_err(|e| format!("Failed to route to {remote}: {e}"))?;
+    socket
+        .local_addr()
+        .map(|addr| addr.ip())
+        .map_err(|e| format!("Failed to get local address: {e}"))
+}
+
+/// Encode a NAT-PMP request to map a TCP port
+fn natpmp_map_request(port: u16, lifetime: u32) -> [u8; 12] {
+    let mut request = [0u8; 12];
+    request[1] = NATPMP_OP_MAP_TCP;
+    request[4..6].copy_from_slice(&port.to_be_bytes());
+    request[6..8].copy_from_slice(&port.to_be_bytes());
+    request[8..12].copy_from_slice(&lifetime.to_be_bytes());
+    request
+}
+
+/// Check a NAT-PMP response's header, and return its payload (after the seconds-since-epoch
+/// field)
+fn natpmp_response_payload(response: &[u8], opcode: u8) -> Result<&[u8], String> {
+    if response.len() < 8 {
+        return Err("NAT-PMP: response too short".into());
+    }
+    if response[0] != 0 || response[1] != (opcode | NATPMP_RESPONSE_BIT) {
+        return Err(format!(
+            "NAT-PMP: unexpected response version {} opcode {}",
+            response[0], response[1]
+        ));
+    }
+    let result_code = u16::from_be_bytes([response[2], response[3]]);
+    if result_code != 0 {
+        return Err(format!(
+            "NAT-PMP: gateway returned result code {result_code}"
+        ));
+    }
+    Ok(&response[8..])
+}
+
+/// Decode a NAT-PMP external address response
+fn parse_natpmp_external_address(response: &[u8]) -> Result<Ipv4Addr, String> {
+    let payload = natpmp_response_payload(response, NATPMP_OP_EXTERNAL_ADDRESS)?;
+    let octets: [u8; 4] = payload
+        .get(0..4)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("NAT-PMP: external address response too short".to_string())?;
+    Ok(Ipv4Addr::from(octets))
+}
+
+/// Decode a NAT-PMP mapping response into the mapped external port and its lifetime
+fn parse_natpmp_map_response(response: &[u8], port: u16) -> Result<(u16, u32), String> {
+    let payload = natpmp_response_payload(response, NATPMP_OP_MAP_TCP)?;
+    if payload.len() < 8 {
+        return Err("NAT-PMP: mapping response too short".into());
+    }
+    let internal_port = u16::from_be_bytes([payload[0], payload[1]]);
+    if internal_port != port {
+        return Err(format!(
+            "NAT-PMP: gateway mapped port {internal_port}, not {port}"
+        ));
+    }
+    let external_port = u16::from_be_bytes([payload[2], payload[3]]);
+    let lifetime = u32::from_be_bytes([payload[4], payload[5], payload[6], payload[7]]);
+    Ok((external_port, lifetime))
+}
+
+/// Send a NAT-PMP request, retrying with a doubling timeout as RFC 6886 suggests, and return the
+/// response
+fn natpmp_request(
+    gateway: SocketAddr,
+    request: &[u8],
+    timeout: Duration,
+) -> Result<Vec<u8>, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| format!("NAT-PMP: bind failed: {e}"))?;
+    socket
+        .connect(gateway)
+        .map_err(|e| format!("NAT-PMP: failed to route to {gateway}: {e}"))?;
+    let mut wait = Duration::from_millis(250);
+    let mut waited = Duration::ZERO;
+    let mut buf = [0u8; 16];
+    while waited < timeout {
+        socket
+            .send(request)
+            .map_err(|e| format!("NAT-PMP: failed to send to {gateway}: {e}"))?;
+        socket
+            .set_read_timeout(Some(wait))
+            .map_err(|e| format!("NAT-PMP: failed to set timeout: {e}"))?;
+        if let Ok(len) = socket.recv(&mut buf) {
+            return Ok(buf[..len].to_vec());
+        }
+        waited += wait;
+        wait *= 2;
+    }
+    Err(format!("NAT-PMP: no response from {gateway}"))
+}
+
+/// Map `port` on a NAT-PMP gateway
+pub fn natpmp_map_port(
+    gateway: SocketAddr,
+    port: u16,
+    lifetime: u64,
+    timeout: Duration,
+) -> Result<NatPortMapping, String> {
+    let lifetime = u32::try_from(lifetime).unwrap_or(u32::MAX);
+    let response = natpmp_request(gateway, &natpmp_map_request(port, lifetime), timeout)?;
+    let (external_port, lifetime) = parse_natpmp_map_response(&response, port)?;
+
+    let response = natpmp_request(gateway, &[0, NATPMP_OP_EXTERNAL_ADDRESS], timeout)?;
+    let external_ip = parse_natpmp_external_address(&response)
+        .inspect_err(|e| debug!("Failed to get external IP address over NAT-PMP: {e}"))
+        .ok()
+        .map(IpAddr::V4);
+
+    Ok(NatPortMapping {
+        protocol: NatProtocol::Natpmp,
+        external_ip,
+        external_port,
+        lifetime: lifetime.into(),
+    })
+}
+
+/// Find an Internet gateway device with an SSDP search, and return the URL of its description
+fn ssdp_discover(timeout: Duration) -> Result<String, String> {
+    let request = "M-SEARCH * HTTP/1.1\r\n\
+                   HOST: 239.255.255.250:1900\r\n\
+                   ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\
+                   MAN: \"ssdp:discover\"\r\n\
+                   MX: 2\r\n\r\n";
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| format!("UPnP: bind failed: {e}"))?;
+    socket
+        .set_read_timeout(Some(timeout))
+        .map_err(|e| format!("UPnP: failed to set timeout: {e}"))?;
+    socket
+        .send_to(request.as_bytes(), SSDP_MULTICAST_ADDR)
+        .map_err(|e| format!("UPnP: failed to send SSDP search: {e}"))?;
+
+    let mut buf = [0u8; 2048];
+    loop {
+        let (len, _) = socket
+            .recv_from(&mut buf)
+            .map_err(|e| format!("UPnP: no Internet gateway device found: {e}"))?;
+        if let Some(location) = parse_ssdp_location(&String::from_utf8_lossy(&buf[..len])) {
+            return Ok(location);
+        }
+    }
+}
+
+/// Get the LOCATION header of an SSDP response
+fn parse_ssdp_location(response: &str) -> Option<String> {
+    response.lines().skip(1).find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("location")
+            .then(|| value.trim().to_string())
+    })
+}
+
+/// Get the text of the first `<tag>` element in `xml`
+fn xml_element<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let start = xml.find(&open)? + open.len();
+    let len = xml[start..].find(&close)?;
+    Some(xml[start..start + len].trim())
+}
+
+/// Find the WAN connection service in an Internet gateway device description, and return its
+/// service type and control URL
+fn parse_igd_description(description: &str) -> Option<(String, String)> {
+    description.split("<service>").skip(1).find_map(|service| {
+        let service_type = xml_element(service, "serviceType")?;
+        if !service_type.starts_with("urn:schemas-upnp-org:service:WANIPConnection:")
+            && !service_type.starts_with("urn:schemas-upnp-org:service:WANPPPConnection:")
+        {
+            return None;
+        }
+        let control_url = xml_element(service, "controlURL")?;
+        Some((service_type.to_string(), control_url.to_string()))
+    })
+}
+
+/// Undo chunked transfer encoding
+fn decode_chunked(body: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decoded = vec![];
+    let mut rest = body;
+    loop {
+        let line_end = rest
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .ok_or("HTTP: truncated chunk size".to_string())?;
+        let size_str = String::from_utf8_lossy(&rest[..line_end]);
+        let size_str = size_str.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size_str, 16)
+            .map_err(|_| format!("HTTP: bad chunk size '{size_str}'"))?;
+        rest = &rest[line_end + 2..];
+        if size == 0 {
+            return Ok(decoded);
+        }
+        let chunk = rest
+            .get(..size)
+            .ok_or("HTTP: truncated chunk".to_string())?;
+        decoded.extend_from_slice(chunk);
+        rest = rest.get(size + 2..).unwrap_or(&[]);
+    }
+}
+
+/// Send an HTTP request to a UPnP gateway, and return the response status code and body
+fn upnp_http_request(
+    url: &Url,
+    method: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+    timeout: Duration,
+) -> Result<(u16, String), String> {
+    let host = url.host_str().ok_or(format!("UPnP: no host in {url}"))?;
+    let port = url.port_or_known_default().unwrap_or(80);
+    let addr = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("UPnP: failed to resolve {host}: {e}"))?
+        .next()
+        .ok_or(format!("UPnP: failed to resolve {host}"))?;
+
+    let mut stream = TcpStream::connect_timeout(&addr, timeout)
+        .map_err(|e| format!("UPnP: failed to connect to {addr}: {e}"))?;
+    stream
+        .set_read_timeout(Some(timeout))
+        .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        .map_err(|e| format!("UPnP: failed to set timeout: {e}"))?;
+
+    let mut request = format!(
+        "{method} {} HTTP/1.1\r\nHost: {host}:{port}\r\nConnection: close\r\nContent-Length: {}\r\n",
+        &url[url::Position::BeforePath..],
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{name}: {value}\r\n"));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| format!("UPnP: failed to send request to {addr}: {e}"))?;
+
+    let mut response = vec![];
+    stream
+        .take(MAX_UPNP_RESPONSE_LEN)
+        .read_to_end(&mut response)
+        .map_err(|e| format!("UPnP: failed to read response from {addr}: {e}"))?;
+    parse_http_response(&response)
+}
+
+/// Decode an HTTP response into its status code and body
+fn parse_http_response(response: &[u8]) -> Result<(u16, String), String> {
+    let header_end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or("HTTP: truncated response headers".to_string())?;
+    let headers = String::from_utf8_lossy(&response[..header_end]);
+    let status = headers
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or("HTTP: bad status line".to_string())?;
+    let chunked = headers.lines().skip(1).any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding")
+                && value.trim().eq_ignore_ascii_case("chunked")
+        })
+    });
+    let body = &response[header_end + 4..];
+    let body = if chunked {
+        decode_chunked(body)?
+    } else {
+        body.to_vec()
+    };
+    Ok((status, String::from_utf8_lossy(&body).into_owned()))
+}
+
+/// Make the SOAP envelope for a UPnP action
+fn soap_envelope(service_type: &str, action: &str, args: &[(&str, String)]) -> String {
+    let args: String = args
+        .iter()
+        .map(|(name, value)| format!("<{name}>{value}</{name}>"))
+        .collect();
+    format!(
+        "<?xml version=\"1.0\"?>\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+         s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+         <s:Body><u:{action} xmlns:u=\"{service_type}\">{args}</u:{action}></s:Body>\
+         </s:Envelope>"
+    )
+}
+
+/// A UPnP Internet gateway device's WAN connection service
+#[derive(Debug, Clone, PartialEq)]
+struct UpnpGateway {
+    service_type: String,
+    control_url: Url,
+}
+
+impl UpnpGateway {
+    /// Load the gateway's description from `location`, and find its WAN connection service
+    fn from_location(location: &str, timeout: Duration) -> Result<Self, String> {
+        let location =
+            Url::parse(location).map_err(|e| format!("UPnP: bad location '{location}': {e}"))?;
+        let (status, description) = upnp_http_request(&location, "GET", &[], "", timeout)?;
+        if status != 200 {
+            return Err(format!(
+                "UPnP: got HTTP {status} for the gateway description"
+            ));
+        }
+        let (service_type, control_url) = parse_igd_description(&description)
+            .ok_or("UPnP: gateway has no WAN connection service".to_string())?;
+        let control_url = location
+            .join(&control_url)
+            .map_err(|e| format!("UPnP: bad control URL '{control_url}': {e}"))?;
+        Ok(Self {
+            service_type,
+            control_url,
+        })
+    }
+
+    /// The address of the gateway's control endpoint
+    fn addr(&self) -> Result<SocketAddr, String> {
+        self.control_url
+            .socket_addrs(|| Some(80))
+            .ok()
+            .and_then(|addrs| addrs.into_iter().next())
+            .ok_or(format!("UPnP: bad control URL {}", &self.control_url))
+    }
+
+    /// Invoke a UPnP action, and return the response body
+    fn call(
+        &self,
+        action: &str,
+        args: &[(&str, String)],
+        timeout: Duration,
+    ) -> Result<String, String> {
+        let body = soap_envelope(&self.service_type, action, args);
+        let soap_action = format!("\"{}#{action}\"", &self.service_type);
+        let (status, response) = upnp_http_request(
+            &self.control_url,
+            "POST",
+            &[
+                ("Content-Type", "text/xml; charset=\"utf-8\""),
+                ("SOAPAction", &soap_action),
+            ],
+            &body,
+            timeout,
+        )?;
+        if status != 200 {
+            let error = xml_element(&response, "errorDescription").unwrap_or("unknown error");
+            return Err(format!("UPnP: {action} failed with HTTP {status}: {error}"));
+        }
+        Ok(response)
+    }
+
+    fn add_port_mapping(
+        &self,
+        port: u16,
+        local_ip: IpAddr,
+        lifetime: u64,
+        timeout: Duration,
+    ) -> Result<(), String> {
+        self.call(
+            "AddPortMapping",
+            &[
+                ("NewRemoteHost", "".into()),
+                ("NewExternalPort", port.to_string()),
+                ("NewProtocol", "TCP".into()),
+                ("NewInternalPort", port.to_string()),
+                ("NewInternalClient", local_ip.to_string()),
+                ("NewEnabled", "1".into()),
+                ("NewPortMappingDescription", PORT_MAPPING_DESCRIPTION.into()),
+                ("NewLeaseDuration", lifetime.to_string()),
+            ],
+            timeout,
+        )
+        .map(|_| ())
+    }
+
+    fn get_external_ip(&self, timeout: Duration) -> Result<IpAddr, String> {
+        let response = self.call("GetExternalIPAddress", &[], timeout)?;
+        xml_element(&response, "NewExternalIPAddress")
+            .and_then(|ip| ip.parse().ok())
+            .ok_or("UPnP: no external IP address in response".to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_natpmp_codec() {
+        assert_eq!(
+            natpmp_map_request(20444, 3600),
+            [0, 2, 0, 0, 0x4f, 0xdc, 0x4f, 0xdc, 0, 0, 0x0e, 0x10]
+        );
+
+        let response = [0, 128, 0, 0, 0, 0, 0, 1, 203, 0, 113, 7];
+        assert_eq!(
+            parse_natpmp_external_address(&response).unwrap(),
+            Ipv4Addr::new(203, 0, 113, 7)
+        );
+
+        let response = [
+            0, 130, 0, 0, 0, 0, 0, 1, 0x4f, 0xdc, 0x4f, 0xdd, 0, 0, 0x07, 0x08,
+        ];
+        assert_eq!(
+            parse_natpmp_map_response(&response, 20444).unwrap(),
+            (20445, 1800)
+        );
+
+        // wrong port, wrong opcode, error result, truncated
+        assert!(parse_natpmp_map_response(&response, 20443).is_err());
+        assert!(parse_natpmp_external_address(&response).is_err());
+        let mut refused = response;
+        refused[3] = 2;
+        assert!(parse_natpmp_map_response(&refused, 20444).is_err());
+        assert!(parse_natpmp_map_response(&response[..12], 20444).is_err());
+    }
+
+    #[test]
+    fn test_natpmp_map_port() {
+        let gateway = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let gateway_addr = gateway.local_addr().unwrap();
+        let gateway_thread = thread::spawn(move || {
+            let mut buf = [0u8; 16];
+            let (len, from) = gateway.recv_from(&mut buf).unwrap();
+            assert_eq!(&buf[..len], &natpmp_map_request(20444, 3600));
+            let response = [
+                0, 130, 0, 0, 0, 0, 0, 1, 0x4f, 0xdc, 0x4f, 0xdc, 0, 0, 0x0e, 0x10,
+            ];
+            gateway.send_to(&response, from).unwrap();
+
+            let (len, from) = gateway.recv_from(&mut buf).unwrap();
+            assert_eq!(&buf[..len], &[0, 0]);
+            let response = [0, 128, 0, 0, 0, 0, 0, 1, 203, 0, 113, 7];
+            gateway.send_to(&response, from).unwrap();
+        });
+
+        let mapping = natpmp_map_port(gateway_addr, 20444, 3600, Duration::from_secs(5)).unwrap();
+        gateway_thread.join().unwrap();
+        assert_eq!(
+            mapping,
+            NatPortMapping {
+                protocol: NatProtocol::Natpmp,
+                external_ip: Some(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7))),
+                external_port: 20444,
+                lifetime: 3600,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_upnp_messages() {
+        let ssdp_response = "HTTP/1.1 200 OK\r\n\
+                             CACHE-CONTROL: max-age=120\r\n\
+                             Location: http://192.168.1.1:5000/rootDesc.xml\r\n\
+                             ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\r\n";
+        assert_eq!(
+            parse_ssdp_location(ssdp_response).as_deref(),
+            Some("http://192.168.1.1:5000/rootDesc.xml")
+        );
+        assert_eq!(parse_ssdp_location("HTTP/1.1 200 OK\r\n\r\n"), None);
+
+        let description = "<root><device><serviceList>\
+             <service><serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>\
+             <controlURL>/ctl/L3F</controlURL></service>\
+             <service><serviceType>urn:schemas-upnp-org:service:WANIPConnection:2</serviceType>\
+             <controlURL> /ctl/IPConn </controlURL></service>\
+             </serviceList></device></root>";
+        assert_eq!(
+            parse_igd_description(description),
+            Some((
+                "urn:schemas-upnp-org:service:WANIPConnection:2".to_string(),
+                "/ctl/IPConn".to_string()
+            ))
+        );
+        assert_eq!(parse_igd_description("<root></root>"), None);
+
+        let response = "<s:Envelope><s:Body><u:GetExternalIPAddressResponse>\
+             <NewExternalIPAddress>203.0.113.7</NewExternalIPAddress>\
+             </u:GetExternalIPAddressResponse></s:Body></s:Envelope>";
+        assert_eq!(
+            xml_element(response, "NewExternalIPAddress"),
+            Some("203.0.113.7")
+        );
+
+        let envelope = soap_envelope(
+            "urn:schemas-upnp-org:service:WANIPConnection:1",
+            "AddPortMapping",
+            &[("NewExternalPort", "20444".into())],
+        );
+        assert!(envelope.contains(
+            "<u:AddPortMapping xmlns:u=\"urn:schemas-upnp-org:service:WANIPConnection:1\">\
+             <NewExternalPort>20444</NewExternalPort></u:AddPortMapping>"
+        ));
+    }
+
+    #[test]
+    fn test_parse_http_response() {
+        let response = b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello";
+        assert_eq!(
+            parse_http_response(response).unwrap(),
+            (200, "hello".to_string())
+        );
+
+        let response =
+            b"HTTP/1.1 500 Internal Server Error\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n6;ext=1\r\n world\r\n0\r\n\r\n";
+        assert_eq!(
+            parse_http_response(response).unwrap(),
+            (500, "hello world".to_string())
+        );
+
+        assert!(parse_http_response(b"HTTP/1.1 200 OK\r\n").is_err());
+        assert!(parse_http_response(b"garbage\r\n\r\n").is_err());
+        assert!(parse_http_response(
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhe"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_default_gateway() {
+        let routes =
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+                      eth0\t0001A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0\n\
+                      eth0\t00000000\t0101A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0\n";
+        assert_eq!(
+            parse_default_gateway(routes),
+            Some(Ipv4Addr::new(192, 168, 1, 1))
+        );
+        assert_eq!(parse_default_gateway("Iface\tDestination\tGateway\n"), None);
+    }
+
+    #[test]
+    fn test_nat_traversal_reachability() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let opts = ConnectionOptions {
+            nat_traversal: true,
+            nat_protocol: Some(NatProtocol::Natpmp),
+            // no NAT-PMP gateway listens here
+            nat_gateway: Some(Ipv4Addr::LOCALHOST),
+            connect_timeout: 1,
+            ..ConnectionOptions::default()
+        };
+        let (mut worker, handle) = NatTraversal::new(port, &opts);
+        assert_eq!(handle.status(), NatTraversalStatus::default());
+
+        // no mapping and no external address, so reachability is unknown
+        let status = worker.run_once();
+        assert_eq!(status.protocol, None);
+        assert!(status.error.is_some());
+        assert_eq!(status.reachable, None);
+        assert_eq!(handle.status(), status);
+
+        // a public peer told us our address, so dial it
+        handle.update_from_network(Some(IpAddr::V4(Ipv4Addr::LOCALHOST)), false);
+        let status = worker.run_once();
+        assert_eq!(
+            status.external_address,
+            Some(SocketAddr::from((Ipv4Addr::LOCALHOST, port)))
+        );
+        assert_eq!(status.reachable, Some(true));
+
+        drop(listener);
+        let status = worker.run_once();
+        assert_eq!(status.reachable, Some(false));
+
+        // a public peer connected to us, so we're reachable
+        handle.update_from_network(Some(IpAddr::V4(Ipv4Addr::LOCALHOST)), true);
+        let status = worker.run_once();
+        assert_eq!(status.reachable, Some(true));
+    }
+}
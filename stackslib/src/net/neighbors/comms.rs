@@ -32,8 +32,8 @@ use crate::net::neighbors::{
 };
 use crate::net::p2p::PeerNetwork;
 use crate::net::{
-    Error as net_error, HandshakeData, Neighbor, NeighborAddress, NeighborKey, PeerAddress,
-    StacksMessage, StacksMessageType, NUM_NEIGHBORS,
+    Error as net_error, Neighbor, NeighborAddress, NeighborKey, PeerAddress, StacksMessage,
+    StacksMessageType, NUM_NEIGHBORS,
 };
 
 /// A trait for representing session state for a set of connected neighbors, for the purposes of executing some P2P
@@ -96,7 +96,7 @@ pub trait NeighborComms {
         let nk = neighbor_addr.to_neighbor_key(network);
 
         // send handshake.
-        let handshake_data = HandshakeData::from_local_peer(network.get_local_peer());
+        let handshake_data = network.handshake_data_for(&nk.addrbytes);
 
         debug!(
             "{:?}: send Handshake to {:?}",
@@ -386,6 +386,7 @@ impl NeighborWalkDB for PeerDBNeighborWalk {
             );
             neighbor_from_handshake
                 .save_update(&tx, db_data.map(|x| x.smart_contracts.as_slice()))?;
+            neighbor_from_handshake.save_services(&tx, handshake.services)?;
             tx.commit()?;
 
             // seen this neighbor before
@@ -403,6 +404,7 @@ impl NeighborWalkDB for PeerDBNeighborWalk {
 
         if added {
             // neighbor was new, and we had space to add it.
+            neighbor_from_handshake.save_services(&tx, handshake.services)?;
             tx.commit()?;
             return Ok((true, neighbor_from_handshake));
         }
@@ -530,6 +532,7 @@ impl NeighborWalkDB for PeerDBNeighborWalk {
             &data.handshake,
         )?;
         neighbor_from_handshake.save_update(&tx, db_data.map(|x| x.smart_contracts.as_slice()))?;
+        neighbor_from_handshake.save_services(&tx, data.handshake.services)?;
         tx.commit()?;
         Ok(neighbor_from_handshake)
     }
@@ -553,6 +556,10 @@ impl NeighborWalkDB for PeerDBNeighborWalk {
             cur_neighbor.save_update(&tx, None)?;
         }
 
+        if let Some(data) = new_data {
+            cur_neighbor.save_services(&tx, data.handshake.services)?;
+        }
+
         tx.commit()?;
         Ok(cur_neighbor)
     }
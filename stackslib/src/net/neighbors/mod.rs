@@ -35,6 +35,7 @@ pub mod comms;
 pub mod db;
 pub mod neighbor;
 pub mod rpc;
+pub mod seeds;
 pub mod walk;
 
 pub use comms::{NeighborComms, PeerNetworkComms, ToNeighborKey};
@@ -75,6 +75,19 @@ impl Neighbor {
         PeerDB::try_insert_peer(tx, self, stacker_dbs.unwrap_or(&[])).map_err(net_error::DBError)
     }
 
+    /// Record the services bitfield this neighbor advertised in its handshake, so we can later
+    /// pick peers that serve the data we need.  Does nothing if it's not in the peer DB.
+    pub fn save_services(&self, tx: &DBTx<'_>, services: u16) -> Result<(), net_error> {
+        PeerDB::set_peer_services(
+            tx,
+            self.addr.network_id,
+            &self.addr.addrbytes,
+            self.addr.port,
+            services,
+        )
+        .map_err(net_error::DBError)
+    }
+
     /// Attempt to load a neighbor from our peer DB, given its NeighborAddress reported by another
     /// peer.  Returns a neighbor in the peer DB if it matches the neighbor address and has a fresh public key
     /// (where "fresh" means "the public key hash matches the neighbor address")  If the neighbor
@@ -20,13 +20,14 @@ use std::{cmp, mem};
 use stacks_common::types::chainstate::StacksPublicKey;
 use stacks_common::types::net::PeerHost;
 use stacks_common::util::hash::Hash160;
-use stacks_common::util::log;
 use stacks_common::util::secp256k1::Secp256k1PublicKey;
+use stacks_common::util::{get_epoch_time_ms, log};
 
 use crate::burnchains::{Address, PublicKey};
 use crate::core::PEER_VERSION_TESTNET;
 use crate::net::connection::{ConnectionOptions, ReplyHandleP2P};
 use crate::net::db::{LocalPeer, PeerDB};
+use crate::net::http::HttpResponsePayload;
 use crate::net::neighbors::comms::ToNeighborKey;
 use crate::net::neighbors::{
     NeighborWalk, NeighborWalkDB, NeighborWalkResult, MAX_NEIGHBOR_BLOCK_DELAY,
@@ -44,7 +45,8 @@ use crate::net::{
 /// neighbor key (or something that converts to it)
 #[derive(Debug)]
 pub struct NeighborRPC {
-    state: HashMap<NeighborAddress, (usize, Option<StacksHttpRequest>)>,
+    /// Event ID, request to send, and the time in milliseconds at which it was queued
+    state: HashMap<NeighborAddress, (usize, Option<StacksHttpRequest>, u128)>,
     dead: HashSet<NeighborKey>,
     broken: HashSet<NeighborKey>,
 }
@@ -104,13 +106,13 @@ impl NeighborRPC {
         let mut inflight = HashMap::new();
         let mut dead = vec![];
         let mut ret = vec![];
-        for (naddr, (event_id, mut request_opt)) in self.state.drain() {
+        for (naddr, (event_id, mut request_opt, sent_ms)) in self.state.drain() {
             let response = match NeighborRPC::poll_next_reply(network, event_id, &mut request_opt) {
                 Ok(Some(response)) => response,
                 Ok(None) => {
                     // keep trying
                     debug!("Still waiting for next reply from {}", &naddr);
-                    inflight.insert(naddr, (event_id, request_opt));
+                    inflight.insert(naddr, (event_id, request_opt, sent_ms));
                     continue;
                 }
                 Err(NetError::WaitingForDNS) => {
@@ -119,7 +121,7 @@ impl NeighborRPC {
                         "Could not yet poll next reply from {}: waiting for DNS",
                         &naddr
                     );
-                    inflight.insert(naddr, (event_id, request_opt));
+                    inflight.insert(naddr, (event_id, request_opt, sent_ms));
                     continue;
                 }
                 Err(_e) => {
@@ -130,6 +132,14 @@ impl NeighborRPC {
                 }
             };
 
+            let nk = naddr.to_neighbor_key(network);
+            if let Some(convo) = network.get_neighbor_convo_mut(&nk) {
+                let elapsed_ms = get_epoch_time_ms().saturating_sub(sent_ms);
+                convo.stats.add_http_download(
+                    Self::response_len(&response),
+                    u64::try_from(elapsed_ms).unwrap_or(u64::MAX),
+                );
+            }
             ret.push((naddr, response));
         }
         for naddr in dead.into_iter() {
@@ -209,10 +219,24 @@ impl NeighborRPC {
             "Send request to {} on event {}: {:?}",
             &naddr, event_id, &request
         );
-        self.state.insert(naddr, (event_id, Some(request)));
+        self.state
+            .insert(naddr, (event_id, Some(request), get_epoch_time_ms()));
         Ok(())
     }
 
+    /// Number of bytes of data in an HTTP response
+    fn response_len(response: &StacksHttpResponse) -> u64 {
+        let len = match response.body() {
+            HttpResponsePayload::Empty => 0,
+            HttpResponsePayload::Bytes(bytes) => bytes.len(),
+            HttpResponsePayload::Text(text) => text.len(),
+            HttpResponsePayload::JSON(_) => {
+                usize::try_from(response.preamble().content_length.unwrap_or(0)).unwrap_or(0)
+            }
+        };
+        u64::try_from(len).unwrap_or(u64::MAX)
+    }
+
     /// Drive I/O on a given network conversation.
     /// Send the HTTP request if we haven't already done so, saturate the underlying TCP socket
     /// with bytes, and poll the event loop for any completed messages.  If we get one, then return
@@ -0,0 +1,285 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! DNS seeds for bootstrapping the neighbor walk.
+//!
+//! A DNS seed names a node by public key and hostname.  The hostname is resolved at runtime, and
+//! every address it resolves to is added to the peer DB's frontier, so a new node has somewhere
+//! to start walking from without a hard-coded `bootstrap_node`.  Seeds are either listed in the
+//! node's config file, or in a seed list signed by a seed operator's ed25519 key.
+
+use std::path::Path;
+use std::str::FromStr;
+use std::{fmt, fs};
+
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use stacks_common::util::hash::{hex_bytes, to_hex};
+use stacks_common::util::secp256k1::Secp256k1PublicKey;
+use stacks_common::util::{get_epoch_time_ms, get_epoch_time_secs};
+
+use crate::net::db::PeerDB;
+use crate::net::dns::DNSClient;
+use crate::net::p2p::PeerNetwork;
+use crate::net::{Neighbor, NeighborKey, PeerAddress};
+
+/// Domain separator for seed list signatures
+const SEED_LIST_SIGNATURE_DOMAIN: &[u8] = b"stacks-seed-list-v1";
+
+/// A seed node, given as `PUBKEY@HOST:PORT`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DNSSeed {
+    pub public_key: Secp256k1PublicKey,
+    /// Hostname or IP address.  IPv6 addresses are given without brackets.
+    pub host: String,
+    pub port: u16,
+}
+
+impl FromStr for DNSSeed {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (pubkey_str, hostport) = s
+            .split_once('@')
+            .ok_or_else(|| format!("Invalid DNS seed '{s}': expected PUBKEY@HOST:PORT"))?;
+        let public_key = Secp256k1PublicKey::from_hex(pubkey_str)
+            .map_err(|e| format!("Invalid DNS seed public key '{pubkey_str}': {e}"))?;
+        let (host, port_str) = hostport
+            .rsplit_once(':')
+            .ok_or_else(|| format!("Invalid DNS seed '{s}': expected PUBKEY@HOST:PORT"))?;
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        if host.is_empty() {
+            return Err(format!("Invalid DNS seed '{s}': no host"));
+        }
+        let port = port_str
+            .parse()
+            .map_err(|e| format!("Invalid DNS seed port '{port_str}': {e}"))?;
+        Ok(Self {
+            public_key,
+            host: host.to_string(),
+            port,
+        })
+    }
+}
+
+impl fmt::Display for DNSSeed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.host.contains(':') {
+            write!(
+                f,
+                "{}@[{}]:{}",
+                self.public_key.to_hex(),
+                self.host,
+                self.port
+            )
+        } else {
+            write!(
+                f,
+                "{}@{}:{}",
+                self.public_key.to_hex(),
+                self.host,
+                self.port
+            )
+        }
+    }
+}
+
+/// A list of seeds, signed by a seed operator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedSeedList {
+    /// Seeds, as `PUBKEY@HOST:PORT`
+    pub seeds: Vec<String>,
+    /// Unix time in seconds after which the list must not be used
+    pub expires_at: u64,
+    /// Hex-encoded ed25519 signature over the seeds and expiry time
+    pub signature: String,
+}
+
+impl SignedSeedList {
+    /// The message a seed operator signs
+    fn signing_message(seeds: &[String], expires_at: u64) -> Vec<u8> {
+        let mut msg = SEED_LIST_SIGNATURE_DOMAIN.to_vec();
+        msg.extend_from_slice(&expires_at.to_be_bytes());
+        for seed in seeds.iter() {
+            let len = u32::try_from(seed.len()).expect("FATAL: seed string exceeds u32::MAX");
+            msg.extend_from_slice(&len.to_be_bytes());
+            msg.extend_from_slice(seed.as_bytes());
+        }
+        msg
+    }
+
+    /// Sign a list of seeds with an operator's key
+    pub fn sign(seeds: Vec<String>, expires_at: u64, operator_key: &SigningKey) -> Self {
+        let signature = operator_key.sign(&Self::signing_message(&seeds, expires_at));
+        Self {
+            seeds,
+            expires_at,
+            signature: to_hex(&signature.to_bytes()),
+        }
+    }
+
+    /// Read a signed seed list from a JSON file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read seed list {}: {e}", path.display()))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse seed list {}: {e}", path.display()))
+    }
+
+    /// Check that this list is signed by one of `operator_keys` and has not expired as of `now`,
+    /// and decode its seeds.
+    pub fn verify(&self, operator_keys: &[VerifyingKey], now: u64) -> Result<Vec<DNSSeed>, String> {
+        if self.expires_at <= now {
+            return Err(format!("Seed list expired at {}", self.expires_at));
+        }
+        let sig_bytes: [u8; 64] = hex_bytes(&self.signature)
+            .map_err(|e| format!("Invalid seed list signature: {e:?}"))?
+            .try_into()
+            .map_err(|_| "Invalid seed list signature: expected 64 bytes".to_string())?;
+        let signature = Signature::from_bytes(&sig_bytes);
+        let msg = Self::signing_message(&self.seeds, self.expires_at);
+        if !operator_keys
+            .iter()
+            .any(|key| key.verify_strict(&msg, &signature).is_ok())
+        {
+            return Err("Seed list is not signed by a trusted operator key".into());
+        }
+        self.seeds.iter().map(|seed| seed.parse()).collect()
+    }
+}
+
+/// Decode a hex-encoded ed25519 seed operator public key
+pub fn parse_operator_key(key_hex: &str) -> Result<VerifyingKey, String> {
+    let key_bytes: [u8; 32] = hex_bytes(key_hex)
+        .map_err(|e| format!("Invalid seed operator key '{key_hex}': {e:?}"))?
+        .try_into()
+        .map_err(|_| format!("Invalid seed operator key '{key_hex}': expected 32 bytes"))?;
+    VerifyingKey::from_bytes(&key_bytes)
+        .map_err(|e| format!("Invalid seed operator key '{key_hex}': {e}"))
+}
+
+/// Progress on resolving the DNS seeds
+#[derive(Debug, Clone, Default)]
+pub struct DNSSeedState {
+    /// When we last began resolving the seeds
+    pub last_resolved_at: u64,
+    /// Seeds whose lookups are still in flight
+    pub inflight: Vec<DNSSeed>,
+    /// Number of seed addresses added to the frontier so far
+    pub num_added: u64,
+}
+
+impl PeerNetwork {
+    /// Resolve the DNS seeds every `dns_seed_interval` seconds, and add every address they resolve
+    /// to to the frontier so the neighbor walk can find them.
+    pub(crate) fn refresh_dns_seeds(&mut self, dns_client: &mut DNSClient) {
+        if self.connection_opts.dns_seeds.is_empty() {
+            return;
+        }
+
+        if self.dns_seed_state.inflight.is_empty() {
+            let now = get_epoch_time_secs();
+            if self.dns_seed_state.last_resolved_at + self.connection_opts.dns_seed_interval > now {
+                return;
+            }
+            let deadline = get_epoch_time_ms() + self.connection_opts.dns_timeout;
+            for seed in self.connection_opts.dns_seeds.iter() {
+                if let Err(e) = dns_client.queue_lookup(&seed.host, seed.port, deadline) {
+                    warn!("{:?}: Failed to queue DNS seed lookup", &self.local_peer;
+                          "seed" => %seed, "error" => ?e);
+                    continue;
+                }
+                self.dns_seed_state.inflight.push(seed.clone());
+            }
+            debug!("{:?}: Resolving DNS seeds", &self.local_peer;
+                   "num_seeds" => self.dns_seed_state.inflight.len());
+            self.dns_seed_state.last_resolved_at = now;
+            return;
+        }
+
+        if let Err(e) = dns_client.try_recv() {
+            warn!(
+                "{:?}: Failed to receive DNS seed lookups: {e:?}",
+                &self.local_peer
+            );
+        }
+
+        let mut neighbors = vec![];
+        let inflight = std::mem::take(&mut self.dns_seed_state.inflight);
+        for seed in inflight.into_iter() {
+            let response = match dns_client.poll_lookup(&seed.host, seed.port) {
+                Ok(Some(response)) => response,
+                Ok(None) => {
+                    self.dns_seed_state.inflight.push(seed);
+                    continue;
+                }
+                Err(e) => {
+                    warn!("{:?}: Failed to poll DNS seed lookup", &self.local_peer;
+                          "seed" => %seed, "error" => ?e);
+                    continue;
+                }
+            };
+            let addrs = match response.result {
+                Ok(addrs) => addrs,
+                Err(e) => {
+                    info!("{:?}: Failed to resolve DNS seed", &self.local_peer;
+                          "seed" => %seed, "error" => %e);
+                    continue;
+                }
+            };
+            for addr in addrs.into_iter() {
+                let nk = NeighborKey {
+                    peer_version: self.peer_version,
+                    network_id: self.local_peer.network_id,
+                    addrbytes: PeerAddress::from_socketaddr(&addr),
+                    port: addr.port(),
+                };
+                if self.is_bound(&nk) {
+                    continue;
+                }
+                neighbors.push(Neighbor::empty(&nk, &seed.public_key, 9999999));
+            }
+        }
+
+        if neighbors.is_empty() {
+            return;
+        }
+        let res: Result<u64, crate::util_lib::db::Error> = (|| {
+            let tx = self.peerdb.tx_begin()?;
+            let mut num_added = 0;
+            for neighbor in neighbors.iter() {
+                if PeerDB::try_insert_peer(&tx, neighbor, &[])? {
+                    num_added += 1;
+                }
+            }
+            tx.commit()?;
+            Ok(num_added)
+        })();
+        match res {
+            Ok(num_added) => {
+                debug!("{:?}: Added DNS seed neighbors to the frontier", &self.local_peer;
+                       "num_resolved" => neighbors.len(),
+                       "num_added" => num_added);
+                self.dns_seed_state.num_added += num_added;
+            }
+            Err(e) => {
+                warn!(
+                    "{:?}: Failed to store DNS seed neighbors: {e:?}",
+                    &self.local_peer
+                );
+            }
+        }
+    }
+}
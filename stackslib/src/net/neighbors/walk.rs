@@ -33,9 +33,9 @@ use crate::net::neighbors::{
 };
 use crate::net::p2p::PeerNetwork;
 use crate::net::{
-    Error as net_error, HandshakeAcceptData, HandshakeData, MessageSequence, Neighbor,
-    NeighborAddress, NeighborKey, PeerAddress, Preamble, StackerDBHandshakeData, StacksMessage,
-    StacksMessageType, NUM_NEIGHBORS,
+    Error as net_error, HandshakeAcceptData, MessageSequence, Neighbor, NeighborAddress,
+    NeighborKey, PeerAddress, Preamble, StackerDBHandshakeData, StacksMessage, StacksMessageType,
+    NUM_NEIGHBORS,
 };
 
 /// This struct records information from an inbound peer that has authenticated to this node.  As
@@ -1723,9 +1723,7 @@ impl<DB: NeighborWalkDB, NC: NeighborComms> NeighborWalk<DB, NC> {
             if let Err(e) = self.comms.neighbor_send(
                 network,
                 naddr,
-                StacksMessageType::Handshake(HandshakeData::from_local_peer(
-                    network.get_local_peer(),
-                )),
+                StacksMessageType::Handshake(network.handshake_data_for(&nk.addrbytes)),
             ) {
                 debug!(
                     "{:?}: Not connected to {:?}: ({:?}",
@@ -16,12 +16,13 @@
 
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::io::Write;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::sync::mpsc::{
     sync_channel, Receiver, RecvError, SendError, SyncSender, TryRecvError, TrySendError,
 };
 use std::thread::JoinHandle;
-use std::{cmp, mem};
+use std::{cmp, fmt, mem};
 
 use clarity::vm::ast::ASTRules;
 use clarity::vm::database::BurnStateDB;
@@ -44,9 +45,10 @@ use crate::chainstate::burn::db::sortdb::{get_ancestor_sort_id, BlockHeaderCache
 use crate::chainstate::burn::BlockSnapshot;
 use crate::chainstate::coordinator::{
     static_get_canonical_affirmation_map, static_get_heaviest_affirmation_map,
-    static_get_stacks_tip_affirmation_map, OnChainRewardSetProvider, RewardCycleInfo,
+    static_get_stacks_tip_affirmation_map, RewardCycleInfo,
 };
 use crate::chainstate::nakamoto::coordinator::load_nakamoto_reward_set;
+use crate::chainstate::nakamoto::NakamotoBlock;
 use crate::chainstate::stacks::boot::{RewardSet, MINERS_NAME};
 use crate::chainstate::stacks::db::{StacksBlockHeaderTypes, StacksChainState};
 use crate::chainstate::stacks::{StacksBlockHeader, MAX_BLOCK_LEN, MAX_TRANSACTION_LEN};
@@ -60,16 +62,22 @@ use crate::net::db::{LocalPeer, PeerDB};
 use crate::net::download::nakamoto::NakamotoDownloadStateMachine;
 use crate::net::download::BlockDownloader;
 use crate::net::http::HttpRequestContents;
-use crate::net::httpcore::StacksHttpRequest;
+use crate::net::httpcore::{AdminToken, StacksHttpRequest};
 use crate::net::inv::inv2x::*;
 use crate::net::inv::nakamoto::{InvGenerator, NakamotoInvStateMachine};
 use crate::net::mempool::MempoolSync;
+use crate::net::nat::{NatHandle, NatTraversalStatus};
+use crate::net::neighbors::seeds::DNSSeedState;
 use crate::net::neighbors::*;
 use crate::net::poll::{NetworkPollState, NetworkState};
 use crate::net::prune::*;
 use crate::net::relay::{RelayerStats, *, *};
 use crate::net::server::*;
 use crate::net::stackerdb::{StackerDBConfig, StackerDBSync, StackerDBTx, StackerDBs};
+use crate::net::stale_tip::StaleTipState;
+#[cfg(test)]
+use crate::net::tests::faults::NetworkFaults;
+use crate::net::tls::MaybeTlsStream;
 use crate::net::{Error as net_error, Neighbor, NeighborKey, *};
 use crate::util_lib::boot::boot_code_id;
 use crate::util_lib::db::{DBConn, DBTx, Error as db_error};
@@ -196,6 +204,37 @@ pub enum PeerNetworkWorkState {
 }
 
 pub type PeerMap = HashMap<usize, ConversationP2P>;
+
+/// Which server a bound interface belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InterfaceKind {
+    P2P,
+    Http,
+}
+
+/// Connection statistics for one bound p2p or HTTP interface
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceStats {
+    pub kind: InterfaceKind,
+    /// The address the interface is bound to
+    pub addr: SocketAddr,
+    /// Number of inbound connections accepted on this interface
+    pub num_accepted: u64,
+    /// Number of inbound conversations on this interface that are still open
+    pub num_open: u64,
+}
+
+impl InterfaceStats {
+    fn new(kind: InterfaceKind, addr: SocketAddr) -> Self {
+        Self {
+            kind,
+            addr,
+            num_accepted: 0,
+            num_open: 0,
+        }
+    }
+}
+
 pub type PendingMessages = HashMap<(usize, NeighborKey), Vec<StacksMessage>>;
 
 pub struct ConnectingPeer {
@@ -285,6 +324,8 @@ pub struct PeerNetwork {
     // refreshed whenever the burnchain advances
     pub chain_view: BurnchainView,
     pub burnchain_tip: BlockSnapshot,
+    /// Height of the highest burnchain header the indexer has downloaded, as of the last pass
+    pub burnchain_headers_height: u64,
     pub chain_view_stable_consensus_hash: ConsensusHash,
     pub ast_rules: ASTRules,
 
@@ -320,6 +361,8 @@ pub struct PeerNetwork {
     // ongoing p2p conversations (either they reached out to us, or we to them)
     pub peers: PeerMap,
     pub sockets: HashMap<usize, mio_net::TcpStream>,
+    /// TLS sessions for p2p conversations, if the network uses TLS
+    pub tls_sessions: HashMap<usize, rustls::Connection>,
     pub events: HashMap<NeighborKey, usize>,
     pub connecting: HashMap<usize, ConnectingPeer>,
     pub bans: HashSet<usize>,
@@ -335,6 +378,10 @@ pub struct PeerNetwork {
     pub network: Option<NetworkState>,
     p2p_network_handle: usize,
     http_network_handle: usize,
+    /// every bound interface, keyed by network handle (including the two above)
+    interfaces: HashMap<usize, InterfaceStats>,
+    /// network handle of the interface which accepted each inbound socket, keyed by event ID
+    interface_events: HashMap<usize, usize>,
 
     // info on the burn chain we're tracking
     pub burnchain: Burnchain,
@@ -360,6 +407,8 @@ pub struct PeerNetwork {
     pub walk_total_step_count: u64,
     pub walk_pingbacks: HashMap<NeighborAddress, NeighborPingback>, // inbound peers for us to try to ping back and add to our frontier, mapped to (peer_version, network_id, timeout, pubkey)
     pub walk_result: NeighborWalkResult, // last successful neighbor walk result
+    /// DNS seed resolution state
+    pub dns_seed_state: DNSSeedState,
 
     /// last time we logged neigbhors
     last_neighbor_log: u128,
@@ -442,12 +491,22 @@ pub struct PeerNetwork {
     // fault injection -- force disconnects
     fault_last_disconnect: u64,
 
+    /// fault injection -- drop, delay, or reorder inbound messages from specific peers
+    #[cfg(test)]
+    pub network_faults: NetworkFaults,
+
     /// Nakamoto-specific cache for sortition and tenure data, for the purposes of generating
     /// tenure inventories
     pub nakamoto_inv_generator: InvGenerator,
 
     /// Thread handle for the async block proposal endpoint.
     block_proposal_thread: Option<JoinHandle<()>>,
+
+    /// Handle to the NAT traversal worker, if `connection_opts.nat_traversal` is set
+    nat: Option<NatHandle>,
+
+    /// How long the Stacks tip has gone without advancing, and why it is considered stale
+    pub stale_tip: StaleTipState,
 }
 
 impl PeerNetwork {
@@ -513,6 +572,7 @@ impl PeerNetwork {
                 &first_burn_header_hash,
                 first_burn_header_ts as u64,
             ),
+            burnchain_headers_height: first_block_height,
             stacks_tip: StacksTipInfo::empty(),
             parent_stacks_tip: StacksTipInfo::empty(),
             tenure_start_block_id: StacksBlockId([0x00; 32]),
@@ -525,6 +585,7 @@ impl PeerNetwork {
 
             peers: PeerMap::new(),
             sockets: HashMap::new(),
+            tls_sessions: HashMap::new(),
             events: HashMap::new(),
             connecting: HashMap::new(),
             bans: HashSet::new(),
@@ -536,6 +597,8 @@ impl PeerNetwork {
             network: None,
             p2p_network_handle: 0,
             http_network_handle: 0,
+            interfaces: HashMap::new(),
+            interface_events: HashMap::new(),
 
             burnchain,
             connection_opts,
@@ -555,6 +618,7 @@ impl PeerNetwork {
             walk_total_step_count: 0,
             walk_pingbacks: HashMap::new(),
             walk_result: NeighborWalkResult::new(),
+            dns_seed_state: DNSSeedState::default(),
 
             last_neighbor_log: 0,
 
@@ -603,10 +667,16 @@ impl PeerNetwork {
             pending_stacks_messages: PendingMessages::new(),
 
             fault_last_disconnect: 0,
+            #[cfg(test)]
+            network_faults: NetworkFaults::new(0),
 
             nakamoto_inv_generator: InvGenerator::new(),
 
             block_proposal_thread: None,
+
+            nat: None,
+
+            stale_tip: StaleTipState::default(),
         };
 
         network.init_block_downloader();
@@ -619,6 +689,15 @@ impl PeerNetwork {
         self.block_proposal_thread = Some(thread);
     }
 
+    pub fn set_nat_handle(&mut self, nat: NatHandle) {
+        self.nat = Some(nat);
+    }
+
+    /// Get the NAT traversal worker's latest status, if it is running
+    pub fn get_nat_status(&self) -> Option<NatTraversalStatus> {
+        self.nat.as_ref().map(|nat| nat.status())
+    }
+
     pub fn is_proposal_thread_running(&mut self) -> bool {
         let Some(block_proposal_thread) = self.block_proposal_thread.take() else {
             // if block_proposal_thread is None, then no proposal thread is running
@@ -668,19 +747,65 @@ impl PeerNetwork {
     /// start serving.
     #[cfg_attr(test, mutants::skip)]
     pub fn bind(&mut self, my_addr: &SocketAddr, http_addr: &SocketAddr) -> Result<(), net_error> {
+        self.bind_interfaces(&[*my_addr], &[*http_addr])
+    }
+
+    /// Start serving on each of `p2p_addrs` and `http_addrs` (e.g. on both an IPv4 and an IPv6
+    /// address).  The first address of each is the primary interface, which identifies this node
+    /// and with which all p2p and HTTP sockets are registered.
+    #[cfg_attr(test, mutants::skip)]
+    pub fn bind_interfaces(
+        &mut self,
+        p2p_addrs: &[SocketAddr],
+        http_addrs: &[SocketAddr],
+    ) -> Result<(), net_error> {
+        let (Some(my_addr), Some(http_addr)) = (p2p_addrs.first(), http_addrs.first()) else {
+            error!("{:?}: no p2p or http address to bind", &self.local_peer);
+            return Err(net_error::BindError);
+        };
         let mut net = NetworkState::new(self.connection_opts.max_sockets)?;
 
         let (p2p_handle, bound_p2p_addr) = net.bind(my_addr)?;
         let (http_handle, bound_http_addr) = net.bind(http_addr)?;
 
+        let mut interfaces = HashMap::new();
+        interfaces.insert(
+            p2p_handle,
+            InterfaceStats::new(InterfaceKind::P2P, bound_p2p_addr),
+        );
+        interfaces.insert(
+            http_handle,
+            InterfaceStats::new(InterfaceKind::Http, bound_http_addr),
+        );
+        for addr in p2p_addrs.iter().skip(1) {
+            let (handle, bound_addr) = net.bind(addr)?;
+            interfaces.insert(handle, InterfaceStats::new(InterfaceKind::P2P, bound_addr));
+        }
+        for addr in http_addrs.iter().skip(1) {
+            let (handle, bound_addr) = net.bind(addr)?;
+            interfaces.insert(handle, InterfaceStats::new(InterfaceKind::Http, bound_addr));
+        }
+
         debug!(
             "{:?}: bound on p2p {:?}, http {:?}",
-            &self.local_peer, bound_p2p_addr, bound_http_addr
+            &self.local_peer,
+            interfaces
+                .values()
+                .filter(|iface| iface.kind == InterfaceKind::P2P)
+                .map(|iface| iface.addr)
+                .collect::<Vec<_>>(),
+            interfaces
+                .values()
+                .filter(|iface| iface.kind == InterfaceKind::Http)
+                .map(|iface| iface.addr)
+                .collect::<Vec<_>>()
         );
 
         self.network = Some(net);
         self.p2p_network_handle = p2p_handle;
         self.http_network_handle = http_handle;
+        self.interfaces = interfaces;
+        self.interface_events.clear();
 
         PeerNetwork::with_http(self, |_, ref mut http| {
             http.set_server_handle(http_handle, bound_http_addr);
@@ -706,12 +831,23 @@ impl PeerNetwork {
         &mut self,
         my_addr: &SocketAddr,
         http_addr: &SocketAddr,
+    ) -> Result<bool, net_error> {
+        self.try_bind_interfaces(&[*my_addr], &[*http_addr])
+    }
+
+    /// Call `bind_interfaces()` only if not already bound.
+    /// Returns `Ok(true)` if bound, `Ok(false)` if skipped, and `Err()` if binding failed.
+    #[cfg_attr(test, mutants::skip)]
+    pub fn try_bind_interfaces(
+        &mut self,
+        p2p_addrs: &[SocketAddr],
+        http_addrs: &[SocketAddr],
     ) -> Result<bool, net_error> {
         if self.network.is_some() {
             // Already bound
             return Ok(false);
         }
-        self.bind(my_addr, http_addr).map(|()| true)
+        self.bind_interfaces(p2p_addrs, http_addrs).map(|()| true)
     }
 
     /// Get bound neighbor key. This is how this PeerNetwork appears to other nodes.
@@ -729,6 +865,16 @@ impl PeerNetwork {
         &self.local_peer
     }
 
+    /// Make the handshake data to send to the peer at `peer_addrbytes`, advertising whichever of
+    /// our addresses is in the peer's address family
+    pub fn handshake_data_for(&self, peer_addrbytes: &PeerAddress) -> HandshakeData {
+        HandshakeData::from_local_peer_for(
+            &self.local_peer,
+            &self.connection_opts.extra_p2p_addresses,
+            peer_addrbytes,
+        )
+    }
+
     /// Get a ref to the connection opts
     pub fn get_connection_opts(&self) -> &ConnectionOptions {
         &self.connection_opts
@@ -911,9 +1057,9 @@ impl PeerNetwork {
     /// the `handle'`s other end (within the `convo`'s inner connection) is up for transmission.
     ///
     /// Return (number of bytes sent, whether or not there's more to send)
-    fn do_saturate_p2p_socket(
+    fn do_saturate_p2p_socket<S: Write + fmt::Debug>(
         convo: &mut ConversationP2P,
-        client_sock: &mut mio::net::TcpStream,
+        client_sock: &mut S,
         handle: &mut ReplyHandleP2P,
     ) -> Result<(usize, bool), net_error> {
         let mut total_sent = 0;
@@ -1274,6 +1420,52 @@ impl PeerNetwork {
         unique.into_iter().collect::<Vec<NeighborKey>>()
     }
 
+    /// Broadcast Nakamoto blocks to a list of neighbors.  Each neighbor is sent only the blocks
+    /// that it has not pushed to us, and that we have not pushed to it, within the last
+    /// `nakamoto_push_dedup_window_ms` milliseconds.  Neighbors that already have every block are
+    /// skipped.
+    fn broadcast_nakamoto_blocks(
+        &mut self,
+        neighbor_keys: Vec<NeighborKey>,
+        relay_hints: Vec<RelayData>,
+        blocks: &[NakamotoBlock],
+    ) {
+        let now = get_epoch_time_ms();
+        let window = self.connection_opts.nakamoto_push_dedup_window_ms;
+        self.relayer_stats.prune_nakamoto_blocks_seen(window, now);
+
+        for nk in neighbor_keys.into_iter() {
+            let unseen = self
+                .relayer_stats
+                .filter_unseen_nakamoto_blocks(&nk, blocks, window, now);
+            if unseen.is_empty() {
+                debug!(
+                    "{:?}: Do not push {} Nakamoto block(s) to {:?}: it already has them",
+                    &self.local_peer,
+                    blocks.len(),
+                    &nk
+                );
+                continue;
+            }
+            if unseen.len() < blocks.len() {
+                debug!(
+                    "{:?}: Push {} of {} Nakamoto block(s) to {:?}; it already has the rest",
+                    &self.local_peer,
+                    unseen.len(),
+                    blocks.len(),
+                    &nk
+                );
+            }
+            self.relayer_stats
+                .add_nakamoto_blocks_seen(&nk, unseen.iter(), now);
+            self.broadcast_message(
+                vec![nk],
+                relay_hints.clone(),
+                StacksMessageType::NakamotoBlocks(NakamotoBlocksData { blocks: unseen }),
+            );
+        }
+    }
+
     /// Sample the available connections to broadcast on.
     /// Up to MAX_BROADCAST_OUTBOUND_PEERS outbound connections will be used.
     /// Up to MAX_BROADCAST_INBOUND_PEERS inbound connections will be used.
@@ -1285,6 +1477,99 @@ impl PeerNetwork {
         &self,
         relay_hints: &[RelayData],
         payload: &R,
+    ) -> Result<Vec<NeighborKey>, net_error> {
+        self.sample_broadcast_peers_limited(
+            relay_hints,
+            payload,
+            MAX_BROADCAST_OUTBOUND_RECEIVERS,
+            MAX_BROADCAST_INBOUND_RECEIVERS,
+        )
+    }
+
+    /// Sample the neighbors to forward a new transaction to.  The transaction goes to up to
+    /// `connection_opts.tx_broadcast_max_outbound` outbound and
+    /// `connection_opts.tx_broadcast_max_inbound` inbound connections, sampled as in
+    /// `sample_broadcast_peers()`.  In addition, it is eagerly pushed to up to
+    /// `connection_opts.tx_push_max_miner_neighbors` neighbors that advertise the MINER service
+    /// bit, so miners see it before their next mempool sync.  Miners come first in the returned
+    /// list.
+    fn sample_tx_broadcast_peers(
+        &self,
+        relay_hints: &[RelayData],
+        tx: &StacksTransaction,
+    ) -> Result<Vec<NeighborKey>, net_error> {
+        let sampled = self.sample_broadcast_peers_limited(
+            relay_hints,
+            tx,
+            self.connection_opts.tx_broadcast_max_outbound,
+            self.connection_opts.tx_broadcast_max_inbound,
+        )?;
+        let max_miners = self.connection_opts.tx_push_max_miner_neighbors;
+        if max_miners == 0 {
+            return Ok(sampled);
+        }
+
+        let relay_pubkhs: HashSet<_> = relay_hints
+            .iter()
+            .map(|rhint| &rhint.peer.public_key_hash)
+            .collect();
+        let mut miners = vec![];
+        for (_, convo) in self.peers.iter() {
+            if !convo.is_authenticated() || !ConversationP2P::advertises_miner(convo.peer_services)
+            {
+                continue;
+            }
+            // don't push the transaction back to a miner that sent it to us
+            if let Some(pubkey) = convo.ref_public_key() {
+                if relay_pubkhs.contains(&Hash160::from_node_public_key(pubkey)) {
+                    continue;
+                }
+            }
+            miners.push(convo.to_neighbor_key());
+        }
+        let mut miners = self.coalesce_neighbors(miners);
+        miners.shuffle(&mut thread_rng());
+        miners.truncate(max_miners);
+
+        debug!(
+            "{:?}: Eagerly push {} to {} miner neighbor(s): {:?}",
+            &self.local_peer,
+            tx.txid(),
+            miners.len(),
+            &miners
+        );
+
+        let miner_set: HashSet<_> = miners.iter().cloned().collect();
+        let miner_events: HashSet<_> = miners
+            .iter()
+            .filter_map(|nk| self.events.get(nk))
+            .filter_map(|event_id| self.find_reciprocal_event(*event_id))
+            .collect();
+        for nk in sampled.into_iter() {
+            if miner_set.contains(&nk) {
+                continue;
+            }
+            // don't send it twice to a miner over its other connection
+            if self
+                .events
+                .get(&nk)
+                .is_some_and(|event_id| miner_events.contains(event_id))
+            {
+                continue;
+            }
+            miners.push(nk);
+        }
+        Ok(miners)
+    }
+
+    /// Sample up to `max_outbound` outbound and `max_inbound` inbound connections to broadcast
+    /// on, as described in `sample_broadcast_peers()`.
+    fn sample_broadcast_peers_limited<R: RelayPayload>(
+        &self,
+        relay_hints: &[RelayData],
+        payload: &R,
+        max_outbound: usize,
+        max_inbound: usize,
     ) -> Result<Vec<NeighborKey>, net_error> {
         // coalesce
         let mut outbound_neighbors = vec![];
@@ -1345,10 +1630,8 @@ impl PeerNetwork {
             &outbound_dist
         );
 
-        let mut outbound_sample =
-            RelayerStats::sample_neighbors(outbound_dist, MAX_BROADCAST_OUTBOUND_RECEIVERS);
-        let mut inbound_sample =
-            RelayerStats::sample_neighbors(inbound_dist, MAX_BROADCAST_INBOUND_RECEIVERS);
+        let mut outbound_sample = RelayerStats::sample_neighbors(outbound_dist, max_outbound);
+        let mut inbound_sample = RelayerStats::sample_neighbors(inbound_dist, max_inbound);
 
         debug!(
             "Inbound recipients (out of {}): {:?}",
@@ -1431,13 +1714,19 @@ impl PeerNetwork {
 
                             all_neighbors.extend(neighbors);
                         }
-                        Ok(all_neighbors.into_iter().collect())
+                        // each neighbor only gets the blocks it doesn't already have
+                        self.broadcast_nakamoto_blocks(
+                            all_neighbors.into_iter().collect(),
+                            relay_hints,
+                            &data.blocks,
+                        );
+                        return Ok(());
                     }
                     StacksMessageType::StackerDBPushChunk(ref data) => {
                         Ok(self.sample_broadcast_peers(&relay_hints, data)?)
                     }
                     StacksMessageType::Transaction(ref data) => {
-                        self.sample_broadcast_peers(&relay_hints, data)
+                        self.sample_tx_broadcast_peers(&relay_hints, data)
                     }
                     _ => {
                         // not suitable for broadcast
@@ -1638,6 +1927,30 @@ impl PeerNetwork {
         None
     }
 
+    /// Ban every neighbor connected from `addr` the next time bans are processed.  Neighbors on
+    /// the allow list are not banned.  Returns the neighbors found.
+    pub fn ban_neighbors_at(&mut self, addr: &SocketAddr) -> Vec<NeighborKey> {
+        let addrbytes = PeerAddress::from_socketaddr(addr);
+        let mut banned = vec![];
+        for (event_id, convo) in self.peers.iter() {
+            let neighbor_key = convo.to_neighbor_key();
+            if neighbor_key.addrbytes != addrbytes || neighbor_key.port != addr.port() {
+                continue;
+            }
+            info!("Request to ban {neighbor_key:?}");
+            self.bans.insert(*event_id);
+            banned.push(neighbor_key);
+        }
+        banned
+    }
+
+    /// Replace the tokens accepted by the `/v3/admin/*` endpoints.  HTTP connections opened from
+    /// now on accept the new tokens; open connections keep the tokens they were opened with.
+    pub fn set_admin_tokens(&mut self, auth_token: Option<String>, admin_tokens: Vec<AdminToken>) {
+        self.connection_opts.auth_token = auth_token;
+        self.connection_opts.admin_tokens = admin_tokens;
+    }
+
     /// Is an event ID connecting?
     pub fn is_connecting(&self, event_id: usize) -> bool {
         self.connecting.contains_key(&event_id)
@@ -1818,6 +2131,25 @@ impl PeerNetwork {
             }
         }
 
+        // if the network uses TLS, every conversation is encrypted
+        let tls_session = match &self.connection_opts.p2p_tls {
+            Some(tls_config) => {
+                let session_res = if outbound {
+                    tls_config.new_outbound_session(client_addr.ip())
+                } else {
+                    tls_config.new_inbound_session()
+                };
+                match session_res {
+                    Ok(session) => Some(session),
+                    Err(e) => {
+                        self.deregister_socket(event_id, socket);
+                        return Err(e);
+                    }
+                }
+            }
+            None => None,
+        };
+
         let mut new_convo = ConversationP2P::new(
             self.local_peer.network_id,
             self.peer_version,
@@ -1841,6 +2173,9 @@ impl PeerNetwork {
         self.sockets.insert(event_id, socket);
         self.peers.insert(event_id, new_convo);
         self.events.insert(neighbor_key, event_id);
+        if let Some(session) = tls_session {
+            self.tls_sessions.insert(event_id, session);
+        }
 
         Ok(())
     }
@@ -1885,6 +2220,9 @@ impl PeerNetwork {
     pub fn deregister_peer(&mut self, event_id: usize) {
         debug!("{:?}: Disconnect event {}", &self.local_peer, event_id);
 
+        #[cfg(test)]
+        self.network_faults.forget_conversation(event_id);
+
         let mut nk_remove: Vec<(NeighborKey, Hash160)> = vec![];
         for (neighbor_key, ev_id) in self.events.iter() {
             if *ev_id == event_id {
@@ -1928,7 +2266,12 @@ impl PeerNetwork {
             None => {}
             Some(ref mut network) => {
                 // deregister socket if connected and registered already
-                if let Some(socket) = self.sockets.remove(&event_id) {
+                if let Some(mut socket) = self.sockets.remove(&event_id) {
+                    if let Some(session) = self.tls_sessions.get_mut(&event_id) {
+                        // best-effort; the socket is about to be closed regardless
+                        session.send_close_notify();
+                        let _ = MaybeTlsStream::new(&mut socket, Some(session)).flush_tls();
+                    }
                     let _ = network.deregister(event_id, &socket);
                 }
                 // deregister socket if still connecting
@@ -1940,6 +2283,7 @@ impl PeerNetwork {
 
         self.relay_handles.remove(&event_id);
         self.peers.remove(&event_id);
+        self.tls_sessions.remove(&event_id);
     }
 
     /// Deregister by neighbor key
@@ -2081,7 +2425,11 @@ impl PeerNetwork {
     /// so `todo` can take a mutable ref to the PeerNetwork
     fn with_p2p_convo<F, R>(&mut self, event_id: usize, todo: F) -> Result<R, net_error>
     where
-        F: FnOnce(&mut PeerNetwork, &mut ConversationP2P, &mut mio_net::TcpStream) -> R,
+        F: FnOnce(
+            &mut PeerNetwork,
+            &mut ConversationP2P,
+            &mut MaybeTlsStream<mio_net::TcpStream>,
+        ) -> R,
     {
         // "check out" the conversation and client socket.
         // If one of them is missing, then "check in" the other so we can properly deregister the
@@ -2108,11 +2456,19 @@ impl PeerNetwork {
                 }
             };
 
-        let res = todo(self, &mut convo, &mut client_sock);
+        let mut tls_session = self.tls_sessions.remove(&event_id);
+        let res = todo(
+            self,
+            &mut convo,
+            &mut MaybeTlsStream::new(&mut client_sock, tls_session.as_mut()),
+        );
 
         // "check in"
         self.peers.insert(event_id, convo);
         self.sockets.insert(event_id, client_sock);
+        if let Some(session) = tls_session {
+            self.tls_sessions.insert(event_id, session);
+        }
 
         Ok(res)
     }
@@ -2180,6 +2536,14 @@ impl PeerNetwork {
                     convo_dead = true;
                 }
             }
+            // send any TLS handshake messages that no message pushed out
+            if let Err(e) = client_sock.flush_tls() {
+                debug!(
+                    "Failed to flush TLS data to event {} (socket {:?}): {:?}",
+                    event_id, &client_sock, &e
+                );
+                convo_dead = true;
+            }
             (unhandled, !convo_dead)
         })
     }
@@ -2483,7 +2847,11 @@ impl PeerNetwork {
         let mut msgs = HashMap::new();
         for (event_id, convo) in self.peers.iter_mut() {
             let nk = convo.to_neighbor_key();
-            let handshake_data = HandshakeData::from_local_peer(&self.local_peer);
+            let handshake_data = HandshakeData::from_local_peer_for(
+                &self.local_peer,
+                &self.connection_opts.extra_p2p_addresses,
+                &nk.addrbytes,
+            );
             let handshake = StacksMessageType::Handshake(handshake_data);
 
             debug!(
@@ -2854,6 +3222,26 @@ impl PeerNetwork {
         }
     }
 
+    /// Tell the NAT traversal worker, if any, what our public IP address is, and whether or not a
+    /// peer with a public address is connected to us (in which case our p2p port is reachable)
+    fn update_nat_traversal(&self) {
+        let Some(nat) = self.nat.as_ref() else {
+            return;
+        };
+        let public_ip = self
+            .local_peer
+            .public_ip_address
+            .as_ref()
+            .map(|(addrbytes, _)| addrbytes.to_socketaddr(0).ip());
+        let inbound_public_peer = self.iter_peer_convos().any(|(_, convo)| {
+            !convo.is_outbound()
+                && convo.is_authenticated()
+                && !convo.peer_addrbytes.is_in_private_range()
+                && !convo.peer_addrbytes.is_loopback()
+        });
+        nat.update_from_network(public_ip, inbound_public_peer);
+    }
+
     /// Learn our publicly-routable IP address
     /// return true if we're done with this state machine
     fn do_get_public_ip(&mut self) -> bool {
@@ -3621,6 +4009,7 @@ impl PeerNetwork {
                 network_result,
             )
         };
+        self.update_nat_traversal();
         prune
     }
 
@@ -3658,6 +4047,10 @@ impl PeerNetwork {
 
         network_result.consume_nakamoto_blocks(new_blocks);
 
+        // re-download tenures if our tip has stopped advancing while neighbors are ahead of us
+        // (this self-throttles)
+        self.check_stale_tip(sortdb, chainstate);
+
         // make sure our public IP is fresh (this self-throttles if we recently learned it).
         if !self.connection_opts.disable_natpunch {
             self.do_get_public_ip();
@@ -4359,6 +4752,7 @@ impl PeerNetwork {
 
         let prev_rc = cur_rc.saturating_sub(1);
         let prev_prev_rc = prev_rc.saturating_sub(1);
+        let reward_set_provider = chainstate.reward_set_provider();
 
         for rc in [cur_rc, prev_rc, prev_prev_rc] {
             debug!("Refresh reward cycle info for cycle {}", rc);
@@ -4382,7 +4776,7 @@ impl PeerNetwork {
                 chainstate,
                 tip_block_id,
                 sortdb,
-                &OnChainRewardSetProvider::new(),
+                &reward_set_provider,
             )
             .inspect_err(|e| warn!("Failed to load reward cycle info for cycle {rc}: {e:?}"))
             .unwrap_or(None) else {
@@ -4811,6 +5205,11 @@ impl PeerNetwork {
             self.prune_connections();
         }
 
+        // Feed DNS seeds into the frontier, if we have any
+        if let Some(dns_client) = dns_client_opt.as_deref_mut() {
+            self.refresh_dns_seeds(dns_client);
+        }
+
         // In parallel, do a neighbor walk
         self.do_network_neighbor_walk(ibd);
 
@@ -4964,6 +5363,22 @@ impl PeerNetwork {
         network_result: &mut NetworkResult,
         event_observer: Option<&dyn MemPoolEventDispatcher>,
     ) -> Result<(), net_error> {
+        if mempool.is_admission_paused() {
+            // discard them, and don't relay them either
+            let num_pushed: usize = network_result
+                .pushed_transactions
+                .drain()
+                .map(|(_nk, tx_data)| tx_data.len())
+                .sum();
+            let num_synced = network_result.synced_transactions.drain(..).len();
+            if num_pushed + num_synced > 0 {
+                debug!("Mempool admission is paused; discarding inbound transactions";
+                       "pushed" => num_pushed,
+                       "synced" => num_synced);
+            }
+            return Ok(());
+        }
+
         let (canonical_consensus_hash, canonical_block_hash) = if let Some(header) =
             NakamotoChainState::get_canonical_block_header(chainstate.db(), sortdb)?
         {
@@ -5162,9 +5577,96 @@ impl PeerNetwork {
             self.get_local_peer(),
             &convo_strs
         );
+        debug!(
+            "{:?}: interface stats are {:?}",
+            self.get_local_peer(),
+            &self.get_interface_stats()
+        );
         self.last_neighbor_log = now;
     }
 
+    /// Combine the poll states of every bound interface into one p2p poll state and one HTTP poll
+    /// state, and count the inbound sockets each interface accepted.  Sockets are all registered
+    /// with the primary interfaces, so the other interfaces only ever report new sockets.
+    fn merge_interface_poll_states(
+        &mut self,
+        mut poll_states: HashMap<usize, NetworkPollState>,
+    ) -> (NetworkPollState, NetworkPollState) {
+        let mut p2p_poll_state = poll_states
+            .remove(&self.p2p_network_handle)
+            .expect("BUG: no poll state for p2p network handle");
+        let mut http_poll_state = poll_states
+            .remove(&self.http_network_handle)
+            .expect("BUG: no poll state for http network handle");
+
+        // forget sockets which have since been closed
+        let http_sockets = self.http.as_ref().map(|http| &http.sockets);
+        let p2p_sockets = &self.sockets;
+        self.interface_events.retain(|event_id, _| {
+            p2p_sockets.contains_key(event_id)
+                || http_sockets.is_some_and(|sockets| sockets.contains_key(event_id))
+        });
+
+        let mut new_sockets: Vec<(usize, Vec<usize>)> = vec![
+            (
+                self.p2p_network_handle,
+                p2p_poll_state.new.keys().copied().collect(),
+            ),
+            (
+                self.http_network_handle,
+                http_poll_state.new.keys().copied().collect(),
+            ),
+        ];
+        for (handle, mut poll_state) in poll_states.into_iter() {
+            let Some(iface) = self.interfaces.get(&handle) else {
+                warn!("{:?}: unknown network handle {handle}", &self.local_peer);
+                continue;
+            };
+            let merged_poll_state = match iface.kind {
+                InterfaceKind::P2P => &mut p2p_poll_state,
+                InterfaceKind::Http => &mut http_poll_state,
+            };
+            new_sockets.push((handle, poll_state.new.keys().copied().collect()));
+            merged_poll_state.new.extend(poll_state.new.drain());
+            merged_poll_state.ready.append(&mut poll_state.ready);
+        }
+
+        for (handle, event_ids) in new_sockets.into_iter() {
+            if let Some(iface) = self.interfaces.get_mut(&handle) {
+                iface.num_accepted += u64::try_from(event_ids.len()).unwrap_or(u64::MAX);
+            }
+            for event_id in event_ids.into_iter() {
+                self.interface_events.insert(event_id, handle);
+            }
+        }
+        (p2p_poll_state, http_poll_state)
+    }
+
+    /// Get the connection statistics of each bound interface, ordered by kind and address
+    pub fn get_interface_stats(&self) -> Vec<InterfaceStats> {
+        let mut interfaces = self.interfaces.clone();
+        for (event_id, handle) in self.interface_events.iter() {
+            let p2p_open = self
+                .peers
+                .get(event_id)
+                .is_some_and(|convo| !convo.is_outbound());
+            let http_open = self
+                .http
+                .as_ref()
+                .and_then(|http| http.peers.get(event_id))
+                .is_some_and(|convo| convo.get_url().is_none());
+            if !p2p_open && !http_open {
+                continue;
+            }
+            if let Some(iface) = interfaces.get_mut(handle) {
+                iface.num_open += 1;
+            }
+        }
+        let mut stats: Vec<_> = interfaces.into_values().collect();
+        stats.sort_by_key(|iface| (iface.kind, iface.addr));
+        stats
+    }
+
     /// Top-level main-loop circuit to take.
     /// -- polls the peer network and http network server sockets to get new sockets and detect ready sockets
     /// -- carries out network conversations
@@ -5199,12 +5701,9 @@ impl PeerNetwork {
             }
         }?;
 
-        let p2p_poll_state = poll_states
-            .remove(&self.p2p_network_handle)
-            .expect("BUG: no poll state for p2p network handle");
-        let http_poll_state = poll_states
-            .remove(&self.http_network_handle)
-            .expect("BUG: no poll state for http network handle");
+        let (p2p_poll_state, http_poll_state) = self.merge_interface_poll_states(poll_states);
+        #[cfg(test)]
+        let p2p_poll_state = self.network_faults.schedule(p2p_poll_state);
 
         // update local-peer state
         self.refresh_local_peer()
@@ -5271,6 +5770,7 @@ impl PeerNetwork {
             // N.B. the indexer reports 1 + num_headers
             .map(|burnchain_height| burnchain_height.saturating_sub(1))
             .unwrap_or(self.burnchain_tip.block_height);
+        self.burnchain_headers_height = burnchain_height;
 
         self.dispatch_network(
             &mut network_result,
@@ -5480,6 +5980,73 @@ mod test {
         })
     }
 
+    #[test]
+    fn test_bind_multiple_interfaces() {
+        let mut p2p = make_test_p2p_network(&[]);
+        let localhost: SocketAddr = "127.0.0.1:0".parse().unwrap();
+        p2p.bind_interfaces(&[localhost, localhost], &[localhost, localhost])
+            .unwrap();
+
+        let stats = p2p.get_interface_stats();
+        assert_eq!(stats.len(), 4);
+        assert_eq!(stats[0].kind, InterfaceKind::P2P);
+        assert_eq!(stats[1].kind, InterfaceKind::P2P);
+        assert_eq!(stats[2].kind, InterfaceKind::Http);
+        assert_eq!(stats[3].kind, InterfaceKind::Http);
+        assert!(stats.iter().all(|iface| iface.num_accepted == 0));
+
+        // connect to the p2p interface which is not the primary one
+        let primary_addr = p2p.interfaces.get(&p2p.p2p_network_handle).unwrap().addr;
+        let extra_addr = stats
+            .iter()
+            .find(|iface| iface.kind == InterfaceKind::P2P && iface.addr != primary_addr)
+            .unwrap()
+            .addr;
+        let _sock = std::net::TcpStream::connect(extra_addr).unwrap();
+
+        for _ in 0..100 {
+            let poll_states = p2p.network.as_mut().unwrap().poll(100).unwrap();
+            let (mut p2p_poll_state, _) = p2p.merge_interface_poll_states(poll_states);
+            p2p.process_new_sockets(&mut p2p_poll_state);
+            if !p2p.peers.is_empty() {
+                break;
+            }
+        }
+
+        let stats = p2p.get_interface_stats();
+        for iface in stats.iter() {
+            if iface.addr == extra_addr {
+                assert_eq!(iface.num_accepted, 1);
+                assert_eq!(iface.num_open, 1);
+            } else {
+                assert_eq!(iface.num_accepted, 0);
+                assert_eq!(iface.num_open, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_handshake_data_for_address_family() {
+        let mut p2p = make_test_p2p_network(&[]);
+        let ipv4_peer = PeerAddress::from_ipv4(1, 2, 3, 4);
+        let ipv6_peer = PeerAddress::from_socketaddr(&"[2001:db8::2]:20444".parse().unwrap());
+        let ipv6_addr = PeerAddress::from_socketaddr(&"[2001:db8::1]:20444".parse().unwrap());
+
+        // no IPv6 address to advertise, so advertise the primary address to everyone
+        let handshake = p2p.handshake_data_for(&ipv6_peer);
+        assert_eq!(handshake.addrbytes, p2p.local_peer.addrbytes);
+        assert_eq!(handshake.port, p2p.local_peer.port);
+
+        p2p.connection_opts.extra_p2p_addresses = vec![(ipv6_addr.clone(), 20445)];
+        let handshake = p2p.handshake_data_for(&ipv6_peer);
+        assert_eq!(handshake.addrbytes, ipv6_addr);
+        assert_eq!(handshake.port, 20445);
+
+        let handshake = p2p.handshake_data_for(&ipv4_peer);
+        assert_eq!(handshake.addrbytes, p2p.local_peer.addrbytes);
+        assert_eq!(handshake.port, p2p.local_peer.port);
+    }
+
     // tests relay_signed_message()
     #[test]
     #[ignore]
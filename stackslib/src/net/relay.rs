@@ -38,12 +38,13 @@ use crate::chainstate::burn::db::sortdb::{
 };
 use crate::chainstate::burn::{BlockSnapshot, ConsensusHash};
 use crate::chainstate::coordinator::comm::CoordinatorChannels;
-use crate::chainstate::coordinator::{
-    BlockEventDispatcher, Error as CoordinatorError, OnChainRewardSetProvider,
-};
+use crate::chainstate::coordinator::{BlockEventDispatcher, Error as CoordinatorError};
+use crate::chainstate::nakamoto::checkpoints::ChainCheckpointHints;
 use crate::chainstate::nakamoto::coordinator::load_nakamoto_reward_set;
+use crate::chainstate::nakamoto::sigcheck::{NakamotoBlockSigChecks, NakamotoSigCheckJob};
 use crate::chainstate::nakamoto::staging_blocks::NakamotoBlockObtainMethod;
 use crate::chainstate::nakamoto::{NakamotoBlock, NakamotoBlockHeader, NakamotoChainState};
+use crate::chainstate::stacks::boot::CHECKPOINTS_NAME;
 use crate::chainstate::stacks::db::unconfirmed::ProcessedUnconfirmedState;
 use crate::chainstate::stacks::db::{StacksChainState, StacksEpochReceipt, StacksHeaderInfo};
 use crate::chainstate::stacks::events::StacksTransactionReceipt;
@@ -62,6 +63,7 @@ use crate::net::stackerdb::{
     StackerDBConfig, StackerDBEventDispatcher, StackerDBSyncResult, StackerDBs,
 };
 use crate::net::{Error as net_error, *};
+use crate::util_lib::boot::boot_code_id;
 
 pub type BlocksAvailableMap = HashMap<BurnchainHeaderHash, (u64, ConsensusHash)>;
 
@@ -126,6 +128,10 @@ pub struct Relayer {
     /// Maps to tenure ID and timestamp, so we can garbage-collect.
     /// Timestamp is in milliseconds
     recently_sent_nakamoto_blocks: HashMap<StacksBlockId, (ConsensusHash, u128)>,
+    /// Checkpoints from the `.checkpoints` StackerDB, tallied while in initial block download
+    checkpoint_hints: Option<ChainCheckpointHints>,
+    /// The Stacks tip last checked against `checkpoint_hints`
+    checkpoints_checked_tip: Option<StacksBlockId>,
 }
 
 #[derive(Debug)]
@@ -141,6 +147,10 @@ pub struct RelayerStats {
     pub(crate) recent_messages: HashMap<NeighborKey, VecDeque<(u64, Sha512Trunc256Sum)>>,
     pub(crate) recent_updates: BTreeMap<u64, NeighborKey>,
 
+    /// Nakamoto blocks each neighbor has pushed to us or that we have pushed to it, keyed by
+    /// signer signature hash.  Maps to the time we last saw or sent the block, in milliseconds.
+    pub(crate) recent_nakamoto_blocks: HashMap<NeighborKey, HashMap<Sha512Trunc256Sum, u128>>,
+
     next_priority: u64,
 }
 
@@ -269,6 +279,7 @@ impl RelayerStats {
             relay_updates: BTreeMap::new(),
             recent_messages: HashMap::new(),
             recent_updates: BTreeMap::new(),
+            recent_nakamoto_blocks: HashMap::new(),
             next_priority: 0,
         }
     }
@@ -362,10 +373,56 @@ impl RelayerStats {
         }
     }
 
+    /// Record that a neighbor has these Nakamoto blocks, either because it pushed them to us or
+    /// because we pushed them to it.
+    pub fn add_nakamoto_blocks_seen<'a>(
+        &mut self,
+        nk: &NeighborKey,
+        blocks: impl IntoIterator<Item = &'a NakamotoBlock>,
+        now_ms: u128,
+    ) {
+        let seen = self.recent_nakamoto_blocks.entry(nk.clone()).or_default();
+        for block in blocks {
+            seen.insert(block.header.signer_signature_hash(), now_ms);
+        }
+    }
+
+    /// Get the subset of `blocks` that `nk` has not pushed to us, and that we have not pushed to
+    /// it, within the last `window_ms` milliseconds.
+    pub fn filter_unseen_nakamoto_blocks(
+        &self,
+        nk: &NeighborKey,
+        blocks: &[NakamotoBlock],
+        window_ms: u128,
+        now_ms: u128,
+    ) -> Vec<NakamotoBlock> {
+        let Some(seen) = self.recent_nakamoto_blocks.get(nk) else {
+            return blocks.to_vec();
+        };
+        blocks
+            .iter()
+            .filter(|block| {
+                seen.get(&block.header.signer_signature_hash())
+                    .map(|ts| ts + window_ms < now_ms)
+                    .unwrap_or(true)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Forget Nakamoto blocks seen more than `window_ms` milliseconds ago
+    pub fn prune_nakamoto_blocks_seen(&mut self, window_ms: u128, now_ms: u128) {
+        self.recent_nakamoto_blocks.retain(|_nk, seen| {
+            seen.retain(|_sighash, ts| *ts + window_ms >= now_ms);
+            !seen.is_empty()
+        });
+    }
+
     /// Process a neighbor ban -- remove any state for this neighbor
     pub fn process_neighbor_ban(&mut self, nk: &NeighborKey) {
         let addr = NeighborAddress::from_neighbor_key((*nk).clone(), Hash160([0u8; 20]));
         self.recent_messages.remove(nk);
+        self.recent_nakamoto_blocks.remove(nk);
         self.relay_stats.remove(&addr);
 
         // old state in self.recent_updates and self.relay_updates will eventually be removed by
@@ -591,6 +648,8 @@ impl Relayer {
             connection_opts,
             stacker_dbs,
             recently_sent_nakamoto_blocks: HashMap::new(),
+            checkpoint_hints: None,
+            checkpoints_checked_tip: None,
         }
     }
 
@@ -640,17 +699,120 @@ impl Relayer {
         Ok(())
     }
 
+    /// Check the signatures of a batch of Nakamoto blocks on up to `num_workers` threads, ahead of
+    /// storing them with `process_new_nakamoto_block_ext()`.  The reward sets and VRF keys the
+    /// checks need are loaded here, on the calling thread; only the signature verification runs
+    /// on the workers.
+    ///
+    /// Blocks whose sortition or reward set can't be loaded get no (or partial) checks; they
+    /// will be checked in full when they are stored.
+    pub fn prevalidate_nakamoto_blocks(
+        burnchain: &Burnchain,
+        sortdb: &SortitionDB,
+        chainstate: &mut StacksChainState,
+        stacks_tip: &StacksBlockId,
+        blocks: &[&NakamotoBlock],
+        num_workers: usize,
+    ) -> HashMap<StacksBlockId, NakamotoBlockSigChecks> {
+        if blocks.is_empty() {
+            return HashMap::new();
+        }
+        let tip_sn = match SortitionDB::get_canonical_burn_chain_tip(sortdb.conn()) {
+            Ok(sn) => sn,
+            Err(e) => {
+                warn!("Failed to load canonical burn chain tip: {e:?}");
+                return HashMap::new();
+            }
+        };
+        let sort_handle = sortdb.index_handle(&tip_sn.sortition_id);
+
+        // load the reward set and VRF inputs for each block
+        let reward_set_provider = chainstate.reward_set_provider();
+        let mut reward_sets = HashMap::new();
+        let mut block_inputs = Vec::with_capacity(blocks.len());
+        for block in blocks.iter() {
+            let Ok(Some(sn)) = SortitionDB::get_block_snapshot_consensus(
+                sortdb.conn(),
+                &block.header.consensus_hash,
+            ) else {
+                continue;
+            };
+            let reward_cycle = burnchain
+                .block_height_to_reward_cycle(sn.block_height)
+                .expect("FATAL: sortition has no reward cycle");
+            if !reward_sets.contains_key(&reward_cycle) {
+                let reward_set = load_nakamoto_reward_set(
+                    reward_cycle,
+                    &tip_sn.sortition_id,
+                    burnchain,
+                    chainstate,
+                    stacks_tip,
+                    sortdb,
+                    &reward_set_provider,
+                )
+                .inspect_err(|e| {
+                    debug!("Failed to load reward set for cycle {reward_cycle}: {e:?}");
+                })
+                .ok()
+                .flatten()
+                .and_then(|(reward_info, _)| reward_info.known_selected_anchor_block_owned());
+                reward_sets.insert(reward_cycle, reward_set);
+            }
+
+            let vrf_inputs = if block.get_coinbase_tx().is_some() {
+                sort_handle
+                    .get_block_commit_by_txid(&sn.sortition_id, &sn.winning_block_txid)
+                    .ok()
+                    .flatten()
+                    .and_then(|commit| {
+                        sort_handle
+                            .get_leader_key_at(
+                                u64::from(commit.key_block_ptr),
+                                u32::from(commit.key_vtxindex),
+                            )
+                            .ok()
+                            .flatten()
+                    })
+                    .map(|leader_key| (leader_key.public_key, sn.sortition_hash))
+            } else {
+                None
+            };
+            block_inputs.push((*block, reward_cycle, vrf_inputs));
+        }
+
+        let jobs: Vec<_> = block_inputs
+            .into_iter()
+            .map(|(block, reward_cycle, vrf_inputs)| NakamotoSigCheckJob {
+                block,
+                reward_set: reward_sets
+                    .get(&reward_cycle)
+                    .and_then(|reward_set| reward_set.as_ref())
+                    .map(|reward_set| (reward_cycle, reward_set)),
+                vrf_inputs,
+            })
+            .collect();
+
+        debug!(
+            "Pre-validating signatures of {} Nakamoto block(s) on up to {num_workers} thread(s)",
+            jobs.len()
+        );
+        NakamotoBlockSigChecks::compute_batch(&jobs, num_workers)
+    }
+
     /// Given Nakamoto blocks pushed to us, verify that they correspond to expected block data.
+    /// Signer signatures which pre-validated in `sig_checks` are not checked again.
     pub fn validate_nakamoto_blocks_push(
         burnchain: &Burnchain,
         sortdb: &SortitionDB,
         chainstate: &mut StacksChainState,
         stacks_tip: &StacksBlockId,
         nakamoto_blocks_data: &NakamotoBlocksData,
+        sig_checks: &HashMap<StacksBlockId, NakamotoBlockSigChecks>,
     ) -> Result<(), net_error> {
         let conn = sortdb.index_conn();
         let mut loaded_reward_sets = HashMap::new();
         let tip_sn = SortitionDB::get_canonical_burn_chain_tip(sortdb.conn())?;
+        let reward_set_provider = chainstate.reward_set_provider();
 
         for nakamoto_block in nakamoto_blocks_data.blocks.iter() {
             // is this the right Stacks block for this sortition?
@@ -683,6 +845,13 @@ impl Relayer {
             let sn_rc = burnchain
                 .block_height_to_reward_cycle(sn.block_height)
                 .expect("FATAL: sortition has no reward cycle");
+            if sig_checks
+                .get(&nakamoto_block.block_id())
+                .and_then(|checks| checks.signer_weight_for(sn_rc))
+                .is_some()
+            {
+                continue;
+            }
             let reward_cycle_info = if let Some(rc_info) = loaded_reward_sets.get(&sn_rc) {
                 rc_info
             } else {
@@ -693,7 +862,7 @@ impl Relayer {
                     chainstate,
                     stacks_tip,
                     sortdb,
-                    &OnChainRewardSetProvider::new(),
+                    &reward_set_provider,
                 )
                 .map_err(|e| {
                     error!(
@@ -738,7 +907,10 @@ impl Relayer {
                 return Err(net_error::NoPoXRewardSet(sn_rc));
             };
 
-            if let Err(e) = nakamoto_block.header.verify_signer_signatures(reward_set) {
+            if let Err(e) = nakamoto_block
+                .header
+                .verify_signer_signatures_of_cycle(sn_rc, reward_set)
+            {
                 warn!(
                     "Signature verification failure for Nakamoto block";
                     "consensus_hash" => %nakamoto_block.header.consensus_hash,
@@ -900,6 +1072,7 @@ impl Relayer {
             coord_comms,
             obtained_method,
             false,
+            None,
         )
     }
 
@@ -915,6 +1088,9 @@ impl Relayer {
     /// * If the reward cycle info could not be determined
     /// * If there was an unrecognized signer
     /// * If the coordinator is closed, and `coord_comms` is Some(..)
+    ///
+    /// `sig_checks`, if given, holds this block's pre-validated signature checks (see
+    /// `prevalidate_nakamoto_blocks()`).
     pub fn process_new_nakamoto_block_ext(
         burnchain: &Burnchain,
         sortdb: &SortitionDB,
@@ -925,6 +1101,7 @@ impl Relayer {
         coord_comms: Option<&CoordinatorChannels>,
         obtained_method: NakamotoBlockObtainMethod,
         force_broadcast: bool,
+        sig_checks: Option<&NakamotoBlockSigChecks>,
     ) -> Result<BlockAcceptResponse, chainstate_error> {
         info!(
             "Handle incoming Nakamoto block {}/{} obtained via {}",
@@ -1029,6 +1206,7 @@ impl Relayer {
         let config = chainstate.config();
         let tip = block_sn.sortition_id;
 
+        let reward_set_provider = chainstate.reward_set_provider();
         let reward_info = match load_nakamoto_reward_set(
             burnchain
                 .block_height_to_reward_cycle(block_sn.block_height)
@@ -1038,7 +1216,7 @@ impl Relayer {
             chainstate,
             stacks_tip,
             sortdb,
-            &OnChainRewardSetProvider::new(),
+            &reward_set_provider,
         ) {
             Ok(Some((reward_info, ..))) => reward_info,
             Ok(None) => {
@@ -1067,6 +1245,7 @@ impl Relayer {
         let Some(reward_set) = reward_info.known_selected_anchor_block_owned() else {
             return Err(chainstate_error::NoRegisteredSigners(reward_cycle));
         };
+        let sig_checks = sig_checks.map(|checks| checks.for_reward_cycle(reward_cycle));
 
         let (headers_conn, staging_db_tx) = chainstate.headers_conn_and_staging_tx_begin()?;
         let accepted = NakamotoChainState::accept_block(
@@ -1077,6 +1256,7 @@ impl Relayer {
             headers_conn,
             &reward_set,
             obtained_method,
+            sig_checks.as_ref(),
         )?;
         staging_db_tx.commit()?;
 
@@ -1105,13 +1285,14 @@ impl Relayer {
         stacks_tip: &StacksBlockId,
         blocks: impl Iterator<Item = NakamotoBlock>,
         coord_comms: Option<&CoordinatorChannels>,
+        sig_checks: &HashMap<StacksBlockId, NakamotoBlockSigChecks>,
     ) -> Result<Vec<NakamotoBlock>, chainstate_error> {
         let mut accepted = vec![];
         let tip = SortitionDB::get_canonical_burn_chain_tip(sortdb.conn())?;
         let mut sort_handle = sortdb.index_handle(&tip.sortition_id);
         for block in blocks {
             let block_id = block.block_id();
-            let accept = match Self::process_new_nakamoto_block(
+            let accept = match Self::process_new_nakamoto_block_ext(
                 burnchain,
                 sortdb,
                 &mut sort_handle,
@@ -1120,6 +1301,8 @@ impl Relayer {
                 &block,
                 coord_comms,
                 NakamotoBlockObtainMethod::Downloaded,
+                false,
+                sig_checks.get(&block_id),
             ) {
                 Ok(x) => x,
                 Err(e) => {
@@ -1704,6 +1887,7 @@ impl Relayer {
         chainstate: &mut StacksChainState,
         coord_comms: Option<&CoordinatorChannels>,
         reject_blocks_pushed: bool,
+        sig_checks: &HashMap<StacksBlockId, NakamotoBlockSigChecks>,
     ) -> Result<(Vec<AcceptedNakamotoBlocks>, Vec<NeighborKey>), net_error> {
         let mut pushed_blocks = vec![];
         let mut bad_neighbors = vec![];
@@ -1722,6 +1906,7 @@ impl Relayer {
                     chainstate,
                     &network_result.stacks_tip,
                     nakamoto_blocks_data,
+                    sig_checks,
                 ) {
                     info!(
                         "Failed to validate Nakamoto blocks pushed from {:?}: {:?}",
@@ -1745,7 +1930,7 @@ impl Relayer {
                         block_id, neighbor_key
                     );
                     let mut sort_handle = sortdb.index_handle(&tip.sortition_id);
-                    match Self::process_new_nakamoto_block(
+                    match Self::process_new_nakamoto_block_ext(
                         burnchain,
                         sortdb,
                         &mut sort_handle,
@@ -1754,6 +1939,8 @@ impl Relayer {
                         &nakamoto_block,
                         coord_comms,
                         NakamotoBlockObtainMethod::Pushed,
+                        false,
+                        sig_checks.get(&block_id),
                     ) {
                         Ok(accept_response) => match accept_response {
                             BlockAcceptResponse::Accepted => {
@@ -2107,10 +2294,30 @@ impl Relayer {
         chainstate: &mut StacksChainState,
         coord_comms: Option<&CoordinatorChannels>,
     ) -> Result<(Vec<AcceptedNakamotoBlocks>, Vec<NeighborKey>), net_error> {
-        // process downloaded Nakamoto blocks.
-        // We treat them as singleton blocks fetched via zero relayers
         let nakamoto_blocks =
             std::mem::replace(&mut network_result.nakamoto_blocks, HashMap::new());
+
+        // check the signatures of every downloaded and pushed block up front, in parallel, so
+        // that storing them below is mostly DB work.
+        let sig_checks = {
+            let pushed_blocks = network_result
+                .pushed_nakamoto_blocks
+                .values()
+                .flatten()
+                .flat_map(|(_, blocks_data)| blocks_data.blocks.iter());
+            let blocks: Vec<_> = nakamoto_blocks.values().chain(pushed_blocks).collect();
+            Self::prevalidate_nakamoto_blocks(
+                burnchain,
+                sortdb,
+                chainstate,
+                &network_result.stacks_tip,
+                &blocks,
+                connection_opts.nakamoto_sigcheck_threads,
+            )
+        };
+
+        // process downloaded Nakamoto blocks.
+        // We treat them as singleton blocks fetched via zero relayers
         let mut accepted_nakamoto_blocks_and_relayers =
             match Self::process_downloaded_nakamoto_blocks(
                 burnchain,
@@ -2119,6 +2326,7 @@ impl Relayer {
                 &network_result.stacks_tip,
                 nakamoto_blocks.into_values(),
                 coord_comms,
+                &sig_checks,
             ) {
                 Ok(accepted) => vec![AcceptedNakamotoBlocks {
                     relayers: vec![],
@@ -2138,6 +2346,7 @@ impl Relayer {
             chainstate,
             coord_comms,
             connection_opts.reject_blocks_pushed,
+            &sig_checks,
         ) {
             Ok(x) => x,
             Err(e) => {
@@ -2562,6 +2771,76 @@ impl Relayer {
         Ok(())
     }
 
+    /// While in initial block download, check the canonical Stacks chain against the
+    /// checkpoints that a threshold of signers have written to `.checkpoints`.  A disagreement
+    /// means that we are syncing a history the signers never accepted.
+    /// The checkpoints are tallied again whenever new ones arrive, and the chain is checked
+    /// again whenever its tip changes.
+    fn check_chain_checkpoints(
+        &mut self,
+        chain_id: u32,
+        sortdb: &SortitionDB,
+        chainstate: &mut StacksChainState,
+        checkpoints_contract_id: &QualifiedContractIdentifier,
+        new_checkpoints: bool,
+    ) -> Result<(), Error> {
+        let Some(tip) = NakamotoChainState::get_canonical_block_header(chainstate.db(), sortdb)?
+        else {
+            return Ok(());
+        };
+        let tip_block_id = tip.index_block_hash();
+        if !new_checkpoints && self.checkpoints_checked_tip.as_ref() == Some(&tip_block_id) {
+            return Ok(());
+        }
+
+        if new_checkpoints || self.checkpoint_hints.is_none() {
+            let sort_tip = SortitionDB::get_canonical_burn_chain_tip(sortdb.conn())?;
+            let reward_cycle_info =
+                sortdb.get_preprocessed_reward_set_of(&sort_tip.sortition_id)?;
+            let Some(reward_set) = reward_cycle_info.known_selected_anchor_block() else {
+                return Ok(());
+            };
+            let signer_keys = NakamotoChainState::get_reward_set_signer_keys(
+                reward_cycle_info.reward_cycle,
+                reward_set,
+            )?;
+            let slot_ids: Vec<u32> = (0..signer_keys.num_signers())
+                .filter_map(|slot_id| u32::try_from(slot_id).ok())
+                .collect();
+            let chunks = self
+                .stacker_dbs
+                .get_latest_chunks(checkpoints_contract_id, &slot_ids)?;
+            let hints = ChainCheckpointHints::tally(
+                chain_id,
+                reward_cycle_info.reward_cycle,
+                &signer_keys,
+                &chunks,
+            );
+            debug!(
+                "Tallied signed checkpoints";
+                "reward_cycle" => hints.reward_cycle,
+                "num_checkpoints" => hints.checkpoints.len(),
+            );
+            self.checkpoint_hints = Some(hints);
+        }
+
+        let Some(hints) = self.checkpoint_hints.as_ref() else {
+            return Ok(());
+        };
+        for conflict in hints.find_conflicts(chainstate, &tip_block_id, tip.stacks_block_height)? {
+            error!(
+                "Canonical Stacks chain disagrees with a signed checkpoint. This node may be syncing a history that the signers did not accept.";
+                "block_height" => conflict.checkpoint.block_height,
+                "checkpoint_block_id" => %conflict.checkpoint.block_id,
+                "local_block_id" => %conflict.local_block_id,
+                "signing_weight" => conflict.checkpoint.weight,
+                "reward_cycle" => hints.reward_cycle,
+            );
+        }
+        self.checkpoints_checked_tip = Some(tip_block_id);
+        Ok(())
+    }
+
     /// Process StackerDB chunks pushed to us.
     /// extract all StackerDBPushChunk messages from `unhandled_messages`
     pub fn process_pushed_stacker_db_chunks(
@@ -2731,6 +3010,31 @@ impl Relayer {
         Ok(ret)
     }
 
+    /// Split a list of Nakamoto blocks into batches that each fit into a single
+    /// `NakamotoBlocksData` message -- at most `NAKAMOTO_BLOCKS_PUSHED_MAX` blocks, and less than
+    /// `MAX_PAYLOAD_LEN` bytes.  Block order is preserved.
+    pub(crate) fn batch_nakamoto_blocks(blocks: Vec<NakamotoBlock>) -> Vec<Vec<NakamotoBlock>> {
+        let mut batches: Vec<Vec<NakamotoBlock>> = vec![];
+        let mut batch_size = 0;
+        for block in blocks.into_iter() {
+            let block_len = block.serialize_to_vec().len();
+            let fits = batches.last().is_some_and(|batch| {
+                batch.len() < NAKAMOTO_BLOCKS_PUSHED_MAX as usize
+                    && batch_size + block_len < MAX_PAYLOAD_LEN as usize
+            });
+            if fits {
+                batch_size += block_len;
+                if let Some(batch) = batches.last_mut() {
+                    batch.push(block);
+                }
+            } else {
+                batch_size = block_len;
+                batches.push(vec![block]);
+            }
+        }
+        batches
+    }
+
     #[cfg_attr(test, mutants::skip)]
     /// Relay Nakamoto blocks.
     /// By default, only sends them if we don't have them yet.
@@ -2765,6 +3069,9 @@ impl Relayer {
             .map(|sn| sn.consensus_hash)
             .collect();
 
+        // Coalesce the accepted blocks into one list per distinct set of relayers, so that
+        // blocks which arrived by the same path go out in as few messages as possible.
+        let mut relay_batches: Vec<(Vec<RelayData>, Vec<NakamotoBlock>)> = vec![];
         for blocks_and_relayers in accepted_blocks.into_iter() {
             let AcceptedNakamotoBlocks { relayers, blocks } = blocks_and_relayers;
             if blocks.is_empty() {
@@ -2825,11 +3132,25 @@ impl Relayer {
                 );
             }
 
-            let msg = StacksMessageType::NakamotoBlocks(NakamotoBlocksData {
-                blocks: relay_blocks,
-            });
-            if let Err(e) = self.p2p.broadcast_message(relayers, msg) {
-                warn!("Failed to broadcast Nakamoto blocks: {:?}", &e);
+            if let Some((_, batch)) = relay_batches.iter_mut().find(|(r, _)| *r == relayers) {
+                batch.extend(relay_blocks);
+            } else {
+                relay_batches.push((relayers, relay_blocks));
+            }
+        }
+
+        for (relayers, relay_blocks) in relay_batches.into_iter() {
+            for batch in Self::batch_nakamoto_blocks(relay_blocks).into_iter() {
+                debug!(
+                    "{:?}: Broadcast batch of {} Nakamoto blocks from {:?}",
+                    _local_peer,
+                    batch.len(),
+                    &relayers
+                );
+                let msg = StacksMessageType::NakamotoBlocks(NakamotoBlocksData { blocks: batch });
+                if let Err(e) = self.p2p.broadcast_message(relayers.clone(), msg) {
+                    warn!("Failed to broadcast Nakamoto blocks: {:?}", &e);
+                }
             }
         }
 
@@ -3005,6 +3326,14 @@ impl Relayer {
             Default::default()
         };
 
+        let checkpoints_contract_id = boot_code_id(CHECKPOINTS_NAME, chainstate.mainnet);
+        let new_checkpoints = network_result.stacker_db_sync_results.iter().any(|result| {
+            result.contract_id == checkpoints_contract_id && !result.chunks_to_store.is_empty()
+        }) || network_result
+            .pushed_stackerdb_chunks
+            .iter()
+            .any(|chunk| chunk.contract_id == checkpoints_contract_id);
+
         // push events for HTTP-uploaded stacker DB chunks
         self.process_uploaded_stackerdb_chunks(
             &network_result.rc_consensus_hash,
@@ -3028,6 +3357,22 @@ impl Relayer {
             event_observer.map(|obs| obs.as_stackerdb_event_dispatcher()),
         )?;
 
+        if ibd
+            && network_result
+                .stacker_db_configs
+                .contains_key(&checkpoints_contract_id)
+        {
+            if let Err(e) = self.check_chain_checkpoints(
+                local_peer.network_id,
+                sortdb,
+                chainstate,
+                &checkpoints_contract_id,
+                new_checkpoints,
+            ) {
+                debug!("Failed to check the Stacks chain against signed checkpoints: {e:?}");
+            }
+        }
+
         update_stacks_tip_height(
             i64::try_from(network_result.stacks_tip_height).unwrap_or(i64::MAX),
         );
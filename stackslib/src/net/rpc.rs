@@ -67,9 +67,12 @@ use crate::cost_estimates::{CostEstimator, FeeEstimator};
 use crate::net::atlas::{AtlasDB, Attachment, MAX_ATTACHMENT_INV_PAGES_PER_REQUEST};
 use crate::net::connection::{ConnectionHttp, ConnectionOptions, ReplyHandleHttp};
 use crate::net::db::PeerDB;
-use crate::net::http::{HttpRequestContents, HttpResponseContents};
+use crate::net::http::{
+    HttpRequestContents, HttpResponseContents, HttpServiceUnavailable, HttpTooManyRequests,
+};
 use crate::net::httpcore::{
-    StacksHttp, StacksHttpMessage, StacksHttpRequest, StacksHttpResponse, HTTP_REQUEST_ID_RESERVED,
+    EndpointClass, HttpRateLimiter, StacksHttp, StacksHttpMessage, StacksHttpRequest,
+    StacksHttpResponse, HTTP_REQUEST_ID_RESERVED,
 };
 use crate::net::p2p::{PeerMap, PeerNetwork};
 use crate::net::relay::Relayer;
@@ -115,6 +118,9 @@ pub struct ConversationHttp {
     pending_request: Option<ReplyHandleHttp>,
     /// outstanding response
     pending_response: Option<StacksHttpResponse>,
+    /// inbound request held by a long-polling endpoint until its response is ready.  Requests
+    /// received after it wait in the inbox, so replies go out in order.
+    deferred_request: Option<StacksHttpRequest>,
     /// how much data to buffer (i.e. the socket's send buffer size)
     socket_send_buffer_size: u32,
 }
@@ -164,6 +170,7 @@ impl ConversationHttp {
             canonical_stacks_tip_height: None,
             pending_request: None,
             pending_response: None,
+            deferred_request: None,
             keep_alive: true,
             total_request_count: 0,
             total_reply_count: 0,
@@ -189,6 +196,11 @@ impl ConversationHttp {
         &self.peer_addr
     }
 
+    /// Is an inbound request being held by a long-polling endpoint?
+    pub fn has_deferred_request(&self) -> bool {
+        self.deferred_request.is_some()
+    }
+
     /// Is a request in-progress?
     pub fn is_request_inflight(&self) -> bool {
         self.pending_request.is_some() || self.pending_response.is_some()
@@ -286,6 +298,88 @@ impl ConversationHttp {
         Ok(relay_msg_opt)
     }
 
+    /// Charge a request against its source's budget for the request's endpoint class.
+    /// Returns Ok(Some(req)) if the request may be handled, or Ok(None) if it was rejected, in
+    /// which case a HTTP 429 reply has been queued.
+    pub(crate) fn check_rate_limit(
+        &mut self,
+        mut req: StacksHttpRequest,
+        rate_limiter: &mut HttpRateLimiter,
+    ) -> Result<Option<StacksHttpRequest>, net_error> {
+        if !rate_limiter.is_enabled() {
+            return Ok(Some(req));
+        }
+        let Some(class) = self.connection.protocol.endpoint_class(&mut req) else {
+            return Ok(Some(req));
+        };
+        let Err(retry_after) = rate_limiter.check(&self.peer_addr.ip(), class) else {
+            return Ok(Some(req));
+        };
+        monitoring::increment_rpc_rate_limited_counter(class.as_str());
+        info!("Rate-limited StacksHTTPRequest";
+              "verb" => %req.verb(),
+              "path" => %req.request_path(),
+              "class" => %class,
+              "retry_after" => retry_after,
+              "conn_id" => self.conn_id,
+              "peer_addr" => &self.peer_addr);
+        self.reply_rate_limited(req, class, retry_after)?;
+        Ok(None)
+    }
+
+    /// Reply to a request with HTTP 429, because its source has used up its request budget for
+    /// this endpoint class.  The connection is kept alive if the client asked for it.
+    fn reply_rate_limited(
+        &mut self,
+        req: StacksHttpRequest,
+        class: EndpointClass,
+        retry_after: u64,
+    ) -> Result<(), net_error> {
+        let response = StacksHttpResponse::new_error(
+            req.preamble(),
+            &HttpTooManyRequests::new(format!(
+                "Too many {class} requests; retry after {retry_after} seconds"
+            )),
+        );
+        self.reply_retry_after(req, response, retry_after)
+    }
+
+    /// Reply to a request for a long-polling endpoint with HTTP 503, because the node is already
+    /// holding as many requests as it allows.  The connection is kept alive if the client asked
+    /// for it.
+    fn reply_too_many_held(&mut self, req: StacksHttpRequest) -> Result<(), net_error> {
+        let response = StacksHttpResponse::new_error(
+            req.preamble(),
+            &HttpServiceUnavailable::new(
+                "Too many long-polling requests are waiting; retry after 1 second".to_string(),
+            ),
+        );
+        self.reply_retry_after(req, response, 1)
+    }
+
+    /// Send an error response to a request that was not handled, telling the client how many
+    /// seconds to wait before retrying
+    fn reply_retry_after(
+        &mut self,
+        req: StacksHttpRequest,
+        response: StacksHttpResponse,
+        retry_after: u64,
+    ) -> Result<(), net_error> {
+        let keep_alive = req.preamble().keep_alive;
+        let (mut preamble, body_contents) = response.try_into_contents()?;
+        preamble.add_header("Retry-After".to_string(), retry_after.to_string());
+        self.connection
+            .protocol
+            .apply_cors_policy(req.preamble(), &mut preamble);
+        preamble.content_length = body_contents.content_length();
+
+        let mut reply = self.connection.make_relay_handle(self.conn_id)?;
+        preamble.consensus_serialize(&mut reply)?;
+        self.reply_streams
+            .push_back((reply, body_contents, keep_alive));
+        Ok(())
+    }
+
     /// Make progress on outbound requests.
     fn send_outbound_responses(&mut self) -> Result<(), net_error> {
         // send out streamed responses in the order they were requested
@@ -479,6 +573,7 @@ impl ConversationHttp {
             self.reply_streams.len()
         );
         self.pending_response.is_none()
+            && self.deferred_request.is_none()
             && self.connection.inbox_len() == 0
             && self.connection.outbox_len() == 0
             && self.reply_streams.is_empty()
@@ -517,15 +612,55 @@ impl ConversationHttp {
         self.connection_time
     }
 
+    /// Handle an external HTTP request, recording metrics and logging how long it took.
+    fn handle_instrumented_request(
+        &mut self,
+        req: StacksHttpRequest,
+        node: &mut StacksNodeState,
+    ) -> Result<Option<StacksMessageType>, net_error> {
+        let latency = req.duration_ms();
+        let start_time = Instant::now();
+        let verb = req.verb().to_string();
+        let request_path = req.request_path().to_string();
+        let msg_opt = monitoring::instrument_http_request_handler(self, req, |conv_http, req| {
+            conv_http.handle_request(req, node)
+        })?;
+
+        info!("Handled StacksHTTPRequest";
+              "verb" => %verb,
+              "path" => %request_path,
+              "processing_time_ms" => start_time.elapsed().as_millis(),
+              "latency_ms" => latency,
+              "conn_id" => self.conn_id,
+              "peer_addr" => &self.peer_addr,
+              "p2p_msg" => ?msg_opt);
+
+        Ok(msg_opt)
+    }
+
     /// Make progress on in-flight requests and replies.
     /// Returns the list of messages we'll need to forward to the peer network
     pub fn chat(
         &mut self,
         node: &mut StacksNodeState,
+        rate_limiter: &mut HttpRateLimiter,
     ) -> Result<Vec<StacksMessageType>, net_error> {
+        let mut ret = vec![];
+
+        // answer the held request once its response is ready.  Until then, leave later requests
+        // in the inbox.
+        if let Some(req) = self.deferred_request.take() {
+            if self.connection.protocol.should_defer_request(&req, node) {
+                self.deferred_request = Some(req);
+                return Ok(ret);
+            }
+            if let Some(msg) = self.handle_instrumented_request(req, node)? {
+                ret.push(msg);
+            }
+        }
+
         // handle in-bound HTTP request(s)
         let num_inbound = self.connection.inbox_len();
-        let mut ret = vec![];
         test_debug!("{:?}: {} HTTP requests pending", &self, num_inbound);
 
         for _i in 0..num_inbound {
@@ -538,26 +673,23 @@ impl ConversationHttp {
                     // new request that we can handle
                     self.total_request_count += 1;
                     self.last_request_timestamp = get_epoch_time_secs();
-                    let latency = req.duration_ms();
-                    let start_time = Instant::now();
-                    let verb = req.verb().to_string();
-                    let request_path = req.request_path().to_string();
-                    let msg_opt = monitoring::instrument_http_request_handler(
-                        self,
-                        req,
-                        |conv_http, req| conv_http.handle_request(req, node),
-                    )?;
-
-                    info!("Handled StacksHTTPRequest";
-                          "verb" => %verb,
-                          "path" => %request_path,
-                          "processing_time_ms" => start_time.elapsed().as_millis(),
-                          "latency_ms" => latency,
-                          "conn_id" => self.conn_id,
-                          "peer_addr" => &self.peer_addr,
-                          "p2p_msg" => ?msg_opt);
-
-                    if let Some(msg) = msg_opt {
+                    let Some(req) = self.check_rate_limit(req, rate_limiter)? else {
+                        continue;
+                    };
+                    if self.connection.protocol.should_defer_request(&req, node) {
+                        if !rate_limiter.try_hold_request() {
+                            info!("Too many held StacksHTTPRequests";
+                                  "path" => %req.request_path(),
+                                  "conn_id" => self.conn_id,
+                                  "peer_addr" => &self.peer_addr);
+                            self.reply_too_many_held(req)?;
+                            continue;
+                        }
+                        test_debug!("{:?}: Hold request {}", &self, req.request_path());
+                        self.deferred_request = Some(req);
+                        break;
+                    }
+                    if let Some(msg) = self.handle_instrumented_request(req, node)? {
                         ret.push(msg);
                     }
                 }
@@ -15,6 +15,7 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::{HashMap, VecDeque};
+use std::fmt;
 use std::io::{Error as io_error, ErrorKind, Read, Write};
 use std::sync::mpsc::{sync_channel, Receiver, RecvError, SendError, SyncSender, TryRecvError};
 
@@ -35,6 +36,7 @@ use crate::net::httpcore::*;
 use crate::net::p2p::{PeerMap, PeerNetwork};
 use crate::net::poll::*;
 use crate::net::rpc::*;
+use crate::net::tls::MaybeTlsStream;
 use crate::net::{Error as net_error, *};
 
 #[derive(Debug)]
@@ -62,6 +64,12 @@ pub struct HttpPeer {
 
     /// connection options
     pub connection_opts: ConnectionOptions,
+
+    /// per-source request budgets, shared by all conversations
+    pub rate_limiter: HttpRateLimiter,
+
+    /// TLS sessions for inbound conversations, if the server has a certificate
+    pub tls_sessions: HashMap<usize, rustls::Connection>,
 }
 
 impl HttpPeer {
@@ -78,6 +86,8 @@ impl HttpPeer {
             http_server_handle: server_handle,
             http_server_addr: server_addr,
 
+            rate_limiter: HttpRateLimiter::new(conn_opts.rpc_rate_limits.clone()),
+            tls_sessions: HashMap::new(),
             connection_opts: conn_opts,
         }
     }
@@ -222,8 +232,12 @@ impl HttpPeer {
         outbound_url: Option<UrlString>,
         initial_request: Option<StacksHttpRequest>,
     ) -> Result<(), net_error> {
-        let send_buffer_size = node_state
-            .with_node_state(|network, _, _, _, _| network.connection_opts.socket_send_buffer_size);
+        // the admin tokens can be reloaded while the node runs, so take the network's current ones
+        let send_buffer_size = node_state.with_node_state(|network, _, _, _, _| {
+            self.connection_opts.auth_token = network.connection_opts.auth_token.clone();
+            self.connection_opts.admin_tokens = network.connection_opts.admin_tokens.clone();
+            network.connection_opts.socket_send_buffer_size
+        });
 
         let client_addr = match socket.peer_addr() {
             Ok(addr) => addr,
@@ -242,6 +256,18 @@ impl HttpPeer {
             }
         }
 
+        // inbound connections are encrypted if we have a certificate
+        let tls_session = match (&self.connection_opts.rpc_tls, &outbound_url) {
+            (Some(tls_config), None) => match tls_config.new_session() {
+                Ok(session) => Some(session),
+                Err(e) => {
+                    let _ = network_state.deregister(event_id, &socket);
+                    return Err(e);
+                }
+            },
+            _ => None,
+        };
+
         let peer_host = match outbound_url {
             Some(ref url_str) => {
                 PeerHost::try_from_url(url_str).unwrap_or(PeerHost::from_socketaddr(&client_addr))
@@ -282,6 +308,9 @@ impl HttpPeer {
 
         self.sockets.insert(event_id, socket);
         self.peers.insert(event_id, new_convo);
+        if let Some(session) = tls_session {
+            self.tls_sessions.insert(event_id, session);
+        }
         Ok(())
     }
 
@@ -291,9 +320,15 @@ impl HttpPeer {
         test_debug!("Remove HTTP event {}", event_id);
         self.peers.remove(&event_id);
 
+        let mut tls_session = self.tls_sessions.remove(&event_id);
         match self.sockets.remove(&event_id) {
             None => {}
-            Some(sock) => {
+            Some(mut sock) => {
+                if let Some(session) = tls_session.as_mut() {
+                    // best-effort; the socket is about to be closed regardless
+                    session.send_close_notify();
+                    let _ = MaybeTlsStream::new(&mut sock, Some(session)).flush_tls();
+                }
                 let _ = network_state.deregister(event_id, &sock);
             }
         }
@@ -317,6 +352,10 @@ impl HttpPeer {
         }
 
         for (event_id, convo) in self.peers.iter() {
+            if convo.has_deferred_request() {
+                // waiting on a long-polling endpoint, which bounds how long it holds requests
+                continue;
+            }
             let mut last_request_time = convo.get_last_request_time();
             if last_request_time == 0 {
                 // never got a request
@@ -345,8 +384,8 @@ impl HttpPeer {
 
     /// Saturate a conversation's socket -- either sends the whole request, or fills the socket
     /// buffer.
-    pub fn saturate_http_socket(
-        client_sock: &mut mio::net::TcpStream,
+    pub fn saturate_http_socket<S: Write + fmt::Debug>(
+        client_sock: &mut S,
         convo: &mut ConversationHttp,
     ) -> Result<(), net_error> {
         // saturate the socket
@@ -420,11 +459,12 @@ impl HttpPeer {
     /// Process network traffic on a HTTP conversation.
     /// Returns whether or not the convo is still alive, as well as any message(s) that need to be
     /// forwarded to the peer network.
-    fn process_http_conversation(
+    fn process_http_conversation<S: Read + Write + fmt::Debug>(
         node_state: &mut StacksNodeState,
         event_id: usize,
-        client_sock: &mut mio_net::TcpStream,
+        client_sock: &mut MaybeTlsStream<S>,
         convo: &mut ConversationHttp,
+        rate_limiter: &mut HttpRateLimiter,
     ) -> Result<(bool, Vec<StacksMessageType>), net_error> {
         // get incoming bytes and update the state of this conversation.
         let mut convo_dead = false;
@@ -480,7 +520,7 @@ impl HttpPeer {
         // react to inbound messages -- do we need to send something out, or fulfill requests
         // to other threads?  Try to chat even if the recv() failed, since we'll want to at
         // least drain the conversation inbox.
-        let msgs = match convo.chat(node_state) {
+        let msgs = match convo.chat(node_state, rate_limiter) {
             Ok(msgs) => msgs,
             Err(e) => {
                 debug!(
@@ -504,6 +544,15 @@ impl HttpPeer {
             }
         }
 
+        // send any TLS handshake messages that no reply pushed out
+        if let Err(e) = client_sock.flush_tls() {
+            debug!(
+                "Failed to flush TLS data to event {} (socket {:?}): {:?}",
+                event_id, &client_sock, &e
+            );
+            convo_dead = true;
+        }
+
         Ok((!convo_dead, msgs))
     }
 
@@ -555,7 +604,23 @@ impl HttpPeer {
     ) -> (Vec<StacksMessageType>, Vec<usize>) {
         let mut to_remove = vec![];
         let mut msgs = vec![];
-        for event_id in &poll_state.ready {
+
+        // conversations holding a request for a long-polling endpoint need to be checked on every
+        // pass, even if their sockets are quiet
+        let mut event_ids = poll_state.ready.clone();
+        let mut held_requests = 0;
+        for (event_id, convo) in self.peers.iter() {
+            if !convo.has_deferred_request() {
+                continue;
+            }
+            held_requests += 1;
+            if !poll_state.ready.contains(event_id) {
+                event_ids.push(*event_id);
+            }
+        }
+        self.rate_limiter.set_held_requests(held_requests);
+
+        for event_id in &event_ids {
             let Some(client_sock) = self.sockets.get_mut(event_id) else {
                 debug!("Rogue socket event {}", event_id);
                 to_remove.push(*event_id);
@@ -566,11 +631,14 @@ impl HttpPeer {
                 Some(ref mut convo) => {
                     // activity on a http socket
                     debug!("Process HTTP data from {:?}", convo);
+                    let mut client_sock =
+                        MaybeTlsStream::new(client_sock, self.tls_sessions.get_mut(event_id));
                     match HttpPeer::process_http_conversation(
                         node_state,
                         *event_id,
-                        client_sock,
+                        &mut client_sock,
                         convo,
+                        &mut self.rate_limiter,
                     ) {
                         Ok((alive, mut new_msgs)) => {
                             if !alive {
@@ -685,7 +753,7 @@ mod test {
     use crate::chainstate::stacks::db::blocks::test::*;
     use crate::chainstate::stacks::db::StacksChainState;
     use crate::chainstate::stacks::test::*;
-    use crate::chainstate::stacks::{Error as chain_error, StacksBlockHeader, *, *};
+    use crate::chainstate::stacks::{Error as chain_error, StacksBlockHeader, *};
     use crate::net::codec::*;
     use crate::net::http::*;
     use crate::net::httpcore::*;
@@ -131,8 +131,9 @@ use stacks_common::util::secp256k1::MessageSignature;
 
 use crate::chainstate::burn::db::sortdb::SortitionDB;
 use crate::chainstate::nakamoto::NakamotoChainState;
-use crate::chainstate::stacks::boot::MINERS_NAME;
+use crate::chainstate::stacks::boot::{CHECKPOINTS_NAME, MINERS_NAME};
 use crate::chainstate::stacks::db::StacksChainState;
+use crate::chainstate::stacks::Error as ChainstateError;
 use crate::net::connection::ConnectionOptions;
 use crate::net::neighbors::NeighborComms;
 use crate::net::p2p::PeerNetwork;
@@ -309,6 +310,28 @@ impl StackerDBs {
                         );
                         StackerDBConfig::noop()
                     })
+            } else if stackerdb_contract_id == boot_code_id(CHECKPOINTS_NAME, chainstate.mainnet) {
+                // .checkpoints contract -- one slot for each signer of the current reward set
+                sortdb
+                    .get_preprocessed_reward_set_of(&tip.sortition_id)
+                    .map_err(ChainstateError::from)
+                    .and_then(|reward_cycle_info| {
+                        let reward_set = reward_cycle_info.known_selected_anchor_block().ok_or(
+                            ChainstateError::NoRegisteredSigners(reward_cycle_info.reward_cycle),
+                        )?;
+                        NakamotoChainState::make_checkpoints_stackerdb_config(
+                            reward_set,
+                            chainstate.mainnet,
+                        )
+                    })
+                    .unwrap_or_else(|e| {
+                        debug!(
+                            "Failed to generate .checkpoints StackerDB config";
+                            "contract" => %stackerdb_contract_id,
+                            "err" => ?e,
+                        );
+                        StackerDBConfig::noop()
+                    })
             } else {
                 // attempt to load the config from the contract itself
                 StackerDBConfig::from_smart_contract(
@@ -478,6 +501,18 @@ pub trait StackerDBEventDispatcher {
     );
 }
 
+/// Durable queue of StackerDB chunks to replicate to other nodes.  A chunk written over RPC is
+/// added to it before the write is committed, so every acknowledged write gets replicated.
+pub trait StackerDBReplicationQueue {
+    /// Durably queue `chunk`, a write to `contract_id`.  Returns an error message if it could not
+    /// be queued in time, in which case the write is refused.
+    fn queue_stackerdb_chunk(
+        &self,
+        contract_id: &QualifiedContractIdentifier,
+        chunk: &StackerDBChunkData,
+    ) -> Result<(), String>;
+}
+
 impl PeerNetwork {
     /// Run all stacker DB sync state-machines.
     /// Return a list of sync results on success, to be incorporated into the NetworkResult.
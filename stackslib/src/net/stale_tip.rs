@@ -0,0 +1,289 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Detection of a Stacks tip that has stopped advancing (`connection_options.stale_tip_timeout_secs`).
+//!
+//! A node can get stuck behind the network without any error: a downloaded tenure can be lost
+//! before the relayer processes it, or a block can sit in the staging DB because its parent never
+//! arrived.  The block downloader then believes it has everything it needs, and the node silently
+//! stays on its old tip.
+//!
+//! Once per `STALE_TIP_CHECK_INTERVAL_SECS`, the Nakamoto p2p state machine checks whether the
+//! Stacks tip has gone `stale_tip_timeout_secs` without changing while neighbors' tenure
+//! inventories report tenures that start after the tip's tenure.  If so, it has the block
+//! downloader fetch the unprocessed tenures again (at most once per timeout), and records a
+//! `StaleTipDiagnostic` -- including any blocks above the tip that were stored but not processed
+//! -- which `/v3/health/ready` reports.
+
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::util::get_epoch_time_secs;
+
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::nakamoto::staging_blocks::UnprocessedStagingBlock;
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::net::inv::nakamoto::NakamotoTenureInv;
+use crate::net::p2p::PeerNetwork;
+use crate::net::Error as net_error;
+
+/// How often to check whether the Stacks tip is stale, in seconds
+pub const STALE_TIP_CHECK_INTERVAL_SECS: u64 = 10;
+/// Most unprocessed staging blocks to report in a `StaleTipDiagnostic`
+pub const MAX_REPORTED_UNPROCESSED_BLOCKS: u32 = 16;
+
+/// Why the node considers its Stacks tip stale.  Reported in `/v3/health/ready`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaleTipDiagnostic {
+    pub stacks_tip: StacksBlockId,
+    pub stacks_tip_height: u64,
+    /// When the Stacks tip last changed, in seconds since the Unix epoch
+    pub stalled_since: u64,
+    /// Burnchain height of the sortition that started the Stacks tip's tenure
+    pub tip_tenure_burn_height: u64,
+    /// Highest burnchain height at which a neighbor reports a tenure
+    pub highest_neighbor_tenure_burn_height: u64,
+    /// How many neighbors report a tenure that starts after the Stacks tip's tenure
+    pub neighbors_ahead: u64,
+    /// How many times the block downloader has been made to fetch the unprocessed tenures again
+    pub redownloads: u64,
+    /// Stored blocks above the Stacks tip that have not been processed, lowest first.  If there
+    /// are any, the node has the blocks but cannot process them, and downloading them again will
+    /// not help unless a block between them and the tip is missing.
+    pub unprocessed_staging_blocks: Vec<UnprocessedStagingBlock>,
+}
+
+/// Tracks how long the Stacks tip has gone without changing
+#[derive(Debug, Clone, Default)]
+pub struct StaleTipState {
+    /// The Stacks tip as of the last check, and when it was first seen
+    last_tip: Option<(StacksBlockId, u64)>,
+    /// When the tip was last checked
+    last_check: u64,
+    /// When the block downloader was last made to fetch the unprocessed tenures again
+    last_redownload: u64,
+    /// How many times that has happened since the tip last changed
+    redownloads: u64,
+    /// Set while the tip is stale
+    diagnostic: Option<StaleTipDiagnostic>,
+}
+
+impl StaleTipState {
+    /// Why the Stacks tip is stale, if it is
+    pub fn diagnostic(&self) -> Option<&StaleTipDiagnostic> {
+        self.diagnostic.as_ref()
+    }
+}
+
+/// Find the highest burnchain height in `(tip_tenure_burn_height, burn_tip_height]` at which
+/// `inv` reports a tenure, if any
+pub fn highest_tenure_after(
+    inv: &NakamotoTenureInv,
+    tip_tenure_burn_height: u64,
+    burn_tip_height: u64,
+) -> Option<u64> {
+    (tip_tenure_burn_height.saturating_add(1)..=burn_tip_height)
+        .rev()
+        .find(|height| inv.has_ith_tenure(*height))
+}
+
+impl PeerNetwork {
+    /// Check whether the Stacks tip has stopped advancing while neighbors report later tenures.
+    /// If so, make the block downloader fetch the unprocessed tenures again, and record why the
+    /// tip is stale for `/v3/health/ready`.  Does nothing if
+    /// `connection_opts.stale_tip_timeout_secs` is 0.
+    pub(crate) fn check_stale_tip(&mut self, sortdb: &SortitionDB, chainstate: &StacksChainState) {
+        let timeout = self.connection_opts.stale_tip_timeout_secs;
+        if timeout == 0 {
+            return;
+        }
+        let now = get_epoch_time_secs();
+        let next_check = self
+            .stale_tip
+            .last_check
+            .saturating_add(STALE_TIP_CHECK_INTERVAL_SECS);
+        if now < next_check {
+            return;
+        }
+        self.stale_tip.last_check = now;
+
+        let stacks_tip = self.stacks_tip.block_id();
+        let stalled_since = match self.stale_tip.last_tip.as_ref() {
+            Some((last_tip, since)) if *last_tip == stacks_tip => *since,
+            _ => {
+                self.reset_stale_tip(stacks_tip, now);
+                return;
+            }
+        };
+        if now < stalled_since.saturating_add(timeout) {
+            return;
+        }
+
+        match self.diagnose_stale_tip(sortdb, chainstate, stalled_since) {
+            Ok(Some(diagnostic)) => self.handle_stale_tip(diagnostic, now),
+            Ok(None) => {
+                // no neighbor is ahead of us, so the tip is not stale
+                self.stale_tip.diagnostic = None;
+            }
+            Err(e) => {
+                warn!(
+                    "{:?}: Failed to check whether the Stacks tip is stale: {e:?}",
+                    &self.local_peer
+                );
+            }
+        }
+    }
+
+    /// Start tracking a new Stacks tip
+    fn reset_stale_tip(&mut self, stacks_tip: StacksBlockId, now: u64) {
+        if self.stale_tip.diagnostic.take().is_some() {
+            info!(
+                "{:?}: Stacks tip is advancing again", &self.local_peer;
+                "stacks_tip" => %stacks_tip,
+                "redownloads" => self.stale_tip.redownloads
+            );
+        }
+        self.stale_tip.last_tip = Some((stacks_tip, now));
+        self.stale_tip.last_redownload = now;
+        self.stale_tip.redownloads = 0;
+    }
+
+    /// Compare the Stacks tip's tenure with our neighbors' tenure inventories.  Returns a
+    /// diagnostic if any neighbor reports a later tenure, and None if none do.
+    fn diagnose_stale_tip(
+        &self,
+        sortdb: &SortitionDB,
+        chainstate: &StacksChainState,
+        stalled_since: u64,
+    ) -> Result<Option<StaleTipDiagnostic>, net_error> {
+        let Some(inv_state) = self.inv_state_nakamoto.as_ref() else {
+            return Ok(None);
+        };
+        let Some(tip_tenure_sn) = SortitionDB::get_block_snapshot_consensus(
+            sortdb.conn(),
+            &self.stacks_tip.consensus_hash,
+        )?
+        else {
+            return Ok(None);
+        };
+
+        let mut neighbors_ahead = 0;
+        let mut highest_neighbor_tenure_burn_height = 0;
+        for inv in inv_state.inventories.values() {
+            let Some(height) = highest_tenure_after(
+                inv,
+                tip_tenure_sn.block_height,
+                self.burnchain_tip.block_height,
+            ) else {
+                continue;
+            };
+            neighbors_ahead += 1;
+            highest_neighbor_tenure_burn_height = highest_neighbor_tenure_burn_height.max(height);
+        }
+        if neighbors_ahead == 0 {
+            return Ok(None);
+        }
+
+        let unprocessed_staging_blocks = chainstate
+            .nakamoto_blocks_db()
+            .get_unprocessed_blocks_above(
+                self.stacks_tip.height,
+                MAX_REPORTED_UNPROCESSED_BLOCKS,
+            )?;
+
+        Ok(Some(StaleTipDiagnostic {
+            stacks_tip: self.stacks_tip.block_id(),
+            stacks_tip_height: self.stacks_tip.height,
+            stalled_since,
+            tip_tenure_burn_height: tip_tenure_sn.block_height,
+            highest_neighbor_tenure_burn_height,
+            neighbors_ahead,
+            redownloads: self.stale_tip.redownloads,
+            unprocessed_staging_blocks,
+        }))
+    }
+
+    /// The Stacks tip is stale.  Re-download the unprocessed tenures if we have not done so
+    /// within the timeout, and record the diagnostic.
+    fn handle_stale_tip(&mut self, mut diagnostic: StaleTipDiagnostic, now: u64) {
+        let timeout = self.connection_opts.stale_tip_timeout_secs;
+        if self.stale_tip.diagnostic.is_none()
+            || now >= self.stale_tip.last_redownload.saturating_add(timeout)
+        {
+            let num_tenures = self
+                .block_downloader_nakamoto
+                .as_mut()
+                .map(|downloader| downloader.redownload_unprocessed_tenures())
+                .unwrap_or(0);
+            self.stale_tip.last_redownload = now;
+            self.stale_tip.redownloads += 1;
+            diagnostic.redownloads = self.stale_tip.redownloads;
+
+            warn!(
+                "{:?}: Stacks tip has not advanced for {} seconds, but neighbors have later tenures; downloading the unprocessed tenures again",
+                &self.local_peer,
+                now.saturating_sub(diagnostic.stalled_since);
+                "stacks_tip" => %diagnostic.stacks_tip,
+                "stacks_tip_height" => diagnostic.stacks_tip_height,
+                "tip_tenure_burn_height" => diagnostic.tip_tenure_burn_height,
+                "highest_neighbor_tenure_burn_height" => diagnostic.highest_neighbor_tenure_burn_height,
+                "neighbors_ahead" => diagnostic.neighbors_ahead,
+                "tenures_to_fetch" => num_tenures,
+                "unprocessed_staging_blocks" => diagnostic.unprocessed_staging_blocks.len()
+            );
+            if let Some(block) = diagnostic.unprocessed_staging_blocks.first() {
+                warn!(
+                    "{:?}: Blocks above the Stacks tip are stored but not processed", &self.local_peer;
+                    "lowest_block_id" => %block.block_id,
+                    "lowest_block_height" => block.height,
+                    "parent_block_id" => %block.parent_block_id,
+                    "burn_attachable" => block.burn_attachable
+                );
+            }
+        }
+        self.stale_tip.diagnostic = Some(diagnostic);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use stacks_common::types::net::PeerAddress;
+    use stacks_common::util::hash::Hash160;
+
+    use super::*;
+    use crate::net::{NakamotoInvData, NeighborAddress};
+
+    #[test]
+    fn test_highest_tenure_after() {
+        let na = NeighborAddress {
+            addrbytes: PeerAddress([0x11; 16]),
+            port: 20444,
+            public_key_hash: Hash160([0x22; 20]),
+        };
+        // reward cycle 1 spans burnchain heights 200-299
+        let mut inv = NakamotoTenureInv::new(100, 100, 1, na);
+        let mut bits = [false; 100];
+        bits[10] = true;
+        bits[40] = true;
+        inv.merge_tenure_inv(NakamotoInvData::try_from(&bits).unwrap().tenures, 1);
+
+        assert_eq!(highest_tenure_after(&inv, 200, 299), Some(240));
+        assert_eq!(highest_tenure_after(&inv, 200, 239), Some(210));
+        assert_eq!(highest_tenure_after(&inv, 210, 239), None);
+        assert_eq!(highest_tenure_after(&inv, 240, 299), None);
+        // the neighbor does not know about burnchain blocks past its inventory
+        assert_eq!(highest_tenure_after(&inv, 300, 400), None);
+        // the burnchain tip is behind the Stacks tip's tenure
+        assert_eq!(highest_tenure_after(&inv, 250, 200), None);
+    }
+}
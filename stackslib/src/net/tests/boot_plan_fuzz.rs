@@ -0,0 +1,273 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Property-based generation of Nakamoto boot plans, for fork-choice fuzzing.
+//!
+//! A plan is first generated as a `BootTenureShape` list, which only records the structure of
+//! the plan (sortitions, empty sortitions, tenure-extends, and transaction counts).  Shapes
+//! shrink well, and are turned into a `Vec<NakamotoBootTenure>` with `make_boot_plan()` once
+//! the transactions' nonces are known.  Each generated plan is also run with or without
+//! malleablized blocks, so that peers have to pick among sibling blocks while converging.
+//!
+//! The fuzz test boots a full set of peers per case, so it is `#[ignore]`d.  Run it with
+//! `PROPTEST_CASES=<n> cargo test fuzz_boot_plan_fork_choice -- --ignored`.  Shrunk failures
+//! are recorded by proptest in `proptest-regressions/` and replayed on subsequent runs.
+
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use stacks_common::address::{AddressHashMode, C32_ADDRESS_VERSION_TESTNET_SINGLESIG};
+use stacks_common::types::chainstate::{
+    ConsensusHash, StacksAddress, StacksBlockId, StacksPrivateKey, StacksPublicKey,
+};
+
+use crate::chainstate::burn::db::sortdb::{SortitionDB, SortitionHandle};
+use crate::chainstate::stacks::{
+    StacksTransaction, StacksTransactionSigner, TokenTransferMemo, TransactionAnchorMode,
+    TransactionAuth, TransactionPayload, TransactionVersion,
+};
+use crate::clarity::vm::types::StacksAddressExtensions;
+use crate::net::test::TestPeer;
+use crate::net::tests::{NakamotoBootPlan, NakamotoBootStep, NakamotoBootTenure};
+
+/// Maximum number of tenures in a generated plan
+pub const MAX_TENURES: usize = 20;
+/// Maximum number of steps in a generated tenure
+pub const MAX_STEPS_PER_TENURE: usize = 6;
+/// Maximum number of transactions in a generated step
+pub const MAX_TXS_PER_STEP: usize = 3;
+/// Number of fuzz cases to run if `PROPTEST_CASES` is not set
+const DEFAULT_FUZZ_CASES: u32 = 4;
+
+/// Structure of a `NakamotoBootStep`, with the number of transactions it carries
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BootStepShape {
+    Block(usize),
+    TenureExtend(usize),
+}
+
+/// Structure of a `NakamotoBootTenure`
+#[derive(Debug, Clone, PartialEq)]
+pub enum BootTenureShape {
+    Sortition(Vec<BootStepShape>),
+    NoSortition(Vec<BootStepShape>),
+}
+
+fn boot_step_shape() -> impl Strategy<Value = BootStepShape> {
+    prop_oneof![
+        3 => (1..=MAX_TXS_PER_STEP).prop_map(BootStepShape::Block),
+        1 => (1..=MAX_TXS_PER_STEP).prop_map(BootStepShape::TenureExtend),
+    ]
+}
+
+/// A tenure's steps.  The first step is always a block, since a tenure-extend needs a block in
+/// the tenure to extend from.
+fn boot_steps_shape() -> impl Strategy<Value = Vec<BootStepShape>> {
+    (
+        1..=MAX_TXS_PER_STEP,
+        prop::collection::vec(boot_step_shape(), 0..MAX_STEPS_PER_TENURE),
+    )
+        .prop_map(|(num_txs, mut steps)| {
+            steps.insert(0, BootStepShape::Block(num_txs));
+            steps
+        })
+}
+
+fn boot_tenure_shape() -> impl Strategy<Value = BootTenureShape> {
+    prop_oneof![
+        3 => boot_steps_shape().prop_map(BootTenureShape::Sortition),
+        1 => boot_steps_shape().prop_map(BootTenureShape::NoSortition),
+    ]
+}
+
+/// Generate the shape of a boot plan with up to `max_tenures` tenures.  The first tenure always
+/// has a sortition, since an empty sortition extends the tenure before it.
+pub fn boot_plan_shape(max_tenures: usize) -> impl Strategy<Value = Vec<BootTenureShape>> {
+    (
+        boot_steps_shape(),
+        prop::collection::vec(boot_tenure_shape(), 0..max_tenures),
+    )
+        .prop_map(|(steps, mut tenures)| {
+            tenures.insert(0, BootTenureShape::Sortition(steps));
+            tenures
+        })
+}
+
+/// Instantiate a boot plan from its shape, filling in each step with transactions from
+/// `next_tx` in plan order.
+pub fn make_boot_plan<F>(shape: &[BootTenureShape], mut next_tx: F) -> Vec<NakamotoBootTenure>
+where
+    F: FnMut() -> StacksTransaction,
+{
+    let mut make_steps = |steps: &[BootStepShape]| -> Vec<NakamotoBootStep> {
+        steps
+            .iter()
+            .map(|step| match step {
+                BootStepShape::Block(num_txs) => {
+                    NakamotoBootStep::Block((0..*num_txs).map(|_| next_tx()).collect())
+                }
+                BootStepShape::TenureExtend(num_txs) => {
+                    NakamotoBootStep::TenureExtend((0..*num_txs).map(|_| next_tx()).collect())
+                }
+            })
+            .collect()
+    };
+    shape
+        .iter()
+        .map(|tenure| match tenure {
+            BootTenureShape::Sortition(steps) => NakamotoBootTenure::Sortition(make_steps(steps)),
+            BootTenureShape::NoSortition(steps) => {
+                NakamotoBootTenure::NoSortition(make_steps(steps))
+            }
+        })
+        .collect()
+}
+
+/// Make a signed 1-uSTX transfer from `private_key`
+pub fn make_stx_transfer(private_key: &StacksPrivateKey, nonce: u64) -> StacksTransaction {
+    let recipient_addr =
+        StacksAddress::from_string("ST2YM3J4KQK09V670TD6ZZ1XYNYCNGCWCVTASN5VM").unwrap();
+    let mut stx_transfer = StacksTransaction::new(
+        TransactionVersion::Testnet,
+        TransactionAuth::from_p2pkh(private_key).unwrap(),
+        TransactionPayload::TokenTransfer(
+            recipient_addr.to_account_principal(),
+            1,
+            TokenTransferMemo([0x00; 34]),
+        ),
+    );
+    stx_transfer.chain_id = 0x80000000;
+    stx_transfer.anchor_mode = TransactionAnchorMode::OnChainOnly;
+    stx_transfer.set_tx_fee(1);
+    stx_transfer.auth.set_origin_nonce(nonce);
+
+    let mut tx_signer = StacksTransactionSigner::new(&stx_transfer);
+    tx_signer.sign_origin(private_key).unwrap();
+    tx_signer.get_tx().unwrap()
+}
+
+/// The sortition tip and Nakamoto chain tip a peer considers canonical
+fn get_canonical_tips(peer: &TestPeer) -> (ConsensusHash, Option<StacksBlockId>) {
+    let sortdb = peer.sortdb.as_ref().unwrap();
+    let sort_tip = SortitionDB::get_canonical_burn_chain_tip(sortdb.conn()).unwrap();
+    let stacks_tip = sortdb
+        .index_handle_at_tip()
+        .get_nakamoto_tip_block_id()
+        .unwrap();
+    (sort_tip.consensus_hash, stacks_tip)
+}
+
+/// Boot a set of peers through the plan described by `shape`, and check that they all converge
+/// on the same sortition and Stacks tips.  `NakamotoBootPlan::boot_into_nakamoto_peers()`
+/// already panics if a peer fails to process the plan; proptest treats that as a failure too,
+/// and shrinks the plan accordingly.
+pub fn check_boot_plan_convergence(
+    test_name: &str,
+    shape: &[BootTenureShape],
+    malleablized_blocks: bool,
+) -> Result<(), TestCaseError> {
+    let private_key = StacksPrivateKey::from_seed(&[2]);
+    let addr = StacksAddress::from_public_keys(
+        C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+        &AddressHashMode::SerializeP2PKH,
+        1,
+        &vec![StacksPublicKey::from_private(&private_key)],
+    )
+    .unwrap();
+
+    let mut sender_nonce = 0;
+    let boot_plan = make_boot_plan(shape, || {
+        let tx = make_stx_transfer(&private_key, sender_nonce);
+        sender_nonce += 1;
+        tx
+    });
+
+    let plan = NakamotoBootPlan::new(test_name)
+        .with_private_key(private_key)
+        .with_pox_constants(10, 3)
+        .with_initial_balances(vec![(addr.into(), 1_000_000)])
+        .with_extra_peers(2)
+        .with_malleablized_blocks(malleablized_blocks);
+
+    let (peer, other_peers) = plan.boot_into_nakamoto_peers(boot_plan, None);
+
+    let (sort_tip, stacks_tip) = get_canonical_tips(&peer);
+    prop_assert!(stacks_tip.is_some());
+    for other_peer in other_peers.iter() {
+        let (other_sort_tip, other_stacks_tip) = get_canonical_tips(other_peer);
+        prop_assert_eq!(&other_sort_tip, &sort_tip);
+        prop_assert_eq!(&other_stacks_tip, &stacks_tip);
+    }
+    Ok(())
+}
+
+fn fuzz_cases() -> u32 {
+    std::env::var("PROPTEST_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(DEFAULT_FUZZ_CASES)
+}
+
+proptest! {
+    #[test]
+    fn test_generated_boot_plans_are_valid(shape in boot_plan_shape(MAX_TENURES)) {
+        let private_key = StacksPrivateKey::from_seed(&[2]);
+        let mut sender_nonce = 0;
+        let plan = make_boot_plan(&shape, || {
+            let tx = make_stx_transfer(&private_key, sender_nonce);
+            sender_nonce += 1;
+            tx
+        });
+
+        prop_assert_eq!(plan.len(), shape.len());
+        prop_assert!(matches!(plan[0], NakamotoBootTenure::Sortition(_)));
+
+        let mut expected_nonce = 0;
+        for tenure in plan.iter() {
+            let steps = match tenure {
+                NakamotoBootTenure::Sortition(steps) | NakamotoBootTenure::NoSortition(steps) => {
+                    steps
+                }
+            };
+            prop_assert!(matches!(steps.first(), Some(NakamotoBootStep::Block(_))));
+            for step in steps.iter() {
+                let txs = match step {
+                    NakamotoBootStep::Block(txs) | NakamotoBootStep::TenureExtend(txs) => txs,
+                };
+                prop_assert!(!txs.is_empty());
+                for tx in txs.iter() {
+                    // transactions are handed out in plan order
+                    prop_assert_eq!(tx.get_origin_nonce(), expected_nonce);
+                    expected_nonce += 1;
+                }
+            }
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: fuzz_cases(),
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    #[ignore]
+    fn fuzz_boot_plan_fork_choice(
+        shape in boot_plan_shape(MAX_TENURES),
+        malleablized_blocks in any::<bool>(),
+    ) {
+        check_boot_plan_convergence("fuzz_boot_plan_fork_choice", &shape, malleablized_blocks)?;
+    }
+}
@@ -38,6 +38,7 @@ use crate::net::download::BlockDownloader;
 use crate::net::inv::inv2x::*;
 use crate::net::relay::*;
 use crate::net::test::*;
+use crate::net::tests::faults::LinkFault;
 use crate::net::*;
 use crate::stacks_common::types::PublicKey;
 use crate::util_lib::strings::*;
@@ -579,6 +580,87 @@ pub fn test_get_blocks_and_microblocks_2_peers_download_plain() {
     })
 }
 
+#[test]
+#[ignore]
+pub fn test_get_blocks_and_microblocks_2_peers_download_partition_heal() {
+    with_timeout(600, || {
+        let heal_round = 20;
+        let mut round = 0;
+        run_get_blocks_and_microblocks(
+            function_name!(),
+            3270,
+            2,
+            |ref mut peer_configs| {
+                // build initial network topology
+                assert_eq!(peer_configs.len(), 2);
+
+                peer_configs[0].connection_opts.disable_block_advertisement = true;
+                peer_configs[1].connection_opts.disable_block_advertisement = true;
+
+                let peer_0 = peer_configs[0].to_neighbor();
+                let peer_1 = peer_configs[1].to_neighbor();
+                peer_configs[0].add_neighbor(&peer_1);
+                peer_configs[1].add_neighbor(&peer_0);
+
+                // start out partitioned
+                let port_0 = peer_configs[0].server_port;
+                let port_1 = peer_configs[1].server_port;
+                peer_configs[0]
+                    .link_faults
+                    .insert(port_1, LinkFault::partition());
+                peer_configs[1]
+                    .link_faults
+                    .insert(port_0, LinkFault::partition());
+            },
+            |num_blocks, ref mut peers| {
+                // build up block data to replicate
+                let mut block_data = vec![];
+                for _ in 0..num_blocks {
+                    let (mut burn_ops, stacks_block, microblocks) = peers[1].make_default_tenure();
+
+                    let (_, burn_header_hash, consensus_hash) =
+                        peers[1].next_burnchain_block(burn_ops.clone());
+                    peers[1].process_stacks_epoch_at_tip(&stacks_block, &microblocks);
+
+                    TestPeer::set_ops_burn_header_hash(&mut burn_ops, &burn_header_hash);
+
+                    peers[0].next_burnchain_block_raw(burn_ops);
+
+                    let sn = SortitionDB::get_canonical_burn_chain_tip(
+                        peers[1].sortdb.as_ref().unwrap().conn(),
+                    )
+                    .unwrap();
+                    block_data.push((
+                        sn.consensus_hash.clone(),
+                        Some(stacks_block),
+                        Some(microblocks),
+                    ));
+                }
+                block_data
+            },
+            |peers| {
+                round += 1;
+                if round < heal_round {
+                    // peer 0 can't get any blocks from peer 1 while they're partitioned
+                    let tip_height = SortitionDB::get_canonical_burn_chain_tip(
+                        peers[0].sortdb.as_ref().unwrap().conn(),
+                    )
+                    .unwrap()
+                    .block_height;
+                    let inv = get_blocks_inventory(&peers[0], 0, tip_height);
+                    assert!((0..inv.bitlen).all(|i| !inv.has_ith_block(i)));
+                } else if round == heal_round {
+                    assert!(peers[0].network.network_faults.num_dropped > 0);
+                    let (peers_0, peers_1) = peers.split_at_mut(1);
+                    peers_0[0].heal(&mut peers_1[0]);
+                }
+            },
+            |_| true,
+            |_| true,
+        );
+    })
+}
+
 fn make_contract_call_transaction(
     miner: &mut TestMiner,
     sortdb: &mut SortitionDB,
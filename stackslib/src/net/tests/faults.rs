@@ -0,0 +1,385 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Deterministic fault injection for p2p messages between `TestPeer`s.
+//!
+//! Each `PeerNetwork` built in test mode carries a `NetworkFaults`, which filters the messages its
+//! conversations receive before they are handled.  Faults are configured per remote peer (by its
+//! p2p port), so a test can partition, slow down, or scramble specific links.  Delays are measured
+//! in calls to `PeerNetwork::run()` rather than wall-clock time, and all randomness comes from a
+//! seeded RNG per link, so a given seed yields the same decisions for the same message sequence.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use rand::Rng;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use stacks_common::types::chainstate::BurnchainHeaderHash;
+
+use crate::burnchains::Burnchain;
+use crate::core::{StacksEpoch, StacksEpochExtension, PEER_VERSION_TESTNET};
+use crate::net::chat::ConversationP2P;
+use crate::net::connection::ConnectionOptions;
+use crate::net::poll::NetworkPollState;
+use crate::net::{PingData, StacksMessage, StacksMessageType};
+
+/// Faults to apply to messages received from a single remote peer
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LinkFault {
+    /// Percent chance (0-100) that each message is dropped
+    pub drop_pct: u8,
+    /// Number of network passes by which to delay each message
+    pub delay_passes: u64,
+    /// Up to this many additional network passes of random delay for each message.  Messages
+    /// that draw different delays are delivered out of order.
+    pub jitter_passes: u64,
+}
+
+impl LinkFault {
+    /// Drop everything
+    pub fn partition() -> Self {
+        Self {
+            drop_pct: 100,
+            ..Self::default()
+        }
+    }
+
+    /// Drop each message with the given probability
+    pub fn lossy(drop_pct: u8) -> Self {
+        Self {
+            drop_pct: drop_pct.min(100),
+            ..Self::default()
+        }
+    }
+
+    /// Delay each message by a fixed number of network passes
+    pub fn delay(passes: u64) -> Self {
+        Self {
+            delay_passes: passes,
+            ..Self::default()
+        }
+    }
+
+    /// Delay each message by a random number of network passes, up to `max_passes`
+    pub fn reorder(max_passes: u64) -> Self {
+        Self {
+            jitter_passes: max_passes,
+            ..Self::default()
+        }
+    }
+}
+
+/// A message that will be delivered on a later network pass
+#[derive(Debug, Clone)]
+struct HeldMessage {
+    release_pass: u64,
+    /// arrival order, to break ties between messages released on the same pass
+    seq: u64,
+    event_id: usize,
+    msg: StacksMessage,
+}
+
+/// Per-`PeerNetwork` fault injection state
+#[derive(Debug, Clone)]
+pub struct NetworkFaults {
+    seed: u64,
+    /// faults and RNGs, keyed by the remote peer's p2p port
+    links: HashMap<u16, (LinkFault, ChaCha20Rng)>,
+    /// number of network passes so far
+    pass: u64,
+    next_seq: u64,
+    held: Vec<HeldMessage>,
+    /// number of messages dropped so far
+    pub num_dropped: u64,
+    /// number of messages delayed so far
+    pub num_delayed: u64,
+}
+
+impl NetworkFaults {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            links: HashMap::new(),
+            pass: 0,
+            next_seq: 0,
+            held: vec![],
+            num_dropped: 0,
+            num_delayed: 0,
+        }
+    }
+
+    /// Make a new fault injector with the given faults, keyed by remote p2p port
+    pub fn from_links(seed: u64, links: &HashMap<u16, LinkFault>) -> Self {
+        let mut faults = Self::new(seed);
+        for (port, fault) in links.iter() {
+            faults.set_link_fault(*port, fault.clone());
+        }
+        faults
+    }
+
+    /// Apply `fault` to all messages subsequently received from the peer on `remote_port`.
+    /// The link's RNG is re-seeded, so a test that sets the same faults in the same order sees the
+    /// same decisions.
+    pub fn set_link_fault(&mut self, remote_port: u16, fault: LinkFault) {
+        let rng = ChaCha20Rng::seed_from_u64(self.seed ^ u64::from(remote_port));
+        self.links.insert(remote_port, (fault, rng));
+    }
+
+    /// Stop applying faults to messages from the peer on `remote_port`.  Messages already held
+    /// back are still delivered when due.
+    pub fn clear_link_fault(&mut self, remote_port: u16) {
+        self.links.remove(&remote_port);
+    }
+
+    /// Stop applying faults to all links
+    pub fn clear(&mut self) {
+        self.links.clear();
+    }
+
+    pub fn get_link_fault(&self, remote_port: u16) -> Option<&LinkFault> {
+        self.links.get(&remote_port).map(|(fault, _)| fault)
+    }
+
+    /// Number of messages held back, waiting to be delivered
+    pub fn num_held(&self) -> usize {
+        self.held.len()
+    }
+
+    /// Begin a new network pass.  Conversations with held messages that are now due are marked
+    /// ready, so they get processed even if no new data arrived on their sockets.
+    pub fn schedule(&mut self, mut poll_state: NetworkPollState) -> NetworkPollState {
+        self.pass += 1;
+        for held in self.held.iter() {
+            if held.release_pass <= self.pass && !poll_state.ready.contains(&held.event_id) {
+                poll_state.ready.push(held.event_id);
+            }
+        }
+        poll_state
+    }
+
+    /// Which peer sent this message?  Handshakes carry the sender's p2p port; otherwise, use the
+    /// port learned from the handshake, or the socket's port if there wasn't one.
+    fn sender_port(convo: &ConversationP2P, msg: &StacksMessage) -> u16 {
+        match &msg.payload {
+            StacksMessageType::Handshake(data) => data.port,
+            StacksMessageType::HandshakeAccept(data)
+            | StacksMessageType::StackerDBHandshakeAccept(data, _) => data.handshake.port,
+            _ if convo.handshake_port != 0 => convo.handshake_port,
+            _ => convo.peer_port,
+        }
+    }
+
+    /// Run the conversation's newly-received messages through this link's faults.  Dropped
+    /// messages are discarded, delayed messages are held back, and the rest -- along with any
+    /// held messages that are now due -- are put back into the inbox to be handled.
+    pub fn filter_inbox(&mut self, convo: &mut ConversationP2P) {
+        if self.links.is_empty() && self.held.is_empty() {
+            return;
+        }
+        let event_id = convo.conn_id;
+
+        let mut received = vec![];
+        while let Some(msg) = convo.connection.next_inbox_message() {
+            received.push(msg);
+        }
+
+        let mut delivered = vec![];
+        for msg in received.into_iter() {
+            let seq = self.next_seq;
+            self.next_seq += 1;
+
+            let Some((fault, rng)) = self.links.get_mut(&Self::sender_port(convo, &msg)) else {
+                delivered.push(msg);
+                continue;
+            };
+            if fault.drop_pct > 0 && rng.gen_range(0..100) < fault.drop_pct {
+                test_debug!(
+                    "{:?}: fault injection: drop {}",
+                    convo,
+                    msg.payload.get_message_description()
+                );
+                self.num_dropped += 1;
+                continue;
+            }
+            let mut delay = fault.delay_passes;
+            if fault.jitter_passes > 0 {
+                delay += rng.gen_range(0..=fault.jitter_passes);
+            }
+            if delay == 0 {
+                delivered.push(msg);
+                continue;
+            }
+            test_debug!(
+                "{:?}: fault injection: delay {} by {} passes",
+                convo,
+                msg.payload.get_message_description(),
+                delay
+            );
+            self.num_delayed += 1;
+            self.held.push(HeldMessage {
+                release_pass: self.pass + delay,
+                seq,
+                event_id,
+                msg,
+            });
+        }
+
+        // held messages arrived earlier, so they go first
+        let pass = self.pass;
+        let (mut due, held): (Vec<_>, Vec<_>) = self
+            .held
+            .drain(..)
+            .partition(|held| held.event_id == event_id && held.release_pass <= pass);
+        self.held = held;
+        due.sort_by_key(|held| (held.release_pass, held.seq));
+
+        for msg in due.into_iter().map(|held| held.msg).chain(delivered) {
+            convo.connection.push_inbox_message(msg);
+        }
+    }
+
+    /// Forget held messages for a conversation that has closed
+    pub fn forget_conversation(&mut self, event_id: usize) {
+        self.held.retain(|held| held.event_id != event_id);
+    }
+}
+
+fn make_convo(remote_port: u16, event_id: usize) -> ConversationP2P {
+    let burnchain = Burnchain::default_unittest(0, &BurnchainHeaderHash([0x00; 32]));
+    let remote_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), remote_port);
+    ConversationP2P::new(
+        0x80000000,
+        PEER_VERSION_TESTNET,
+        &burnchain,
+        &remote_addr,
+        &ConnectionOptions::default(),
+        true,
+        event_id,
+        StacksEpoch::unit_test_pre_2_05(0),
+    )
+}
+
+fn make_ping(nonce: u32) -> StacksMessage {
+    StacksMessage::new(
+        PEER_VERSION_TESTNET,
+        0x80000000,
+        0,
+        &BurnchainHeaderHash([0x00; 32]),
+        0,
+        &BurnchainHeaderHash([0x00; 32]),
+        StacksMessageType::Ping(PingData { nonce }),
+    )
+}
+
+/// Receive pings with nonces `0..num_pings` on `convo`, and run `num_passes` network passes,
+/// filtering the inbox on each.  Returns the nonces of the pings delivered, in order.
+fn run_pings(
+    faults: &mut NetworkFaults,
+    convo: &mut ConversationP2P,
+    num_pings: u32,
+    num_passes: u64,
+) -> Vec<u32> {
+    for nonce in 0..num_pings {
+        convo.connection.push_inbox_message(make_ping(nonce));
+    }
+    let mut delivered = vec![];
+    for _ in 0..num_passes {
+        faults.schedule(NetworkPollState::new());
+        faults.filter_inbox(convo);
+        while let Some(msg) = convo.connection.next_inbox_message() {
+            let StacksMessageType::Ping(data) = msg.payload else {
+                panic!("Expected a ping");
+            };
+            delivered.push(data.nonce);
+        }
+    }
+    delivered
+}
+
+#[test]
+fn test_network_faults_partition() {
+    let mut faults = NetworkFaults::new(0);
+    let mut convo = make_convo(20000, 1);
+    let mut other_convo = make_convo(20002, 2);
+
+    faults.set_link_fault(20000, LinkFault::partition());
+    assert!(run_pings(&mut faults, &mut convo, 10, 1).is_empty());
+    assert_eq!(faults.num_dropped, 10);
+
+    // other links are unaffected
+    assert_eq!(
+        run_pings(&mut faults, &mut other_convo, 10, 1),
+        (0..10).collect::<Vec<_>>()
+    );
+
+    // heal
+    faults.clear_link_fault(20000);
+    assert_eq!(
+        run_pings(&mut faults, &mut convo, 10, 1),
+        (0..10).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_network_faults_delay() {
+    let mut faults = NetworkFaults::new(0);
+    let mut convo = make_convo(20000, 1);
+
+    faults.set_link_fault(20000, LinkFault::delay(3));
+
+    // nothing arrives until the 4th pass
+    assert!(run_pings(&mut faults, &mut convo, 10, 3).is_empty());
+    assert_eq!(faults.num_held(), 10);
+    assert_eq!(
+        run_pings(&mut faults, &mut convo, 0, 1),
+        (0..10).collect::<Vec<_>>()
+    );
+    assert_eq!(faults.num_held(), 0);
+
+    // held messages for a closed conversation are forgotten
+    run_pings(&mut faults, &mut convo, 10, 1);
+    assert_eq!(faults.num_held(), 10);
+    faults.forget_conversation(1);
+    assert_eq!(faults.num_held(), 0);
+}
+
+#[test]
+fn test_network_faults_deterministic() {
+    let run = |seed: u64, fault: LinkFault| {
+        let mut faults = NetworkFaults::new(seed);
+        let mut convo = make_convo(20000, 1);
+        faults.set_link_fault(20000, fault);
+        run_pings(&mut faults, &mut convo, 100, 20)
+    };
+
+    // reordering delivers everything, but not in order
+    let reordered = run(1, LinkFault::reorder(10));
+    assert_eq!(reordered.len(), 100);
+    assert_ne!(reordered, (0..100).collect::<Vec<_>>());
+    let mut sorted = reordered.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..100).collect::<Vec<_>>());
+
+    // same seed, same result
+    assert_eq!(run(1, LinkFault::reorder(10)), reordered);
+    assert_ne!(run(2, LinkFault::reorder(10)), reordered);
+
+    // lossy links drop some, but not all, messages -- and always the same ones
+    let lossy = run(1, LinkFault::lossy(50));
+    assert!(!lossy.is_empty());
+    assert!(lossy.len() < 100);
+    assert_eq!(run(1, LinkFault::lossy(50)), lossy);
+}
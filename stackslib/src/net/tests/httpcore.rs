@@ -15,7 +15,7 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::io::{Read, Write};
-use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
 use std::sync::mpsc::{channel, Receiver};
 use std::time::{Duration, Instant};
 use std::{str, thread};
@@ -44,8 +44,9 @@ use crate::net::http::{
     HttpResponsePreamble, HttpVersion, HTTP_PREAMBLE_MAX_NUM_HEADERS,
 };
 use crate::net::httpcore::{
-    send_http_request, HttpPreambleExtensions, HttpRequestContentsExtensions, StacksHttp,
-    StacksHttpMessage, StacksHttpPreamble, StacksHttpRequest, StacksHttpResponse,
+    send_http_request, AdminAuth, AdminRole, AdminToken, EndpointClass, HttpCorsPolicy,
+    HttpPreambleExtensions, HttpRateLimitConfig, HttpRateLimiter, HttpRequestContentsExtensions,
+    StacksHttp, StacksHttpMessage, StacksHttpPreamble, StacksHttpRequest, StacksHttpResponse,
 };
 use crate::net::rpc::ConversationHttp;
 use crate::net::{ProtocolFamily, TipRequest};
@@ -1118,6 +1119,448 @@ fn test_metrics_identifiers() {
     }
 }
 
+#[test]
+fn test_endpoint_classes() {
+    let http = StacksHttp::new(
+        "127.0.0.1:20443".parse().unwrap(),
+        &ConnectionOptions::default(),
+    );
+
+    let fixtures = vec![
+        (("GET", "/v2/info"), Some(EndpointClass::Read)),
+        (
+            (
+                "GET",
+                "/v2/blocks/d8bd3c7e7cf7a9d783560a71356d3d9dbc84dc2f0c1a0001be8b141927c9d7ab",
+            ),
+            Some(EndpointClass::ReadHeavy),
+        ),
+        (
+            (
+                "POST",
+                "/v2/contracts/call-read/ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R/hello-world/get-value",
+            ),
+            Some(EndpointClass::ReadHeavy),
+        ),
+        (("POST", "/v2/transactions"), Some(EndpointClass::Write)),
+        (("POST", "/v3/block_proposal"), Some(EndpointClass::Admin)),
+        (("GET", "/some/nonexistent/endpoint"), None),
+    ];
+
+    for ((verb, path), expected_class) in fixtures {
+        let preamble = HttpRequestPreamble::new(
+            HttpVersion::Http11,
+            verb.to_string(),
+            path.to_string(),
+            "localhost".to_string(),
+            12345,
+            true,
+        );
+        let mut request = StacksHttpRequest::new(preamble, HttpRequestContents::new());
+        assert_eq!(
+            http.endpoint_class(&mut request),
+            expected_class,
+            "{verb} {path}"
+        );
+    }
+}
+
+#[test]
+fn test_rate_limiter_token_bucket() {
+    let mut limiter = HttpRateLimiter::new(HttpRateLimitConfig {
+        read: Some(2),
+        write: Some(1),
+        burst_secs: 3,
+        ..HttpRateLimitConfig::default()
+    });
+    assert!(limiter.is_enabled());
+
+    let addr: IpAddr = "1.2.3.4".parse().unwrap();
+    let start = Instant::now();
+
+    // burst of 2 req/sec * 3 secs
+    for _ in 0..6 {
+        limiter.check_at(&addr, EndpointClass::Read, start).unwrap();
+    }
+    // bucket is drained; one token comes back after half a second, so retry after 1 second
+    assert_eq!(limiter.check_at(&addr, EndpointClass::Read, start), Err(1));
+
+    // classes have separate budgets, and unlimited classes are never limited
+    for _ in 0..3 {
+        limiter
+            .check_at(&addr, EndpointClass::Write, start)
+            .unwrap();
+    }
+    assert!(limiter
+        .check_at(&addr, EndpointClass::Write, start)
+        .is_err());
+    for _ in 0..100 {
+        limiter
+            .check_at(&addr, EndpointClass::ReadHeavy, start)
+            .unwrap();
+    }
+
+    // other sources have their own budget
+    let other_addr: IpAddr = "1.2.3.5".parse().unwrap();
+    limiter
+        .check_at(&other_addr, EndpointClass::Read, start)
+        .unwrap();
+
+    // tokens refill over time
+    let later = start + Duration::from_secs(1);
+    limiter.check_at(&addr, EndpointClass::Read, later).unwrap();
+    limiter.check_at(&addr, EndpointClass::Read, later).unwrap();
+    assert!(limiter.check_at(&addr, EndpointClass::Read, later).is_err());
+
+    // full buckets get pruned
+    let much_later = start + Duration::from_secs(120);
+    limiter
+        .check_at(&addr, EndpointClass::Read, much_later)
+        .unwrap();
+    assert_eq!(limiter.num_buckets(), 1);
+}
+
+#[test]
+fn test_rate_limiter_subnets() {
+    let mut limiter = HttpRateLimiter::new(HttpRateLimitConfig {
+        read: Some(1),
+        burst_secs: 1,
+        ipv4_prefix_len: 24,
+        ipv6_prefix_len: 48,
+        ..HttpRateLimitConfig::default()
+    });
+    let now = Instant::now();
+
+    let addr_1: IpAddr = "10.0.0.1".parse().unwrap();
+    let addr_2: IpAddr = "10.0.0.2".parse().unwrap();
+    let addr_3: IpAddr = "10.0.1.1".parse().unwrap();
+    limiter.check_at(&addr_1, EndpointClass::Read, now).unwrap();
+    // same /24
+    assert!(limiter.check_at(&addr_2, EndpointClass::Read, now).is_err());
+    // different /24
+    limiter.check_at(&addr_3, EndpointClass::Read, now).unwrap();
+
+    // IPv4-mapped IPv6 addresses count against the IPv4 source
+    let mapped: IpAddr = "::ffff:10.0.0.3".parse().unwrap();
+    assert!(limiter.check_at(&mapped, EndpointClass::Read, now).is_err());
+
+    let addr_6_1: IpAddr = "2001:db8:1:1::1".parse().unwrap();
+    let addr_6_2: IpAddr = "2001:db8:1:2::1".parse().unwrap();
+    let addr_6_3: IpAddr = "2001:db8:2::1".parse().unwrap();
+    limiter
+        .check_at(&addr_6_1, EndpointClass::Read, now)
+        .unwrap();
+    // same /48
+    assert!(limiter
+        .check_at(&addr_6_2, EndpointClass::Read, now)
+        .is_err());
+    // different /48
+    limiter
+        .check_at(&addr_6_3, EndpointClass::Read, now)
+        .unwrap();
+}
+
+#[test]
+fn test_rate_limiter_disabled() {
+    let mut limiter = HttpRateLimiter::new(HttpRateLimitConfig::default());
+    assert!(!limiter.is_enabled());
+    let addr: IpAddr = "1.2.3.4".parse().unwrap();
+    for _ in 0..1000 {
+        limiter.check(&addr, EndpointClass::Admin).unwrap();
+    }
+    assert_eq!(limiter.num_buckets(), 0);
+}
+
+#[test]
+fn test_rate_limiter_held_requests() {
+    let mut limiter = HttpRateLimiter::new(HttpRateLimitConfig {
+        max_held_requests: 2,
+        ..HttpRateLimitConfig::default()
+    });
+    // the cap applies even with no per-source limits
+    assert!(!limiter.is_enabled());
+    assert!(limiter.try_hold_request());
+    assert!(limiter.try_hold_request());
+    assert!(!limiter.try_hold_request());
+
+    // a new pass finds one request was released
+    limiter.set_held_requests(1);
+    assert!(limiter.try_hold_request());
+    assert!(!limiter.try_hold_request());
+}
+
+#[test]
+fn test_reply_rate_limited() {
+    let mut conn_opts = ConnectionOptions::default();
+    conn_opts.rpc_rate_limits.read = Some(1);
+    conn_opts.rpc_rate_limits.burst_secs = 1;
+    let mut rate_limiter = HttpRateLimiter::new(conn_opts.rpc_rate_limits.clone());
+    let addr: IpAddr = "127.0.0.1".parse().unwrap();
+    rate_limiter.check(&addr, EndpointClass::Read).unwrap();
+
+    let mut convo = ConversationHttp::new(
+        "127.0.0.1:12345".parse().unwrap(),
+        None,
+        PeerHost::DNS("localhost".to_string(), 12345),
+        &conn_opts,
+        100,
+        32,
+    );
+    let request = StacksHttpRequest::new_for_peer(
+        PeerHost::DNS("localhost".to_string(), 12345),
+        "GET".into(),
+        "/v2/info".into(),
+        HttpRequestContents::new(),
+    )
+    .unwrap();
+
+    // the source already used up its budget, so the request is answered with a 429
+    assert!(convo
+        .check_rate_limit(request, &mut rate_limiter)
+        .unwrap()
+        .is_none());
+
+    // other classes are unlimited
+    let request = StacksHttpRequest::new_for_peer(
+        PeerHost::DNS("localhost".to_string(), 12345),
+        "POST".into(),
+        "/v2/transactions".into(),
+        HttpRequestContents::new(),
+    )
+    .unwrap();
+    assert!(convo
+        .check_rate_limit(request, &mut rate_limiter)
+        .unwrap()
+        .is_some());
+
+    let mut response_bytes = vec![];
+    convo.send(&mut response_bytes).unwrap();
+    let response_txt = String::from_utf8_lossy(&response_bytes);
+    assert!(response_txt.starts_with("HTTP/1.1 429 Too Many Requests\r\n"));
+    assert!(response_txt.to_lowercase().contains("retry-after: "));
+}
+
+#[test]
+fn test_cors_policy() {
+    let make_response = || {
+        HttpResponsePreamble::new(
+            HttpVersion::Http11,
+            200,
+            "OK".to_string(),
+            Some(0),
+            HttpContentType::JSON,
+            true,
+        )
+    };
+
+    // any origin, including requests without one
+    let policy = HttpCorsPolicy::default();
+    assert!(policy.allows_any_origin());
+    let mut response = make_response();
+    policy.apply(None, &mut response);
+    assert_eq!(
+        response.get_header("Access-Control-Allow-Origin".into()),
+        Some("*".to_string())
+    );
+    assert_eq!(
+        response.get_header("Access-Control-Allow-Methods".into()),
+        Some(HttpCorsPolicy::ALLOWED_METHODS.to_string())
+    );
+    assert!(response.get_header("Vary".into()).is_none());
+
+    // only listed origins
+    let policy = HttpCorsPolicy::from_origins(&[
+        "https://wallet.example.com/".to_string(),
+        "HTTP://localhost:3000".to_string(),
+    ])
+    .unwrap();
+    assert!(!policy.allows_any_origin());
+    assert_eq!(
+        policy.allowed_origin(Some("https://wallet.example.com")),
+        Some("https://wallet.example.com".to_string())
+    );
+    assert_eq!(
+        policy.allowed_origin(Some("http://localhost:3000")),
+        Some("http://localhost:3000".to_string())
+    );
+    assert_eq!(
+        policy.allowed_origin(Some("https://evil.example.com")),
+        None
+    );
+    assert_eq!(policy.allowed_origin(None), None);
+
+    let mut response = make_response();
+    policy.apply(Some("https://wallet.example.com"), &mut response);
+    assert_eq!(
+        response.get_header("Access-Control-Allow-Origin".into()),
+        Some("https://wallet.example.com".to_string())
+    );
+    assert_eq!(
+        response.get_header("Vary".into()),
+        Some("Origin".to_string())
+    );
+
+    let mut response = make_response();
+    policy.apply(Some("https://evil.example.com"), &mut response);
+    assert!(response
+        .get_header("Access-Control-Allow-Origin".into())
+        .is_none());
+    assert_eq!(
+        response.get_header("Vary".into()),
+        Some("Origin".to_string())
+    );
+
+    // no origins
+    let policy = HttpCorsPolicy::from_origins(&[]).unwrap();
+    let mut response = make_response();
+    policy.apply(Some("https://wallet.example.com"), &mut response);
+    assert!(response
+        .get_header("Access-Control-Allow-Origin".into())
+        .is_none());
+
+    // origins have no path
+    assert!(HttpCorsPolicy::from_origins(&["https://wallet.example.com/app".to_string()]).is_err());
+    assert!(HttpCorsPolicy::from_origins(&["wallet.example.com".to_string()]).is_err());
+    assert!(HttpCorsPolicy::from_origins(&["*".to_string()])
+        .unwrap()
+        .allows_any_origin());
+}
+
+#[test]
+fn test_admin_auth_roles() {
+    let make_request = |auth: Option<&str>| {
+        let mut preamble = HttpRequestPreamble::new(
+            HttpVersion::Http11,
+            "POST".into(),
+            "/v3/admin/backup".into(),
+            "localhost".into(),
+            20443,
+            true,
+        );
+        if let Some(auth) = auth {
+            preamble.add_header("authorization".into(), auth.into());
+        }
+        preamble
+    };
+    let expect_code = |res: Result<&str, crate::net::http::Error>, code: u16| match res {
+        Err(crate::net::http::Error::Http(err_code, _)) => assert_eq!(err_code, code),
+        x => panic!("Expected HTTP {code}, got {x:?}"),
+    };
+
+    // no tokens, so every admin endpoint is disabled
+    let auth = AdminAuth::default();
+    for role in AdminRole::ALL {
+        assert!(!auth.allows(role));
+        expect_code(auth.authorize(&make_request(Some("password")), role), 400);
+    }
+
+    let auth = AdminAuth::new(
+        Some("password"),
+        &[
+            AdminToken {
+                name: "backups".into(),
+                token: "hunter2".into(),
+                roles: [AdminRole::Backup].into_iter().collect(),
+            },
+            AdminToken {
+                name: "ops".into(),
+                token: "correct-horse".into(),
+                roles: [AdminRole::Peers, AdminRole::Mining].into_iter().collect(),
+            },
+        ],
+    );
+    for role in AdminRole::ALL {
+        assert!(auth.allows(role));
+        // the auth token may do anything
+        assert_eq!(
+            auth.authorize(&make_request(Some("password")), role)
+                .unwrap(),
+            AdminAuth::AUTH_TOKEN_NAME
+        );
+        expect_code(auth.authorize(&make_request(None), role), 401);
+        expect_code(auth.authorize(&make_request(Some("nope")), role), 401);
+    }
+
+    // named tokens are limited to their roles
+    assert_eq!(
+        auth.authorize(&make_request(Some("hunter2")), AdminRole::Backup)
+            .unwrap(),
+        "backups"
+    );
+    expect_code(
+        auth.authorize(&make_request(Some("hunter2")), AdminRole::Mining),
+        403,
+    );
+    assert_eq!(
+        auth.authorize(&make_request(Some("correct-horse")), AdminRole::Mining)
+            .unwrap(),
+        "ops"
+    );
+    expect_code(
+        auth.authorize(&make_request(Some("correct-horse")), AdminRole::Mempool),
+        403,
+    );
+
+    // without the auth token, roles no token has are disabled
+    let auth = AdminAuth::new(
+        None,
+        &[AdminToken {
+            name: "backups".into(),
+            token: "hunter2".into(),
+            roles: [AdminRole::Backup].into_iter().collect(),
+        }],
+    );
+    assert!(auth.allows(AdminRole::Backup));
+    assert!(!auth.allows(AdminRole::Mempool));
+    expect_code(
+        auth.authorize(&make_request(Some("hunter2")), AdminRole::Mempool),
+        400,
+    );
+
+    for role in AdminRole::ALL {
+        assert_eq!(role.as_str().parse::<AdminRole>().unwrap(), role);
+    }
+    assert!("root".parse::<AdminRole>().is_err());
+}
+
+#[test]
+fn test_http_error_cors_headers() {
+    let mut conn_opts = ConnectionOptions::default();
+    conn_opts.rpc_cors_policy =
+        HttpCorsPolicy::from_origins(&["https://wallet.example.com".to_string()]).unwrap();
+    let mut http = StacksHttp::new("127.0.0.1:20443".parse().unwrap(), &conn_opts);
+
+    for (origin, expected) in [
+        (
+            "https://wallet.example.com",
+            Some("https://wallet.example.com".to_string()),
+        ),
+        ("https://evil.example.com", None),
+    ] {
+        let request = StacksHttpRequest::new_for_peer(
+            PeerHost::DNS("localhost".to_string(), 20443),
+            "GET".into(),
+            "/v2/no-such-endpoint".into(),
+            HttpRequestContents::new(),
+        )
+        .unwrap()
+        .with_header("Origin".into(), origin.into());
+        let bytes = request.try_serialize().unwrap();
+        let (preamble, offset) = http.read_preamble(&bytes).unwrap();
+        let (msg, _) = http.read_payload(&preamble, &bytes[offset..]).unwrap();
+        let StacksHttpMessage::Error(_, response) = msg else {
+            panic!("Expected an error response");
+        };
+        assert_eq!(response.preamble().status_code, 404);
+        assert_eq!(
+            response
+                .preamble()
+                .get_header("Access-Control-Allow-Origin".into()),
+            expected
+        );
+    }
+}
+
 fn json_body(host: &str, port: u16, path: &str, json_bytes: &[u8]) -> StacksHttpRequest {
     let peerhost: PeerHost = format!("{host}:{port}")
         .parse()
@@ -14,18 +14,23 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+pub mod boot_plan_fuzz;
 pub mod convergence;
 pub mod download;
+pub mod faults;
 pub mod httpcore;
 pub mod inv;
 pub mod mempool;
 pub mod neighbors;
 pub mod relay;
+pub mod tls;
 
 use std::collections::{HashMap, HashSet};
 
 use clarity::vm::clarity::ClarityConnection;
-use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use clarity::vm::costs::ExecutionCost;
+use clarity::vm::events::SmartContractEventData;
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, Value};
 use libstackerdb::StackerDBChunkData;
 use rand::prelude::SliceRandom;
 use rand::{thread_rng, Rng, RngCore};
@@ -33,8 +38,8 @@ use stacks_common::address::{AddressHashMode, C32_ADDRESS_VERSION_TESTNET_SINGLE
 use stacks_common::bitvec::BitVec;
 use stacks_common::consts::{FIRST_BURNCHAIN_CONSENSUS_HASH, FIRST_STACKS_BLOCK_HASH};
 use stacks_common::types::chainstate::{
-    BurnchainHeaderHash, ConsensusHash, StacksAddress, StacksBlockId, StacksPrivateKey,
-    StacksPublicKey, TrieHash,
+    BlockHeaderHash, BurnchainHeaderHash, ConsensusHash, StacksAddress, StacksBlockId,
+    StacksPrivateKey, StacksPublicKey, TrieHash,
 };
 use stacks_common::types::net::PeerAddress;
 use stacks_common::types::{Address, StacksEpochId};
@@ -42,10 +47,11 @@ use stacks_common::util::hash::Sha512Trunc256Sum;
 use stacks_common::util::secp256k1::MessageSignature;
 use stacks_common::util::vrf::VRFProof;
 
-use crate::burnchains::PoxConstants;
+use crate::burnchains::{PoxConstants, Txid};
 use crate::chainstate::burn::db::sortdb::{SortitionDB, SortitionHandle};
 use crate::chainstate::burn::operations::BlockstackOperationType;
 use crate::chainstate::coordinator::tests::p2pkh_from;
+use crate::chainstate::coordinator::BlockEventDispatcher;
 use crate::chainstate::nakamoto::coordinator::tests::boot_nakamoto;
 use crate::chainstate::nakamoto::staging_blocks::NakamotoBlockObtainMethod;
 use crate::chainstate::nakamoto::test_signers::TestSigners;
@@ -58,11 +64,15 @@ use crate::chainstate::stacks::boot::test::{
     with_sortdb,
 };
 use crate::chainstate::stacks::boot::{
-    MINERS_NAME, SIGNERS_VOTING_FUNCTION_NAME, SIGNERS_VOTING_NAME,
+    MINERS_NAME, POX_4_NAME, SIGNERS_VOTING_FUNCTION_NAME, SIGNERS_VOTING_NAME,
 };
 use crate::chainstate::stacks::db::blocks::test::make_empty_coinbase_block;
-use crate::chainstate::stacks::db::{MinerPaymentTxFees, StacksAccount, StacksChainState};
-use crate::chainstate::stacks::events::TransactionOrigin;
+use crate::chainstate::stacks::db::{
+    MinerPaymentTxFees, StacksAccount, StacksChainState, StacksHeaderInfo,
+};
+use crate::chainstate::stacks::events::{
+    StacksBlockEventData, StacksTransactionEvent, StacksTransactionReceipt, TransactionOrigin,
+};
 use crate::chainstate::stacks::test::make_codec_test_microblock;
 use crate::chainstate::stacks::{
     CoinbasePayload, StacksTransaction, StacksTransactionSigner, TenureChangeCause,
@@ -70,7 +80,7 @@ use crate::chainstate::stacks::{
     TransactionContractCall, TransactionPayload, TransactionVersion,
 };
 use crate::clarity::vm::types::StacksAddressExtensions;
-use crate::core::{StacksEpoch, StacksEpochExtension};
+use crate::core::{EpochList, StacksEpoch, StacksEpochExtension};
 use crate::net::relay::{BlockAcceptResponse, Relayer};
 use crate::net::stackerdb::StackerDBConfig;
 use crate::net::test::{TestEventObserver, TestPeer, TestPeerConfig};
@@ -108,6 +118,8 @@ pub struct NakamotoBootPlan {
     /// Whether or not to produce malleablized blocks
     pub malleablized_blocks: bool,
     pub network_id: u32,
+    /// Epochs to use instead of an epoch 3.0 which lasts forever
+    pub epochs: Option<EpochList>,
 }
 
 impl NakamotoBootPlan {
@@ -125,6 +137,7 @@ impl NakamotoBootPlan {
             add_default_balance: true,
             malleablized_blocks: true,
             network_id: TestPeerConfig::default().network_id,
+            epochs: None,
         }
     }
 
@@ -185,6 +198,11 @@ impl NakamotoBootPlan {
         self
     }
 
+    pub fn with_epochs(mut self, epochs: EpochList) -> Self {
+        self.epochs = Some(epochs);
+        self
+    }
+
     /// This is the first tenure in which nakamoto blocks will be built.
     /// However, it is also the last sortition for an epoch 2.x block.
     pub fn nakamoto_start_burn_height(pox_consts: &PoxConstants) -> u64 {
@@ -374,12 +392,14 @@ impl NakamotoBootPlan {
         peer_config
             .stacker_dbs
             .push(boot_code_id(MINERS_NAME, false));
-        peer_config.epochs = Some(StacksEpoch::unit_test_3_0_only(
-            (self.pox_constants.pox_4_activation_height
-                + self.pox_constants.reward_cycle_length
-                + 1)
-            .into(),
-        ));
+        peer_config.epochs = Some(self.epochs.clone().unwrap_or_else(|| {
+            StacksEpoch::unit_test_3_0_only(
+                (self.pox_constants.pox_4_activation_height
+                    + self.pox_constants.reward_cycle_length
+                    + 1)
+                .into(),
+            )
+        }));
         peer_config.initial_balances = vec![];
         if self.add_default_balance {
             peer_config
@@ -965,10 +985,8 @@ impl NakamotoBootPlan {
                     for (receipt, tx) in stacks_receipts.iter().zip(block.txs.iter()) {
                         // transactions processed in the same order
                         assert_eq!(receipt.transaction.txid(), tx.txid());
-                        // no CheckErrors
-                        assert!(receipt.vm_error.is_none());
-                        // transaction was not aborted post-hoc
-                        assert!(!receipt.post_condition_aborted);
+                        // no CheckErrors, and not aborted post-hoc
+                        observer.assert_tx_succeeded(&tx.txid());
                     }
                 }
             }
@@ -1149,6 +1167,132 @@ fn test_boot_nakamoto_peer() {
 
     let observer = TestEventObserver::new();
     let (peer, other_peers) = plan.boot_into_nakamoto_peers(boot_tenures, Some(&observer));
+
+    // the stackers' lockups were recorded by pox-4
+    let pox_4 = boot_code_id(POX_4_NAME, false);
+    observer.assert_event_emitted(&pox_4, "print", |value| {
+        value
+            .clone()
+            .expect_result_ok()
+            .and_then(|data| data.expect_tuple())
+            .ok()
+            .and_then(|tuple| tuple.get("name").ok().cloned())
+            .and_then(|name| name.expect_ascii().ok())
+            .is_some_and(|name| name == "stack-stx")
+    });
+    assert!(!observer.get_events_by_type("stx_transfer_event").is_empty());
+}
+
+#[test]
+fn test_event_observer_index() {
+    let private_key = StacksPrivateKey::from_seed(&[3]);
+    let contract_id = boot_code_id("event-source", false);
+    let other_contract_id = boot_code_id("other-event-source", false);
+
+    let make_tx = |nonce: u64| {
+        let mut tx = StacksTransaction::new(
+            TransactionVersion::Testnet,
+            TransactionAuth::from_p2pkh(&private_key).unwrap(),
+            TransactionPayload::TokenTransfer(
+                PrincipalData::from(contract_id.clone()),
+                1,
+                TokenTransferMemo([0x00; 34]),
+            ),
+        );
+        tx.auth.set_origin_nonce(nonce);
+        tx
+    };
+    let make_print = |contract_id: &QualifiedContractIdentifier, value: Value| {
+        StacksTransactionEvent::SmartContractEvent(SmartContractEventData {
+            key: (contract_id.clone(), "print".into()),
+            value,
+        })
+    };
+
+    let tx_1 = make_tx(0);
+    let tx_2 = make_tx(1);
+    let mut aborted_receipt = StacksTransactionReceipt::from_contract_call(
+        tx_2.clone(),
+        vec![make_print(&other_contract_id, Value::UInt(3))],
+        Value::okay_true(),
+        0,
+        ExecutionCost::ZERO,
+    );
+    aborted_receipt.post_condition_aborted = true;
+
+    let receipts = vec![
+        StacksTransactionReceipt::from_contract_call(
+            tx_1.clone(),
+            vec![
+                make_print(&contract_id, Value::UInt(1)),
+                make_print(&contract_id, Value::UInt(2)),
+            ],
+            Value::okay_true(),
+            0,
+            ExecutionCost::ZERO,
+        ),
+        aborted_receipt,
+    ];
+
+    let observer = TestEventObserver::new();
+    let header = StacksHeaderInfo::regtest_genesis();
+    observer.announce_block(
+        &StacksBlockEventData {
+            block_hash: header.anchored_header.block_hash(),
+            parent_block_hash: BlockHeaderHash([0x00; 32]),
+            parent_microblock_hash: BlockHeaderHash([0x00; 32]),
+            parent_microblock_sequence: 0,
+        },
+        &header,
+        &receipts,
+        &StacksBlockId([0x00; 32]),
+        Txid([0x00; 32]),
+        &[],
+        None,
+        BurnchainHeaderHash([0x00; 32]),
+        0,
+        0,
+        &ExecutionCost::ZERO,
+        &ExecutionCost::ZERO,
+        &PoxConstants::test_default(),
+        &None,
+        &None,
+        None,
+        0,
+    );
+
+    assert_eq!(observer.get_blocks().len(), 1);
+    assert_eq!(
+        observer.get_tx_receipt(&tx_1.txid()).unwrap().events.len(),
+        2
+    );
+    assert!(observer.get_tx_receipt(&Txid([0x01; 32])).is_none());
+    assert_eq!(
+        observer.get_tx_block(&tx_2.txid()).unwrap().metadata,
+        header
+    );
+
+    assert_eq!(
+        observer.get_contract_event_values(&contract_id, "print"),
+        vec![Value::UInt(1), Value::UInt(2)]
+    );
+    assert_eq!(observer.get_contract_events(&other_contract_id).len(), 1);
+    assert_eq!(observer.get_events_by_type("contract_event").len(), 3);
+    assert!(observer.get_events_by_type("stx_lock_event").is_empty());
+
+    let event =
+        observer.assert_event_emitted(&contract_id, "print", |value| value == &Value::UInt(2));
+    assert_eq!(event.event_index, 1);
+    assert_eq!(event.block_id, header.index_block_hash());
+    observer.assert_event_not_emitted(&contract_id, "print", |value| value == &Value::UInt(3));
+
+    observer.assert_tx_succeeded(&tx_1.txid());
+    let result = std::panic::catch_unwind(|| observer.assert_tx_succeeded(&tx_2.txid()));
+    assert!(result.is_err());
+    let result = std::panic::catch_unwind(|| {
+        observer.assert_event_emitted(&contract_id, "print", |value| value == &Value::UInt(3))
+    });
+    assert!(result.is_err());
 }
 
 #[test]
@@ -18,6 +18,7 @@ use clarity::vm::types::{StacksAddressExtensions, StandardPrincipalData};
 use rand::prelude::*;
 use rand::thread_rng;
 use stacks_common::util::hash::*;
+use stacks_common::util::secp256k1::{Secp256k1PrivateKey, Secp256k1PublicKey};
 use stacks_common::util::sleep_ms;
 
 use crate::core::{
@@ -27,6 +28,7 @@ use crate::core::{
 use crate::net::asn::*;
 use crate::net::chat::*;
 use crate::net::db::*;
+use crate::net::neighbors::seeds::{DNSSeed, SignedSeedList};
 use crate::net::neighbors::*;
 use crate::net::test::*;
 use crate::net::{Error as net_error, *};
@@ -144,6 +146,46 @@ fn test_step_walk_1_neighbor_plain() {
     })
 }
 
+#[test]
+fn test_step_walk_1_neighbor_partition_heal() {
+    with_timeout(600, || {
+        let peer_1_config = TestPeerConfig::new(function_name!(), 0, 0);
+        let peer_2_config = TestPeerConfig::new(function_name!(), 0, 0);
+
+        let mut peer_1 = TestPeer::new(peer_1_config);
+        let mut peer_2 = TestPeer::new(peer_2_config);
+
+        peer_1.add_neighbor(&mut peer_2.to_neighbor(), None, true);
+
+        let has_handshaked = |peer_1: &TestPeer, peer_2: &TestPeer| {
+            peer_1
+                .network
+                .get_neighbor_stats(&peer_2.to_neighbor().addr)
+                .is_some_and(|stats| stats.last_handshake_time > 0)
+        };
+
+        // while partitioned, peer 1 never completes a handshake with peer 2
+        peer_1.partition(&mut peer_2);
+        for _ in 0..50 {
+            let _ = peer_1.step();
+            let _ = peer_2.step();
+            assert!(!has_handshaked(&peer_1, &peer_2));
+        }
+        assert!(peer_2.network.network_faults.num_dropped > 0);
+
+        // once healed, peer 1 reaches peer 2
+        peer_1.heal(&mut peer_2);
+        let mut i = 0;
+        while !has_handshaked(&peer_1, &peer_2) {
+            let _ = peer_1.step();
+            let _ = peer_2.step();
+            i += 1;
+        }
+
+        debug!("Healed partition after {} step(s)", i);
+    })
+}
+
 #[test]
 fn test_step_walk_1_neighbor_plain_no_natpunch() {
     with_timeout(600, || {
@@ -1602,3 +1644,160 @@ fn test_step_walk_2_neighbors_different_networks() {
         assert!(stats_2.is_none());
     })
 }
+
+#[test]
+fn test_dns_seed_parse() {
+    let pubkey = Secp256k1PublicKey::from_private(&Secp256k1PrivateKey::random());
+
+    let seed: DNSSeed = format!("{}@seed.example.com:20444", pubkey.to_hex())
+        .parse()
+        .unwrap();
+    assert_eq!(seed.public_key, pubkey);
+    assert_eq!(seed.host, "seed.example.com");
+    assert_eq!(seed.port, 20444);
+    assert_eq!(
+        seed.to_string(),
+        format!("{}@seed.example.com:20444", pubkey.to_hex())
+    );
+
+    let seed: DNSSeed = format!("{}@[2001:db8::1]:20444", pubkey.to_hex())
+        .parse()
+        .unwrap();
+    assert_eq!(seed.host, "2001:db8::1");
+    assert_eq!(seed.port, 20444);
+    assert_eq!(
+        seed.to_string(),
+        format!("{}@[2001:db8::1]:20444", pubkey.to_hex())
+    );
+
+    assert!("seed.example.com:20444".parse::<DNSSeed>().is_err());
+    assert!(format!("{}@seed.example.com", pubkey.to_hex())
+        .parse::<DNSSeed>()
+        .is_err());
+    assert!(format!("{}@:20444", pubkey.to_hex())
+        .parse::<DNSSeed>()
+        .is_err());
+    assert!("00@seed.example.com:20444".parse::<DNSSeed>().is_err());
+}
+
+#[test]
+fn test_signed_seed_list_verify() {
+    let operator_key = ed25519_dalek::SigningKey::from_bytes(&[0x11; 32]);
+    let other_key = ed25519_dalek::SigningKey::from_bytes(&[0x22; 32]);
+    let pubkey = Secp256k1PublicKey::from_private(&Secp256k1PrivateKey::random());
+    let seeds = vec![
+        format!("{}@seed-1.example.com:20444", pubkey.to_hex()),
+        format!("{}@seed-2.example.com:20444", pubkey.to_hex()),
+    ];
+
+    let list = SignedSeedList::sign(seeds.clone(), 1000, &operator_key);
+
+    // trusted key, not expired
+    let decoded = list
+        .verify(
+            &[other_key.verifying_key(), operator_key.verifying_key()],
+            999,
+        )
+        .unwrap();
+    assert_eq!(decoded.len(), 2);
+    assert_eq!(decoded[0].host, "seed-1.example.com");
+    assert_eq!(decoded[1].host, "seed-2.example.com");
+
+    // round-trips through JSON
+    let json = serde_json::to_string(&list).unwrap();
+    let list_2: SignedSeedList = serde_json::from_str(&json).unwrap();
+    assert_eq!(list, list_2);
+
+    // expired
+    let err = list
+        .verify(&[operator_key.verifying_key()], 1000)
+        .unwrap_err();
+    assert!(err.contains("expired"), "{err}");
+
+    // untrusted key
+    let err = list.verify(&[other_key.verifying_key()], 999).unwrap_err();
+    assert!(err.contains("not signed"), "{err}");
+
+    // tampered seeds
+    let mut tampered = list.clone();
+    tampered.seeds.pop();
+    assert!(tampered
+        .verify(&[operator_key.verifying_key()], 999)
+        .is_err());
+
+    // tampered expiry
+    let mut tampered = list.clone();
+    tampered.expires_at += 1;
+    assert!(tampered
+        .verify(&[operator_key.verifying_key()], 999)
+        .is_err());
+
+    // seed boundaries are part of the signed message
+    let mut tampered = list;
+    tampered.seeds = vec![seeds.concat()];
+    assert!(tampered
+        .verify(&[operator_key.verifying_key()], 999)
+        .is_err());
+}
+
+#[test]
+fn test_step_walk_1_neighbor_dns_seed() {
+    with_timeout(600, || {
+        let mut peer_1_config = TestPeerConfig::new(function_name!(), 0, 0);
+        let peer_2_config = TestPeerConfig::new(function_name!(), 0, 0);
+
+        // peer 1 has no bootstrap neighbors, but has a DNS seed for peer 2
+        let seed = DNSSeed {
+            public_key: Secp256k1PublicKey::from_private(&peer_2_config.private_key),
+            host: "127.0.0.1".into(),
+            port: peer_2_config.server_port,
+        };
+        peer_1_config.connection_opts.dns_seeds = vec![seed];
+
+        let mut peer_1 = TestPeer::new(peer_1_config);
+        let mut peer_2 = TestPeer::new(peer_2_config);
+
+        let neighbor_2 = peer_2.to_neighbor();
+        assert!(PeerDB::get_peer(
+            peer_1.network.peerdb.conn(),
+            neighbor_2.addr.network_id,
+            &neighbor_2.addr.addrbytes,
+            neighbor_2.addr.port
+        )
+        .unwrap()
+        .is_none());
+
+        let (mut dns_client, dns_thread_handle) = dns_thread_start(100);
+
+        let mut i = 0;
+        while peer_1
+            .network
+            .get_neighbor_stats(&neighbor_2.addr)
+            .is_none()
+        {
+            let _ = peer_1.step_dns(&mut dns_client);
+            let _ = peer_2.step();
+            i += 1;
+        }
+
+        debug!("Completed walk round {i} step(s)");
+
+        // peer 2 was resolved from the seed and added to peer 1's frontier
+        let seeded = PeerDB::get_peer(
+            peer_1.network.peerdb.conn(),
+            neighbor_2.addr.network_id,
+            &neighbor_2.addr.addrbytes,
+            neighbor_2.addr.port,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(seeded.public_key, neighbor_2.public_key);
+        assert!(peer_1.network.dns_seed_state.num_added > 0);
+
+        // peer 1 contacted peer 2
+        let stats_1 = peer_1.network.get_neighbor_stats(&neighbor_2.addr).unwrap();
+        assert!(stats_1.last_handshake_time > 0);
+
+        dns_thread_shutdown(dns_client, dns_thread_handle);
+    })
+}
@@ -31,6 +31,7 @@ use stacks_common::util::sleep_ms;
 use stacks_common::util::vrf::VRFProof;
 
 use crate::burnchains::tests::TestMiner;
+use crate::chainstate::stacks::block::TransactionEpochError;
 use crate::chainstate::stacks::db::blocks::{MINIMUM_TX_FEE, MINIMUM_TX_FEE_RATE_PER_BYTE};
 use crate::chainstate::stacks::miner::{BlockBuilderSettings, StacksMicroblockBuilder};
 use crate::chainstate::stacks::test::codec_all_transactions;
@@ -3667,8 +3668,12 @@ fn test_block_versioned_smart_contract_mempool_rejection_until_v210() {
             &versioned_contract,
             versioned_contract_len as u64,
         ) {
-            Err(MemPoolRejection::Other(msg)) => {
-                assert!(msg.find("not supported in this epoch").is_some());
+            Err(MemPoolRejection::NotSupportedInEpoch(epoch_id, reason)) => {
+                assert!(epoch_id < StacksEpochId::Epoch21);
+                assert_eq!(
+                    reason,
+                    TransactionEpochError::VersionedSmartContractNotSupported
+                );
             }
             Err(e) => {
                 panic!("will_admit_mempool_tx {:?}", &e);
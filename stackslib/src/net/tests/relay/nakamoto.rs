@@ -61,7 +61,7 @@ use crate::net::download::*;
 use crate::net::http::{HttpRequestContents, HttpRequestPreamble};
 use crate::net::httpcore::StacksHttpMessage;
 use crate::net::inv::inv2x::*;
-use crate::net::relay::{AcceptedNakamotoBlocks, ProcessedNetReceipts, Relayer};
+use crate::net::relay::{AcceptedNakamotoBlocks, ProcessedNetReceipts, Relayer, RelayerStats};
 use crate::net::test::*;
 use crate::net::tests::download::epoch2x::run_get_blocks_and_microblocks;
 use crate::net::tests::inv::nakamoto::make_nakamoto_peers_from_invs;
@@ -1156,3 +1156,93 @@ fn test_nakamoto_boot_node_from_block_push() {
         assert!(synced);
     });
 }
+
+fn make_dummy_nakamoto_block(chain_length: u64) -> NakamotoBlock {
+    NakamotoBlock {
+        header: NakamotoBlockHeader {
+            version: 1,
+            chain_length,
+            burn_spent: 126,
+            consensus_hash: ConsensusHash([0x55; 20]),
+            parent_block_id: StacksBlockId([0x03; 32]),
+            tx_merkle_root: Sha512Trunc256Sum([0x05; 32]),
+            state_index_root: TrieHash([0x07; 32]),
+            timestamp: 8,
+            miner_signature: MessageSignature::empty(),
+            signer_signature: vec![],
+            pox_treatment: BitVec::zeros(1).unwrap(),
+        },
+        txs: vec![],
+    }
+}
+
+#[test]
+fn test_batch_nakamoto_blocks() {
+    let blocks: Vec<_> = (0..(NAKAMOTO_BLOCKS_PUSHED_MAX as u64 + 8))
+        .map(make_dummy_nakamoto_block)
+        .collect();
+
+    let batches = Relayer::batch_nakamoto_blocks(blocks.clone());
+    assert_eq!(batches.len(), 2);
+    assert_eq!(batches[0].len(), NAKAMOTO_BLOCKS_PUSHED_MAX as usize);
+    assert_eq!(batches[1].len(), 8);
+
+    // order is preserved
+    let rebatched: Vec<_> = batches.into_iter().flatten().collect();
+    assert_eq!(rebatched, blocks);
+
+    assert!(Relayer::batch_nakamoto_blocks(vec![]).is_empty());
+}
+
+#[test]
+fn test_nakamoto_push_dedup_window() {
+    let mut relayer_stats = RelayerStats::new();
+    let blocks: Vec<_> = (0..4).map(make_dummy_nakamoto_block).collect();
+
+    let mut nk_1 = NeighborKey::empty();
+    nk_1.port = 1;
+    let mut nk_2 = NeighborKey::empty();
+    nk_2.port = 2;
+
+    // nothing seen yet
+    assert_eq!(
+        relayer_stats.filter_unseen_nakamoto_blocks(&nk_1, &blocks, 1000, 100),
+        blocks
+    );
+
+    // nk_1 pushed us the first two blocks
+    relayer_stats.add_nakamoto_blocks_seen(&nk_1, blocks[0..2].iter(), 100);
+    assert_eq!(
+        relayer_stats.filter_unseen_nakamoto_blocks(&nk_1, &blocks, 1000, 200),
+        blocks[2..4].to_vec()
+    );
+
+    // nk_2 is unaffected
+    assert_eq!(
+        relayer_stats.filter_unseen_nakamoto_blocks(&nk_2, &blocks, 1000, 200),
+        blocks
+    );
+
+    // we pushed nk_1 the rest
+    relayer_stats.add_nakamoto_blocks_seen(&nk_1, blocks[2..4].iter(), 300);
+    assert!(relayer_stats
+        .filter_unseen_nakamoto_blocks(&nk_1, &blocks, 1000, 400)
+        .is_empty());
+
+    // the first two fall out of the window
+    assert_eq!(
+        relayer_stats.filter_unseen_nakamoto_blocks(&nk_1, &blocks, 1000, 1101),
+        blocks[0..2].to_vec()
+    );
+
+    // pruning forgets stale entries, and drops neighbors with nothing left
+    relayer_stats.prune_nakamoto_blocks_seen(1000, 1101);
+    assert_eq!(relayer_stats.recent_nakamoto_blocks[&nk_1].len(), 2);
+    relayer_stats.prune_nakamoto_blocks_seen(1000, 1301);
+    assert!(relayer_stats.recent_nakamoto_blocks.is_empty());
+
+    // bans clear state
+    relayer_stats.add_nakamoto_blocks_seen(&nk_2, blocks.iter(), 2000);
+    relayer_stats.process_neighbor_ban(&nk_2);
+    assert!(relayer_stats.recent_nakamoto_blocks.is_empty());
+}
@@ -0,0 +1,259 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::net::tls::{MaybeTlsStream, TlsPeerConfig, TlsServerConfig};
+
+/// Path to a test certificate or key.  `node.pem` is signed by `ca.pem` and names 127.0.0.1.
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/net/tests/tls")
+        .join(name)
+}
+
+/// Make a connected pair of non-blocking loopback sockets
+fn socket_pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (server, _) = listener.accept().unwrap();
+    client.set_nonblocking(true).unwrap();
+    server.set_nonblocking(true).unwrap();
+    (client, server)
+}
+
+/// Read whatever plaintext is available
+fn try_read(stream: &mut MaybeTlsStream<TcpStream>, recv: &mut Vec<u8>) -> io::Result<()> {
+    let mut buf = [0u8; 1024];
+    loop {
+        match stream.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(num_read) => recv.extend_from_slice(&buf[..num_read]),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Drive both ends of a connection until the client and server have received at least
+/// `client_expected` and `server_expected` bytes, or until one of them fails
+fn exchange(
+    client: &mut MaybeTlsStream<TcpStream>,
+    server: &mut MaybeTlsStream<TcpStream>,
+    client_recv: &mut Vec<u8>,
+    server_recv: &mut Vec<u8>,
+    client_expected: usize,
+    server_expected: usize,
+) -> io::Result<()> {
+    for _ in 0..1000 {
+        try_read(client, client_recv)?;
+        try_read(server, server_recv)?;
+        client.flush_tls()?;
+        server.flush_tls()?;
+        if client_recv.len() >= client_expected && server_recv.len() >= server_expected {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+    Err(io::ErrorKind::TimedOut.into())
+}
+
+#[test]
+fn test_load_tls_config() {
+    TlsServerConfig::load(&fixture("node.pem"), &fixture("node.key")).unwrap();
+    TlsPeerConfig::load(
+        &fixture("node.pem"),
+        &fixture("node.key"),
+        &fixture("ca.pem"),
+    )
+    .unwrap();
+
+    let err = TlsServerConfig::load(&fixture("node.pem"), &fixture("no-such.key")).unwrap_err();
+    assert!(err.contains("Failed to open TLS private key"), "{err}");
+
+    let err = TlsServerConfig::load(&fixture("node.pem"), &fixture("node.pem")).unwrap_err();
+    assert!(err.contains("No private key found"), "{err}");
+
+    let err = TlsServerConfig::load(&fixture("node.key"), &fixture("node.key")).unwrap_err();
+    assert!(err.contains("No certificates found"), "{err}");
+}
+
+#[test]
+fn test_mutual_tls_session() {
+    let config = TlsPeerConfig::load(
+        &fixture("node.pem"),
+        &fixture("node.key"),
+        &fixture("ca.pem"),
+    )
+    .unwrap();
+    let (mut client_sock, mut server_sock) = socket_pair();
+    let mut client_session = config
+        .new_outbound_session("127.0.0.1".parse().unwrap())
+        .unwrap();
+    let mut server_session = config.new_inbound_session().unwrap();
+
+    let mut client = MaybeTlsStream::new(&mut client_sock, Some(&mut client_session));
+    let mut server = MaybeTlsStream::new(&mut server_sock, Some(&mut server_session));
+    assert!(client.is_tls());
+
+    // plaintext written before the handshake completes is sent once it does
+    assert_eq!(client.write(b"hello").unwrap(), 5);
+
+    let mut client_recv = vec![];
+    let mut server_recv = vec![];
+    exchange(
+        &mut client,
+        &mut server,
+        &mut client_recv,
+        &mut server_recv,
+        0,
+        5,
+    )
+    .unwrap();
+    assert_eq!(server_recv, b"hello");
+
+    assert_eq!(server.write(b"world").unwrap(), 5);
+    exchange(
+        &mut client,
+        &mut server,
+        &mut client_recv,
+        &mut server_recv,
+        5,
+        5,
+    )
+    .unwrap();
+    assert_eq!(client_recv, b"world");
+
+    drop(client);
+    drop(server);
+    assert!(!client_session.is_handshaking());
+    assert!(!server_session.is_handshaking());
+}
+
+#[test]
+fn test_rpc_tls_session() {
+    let server_config = TlsServerConfig::load(&fixture("node.pem"), &fixture("node.key")).unwrap();
+    let client_config = TlsPeerConfig::load(
+        &fixture("node.pem"),
+        &fixture("node.key"),
+        &fixture("ca.pem"),
+    )
+    .unwrap();
+    let (mut client_sock, mut server_sock) = socket_pair();
+    let mut client_session = client_config
+        .new_outbound_session("127.0.0.1".parse().unwrap())
+        .unwrap();
+    let mut server_session = server_config.new_session().unwrap();
+
+    let mut client = MaybeTlsStream::new(&mut client_sock, Some(&mut client_session));
+    let mut server = MaybeTlsStream::new(&mut server_sock, Some(&mut server_session));
+
+    client.write_all(b"GET /v2/info HTTP/1.1\r\n\r\n").unwrap();
+    let mut client_recv = vec![];
+    let mut server_recv = vec![];
+    exchange(
+        &mut client,
+        &mut server,
+        &mut client_recv,
+        &mut server_recv,
+        0,
+        25,
+    )
+    .unwrap();
+    assert_eq!(server_recv, b"GET /v2/info HTTP/1.1\r\n\r\n");
+}
+
+#[test]
+fn test_mutual_tls_rejects_client_without_certificate() {
+    let config = TlsPeerConfig::load(
+        &fixture("node.pem"),
+        &fixture("node.key"),
+        &fixture("ca.pem"),
+    )
+    .unwrap();
+
+    // a client which trusts the CA, but has no certificate of its own
+    let mut roots = rustls::RootCertStore::empty();
+    let ca_certs =
+        rustls_pemfile::certs(&mut BufReader::new(File::open(fixture("ca.pem")).unwrap())).unwrap();
+    for cert in ca_certs {
+        roots.add(&rustls::Certificate(cert)).unwrap();
+    }
+    let anonymous_config = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let mut client_session: rustls::Connection = rustls::ClientConnection::new(
+        Arc::new(anonymous_config),
+        rustls::ServerName::IpAddress("127.0.0.1".parse().unwrap()),
+    )
+    .unwrap()
+    .into();
+    let mut server_session = config.new_inbound_session().unwrap();
+
+    let (mut client_sock, mut server_sock) = socket_pair();
+    let mut client = MaybeTlsStream::new(&mut client_sock, Some(&mut client_session));
+    let mut server = MaybeTlsStream::new(&mut server_sock, Some(&mut server_session));
+
+    client.write_all(b"hello").unwrap();
+    let mut server_recv = vec![];
+    let mut server_err = None;
+    for _ in 0..1000 {
+        let mut client_recv = vec![];
+        let _ = try_read(&mut client, &mut client_recv);
+        let _ = client.flush_tls();
+        if let Err(e) = try_read(&mut server, &mut server_recv) {
+            server_err = Some(e);
+            break;
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(
+        server_err
+            .expect("server accepted an anonymous client")
+            .kind(),
+        io::ErrorKind::InvalidData
+    );
+    assert!(server_recv.is_empty());
+}
+
+#[test]
+fn test_plaintext_stream() {
+    let (mut client_sock, mut server_sock) = socket_pair();
+    let mut client = MaybeTlsStream::new(&mut client_sock, None);
+    let mut server = MaybeTlsStream::new(&mut server_sock, None);
+    assert!(!client.is_tls());
+
+    client.write_all(b"hello").unwrap();
+    let mut client_recv = vec![];
+    let mut server_recv = vec![];
+    exchange(
+        &mut client,
+        &mut server,
+        &mut client_recv,
+        &mut server_recv,
+        0,
+        5,
+    )
+    .unwrap();
+    assert_eq!(server_recv, b"hello");
+}
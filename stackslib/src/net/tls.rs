@@ -0,0 +1,317 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! TLS sessions for the RPC and p2p listeners.
+//!
+//! The node's sockets are non-blocking and driven by the poll loop, so TLS is layered on top of
+//! them as a `rustls::Connection` per socket.  `MaybeTlsStream` pairs a socket with its session
+//! (if any), so the conversation state machines can read and write plaintext without knowing
+//! whether or not the socket is encrypted.
+
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{fmt, io};
+
+use rustls::server::AllowAnyAuthenticatedClient;
+use rustls::{
+    Certificate, ClientConfig, ClientConnection, Connection, PrivateKey, RootCertStore,
+    ServerConfig, ServerConnection, ServerName,
+};
+
+use crate::net::Error as NetError;
+
+/// Load all certificates in a PEM file
+fn load_certs(path: &Path) -> Result<Vec<Certificate>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open TLS certificate {}: {e}", path.display()))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .map_err(|e| format!("Failed to read TLS certificate {}: {e}", path.display()))?;
+    if certs.is_empty() {
+        return Err(format!("No certificates found in {}", path.display()));
+    }
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+/// Load the first PKCS#8, RSA, or EC private key in a PEM file
+fn load_private_key(path: &Path) -> Result<PrivateKey, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open TLS private key {}: {e}", path.display()))?;
+    let items = rustls_pemfile::read_all(&mut BufReader::new(file))
+        .map_err(|e| format!("Failed to read TLS private key {}: {e}", path.display()))?;
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| format!("No private key found in {}", path.display()))
+}
+
+/// Load the CA certificates in a PEM file into a root store
+fn load_root_store(path: &Path) -> Result<RootCertStore, String> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(path)? {
+        roots
+            .add(&cert)
+            .map_err(|e| format!("Invalid CA certificate in {}: {e}", path.display()))?;
+    }
+    Ok(roots)
+}
+
+/// TLS settings for the RPC server
+#[derive(Clone)]
+pub struct TlsServerConfig {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    config: Arc<ServerConfig>,
+}
+
+impl TlsServerConfig {
+    /// Load the server's certificate chain and private key from PEM files
+    pub fn load(cert_path: &Path, key_path: &Path) -> Result<Self, String> {
+        let config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(load_certs(cert_path)?, load_private_key(key_path)?)
+            .map_err(|e| format!("Invalid TLS certificate or key: {e}"))?;
+        Ok(Self {
+            cert_path: cert_path.to_path_buf(),
+            key_path: key_path.to_path_buf(),
+            config: Arc::new(config),
+        })
+    }
+
+    /// Start a session for a newly-accepted connection
+    pub fn new_session(&self) -> Result<Connection, NetError> {
+        ServerConnection::new(self.config.clone())
+            .map(Connection::from)
+            .map_err(|e| {
+                warn!("Failed to start TLS server session: {e}");
+                NetError::ConnectionError
+            })
+    }
+}
+
+impl fmt::Debug for TlsServerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TlsServerConfig")
+            .field("cert_path", &self.cert_path)
+            .field("key_path", &self.key_path)
+            .finish()
+    }
+}
+
+impl PartialEq for TlsServerConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.cert_path == other.cert_path && self.key_path == other.key_path
+    }
+}
+
+/// Mutual TLS settings for p2p sessions.  Every node in the network presents a certificate signed
+/// by a shared CA, and refuses peers that do not.  Outbound sessions additionally require the
+/// remote node's certificate to name the IP address we connected to.
+#[derive(Clone)]
+pub struct TlsPeerConfig {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    ca_path: PathBuf,
+    server_config: Arc<ServerConfig>,
+    client_config: Arc<ClientConfig>,
+}
+
+impl TlsPeerConfig {
+    /// Load this node's certificate chain and private key, and the CA that signs every node's
+    /// certificate, from PEM files
+    pub fn load(cert_path: &Path, key_path: &Path, ca_path: &Path) -> Result<Self, String> {
+        let certs = load_certs(cert_path)?;
+        let key = load_private_key(key_path)?;
+        let roots = load_root_store(ca_path)?;
+
+        let server_config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots.clone()).boxed())
+            .with_single_cert(certs.clone(), key.clone())
+            .map_err(|e| format!("Invalid TLS certificate or key: {e}"))?;
+        let client_config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_client_auth_cert(certs, key)
+            .map_err(|e| format!("Invalid TLS certificate or key: {e}"))?;
+
+        Ok(Self {
+            cert_path: cert_path.to_path_buf(),
+            key_path: key_path.to_path_buf(),
+            ca_path: ca_path.to_path_buf(),
+            server_config: Arc::new(server_config),
+            client_config: Arc::new(client_config),
+        })
+    }
+
+    /// Start a session for a connection a peer made to us
+    pub fn new_inbound_session(&self) -> Result<Connection, NetError> {
+        ServerConnection::new(self.server_config.clone())
+            .map(Connection::from)
+            .map_err(|e| {
+                warn!("Failed to start inbound p2p TLS session: {e}");
+                NetError::ConnectionError
+            })
+    }
+
+    /// Start a session for a connection we made to the peer at `peer_ip`
+    pub fn new_outbound_session(&self, peer_ip: IpAddr) -> Result<Connection, NetError> {
+        ClientConnection::new(self.client_config.clone(), ServerName::IpAddress(peer_ip))
+            .map(Connection::from)
+            .map_err(|e| {
+                warn!("Failed to start outbound p2p TLS session to {peer_ip}: {e}");
+                NetError::ConnectionError
+            })
+    }
+}
+
+impl fmt::Debug for TlsPeerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TlsPeerConfig")
+            .field("cert_path", &self.cert_path)
+            .field("key_path", &self.key_path)
+            .field("ca_path", &self.ca_path)
+            .finish()
+    }
+}
+
+impl PartialEq for TlsPeerConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.cert_path == other.cert_path
+            && self.key_path == other.key_path
+            && self.ca_path == other.ca_path
+    }
+}
+
+/// A non-blocking socket, and the TLS session running over it if there is one.
+/// Reads and writes are plaintext either way.
+pub struct MaybeTlsStream<'a, S: Read + Write> {
+    sock: &'a mut S,
+    session: Option<&'a mut Connection>,
+}
+
+impl<'a, S: Read + Write> MaybeTlsStream<'a, S> {
+    pub fn new(sock: &'a mut S, session: Option<&'a mut Connection>) -> Self {
+        Self { sock, session }
+    }
+
+    pub fn is_tls(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// Write out as much buffered ciphertext (including handshake messages and alerts) as the
+    /// socket will take.  Does nothing for plaintext sockets.
+    pub fn flush_tls(&mut self) -> io::Result<()> {
+        let Some(session) = self.session.as_mut() else {
+            return Ok(());
+        };
+        while session.wants_write() {
+            match session.write_tls(self.sock) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: Read + Write> Read for MaybeTlsStream<'_, S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some(session) = self.session.as_mut() else {
+            return self.sock.read(buf);
+        };
+
+        // pull in whatever ciphertext is available
+        let mut eof = false;
+        while session.wants_read() {
+            match session.read_tls(self.sock) {
+                Ok(0) => {
+                    eof = true;
+                    break;
+                }
+                Ok(_) => {
+                    if let Err(e) = session.process_new_packets() {
+                        // try to tell the remote end why before giving up
+                        let _ = session.write_tls(self.sock);
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, e));
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        // processing the ciphertext may have produced handshake messages
+        self.flush_tls()?;
+
+        let Some(session) = self.session.as_mut() else {
+            unreachable!("TLS session disappeared");
+        };
+        match session.reader().read(buf) {
+            // Ok(0) means the remote end sent close_notify
+            Ok(num_read) => Ok(num_read),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock && eof => Ok(0),
+            // the remote end closed the socket without sending close_notify
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<S: Read + Write> Write for MaybeTlsStream<'_, S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.session.is_none() {
+            return self.sock.write(buf);
+        }
+
+        // don't let ciphertext pile up in the session if the socket is not draining
+        self.flush_tls()?;
+        let Some(session) = self.session.as_mut() else {
+            unreachable!("TLS session disappeared");
+        };
+        if session.wants_write() {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        let num_written = session.writer().write(buf)?;
+        self.flush_tls()?;
+        Ok(num_written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flush_tls()?;
+        self.sock.flush()
+    }
+}
+
+impl<S: Read + Write + fmt::Debug> fmt::Debug for MaybeTlsStream<'_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_tls() {
+            write!(f, "TLS({:?})", &self.sock)
+        } else {
+            write!(f, "{:?}", &self.sock)
+        }
+    }
+}
@@ -17,6 +17,7 @@
 use std::collections::HashMap;
 
 use stacks_common::types::chainstate::{BlockHeaderHash, ConsensusHash};
+use stacks_common::util::get_epoch_time_ms;
 
 use crate::chainstate::burn::db::sortdb::SortitionDB;
 use crate::chainstate::nakamoto::NakamotoBlock;
@@ -821,7 +822,10 @@ impl PeerNetwork {
             return false;
         };
 
-        if let Err(e) = nakamoto_block.header.verify_signer_signatures(reward_set) {
+        if let Err(e) = nakamoto_block
+            .header
+            .verify_signer_signatures_of_cycle(reward_cycle, reward_set)
+        {
             info!(
                 "{:?}: signature verification failure for Nakamoto block {}/{} in reward cycle {}: {:?}", self.get_local_peer(), &nakamoto_block.header.consensus_hash, &nakamoto_block.header.block_hash(), reward_cycle, &e
             );
@@ -944,6 +948,15 @@ impl PeerNetwork {
             nakamoto_blocks.blocks.len()
         );
 
+        // the remote peer has these blocks, so don't push them back to it
+        if let Some(remote_neighbor_key) = remote_neighbor_key_opt.as_ref() {
+            self.relayer_stats.add_nakamoto_blocks_seen(
+                remote_neighbor_key,
+                nakamoto_blocks.blocks.iter(),
+                get_epoch_time_ms(),
+            );
+        }
+
         let mut to_buffer = false;
         for nakamoto_block in nakamoto_blocks.blocks.iter() {
             if self.is_nakamoto_block_bufferable(sortdb, chainstate, nakamoto_block) {
@@ -0,0 +1,449 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Rotated backups of the node's sqlite databases.
+//!
+//! Each backup is a directory `backup-<unix-time>` in the rotation directory, holding one
+//! snapshot per database plus a `manifest.json` describing it.  Snapshots are taken with
+//! sqlite's online backup API in a single step, so each one is a consistent view of its
+//! database even while the node is writing to it.  A MARF's external blob file is copied after
+//! its index is snapshotted; blob files are append-only, so the copy holds every blob the
+//! snapshot refers to.  Snapshots of different databases are taken one after the other, so
+//! they need not be at the same chain tip.
+//!
+//! A backup is written to `backup-<unix-time>.partial` and renamed once complete, so an
+//! interrupted backup is never mistaken for a usable one.  Once a backup completes, the oldest
+//! backups beyond the retention limit are deleted.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use std::{fs, thread};
+
+use lazy_static::lazy_static;
+use rusqlite::backup::{Backup, StepResult};
+use rusqlite::{Connection, OpenFlags};
+use stacks_common::util::get_epoch_time_secs;
+
+use crate::util_lib::db::Error as db_error;
+
+/// Prefix of backup directory names
+const BACKUP_DIR_PREFIX: &str = "backup-";
+/// Suffix of backup directories which are still being written
+const PARTIAL_SUFFIX: &str = ".partial";
+/// Name of the file describing a backup
+const MANIFEST_FILE: &str = "manifest.json";
+/// How long to wait before retrying a snapshot step which found the source DB locked
+const LOCKED_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A database to include in backups
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupSource {
+    /// Path of the snapshot relative to each backup's directory
+    pub name: String,
+    /// Path to the live database
+    pub path: PathBuf,
+}
+
+impl BackupSource {
+    pub fn new(name: &str, path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.to_string(),
+            path: path.into(),
+        }
+    }
+}
+
+/// Description of a completed backup.  This is also the contents of its manifest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupInfo {
+    /// Directory holding the backup
+    pub path: String,
+    /// When the backup was started, in seconds since the epoch
+    pub timestamp: u64,
+    /// Names of the database snapshots in the backup
+    pub databases: Vec<String>,
+    /// How long the backup took
+    pub duration_ms: u64,
+}
+
+/// A rotation directory of backups of a set of databases
+pub struct BackupRotation {
+    dir: PathBuf,
+    retention: usize,
+    sources: Vec<BackupSource>,
+}
+
+impl BackupRotation {
+    /// Back up `sources` into `dir`, keeping at most `retention` backups (at least one is always
+    /// kept)
+    pub fn new(dir: impl Into<PathBuf>, retention: usize, sources: Vec<BackupSource>) -> Self {
+        Self {
+            dir: dir.into(),
+            retention: retention.max(1),
+            sources,
+        }
+    }
+
+    /// Take a backup of every source database which exists, and prune old backups
+    pub fn run_backup(&self) -> Result<BackupInfo, db_error> {
+        let start = Instant::now();
+        let mut timestamp = get_epoch_time_secs();
+        // don't clobber a backup taken within the same second
+        while self.backup_path(timestamp).exists() {
+            timestamp += 1;
+        }
+        let final_path = self.backup_path(timestamp);
+        let partial_path = self
+            .dir
+            .join(format!("{BACKUP_DIR_PREFIX}{timestamp}{PARTIAL_SUFFIX}"));
+        fs::create_dir_all(&partial_path).map_err(db_error::IOError)?;
+
+        let result = self.write_backup(&partial_path, timestamp, start, &final_path);
+        if result.is_err() {
+            if let Err(e) = fs::remove_dir_all(&partial_path) {
+                warn!("Failed to remove partial backup";
+                      "path" => %partial_path.display(), "error" => ?e);
+            }
+        }
+        let info = result?;
+        fs::rename(&partial_path, &final_path).map_err(db_error::IOError)?;
+        info!("Backed up node databases";
+              "path" => %info.path,
+              "databases" => ?info.databases,
+              "duration_ms" => info.duration_ms);
+
+        self.prune()?;
+        Ok(info)
+    }
+
+    /// Snapshot each source into `partial_path`, and write the manifest
+    fn write_backup(
+        &self,
+        partial_path: &Path,
+        timestamp: u64,
+        start: Instant,
+        final_path: &Path,
+    ) -> Result<BackupInfo, db_error> {
+        let mut databases = vec![];
+        for source in self.sources.iter() {
+            if !source.path.exists() {
+                debug!("Not backing up nonexistent database"; "path" => %source.path.display());
+                continue;
+            }
+            let dest = partial_path.join(&source.name);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).map_err(db_error::IOError)?;
+            }
+            snapshot_database(&source.path, &dest)?;
+
+            let mut blobs_path = source.path.clone().into_os_string();
+            blobs_path.push(".blobs");
+            let blobs_path = PathBuf::from(blobs_path);
+            if blobs_path.exists() {
+                let mut dest_blobs = dest.into_os_string();
+                dest_blobs.push(".blobs");
+                fs::copy(&blobs_path, dest_blobs).map_err(db_error::IOError)?;
+            }
+            databases.push(source.name.clone());
+        }
+
+        let info = BackupInfo {
+            path: final_path.display().to_string(),
+            timestamp,
+            databases,
+            duration_ms: u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX),
+        };
+        let manifest = serde_json::to_vec_pretty(&info)?;
+        fs::write(partial_path.join(MANIFEST_FILE), manifest).map_err(db_error::IOError)?;
+        Ok(info)
+    }
+
+    fn backup_path(&self, timestamp: u64) -> PathBuf {
+        self.dir.join(format!("{BACKUP_DIR_PREFIX}{timestamp}"))
+    }
+
+    /// List the completed backups in the rotation directory, oldest first
+    pub fn list_backups(&self) -> Result<Vec<PathBuf>, db_error> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut backups = vec![];
+        for entry in fs::read_dir(&self.dir).map_err(db_error::IOError)? {
+            let entry = entry.map_err(db_error::IOError)?;
+            let file_name = entry.file_name();
+            let Some(timestamp) = file_name
+                .to_str()
+                .and_then(|name| name.strip_prefix(BACKUP_DIR_PREFIX))
+                .and_then(|ts| ts.parse::<u64>().ok())
+            else {
+                continue;
+            };
+            backups.push((timestamp, entry.path()));
+        }
+        backups.sort();
+        Ok(backups.into_iter().map(|(_, path)| path).collect())
+    }
+
+    /// Delete the oldest completed backups beyond the retention limit
+    fn prune(&self) -> Result<(), db_error> {
+        let backups = self.list_backups()?;
+        let excess = backups.len().saturating_sub(self.retention);
+        for path in backups.into_iter().take(excess) {
+            info!("Removing old backup"; "path" => %path.display());
+            fs::remove_dir_all(&path).map_err(db_error::IOError)?;
+        }
+        Ok(())
+    }
+}
+
+/// Copy a consistent snapshot of the sqlite DB at `src` to `dest`
+fn snapshot_database(src: &Path, dest: &Path) -> Result<(), db_error> {
+    let src_conn = Connection::open_with_flags(
+        src,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    let mut dest_conn = Connection::open(dest)?;
+    let backup = Backup::new(&src_conn, &mut dest_conn)?;
+    loop {
+        // copy all pages in one step, so the snapshot is taken within a single read
+        // transaction and concurrent writes cannot restart it
+        match backup.step(-1)? {
+            StepResult::Done => return Ok(()),
+            StepResult::Busy | StepResult::Locked => thread::sleep(LOCKED_RETRY_INTERVAL),
+            _ => {}
+        }
+    }
+}
+
+/// Status of a node's backup service, as reported to RPC clients
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackupStatus {
+    /// Whether a backup has been requested but not yet started
+    pub pending: bool,
+    /// The last backup which completed
+    pub last_backup: Option<BackupInfo>,
+    /// Why the last backup failed, if it did
+    pub last_error: Option<String>,
+}
+
+/// Channel through which on-demand backups are requested from a node's backup thread
+#[derive(Default)]
+pub struct BackupRequests {
+    status: Mutex<BackupStatus>,
+    cvar: Condvar,
+}
+
+impl BackupRequests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask the backup thread to take a backup as soon as possible
+    pub fn request(&self) -> BackupStatus {
+        let mut status = self
+            .status
+            .lock()
+            .expect("FATAL: backup status lock poisoned");
+        status.pending = true;
+        self.cvar.notify_all();
+        status.clone()
+    }
+
+    /// Get the current status of the backup service
+    pub fn status(&self) -> BackupStatus {
+        self.status
+            .lock()
+            .expect("FATAL: backup status lock poisoned")
+            .clone()
+    }
+
+    /// Wait up to `timeout` for a backup to be requested.  Returns true, and clears the request,
+    /// if one was made.
+    pub fn wait_for_request(&self, timeout: Duration) -> bool {
+        let status = self
+            .status
+            .lock()
+            .expect("FATAL: backup status lock poisoned");
+        let (mut status, _) = self
+            .cvar
+            .wait_timeout_while(status, timeout, |status| !status.pending)
+            .expect("FATAL: backup status lock poisoned");
+        let requested = status.pending;
+        status.pending = false;
+        requested
+    }
+
+    /// Record the outcome of a backup
+    pub fn finish(&self, result: Result<BackupInfo, String>) {
+        let mut status = self
+            .status
+            .lock()
+            .expect("FATAL: backup status lock poisoned");
+        match result {
+            Ok(info) => {
+                status.last_backup = Some(info);
+                status.last_error = None;
+            }
+            Err(e) => status.last_error = Some(e),
+        }
+    }
+}
+
+lazy_static! {
+    /// Backup services by the root path of the chainstate they back up, so that RPC handlers
+    /// can find the one for their node (tests run many nodes in one process)
+    static ref BACKUP_SERVICES: Mutex<HashMap<String, Arc<BackupRequests>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Make the backup service for the chainstate at `root_path` available to RPC handlers
+pub fn register_backup_service(root_path: &str, requests: Arc<BackupRequests>) {
+    BACKUP_SERVICES
+        .lock()
+        .expect("FATAL: backup services lock poisoned")
+        .insert(root_path.to_string(), requests);
+}
+
+/// Withdraw the backup service for the chainstate at `root_path`
+pub fn unregister_backup_service(root_path: &str) {
+    BACKUP_SERVICES
+        .lock()
+        .expect("FATAL: backup services lock poisoned")
+        .remove(root_path);
+}
+
+/// Get the backup service for the chainstate at `root_path`, if there is one
+pub fn get_backup_service(root_path: &str) -> Option<Arc<BackupRequests>> {
+    BACKUP_SERVICES
+        .lock()
+        .expect("FATAL: backup services lock poisoned")
+        .get(root_path)
+        .cloned()
+}
+
+#[cfg(test)]
+mod test {
+    use rusqlite::params;
+
+    use super::*;
+
+    fn make_db(path: &Path, rows: u64) {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL; CREATE TABLE IF NOT EXISTS t (x INTEGER NOT NULL);",
+        )
+        .unwrap();
+        for x in 0..rows {
+            conn.execute("INSERT INTO t (x) VALUES (?1)", params![x])
+                .unwrap();
+        }
+    }
+
+    fn count_rows(path: &Path) -> u64 {
+        let conn = Connection::open(path).unwrap();
+        conn.query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_backup_rotation() {
+        let root = PathBuf::from("/tmp/stacks-backup-tests/test_backup_rotation");
+        if root.exists() {
+            fs::remove_dir_all(&root).unwrap();
+        }
+        let live = root.join("live");
+        fs::create_dir_all(&live).unwrap();
+        make_db(&live.join("a.sqlite"), 10);
+        make_db(&live.join("b.sqlite"), 5);
+        fs::write(live.join("b.sqlite.blobs"), b"blobs").unwrap();
+
+        let rotation = BackupRotation::new(
+            root.join("backups"),
+            2,
+            vec![
+                BackupSource::new("a.sqlite", live.join("a.sqlite")),
+                BackupSource::new("sub/b.sqlite", live.join("b.sqlite")),
+                BackupSource::new("missing.sqlite", live.join("missing.sqlite")),
+            ],
+        );
+        assert!(rotation.list_backups().unwrap().is_empty());
+
+        let info = rotation.run_backup().unwrap();
+        assert_eq!(info.databases, vec!["a.sqlite", "sub/b.sqlite"]);
+        let backup_path = PathBuf::from(&info.path);
+        assert_eq!(count_rows(&backup_path.join("a.sqlite")), 10);
+        assert_eq!(count_rows(&backup_path.join("sub/b.sqlite")), 5);
+        assert_eq!(
+            fs::read(backup_path.join("sub/b.sqlite.blobs")).unwrap(),
+            b"blobs"
+        );
+        assert!(!backup_path.join("missing.sqlite").exists());
+        let manifest: BackupInfo =
+            serde_json::from_slice(&fs::read(backup_path.join(MANIFEST_FILE)).unwrap()).unwrap();
+        assert_eq!(manifest, info);
+
+        // later backups see later writes, and only the last two are kept
+        make_db(&live.join("a.sqlite"), 1);
+        let second = rotation.run_backup().unwrap();
+        make_db(&live.join("a.sqlite"), 1);
+        let third = rotation.run_backup().unwrap();
+        assert!(second.timestamp > info.timestamp);
+        assert!(third.timestamp > second.timestamp);
+        assert_eq!(count_rows(&Path::new(&second.path).join("a.sqlite")), 11);
+        assert_eq!(count_rows(&Path::new(&third.path).join("a.sqlite")), 12);
+
+        let backups = rotation.list_backups().unwrap();
+        assert_eq!(
+            backups,
+            vec![PathBuf::from(&second.path), PathBuf::from(&third.path)]
+        );
+        assert!(!backup_path.exists());
+
+        // partial backups are neither listed nor pruned
+        let partial = root.join("backups").join("backup-1.partial");
+        fs::create_dir_all(&partial).unwrap();
+        rotation.run_backup().unwrap();
+        assert_eq!(rotation.list_backups().unwrap().len(), 2);
+        assert!(partial.exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_backup_requests() {
+        let requests = BackupRequests::new();
+        assert!(!requests.wait_for_request(Duration::from_millis(1)));
+
+        let status = requests.request();
+        assert!(status.pending);
+        assert!(requests.wait_for_request(Duration::from_secs(10)));
+        assert!(!requests.status().pending);
+
+        requests.finish(Err("disk full".into()));
+        assert_eq!(requests.status().last_error, Some("disk full".into()));
+        let info = BackupInfo {
+            path: "/tmp/backup-1".into(),
+            timestamp: 1,
+            databases: vec!["a.sqlite".into()],
+            duration_ms: 2,
+        };
+        requests.finish(Ok(info.clone()));
+        let status = requests.status();
+        assert_eq!(status.last_backup, Some(info));
+        assert!(status.last_error.is_none());
+    }
+}
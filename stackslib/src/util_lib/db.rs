@@ -676,6 +676,16 @@ pub fn tx_begin_immediate_sqlite(conn: &mut Connection) -> Result<DBTx<'_>, sqli
     Ok(tx)
 }
 
+/// Begin a deferred-mode transaction, and handle busy errors with exponential backoff.
+/// Reads made in the transaction all see the database as of its first read.  Unlike
+/// `tx_begin_immediate()`, this only borrows `conn`, so the connection can still be read
+/// through other handles (e.g. a MARF that owns it) while the transaction is open.
+pub fn tx_begin_deferred(conn: &Connection) -> Result<DBTx<'_>, Error> {
+    conn.busy_handler(Some(tx_busy_handler))?;
+    let tx = Transaction::new_unchecked(conn, TransactionBehavior::Deferred)?;
+    Ok(tx)
+}
+
 #[cfg(feature = "profile-sqlite")]
 fn trace_profile(query: &str, duration: Duration) {
     use serde_json::json;
@@ -0,0 +1,473 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tabular export files for offline analytics (`stacks-inspect export`).
+//!
+//! A table is a fixed list of typed columns, written one row at a time to either a CSV file or
+//! a Parquet file.  Both can be loaded directly by DuckDB, Athena and the like.  Parquet support
+//! needs the `parquet-export` feature; without it, only CSV can be written.
+//!
+//! Parquet files are written in row groups of `PARQUET_ROW_GROUP_SIZE` rows, so memory use does
+//! not grow with the size of the table.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+#[cfg(feature = "parquet-export")]
+use std::sync::Arc;
+
+#[cfg(feature = "parquet-export")]
+use parquet::basic::Compression;
+#[cfg(feature = "parquet-export")]
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, Int64Type};
+#[cfg(feature = "parquet-export")]
+use parquet::file::properties::WriterProperties;
+#[cfg(feature = "parquet-export")]
+use parquet::file::writer::SerializedFileWriter;
+#[cfg(feature = "parquet-export")]
+use parquet::schema::parser::parse_message_type;
+
+/// How many rows to buffer before writing a Parquet row group
+pub const PARQUET_ROW_GROUP_SIZE: usize = 65_536;
+
+/// File format of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<ExportFormat> {
+        match s {
+            "csv" => Some(ExportFormat::Csv),
+            "parquet" => Some(ExportFormat::Parquet),
+            _ => None,
+        }
+    }
+
+    /// File extension for tables in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+
+    /// Can this build of the node write this format?
+    pub fn is_supported(&self) -> bool {
+        match self {
+            ExportFormat::Csv => true,
+            ExportFormat::Parquet => cfg!(feature = "parquet-export"),
+        }
+    }
+}
+
+/// Type of a column in an exported table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    /// Signed 64-bit integer.  Unsigned values saturate at `i64::MAX`.
+    Int,
+    Bool,
+    /// UTF-8 string
+    Text,
+}
+
+/// A column in an exported table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Column {
+    pub name: &'static str,
+    pub column_type: ColumnType,
+}
+
+impl Column {
+    pub const fn new(name: &'static str, column_type: ColumnType) -> Self {
+        Self { name, column_type }
+    }
+}
+
+/// A value in an exported row
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportValue {
+    Int(i64),
+    Bool(bool),
+    Text(String),
+}
+
+impl ExportValue {
+    /// Make an `Int` value from an unsigned integer, saturating at `i64::MAX`
+    pub fn uint(value: u64) -> Self {
+        ExportValue::Int(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+
+    fn column_type(&self) -> ColumnType {
+        match self {
+            ExportValue::Int(_) => ColumnType::Int,
+            ExportValue::Bool(_) => ColumnType::Bool,
+            ExportValue::Text(_) => ColumnType::Text,
+        }
+    }
+}
+
+impl From<bool> for ExportValue {
+    fn from(value: bool) -> Self {
+        ExportValue::Bool(value)
+    }
+}
+
+impl From<String> for ExportValue {
+    fn from(value: String) -> Self {
+        ExportValue::Text(value)
+    }
+}
+
+impl From<&str> for ExportValue {
+    fn from(value: &str) -> Self {
+        ExportValue::Text(value.to_string())
+    }
+}
+
+/// Writes the rows of one exported table to a file
+pub trait TableWriter {
+    /// Append a row.  Its values must match the table's columns in number, order and type.
+    fn write_row(&mut self, row: Vec<ExportValue>) -> io::Result<()>;
+
+    /// Flush any buffered rows and close the file.  Returns how many rows were written.
+    fn finish(self: Box<Self>) -> io::Result<u64>;
+}
+
+/// Create `path` and return a writer for a table with `columns` in `format`.  `table` names the
+/// table in the Parquet schema.
+pub fn create_table_writer(
+    format: ExportFormat,
+    path: &Path,
+    table: &str,
+    columns: &'static [Column],
+) -> io::Result<Box<dyn TableWriter>> {
+    match format {
+        ExportFormat::Csv => Ok(Box::new(CsvTableWriter::new(path, columns)?)),
+        #[cfg(feature = "parquet-export")]
+        ExportFormat::Parquet => Ok(Box::new(ParquetTableWriter::new(path, table, columns)?)),
+        #[cfg(not(feature = "parquet-export"))]
+        ExportFormat::Parquet => {
+            let _ = table;
+            Err(io::Error::other(
+                "this build does not support Parquet; rebuild with `--features parquet-export`",
+            ))
+        }
+    }
+}
+
+/// Check that `row` matches `columns`
+fn check_row(columns: &[Column], row: &[ExportValue]) -> io::Result<()> {
+    if row.len() != columns.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("expected {} values, got {}", columns.len(), row.len()),
+        ));
+    }
+    for (column, value) in columns.iter().zip(row.iter()) {
+        if column.column_type != value.column_type() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "column {} expects {:?}, got {value:?}",
+                    column.name, column.column_type
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Writes a table as CSV, with a header row of column names.  Fields are quoted as in RFC 4180.
+pub struct CsvTableWriter {
+    out: BufWriter<File>,
+    columns: &'static [Column],
+    num_rows: u64,
+}
+
+impl CsvTableWriter {
+    pub fn new(path: &Path, columns: &'static [Column]) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+        let header: Vec<_> = columns.iter().map(|c| Self::escape(c.name)).collect();
+        writeln!(out, "{}", header.join(","))?;
+        Ok(Self {
+            out,
+            columns,
+            num_rows: 0,
+        })
+    }
+
+    /// Quote a field if it contains a separator, quote or line break
+    fn escape(field: &str) -> String {
+        if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+}
+
+impl TableWriter for CsvTableWriter {
+    fn write_row(&mut self, row: Vec<ExportValue>) -> io::Result<()> {
+        check_row(self.columns, &row)?;
+        let fields: Vec<_> = row
+            .into_iter()
+            .map(|value| match value {
+                ExportValue::Int(i) => i.to_string(),
+                ExportValue::Bool(b) => b.to_string(),
+                ExportValue::Text(s) => Self::escape(&s),
+            })
+            .collect();
+        writeln!(self.out, "{}", fields.join(","))?;
+        self.num_rows += 1;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> io::Result<u64> {
+        self.out.flush()?;
+        Ok(self.num_rows)
+    }
+}
+
+/// Buffered values of one Parquet column
+#[cfg(feature = "parquet-export")]
+enum ColumnBuffer {
+    Int(Vec<i64>),
+    Bool(Vec<bool>),
+    Text(Vec<ByteArray>),
+}
+
+/// Writes a table as a Snappy-compressed Parquet file
+#[cfg(feature = "parquet-export")]
+pub struct ParquetTableWriter {
+    writer: SerializedFileWriter<File>,
+    columns: &'static [Column],
+    buffers: Vec<ColumnBuffer>,
+    buffered_rows: usize,
+    num_rows: u64,
+}
+
+#[cfg(feature = "parquet-export")]
+impl ParquetTableWriter {
+    pub fn new(path: &Path, table: &str, columns: &'static [Column]) -> io::Result<Self> {
+        let schema = Arc::new(
+            parse_message_type(&Self::schema_message(table, columns)).map_err(io::Error::other)?,
+        );
+        let props = Arc::new(
+            WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build(),
+        );
+        let writer = SerializedFileWriter::new(File::create(path)?, schema, props)
+            .map_err(io::Error::other)?;
+        let buffers = columns
+            .iter()
+            .map(|column| match column.column_type {
+                ColumnType::Int => ColumnBuffer::Int(vec![]),
+                ColumnType::Bool => ColumnBuffer::Bool(vec![]),
+                ColumnType::Text => ColumnBuffer::Text(vec![]),
+            })
+            .collect();
+        Ok(Self {
+            writer,
+            columns,
+            buffers,
+            buffered_rows: 0,
+            num_rows: 0,
+        })
+    }
+
+    /// The Parquet schema of a table, in the message type syntax
+    fn schema_message(table: &str, columns: &[Column]) -> String {
+        let fields: Vec<_> = columns
+            .iter()
+            .map(|column| match column.column_type {
+                ColumnType::Int => format!("REQUIRED INT64 {};", column.name),
+                ColumnType::Bool => format!("REQUIRED BOOLEAN {};", column.name),
+                ColumnType::Text => format!("REQUIRED BYTE_ARRAY {} (UTF8);", column.name),
+            })
+            .collect();
+        format!("message {table} {{ {} }}", fields.join(" "))
+    }
+
+    /// Write the buffered rows as a row group
+    fn flush_row_group(&mut self) -> io::Result<()> {
+        if self.buffered_rows == 0 {
+            return Ok(());
+        }
+        let mut row_group = self.writer.next_row_group().map_err(io::Error::other)?;
+        for buffer in self.buffers.iter_mut() {
+            let mut column = row_group
+                .next_column()
+                .map_err(io::Error::other)?
+                .ok_or_else(|| io::Error::other("Parquet schema has too few columns"))?;
+            match buffer {
+                ColumnBuffer::Int(values) => {
+                    column.typed::<Int64Type>().write_batch(values, None, None)
+                }
+                ColumnBuffer::Bool(values) => {
+                    column.typed::<BoolType>().write_batch(values, None, None)
+                }
+                ColumnBuffer::Text(values) => column
+                    .typed::<ByteArrayType>()
+                    .write_batch(values, None, None),
+            }
+            .map_err(io::Error::other)?;
+            column.close().map_err(io::Error::other)?;
+            match buffer {
+                ColumnBuffer::Int(values) => values.clear(),
+                ColumnBuffer::Bool(values) => values.clear(),
+                ColumnBuffer::Text(values) => values.clear(),
+            }
+        }
+        row_group.close().map_err(io::Error::other)?;
+        self.buffered_rows = 0;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "parquet-export")]
+impl TableWriter for ParquetTableWriter {
+    fn write_row(&mut self, row: Vec<ExportValue>) -> io::Result<()> {
+        check_row(self.columns, &row)?;
+        for (buffer, value) in self.buffers.iter_mut().zip(row) {
+            match (buffer, value) {
+                (ColumnBuffer::Int(values), ExportValue::Int(i)) => values.push(i),
+                (ColumnBuffer::Bool(values), ExportValue::Bool(b)) => values.push(b),
+                (ColumnBuffer::Text(values), ExportValue::Text(s)) => values.push(s.into()),
+                _ => unreachable!("BUG: row was checked against the columns"),
+            }
+        }
+        self.buffered_rows += 1;
+        self.num_rows += 1;
+        if self.buffered_rows >= PARQUET_ROW_GROUP_SIZE {
+            self.flush_row_group()?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> io::Result<u64> {
+        self.flush_row_group()?;
+        let num_rows = self.num_rows;
+        self.writer.close().map_err(io::Error::other)?;
+        Ok(num_rows)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    const COLUMNS: &[Column] = &[
+        Column::new("height", ColumnType::Int),
+        Column::new("canonical", ColumnType::Bool),
+        Column::new("memo", ColumnType::Text),
+    ];
+
+    fn temp_path(extension: &str) -> PathBuf {
+        env::temp_dir().join(format!("export-test-{}.{extension}", rand::random::<u64>()))
+    }
+
+    fn rows() -> Vec<Vec<ExportValue>> {
+        vec![
+            vec![ExportValue::uint(1), true.into(), "plain".into()],
+            vec![
+                ExportValue::uint(u64::MAX),
+                false.into(),
+                "has \"quotes\", a comma\nand a newline".into(),
+            ],
+        ]
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let path = temp_path("csv");
+        let mut writer = create_table_writer(ExportFormat::Csv, &path, "test", COLUMNS).unwrap();
+        for row in rows() {
+            writer.write_row(row).unwrap();
+        }
+        // rows must match the columns
+        assert!(writer.write_row(vec![ExportValue::uint(1)]).is_err());
+        assert!(writer
+            .write_row(vec![true.into(), true.into(), "x".into()])
+            .is_err());
+        assert_eq!(writer.finish().unwrap(), 2);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            contents,
+            "height,canonical,memo\n\
+             1,true,plain\n\
+             9223372036854775807,false,\"has \"\"quotes\"\", a comma\nand a newline\"\n"
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "parquet-export")]
+    #[test]
+    fn test_parquet_export() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let path = temp_path("parquet");
+        let mut writer =
+            create_table_writer(ExportFormat::Parquet, &path, "test", COLUMNS).unwrap();
+        // span more than one row group
+        for _ in 0..(PARQUET_ROW_GROUP_SIZE / 2 + 1) {
+            for row in rows() {
+                writer.write_row(row).unwrap();
+            }
+        }
+        let num_rows = writer.finish().unwrap();
+        assert_eq!(num_rows, u64::try_from(PARQUET_ROW_GROUP_SIZE + 2).unwrap());
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.num_row_groups(), 2);
+        assert_eq!(
+            metadata.file_metadata().num_rows(),
+            i64::try_from(num_rows).unwrap()
+        );
+        let names: Vec<_> = metadata
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .map(|column| column.name().to_string())
+            .collect();
+        assert_eq!(names, vec!["height", "canonical", "memo"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_export_format() {
+        assert_eq!(ExportFormat::from_str("csv"), Some(ExportFormat::Csv));
+        assert_eq!(
+            ExportFormat::from_str("parquet"),
+            Some(ExportFormat::Parquet)
+        );
+        assert_eq!(ExportFormat::from_str("json"), None);
+        assert!(ExportFormat::Csv.is_supported());
+        assert_eq!(
+            ExportFormat::Parquet.is_supported(),
+            cfg!(feature = "parquet-export")
+        );
+    }
+}
@@ -1,9 +1,13 @@
 #[macro_use]
 pub mod db;
+pub mod backup;
 pub mod bloom;
 pub mod boot;
+pub mod export;
 pub mod signed_structured_data;
 pub mod strings;
+#[cfg(any(test, feature = "tx-builder"))]
+pub mod tx_builder;
 
 #[cfg(test)]
 pub mod test {
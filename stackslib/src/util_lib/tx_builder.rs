@@ -0,0 +1,354 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers for constructing signed Stacks transactions.
+//!
+//! Enabled with the `tx-builder` feature.  Each helper builds a transaction paying for itself
+//! from a single-signature p2pkh account (or, for sponsored transactions, from a sponsor's
+//! account), signs it with the given private key(s), and returns its consensus serialization,
+//! ready to be posted to `/v2/transactions`.  Transactions are built with post-condition mode
+//! `Allow` and anchor mode `OnChainOnly` unless otherwise stated.
+//!
+//! The helpers that do not take a `TransactionVersion` build testnet transactions; use
+//! `serialize_sign_tx_anchor_mode_version()` to build mainnet ones.
+
+use clarity::vm::types::PrincipalData;
+use clarity::vm::{ClarityName, ClarityVersion, ContractName, Value};
+use stacks_common::codec::StacksMessageCodec;
+use stacks_common::types::chainstate::StacksAddress;
+
+use crate::chainstate::stacks::{
+    StacksPrivateKey, StacksPublicKey, StacksTransaction, StacksTransactionSigner,
+    TokenTransferMemo, TransactionAnchorMode, TransactionAuth, TransactionContractCall,
+    TransactionPayload, TransactionPostConditionMode, TransactionSmartContract,
+    TransactionSpendingCondition, TransactionVersion,
+};
+use crate::util_lib::strings::StacksString;
+
+/// Build, sign, and serialize a transaction with `payload`.  If `payer` and `payer_nonce` are
+/// given, the transaction is sponsored by `payer`, who pays `tx_fee`; otherwise `sender` pays it.
+#[allow(clippy::too_many_arguments)]
+pub fn serialize_sign_tx_anchor_mode_version(
+    payload: TransactionPayload,
+    sender: &StacksPrivateKey,
+    payer: Option<&StacksPrivateKey>,
+    sender_nonce: u64,
+    payer_nonce: Option<u64>,
+    tx_fee: u64,
+    chain_id: u32,
+    anchor_mode: TransactionAnchorMode,
+    version: TransactionVersion,
+) -> Vec<u8> {
+    let mut sender_spending_condition =
+        TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(sender))
+            .expect("Failed to create p2pkh spending condition from public key.");
+    sender_spending_condition.set_nonce(sender_nonce);
+
+    let auth = match (payer, payer_nonce) {
+        (Some(payer), Some(payer_nonce)) => {
+            let mut payer_spending_condition = TransactionSpendingCondition::new_singlesig_p2pkh(
+                StacksPublicKey::from_private(payer),
+            )
+            .expect("Failed to create p2pkh spending condition from public key.");
+            payer_spending_condition.set_nonce(payer_nonce);
+            payer_spending_condition.set_tx_fee(tx_fee);
+            TransactionAuth::Sponsored(sender_spending_condition, payer_spending_condition)
+        }
+        _ => {
+            sender_spending_condition.set_tx_fee(tx_fee);
+            TransactionAuth::Standard(sender_spending_condition)
+        }
+    };
+    let mut unsigned_tx = StacksTransaction::new(version, auth, payload);
+    unsigned_tx.anchor_mode = anchor_mode;
+    unsigned_tx.post_condition_mode = TransactionPostConditionMode::Allow;
+    unsigned_tx.chain_id = chain_id;
+
+    let mut tx_signer = StacksTransactionSigner::new(&unsigned_tx);
+    tx_signer.sign_origin(sender).unwrap();
+    if let (Some(payer), Some(_)) = (payer, payer_nonce) {
+        tx_signer.sign_sponsor(payer).unwrap();
+    }
+
+    let mut buf = vec![];
+    tx_signer
+        .get_tx()
+        .unwrap()
+        .consensus_serialize(&mut buf)
+        .unwrap();
+    buf
+}
+
+/// Build, sign, and serialize a transaction with `payload`, sponsored by `payer`
+#[allow(clippy::too_many_arguments)]
+pub fn serialize_sign_sponsored_sig_tx_anchor_mode_version(
+    payload: TransactionPayload,
+    sender: &StacksPrivateKey,
+    payer: &StacksPrivateKey,
+    sender_nonce: u64,
+    payer_nonce: u64,
+    tx_fee: u64,
+    chain_id: u32,
+    anchor_mode: TransactionAnchorMode,
+    version: TransactionVersion,
+) -> Vec<u8> {
+    serialize_sign_tx_anchor_mode_version(
+        payload,
+        sender,
+        Some(payer),
+        sender_nonce,
+        Some(payer_nonce),
+        tx_fee,
+        chain_id,
+        anchor_mode,
+        version,
+    )
+}
+
+/// Build, sign, and serialize a transaction with `payload`, paid for by `sender`
+pub fn serialize_sign_standard_single_sig_tx_anchor_mode_version(
+    payload: TransactionPayload,
+    sender: &StacksPrivateKey,
+    nonce: u64,
+    tx_fee: u64,
+    chain_id: u32,
+    anchor_mode: TransactionAnchorMode,
+    version: TransactionVersion,
+) -> Vec<u8> {
+    serialize_sign_tx_anchor_mode_version(
+        payload,
+        sender,
+        None,
+        nonce,
+        None,
+        tx_fee,
+        chain_id,
+        anchor_mode,
+        version,
+    )
+}
+
+/// Build, sign, and serialize a testnet transaction with `payload`, paid for by `sender`
+pub fn serialize_sign_standard_single_sig_tx_anchor_mode(
+    payload: TransactionPayload,
+    sender: &StacksPrivateKey,
+    nonce: u64,
+    tx_fee: u64,
+    chain_id: u32,
+    anchor_mode: TransactionAnchorMode,
+) -> Vec<u8> {
+    serialize_sign_standard_single_sig_tx_anchor_mode_version(
+        payload,
+        sender,
+        nonce,
+        tx_fee,
+        chain_id,
+        anchor_mode,
+        TransactionVersion::Testnet,
+    )
+}
+
+/// Build, sign, and serialize an on-chain-only testnet transaction with `payload`, paid for by
+/// `sender`
+pub fn serialize_sign_standard_single_sig_tx(
+    payload: TransactionPayload,
+    sender: &StacksPrivateKey,
+    nonce: u64,
+    tx_fee: u64,
+    chain_id: u32,
+) -> Vec<u8> {
+    serialize_sign_standard_single_sig_tx_anchor_mode(
+        payload,
+        sender,
+        nonce,
+        tx_fee,
+        chain_id,
+        TransactionAnchorMode::OnChainOnly,
+    )
+}
+
+/// Make a signed testnet transaction that transfers `amount` microSTX to `recipient`
+pub fn make_stacks_transfer(
+    sender: &StacksPrivateKey,
+    nonce: u64,
+    tx_fee: u64,
+    chain_id: u32,
+    recipient: &PrincipalData,
+    amount: u64,
+) -> Vec<u8> {
+    let payload =
+        TransactionPayload::TokenTransfer(recipient.clone(), amount, TokenTransferMemo([0; 34]));
+    serialize_sign_standard_single_sig_tx(payload, sender, nonce, tx_fee, chain_id)
+}
+
+/// Make a signed testnet transaction that publishes `contract_content` as `contract_name`,
+/// with the given Clarity version (or the current epoch's default version, if `None`)
+pub fn make_contract_publish_versioned(
+    sender: &StacksPrivateKey,
+    nonce: u64,
+    tx_fee: u64,
+    chain_id: u32,
+    contract_name: &str,
+    contract_content: &str,
+    version: Option<ClarityVersion>,
+) -> Vec<u8> {
+    let name = ContractName::from(contract_name);
+    let code_body = StacksString::from_string(&contract_content.to_string()).unwrap();
+
+    let payload =
+        TransactionPayload::SmartContract(TransactionSmartContract { name, code_body }, version);
+
+    serialize_sign_standard_single_sig_tx(payload, sender, nonce, tx_fee, chain_id)
+}
+
+/// Make a signed testnet transaction that publishes `contract_content` as `contract_name`
+pub fn make_contract_publish(
+    sender: &StacksPrivateKey,
+    nonce: u64,
+    tx_fee: u64,
+    chain_id: u32,
+    contract_name: &str,
+    contract_content: &str,
+) -> Vec<u8> {
+    make_contract_publish_versioned(
+        sender,
+        nonce,
+        tx_fee,
+        chain_id,
+        contract_name,
+        contract_content,
+        None,
+    )
+}
+
+/// Make a signed testnet transaction that calls `function_name` in the contract
+/// `contract_addr.contract_name` with `function_args`
+#[allow(clippy::too_many_arguments)]
+pub fn make_contract_call(
+    sender: &StacksPrivateKey,
+    nonce: u64,
+    tx_fee: u64,
+    chain_id: u32,
+    contract_addr: &StacksAddress,
+    contract_name: &str,
+    function_name: &str,
+    function_args: &[Value],
+) -> Vec<u8> {
+    let contract_name = ContractName::from(contract_name);
+    let function_name = ClarityName::from(function_name);
+
+    let payload = TransactionContractCall {
+        address: *contract_addr,
+        contract_name,
+        function_name,
+        function_args: function_args.to_vec(),
+    };
+
+    serialize_sign_standard_single_sig_tx(payload.into(), sender, nonce, tx_fee, chain_id)
+}
+
+#[cfg(test)]
+mod test {
+    use stacks_common::address::AddressHashMode;
+
+    use super::*;
+    use crate::chainstate::stacks::C32_ADDRESS_VERSION_TESTNET_SINGLESIG;
+    use crate::core::CHAIN_ID_TESTNET;
+
+    fn decode(tx_bytes: &[u8]) -> StacksTransaction {
+        let tx = StacksTransaction::consensus_deserialize(&mut &tx_bytes[..]).unwrap();
+        tx.verify().unwrap();
+        tx
+    }
+
+    #[test]
+    fn test_built_transactions_are_signed() {
+        let sender = StacksPrivateKey::random();
+        let payer = StacksPrivateKey::random();
+        let sender_addr = StacksAddress::from_public_keys(
+            C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+            &AddressHashMode::SerializeP2PKH,
+            1,
+            &vec![StacksPublicKey::from_private(&sender)],
+        )
+        .unwrap();
+        let recipient = PrincipalData::from(StacksAddress::burn_address(false));
+
+        let tx = decode(&make_stacks_transfer(
+            &sender,
+            1,
+            180,
+            CHAIN_ID_TESTNET,
+            &recipient,
+            1000,
+        ));
+        assert_eq!(tx.origin_address(), sender_addr);
+        assert_eq!(tx.get_origin_nonce(), 1);
+        assert_eq!(tx.get_tx_fee(), 180);
+        assert_eq!(tx.chain_id, CHAIN_ID_TESTNET);
+        assert_eq!(tx.version, TransactionVersion::Testnet);
+        assert!(matches!(
+            tx.payload,
+            TransactionPayload::TokenTransfer(ref addr, 1000, _) if *addr == recipient
+        ));
+
+        let tx = decode(&make_contract_publish(
+            &sender,
+            2,
+            1000,
+            CHAIN_ID_TESTNET,
+            "hello",
+            "(define-read-only (hello) u1)",
+        ));
+        assert!(matches!(
+            tx.payload,
+            TransactionPayload::SmartContract(ref smart_contract, None)
+                if smart_contract.name.as_str() == "hello"
+        ));
+
+        let tx = decode(&make_contract_call(
+            &sender,
+            3,
+            200,
+            CHAIN_ID_TESTNET,
+            &sender_addr,
+            "hello",
+            "hello",
+            &[],
+        ));
+        assert!(matches!(
+            tx.payload,
+            TransactionPayload::ContractCall(ref call)
+                if call.function_name.as_str() == "hello" && call.address == sender_addr
+        ));
+
+        let payload = TransactionPayload::TokenTransfer(recipient, 1, TokenTransferMemo([0; 34]));
+        let tx = decode(&serialize_sign_sponsored_sig_tx_anchor_mode_version(
+            payload,
+            &sender,
+            &payer,
+            4,
+            5,
+            300,
+            CHAIN_ID_TESTNET,
+            TransactionAnchorMode::OnChainOnly,
+            TransactionVersion::Testnet,
+        ));
+        assert_eq!(tx.origin_address(), sender_addr);
+        assert_eq!(tx.get_origin_nonce(), 4);
+        assert_eq!(tx.get_sponsor_nonce(), Some(5));
+        assert_eq!(tx.get_tx_fee(), 300);
+    }
+}
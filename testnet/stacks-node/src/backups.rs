@@ -0,0 +1,122 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Periodic and on-demand backups of the node's databases (`[node] backup_interval`).
+//!
+//! A dedicated thread snapshots the sortition DB, burnchain DB, chainstate headers DB and
+//! StackerDB into `[node] backup_dir` every `backup_interval` seconds, and whenever a backup is
+//! requested through `POST /v3/admin/backup`.  Snapshots are laid out as in the node's working
+//! directory, so a backup can be restored by copying it over `<working_dir>/<mode>`.  Clarity
+//! state is not backed up; it is re-derived by replaying the chain from the backed-up headers.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use stacks::chainstate::stacks::db::StacksChainState;
+use stacks::config::Config;
+use stacks::net::httpcore::{AdminAuth, AdminRole};
+use stacks::util_lib::backup::{
+    register_backup_service, unregister_backup_service, BackupRequests, BackupRotation,
+    BackupSource,
+};
+
+/// How often the backup thread checks whether it should stop
+const BACKUP_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The databases to back up, named by their paths relative to `<working_dir>/<mode>`
+fn backup_sources(config: &Config) -> Vec<BackupSource> {
+    let mut sortition_db = PathBuf::from(config.get_burn_db_file_path());
+    sortition_db.push("marf.sqlite");
+    let mut burnchain_db = PathBuf::from(config.get_burn_db_path());
+    burnchain_db.push("burnchain.sqlite");
+    let headers_db = StacksChainState::header_index_root_path(config.get_chainstate_path());
+
+    vec![
+        BackupSource::new("burnchain/sortition/marf.sqlite", sortition_db),
+        BackupSource::new("burnchain/burnchain.sqlite", burnchain_db),
+        BackupSource::new("chainstate/vm/index.sqlite", headers_db),
+        BackupSource::new("stacker_db.sqlite", config.get_stacker_db_file_path()),
+    ]
+}
+
+pub struct BackupThread {
+    keep_running: Arc<AtomicBool>,
+    root_path: String,
+    handle: JoinHandle<()>,
+}
+
+impl BackupThread {
+    /// Start the backup thread, if periodic backups are configured or backups can be requested
+    /// through the admin RPC endpoints
+    pub fn spawn(config: &Config) -> Option<Self> {
+        let interval = config.node.backup_interval;
+        let admin_auth = AdminAuth::new(
+            config.connection_options.auth_token.as_deref(),
+            &config.connection_options.admin_tokens,
+        );
+        if interval == 0 && !admin_auth.allows(AdminRole::Backup) {
+            return None;
+        }
+        let rotation = BackupRotation::new(
+            config.get_backup_dir(),
+            usize::try_from(config.node.backup_retention).unwrap_or(usize::MAX),
+            backup_sources(config),
+        );
+        let requests = Arc::new(BackupRequests::new());
+        let root_path = config.get_chainstate_path_str();
+        register_backup_service(&root_path, requests.clone());
+        let keep_running = Arc::new(AtomicBool::new(true));
+        let thread_keep_running = keep_running.clone();
+
+        let handle = thread::Builder::new()
+            .name("backups".into())
+            .spawn(move || {
+                let mut last_backup = Instant::now();
+                while thread_keep_running.load(Ordering::SeqCst) {
+                    let requested = requests.wait_for_request(BACKUP_POLL_INTERVAL);
+                    let due = interval > 0 && last_backup.elapsed().as_secs() >= interval;
+                    if !requested && !due {
+                        continue;
+                    }
+                    last_backup = Instant::now();
+                    let result = rotation.run_backup().map_err(|e| {
+                        warn!("Failed to back up node databases: {e:?}");
+                        e.to_string()
+                    });
+                    requests.finish(result);
+                }
+            })
+            .expect("FATAL: failed to start backups thread");
+
+        Some(Self {
+            keep_running,
+            root_path,
+            handle,
+        })
+    }
+
+    /// Stop the backup thread and wait for it to exit
+    pub fn stop(self) {
+        unregister_backup_service(&self.root_path);
+        self.keep_running.store(false, Ordering::SeqCst);
+        if let Err(e) = self.handle.join() {
+            error!("Backups thread panicked: {e:?}");
+        }
+    }
+}
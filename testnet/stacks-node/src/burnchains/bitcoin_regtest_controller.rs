@@ -16,7 +16,7 @@
 
 use std::io::Cursor;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use std::{cmp, io};
 
@@ -30,7 +30,7 @@ use stacks::burnchains::bitcoin::address::{
 use stacks::burnchains::bitcoin::indexer::{
     BitcoinIndexer, BitcoinIndexerConfig, BitcoinIndexerRuntime,
 };
-use stacks::burnchains::bitcoin::spv::SpvClient;
+use stacks::burnchains::bitcoin::spv::{HeaderSyncTracker, SpvClient};
 use stacks::burnchains::bitcoin::BitcoinNetworkType;
 use stacks::burnchains::db::BurnchainDB;
 use stacks::burnchains::indexer::BurnchainIndexer;
@@ -41,21 +41,23 @@ use stacks::burnchains::{
 use stacks::chainstate::burn::db::sortdb::SortitionDB;
 use stacks::chainstate::burn::operations::{
     BlockstackOperationType, DelegateStxOp, LeaderBlockCommitOp, LeaderKeyRegisterOp, PreStxOp,
-    StackStxOp, TransferStxOp, VoteForAggregateKeyOp,
+    StackAggregationCommitOp, StackStxOp, TransferStxOp, VoteForAggregateKeyOp,
 };
-#[cfg(test)]
 use stacks::chainstate::burn::Opcodes;
 use stacks::chainstate::coordinator::comm::CoordinatorChannels;
-#[cfg(test)]
 use stacks::chainstate::stacks::address::PoxAddress;
-use stacks::config::BurnchainConfig;
-#[cfg(test)]
 use stacks::config::{
-    OP_TX_ANY_ESTIM_SIZE, OP_TX_DELEGATE_STACKS_ESTIM_SIZE, OP_TX_PRE_STACKS_ESTIM_SIZE,
-    OP_TX_STACK_STX_ESTIM_SIZE, OP_TX_TRANSFER_STACKS_ESTIM_SIZE, OP_TX_VOTE_AGG_ESTIM_SIZE,
+    BitcoinWalletMode, BurnchainConfig, CoinSelectionStrategy, OP_TX_ANY_ESTIM_SIZE,
+    OP_TX_DELEGATE_STACKS_ESTIM_SIZE, OP_TX_PRE_STACKS_ESTIM_SIZE, OP_TX_STACK_STX_ESTIM_SIZE,
+    OP_TX_TRANSFER_STACKS_ESTIM_SIZE, OP_TX_VOTE_AGG_ESTIM_SIZE,
 };
 use stacks::core::{EpochList, StacksEpochId};
-use stacks::monitoring::{increment_btc_blocks_received_counter, increment_btc_ops_sent_counter};
+use stacks::monitoring::{
+    increment_btc_blocks_received_counter, increment_btc_ops_sent_counter,
+    increment_miner_commit_fee_cap_reached_counter, increment_miner_commit_outbid_counter,
+    update_miner_commit_fee_rate,
+};
+use stacks::net::api::getminerutxos::MinerUtxoStatus;
 use stacks::net::http::{HttpRequestContents, HttpResponsePayload};
 use stacks::net::httpcore::{send_http_request, StacksHttpRequest};
 use stacks::net::Error as NetError;
@@ -66,9 +68,9 @@ use stacks_common::deps_common::bitcoin::blockdata::transaction::{
     OutPoint, Transaction, TxIn, TxOut,
 };
 use stacks_common::deps_common::bitcoin::network::encodable::ConsensusEncodable;
-#[cfg(test)]
-use stacks_common::deps_common::bitcoin::network::serialize::deserialize as btc_deserialize;
-use stacks_common::deps_common::bitcoin::network::serialize::RawEncoder;
+use stacks_common::deps_common::bitcoin::network::serialize::{
+    deserialize as btc_deserialize, RawEncoder,
+};
 use stacks_common::deps_common::bitcoin::util::hash::Sha256dHash;
 use stacks_common::types::chainstate::BurnchainHeaderHash;
 use stacks_common::types::net::PeerHost;
@@ -79,6 +81,12 @@ use url::Url;
 
 use super::super::operations::BurnchainOpSigner;
 use super::super::Config;
+use super::commit_fee_policy::{
+    is_block_commit_tx, CommitFeePolicy, CompetingCommitTracker, MempoolEntry,
+};
+use super::mock_bitcoin::{submit_mocknet_operation, sync_mocknet_burnchain};
+use super::utxo_manager::{select_coins, UtxoManager};
+use super::wallet::{add_placeholder_signatures, funding_tx_out, run_signer_command};
 use super::{BurnchainController, BurnchainTip, Error as BurnchainControllerError};
 
 /// The number of bitcoin blocks that can have
@@ -102,6 +110,8 @@ pub struct BitcoinRegtestController {
     ongoing_block_commit: Option<OngoingBlockCommit>,
     should_keep_running: Option<Arc<AtomicBool>>,
     allow_rbf: bool,
+    competing_commits: CompetingCommitTracker,
+    utxo_manager: UtxoManager,
 }
 
 #[derive(Clone)]
@@ -190,6 +200,7 @@ pub fn make_bitcoin_indexer(
         config: indexer_config,
         runtime: indexer_runtime,
         should_keep_running,
+        header_sync_tracker: None,
     }
 }
 
@@ -197,14 +208,6 @@ pub fn get_satoshis_per_byte(config: &Config) -> u64 {
     config.get_burnchain_config().satoshis_per_byte
 }
 
-pub fn get_rbf_fee_increment(config: &Config) -> u64 {
-    config.get_burnchain_config().rbf_fee_increment
-}
-
-pub fn get_max_rbf(config: &Config) -> u64 {
-    config.get_burnchain_config().max_rbf
-}
-
 impl LeaderBlockCommitFees {
     pub fn fees_from_previous_tx(
         &self,
@@ -214,7 +217,6 @@ impl LeaderBlockCommitFees {
         let mut fees = LeaderBlockCommitFees::estimated_fees_from_payload(payload, config);
         fees.spent_in_attempts = cmp::max(1, self.spent_in_attempts);
         fees.final_size = self.final_size;
-        fees.fee_rate = self.fee_rate + get_rbf_fee_increment(config);
         fees.is_rbf_enabled = true;
         fees
     }
@@ -351,6 +353,7 @@ impl BitcoinRegtestController {
             config: indexer_config,
             runtime: indexer_runtime,
             should_keep_running: should_keep_running.clone(),
+            header_sync_tracker: None,
         };
 
         Self {
@@ -364,6 +367,8 @@ impl BitcoinRegtestController {
             ongoing_block_commit: None,
             should_keep_running,
             allow_rbf: true,
+            competing_commits: CompetingCommitTracker::new(),
+            utxo_manager: UtxoManager::new(),
         }
     }
 
@@ -397,6 +402,7 @@ impl BitcoinRegtestController {
             config: indexer_config,
             runtime: indexer_runtime,
             should_keep_running: None,
+            header_sync_tracker: None,
         };
 
         Self {
@@ -410,6 +416,8 @@ impl BitcoinRegtestController {
             ongoing_block_commit: None,
             should_keep_running: None,
             allow_rbf: true,
+            competing_commits: CompetingCommitTracker::new(),
+            utxo_manager: UtxoManager::new(),
         }
     }
 
@@ -420,6 +428,17 @@ impl BitcoinRegtestController {
         ret
     }
 
+    /// Report the miner's UTXOs through `status` (served at `/v3/miner/utxos`)
+    pub fn set_utxo_status(&mut self, status: Arc<Mutex<MinerUtxoStatus>>) {
+        self.utxo_manager.set_status(status);
+    }
+
+    /// Report the progress of Bitcoin header downloads through `tracker` (served at
+    /// `/v3/burnchain/sync-status`)
+    pub fn set_header_sync_tracker(&mut self, tracker: HeaderSyncTracker) {
+        self.indexer.header_sync_tracker = Some(tracker);
+    }
+
     /// Get an owned copy of the ongoing block commit state
     pub fn get_ongoing_commit(&self) -> Option<OngoingBlockCommit> {
         self.ongoing_block_commit.clone()
@@ -532,13 +551,25 @@ impl BitcoinRegtestController {
                 return Err(BurnchainControllerError::CoordinatorClosed);
             }
 
-            match burnchain.sync_with_indexer(
-                &mut self.indexer,
-                coordinator_comms.clone(),
-                target_block_height_opt,
-                Some(burnchain.pox_constants.reward_cycle_length as u64),
-                self.should_keep_running.clone(),
-            ) {
+            let sync_result = if self.config.burnchain.is_mocknet() {
+                // only sync() mines mocknet blocks; start() just opens the burnchain
+                sync_mocknet_burnchain(
+                    &self.config,
+                    &burnchain,
+                    &mut self.indexer,
+                    &coordinator_comms,
+                    block_for_sortitions,
+                )
+            } else {
+                burnchain.sync_with_indexer(
+                    &mut self.indexer,
+                    coordinator_comms.clone(),
+                    target_block_height_opt,
+                    Some(burnchain.pox_constants.reward_cycle_length as u64),
+                    self.should_keep_running.clone(),
+                )
+            };
+            match sync_result {
                 Ok(x) => {
                     increment_btc_blocks_received_counter();
 
@@ -726,11 +757,18 @@ impl BitcoinRegtestController {
     }
 
     /// Checks if the config-supplied wallet exists.
-    /// If it does not exist, this function creates it.
+    /// If it does not exist, this function creates it (as a watch-only wallet), unless it is
+    /// meant to be a descriptor wallet holding the miner's keys.
     pub fn create_wallet_if_dne(&self) -> RPCResult<()> {
         let wallets = BitcoinRPCRequest::list_wallets(&self.config)?;
 
         if !wallets.contains(&self.config.burnchain.wallet_name) {
+            if self.config.burnchain.wallet_mode == BitcoinWalletMode::Descriptor {
+                return Err(RPCError::Bitcoind(format!(
+                    "Descriptor wallet '{}' is not loaded in bitcoind",
+                    &self.config.burnchain.wallet_name
+                )));
+            }
             BitcoinRPCRequest::create_wallet(&self.config, &self.config.burnchain.wallet_name)?;
         }
         Ok(())
@@ -744,35 +782,100 @@ impl BitcoinRegtestController {
         utxos_to_exclude: Option<UTXOSet>,
         block_height: u64,
     ) -> Option<UTXOSet> {
-        // if mock mining, do not even bother requesting UTXOs
-        if self.config.get_node_config(false).mock_mining {
-            return None;
+        let pubk = self.get_utxo_public_key(epoch_id, public_key);
+
+        // Configure UTXO filter
+        let address = self.get_miner_address(epoch_id, &pubk);
+        test_debug!("Get UTXOs for {}", pubk.to_hex());
+        self.get_utxos_at_address(
+            &address,
+            Some(&pubk),
+            total_required,
+            total_required,
+            utxos_to_exclude,
+            block_height,
+        )
+    }
+
+    /// Get the form of `public_key` whose address holds the miner's UTXOs
+    fn get_utxo_public_key(
+        &self,
+        epoch_id: StacksEpochId,
+        public_key: &Secp256k1PublicKey,
+    ) -> Secp256k1PublicKey {
+        let mut pubk = *public_key;
+        if self.config.miner.segwit && epoch_id >= StacksEpochId::Epoch21 {
+            pubk.set_compressed(true);
         }
+        pubk
+    }
 
-        let pubk = if self.config.miner.segwit && epoch_id >= StacksEpochId::Epoch21 {
-            let mut p = *public_key;
-            p.set_compressed(true);
-            p
+    /// Get UTXOs to fund the miner's transactions.  These are at the wallet's funding address if
+    /// the wallet funds the miner, and at the address of `public_key` otherwise.  Unless the
+    /// miner selects coins newest-first, all of its UTXOs are fetched, not just those which can
+    /// fund a transaction alone (see `utxo_manager`).
+    pub fn get_miner_utxos(
+        &self,
+        epoch_id: StacksEpochId,
+        public_key: &Secp256k1PublicKey,
+        total_required: u64,
+        utxos_to_exclude: Option<UTXOSet>,
+        block_height: u64,
+    ) -> Option<UTXOSet> {
+        let (address, pubk) = match self.config.burnchain.get_wallet_funding_address() {
+            Some(address) => (address, None),
+            None => {
+                let pubk = self.get_utxo_public_key(epoch_id, public_key);
+                (self.get_miner_address(epoch_id, &pubk), Some(pubk))
+            }
+        };
+        let min_utxo_amount = if UtxoManager::fetch_all_utxos(&self.config) {
+            DUST_UTXO_LIMIT
         } else {
-            *public_key
+            total_required
         };
+        let mut utxos = self.get_utxos_at_address(
+            &address,
+            pubk.as_ref(),
+            total_required,
+            min_utxo_amount,
+            utxos_to_exclude,
+            block_height,
+        )?;
 
-        // Configure UTXO filter
-        let address = self.get_miner_address(epoch_id, &pubk);
-        test_debug!(
-            "Get UTXOs for {} ({}) rbf={}",
-            pubk.to_hex(),
-            addr2str(&address),
-            self.allow_rbf
-        );
-        let filter_addresses = vec![addr2str(&address)];
+        let strategy = UtxoManager::strategy_for(&self.config, utxos.num_utxos());
+        utxos.selection = Some(strategy);
+        self.utxo_manager
+            .record_utxos(&self.config, addr2str(&address), strategy, &utxos.utxos);
+        Some(utxos)
+    }
+
+    /// Get UTXOs at `address` worth at least `total_required`, ignoring any worth less than
+    /// `min_utxo_amount`.  If `public_key` is given and this is a regtest node, the public key
+    /// is imported into bitcoind's wallet if no UTXOs are found at first.
+    fn get_utxos_at_address(
+        &self,
+        address: &BitcoinAddress,
+        public_key: Option<&Secp256k1PublicKey>,
+        total_required: u64,
+        min_utxo_amount: u64,
+        utxos_to_exclude: Option<UTXOSet>,
+        block_height: u64,
+    ) -> Option<UTXOSet> {
+        // if mock mining, do not even bother requesting UTXOs
+        if self.config.get_node_config(false).mock_mining {
+            return None;
+        }
+
+        test_debug!("Get UTXOs for {} rbf={}", addr2str(address), self.allow_rbf);
+        let filter_addresses = vec![addr2str(address)];
 
         let mut utxos = loop {
             let result = BitcoinRPCRequest::list_unspent(
                 &self.config,
                 filter_addresses.clone(),
                 !self.allow_rbf, // if RBF is disabled, then we can use 0-conf txs
-                total_required,
+                min_utxo_amount,
                 &utxos_to_exclude,
                 block_height,
             );
@@ -793,12 +896,12 @@ impl BitcoinRegtestController {
         let utxos = if utxos.is_empty() {
             let (_, network) = self.config.burnchain.get_bitcoin_network();
             loop {
-                if let BitcoinNetworkType::Regtest = network {
+                if let (BitcoinNetworkType::Regtest, Some(pubk)) = (network, public_key) {
                     // Performing this operation on Mainnet / Testnet is very expensive, and can be longer than bitcoin block time.
                     // Assuming that miners are in charge of correctly operating their bitcoind nodes sounds
                     // reasonable to me.
                     // $ bitcoin-cli importaddress mxVFsFW5N4mu1HPkxPttorvocvzeZ7KZyk
-                    let _result = BitcoinRPCRequest::import_public_key(&self.config, &pubk);
+                    let _result = BitcoinRPCRequest::import_public_key(&self.config, pubk);
                     sleep_ms(1000);
                 }
 
@@ -806,7 +909,7 @@ impl BitcoinRegtestController {
                     &self.config,
                     filter_addresses.clone(),
                     !self.allow_rbf, // if RBF is disabled, then we can use 0-conf txs
-                    total_required,
+                    min_utxo_amount,
                     &utxos_to_exclude,
                     block_height,
                 );
@@ -836,8 +939,8 @@ impl BitcoinRegtestController {
         let total_unspent = utxos.total_available();
         if total_unspent < total_required {
             warn!(
-                "Total unspent {total_unspent} < {total_required} for {:?}",
-                &pubk.to_hex()
+                "Total unspent {total_unspent} < {total_required} for {}",
+                addr2str(address)
             );
             return None;
         }
@@ -861,7 +964,7 @@ impl BitcoinRegtestController {
         let total_required = btc_miner_fee + budget_for_outputs;
 
         let (mut tx, mut utxos) =
-            self.prepare_tx(epoch_id, &public_key, total_required, None, None, 0)?;
+            self.prepare_tx(epoch_id, &public_key, total_required, None, None, 0, true)?;
 
         // Serialize the payload
         let op_bytes = {
@@ -896,7 +999,7 @@ impl BitcoinRegtestController {
             &mut utxos,
             signer,
             true, // key register op requires change output to exist
-        );
+        )?;
 
         increment_btc_ops_sent_counter();
 
@@ -908,29 +1011,10 @@ impl BitcoinRegtestController {
         Ok(tx)
     }
 
-    #[cfg(not(test))]
-    fn build_transfer_stacks_tx(
-        &mut self,
-        _epoch_id: StacksEpochId,
-        _payload: TransferStxOp,
-        _signer: &mut BurnchainOpSigner,
-        _utxo: Option<UTXO>,
-    ) -> Result<Transaction, BurnchainControllerError> {
-        unimplemented!()
-    }
-
-    #[cfg(not(test))]
-    fn build_delegate_stacks_tx(
-        &mut self,
-        _epoch_id: StacksEpochId,
-        _payload: DelegateStxOp,
-        _signer: &mut BurnchainOpSigner,
-        _utxo: Option<UTXO>,
-    ) -> Result<Transaction, BurnchainControllerError> {
-        unimplemented!()
-    }
-
-    #[cfg(test)]
+    /// Build, sign, and send a Stacks-on-Bitcoin operation which a user (not a miner) would
+    /// submit.  If `utxo` is given, then the operation's transaction will spend it; this is
+    /// required for operations which must spend the output of a `PreStxOp`.
+    /// Returns the transaction that was sent.
     pub fn submit_manual(
         &mut self,
         epoch_id: StacksEpochId,
@@ -940,11 +1024,10 @@ impl BitcoinRegtestController {
     ) -> Result<Transaction, BurnchainControllerError> {
         let transaction = match operation {
             BlockstackOperationType::LeaderBlockCommit(_)
-            | BlockstackOperationType::LeaderKeyRegister(_)
-            | BlockstackOperationType::StackStx(_)
-            | BlockstackOperationType::DelegateStx(_)
-            | BlockstackOperationType::VoteForAggregateKey(_) => {
-                unimplemented!();
+            | BlockstackOperationType::LeaderKeyRegister(_) => {
+                return Err(BurnchainControllerError::UnsupportedOperation(
+                    "miner operations must be submitted through the miner".into(),
+                ));
             }
             BlockstackOperationType::PreStx(payload) => {
                 self.build_pre_stacks_tx(epoch_id, payload, op_signer)
@@ -952,6 +1035,18 @@ impl BitcoinRegtestController {
             BlockstackOperationType::TransferStx(payload) => {
                 self.build_transfer_stacks_tx(epoch_id, payload, op_signer, utxo)
             }
+            BlockstackOperationType::StackStx(payload) => {
+                self.build_stack_stx_tx(epoch_id, payload, op_signer, utxo)
+            }
+            BlockstackOperationType::DelegateStx(payload) => {
+                self.build_delegate_stacks_tx(epoch_id, payload, op_signer, utxo)
+            }
+            BlockstackOperationType::VoteForAggregateKey(payload) => {
+                self.build_vote_for_aggregate_key_tx(epoch_id, payload, op_signer, utxo)
+            }
+            BlockstackOperationType::StackAggregationCommit(payload) => {
+                self.build_stack_aggregation_commit_tx(epoch_id, payload, op_signer, utxo)
+            }
         }?;
 
         let ser_transaction = SerializedTx::new(transaction.clone());
@@ -959,13 +1054,64 @@ impl BitcoinRegtestController {
         self.send_transaction(ser_transaction).map(|_| transaction)
     }
 
-    #[cfg(test)]
-    /// Build a transfer stacks tx.
-    ///   this *only* works if the only existant UTXO is from a PreStx Op
-    ///   this is okay for testing, but obviously not okay for actual use.
-    ///   The reason for this constraint is that the bitcoin_regtest_controller's UTXO
-    ///     and signing logic are fairly intertwined, and untangling the two seems excessive
-    ///     for a functionality that won't be implemented for production via this controller.
+    /// Start a transaction which spends `pre_stx_utxo`, the output of a PreStx op.  Every
+    /// operation which a PreStx op authorizes must spend its output as the transaction's only
+    /// input, since the signer's other UTXOs may fund unrelated operations.
+    fn prepare_pre_stx_spend_tx(
+        &mut self,
+        epoch_id: StacksEpochId,
+        public_key: &Secp256k1PublicKey,
+        max_tx_size: u64,
+        pre_stx_utxo: Option<UTXO>,
+    ) -> Result<(Transaction, UTXOSet), BurnchainControllerError> {
+        let total_required = DUST_UTXO_LIMIT + max_tx_size * get_satoshis_per_byte(&self.config);
+        let Some(utxo) = pre_stx_utxo else {
+            // the integration tests submit these operations from addresses whose only UTXO is
+            // the PreStx op's output
+            #[cfg(test)]
+            return self.prepare_tx(epoch_id, public_key, total_required, None, None, 0, false);
+            #[cfg(not(test))]
+            return Err(BurnchainControllerError::UnsupportedOperation(
+                "operation must spend the output of a pre-stx operation".into(),
+            ));
+        };
+
+        let signer_script_pubkey =
+            funding_tx_out(&self.get_miner_address(epoch_id, public_key), 0).script_pubkey;
+        if utxo.script_pub_key != signer_script_pubkey {
+            return Err(BurnchainControllerError::TransactionSubmissionFailed(
+                format!(
+                    "Pre-stx output {}:{} is not spendable by {}",
+                    utxo.txid,
+                    utxo.vout,
+                    public_key.to_hex()
+                ),
+            ));
+        }
+        if utxo.amount < total_required {
+            return Err(BurnchainControllerError::TransactionSubmissionFailed(
+                format!(
+                    "Pre-stx output {}:{} holds {} sats, but the operation needs {total_required}",
+                    utxo.txid, utxo.vout, utxo.amount
+                ),
+            ));
+        }
+
+        let tx = Transaction {
+            input: vec![],
+            output: vec![],
+            version: 1,
+            lock_time: 0,
+        };
+        let utxos = UTXOSet {
+            bhh: BurnchainHeaderHash::zero(),
+            utxos: vec![utxo],
+            selection: None,
+        };
+        Ok((tx, utxos))
+    }
+
+    /// Build a transfer stacks tx, which spends the output of the sender's PreStx op
     fn build_transfer_stacks_tx(
         &mut self,
         epoch_id: StacksEpochId,
@@ -975,29 +1121,8 @@ impl BitcoinRegtestController {
     ) -> Result<Transaction, BurnchainControllerError> {
         let public_key = signer.get_public_key();
         let max_tx_size = OP_TX_TRANSFER_STACKS_ESTIM_SIZE;
-        let (mut tx, mut utxos) = if let Some(utxo) = utxo_to_use {
-            (
-                Transaction {
-                    input: vec![],
-                    output: vec![],
-                    version: 1,
-                    lock_time: 0,
-                },
-                UTXOSet {
-                    bhh: BurnchainHeaderHash::zero(),
-                    utxos: vec![utxo],
-                },
-            )
-        } else {
-            self.prepare_tx(
-                epoch_id,
-                &public_key,
-                DUST_UTXO_LIMIT + max_tx_size * get_satoshis_per_byte(&self.config),
-                None,
-                None,
-                0,
-            )?
-        };
+        let (mut tx, mut utxos) =
+            self.prepare_pre_stx_spend_tx(epoch_id, &public_key, max_tx_size, utxo_to_use)?;
 
         // Serialize the payload
         let op_bytes = {
@@ -1030,7 +1155,7 @@ impl BitcoinRegtestController {
             &mut utxos,
             signer,
             false,
-        );
+        )?;
 
         increment_btc_ops_sent_counter();
 
@@ -1042,13 +1167,7 @@ impl BitcoinRegtestController {
         Ok(tx)
     }
 
-    #[cfg(test)]
-    /// Build a delegate stacks tx.
-    ///   this *only* works if the only existant UTXO is from a PreStx Op
-    ///   this is okay for testing, but obviously not okay for actual use.
-    ///   The reason for this constraint is that the bitcoin_regtest_controller's UTXO
-    ///     and signing logic are fairly intertwined, and untangling the two seems excessive
-    ///     for a functionality that won't be implemented for production via this controller.
+    /// Build a delegate stacks tx, which spends the output of the sender's PreStx op
     fn build_delegate_stacks_tx(
         &mut self,
         epoch_id: StacksEpochId,
@@ -1059,29 +1178,8 @@ impl BitcoinRegtestController {
         let public_key = signer.get_public_key();
         let max_tx_size = OP_TX_DELEGATE_STACKS_ESTIM_SIZE;
 
-        let (mut tx, mut utxos) = if let Some(utxo) = utxo_to_use {
-            (
-                Transaction {
-                    input: vec![],
-                    output: vec![],
-                    version: 1,
-                    lock_time: 0,
-                },
-                UTXOSet {
-                    bhh: BurnchainHeaderHash::zero(),
-                    utxos: vec![utxo],
-                },
-            )
-        } else {
-            self.prepare_tx(
-                epoch_id,
-                &public_key,
-                DUST_UTXO_LIMIT + max_tx_size * get_satoshis_per_byte(&self.config),
-                None,
-                None,
-                0,
-            )?
-        };
+        let (mut tx, mut utxos) =
+            self.prepare_pre_stx_spend_tx(epoch_id, &public_key, max_tx_size, utxo_to_use)?;
 
         // Serialize the payload
         let op_bytes = {
@@ -1115,7 +1213,7 @@ impl BitcoinRegtestController {
             &mut utxos,
             signer,
             false,
-        );
+        )?;
 
         increment_btc_ops_sent_counter();
 
@@ -1127,7 +1225,6 @@ impl BitcoinRegtestController {
         Ok(tx)
     }
 
-    #[cfg(test)]
     /// Build a vote-for-aggregate-key burn op tx
     fn build_vote_for_aggregate_key_tx(
         &mut self,
@@ -1139,29 +1236,8 @@ impl BitcoinRegtestController {
         let public_key = signer.get_public_key();
         let max_tx_size = OP_TX_VOTE_AGG_ESTIM_SIZE;
 
-        let (mut tx, mut utxos) = if let Some(utxo) = utxo_to_use {
-            (
-                Transaction {
-                    input: vec![],
-                    output: vec![],
-                    version: 1,
-                    lock_time: 0,
-                },
-                UTXOSet {
-                    bhh: BurnchainHeaderHash::zero(),
-                    utxos: vec![utxo],
-                },
-            )
-        } else {
-            self.prepare_tx(
-                epoch_id,
-                &public_key,
-                DUST_UTXO_LIMIT + max_tx_size * get_satoshis_per_byte(&self.config),
-                None,
-                None,
-                0,
-            )?
-        };
+        let (mut tx, mut utxos) =
+            self.prepare_pre_stx_spend_tx(epoch_id, &public_key, max_tx_size, utxo_to_use)?;
 
         // Serialize the payload
         let op_bytes = {
@@ -1192,7 +1268,7 @@ impl BitcoinRegtestController {
             &mut utxos,
             signer,
             false,
-        );
+        )?;
 
         increment_btc_ops_sent_counter();
 
@@ -1204,29 +1280,6 @@ impl BitcoinRegtestController {
         Ok(tx)
     }
 
-    #[cfg(not(test))]
-    /// Build a vote-for-aggregate-key burn op tx
-    fn build_vote_for_aggregate_key_tx(
-        &mut self,
-        _epoch_id: StacksEpochId,
-        _payload: VoteForAggregateKeyOp,
-        _signer: &mut BurnchainOpSigner,
-        _utxo_to_use: Option<UTXO>,
-    ) -> Result<Transaction, BurnchainControllerError> {
-        unimplemented!()
-    }
-
-    #[cfg(not(test))]
-    fn build_pre_stacks_tx(
-        &mut self,
-        _epoch_id: StacksEpochId,
-        _payload: PreStxOp,
-        _signer: &mut BurnchainOpSigner,
-    ) -> Result<Transaction, BurnchainControllerError> {
-        unimplemented!()
-    }
-
-    #[cfg(test)]
     fn build_pre_stacks_tx(
         &mut self,
         epoch_id: StacksEpochId,
@@ -1240,7 +1293,7 @@ impl BitcoinRegtestController {
         let output_amt = DUST_UTXO_LIMIT + max_tx_size_any_op * get_satoshis_per_byte(&self.config);
 
         let (mut tx, mut utxos) =
-            self.prepare_tx(epoch_id, &public_key, output_amt, None, None, 0)?;
+            self.prepare_tx(epoch_id, &public_key, output_amt, None, None, 0, false)?;
 
         // Serialize the payload
         let op_bytes = {
@@ -1271,7 +1324,7 @@ impl BitcoinRegtestController {
             &mut utxos,
             signer,
             false,
-        );
+        )?;
 
         increment_btc_ops_sent_counter();
 
@@ -1283,52 +1336,76 @@ impl BitcoinRegtestController {
         Ok(tx)
     }
 
-    #[cfg_attr(test, mutants::skip)]
-    #[cfg(not(test))]
     fn build_stack_stx_tx(
         &mut self,
-        _epoch_id: StacksEpochId,
-        _payload: StackStxOp,
-        _signer: &mut BurnchainOpSigner,
-        _utxo_to_use: Option<UTXO>,
+        epoch_id: StacksEpochId,
+        payload: StackStxOp,
+        signer: &mut BurnchainOpSigner,
+        utxo_to_use: Option<UTXO>,
     ) -> Result<Transaction, BurnchainControllerError> {
-        unimplemented!()
+        let public_key = signer.get_public_key();
+        let max_tx_size = OP_TX_STACK_STX_ESTIM_SIZE;
+
+        let (mut tx, mut utxos) =
+            self.prepare_pre_stx_spend_tx(epoch_id, &public_key, max_tx_size, utxo_to_use)?;
+
+        // Serialize the payload
+        let op_bytes = {
+            let mut bytes = self.config.burnchain.magic_bytes.as_bytes().to_vec();
+            payload
+                .consensus_serialize(&mut bytes)
+                .map_err(BurnchainControllerError::SerializerError)?;
+            bytes
+        };
+
+        let consensus_output = TxOut {
+            value: 0,
+            script_pubkey: Builder::new()
+                .push_opcode(opcodes::All::OP_RETURN)
+                .push_slice(&op_bytes)
+                .into_script(),
+        };
+
+        tx.output = vec![consensus_output];
+        tx.output
+            .push(payload.reward_addr.to_bitcoin_tx_out(DUST_UTXO_LIMIT));
+
+        self.finalize_tx(
+            epoch_id,
+            &mut tx,
+            DUST_UTXO_LIMIT,
+            0,
+            max_tx_size,
+            get_satoshis_per_byte(&self.config),
+            &mut utxos,
+            signer,
+            false,
+        )?;
+
+        increment_btc_ops_sent_counter();
+
+        info!(
+            "Miner node: submitting stack-stx op - {}",
+            public_key.to_hex()
+        );
+
+        Ok(tx)
     }
 
-    #[cfg(test)]
-    fn build_stack_stx_tx(
+    /// Build a stack-aggregation-commit burn op tx.  The PoX address to commit to is the
+    /// transaction's first non-OP_RETURN output.
+    fn build_stack_aggregation_commit_tx(
         &mut self,
         epoch_id: StacksEpochId,
-        payload: StackStxOp,
+        payload: StackAggregationCommitOp,
         signer: &mut BurnchainOpSigner,
         utxo_to_use: Option<UTXO>,
     ) -> Result<Transaction, BurnchainControllerError> {
         let public_key = signer.get_public_key();
         let max_tx_size = OP_TX_STACK_STX_ESTIM_SIZE;
 
-        let (mut tx, mut utxos) = if let Some(utxo) = utxo_to_use {
-            (
-                Transaction {
-                    input: vec![],
-                    output: vec![],
-                    version: 1,
-                    lock_time: 0,
-                },
-                UTXOSet {
-                    bhh: BurnchainHeaderHash::zero(),
-                    utxos: vec![utxo],
-                },
-            )
-        } else {
-            self.prepare_tx(
-                epoch_id,
-                &public_key,
-                DUST_UTXO_LIMIT + max_tx_size * get_satoshis_per_byte(&self.config),
-                None,
-                None,
-                0,
-            )?
-        };
+        let (mut tx, mut utxos) =
+            self.prepare_pre_stx_spend_tx(epoch_id, &public_key, max_tx_size, utxo_to_use)?;
 
         // Serialize the payload
         let op_bytes = {
@@ -1361,12 +1438,12 @@ impl BitcoinRegtestController {
             &mut utxos,
             signer,
             false,
-        );
+        )?;
 
         increment_btc_ops_sent_counter();
 
         info!(
-            "Miner node: submitting stack-stx op - {}",
+            "Miner node: submitting stack-aggregation-commit op - {}",
             public_key.to_hex()
         );
 
@@ -1396,6 +1473,7 @@ impl BitcoinRegtestController {
         utxos_to_exclude: Option<UTXOSet>,
         previous_fees: Option<LeaderBlockCommitFees>,
         previous_txids: &[Txid],
+        fee_rate: u64,
     ) -> Result<Transaction, BurnchainControllerError> {
         let _ = self.sortdb_mut();
         let burn_chain_tip = self
@@ -1404,10 +1482,11 @@ impl BitcoinRegtestController {
             .ok_or(BurnchainControllerError::BurnchainError)?
             .get_canonical_chain_tip()
             .map_err(|_| BurnchainControllerError::BurnchainError)?;
-        let estimated_fees = match previous_fees {
+        let mut estimated_fees = match previous_fees {
             Some(fees) => fees.fees_from_previous_tx(&payload, &self.config),
             None => LeaderBlockCommitFees::estimated_fees_from_payload(&payload, &self.config),
         };
+        estimated_fees.fee_rate = fee_rate;
 
         self.send_block_commit_operation_at_burnchain_height(
             epoch_id,
@@ -1441,6 +1520,7 @@ impl BitcoinRegtestController {
             utxos_to_include,
             utxos_to_exclude,
             burnchain_block_height,
+            true,
         )?;
 
         // Serialize the payload
@@ -1480,7 +1560,7 @@ impl BitcoinRegtestController {
             &mut utxos,
             signer,
             true, // block commit op requires change output to exist
-        );
+        )?;
 
         let serialized_tx = SerializedTx::new(tx.clone());
 
@@ -1511,6 +1591,7 @@ impl BitcoinRegtestController {
         self.ongoing_block_commit = Some(ongoing_block_commit);
 
         increment_btc_ops_sent_counter();
+        update_miner_commit_fee_rate(i64::try_from(fee_rate).unwrap_or(i64::MAX));
 
         Ok(tx)
     }
@@ -1522,15 +1603,28 @@ impl BitcoinRegtestController {
         signer: &mut BurnchainOpSigner,
         _attempt: u64,
     ) -> Result<Transaction, BurnchainControllerError> {
+        let fee_policy = CommitFeePolicy::from_config(&self.config);
+
         // Are we currently tracking an operation?
         if self.ongoing_block_commit.is_none() || !self.allow_rbf {
             // Good to go, let's build the transaction and send it.
-            let res =
-                self.send_block_commit_operation(epoch_id, payload, signer, None, None, None, &[]);
+            let competing_fee_rate = self.get_competing_commit_fee_rate(&fee_policy, &[]);
+            let res = self.send_block_commit_operation(
+                epoch_id,
+                payload,
+                signer,
+                None,
+                None,
+                None,
+                &[],
+                fee_policy.initial_fee_rate(competing_fee_rate),
+            );
             return res;
         }
 
         let ongoing_op = self.ongoing_block_commit.take().unwrap();
+        let competing_fee_rate = self.get_competing_commit_fee_rate(&fee_policy, &ongoing_op.txids);
+        let initial_fee_rate = fee_policy.initial_fee_rate(competing_fee_rate);
 
         let _ = self.sortdb_mut();
         let burnchain_db = self.burnchain_db.as_ref().expect("BurnchainDB not opened");
@@ -1553,6 +1647,7 @@ impl BitcoinRegtestController {
                     None,
                     None,
                     &[],
+                    initial_fee_rate,
                 );
                 return res;
             } else {
@@ -1587,25 +1682,46 @@ impl BitcoinRegtestController {
                 "Possible presence of fork or stale UTXO cache, invalidating cached set of UTXOs.";
                 "cached_burn_block_hash" => %ongoing_op.utxos.bhh,
             );
-            let res =
-                self.send_block_commit_operation(epoch_id, payload, signer, None, None, None, &[]);
+            let res = self.send_block_commit_operation(
+                epoch_id,
+                payload,
+                signer,
+                None,
+                None,
+                None,
+                &[],
+                initial_fee_rate,
+            );
             return res;
         }
 
-        // Stop as soon as the fee_rate is ${self.config.burnchain.max_rbf} percent higher, stop RBF
-        if ongoing_op.fees.fee_rate
-            > (get_satoshis_per_byte(&self.config) * get_max_rbf(&self.config) / 100)
-        {
+        let outbid = fee_policy.is_outbid(ongoing_op.fees.fee_rate, competing_fee_rate);
+        if outbid {
+            increment_miner_commit_outbid_counter();
+            info!(
+                "Ongoing leader block commit was outbid by a competing commit";
+                "fee_rate" => ongoing_op.fees.fee_rate,
+                "competing_fee_rate" => ?competing_fee_rate,
+                "ongoing_txids" => ?ongoing_op.txids
+            );
+        }
+
+        // Stop RBF once the fee rate reaches the policy's cap
+        let Some(replacement_fee_rate) =
+            fee_policy.replacement_fee_rate(ongoing_op.fees.fee_rate, competing_fee_rate)
+        else {
             warn!(
-                "RBF'd block commits reached {}% satoshi per byte fee rate, not resubmitting",
-                get_max_rbf(&self.config)
+                "RBF'd block commits reached the maximum fee rate of {} sats/vbyte, not resubmitting",
+                fee_policy.max_fee_rate
             );
+            increment_miner_commit_fee_cap_reached_counter();
             self.ongoing_block_commit = Some(ongoing_op);
             return Err(BurnchainControllerError::MaxFeeRateExceeded);
-        }
+        };
 
         // An ongoing operation is in the mempool and we received a new block. The desired behaviour is the following:
-        // 1) If the ongoing and the incoming operation are **strictly** identical, we will be idempotent and discard the incoming.
+        // 1) If the ongoing and the incoming operation are **strictly** identical, we will be idempotent and discard the incoming,
+        //  unless a competing commit outbid the ongoing one, in which case we RBF it.
         // 2) If the 2 operations are different, we will try to avoid wasting UTXOs, and attempt to RBF the outgoing transaction:
         //  i) If UTXOs are insufficient,
         //    a) If no other UTXOs, we'll have to wait on the ongoing operation to be mined before resuming operation.
@@ -1613,7 +1729,8 @@ impl BitcoinRegtestController {
         //  ii) If UTXOs initially used are sufficient for paying for a fee bump, then RBF
 
         // Let's start by early returning 1)
-        if payload == ongoing_op.payload {
+        let identical = payload == ongoing_op.payload;
+        if identical && !outbid {
             info!("Abort attempt to re-submit identical LeaderBlockCommit");
             self.ongoing_block_commit = Some(ongoing_op);
             return Err(BurnchainControllerError::IdenticalOperation);
@@ -1621,6 +1738,12 @@ impl BitcoinRegtestController {
 
         // Let's proceed and early return 2) i)
         let res = if ongoing_op.fees.estimated_amount_required() > ongoing_op.sum_utxos() {
+            if identical {
+                // a second copy of the same commit would only waste BTC
+                info!("Cannot afford to replace outbid leader block commit");
+                self.ongoing_block_commit = Some(ongoing_op);
+                return Err(BurnchainControllerError::IdenticalOperation);
+            }
             // Try to build and submit op, excluding UTXOs currently used
             info!("Attempt to submit another leader_block_commit, despite an ongoing (outdated) commit");
             self.send_block_commit_operation(
@@ -1631,12 +1754,14 @@ impl BitcoinRegtestController {
                 Some(ongoing_op.utxos.clone()),
                 None,
                 &[],
+                initial_fee_rate,
             )
         } else {
             // Case 2) ii): Attempt to RBF
             info!(
                 "Attempt to replace by fee an outdated leader block commit";
-                "ongoing_txids" => ?ongoing_op.txids
+                "ongoing_txids" => ?ongoing_op.txids,
+                "fee_rate" => replacement_fee_rate
             );
             self.send_block_commit_operation(
                 epoch_id,
@@ -1646,6 +1771,7 @@ impl BitcoinRegtestController {
                 None,
                 Some(ongoing_op.fees.clone()),
                 &ongoing_op.txids,
+                replacement_fee_rate,
             )
         };
 
@@ -1656,6 +1782,32 @@ impl BitcoinRegtestController {
         res
     }
 
+    /// Get the highest fee rate paid by another miner's block commit in bitcoind's mempool, if
+    /// the fee policy tracks competing commits
+    fn get_competing_commit_fee_rate(
+        &mut self,
+        fee_policy: &CommitFeePolicy,
+        own_txids: &[Txid],
+    ) -> Option<u64> {
+        if !fee_policy.track_competing_commits {
+            return None;
+        }
+        let mempool = BitcoinRPCRequest::get_raw_mempool_entries(&self.config)
+            .inspect_err(|e| warn!("Failed to load bitcoind mempool: {e:?}"))
+            .ok()?;
+        let magic_bytes = self.magic_bytes();
+        let config = &self.config;
+        self.competing_commits
+            .highest_competing_fee_rate(&mempool, own_txids, |txid| {
+                let txstr = BitcoinRPCRequest::get_raw_transaction(config, txid).ok()?;
+                let is_commit = hex_bytes(&txstr)
+                    .ok()
+                    .and_then(|tx_bytes| btc_deserialize::<Transaction>(&tx_bytes).ok())
+                    .is_some_and(|tx| is_block_commit_tx(&tx, &magic_bytes));
+                Some(is_commit)
+            })
+    }
+
     pub(crate) fn get_miner_address(
         &self,
         epoch_id: StacksEpochId,
@@ -1678,8 +1830,11 @@ impl BitcoinRegtestController {
         }
     }
 
+    /// Fetch the UTXOs to spend in a new transaction.  If `is_miner_op` is true, the transaction
+    /// is funded by the miner's wallet (see `get_miner_utxos()`).
     // TODO: add tests from mutation testing results #4865
     #[cfg_attr(test, mutants::skip)]
+    #[allow(clippy::too_many_arguments)]
     fn prepare_tx(
         &mut self,
         epoch_id: StacksEpochId,
@@ -1688,20 +1843,35 @@ impl BitcoinRegtestController {
         utxos_to_include: Option<UTXOSet>,
         utxos_to_exclude: Option<UTXOSet>,
         block_height: u64,
+        is_miner_op: bool,
     ) -> Result<(Transaction, UTXOSet), BurnchainControllerError> {
         let utxos = if let Some(utxos) = utxos_to_include {
             // in RBF, you have to consume the same UTXOs
             utxos
         } else {
             // Fetch some UTXOs
-            let addr = self.get_miner_address(epoch_id, public_key);
-            match self.get_utxos(
-                epoch_id,
-                public_key,
-                total_required,
-                utxos_to_exclude,
-                block_height,
-            ) {
+            let addr = match self.config.burnchain.get_wallet_funding_address() {
+                Some(funding_address) if is_miner_op => funding_address,
+                _ => self.get_miner_address(epoch_id, public_key),
+            };
+            let utxos = if is_miner_op {
+                self.get_miner_utxos(
+                    epoch_id,
+                    public_key,
+                    total_required,
+                    utxos_to_exclude,
+                    block_height,
+                )
+            } else {
+                self.get_utxos(
+                    epoch_id,
+                    public_key,
+                    total_required,
+                    utxos_to_exclude,
+                    block_height,
+                )
+            };
+            match utxos {
                 Some(utxos) => utxos,
                 None => {
                     warn!(
@@ -1737,7 +1907,7 @@ impl BitcoinRegtestController {
         utxos_set: &mut UTXOSet,
         signer: &mut BurnchainOpSigner,
         force_change_output: bool,
-    ) {
+    ) -> Result<(), BurnchainControllerError> {
         // spend UTXOs in order by confirmations.  Spend the least-confirmed UTXO first, and in the
         // event of a tie, spend the smallest-value UTXO first.
         utxos_set.utxos.sort_by(|u1, u2| {
@@ -1786,7 +1956,85 @@ impl BitcoinRegtestController {
             signer,
             force_change_output,
         );
+        if self.wallet_funding_address_for(utxos_set).is_some() {
+            *tx = self.sign_with_wallet(tx)?;
+        }
+        if let Some(strategy) = utxos_set.selection {
+            let total_output: u64 = tx.output.iter().map(|output| output.value).sum();
+            let fee = utxos_set.total_available().saturating_sub(total_output);
+            self.utxo_manager
+                .record_selection(strategy, &utxos_set.utxos, fee);
+        }
         signer.dispose();
+        Ok(())
+    }
+
+    /// Get the miner wallet's funding address, if `utxos` are all held at it.  Transactions which
+    /// spend these UTXOs are signed by the wallet instead of by an op signer.
+    fn wallet_funding_address_for(&self, utxos: &UTXOSet) -> Option<BitcoinAddress> {
+        let address = self.config.burnchain.get_wallet_funding_address()?;
+        let script_pubkey = funding_tx_out(&address, 0).script_pubkey;
+        utxos
+            .utxos
+            .iter()
+            .all(|utxo| utxo.script_pub_key == script_pubkey)
+            .then_some(address)
+    }
+
+    /// Have the miner's wallet sign `tx`.  bitcoind fills in the details of the inputs it holds,
+    /// and then either signs the transaction itself (`descriptor` mode) or leaves it to
+    /// `wallet_signer_command` (`external` mode).
+    fn sign_with_wallet(&self, tx: &Transaction) -> Result<Transaction, BurnchainControllerError> {
+        let signing_failed =
+            |e: RPCError| BurnchainControllerError::SigningFailed(format!("{e:?}"));
+
+        let mut unsigned_tx = tx.clone();
+        for input in unsigned_tx.input.iter_mut() {
+            input.script_sig = Script::new();
+            input.witness.clear();
+        }
+        let psbt = BitcoinRPCRequest::convert_to_psbt(
+            &self.config,
+            &SerializedTx::new(unsigned_tx).to_hex(),
+        )
+        .map_err(signing_failed)?;
+
+        let sign_in_wallet = self.config.burnchain.wallet_mode == BitcoinWalletMode::Descriptor;
+        let psbt = BitcoinRPCRequest::wallet_process_psbt(&self.config, &psbt, sign_in_wallet)
+            .map_err(signing_failed)?;
+        let psbt = match self.config.burnchain.wallet_signer_command.as_deref() {
+            Some(command) if !sign_in_wallet => {
+                debug!("Sending PSBT to external wallet signer: {psbt}");
+                run_signer_command(command, &psbt)
+                    .map_err(BurnchainControllerError::SigningFailed)?
+            }
+            _ => psbt,
+        };
+        let signed_tx_hex =
+            BitcoinRPCRequest::finalize_psbt(&self.config, &psbt).map_err(signing_failed)?;
+
+        let signed_tx: Transaction = hex_bytes(&signed_tx_hex)
+            .ok()
+            .and_then(|tx_bytes| btc_deserialize(&tx_bytes).ok())
+            .ok_or_else(|| {
+                BurnchainControllerError::SigningFailed(
+                    "Failed to decode the signed transaction".into(),
+                )
+            })?;
+
+        // the signer may only add signatures
+        let same_inputs = signed_tx.input.len() == tx.input.len()
+            && signed_tx
+                .input
+                .iter()
+                .zip(tx.input.iter())
+                .all(|(signed, unsigned)| signed.previous_output == unsigned.previous_output);
+        if !same_inputs || signed_tx.output != tx.output {
+            return Err(BurnchainControllerError::SigningFailed(
+                "The wallet changed the transaction's inputs or outputs".into(),
+            ));
+        }
+        Ok(signed_tx)
     }
 
     /// Sign and serialize a tx, consuming the UTXOs in utxo_set and spending total_to_spend
@@ -1811,30 +2059,33 @@ impl BitcoinRegtestController {
         };
 
         // select UTXOs until we have enough to cover the cost
-        let mut total_consumed = 0;
-        let mut available_utxos = vec![];
-        available_utxos.append(&mut utxos_set.utxos);
-        for utxo in available_utxos.into_iter() {
-            total_consumed += utxo.amount;
-            utxos_set.utxos.push(utxo);
-
-            if total_consumed >= total_target {
-                break;
-            }
+        let strategy = utxos_set
+            .selection
+            .unwrap_or(CoinSelectionStrategy::NewestFirst);
+        if let Some(selected) =
+            select_coins(strategy, &utxos_set.utxos, total_target, DUST_UTXO_LIMIT)
+        {
+            utxos_set.utxos = selected;
         }
+        let total_consumed = utxos_set.total_available();
 
         if total_consumed < total_target {
             warn!("Consumed total {total_consumed} is less than intended spend: {total_target}");
             return false;
         }
 
+        // UTXOs held by the miner's wallet are signed by the wallet, which also takes the change
+        let wallet_funding_address = self.wallet_funding_address_for(utxos_set);
+
         // Append the change output
         let value = total_consumed - tx_cost;
         debug!(
             "Payments value: {value:?}, total_consumed: {total_consumed:?}, total_spent: {total_target:?}"
         );
         if value >= DUST_UTXO_LIMIT {
-            let change_output = if self.config.miner.segwit && epoch_id >= StacksEpochId::Epoch21 {
+            let change_output = if let Some(address) = wallet_funding_address.as_ref() {
+                funding_tx_out(address, value)
+            } else if self.config.miner.segwit && epoch_id >= StacksEpochId::Epoch21 {
                 // p2wpkh
                 public_key.set_compressed(true);
                 let change_address_hash = Hash160::from_data(&public_key.to_bytes());
@@ -1862,6 +2113,11 @@ impl BitcoinRegtestController {
             };
             tx.input.push(input);
         }
+        if let Some(address) = wallet_funding_address.as_ref() {
+            // the wallet signs the finalized transaction
+            add_placeholder_signatures(tx, address);
+            return true;
+        }
         for (i, utxo) in utxos_set.utxos.iter().enumerate() {
             let script_pub_key = utxo.script_pub_key.clone();
             let sig_hash_all = 0x01;
@@ -2082,11 +2338,68 @@ impl BitcoinRegtestController {
             BlockstackOperationType::VoteForAggregateKey(payload) => {
                 self.build_vote_for_aggregate_key_tx(epoch_id, payload, op_signer, None)
             }
+            BlockstackOperationType::StackAggregationCommit(payload) => {
+                self.build_stack_aggregation_commit_tx(epoch_id, payload, op_signer, None)
+            }
         };
 
         transaction.map(SerializedTx::new)
     }
 
+    /// Fetch and decode a transaction from bitcoind.
+    /// The transaction must be in bitcoind's mempool, or bitcoind must have `txindex=1`.
+    pub fn fetch_transaction(&self, txid: &Txid) -> Result<Transaction, BurnchainControllerError> {
+        let txstr = BitcoinRPCRequest::get_raw_transaction(&self.config, txid).map_err(|e| {
+            BurnchainControllerError::TransactionSubmissionFailed(format!(
+                "Failed to fetch transaction {txid}: {e:?}"
+            ))
+        })?;
+        let tx_bytes = hex_bytes(&txstr).map_err(|e| {
+            BurnchainControllerError::TransactionSubmissionFailed(format!(
+                "Transaction {txid} is not hex: {e:?}"
+            ))
+        })?;
+        btc_deserialize(&tx_bytes).map_err(|e| {
+            BurnchainControllerError::TransactionSubmissionFailed(format!(
+                "Failed to decode transaction {txid}: {e:?}"
+            ))
+        })
+    }
+
+    /// Get the UTXO created by a `PreStxOp` transaction.  This is the output that a subsequent
+    /// `StackStxOp`, `DelegateStxOp`, `TransferStxOp`, `VoteForAggregateKeyOp`, or
+    /// `StackAggregationCommitOp` must spend in order to be attributed to the `PreStxOp`'s
+    /// output address.
+    pub fn get_pre_stx_utxo(&self, pre_stx_txid: &Txid) -> Result<UTXO, BurnchainControllerError> {
+        let tx = self.fetch_transaction(pre_stx_txid)?;
+        let Some(output) = tx.output.get(1) else {
+            return Err(BurnchainControllerError::TransactionSubmissionFailed(
+                format!("Transaction {pre_stx_txid} is not a pre-stx transaction"),
+            ));
+        };
+        Ok(UTXO {
+            txid: tx.txid(),
+            vout: 1,
+            script_pub_key: output.script_pubkey.clone(),
+            amount: output.value,
+            confirmations: 0,
+        })
+    }
+
+    /// Get the epoch that the next bitcoin block will be in, according to bitcoind's chain tip
+    pub fn get_next_block_epoch_id(&self) -> Result<StacksEpochId, BurnchainControllerError> {
+        let tip_height = BitcoinRPCRequest::get_block_count(&self.config).map_err(|e| {
+            BurnchainControllerError::TransactionSubmissionFailed(format!(
+                "Failed to query bitcoind block height: {e:?}"
+            ))
+        })?;
+        self.config
+            .burnchain
+            .get_epoch_list()
+            .epoch_id_at_height(tip_height + 1)
+            .ok_or(BurnchainControllerError::BurnchainError)
+    }
+
     #[cfg(test)]
     pub fn get_raw_transaction(&self, txid: &Txid) -> Transaction {
         let txstr = BitcoinRPCRequest::get_raw_transaction(&self.config, txid).unwrap();
@@ -2096,7 +2409,6 @@ impl BitcoinRegtestController {
 
     /// Produce `num_blocks` regtest bitcoin blocks, sending the bitcoin coinbase rewards
     ///  to the bitcoin single sig addresses corresponding to `pks` in a round robin fashion.
-    #[cfg(test)]
     pub fn bootstrap_chain_to_pks(&mut self, num_blocks: usize, pks: &[Secp256k1PublicKey]) {
         info!("Creating wallet if it does not exist");
         if let Err(e) = self.create_wallet_if_dne() {
@@ -2226,6 +2538,9 @@ impl BurnchainController for BitcoinRegtestController {
             // Helium: this node is responsible for mining new burnchain blocks
             self.build_next_block(1);
             self.receive_blocks(true, None)?
+        } else if self.config.burnchain.is_mocknet() {
+            // Mocknet: this node mines its own synthetic burnchain blocks
+            self.receive_blocks(true, None)?
         } else {
             // Neon: this node is waiting on a block to be produced
             self.receive_blocks(true, target_block_height_opt)?
@@ -2251,6 +2566,9 @@ impl BurnchainController for BitcoinRegtestController {
         op_signer: &mut BurnchainOpSigner,
         attempt: u64,
     ) -> Result<Txid, BurnchainControllerError> {
+        if self.config.burnchain.is_mocknet() {
+            return Ok(submit_mocknet_operation(&self.config, operation));
+        }
         let transaction = self.make_operation_tx(epoch_id, operation, op_signer, attempt)?;
         self.send_transaction(transaction)
     }
@@ -2277,6 +2595,9 @@ impl BurnchainController for BitcoinRegtestController {
 pub struct UTXOSet {
     bhh: BurnchainHeaderHash,
     utxos: Vec<UTXO>,
+    /// How to choose which of `utxos` to spend.  Only set for the miner's UTXOs; others are
+    /// spent newest-first.
+    selection: Option<CoinSelectionStrategy>,
 }
 
 impl UTXOSet {
@@ -2443,7 +2764,9 @@ impl BitcoinRPCRequest {
         let url = {
             // some methods require a wallet ID
             let wallet_id = match payload.method.as_str() {
-                "importaddress" | "listunspent" => Some(config.burnchain.wallet_name.clone()),
+                "importaddress" | "listunspent" | "walletprocesspsbt" => {
+                    Some(config.burnchain.wallet_name.clone())
+                }
                 _ => None,
             };
             let url = config.burnchain.get_rpc_url(wallet_id);
@@ -2484,7 +2807,6 @@ impl BitcoinRPCRequest {
         request
     }
 
-    #[cfg(test)]
     pub fn get_raw_transaction(config: &Config, txid: &Txid) -> RPCResult<String> {
         debug!("Get raw transaction {txid}");
         let payload = BitcoinRPCRequest {
@@ -2495,7 +2817,68 @@ impl BitcoinRPCRequest {
         };
         let res = BitcoinRPCRequest::send(config, payload)?;
         debug!("Got raw transaction {txid}: {res:?}");
-        Ok(res.get("result").unwrap().as_str().unwrap().to_string())
+        res.get("result")
+            .and_then(|result| result.as_str())
+            .map(|result| result.to_string())
+            .ok_or_else(|| RPCError::Parsing("No 'result' field in bitcoind RPC response".into()))
+    }
+
+    /// Get the txid, size and fee of each transaction in bitcoind's mempool
+    pub fn get_raw_mempool_entries(config: &Config) -> RPCResult<Vec<MempoolEntry>> {
+        let payload = BitcoinRPCRequest {
+            method: "getrawmempool".to_string(),
+            params: vec![true.into()],
+            id: "stacks".to_string(),
+            jsonrpc: "2.0".to_string(),
+        };
+        let res = BitcoinRPCRequest::send(config, payload)?;
+        let entries = res
+            .get("result")
+            .and_then(|result| result.as_object())
+            .ok_or_else(|| {
+                RPCError::Parsing("No 'result' object in bitcoind RPC response".into())
+            })?;
+
+        let mut mempool = Vec::with_capacity(entries.len());
+        for (txid, entry) in entries.iter() {
+            let txid = Txid::from_hex(txid)
+                .map_err(|_| RPCError::Parsing(format!("Invalid mempool txid '{txid}'")))?;
+            let vsize = entry
+                .get("vsize")
+                .and_then(|vsize| vsize.as_u64())
+                .ok_or_else(|| {
+                    RPCError::Parsing(format!("No 'vsize' for mempool transaction {txid}"))
+                })?;
+            let fee_btc = entry
+                .get("fees")
+                .and_then(|fees| fees.get("base"))
+                .and_then(|fee| fee.as_f64())
+                .ok_or_else(|| {
+                    RPCError::Parsing(format!("No 'fees.base' for mempool transaction {txid}"))
+                })?;
+            mempool.push(MempoolEntry {
+                txid,
+                vsize,
+                fee: (fee_btc * 100_000_000.0).round() as u64,
+            });
+        }
+        Ok(mempool)
+    }
+
+    /// Get the height of bitcoind's chain tip
+    pub fn get_block_count(config: &Config) -> RPCResult<u64> {
+        let payload = BitcoinRPCRequest {
+            method: "getblockcount".to_string(),
+            params: vec![],
+            id: "stacks".to_string(),
+            jsonrpc: "2.0".to_string(),
+        };
+        let res = BitcoinRPCRequest::send(config, payload)?;
+        res.get("result")
+            .and_then(|result| result.as_u64())
+            .ok_or_else(|| {
+                RPCError::Parsing("No numeric 'result' field in bitcoind RPC response".into())
+            })
     }
 
     /// Was a given transaction ID confirmed by the burnchain?
@@ -2649,7 +3032,11 @@ impl BitcoinRPCRequest {
             }
         };
 
-        Ok(UTXOSet { bhh, utxos })
+        Ok(UTXOSet {
+            bhh,
+            utxos,
+            selection: None,
+        })
     }
 
     pub fn send_raw_transaction(config: &Config, tx: String) -> RPCResult<()> {
@@ -2732,6 +3119,85 @@ impl BitcoinRPCRequest {
         Ok(())
     }
 
+    /// Convert an unsigned, non-segwit-serialized transaction to a base64-encoded PSBT
+    pub fn convert_to_psbt(config: &Config, tx_hex: &str) -> RPCResult<String> {
+        let payload = BitcoinRPCRequest {
+            method: "converttopsbt".to_string(),
+            params: vec![tx_hex.into(), false.into(), false.into()],
+            id: "stacks".to_string(),
+            jsonrpc: "2.0".to_string(),
+        };
+        let res = BitcoinRPCRequest::send(config, payload)?;
+        Self::check_error(&res)?;
+        res.get("result")
+            .and_then(|result| result.as_str())
+            .map(|psbt| psbt.to_string())
+            .ok_or_else(|| RPCError::Parsing("No PSBT in `converttopsbt` response".into()))
+    }
+
+    /// Have the configured wallet fill in the details of the PSBT's inputs that it holds, and
+    /// sign them if `sign` is true.  Returns the updated PSBT.
+    pub fn wallet_process_psbt(config: &Config, psbt: &str, sign: bool) -> RPCResult<String> {
+        let payload = BitcoinRPCRequest {
+            method: "walletprocesspsbt".to_string(),
+            params: vec![psbt.into(), sign.into(), "ALL".into(), true.into()],
+            id: "stacks".to_string(),
+            jsonrpc: "2.0".to_string(),
+        };
+        let res = BitcoinRPCRequest::send(config, payload)?;
+        Self::check_error(&res)?;
+        let result = res.get("result");
+        if sign
+            && !result
+                .and_then(|result| result.get("complete"))
+                .and_then(|complete| complete.as_bool())
+                .unwrap_or(false)
+        {
+            return Err(RPCError::Bitcoind(format!(
+                "Wallet '{}' could not sign the transaction",
+                &config.burnchain.wallet_name
+            )));
+        }
+        result
+            .and_then(|result| result.get("psbt"))
+            .and_then(|psbt| psbt.as_str())
+            .map(|psbt| psbt.to_string())
+            .ok_or_else(|| RPCError::Parsing("No PSBT in `walletprocesspsbt` response".into()))
+    }
+
+    /// Finalize a fully-signed PSBT, and return the hex-encoded transaction
+    pub fn finalize_psbt(config: &Config, psbt: &str) -> RPCResult<String> {
+        let payload = BitcoinRPCRequest {
+            method: "finalizepsbt".to_string(),
+            params: vec![psbt.into(), true.into()],
+            id: "stacks".to_string(),
+            jsonrpc: "2.0".to_string(),
+        };
+        let res = BitcoinRPCRequest::send(config, payload)?;
+        Self::check_error(&res)?;
+        let result = res.get("result");
+        let complete = result
+            .and_then(|result| result.get("complete"))
+            .and_then(|complete| complete.as_bool())
+            .unwrap_or(false);
+        if !complete {
+            return Err(RPCError::Bitcoind("PSBT is not fully signed".into()));
+        }
+        result
+            .and_then(|result| result.get("hex"))
+            .and_then(|hex| hex.as_str())
+            .map(|hex| hex.to_string())
+            .ok_or_else(|| RPCError::Parsing("No transaction in `finalizepsbt` response".into()))
+    }
+
+    /// Fail if bitcoind answered with an error
+    fn check_error(res: &serde_json::Value) -> RPCResult<()> {
+        match res.get("error") {
+            Some(e) if !e.is_null() => Err(RPCError::Bitcoind(e.to_string())),
+            _ => Ok(()),
+        }
+    }
+
     /// Calls `listwallets` method through RPC call and returns wallet names as a vector of Strings
     pub fn list_wallets(config: &Config) -> RPCResult<Vec<String>> {
         let payload = BitcoinRPCRequest {
@@ -2809,10 +3275,15 @@ mod tests {
     use stacks::config::DEFAULT_SATS_PER_VB;
     use stacks_common::deps_common::bitcoin::blockdata::script::Builder;
     use stacks_common::types::chainstate::{BlockHeaderHash, StacksAddress, VRFSeed};
+    use stacks_common::types::StacksPublicKeyBuffer;
     use stacks_common::util::hash::to_hex;
     use stacks_common::util::secp256k1::Secp256k1PrivateKey;
 
     use super::*;
+    use crate::operations::{
+        make_delegate_stx_op, make_stack_aggregation_commit_op, make_stack_stx_op,
+        make_vote_for_aggregate_key_op,
+    };
 
     #[test]
     fn test_get_satoshis_per_byte() {
@@ -2874,6 +3345,7 @@ mod tests {
         let mut utxo_set = UTXOSet {
             bhh: BurnchainHeaderHash([0x01; 32]),
             utxos: spend_utxos.clone(),
+            selection: None,
         };
         let mut transaction = Transaction {
             input: vec![],
@@ -2916,6 +3388,7 @@ mod tests {
         let utxo_set = UTXOSet {
             bhh: BurnchainHeaderHash([0x01; 32]),
             utxos: spend_utxos,
+            selection: None,
         };
 
         let commit_op = LeaderBlockCommitOp {
@@ -2987,4 +3460,203 @@ mod tests {
 
         assert_eq!(&SerializedTx::new(block_commit).to_hex(), "0100000002eeda098987728e4a2e21b34b74000dcb0bd0e4d20e55735492ec3cba3afbead3030000006a4730440220558286e20e10ce31537f0625dae5cc62fac7961b9d2cf272c990de96323d7e2502202255adbea3d2e0509b80c5d8a3a4fe6397a87bcf18da1852740d5267d89a0cb20121035379aa40c02890d253cfa577964116eb5295570ae9f7287cbae5f2585f5b2c7cfdffffff243b0b329a5889ab8801b315eea19810848d4c2133e0245671cc984a2d2f1301000000006a47304402206d9f8de107f9e1eb15aafac66c2bb34331a7523260b30e18779257e367048d34022013c7dabb32a5c281aa00d405e2ccbd00f34f03a65b2336553a4acd6c52c251ef0121035379aa40c02890d253cfa577964116eb5295570ae9f7287cbae5f2585f5b2c7cfdffffff040000000000000000536a4c5054335be88c3d30cb59a142f83de3b27f897a43bbb0f13316911bb98a3229973dae32afd5b9f21bc1f40f24e2c101ecd13c55b8619e5e03dad81de2c62a1cc1d8c1b375000008a300010000059800015a10270000000000001976a914000000000000000000000000000000000000000088ac10270000000000001976a914000000000000000000000000000000000000000088acb3ef0400000000001976a9141dc27eba0247f8cc9575e7d45e50a0bc7e72427d88ac00000000");
     }
+
+    /// Transactions which spend UTXOs held by the miner's wallet pay change back to the wallet,
+    /// and are left for the wallet to sign
+    #[test]
+    fn test_wallet_funded_tx() {
+        let wallet_script_pubkey =
+            hex_bytes("76a9141dc27eba0247f8cc9575e7d45e50a0bc7e72427d88ac").unwrap();
+        let wallet_address = BitcoinAddress::from_bytes_legacy(
+            BitcoinNetworkType::Testnet,
+            LegacyBitcoinAddressType::PublicKeyHash,
+            &wallet_script_pubkey[3..23],
+        )
+        .unwrap();
+        let make_utxo = |script_pubkey: &[u8], amount| UTXO {
+            txid: Sha256dHash([0x01; 32]),
+            vout: 0,
+            script_pub_key: Builder::from(script_pubkey.to_vec()).into_script(),
+            amount,
+            confirmations: 10,
+        };
+
+        let mut config = Config::default();
+        config.burnchain.wallet_mode = BitcoinWalletMode::Descriptor;
+        config.burnchain.wallet_name = "miner".into();
+        config.burnchain.wallet_funding_address = Some(wallet_address.to_string());
+        let mut btc_controller = BitcoinRegtestController::new(config, None);
+
+        let mut signer = BurnchainOpSigner::new(
+            Secp256k1PrivateKey::from_hex(
+                "9e446f6b0c6a96cf2190e54bcd5a8569c3e386f091605499464389b8d4e0bfc201",
+            )
+            .unwrap(),
+            false,
+        );
+
+        // UTXOs held by some other key are signed by the op signer
+        let other_script_pubkey =
+            hex_bytes("76a914000000000000000000000000000000000000000088ac").unwrap();
+        let utxo_set = UTXOSet {
+            bhh: BurnchainHeaderHash([0x01; 32]),
+            utxos: vec![
+                make_utxo(&wallet_script_pubkey, 50_000),
+                make_utxo(&other_script_pubkey, 50_000),
+            ],
+            selection: None,
+        };
+        assert!(btc_controller
+            .wallet_funding_address_for(&utxo_set)
+            .is_none());
+
+        let mut utxo_set = UTXOSet {
+            bhh: BurnchainHeaderHash([0x01; 32]),
+            utxos: vec![make_utxo(&wallet_script_pubkey, 100_000)],
+            selection: None,
+        };
+        assert_eq!(
+            btc_controller.wallet_funding_address_for(&utxo_set),
+            Some(wallet_address)
+        );
+
+        let mut transaction = Transaction {
+            input: vec![],
+            output: vec![TxOut {
+                value: 10_000,
+                script_pubkey: Builder::from(other_script_pubkey).into_script(),
+            }],
+            version: 1,
+            lock_time: 0,
+        };
+        assert!(btc_controller.serialize_tx(
+            StacksEpochId::Epoch30,
+            &mut transaction,
+            20_000,
+            &mut utxo_set,
+            &mut signer,
+            true
+        ));
+
+        // change goes back to the wallet
+        assert_eq!(transaction.output.len(), 2);
+        assert_eq!(transaction.output[1].value, 80_000);
+        assert_eq!(
+            transaction.output[1].script_pubkey.as_bytes(),
+            &wallet_script_pubkey[..]
+        );
+
+        // the input carries a placeholder, not the op signer's signature
+        assert_eq!(transaction.input.len(), 1);
+        assert!(transaction.input[0]
+            .script_sig
+            .as_bytes()
+            .iter()
+            .skip(1)
+            .take(73)
+            .all(|b| *b == 0));
+    }
+
+    /// Operations which a PreStx op authorizes spend its output and nothing else, so that they
+    /// are attributed to the PreStx op's output address
+    #[test]
+    fn test_pre_stx_spend_tx() {
+        let signer_sk = Secp256k1PrivateKey::from_hex(
+            "9e446f6b0c6a96cf2190e54bcd5a8569c3e386f091605499464389b8d4e0bfc201",
+        )
+        .unwrap();
+        let signer_script_pubkey =
+            hex_bytes("76a9141dc27eba0247f8cc9575e7d45e50a0bc7e72427d88ac").unwrap();
+        let make_utxo = |script_pubkey: &[u8], amount| UTXO {
+            txid: Sha256dHash([0x01; 32]),
+            vout: 1,
+            script_pub_key: Builder::from(script_pubkey.to_vec()).into_script(),
+            amount,
+            confirmations: 0,
+        };
+
+        let config = Config::default();
+        // what a PreStx op pays to its output
+        let pre_stx_amount =
+            DUST_UTXO_LIMIT + OP_TX_ANY_ESTIM_SIZE * get_satoshis_per_byte(&config);
+        let mut btc_controller = BitcoinRegtestController::new(config, None);
+
+        let sender = StacksAddress::burn_address(false);
+        let reward_addr = PoxAddress::Standard(sender, None);
+        let signer_key =
+            StacksPublicKeyBuffer::from_public_key(&Secp256k1PublicKey::from_private(&signer_sk));
+        let mut build = |operation: BlockstackOperationType, utxo: UTXO| {
+            let mut signer = BurnchainOpSigner::new(signer_sk, false);
+            let epoch_id = StacksEpochId::Epoch25;
+            let utxo = Some(utxo);
+            match operation {
+                BlockstackOperationType::StackStx(op) => {
+                    btc_controller.build_stack_stx_tx(epoch_id, op, &mut signer, utxo)
+                }
+                BlockstackOperationType::DelegateStx(op) => {
+                    btc_controller.build_delegate_stacks_tx(epoch_id, op, &mut signer, utxo)
+                }
+                BlockstackOperationType::VoteForAggregateKey(op) => {
+                    btc_controller.build_vote_for_aggregate_key_tx(epoch_id, op, &mut signer, utxo)
+                }
+                BlockstackOperationType::StackAggregationCommit(op) => btc_controller
+                    .build_stack_aggregation_commit_tx(epoch_id, op, &mut signer, utxo),
+                _ => unreachable!(),
+            }
+        };
+
+        let operations = [
+            BlockstackOperationType::StackStx(make_stack_stx_op(
+                sender,
+                reward_addr.clone(),
+                1_000_000,
+                6,
+                Some(signer_key),
+                Some(u128::MAX),
+                Some(1),
+            )),
+            BlockstackOperationType::DelegateStx(make_delegate_stx_op(
+                sender, sender, 1_000_000, None,
+            )),
+            BlockstackOperationType::VoteForAggregateKey(make_vote_for_aggregate_key_op(
+                sender, signer_key, 0, 1, 0, signer_key,
+            )),
+            BlockstackOperationType::StackAggregationCommit(make_stack_aggregation_commit_op(
+                sender,
+                reward_addr,
+                1,
+                signer_key,
+                u128::MAX,
+                1,
+            )),
+        ];
+        for operation in operations {
+            let tx = build(
+                operation.clone(),
+                make_utxo(&signer_script_pubkey, pre_stx_amount),
+            )
+            .unwrap();
+            assert_eq!(tx.input.len(), 1);
+            assert_eq!(tx.input[0].previous_output.txid, Sha256dHash([0x01; 32]));
+            assert_eq!(tx.input[0].previous_output.vout, 1);
+            assert!(tx.output[0].script_pubkey.is_op_return());
+
+            // the signer can't spend someone else's PreStx output
+            let other_script_pubkey =
+                hex_bytes("76a914000000000000000000000000000000000000000088ac").unwrap();
+            assert!(matches!(
+                build(
+                    operation.clone(),
+                    make_utxo(&other_script_pubkey, pre_stx_amount)
+                ),
+                Err(BurnchainControllerError::TransactionSubmissionFailed(_))
+            ));
+
+            // and the PreStx output must pay for the operation
+            assert!(matches!(
+                build(operation, make_utxo(&signer_script_pubkey, DUST_UTXO_LIMIT)),
+                Err(BurnchainControllerError::TransactionSubmissionFailed(_))
+            ));
+        }
+    }
 }
@@ -0,0 +1,344 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fee policy for block commits.
+//!
+//! A block commit that is not mined in the next bitcoin block is worthless, so when the bitcoin
+//! mempool is congested a miner's commit competes with the other miners' commits for block
+//! space.  If `miner.max_commit_fee_rate` is set, the controller watches the mempool for
+//! competing commits, starts each commit at a fee rate above the best competing one, and
+//! replaces its pending commit by fee if it has been outbid when the miner next submits a commit,
+//! never paying more than `max_commit_fee_rate`.  Otherwise, commits start at `burnchain.satoshis_per_byte` and RBF
+//! bumps stop at `burnchain.max_rbf` percent of it, as before.
+
+use std::cmp;
+use std::collections::{HashMap, HashSet};
+
+use stacks::burnchains::bitcoin::bits::parse_script;
+use stacks::burnchains::{Txid, MAGIC_BYTES_LENGTH};
+use stacks::chainstate::burn::Opcodes;
+use stacks_common::deps_common::bitcoin::blockdata::opcodes::All as btc_opcodes;
+use stacks_common::deps_common::bitcoin::blockdata::script::Instruction;
+use stacks_common::deps_common::bitcoin::blockdata::transaction::Transaction;
+
+use super::super::Config;
+
+/// Mempool transactions larger than this (in vbytes) are assumed not to be block commits, and
+/// are never fetched.  Commits with a handful of inputs are a few hundred vbytes.
+const MAX_COMMIT_VSIZE: u64 = 2_000;
+
+/// Most mempool transactions to fetch and decode in one scan.  Decoded transactions are
+/// remembered, so a busy mempool is covered over several scans.
+const MAX_TX_FETCHES_PER_SCAN: usize = 256;
+
+/// Fee rates to use for block commits
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitFeePolicy {
+    /// Fee rate (sats/vbyte) of a new commit, absent competition
+    pub base_fee_rate: u64,
+    /// How much to raise the fee rate with each replacement
+    pub rbf_increment: u64,
+    /// Highest fee rate to ever pay
+    pub max_fee_rate: u64,
+    /// Whether to watch the mempool for competing commits
+    pub track_competing_commits: bool,
+}
+
+impl CommitFeePolicy {
+    /// Load the policy from the config file, so fee settings can change without a restart
+    pub fn from_config(config: &Config) -> Self {
+        let burnchain_config = config.get_burnchain_config();
+        let miner_config = config.get_miner_config();
+        let base_fee_rate = burnchain_config.satoshis_per_byte;
+        let rbf_increment = miner_config
+            .commit_rbf_increment
+            .unwrap_or(burnchain_config.rbf_fee_increment);
+        let (max_fee_rate, track_competing_commits) = match miner_config.max_commit_fee_rate {
+            Some(max_fee_rate) => (max_fee_rate, true),
+            None => (base_fee_rate * burnchain_config.max_rbf / 100, false),
+        };
+        Self {
+            base_fee_rate,
+            rbf_increment: cmp::max(rbf_increment, 1),
+            max_fee_rate,
+            track_competing_commits,
+        }
+    }
+
+    /// Fee rate for a new commit, given the highest fee rate paid by a competing commit
+    pub fn initial_fee_rate(&self, competing_fee_rate: Option<u64>) -> u64 {
+        let fee_rate = match competing_fee_rate {
+            Some(competing) if competing >= self.base_fee_rate => competing + self.rbf_increment,
+            _ => self.base_fee_rate,
+        };
+        // the base fee rate is paid even if it exceeds the cap
+        cmp::max(cmp::min(fee_rate, self.max_fee_rate), self.base_fee_rate)
+    }
+
+    /// Fee rate for a replacement of a commit paying `current_fee_rate`, given the highest fee
+    /// rate paid by a competing commit.  Returns None if the replacement would exceed the cap.
+    pub fn replacement_fee_rate(
+        &self,
+        current_fee_rate: u64,
+        competing_fee_rate: Option<u64>,
+    ) -> Option<u64> {
+        if current_fee_rate >= self.max_fee_rate {
+            return None;
+        }
+        let mut fee_rate = current_fee_rate + self.rbf_increment;
+        if let Some(competing) = competing_fee_rate {
+            fee_rate = cmp::max(fee_rate, competing + self.rbf_increment);
+        }
+        Some(cmp::min(fee_rate, self.max_fee_rate))
+    }
+
+    /// Does a competing commit pay more than ours?
+    pub fn is_outbid(&self, fee_rate: u64, competing_fee_rate: Option<u64>) -> bool {
+        competing_fee_rate.is_some_and(|competing| competing > fee_rate)
+    }
+}
+
+/// A transaction in bitcoind's mempool
+#[derive(Debug, Clone, PartialEq)]
+pub struct MempoolEntry {
+    pub txid: Txid,
+    pub vsize: u64,
+    pub fee: u64,
+}
+
+impl MempoolEntry {
+    pub fn fee_rate(&self) -> u64 {
+        self.fee / cmp::max(self.vsize, 1)
+    }
+}
+
+/// Is `tx` a block commit for the chain with these magic bytes?
+pub fn is_block_commit_tx(tx: &Transaction, magic_bytes: &[u8]) -> bool {
+    let Some(data_output) = tx.output.first() else {
+        return false;
+    };
+    if !data_output.script_pubkey.is_op_return() {
+        return false;
+    }
+    match parse_script(&data_output.script_pubkey).as_slice() {
+        [Instruction::Op(btc_opcodes::OP_RETURN), Instruction::PushBytes(data)] => {
+            data.len() > MAGIC_BYTES_LENGTH
+                && data.starts_with(magic_bytes)
+                && data[MAGIC_BYTES_LENGTH] == Opcodes::LeaderBlockCommit as u8
+        }
+        _ => false,
+    }
+}
+
+/// Finds other miners' block commits in the bitcoin mempool
+#[derive(Default)]
+pub struct CompetingCommitTracker {
+    /// Mempool transactions which have been fetched, and whether each is a block commit
+    decoded: HashMap<Txid, bool>,
+}
+
+impl CompetingCommitTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the highest fee rate paid by a block commit in `mempool` which is not one of
+    /// `own_txids`.  `is_block_commit` fetches and decodes a mempool transaction, returning None
+    /// if it could not be fetched.
+    pub fn highest_competing_fee_rate<F>(
+        &mut self,
+        mempool: &[MempoolEntry],
+        own_txids: &[Txid],
+        mut is_block_commit: F,
+    ) -> Option<u64>
+    where
+        F: FnMut(&Txid) -> Option<bool>,
+    {
+        // forget transactions which have left the mempool
+        let in_mempool: HashSet<_> = mempool.iter().map(|entry| &entry.txid).collect();
+        self.decoded.retain(|txid, _| in_mempool.contains(txid));
+
+        // look at the highest-paying candidates first, so a truncated scan still finds the
+        // commits that matter
+        let mut candidates: Vec<_> = mempool
+            .iter()
+            .filter(|entry| entry.vsize <= MAX_COMMIT_VSIZE && !own_txids.contains(&entry.txid))
+            .collect();
+        candidates.sort_by_key(|entry| cmp::Reverse(entry.fee_rate()));
+
+        let mut fetches = 0;
+        for entry in candidates {
+            let is_commit = match self.decoded.get(&entry.txid) {
+                Some(is_commit) => *is_commit,
+                None => {
+                    if fetches >= MAX_TX_FETCHES_PER_SCAN {
+                        continue;
+                    }
+                    fetches += 1;
+                    let Some(is_commit) = is_block_commit(&entry.txid) else {
+                        continue;
+                    };
+                    self.decoded.insert(entry.txid.clone(), is_commit);
+                    is_commit
+                }
+            };
+            if is_commit {
+                return Some(entry.fee_rate());
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stacks_common::deps_common::bitcoin::blockdata::opcodes;
+    use stacks_common::deps_common::bitcoin::blockdata::script::Builder;
+    use stacks_common::deps_common::bitcoin::blockdata::transaction::TxOut;
+
+    use super::*;
+
+    fn make_policy(max_fee_rate: u64) -> CommitFeePolicy {
+        CommitFeePolicy {
+            base_fee_rate: 50,
+            rbf_increment: 5,
+            max_fee_rate,
+            track_competing_commits: true,
+        }
+    }
+
+    fn entry(byte: u8, vsize: u64, fee_rate: u64) -> MempoolEntry {
+        MempoolEntry {
+            txid: Txid([byte; 32]),
+            vsize,
+            fee: vsize * fee_rate,
+        }
+    }
+
+    #[test]
+    fn test_initial_fee_rate() {
+        let policy = make_policy(100);
+        assert_eq!(policy.initial_fee_rate(None), 50);
+        assert_eq!(policy.initial_fee_rate(Some(10)), 50);
+        assert_eq!(policy.initial_fee_rate(Some(50)), 55);
+        assert_eq!(policy.initial_fee_rate(Some(70)), 75);
+        assert_eq!(policy.initial_fee_rate(Some(200)), 100);
+
+        // a cap below the base rate does not lower the base rate
+        assert_eq!(make_policy(20).initial_fee_rate(Some(70)), 50);
+    }
+
+    #[test]
+    fn test_replacement_fee_rate() {
+        let policy = make_policy(100);
+        assert_eq!(policy.replacement_fee_rate(50, None), Some(55));
+        assert_eq!(policy.replacement_fee_rate(50, Some(40)), Some(55));
+        assert_eq!(policy.replacement_fee_rate(50, Some(80)), Some(85));
+        assert_eq!(policy.replacement_fee_rate(97, None), Some(100));
+        assert_eq!(policy.replacement_fee_rate(60, Some(150)), Some(100));
+        assert_eq!(policy.replacement_fee_rate(100, None), None);
+        assert_eq!(policy.replacement_fee_rate(100, Some(150)), None);
+
+        assert!(policy.is_outbid(50, Some(51)));
+        assert!(!policy.is_outbid(50, Some(50)));
+        assert!(!policy.is_outbid(50, None));
+    }
+
+    #[test]
+    fn test_competing_commit_tracker() {
+        let mut tracker = CompetingCommitTracker::new();
+        let commits = [Txid([1; 32]), Txid([2; 32]), Txid([4; 32])];
+        let mempool = vec![
+            entry(1, 300, 40),
+            entry(2, 300, 60),
+            // not a commit
+            entry(3, 300, 90),
+            // ours
+            entry(4, 300, 80),
+            // too big to be a commit
+            entry(5, 50_000, 200),
+        ];
+
+        let mut fetched = vec![];
+        let highest = tracker.highest_competing_fee_rate(&mempool, &[Txid([4; 32])], |txid| {
+            fetched.push(txid.clone());
+            Some(commits.contains(txid))
+        });
+        assert_eq!(highest, Some(60));
+        assert_eq!(fetched, vec![Txid([3; 32]), Txid([2; 32])]);
+
+        // decoded transactions are not fetched again
+        let highest = tracker
+            .highest_competing_fee_rate(&mempool, &[Txid([4; 32])], |_| panic!("unexpected fetch"));
+        assert_eq!(highest, Some(60));
+
+        // once the best commit is mined, the next best is found
+        let mempool: Vec<_> = mempool
+            .into_iter()
+            .filter(|entry| entry.txid != Txid([2; 32]))
+            .collect();
+        let mut fetched = vec![];
+        let highest = tracker.highest_competing_fee_rate(&mempool, &[Txid([4; 32])], |txid| {
+            fetched.push(txid.clone());
+            Some(commits.contains(txid))
+        });
+        assert_eq!(highest, Some(40));
+        assert_eq!(fetched, vec![Txid([1; 32])]);
+
+        // transactions that could not be fetched are retried
+        let mut tracker = CompetingCommitTracker::new();
+        assert_eq!(
+            tracker.highest_competing_fee_rate(&mempool, &[], |_| None),
+            None
+        );
+        assert_eq!(
+            tracker.highest_competing_fee_rate(&mempool, &[], |txid| Some(commits.contains(txid))),
+            Some(80)
+        );
+    }
+
+    #[test]
+    fn test_is_block_commit_tx() {
+        let make_tx = |data: &[u8]| Transaction {
+            version: 1,
+            lock_time: 0,
+            input: vec![],
+            output: vec![TxOut {
+                value: 0,
+                script_pubkey: Builder::new()
+                    .push_opcode(opcodes::All::OP_RETURN)
+                    .push_slice(data)
+                    .into_script(),
+            }],
+        };
+        let mut commit = b"T3[".to_vec();
+        commit.extend_from_slice(&[0u8; 77]);
+        assert!(is_block_commit_tx(&make_tx(&commit), b"T3"));
+        assert!(!is_block_commit_tx(&make_tx(&commit), b"X2"));
+
+        let mut key_register = b"T3^".to_vec();
+        key_register.extend_from_slice(&[0u8; 77]);
+        assert!(!is_block_commit_tx(&make_tx(&key_register), b"T3"));
+        assert!(!is_block_commit_tx(&make_tx(b"T3"), b"T3"));
+
+        let no_outputs = Transaction {
+            version: 1,
+            lock_time: 0,
+            input: vec![],
+            output: vec![],
+        };
+        assert!(!is_block_commit_tx(&no_outputs, b"T3"));
+    }
+}
@@ -0,0 +1,294 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The synthetic burnchain of a `mocknet` node that runs the Nakamoto run loop.
+//!
+//! Instead of syncing with a bitcoind, the node's `BitcoinRegtestController` mines burnchain
+//! blocks itself, on a timer.  Each block holds the burnchain operations the node submitted
+//! since the previous block, in submission order.  Its header is a mock Bitcoin header, so its
+//! hash depends only on its parent and its timestamp, and each operation's txid depends only
+//! on the operation and on how many operations were submitted before it.  The blocks are
+//! written to the same headers DB and burnchain DB that a bitcoind-backed node syncs into, so
+//! the chains coordinator processes them, and runs their sortitions, as it would real blocks.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use stacks::burnchains::bitcoin::indexer::BitcoinIndexer;
+use stacks::burnchains::indexer::BurnchainIndexer;
+use stacks::burnchains::{Burnchain, BurnchainBlockHeader, Error as burnchain_error, Txid};
+use stacks::chainstate::burn::operations::{BlockstackOperationType, LeaderBlockCommitOp};
+use stacks::chainstate::coordinator::comm::CoordinatorChannels;
+use stacks_common::deps_common::bitcoin::network::serialize::BitcoinHash;
+use stacks_common::types::chainstate::BurnchainHeaderHash;
+use stacks_common::util::get_epoch_time_secs;
+use stacks_common::util::hash::Sha256Sum;
+
+use crate::Config;
+
+/// The pending operations of each mocknet burnchain in this process, keyed by the path of its
+/// headers DB.  The node's burnchain controllers share them: the run loop's controller mines
+/// the blocks, and the relayer's controller submits the miner's operations.
+static MOCKNET_MEMPOOLS: LazyLock<Mutex<HashMap<String, Arc<Mutex<MocknetMempool>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Default)]
+struct MocknetMempool {
+    /// Operations to include in the next block, in submission order
+    pending: Vec<BlockstackOperationType>,
+    /// The output that each miner's next block-commit spends, by the miner's apparent sender.
+    /// This is an output of the miner's last mined block-commit, as it would be on Bitcoin.
+    commit_inputs: HashMap<String, (Txid, u32)>,
+    /// Number of operations submitted so far
+    num_submitted: u64,
+    /// When this process last mined a block
+    last_mined: Option<Instant>,
+}
+
+impl MocknetMempool {
+    /// Queue an operation for the next block.  A block-commit replaces any pending block-commit
+    /// from the same miner, as a fee-bumped Bitcoin transaction would.
+    fn submit(&mut self, mut operation: BlockstackOperationType) -> Txid {
+        let mut txid_preimage = b"stacks-mocknet-op".to_vec();
+        txid_preimage.extend_from_slice(&self.num_submitted.to_be_bytes());
+        txid_preimage.extend_from_slice(
+            serde_json::to_string(&operation)
+                .expect("FATAL: failed to serialize burnchain operation")
+                .as_bytes(),
+        );
+        let txid = Txid(Sha256Sum::from_data(&txid_preimage).0);
+        operation.set_txid(txid.clone());
+        self.num_submitted += 1;
+
+        if let BlockstackOperationType::LeaderBlockCommit(ref mut commit) = operation {
+            let sender = commit.apparent_sender.to_string();
+            commit.input = self
+                .commit_inputs
+                .get(&sender)
+                .cloned()
+                .unwrap_or((Txid([0; 32]), 0));
+            self.pending.retain(|pending| {
+                !matches!(pending, BlockstackOperationType::LeaderBlockCommit(pending_commit)
+                    if pending_commit.apparent_sender.to_string() == sender)
+            });
+        }
+        self.pending.push(operation);
+        txid
+    }
+
+    /// Take the pending operations, and place them in a new block atop `parent`
+    fn mine(
+        &mut self,
+        parent: &BurnchainBlockHeader,
+    ) -> (BurnchainBlockHeader, Vec<BlockstackOperationType>) {
+        let timestamp = get_epoch_time_secs().max(parent.timestamp + 1);
+        let mock_header = BitcoinIndexer::mock_bitcoin_header(
+            &parent.block_hash,
+            u32::try_from(timestamp).expect("FATAL: block timestamp does not fit in a u32"),
+        );
+        let mut ops = std::mem::take(&mut self.pending);
+        let header = BurnchainBlockHeader {
+            block_height: parent.block_height + 1,
+            block_hash: BurnchainHeaderHash::from_bitcoin_hash(&mock_header.bitcoin_hash()),
+            parent_block_hash: parent.block_hash.clone(),
+            // the coinbase, and one transaction per operation
+            num_txs: ops.len() as u64 + 1,
+            timestamp,
+        };
+
+        for (i, op) in ops.iter_mut().enumerate() {
+            op.set_block_height(header.block_height);
+            op.set_burn_header_hash(header.block_hash.clone());
+            op.set_vtxindex(u32::try_from(i + 1).expect("FATAL: too many operations in a block"));
+            if let BlockstackOperationType::LeaderBlockCommit(commit) = op {
+                self.commit_inputs.insert(
+                    commit.apparent_sender.to_string(),
+                    (
+                        commit.txid.clone(),
+                        LeaderBlockCommitOp::expected_chained_utxo(commit.all_outputs_burn()),
+                    ),
+                );
+            }
+        }
+        self.last_mined = Some(Instant::now());
+        (header, ops)
+    }
+}
+
+fn mempool_for(config: &Config) -> Arc<Mutex<MocknetMempool>> {
+    MOCKNET_MEMPOOLS
+        .lock()
+        .expect("FATAL: mocknet mempools lock poisoned")
+        .entry(config.get_spv_headers_file_path())
+        .or_default()
+        .clone()
+}
+
+/// Queue an operation for the node's next mocknet burnchain block, and return its txid
+pub fn submit_mocknet_operation(config: &Config, operation: BlockstackOperationType) -> Txid {
+    let mempool = mempool_for(config);
+    let mut mempool = mempool
+        .lock()
+        .expect("FATAL: mocknet mempool lock poisoned");
+    mempool.submit(operation)
+}
+
+/// Bring the node's burnchain DBs up to date with its mocknet burnchain, and return the
+/// header of the burnchain tip.  If `mine` is set and `burnchain.mocknet_block_time_ms` has
+/// passed since this process last mined a block, a new block is mined first, and the chains
+/// coordinator is told about it.
+pub fn sync_mocknet_burnchain(
+    config: &Config,
+    burnchain: &Burnchain,
+    indexer: &mut BitcoinIndexer,
+    coord_comms: &CoordinatorChannels,
+    mine: bool,
+) -> Result<BurnchainBlockHeader, burnchain_error> {
+    let (_, mut burnchain_db) = burnchain.connect_db(
+        true,
+        indexer.get_first_block_header_hash()?,
+        indexer.get_first_block_header_timestamp()?,
+        indexer.get_stacks_epochs(),
+    )?;
+    let tip = burnchain_db.get_canonical_chain_tip()?;
+
+    // a block's header is stored before the block itself, so a node that stopped in between
+    // has a header without a block
+    if indexer.get_highest_header_height()? > tip.block_height {
+        indexer.drop_headers(tip.block_height)?;
+    }
+
+    let mempool = mempool_for(config);
+    let mut mempool = mempool
+        .lock()
+        .expect("FATAL: mocknet mempool lock poisoned");
+    let block_time = Duration::from_millis(config.burnchain.mocknet_block_time_ms);
+    let block_is_due = mempool
+        .last_mined
+        .map_or(true, |last_mined| last_mined.elapsed() >= block_time);
+    if !mine || !block_is_due {
+        return Ok(tip);
+    }
+
+    let (header, ops) = mempool.mine(&tip);
+    debug!(
+        "Mocknet: mined burnchain block {} at height {} with {} operations",
+        &header.block_hash,
+        header.block_height,
+        ops.len()
+    );
+    indexer
+        .raw_store_header(header.clone())
+        .map_err(burnchain_error::Bitcoin)?;
+    burnchain_db.store_new_burnchain_block_ops_unchecked(burnchain, indexer, &header, &ops)?;
+    coord_comms.announce_new_burn_block();
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use stacks::burnchains::BurnchainSigner;
+    use stacks::chainstate::stacks::address::PoxAddress;
+    use stacks_common::types::chainstate::{BlockHeaderHash, StacksAddress, VRFSeed};
+
+    use super::*;
+
+    fn make_commit(sender: &str, burn_fee: u64) -> BlockstackOperationType {
+        BlockstackOperationType::LeaderBlockCommit(LeaderBlockCommitOp {
+            block_header_hash: BlockHeaderHash([0x11; 32]),
+            new_seed: VRFSeed([0x22; 32]),
+            parent_block_ptr: 0,
+            parent_vtxindex: 0,
+            key_block_ptr: 0,
+            key_vtxindex: 0,
+            memo: vec![],
+            burn_fee,
+            input: (Txid([0x00; 32]), 0),
+            burn_parent_modulus: 0,
+            apparent_sender: BurnchainSigner(sender.to_string()),
+            commit_outs: vec![
+                PoxAddress::Standard(StacksAddress::burn_address(false), None),
+                PoxAddress::Standard(StacksAddress::burn_address(false), None),
+            ],
+            treatment: vec![],
+            sunset_burn: 0,
+            txid: Txid([0x00; 32]),
+            vtxindex: 0,
+            block_height: 0,
+            burn_header_hash: BurnchainHeaderHash([0x00; 32]),
+        })
+    }
+
+    fn genesis() -> BurnchainBlockHeader {
+        BurnchainBlockHeader {
+            block_height: 0,
+            block_hash: BurnchainHeaderHash([0x01; 32]),
+            parent_block_hash: BurnchainHeaderHash([0x00; 32]),
+            num_txs: 1,
+            timestamp: 1,
+        }
+    }
+
+    #[test]
+    fn mocknet_mempool_mines_submitted_operations() {
+        let mut mempool = MocknetMempool::default();
+        let first_txid = mempool.submit(make_commit("miner-1", 100));
+        // a second commit from the same miner replaces the first
+        let replacement_txid = mempool.submit(make_commit("miner-1", 200));
+        let other_txid = mempool.submit(make_commit("miner-2", 300));
+        assert_ne!(first_txid, replacement_txid);
+
+        let (header, ops) = mempool.mine(&genesis());
+        assert_eq!(header.block_height, 1);
+        assert_eq!(header.parent_block_hash, genesis().block_hash);
+        assert_eq!(header.num_txs, 3);
+        assert!(mempool.pending.is_empty());
+
+        let commits: Vec<_> = ops
+            .iter()
+            .map(|op| match op {
+                BlockstackOperationType::LeaderBlockCommit(commit) => commit.clone(),
+                _ => panic!("Expected a block-commit"),
+            })
+            .collect();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].txid, replacement_txid);
+        assert_eq!(commits[0].burn_fee, 200);
+        assert_eq!(commits[0].vtxindex, 1);
+        assert_eq!(commits[1].txid, other_txid);
+        assert_eq!(commits[1].vtxindex, 2);
+        for commit in commits.iter() {
+            assert_eq!(commit.block_height, header.block_height);
+            assert_eq!(commit.burn_header_hash, header.block_hash);
+            assert_eq!(commit.input, (Txid([0x00; 32]), 0));
+        }
+
+        // the miner's next commit spends an output of its last mined commit
+        mempool.submit(make_commit("miner-1", 100));
+        let (next_header, ops) = mempool.mine(&header);
+        assert_eq!(next_header.parent_block_hash, header.block_hash);
+        let BlockstackOperationType::LeaderBlockCommit(ref commit) = ops[0] else {
+            panic!("Expected a block-commit");
+        };
+        assert_eq!(
+            commit.input,
+            (
+                replacement_txid,
+                LeaderBlockCommitOp::expected_chained_utxo(true)
+            )
+        );
+    }
+}
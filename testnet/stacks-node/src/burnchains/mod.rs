@@ -1,5 +1,10 @@
 pub mod bitcoin_regtest_controller;
+pub mod bitcoind;
+pub mod commit_fee_policy;
+pub mod mock_bitcoin;
 pub mod mocknet_controller;
+pub mod utxo_manager;
+pub mod wallet;
 
 use std::time::Instant;
 
@@ -33,6 +38,10 @@ pub enum Error {
     TransactionSubmissionFailed(String),
     #[error("Serializer error: {0}")]
     SerializerError(CodecError),
+    #[error("Unsupported operation: {0}")]
+    UnsupportedOperation(String),
+    #[error("Failed to sign transaction: {0}")]
+    SigningFailed(String),
 }
 
 pub trait BurnchainController {
@@ -0,0 +1,438 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Coin selection for the miner's burnchain transactions (`miner.utxo_selection`).
+//!
+//! By default (`newest-first`), the miner only fetches UTXOs which can each fund a block commit
+//! alone, and spends the most recent one.  Each commit's change is then spent by the next
+//! commit, but any UTXO too small to fund a commit by itself (such as the change left over when
+//! the miner's balance was low, or a deposit split across several outputs) is never spent, and
+//! the miner's balance fragments over time.  The other strategies fetch all of the miner's
+//! UTXOs and combine them as needed:
+//!
+//! * `largest-first` adds the largest UTXOs until the transaction is funded.
+//! * `branch-and-bound` searches for a set of UTXOs which funds the transaction with less than
+//!   a dust output's worth left over, so that no change output is needed.
+//! * `consolidate` spends as many of the smallest UTXOs as a transaction can hold.
+//!
+//! If `miner.utxo_consolidation_fee_rate` is set, the miner also consolidates whenever the
+//! burnchain fee rate is at or below it and it holds at least `miner.utxo_consolidation_min_count`
+//! UTXOs, so that the extra inputs are paid for while fees are low.
+//!
+//! Whatever the strategy, the least-confirmed UTXO is always spent first, since it is usually
+//! the change from the miner's previous block commit, and spending it keeps the miner's commits
+//! chained together.
+
+use std::sync::{Arc, Mutex};
+
+use stacks::config::CoinSelectionStrategy;
+use stacks::net::api::getminerutxos::{MinerUtxo, MinerUtxoSelection, MinerUtxoStatus};
+use stacks::util::get_epoch_time_secs;
+
+use super::super::Config;
+use super::bitcoin_regtest_controller::UTXO;
+
+/// Most UTXOs to spend when consolidating, which keeps the transaction well within standard
+/// size limits
+const MAX_CONSOLIDATION_INPUTS: usize = 50;
+
+/// Most branches to explore in a branch-and-bound search before giving up
+const MAX_BNB_TRIES: usize = 100_000;
+
+/// Chooses which of the miner's UTXOs to spend, and reports them at `/v3/miner/utxos`
+#[derive(Default)]
+pub struct UtxoManager {
+    status: Option<Arc<Mutex<MinerUtxoStatus>>>,
+}
+
+impl UtxoManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report the miner's UTXOs through `status`
+    pub fn set_status(&mut self, status: Arc<Mutex<MinerUtxoStatus>>) {
+        self.status = Some(status);
+    }
+
+    /// Whether to fetch all of the miner's UTXOs, or only those which can each fund a
+    /// transaction alone.  The config is reloaded, so the strategy can change without a restart.
+    pub fn fetch_all_utxos(config: &Config) -> bool {
+        let miner_config = config.get_miner_config();
+        miner_config.utxo_selection != CoinSelectionStrategy::NewestFirst
+            || miner_config.utxo_consolidation_fee_rate.is_some()
+    }
+
+    /// Which strategy to spend the miner's `num_utxos` UTXOs with, given the current fee rate
+    pub fn strategy_for(config: &Config, num_utxos: usize) -> CoinSelectionStrategy {
+        let miner_config = config.get_miner_config();
+        let fee_rate = config.get_burnchain_config().satoshis_per_byte;
+        match miner_config.utxo_consolidation_fee_rate {
+            Some(max_fee_rate)
+                if fee_rate <= max_fee_rate
+                    && num_utxos as u64 >= miner_config.utxo_consolidation_min_count =>
+            {
+                CoinSelectionStrategy::Consolidate
+            }
+            _ => miner_config.utxo_selection,
+        }
+    }
+
+    /// Record the UTXOs which were just fetched for the miner at `address`
+    pub fn record_utxos(
+        &self,
+        config: &Config,
+        address: String,
+        strategy: CoinSelectionStrategy,
+        utxos: &[UTXO],
+    ) {
+        let Some(status) = self.status.as_ref() else {
+            return;
+        };
+        let mut status = status
+            .lock()
+            .expect("FATAL: miner UTXO status mutex poisoned");
+        status.address = Some(address);
+        status.strategy = config.get_miner_config().utxo_selection.to_string();
+        status.fee_rate = config.get_burnchain_config().satoshis_per_byte;
+        status.consolidating = strategy == CoinSelectionStrategy::Consolidate;
+        status.utxos = utxos
+            .iter()
+            .map(|utxo| MinerUtxo {
+                txid: utxo.txid.be_hex_string(),
+                vout: utxo.vout,
+                amount: utxo.amount,
+                confirmations: utxo.confirmations,
+            })
+            .collect();
+        status.utxos.sort_by_key(|utxo| utxo.confirmations);
+        status.total_amount = utxos.iter().map(|utxo| utxo.amount).sum();
+        status.updated_at = get_epoch_time_secs();
+    }
+
+    /// Record the UTXOs which the miner's latest transaction spent, and the fee it paid
+    pub fn record_selection(&self, strategy: CoinSelectionStrategy, inputs: &[UTXO], fee: u64) {
+        let Some(status) = self.status.as_ref() else {
+            return;
+        };
+        let mut status = status
+            .lock()
+            .expect("FATAL: miner UTXO status mutex poisoned");
+        status.last_selection = Some(MinerUtxoSelection {
+            strategy: strategy.to_string(),
+            num_inputs: inputs.len() as u64,
+            total_input: inputs.iter().map(|utxo| utxo.amount).sum(),
+            fee,
+        });
+    }
+}
+
+/// Choose which of `utxos` to spend to raise at least `target` satoshis.  `utxos` are in the
+/// order the miner prefers to spend them (least-confirmed first), and the first is always
+/// spent.  A branch-and-bound selection may exceed `target` by up to `tolerance`.  Returns
+/// `None` if `utxos` are worth less than `target` altogether.
+pub fn select_coins(
+    strategy: CoinSelectionStrategy,
+    utxos: &[UTXO],
+    target: u64,
+    tolerance: u64,
+) -> Option<Vec<UTXO>> {
+    if strategy == CoinSelectionStrategy::NewestFirst {
+        return select_in_order(utxos.iter(), target);
+    }
+
+    let (first, rest) = utxos.split_first()?;
+    if first.amount >= target && strategy != CoinSelectionStrategy::Consolidate {
+        return Some(vec![first.clone()]);
+    }
+    let remaining_target = target.saturating_sub(first.amount);
+
+    let mut largest_first: Vec<&UTXO> = rest.iter().collect();
+    largest_first.sort_by(|u1, u2| u2.amount.cmp(&u1.amount));
+
+    let selected = match strategy {
+        CoinSelectionStrategy::BranchAndBound => {
+            let amounts: Vec<u64> = largest_first.iter().map(|utxo| utxo.amount).collect();
+            branch_and_bound(&amounts, remaining_target, tolerance).map(|indexes| {
+                let mut selected = vec![first.clone()];
+                selected.extend(indexes.into_iter().map(|i| largest_first[i].clone()));
+                selected
+            })
+        }
+        CoinSelectionStrategy::Consolidate => {
+            let mut smallest_first = largest_first.clone();
+            smallest_first.reverse();
+            let mut selected = vec![first.clone()];
+            selected.extend(
+                smallest_first
+                    .into_iter()
+                    .take(MAX_CONSOLIDATION_INPUTS - 1)
+                    .cloned(),
+            );
+            let total: u64 = selected.iter().map(|utxo| utxo.amount).sum();
+            (total >= target).then_some(selected)
+        }
+        CoinSelectionStrategy::LargestFirst | CoinSelectionStrategy::NewestFirst => None,
+    };
+    selected.or_else(|| {
+        select_in_order(
+            std::iter::once(first).chain(largest_first.into_iter()),
+            target,
+        )
+    })
+}
+
+/// Spend `utxos` in order until at least `target` satoshis are raised
+fn select_in_order<'a>(utxos: impl Iterator<Item = &'a UTXO>, target: u64) -> Option<Vec<UTXO>> {
+    let mut total = 0;
+    let mut selected = vec![];
+    for utxo in utxos {
+        total += utxo.amount;
+        selected.push(utxo.clone());
+        if total >= target {
+            return Some(selected);
+        }
+    }
+    None
+}
+
+/// Depth-first search for a subset of `amounts` (in descending order) whose sum is between
+/// `target` and `target + tolerance`, preferring the smallest excess.  Returns the subset's
+/// indexes, or `None` if no such subset was found within `MAX_BNB_TRIES` branches.
+fn branch_and_bound(amounts: &[u64], target: u64, tolerance: u64) -> Option<Vec<usize>> {
+    if target == 0 {
+        return Some(vec![]);
+    }
+    let mut remaining = vec![0; amounts.len() + 1];
+    for i in (0..amounts.len()).rev() {
+        remaining[i] = remaining[i + 1] + amounts[i];
+    }
+    let mut search = BranchAndBound {
+        amounts,
+        remaining,
+        target,
+        upper_bound: target.saturating_add(tolerance),
+        tries: 0,
+        selected: vec![],
+        best: None,
+    };
+    search.explore(0, 0);
+    search.best.map(|(_excess, indexes)| indexes)
+}
+
+struct BranchAndBound<'a> {
+    amounts: &'a [u64],
+    /// `remaining[i]` is the sum of `amounts[i..]`
+    remaining: Vec<u64>,
+    target: u64,
+    upper_bound: u64,
+    tries: usize,
+    selected: Vec<usize>,
+    /// Smallest excess found so far, and the subset which produced it
+    best: Option<(u64, Vec<usize>)>,
+}
+
+impl BranchAndBound<'_> {
+    fn explore(&mut self, index: usize, total: u64) {
+        if self.tries >= MAX_BNB_TRIES || total > self.upper_bound {
+            return;
+        }
+        self.tries += 1;
+        if total >= self.target {
+            let excess = total - self.target;
+            if self
+                .best
+                .as_ref()
+                .map_or(true, |(best_excess, _)| excess < *best_excess)
+            {
+                self.best = Some((excess, self.selected.clone()));
+            }
+            return;
+        }
+        if index >= self.amounts.len() || total + self.remaining[index] < self.target {
+            return;
+        }
+
+        self.selected.push(index);
+        self.explore(index + 1, total + self.amounts[index]);
+        self.selected.pop();
+        if matches!(self.best, Some((0, _))) {
+            return;
+        }
+        self.explore(index + 1, total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stacks_common::deps_common::bitcoin::blockdata::script::Script;
+    use stacks_common::deps_common::bitcoin::util::hash::Sha256dHash;
+
+    use super::*;
+
+    fn make_utxos(amounts: &[u64]) -> Vec<UTXO> {
+        amounts
+            .iter()
+            .enumerate()
+            .map(|(i, amount)| UTXO {
+                txid: Sha256dHash([i as u8; 32]),
+                vout: 0,
+                script_pub_key: Script::new(),
+                amount: *amount,
+                confirmations: i as u32,
+            })
+            .collect()
+    }
+
+    fn amounts(utxos: &[UTXO]) -> Vec<u64> {
+        utxos.iter().map(|utxo| utxo.amount).collect()
+    }
+
+    #[test]
+    fn test_newest_first() {
+        let utxos = make_utxos(&[100, 50, 1_000]);
+        let selected = select_coins(CoinSelectionStrategy::NewestFirst, &utxos, 120, 0).unwrap();
+        assert_eq!(amounts(&selected), vec![100, 50]);
+        assert!(select_coins(CoinSelectionStrategy::NewestFirst, &utxos, 2_000, 0).is_none());
+    }
+
+    #[test]
+    fn test_largest_first() {
+        let utxos = make_utxos(&[100, 50, 1_000, 500]);
+        // the first UTXO is always spent, even if a larger one would do alone
+        let selected = select_coins(CoinSelectionStrategy::LargestFirst, &utxos, 120, 0).unwrap();
+        assert_eq!(amounts(&selected), vec![100, 1_000]);
+
+        let selected = select_coins(CoinSelectionStrategy::LargestFirst, &utxos, 90, 0).unwrap();
+        assert_eq!(amounts(&selected), vec![100]);
+
+        let selected = select_coins(CoinSelectionStrategy::LargestFirst, &utxos, 1_600, 0).unwrap();
+        assert_eq!(amounts(&selected), vec![100, 1_000, 500]);
+        assert!(select_coins(CoinSelectionStrategy::LargestFirst, &utxos, 1_651, 0).is_none());
+    }
+
+    #[test]
+    fn test_branch_and_bound() {
+        let utxos = make_utxos(&[100, 1_000, 300, 200, 700]);
+        // 100 + 300 + 200 is exact
+        let mut selected =
+            select_coins(CoinSelectionStrategy::BranchAndBound, &utxos, 600, 0).unwrap();
+        assert_eq!(selected[0].amount, 100);
+        selected.sort_by_key(|utxo| utxo.amount);
+        assert_eq!(amounts(&selected), vec![100, 200, 300]);
+
+        // nothing within tolerance; falls back to largest-first
+        let selected =
+            select_coins(CoinSelectionStrategy::BranchAndBound, &utxos, 2_250, 10).unwrap();
+        assert_eq!(amounts(&selected), vec![100, 1_000, 700, 300, 200]);
+
+        // 100 + 1000 overshoots 1050 by 50, which is within tolerance
+        let selected =
+            select_coins(CoinSelectionStrategy::BranchAndBound, &utxos, 1_050, 60).unwrap();
+        assert_eq!(amounts(&selected), vec![100, 1_000]);
+
+        assert_eq!(branch_and_bound(&[5, 4, 3], 7, 0), Some(vec![1, 2]));
+        assert_eq!(branch_and_bound(&[5, 4, 3], 13, 0), None);
+        assert_eq!(branch_and_bound(&[5, 4, 3], 0, 0), Some(vec![]));
+    }
+
+    #[test]
+    fn test_consolidate() {
+        let utxos = make_utxos(&[100, 1_000, 30, 20, 700]);
+        let selected = select_coins(CoinSelectionStrategy::Consolidate, &utxos, 50, 0).unwrap();
+        assert_eq!(amounts(&selected), vec![100, 20, 30, 700, 1_000]);
+
+        // at most MAX_CONSOLIDATION_INPUTS UTXOs are spent
+        let many: Vec<u64> = (0..100).map(|_| 10).collect();
+        let utxos = make_utxos(&many);
+        let selected = select_coins(CoinSelectionStrategy::Consolidate, &utxos, 10, 0).unwrap();
+        assert_eq!(selected.len(), MAX_CONSOLIDATION_INPUTS);
+
+        // if the smallest UTXOs are not enough, fall back to largest-first
+        let mut many: Vec<u64> = (0..100).map(|_| 10).collect();
+        many.push(10_000);
+        let utxos = make_utxos(&many);
+        let selected = select_coins(CoinSelectionStrategy::Consolidate, &utxos, 5_000, 0).unwrap();
+        assert_eq!(amounts(&selected), vec![10, 10_000]);
+    }
+
+    #[test]
+    fn test_record_status() {
+        let config = Config::default();
+        let status = Arc::new(Mutex::new(MinerUtxoStatus::default()));
+        let mut manager = UtxoManager::new();
+        let utxos = make_utxos(&[100, 200]);
+
+        // nothing is recorded until there is somewhere to record it
+        manager.record_utxos(
+            &config,
+            "addr".into(),
+            CoinSelectionStrategy::NewestFirst,
+            &utxos,
+        );
+        manager.set_status(status.clone());
+        assert_eq!(*status.lock().unwrap(), MinerUtxoStatus::default());
+
+        manager.record_utxos(
+            &config,
+            "addr".into(),
+            CoinSelectionStrategy::Consolidate,
+            &utxos,
+        );
+        manager.record_selection(CoinSelectionStrategy::Consolidate, &utxos, 25);
+        let status = status.lock().unwrap().clone();
+        assert_eq!(status.address.as_deref(), Some("addr"));
+        assert_eq!(status.strategy, "newest-first");
+        assert!(status.consolidating);
+        assert_eq!(status.utxos.len(), 2);
+        assert_eq!(status.total_amount, 300);
+        assert!(status.updated_at > 0);
+        let selection = status.last_selection.unwrap();
+        assert_eq!(selection.strategy, "consolidate");
+        assert_eq!(selection.num_inputs, 2);
+        assert_eq!(selection.total_input, 300);
+        assert_eq!(selection.fee, 25);
+    }
+
+    #[test]
+    fn test_strategy_for() {
+        let mut config = Config::default();
+        assert!(!UtxoManager::fetch_all_utxos(&config));
+        assert_eq!(
+            UtxoManager::strategy_for(&config, 100),
+            CoinSelectionStrategy::NewestFirst
+        );
+
+        config.burnchain.satoshis_per_byte = 5;
+        config.miner.utxo_consolidation_fee_rate = Some(5);
+        config.miner.utxo_consolidation_min_count = 10;
+        assert!(UtxoManager::fetch_all_utxos(&config));
+        assert_eq!(
+            UtxoManager::strategy_for(&config, 10),
+            CoinSelectionStrategy::Consolidate
+        );
+        assert_eq!(
+            UtxoManager::strategy_for(&config, 9),
+            CoinSelectionStrategy::NewestFirst
+        );
+
+        config.burnchain.satoshis_per_byte = 6;
+        config.miner.utxo_selection = CoinSelectionStrategy::BranchAndBound;
+        assert_eq!(
+            UtxoManager::strategy_for(&config, 10),
+            CoinSelectionStrategy::BranchAndBound
+        );
+    }
+}
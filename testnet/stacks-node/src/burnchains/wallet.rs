@@ -0,0 +1,250 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Funding and signing of the miner's burnchain transactions (`[burnchain] wallet_mode`).
+//!
+//! By default, the miner's block-commit and leader key register transactions spend UTXOs at the
+//! address of the node's burnchain op key, and the node signs them itself.  In `descriptor` and
+//! `external` wallet modes, they instead spend UTXOs at `wallet_funding_address` in bitcoind's
+//! `wallet_name` wallet and send change back to it.  The node builds each transaction unsigned,
+//! converts it to a PSBT, and has bitcoind fill in the inputs' details.  A descriptor wallet then
+//! signs the PSBT itself; in `external` mode, the wallet is watch-only and the PSBT is handed to
+//! `wallet_signer_command` (e.g. an HSM bridge) to sign.  Either way, the node never holds the
+//! funding keys.  Operations which a user submits (e.g. `stack-stx`) are always signed by the
+//! user's own key.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use stacks::burnchains::bitcoin::address::{
+    BitcoinAddress, LegacyBitcoinAddress, LegacyBitcoinAddressType, SegwitBitcoinAddress,
+};
+use stacks_common::deps_common::bitcoin::blockdata::script::{Builder, Script};
+use stacks_common::deps_common::bitcoin::blockdata::transaction::{Transaction, TxOut};
+
+/// Size of a DER-encoded ECDSA signature, plus its sighash byte, at its largest
+const PLACEHOLDER_ECDSA_SIG_LEN: usize = 73;
+/// Size of a compressed public key
+const PLACEHOLDER_PUBKEY_LEN: usize = 33;
+/// Size of a BIP-340 Schnorr signature with the default sighash
+const PLACEHOLDER_SCHNORR_SIG_LEN: usize = 64;
+
+/// Make an output which pays `value` to the wallet's funding address
+pub fn funding_tx_out(address: &BitcoinAddress, value: u64) -> TxOut {
+    match address {
+        BitcoinAddress::Legacy(legacy) => match legacy.addrtype {
+            LegacyBitcoinAddressType::PublicKeyHash => {
+                LegacyBitcoinAddress::to_p2pkh_tx_out(&legacy.bytes, value)
+            }
+            LegacyBitcoinAddressType::ScriptHash => {
+                LegacyBitcoinAddress::to_p2sh_tx_out(&legacy.bytes, value)
+            }
+        },
+        BitcoinAddress::Segwit(SegwitBitcoinAddress::P2WPKH(_, bytes)) => {
+            SegwitBitcoinAddress::to_p2wpkh_tx_out(bytes, value)
+        }
+        BitcoinAddress::Segwit(SegwitBitcoinAddress::P2WSH(_, bytes)) => {
+            SegwitBitcoinAddress::to_p2wsh_tx_out(bytes, value)
+        }
+        BitcoinAddress::Segwit(SegwitBitcoinAddress::P2TR(_, bytes)) => {
+            SegwitBitcoinAddress::to_p2tr_tx_out(bytes, value)
+        }
+    }
+}
+
+/// Fill each of `tx`'s inputs, which spend from the wallet's funding address, with a
+/// placeholder signature of the largest size the wallet could produce, so that the size (and
+/// hence the fee) of the signed transaction can be estimated before the wallet signs it.
+pub fn add_placeholder_signatures(tx: &mut Transaction, address: &BitcoinAddress) {
+    for input in tx.input.iter_mut() {
+        match address {
+            BitcoinAddress::Legacy(_) => {
+                input.script_sig = Builder::new()
+                    .push_slice(&[0u8; PLACEHOLDER_ECDSA_SIG_LEN])
+                    .push_slice(&[0u8; PLACEHOLDER_PUBKEY_LEN])
+                    .into_script();
+                input.witness.clear();
+            }
+            BitcoinAddress::Segwit(segwit) if segwit.is_p2tr() => {
+                input.script_sig = Script::new();
+                input.witness = vec![vec![0u8; PLACEHOLDER_SCHNORR_SIG_LEN]];
+            }
+            BitcoinAddress::Segwit(_) => {
+                input.script_sig = Script::new();
+                input.witness = vec![
+                    vec![0u8; PLACEHOLDER_ECDSA_SIG_LEN],
+                    vec![0u8; PLACEHOLDER_PUBKEY_LEN],
+                ];
+            }
+        }
+    }
+}
+
+/// Have `wallet_signer_command` sign a base64-encoded PSBT.  The command is run without a shell,
+/// with the PSBT on its stdin, and must exit successfully after printing the signed PSBT (also
+/// base64-encoded) to its stdout.
+pub fn run_signer_command(command: &str, psbt: &str) -> Result<String, String> {
+    let mut args = command.split_whitespace();
+    let program = args
+        .next()
+        .ok_or_else(|| "burnchain.wallet_signer_command is empty".to_string())?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run wallet signer '{program}': {e}"))?;
+
+    child
+        .stdin
+        .take()
+        .expect("FATAL: signer stdin was not piped")
+        .write_all(psbt.as_bytes())
+        .map_err(|e| format!("Failed to send PSBT to wallet signer: {e}"))?;
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to wait for wallet signer: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Wallet signer exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let signed_psbt = String::from_utf8(output.stdout)
+        .map_err(|_| "Wallet signer printed a non-UTF-8 PSBT".to_string())?
+        .trim()
+        .to_string();
+    if signed_psbt.is_empty() {
+        return Err("Wallet signer did not print a PSBT".into());
+    }
+    Ok(signed_psbt)
+}
+
+#[cfg(test)]
+mod tests {
+    use stacks::burnchains::bitcoin::BitcoinNetworkType;
+    use stacks_common::deps_common::bitcoin::blockdata::transaction::{OutPoint, TxIn};
+    use stacks_common::deps_common::bitcoin::network::serialize::serialize;
+
+    use super::*;
+
+    fn make_tx(num_inputs: usize) -> Transaction {
+        Transaction {
+            version: 1,
+            lock_time: 0,
+            input: (0..num_inputs)
+                .map(|vout| TxIn {
+                    previous_output: OutPoint {
+                        txid: Default::default(),
+                        vout: vout as u32,
+                    },
+                    script_sig: Script::new(),
+                    sequence: 0xFFFFFFFD,
+                    witness: vec![],
+                })
+                .collect(),
+            output: vec![],
+        }
+    }
+
+    #[test]
+    fn test_funding_tx_out() {
+        let p2pkh = BitcoinAddress::from_bytes_legacy(
+            BitcoinNetworkType::Testnet,
+            LegacyBitcoinAddressType::PublicKeyHash,
+            &[0x01; 20],
+        )
+        .unwrap();
+        let tx_out = funding_tx_out(&p2pkh, 1000);
+        assert_eq!(tx_out.value, 1000);
+        assert!(tx_out.script_pubkey.is_p2pkh());
+
+        let p2wpkh =
+            BitcoinAddress::from_bytes_segwit_p2wpkh(BitcoinNetworkType::Testnet, &[0x02; 20])
+                .unwrap();
+        let tx_out = funding_tx_out(&p2wpkh, 2000);
+        assert_eq!(tx_out.value, 2000);
+        assert_eq!(&tx_out.script_pubkey.as_bytes()[0..2], &[0x00, 0x14]);
+        assert_eq!(&tx_out.script_pubkey.as_bytes()[2..], &[0x02; 20]);
+
+        let p2tr = BitcoinAddress::Segwit(SegwitBitcoinAddress::P2TR(false, [0x03; 32]));
+        let tx_out = funding_tx_out(&p2tr, 3000);
+        assert_eq!(&tx_out.script_pubkey.as_bytes()[0..2], &[0x51, 0x20]);
+    }
+
+    #[test]
+    fn test_add_placeholder_signatures() {
+        let p2pkh = BitcoinAddress::from_bytes_legacy(
+            BitcoinNetworkType::Testnet,
+            LegacyBitcoinAddressType::PublicKeyHash,
+            &[0x01; 20],
+        )
+        .unwrap();
+        let mut tx = make_tx(2);
+        let unsigned_len = serialize(&tx).unwrap().len();
+        add_placeholder_signatures(&mut tx, &p2pkh);
+        for input in tx.input.iter() {
+            // two pushes, each with a 1-byte length prefix
+            assert_eq!(
+                input.script_sig.len(),
+                PLACEHOLDER_ECDSA_SIG_LEN + PLACEHOLDER_PUBKEY_LEN + 2
+            );
+            assert!(input.witness.is_empty());
+        }
+        assert!(serialize(&tx).unwrap().len() > unsigned_len);
+
+        let p2wpkh =
+            BitcoinAddress::from_bytes_segwit_p2wpkh(BitcoinNetworkType::Testnet, &[0x02; 20])
+                .unwrap();
+        let mut tx = make_tx(2);
+        add_placeholder_signatures(&mut tx, &p2wpkh);
+        for input in tx.input.iter() {
+            assert!(input.script_sig.is_empty());
+            assert_eq!(
+                input.witness,
+                vec![
+                    vec![0u8; PLACEHOLDER_ECDSA_SIG_LEN],
+                    vec![0u8; PLACEHOLDER_PUBKEY_LEN]
+                ]
+            );
+        }
+
+        let p2tr = BitcoinAddress::Segwit(SegwitBitcoinAddress::P2TR(false, [0x03; 32]));
+        let mut tx = make_tx(1);
+        add_placeholder_signatures(&mut tx, &p2tr);
+        assert_eq!(
+            tx.input[0].witness,
+            vec![vec![0u8; PLACEHOLDER_SCHNORR_SIG_LEN]]
+        );
+    }
+
+    #[test]
+    fn test_run_signer_command() {
+        // `cat` "signs" the PSBT by echoing it back
+        assert_eq!(
+            run_signer_command("cat", "cHNidP8BAAoCAAAAAAAAAAAAAA==\n").unwrap(),
+            "cHNidP8BAAoCAAAAAAAAAAAAAA=="
+        );
+        assert!(run_signer_command("false", "cHNidP8=").is_err());
+        assert!(run_signer_command("true", "cHNidP8=")
+            .unwrap_err()
+            .contains("did not print a PSBT"));
+        assert!(run_signer_command("", "cHNidP8=").is_err());
+        assert!(run_signer_command("/nonexistent/signer", "cHNidP8=").is_err());
+    }
+}
@@ -0,0 +1,773 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A one-command local Nakamoto network (`stacks-node devnet`).
+//!
+//! The devnet runs a regtest `bitcoind` as a child process, a mining Stacks node, and a set of
+//! in-process signers in a single process.  The signers and a set of user accounts are funded
+//! at genesis, and the devnet stacks on the signers' behalf so that they make up the reward
+//! set when epoch 3.0 starts.  Until then, bitcoin blocks are mined as fast as the node can
+//! process them, which gets the chain to epoch 3.0 in under a minute.  From then on, a bitcoin
+//! block is mined every `block_time`.
+//!
+//! All keys are derived from fixed seeds, so a devnet restarted in the same working directory
+//! picks up where it left off.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use clarity::vm::costs::ExecutionCost;
+use clarity::vm::types::PrincipalData;
+use clarity::vm::{ClarityName, ContractName, Value};
+use libsigner::v0::messages::{BlockResponse, MessageSlotID, SignerMessage as SignerMessageV0};
+use libsigner::{BlockProposal, SignerSession, StackerDBSession};
+use stacks::burnchains::{Burnchain, MagicBytes, PrivateKey};
+use stacks::chainstate::stacks::address::PoxAddress;
+use stacks::chainstate::stacks::boot::{MINERS_NAME, POX_4_NAME};
+use stacks::chainstate::stacks::{
+    StacksTransaction, StacksTransactionSigner, TransactionAnchorMode, TransactionAuth,
+    TransactionContractCall, TransactionPostConditionMode, TransactionSpendingCondition,
+    TransactionVersion,
+};
+use stacks::codec::StacksMessageCodec;
+use stacks::core::{
+    EpochList, StacksEpoch, StacksEpochId, BLOCK_LIMIT_MAINNET_10, BLOCK_LIMIT_MAINNET_20,
+    BLOCK_LIMIT_MAINNET_205, BLOCK_LIMIT_MAINNET_21, PEER_VERSION_EPOCH_1_0,
+    PEER_VERSION_EPOCH_2_0, PEER_VERSION_EPOCH_2_05, PEER_VERSION_EPOCH_2_1,
+    PEER_VERSION_EPOCH_2_2, PEER_VERSION_EPOCH_2_3, PEER_VERSION_EPOCH_2_4, PEER_VERSION_EPOCH_2_5,
+    PEER_VERSION_EPOCH_3_0, PEER_VERSION_EPOCH_3_1,
+};
+use stacks::libstackerdb::StackerDBChunkData;
+use stacks::net::api::getaccount::AccountEntryResponse;
+use stacks::net::api::getinfo::RPCPeerInfoData;
+use stacks::net::httpcore::{send_http_request, StacksHttpRequest, StacksHttpResponse, TipRequest};
+use stacks::net::stackerdb::StackerDBs;
+use stacks::util::hash::{MerkleHashFunc, Sha512Trunc256Sum};
+use stacks::util_lib::boot::{boot_code_addr, boot_code_id};
+use stacks::util_lib::signed_structured_data::pox4::{
+    make_pox_4_signer_key_signature, Pox4SignatureTopic,
+};
+use stacks_common::address::AddressHashMode;
+use stacks_common::consts::STACKS_EPOCH_MAX;
+use stacks_common::types::chainstate::{StacksAddress, StacksPrivateKey, StacksPublicKey};
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::secp256k1::Secp256k1PublicKey;
+
+use crate::burnchains::bitcoin_regtest_controller::BitcoinRPCRequest;
+use crate::burnchains::bitcoind::BitcoinCoreController;
+use crate::run_loop::boot_nakamoto::BootRunLoop;
+use crate::{BitcoinRegtestController, Config, Keychain};
+
+/// Seed for the node's keychain, which holds the miner's bitcoin key
+const DEVNET_NODE_SEED: &[u8] = b"stacks-devnet-node";
+/// Number of bitcoin blocks to mine before starting the node, so that the miner's first
+/// coinbase has matured by the time it needs to send block-commits
+const DEVNET_BOOTSTRAP_BLOCKS: usize = 201;
+/// uSTX each signer holds at genesis
+pub const DEVNET_SIGNER_BALANCE: u64 = 100_000_000_000_000;
+/// uSTX each signer stacks
+pub const DEVNET_SIGNER_STACKED_AMOUNT: u128 = 99_000_000_000_000;
+/// uSTX each pre-funded account holds at genesis
+pub const DEVNET_ACCOUNT_BALANCE: u64 = 10_000_000_000_000;
+/// Longest lock period PoX allows, in reward cycles
+const DEVNET_LOCK_PERIOD: u64 = 12;
+/// Signers extend their lock once it has this many or fewer reward cycles left
+const DEVNET_EXTEND_THRESHOLD: u64 = 6;
+/// Fee paid by the signers' stacking transactions
+const DEVNET_STACKING_TX_FEE: u64 = 1_000;
+/// How often the signers look for new block proposals
+const DEVNET_SIGNER_POLL: Duration = Duration::from_millis(100);
+/// How long to wait for the node to process a bitcoin block before mining the next one anyway
+const DEVNET_BLOCK_PROCESS_TIMEOUT: Duration = Duration::from_secs(30);
+/// Timeout for RPC requests to the node
+const DEVNET_RPC_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Command-line options for `stacks-node devnet`
+#[derive(Debug, Clone)]
+pub struct DevnetOpts {
+    /// Directory holding the chainstate and bitcoind's data
+    pub working_dir: String,
+    /// Number of signers to run and stack for
+    pub num_signers: usize,
+    /// Number of pre-funded accounts to create
+    pub num_accounts: usize,
+    /// Time between bitcoin blocks once epoch 3.0 has started
+    pub block_time: Duration,
+    /// Port for the node's RPC server.  The p2p server listens on the next port.
+    pub rpc_port: u16,
+    /// Port for bitcoind's RPC server.  bitcoind's p2p server listens on the next port.
+    pub bitcoind_port: u16,
+}
+
+impl Default for DevnetOpts {
+    fn default() -> Self {
+        Self {
+            working_dir: std::env::temp_dir()
+                .join("stacks-devnet")
+                .to_string_lossy()
+                .into_owned(),
+            num_signers: 3,
+            num_accounts: 10,
+            block_time: Duration::from_secs(10),
+            rpc_port: 20443,
+            bitcoind_port: 18443,
+        }
+    }
+}
+
+/// The keys used by a devnet
+#[derive(Debug, Clone)]
+pub struct DevnetKeys {
+    /// Key the miner signs blocks and StackerDB messages with
+    pub miner: StacksPrivateKey,
+    /// Signing keys of the signers.  Each signer also stacks from the address of its key.
+    pub signers: Vec<StacksPrivateKey>,
+    /// Keys of the pre-funded accounts
+    pub accounts: Vec<StacksPrivateKey>,
+}
+
+impl DevnetKeys {
+    /// Derive the devnet keys.  The same numbers of signers and accounts always produce the
+    /// same keys.
+    pub fn new(num_signers: usize, num_accounts: usize) -> Self {
+        let derive = |prefix: &str, i: usize| {
+            StacksPrivateKey::from_seed(format!("stacks-devnet-{prefix}-{i}").as_bytes())
+        };
+        Self {
+            miner: StacksPrivateKey::from_seed(b"stacks-devnet-miner"),
+            signers: (0..num_signers).map(|i| derive("signer", i)).collect(),
+            accounts: (0..num_accounts).map(|i| derive("account", i)).collect(),
+        }
+    }
+}
+
+/// The devnet's epoch schedule.  Epochs 1.0 through 2.4 begin within the first bitcoin blocks,
+/// which are mined before the node starts.  Epoch 2.5 begins once the miner's first coinbase
+/// has matured, and epoch 3.0 begins a reward cycle and a half later, which leaves enough time
+/// to stack for the signers before the reward set is calculated.
+pub fn devnet_epochs() -> EpochList<ExecutionCost> {
+    let epoch = |epoch_id, start_height, end_height, block_limit, network_epoch| StacksEpoch {
+        epoch_id,
+        start_height,
+        end_height,
+        block_limit,
+        network_epoch,
+    };
+    EpochList::new(&[
+        epoch(
+            StacksEpochId::Epoch10,
+            0,
+            0,
+            BLOCK_LIMIT_MAINNET_10,
+            PEER_VERSION_EPOCH_1_0,
+        ),
+        epoch(
+            StacksEpochId::Epoch20,
+            0,
+            1,
+            BLOCK_LIMIT_MAINNET_20,
+            PEER_VERSION_EPOCH_2_0,
+        ),
+        epoch(
+            StacksEpochId::Epoch2_05,
+            1,
+            2,
+            BLOCK_LIMIT_MAINNET_205,
+            PEER_VERSION_EPOCH_2_05,
+        ),
+        epoch(
+            StacksEpochId::Epoch21,
+            2,
+            3,
+            BLOCK_LIMIT_MAINNET_21,
+            PEER_VERSION_EPOCH_2_1,
+        ),
+        epoch(
+            StacksEpochId::Epoch22,
+            3,
+            4,
+            BLOCK_LIMIT_MAINNET_21,
+            PEER_VERSION_EPOCH_2_2,
+        ),
+        epoch(
+            StacksEpochId::Epoch23,
+            4,
+            5,
+            BLOCK_LIMIT_MAINNET_21,
+            PEER_VERSION_EPOCH_2_3,
+        ),
+        epoch(
+            StacksEpochId::Epoch24,
+            5,
+            201,
+            BLOCK_LIMIT_MAINNET_21,
+            PEER_VERSION_EPOCH_2_4,
+        ),
+        epoch(
+            StacksEpochId::Epoch25,
+            201,
+            231,
+            BLOCK_LIMIT_MAINNET_21,
+            PEER_VERSION_EPOCH_2_5,
+        ),
+        epoch(
+            StacksEpochId::Epoch30,
+            231,
+            241,
+            BLOCK_LIMIT_MAINNET_21,
+            PEER_VERSION_EPOCH_3_0,
+        ),
+        epoch(
+            StacksEpochId::Epoch31,
+            241,
+            STACKS_EPOCH_MAX,
+            BLOCK_LIMIT_MAINNET_21,
+            PEER_VERSION_EPOCH_3_1,
+        ),
+    ])
+}
+
+/// Make the node config for a devnet
+pub fn make_devnet_config(opts: &DevnetOpts, keys: &DevnetKeys) -> Config {
+    let mut conf = Config::default();
+    let localhost = "127.0.0.1";
+
+    conf.node.working_dir = opts.working_dir.clone();
+    conf.node.seed = DEVNET_NODE_SEED.to_vec();
+    conf.node.rpc_bind = format!("{localhost}:{}", opts.rpc_port);
+    conf.node.p2p_bind = format!("{localhost}:{}", opts.rpc_port.saturating_add(1));
+    conf.node.data_url = format!("http://{}", &conf.node.rpc_bind);
+    conf.node.p2p_address = conf.node.p2p_bind.clone();
+    conf.node.miner = true;
+    conf.node.stacker = true;
+    conf.node.mine_microblocks = false;
+    conf.node.microblock_frequency = 0;
+    conf.node.wait_time_for_blocks = 200;
+    conf.node.pox_sync_sample_secs = 0;
+    conf.node.add_signers_stackerdbs(false);
+    conf.node.add_miner_stackerdb(false);
+
+    conf.miner.mining_key = Some(keys.miner.clone());
+    // there is only one node, so there is nothing to download
+    conf.miner.wait_for_block_download = false;
+
+    let keychain = Keychain::default(conf.node.seed.clone());
+    conf.burnchain.mode = "nakamoto-neon".into();
+    conf.burnchain.epochs = Some(devnet_epochs());
+    conf.burnchain.magic_bytes = MagicBytes::from([b'T', b'3'].as_ref());
+    conf.burnchain.pox_prepare_length = Some(5);
+    conf.burnchain.pox_reward_length = Some(20);
+    conf.burnchain.peer_host = localhost.into();
+    conf.burnchain.rpc_port = opts.bitcoind_port;
+    conf.burnchain.peer_port = opts.bitcoind_port.saturating_add(1);
+    conf.burnchain.username = Some("devnet".into());
+    conf.burnchain.password = Some("devnet".into());
+    conf.burnchain.local_mining_public_key =
+        Some(keychain.generate_op_signer().get_public_key().to_hex());
+    conf.burnchain.commit_anchor_block_within = 0;
+    conf.burnchain.burn_fee_cap = 20_000;
+    conf.burnchain.poll_time_secs = 1;
+
+    conf.connection_options.inv_sync_interval = 1;
+
+    for signer_key in keys.signers.iter() {
+        conf.add_initial_balance(
+            PrincipalData::from(stacks_address(signer_key)).to_string(),
+            DEVNET_SIGNER_BALANCE,
+        );
+    }
+    for account_key in keys.accounts.iter() {
+        conf.add_initial_balance(
+            PrincipalData::from(stacks_address(account_key)).to_string(),
+            DEVNET_ACCOUNT_BALANCE,
+        );
+    }
+
+    conf
+}
+
+/// The testnet single-sig address of a key
+fn stacks_address(privk: &StacksPrivateKey) -> StacksAddress {
+    StacksAddress::p2pkh(false, &StacksPublicKey::from_private(privk))
+}
+
+/// Print where to reach the devnet and the keys it uses
+fn print_devnet_info(config: &Config, keys: &DevnetKeys) {
+    println!("Stacks devnet");
+    println!("  working directory: {}", &config.node.working_dir);
+    println!("  Stacks RPC:        http://{}", &config.node.rpc_bind);
+    println!(
+        "  bitcoind RPC:      http://{}:{} (user {}, password {})",
+        &config.burnchain.peer_host,
+        config.burnchain.rpc_port,
+        config.burnchain.username.as_deref().unwrap_or(""),
+        config.burnchain.password.as_deref().unwrap_or("")
+    );
+    println!("Signers:");
+    for signer_key in keys.signers.iter() {
+        println!(
+            "  {} (key {})",
+            stacks_address(signer_key),
+            signer_key.to_hex()
+        );
+    }
+    println!("Accounts ({DEVNET_ACCOUNT_BALANCE} uSTX each):");
+    for account_key in keys.accounts.iter() {
+        println!(
+            "  {} (key {})",
+            stacks_address(account_key),
+            account_key.to_hex()
+        );
+    }
+}
+
+/// Run a devnet until the node is asked to stop
+pub fn run_devnet(opts: DevnetOpts) -> Result<(), String> {
+    if opts.num_signers == 0 {
+        return Err("A devnet needs at least one signer".into());
+    }
+    let keys = DevnetKeys::new(opts.num_signers, opts.num_accounts);
+    let config = make_devnet_config(&opts, &keys);
+    print_devnet_info(&config, &keys);
+
+    let mut bitcoind = BitcoinCoreController::new(config.clone());
+    bitcoind
+        .start_bitcoind()
+        .map_err(|e| format!("Failed to start bitcoind: {e}"))?;
+
+    // The miner must own mature UTXOs when the node starts, or it will not mine
+    let mut btc_controller = BitcoinRegtestController::new(config.clone(), None);
+    let btc_height = BitcoinRPCRequest::get_block_count(&config)
+        .map_err(|e| format!("Failed to query bitcoind: {e:?}"))?;
+    if btc_height == 0 {
+        let miner_pubkey = config
+            .burnchain
+            .local_mining_public_key
+            .as_ref()
+            .and_then(|pubkey| Secp256k1PublicKey::from_hex(pubkey).ok())
+            .ok_or("Invalid miner public key")?;
+        info!("Devnet: mining {DEVNET_BOOTSTRAP_BLOCKS} bitcoin blocks to fund the miner");
+        btc_controller.bootstrap_chain_to_pks(DEVNET_BOOTSTRAP_BLOCKS, &[miner_pubkey]);
+    }
+
+    let mut run_loop = BootRunLoop::new(config.clone())?;
+    let should_run = Arc::new(AtomicBool::new(true));
+    let driver = DevnetDriver {
+        config: config.clone(),
+        burnchain: config.get_burnchain(),
+        btc_controller,
+        signer_keys: keys.signers.clone(),
+        block_time: opts.block_time,
+        last_stacking_cycle: None,
+    };
+    let driver_thread = driver.spawn(should_run.clone())?;
+    let signers = DevnetSigners {
+        config: config.clone(),
+        signer_keys: keys.signers.clone(),
+        signed_blocks: HashSet::new(),
+    };
+    let signers_thread = signers.spawn(should_run.clone())?;
+
+    run_loop.start(None, 0);
+
+    info!("Devnet: node stopped, shutting down");
+    should_run.store(false, Ordering::SeqCst);
+    for thread in [driver_thread, signers_thread] {
+        if let Err(e) = thread.join() {
+            warn!("Devnet: failed to join thread: {e:?}");
+        }
+    }
+    bitcoind
+        .stop_bitcoind()
+        .map_err(|e| format!("Failed to stop bitcoind: {e}"))
+}
+
+/// Mines the devnet's bitcoin blocks, and stacks for the signers
+struct DevnetDriver {
+    config: Config,
+    burnchain: Burnchain,
+    btc_controller: BitcoinRegtestController,
+    signer_keys: Vec<StacksPrivateKey>,
+    block_time: Duration,
+    /// Reward cycle in which the signers' stacking was last checked
+    last_stacking_cycle: Option<u64>,
+}
+
+impl DevnetDriver {
+    fn spawn(self, should_run: Arc<AtomicBool>) -> Result<JoinHandle<()>, String> {
+        thread::Builder::new()
+            .name("devnet-driver".into())
+            .spawn(move || self.run(should_run))
+            .map_err(|e| format!("Failed to spawn devnet driver thread: {e}"))
+    }
+
+    fn run(mut self, should_run: Arc<AtomicBool>) {
+        let epochs = self.config.burnchain.get_epoch_list();
+        let epoch_3_start = epochs
+            .get(StacksEpochId::Epoch30)
+            .map(|epoch| epoch.start_height)
+            .unwrap_or(STACKS_EPOCH_MAX);
+
+        let mut last_block_time = Instant::now();
+        while should_run.load(Ordering::SeqCst) {
+            let Some(info) = self.get_info() else {
+                thread::sleep(Duration::from_secs(1));
+                continue;
+            };
+            self.maybe_stack(&info);
+
+            if info.burn_block_height + 1 < epoch_3_start {
+                // Epoch 2.x: mine as soon as the node has caught up
+                self.mine_block_and_wait(&should_run);
+                continue;
+            }
+            if last_block_time.elapsed() >= self.block_time {
+                if info.burn_block_height + 1 == epoch_3_start {
+                    info!("Devnet: mining the first epoch 3.0 bitcoin block");
+                }
+                self.btc_controller.build_next_block(1);
+                last_block_time = Instant::now();
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    /// Mine a bitcoin block, and wait for the node to process it
+    fn mine_block_and_wait(&mut self, should_run: &AtomicBool) {
+        self.btc_controller.build_next_block(1);
+        let Ok(btc_height) = BitcoinRPCRequest::get_block_count(&self.config) else {
+            return;
+        };
+        let start = Instant::now();
+        while should_run.load(Ordering::SeqCst) {
+            if self
+                .get_info()
+                .is_some_and(|info| info.burn_block_height >= btc_height)
+            {
+                return;
+            }
+            if start.elapsed() > DEVNET_BLOCK_PROCESS_TIMEOUT {
+                warn!(
+                    "Devnet: timed out waiting for the node to process bitcoin block {btc_height}"
+                );
+                return;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    /// Stack for any signer that is not stacked, and extend the lock of any signer whose STX
+    /// unlock soon.  This is done once per reward cycle, during the reward phase, once pox-4
+    /// is active and the chain has a Stacks block to build on.
+    fn maybe_stack(&mut self, info: &RPCPeerInfoData) {
+        let burn_height = info.burn_block_height;
+        let epochs = self.config.burnchain.get_epoch_list();
+        let Some(epoch_25) = epochs.get(StacksEpochId::Epoch25) else {
+            return;
+        };
+        if burn_height < epoch_25.start_height + 2 || info.stacks_tip_height == 0 {
+            return;
+        }
+        if self.burnchain.is_in_prepare_phase(burn_height) {
+            return;
+        }
+        let Some(reward_cycle) = self.burnchain.block_height_to_reward_cycle(burn_height) else {
+            return;
+        };
+        if self.last_stacking_cycle == Some(reward_cycle) {
+            return;
+        }
+        self.last_stacking_cycle = Some(reward_cycle);
+
+        for signer_key in self.signer_keys.iter() {
+            if let Err(e) = self.stack_signer(signer_key, burn_height, reward_cycle) {
+                warn!(
+                    "Devnet: failed to stack for signer {}: {e}",
+                    stacks_address(signer_key)
+                );
+            }
+        }
+    }
+
+    fn stack_signer(
+        &self,
+        signer_key: &StacksPrivateKey,
+        burn_height: u64,
+        reward_cycle: u64,
+    ) -> Result<(), String> {
+        let address = stacks_address(signer_key);
+        let account = self.get_account(&address)?;
+        let locked = u128::from_str_radix(account.locked.trim_start_matches("0x"), 16)
+            .map_err(|e| format!("Invalid locked balance {}: {e}", &account.locked))?;
+        let pox_addr =
+            PoxAddress::from_legacy(AddressHashMode::SerializeP2PKH, address.bytes().clone());
+        let pox_addr_tuple: Value = pox_addr
+            .clone()
+            .as_clarity_tuple()
+            .ok_or("Failed to make a PoX address tuple")?
+            .into();
+        let signer_pubkey =
+            Value::buff_from(StacksPublicKey::from_private(signer_key).to_bytes_compressed())
+                .map_err(|e| e.to_string())?;
+
+        let (function_name, topic, period, mut args) = if locked == 0 {
+            info!("Devnet: stacking for signer {address}"; "reward_cycle" => reward_cycle);
+            (
+                "stack-stx",
+                Pox4SignatureTopic::StackStx,
+                DEVNET_LOCK_PERIOD,
+                vec![
+                    Value::UInt(DEVNET_SIGNER_STACKED_AMOUNT),
+                    pox_addr_tuple,
+                    Value::UInt(burn_height.into()),
+                    Value::UInt(DEVNET_LOCK_PERIOD.into()),
+                ],
+            )
+        } else {
+            let unlock_cycle = self
+                .burnchain
+                .block_height_to_reward_cycle(account.unlock_height)
+                .ok_or("Unlock height is before the first burnchain block")?;
+            let cycles_left = unlock_cycle.saturating_sub(reward_cycle);
+            if cycles_left > DEVNET_EXTEND_THRESHOLD {
+                return Ok(());
+            }
+            // keep the total lock period at the maximum PoX allows
+            let extend_count = DEVNET_LOCK_PERIOD.saturating_sub(cycles_left);
+            info!("Devnet: extending stacking for signer {address}"; "reward_cycle" => reward_cycle, "extend_count" => extend_count);
+            (
+                "stack-extend",
+                Pox4SignatureTopic::StackExtend,
+                extend_count,
+                vec![Value::UInt(extend_count.into()), pox_addr_tuple],
+            )
+        };
+
+        let signature = make_pox_4_signer_key_signature(
+            &pox_addr,
+            signer_key,
+            reward_cycle.into(),
+            &topic,
+            self.config.burnchain.chain_id,
+            period.into(),
+            u128::MAX,
+            reward_cycle.into(),
+        )?
+        .to_rsv();
+        args.extend([
+            Value::some(Value::buff_from(signature).map_err(|e| e.to_string())?)
+                .map_err(|e| e.to_string())?,
+            signer_pubkey,
+            Value::UInt(u128::MAX),
+            Value::UInt(reward_cycle.into()),
+        ]);
+
+        let tx = make_pox_4_call(
+            signer_key,
+            account.nonce,
+            self.config.burnchain.chain_id,
+            function_name,
+            args,
+        )?;
+        self.post_transaction(tx)
+    }
+
+    /// Send a request to the node's RPC server
+    fn rpc_request(
+        &self,
+        make_request: impl FnOnce(PeerHost) -> StacksHttpRequest,
+    ) -> Result<StacksHttpResponse, String> {
+        let rpc_port = self.config.node.get_rpc_loopback().map(|addr| addr.port());
+        let rpc_port = rpc_port.ok_or("Invalid RPC bind address")?;
+        let peerhost: PeerHost = format!("127.0.0.1:{rpc_port}")
+            .parse()
+            .map_err(|e| format!("Invalid RPC address: {e:?}"))?;
+        let mut request = make_request(peerhost);
+        request.add_header("Connection".into(), "close".into());
+        send_http_request("127.0.0.1", rpc_port, request, DEVNET_RPC_TIMEOUT)
+            .map_err(|e| e.to_string())
+    }
+
+    fn get_info(&self) -> Option<RPCPeerInfoData> {
+        self.rpc_request(|host| StacksHttpRequest::new_getinfo(host, None))
+            .and_then(|response| response.decode_peer_info().map_err(|e| e.to_string()))
+            .ok()
+    }
+
+    fn get_account(&self, address: &StacksAddress) -> Result<AccountEntryResponse, String> {
+        self.rpc_request(|host| {
+            StacksHttpRequest::new_getaccount(
+                host,
+                PrincipalData::from(*address),
+                TipRequest::UseLatestAnchoredTip,
+                false,
+            )
+        })?
+        .decode_account_entry_response()
+        .map_err(|e| e.to_string())
+    }
+
+    fn post_transaction(&self, tx: StacksTransaction) -> Result<(), String> {
+        let txid = self
+            .rpc_request(|host| StacksHttpRequest::new_post_transaction(host, tx))?
+            .decode_txid()
+            .map_err(|e| e.to_string())?;
+        debug!("Devnet: submitted stacking transaction {txid}");
+        Ok(())
+    }
+}
+
+/// Make a signed call to pox-4
+fn make_pox_4_call(
+    sender: &StacksPrivateKey,
+    nonce: u64,
+    chain_id: u32,
+    function_name: &str,
+    function_args: Vec<Value>,
+) -> Result<StacksTransaction, String> {
+    let payload = TransactionContractCall {
+        address: boot_code_addr(false),
+        contract_name: ContractName::from(POX_4_NAME),
+        function_name: ClarityName::from(function_name),
+        function_args,
+    };
+    let mut spending_condition =
+        TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(sender))
+            .ok_or("Failed to create p2pkh spending condition")?;
+    spending_condition.set_nonce(nonce);
+    spending_condition.set_tx_fee(DEVNET_STACKING_TX_FEE);
+
+    let mut unsigned_tx = StacksTransaction::new(
+        TransactionVersion::Testnet,
+        TransactionAuth::Standard(spending_condition),
+        payload.into(),
+    );
+    unsigned_tx.anchor_mode = TransactionAnchorMode::OnChainOnly;
+    unsigned_tx.post_condition_mode = TransactionPostConditionMode::Allow;
+    unsigned_tx.chain_id = chain_id;
+
+    let mut tx_signer = StacksTransactionSigner::new(&unsigned_tx);
+    tx_signer.sign_origin(sender).map_err(|e| e.to_string())?;
+    tx_signer
+        .get_tx()
+        .ok_or_else(|| "Failed to sign transaction".to_string())
+}
+
+/// The devnet's signers.  They approve every block the miner proposes by writing a
+/// `BlockAccepted` response to their slots in the signers' StackerDB, as a `stacks-signer`
+/// would, but without validating the block first.
+struct DevnetSigners {
+    config: Config,
+    signer_keys: Vec<StacksPrivateKey>,
+    /// Signer signature hashes of the blocks approved so far
+    signed_blocks: HashSet<Sha512Trunc256Sum>,
+}
+
+impl DevnetSigners {
+    fn spawn(self, should_run: Arc<AtomicBool>) -> Result<JoinHandle<()>, String> {
+        thread::Builder::new()
+            .name("devnet-signers".into())
+            .spawn(move || self.run(should_run))
+            .map_err(|e| format!("Failed to spawn devnet signers thread: {e}"))
+    }
+
+    fn run(mut self, should_run: Arc<AtomicBool>) {
+        while should_run.load(Ordering::SeqCst) {
+            if let Err(e) = self.sign_new_proposals() {
+                debug!("Devnet: failed to sign block proposals: {e}");
+            }
+            thread::sleep(DEVNET_SIGNER_POLL);
+        }
+    }
+
+    /// Approve any block proposals in the miners' StackerDB that have not been approved yet
+    fn sign_new_proposals(&mut self) -> Result<(), String> {
+        let stackerdbs = StackerDBs::connect(&self.config.get_stacker_db_file_path(), false)
+            .map_err(|e| e.to_string())?;
+        let miners_contract_id = boot_code_id(MINERS_NAME, false);
+        let num_slots = stackerdbs
+            .get_slot_versions(&miners_contract_id)
+            .map_err(|e| e.to_string())?
+            .len();
+        for slot_id in 0..num_slots {
+            let slot_id = u32::try_from(slot_id).map_err(|e| e.to_string())?;
+            let Some(chunk) = stackerdbs
+                .get_latest_chunk(&miners_contract_id, slot_id)
+                .map_err(|e| e.to_string())?
+            else {
+                continue;
+            };
+            let Ok(SignerMessageV0::BlockProposal(proposal)) =
+                SignerMessageV0::consensus_deserialize(&mut chunk.as_slice())
+            else {
+                continue;
+            };
+            let signer_sighash = proposal.block.header.signer_signature_hash();
+            if self.signed_blocks.contains(&signer_sighash) {
+                continue;
+            }
+            self.accept_block(&stackerdbs, &proposal)?;
+            self.signed_blocks.insert(signer_sighash);
+        }
+        Ok(())
+    }
+
+    /// Write an approval of the proposed block to the slot of every devnet signer in the
+    /// proposal's reward cycle
+    fn accept_block(
+        &self,
+        stackerdbs: &StackerDBs,
+        proposal: &BlockProposal,
+    ) -> Result<(), String> {
+        let signer_sighash = proposal.block.header.signer_signature_hash();
+        let contract_id =
+            MessageSlotID::BlockResponse.stacker_db_contract(false, proposal.reward_cycle);
+        let mut session = StackerDBSession::new(&self.config.node.rpc_bind, contract_id.clone());
+
+        let mut slot_id = 0;
+        while let Some(slot_signer) = stackerdbs
+            .get_slot_signer(&contract_id, slot_id)
+            .map_err(|e| e.to_string())?
+        {
+            let signer_key = self
+                .signer_keys
+                .iter()
+                .find(|signer_key| stacks_address(signer_key) == slot_signer);
+            if let Some(signer_key) = signer_key {
+                let signature = signer_key.sign(signer_sighash.bits())?;
+                let response: SignerMessageV0 =
+                    BlockResponse::accepted(signer_sighash, signature, u64::MAX).into();
+                let slot_version = stackerdbs
+                    .get_slot_version(&contract_id, slot_id)
+                    .map_err(|e| e.to_string())?
+                    .unwrap_or(0)
+                    .saturating_add(1);
+                let mut chunk =
+                    StackerDBChunkData::new(slot_id, slot_version, response.serialize_to_vec());
+                chunk.sign(signer_key).map_err(|e| e.to_string())?;
+                let ack = session.put_chunk(&chunk).map_err(|e| format!("{e:?}"))?;
+                if !ack.accepted {
+                    warn!("Devnet: signer's block response was not stored: {ack:?}");
+                }
+            }
+            slot_id += 1;
+        }
+        info!("Devnet: signers approved block"; "signer_sighash" => %signer_sighash, "height" => proposal.block.header.chain_length);
+        Ok(())
+    }
+}
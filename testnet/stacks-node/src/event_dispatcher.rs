@@ -21,7 +21,7 @@ use std::sync::mpsc::{channel, Receiver, Sender};
 #[cfg(test)]
 use std::sync::LazyLock;
 use std::sync::{Arc, Mutex};
-use std::thread::sleep;
+use std::thread::{self, sleep};
 use std::time::Duration;
 
 use clarity::vm::analysis::contract_interface_builder::build_contract_interface;
@@ -33,10 +33,14 @@ use lazy_static::lazy_static;
 use rand::Rng;
 use rusqlite::{params, Connection};
 use serde_json::json;
+use stacks::burnchains::bitcoin::spv::{HeaderSyncObserver, HeaderSyncStatus};
 use stacks::burnchains::{PoxConstants, Txid};
 use stacks::chainstate::burn::operations::BlockstackOperationType;
 use stacks::chainstate::burn::ConsensusHash;
-use stacks::chainstate::coordinator::BlockEventDispatcher;
+use stacks::chainstate::coordinator::comm::PendingStacksReorg;
+use stacks::chainstate::coordinator::{
+    BlockEventDispatcher, PoxAnchorBlockStatus, RewardCycleInfo,
+};
 use stacks::chainstate::nakamoto::NakamotoBlock;
 use stacks::chainstate::stacks::address::PoxAddress;
 use stacks::chainstate::stacks::boot::{
@@ -53,16 +57,18 @@ use stacks::chainstate::stacks::miner::TransactionEvent;
 use stacks::chainstate::stacks::{
     StacksBlock, StacksMicroblock, StacksTransaction, TransactionPayload,
 };
-use stacks::config::{EventKeyType, EventObserverConfig};
+use stacks::config::{EventKeyType, EventObserverConfig, StackerDBStandbyConfig};
 use stacks::core::mempool::{MemPoolDropReason, MemPoolEventDispatcher, ProposalCallbackReceiver};
 use stacks::libstackerdb::StackerDBChunkData;
+use stacks::monitoring::chain_quality::ChainQualityAlert;
+use stacks::net::api::getminerreports::MinerTenureReport;
 use stacks::net::api::postblock_proposal::{
     BlockValidateOk, BlockValidateReject, BlockValidateResponse,
 };
 use stacks::net::atlas::{Attachment, AttachmentInstance};
 use stacks::net::http::HttpRequestContents;
 use stacks::net::httpcore::{send_http_request, StacksHttpRequest};
-use stacks::net::stackerdb::StackerDBEventDispatcher;
+use stacks::net::stackerdb::{StackerDBEventDispatcher, StackerDBReplicationQueue};
 use stacks::util::hash::to_hex;
 #[cfg(any(test, feature = "testing"))]
 use stacks::util::tests::TestFlag;
@@ -75,6 +81,8 @@ use stacks_common::util::hash::{bytes_to_hex, Sha512Trunc256Sum};
 use stacks_common::util::secp256k1::MessageSignature;
 use url::Url;
 
+use crate::stackerdb_replication::StackerDBReplicator;
+
 #[cfg(any(test, feature = "testing"))]
 lazy_static! {
     /// Do not announce a signed/mined block to the network when set to true.
@@ -90,6 +98,20 @@ struct EventObserver {
     endpoint: String,
     /// Timeout for sending events to this observer
     timeout: Duration,
+    /// Channel to this observer's delivery thread, if it has one.  Observers with a delivery
+    /// thread retry failed sends on that thread, so an unreachable observer only delays its own
+    /// events.  If `None`, events are sent on the caller's thread.
+    delivery_tx: Option<Sender<ObserverDelivery>>,
+}
+
+/// Work for an event observer's delivery thread
+enum ObserverDelivery {
+    /// New payloads were stored in the observer's database
+    Pending,
+    /// Send a payload to the given URL.  Used by observers without a database.
+    Payload(serde_json::Value, String),
+    /// Signal the given channel once everything queued before this has been delivered
+    Flush(Sender<()>),
 }
 
 struct ReceiptPayloadInfo<'a> {
@@ -115,6 +137,11 @@ pub const PATH_MINED_NAKAMOTO_BLOCK: &str = "mined_nakamoto_block";
 pub const PATH_STACKERDB_CHUNKS: &str = "stackerdb_chunks";
 pub const PATH_BURN_BLOCK_SUBMIT: &str = "new_burn_block";
 pub const PATH_BLOCK_PROCESSED: &str = "new_block";
+pub const PATH_RAW_BLOCK_PROCESSED: &str = "new_raw_block";
+pub const PATH_REWARD_CYCLE: &str = "new_reward_cycle";
+pub const PATH_ALERT: &str = "alert";
+pub const PATH_BURNCHAIN_SYNC_PROGRESS: &str = "burnchain_sync_progress";
+pub const PATH_MINER_TENURE_REPORT: &str = "miner_tenure_report";
 pub const PATH_ATTACHMENT_PROCESSED: &str = "attachments/new";
 pub const PATH_PROPOSAL_RESPONSE: &str = "proposal_response";
 
@@ -171,6 +198,17 @@ pub struct MinedNakamotoBlockEvent {
     pub signer_signature: Vec<MessageSignature>,
 }
 
+/// Payload sent to `raw_blocks` observers for each processed Nakamoto block
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RawNakamotoBlockEvent {
+    pub block_id: String,
+    pub consensus_hash: String,
+    pub block_hash: String,
+    pub block_height: u64,
+    /// Hex-encoded, consensus-serialized `NakamotoBlock`
+    pub raw_block: String,
+}
+
 impl InnerStackerDBChannel {
     pub fn new_miner_receiver() -> (Receiver<StackerDBChunksEvent>, Self) {
         let (sender, recv) = channel();
@@ -425,7 +463,9 @@ impl EventObserver {
         Ok(())
     }
 
-    fn process_pending_payloads(conn: &Connection) {
+    /// Send the pending payloads whose URLs start with `url_prefix`, in the order they were
+    /// stored.  Payloads for other observers that share the database are left alone.
+    fn process_pending_payloads(conn: &Connection, url_prefix: &str) {
         let pending_payloads = match Self::get_pending_payloads(conn) {
             Ok(payloads) => payloads,
             Err(e) => {
@@ -438,6 +478,9 @@ impl EventObserver {
         };
 
         for (id, url, payload, timeout_ms) in pending_payloads {
+            if !url.starts_with(url_prefix) {
+                continue;
+            }
             let timeout = Duration::from_millis(timeout_ms);
             Self::send_payload_directly(&payload, &url, timeout);
 
@@ -541,19 +584,80 @@ impl EventObserver {
             db_path,
             endpoint,
             timeout,
+            delivery_tx: None,
         }
     }
 
-    /// Send the payload to the given URL.
-    /// Before sending this payload, any pending payloads in the database will be sent first.
-    pub fn send_payload(&self, payload: &serde_json::Value, path: &str) {
-        // Construct the full URL
+    /// Start a thread that delivers this observer's events from now on.  The thread first sends
+    /// any payloads that were left pending in the database by a previous run.
+    fn spawn_delivery_thread(&mut self) {
+        let (delivery_tx, delivery_rx) = channel();
+        let observer = self.clone();
+        thread::Builder::new()
+            .name(format!("event-observer:{}", &self.endpoint))
+            .spawn(move || observer.run_delivery(delivery_rx))
+            .expect("FATAL: failed to spawn event observer delivery thread");
+        self.delivery_tx = Some(delivery_tx);
+    }
+
+    /// Main loop of an observer's delivery thread.  Runs until every handle to the observer has
+    /// been dropped.
+    fn run_delivery(self, delivery_rx: Receiver<ObserverDelivery>) {
+        if self.db_path.is_some() {
+            self.send_pending_payloads();
+        }
+        while let Ok(delivery) = delivery_rx.recv() {
+            match delivery {
+                ObserverDelivery::Pending => self.send_pending_payloads(),
+                ObserverDelivery::Payload(payload, full_url) => {
+                    Self::send_payload_directly(&payload, &full_url, self.timeout)
+                }
+                ObserverDelivery::Flush(done_tx) => {
+                    let _ = done_tx.send(());
+                }
+            }
+        }
+        debug!("Event observer: delivery thread exiting"; "endpoint" => %self.endpoint);
+    }
+
+    /// Block until this observer's delivery thread, if it has one, has delivered every payload
+    /// handed to it so far
+    fn wait_for_delivery(&self) {
+        let Some(delivery_tx) = &self.delivery_tx else {
+            return;
+        };
+        let (done_tx, done_rx) = channel();
+        if delivery_tx.send(ObserverDelivery::Flush(done_tx)).is_err() {
+            return;
+        }
+        let _ = done_rx.recv();
+    }
+
+    /// Send this observer's pending payloads from the database
+    fn send_pending_payloads(&self) {
+        let Some(db_path) = &self.db_path else {
+            return;
+        };
+        let conn = Connection::open(db_path).expect("Failed to open database for event observer");
+        Self::process_pending_payloads(&conn, &self.full_url(""));
+    }
+
+    /// Construct the full URL of `path` on this observer
+    fn full_url(&self, path: &str) -> String {
         let url_str = if path.starts_with('/') {
             format!("{}{path}", &self.endpoint)
         } else {
             format!("{}/{path}", &self.endpoint)
         };
-        let full_url = format!("http://{url_str}");
+        format!("http://{url_str}")
+    }
+
+    /// Send the payload to the given URL.
+    /// Before sending this payload, any pending payloads in the database will be sent first.
+    /// If this observer has a delivery thread, the payload is handed to it and this returns
+    /// without waiting for the observer to accept it.
+    pub fn send_payload(&self, payload: &serde_json::Value, path: &str) {
+        let full_url = self.full_url(path);
 
         if let Some(db_path) = &self.db_path {
             let conn =
@@ -562,8 +666,30 @@ impl EventObserver {
             // Insert the new payload into the database
             Self::insert_payload_with_retry(&conn, &full_url, payload, self.timeout);
 
-            // Process all pending payloads
-            Self::process_pending_payloads(&conn);
+            match &self.delivery_tx {
+                Some(delivery_tx) => {
+                    // The payload stays in the database, so it is sent on restart even if
+                    // the delivery thread is gone.
+                    if delivery_tx.send(ObserverDelivery::Pending).is_err() {
+                        error!(
+                            "Event observer: delivery thread is not running";
+                            "endpoint" => %self.endpoint
+                        );
+                    }
+                }
+                // Process all pending payloads
+                None => Self::process_pending_payloads(&conn, &self.full_url("")),
+            }
+        } else if let Some(delivery_tx) = &self.delivery_tx {
+            if delivery_tx
+                .send(ObserverDelivery::Payload(payload.clone(), full_url))
+                .is_err()
+            {
+                error!(
+                    "Event observer: delivery thread is not running, dropping payload";
+                    "endpoint" => %self.endpoint
+                );
+            }
         } else {
             // No database, just send the payload
             Self::send_payload_directly(payload, &full_url, self.timeout);
@@ -778,6 +904,155 @@ impl EventObserver {
         self.send_payload(payload, PATH_BURN_BLOCK_SUBMIT);
     }
 
+    fn send_raw_nakamoto_block(&self, payload: &serde_json::Value) {
+        self.send_payload(payload, PATH_RAW_BLOCK_PROCESSED);
+    }
+
+    fn send_reward_cycle(&self, payload: &serde_json::Value) {
+        self.send_payload(payload, PATH_REWARD_CYCLE);
+    }
+
+    fn send_alert(&self, payload: &serde_json::Value) {
+        self.send_payload(payload, PATH_ALERT);
+    }
+
+    fn send_burnchain_sync_progress(&self, payload: &serde_json::Value) {
+        self.send_payload(payload, PATH_BURNCHAIN_SYNC_PROGRESS);
+    }
+
+    fn send_miner_tenure_report(&self, payload: &serde_json::Value) {
+        self.send_payload(payload, PATH_MINER_TENURE_REPORT);
+    }
+
+    fn make_deep_reorg_alert_payload(reorg: &PendingStacksReorg) -> serde_json::Value {
+        json!({
+            "alert": "deep_stacks_reorg",
+            "severity": "critical",
+            "message": format!(
+                "Stacks block processing is paused: switching to the fork at {} would abandon {} blocks, more than node.max_reorg_depth ({}) allows",
+                &reorg.new_tip, reorg.depth, reorg.max_depth
+            ),
+            "old_tip": format!("0x{}", &reorg.old_tip),
+            "old_tip_height": reorg.old_tip_height,
+            "new_tip": format!("0x{}", &reorg.new_tip),
+            "new_tip_height": reorg.new_tip_height,
+            "fork_height": reorg.fork_height,
+            "depth": reorg.depth,
+            "max_depth": reorg.max_depth,
+        })
+    }
+
+    fn make_chain_quality_alert_payload(alert: &ChainQualityAlert) -> serde_json::Value {
+        let mut payload = json!({
+            "alert": alert.name(),
+            "severity": "warning",
+            "message": alert.message(),
+        });
+        let details = match alert {
+            ChainQualityAlert::MissedSortitions { burn_height, count } => json!({
+                "burn_block_height": burn_height,
+                "count": count,
+            }),
+            ChainQualityAlert::EmptyTenures {
+                consensus_hash,
+                count,
+            } => json!({
+                "consensus_hash": format!("0x{consensus_hash}"),
+                "count": count,
+            }),
+            ChainQualityAlert::LowSignerParticipation {
+                block_id,
+                participation,
+                threshold,
+            } => json!({
+                "index_block_hash": format!("0x{block_id}"),
+                "participation": participation,
+                "threshold": threshold,
+            }),
+        };
+        if let (Some(payload), serde_json::Value::Object(details)) =
+            (payload.as_object_mut(), details)
+        {
+            payload.extend(details);
+        }
+        payload
+    }
+
+    fn make_reward_cycle_payload(
+        reward_cycle_info: &RewardCycleInfo,
+        burn_block: &BurnchainHeaderHash,
+        burn_block_height: u64,
+        consensus_hash: &ConsensusHash,
+        pox_constants: &PoxConstants,
+    ) -> serde_json::Value {
+        let (status, reward_set) = match &reward_cycle_info.anchor_status {
+            PoxAnchorBlockStatus::SelectedAndKnown(_, _, reward_set) => {
+                ("selected_and_known", Some(reward_set))
+            }
+            PoxAnchorBlockStatus::SelectedAndUnknown(..) => ("selected_and_unknown", None),
+            PoxAnchorBlockStatus::NotSelected => ("not_selected", None),
+        };
+        let (anchor_block_hash, anchor_block_txid) = match reward_cycle_info.selected_anchor_block()
+        {
+            Some((block_hash, txid)) => {
+                (json!(format!("0x{block_hash}")), json!(format!("0x{txid}")))
+            }
+            None => (serde_json::Value::Null, serde_json::Value::Null),
+        };
+
+        let reward_set_value = reward_set
+            .map(|reward_set| {
+                serde_json::to_value(RewardSetEventPayload::from_reward_set(reward_set))
+                    .unwrap_or_default()
+            })
+            .unwrap_or_default();
+        let total_signer_weight = reward_set
+            .and_then(|reward_set| reward_set.signers.as_ref())
+            .map(|signers| {
+                signers
+                    .iter()
+                    .map(|signer| u64::from(signer.weight))
+                    .sum::<u64>()
+            });
+        let early_unlocks: Vec<_> = reward_set
+            .map(|reward_set| {
+                reward_set
+                    .start_cycle_state
+                    .missed_reward_slots
+                    .iter()
+                    .map(|(principal, amount)| {
+                        json!({
+                            "principal": principal.to_string(),
+                            "amount": amount.to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        json!({
+            "reward_cycle": reward_cycle_info.reward_cycle,
+            "burn_block_hash": format!("0x{burn_block}"),
+            "burn_block_height": burn_block_height,
+            "consensus_hash": format!("0x{consensus_hash}"),
+            "reward_cycle_length": pox_constants.reward_cycle_length,
+            "prepare_phase_length": pox_constants.prepare_length,
+            "anchor_block": {
+                "status": status,
+                "block_hash": anchor_block_hash,
+                "txid": anchor_block_txid,
+            },
+            "reward_set": reward_set_value,
+            "total_signer_weight": total_signer_weight,
+            "unlocks": {
+                "pox_v1_unlock_height": pox_constants.v1_unlock_height,
+                "pox_v2_unlock_height": pox_constants.v2_unlock_height,
+                "pox_v3_unlock_height": pox_constants.v3_unlock_height,
+                "early_unlocks": early_unlocks,
+            },
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn make_new_block_processed_payload(
         &self,
@@ -921,8 +1196,20 @@ pub struct EventDispatcher {
     /// Index into `registered_observers` that will receive block proposal events (Nakamoto and
     /// later)
     block_proposal_observers_lookup: HashSet<u16>,
+    /// Index into `registered_observers` that will receive consensus-serialized Nakamoto blocks
+    raw_block_observers_lookup: HashSet<u16>,
+    /// Index into `registered_observers` that will receive reward cycle transitions
+    reward_cycle_observers_lookup: HashSet<u16>,
+    /// Index into `registered_observers` that will receive operator alerts
+    alert_observers_lookup: HashSet<u16>,
+    /// Index into `registered_observers` that will receive Bitcoin header download progress
+    burnchain_sync_observers_lookup: HashSet<u16>,
+    /// Index into `registered_observers` that will receive the miner's tenure reports
+    miner_report_observers_lookup: HashSet<u16>,
     /// Channel for sending StackerDB events to the miner coordinator
     pub stackerdb_channel: Arc<Mutex<StackerDBChannel>>,
+    /// Forwards accepted StackerDB chunks to standby nodes, if any are configured
+    stackerdb_replicator: Option<StackerDBReplicator>,
 }
 
 /// This struct is used specifically for receiving proposal responses.
@@ -1093,6 +1380,10 @@ impl BlockEventDispatcher for EventDispatcher {
         );
     }
 
+    fn announce_raw_nakamoto_block(&self, block: &NakamotoBlock) {
+        self.process_raw_nakamoto_block(block);
+    }
+
     fn announce_burn_block(
         &self,
         burn_block: &BurnchainHeaderHash,
@@ -1111,6 +1402,37 @@ impl BlockEventDispatcher for EventDispatcher {
             consensus_hash,
         )
     }
+
+    fn announce_reward_cycle(
+        &self,
+        reward_cycle_info: &RewardCycleInfo,
+        burn_block: &BurnchainHeaderHash,
+        burn_block_height: u64,
+        consensus_hash: &ConsensusHash,
+        pox_constants: &PoxConstants,
+    ) {
+        self.process_reward_cycle(
+            reward_cycle_info,
+            burn_block,
+            burn_block_height,
+            consensus_hash,
+            pox_constants,
+        )
+    }
+
+    fn announce_deep_reorg(&self, reorg: &PendingStacksReorg) {
+        self.process_deep_reorg(reorg)
+    }
+
+    fn announce_chain_quality_alert(&self, alert: &ChainQualityAlert) {
+        self.process_chain_quality_alert(alert)
+    }
+}
+
+impl HeaderSyncObserver for EventDispatcher {
+    fn header_sync_progress(&self, status: &HeaderSyncStatus) {
+        self.process_header_sync_progress(status)
+    }
 }
 
 impl Default for EventDispatcher {
@@ -1135,9 +1457,40 @@ impl EventDispatcher {
             mined_microblocks_observers_lookup: HashSet::new(),
             stackerdb_observers_lookup: HashSet::new(),
             block_proposal_observers_lookup: HashSet::new(),
+            raw_block_observers_lookup: HashSet::new(),
+            reward_cycle_observers_lookup: HashSet::new(),
+            alert_observers_lookup: HashSet::new(),
+            burnchain_sync_observers_lookup: HashSet::new(),
+            miner_report_observers_lookup: HashSet::new(),
+            stackerdb_replicator: None,
+        }
+    }
+
+    /// Block until every observer has accepted the events dispatched so far.  This waits for as
+    /// long as an observer is unreachable.
+    pub fn wait_for_delivery(&self) {
+        for observer in self.registered_observers.iter() {
+            observer.wait_for_delivery();
         }
     }
 
+    /// Start replicating accepted StackerDB chunks to the given standby nodes.  The outbound
+    /// queue is kept in `working_dir`.
+    pub fn register_stackerdb_standbys(
+        &mut self,
+        standbys: &[StackerDBStandbyConfig],
+        working_dir: PathBuf,
+    ) {
+        self.stackerdb_replicator = StackerDBReplicator::spawn(standbys, working_dir);
+    }
+
+    /// Queues StackerDB chunks written over RPC for the standby nodes, if any are configured
+    pub fn stackerdb_replication(&self) -> Option<&dyn StackerDBReplicationQueue> {
+        self.stackerdb_replicator
+            .as_ref()
+            .map(|replicator| replicator as &dyn StackerDBReplicationQueue)
+    }
+
     pub fn process_burn_block(
         &self,
         burn_block: &BurnchainHeaderHash,
@@ -1547,6 +1900,131 @@ impl EventDispatcher {
         }
     }
 
+    /// Announce the start of a new reward cycle to `reward_cycles` observers.  These are not sent
+    /// to `AnyEvent` observers, which would otherwise start receiving a path they may not serve.
+    pub fn process_reward_cycle(
+        &self,
+        reward_cycle_info: &RewardCycleInfo,
+        burn_block: &BurnchainHeaderHash,
+        burn_block_height: u64,
+        consensus_hash: &ConsensusHash,
+        pox_constants: &PoxConstants,
+    ) {
+        // lazily assemble payload only if we have observers
+        let interested_observers =
+            self.filter_observers(&self.reward_cycle_observers_lookup, false);
+        if interested_observers.is_empty() {
+            return;
+        }
+
+        let payload = EventObserver::make_reward_cycle_payload(
+            reward_cycle_info,
+            burn_block,
+            burn_block_height,
+            consensus_hash,
+            pox_constants,
+        );
+
+        for observer in interested_observers.iter() {
+            observer.send_reward_cycle(&payload);
+        }
+    }
+
+    /// Alert `alerts` observers that block processing is paused on a deep Stacks reorg.  Like
+    /// reward cycle events, these are not sent to `AnyEvent` observers.
+    pub fn process_deep_reorg(&self, reorg: &PendingStacksReorg) {
+        let interested_observers = self.filter_observers(&self.alert_observers_lookup, false);
+        if interested_observers.is_empty() {
+            return;
+        }
+
+        let payload = EventObserver::make_deep_reorg_alert_payload(reorg);
+        for observer in interested_observers.iter() {
+            observer.send_alert(&payload);
+        }
+    }
+
+    /// Alert `alerts` observers about missed sortitions, empty tenures, or low signer
+    /// participation.
+    pub fn process_chain_quality_alert(&self, alert: &ChainQualityAlert) {
+        let interested_observers = self.filter_observers(&self.alert_observers_lookup, false);
+        if interested_observers.is_empty() {
+            return;
+        }
+
+        let payload = EventObserver::make_chain_quality_alert_payload(alert);
+        for observer in interested_observers.iter() {
+            observer.send_alert(&payload);
+        }
+    }
+
+    /// Report the progress of the Bitcoin header download to `burnchain_sync` observers.  These
+    /// are not sent to `AnyEvent` observers.
+    pub fn process_header_sync_progress(&self, status: &HeaderSyncStatus) {
+        let interested_observers =
+            self.filter_observers(&self.burnchain_sync_observers_lookup, false);
+        if interested_observers.is_empty() {
+            return;
+        }
+
+        let payload = match serde_json::to_value(status) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!("Failed to serialize header sync progress: {e:?}");
+                return;
+            }
+        };
+        for observer in interested_observers.iter() {
+            observer.send_burnchain_sync_progress(&payload);
+        }
+    }
+
+    /// Send the report of a tenure this node mined to `miner_reports` observers.  These are not
+    /// sent to `AnyEvent` observers.
+    pub fn process_miner_tenure_report(&self, report: &MinerTenureReport) {
+        let interested_observers =
+            self.filter_observers(&self.miner_report_observers_lookup, false);
+        if interested_observers.is_empty() {
+            return;
+        }
+
+        let payload = match serde_json::to_value(report) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!("Failed to serialize miner tenure report: {e:?}");
+                return;
+            }
+        };
+        for observer in interested_observers.iter() {
+            observer.send_miner_tenure_report(&payload);
+        }
+    }
+
+    /// Forward the consensus-serialized bytes of a newly-processed Nakamoto block to `raw_blocks`
+    /// observers.  These are not sent to `AnyEvent` observers, since they duplicate the block
+    /// sent to `new_block`.
+    pub fn process_raw_nakamoto_block(&self, block: &NakamotoBlock) {
+        // lazily assemble payload only if we have observers
+        let interested_observers = self.filter_observers(&self.raw_block_observers_lookup, false);
+        if interested_observers.is_empty() {
+            return;
+        }
+
+        let event = RawNakamotoBlockEvent {
+            block_id: block.block_id().to_string(),
+            consensus_hash: block.header.consensus_hash.to_string(),
+            block_hash: block.header.block_hash().to_string(),
+            block_height: block.header.chain_length,
+            raw_block: to_hex(&block.serialize_to_vec()),
+        };
+        let payload = serde_json::to_value(&event)
+            .expect("FATAL: failed to serialize RawNakamotoBlockEvent to JSON");
+
+        for observer in interested_observers.iter() {
+            observer.send_raw_nakamoto_block(&payload);
+        }
+    }
+
     /// Forward newly-accepted StackerDB chunk metadata to downstream `stackerdb` observers.
     /// Infallible.
     pub fn process_new_stackerdb_chunks(
@@ -1558,6 +2036,16 @@ impl EventDispatcher {
             "event_dispatcher: New StackerDB chunk events for {contract_id}: {modified_slots:?}"
         );
 
+        if let Some(replicator) = self.stackerdb_replicator.as_ref() {
+            // chunks written over RPC were queued before they were stored; the others come from
+            // p2p, and the standbys' own StackerDB sync fetches them if they cannot be queued
+            if let Err(e) = replicator.replicate(&contract_id, &modified_slots) {
+                warn!("StackerDB replication: failed to queue chunks";
+                      "contract_id" => %contract_id,
+                      "error" => ?e);
+            }
+        }
+
         let interested_observers = self.filter_observers(&self.stackerdb_observers_lookup, false);
 
         let stackerdb_channel = self
@@ -1662,7 +2150,7 @@ impl EventDispatcher {
 
     pub fn register_observer(&mut self, conf: &EventObserverConfig, working_dir: PathBuf) {
         info!("Registering event observer at: {}", conf.endpoint);
-        let event_observer = EventObserver::new(
+        let mut event_observer = EventObserver::new(
             Some(working_dir),
             conf.endpoint.clone(),
             Duration::from_millis(conf.timeout_ms),
@@ -1727,9 +2215,25 @@ impl EventDispatcher {
                 EventKeyType::BlockProposal => {
                     self.block_proposal_observers_lookup.insert(observer_index);
                 }
+                EventKeyType::RawBlocks => {
+                    self.raw_block_observers_lookup.insert(observer_index);
+                }
+                EventKeyType::RewardCycles => {
+                    self.reward_cycle_observers_lookup.insert(observer_index);
+                }
+                EventKeyType::Alerts => {
+                    self.alert_observers_lookup.insert(observer_index);
+                }
+                EventKeyType::BurnchainSync => {
+                    self.burnchain_sync_observers_lookup.insert(observer_index);
+                }
+                EventKeyType::MinerReports => {
+                    self.miner_report_observers_lookup.insert(observer_index);
+                }
             }
         }
 
+        event_observer.spawn_delivery_thread();
         self.registered_observers.push(event_observer);
     }
 }
@@ -1753,6 +2257,7 @@ mod test {
     use std::time::Instant;
 
     use clarity::vm::costs::ExecutionCost;
+    use clarity::vm::types::PrincipalData;
     use serial_test::serial;
     use stacks::burnchains::{PoxConstants, Txid};
     use stacks::chainstate::nakamoto::{NakamotoBlock, NakamotoBlockHeader};
@@ -1760,6 +2265,7 @@ mod test {
     use stacks::chainstate::stacks::events::StacksBlockEventData;
     use stacks::chainstate::stacks::StacksBlock;
     use stacks::types::chainstate::BlockHeaderHash;
+    use stacks::util::hash::hex_bytes;
     use stacks::util::secp256k1::MessageSignature;
     use stacks_common::bitvec::BitVec;
     use stacks_common::types::chainstate::{BurnchainHeaderHash, StacksBlockId};
@@ -2068,7 +2574,7 @@ mod test {
             .expect("Failed to insert payload");
 
         // Process pending payloads
-        EventObserver::process_pending_payloads(&conn);
+        EventObserver::process_pending_payloads(&conn, &format!("{}/", &server.url()));
 
         // Verify that the pending payloads list is empty
         let pending_payloads =
@@ -2417,4 +2923,369 @@ mod test {
         rx.recv_timeout(Duration::from_secs(5))
             .expect("Server did not receive request in time");
     }
+
+    #[test]
+    fn test_raw_nakamoto_block_event() {
+        let port = get_random_port();
+        let dir = tempdir().unwrap();
+
+        // Set up a channel to pass the received payload back to the test
+        let (tx, rx) = channel();
+
+        // Start a mock server in a separate thread
+        let server = Server::http(format!("127.0.0.1:{port}")).unwrap();
+        thread::spawn(move || {
+            let mut request = server.recv().unwrap();
+            assert_eq!(request.url(), format!("/{PATH_RAW_BLOCK_PROCESSED}"));
+            assert_eq!(request.method(), &Method::Post);
+
+            let mut payload = String::new();
+            request.as_reader().read_to_string(&mut payload).unwrap();
+
+            let response = Response::from_string("HTTP/1.1 200 OK");
+            request.respond(response).unwrap();
+            tx.send(payload).unwrap();
+        });
+
+        let mut dispatcher = EventDispatcher::new();
+        dispatcher.register_observer(
+            &EventObserverConfig {
+                endpoint: format!("127.0.0.1:{port}"),
+                events_keys: vec![EventKeyType::RawBlocks],
+                timeout_ms: 3_000,
+            },
+            dir.path().to_path_buf(),
+        );
+
+        let mut header = NakamotoBlockHeader::empty();
+        header.chain_length = 123;
+        let block = NakamotoBlock {
+            header,
+            txs: vec![],
+        };
+        dispatcher.announce_raw_nakamoto_block(&block);
+
+        let payload = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("Server did not receive request in time");
+        let event: RawNakamotoBlockEvent = serde_json::from_str(&payload).unwrap();
+        assert_eq!(event.block_id, block.block_id().to_string());
+        assert_eq!(
+            event.consensus_hash,
+            block.header.consensus_hash.to_string()
+        );
+        assert_eq!(event.block_hash, block.header.block_hash().to_string());
+        assert_eq!(event.block_height, 123);
+
+        // the raw bytes decode to the very same block
+        let raw_block = hex_bytes(&event.raw_block).unwrap();
+        let decoded_block = NakamotoBlock::consensus_deserialize(&mut &raw_block[..]).unwrap();
+        assert_eq!(decoded_block, block);
+    }
+
+    #[test]
+    fn test_header_sync_progress_event() {
+        let port = get_random_port();
+        let dir = tempdir().unwrap();
+
+        // Set up a channel to pass the received payload back to the test
+        let (tx, rx) = channel();
+
+        // Start a mock server in a separate thread
+        let server = Server::http(format!("127.0.0.1:{port}")).unwrap();
+        thread::spawn(move || {
+            let mut request = server.recv().unwrap();
+            assert_eq!(request.url(), format!("/{PATH_BURNCHAIN_SYNC_PROGRESS}"));
+            assert_eq!(request.method(), &Method::Post);
+
+            let mut payload = String::new();
+            request.as_reader().read_to_string(&mut payload).unwrap();
+
+            let response = Response::from_string("HTTP/1.1 200 OK");
+            request.respond(response).unwrap();
+            tx.send(payload).unwrap();
+        });
+
+        let mut dispatcher = EventDispatcher::new();
+        dispatcher.register_observer(
+            &EventObserverConfig {
+                endpoint: format!("127.0.0.1:{port}"),
+                events_keys: vec![EventKeyType::BurnchainSync],
+                timeout_ms: 3_000,
+            },
+            dir.path().to_path_buf(),
+        );
+
+        let status = HeaderSyncStatus {
+            syncing: true,
+            first_height: 0,
+            current_height: 100_000,
+            target_height: 900_000,
+            headers_per_second: 2_000.0,
+            eta_secs: Some(400),
+            started_at: 1_700_000_000,
+            updated_at: 1_700_000_050,
+        };
+        dispatcher.header_sync_progress(&status);
+
+        let payload = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("Server did not receive request in time");
+        let event: HeaderSyncStatus = serde_json::from_str(&payload).unwrap();
+        assert_eq!(event, status);
+    }
+
+    #[test]
+    fn test_miner_tenure_report_event() {
+        let port = get_random_port();
+        let dir = tempdir().unwrap();
+
+        // Set up a channel to pass the received payload back to the test
+        let (tx, rx) = channel();
+
+        // Start a mock server in a separate thread
+        let server = Server::http(format!("127.0.0.1:{port}")).unwrap();
+        thread::spawn(move || {
+            let mut request = server.recv().unwrap();
+            assert_eq!(request.url(), format!("/{PATH_MINER_TENURE_REPORT}"));
+            assert_eq!(request.method(), &Method::Post);
+
+            let mut payload = String::new();
+            request.as_reader().read_to_string(&mut payload).unwrap();
+
+            let response = Response::from_string("HTTP/1.1 200 OK");
+            request.respond(response).unwrap();
+            tx.send(payload).unwrap();
+        });
+
+        let mut dispatcher = EventDispatcher::new();
+        dispatcher.register_observer(
+            &EventObserverConfig {
+                endpoint: format!("127.0.0.1:{port}"),
+                events_keys: vec![EventKeyType::MinerReports],
+                timeout_ms: 3_000,
+            },
+            dir.path().to_path_buf(),
+        );
+
+        let report = MinerTenureReport {
+            tenure_consensus_hash: ConsensusHash([0x01; 20]),
+            burn_view_consensus_hash: ConsensusHash([0x01; 20]),
+            burn_block_height: 900_000,
+            tenure_extend: false,
+            started_at: 1_700_000_000,
+            ended_at: 1_700_000_600,
+            blocks_mined: 12,
+            first_block_height: Some(1_000),
+            last_block_height: Some(1_011),
+            txs_included: 150,
+            fees_earned: 2_500_000,
+            commit_cost_sats: Some(20_000),
+            proposals_rejected: 1,
+            time_to_first_signature_ms: Some(4_200),
+        };
+        dispatcher.process_miner_tenure_report(&report);
+
+        let payload = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("Server did not receive request in time");
+        let event: MinerTenureReport = serde_json::from_str(&payload).unwrap();
+        assert_eq!(event, report);
+    }
+
+    #[test]
+    fn test_chain_quality_alert_payload() {
+        let alert = ChainQualityAlert::MissedSortitions {
+            burn_height: 850_000,
+            count: 3,
+        };
+        let payload = EventObserver::make_chain_quality_alert_payload(&alert);
+        assert_eq!(payload["alert"], "missed_sortitions");
+        assert_eq!(payload["severity"], "warning");
+        assert_eq!(payload["message"], alert.message());
+        assert_eq!(payload["burn_block_height"], 850_000);
+        assert_eq!(payload["count"], 3);
+
+        let alert = ChainQualityAlert::LowSignerParticipation {
+            block_id: StacksBlockId([0x01; 32]),
+            participation: 0.5,
+            threshold: 0.7,
+        };
+        let payload = EventObserver::make_chain_quality_alert_payload(&alert);
+        assert_eq!(payload["alert"], "low_signer_participation");
+        assert_eq!(
+            payload["index_block_hash"],
+            format!("0x{}", "01".repeat(32))
+        );
+        assert_eq!(payload["participation"], 0.5);
+        assert_eq!(payload["threshold"], 0.7);
+    }
+
+    #[test]
+    #[serial]
+    fn test_unreachable_observer_does_not_block_others() {
+        let port = get_random_port();
+        // nothing listens on this port
+        let unreachable_port = get_random_port();
+        let dir = tempdir().unwrap();
+
+        // Set up a channel to pass the received payloads back to the test
+        let (tx, rx) = channel();
+
+        // Start a mock server in a separate thread
+        let server = Server::http(format!("127.0.0.1:{port}")).unwrap();
+        thread::spawn(move || {
+            for _ in 0..2 {
+                let mut request = server.recv().unwrap();
+                let mut payload = String::new();
+                request.as_reader().read_to_string(&mut payload).unwrap();
+
+                let response = Response::from_string("HTTP/1.1 200 OK");
+                request.respond(response).unwrap();
+                tx.send(payload).unwrap();
+            }
+        });
+
+        TEST_EVENT_OBSERVER_SKIP_RETRY.set(false);
+
+        // both observers share the same pending payloads database
+        let mut dispatcher = EventDispatcher::new();
+        for observer_port in [unreachable_port, port] {
+            dispatcher.register_observer(
+                &EventObserverConfig {
+                    endpoint: format!("127.0.0.1:{observer_port}"),
+                    events_keys: vec![EventKeyType::RawBlocks],
+                    timeout_ms: 1_000,
+                },
+                dir.path().to_path_buf(),
+            );
+        }
+
+        let start = Instant::now();
+        let mut blocks = vec![];
+        for chain_length in [1, 2] {
+            let mut header = NakamotoBlockHeader::empty();
+            header.chain_length = chain_length;
+            let block = NakamotoBlock {
+                header,
+                txs: vec![],
+            };
+            dispatcher.announce_raw_nakamoto_block(&block);
+            blocks.push(block);
+        }
+        assert!(
+            start.elapsed() < Duration::from_secs(1),
+            "Announcing blocks waited on an unreachable observer"
+        );
+
+        // the reachable observer gets its events in order, while the unreachable one retries
+        for block in blocks.iter() {
+            let payload = rx
+                .recv_timeout(Duration::from_secs(5))
+                .expect("Server did not receive request in time");
+            let event: RawNakamotoBlockEvent = serde_json::from_str(&payload).unwrap();
+            assert_eq!(event.block_id, block.block_id().to_string());
+        }
+
+        // the unreachable observer's events are still pending
+        let conn = Connection::open(dir.path().join("event_observers.sqlite")).unwrap();
+        let unreachable_url = format!("http://127.0.0.1:{unreachable_port}/");
+        let num_unreachable_pending = EventObserver::get_pending_payloads(&conn)
+            .expect("Failed to get pending payloads")
+            .into_iter()
+            .filter(|(_, url, _, _)| url.starts_with(&unreachable_url))
+            .count();
+        assert_eq!(num_unreachable_pending, 2);
+    }
+
+    #[test]
+    fn build_reward_cycle_event() {
+        let pox_constants = PoxConstants::testnet_default();
+        let burn_block = BurnchainHeaderHash([1; 32]);
+        let consensus_hash = ConsensusHash([2; 20]);
+        let anchor_block = BlockHeaderHash([3; 32]);
+        let anchor_txid = Txid([4; 32]);
+        let stacker = PrincipalData::parse("ST000000000000000000002AMW42H").unwrap();
+        let reward_set = RewardSet {
+            rewarded_addresses: vec![],
+            start_cycle_state: PoxStartCycleInfo {
+                missed_reward_slots: vec![(stacker.clone(), 1_000)],
+            },
+            signers: Some(vec![
+                NakamotoSignerEntry {
+                    signing_key: [5; 33],
+                    stacked_amt: 100,
+                    weight: 3,
+                },
+                NakamotoSignerEntry {
+                    signing_key: [6; 33],
+                    stacked_amt: 200,
+                    weight: 4,
+                },
+            ]),
+            pox_ustx_threshold: Some(50),
+        };
+        let reward_cycle_info = RewardCycleInfo {
+            reward_cycle: 12,
+            anchor_status: PoxAnchorBlockStatus::SelectedAndKnown(
+                anchor_block.clone(),
+                anchor_txid.clone(),
+                reward_set.clone(),
+            ),
+        };
+
+        let payload = EventObserver::make_reward_cycle_payload(
+            &reward_cycle_info,
+            &burn_block,
+            1234,
+            &consensus_hash,
+            &pox_constants,
+        );
+        assert_eq!(payload["reward_cycle"], 12);
+        assert_eq!(payload["burn_block_hash"], format!("0x{burn_block}"));
+        assert_eq!(payload["burn_block_height"], 1234);
+        assert_eq!(payload["consensus_hash"], format!("0x{consensus_hash}"));
+        assert_eq!(
+            payload["reward_cycle_length"],
+            pox_constants.reward_cycle_length
+        );
+        assert_eq!(payload["anchor_block"]["status"], "selected_and_known");
+        assert_eq!(
+            payload["anchor_block"]["block_hash"],
+            format!("0x{anchor_block}")
+        );
+        assert_eq!(payload["anchor_block"]["txid"], format!("0x{anchor_txid}"));
+        assert_eq!(
+            payload["reward_set"],
+            serde_json::to_value(RewardSetEventPayload::from_reward_set(&reward_set)).unwrap()
+        );
+        assert_eq!(payload["total_signer_weight"], 7);
+        assert_eq!(
+            payload["unlocks"]["pox_v3_unlock_height"],
+            pox_constants.v3_unlock_height
+        );
+        assert_eq!(
+            payload["unlocks"]["early_unlocks"],
+            json!([{ "principal": stacker.to_string(), "amount": "1000" }])
+        );
+
+        // without an anchor block, there is no reward set to report
+        let reward_cycle_info = RewardCycleInfo {
+            reward_cycle: 13,
+            anchor_status: PoxAnchorBlockStatus::NotSelected,
+        };
+        let payload = EventObserver::make_reward_cycle_payload(
+            &reward_cycle_info,
+            &burn_block,
+            1235,
+            &consensus_hash,
+            &pox_constants,
+        );
+        assert_eq!(payload["reward_cycle"], 13);
+        assert_eq!(payload["anchor_block"]["status"], "not_selected");
+        assert!(payload["anchor_block"]["block_hash"].is_null());
+        assert!(payload["reward_set"].is_null());
+        assert!(payload["total_signer_weight"].is_null());
+        assert_eq!(payload["unlocks"]["early_unlocks"], json!([]));
+    }
 }
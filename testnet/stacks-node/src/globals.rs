@@ -3,6 +3,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::SyncSender;
 use std::sync::{Arc, Mutex};
 
+use stacks::burnchains::bitcoin::spv::HeaderSyncTracker;
 use stacks::burnchains::Txid;
 use stacks::chainstate::burn::operations::LeaderKeyRegisterOp;
 use stacks::chainstate::burn::BlockSnapshot;
@@ -11,9 +12,13 @@ use stacks::chainstate::stacks::db::unconfirmed::UnconfirmedTxMap;
 use stacks::chainstate::stacks::db::StacksChainState;
 use stacks::chainstate::stacks::miner::MinerStatus;
 use stacks::config::MinerConfig;
+use stacks::cost_estimates::fill_stats::MinerFillStats;
+use stacks::net::api::getminerreports::MinerReports;
+use stacks::net::api::getminerutxos::MinerUtxoStatus;
 use stacks::net::NetworkResult;
 use stacks_common::types::chainstate::{BlockHeaderHash, BurnchainHeaderHash, ConsensusHash};
 
+use crate::nakamoto_node::signature_wait::SignerLatencyStats;
 use crate::neon::Counters;
 use crate::neon_node::LeaderKeyRegistrationState;
 use crate::run_loop::RegisteredKey;
@@ -73,6 +78,21 @@ pub struct Globals<T> {
     /// Initiative flag.
     /// Raised when the main loop should wake up and do something.
     initiative: Arc<Mutex<Option<String>>>,
+    /// Fill rates of recently-mined blocks (shared between the miner and p2p threads)
+    miner_fill_stats: Arc<Mutex<MinerFillStats>>,
+    /// The miner's burnchain UTXOs (shared between the relayer and p2p threads)
+    miner_utxo_status: Arc<Mutex<MinerUtxoStatus>>,
+    /// Reports of the miner's recent tenures (shared between the miner and p2p threads)
+    miner_reports: Arc<Mutex<MinerReports>>,
+    /// How long the signers took to sign the miner's recent blocks (shared between the miner
+    /// threads of successive tenures)
+    signer_latency_stats: Arc<Mutex<SignerLatencyStats>>,
+    /// Progress of Bitcoin header downloads (shared between the burnchain controller and p2p
+    /// threads)
+    header_sync_tracker: HeaderSyncTracker,
+    /// Set while the node operator has paused mining through the admin RPC endpoints (shared
+    /// between the relayer and p2p threads)
+    mining_paused: Arc<AtomicBool>,
 }
 
 // Need to manually implement Clone, because [derive(Clone)] requires
@@ -95,6 +115,12 @@ impl<T> Clone for Globals<T> {
             estimated_winning_probs: self.estimated_winning_probs.clone(),
             previous_best_tips: self.previous_best_tips.clone(),
             initiative: self.initiative.clone(),
+            miner_fill_stats: self.miner_fill_stats.clone(),
+            miner_utxo_status: self.miner_utxo_status.clone(),
+            miner_reports: self.miner_reports.clone(),
+            signer_latency_stats: self.signer_latency_stats.clone(),
+            header_sync_tracker: self.header_sync_tracker.clone(),
+            mining_paused: self.mining_paused.clone(),
         }
     }
 }
@@ -110,6 +136,7 @@ impl<T> Globals<T> {
         should_keep_running: Arc<AtomicBool>,
         start_mining_height: u64,
         leader_key_registration_state: LeaderKeyRegistrationState,
+        header_sync_tracker: HeaderSyncTracker,
     ) -> Globals<T> {
         Globals {
             last_sortition: Arc::new(Mutex::new(None)),
@@ -126,6 +153,12 @@ impl<T> Globals<T> {
             estimated_winning_probs: Arc::new(Mutex::new(HashMap::new())),
             previous_best_tips: Arc::new(Mutex::new(BTreeMap::new())),
             initiative: Arc::new(Mutex::new(None)),
+            miner_fill_stats: Arc::new(Mutex::new(MinerFillStats::default())),
+            miner_utxo_status: Arc::new(Mutex::new(MinerUtxoStatus::default())),
+            miner_reports: Arc::new(Mutex::new(MinerReports::default())),
+            signer_latency_stats: Arc::new(Mutex::new(SignerLatencyStats::default())),
+            header_sync_tracker,
+            mining_paused: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -329,6 +362,41 @@ impl<T> Globals<T> {
         }
     }
 
+    /// Get the fill rates of recently-mined blocks
+    pub fn get_miner_fill_stats(&self) -> Arc<Mutex<MinerFillStats>> {
+        self.miner_fill_stats.clone()
+    }
+
+    /// Get the miner's burnchain UTXO status
+    pub fn get_miner_utxo_status(&self) -> Arc<Mutex<MinerUtxoStatus>> {
+        self.miner_utxo_status.clone()
+    }
+
+    /// Get the reports of the miner's recent tenures
+    pub fn get_miner_reports(&self) -> Arc<Mutex<MinerReports>> {
+        self.miner_reports.clone()
+    }
+
+    /// Get the miner's recent signing latencies
+    pub fn get_signer_latency_stats(&self) -> Arc<Mutex<SignerLatencyStats>> {
+        self.signer_latency_stats.clone()
+    }
+
+    /// Get the progress tracker for Bitcoin header downloads
+    pub fn get_header_sync_tracker(&self) -> HeaderSyncTracker {
+        self.header_sync_tracker.clone()
+    }
+
+    /// Get the flag the admin RPC endpoints set to pause mining
+    pub fn get_mining_paused(&self) -> Arc<AtomicBool> {
+        self.mining_paused.clone()
+    }
+
+    /// Has the node operator paused mining?
+    pub fn is_mining_paused(&self) -> bool {
+        self.mining_paused.load(Ordering::SeqCst)
+    }
+
     /// Get the last miner config loaded
     pub fn get_last_miner_config(&self) -> Option<MinerConfig> {
         match self.last_miner_config.lock() {
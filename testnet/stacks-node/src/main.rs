@@ -15,7 +15,9 @@ use stacks_common::util::hash::hex_bytes;
 
 pub mod monitoring;
 
+pub mod backups;
 pub mod burnchains;
+pub mod devnet;
 pub mod event_dispatcher;
 pub mod genesis_data;
 pub mod globals;
@@ -25,25 +27,40 @@ pub mod neon_node;
 pub mod node;
 pub mod operations;
 pub mod run_loop;
+pub mod stackerdb_replication;
 pub mod syncctl;
 pub mod tenure;
+pub mod webhooks;
 
 use std::collections::HashMap;
-use std::{env, panic, process};
+use std::time::Duration;
+use std::{env, fs, panic, process};
 
 use backtrace::Backtrace;
 use pico_args::Arguments;
+use stacks::burnchains::Txid;
 use stacks::chainstate::burn::db::sortdb::SortitionDB;
 use stacks::chainstate::burn::operations::leader_block_commit::RewardSetInfo;
+use stacks::chainstate::burn::operations::BlockstackOperationType;
 use stacks::chainstate::coordinator::{get_next_recipients, OnChainRewardSetProvider};
 use stacks::chainstate::stacks::address::PoxAddress;
 use stacks::chainstate::stacks::db::blocks::DummyEventDispatcher;
 use stacks::chainstate::stacks::db::StacksChainState;
+use stacks::cli::{load_canonical_block_range, replay_block_events};
 use stacks::config::chain_data::MinerStats;
 pub use stacks::config::{Config, ConfigFile};
+use stacks::config::{EventKeyType, EventObserverConfig};
+use stacks::core::StacksEpochId;
+use stacks_common::types::chainstate::StacksAddress;
+use stacks_common::types::{Address, StacksPublicKeyBuffer};
+use stacks_common::util::log::{
+    parse_component_loglevels, set_component_loglevels, set_log_format, LogFormat,
+};
+use stacks_common::util::secp256k1::Secp256k1PublicKey;
 #[cfg(not(any(target_os = "macos", target_os = "windows", target_arch = "arm")))]
 use tikv_jemallocator::Jemalloc;
 
+use self::burnchains::bitcoin_regtest_controller::SerializedTx;
 pub use self::burnchains::{
     BitcoinRegtestController, BurnchainController, BurnchainTip, MocknetController,
 };
@@ -53,6 +70,10 @@ pub use self::node::{ChainTip, Node};
 pub use self::run_loop::{helium, neon};
 pub use self::tenure::Tenure;
 use crate::neon_node::{BlockMinerThread, TipCandidate};
+use crate::operations::{
+    make_delegate_stx_op, make_pre_stx_op, make_stack_aggregation_commit_op, make_stack_stx_op,
+    make_vote_for_aggregate_key_op, BurnchainOpSigner,
+};
 use crate::run_loop::boot_nakamoto;
 
 #[cfg(not(any(target_os = "macos", target_os = "windows", target_arch = "arm")))]
@@ -250,6 +271,237 @@ fn cli_get_miner_spend(
     spend_amount
 }
 
+/// Parse a required CLI argument, or exit with an error message
+fn cli_required<T: std::str::FromStr>(args: &mut Arguments, key: &'static str) -> T
+where
+    T::Err: std::fmt::Display,
+{
+    match args.value_from_str(key) {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("Invalid or missing argument {key}: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Parse an optional CLI argument, or exit with an error message if it is malformed
+fn cli_optional<T: std::str::FromStr>(args: &mut Arguments, key: &'static str) -> Option<T>
+where
+    T::Err: std::fmt::Display,
+{
+    match args.opt_value_from_str(key) {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("Invalid argument {key}: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Parse a Stacks address CLI argument
+fn cli_stacks_address(args: &mut Arguments, key: &'static str) -> StacksAddress {
+    let addr_str: String = cli_required(args, key);
+    StacksAddress::from_string(&addr_str).unwrap_or_else(|| {
+        eprintln!("Invalid Stacks address for {key}: {addr_str}");
+        process::exit(1);
+    })
+}
+
+/// Parse a compressed secp256k1 public key CLI argument
+fn cli_public_key(args: &mut Arguments, key: &'static str) -> Option<StacksPublicKeyBuffer> {
+    let pubkey_str: String = cli_optional(args, key)?;
+    let pubkey = Secp256k1PublicKey::from_hex(&pubkey_str).unwrap_or_else(|e| {
+        eprintln!("Invalid public key for {key}: {e}");
+        process::exit(1);
+    });
+    Some(StacksPublicKeyBuffer::from_public_key(&pubkey))
+}
+
+/// Implementation of the `burn-op` CLI subcommand.
+/// Constructs, signs, and sends a Stacks-on-Bitcoin operation to the config's bitcoind.
+fn cli_burn_op(op_name: &str, mut args: Arguments) -> Result<Txid, String> {
+    let args = &mut args;
+    let config_path: String = cli_required(args, "--config");
+    let signer_key: String = cli_required(args, "--signer-key");
+    let pre_stx_txid: Option<String> = cli_optional(args, "--pre-stx-txid");
+
+    info!("Loading config at path {config_path}");
+    let config = ConfigFile::from_path(&config_path)
+        .and_then(|config_file| Config::from_config_file(config_file, true))
+        .map_err(|e| format!("Invalid config: {e}"))?;
+    let mainnet = config.is_mainnet();
+
+    let mut op_signer = BurnchainOpSigner::from_secret_key_str(&signer_key, false)?;
+    let sender = op_signer.get_stacks_address(mainnet);
+
+    let operation = match op_name {
+        "pre-stx" => {
+            let output = match cli_optional::<String>(args, "--output") {
+                Some(addr_str) => StacksAddress::from_string(&addr_str)
+                    .ok_or_else(|| format!("Invalid Stacks address for --output: {addr_str}"))?,
+                None => sender,
+            };
+            BlockstackOperationType::PreStx(make_pre_stx_op(output))
+        }
+        "stack-stx" => {
+            let reward_addr_str: String = cli_required(args, "--reward-addr");
+            let reward_addr = PoxAddress::from_b58(&reward_addr_str)
+                .ok_or_else(|| format!("Invalid PoX address: {reward_addr_str}"))?;
+            BlockstackOperationType::StackStx(make_stack_stx_op(
+                sender,
+                reward_addr,
+                cli_required(args, "--amount"),
+                cli_required(args, "--cycles"),
+                cli_public_key(args, "--signer-pubkey"),
+                cli_optional(args, "--max-amount"),
+                cli_optional(args, "--auth-id"),
+            ))
+        }
+        "delegate-stx" => BlockstackOperationType::DelegateStx(make_delegate_stx_op(
+            sender,
+            cli_stacks_address(args, "--delegate-to"),
+            cli_required(args, "--amount"),
+            cli_optional(args, "--until-burn-height"),
+        )),
+        "vote-for-aggregate-key" => {
+            let aggregate_key = cli_public_key(args, "--aggregate-key")
+                .ok_or_else(|| "Missing argument --aggregate-key".to_string())?;
+            let signer_key = cli_public_key(args, "--signer-pubkey")
+                .ok_or_else(|| "Missing argument --signer-pubkey".to_string())?;
+            BlockstackOperationType::VoteForAggregateKey(make_vote_for_aggregate_key_op(
+                sender,
+                aggregate_key,
+                cli_required(args, "--round"),
+                cli_required(args, "--reward-cycle"),
+                cli_required(args, "--signer-index"),
+                signer_key,
+            ))
+        }
+        "stack-aggregation-commit" => {
+            let reward_addr_str: String = cli_required(args, "--reward-addr");
+            let reward_addr = PoxAddress::from_b58(&reward_addr_str)
+                .ok_or_else(|| format!("Invalid PoX address: {reward_addr_str}"))?;
+            let signer_key = cli_public_key(args, "--signer-pubkey")
+                .ok_or_else(|| "Missing argument --signer-pubkey".to_string())?;
+            BlockstackOperationType::StackAggregationCommit(make_stack_aggregation_commit_op(
+                sender,
+                reward_addr,
+                cli_required(args, "--reward-cycle"),
+                signer_key,
+                cli_required(args, "--max-amount"),
+                cli_required(args, "--auth-id"),
+            ))
+        }
+        _ => {
+            return Err(format!("Unrecognized burn operation '{op_name}'"));
+        }
+    };
+    let unused_args = std::mem::replace(args, Arguments::from_vec(vec![])).finish();
+    if !unused_args.is_empty() {
+        return Err(format!("Unrecognized arguments: {unused_args:?}"));
+    }
+
+    let mut controller = BitcoinRegtestController::new_dummy(config);
+    let utxo = match (&operation, pre_stx_txid) {
+        (BlockstackOperationType::PreStx(_), _) => None,
+        (_, Some(txid_str)) => {
+            let txid =
+                Txid::from_hex(&txid_str).map_err(|e| format!("Invalid --pre-stx-txid: {e:?}"))?;
+            Some(
+                controller
+                    .get_pre_stx_utxo(&txid)
+                    .map_err(|e| format!("Failed to load pre-stx output: {e}"))?,
+            )
+        }
+        (_, None) => {
+            return Err(format!(
+                "{op_name} must spend the output of a pre-stx operation; pass --pre-stx-txid"
+            ));
+        }
+    };
+
+    let epoch_id = controller
+        .get_next_block_epoch_id()
+        .map_err(|e| format!("Failed to determine current epoch: {e}"))?;
+
+    info!("Submitting {op_name} operation"; "sender" => %sender, "epoch" => %epoch_id);
+    let tx = controller
+        .submit_manual(epoch_id, operation, &mut op_signer, utxo)
+        .map_err(|e| format!("Failed to submit {op_name} operation: {e}"))?;
+    Ok(SerializedTx::new(tx).txid())
+}
+
+/// Implementation of the `replay-events` CLI subcommand.
+/// Re-executes the canonical fork's blocks in `[from_height, to_height]` without committing
+/// them, and posts the resulting `new_block` events to `endpoint`.  Returns the number of blocks
+/// replayed.
+fn cli_replay_events(
+    config_path: &str,
+    from_height: u64,
+    to_height: u64,
+    endpoint: &str,
+) -> Result<usize, String> {
+    if from_height > to_height {
+        return Err(format!(
+            "--from-height {from_height} is greater than --to-height {to_height}"
+        ));
+    }
+    if endpoint.starts_with("https://") {
+        return Err("Event observers must be served over http".into());
+    }
+
+    info!("Loading config at path {config_path}");
+    let config = ConfigFile::from_path(config_path)
+        .and_then(|config_file| Config::from_config_file(config_file, true))
+        .map_err(|e| format!("Invalid config: {e}"))?;
+    let db_path = config
+        .get_chainstate_path()
+        .parent()
+        .and_then(|path| path.to_str())
+        .expect("FATAL: chainstate path has no parent directory")
+        .to_string();
+
+    let blocks = load_canonical_block_range(&db_path, &config, from_height, to_height)
+        .map_err(|e| format!("Failed to load blocks: {e:?}"))?;
+    info!("Will replay events for {} blocks", blocks.len());
+
+    // Pending payloads are kept apart from the node's own, so a replay that is interrupted
+    // resumes delivery the next time it runs.
+    let mut replay_dir = config.get_working_dir();
+    replay_dir.push("replay-events");
+    fs::create_dir_all(&replay_dir)
+        .map_err(|e| format!("Failed to create {}: {e}", replay_dir.display()))?;
+    let mut event_dispatcher = EventDispatcher::new();
+    event_dispatcher.register_observer(
+        &EventObserverConfig {
+            endpoint: endpoint.trim_start_matches("http://").to_string(),
+            events_keys: vec![EventKeyType::AnyEvent],
+            timeout_ms: 30_000,
+        },
+        replay_dir,
+    );
+
+    for (block_id, height, is_nakamoto) in blocks.iter() {
+        match replay_block_events(&db_path, block_id, *is_nakamoto, &config, &event_dispatcher) {
+            Ok(Some(_receipt)) => {}
+            Ok(None) => {
+                return Err(format!(
+                    "Failed to replay block {block_id} at height {height}: it could not be evaluated"
+                ));
+            }
+            Err(e) => {
+                return Err(format!(
+                    "Failed to replay block {block_id} at height {height}: {e:?}"
+                ));
+            }
+        }
+        event_dispatcher.wait_for_delivery();
+        info!("Replayed events"; "block_id" => %block_id, "height" => height);
+    }
+    Ok(blocks.len())
+}
+
 fn main() {
     panic::set_hook(Box::new(|panic_info| {
         error!("Process abort due to thread panic: {panic_info}");
@@ -275,6 +527,22 @@ fn main() {
     let mut args = Arguments::from_env();
     let subcommand = args.subcommand().unwrap().unwrap_or_default();
 
+    // must happen before anything is logged
+    let log_format: Option<LogFormat> = args
+        .opt_value_from_str("--log-format")
+        .expect("Failed to parse --log-format argument");
+    if let Some(log_format) = log_format {
+        set_log_format(log_format).expect("FATAL: failed to set log format");
+    }
+    let log_levels: Option<String> = args
+        .opt_value_from_str("--log-levels")
+        .expect("Failed to parse --log-levels argument");
+    if let Some(log_levels) = log_levels {
+        let log_levels =
+            parse_component_loglevels(&log_levels).expect("Failed to parse --log-levels argument");
+        set_component_loglevels(log_levels);
+    }
+
     info!("{}", version());
 
     let mine_start: Option<u64> = args
@@ -304,18 +572,20 @@ fn main() {
         }
         "check-config" => {
             let config_path: String = args.value_from_str("--config").unwrap();
+            let profile: Option<String> = args.opt_value_from_str("--profile").unwrap();
             args.finish();
-            info!("Loading config at path {config_path}");
-            let config_file = match ConfigFile::from_path(&config_path) {
-                Ok(config_file) => {
-                    debug!("Loaded config file: {config_file:?}");
-                    config_file
-                }
-                Err(e) => {
-                    warn!("Invalid config file: {e}");
-                    process::exit(1);
-                }
-            };
+            info!("Loading config at path {config_path}"; "profile" => ?profile);
+            let config_file =
+                match ConfigFile::from_path_with_profile(&config_path, profile.as_deref()) {
+                    Ok(config_file) => {
+                        debug!("Loaded config file: {config_file:?}");
+                        config_file
+                    }
+                    Err(e) => {
+                        warn!("Invalid config file: {e}");
+                        process::exit(1);
+                    }
+                };
             match Config::from_config_file(config_file, true) {
                 Ok(_) => {
                     info!("Loaded config!");
@@ -329,9 +599,10 @@ fn main() {
         }
         "start" => {
             let config_path: String = args.value_from_str("--config").unwrap();
+            let profile: Option<String> = args.opt_value_from_str("--profile").unwrap();
             args.finish();
-            info!("Loading config at path {config_path}");
-            match ConfigFile::from_path(&config_path) {
+            info!("Loading config at path {config_path}"; "profile" => ?profile);
+            match ConfigFile::from_path_with_profile(&config_path, profile.as_deref()) {
                 Ok(config_file) => config_file,
                 Err(e) => {
                     warn!("Invalid config file: {e}");
@@ -384,6 +655,59 @@ fn main() {
             println!("Best tip is {best_tip:?}");
             process::exit(0);
         }
+        "burn-op" => {
+            let op_name = args.subcommand().unwrap().unwrap_or_default();
+            match cli_burn_op(&op_name, args) {
+                Ok(txid) => {
+                    println!("{txid}");
+                    process::exit(0);
+                }
+                Err(e) => {
+                    eprintln!("{e}");
+                    process::exit(1);
+                }
+            }
+        }
+        "devnet" => {
+            let defaults = devnet::DevnetOpts::default();
+            let block_time: Option<u64> = cli_optional(&mut args, "--block-time");
+            let opts = devnet::DevnetOpts {
+                working_dir: cli_optional(&mut args, "--working-dir")
+                    .unwrap_or(defaults.working_dir),
+                num_signers: cli_optional(&mut args, "--signers").unwrap_or(defaults.num_signers),
+                num_accounts: cli_optional(&mut args, "--accounts")
+                    .unwrap_or(defaults.num_accounts),
+                block_time: block_time
+                    .map(Duration::from_secs)
+                    .unwrap_or(defaults.block_time),
+                rpc_port: cli_optional(&mut args, "--rpc-port").unwrap_or(defaults.rpc_port),
+                bitcoind_port: cli_optional(&mut args, "--bitcoind-port")
+                    .unwrap_or(defaults.bitcoind_port),
+            };
+            args.finish();
+            if let Err(e) = devnet::run_devnet(opts) {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+            process::exit(0);
+        }
+        "replay-events" => {
+            let config_path: String = cli_required(&mut args, "--config");
+            let from_height: u64 = cli_required(&mut args, "--from-height");
+            let to_height: u64 = cli_required(&mut args, "--to-height");
+            let endpoint: String = cli_required(&mut args, "--endpoint");
+            args.finish();
+            match cli_replay_events(&config_path, from_height, to_height, &endpoint) {
+                Ok(num_blocks) => {
+                    println!("Replayed events for {num_blocks} blocks");
+                    process::exit(0);
+                }
+                Err(e) => {
+                    eprintln!("{e}");
+                    process::exit(1);
+                }
+            }
+        }
         "get-spend-amount" => {
             let config_path: String = args.value_from_str("--config").unwrap();
             let at_burnchain_height: Option<u64> =
@@ -414,7 +738,16 @@ fn main() {
 
     let num_round: u64 = 0; // Infinite number of rounds
 
-    if conf.burnchain.mode == "helium" || conf.burnchain.mode == "mocknet" {
+    // A mocknet node whose epoch schedule reaches Nakamoto runs the Nakamoto run loop on a
+    // synthetic burnchain.  Other mocknet nodes run the helium run loop.
+    let nakamoto_mocknet = conf.burnchain.is_mocknet()
+        && conf
+            .burnchain
+            .epochs
+            .as_ref()
+            .is_some_and(|epochs| epochs.get(StacksEpochId::Epoch30).is_some());
+
+    if conf.burnchain.mode == "helium" || (conf.burnchain.mode == "mocknet" && !nakamoto_mocknet) {
         let mut run_loop = helium::RunLoop::new(conf);
         if let Err(e) = run_loop.start(num_round) {
             warn!("Helium runloop exited: {e}");
@@ -424,6 +757,7 @@ fn main() {
         || conf.burnchain.mode == "xenon"
         || conf.burnchain.mode == "krypton"
         || conf.burnchain.mode == "mainnet"
+        || nakamoto_mocknet
     {
         let mut run_loop = boot_nakamoto::BootRunLoop::new(conf).unwrap();
         run_loop.start(None, 0);
@@ -472,8 +806,10 @@ testnet\t\tStart a node that will join and stream blocks from the public testnet
 start\t\tStart a node with a config of your own. Can be used for joining a network, starting new chain, etc.
 \t\tArguments:
 \t\t  --config: path of the config (such as https://github.com/blockstack/stacks-blockchain/blob/master/sample/conf/testnet-follower-conf.toml).
+\t\t  --profile: optional name of a `[profile.<name>]` in the config whose settings override the top-level ones.
 \t\tExample:
 \t\t  stacks-node start --config /path/to/config.toml
+\t\t  stacks-node start --config /path/to/config.toml --profile testnet
 
 check-config\t\tValidates the config file without starting up the node. Uses same arguments as start subcommand.
 
@@ -483,6 +819,55 @@ key-for-seed\tOutput the associated secret key for a burnchain signer created wi
 \t\tCan be passed a config file for the seed via the `--config <file>` option *or* by supplying the hex seed on
 \t\tthe command line directly.
 
+burn-op\t\tConstruct, sign, and send a Stacks operation on Bitcoin via the config's bitcoind.
+\t\tPrints the txid of the Bitcoin transaction.
+\t\tCommon arguments:
+\t\t  --config: path to the config file
+\t\t  --signer-key: hex or WIF secret key which owns the Bitcoin UTXOs to spend
+\t\t  --pre-stx-txid: txid of a confirmed pre-stx operation whose output to spend
+\t\t                 (required for all operations other than pre-stx)
+\t\tOperations:
+\t\t  pre-stx [--output <stacks address>]
+\t\t  stack-stx --reward-addr <btc address> --amount <ustx> --cycles <n>
+\t\t            [--signer-pubkey <hex>] [--max-amount <ustx>] [--auth-id <n>]
+\t\t  delegate-stx --delegate-to <stacks address> --amount <ustx> [--until-burn-height <height>]
+\t\t  vote-for-aggregate-key --aggregate-key <hex> --signer-pubkey <hex> --round <n>
+\t\t            --reward-cycle <n> --signer-index <n>
+\t\t  stack-aggregation-commit --reward-addr <btc address> --reward-cycle <n>
+\t\t            --signer-pubkey <hex> --max-amount <ustx> --auth-id <n>
+\t\t            (only applied from epoch 3.1 onward)
+\t\tExample:
+\t\t  stacks-node burn-op pre-stx --config /path/to/config.toml --signer-key <key>
+\t\t  stacks-node burn-op stack-stx --config /path/to/config.toml --signer-key <key> \\
+\t\t      --pre-stx-txid <txid> --reward-addr <addr> --amount 100000000000 --cycles 6
+
+devnet\t\tStart a local Nakamoto network: a regtest bitcoind (which must be on the PATH), a miner,
+\t\tsigners, and pre-funded accounts.  The signers are stacked automatically, and the network
+\t\treaches epoch 3.0 in under a minute.  The keys and addresses are printed on startup.
+\t\tArguments:
+\t\t  --working-dir: directory for the chainstate and bitcoind's data.  Restarting in the same
+\t\t                 directory resumes the network.
+\t\t  --signers: number of signers (default 3)
+\t\t  --accounts: number of pre-funded accounts (default 10)
+\t\t  --block-time: seconds between bitcoin blocks once epoch 3.0 starts (default 10)
+\t\t  --rpc-port: the node's RPC port; the p2p port is the next one (default 20443)
+\t\t  --bitcoind-port: bitcoind's RPC port; its p2p port is the next one (default 18443)
+\t\tExample:
+\t\t  stacks-node devnet --signers 5 --block-time 5
+
+replay-events\tRe-execute the canonical fork's blocks in a height range from the node's chainstate, without
+\t\tcommitting them, and post their `new_block` events to an event observer, so that it can
+\t\trecover events it missed.  Each block's events are delivered before the next block is replayed.
+\t\tThe node should not be running.
+\t\tArguments:
+\t\t  --config: path to the node's config file
+\t\t  --from-height: Stacks block height of the first block to replay
+\t\t  --to-height: Stacks block height of the last block to replay
+\t\t  --endpoint: the observer's `host:port` (or `http://host:port`)
+\t\tExample:
+\t\t  stacks-node replay-events --config /path/to/config.toml --from-height 1000 \\
+\t\t      --to-height 2000 --endpoint http://127.0.0.1:3700
+
 replay-mock-mining\tReplay mock mined blocks from <dir>
 \t\tArguments:
 \t\t  --path: path to directory of mock mined blocks
@@ -494,6 +879,11 @@ OPTIONAL ARGUMENTS:
 
 \t\t--mine-at-height=<height>: optional argument for a miner to not attempt mining until Stacks block has sync'ed to <height>
 
+\t\t--log-format=<text|json>: optional argument to write logs as human-readable lines (the default) or as one JSON object per line
+
+\t\t--log-levels=<levels>: optional comma-separated per-component log levels, e.g. `net=debug,chainstate=info,miner=trace`.
+\t\t   A bare level sets the default.  These can be changed at runtime with POST /v3/admin/log_levels.
+
 ", argv[0]);
 }
 
@@ -42,9 +42,12 @@ use crate::run_loop::boot_nakamoto::Neon2NakaData;
 use crate::run_loop::nakamoto::{Globals, RunLoop};
 use crate::run_loop::RegisteredKey;
 
+pub mod commit_bid;
 pub mod miner;
+pub mod miner_lease;
 pub mod peer;
 pub mod relayer;
+pub mod signature_wait;
 pub mod signer_coordinator;
 pub mod stackerdb_listener;
 
@@ -137,6 +140,9 @@ pub enum Error {
     // The thread that we tried to send to has closed
     #[error("The thread that we tried to send to has closed")]
     ChannelClosed,
+    /// The miner lease shared with redundant nodes could not be read or written
+    #[error("Failed to access the miner lease: {0}")]
+    MinerLeaseError(DBError),
     /// DBError wrapper
     #[error("DBError: {0}")]
     DBError(#[from] DBError),
@@ -195,9 +201,13 @@ impl StacksNode {
 
         NeonNode::setup_ast_size_precheck(&config, &mut sortdb);
 
-        let _ = config
+        let mut mempool = config
             .connect_mempool_db()
             .expect("FATAL: database failure opening mempool");
+        if data_from_neon.is_none() {
+            // if this node started in epoch 2.x, the neon node already revalidated the mempool
+            NeonNode::replay_mempool_admissions(&config, &sortdb, &mut mempool);
+        }
 
         let data_from_neon = data_from_neon.unwrap_or_default();
 
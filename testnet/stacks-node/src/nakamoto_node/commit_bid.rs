@@ -0,0 +1,285 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Burn fee auto-bidding (`miner.commit_bid_auto` in the config file).
+//!
+//! Instead of always burning `burnchain.burn_fee_cap`, the miner looks at the block-commits in
+//! the last `miner.commit_bid_window` sortitions and bids so that, had it made the same bid in
+//! each of them, its share of the total burn would have been `miner.commit_bid_target_win_pct`.
+//! Since sortition winners are drawn in proportion to their burns, this share is roughly the
+//! miner's chance of winning.  The bid is clamped to `miner.commit_bid_min_sats` and
+//! `miner.commit_bid_max_sats`, and is chosen once per burnchain block.
+
+use std::collections::HashSet;
+
+use stacks::burnchains::BurnchainSigner;
+use stacks::chainstate::burn::db::sortdb::SortitionDB;
+use stacks::chainstate::burn::{BlockSnapshot, ConsensusHash};
+use stacks::config::MinerConfig;
+use stacks::monitoring::update_commit_bid;
+use stacks::util_lib::db::Error as DBError;
+
+/// The block-commits in one sortition, as seen by this miner
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SortitionBurns {
+    /// Total amount burnt by every block-commit in the sortition
+    pub total_burn: u64,
+    /// Amount burnt by this miner's block-commits
+    pub own_burn: u64,
+    /// Amount burnt by the winning block-commit, if there was a winner
+    pub winner_burn: Option<u64>,
+    /// Number of other miners which sent a block-commit
+    pub competitors: u64,
+}
+
+/// Bounds and target for the commit-bid controller, taken from the miner config
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitBidSettings {
+    pub min_sats: u64,
+    pub max_sats: u64,
+    /// Number of recent sortitions to consider
+    pub window: u64,
+    /// Target share of the total burn, in percent
+    pub target_win_pct: u8,
+}
+
+impl CommitBidSettings {
+    /// Read the controller settings from the miner config, using `burn_fee_cap` as the upper
+    /// bound if `miner.commit_bid_max_sats` is not set
+    pub fn from_config(miner_config: &MinerConfig, burn_fee_cap: u64) -> Self {
+        Self {
+            min_sats: miner_config.commit_bid_min_sats,
+            max_sats: miner_config.commit_bid_max_sats.unwrap_or(burn_fee_cap),
+            window: miner_config.commit_bid_window,
+            target_win_pct: miner_config.commit_bid_target_win_pct,
+        }
+    }
+}
+
+/// What the commit-bid controller saw in recent sortitions, and the bid it chose
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitBidDecision {
+    /// Number of sortitions analyzed
+    pub sortitions: u64,
+    /// Average total burn per sortition
+    pub avg_total_burn: u64,
+    /// Average amount burnt by other miners per sortition
+    pub avg_competitor_burn: u64,
+    /// Median amount burnt by the sortitions' winners, if any had a winner
+    pub median_winner_burn: Option<u64>,
+    /// Average number of other miners per sortition
+    pub avg_competitors: f64,
+    /// The bid which would have won `target_win_pct` of the total burn
+    pub target_bid: u64,
+    /// The target bid, clamped to the operator's bounds
+    pub bid: u64,
+}
+
+impl CommitBidDecision {
+    /// Choose a bid from the block-commits in recent sortitions
+    pub fn choose(sortitions: &[SortitionBurns], settings: &CommitBidSettings) -> Self {
+        let num_sortitions = u64::try_from(sortitions.len()).unwrap_or(u64::MAX);
+        let divisor = u128::from(num_sortitions.max(1));
+        let average = |sum: u128| u64::try_from(sum / divisor).unwrap_or(u64::MAX);
+
+        let avg_total_burn = average(sortitions.iter().map(|s| u128::from(s.total_burn)).sum());
+        let avg_competitor_burn = average(
+            sortitions
+                .iter()
+                .map(|s| u128::from(s.total_burn.saturating_sub(s.own_burn)))
+                .sum(),
+        );
+        let mut winner_burns: Vec<_> = sortitions.iter().filter_map(|s| s.winner_burn).collect();
+        winner_burns.sort_unstable();
+        let median_winner_burn = winner_burns.get(winner_burns.len() / 2).copied();
+        let avg_competitors = sortitions.iter().map(|s| s.competitors).sum::<u64>() as f64
+            / num_sortitions.max(1) as f64;
+
+        // bid / (bid + competitor burn) = target share
+        let target_win_pct = u128::from(settings.target_win_pct.clamp(1, 99));
+        let target_bid = u64::try_from(
+            u128::from(avg_competitor_burn) * target_win_pct / (100 - target_win_pct),
+        )
+        .unwrap_or(u64::MAX);
+        let bid = target_bid
+            .min(settings.max_sats)
+            .max(settings.min_sats.min(settings.max_sats));
+
+        Self {
+            sortitions: num_sortitions,
+            avg_total_burn,
+            avg_competitor_burn,
+            median_winner_burn,
+            avg_competitors,
+            target_bid,
+            bid,
+        }
+    }
+}
+
+/// Load the block-commits in the `window` sortitions up to and including `tip`, newest first
+pub fn load_recent_sortition_burns(
+    sortdb: &SortitionDB,
+    tip: &BlockSnapshot,
+    window: u64,
+    miner: &BurnchainSigner,
+) -> Result<Vec<SortitionBurns>, DBError> {
+    let mut burns = vec![];
+    let mut cursor = Some(tip.clone());
+    while let Some(snapshot) = cursor {
+        if u64::try_from(burns.len()).unwrap_or(u64::MAX) >= window || snapshot.block_height == 0 {
+            break;
+        }
+        let commits =
+            SortitionDB::get_block_commits_by_block(sortdb.conn(), &snapshot.sortition_id)?;
+        let mut sortition = SortitionBurns::default();
+        let mut competitors = HashSet::new();
+        for commit in commits.iter() {
+            sortition.total_burn = sortition.total_burn.saturating_add(commit.burn_fee);
+            if &commit.apparent_sender == miner {
+                sortition.own_burn = sortition.own_burn.saturating_add(commit.burn_fee);
+            } else {
+                competitors.insert(commit.apparent_sender.0.as_str());
+            }
+            if snapshot.sortition && commit.txid == snapshot.winning_block_txid {
+                sortition.winner_burn = Some(commit.burn_fee);
+            }
+        }
+        sortition.competitors = u64::try_from(competitors.len()).unwrap_or(u64::MAX);
+        burns.push(sortition);
+        cursor = SortitionDB::get_block_snapshot(sortdb.conn(), &snapshot.parent_sortition_id)?;
+    }
+    Ok(burns)
+}
+
+/// Chooses how much the miner burns in its block-commits, once per burnchain block
+#[derive(Debug, Default)]
+pub struct CommitBidController {
+    /// The burnchain tip the last decision was made at, and the decision
+    last_decision: Option<(ConsensusHash, CommitBidDecision)>,
+}
+
+impl CommitBidController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Choose the amount to burn in a block-commit built atop `tip`.  The decision is made (and
+    /// logged and exported as metrics) the first time this is called for a burnchain tip, and
+    /// reused for any other block-commits built atop it.
+    pub fn get_bid(
+        &mut self,
+        sortdb: &SortitionDB,
+        tip: &BlockSnapshot,
+        miner: &BurnchainSigner,
+        settings: &CommitBidSettings,
+    ) -> Result<u64, DBError> {
+        if let Some((consensus_hash, decision)) = self.last_decision.as_ref() {
+            if consensus_hash == &tip.consensus_hash {
+                return Ok(decision.bid);
+            }
+        }
+
+        let burns = load_recent_sortition_burns(sortdb, tip, settings.window, miner)?;
+        let decision = CommitBidDecision::choose(&burns, settings);
+        info!("Relayer: chose block-commit bid";
+            "burn_block_height" => tip.block_height,
+            "bid" => decision.bid,
+            "target_bid" => decision.target_bid,
+            "min_sats" => settings.min_sats,
+            "max_sats" => settings.max_sats,
+            "target_win_pct" => settings.target_win_pct,
+            "sortitions" => decision.sortitions,
+            "avg_total_burn" => decision.avg_total_burn,
+            "avg_competitor_burn" => decision.avg_competitor_burn,
+            "median_winner_burn" => ?decision.median_winner_burn,
+            "avg_competitors" => decision.avg_competitors,
+        );
+        update_commit_bid(
+            decision.bid,
+            decision.target_bid,
+            decision.avg_competitor_burn,
+            decision.median_winner_burn.unwrap_or(0),
+            decision.avg_competitors,
+        );
+
+        let bid = decision.bid;
+        self.last_decision = Some((tip.consensus_hash, decision));
+        Ok(bid)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn settings(min_sats: u64, max_sats: u64, target_win_pct: u8) -> CommitBidSettings {
+        CommitBidSettings {
+            min_sats,
+            max_sats,
+            window: 6,
+            target_win_pct,
+        }
+    }
+
+    fn sortition(total_burn: u64, own_burn: u64, winner_burn: Option<u64>) -> SortitionBurns {
+        SortitionBurns {
+            total_burn,
+            own_burn,
+            winner_burn,
+            competitors: 2,
+        }
+    }
+
+    #[test]
+    fn test_choose_commit_bid() {
+        let sortitions = vec![
+            sortition(30_000, 10_000, Some(15_000)),
+            sortition(40_000, 10_000, Some(20_000)),
+            sortition(50_000, 10_000, None),
+        ];
+
+        // other miners burn 30,000 sats per sortition on average, so winning half the time
+        // takes a bid of 30,000 sats
+        let decision = CommitBidDecision::choose(&sortitions, &settings(1_000, 100_000, 50));
+        assert_eq!(decision.sortitions, 3);
+        assert_eq!(decision.avg_total_burn, 40_000);
+        assert_eq!(decision.avg_competitor_burn, 30_000);
+        assert_eq!(decision.median_winner_burn, Some(20_000));
+        assert_eq!(decision.avg_competitors, 2.0);
+        assert_eq!(decision.target_bid, 30_000);
+        assert_eq!(decision.bid, 30_000);
+
+        // ...and winning a quarter of the time takes 10,000 sats
+        let decision = CommitBidDecision::choose(&sortitions, &settings(1_000, 100_000, 25));
+        assert_eq!(decision.target_bid, 10_000);
+        assert_eq!(decision.bid, 10_000);
+
+        // the bid stays within the operator's bounds
+        let decision = CommitBidDecision::choose(&sortitions, &settings(1_000, 20_000, 50));
+        assert_eq!(decision.target_bid, 30_000);
+        assert_eq!(decision.bid, 20_000);
+
+        let decision = CommitBidDecision::choose(&sortitions, &settings(15_000, 20_000, 25));
+        assert_eq!(decision.bid, 15_000);
+
+        // with no competition, the miner bids the minimum
+        let decision = CommitBidDecision::choose(&[], &settings(1_000, 20_000, 50));
+        assert_eq!(decision.sortitions, 0);
+        assert_eq!(decision.median_winner_burn, None);
+        assert_eq!(decision.target_bid, 0);
+        assert_eq!(decision.bid, 1_000);
+    }
+}
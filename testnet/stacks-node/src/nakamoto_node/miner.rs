@@ -21,7 +21,6 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use clarity::boot_util::boot_code_id;
-use clarity::vm::costs::ExecutionCost;
 use clarity::vm::types::PrincipalData;
 use libsigner::v0::messages::{MinerSlotID, SignerMessage};
 use libsigner::StackerDBSession;
@@ -29,9 +28,10 @@ use rand::{thread_rng, Rng};
 use stacks::burnchains::Burnchain;
 use stacks::chainstate::burn::db::sortdb::SortitionDB;
 use stacks::chainstate::burn::{BlockSnapshot, ConsensusHash};
-use stacks::chainstate::coordinator::OnChainRewardSetProvider;
 use stacks::chainstate::nakamoto::coordinator::load_nakamoto_reward_set;
-use stacks::chainstate::nakamoto::miner::{NakamotoBlockBuilder, NakamotoTenureInfo};
+use stacks::chainstate::nakamoto::miner::{
+    BlockMetadata, NakamotoBlockBuilder, NakamotoTenureInfo,
+};
 use stacks::chainstate::nakamoto::staging_blocks::NakamotoBlockObtainMethod;
 use stacks::chainstate::nakamoto::{NakamotoBlock, NakamotoChainState};
 use stacks::chainstate::stacks::boot::{RewardSet, MINERS_NAME};
@@ -41,6 +41,8 @@ use stacks::chainstate::stacks::{
     TenureChangeCause, TenureChangePayload, TransactionAnchorMode, TransactionPayload,
     TransactionVersion,
 };
+use stacks::cost_estimates::fill_stats::BlockFillRecord;
+use stacks::net::api::getminerreports::MinerTenureReport;
 use stacks::net::api::poststackerdbchunk::StackerDBErrorCodes;
 use stacks::net::p2p::NetworkHandle;
 use stacks::net::stackerdb::StackerDBs;
@@ -178,10 +180,6 @@ pub struct BlockMinerThread {
     last_block_mined: Option<NakamotoBlock>,
     /// Number of blocks mined since a tenure change/extend was attempted
     mined_blocks: u64,
-    /// Cost consumed by the current tenure
-    tenure_cost: ExecutionCost,
-    /// Cost budget for the current tenure
-    tenure_budget: ExecutionCost,
     /// Copy of the node's registered VRF key
     registered_key: RegisteredKey,
     /// Burnchain block snapshot which elected this miner
@@ -211,6 +209,13 @@ pub struct BlockMinerThread {
     burn_tip_at_start: ConsensusHash,
     /// flag to indicate an abort driven from the relayer
     abort_flag: Arc<AtomicBool>,
+    /// Number of blocks reproposed in a row, without pausing, after signers rejected
+    /// transactions in the previous proposal
+    reproposals: u32,
+    /// The time at which this miner thread was started
+    start_time: Instant,
+    /// What this miner thread has mined so far, reported when it stops
+    tenure_report: MinerTenureReport,
 }
 
 impl BlockMinerThread {
@@ -224,6 +229,22 @@ impl BlockMinerThread {
         burn_tip_at_start: &ConsensusHash,
         reason: MinerReason,
     ) -> BlockMinerThread {
+        let tenure_report = MinerTenureReport {
+            tenure_consensus_hash: burn_election_block.consensus_hash,
+            burn_view_consensus_hash: burn_block.consensus_hash,
+            burn_block_height: burn_block.block_height,
+            tenure_extend: matches!(reason, MinerReason::Extended { .. }),
+            started_at: get_epoch_time_secs(),
+            ended_at: 0,
+            blocks_mined: 0,
+            first_block_height: None,
+            last_block_height: None,
+            txs_included: 0,
+            fees_earned: 0,
+            commit_cost_sats: None,
+            proposals_rejected: 0,
+            time_to_first_signature_ms: None,
+        };
         BlockMinerThread {
             config: rt.config.clone(),
             globals: rt.globals.clone(),
@@ -242,8 +263,9 @@ impl BlockMinerThread {
             burn_tip_at_start: burn_tip_at_start.clone(),
             tenure_change_time: Instant::now(),
             abort_flag: Arc::new(AtomicBool::new(false)),
-            tenure_cost: ExecutionCost::ZERO,
-            tenure_budget: ExecutionCost::ZERO,
+            reproposals: 0,
+            start_time: Instant::now(),
+            tenure_report,
         }
     }
 
@@ -377,6 +399,7 @@ impl BlockMinerThread {
             miner_privkey,
             &self.config,
             &self.burn_tip_at_start,
+            self.globals.get_signer_latency_stats(),
         )
         .map_err(|e| {
             NakamotoNodeError::SigningCoordinatorFailure(format!(
@@ -395,11 +418,69 @@ impl BlockMinerThread {
             ) {
                 // Before stopping this miner, shutdown the coordinator thread.
                 coordinator.shutdown();
+                self.send_tenure_report(&sortdb);
                 return Err(e);
             }
         }
     }
 
+    /// Add a block that the signers signed and this miner broadcast to the tenure report
+    fn record_mined_block(&mut self, block: &NakamotoBlock) {
+        let report = &mut self.tenure_report;
+        let height = block.header.chain_length;
+        report.blocks_mined += 1;
+        report.first_block_height.get_or_insert(height);
+        report.last_block_height = Some(height);
+        for tx in block.txs.iter() {
+            if matches!(
+                tx.payload,
+                TransactionPayload::TenureChange(..) | TransactionPayload::Coinbase(..)
+            ) {
+                continue;
+            }
+            report.txs_included += 1;
+            report.fees_earned = report.fees_earned.saturating_add(tx.get_tx_fee());
+        }
+    }
+
+    /// Finish the report of what this miner thread mined, and send it to `miner_reports` event
+    /// observers and the `/v3/miner/reports` history
+    fn send_tenure_report(&self, sortdb: &SortitionDB) {
+        let mut report = self.tenure_report.clone();
+        report.ended_at = get_epoch_time_secs();
+        if !report.tenure_extend {
+            report.commit_cost_sats = match SortitionDB::get_block_commit(
+                sortdb.conn(),
+                &self.burn_election_block.winning_block_txid,
+                &self.burn_election_block.sortition_id,
+            ) {
+                Ok(commit_opt) => commit_opt.map(|commit| commit.burn_fee),
+                Err(e) => {
+                    warn!("Miner: failed to load winning block commit for tenure report: {e:?}");
+                    None
+                }
+            };
+        }
+
+        info!("Miner: tenure report";
+            "tenure_consensus_hash" => %report.tenure_consensus_hash,
+            "burn_view_consensus_hash" => %report.burn_view_consensus_hash,
+            "tenure_extend" => report.tenure_extend,
+            "blocks_mined" => report.blocks_mined,
+            "txs_included" => report.txs_included,
+            "fees_earned" => report.fees_earned,
+            "commit_cost_sats" => ?report.commit_cost_sats,
+            "proposals_rejected" => report.proposals_rejected,
+            "time_to_first_signature_ms" => ?report.time_to_first_signature_ms,
+        );
+        self.event_dispatcher.process_miner_tenure_report(&report);
+        self.globals
+            .get_miner_reports()
+            .lock()
+            .expect("FATAL: miner reports mutex poisoned")
+            .record(report);
+    }
+
     /// Pause the miner thread and retry to mine
     fn pause_and_retry(
         &self,
@@ -423,6 +504,66 @@ impl BlockMinerThread {
         *last_block_rejected = true;
     }
 
+    /// Feed the transactions that signers blamed for rejecting `new_block` back into the
+    /// mempool, so that the next block assembly skips them until their penalty expires.
+    /// Returns true if the next block assembly will leave out at least one of them.
+    fn penalize_rejected_txs(
+        &self,
+        coordinator: &SignerCoordinator,
+        new_block: &NakamotoBlock,
+    ) -> bool {
+        let rejected_txs = coordinator.get_rejected_txs(new_block);
+        if rejected_txs.is_empty() {
+            return false;
+        }
+        info!("Signers rejected transactions in block proposal, penalizing them in the mempool";
+            "signer_sighash" => %new_block.header.signer_signature_hash(),
+            "txids" => ?rejected_txs.iter().map(|(txid, _)| txid).collect::<Vec<_>>(),
+        );
+        let mut mem_pool = match self.config.connect_mempool_db() {
+            Ok(mem_pool) => mem_pool,
+            Err(e) => {
+                warn!("Failed to open mempool to penalize rejected transactions: {e:?}");
+                return false;
+            }
+        };
+        if let Err(e) = mem_pool.penalize_rejected_txs(&rejected_txs) {
+            warn!("Failed to penalize rejected transactions in the mempool: {e:?}");
+            return false;
+        }
+        true
+    }
+
+    /// Handle a rejection of `new_block` by the signers. If they blamed specific transactions,
+    /// and the miner has not reproposed too many blocks in a row already, retry right away: the
+    /// next block leaves those transactions out, so it is not an identical proposal.
+    /// Otherwise, pause before retrying.
+    fn handle_rejection(
+        &mut self,
+        coordinator: &SignerCoordinator,
+        new_block: &NakamotoBlock,
+        last_block_rejected: &mut bool,
+        e: NakamotoNodeError,
+    ) {
+        self.tenure_report.proposals_rejected += 1;
+        let penalized_txs = self.penalize_rejected_txs(coordinator, new_block);
+        if penalized_txs && self.reproposals < self.config.miner.max_reproposals {
+            self.reproposals += 1;
+            info!("Miner: Reproposing block without the transactions that signers rejected";
+                "signer_sighash" => %new_block.header.signer_signature_hash(),
+                "block_height" => new_block.header.chain_length,
+                "consensus_hash" => %new_block.header.consensus_hash,
+                "reproposals" => self.reproposals,
+                "max_reproposals" => self.config.miner.max_reproposals,
+            );
+            self.globals.counters.bump_naka_reproposed_blocks();
+            *last_block_rejected = true;
+            return;
+        }
+        self.reproposals = 0;
+        self.pause_and_retry(new_block, last_block_rejected, e);
+    }
+
     /// The main loop for the miner thread. This is where the miner will mine
     /// blocks and then attempt to sign and broadcast them.
     fn miner_main_loop(
@@ -555,12 +696,18 @@ impl BlockMinerThread {
                         return Ok(());
                     }
                     _ => {
-                        self.pause_and_retry(&new_block, last_block_rejected, e);
+                        self.handle_rejection(coordinator, &new_block, last_block_rejected, e);
                         return Ok(());
                     }
                 },
             };
             *last_block_rejected = false;
+            self.reproposals = 0;
+            if self.tenure_report.time_to_first_signature_ms.is_none() {
+                let elapsed_ms = self.start_time.elapsed().as_millis();
+                self.tenure_report.time_to_first_signature_ms =
+                    Some(u64::try_from(elapsed_ms).unwrap_or(u64::MAX));
+            }
 
             new_block.header.signer_signature = signer_signature;
             if let Err(e) = self.broadcast(new_block.clone(), reward_set, stackerdbs) {
@@ -588,6 +735,7 @@ impl BlockMinerThread {
             Self::fault_injection_block_announce_stall(&new_block);
             self.globals.coord().announce_new_stacks_block();
 
+            self.record_mined_block(&new_block);
             self.last_block_mined = Some(new_block);
             self.mined_blocks += 1;
         }
@@ -674,6 +822,7 @@ impl BlockMinerThread {
             .block_height_to_reward_cycle(burn_election_height)
             .expect("FATAL: no reward cycle for sortition");
 
+        let reward_set_provider = chain_state.reward_set_provider();
         let reward_info = match load_nakamoto_reward_set(
             reward_cycle,
             &self.burn_election_block.sortition_id,
@@ -681,7 +830,7 @@ impl BlockMinerThread {
             &mut chain_state,
             &self.parent_tenure_id,
             &sort_db,
-            &OnChainRewardSetProvider::new(),
+            &reward_set_provider,
         ) {
             Ok(Some((reward_info, _))) => reward_info,
             Ok(None) => {
@@ -756,6 +905,7 @@ impl BlockMinerThread {
             headers_conn,
             reward_set,
             NakamotoBlockObtainMethod::Mined,
+            None,
         )?;
         staging_tx.commit()?;
 
@@ -1170,6 +1320,7 @@ impl BlockMinerThread {
         // create our coinbase if this is the first block we've mined this tenure
         let tenure_start_info = self.make_tenure_start_info(
             &chain_state,
+            &burn_db,
             &parent_block_info,
             vrf_proof,
             target_epoch_id,
@@ -1189,6 +1340,14 @@ impl BlockMinerThread {
             return Err(ChainstateError::MinerAborted.into());
         }
 
+        let settings = self
+            .config
+            .make_nakamoto_block_builder_settings(self.globals.get_miner_status());
+        let attempt_time_ms = settings.max_miner_time_ms;
+        let soft_limit_pct = settings
+            .mempool_settings
+            .tenure_cost_limit_per_block_percentage;
+
         // build the block itself
         let mut block_metadata = NakamotoBlockBuilder::build_nakamoto_block(
             &chain_state,
@@ -1200,8 +1359,7 @@ impl BlockMinerThread {
             &self.burn_election_block.consensus_hash,
             self.burn_block.total_burn,
             tenure_start_info,
-            self.config
-                .make_nakamoto_block_builder_settings(self.globals.get_miner_status()),
+            settings,
             // we'll invoke the event dispatcher ourselves so that it calculates the
             //  correct signer_sighash for `process_mined_nakamoto_block_event`
             Some(&self.event_dispatcher),
@@ -1251,8 +1409,7 @@ impl BlockMinerThread {
             block_metadata.tx_events,
         );
 
-        self.tenure_cost = block_metadata.tenure_consumed;
-        self.tenure_budget = block_metadata.tenure_budget;
+        self.record_block_fill(&block_metadata, attempt_time_ms, soft_limit_pct);
 
         // last chance -- confirm that the stacks tip is unchanged (since it could have taken long
         // enough to build this block that another block could have arrived), and confirm that all
@@ -1261,11 +1418,37 @@ impl BlockMinerThread {
         Ok(block_metadata.block)
     }
 
+    /// Record how full a newly-assembled block is, so operators can tune the miner's settings
+    fn record_block_fill(
+        &self,
+        block_metadata: &BlockMetadata,
+        attempt_time_ms: u64,
+        soft_limit_pct: Option<u8>,
+    ) {
+        let record = BlockFillRecord {
+            stacks_block_height: block_metadata.block.header.chain_length,
+            tx_count: block_metadata.block.txs.len() as u64,
+            size: block_metadata.tenure_size,
+            block_consumed: block_metadata.block_consumed.clone(),
+            tenure_consumed: block_metadata.tenure_consumed.clone(),
+            tenure_budget: block_metadata.tenure_budget.clone(),
+            assembly_time_ms: block_metadata.assembly_time_ms,
+            attempt_time_ms,
+            soft_limit_pct,
+        };
+        self.globals
+            .get_miner_fill_stats()
+            .lock()
+            .expect("FATAL: miner fill stats mutex poisoned")
+            .record_block(record);
+    }
+
     #[cfg_attr(test, mutants::skip)]
     /// Create the tenure start info for the block we're going to build
     fn make_tenure_start_info(
         &mut self,
         chainstate: &StacksChainState,
+        sortdb: &SortitionDB,
         parent_block_info: &ParentStacksBlockInfo,
         vrf_proof: VRFProof,
         target_epoch_id: StacksEpochId,
@@ -1292,11 +1475,21 @@ impl BlockMinerThread {
         // Check if we can and should include a time-based tenure extend.
         if self.last_block_mined.is_some() {
             // Do not extend if we have spent < 50% of the budget, since it is
-            // not necessary.
-            let usage = self
-                .tenure_budget
-                .proportion_largest_dimension(&self.tenure_cost);
-            if usage < self.config.miner.tenure_extend_cost_threshold {
+            // not necessary -- unless another block like the last one would
+            // run into the budget.
+            let parent_block_id = parent_block_info.stacks_parent_header.index_block_hash();
+            let usage = NakamotoChainState::get_tenure_budget_usage(
+                chainstate.db(),
+                sortdb.conn(),
+                &parent_block_id,
+            )
+            .map_err(NakamotoNodeError::MiningFailure)?;
+            let (percent_used, projected_percent_used) = usage
+                .map(|usage| (usage.percent_used, usage.projected_percent_used))
+                .unwrap_or((0, 0));
+            if percent_used < self.config.miner.tenure_extend_cost_threshold
+                && projected_percent_used < 100
+            {
                 return Ok(NakamotoTenureInfo {
                     coinbase_tx: None,
                     tenure_change_tx: None,
@@ -1314,6 +1507,8 @@ impl BlockMinerThread {
             }
 
             info!("Miner: Time-based tenure extend";
+                "tenure_budget_percent_used" => percent_used,
+                "tenure_budget_projected_percent_used" => projected_percent_used,
                 "current_timestamp" => get_epoch_time_secs(),
                 "tenure_extend_timestamp" => tenure_extend_timestamp,
                 "tenure_change_time_elapsed" => self.tenure_change_time.elapsed().as_secs(),
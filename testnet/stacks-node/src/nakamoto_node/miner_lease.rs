@@ -0,0 +1,213 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Active/standby election for redundant miners (`miner.failover_lease_path` in the config file).
+//!
+//! Two or more nodes configured with the same `mining_key` must never both submit block-commits,
+//! or they will outbid each other and produce conflicting tenures.  Each node opens the same
+//! SQLite lease file (e.g. on shared storage), and only the node that holds the lease commits
+//! and mines.  The active node renews the lease every time it submits a block-commit, so if it
+//! stops committing for `miner.failover_lease_ttl_secs`, the lease expires and the next standby
+//! to issue a block-commit takes it over.
+//!
+//! The lease file is not opened in WAL mode, since WAL does not work on network filesystems.
+
+use std::path::Path;
+
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use stacks::util_lib::db::{tx_begin_immediate, tx_busy_handler, Error as DBError};
+
+const LEASE_SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS miner_lease(
+        -- there is only ever one lease
+        id INTEGER PRIMARY KEY CHECK (id = 0),
+        node_id TEXT NOT NULL,
+        expires_at INTEGER NOT NULL
+    );";
+
+/// Who holds the miner lease
+#[derive(Debug, Clone, PartialEq)]
+pub enum LeaseStatus {
+    /// This node holds the lease, and may commit and mine
+    Held,
+    /// Another node holds the lease until `expires_at` (epoch seconds)
+    HeldBy { node_id: String, expires_at: u64 },
+}
+
+/// A handle to the miner lease shared by redundant nodes
+pub struct MinerLease {
+    conn: Connection,
+    node_id: String,
+    ttl_secs: u64,
+}
+
+impl MinerLease {
+    /// Open (and create, if need be) the lease file at `path` for the node `node_id`
+    pub fn open<P: AsRef<Path>>(path: P, node_id: &str, ttl_secs: u64) -> Result<Self, DBError> {
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE,
+        )?;
+        conn.busy_handler(Some(tx_busy_handler))?;
+        conn.execute_batch(LEASE_SCHEMA)?;
+        Ok(Self {
+            conn,
+            node_id: node_id.to_string(),
+            ttl_secs,
+        })
+    }
+
+    /// This node's ID
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Load the current lease holder and its expiry, if any node has ever held the lease
+    fn get_lease(conn: &Connection) -> Result<Option<(String, u64)>, DBError> {
+        let lease = conn
+            .query_row(
+                "SELECT node_id, expires_at FROM miner_lease WHERE id = 0",
+                [],
+                |row| {
+                    let node_id: String = row.get(0)?;
+                    let expires_at: i64 = row.get(1)?;
+                    Ok((node_id, u64::try_from(expires_at).unwrap_or(0)))
+                },
+            )
+            .optional()?;
+        Ok(lease)
+    }
+
+    /// Is this node the most recent holder of the lease?  A node keeps the lease after it expires
+    /// until some other node takes it over.
+    pub fn is_held(&self) -> Result<bool, DBError> {
+        Ok(Self::get_lease(&self.conn)?.is_some_and(|(node_id, _)| node_id == self.node_id))
+    }
+
+    /// Try to take the lease at time `now` (epoch seconds).  Succeeds if this node already holds
+    /// it, if no node holds it, or if another node's lease has expired.  A lease this node already
+    /// holds is not extended; call `renew()` once a block-commit has been sent.
+    pub fn try_acquire(&mut self, now: u64) -> Result<LeaseStatus, DBError> {
+        let tx = tx_begin_immediate(&mut self.conn)?;
+        let previous = match Self::get_lease(&tx)? {
+            Some((node_id, _)) if node_id == self.node_id => return Ok(LeaseStatus::Held),
+            Some((node_id, expires_at)) if expires_at > now => {
+                return Ok(LeaseStatus::HeldBy {
+                    node_id,
+                    expires_at,
+                })
+            }
+            previous => previous,
+        };
+        Self::write_lease(&tx, &self.node_id, now.saturating_add(self.ttl_secs))?;
+        tx.commit()?;
+        info!("Miner lease: this node is now the active miner";
+              "node_id" => &self.node_id,
+              "previous_node_id" => previous.as_ref().map(|(node_id, _)| node_id.as_str()).unwrap_or("none"),
+              "previous_expires_at" => previous.map(|(_, expires_at)| expires_at).unwrap_or(0));
+        Ok(LeaseStatus::Held)
+    }
+
+    /// Extend this node's lease to `now + ttl`, unless another node took it over in the
+    /// meantime.
+    pub fn renew(&mut self, now: u64) -> Result<LeaseStatus, DBError> {
+        let tx = tx_begin_immediate(&mut self.conn)?;
+        if let Some((node_id, expires_at)) = Self::get_lease(&tx)? {
+            if node_id != self.node_id && expires_at > now {
+                return Ok(LeaseStatus::HeldBy {
+                    node_id,
+                    expires_at,
+                });
+            }
+        }
+        Self::write_lease(&tx, &self.node_id, now.saturating_add(self.ttl_secs))?;
+        tx.commit()?;
+        Ok(LeaseStatus::Held)
+    }
+
+    fn write_lease(conn: &Connection, node_id: &str, expires_at: u64) -> Result<(), DBError> {
+        let expires_at = i64::try_from(expires_at).map_err(|_| DBError::ParseError)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO miner_lease (id, node_id, expires_at) VALUES (0, ?1, ?2)",
+            params![node_id, expires_at],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_miner_lease_failover() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("miner-lease.sqlite");
+        let mut node_a = MinerLease::open(&path, "node-a", 100).unwrap();
+        let mut node_b = MinerLease::open(&path, "node-b", 100).unwrap();
+
+        assert!(!node_a.is_held().unwrap());
+        assert!(!node_b.is_held().unwrap());
+
+        // first come, first served
+        assert_eq!(node_a.try_acquire(1000).unwrap(), LeaseStatus::Held);
+        assert!(node_a.is_held().unwrap());
+        assert_eq!(
+            node_b.try_acquire(1000).unwrap(),
+            LeaseStatus::HeldBy {
+                node_id: "node-a".into(),
+                expires_at: 1100
+            }
+        );
+        assert!(!node_b.is_held().unwrap());
+
+        // the active node keeps its lease by renewing it
+        assert_eq!(node_a.renew(1090).unwrap(), LeaseStatus::Held);
+        assert_eq!(
+            node_b.try_acquire(1150).unwrap(),
+            LeaseStatus::HeldBy {
+                node_id: "node-a".into(),
+                expires_at: 1190
+            }
+        );
+
+        // once the active node stops renewing, the standby takes over
+        assert_eq!(node_b.try_acquire(1190).unwrap(), LeaseStatus::Held);
+        assert!(node_b.is_held().unwrap());
+        assert!(!node_a.is_held().unwrap());
+
+        // ...and the old active node cannot renew its way back in
+        assert_eq!(
+            node_a.renew(1200).unwrap(),
+            LeaseStatus::HeldBy {
+                node_id: "node-b".into(),
+                expires_at: 1290
+            }
+        );
+        assert_eq!(
+            node_a.try_acquire(1200).unwrap(),
+            LeaseStatus::HeldBy {
+                node_id: "node-b".into(),
+                expires_at: 1290
+            }
+        );
+
+        // acquiring a lease this node already holds does not extend it
+        assert_eq!(node_b.try_acquire(1250).unwrap(), LeaseStatus::Held);
+        assert_eq!(node_a.try_acquire(1290).unwrap(), LeaseStatus::Held);
+    }
+}
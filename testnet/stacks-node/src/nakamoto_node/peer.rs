@@ -13,7 +13,6 @@
 //
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
-use std::net::SocketAddr;
 use std::sync::mpsc::TrySendError;
 use std::thread;
 use std::time::Duration;
@@ -21,12 +20,17 @@ use std::time::Duration;
 use stacks::burnchains::db::BurnchainHeaderReader;
 use stacks::burnchains::PoxConstants;
 use stacks::chainstate::burn::db::sortdb::SortitionDB;
+use stacks::chainstate::stacks::db::receipts::TransactionReceiptDB;
+use stacks::chainstate::stacks::db::state_diffs::StateDiffDB;
 use stacks::chainstate::stacks::db::StacksChainState;
 use stacks::chainstate::stacks::miner::signal_mining_blocked;
+use stacks::config::ConfigFileReloader;
 use stacks::core::mempool::MemPoolDB;
+use stacks::cost_estimates::contract_costs::ContractCostDB;
 use stacks::cost_estimates::metrics::{CostMetric, UnitMetric};
 use stacks::cost_estimates::{CostEstimator, FeeEstimator, UnitEstimator};
 use stacks::net::dns::{DNSClient, DNSResolver};
+use stacks::net::nat::NatTraversal;
 use stacks::net::p2p::PeerNetwork;
 use stacks::net::RPCHandlerArgs;
 use stacks_common::util::hash::Sha256Sum;
@@ -90,6 +94,23 @@ impl PeerThread {
                 .unwrap();
         }
 
+        // spawn a daemon thread that maps the p2p port on the NAT gateway, if asked.
+        // It will die when the PeerNetwork dies.
+        if self.config.connection_options.nat_traversal {
+            let (mut nat_traversal, nat_handle) = NatTraversal::new(
+                self.net.bound_neighbor_key().port,
+                &self.config.connection_options,
+            );
+            self.net.set_nat_handle(nat_handle);
+            let _jh = thread::Builder::new()
+                .name("nat-traversal".to_string())
+                .spawn(move || {
+                    debug!("NAT traversal thread ID is {:?}", thread::current().id());
+                    nat_traversal.thread_main();
+                })
+                .unwrap();
+        }
+
         // NOTE: these must be instantiated in the thread context, since it can't be safely sent
         // between threads
         let fee_estimator_opt = self.config.make_fee_estimator();
@@ -101,6 +122,9 @@ impl PeerThread {
             .config
             .make_cost_metric()
             .unwrap_or_else(|| Box::new(UnitMetric));
+        let contract_cost_db_opt = self.config.make_contract_cost_db();
+        let receipt_db_opt = self.config.make_receipt_db();
+        let state_diff_db_opt = self.config.make_state_diff_db();
 
         let indexer = make_bitcoin_indexer(&self.config, Some(should_keep_running));
 
@@ -116,6 +140,9 @@ impl PeerThread {
                 &cost_estimator,
                 &cost_metric,
                 fee_estimator_opt.as_ref(),
+                contract_cost_db_opt.as_ref(),
+                receipt_db_opt.as_ref(),
+                state_diff_db_opt.as_ref(),
             ) {
                 break;
             }
@@ -165,22 +192,17 @@ impl PeerThread {
         let sortdb = SortitionDB::open(&burn_db_path, false, pox_constants)
             .expect("FATAL: could not open sortition DB");
 
-        let chainstate =
+        let mut chainstate =
             open_chainstate_with_faults(&config).expect("FATAL: could not open chainstate DB");
-
-        let p2p_sock: SocketAddr = config
-            .node
-            .p2p_bind
-            .parse()
-            .unwrap_or_else(|_| panic!("Failed to parse socket: {}", &config.node.p2p_bind));
-        let rpc_sock = config
-            .node
-            .rpc_bind
-            .parse()
-            .unwrap_or_else(|_| panic!("Failed to parse socket: {}", &config.node.rpc_bind));
+        chainstate
+            .enable_read_replicas(&sortdb, config.connection_options.rpc_read_replicas)
+            .expect("FATAL: could not open chainstate read replicas");
 
         let did_bind = net
-            .try_bind(&p2p_sock, &rpc_sock)
+            .try_bind_interfaces(
+                &config.node.get_p2p_bind_addrs(),
+                &config.node.get_rpc_bind_addrs(),
+            )
             .expect("BUG: PeerNetwork could not bind");
 
         if !did_bind {
@@ -224,7 +246,7 @@ impl PeerThread {
 
     /// Run one pass of the p2p/http state machine
     /// Return true if we should continue running passes; false if not
-    #[allow(clippy::borrowed_box)]
+    #[allow(clippy::borrowed_box, clippy::too_many_arguments)]
     pub(crate) fn run_one_pass<B: BurnchainHeaderReader>(
         &mut self,
         indexer: &B,
@@ -233,6 +255,9 @@ impl PeerThread {
         cost_estimator: &Box<dyn CostEstimator>,
         cost_metric: &Box<dyn CostMetric>,
         fee_estimator: Option<&Box<dyn FeeEstimator>>,
+        contract_cost_db: Option<&ContractCostDB>,
+        receipt_db: Option<&TransactionReceiptDB>,
+        state_diff_db: Option<&StateDiffDB>,
     ) -> bool {
         // initial block download?
         let ibd = self.globals.sync_comms.get_ibd();
@@ -261,6 +286,13 @@ impl PeerThread {
 
         self.refresh_stackerdb();
 
+        let miner_fill_stats = self.globals.get_miner_fill_stats();
+        let miner_utxo_status = self.globals.get_miner_utxo_status();
+        let miner_reports = self.globals.get_miner_reports();
+        let header_sync_tracker = self.globals.get_header_sync_tracker();
+        let mining_paused = self.globals.get_mining_paused();
+        let config_reloader = ConfigFileReloader::new(&self.config);
+
         // do one pass
         let p2p_res = {
             // NOTE: handler_args must be created such that it outlives the inner net.run() call and
@@ -270,10 +302,20 @@ impl PeerThread {
                 genesis_chainstate_hash: Sha256Sum::from_hex(stx_genesis::GENESIS_CHAINSTATE_HASH)
                     .unwrap(),
                 event_observer: Some(event_dispatcher),
+                stackerdb_replication: event_dispatcher.stackerdb_replication(),
                 cost_estimator: Some(cost_estimator.as_ref()),
                 cost_metric: Some(cost_metric.as_ref()),
                 fee_estimator: fee_estimator.map(|boxed_estimator| boxed_estimator.as_ref()),
+                contract_cost_db,
+                receipt_db,
+                state_diff_db,
+                miner_fill_stats: Some(miner_fill_stats.as_ref()),
+                miner_utxos: Some(miner_utxo_status.as_ref()),
+                miner_reports: Some(miner_reports.as_ref()),
+                mining_paused: Some(mining_paused.as_ref()),
+                config_reloader: Some(&config_reloader),
                 coord_comms: Some(&self.globals.coord_comms),
+                header_sync: Some(&header_sync_tracker),
             };
             self.net.run(
                 indexer,
@@ -40,7 +40,7 @@ use stacks::chainstate::nakamoto::{NakamotoBlockHeader, NakamotoChainState};
 use stacks::chainstate::stacks::address::PoxAddress;
 use stacks::chainstate::stacks::db::StacksChainState;
 use stacks::chainstate::stacks::miner::{
-    get_mining_spend_amount, signal_mining_blocked, signal_mining_ready,
+    get_mining_spend_amount, set_mining_spend_amount, signal_mining_blocked, signal_mining_ready,
 };
 use stacks::chainstate::stacks::Error as ChainstateError;
 use stacks::core::mempool::MemPoolDB;
@@ -54,13 +54,15 @@ use stacks_common::types::chainstate::{
     BlockHeaderHash, BurnchainHeaderHash, StacksBlockId, StacksPublicKey, VRFSeed,
 };
 use stacks_common::types::StacksEpochId;
-use stacks_common::util::get_epoch_time_ms;
 use stacks_common::util::hash::Hash160;
 #[cfg(test)]
 use stacks_common::util::tests::TestFlag;
 use stacks_common::util::vrf::VRFPublicKey;
+use stacks_common::util::{get_epoch_time_ms, get_epoch_time_secs};
 
+use super::commit_bid::{CommitBidController, CommitBidSettings};
 use super::miner::MinerReason;
+use super::miner_lease::{LeaseStatus, MinerLease};
 use super::{
     BlockCommits, Config, Error as NakamotoNodeError, EventDispatcher, Keychain,
     BLOCK_PROCESSOR_STACK_SIZE,
@@ -321,6 +323,11 @@ pub struct RelayerThread {
     new_tenure_timeout: Option<Instant>,
     /// Timeout for waiting for a BlockFound in a subsequent tenure before trying to extend our own
     tenure_extend_timeout: Option<Instant>,
+    /// Lease shared with redundant nodes that use the same mining key, if configured.  Only the
+    /// node holding it may commit and mine.
+    miner_lease: Option<MinerLease>,
+    /// Chooses how much to burn in each block-commit, if burn fee auto-bidding is enabled
+    commit_bid: Option<CommitBidController>,
 }
 
 impl RelayerThread {
@@ -347,10 +354,22 @@ impl RelayerThread {
             .connect_mempool_db()
             .expect("Database failure opening mempool");
 
-        let bitcoin_controller = BitcoinRegtestController::new_dummy(config.clone());
+        let mut bitcoin_controller = BitcoinRegtestController::new_dummy(config.clone());
+        bitcoin_controller.set_utxo_status(globals.get_miner_utxo_status());
 
         let next_initiative_delay = config.node.next_initiative_delay;
 
+        let miner_lease = config.miner.failover_lease_path.as_ref().map(|path| {
+            let node_id =
+                config.miner.failover_node_id.as_deref().expect(
+                    "FATAL: miner.failover_lease_path is set without miner.failover_node_id",
+                );
+            MinerLease::open(path, node_id, config.miner.failover_lease_ttl.as_secs())
+                .expect("FATAL: failed to open miner lease")
+        });
+
+        let commit_bid = config.miner.commit_bid_auto.then(CommitBidController::new);
+
         RelayerThread {
             config,
             sortdb,
@@ -381,6 +400,8 @@ impl RelayerThread {
             last_committed: None,
             new_tenure_timeout: None,
             tenure_extend_timeout: None,
+            miner_lease,
+            commit_bid,
         }
     }
 
@@ -717,7 +738,13 @@ impl RelayerThread {
             false
         };
 
-        let won_sortition = sn.sortition && was_winning_pkh;
+        // a standby that shares our mining key sees the same winning key hash, but only the node
+        // holding the miner lease may mine the tenure.  Nor may we mine it while the node operator
+        // has paused mining.
+        let won_sortition = sn.sortition
+            && was_winning_pkh
+            && self.holds_miner_lease()
+            && !self.globals.is_mining_paused();
         if won_sortition {
             increment_stx_blocks_mined_counter();
         }
@@ -942,7 +969,7 @@ impl RelayerThread {
         };
 
         // amount of burnchain tokens (e.g. sats) we'll spend across the PoX outputs
-        let burn_fee_cap = get_mining_spend_amount(self.globals.get_miner_status());
+        let burn_fee_cap = self.get_commit_bid(&sort_tip);
 
         // let's commit, but target the current burnchain tip with our modulus so the commit is
         // only valid if it lands in the targeted burnchain block height
@@ -1008,6 +1035,28 @@ impl RelayerThread {
         ))
     }
 
+    /// Get the amount of burnchain tokens to spend in a block-commit atop `sort_tip`.  This is
+    /// `burnchain.burn_fee_cap`, unless burn fee auto-bidding is enabled, in which case it is
+    /// chosen from the block-commits in recent sortitions.
+    fn get_commit_bid(&mut self, sort_tip: &BlockSnapshot) -> u64 {
+        let Some(commit_bid) = self.commit_bid.as_mut() else {
+            return get_mining_spend_amount(self.globals.get_miner_status());
+        };
+        let settings =
+            CommitBidSettings::from_config(&self.config.miner, self.config.burnchain.burn_fee_cap);
+        let sender = self.keychain.get_burnchain_signer();
+        match commit_bid.get_bid(&self.sortdb, sort_tip, &sender, &settings) {
+            Ok(bid) => {
+                set_mining_spend_amount(self.globals.get_miner_status(), bid);
+                bid
+            }
+            Err(e) => {
+                warn!("Relayer: failed to choose block-commit bid, using the last one: {e:?}");
+                get_mining_spend_amount(self.globals.get_miner_status())
+            }
+        }
+    }
+
     #[cfg(test)]
     fn fault_injection_stall_miner_startup() {
         if TEST_MINER_THREAD_STALL.get() {
@@ -1199,6 +1248,17 @@ impl RelayerThread {
         Ok(())
     }
 
+    /// Does this node hold the miner lease?  Always true if no lease is configured.
+    fn holds_miner_lease(&self) -> bool {
+        let Some(miner_lease) = self.miner_lease.as_ref() else {
+            return true;
+        };
+        miner_lease.is_held().unwrap_or_else(|e| {
+            warn!("Relayer: failed to check miner lease"; "err" => ?e);
+            false
+        })
+    }
+
     /// Get the public key hash for the mining key.
     fn get_mining_key_pkh(&self) -> Option<Hash160> {
         let Some(ref mining_key) = self.config.miner.mining_key else {
@@ -1439,6 +1499,15 @@ impl RelayerThread {
         }
         debug!("Relayer: successfully stopped tenure; will try to continue.");
 
+        if !self.holds_miner_lease() {
+            debug!("Relayer: another node holds the miner lease; will not continue tenure");
+            return Ok(());
+        }
+        if self.globals.is_mining_paused() {
+            info!("Relayer: mining is paused; will not continue tenure");
+            return Ok(());
+        }
+
         let mining_pkh_opt = self.get_mining_key_pkh();
         let Some(canonical_stacks_tip_election_snapshot) = Self::can_continue_tenure(
             &self.sortdb,
@@ -1573,6 +1642,27 @@ impl RelayerThread {
             warn!("Relayer: not submitting block-commit to bitcoin network due to test directive.");
             return Ok(());
         }
+        if self.globals.is_mining_paused() {
+            info!("Relayer: mining is paused; will not submit block-commit");
+            return Ok(());
+        }
+        if let Some(miner_lease) = self.miner_lease.as_mut() {
+            match miner_lease
+                .try_acquire(get_epoch_time_secs())
+                .map_err(NakamotoNodeError::MinerLeaseError)?
+            {
+                LeaseStatus::Held => {}
+                LeaseStatus::HeldBy {
+                    node_id,
+                    expires_at,
+                } => {
+                    debug!("Relayer: another node holds the miner lease; not submitting block-commit";
+                           "active_node_id" => %node_id,
+                           "expires_at" => expires_at);
+                    return Ok(());
+                }
+            }
+        }
         let mut last_committed = self.make_block_commit(&tip_block_ch, &tip_block_bh)?;
 
         // last chance -- is this still the stacks tip?
@@ -1642,11 +1732,24 @@ impl RelayerThread {
             .bump_naka_submitted_commits(last_committed.burn_tip.block_height, tip_height);
         self.last_committed = Some(last_committed);
 
+        if let Some(miner_lease) = self.miner_lease.as_mut() {
+            match miner_lease.renew(get_epoch_time_secs()) {
+                Ok(LeaseStatus::Held) => {}
+                Ok(LeaseStatus::HeldBy { node_id, .. }) => {
+                    warn!("Relayer: miner lease was taken over while submitting block-commit";
+                          "active_node_id" => %node_id);
+                }
+                Err(e) => {
+                    warn!("Relayer: failed to renew miner lease"; "err" => ?e);
+                }
+            }
+        }
+
         Ok(())
     }
 
     /// Determine what the relayer should do to advance the chain.
-    /// * If this isn't a miner, then it's always nothing.
+    /// * If this isn't a miner, or the node operator has paused mining, then it's always nothing.
     /// * Otherwise, if we haven't done so already, go register a VRF public key
     /// * If the stacks chain tip or burnchain tip has changed, then issue a block-commit
     /// * If the last burn view we started a miner for is not the canonical burn view, then
@@ -1655,6 +1758,9 @@ impl RelayerThread {
         if !self.is_miner {
             return None;
         }
+        if self.globals.is_mining_paused() {
+            return None;
+        }
 
         match self.globals.get_leader_key_registration_state() {
             // do we need a VRF key registration?
@@ -0,0 +1,198 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pacing of the miner's wait for signatures on a block proposal.
+//!
+//! With `miner.signature_wait_mode = "fixed"`, the miner waits `miner.signature_initial_wait_ms`
+//! before it first checks the staging DB and burnchain tip, and then checks every
+//! `miner.signature_poll_interval_ms`.  With `"adaptive"`, it remembers how long the signers took
+//! to sign its recent blocks, waits about that long before the first check, and checks more
+//! often when the signers are fast.  Either way, `miner.signature_max_wait_ms` bounds the whole
+//! wait.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use stacks::config::{MinerConfig, PartialThresholdAction, SignatureWaitMode};
+
+/// Number of recent signing latencies the adaptive mode learns from
+pub const SIGNER_LATENCY_WINDOW: usize = 20;
+/// Fewest latencies the adaptive mode needs before it departs from the fixed timings
+pub const MIN_SIGNER_LATENCY_SAMPLES: usize = 3;
+/// Shortest poll interval the adaptive mode will use
+pub const MIN_ADAPTIVE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long the signers took to sign the miner's recent blocks (shared between the miner threads
+/// of successive tenures)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignerLatencyStats {
+    /// Latencies, oldest first
+    samples: VecDeque<Duration>,
+    /// Most latencies to keep
+    capacity: usize,
+}
+
+impl Default for SignerLatencyStats {
+    fn default() -> Self {
+        Self::new(SIGNER_LATENCY_WINDOW)
+    }
+}
+
+impl SignerLatencyStats {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Record the time between proposing a block and reaching the signing threshold
+    pub fn record(&mut self, latency: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(latency);
+    }
+
+    /// The median recent latency, if there are enough samples to go on
+    pub fn typical(&self) -> Option<Duration> {
+        if self.samples.len() < MIN_SIGNER_LATENCY_SAMPLES {
+            return None;
+        }
+        let mut sorted: Vec<_> = self.samples.iter().copied().collect();
+        sorted.sort();
+        sorted.get(sorted.len() / 2).copied()
+    }
+}
+
+/// The timings for one wait for signatures
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureWaitPlan {
+    /// How long to wait before first checking the staging DB and burnchain tip
+    pub initial_wait: Duration,
+    /// Time between subsequent checks
+    pub poll_interval: Duration,
+    /// Longest time to wait, whatever the number of rejections
+    pub max_wait: Option<Duration>,
+    /// What to do at `max_wait` if some, but not enough, signing weight has signed
+    pub partial_threshold: PartialThresholdAction,
+}
+
+impl SignatureWaitPlan {
+    /// Choose the timings for the next wait from the miner config and, in adaptive mode, the
+    /// recent signing latencies
+    pub fn new(miner_config: &MinerConfig, latency: &SignerLatencyStats) -> Self {
+        let mut plan = Self {
+            initial_wait: miner_config.signature_initial_wait,
+            poll_interval: miner_config.signature_poll_interval,
+            max_wait: miner_config.signature_max_wait,
+            partial_threshold: miner_config.signature_partial_threshold,
+        };
+        if miner_config.signature_wait_mode != SignatureWaitMode::Adaptive {
+            return plan;
+        }
+        let Some(typical) = latency.typical() else {
+            return plan;
+        };
+        // the signers usually take `typical`, so there is little point in checking sooner
+        plan.initial_wait = typical;
+        if let Some(max_wait) = plan.max_wait {
+            plan.initial_wait = plan.initial_wait.min(max_wait);
+        }
+        plan.poll_interval = (typical / 10)
+            .max(MIN_ADAPTIVE_POLL_INTERVAL)
+            .min(miner_config.signature_poll_interval);
+        plan
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adaptive_config() -> MinerConfig {
+        MinerConfig {
+            signature_wait_mode: SignatureWaitMode::Adaptive,
+            ..MinerConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_signer_latency_stats() {
+        let mut stats = SignerLatencyStats::new(4);
+        stats.record(Duration::from_millis(900));
+        stats.record(Duration::from_millis(100));
+        assert_eq!(stats.typical(), None);
+
+        stats.record(Duration::from_millis(300));
+        assert_eq!(stats.typical(), Some(Duration::from_millis(300)));
+
+        // the oldest latencies are forgotten
+        stats.record(Duration::from_millis(200));
+        stats.record(Duration::from_millis(250));
+        stats.record(Duration::from_millis(150));
+        assert_eq!(stats.typical(), Some(Duration::from_millis(250)));
+
+        let mut stats = SignerLatencyStats::new(0);
+        stats.record(Duration::from_millis(100));
+        assert_eq!(stats, SignerLatencyStats::new(0));
+    }
+
+    #[test]
+    fn test_signature_wait_plan() {
+        let mut stats = SignerLatencyStats::default();
+        for ms in [1_500, 2_000, 2_500] {
+            stats.record(Duration::from_millis(ms));
+        }
+
+        // fixed mode ignores the latencies
+        let fixed_config = MinerConfig::default();
+        let plan = SignatureWaitPlan::new(&fixed_config, &stats);
+        assert_eq!(plan.initial_wait, fixed_config.signature_initial_wait);
+        assert_eq!(plan.poll_interval, fixed_config.signature_poll_interval);
+
+        // adaptive mode waits for the typical latency, then polls at a tenth of it
+        let config = adaptive_config();
+        let plan = SignatureWaitPlan::new(&config, &stats);
+        assert_eq!(plan.initial_wait, Duration::from_secs(2));
+        assert_eq!(plan.poll_interval, Duration::from_millis(200));
+
+        // ...but never waits past the max wait, or polls less often than configured
+        let config = MinerConfig {
+            signature_max_wait: Some(Duration::from_secs(1)),
+            signature_poll_interval: Duration::from_millis(100),
+            ..adaptive_config()
+        };
+        let plan = SignatureWaitPlan::new(&config, &stats);
+        assert_eq!(plan.initial_wait, Duration::from_secs(1));
+        assert_eq!(plan.poll_interval, Duration::from_millis(100));
+
+        // very fast signers don't make the miner spin
+        let mut fast_stats = SignerLatencyStats::default();
+        for ms in [10, 20, 30] {
+            fast_stats.record(Duration::from_millis(ms));
+        }
+        let plan = SignatureWaitPlan::new(&adaptive_config(), &fast_stats);
+        assert_eq!(plan.poll_interval, MIN_ADAPTIVE_POLL_INTERVAL);
+
+        // with too little history, adaptive mode uses the fixed timings
+        let plan = SignatureWaitPlan::new(&adaptive_config(), &SignerLatencyStats::default());
+        assert_eq!(plan.initial_wait, fixed_config.signature_initial_wait);
+        assert_eq!(plan.poll_interval, fixed_config.signature_poll_interval);
+    }
+}
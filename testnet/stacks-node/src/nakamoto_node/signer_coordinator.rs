@@ -22,7 +22,7 @@ use std::time::{Duration, Instant};
 
 use libsigner::v0::messages::{MinerSlotID, SignerMessage as SignerMessageV0};
 use libsigner::{BlockProposal, SignerSession, StackerDBSession};
-use stacks::burnchains::Burnchain;
+use stacks::burnchains::{Burnchain, Txid};
 use stacks::chainstate::burn::db::sortdb::SortitionDB;
 use stacks::chainstate::burn::{BlockSnapshot, ConsensusHash};
 use stacks::chainstate::nakamoto::{NakamotoBlock, NakamotoChainState};
@@ -30,6 +30,7 @@ use stacks::chainstate::stacks::boot::{RewardSet, MINERS_NAME};
 use stacks::chainstate::stacks::db::StacksChainState;
 use stacks::chainstate::stacks::Error as ChainstateError;
 use stacks::codec::StacksMessageCodec;
+use stacks::config::{MinerConfig, PartialThresholdAction};
 use stacks::libstackerdb::StackerDBChunkData;
 use stacks::net::stackerdb::StackerDBs;
 use stacks::types::chainstate::{StacksBlockId, StacksPrivateKey};
@@ -37,10 +38,11 @@ use stacks::util::hash::Sha512Trunc256Sum;
 use stacks::util::secp256k1::MessageSignature;
 use stacks::util_lib::boot::boot_code_id;
 
+use super::signature_wait::{SignatureWaitPlan, SignerLatencyStats};
 use super::stackerdb_listener::StackerDBListenerComms;
 use super::Error as NakamotoNodeError;
 use crate::event_dispatcher::StackerDBChannel;
-use crate::nakamoto_node::stackerdb_listener::{StackerDBListener, EVENT_RECEIVER_POLL};
+use crate::nakamoto_node::stackerdb_listener::StackerDBListener;
 use crate::neon::Counters;
 use crate::Config;
 
@@ -71,11 +73,16 @@ pub struct SignerCoordinator {
     burn_tip_at_start: ConsensusHash,
     /// The timeout configuration based on the percentage of rejections
     block_rejection_timeout_steps: BTreeMap<u32, Duration>,
+    /// The miner config, for the signature-wait settings
+    miner_config: MinerConfig,
+    /// How long the signers took to sign the miner's recent blocks
+    signer_latency_stats: Arc<Mutex<SignerLatencyStats>>,
 }
 
 impl SignerCoordinator {
     /// Create a new `SignerCoordinator` instance.
     /// This will spawn a new thread to listen for messages from the signer DB.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         stackerdb_channel: Arc<Mutex<StackerDBChannel>>,
         node_keep_running: Arc<AtomicBool>,
@@ -85,6 +92,7 @@ impl SignerCoordinator {
         message_key: StacksPrivateKey,
         config: &Config,
         burn_tip_at_start: &ConsensusHash,
+        signer_latency_stats: Arc<Mutex<SignerLatencyStats>>,
     ) -> Result<Self, ChainstateError> {
         info!("SignerCoordinator: starting up");
         let keep_running = Arc::new(AtomicBool::new(true));
@@ -97,6 +105,7 @@ impl SignerCoordinator {
             reward_set,
             election_block,
             burnchain,
+            &config.webhooks,
         )?;
         let is_mainnet = config.is_mainnet();
         let rpc_socket = config
@@ -125,6 +134,8 @@ impl SignerCoordinator {
             listener_thread: None,
             burn_tip_at_start: burn_tip_at_start.clone(),
             block_rejection_timeout_steps,
+            miner_config: config.miner.clone(),
+            signer_latency_stats,
         };
 
         // Spawn the signer DB listener thread
@@ -319,15 +330,29 @@ impl SignerCoordinator {
                 )
             })?;
 
+        let wait_plan = {
+            let latency_stats = self
+                .signer_latency_stats
+                .lock()
+                .expect("FATAL: failed to lock signer latency stats");
+            SignatureWaitPlan::new(&self.miner_config, &latency_stats)
+        };
+        debug!("SignerCoordinator: waiting for signatures"; "wait_plan" => ?wait_plan);
+        // the signing weight gathered so far
+        let mut signed_weight: u32 = 0;
+        let mut poll_timeout = wait_plan.initial_wait;
+
         // this is used to track the start of the waiting cycle
         let rejections_timer = Instant::now();
         loop {
+            self.check_signature_max_wait(&wait_plan, rejections_timer, signed_weight)?;
+
             // At every iteration wait for the block_status.
             // Exit when the amount of confirmations/rejections reaches the threshold (or until timeout)
             // Based on the amount of rejections, eventually modify the timeout.
-            let block_status = match self.stackerdb_comms.wait_for_block_status(
+            let wait_result = self.stackerdb_comms.wait_for_block_status(
                 block_signer_sighash,
-                EVENT_RECEIVER_POLL,
+                poll_timeout,
                 |status| {
                     // rejections-based timeout expired?
                     if rejections_timer.elapsed() > *rejections_timeout {
@@ -337,10 +362,16 @@ impl SignerCoordinator {
                     if status.total_reject_weight != rejections {
                         return false;
                     }
+                    // more signatures?
+                    if status.total_weight_signed != signed_weight {
+                        return false;
+                    }
                     // enough signatures?
                     return status.total_weight_signed < self.weight_threshold;
                 },
-            )? {
+            )?;
+            poll_timeout = wait_plan.poll_interval;
+            let block_status = match wait_result {
                 Some(status) => status,
                 None => {
                     // If we just received a timeout, we should check if the burnchain
@@ -388,6 +419,7 @@ impl SignerCoordinator {
                 }
             };
 
+            signed_weight = block_status.total_weight_signed;
             if rejections != block_status.total_reject_weight {
                 rejections = block_status.total_reject_weight;
                 let (rejections_step, new_rejections_timeout) = self
@@ -419,13 +451,21 @@ impl SignerCoordinator {
                     "{}/{} signers vote to reject block",
                     block_status.total_reject_weight, self.total_weight;
                     "block_signer_sighash" => %block_signer_sighash,
+                    "reject_weight_by_reason" => ?block_status.reject_weight_by_reason,
+                    "rejected_tx_indexes" => ?block_status.rejected_txs.keys().collect::<Vec<_>>(),
                 );
                 counters.bump_naka_rejected_blocks();
                 return Err(NakamotoNodeError::SignersRejected);
             } else if block_status.total_weight_signed >= self.weight_threshold {
+                let latency = rejections_timer.elapsed();
                 info!("Received enough signatures, block accepted";
                     "block_signer_sighash" => %block_signer_sighash,
+                    "latency_ms" => latency.as_millis(),
                 );
+                self.signer_latency_stats
+                    .lock()
+                    .expect("FATAL: failed to lock signer latency stats")
+                    .record(latency);
                 return Ok(block_status.gathered_signatures.values().cloned().collect());
             } else if rejections_timer.elapsed() > *rejections_timeout {
                 warn!("Timed out while waiting for responses from signers";
@@ -443,6 +483,34 @@ impl SignerCoordinator {
         }
     }
 
+    /// Give up on the proposal if it has waited longer than `miner.signature_max_wait_ms`,
+    /// unless some signing weight has arrived and the miner is configured to keep waiting
+    fn check_signature_max_wait(
+        &self,
+        wait_plan: &SignatureWaitPlan,
+        wait_start: Instant,
+        signed_weight: u32,
+    ) -> Result<(), NakamotoNodeError> {
+        let Some(max_wait) = wait_plan.max_wait else {
+            return Ok(());
+        };
+        if wait_start.elapsed() <= max_wait {
+            return Ok(());
+        }
+        if signed_weight > 0 && wait_plan.partial_threshold == PartialThresholdAction::KeepWaiting {
+            return Ok(());
+        }
+        warn!("Reached max wait for signatures, abandoning block proposal";
+            "elapsed" => wait_start.elapsed().as_secs(),
+            "max_wait" => max_wait.as_secs(),
+            "signed_weight" => signed_weight,
+            "weight_threshold" => self.weight_threshold,
+        );
+        Err(NakamotoNodeError::SigningCoordinatorFailure(
+            "Timed out while waiting for signatures".into(),
+        ))
+    }
+
     /// Get the timestamp at which at least 70% of the signing power should be
     /// willing to accept a time-based tenure extension.
     pub fn get_tenure_extend_timestamp(&self) -> u64 {
@@ -450,6 +518,25 @@ impl SignerCoordinator {
             .get_tenure_extend_timestamp(self.weight_threshold)
     }
 
+    /// Get the transactions in `block` that signers blamed for rejecting it,
+    /// along with the rejection reason for each.
+    pub fn get_rejected_txs(&self, block: &NakamotoBlock) -> Vec<(Txid, String)> {
+        self.stackerdb_comms
+            .get_rejected_txs(&block.header.signer_signature_hash())
+            .into_iter()
+            .filter_map(|(tx_index, reason)| {
+                let Some(tx) = block.txs.get(tx_index) else {
+                    warn!("SignerCoordinator: signers rejected a tx index that is not in the block";
+                        "tx_index" => tx_index,
+                        "reason" => &reason,
+                    );
+                    return None;
+                };
+                Some((tx.txid(), reason))
+            })
+            .collect()
+    }
+
     /// Check if the tenure needs to change
     fn check_burn_tip_changed(&self, sortdb: &SortitionDB) -> bool {
         let cur_burn_chain_tip = SortitionDB::get_canonical_burn_chain_tip(sortdb.conn())
@@ -22,7 +22,9 @@ use std::sync::{Arc, Condvar, Mutex};
 use std::time::Duration;
 
 use hashbrown::{HashMap, HashSet};
-use libsigner::v0::messages::{BlockAccepted, BlockResponse, SignerMessage as SignerMessageV0};
+use libsigner::v0::messages::{
+    BlockAccepted, BlockResponse, RejectCode, SignerMessage as SignerMessageV0,
+};
 use libsigner::SignerEvent;
 use stacks::burnchains::Burnchain;
 use stacks::chainstate::burn::BlockSnapshot;
@@ -30,16 +32,20 @@ use stacks::chainstate::nakamoto::NakamotoBlockHeader;
 use stacks::chainstate::stacks::boot::{NakamotoSignerEntry, RewardSet, SIGNERS_NAME};
 use stacks::chainstate::stacks::events::StackerDBChunksEvent;
 use stacks::chainstate::stacks::Error as ChainstateError;
+use stacks::config::{WebhookConfig, WebhookEventType};
+use stacks::net::api::postblock_proposal::ValidateRejectCode;
 use stacks::types::chainstate::StacksPublicKey;
 use stacks::types::PublicKey;
 use stacks::util::get_epoch_time_secs;
 use stacks::util::hash::{MerkleHashFunc, Sha512Trunc256Sum};
 use stacks::util::secp256k1::MessageSignature;
+use stacks_common::bitvec::BitVec;
 #[cfg(test)]
 use stacks_common::util::tests::TestFlag;
 
 use super::Error as NakamotoNodeError;
 use crate::event_dispatcher::StackerDBChannel;
+use crate::webhooks::{WebhookDispatcher, WebhookEvent};
 
 #[cfg(test)]
 /// Fault injection flag to prevent the miner from seeing enough signer signatures.
@@ -52,10 +58,18 @@ pub static EVENT_RECEIVER_POLL: Duration = Duration::from_millis(500);
 
 #[derive(Debug, Clone)]
 pub struct BlockStatus {
+    /// The header of the proposed block
+    pub header: NakamotoBlockHeader,
     pub responded_signers: HashSet<StacksPublicKey>,
     pub gathered_signatures: BTreeMap<u32, MessageSignature>,
     pub total_weight_signed: u32,
     pub total_reject_weight: u32,
+    /// Transactions that signers blamed for rejecting the block
+    ///   - key: index of the transaction in the block
+    ///   - value: the first rejection reason received for it
+    pub rejected_txs: BTreeMap<usize, String>,
+    /// Total weight of the signers that rejected the block, by rejection reason code
+    pub reject_weight_by_reason: BTreeMap<String, u32>,
 }
 
 #[derive(Debug, Clone)]
@@ -92,6 +106,10 @@ pub struct StackerDBListener {
     ///  - key: StacksPublicKey
     ///  - value: TimestampInfo
     pub(crate) signer_idle_timestamps: Arc<Mutex<HashMap<StacksPublicKey, TimestampInfo>>>,
+    /// The burn block that elected this tenure's miner
+    burn_tip: BlockSnapshot,
+    /// The webhooks to notify when a block gathers the signature threshold, if any
+    webhooks: Option<Arc<WebhookDispatcher>>,
 }
 
 /// Interface for other threads to retrieve info from the StackerDBListener
@@ -115,6 +133,7 @@ impl StackerDBListener {
         reward_set: &RewardSet,
         burn_tip: &BlockSnapshot,
         burnchain: &Burnchain,
+        webhooks: &[WebhookConfig],
     ) -> Result<Self, ChainstateError> {
         let (receiver, replaced_other) = stackerdb_channel
             .lock()
@@ -168,6 +187,9 @@ impl StackerDBListener {
             signer_entries,
             blocks: Arc::new((Mutex::new(HashMap::new()), Condvar::new())),
             signer_idle_timestamps: Arc::new(Mutex::new(HashMap::new())),
+            burn_tip: burn_tip.clone(),
+            webhooks: WebhookDispatcher::for_events(webhooks, &[WebhookEventType::BlockAccepted])
+                .map(Arc::new),
         })
     }
 
@@ -317,6 +339,7 @@ impl StackerDBListener {
                             continue;
                         }
 
+                        let was_below_threshold = block.total_weight_signed < self.weight_threshold;
                         if !block.gathered_signatures.contains_key(&slot_id) {
                             block.total_weight_signed = block
                                 .total_weight_signed
@@ -340,6 +363,9 @@ impl StackerDBListener {
                         if block.total_weight_signed >= self.weight_threshold {
                             // Signal to anyone waiting on this block that we have enough signatures
                             cvar.notify_all();
+                            if was_below_threshold {
+                                self.notify_block_accepted(block);
+                            }
                         }
 
                         // Update the idle timestamp for this signer
@@ -382,6 +408,24 @@ impl StackerDBListener {
                             .total_reject_weight
                             .checked_add(signer_entry.weight)
                             .expect("FATAL: total weight rejected exceeds u32::MAX");
+                        let reason_weight = block
+                            .reject_weight_by_reason
+                            .entry(rejected_data.reason_code.to_string())
+                            .or_insert(0);
+                        *reason_weight = reason_weight
+                            .checked_add(signer_entry.weight)
+                            .expect("FATAL: total weight rejected exceeds u32::MAX");
+
+                        if rejected_data.reason_code
+                            == RejectCode::ValidationFailed(ValidateRejectCode::BadTransaction)
+                        {
+                            if let Some(tx_index) = parse_rejected_tx_index(&rejected_data.reason) {
+                                block
+                                    .rejected_txs
+                                    .entry(tx_index)
+                                    .or_insert_with(|| rejected_data.reason.clone());
+                            }
+                        }
 
                         info!("StackerDBListener: Signer rejected block";
                             "block_signer_sighash" => %rejected_data.signer_signature_hash,
@@ -428,6 +472,42 @@ impl StackerDBListener {
         }
     }
 
+    /// Tell the `block_accepted` webhooks, if any, that `block` just gathered the signature
+    /// threshold.  Delivery happens on its own thread, so this does not hold up the miner.
+    fn notify_block_accepted(&self, block: &BlockStatus) {
+        let Some(webhooks) = self.webhooks.as_ref() else {
+            return;
+        };
+        let signer_bitvec = u16::try_from(self.signer_entries.len())
+            .map_err(|e| e.to_string())
+            .and_then(BitVec::<4000>::zeros)
+            .and_then(|mut bitvec| {
+                for slot_id in block.gathered_signatures.keys() {
+                    let i = u16::try_from(*slot_id).map_err(|e| e.to_string())?;
+                    bitvec.set(i, true)?;
+                }
+                Ok(bitvec)
+            });
+        let signer_bitvec = match signer_bitvec {
+            Ok(bitvec) => bitvec,
+            Err(e) => {
+                warn!("StackerDBListener: failed to build signer bitvec for block_accepted webhook: {e}";
+                    "block_signer_sighash" => %block.header.signer_signature_hash(),
+                );
+                return;
+            }
+        };
+        let event = WebhookEvent::block_accepted(
+            &self.burn_tip,
+            &block.header,
+            &signer_bitvec,
+            block.total_weight_signed,
+            self.total_weight,
+            self.weight_threshold,
+        );
+        webhooks.dispatch_in_background(event);
+    }
+
     fn update_idle_timestamp(&self, signer_pubkey: StacksPublicKey, timestamp: u64, weight: u32) {
         let mut idle_timestamps = self
             .signer_idle_timestamps
@@ -471,16 +551,35 @@ impl Drop for StackerDBListener {
     }
 }
 
+/// Extract the index of the offending transaction from a `BadTransaction` rejection reason.
+/// The block validation endpoint formats these as `tx {i} skipped: ...`,
+/// `Error processing tx {i}: ...`, `Problematic tx {i}: ...` or `tx {i} is a replay of tx {j}`.
+pub(crate) fn parse_rejected_tx_index(reason: &str) -> Option<usize> {
+    let mut words = reason.split_whitespace();
+    while let Some(word) = words.next() {
+        if word != "tx" {
+            continue;
+        }
+        if let Some(Ok(index)) = words.next().map(|w| w.trim_end_matches(':').parse()) {
+            return Some(index);
+        }
+    }
+    None
+}
+
 impl StackerDBListenerComms {
     /// Insert a block into the block status map with initial values.
     pub fn insert_block(&self, block: &NakamotoBlockHeader) {
         let (lock, _cvar) = &*self.blocks;
         let mut blocks = lock.lock().expect("FATAL: failed to lock block status");
         let block_status = BlockStatus {
+            header: block.clone(),
             responded_signers: HashSet::new(),
             gathered_signatures: BTreeMap::new(),
             total_weight_signed: 0,
             total_reject_weight: 0,
+            rejected_txs: BTreeMap::new(),
+            reject_weight_by_reason: BTreeMap::new(),
         };
         blocks.insert(block.signer_signature_hash(), block_status);
     }
@@ -526,6 +625,20 @@ impl StackerDBListenerComms {
         }
     }
 
+    /// Get the transactions that signers blamed for rejecting the block with
+    /// the given signer signature hash, keyed by their index in the block.
+    pub fn get_rejected_txs(
+        &self,
+        block_signer_sighash: &Sha512Trunc256Sum,
+    ) -> BTreeMap<usize, String> {
+        let (lock, _cvar) = &*self.blocks;
+        let blocks = lock.lock().expect("FATAL: failed to lock block status");
+        blocks
+            .get(block_signer_sighash)
+            .map(|status| status.rejected_txs.clone())
+            .unwrap_or_default()
+    }
+
     /// Get the timestamp at which at least 70% of the signing power should be
     /// willing to accept a time-based tenure extension.
     pub fn get_tenure_extend_timestamp(&self, weight_threshold: u32) -> u64 {
@@ -554,3 +667,27 @@ impl StackerDBListenerComms {
         u64::MAX
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_rejected_tx_index;
+
+    #[test]
+    fn parse_rejected_tx_index_from_reason() {
+        assert_eq!(
+            parse_rejected_tx_index("tx 3 skipped: Transaction skipped"),
+            Some(3)
+        );
+        assert_eq!(
+            parse_rejected_tx_index("Error processing tx 12: Invalid nonce"),
+            Some(12)
+        );
+        assert_eq!(
+            parse_rejected_tx_index("Problematic tx 0: Problematic transaction"),
+            Some(0)
+        );
+        assert_eq!(parse_rejected_tx_index("tx 5 is a replay of tx 2"), Some(5));
+        assert_eq!(parse_rejected_tx_index("Invalid parent block"), None);
+        assert_eq!(parse_rejected_tx_index("tx too large"), None);
+    }
+}
@@ -172,11 +172,13 @@ use stacks::chainstate::burn::operations::{
     BlockstackOperationType, LeaderBlockCommitOp, LeaderKeyRegisterOp,
 };
 use stacks::chainstate::burn::{BlockSnapshot, ConsensusHash};
-use stacks::chainstate::coordinator::{get_next_recipients, OnChainRewardSetProvider};
+use stacks::chainstate::coordinator::get_next_recipients;
 use stacks::chainstate::nakamoto::NakamotoChainState;
 use stacks::chainstate::stacks::address::PoxAddress;
 use stacks::chainstate::stacks::boot::MINERS_NAME;
 use stacks::chainstate::stacks::db::blocks::StagingBlock;
+use stacks::chainstate::stacks::db::receipts::TransactionReceiptDB;
+use stacks::chainstate::stacks::db::state_diffs::StateDiffDB;
 use stacks::chainstate::stacks::db::{StacksChainState, StacksHeaderInfo, MINER_REWARD_MATURITY};
 use stacks::chainstate::stacks::miner::{
     signal_mining_blocked, signal_mining_ready, AssembledAnchorBlock, BlockBuilderSettings,
@@ -188,15 +190,17 @@ use stacks::chainstate::stacks::{
     TransactionAnchorMode, TransactionPayload, TransactionVersion,
 };
 use stacks::config::chain_data::MinerStats;
-use stacks::config::NodeConfig;
+use stacks::config::{ConfigFileReloader, NodeConfig};
 use stacks::core::mempool::MemPoolDB;
 use stacks::core::{EpochList, FIRST_BURNCHAIN_CONSENSUS_HASH, STACKS_EPOCH_3_0_MARKER};
+use stacks::cost_estimates::contract_costs::ContractCostDB;
 use stacks::cost_estimates::metrics::{CostMetric, UnitMetric};
 use stacks::cost_estimates::{CostEstimator, FeeEstimator, UnitEstimator};
 use stacks::monitoring::{increment_stx_blocks_mined_counter, update_active_miners_count_gauge};
 use stacks::net::atlas::{AtlasConfig, AtlasDB};
 use stacks::net::db::{LocalPeer, PeerDB};
 use stacks::net::dns::{DNSClient, DNSResolver};
+use stacks::net::nat::NatTraversal;
 use stacks::net::p2p::PeerNetwork;
 use stacks::net::relay::Relayer;
 use stacks::net::stackerdb::{StackerDBConfig, StackerDBSync, StackerDBs, MINER_SLOT_COUNT};
@@ -360,6 +364,7 @@ pub(crate) fn open_chainstate_with_faults(
     )?;
 
     chainstate.fault_injection.hide_blocks = config.node.fault_injection_hide_blocks;
+    chainstate.injected_reward_sets = config.node.reward_sets.clone();
     Ok(chainstate)
 }
 
@@ -2077,12 +2082,13 @@ impl BlockMinerThread {
         target_epoch_id: StacksEpochId,
     ) -> Option<BlockstackOperationType> {
         // let's figure out the recipient set!
+        let reward_set_provider = chain_state.reward_set_provider();
         let recipients = match get_next_recipients(
             &self.burn_block,
             chain_state,
             burn_db,
             &self.burnchain,
-            &OnChainRewardSetProvider::new(),
+            &reward_set_provider,
             self.config.node.always_use_affirmation_maps,
         ) {
             Ok(x) => x,
@@ -3741,6 +3747,10 @@ impl RelayerThread {
         last_burn_block: BlockSnapshot,
         issue_timestamp_ms: u128,
     ) -> bool {
+        if self.globals.is_mining_paused() {
+            info!("Relayer: mining is paused; will not start a tenure");
+            return false;
+        }
         if !self.miner_thread_try_join() {
             return false;
         }
@@ -3787,6 +3797,10 @@ impl RelayerThread {
             test_debug!("Relayer: not configured to mine microblocks");
             return false;
         }
+        if self.globals.is_mining_paused() {
+            debug!("Relayer: mining is paused; will not mine microblocks");
+            return false;
+        }
 
         let burn_tip = SortitionDB::get_canonical_burn_chain_tip(self.sortdb_ref().conn())
             .expect("FATAL: failed to read current burnchain tip");
@@ -4330,22 +4344,9 @@ pub struct PeerThread {
 impl PeerThread {
     /// set up the mempool DB connection
     pub fn connect_mempool_db(config: &Config) -> MemPoolDB {
-        // create estimators, metric instances for RPC handler
-        let cost_estimator = config
-            .make_cost_estimator()
-            .unwrap_or_else(|| Box::new(UnitEstimator));
-        let metric = config
-            .make_cost_metric()
-            .unwrap_or_else(|| Box::new(UnitMetric));
-
-        MemPoolDB::open(
-            config.is_mainnet(),
-            config.burnchain.chain_id,
-            &config.get_chainstate_path_str(),
-            cost_estimator,
-            metric,
-        )
-        .expect("Database failure opening mempool")
+        config
+            .connect_mempool_db()
+            .expect("Database failure opening mempool")
     }
 
     /// Instantiate the p2p thread.
@@ -4374,22 +4375,17 @@ impl PeerThread {
         let sortdb = SortitionDB::open(&burn_db_path, false, pox_constants)
             .expect("FATAL: could not open sortition DB");
 
-        let chainstate =
+        let mut chainstate =
             open_chainstate_with_faults(&config).expect("FATAL: could not open chainstate DB");
+        chainstate
+            .enable_read_replicas(&sortdb, config.connection_options.rpc_read_replicas)
+            .expect("FATAL: could not open chainstate read replicas");
 
-        let p2p_sock: SocketAddr = config
-            .node
-            .p2p_bind
-            .parse()
-            .unwrap_or_else(|_| panic!("Failed to parse socket: {}", &config.node.p2p_bind));
-        let rpc_sock = config
-            .node
-            .rpc_bind
-            .parse()
-            .unwrap_or_else(|_| panic!("Failed to parse socket: {}", &config.node.rpc_bind));
-
-        net.bind(&p2p_sock, &rpc_sock)
-            .expect("BUG: PeerNetwork could not bind or is already bound");
+        net.bind_interfaces(
+            &config.node.get_p2p_bind_addrs(),
+            &config.node.get_rpc_bind_addrs(),
+        )
+        .expect("BUG: PeerNetwork could not bind or is already bound");
 
         let poll_timeout = config.get_poll_time();
 
@@ -4455,7 +4451,7 @@ impl PeerThread {
 
     /// Run one pass of the p2p/http state machine
     /// Return true if we should continue running passes; false if not
-    #[allow(clippy::borrowed_box)]
+    #[allow(clippy::borrowed_box, clippy::too_many_arguments)]
     pub fn run_one_pass<B: BurnchainHeaderReader>(
         &mut self,
         indexer: &B,
@@ -4464,6 +4460,9 @@ impl PeerThread {
         cost_estimator: &Box<dyn CostEstimator>,
         cost_metric: &Box<dyn CostMetric>,
         fee_estimator: Option<&Box<dyn FeeEstimator>>,
+        contract_cost_db: Option<&ContractCostDB>,
+        receipt_db: Option<&TransactionReceiptDB>,
+        state_diff_db: Option<&StateDiffDB>,
     ) -> bool {
         // initial block download?
         let ibd = self.globals.sync_comms.get_ibd();
@@ -4485,6 +4484,10 @@ impl PeerThread {
             p2p_thread.globals.recv_unconfirmed_txs(chainstate);
         });
 
+        let header_sync_tracker = self.globals.get_header_sync_tracker();
+        let mining_paused = self.globals.get_mining_paused();
+        let config_reloader = ConfigFileReloader::new(&self.config);
+
         // do one pass
         let p2p_res = self.with_chainstate(|p2p_thread, sortdb, chainstate, mempool| {
             // NOTE: handler_args must be created such that it outlives the inner net.run() call and
@@ -4494,9 +4497,16 @@ impl PeerThread {
                 genesis_chainstate_hash: Sha256Sum::from_hex(stx_genesis::GENESIS_CHAINSTATE_HASH)
                     .unwrap(),
                 event_observer: Some(event_dispatcher),
+                stackerdb_replication: event_dispatcher.stackerdb_replication(),
                 cost_estimator: Some(cost_estimator.as_ref()),
                 cost_metric: Some(cost_metric.as_ref()),
                 fee_estimator: fee_estimator.map(|boxed_estimator| boxed_estimator.as_ref()),
+                contract_cost_db,
+                receipt_db,
+                state_diff_db,
+                mining_paused: Some(mining_paused.as_ref()),
+                config_reloader: Some(&config_reloader),
+                header_sync: Some(&header_sync_tracker),
                 ..RPCHandlerArgs::default()
             };
             p2p_thread.with_network(|_, net| {
@@ -4655,6 +4665,72 @@ impl StacksNode {
         .expect("BUG: failed to instantiate mempool")
     }
 
+    /// Re-check the transactions left in the mempool from before the node restarted.  Only
+    /// transactions admitted against a different Stacks tip than the current canonical one are
+    /// re-run through the admission checks, up to `node.mempool_revalidate_max_txs` of them; the
+    /// ones that no longer pass are dropped.  Skipped unless `node.mempool_revalidate_on_start` is
+    /// set.  Failures are logged, not fatal, since the mempool still works without the replay.
+    pub(crate) fn replay_mempool_admissions(
+        config: &Config,
+        sortdb: &SortitionDB,
+        mempool: &mut MemPoolDB,
+    ) {
+        if !config.node.mempool_revalidate_on_start {
+            info!("Mempool: not revalidating persisted transactions on startup");
+            return;
+        }
+        let (consensus_hash, block_hash) =
+            match SortitionDB::get_canonical_stacks_chain_tip_hash(sortdb.conn()) {
+                Ok(tip) => tip,
+                Err(e) => {
+                    warn!(
+                        "Mempool: failed to load the canonical Stacks tip, not revalidating: {e:?}"
+                    );
+                    return;
+                }
+            };
+        let block_limit = match SortitionDB::get_canonical_burn_chain_tip(sortdb.conn())
+            .and_then(|sn| SortitionDB::get_stacks_epoch(sortdb.conn(), sn.block_height))
+        {
+            Ok(Some(epoch)) => epoch.block_limit,
+            Ok(None) => {
+                warn!("Mempool: no epoch defined at the burnchain tip, not revalidating");
+                return;
+            }
+            Err(e) => {
+                warn!("Mempool: failed to load the current epoch, not revalidating: {e:?}");
+                return;
+            }
+        };
+        let mut chainstate = match open_chainstate_with_faults(config) {
+            Ok(chainstate) => chainstate,
+            Err(e) => {
+                warn!("Mempool: failed to open chainstate, not revalidating: {e:?}");
+                return;
+            }
+        };
+        match mempool.replay_admissions(
+            &mut chainstate,
+            sortdb,
+            &consensus_hash,
+            &block_hash,
+            &block_limit,
+            config.node.mempool_revalidate_max_txs,
+        ) {
+            Ok(stats) => {
+                info!("Mempool: revalidated persisted transactions";
+                      "stacks_tip" => %format!("{consensus_hash}/{block_hash}"),
+                      "unchanged" => stats.unchanged,
+                      "revalidated" => stats.revalidated,
+                      "dropped" => stats.dropped,
+                      "deferred" => stats.deferred);
+            }
+            Err(e) => {
+                warn!("Mempool: failed to revalidate persisted transactions: {e:?}");
+            }
+        }
+    }
+
     /// Set up the Peer DB and update any soft state from the config file. This includes:
     ///   * Blacklisted/whitelisted nodes
     ///   * Node keys
@@ -4749,16 +4825,25 @@ impl StacksNode {
             tx.commit().unwrap();
         }
 
-        // update services to indicate we can support mempool sync and stackerdb
+        // update services to indicate we can support mempool sync, stackerdb and tenure
+        // downloads, whether or not neighbors should eagerly push transactions to us, and
+        // whether or not we accept compressed payloads
         {
+            let mut services = (ServiceFlags::RPC as u16)
+                | (ServiceFlags::RELAY as u16)
+                | (ServiceFlags::STACKERDB as u16)
+                | (ServiceFlags::TENURES as u16);
+            if config.node.miner && !config.node.mock_mining {
+                services |= ServiceFlags::MINER as u16;
+            }
+            if config.node.archival {
+                services |= ServiceFlags::ARCHIVAL as u16;
+            }
+            if config.connection_options.p2p_compression {
+                services |= ServiceFlags::COMPRESSION as u16;
+            }
             let tx = peerdb.tx_begin().unwrap();
-            PeerDB::set_local_services(
-                &tx,
-                (ServiceFlags::RPC as u16)
-                    | (ServiceFlags::RELAY as u16)
-                    | (ServiceFlags::STACKERDB as u16),
-            )
-            .unwrap();
+            PeerDB::set_local_services(&tx, services).unwrap();
             tx.commit().unwrap();
         }
 
@@ -4893,6 +4978,27 @@ impl StacksNode {
                 .unwrap();
         }
 
+        // spawn a daemon thread that maps the p2p port on the NAT gateway, if asked.
+        // It will die when the PeerNetwork dies.
+        if p2p_thread.config.connection_options.nat_traversal {
+            let (mut nat_traversal, nat_handle) = NatTraversal::new(
+                p2p_thread.get_network().bound_neighbor_key().port,
+                &p2p_thread.config.connection_options,
+            );
+            p2p_thread
+                .net
+                .as_mut()
+                .expect("BUG: did not replace net")
+                .set_nat_handle(nat_handle);
+            let _jh = thread::Builder::new()
+                .name("nat-traversal".to_string())
+                .spawn(move || {
+                    debug!("NAT traversal thread ID is {:?}", thread::current().id());
+                    nat_traversal.thread_main();
+                })
+                .unwrap();
+        }
+
         // NOTE: these must be instantiated in the thread context, since it can't be safely sent
         // between threads
         let fee_estimator_opt = p2p_thread.config.make_fee_estimator();
@@ -4904,6 +5010,9 @@ impl StacksNode {
             .config
             .make_cost_metric()
             .unwrap_or_else(|| Box::new(UnitMetric));
+        let contract_cost_db_opt = p2p_thread.config.make_contract_cost_db();
+        let receipt_db_opt = p2p_thread.config.make_receipt_db();
+        let state_diff_db_opt = p2p_thread.config.make_state_diff_db();
 
         let indexer = make_bitcoin_indexer(&p2p_thread.config, Some(should_keep_running));
 
@@ -4919,6 +5028,9 @@ impl StacksNode {
                 &cost_estimator,
                 &cost_metric,
                 fee_estimator_opt.as_ref(),
+                contract_cost_db_opt.as_ref(),
+                receipt_db_opt.as_ref(),
+                state_diff_db_opt.as_ref(),
             ) {
                 break;
             }
@@ -4988,7 +5100,8 @@ impl StacksNode {
 
         Self::setup_ast_size_precheck(&config, &mut sortdb);
 
-        let _ = Self::setup_mempool_db(&config);
+        let mut mempool = Self::setup_mempool_db(&config);
+        Self::replay_mempool_admissions(&config, &sortdb, &mut mempool);
 
         let mut p2p_net = Self::setup_peer_network(&config, &atlas_config, burnchain);
 
@@ -958,6 +958,7 @@ impl Node {
                                     &event_data.value,
                                     &contract_id,
                                     epoch_receipt.header.index_block_hash(),
+                                    epoch_receipt.header.consensus_hash,
                                     epoch_receipt.header.stacks_block_height,
                                     receipt.transaction.txid(),
                                     self.chain_tip
@@ -1,4 +1,11 @@
-use stacks::burnchains::PrivateKey;
+use stacks::burnchains::{PrivateKey, Txid};
+use stacks::chainstate::burn::operations::{
+    DelegateStxOp, PreStxOp, StackAggregationCommitOp, StackStxOp, VoteForAggregateKeyOp,
+};
+use stacks::chainstate::stacks::address::PoxAddress;
+use stacks_common::address::b58;
+use stacks_common::types::chainstate::{BurnchainHeaderHash, StacksAddress, StacksPublicKey};
+use stacks_common::types::StacksPublicKeyBuffer;
 use stacks_common::util::hash::hex_bytes;
 use stacks_common::util::secp256k1::{MessageSignature, Secp256k1PrivateKey, Secp256k1PublicKey};
 
@@ -19,6 +26,27 @@ impl BurnchainOpSigner {
         }
     }
 
+    /// Instantiate from a WIF-encoded secret key, as produced by `get_sk_as_wif()` or by
+    /// bitcoind's `dumpprivkey`.
+    pub fn from_wif(wif: &str, is_one_off: bool) -> Result<BurnchainOpSigner, String> {
+        let mut bytes = b58::from_check(wif).map_err(|e| format!("Invalid WIF: {e:?}"))?;
+        if bytes.first() != Some(&0x80) && bytes.first() != Some(&0xef) {
+            return Err("Invalid WIF: unrecognized version byte".into());
+        }
+        bytes.remove(0);
+        let secret_key =
+            Secp256k1PrivateKey::from_slice(&bytes).map_err(|e| format!("Invalid WIF: {e}"))?;
+        Ok(BurnchainOpSigner::new(secret_key, is_one_off))
+    }
+
+    /// Instantiate from either a hex-encoded or WIF-encoded secret key
+    pub fn from_secret_key_str(sk: &str, is_one_off: bool) -> Result<BurnchainOpSigner, String> {
+        if let Ok(secret_key) = Secp256k1PrivateKey::from_hex(sk) {
+            return Ok(BurnchainOpSigner::new(secret_key, is_one_off));
+        }
+        BurnchainOpSigner::from_wif(sk, is_one_off)
+    }
+
     pub fn get_sk_as_wif(&self) -> String {
         let hex_encoded = self.secret_key.to_hex();
         let mut as_bytes = hex_bytes(&hex_encoded).unwrap();
@@ -59,6 +87,122 @@ impl BurnchainOpSigner {
     pub fn dispose(&mut self) {
         self.is_disposed = true;
     }
+
+    /// Get the single-sig Stacks address that corresponds to this signer's key.
+    /// This is the address to which a `PreStxOp` signed by this key should pay, and it is the
+    /// `sender` of any subsequent operation which spends that `PreStxOp`'s output.
+    pub fn get_stacks_address(&self, mainnet: bool) -> StacksAddress {
+        StacksAddress::p2pkh(mainnet, &StacksPublicKey::from_private(&self.secret_key))
+    }
+}
+
+/// Construct a `PreStxOp` which pays to `output`.
+/// The burnchain-assigned fields (txid, vtxindex, block height, and burn header hash) are
+/// zeroed; they are filled in when the operation is mined.
+pub fn make_pre_stx_op(output: StacksAddress) -> PreStxOp {
+    PreStxOp {
+        output,
+        txid: Txid([0u8; 32]),
+        vtxindex: 0,
+        block_height: 0,
+        burn_header_hash: BurnchainHeaderHash([0u8; 32]),
+    }
+}
+
+/// Construct a `StackStxOp` for `sender`, which must be the output address of a `PreStxOp`
+/// whose UTXO the operation will spend.
+pub fn make_stack_stx_op(
+    sender: StacksAddress,
+    reward_addr: PoxAddress,
+    stacked_ustx: u128,
+    num_cycles: u8,
+    signer_key: Option<StacksPublicKeyBuffer>,
+    max_amount: Option<u128>,
+    auth_id: Option<u32>,
+) -> StackStxOp {
+    StackStxOp {
+        sender,
+        reward_addr,
+        stacked_ustx,
+        num_cycles,
+        signer_key,
+        max_amount,
+        auth_id,
+        txid: Txid([0u8; 32]),
+        vtxindex: 0,
+        block_height: 0,
+        burn_header_hash: BurnchainHeaderHash([0u8; 32]),
+    }
+}
+
+/// Construct a `DelegateStxOp` for `sender`, which must be the output address of a `PreStxOp`
+/// whose UTXO the operation will spend.
+pub fn make_delegate_stx_op(
+    sender: StacksAddress,
+    delegate_to: StacksAddress,
+    delegated_ustx: u128,
+    until_burn_height: Option<u64>,
+) -> DelegateStxOp {
+    DelegateStxOp {
+        sender,
+        delegate_to,
+        reward_addr: None,
+        delegated_ustx,
+        until_burn_height,
+        txid: Txid([0u8; 32]),
+        vtxindex: 0,
+        block_height: 0,
+        burn_header_hash: BurnchainHeaderHash([0u8; 32]),
+    }
+}
+
+/// Construct a `VoteForAggregateKeyOp` for `sender`, which must be the output address of a
+/// `PreStxOp` whose UTXO the operation will spend.
+pub fn make_vote_for_aggregate_key_op(
+    sender: StacksAddress,
+    aggregate_key: StacksPublicKeyBuffer,
+    round: u32,
+    reward_cycle: u64,
+    signer_index: u16,
+    signer_key: StacksPublicKeyBuffer,
+) -> VoteForAggregateKeyOp {
+    VoteForAggregateKeyOp {
+        sender,
+        aggregate_key,
+        round,
+        reward_cycle,
+        signer_index,
+        signer_key,
+        txid: Txid([0u8; 32]),
+        vtxindex: 0,
+        block_height: 0,
+        burn_header_hash: BurnchainHeaderHash([0u8; 32]),
+    }
+}
+
+/// Construct a `StackAggregationCommitOp` for `sender`, which must be the output address of a
+/// `PreStxOp` whose UTXO the operation will spend.  `signer_key` must have authorized the
+/// commit via `set-signer-key-authorization` before the operation is mined.
+pub fn make_stack_aggregation_commit_op(
+    sender: StacksAddress,
+    reward_addr: PoxAddress,
+    reward_cycle: u64,
+    signer_key: StacksPublicKeyBuffer,
+    max_amount: u128,
+    auth_id: u32,
+) -> StackAggregationCommitOp {
+    StackAggregationCommitOp {
+        sender,
+        reward_addr,
+        reward_cycle,
+        signer_key,
+        max_amount,
+        auth_id,
+        txid: Txid([0u8; 32]),
+        vtxindex: 0,
+        block_height: 0,
+        burn_header_hash: BurnchainHeaderHash([0u8; 32]),
+    }
 }
 
 #[cfg(test)]
@@ -79,4 +223,20 @@ mod test {
             assert_eq!(expected_wif, &op_signer.get_sk_as_wif());
         }
     }
+
+    #[test]
+    fn test_from_wif() {
+        let secret_key = "0C28FCA386C7A227600B2FE50B7CAE11EC86D3BF1FBE471BE89827E19D72AA1D";
+        let wif = "5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ";
+        let op_signer = BurnchainOpSigner::from_wif(wif, false).unwrap();
+        assert_eq!(op_signer.get_sk_as_hex(), secret_key.to_lowercase());
+        assert_eq!(op_signer.get_sk_as_wif(), wif);
+
+        let op_signer = BurnchainOpSigner::from_secret_key_str(wif, false).unwrap();
+        assert_eq!(op_signer.get_sk_as_hex(), secret_key.to_lowercase());
+        let op_signer = BurnchainOpSigner::from_secret_key_str(secret_key, false).unwrap();
+        assert_eq!(op_signer.get_sk_as_wif(), wif);
+
+        assert!(BurnchainOpSigner::from_wif("not-a-wif", false).is_err());
+    }
 }
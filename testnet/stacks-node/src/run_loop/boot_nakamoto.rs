@@ -25,12 +25,14 @@ use stacks::chainstate::coordinator::comm::CoordinatorChannels;
 use stacks::net::p2p::PeerNetwork;
 use stacks_common::types::StacksEpochId;
 
+use crate::backups::BackupThread;
 use crate::event_dispatcher::EventDispatcher;
 use crate::globals::NeonGlobals;
 use crate::neon::Counters;
 use crate::neon_node::LeaderKeyRegistrationState;
 use crate::run_loop::nakamoto::RunLoop as NakaRunLoop;
 use crate::run_loop::neon::RunLoop as NeonRunLoop;
+use crate::webhooks::WebhookThread;
 use crate::Config;
 
 /// Data which should persist through transition from Neon => Nakamoto run loop
@@ -135,10 +137,19 @@ impl BootRunLoop {
     /// The main entry point for the run loop. This starts either a 2.x-neon or 3.x-nakamoto
     /// node depending on the current burnchain height.
     pub fn start(&mut self, burnchain_opt: Option<Burnchain>, mine_start: u64) {
+        // webhooks and backups outlive the epoch-2/3 transition, so they're run from here
+        let webhook_thread = WebhookThread::spawn(&self.config);
+        let backup_thread = BackupThread::spawn(&self.config);
         match self.active_loop {
             InnerLoops::Epoch2(_) => self.start_from_neon(burnchain_opt, mine_start),
             InnerLoops::Epoch3(_) => self.start_from_naka(burnchain_opt, mine_start),
         }
+        if let Some(webhook_thread) = webhook_thread {
+            webhook_thread.stop();
+        }
+        if let Some(backup_thread) = backup_thread {
+            backup_thread.stop();
+        }
     }
 
     fn start_from_naka(&mut self, burnchain_opt: Option<Burnchain>, mine_start: u64) {
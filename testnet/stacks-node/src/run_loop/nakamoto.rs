@@ -20,6 +20,7 @@ use std::thread::JoinHandle;
 use std::{cmp, thread};
 
 use stacks::burnchains::bitcoin::address::{BitcoinAddress, LegacyBitcoinAddressType};
+use stacks::burnchains::bitcoin::spv::HeaderSyncTracker;
 use stacks::burnchains::{Burnchain, Error as burnchain_error};
 use stacks::chainstate::burn::db::sortdb::SortitionDB;
 use stacks::chainstate::burn::BlockSnapshot;
@@ -95,6 +96,8 @@ impl RunLoop {
         for observer in config.events_observers.iter() {
             event_dispatcher.register_observer(observer, config.get_working_dir());
         }
+        event_dispatcher
+            .register_stackerdb_standbys(&config.stackerdb_standbys, config.get_working_dir());
 
         Self {
             config,
@@ -164,6 +167,10 @@ impl RunLoop {
     /// Determine if we're the miner.
     /// If there's a network error, then assume that we're not a miner.
     fn check_is_miner(&mut self, burnchain: &mut BitcoinRegtestController) -> bool {
+        if self.config.node.miner && self.config.burnchain.is_mocknet() {
+            info!("Mocknet burnchain operations need no UTXOs - will run as a Miner node");
+            return true;
+        }
         if self.config.node.miner {
             let keychain = Keychain::default(self.config.node.seed.clone());
             let mut op_signer = keychain.generate_op_signer();
@@ -191,13 +198,22 @@ impl RunLoop {
                     .expect("FATAL: failed to construct segwit p2wpkh address"),
                 ));
             }
+            if let Some(funding_address) = self.config.burnchain.get_wallet_funding_address() {
+                // the miner's wallet funds its transactions
+                btc_addrs = vec![(StacksEpochId::Epoch21, funding_address)];
+            }
 
             // retry UTXO check a few times, in case bitcoind is still starting up
             for _ in 0..Self::UTXO_RETRY_COUNT {
                 for (epoch_id, btc_addr) in &btc_addrs {
                     info!("Miner node: checking UTXOs at address: {btc_addr}");
-                    let utxos =
-                        burnchain.get_utxos(*epoch_id, &op_signer.get_public_key(), 1, None, 0);
+                    let utxos = burnchain.get_miner_utxos(
+                        *epoch_id,
+                        &op_signer.get_public_key(),
+                        1,
+                        None,
+                        0,
+                    );
                     if utxos.is_none() {
                         warn!("UTXOs not found for {btc_addr}. If this is unexpected, please ensure that your bitcoind instance is indexing transactions for the address {btc_addr} (importaddress)");
                     } else {
@@ -324,6 +340,16 @@ impl RunLoop {
                     require_affirmed_anchor_blocks: moved_config
                         .node
                         .require_affirmed_anchor_blocks,
+                    reward_sets: moved_config.node.reward_sets.clone(),
+                    contract_cost_db_path: moved_config.get_contract_cost_db_path(),
+                    contract_cost_retention: moved_config.node.contract_cost_retention,
+                    receipt_db_path: moved_config.get_receipt_db_path(),
+                    receipt_retention: moved_config.node.receipt_retention,
+                    state_diff_db_path: moved_config.get_state_diff_db_path(),
+                    state_diff_retention: moved_config.node.state_diff_retention,
+                    prune_horizon: moved_config.get_prune_horizon(),
+                    max_reorg_depth: moved_config.node.max_reorg_depth,
+                    chain_quality: moved_config.get_chain_quality_thresholds(),
                 };
                 ChainsCoordinator::run(
                     coord_config,
@@ -416,11 +442,14 @@ impl RunLoop {
         // setup the termination handler, allow it to error if a prior runloop already set it
         neon::RunLoop::setup_termination_handler(self.should_keep_running.clone(), true);
 
+        let header_sync_tracker =
+            HeaderSyncTracker::new(Some(Arc::new(self.event_dispatcher.clone())));
         let burnchain_result = neon::RunLoop::instantiate_burnchain_state(
             &self.config,
             self.should_keep_running.clone(),
             burnchain_opt,
             coordinator_senders.clone(),
+            header_sync_tracker.clone(),
         );
 
         let mut burnchain = match burnchain_result {
@@ -456,6 +485,7 @@ impl RunLoop {
             self.should_keep_running.clone(),
             mine_start,
             LeaderKeyRegistrationState::default(),
+            header_sync_tracker,
         );
         self.set_globals(globals.clone());
 
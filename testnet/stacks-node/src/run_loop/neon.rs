@@ -8,6 +8,7 @@ use std::{cmp, thread};
 
 use libc;
 use stacks::burnchains::bitcoin::address::{BitcoinAddress, LegacyBitcoinAddressType};
+use stacks::burnchains::bitcoin::spv::HeaderSyncTracker;
 use stacks::burnchains::{Burnchain, Error as burnchain_error};
 use stacks::chainstate::burn::db::sortdb::SortitionDB;
 use stacks::chainstate::burn::BlockSnapshot;
@@ -112,6 +113,8 @@ pub struct Counters {
     pub naka_submitted_commit_last_burn_height: RunLoopCounter,
     pub naka_mined_blocks: RunLoopCounter,
     pub naka_rejected_blocks: RunLoopCounter,
+    /// the number of blocks reproposed without the transactions that signers rejected
+    pub naka_reproposed_blocks: RunLoopCounter,
     pub naka_proposed_blocks: RunLoopCounter,
     pub naka_mined_tenures: RunLoopCounter,
     pub naka_signer_pushed_blocks: RunLoopCounter,
@@ -202,6 +205,10 @@ impl Counters {
         Counters::inc(&self.naka_rejected_blocks);
     }
 
+    pub fn bump_naka_reproposed_blocks(&self) {
+        Counters::inc(&self.naka_reproposed_blocks);
+    }
+
     pub fn bump_naka_signer_pushed_blocks(&self) {
         Counters::inc(&self.naka_signer_pushed_blocks);
     }
@@ -278,6 +285,8 @@ impl RunLoop {
         for observer in config.events_observers.iter() {
             event_dispatcher.register_observer(observer, config.get_working_dir());
         }
+        event_dispatcher
+            .register_stackerdb_standbys(&config.stackerdb_standbys, config.get_working_dir());
 
         Self {
             config,
@@ -406,6 +415,10 @@ impl RunLoop {
     /// Determine if we're the miner.
     /// If there's a network error, then assume that we're not a miner.
     fn check_is_miner(&mut self, burnchain: &mut BitcoinRegtestController) -> bool {
+        if self.config.node.miner && self.config.burnchain.is_mocknet() {
+            info!("Mocknet burnchain operations need no UTXOs - will run as a Miner node");
+            return true;
+        }
         if self.config.node.miner {
             let keychain = Keychain::default(self.config.node.seed.clone());
             let mut op_signer = keychain.generate_op_signer();
@@ -433,13 +446,22 @@ impl RunLoop {
                     .expect("FATAL: failed to construct segwit p2wpkh address"),
                 ));
             }
+            if let Some(funding_address) = self.config.burnchain.get_wallet_funding_address() {
+                // the miner's wallet funds its transactions
+                btc_addrs = vec![(StacksEpochId::Epoch21, funding_address)];
+            }
 
             // retry UTXO check a few times, in case bitcoind is still starting up
             for _ in 0..Self::UTXO_RETRY_COUNT {
                 for (epoch_id, btc_addr) in &btc_addrs {
                     info!("Miner node: checking UTXOs at address: {btc_addr}");
-                    let utxos =
-                        burnchain.get_utxos(*epoch_id, &op_signer.get_public_key(), 1, None, 0);
+                    let utxos = burnchain.get_miner_utxos(
+                        *epoch_id,
+                        &op_signer.get_public_key(),
+                        1,
+                        None,
+                        0,
+                    );
                     if utxos.is_none() {
                         warn!("UTXOs not found for {btc_addr}. If this is unexpected, please ensure that your bitcoind instance is indexing transactions for the address {btc_addr} (importaddress)");
                     } else {
@@ -468,6 +490,7 @@ impl RunLoop {
         should_keep_running: Arc<AtomicBool>,
         burnchain_opt: Option<Burnchain>,
         coordinator_senders: CoordinatorChannels,
+        header_sync_tracker: HeaderSyncTracker,
     ) -> Result<BitcoinRegtestController, burnchain_error> {
         // Initialize and start the burnchain.
         let mut burnchain_controller = BitcoinRegtestController::with_burnchain(
@@ -476,6 +499,7 @@ impl RunLoop {
             burnchain_opt,
             Some(should_keep_running.clone()),
         );
+        burnchain_controller.set_header_sync_tracker(header_sync_tracker);
 
         let burnchain = burnchain_controller.get_burnchain();
         let epochs = burnchain_controller.get_stacks_epochs();
@@ -657,6 +681,16 @@ impl RunLoop {
                     require_affirmed_anchor_blocks: moved_config
                         .node
                         .require_affirmed_anchor_blocks,
+                    reward_sets: moved_config.node.reward_sets.clone(),
+                    contract_cost_db_path: moved_config.get_contract_cost_db_path(),
+                    contract_cost_retention: moved_config.node.contract_cost_retention,
+                    receipt_db_path: moved_config.get_receipt_db_path(),
+                    receipt_retention: moved_config.node.receipt_retention,
+                    state_diff_db_path: moved_config.get_state_diff_db_path(),
+                    state_diff_retention: moved_config.node.state_diff_retention,
+                    prune_horizon: moved_config.get_prune_horizon(),
+                    max_reorg_depth: moved_config.node.max_reorg_depth,
+                    chain_quality: moved_config.get_chain_quality_thresholds(),
                 };
                 ChainsCoordinator::run(
                     coord_config,
@@ -1078,11 +1112,14 @@ impl RunLoop {
 
         Self::setup_termination_handler(self.should_keep_running.clone(), false);
 
+        let header_sync_tracker =
+            HeaderSyncTracker::new(Some(Arc::new(self.event_dispatcher.clone())));
         let burnchain_result = Self::instantiate_burnchain_state(
             &self.config,
             self.should_keep_running.clone(),
             burnchain_opt,
             coordinator_senders.clone(),
+            header_sync_tracker.clone(),
         );
 
         let mut burnchain = match burnchain_result {
@@ -1118,6 +1155,7 @@ impl RunLoop {
             self.should_keep_running.clone(),
             mine_start,
             LeaderKeyRegistrationState::default(),
+            header_sync_tracker,
         );
         self.set_globals(globals.clone());
 
@@ -1171,6 +1209,15 @@ impl RunLoop {
             tx.commit()
                 .expect("FATAL: failed to commit burnchain DB tx");
         }
+        {
+            let tx = burnchain_db
+                .tx_begin()
+                .expect("FATAL: failed to begin burnchain DB tx");
+            tx.sync_config_anchor_block_overrides(&self.config.burnchain.anchor_block_overrides)
+                .expect("FATAL: failed to apply configured PoX anchor block overrides");
+            tx.commit()
+                .expect("FATAL: failed to commit burnchain DB tx");
+        }
         let burnchain_db_tip = burnchain_db
             .get_canonical_chain_tip()
             .expect("FATAL: failed to query burnchain DB");
@@ -0,0 +1,610 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! StackerDB standby replication (`[[stackerdb_standby]]` in the config file).
+//!
+//! Every StackerDB chunk this node accepts for a configured contract is forwarded to each standby
+//! node's authenticated `POST /v3/admin/stackerdb/:principal/:contract_name/chunks` endpoint, so a
+//! hot-standby signer or miner has a current view of StackerDB as soon as it takes over, instead
+//! of waiting for p2p sync to catch it up.
+//!
+//! Replication is write-ahead.  A chunk is first stored in the outbound queue in
+//! `stackerdb_replication.sqlite` in the node's working directory, and a chunk written over RPC
+//! is queued before it is committed to StackerDB; if it cannot be queued within
+//! `DB_BUSY_TIMEOUT`, the write is refused.  Each standby has its own thread, which
+//! delivers the standby's queued chunks in order and removes each one from the queue once the
+//! standby has answered for it.  A standby that cannot be reached is retried with backoff until
+//! it comes back, so a slow or unreachable standby never holds up the relayer or the other
+//! standbys, and queued chunks survive a restart of this node.  The queue only keeps the newest
+//! version of each slot for each standby.
+//!
+//! Chunks are signed by their writers, so a standby validates them like any other write.  A
+//! standby declines a chunk it already has, which happens when a chunk written over RPC is queued
+//! again once it is relayed.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, sleep};
+use std::time::Duration;
+
+use clarity::vm::types::QualifiedContractIdentifier;
+use rusqlite::{params, Connection};
+use stacks::config::StackerDBStandbyConfig;
+use stacks::libstackerdb::{StackerDBChunkAckData, StackerDBChunkData};
+use stacks::net::httpcore::{send_http_request, StacksHttpRequest};
+use stacks::net::stackerdb::StackerDBReplicationQueue;
+use stacks::util_lib::db::{sql_pragma, Error as db_error};
+use stacks_common::types::net::PeerHost;
+use url::Url;
+
+/// Name of the outbound queue's database in the node's working directory
+const REPLICATION_DB_NAME: &str = "stackerdb_replication.sqlite";
+
+/// How long a write to the outbound queue may wait for another connection to it before failing
+const DB_BUSY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long to wait before the first retry of a chunk that could not be delivered
+const RETRY_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Longest wait between retries
+const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A standby to replicate chunks to
+#[derive(Clone)]
+struct StandbyQueue {
+    url: String,
+    contracts: HashSet<QualifiedContractIdentifier>,
+    /// Wakes up the standby's thread once chunks are queued for it
+    wakeup: SyncSender<()>,
+}
+
+/// Forwards accepted StackerDB chunks to the configured standby nodes.  Each standby's thread
+/// exits once every clone of this struct has been dropped.
+#[derive(Clone)]
+pub struct StackerDBReplicator {
+    /// Connection to the outbound queue's database, shared by every clone
+    queue_db: Arc<Mutex<Connection>>,
+    standbys: Vec<StandbyQueue>,
+}
+
+impl StackerDBReplicator {
+    /// Start a replication thread for each standby, if any standbys are configured.  The outbound
+    /// queue is kept in `working_dir`, and each thread first delivers whatever was left in it by
+    /// the last run.
+    pub fn spawn(standbys: &[StackerDBStandbyConfig], working_dir: PathBuf) -> Option<Self> {
+        if standbys.is_empty() {
+            return None;
+        }
+        let mut db_path = working_dir;
+        db_path.push(REPLICATION_DB_NAME);
+        let queue_db =
+            Self::init_db(&db_path).expect("FATAL: failed to open the StackerDB replication queue");
+
+        let standbys = standbys
+            .iter()
+            .map(|standby| {
+                let (wakeup, wakeup_rx) = sync_channel(1);
+                let thread_standby = standby.clone();
+                let thread_db_path = db_path.clone();
+                thread::Builder::new()
+                    .name(format!("stackerdb-replication:{}", &standby.url))
+                    .spawn(move || Self::run_standby(&thread_standby, &thread_db_path, wakeup_rx))
+                    .expect("FATAL: failed to start StackerDB replication thread");
+                StandbyQueue {
+                    url: standby.url.clone(),
+                    contracts: standby.contracts.iter().cloned().collect(),
+                    wakeup,
+                }
+            })
+            .collect();
+        Some(Self {
+            queue_db: Arc::new(Mutex::new(queue_db)),
+            standbys,
+        })
+    }
+
+    fn open_db(db_path: &Path) -> Result<Connection, db_error> {
+        let conn = Connection::open(db_path)?;
+        conn.busy_timeout(DB_BUSY_TIMEOUT)?;
+        Ok(conn)
+    }
+
+    fn init_db(db_path: &Path) -> Result<Connection, db_error> {
+        let conn = Self::open_db(db_path)?;
+        // the standby threads read the queue while chunks are being added to it
+        sql_pragma(&conn, "journal_mode", &"WAL")?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS pending_chunks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                standby TEXT NOT NULL,
+                contract_id TEXT NOT NULL,
+                slot_id INTEGER NOT NULL,
+                slot_version INTEGER NOT NULL,
+                chunk TEXT NOT NULL,
+                UNIQUE(standby, contract_id, slot_id)
+            )",
+            [],
+        )?;
+        Ok(conn)
+    }
+
+    /// Queue `chunks` for each of `standbys` in one transaction.  A chunk replaces an older
+    /// version of its slot that is still queued for a standby.
+    fn insert_chunks(
+        conn: &mut Connection,
+        standbys: &[&StandbyQueue],
+        contract_id: &QualifiedContractIdentifier,
+        chunks: &[StackerDBChunkData],
+    ) -> Result<(), db_error> {
+        let tx = conn.transaction()?;
+        for standby in standbys.iter() {
+            for chunk in chunks.iter() {
+                let chunk_text =
+                    serde_json::to_string(chunk).map_err(db_error::SerializationError)?;
+                tx.execute(
+                    "INSERT INTO pending_chunks (standby, contract_id, slot_id, slot_version, chunk)
+                     VALUES (?1, ?2, ?3, ?4, ?5)
+                     ON CONFLICT(standby, contract_id, slot_id) DO UPDATE
+                     SET slot_version = excluded.slot_version, chunk = excluded.chunk
+                     WHERE excluded.slot_version > pending_chunks.slot_version",
+                    params![
+                        &standby.url,
+                        contract_id.to_string(),
+                        chunk.slot_id,
+                        chunk.slot_version,
+                        chunk_text
+                    ],
+                )?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Get the chunks queued for the standby at `url`, in the order they were queued
+    fn get_pending_chunks(
+        conn: &Connection,
+        url: &str,
+    ) -> Result<Vec<(i64, QualifiedContractIdentifier, StackerDBChunkData)>, db_error> {
+        let mut stmt = conn.prepare(
+            "SELECT id, contract_id, chunk FROM pending_chunks WHERE standby = ?1 ORDER BY id",
+        )?;
+        let chunk_iter = stmt.query_and_then(
+            params![url],
+            |row| -> Result<(i64, QualifiedContractIdentifier, StackerDBChunkData), db_error> {
+                let id: i64 = row.get(0)?;
+                let contract_id_text: String = row.get(1)?;
+                let contract_id = QualifiedContractIdentifier::parse(&contract_id_text)
+                    .map_err(|_| db_error::ParseError)?;
+                let chunk_text: String = row.get(2)?;
+                let chunk: StackerDBChunkData =
+                    serde_json::from_str(&chunk_text).map_err(db_error::SerializationError)?;
+                Ok((id, contract_id, chunk))
+            },
+        )?;
+        chunk_iter.collect()
+    }
+
+    /// Remove a delivered chunk from the queue, unless a newer version of its slot replaced it in
+    /// the meantime
+    fn delete_chunk(conn: &Connection, id: i64, slot_version: u32) -> Result<(), db_error> {
+        conn.execute(
+            "DELETE FROM pending_chunks WHERE id = ?1 AND slot_version = ?2",
+            params![id, slot_version],
+        )?;
+        Ok(())
+    }
+
+    /// Durably queue newly-accepted chunks for each standby that replicates `contract_id`, and
+    /// wake up those standbys' threads.  Fails if the queue's database stays busy for longer than
+    /// `DB_BUSY_TIMEOUT`, or cannot be written.
+    pub fn replicate(
+        &self,
+        contract_id: &QualifiedContractIdentifier,
+        chunks: &[StackerDBChunkData],
+    ) -> Result<(), db_error> {
+        let standbys: Vec<_> = self
+            .standbys
+            .iter()
+            .filter(|standby| standby.contracts.contains(contract_id))
+            .collect();
+        if standbys.is_empty() || chunks.is_empty() {
+            return Ok(());
+        }
+
+        {
+            let mut queue_db = self
+                .queue_db
+                .lock()
+                .expect("FATAL: StackerDB replication queue lock poisoned");
+            Self::insert_chunks(&mut queue_db, &standbys, contract_id, chunks)?;
+        }
+
+        for standby in standbys.into_iter() {
+            // if the channel is full, the thread has a wakeup pending already
+            let _ = standby.wakeup.try_send(());
+        }
+        Ok(())
+    }
+
+    /// Deliver the standby's queued chunks in order, until the queue is empty or the standby
+    /// cannot take any more.
+    /// Returns true if every queued chunk was delivered.
+    fn send_pending_chunks(standby: &StackerDBStandbyConfig, conn: &Connection) -> bool {
+        let pending_chunks = match Self::get_pending_chunks(conn, &standby.url) {
+            Ok(chunks) => chunks,
+            Err(e) => {
+                warn!("StackerDB replication: failed to load queued chunks";
+                      "standby" => &standby.url,
+                      "error" => ?e);
+                return false;
+            }
+        };
+        for (id, contract_id, chunk) in pending_chunks.into_iter() {
+            match Self::send_chunk(standby, &contract_id, &chunk) {
+                Ok(ack) => {
+                    // a standby that already has this chunk (e.g. from p2p sync) declines it
+                    debug!("StackerDB replication: delivered chunk";
+                           "standby" => &standby.url,
+                           "contract_id" => %contract_id,
+                           "slot_id" => chunk.slot_id,
+                           "slot_version" => chunk.slot_version,
+                           "accepted" => ack.accepted,
+                           "reason" => ?ack.reason);
+                    if let Err(e) = Self::delete_chunk(conn, id, chunk.slot_version) {
+                        warn!("StackerDB replication: failed to dequeue delivered chunk";
+                              "standby" => &standby.url,
+                              "error" => ?e);
+                        return false;
+                    }
+                }
+                Err(e) => {
+                    warn!("StackerDB replication: failed to deliver chunk, will retry";
+                          "standby" => &standby.url,
+                          "contract_id" => %contract_id,
+                          "slot_id" => chunk.slot_id,
+                          "slot_version" => chunk.slot_version,
+                          "error" => %e);
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Deliver queued chunks to `standby` until the replicator is dropped.  Chunks that are still
+    /// queued then are delivered on the next run.
+    fn run_standby(standby: &StackerDBStandbyConfig, db_path: &Path, wakeup_rx: Receiver<()>) {
+        let conn =
+            Self::open_db(db_path).expect("FATAL: failed to open the StackerDB replication queue");
+        let mut backoff = RETRY_INTERVAL;
+        loop {
+            if Self::send_pending_chunks(standby, &conn) {
+                backoff = RETRY_INTERVAL;
+                if wakeup_rx.recv().is_err() {
+                    break;
+                }
+            } else {
+                sleep(backoff);
+                backoff = std::cmp::min(backoff.saturating_mul(2), MAX_RETRY_INTERVAL);
+                if let Err(TryRecvError::Disconnected) = wakeup_rx.try_recv() {
+                    break;
+                }
+            }
+        }
+        debug!("StackerDB replication: queue closed"; "standby" => &standby.url);
+    }
+
+    /// Make a single attempt to store `chunk` on `standby`
+    pub fn send_chunk(
+        standby: &StackerDBStandbyConfig,
+        contract_id: &QualifiedContractIdentifier,
+        chunk: &StackerDBChunkData,
+    ) -> Result<StackerDBChunkAckData, String> {
+        let url =
+            Url::parse(&standby.url).map_err(|e| format!("invalid URL {}: {e}", &standby.url))?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| format!("invalid URL {}: missing host", &standby.url))?;
+        let port = url.port_or_known_default().unwrap_or(80);
+        let peerhost: PeerHost = format!("{host}:{port}")
+            .parse()
+            .unwrap_or(PeerHost::DNS(host.to_string(), port));
+
+        let mut request = StacksHttpRequest::new_post_stackerdb_replica(
+            peerhost,
+            contract_id,
+            chunk,
+            &standby.auth_token,
+        );
+        request.add_header("Connection".into(), "close".into());
+
+        let response = send_http_request(
+            host,
+            port,
+            request,
+            Duration::from_millis(standby.timeout_ms),
+        )
+        .map_err(|e| e.to_string())?;
+        response
+            .decode_stackerdb_chunk_ack()
+            .map_err(|e| format!("{} did not store the chunk: {e}", &standby.url))
+    }
+}
+
+impl StackerDBReplicationQueue for StackerDBReplicator {
+    /// Queue a chunk that was written over RPC, before the write is committed
+    fn queue_stackerdb_chunk(
+        &self,
+        contract_id: &QualifiedContractIdentifier,
+        chunk: &StackerDBChunkData,
+    ) -> Result<(), String> {
+        self.replicate(contract_id, std::slice::from_ref(chunk))
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::sync::mpsc::channel;
+
+    use mockito::Matcher;
+    use stacks_common::util::secp256k1::MessageSignature;
+    use tempfile::tempdir;
+    use tiny_http::{Header, Response, Server};
+
+    use super::*;
+
+    fn make_chunk(slot_id: u32, slot_version: u32) -> StackerDBChunkData {
+        StackerDBChunkData {
+            slot_id,
+            slot_version,
+            sig: MessageSignature::empty(),
+            data: vec![1, 2, 3, 4],
+        }
+    }
+
+    #[test]
+    fn test_send_chunk() {
+        let contract_id =
+            QualifiedContractIdentifier::parse("SP000000000000000000002Q6VF78.signers-0-0")
+                .unwrap();
+        let chunk = make_chunk(3, 1);
+        let ack = StackerDBChunkAckData {
+            accepted: true,
+            reason: None,
+            metadata: None,
+            code: None,
+        };
+
+        let mut server = mockito::Server::new();
+        let endpoint = server
+            .mock(
+                "POST",
+                "/v3/admin/stackerdb/SP000000000000000000002Q6VF78/signers-0-0/chunks",
+            )
+            .match_header("authorization", "hunter2")
+            .match_body(Matcher::Json(serde_json::to_value(&chunk).unwrap()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::to_string(&ack).unwrap())
+            .create();
+
+        let standby = StackerDBStandbyConfig {
+            url: server.url(),
+            auth_token: "hunter2".into(),
+            contracts: vec![contract_id.clone()],
+            timeout_ms: 5_000,
+        };
+        let resp = StackerDBReplicator::send_chunk(&standby, &contract_id, &chunk).unwrap();
+        assert_eq!(resp, ack);
+        endpoint.assert();
+
+        // a standby that rejects the auth token is an error
+        let unauthorized = server
+            .mock(
+                "POST",
+                "/v3/admin/stackerdb/SP000000000000000000002Q6VF78/signers-0-0/chunks",
+            )
+            .match_header("authorization", "wrong")
+            .with_status(401)
+            .create();
+        let standby = StackerDBStandbyConfig {
+            auth_token: "wrong".into(),
+            ..standby
+        };
+        assert!(StackerDBReplicator::send_chunk(&standby, &contract_id, &chunk).is_err());
+        unauthorized.assert();
+    }
+
+    #[test]
+    fn test_replicate_queues_chunks() {
+        let replicated =
+            QualifiedContractIdentifier::parse("SP000000000000000000002Q6VF78.signers-0-0")
+                .unwrap();
+        let not_replicated =
+            QualifiedContractIdentifier::parse("SP000000000000000000002Q6VF78.miners").unwrap();
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join(REPLICATION_DB_NAME);
+        let conn = StackerDBReplicator::init_db(&db_path).unwrap();
+
+        let url = "http://127.0.0.1:30443";
+        let (wakeup, wakeup_rx) = sync_channel(1);
+        let replicator = StackerDBReplicator {
+            queue_db: Arc::new(Mutex::new(StackerDBReplicator::open_db(&db_path).unwrap())),
+            standbys: vec![StandbyQueue {
+                url: url.into(),
+                contracts: HashSet::from([replicated.clone()]),
+                wakeup,
+            }],
+        };
+
+        replicator
+            .replicate(&not_replicated, &[make_chunk(0, 1)])
+            .unwrap();
+        assert!(StackerDBReplicator::get_pending_chunks(&conn, url)
+            .unwrap()
+            .is_empty());
+        assert!(wakeup_rx.try_recv().is_err());
+
+        replicator
+            .replicate(&replicated, &[make_chunk(0, 1), make_chunk(1, 1)])
+            .unwrap();
+        wakeup_rx.try_recv().unwrap();
+
+        // a newer version of a queued slot replaces it, and an older one is ignored
+        replicator
+            .replicate(&replicated, &[make_chunk(0, 2)])
+            .unwrap();
+        replicator
+            .replicate(&replicated, &[make_chunk(1, 0)])
+            .unwrap();
+
+        let queued: Vec<_> = StackerDBReplicator::get_pending_chunks(&conn, url)
+            .unwrap()
+            .into_iter()
+            .map(|(_, contract_id, chunk)| (contract_id, chunk))
+            .collect();
+        assert_eq!(
+            queued,
+            vec![
+                (replicated.clone(), make_chunk(0, 2)),
+                (replicated.clone(), make_chunk(1, 1)),
+            ]
+        );
+
+        // a chunk that cannot be queued in time is an error, which refuses an RPC write
+        let mut blocker = StackerDBReplicator::open_db(&db_path).unwrap();
+        let blocker_tx = blocker
+            .transaction_with_behavior(rusqlite::TransactionBehavior::Exclusive)
+            .unwrap();
+        let start = std::time::Instant::now();
+        assert!(replicator
+            .queue_stackerdb_chunk(&replicated, &make_chunk(2, 1))
+            .is_err());
+        assert!(start.elapsed() < DB_BUSY_TIMEOUT * 5);
+        blocker_tx.rollback().unwrap();
+
+        replicator
+            .queue_stackerdb_chunk(&replicated, &make_chunk(2, 1))
+            .unwrap();
+        assert_eq!(
+            StackerDBReplicator::get_pending_chunks(&conn, url)
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_replicate_to_dropped_standby() {
+        let contract_id =
+            QualifiedContractIdentifier::parse("SP000000000000000000002Q6VF78.signers-0-0")
+                .unwrap();
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let dir = tempdir().unwrap();
+
+        // Serve `num_chunks` chunks on `port`, then drop the standby.  The received chunks are
+        // sent back to the test.
+        let serve_chunks = |num_chunks: usize| {
+            let (tx, rx) = channel();
+            // the last standby on this port may take a moment to let go of it
+            let server = (0..50)
+                .find_map(|_| {
+                    Server::http(format!("127.0.0.1:{port}"))
+                        .map_err(|_| sleep(Duration::from_millis(100)))
+                        .ok()
+                })
+                .unwrap();
+            thread::spawn(move || {
+                for _ in 0..num_chunks {
+                    let mut request = server.recv().unwrap();
+                    assert_eq!(
+                        request.url(),
+                        "/v3/admin/stackerdb/SP000000000000000000002Q6VF78/signers-0-0/chunks"
+                    );
+                    let mut body = String::new();
+                    request.as_reader().read_to_string(&mut body).unwrap();
+                    let chunk: StackerDBChunkData = serde_json::from_str(&body).unwrap();
+                    let ack = StackerDBChunkAckData {
+                        accepted: true,
+                        reason: None,
+                        metadata: None,
+                        code: None,
+                    };
+                    let response = Response::from_string(serde_json::to_string(&ack).unwrap())
+                        .with_header(
+                            Header::from_bytes("Content-Type", "application/json").unwrap(),
+                        );
+                    request.respond(response).unwrap();
+                    tx.send(chunk).unwrap();
+                }
+            });
+            rx
+        };
+
+        let standby = StackerDBStandbyConfig {
+            url: format!("http://127.0.0.1:{port}"),
+            auth_token: "hunter2".into(),
+            contracts: vec![contract_id.clone()],
+            timeout_ms: 1_000,
+        };
+        let replicator = StackerDBReplicator::spawn(&[standby], dir.path().to_path_buf()).unwrap();
+        let conn = StackerDBReplicator::open_db(&dir.path().join(REPLICATION_DB_NAME)).unwrap();
+        let num_queued = || {
+            StackerDBReplicator::get_pending_chunks(&conn, &format!("http://127.0.0.1:{port}"))
+                .unwrap()
+                .len()
+        };
+
+        let received = serve_chunks(1);
+        replicator
+            .replicate(&contract_id, &[make_chunk(0, 1)])
+            .unwrap();
+        assert_eq!(
+            received.recv_timeout(Duration::from_secs(10)).unwrap(),
+            make_chunk(0, 1)
+        );
+
+        // the standby is gone, so the next chunk stays queued
+        replicator
+            .replicate(&contract_id, &[make_chunk(1, 1)])
+            .unwrap();
+        sleep(Duration::from_secs(1));
+        assert_eq!(num_queued(), 1);
+
+        // once the standby is back, the chunk is delivered and dequeued
+        let received = serve_chunks(1);
+        assert_eq!(
+            received.recv_timeout(Duration::from_secs(30)).unwrap(),
+            make_chunk(1, 1)
+        );
+        for _ in 0..100 {
+            if num_queued() == 0 {
+                break;
+            }
+            sleep(Duration::from_millis(100));
+        }
+        assert_eq!(num_queued(), 0);
+    }
+}
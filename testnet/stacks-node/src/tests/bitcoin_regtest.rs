@@ -1,6 +1,4 @@
 use std::env;
-use std::io::{BufRead, BufReader};
-use std::process::{Child, Command, Stdio};
 
 use clarity::vm::costs::ExecutionCost;
 use stacks::chainstate::burn::operations::BlockstackOperationType::{
@@ -12,131 +10,9 @@ use stacks::core::StacksEpochId;
 use stacks_common::util::hash::hex_bytes;
 
 use super::PUBLISH_CONTRACT;
-use crate::burnchains::bitcoin_regtest_controller::BitcoinRPCRequest;
+pub use crate::burnchains::bitcoind::{BitcoinCoreController, BitcoinCoreError};
 use crate::helium::RunLoop;
 use crate::tests::to_addr;
-use crate::Config;
-
-#[derive(Debug, thiserror::Error)]
-pub enum BitcoinCoreError {
-    #[error("bitcoind spawn failed: {0}")]
-    SpawnFailed(String),
-    #[error("bitcoind stop failed: {0}")]
-    StopFailed(String),
-}
-
-type BitcoinResult<T> = Result<T, BitcoinCoreError>;
-
-pub struct BitcoinCoreController {
-    bitcoind_process: Option<Child>,
-    config: Config,
-}
-
-impl BitcoinCoreController {
-    pub fn new(config: Config) -> BitcoinCoreController {
-        BitcoinCoreController {
-            bitcoind_process: None,
-            config,
-        }
-    }
-
-    fn add_rpc_cli_args(&self, command: &mut Command) {
-        command.arg(format!("-rpcport={}", self.config.burnchain.rpc_port));
-
-        if let (Some(username), Some(password)) = (
-            &self.config.burnchain.username,
-            &self.config.burnchain.password,
-        ) {
-            command
-                .arg(format!("-rpcuser={username}"))
-                .arg(format!("-rpcpassword={password}"));
-        }
-    }
-
-    pub fn start_bitcoind(&mut self) -> BitcoinResult<()> {
-        std::fs::create_dir_all(self.config.get_burnchain_path_str()).unwrap();
-
-        let mut command = Command::new("bitcoind");
-        command
-            .stdout(Stdio::piped())
-            .arg("-regtest")
-            .arg("-nodebug")
-            .arg("-nodebuglogfile")
-            .arg("-rest")
-            .arg("-txindex=1")
-            .arg("-server=1")
-            .arg("-listenonion=0")
-            .arg("-rpcbind=127.0.0.1")
-            .arg(format!("-port={}", self.config.burnchain.peer_port))
-            .arg(format!("-datadir={}", self.config.get_burnchain_path_str()));
-
-        self.add_rpc_cli_args(&mut command);
-
-        eprintln!("bitcoind spawn: {command:?}");
-
-        let mut process = match command.spawn() {
-            Ok(child) => child,
-            Err(e) => return Err(BitcoinCoreError::SpawnFailed(format!("{e:?}"))),
-        };
-
-        let mut out_reader = BufReader::new(process.stdout.take().unwrap());
-
-        let mut line = String::new();
-        while let Ok(bytes_read) = out_reader.read_line(&mut line) {
-            if bytes_read == 0 {
-                return Err(BitcoinCoreError::SpawnFailed(
-                    "Bitcoind closed before spawning network".into(),
-                ));
-            }
-            if line.contains("Done loading") {
-                break;
-            }
-        }
-
-        eprintln!("bitcoind startup finished");
-
-        self.bitcoind_process = Some(process);
-
-        Ok(())
-    }
-
-    pub fn stop_bitcoind(&mut self) -> Result<(), BitcoinCoreError> {
-        if self.bitcoind_process.take().is_some() {
-            let payload = BitcoinRPCRequest {
-                method: "stop".to_string(),
-                params: vec![],
-                id: "stacks".to_string(),
-                jsonrpc: "2.0".to_string(),
-            };
-
-            let res = BitcoinRPCRequest::send(&self.config, payload)
-                .map_err(|e| BitcoinCoreError::StopFailed(format!("{e:?}")))?;
-
-            if let Some(err) = res.get("error") {
-                if !err.is_null() {
-                    return Err(BitcoinCoreError::StopFailed(format!("{err}")));
-                }
-            } else {
-                return Err(BitcoinCoreError::StopFailed(format!(
-                    "Invalid response: {res:?}"
-                )));
-            }
-        }
-        Ok(())
-    }
-
-    pub fn kill_bitcoind(&mut self) {
-        if let Some(mut bitcoind_process) = self.bitcoind_process.take() {
-            bitcoind_process.kill().unwrap();
-        }
-    }
-}
-
-impl Drop for BitcoinCoreController {
-    fn drop(&mut self) {
-        self.kill_bitcoind();
-    }
-}
 
 const BITCOIND_INT_TEST_COMMITS: u64 = 11000;
 
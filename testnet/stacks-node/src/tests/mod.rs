@@ -31,13 +31,18 @@ use stacks::chainstate::stacks::events::StacksTransactionEvent;
 use stacks::chainstate::stacks::miner::{BlockBuilderSettings, StacksMicroblockBuilder};
 use stacks::chainstate::stacks::{
     CoinbasePayload, StacksBlock, StacksMicroblock, StacksMicroblockHeader, StacksPrivateKey,
-    StacksPublicKey, StacksTransaction, StacksTransactionSigner, TokenTransferMemo,
-    TransactionAnchorMode, TransactionAuth, TransactionContractCall, TransactionPayload,
-    TransactionPostConditionMode, TransactionSmartContract, TransactionSpendingCondition,
-    TransactionVersion, C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+    StacksPublicKey, StacksTransaction, TokenTransferMemo, TransactionAnchorMode,
+    TransactionContractCall, TransactionPayload, TransactionSmartContract, TransactionVersion,
+    C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
 };
 use stacks::core::{StacksEpoch, StacksEpochExtension, StacksEpochId, CHAIN_ID_TESTNET};
 use stacks::util_lib::strings::StacksString;
+pub use stacks::util_lib::tx_builder::{
+    make_contract_call, make_contract_publish, make_contract_publish_versioned,
+    make_stacks_transfer, serialize_sign_sponsored_sig_tx_anchor_mode_version,
+    serialize_sign_standard_single_sig_tx, serialize_sign_standard_single_sig_tx_anchor_mode,
+    serialize_sign_standard_single_sig_tx_anchor_mode_version,
+};
 use stacks_common::address::AddressHashMode;
 use stacks_common::codec::StacksMessageCodec;
 use stacks_common::types::chainstate::{BlockHeaderHash, StacksAddress};
@@ -133,178 +138,6 @@ pub fn insert_new_port(port: u16) -> bool {
     ports.insert(port)
 }
 
-#[allow(clippy::too_many_arguments)]
-pub fn serialize_sign_sponsored_sig_tx_anchor_mode_version(
-    payload: TransactionPayload,
-    sender: &StacksPrivateKey,
-    payer: &StacksPrivateKey,
-    sender_nonce: u64,
-    payer_nonce: u64,
-    tx_fee: u64,
-    chain_id: u32,
-    anchor_mode: TransactionAnchorMode,
-    version: TransactionVersion,
-) -> Vec<u8> {
-    serialize_sign_tx_anchor_mode_version(
-        payload,
-        sender,
-        Some(payer),
-        sender_nonce,
-        Some(payer_nonce),
-        tx_fee,
-        chain_id,
-        anchor_mode,
-        version,
-    )
-}
-
-pub fn serialize_sign_standard_single_sig_tx(
-    payload: TransactionPayload,
-    sender: &StacksPrivateKey,
-    nonce: u64,
-    tx_fee: u64,
-    chain_id: u32,
-) -> Vec<u8> {
-    serialize_sign_standard_single_sig_tx_anchor_mode(
-        payload,
-        sender,
-        nonce,
-        tx_fee,
-        chain_id,
-        TransactionAnchorMode::OnChainOnly,
-    )
-}
-
-pub fn serialize_sign_standard_single_sig_tx_anchor_mode(
-    payload: TransactionPayload,
-    sender: &StacksPrivateKey,
-    nonce: u64,
-    tx_fee: u64,
-    chain_id: u32,
-    anchor_mode: TransactionAnchorMode,
-) -> Vec<u8> {
-    serialize_sign_standard_single_sig_tx_anchor_mode_version(
-        payload,
-        sender,
-        nonce,
-        tx_fee,
-        chain_id,
-        anchor_mode,
-        TransactionVersion::Testnet,
-    )
-}
-
-pub fn serialize_sign_standard_single_sig_tx_anchor_mode_version(
-    payload: TransactionPayload,
-    sender: &StacksPrivateKey,
-    nonce: u64,
-    tx_fee: u64,
-    chain_id: u32,
-    anchor_mode: TransactionAnchorMode,
-    version: TransactionVersion,
-) -> Vec<u8> {
-    serialize_sign_tx_anchor_mode_version(
-        payload,
-        sender,
-        None,
-        nonce,
-        None,
-        tx_fee,
-        chain_id,
-        anchor_mode,
-        version,
-    )
-}
-
-#[allow(clippy::too_many_arguments)]
-pub fn serialize_sign_tx_anchor_mode_version(
-    payload: TransactionPayload,
-    sender: &StacksPrivateKey,
-    payer: Option<&StacksPrivateKey>,
-    sender_nonce: u64,
-    payer_nonce: Option<u64>,
-    tx_fee: u64,
-    chain_id: u32,
-    anchor_mode: TransactionAnchorMode,
-    version: TransactionVersion,
-) -> Vec<u8> {
-    let mut sender_spending_condition =
-        TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(sender))
-            .expect("Failed to create p2pkh spending condition from public key.");
-    sender_spending_condition.set_nonce(sender_nonce);
-
-    let auth = match (payer, payer_nonce) {
-        (Some(payer), Some(payer_nonce)) => {
-            let mut payer_spending_condition = TransactionSpendingCondition::new_singlesig_p2pkh(
-                StacksPublicKey::from_private(payer),
-            )
-            .expect("Failed to create p2pkh spending condition from public key.");
-            payer_spending_condition.set_nonce(payer_nonce);
-            payer_spending_condition.set_tx_fee(tx_fee);
-            TransactionAuth::Sponsored(sender_spending_condition, payer_spending_condition)
-        }
-        _ => {
-            sender_spending_condition.set_tx_fee(tx_fee);
-            TransactionAuth::Standard(sender_spending_condition)
-        }
-    };
-    let mut unsigned_tx = StacksTransaction::new(version, auth, payload);
-    unsigned_tx.anchor_mode = anchor_mode;
-    unsigned_tx.post_condition_mode = TransactionPostConditionMode::Allow;
-    unsigned_tx.chain_id = chain_id;
-
-    let mut tx_signer = StacksTransactionSigner::new(&unsigned_tx);
-    tx_signer.sign_origin(sender).unwrap();
-    if let (Some(payer), Some(_)) = (payer, payer_nonce) {
-        tx_signer.sign_sponsor(payer).unwrap();
-    }
-
-    let mut buf = vec![];
-    tx_signer
-        .get_tx()
-        .unwrap()
-        .consensus_serialize(&mut buf)
-        .unwrap();
-    buf
-}
-
-pub fn make_contract_publish_versioned(
-    sender: &StacksPrivateKey,
-    nonce: u64,
-    tx_fee: u64,
-    chain_id: u32,
-    contract_name: &str,
-    contract_content: &str,
-    version: Option<ClarityVersion>,
-) -> Vec<u8> {
-    let name = ContractName::from(contract_name);
-    let code_body = StacksString::from_string(&contract_content.to_string()).unwrap();
-
-    let payload =
-        TransactionPayload::SmartContract(TransactionSmartContract { name, code_body }, version);
-
-    serialize_sign_standard_single_sig_tx(payload, sender, nonce, tx_fee, chain_id)
-}
-
-pub fn make_contract_publish(
-    sender: &StacksPrivateKey,
-    nonce: u64,
-    tx_fee: u64,
-    chain_id: u32,
-    contract_name: &str,
-    contract_content: &str,
-) -> Vec<u8> {
-    make_contract_publish_versioned(
-        sender,
-        nonce,
-        tx_fee,
-        chain_id,
-        contract_name,
-        contract_content,
-        None,
-    )
-}
-
 pub fn make_contract_publish_microblock_only_versioned(
     sender: &StacksPrivateKey,
     nonce: u64,
@@ -419,19 +252,6 @@ pub fn to_addr(sk: &StacksPrivateKey) -> StacksAddress {
     .unwrap()
 }
 
-pub fn make_stacks_transfer(
-    sender: &StacksPrivateKey,
-    nonce: u64,
-    tx_fee: u64,
-    chain_id: u32,
-    recipient: &PrincipalData,
-    amount: u64,
-) -> Vec<u8> {
-    let payload =
-        TransactionPayload::TokenTransfer(recipient.clone(), amount, TokenTransferMemo([0; 34]));
-    serialize_sign_standard_single_sig_tx(payload, sender, nonce, tx_fee, chain_id)
-}
-
 #[allow(clippy::too_many_arguments)]
 pub fn make_sponsored_stacks_transfer_on_testnet(
     sender: &StacksPrivateKey,
@@ -495,30 +315,6 @@ pub fn make_coinbase(sender: &StacksPrivateKey, nonce: u64, tx_fee: u64, chain_i
     serialize_sign_standard_single_sig_tx(payload, sender, nonce, tx_fee, chain_id)
 }
 
-#[allow(clippy::too_many_arguments)]
-pub fn make_contract_call(
-    sender: &StacksPrivateKey,
-    nonce: u64,
-    tx_fee: u64,
-    chain_id: u32,
-    contract_addr: &StacksAddress,
-    contract_name: &str,
-    function_name: &str,
-    function_args: &[Value],
-) -> Vec<u8> {
-    let contract_name = ContractName::from(contract_name);
-    let function_name = ClarityName::from(function_name);
-
-    let payload = TransactionContractCall {
-        address: *contract_addr,
-        contract_name,
-        function_name,
-        function_args: function_args.to_vec(),
-    };
-
-    serialize_sign_standard_single_sig_tx(payload.into(), sender, nonce, tx_fee, chain_id)
-}
-
 #[allow(clippy::too_many_arguments)]
 pub fn make_contract_call_mblock_only(
     sender: &StacksPrivateKey,
@@ -50,6 +50,7 @@ use stacks::core::{
     PEER_VERSION_TESTNET,
 };
 use stacks::net::api::getaccount::AccountEntryResponse;
+use stacks::net::api::getburnops::{BurnOpStatus, BurnOpsResponse};
 use stacks::net::api::getcontractsrc::ContractSrcResponse;
 use stacks::net::api::getinfo::RPCPeerInfoData;
 use stacks::net::api::getpoxinfo::RPCPoxInfoData;
@@ -83,9 +84,11 @@ use super::{
     make_microblock, make_stacks_transfer, make_stacks_transfer_mblock_only, to_addr, ADDR_4, SK_1,
     SK_2, SK_3,
 };
-use crate::burnchains::bitcoin_regtest_controller::{self, addr2str, BitcoinRPCRequest, UTXO};
+use crate::burnchains::bitcoin_regtest_controller::{
+    self, addr2str, BitcoinRPCRequest, SerializedTx, UTXO,
+};
 use crate::neon_node::RelayerThread;
-use crate::operations::BurnchainOpSigner;
+use crate::operations::{make_delegate_stx_op, make_pre_stx_op, BurnchainOpSigner};
 use crate::stacks_common::types::PrivateKey;
 use crate::syncctl::PoxSyncWatchdogComms;
 use crate::tests::gen_random_port;
@@ -206,7 +209,9 @@ pub mod test_observer {
     use warp::Filter;
     use {tokio, warp};
 
-    use crate::event_dispatcher::{MinedBlockEvent, MinedMicroblockEvent, MinedNakamotoBlockEvent};
+    use crate::event_dispatcher::{
+        MinedBlockEvent, MinedMicroblockEvent, MinedNakamotoBlockEvent, RawNakamotoBlockEvent,
+    };
     use crate::Config;
 
     pub const EVENT_OBSERVER_PORT: u16 = 50303;
@@ -223,6 +228,8 @@ pub mod test_observer {
     pub static ATTACHMENTS: Mutex<Vec<serde_json::Value>> = Mutex::new(Vec::new());
     pub static PROPOSAL_RESPONSES: Mutex<Vec<BlockValidateResponse>> = Mutex::new(Vec::new());
     pub static STACKER_SETS: Mutex<Vec<(StacksBlockId, u64, RewardSet)>> = Mutex::new(Vec::new());
+    pub static RAW_BLOCKS: Mutex<Vec<RawNakamotoBlockEvent>> = Mutex::new(Vec::new());
+    pub static REWARD_CYCLES: Mutex<Vec<serde_json::Value>> = Mutex::new(Vec::new());
 
     async fn handle_proposal_response(
         response: serde_json::Value,
@@ -249,6 +256,20 @@ pub mod test_observer {
         Ok(warp::http::StatusCode::OK)
     }
 
+    async fn handle_raw_block(block: serde_json::Value) -> Result<impl warp::Reply, Infallible> {
+        let mut raw_blocks = RAW_BLOCKS.lock().unwrap();
+        raw_blocks.push(serde_json::from_value(block).unwrap());
+        Ok(warp::http::StatusCode::OK)
+    }
+
+    async fn handle_reward_cycle(
+        reward_cycle: serde_json::Value,
+    ) -> Result<impl warp::Reply, Infallible> {
+        let mut reward_cycles = REWARD_CYCLES.lock().unwrap();
+        reward_cycles.push(reward_cycle);
+        Ok(warp::http::StatusCode::OK)
+    }
+
     async fn handle_microblocks(
         microblocks: serde_json::Value,
     ) -> Result<impl warp::Reply, Infallible> {
@@ -448,6 +469,14 @@ pub mod test_observer {
         NEW_BLOCKS.lock().unwrap().clone()
     }
 
+    pub fn get_raw_blocks() -> Vec<RawNakamotoBlockEvent> {
+        RAW_BLOCKS.lock().unwrap().clone()
+    }
+
+    pub fn get_reward_cycles() -> Vec<serde_json::Value> {
+        REWARD_CYCLES.lock().unwrap().clone()
+    }
+
     pub fn get_microblocks() -> Vec<serde_json::Value> {
         NEW_MICROBLOCKS.lock().unwrap().clone()
     }
@@ -530,6 +559,14 @@ pub mod test_observer {
             .and(warp::post())
             .and(warp::body::json())
             .and_then(handle_pox_stacker_set);
+        let raw_blocks = warp::path!("new_raw_block")
+            .and(warp::post())
+            .and(warp::body::json())
+            .and_then(handle_raw_block);
+        let reward_cycles = warp::path!("new_reward_cycle")
+            .and(warp::post())
+            .and(warp::body::json())
+            .and_then(handle_reward_cycle);
 
         info!("Spawning event-observer warp server");
         warp::serve(
@@ -544,7 +581,9 @@ pub mod test_observer {
                 .or(mined_nakamoto_blocks)
                 .or(new_stackerdb_chunks)
                 .or(block_proposals)
-                .or(stacker_sets),
+                .or(stacker_sets)
+                .or(raw_blocks)
+                .or(reward_cycles),
         )
         .run(([127, 0, 0, 1], port))
         .await
@@ -577,6 +616,8 @@ pub mod test_observer {
         MEMTXS_DROPPED.lock().unwrap().clear();
         ATTACHMENTS.lock().unwrap().clear();
         PROPOSAL_RESPONSES.lock().unwrap().clear();
+        RAW_BLOCKS.lock().unwrap().clear();
+        REWARD_CYCLES.lock().unwrap().clear();
     }
 
     /// Parse the StacksTransactions from a block (does not include burn ops or phantom txs)
@@ -1403,6 +1444,12 @@ pub fn get_pox_info(http_origin: &str) -> Option<RPCPoxInfoData> {
     client.get(&path).send().ok()?.json::<RPCPoxInfoData>().ok()
 }
 
+pub fn get_burn_ops(http_origin: &str, burn_height: u64) -> BurnOpsResponse {
+    let client = reqwest::blocking::Client::new();
+    let path = format!("{http_origin}/v3/burn-ops/{burn_height}");
+    client.get(&path).send().unwrap().json().unwrap()
+}
+
 fn get_chain_tip(http_origin: &str) -> (ConsensusHash, BlockHeaderHash) {
     let client = reqwest::blocking::Client::new();
     let path = format!("{http_origin}/v2/info");
@@ -2117,6 +2164,323 @@ fn stx_transfer_btc_integration_test() {
     channel.stop_chains_coordinator();
 }
 
+#[test]
+#[ignore]
+/// Test that /v3/burn-ops reports a transfer-stx op as pending until a Stacks block applies it
+fn burn_ops_endpoint_test() {
+    if env::var("BITCOIND_TEST") != Ok("1".into()) {
+        return;
+    }
+
+    let spender_sk = StacksPrivateKey::from_hex(SK_1).unwrap();
+    let spender_stx_addr: StacksAddress = to_addr(&spender_sk);
+    let spender_addr: PrincipalData = spender_stx_addr.into();
+
+    let (mut conf, _miner_account) = neon_integration_test_conf();
+
+    conf.initial_balances.push(InitialBalance {
+        address: spender_addr.clone(),
+        amount: 100300,
+    });
+
+    let mut btcd_controller = BitcoinCoreController::new(conf.clone());
+    btcd_controller
+        .start_bitcoind()
+        .expect("Failed starting bitcoind");
+
+    let mut btc_regtest_controller = BitcoinRegtestController::new(conf.clone(), None);
+    let http_origin = format!("http://{}", &conf.node.rpc_bind);
+
+    btc_regtest_controller.bootstrap_chain(201);
+
+    eprintln!("Chain bootstrapped...");
+
+    let mut run_loop = neon::RunLoop::new(conf.clone());
+    let blocks_processed = run_loop.get_blocks_processed_arc();
+
+    let channel = run_loop.get_coordinator_channel().unwrap();
+
+    thread::spawn(move || run_loop.start(None, 0));
+
+    // give the run loop some time to start up!
+    wait_for_runloop(&blocks_processed);
+
+    // first block wakes up the run loop
+    next_block_and_wait(&mut btc_regtest_controller, &blocks_processed);
+
+    // first block will hold our VRF registration
+    next_block_and_wait(&mut btc_regtest_controller, &blocks_processed);
+
+    // second block will be the first mined Stacks block
+    next_block_and_wait(&mut btc_regtest_controller, &blocks_processed);
+
+    let pre_stx_op = PreStxOp {
+        output: spender_stx_addr,
+        // to be filled in
+        txid: Txid([0u8; 32]),
+        vtxindex: 0,
+        block_height: 0,
+        burn_header_hash: BurnchainHeaderHash([0u8; 32]),
+    };
+
+    let mut miner_signer = Keychain::default(conf.node.seed.clone()).generate_op_signer();
+
+    assert!(
+        btc_regtest_controller
+            .submit_operation(
+                StacksEpochId::Epoch2_05,
+                BlockstackOperationType::PreStx(pre_stx_op),
+                &mut miner_signer,
+                1
+            )
+            .is_ok(),
+        "Pre-stx operation should submit successfully"
+    );
+
+    next_block_and_wait(&mut btc_regtest_controller, &blocks_processed);
+
+    // the pre-stx op is accepted, but never applied itself
+    let pre_stx_height = channel.get_sortitions_processed();
+    let burn_ops = get_burn_ops(&http_origin, pre_stx_height);
+    assert_eq!(burn_ops.burn_block_height, pre_stx_height);
+    assert_eq!(burn_ops.ops.len(), 1);
+    assert!(burn_ops.ops[0].op.get("pre_stx").is_some());
+    assert_eq!(burn_ops.ops[0].status, BurnOpStatus::Accepted);
+
+    let recipient_sk = StacksPrivateKey::random();
+    let recipient_addr = to_addr(&recipient_sk);
+    let transfer_stx_op = TransferStxOp {
+        sender: spender_stx_addr,
+        recipient: recipient_addr,
+        transfered_ustx: 100_000,
+        memo: vec![],
+        // to be filled in
+        txid: Txid([0u8; 32]),
+        vtxindex: 0,
+        block_height: 0,
+        burn_header_hash: BurnchainHeaderHash([0u8; 32]),
+    };
+
+    let mut spender_signer = BurnchainOpSigner::new(spender_sk, false);
+
+    assert!(
+        btc_regtest_controller
+            .submit_operation(
+                StacksEpochId::Epoch2_05,
+                BlockstackOperationType::TransferStx(transfer_stx_op),
+                &mut spender_signer,
+                1
+            )
+            .is_ok(),
+        "Transfer operation should submit successfully"
+    );
+
+    // the transfer is mined, but the Stacks block elected in the same burn block doesn't apply it
+    next_block_and_wait(&mut btc_regtest_controller, &blocks_processed);
+    let transfer_height = channel.get_sortitions_processed();
+    let burn_ops = get_burn_ops(&http_origin, transfer_height);
+    assert_eq!(burn_ops.ops.len(), 1);
+    let transfer_entry = &burn_ops.ops[0];
+    assert!(transfer_entry.op.get("transfer_stx").is_some());
+    assert_eq!(transfer_entry.status, BurnOpStatus::Pending);
+    assert!(transfer_entry.applied_in.is_none());
+    assert!(transfer_entry.result.is_none());
+
+    // this block applies the transfer
+    next_block_and_wait(&mut btc_regtest_controller, &blocks_processed);
+    assert_eq!(get_balance(&http_origin, &recipient_addr), 100_000);
+
+    let burn_ops = get_burn_ops(&http_origin, transfer_height);
+    assert_eq!(burn_ops.ops.len(), 1);
+    let applied_entry = &burn_ops.ops[0];
+    assert_eq!(applied_entry.txid, transfer_entry.txid);
+    assert_eq!(applied_entry.status, BurnOpStatus::Applied);
+    assert!(applied_entry.applied_in.is_some());
+
+    let raw_result = applied_entry.result.as_ref().unwrap();
+    let parsed = Value::try_deserialize_hex_untyped(&raw_result[2..]).unwrap();
+    parsed
+        .expect_result_ok()
+        .expect("Expected OK result for transfer-stx op");
+    assert!(applied_entry
+        .events
+        .iter()
+        .any(|event| event.get("stx_transfer_event").is_some()));
+
+    channel.stop_chains_coordinator();
+}
+
+#[test]
+#[ignore]
+/// Test that a delegate-stx op submitted the way `stacks-node burn-op` submits it spends only
+/// the output of the pre-stx op it names, even if its sender holds other UTXOs
+fn burn_op_cli_delegate_stx_test() {
+    if env::var("BITCOIND_TEST") != Ok("1".into()) {
+        return;
+    }
+
+    let spender_sk = StacksPrivateKey::from_hex(SK_1).unwrap();
+    let spender_stx_addr: StacksAddress = to_addr(&spender_sk);
+    let spender_addr: PrincipalData = spender_stx_addr.into();
+
+    let recipient_sk = StacksPrivateKey::random();
+    let recipient_addr = to_addr(&recipient_sk);
+
+    let (mut conf, _miner_account) = neon_integration_test_conf();
+
+    conf.initial_balances.push(InitialBalance {
+        address: spender_addr,
+        amount: 100300,
+    });
+
+    // update epoch info so that Epoch 2.1 takes effect
+    conf.burnchain.epochs = Some(EpochList::new(&[
+        StacksEpoch {
+            epoch_id: StacksEpochId::Epoch20,
+            start_height: 0,
+            end_height: 1,
+            block_limit: BLOCK_LIMIT_MAINNET_20.clone(),
+            network_epoch: PEER_VERSION_EPOCH_2_0,
+        },
+        StacksEpoch {
+            epoch_id: StacksEpochId::Epoch2_05,
+            start_height: 1,
+            end_height: 2,
+            block_limit: BLOCK_LIMIT_MAINNET_205.clone(),
+            network_epoch: PEER_VERSION_EPOCH_2_05,
+        },
+        StacksEpoch {
+            epoch_id: StacksEpochId::Epoch21,
+            start_height: 2,
+            end_height: 9223372036854775807,
+            block_limit: BLOCK_LIMIT_MAINNET_21.clone(),
+            network_epoch: PEER_VERSION_EPOCH_2_1,
+        },
+    ]));
+    conf.burnchain.pox_2_activation = Some(3);
+
+    let mut btcd_controller = BitcoinCoreController::new(conf.clone());
+    btcd_controller
+        .start_bitcoind()
+        .expect("Failed starting bitcoind");
+
+    let mut burnchain_config = Burnchain::regtest(&conf.get_burn_db_path());
+
+    // reward cycle length = 5, so 3 reward cycle slots + 2 prepare-phase burns
+    let reward_cycle_len = 5;
+    let prepare_phase_len = 2;
+    let pox_constants = PoxConstants::new(
+        reward_cycle_len,
+        prepare_phase_len,
+        2,
+        5,
+        15,
+        (16 * reward_cycle_len - 1).into(),
+        (17 * reward_cycle_len).into(),
+        u32::MAX,
+        u32::MAX,
+        u32::MAX,
+        u32::MAX,
+    );
+    burnchain_config.pox_constants = pox_constants;
+
+    let mut btc_regtest_controller =
+        BitcoinRegtestController::with_burnchain(conf.clone(), None, Some(burnchain_config), None);
+    let http_origin = format!("http://{}", &conf.node.rpc_bind);
+
+    btc_regtest_controller.bootstrap_chain(201);
+
+    eprintln!("Chain bootstrapped...");
+
+    let mut run_loop = neon::RunLoop::new(conf.clone());
+    let blocks_processed = run_loop.get_blocks_processed_arc();
+
+    let channel = run_loop.get_coordinator_channel().unwrap();
+
+    thread::spawn(move || run_loop.start(None, 0));
+
+    // give the run loop some time to start up!
+    wait_for_runloop(&blocks_processed);
+
+    // Mine a few blocks so that Epoch 2.1 (and thus pox-2) can take effect.
+    for _i in 0..6 {
+        next_block_and_wait(&mut btc_regtest_controller, &blocks_processed);
+    }
+
+    // the spender ends up with two pre-stx outputs
+    let mut miner_signer = Keychain::default(conf.node.seed.clone()).generate_op_signer();
+    btc_regtest_controller
+        .submit_manual(
+            StacksEpochId::Epoch21,
+            BlockstackOperationType::PreStx(make_pre_stx_op(spender_stx_addr)),
+            &mut miner_signer,
+            None,
+        )
+        .expect("Pre-stx operation should submit successfully");
+    next_block_and_wait(&mut btc_regtest_controller, &blocks_processed);
+
+    let mut miner_signer = Keychain::default(conf.node.seed.clone()).generate_op_signer();
+    let pre_stx_tx = btc_regtest_controller
+        .submit_manual(
+            StacksEpochId::Epoch21,
+            BlockstackOperationType::PreStx(make_pre_stx_op(spender_stx_addr)),
+            &mut miner_signer,
+            None,
+        )
+        .expect("Pre-stx operation should submit successfully");
+
+    // look up the pre-stx output by the txid that `burn-op pre-stx` prints, while it is still
+    // in bitcoind's mempool
+    let pre_stx_txid = SerializedTx::new(pre_stx_tx.clone()).txid();
+    let pre_stx_utxo = btc_regtest_controller
+        .get_pre_stx_utxo(&pre_stx_txid)
+        .expect("Failed to load pre-stx output");
+    assert_eq!(pre_stx_utxo.txid, pre_stx_tx.txid());
+    next_block_and_wait(&mut btc_regtest_controller, &blocks_processed);
+
+    // the CLI takes WIF-encoded keys, too
+    let wif = BurnchainOpSigner::new(spender_sk, false).get_sk_as_wif();
+    let mut spender_signer = BurnchainOpSigner::from_secret_key_str(&wif, false).unwrap();
+    assert_eq!(
+        spender_signer.get_stacks_address(conf.is_mainnet()),
+        spender_stx_addr
+    );
+    let del_stx_op = make_delegate_stx_op(spender_stx_addr, recipient_addr, 100_000, None);
+    let del_stx_tx = btc_regtest_controller
+        .submit_manual(
+            StacksEpochId::Epoch21,
+            BlockstackOperationType::DelegateStx(del_stx_op),
+            &mut spender_signer,
+            Some(pre_stx_utxo),
+        )
+        .expect("Delegate operation should submit successfully");
+
+    // the op spends the named pre-stx output, and nothing else
+    assert_eq!(del_stx_tx.input.len(), 1);
+    assert_eq!(del_stx_tx.input[0].previous_output.txid, pre_stx_tx.txid());
+    assert_eq!(del_stx_tx.input[0].previous_output.vout, 1);
+
+    // the second block should process the delegation
+    next_block_and_wait(&mut btc_regtest_controller, &blocks_processed);
+    let del_stx_height = channel.get_sortitions_processed();
+    next_block_and_wait(&mut btc_regtest_controller, &blocks_processed);
+
+    let del_stx_txid = SerializedTx::new(del_stx_tx).txid();
+    let entry = get_burn_ops(&http_origin, del_stx_height)
+        .ops
+        .into_iter()
+        .find(|entry| entry.txid == del_stx_txid)
+        .expect("Expected the delegate-stx op to be mined");
+    assert!(entry.op.get("delegate_stx").is_some());
+    assert_eq!(
+        entry.op["delegate_stx"]["sender"]["address"],
+        spender_stx_addr.to_string()
+    );
+    assert_eq!(entry.status, BurnOpStatus::Applied);
+
+    channel.stop_chains_coordinator();
+}
+
 #[test]
 #[ignore]
 fn stx_delegate_btc_integration_test() {
@@ -0,0 +1,676 @@
+// Copyright (C) 2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Burnchain and miner webhooks (`[[webhook]]` in the config file).
+//!
+//! Unlike event observers, webhooks are meant for alerting integrations: each event is a small
+//! JSON object, delivery is attempted once and never blocks block processing, and each hook's
+//! URL is a template into which the event's values are substituted.  A dedicated thread polls
+//! the sortition DB's canonical burnchain tip and fires `burn_block`, `new_tenure`,
+//! `missed_sortition` and `reorg` events as it changes.
+//!
+//! A miner also fires `block_accepted` from its StackerDB listener as soon as one of its block
+//! proposals gathers the signature threshold, before the block is assembled and processed, so
+//! that mining infrastructure can start on the next block right away.
+//!
+//! Hashes in payloads are `0x`-prefixed hex, like in event observer payloads.  Hashes substituted
+//! into URLs are bare hex.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::{fs, thread};
+
+use rusqlite::Connection;
+use serde_json::json;
+use stacks::chainstate::burn::db::sortdb::SortitionDB;
+use stacks::chainstate::burn::{BlockSnapshot, ConsensusHash};
+use stacks::chainstate::nakamoto::NakamotoBlockHeader;
+use stacks::config::{Config, WebhookConfig, WebhookEventType};
+use stacks::net::http::HttpRequestContents;
+use stacks::net::httpcore::{send_http_request, StacksHttpRequest};
+use stacks::util_lib::db::Error as db_error;
+use stacks_common::bitvec::BitVec;
+use stacks_common::types::chainstate::BurnchainHeaderHash;
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::hash::{to_hex, Sha256Sum};
+use url::Url;
+
+/// How often the webhook thread checks for a new burnchain tip
+const WEBHOOK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Most burnchain blocks to fire per-block events for in one poll.  A node that is syncing can
+/// process thousands of burnchain blocks between polls, and alerting on each of them is noise.
+const MAX_BLOCKS_PER_POLL: usize = 16;
+
+/// An event to deliver to webhooks
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookEvent {
+    pub event_type: WebhookEventType,
+    pub burn_block_hash: BurnchainHeaderHash,
+    pub burn_block_height: u64,
+    pub consensus_hash: ConsensusHash,
+    pub payload: serde_json::Value,
+}
+
+impl WebhookEvent {
+    /// Make a `burn_block`, `new_tenure` or `missed_sortition` event for a snapshot
+    fn from_snapshot(event_type: WebhookEventType, sn: &BlockSnapshot) -> Self {
+        let mut payload = json!({
+            "event": event_type.as_str(),
+            "burn_block_hash": format!("0x{}", &sn.burn_header_hash),
+            "burn_block_height": sn.block_height,
+            "burn_block_timestamp": sn.burn_header_timestamp,
+            "consensus_hash": format!("0x{}", &sn.consensus_hash),
+        });
+        if event_type == WebhookEventType::NewTenure {
+            payload["winning_block_txid"] = json!(format!("0x{}", &sn.winning_block_txid));
+            payload["winning_stacks_block_hash"] =
+                json!(format!("0x{}", &sn.winning_stacks_block_hash));
+        }
+        Self {
+            event_type,
+            burn_block_hash: sn.burn_header_hash.clone(),
+            burn_block_height: sn.block_height,
+            consensus_hash: sn.consensus_hash.clone(),
+            payload,
+        }
+    }
+
+    /// Make a `reorg` event for a switch from `old_tip` to `new_tip`, whose last common ancestor
+    /// is `fork_point`
+    fn reorg(old_tip: &BlockSnapshot, new_tip: &BlockSnapshot, fork_point: &BlockSnapshot) -> Self {
+        let payload = json!({
+            "event": WebhookEventType::Reorg.as_str(),
+            "burn_block_hash": format!("0x{}", &new_tip.burn_header_hash),
+            "burn_block_height": new_tip.block_height,
+            "consensus_hash": format!("0x{}", &new_tip.consensus_hash),
+            "old_burn_block_hash": format!("0x{}", &old_tip.burn_header_hash),
+            "old_burn_block_height": old_tip.block_height,
+            "fork_burn_block_hash": format!("0x{}", &fork_point.burn_header_hash),
+            "fork_burn_block_height": fork_point.block_height,
+        });
+        Self {
+            event_type: WebhookEventType::Reorg,
+            burn_block_hash: new_tip.burn_header_hash.clone(),
+            burn_block_height: new_tip.block_height,
+            consensus_hash: new_tip.consensus_hash.clone(),
+            payload,
+        }
+    }
+
+    /// Make a `block_accepted` event for a block proposed by this node's miner in the tenure
+    /// elected by `election_sn`.  `signer_bitvec` has a bit set for each signer (by slot) whose
+    /// signature the miner gathered.
+    pub fn block_accepted(
+        election_sn: &BlockSnapshot,
+        header: &NakamotoBlockHeader,
+        signer_bitvec: &BitVec<4000>,
+        total_weight_signed: u32,
+        total_weight: u32,
+        weight_threshold: u32,
+    ) -> Self {
+        let payload = json!({
+            "event": WebhookEventType::BlockAccepted.as_str(),
+            "block_id": format!("0x{}", &header.block_id()),
+            "block_height": header.chain_length,
+            "signer_signature_hash": format!("0x{}", &header.signer_signature_hash()),
+            "consensus_hash": format!("0x{}", &header.consensus_hash),
+            "burn_block_hash": format!("0x{}", &election_sn.burn_header_hash),
+            "burn_block_height": election_sn.block_height,
+            "signer_bitvec": serde_json::to_value(signer_bitvec).unwrap_or_default(),
+            "total_weight_signed": total_weight_signed,
+            "total_weight": total_weight,
+            "weight_threshold": weight_threshold,
+        });
+        Self {
+            event_type: WebhookEventType::BlockAccepted,
+            burn_block_hash: election_sn.burn_header_hash.clone(),
+            burn_block_height: election_sn.block_height,
+            consensus_hash: header.consensus_hash.clone(),
+            payload,
+        }
+    }
+
+    /// Substitute this event's values into a webhook URL template
+    pub fn render_url(&self, template: &str) -> String {
+        template
+            .replace("{event}", self.event_type.as_str())
+            .replace("{burn_block_height}", &self.burn_block_height.to_string())
+            .replace("{burn_block_hash}", &self.burn_block_hash.to_string())
+            .replace("{consensus_hash}", &self.consensus_hash.to_string())
+    }
+}
+
+/// Tracks the canonical burnchain tip, and works out which events happened between polls
+#[derive(Default)]
+pub struct BurnchainEventTracker {
+    last_tip: Option<BlockSnapshot>,
+}
+
+impl BurnchainEventTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the current canonical burnchain tip, and return the events that happened since the
+    /// last poll.  The first poll only records the tip.
+    pub fn poll(&mut self, conn: &Connection) -> Result<Vec<WebhookEvent>, db_error> {
+        let tip = SortitionDB::get_canonical_burn_chain_tip(conn)?;
+        self.advance(tip, |sn| {
+            SortitionDB::get_block_snapshot(conn, &sn.parent_sortition_id)?
+                .ok_or(db_error::NotFoundError)
+        })
+    }
+
+    /// Move to `tip`, using `get_parent` to walk back to where the last tip's fork and `tip`'s
+    /// fork meet.  Forks are compared by burnchain block hash, so PoX reorgs that do not change
+    /// the burnchain fork do not fire events.
+    fn advance<F>(
+        &mut self,
+        tip: BlockSnapshot,
+        mut get_parent: F,
+    ) -> Result<Vec<WebhookEvent>, db_error>
+    where
+        F: FnMut(&BlockSnapshot) -> Result<BlockSnapshot, db_error>,
+    {
+        let Some(last_tip) = self.last_tip.replace(tip.clone()) else {
+            return Ok(vec![]);
+        };
+        if last_tip.burn_header_hash == tip.burn_header_hash {
+            return Ok(vec![]);
+        }
+
+        // walk the new fork back to the height of the last tip...
+        let mut new_blocks = vec![];
+        let mut cursor = tip.clone();
+        while cursor.block_height > last_tip.block_height {
+            let parent = get_parent(&cursor)?;
+            new_blocks.push(cursor);
+            cursor = parent;
+        }
+
+        // ...and then walk both forks back until they meet
+        let mut old_cursor = last_tip.clone();
+        while old_cursor.block_height > cursor.block_height {
+            old_cursor = get_parent(&old_cursor)?;
+        }
+        let mut reorged = false;
+        while old_cursor.burn_header_hash != cursor.burn_header_hash {
+            reorged = true;
+            let parent = get_parent(&cursor)?;
+            new_blocks.push(cursor);
+            cursor = parent;
+            old_cursor = get_parent(&old_cursor)?;
+        }
+        new_blocks.reverse();
+
+        let mut events = vec![];
+        if reorged {
+            events.push(WebhookEvent::reorg(&last_tip, &tip, &cursor));
+        }
+
+        let skipped = new_blocks.len().saturating_sub(MAX_BLOCKS_PER_POLL);
+        if skipped > 0 {
+            info!("Webhooks: not firing events for {skipped} burnchain blocks processed since the last poll");
+        }
+        for sn in new_blocks.iter().skip(skipped) {
+            events.push(WebhookEvent::from_snapshot(WebhookEventType::BurnBlock, sn));
+            let sortition_event = if sn.sortition {
+                WebhookEventType::NewTenure
+            } else {
+                WebhookEventType::MissedSortition
+            };
+            events.push(WebhookEvent::from_snapshot(sortition_event, sn));
+        }
+        Ok(events)
+    }
+}
+
+/// Delivers webhook events to the configured webhooks
+pub struct WebhookDispatcher {
+    hooks: Vec<WebhookConfig>,
+}
+
+impl WebhookDispatcher {
+    pub fn new(hooks: Vec<WebhookConfig>) -> Self {
+        Self { hooks }
+    }
+
+    /// Make a dispatcher for the webhooks that fire on any of `events`, if there are any
+    pub fn for_events(hooks: &[WebhookConfig], events: &[WebhookEventType]) -> Option<Self> {
+        let hooks: Vec<_> = hooks
+            .iter()
+            .filter(|hook| hook.events.iter().any(|e| events.contains(e)))
+            .cloned()
+            .collect();
+        if hooks.is_empty() {
+            return None;
+        }
+        Some(Self::new(hooks))
+    }
+
+    /// Send `event` to every webhook that is interested in it
+    pub fn dispatch(&self, event: &WebhookEvent) {
+        for hook in self.hooks.iter() {
+            if !hook.events.contains(&event.event_type) {
+                continue;
+            }
+            if let Err(e) = Self::send(hook, event) {
+                warn!("Webhooks: failed to deliver event";
+                      "event" => event.event_type.as_str(),
+                      "burn_block_height" => event.burn_block_height,
+                      "error" => %e);
+            }
+        }
+    }
+
+    /// Send `event` to every webhook that is interested in it, from a new thread so that the
+    /// caller does not wait on the webhooks
+    pub fn dispatch_in_background(self: &Arc<Self>, event: WebhookEvent) {
+        let dispatcher = self.clone();
+        if let Err(e) = thread::Builder::new()
+            .name(format!("webhook_{}", event.event_type.as_str()))
+            .spawn(move || dispatcher.dispatch(&event))
+        {
+            warn!("Webhooks: failed to spawn delivery thread: {e:?}");
+        }
+    }
+
+    /// Compute the `X-Stacks-Signature` header value for a payload body
+    pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+        format!(
+            "sha256={}",
+            to_hex(Sha256Sum::hmac(secret.as_bytes(), body).as_bytes())
+        )
+    }
+
+    /// Make a single attempt to deliver `event` to `hook`
+    fn send(hook: &WebhookConfig, event: &WebhookEvent) -> Result<(), String> {
+        let full_url = event.render_url(&hook.url);
+        let url = Url::parse(&full_url).map_err(|e| format!("invalid URL {full_url}: {e}"))?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| format!("invalid URL {full_url}: missing host"))?;
+        let port = url.port_or_known_default().unwrap_or(80);
+        let peerhost: PeerHost = format!("{host}:{port}")
+            .parse()
+            .unwrap_or(PeerHost::DNS(host.to_string(), port));
+
+        let body = serde_json::to_vec(&event.payload).map_err(|e| e.to_string())?;
+        let mut request = StacksHttpRequest::new_for_peer(
+            peerhost,
+            "POST".into(),
+            url.path().into(),
+            HttpRequestContents::new()
+                .query_string(url.query())
+                .payload_json(event.payload.clone()),
+        )
+        .map_err(|e| format!("failed to encode request: {e:?}"))?;
+        request.add_header("Connection".into(), "close".into());
+        request.add_header(
+            "X-Stacks-Webhook-Event".into(),
+            event.event_type.as_str().into(),
+        );
+        if let Some(secret) = hook.secret.as_ref() {
+            request.add_header(
+                "X-Stacks-Signature".into(),
+                Self::sign_payload(secret, &body),
+            );
+        }
+
+        let response =
+            send_http_request(host, port, request, Duration::from_millis(hook.timeout_ms))
+                .map_err(|e| e.to_string())?;
+        let status = response.preamble().status_code;
+        if !(200..300).contains(&status) {
+            return Err(format!("{full_url} responded with status {status}"));
+        }
+        debug!("Webhooks: delivered event"; "event" => event.event_type.as_str(), "url" => %url);
+        Ok(())
+    }
+}
+
+/// The thread that polls the burnchain tip and fires webhooks
+pub struct WebhookThread {
+    keep_running: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl WebhookThread {
+    /// Start the webhook thread, if any webhooks fire on burnchain events
+    pub fn spawn(config: &Config) -> Option<Self> {
+        let dispatcher =
+            WebhookDispatcher::for_events(&config.webhooks, &WebhookEventType::BURNCHAIN)?;
+        let sortdb_path = config.get_burn_db_file_path();
+        let pox_constants = config.get_burnchain().pox_constants;
+        let keep_running = Arc::new(AtomicBool::new(true));
+        let thread_keep_running = keep_running.clone();
+
+        let handle = thread::Builder::new()
+            .name("webhooks".into())
+            .spawn(move || {
+                let mut tracker = BurnchainEventTracker::new();
+                let mut sortdb = None;
+                while thread_keep_running.load(Ordering::SeqCst) {
+                    // the sortition DB may not exist until the node has booted
+                    if sortdb.is_none() && fs::metadata(&sortdb_path).is_ok() {
+                        sortdb = SortitionDB::open(&sortdb_path, false, pox_constants.clone())
+                            .inspect_err(|e| debug!("Webhooks: failed to open sortition DB: {e:?}"))
+                            .ok();
+                    }
+                    if let Some(sortdb) = sortdb.as_ref() {
+                        match tracker.poll(sortdb.conn()) {
+                            Ok(events) => {
+                                for event in events.iter() {
+                                    dispatcher.dispatch(event);
+                                }
+                            }
+                            Err(e) => warn!("Webhooks: failed to load burnchain events: {e:?}"),
+                        }
+                    }
+                    thread::sleep(WEBHOOK_POLL_INTERVAL);
+                }
+            })
+            .expect("FATAL: failed to start webhooks thread");
+
+        Some(Self {
+            keep_running,
+            handle,
+        })
+    }
+
+    /// Stop the webhook thread and wait for it to exit
+    pub fn stop(self) {
+        self.keep_running.store(false, Ordering::SeqCst);
+        if let Err(e) = self.handle.join() {
+            error!("Webhooks thread panicked: {e:?}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use mockito::Matcher;
+    use stacks::burnchains::Txid;
+    use stacks_common::types::chainstate::{BlockHeaderHash, SortitionId};
+
+    use super::*;
+
+    /// Make a snapshot at `height` on the fork identified by `fork`
+    fn make_snapshot(
+        fork: u8,
+        height: u64,
+        parent: Option<&BlockSnapshot>,
+        sortition: bool,
+    ) -> BlockSnapshot {
+        let mut sn = BlockSnapshot::empty();
+        sn.block_height = height;
+        sn.burn_header_hash = BurnchainHeaderHash([fork; 32]);
+        sn.burn_header_hash.0[0..8].copy_from_slice(&height.to_be_bytes());
+        sn.consensus_hash = ConsensusHash([fork; 20]);
+        sn.consensus_hash.0[0..8].copy_from_slice(&height.to_be_bytes());
+        sn.sortition_id = SortitionId(sn.burn_header_hash.0);
+        sn.sortition = sortition;
+        if sortition {
+            sn.winning_block_txid = Txid([0x11; 32]);
+            sn.winning_stacks_block_hash = BlockHeaderHash([0x22; 32]);
+        }
+        if let Some(parent) = parent {
+            sn.parent_sortition_id = parent.sortition_id.clone();
+            sn.parent_burn_header_hash = parent.burn_header_hash.clone();
+        }
+        sn
+    }
+
+    fn event_types(events: &[WebhookEvent]) -> Vec<(WebhookEventType, u64)> {
+        events
+            .iter()
+            .map(|e| (e.event_type, e.burn_block_height))
+            .collect()
+    }
+
+    #[test]
+    fn test_tracker_events() {
+        let mut snapshots: HashMap<SortitionId, BlockSnapshot> = HashMap::new();
+        let mut fork_a = vec![make_snapshot(0xaa, 100, None, true)];
+        for height in 101..=104 {
+            let sn = make_snapshot(0xaa, height, fork_a.last(), height != 102);
+            fork_a.push(sn);
+        }
+        // fork b branches off of fork a at height 102
+        let mut fork_b = vec![fork_a[2].clone()];
+        for height in 103..=105 {
+            let sn = make_snapshot(0xbb, height, fork_b.last(), true);
+            fork_b.push(sn);
+        }
+        for sn in fork_a.iter().chain(fork_b.iter()) {
+            snapshots.insert(sn.sortition_id.clone(), sn.clone());
+        }
+        let get_parent = |sn: &BlockSnapshot| {
+            snapshots
+                .get(&sn.parent_sortition_id)
+                .cloned()
+                .ok_or(db_error::NotFoundError)
+        };
+
+        let mut tracker = BurnchainEventTracker::new();
+
+        // first poll only records the tip
+        assert!(tracker
+            .advance(fork_a[0].clone(), get_parent)
+            .unwrap()
+            .is_empty());
+
+        // no change
+        assert!(tracker
+            .advance(fork_a[0].clone(), get_parent)
+            .unwrap()
+            .is_empty());
+
+        // two new blocks, one without a sortition
+        let events = tracker.advance(fork_a[2].clone(), get_parent).unwrap();
+        assert_eq!(
+            event_types(&events),
+            vec![
+                (WebhookEventType::BurnBlock, 101),
+                (WebhookEventType::NewTenure, 101),
+                (WebhookEventType::BurnBlock, 102),
+                (WebhookEventType::MissedSortition, 102),
+            ]
+        );
+
+        let events = tracker.advance(fork_a[4].clone(), get_parent).unwrap();
+        assert_eq!(events.len(), 4);
+
+        // switch to fork b, which is longer
+        let events = tracker.advance(fork_b[3].clone(), get_parent).unwrap();
+        assert_eq!(
+            event_types(&events),
+            vec![
+                (WebhookEventType::Reorg, 105),
+                (WebhookEventType::BurnBlock, 103),
+                (WebhookEventType::NewTenure, 103),
+                (WebhookEventType::BurnBlock, 104),
+                (WebhookEventType::NewTenure, 104),
+                (WebhookEventType::BurnBlock, 105),
+                (WebhookEventType::NewTenure, 105),
+            ]
+        );
+        let reorg = &events[0].payload;
+        assert_eq!(
+            reorg["old_burn_block_hash"],
+            json!(format!("0x{}", &fork_a[4].burn_header_hash))
+        );
+        assert_eq!(reorg["old_burn_block_height"], json!(104));
+        assert_eq!(
+            reorg["fork_burn_block_hash"],
+            json!(format!("0x{}", &fork_a[2].burn_header_hash))
+        );
+        assert_eq!(reorg["fork_burn_block_height"], json!(102));
+
+        // switch back to fork a, which is shorter
+        let events = tracker.advance(fork_a[4].clone(), get_parent).unwrap();
+        assert_eq!(
+            event_types(&events),
+            vec![
+                (WebhookEventType::Reorg, 104),
+                (WebhookEventType::BurnBlock, 103),
+                (WebhookEventType::NewTenure, 103),
+                (WebhookEventType::BurnBlock, 104),
+                (WebhookEventType::NewTenure, 104),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_url() {
+        let sn = make_snapshot(0xaa, 123, None, true);
+        let event = WebhookEvent::from_snapshot(WebhookEventType::NewTenure, &sn);
+        assert_eq!(
+            event.render_url("http://localhost:9000/{event}/{burn_block_hash}?height={burn_block_height}&ch={consensus_hash}"),
+            format!(
+                "http://localhost:9000/new_tenure/{}?height=123&ch={}",
+                &sn.burn_header_hash, &sn.consensus_hash
+            )
+        );
+        assert_eq!(
+            event.payload["winning_block_txid"],
+            json!(format!("0x{}", Txid([0x11; 32])))
+        );
+    }
+
+    #[test]
+    fn test_block_accepted_event() {
+        let sn = make_snapshot(0xaa, 123, None, true);
+        let mut header = NakamotoBlockHeader::empty();
+        header.chain_length = 456;
+        header.consensus_hash = sn.consensus_hash.clone();
+        let mut signer_bitvec = BitVec::zeros(5).unwrap();
+        signer_bitvec.set(0, true).unwrap();
+        signer_bitvec.set(3, true).unwrap();
+
+        let event = WebhookEvent::block_accepted(&sn, &header, &signer_bitvec, 7, 10, 7);
+        assert_eq!(event.event_type, WebhookEventType::BlockAccepted);
+        assert_eq!(
+            event.render_url("http://localhost:9000/{event}?ch={consensus_hash}"),
+            format!(
+                "http://localhost:9000/block_accepted?ch={}",
+                &header.consensus_hash
+            )
+        );
+        assert_eq!(
+            event.payload["block_id"],
+            json!(format!("0x{}", &header.block_id()))
+        );
+        assert_eq!(event.payload["block_height"], json!(456));
+        assert_eq!(
+            event.payload["signer_signature_hash"],
+            json!(format!("0x{}", &header.signer_signature_hash()))
+        );
+        assert_eq!(event.payload["burn_block_height"], json!(123));
+        assert_eq!(
+            event.payload["signer_bitvec"],
+            serde_json::to_value(signer_bitvec).unwrap()
+        );
+        assert_eq!(event.payload["total_weight_signed"], json!(7));
+        assert_eq!(event.payload["total_weight"], json!(10));
+        assert_eq!(event.payload["weight_threshold"], json!(7));
+    }
+
+    #[test]
+    fn test_dispatcher_for_events() {
+        let hook = |events: Vec<WebhookEventType>| WebhookConfig {
+            url: "http://localhost:9000".into(),
+            events,
+            secret: None,
+            timeout_ms: 1_000,
+        };
+        let hooks = vec![
+            hook(vec![WebhookEventType::Reorg]),
+            hook(vec![
+                WebhookEventType::BlockAccepted,
+                WebhookEventType::Reorg,
+            ]),
+        ];
+        let dispatcher =
+            WebhookDispatcher::for_events(&hooks, &[WebhookEventType::BlockAccepted]).unwrap();
+        assert_eq!(dispatcher.hooks, hooks[1..]);
+        let dispatcher =
+            WebhookDispatcher::for_events(&hooks, &WebhookEventType::BURNCHAIN).unwrap();
+        assert_eq!(dispatcher.hooks, hooks);
+        assert!(WebhookDispatcher::for_events(
+            &[hook(vec![WebhookEventType::BlockAccepted])],
+            &WebhookEventType::BURNCHAIN
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_send_signed_webhook() {
+        let sn = make_snapshot(0xaa, 123, None, false);
+        let event = WebhookEvent::from_snapshot(WebhookEventType::MissedSortition, &sn);
+        let body = serde_json::to_vec(&event.payload).unwrap();
+        let signature = WebhookDispatcher::sign_payload("hunter2", &body);
+
+        let mut server = mockito::Server::new();
+        let signed = server
+            .mock("POST", "/alerts/missed_sortition")
+            .match_query(Matcher::UrlEncoded("height".into(), "123".into()))
+            .match_header("x-stacks-webhook-event", "missed_sortition")
+            .match_header("x-stacks-signature", signature.as_str())
+            .match_body(Matcher::Json(event.payload.clone()))
+            .with_status(200)
+            .create();
+        let unsigned = server
+            .mock("POST", "/unsigned")
+            .match_header("x-stacks-signature", Matcher::Missing)
+            .with_status(200)
+            .create();
+        let uninterested = server.mock("POST", "/tenures").expect(0).create();
+
+        let dispatcher = WebhookDispatcher::new(vec![
+            WebhookConfig {
+                url: format!(
+                    "{}/alerts/{{event}}?height={{burn_block_height}}",
+                    server.url()
+                ),
+                events: vec![WebhookEventType::MissedSortition],
+                secret: Some("hunter2".into()),
+                timeout_ms: 5_000,
+            },
+            WebhookConfig {
+                url: format!("{}/unsigned", server.url()),
+                events: WebhookEventType::ALL.to_vec(),
+                secret: None,
+                timeout_ms: 5_000,
+            },
+            WebhookConfig {
+                url: format!("{}/tenures", server.url()),
+                events: vec![WebhookEventType::NewTenure],
+                secret: None,
+                timeout_ms: 5_000,
+            },
+        ]);
+        dispatcher.dispatch(&event);
+
+        signed.assert();
+        unsigned.assert();
+        uninterested.assert();
+    }
+}